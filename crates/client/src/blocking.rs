@@ -0,0 +1,284 @@
+//! Synchronous wrapper around [`Client`](crate::Client) for scripts and tools
+//! without an async runtime. Each call blocks the current thread; do not use it
+//! from inside a Tokio runtime.
+
+use autosint_common::api::engine::{
+    AssessmentFeedbackRequest, AssessmentFeedbackResponse, AuditQuery, AuditResponse,
+    CancelMaintenanceJobResponse, ClaimSearchQuery, ClaimSearchResponse,
+    CreateMaintenanceJobRequest, CreateMonitorRequest, EntitySearchQuery, EntitySearchResponse,
+    FlagOverrideRequest, FlagsReport, FootprintResponse, GeoSeedReport, GraphConsistencyReport,
+    GraphStats, InvestigateRequest, InvestigateResponse, InvestigationImportReport,
+    ListInvestigationsQuery, MaintenanceJobQuery, PiiRedactionQuery, PiiRedactionReport,
+    PromptTriage, PromptsReport, PurgeReport, QueueStats, ReadOnlyStatus, RecoveryReport,
+    ReembedReport, SelfTestReport, TelemetryRollupsQuery, TelemetryRollupsResponse, TimelineQuery,
+    TimelineResponse, UpdateMonitorRequest,
+};
+use autosint_common::types::{
+    Assessment, AssessmentFeedback, Entity, Investigation, MaintenanceJob, Monitor, TagUsage,
+    WorkOrder,
+};
+use autosint_common::{AssessmentId, EntityId, InvestigationId, MaintenanceJobId, MonitorId};
+
+use crate::{ClientBuilder, ClientError};
+
+/// Blocking Engine client. Owns a single-threaded Tokio runtime.
+pub struct BlockingClient {
+    inner: crate::Client,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl BlockingClient {
+    /// Client with default settings and no credentials.
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::from_builder(crate::Client::builder(base_url))
+    }
+
+    /// Build from a configured [`ClientBuilder`].
+    pub fn from_builder(builder: ClientBuilder) -> Result<Self, ClientError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ClientError::Config(format!("failed to start runtime: {}", e)))?;
+        // reqwest's client spawns nothing at build time, but build inside the
+        // runtime anyway so connector setup sees a reactor.
+        let inner = runtime.block_on(async { builder.build() })?;
+        Ok(Self { inner, runtime })
+    }
+
+    pub fn start_investigation(
+        &self,
+        request: &InvestigateRequest,
+    ) -> Result<InvestigateResponse, ClientError> {
+        self.runtime
+            .block_on(self.inner.start_investigation(request))
+    }
+
+    pub fn preflight_investigation(
+        &self,
+        request: &InvestigateRequest,
+    ) -> Result<PromptTriage, ClientError> {
+        self.runtime
+            .block_on(self.inner.preflight_investigation(request))
+    }
+
+    pub fn get_status(&self, id: InvestigationId) -> Result<Investigation, ClientError> {
+        self.runtime.block_on(self.inner.get_status(id))
+    }
+
+    pub fn list_investigations(
+        &self,
+        query: &ListInvestigationsQuery,
+    ) -> Result<Vec<Investigation>, ClientError> {
+        self.runtime.block_on(self.inner.list_investigations(query))
+    }
+
+    pub fn list_work_orders(&self, id: InvestigationId) -> Result<Vec<WorkOrder>, ClientError> {
+        self.runtime.block_on(self.inner.list_work_orders(id))
+    }
+
+    pub fn get_footprint(&self, id: InvestigationId) -> Result<FootprintResponse, ClientError> {
+        self.runtime.block_on(self.inner.get_footprint(id))
+    }
+
+    pub fn get_timeline(
+        &self,
+        id: InvestigationId,
+        query: &TimelineQuery,
+    ) -> Result<TimelineResponse, ClientError> {
+        self.runtime.block_on(self.inner.get_timeline(id, query))
+    }
+
+    pub fn get_assessment(&self, id: InvestigationId) -> Result<Assessment, ClientError> {
+        self.runtime.block_on(self.inner.get_assessment(id))
+    }
+
+    pub fn get_assessment_markdown(&self, id: InvestigationId) -> Result<String, ClientError> {
+        self.runtime
+            .block_on(self.inner.get_assessment_markdown(id))
+    }
+
+    pub fn get_report_html(&self, id: InvestigationId) -> Result<String, ClientError> {
+        self.runtime.block_on(self.inner.get_report_html(id))
+    }
+
+    pub fn get_report_bundle(&self, id: InvestigationId) -> Result<Vec<u8>, ClientError> {
+        self.runtime.block_on(self.inner.get_report_bundle(id))
+    }
+
+    pub fn export_investigation(&self, id: InvestigationId) -> Result<Vec<u8>, ClientError> {
+        self.runtime.block_on(self.inner.export_investigation(id))
+    }
+
+    pub fn import_investigation(
+        &self,
+        archive: Vec<u8>,
+    ) -> Result<InvestigationImportReport, ClientError> {
+        self.runtime
+            .block_on(self.inner.import_investigation(archive))
+    }
+
+    pub fn submit_assessment_feedback(
+        &self,
+        id: AssessmentId,
+        request: &AssessmentFeedbackRequest,
+    ) -> Result<AssessmentFeedback, ClientError> {
+        self.runtime
+            .block_on(self.inner.submit_assessment_feedback(id, request))
+    }
+
+    pub fn list_assessment_feedback(
+        &self,
+        id: AssessmentId,
+    ) -> Result<AssessmentFeedbackResponse, ClientError> {
+        self.runtime
+            .block_on(self.inner.list_assessment_feedback(id))
+    }
+
+    pub fn tags(&self) -> Result<Vec<TagUsage>, ClientError> {
+        self.runtime.block_on(self.inner.tags())
+    }
+
+    pub fn search_entities(
+        &self,
+        query: &EntitySearchQuery,
+    ) -> Result<EntitySearchResponse, ClientError> {
+        self.runtime.block_on(self.inner.search_entities(query))
+    }
+
+    pub fn search_claims(
+        &self,
+        query: &ClaimSearchQuery,
+    ) -> Result<ClaimSearchResponse, ClientError> {
+        self.runtime.block_on(self.inner.search_claims(query))
+    }
+
+    pub fn queue_stats(&self) -> Result<QueueStats, ClientError> {
+        self.runtime.block_on(self.inner.queue_stats())
+    }
+
+    pub fn graph_stats(&self) -> Result<GraphStats, ClientError> {
+        self.runtime.block_on(self.inner.graph_stats())
+    }
+
+    pub fn graph_consistency(&self) -> Result<GraphConsistencyReport, ClientError> {
+        self.runtime.block_on(self.inner.graph_consistency())
+    }
+
+    pub fn create_monitor(&self, request: &CreateMonitorRequest) -> Result<Monitor, ClientError> {
+        self.runtime.block_on(self.inner.create_monitor(request))
+    }
+
+    pub fn list_monitors(&self) -> Result<Vec<Monitor>, ClientError> {
+        self.runtime.block_on(self.inner.list_monitors())
+    }
+
+    pub fn get_monitor(&self, id: MonitorId) -> Result<Monitor, ClientError> {
+        self.runtime.block_on(self.inner.get_monitor(id))
+    }
+
+    pub fn update_monitor(
+        &self,
+        id: MonitorId,
+        request: &UpdateMonitorRequest,
+    ) -> Result<Monitor, ClientError> {
+        self.runtime
+            .block_on(self.inner.update_monitor(id, request))
+    }
+
+    pub fn delete_monitor(&self, id: MonitorId) -> Result<Monitor, ClientError> {
+        self.runtime.block_on(self.inner.delete_monitor(id))
+    }
+
+    pub fn last_recovery(&self) -> Result<RecoveryReport, ClientError> {
+        self.runtime.block_on(self.inner.last_recovery())
+    }
+
+    pub fn purge_investigation(
+        &self,
+        id: InvestigationId,
+        dry_run: bool,
+    ) -> Result<PurgeReport, ClientError> {
+        self.runtime
+            .block_on(self.inner.purge_investigation(id, dry_run))
+    }
+
+    pub fn seed_geo(&self) -> Result<GeoSeedReport, ClientError> {
+        self.runtime.block_on(self.inner.seed_geo())
+    }
+
+    pub fn reembed(&self) -> Result<ReembedReport, ClientError> {
+        self.runtime.block_on(self.inner.reembed())
+    }
+
+    pub fn create_job(
+        &self,
+        request: &CreateMaintenanceJobRequest,
+    ) -> Result<MaintenanceJob, ClientError> {
+        self.runtime.block_on(self.inner.create_job(request))
+    }
+
+    pub fn list_jobs(
+        &self,
+        query: &MaintenanceJobQuery,
+    ) -> Result<Vec<MaintenanceJob>, ClientError> {
+        self.runtime.block_on(self.inner.list_jobs(query))
+    }
+
+    pub fn get_job(&self, id: MaintenanceJobId) -> Result<MaintenanceJob, ClientError> {
+        self.runtime.block_on(self.inner.get_job(id))
+    }
+
+    pub fn cancel_job(
+        &self,
+        id: MaintenanceJobId,
+    ) -> Result<CancelMaintenanceJobResponse, ClientError> {
+        self.runtime.block_on(self.inner.cancel_job(id))
+    }
+
+    pub fn reload_prompts(&self) -> Result<PromptsReport, ClientError> {
+        self.runtime.block_on(self.inner.reload_prompts())
+    }
+
+    pub fn set_read_only(&self, enabled: bool) -> Result<ReadOnlyStatus, ClientError> {
+        self.runtime.block_on(self.inner.set_read_only(enabled))
+    }
+
+    pub fn flags(&self) -> Result<FlagsReport, ClientError> {
+        self.runtime.block_on(self.inner.flags())
+    }
+
+    pub fn set_flag(&self, request: &FlagOverrideRequest) -> Result<FlagsReport, ClientError> {
+        self.runtime.block_on(self.inner.set_flag(request))
+    }
+
+    pub fn pii_redactions(
+        &self,
+        query: &PiiRedactionQuery,
+    ) -> Result<PiiRedactionReport, ClientError> {
+        self.runtime.block_on(self.inner.pii_redactions(query))
+    }
+
+    pub fn telemetry_rollups(
+        &self,
+        query: &TelemetryRollupsQuery,
+    ) -> Result<TelemetryRollupsResponse, ClientError> {
+        self.runtime.block_on(self.inner.telemetry_rollups(query))
+    }
+
+    pub fn audit(&self, query: &AuditQuery) -> Result<AuditResponse, ClientError> {
+        self.runtime.block_on(self.inner.audit(query))
+    }
+
+    pub fn set_entity_trust_level(
+        &self,
+        id: EntityId,
+        trust_level: Option<u8>,
+    ) -> Result<Entity, ClientError> {
+        self.runtime
+            .block_on(self.inner.set_entity_trust_level(id, trust_level))
+    }
+
+    pub fn selftest(&self) -> Result<SelfTestReport, ClientError> {
+        self.runtime.block_on(self.inner.selftest())
+    }
+}
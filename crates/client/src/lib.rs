@@ -0,0 +1,1009 @@
+//! Typed async client for the AutOSINT Engine HTTP API.
+//!
+//! Request and response types are shared with the Engine through
+//! `autosint_common::api::engine`, so the client and the handlers cannot drift.
+//! Idempotent GETs are retried with exponential backoff on connection errors,
+//! 5xx and 429; POST and DELETE are sent once.
+//!
+//! Enable the `blocking` feature for a synchronous wrapper.
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use autosint_common::api::engine::{
+    AssessmentFeedbackRequest, AssessmentFeedbackResponse, AssessmentFormat, AssessmentQuery,
+    AuditQuery, AuditResponse, CancelMaintenanceJobResponse, ClaimSearchQuery, ClaimSearchResponse,
+    CreateMaintenanceJobRequest, CreateMonitorRequest, EntitySearchQuery, EntitySearchResponse,
+    ErrorResponse, FlagOverrideRequest, FlagsReport, FootprintResponse, GeoSeedReport,
+    GraphConsistencyReport, GraphStats, InvestigateRequest, InvestigateResponse,
+    InvestigationImportReport, ListInvestigationsQuery, ListInvestigationsResponse,
+    ListMonitorsResponse, MaintenanceJobQuery, MaintenanceJobsResponse, PiiRedactionQuery,
+    PiiRedactionReport, PreflightResponse, PromptTriage, PromptsReport, PurgeQuery, PurgeReport,
+    QueueStats, ReadOnlyRequest, ReadOnlyStatus, RecoveryReport, ReembedReport, ReportFormat,
+    ReportQuery, SelfTestReport, TagsResponse, TelemetryRollupsQuery, TelemetryRollupsResponse,
+    TimelineQuery, TimelineResponse, TrustLevelRequest, UpdateInvestigationRequest,
+    UpdateMonitorRequest, WorkOrdersResponse,
+};
+use autosint_common::config::RetryConfig;
+use autosint_common::retry::{retry_with_backoff, RetryAttempt, RetryClass, RetryPolicy};
+use autosint_common::tls::{ClientTlsPaths, TlsError};
+use autosint_common::types::{
+    Assessment, AssessmentFeedback, Entity, Investigation, MaintenanceJob, Monitor, TagUsage,
+    WorkOrder,
+};
+use autosint_common::{AssessmentId, EntityId, InvestigationId, MaintenanceJobId, MonitorId};
+
+pub use autosint_common::api::engine;
+
+/// Header carrying the Engine admin key (required for purges and seeding).
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("Invalid client configuration: {0}")]
+    Config(String),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Engine returned {status}: {message}")]
+    Api { status: StatusCode, message: String },
+
+    #[error("Failed to decode response: {0}")]
+    Decode(String),
+}
+
+impl ClientError {
+    /// HTTP status returned by the Engine, if the request got that far.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            ClientError::Http(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Http(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            ClientError::Api { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Builder for [`Client`].
+pub struct ClientBuilder {
+    base_url: String,
+    admin_key: Option<String>,
+    bearer_token: Option<String>,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+    retry: RetryConfig,
+    tls: Option<ClientTlsPaths>,
+}
+
+impl ClientBuilder {
+    fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            admin_key: None,
+            bearer_token: None,
+            headers: Vec::new(),
+            timeout: Duration::from_secs(30),
+            retry: RetryConfig {
+                max_attempts: 3,
+                initial_backoff_ms: 200,
+                max_backoff_ms: 5_000,
+                backoff_multiplier: 2.0,
+                jitter: true,
+            },
+            tls: None,
+        }
+    }
+
+    /// Admin key sent as `x-admin-key` on every request.
+    pub fn admin_key(mut self, key: impl Into<String>) -> Self {
+        self.admin_key = Some(key.into());
+        self
+    }
+
+    /// Token sent as `Authorization: Bearer <token>`, for deployments that put
+    /// the Engine behind an authenticating proxy.
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Extra header sent on every request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Per-request timeout (default 30s).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry policy for idempotent requests. `max_attempts: 1` disables retries.
+    pub fn retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// CA and client certificate for an Engine serving TLS / mutual TLS.
+    pub fn tls(mut self, paths: ClientTlsPaths) -> Self {
+        self.tls = Some(paths);
+        self
+    }
+
+    pub fn build(self) -> Result<Client, ClientError> {
+        if !self.base_url.starts_with("http://") && !self.base_url.starts_with("https://") {
+            return Err(ClientError::Config(format!(
+                "base URL must start with http:// or https://: {}",
+                self.base_url
+            )));
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some(key) = &self.admin_key {
+            headers.insert(ADMIN_KEY_HEADER, sensitive_header(key)?);
+        }
+        if let Some(token) = &self.bearer_token {
+            headers.insert(
+                AUTHORIZATION,
+                sensitive_header(&format!("Bearer {}", token))?,
+            );
+        }
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| ClientError::Config(format!("header {}: {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| ClientError::Config(format!("header {}: {}", name, e)))?;
+            headers.insert(name, value);
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .default_headers(headers);
+        if let Some(paths) = &self.tls {
+            builder = paths
+                .apply(builder)
+                .map_err(|e: TlsError| ClientError::Config(e.to_string()))?;
+        }
+
+        Ok(Client {
+            http: builder.build()?,
+            base_url: self.base_url,
+            retry: self.retry,
+        })
+    }
+}
+
+fn sensitive_header(value: &str) -> Result<HeaderValue, ClientError> {
+    let mut value = HeaderValue::from_str(value)
+        .map_err(|e| ClientError::Config(format!("credential is not a valid header: {}", e)))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// Async client for the Engine API. Cheap to clone.
+#[derive(Clone, Debug)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+impl Client {
+    pub fn builder(base_url: &str) -> ClientBuilder {
+        ClientBuilder::new(base_url)
+    }
+
+    /// Client with default settings and no credentials.
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::builder(base_url).build()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// POST /investigate — start an investigation. Not retried: a retry after a
+    /// lost response would start a second investigation.
+    pub async fn start_investigation(
+        &self,
+        request: &InvestigateRequest,
+    ) -> Result<InvestigateResponse, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/investigate"))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /investigate with `suggest_only` — triage a prompt without starting
+    /// an investigation.
+    pub async fn preflight_investigation(
+        &self,
+        request: &InvestigateRequest,
+    ) -> Result<PromptTriage, ClientError> {
+        let request = InvestigateRequest {
+            suggest_only: true,
+            ..request.clone()
+        };
+        let response = self
+            .http
+            .post(self.url("/investigate"))
+            .json(&request)
+            .send()
+            .await?;
+        let response: PreflightResponse = decode_json(response).await?;
+        Ok(response.triage)
+    }
+
+    /// GET /investigations/{id} — the investigation record and its status.
+    pub async fn get_status(&self, id: InvestigationId) -> Result<Investigation, ClientError> {
+        self.get_json(&format!("/investigations/{}", id), &()).await
+    }
+
+    /// GET /investigations — newest first, filtered by `query`.
+    pub async fn list_investigations(
+        &self,
+        query: &ListInvestigationsQuery,
+    ) -> Result<Vec<Investigation>, ClientError> {
+        let response: ListInvestigationsResponse = self.get_json("/investigations", query).await?;
+        Ok(response.investigations)
+    }
+
+    /// GET /investigations/{id}/work_orders — the investigation's work orders,
+    /// by cycle then creation time. Failed ones carry `failure_detail`.
+    pub async fn list_work_orders(
+        &self,
+        id: InvestigationId,
+    ) -> Result<Vec<WorkOrder>, ClientError> {
+        let response: WorkOrdersResponse = self
+            .get_json(&format!("/investigations/{}/work_orders", id), &())
+            .await?;
+        Ok(response.work_orders)
+    }
+
+    /// GET /investigate/{id}/footprint — fetches and searches of the
+    /// investigation's finished work orders, with its fetch budget.
+    pub async fn get_footprint(
+        &self,
+        id: InvestigationId,
+    ) -> Result<FootprintResponse, ClientError> {
+        self.get_json(&format!("/investigate/{}/footprint", id), &())
+            .await
+    }
+
+    /// GET /investigate/{id}/timeline — one page of the investigation's
+    /// lifecycle events, oldest first.
+    pub async fn get_timeline(
+        &self,
+        id: InvestigationId,
+        query: &TimelineQuery,
+    ) -> Result<TimelineResponse, ClientError> {
+        self.get_json(&format!("/investigate/{}/timeline", id), query)
+            .await
+    }
+
+    /// GET /investigations/{id}/assessment — the latest assessment.
+    pub async fn get_assessment(&self, id: InvestigationId) -> Result<Assessment, ClientError> {
+        self.get_json(
+            &format!("/investigations/{}/assessment", id),
+            &AssessmentQuery::default(),
+        )
+        .await
+    }
+
+    /// GET /investigations/{id}/assessment?format=markdown — the latest
+    /// assessment rendered as Markdown.
+    pub async fn get_assessment_markdown(
+        &self,
+        id: InvestigationId,
+    ) -> Result<String, ClientError> {
+        let query = AssessmentQuery {
+            format: AssessmentFormat::Markdown,
+        };
+        let response = self
+            .get(&format!("/investigations/{}/assessment", id), &query)
+            .await?;
+        response
+            .text()
+            .await
+            .map_err(|e| ClientError::Decode(e.to_string()))
+    }
+
+    /// GET /investigations/{id}/report — a self-contained HTML report of the
+    /// investigation, its evidence and entity graph.
+    pub async fn get_report_html(&self, id: InvestigationId) -> Result<String, ClientError> {
+        let response = self
+            .get(
+                &format!("/investigations/{}/report", id),
+                &ReportQuery::default(),
+            )
+            .await?;
+        response
+            .text()
+            .await
+            .map_err(|e| ClientError::Decode(e.to_string()))
+    }
+
+    /// GET /investigations/{id}/report?format=zip — the HTML report bundled
+    /// with the source documents its evidence cites.
+    pub async fn get_report_bundle(&self, id: InvestigationId) -> Result<Vec<u8>, ClientError> {
+        let response = self
+            .get(
+                &format!("/investigations/{}/report", id),
+                &ReportQuery {
+                    format: ReportFormat::Zip,
+                },
+            )
+            .await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| ClientError::Decode(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// GET /investigations/{id}/export — a terminal investigation as a
+    /// `.tar.gz` archive, for [`import_investigation`](Self::import_investigation)
+    /// on another engine.
+    pub async fn export_investigation(&self, id: InvestigationId) -> Result<Vec<u8>, ClientError> {
+        let response = self
+            .get(&format!("/investigations/{}/export", id), &())
+            .await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| ClientError::Decode(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// POST /investigations/import — import an archive from
+    /// [`export_investigation`](Self::export_investigation). Requires the
+    /// admin key. Not retried: a retry after a lost response would import a
+    /// second copy.
+    pub async fn import_investigation(
+        &self,
+        archive: Vec<u8>,
+    ) -> Result<InvestigationImportReport, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/investigations/import"))
+            .header(reqwest::header::CONTENT_TYPE, "application/gzip")
+            .body(archive)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /assessments/{id}/feedback — rate an assessment and dispute
+    /// claims behind it.
+    pub async fn submit_assessment_feedback(
+        &self,
+        id: AssessmentId,
+        request: &AssessmentFeedbackRequest,
+    ) -> Result<AssessmentFeedback, ClientError> {
+        let response = self
+            .http
+            .post(self.url(&format!("/assessments/{}/feedback", id)))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// GET /assessments/{id}/feedback — the assessment's feedback, oldest
+    /// first, with counts by rating.
+    pub async fn list_assessment_feedback(
+        &self,
+        id: AssessmentId,
+    ) -> Result<AssessmentFeedbackResponse, ClientError> {
+        self.get_json(&format!("/assessments/{}/feedback", id), &())
+            .await
+    }
+
+    /// GET /tags — tag usage counts, most used first.
+    pub async fn tags(&self) -> Result<Vec<TagUsage>, ClientError> {
+        let response: TagsResponse = self.get_json("/tags", &()).await?;
+        Ok(response.tags)
+    }
+
+    /// GET /graph/entities/search — fulltext entity search.
+    pub async fn search_entities(
+        &self,
+        query: &EntitySearchQuery,
+    ) -> Result<EntitySearchResponse, ClientError> {
+        self.get_json("/graph/entities/search", query).await
+    }
+
+    /// GET /graph/claims/search — fulltext claim search.
+    pub async fn search_claims(
+        &self,
+        query: &ClaimSearchQuery,
+    ) -> Result<ClaimSearchResponse, ClientError> {
+        self.get_json("/graph/claims/search", query).await
+    }
+
+    /// GET /stats/queue — work order stream depths and queued wait ages.
+    pub async fn queue_stats(&self) -> Result<QueueStats, ClientError> {
+        self.get_json("/stats/queue", &()).await
+    }
+
+    /// GET /stats/graph — node and edge counts.
+    pub async fn graph_stats(&self) -> Result<GraphStats, ClientError> {
+        self.get_json("/stats/graph", &()).await
+    }
+
+    /// GET /graph/consistency — run the read-only graph consistency checks.
+    pub async fn graph_consistency(&self) -> Result<GraphConsistencyReport, ClientError> {
+        self.get_json("/graph/consistency", &()).await
+    }
+
+    /// POST /monitors — subscribe to a Fetch service source. Not retried: a
+    /// retry after a lost response would create a second monitor.
+    pub async fn create_monitor(
+        &self,
+        request: &CreateMonitorRequest,
+    ) -> Result<Monitor, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/monitors"))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// GET /monitors — every monitor with its last run.
+    pub async fn list_monitors(&self) -> Result<Vec<Monitor>, ClientError> {
+        let response: ListMonitorsResponse = self.get_json("/monitors", &()).await?;
+        Ok(response.monitors)
+    }
+
+    /// GET /monitors/{id}
+    pub async fn get_monitor(&self, id: MonitorId) -> Result<Monitor, ClientError> {
+        self.get_json(&format!("/monitors/{}", id), &()).await
+    }
+
+    /// PATCH /monitors/{id} — change a monitor's settings.
+    pub async fn update_monitor(
+        &self,
+        id: MonitorId,
+        request: &UpdateMonitorRequest,
+    ) -> Result<Monitor, ClientError> {
+        let response = self
+            .http
+            .patch(self.url(&format!("/monitors/{}", id)))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// DELETE /monitors/{id} — stop and remove a monitor.
+    pub async fn delete_monitor(&self, id: MonitorId) -> Result<Monitor, ClientError> {
+        let response = self
+            .http
+            .delete(self.url(&format!("/monitors/{}", id)))
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// GET /recovery/last — the startup recovery report, complete or under way.
+    pub async fn last_recovery(&self) -> Result<RecoveryReport, ClientError> {
+        self.get_json("/recovery/last", &()).await
+    }
+
+    /// DELETE /investigations/{id} — purge an investigation (or preview the
+    /// purge with `dry_run`). Requires the admin key. Not retried.
+    pub async fn purge_investigation(
+        &self,
+        id: InvestigationId,
+        dry_run: bool,
+    ) -> Result<PurgeReport, ClientError> {
+        let query = PurgeQuery {
+            confirm: !dry_run,
+            dry_run,
+        };
+        let response = self
+            .http
+            .delete(self.url(&format!("/investigations/{}", id)))
+            .query(&query)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// PATCH /investigations/{id} — edit a Pending investigation, made against
+    /// `request.version`. Requires the admin key. Not retried: a conflict means
+    /// re-reading the investigation first.
+    pub async fn update_investigation(
+        &self,
+        id: InvestigationId,
+        request: &UpdateInvestigationRequest,
+    ) -> Result<Investigation, ClientError> {
+        let response = self
+            .http
+            .patch(self.url(&format!("/investigations/{}", id)))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /investigate/{id}/resume — start driving a non-terminal
+    /// investigation no task owns, such as one stuck Suspended. Requires the
+    /// admin key. Idempotent.
+    pub async fn resume_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> Result<InvestigateResponse, ClientError> {
+        let response = self
+            .http
+            .post(self.url(&format!("/investigate/{}/resume", id)))
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /investigate/{id}/fail — produce a partial assessment and mark an
+    /// investigation no task is driving Failed. Requires the admin key.
+    /// Long-running: set a generous timeout. Idempotent, but not retried.
+    pub async fn fail_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> Result<InvestigateResponse, ClientError> {
+        let response = self
+            .http
+            .post(self.url(&format!("/investigate/{}/fail", id)))
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /admin/seed/geo — create or refresh the seeded country and region
+    /// entities. Requires the admin key. Idempotent, but not retried.
+    pub async fn seed_geo(&self) -> Result<GeoSeedReport, ClientError> {
+        let response = self.http.post(self.url("/admin/seed/geo")).send().await?;
+        decode_json(response).await
+    }
+
+    /// POST /admin/reembed — re-embed the graph with the engine's `[reembed]`
+    /// model and swap the new vectors in. Requires the admin key. Long-running:
+    /// set a generous timeout. Not retried.
+    pub async fn reembed(&self) -> Result<ReembedReport, ClientError> {
+        let response = self.http.post(self.url("/admin/reembed")).send().await?;
+        decode_json(response).await
+    }
+
+    /// POST /admin/jobs — queue a maintenance job such as `reembed_claims`.
+    /// Poll [`get_job`](Self::get_job) for its progress. Requires the admin
+    /// key. Not retried: a retry after a lost response would queue a second
+    /// job.
+    pub async fn create_job(
+        &self,
+        request: &CreateMaintenanceJobRequest,
+    ) -> Result<MaintenanceJob, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/admin/jobs"))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// GET /admin/jobs — maintenance jobs, newest first. Requires the admin
+    /// key.
+    pub async fn list_jobs(
+        &self,
+        query: &MaintenanceJobQuery,
+    ) -> Result<Vec<MaintenanceJob>, ClientError> {
+        let response: MaintenanceJobsResponse = self.get_json("/admin/jobs", query).await?;
+        Ok(response.jobs)
+    }
+
+    /// GET /admin/jobs/{id} — a maintenance job with its progress. Requires
+    /// the admin key.
+    pub async fn get_job(&self, id: MaintenanceJobId) -> Result<MaintenanceJob, ClientError> {
+        self.get_json(&format!("/admin/jobs/{}", id), &()).await
+    }
+
+    /// POST /admin/jobs/{id}/cancel — cancel a queued job, or stop a running
+    /// one after its current batch. Requires the admin key.
+    pub async fn cancel_job(
+        &self,
+        id: MaintenanceJobId,
+    ) -> Result<CancelMaintenanceJobResponse, ClientError> {
+        let response = self
+            .http
+            .post(self.url(&format!("/admin/jobs/{}/cancel", id)))
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /config/prompts/reload — re-read the engine's prompt templates.
+    /// Sessions already running keep their prompts. Requires the admin key.
+    pub async fn reload_prompts(&self) -> Result<PromptsReport, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/config/prompts/reload"))
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /admin/read_only — turn read-only mode on or off for every
+    /// replica, e.g. around a Neo4j upgrade. Requires the admin key.
+    pub async fn set_read_only(&self, enabled: bool) -> Result<ReadOnlyStatus, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/admin/read_only"))
+            .json(&ReadOnlyRequest { enabled })
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// GET /admin/flags — every feature flag's effective value, config default
+    /// and override. Requires the admin key.
+    pub async fn flags(&self) -> Result<FlagsReport, ClientError> {
+        self.get_json("/admin/flags", &()).await
+    }
+
+    /// POST /admin/flags — override a feature flag for every replica, or clear
+    /// its override with `enabled: None`. Requires the admin key.
+    pub async fn set_flag(
+        &self,
+        request: &FlagOverrideRequest,
+    ) -> Result<FlagsReport, ClientError> {
+        let response = self
+            .http
+            .post(self.url("/admin/flags"))
+            .json(request)
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// GET /admin/pii/redactions — personal data found in claim content, by
+    /// type and day. Requires the admin key.
+    pub async fn pii_redactions(
+        &self,
+        query: &PiiRedactionQuery,
+    ) -> Result<PiiRedactionReport, ClientError> {
+        self.get_json("/admin/pii/redactions", query).await
+    }
+
+    /// GET /telemetry/rollups — session statistics per prompt hash and
+    /// window, newest first.
+    pub async fn telemetry_rollups(
+        &self,
+        query: &TelemetryRollupsQuery,
+    ) -> Result<TelemetryRollupsResponse, ClientError> {
+        self.get_json("/telemetry/rollups", query).await
+    }
+
+    /// GET /audit — who changed what, newest first, filtered by target,
+    /// actor, operation and time. Requires the admin key.
+    pub async fn audit(&self, query: &AuditQuery) -> Result<AuditResponse, ClientError> {
+        self.get_json("/audit", query).await
+    }
+
+    /// PUT /admin/entities/{id}/trust_level — rate a source entity from 1
+    /// (unreliable) to 5 (authoritative), or clear its rating with `None`.
+    /// Requires the admin key.
+    pub async fn set_entity_trust_level(
+        &self,
+        id: EntityId,
+        trust_level: Option<u8>,
+    ) -> Result<Entity, ClientError> {
+        let response = self
+            .http
+            .put(self.url(&format!("/admin/entities/{}/trust_level", id)))
+            .json(&TrustLevelRequest { trust_level })
+            .send()
+            .await?;
+        decode_json(response).await
+    }
+
+    /// POST /selftest — run a canary investigation through the full pipeline
+    /// and report each stage. Requires the admin key. Long-running: set a
+    /// generous timeout. Not retried.
+    pub async fn selftest(&self) -> Result<SelfTestReport, ClientError> {
+        let response = self.http.post(self.url("/selftest")).send().await?;
+        decode_json(response).await
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &impl Serialize,
+    ) -> Result<T, ClientError> {
+        let response = self.get(path, query).await?;
+        response
+            .json()
+            .await
+            .map_err(|e| ClientError::Decode(e.to_string()))
+    }
+
+    /// Send an idempotent GET, retrying per the client's retry policy. Returns
+    /// the response only when it has a success status.
+    async fn get(
+        &self,
+        path: &str,
+        query: &impl Serialize,
+    ) -> Result<reqwest::Response, ClientError> {
+        let url = self.url(path);
+        let policy =
+            RetryPolicy::from(&self.retry).with_max_attempts(self.retry.max_attempts.max(1));
+        let classify = |e: &ClientError| {
+            if e.is_retryable() {
+                RetryClass::Retryable
+            } else {
+                RetryClass::Fatal
+            }
+        };
+        let on_retry = |retry: &RetryAttempt<'_, ClientError>| {
+            tracing::warn!(
+                attempt = retry.attempt,
+                wait_ms = retry.wait.as_millis() as u64,
+                url = %url,
+                error = %retry.error,
+                "Engine request failed, retrying"
+            );
+        };
+        retry_with_backoff(&policy, classify, on_retry, || async {
+            match self
+                .http
+                .request(Method::GET, &url)
+                .query(query)
+                .send()
+                .await
+            {
+                Ok(response) => check_status(response).await,
+                Err(e) => Err(ClientError::Http(e)),
+            }
+        })
+        .await
+        .map_err(|failure| failure.error)
+    }
+}
+
+/// Map a non-success response to `ClientError::Api`, using the Engine's error
+/// envelope when the body has one.
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, ClientError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = ErrorResponse::message_from_body(body);
+    Err(ClientError::Api { status, message })
+}
+
+async fn decode_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, ClientError> {
+    check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| ClientError::Decode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use axum::extract::State;
+    use axum::http::HeaderMap as AxumHeaderMap;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+
+    async fn serve(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn fast_retry(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 5,
+            backoff_multiplier: 2.0,
+            jitter: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_retries_server_errors() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let app = Router::new()
+            .route(
+                "/stats/graph",
+                get(|State(calls): State<Arc<AtomicU32>>| async move {
+                    if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                        Err((
+                            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                            Json(ErrorResponse::new("warming up")),
+                        ))
+                    } else {
+                        Ok(Json(GraphStats {
+                            entities: 3,
+                            relationships: 2,
+                            claims: 1,
+                            claims_by_language: Default::default(),
+                        }))
+                    }
+                }),
+            )
+            .with_state(Arc::clone(&calls));
+        let client = Client::builder(&serve(app).await)
+            .retry(fast_retry(3))
+            .build()
+            .unwrap();
+
+        let stats = client.graph_stats().await.unwrap();
+        assert_eq!(stats.entities, 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_gives_up_after_max_attempts() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let app = Router::new()
+            .route(
+                "/stats/queue",
+                get(|State(calls): State<Arc<AtomicU32>>| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    (
+                        axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse::new("Internal error")),
+                    )
+                }),
+            )
+            .with_state(Arc::clone(&calls));
+        let client = Client::builder(&serve(app).await)
+            .retry(fast_retry(2))
+            .build()
+            .unwrap();
+
+        let err = client.queue_stats().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(err.to_string().contains("Internal error"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_not_retried() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let app = Router::new()
+            .route(
+                "/investigations/{id}",
+                get(|State(calls): State<Arc<AtomicU32>>| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    (
+                        axum::http::StatusCode::NOT_FOUND,
+                        Json(ErrorResponse::new("Investigation not found")),
+                    )
+                }),
+            )
+            .with_state(Arc::clone(&calls));
+        let client = Client::builder(&serve(app).await)
+            .retry(fast_retry(3))
+            .build()
+            .unwrap();
+
+        let err = client.get_status(InvestigationId::new()).await.unwrap_err();
+        assert!(err.is_not_found());
+        assert!(
+            matches!(err, ClientError::Api { ref message, .. } if message == "Investigation not found")
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_post_not_retried() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let app = Router::new()
+            .route(
+                "/investigate",
+                post(|State(calls): State<Arc<AtomicU32>>| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    axum::http::StatusCode::BAD_GATEWAY
+                }),
+            )
+            .with_state(Arc::clone(&calls));
+        let client = Client::builder(&serve(app).await)
+            .retry(fast_retry(3))
+            .build()
+            .unwrap();
+
+        let err = client
+            .start_investigation(&InvestigateRequest {
+                prompt: "Who owns the vessel?".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_GATEWAY));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_auth_headers_sent() {
+        let app = Router::new().route(
+            "/tags",
+            get(|headers: AxumHeaderMap| async move {
+                let admin = headers.get(ADMIN_KEY_HEADER).and_then(|v| v.to_str().ok());
+                let bearer = headers.get("authorization").and_then(|v| v.to_str().ok());
+                if admin == Some("s3cret") && bearer == Some("Bearer tok") {
+                    Ok(Json(TagsResponse {
+                        tags: vec![TagUsage {
+                            tag: "maritime".into(),
+                            investigations: 2,
+                            assessments: 1,
+                        }],
+                    }))
+                } else {
+                    Err(axum::http::StatusCode::FORBIDDEN)
+                }
+            }),
+        );
+        let base = serve(app).await;
+
+        let client = Client::builder(&base)
+            .admin_key("s3cret")
+            .bearer_token("tok")
+            .build()
+            .unwrap();
+        let tags = client.tags().await.unwrap();
+        assert_eq!(tags[0].tag, "maritime");
+
+        let anonymous = Client::new(&base).unwrap();
+        let err = anonymous.tags().await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_base_url() {
+        assert!(matches!(
+            Client::new("localhost:8080"),
+            Err(ClientError::Config(_))
+        ));
+        let client = Client::new("http://localhost:8080/").unwrap();
+        assert_eq!(client.base_url(), "http://localhost:8080");
+    }
+}
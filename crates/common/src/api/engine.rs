@@ -0,0 +1,1889 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::validate::FieldErrors;
+use super::{FieldError, HealthStatus, Validate};
+use crate::config::PiiPolicy;
+use crate::ids::{
+    ClaimId, EnrichmentRequestId, EntityId, InvestigationId, MaintenanceJobId, RelationshipId,
+    WorkOrderId,
+};
+use crate::types::{
+    normalize_correlation_id, normalize_language, normalize_tags, parse_trust_level,
+    AssessmentFeedback, AuditActorType, AuditEntry, Claim, EnrichmentStatus, Entity, FeatureFlag,
+    FeedbackRating, FeedbackSummary, FetchBudget, Investigation, InvestigationEvent,
+    InvestigationStatus, MaintenanceJob, MaintenanceJobStatus, MaintenanceJobType, Monitor,
+    OutboundFootprint, PromptRollup, StrictnessProfile, TagUsage, TelemetryRole, WorkOrder,
+    WorkOrderPriority,
+};
+
+pub use super::ErrorResponse;
+
+/// GET /health response. 200 when every dependency is healthy, 503 otherwise.
+/// In read-only mode Neo4j may be down for maintenance: 200 while PostgreSQL
+/// and Redis are up.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthResponse {
+    /// `degraded` when the dependencies are healthy but the last self-test
+    /// failed, or while the engine is read-only.
+    pub status: HealthStatus,
+    pub services: ServiceHealth,
+    /// Supervised background tasks, by name.
+    pub tasks: Vec<TaskStatus>,
+    /// The last self-test run, if any.
+    #[serde(default)]
+    pub selftest: Option<SelfTestReport>,
+    /// Hash of each prompt template in use, by name.
+    #[serde(default)]
+    pub prompts: BTreeMap<String, String>,
+    /// Read-only mode is on (see POST /admin/read_only).
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Reachability of the Engine's databases.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceHealth {
+    pub neo4j: HealthStatus,
+    pub postgres: HealthStatus,
+    pub redis: HealthStatus,
+}
+
+/// Point-in-time status of a supervised task, reported on `/health`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub name: String,
+    /// Critical tasks are restarted after a panic.
+    pub critical: bool,
+    /// Number of live instances (one-shot tasks may share a name).
+    pub running: u32,
+    pub panics: u64,
+    pub restarts: u64,
+}
+
+/// POST /investigate request — start a new investigation.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InvestigateRequest {
+    pub prompt: String,
+    /// Free-form organizational tags (see `normalize_tags`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Triage the prompt before creating the investigation and apply the
+    /// suggestions. Unset uses `triage.preflight_by_default`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preflight: Option<bool>,
+    /// Only triage the prompt: respond 200 with a [`PreflightResponse`] and
+    /// create nothing.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub suggest_only: bool,
+    /// Highest work order priority the investigation may use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_cap: Option<WorkOrderPriority>,
+    /// External ID to tie the investigation to (e.g. a case number), at most
+    /// `MAX_CORRELATION_ID_LEN` characters. Shows up in logs and exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Limit on the investigation's page fetches. Fetches past it fail, so the
+    /// Processors work with what they have.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_budget: Option<FetchBudget>,
+    /// Run against the graph as it stood at this past time: the Analyst only
+    /// sees what had been ingested by then, and the run can't change the
+    /// graph or fetch anything. Its assessments are flagged with the cutoff.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+impl Validate for InvestigateRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(
+            !self.prompt.trim().is_empty(),
+            "prompt",
+            "must not be empty",
+        );
+        errors.check_result(normalize_tags(&self.tags), "tags");
+        errors.check_result(
+            normalize_correlation_id(self.correlation_id.as_deref()),
+            "correlation_id",
+        );
+        if let Some(budget) = &self.fetch_budget {
+            errors.check(
+                budget.max_fetches != Some(0),
+                "fetch_budget.max_fetches",
+                "must be at least 1",
+            );
+            errors.check(
+                budget.max_fetches_per_domain != Some(0),
+                "fetch_budget.max_fetches_per_domain",
+                "must be at least 1",
+            );
+        }
+        errors.check(
+            self.as_of.is_none_or(|as_of| as_of <= Utc::now()),
+            "as_of",
+            "must not be in the future",
+        );
+        errors.finish()
+    }
+}
+
+/// PATCH /investigations/{id} request — edit an investigation before it
+/// starts. Unset fields are left as they are. `version` is the investigation's
+/// version the edit was made against; the edit is rejected with 409 if the
+/// investigation has changed since (or has started).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UpdateInvestigationRequest {
+    pub version: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_cap: Option<WorkOrderPriority>,
+    /// Replaces the focus entities.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus_entities: Option<Vec<String>>,
+    /// Cycle limit, at most `safety.max_cycles_per_investigation`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cycles: Option<u32>,
+    /// Replaces the tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl Validate for UpdateInvestigationRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        if let Some(prompt) = &self.prompt {
+            errors.check(!prompt.trim().is_empty(), "prompt", "must not be empty");
+        }
+        if let Some(focus_entities) = &self.focus_entities {
+            errors.check(
+                focus_entities.iter().all(|e| !e.trim().is_empty()),
+                "focus_entities",
+                "must not contain empty names",
+            );
+        }
+        errors.check(
+            self.max_cycles != Some(0),
+            "max_cycles",
+            "must be at least 1",
+        );
+        if let Some(tags) = &self.tags {
+            errors.check_result(normalize_tags(tags), "tags");
+        }
+        errors.finish()
+    }
+}
+
+/// POST /investigate response (202 Accepted). The investigation runs in the
+/// background; poll GET /investigations/{id} for progress. Also the response of
+/// the operator controls POST /investigate/{id}/resume and /fail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InvestigateResponse {
+    pub investigation_id: InvestigationId,
+    pub status: InvestigationStatus,
+    pub message: String,
+    /// Pre-flight triage applied to the investigation, if it ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<PromptTriage>,
+}
+
+/// POST /investigate response (200 OK) for `suggest_only` requests.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreflightResponse {
+    pub triage: PromptTriage,
+}
+
+/// How much ground a prompt covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptScope {
+    Narrow,
+    Medium,
+    Broad,
+}
+
+/// Pre-flight assessment of an investigation prompt.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PromptTriage {
+    pub scope: PromptScope,
+    /// Entities the investigation should center on.
+    #[serde(default)]
+    pub focus_entities: Vec<String>,
+    /// Suggested cycle limit, within `safety.max_cycles_per_investigation`.
+    pub max_cycles: u32,
+    pub strictness: StrictnessProfile,
+    /// The prompt is too vague to investigate as written.
+    pub too_vague: bool,
+    /// Why the prompt is too vague, or what would sharpen it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// GET /investigations query parameters.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ListInvestigationsQuery {
+    /// Comma-separated tags; investigations must carry all of them (exact match).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    /// Investigation status (`InvestigationStatus::as_db_str`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Exact correlation ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Clamped to 1..=500 by the engine.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+impl ListInvestigationsQuery {
+    pub fn with_tags<S: AsRef<str>>(mut self, tags: &[S]) -> Self {
+        let joined = tags.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(",");
+        self.tags = (!joined.is_empty()).then_some(joined);
+        self
+    }
+
+    pub fn with_status(mut self, status: InvestigationStatus) -> Self {
+        self.status = Some(status.as_db_str().to_string());
+        self
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+impl ListInvestigationsQuery {
+    /// The `tags` filter split on commas, blanks dropped.
+    pub fn tag_list(&self) -> Vec<&str> {
+        self.tags
+            .as_deref()
+            .map(|t| t.split(',').filter(|t| !t.trim().is_empty()).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Validate for ListInvestigationsQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check_result(normalize_tags(&self.tag_list()), "tags");
+        if let Some(status) = &self.status {
+            errors.check_result(status.parse::<InvestigationStatus>(), "status");
+        }
+        errors.check_result(
+            normalize_correlation_id(self.correlation_id.as_deref()),
+            "correlation_id",
+        );
+        errors.finish()
+    }
+}
+
+/// GET /investigations response, newest first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ListInvestigationsResponse {
+    pub investigations: Vec<Investigation>,
+}
+
+/// GET /investigations/{id}/work_orders response, by cycle then creation time.
+/// Failed work orders carry their `failure_detail`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkOrdersResponse {
+    pub work_orders: Vec<WorkOrder>,
+}
+
+/// GET /investigate/{id}/footprint response: the fetches and searches of the
+/// investigation's work orders so far. Work orders still processing are not
+/// counted until they finish.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FootprintResponse {
+    pub investigation_id: InvestigationId,
+    pub footprint: OutboundFootprint,
+    /// Work orders whose footprint is counted.
+    pub work_orders: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_budget: Option<FetchBudget>,
+}
+
+/// GET /investigate/{id}/timeline query parameters.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TimelineQuery {
+    /// Only events recorded at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only events recorded before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    /// `next_cursor` from the previous page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<i64>,
+    /// Clamped to 1..=500 by the engine; 100 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+impl Validate for TimelineQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            errors.check(since < until, "until", "must be later than since");
+        }
+        errors.finish()
+    }
+}
+
+/// GET /investigate/{id}/timeline response: the investigation's lifecycle
+/// events in the order they were recorded — status changes, suspensions and
+/// resumptions, Analyst session starts and ends, and each work order's
+/// creation, enqueue, dequeue and completion or failure.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimelineResponse {
+    pub investigation_id: InvestigationId,
+    pub events: Vec<InvestigationEvent>,
+    /// Present when there may be more events; pass back as `cursor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<i64>,
+}
+
+/// GET /tags response, most used first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TagsResponse {
+    pub tags: Vec<TagUsage>,
+}
+
+/// GET /audit query parameters. All filters are optional and combine.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AuditQuery {
+    /// Only changes to this record: an entity, claim, relationship, work
+    /// order, assessment or investigation ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_type: Option<AuditActorType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<String>,
+    /// Tool name, or `METHOD /route` for HTTP requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Only entries recorded at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries recorded before this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    /// `next_cursor` from the previous page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<i64>,
+    /// Clamped to 1..=500 by the engine; 100 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+impl Validate for AuditQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            errors.check(since < until, "until", "must be later than since");
+        }
+        errors.finish()
+    }
+}
+
+/// GET /audit response: matching audit entries, newest first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditResponse {
+    pub entries: Vec<AuditEntry>,
+    /// Present when there may be older entries; pass back as `cursor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<i64>,
+}
+
+/// Shortest time between runs of a monitor.
+pub const MIN_MONITOR_INTERVAL_SECONDS: u64 = 60;
+
+/// POST /monitors request — subscribe to a Fetch service source.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CreateMonitorRequest {
+    pub name: String,
+    /// Fetch service source catalog ID.
+    pub source_id: String,
+    /// Source query parameters sent with every run.
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub params: serde_json::Map<String, serde_json::Value>,
+    /// Time between runs, at least `MIN_MONITOR_INTERVAL_SECONDS`.
+    pub interval_seconds: u64,
+    /// Unset creates the monitor enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strictness: Option<StrictnessProfile>,
+    /// Topics to focus extraction on (see `normalize_tags`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_tags: Vec<String>,
+    /// Unset uses `ingest.monitors.max_items_per_run`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_items_per_run: Option<u32>,
+}
+
+impl Validate for CreateMonitorRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(!self.name.trim().is_empty(), "name", "must not be empty");
+        errors.check(
+            !self.source_id.trim().is_empty(),
+            "source_id",
+            "must not be empty",
+        );
+        check_monitor_fields(
+            &mut errors,
+            Some(self.interval_seconds),
+            &self.focus_tags,
+            self.max_items_per_run,
+        );
+        errors.finish()
+    }
+}
+
+/// PATCH /monitors/{id} request. Unset fields are left as they are.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UpdateMonitorRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Replaces the query parameters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strictness: Option<StrictnessProfile>,
+    /// Replaces the focus tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus_tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_items_per_run: Option<u32>,
+}
+
+impl Validate for UpdateMonitorRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        if let Some(name) = &self.name {
+            errors.check(!name.trim().is_empty(), "name", "must not be empty");
+        }
+        check_monitor_fields(
+            &mut errors,
+            self.interval_seconds,
+            self.focus_tags.as_deref().unwrap_or_default(),
+            self.max_items_per_run,
+        );
+        errors.finish()
+    }
+}
+
+fn check_monitor_fields(
+    errors: &mut FieldErrors,
+    interval_seconds: Option<u64>,
+    focus_tags: &[String],
+    max_items_per_run: Option<u32>,
+) {
+    if let Some(interval) = interval_seconds {
+        errors.check(
+            interval >= MIN_MONITOR_INTERVAL_SECONDS,
+            "interval_seconds",
+            &format!("must be at least {}", MIN_MONITOR_INTERVAL_SECONDS),
+        );
+    }
+    errors.check_result(normalize_tags(focus_tags), "focus_tags");
+    errors.check(
+        max_items_per_run != Some(0),
+        "max_items_per_run",
+        "must be at least 1",
+    );
+}
+
+/// GET /monitors response, by name. Each monitor carries its last run's
+/// outcome and its item totals.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ListMonitorsResponse {
+    pub monitors: Vec<Monitor>,
+}
+
+/// Representation returned by GET /investigations/{id}/assessment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssessmentFormat {
+    /// The stored `Assessment` as JSON.
+    #[default]
+    Json,
+    /// A readable `text/markdown` rendering of the assessment.
+    Markdown,
+}
+
+/// GET /investigations/{id}/assessment query parameters.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AssessmentQuery {
+    #[serde(default)]
+    pub format: AssessmentFormat,
+}
+
+/// Representation returned by GET /investigations/{id}/report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// A single self-contained `text/html` page: assessment, cited evidence,
+    /// entities and an entity graph, linked by in-page anchors.
+    #[default]
+    Html,
+    /// An `application/zip` bundle of the page as `report.html` and, under
+    /// `sources/`, the text of each stored source document its claims cite,
+    /// linked from the claims citing it.
+    Zip,
+}
+
+/// GET /investigations/{id}/report query parameters.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ReportQuery {
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// GET /graph/entities/search query parameters (fulltext search over names and
+/// aliases).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntitySearchQuery {
+    pub q: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// `next_cursor` from the previous page of the same search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+impl Validate for EntitySearchQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(!self.q.trim().is_empty(), "q", "is required");
+        errors.finish()
+    }
+}
+
+/// An entity search hit. Embeddings are not included.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoredEntity {
+    pub entity: Entity,
+    pub score: f64,
+}
+
+/// GET /graph/entities/search response, best match first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntitySearchResponse {
+    pub results: Vec<ScoredEntity>,
+    /// Present when there may be more results; pass back as `cursor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// GET /graph/claims/search query parameters (fulltext search over content).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClaimSearchQuery {
+    pub q: String,
+    /// Only claims in this language (ISO 639-1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Only claims about this region: an ISO 3166 code or a name matched
+    /// against the seeded geo entities.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// Only claims whose source was rated at least this trust level when
+    /// they were ingested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_trust: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// `next_cursor` from the previous page of the same search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Return claims' source-language wording (`original_content`) where
+    /// they have one. Off by default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_original: bool,
+}
+
+impl Validate for ClaimSearchQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(!self.q.trim().is_empty(), "q", "is required");
+        errors.check(
+            self.language
+                .as_deref()
+                .is_none_or(|l| normalize_language(l).is_some()),
+            "language",
+            "must be an ISO 639-1 code",
+        );
+        if let Some(level) = self.min_trust {
+            errors.check_result(parse_trust_level(&level.into()), "min_trust");
+        }
+        errors.finish()
+    }
+}
+
+/// A claim search hit. Embeddings are not included.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoredClaim {
+    pub claim: Claim,
+    pub score: f64,
+    /// Number of assessments citing the claim.
+    #[serde(default)]
+    pub cited_in_assessments: u64,
+}
+
+/// GET /graph/claims/search response, best match first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClaimSearchResponse {
+    pub results: Vec<ScoredClaim>,
+    /// Present when there may be more results; pass back as `cursor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// GET /stats/queue response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueueStats {
+    /// One entry per priority stream, high → low.
+    pub streams: Vec<StreamStats>,
+    /// Work orders held for delayed delivery.
+    pub delayed: u64,
+    /// Queued work orders and how long they have waited, per priority, high → low.
+    #[serde(default)]
+    pub waiting: Vec<PriorityWaitStats>,
+}
+
+/// Depth of one work order priority stream.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamStats {
+    pub priority: WorkOrderPriority,
+    /// Entries in the stream, including delivered ones not yet trimmed.
+    pub length: u64,
+    /// Delivered to a Processor but not yet acknowledged.
+    pub pending: u64,
+}
+
+/// Queued (not yet started) work orders of one priority, from Postgres.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriorityWaitStats {
+    pub priority: WorkOrderPriority,
+    pub queued: u64,
+    /// Median time the queued orders have waited so far. 0 when none are queued.
+    pub wait_age_p50_seconds: f64,
+    pub wait_age_p95_seconds: f64,
+}
+
+/// GET /stats/graph response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphStats {
+    pub entities: u64,
+    pub relationships: u64,
+    pub claims: u64,
+    /// Claim counts by ISO 639-1 language; claims with no recorded language
+    /// are counted under `"unknown"`.
+    #[serde(default)]
+    pub claims_by_language: BTreeMap<String, u64>,
+}
+
+/// How much a graph consistency violation matters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencySeverity {
+    /// Data that is wrong or unreachable.
+    Error,
+    /// Data that misleads search or linking until fixed.
+    Warning,
+    /// Bookkeeping drift with no effect on answers.
+    Info,
+}
+
+impl ConsistencySeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+}
+
+/// GET /graph/consistency response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphConsistencyReport {
+    pub checks: Vec<ConsistencyCheckResult>,
+    pub duration_ms: u64,
+}
+
+impl GraphConsistencyReport {
+    /// Violations of `severity` across all checks.
+    pub fn violations(&self, severity: ConsistencySeverity) -> u64 {
+        self.checks
+            .iter()
+            .filter(|c| c.severity == severity)
+            .map(|c| c.violations)
+            .sum()
+    }
+}
+
+/// One consistency check's findings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyCheckResult {
+    pub name: String,
+    pub description: String,
+    pub severity: ConsistencySeverity,
+    pub violations: u64,
+    /// IDs of the first violating nodes or relationships.
+    pub sample_ids: Vec<String>,
+    /// Whether the check has a safe automatic repair.
+    pub repairable: bool,
+}
+
+/// What a consistency repair run fixed (or, on dry run, would fix).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphRepairReport {
+    pub dry_run: bool,
+    pub repairs: Vec<ConsistencyRepairResult>,
+}
+
+/// One repairable check's repair.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyRepairResult {
+    pub name: String,
+    /// Violations with a safe fix. Others are left for an operator.
+    pub repairable: u64,
+    /// Violations fixed. 0 on dry run.
+    pub repaired: u64,
+}
+
+/// DELETE /investigations/{id} query parameters. One of the two must be set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PurgeQuery {
+    #[serde(default)]
+    pub confirm: bool,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl Validate for PurgeQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(
+            self.confirm || self.dry_run,
+            "confirm",
+            "purge is destructive: pass confirm=true, or dry_run=true to preview",
+        );
+        errors.finish()
+    }
+}
+
+/// GET /export/stix query parameters: an entity neighborhood to export.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StixExportQuery {
+    /// Comma-separated entity IDs to export around.
+    pub entity_ids: String,
+    /// RELATES_TO hops to include beyond the seed entities.
+    #[serde(default = "default_export_depth")]
+    pub depth: u32,
+}
+
+fn default_export_depth() -> u32 {
+    1
+}
+
+impl StixExportQuery {
+    /// The seed entity IDs, or the first one that doesn't parse.
+    pub fn seeds(&self) -> Result<Vec<EntityId>, uuid::Error> {
+        self.entity_ids
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<uuid::Uuid>().map(EntityId::from_uuid))
+            .collect()
+    }
+}
+
+impl Validate for StixExportQuery {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        match self.seeds() {
+            Ok(seeds) => errors.check(!seeds.is_empty(), "entity_ids", "is required"),
+            Err(e) => errors.push("entity_ids", format!("invalid entity ID: {}", e)),
+        }
+        errors.finish()
+    }
+}
+
+/// DELETE /investigations/{id} response: what a purge removed from Postgres and
+/// Neo4j (or, on dry run, would remove).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub investigation_id: InvestigationId,
+    pub dry_run: bool,
+    pub store: StorePurgeReport,
+    pub graph: GraphPurgeReport,
+}
+
+/// Postgres rows removed (or, on dry run, that would be removed) by an investigation purge.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorePurgeReport {
+    pub work_orders: u64,
+    pub assessments: u64,
+    /// Follow-up investigations whose parent link was cleared (the children are kept).
+    pub child_investigations_detached: u64,
+    #[serde(default)]
+    pub scratchpad_sections: u64,
+    /// Claim merge records holding a copy of one of the investigation's claims.
+    #[serde(default)]
+    pub claim_merges: u64,
+}
+
+/// Graph data removed (or, on dry run, that would be removed) by an investigation purge.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphPurgeReport {
+    pub claim_ids: Vec<ClaimId>,
+    pub relationship_ids: Vec<RelationshipId>,
+    pub entity_ids: Vec<EntityId>,
+}
+
+/// POST /investigations/import response: where an archived investigation
+/// landed and how its graph data was reconciled with this engine's graph.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvestigationImportReport {
+    /// The investigation's ID here.
+    pub investigation_id: InvestigationId,
+    /// Its ID in the engine it was exported from.
+    pub source_investigation_id: InvestigationId,
+    /// Archive format version read.
+    pub format_version: u32,
+    pub work_orders: u64,
+    pub assessments: u64,
+    pub assessment_feedback: u64,
+    pub scratchpad_sections: u64,
+    pub entities_created: u64,
+    /// Archived entities dedup matched to entities already here, which were
+    /// used instead of creating duplicates.
+    #[serde(default)]
+    pub entities_matched: Vec<ImportedEntityMatch>,
+    pub relationships_created: u64,
+    /// Archived relationships between matched entities that already existed.
+    pub relationships_matched: u64,
+    pub claims_created: u64,
+    /// Records and graph items whose ID was already taken here, and were
+    /// given a new one.
+    pub ids_reassigned: u64,
+    /// References to entities, claims or relationships in neither the archive
+    /// nor this graph, left out.
+    pub references_dropped: u64,
+}
+
+/// An archived entity mapped onto an existing one by an import.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportedEntityMatch {
+    pub archived_id: EntityId,
+    pub entity_id: EntityId,
+    pub canonical_name: String,
+}
+
+/// POST /admin/reembed response: items re-embedded with the `[reembed]` model.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReembedReport {
+    pub entities: u64,
+    pub claims: u64,
+    pub relationships: u64,
+    /// Dimensions of the rebuilt vector indexes.
+    pub dimensions: u32,
+}
+
+/// POST /admin/jobs request: start a maintenance job.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateMaintenanceJobRequest {
+    pub job_type: MaintenanceJobType,
+    /// Job-specific parameters, a JSON object.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub params: serde_json::Value,
+    /// Count what the job would change without changing it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+impl Validate for CreateMaintenanceJobRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(
+            self.params.is_null() || self.params.is_object(),
+            "params",
+            "must be an object",
+        );
+        errors.finish()
+    }
+}
+
+/// GET /admin/jobs query parameters.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MaintenanceJobQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<MaintenanceJobStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+/// GET /admin/jobs response: maintenance jobs, newest first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaintenanceJobsResponse {
+    pub jobs: Vec<MaintenanceJob>,
+}
+
+/// POST /admin/jobs/{id}/cancel response.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CancelMaintenanceJobResponse {
+    pub id: MaintenanceJobId,
+    /// The job's status after the cancel: `cancelled` for a job that hadn't
+    /// started, `running` for one that stops after its current batch.
+    pub status: MaintenanceJobStatus,
+}
+
+/// POST /admin/stubs/resolve query: limit the pass to stubs one
+/// investigation's claims mention.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StubResolutionQuery {
+    #[serde(default)]
+    pub investigation_id: Option<InvestigationId>,
+}
+
+/// POST /admin/stubs/resolve response: what a stub resolution pass did with
+/// each stub it looked at.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StubResolutionReport {
+    /// Stubs merged into a full entity that matches them.
+    pub merged: Vec<StubMerge>,
+    /// Stubs queued for a `profile_entity` work order.
+    pub enqueued: Vec<StubEnrichment>,
+    /// Unreferenced stubs deleted.
+    pub deleted: Vec<EntityId>,
+    /// Stubs left for a later run: over the enrichment cap, already being
+    /// profiled, or mentioned only by claims outside any investigation.
+    pub deferred: u64,
+}
+
+/// A stub merged into the full entity it turned out to be.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StubMerge {
+    pub stub_id: EntityId,
+    pub target_id: EntityId,
+    /// `name` or `embedding`.
+    pub matched_by: String,
+    pub similarity: f64,
+}
+
+/// A stub queued to be profiled.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StubEnrichment {
+    pub stub_id: EntityId,
+    pub work_order_id: WorkOrderId,
+    /// Investigation the work order runs under: that of the stub's most recent
+    /// claim.
+    pub investigation_id: InvestigationId,
+}
+
+/// POST /config/prompts/reload response: the hash of each prompt now in use.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptsReport {
+    /// Hex SHA-256 of each prompt template, by name (e.g. "analyst").
+    pub prompts: BTreeMap<String, String>,
+}
+
+/// POST /admin/read_only request: turn the shared read-only toggle on or off.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadOnlyRequest {
+    pub enabled: bool,
+}
+
+/// POST /admin/read_only response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadOnlyStatus {
+    /// Whether this replica is read-only now.
+    pub read_only: bool,
+    /// The shared toggle, as every replica sees it.
+    pub toggle: bool,
+    /// This replica is read-only from startup (`maintenance.read_only` or
+    /// `ENGINE_READ_ONLY`), whatever the toggle says.
+    pub pinned: bool,
+}
+
+/// POST /admin/flags request: override a feature flag for every replica, or
+/// clear its override with `null`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagOverrideRequest {
+    pub flag: FeatureFlag,
+    pub enabled: Option<bool>,
+}
+
+/// One feature flag's state on this replica.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagStatus {
+    pub flag: FeatureFlag,
+    /// Effective value: the override if set, otherwise the default.
+    pub enabled: bool,
+    /// Value from `[flags.defaults]`.
+    pub default: bool,
+    #[serde(rename = "override")]
+    pub override_value: Option<bool>,
+}
+
+/// GET and POST /admin/flags response: every feature flag.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagsReport {
+    pub flags: Vec<FlagStatus>,
+}
+
+/// GET /admin/pii/redactions query.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PiiRedactionQuery {
+    /// Days to report, counting today. Clamped to 1..=365 by the engine; 30
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+}
+
+/// Personal data of one type found in claim content, in total or on one day.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PiiRedactionCounts {
+    /// Matches found.
+    pub matches: u64,
+    /// Claims with at least one match.
+    pub claims: u64,
+    /// Of those claims, the ones refused under the `reject` policy.
+    pub rejected_claims: u64,
+}
+
+/// One type's counts on one day (UTC).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PiiRedactionDay {
+    pub date: NaiveDate,
+    /// Detector name: `email`, `phone`, `national_id` or a configured pattern.
+    pub kind: String,
+    #[serde(flatten)]
+    pub counts: PiiRedactionCounts,
+}
+
+/// GET /admin/pii/redactions response: personal data found in claims over
+/// the last `days` days. Counts only; the values are never stored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PiiRedactionReport {
+    /// Whether claims are scrubbed now.
+    pub enabled: bool,
+    pub policy: PiiPolicy,
+    pub days: u32,
+    /// Totals over the period, by type.
+    pub totals: BTreeMap<String, PiiRedactionCounts>,
+    /// Newest day first; days without matches are left out.
+    pub daily: Vec<PiiRedactionDay>,
+}
+
+/// GET /telemetry/rollups query. All filters are optional and combine.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TelemetryRollupsQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<TelemetryRole>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hash: Option<String>,
+    /// Only windows starting at or after this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Most rollups returned. Clamped to 1..=1000 by the engine; 200 when
+    /// unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+}
+
+/// GET /telemetry/rollups response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryRollupsResponse {
+    /// Newest window first.
+    pub rollups: Vec<PromptRollup>,
+}
+
+/// POST /callbacks/enrichment/{token} response: the request the callback
+/// completed. Providers post `{"status": "completed", "result": ...}` or
+/// `{"status": "failed", "error": "..."}`, signed with their shared secret.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnrichmentCallbackResponse {
+    pub request_id: EnrichmentRequestId,
+    /// `completed` or `failed`.
+    pub status: EnrichmentStatus,
+}
+
+/// PUT /admin/entities/{id}/trust_level request: rate a source entity, or
+/// clear its rating with `null`. Responds with the updated entity.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustLevelRequest {
+    /// From `MIN_TRUST_LEVEL` to `MAX_TRUST_LEVEL`.
+    pub trust_level: Option<u8>,
+}
+
+impl Validate for TrustLevelRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        if let Some(level) = self.trust_level {
+            errors.check_result(parse_trust_level(&level.into()), "trust_level");
+        }
+        errors.finish()
+    }
+}
+
+/// Longest feedback comment, in characters.
+pub const MAX_FEEDBACK_COMMENTS_CHARS: usize = 10_000;
+/// Most claims one feedback entry may dispute.
+pub const MAX_DISPUTED_CLAIMS: usize = 100;
+
+/// POST /assessments/{id}/feedback request: a reader's verdict on an
+/// assessment. Responds with the stored feedback.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssessmentFeedbackRequest {
+    pub rating: FeedbackRating,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    /// Claims the reader disputes; each is marked disputed in the graph, and
+    /// the assessments citing it are flagged for review.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disputed_claim_ids: Vec<ClaimId>,
+}
+
+impl Validate for AssessmentFeedbackRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(
+            self.comments
+                .as_ref()
+                .is_none_or(|c| c.chars().count() <= MAX_FEEDBACK_COMMENTS_CHARS),
+            "comments",
+            &format!("must be at most {} characters", MAX_FEEDBACK_COMMENTS_CHARS),
+        );
+        errors.check(
+            self.disputed_claim_ids.len() <= MAX_DISPUTED_CLAIMS,
+            "disputed_claim_ids",
+            &format!("must list at most {} claims", MAX_DISPUTED_CLAIMS),
+        );
+        errors.finish()
+    }
+}
+
+/// GET /assessments/{id}/feedback response, oldest first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AssessmentFeedbackResponse {
+    pub feedback: Vec<AssessmentFeedback>,
+    pub summary: FeedbackSummary,
+}
+
+/// POST /selftest response: outcome of a canary investigation run through the
+/// full pipeline.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    /// Every stage passed.
+    pub passed: bool,
+    /// The canary investigation, if one was created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub investigation_id: Option<InvestigationId>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Stages in pipeline order.
+    pub stages: Vec<SelfTestStage>,
+}
+
+/// One checked stage of a self-test run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SelfTestStage {
+    /// `analyst_session`, `work_order_round_trip`, `graph_write`, `assessment` or `purge`.
+    pub name: String,
+    pub passed: bool,
+    /// What was found, or why the stage failed.
+    pub detail: String,
+}
+
+/// GET /recovery/last response: what startup recovery did with the
+/// investigations a restart left non-terminal. Reported while recovery is
+/// still under way, with `completed_at` unset.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryReport {
+    pub started_at: DateTime<Utc>,
+    /// Set once every investigation has an outcome.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Investigations found, by status at startup.
+    pub by_prior_status: BTreeMap<String, u32>,
+    pub resumed: u32,
+    pub suspended: u32,
+    pub skipped: u32,
+    pub errored: u32,
+    /// In the order they were recovered.
+    pub investigations: Vec<RecoveredInvestigation>,
+}
+
+/// What startup recovery did with one investigation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveredInvestigation {
+    pub investigation_id: InvestigationId,
+    pub prior_status: InvestigationStatus,
+    pub outcome: RecoveryOutcome,
+    /// Why it was skipped or errored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// When its driver started, if resumed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryOutcome {
+    /// Not handled yet: waiting for the stagger delay or a free slot.
+    Pending,
+    /// A driver took it over.
+    Resumed,
+    /// Left Suspended for an operator to resume (`recovery.auto_resume` off).
+    Suspended,
+    /// Not resumed: a self-test, owned by another task, or left Pending.
+    Skipped,
+    /// A store or queue error stopped its recovery. Left as found.
+    Errored,
+}
+
+impl RecoveryOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Resumed => "resumed",
+            Self::Suspended => "suspended",
+            Self::Skipped => "skipped",
+            Self::Errored => "errored",
+        }
+    }
+}
+
+/// POST /admin/seed/geo response: seeded country and region entities written.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeoSeedReport {
+    /// Records in the seed file.
+    pub records: u64,
+    pub created: u64,
+    /// Existing seeded entities refreshed, plus unseeded entities adopted by name.
+    pub updated: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{
+        AttributionDepth, InformationType, InvestigationEventKind, MonitorRun, MonitorRunStatus,
+    };
+
+    /// Serialize `value`, decode it back and check nothing was lost.
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) {
+        let encoded = serde_json::to_value(value).unwrap();
+        let decoded: T = serde_json::from_value(encoded.clone())
+            .unwrap_or_else(|e| panic!("{} does not decode: {}", std::any::type_name::<T>(), e));
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            encoded,
+            "{} does not round-trip",
+            std::any::type_name::<T>()
+        );
+    }
+
+    fn triage() -> PromptTriage {
+        PromptTriage {
+            scope: PromptScope::Narrow,
+            focus_entities: vec!["Northwind Star".into()],
+            max_cycles: 4,
+            strictness: StrictnessProfile::Strict,
+            too_vague: false,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_request_types_round_trip() {
+        round_trip(&InvestigateRequest {
+            prompt: "Who operates the Northwind Star?".into(),
+            tags: vec!["maritime".into()],
+            preflight: Some(true),
+            suggest_only: true,
+            priority_cap: Some(WorkOrderPriority::Normal),
+            correlation_id: Some("CASE-1".into()),
+            fetch_budget: Some(FetchBudget {
+                max_fetches: Some(40),
+                max_fetches_per_domain: None,
+            }),
+            as_of: Some(Utc::now() - chrono::Duration::days(30)),
+        });
+        round_trip(&InvestigateRequest::default());
+        round_trip(
+            &ListInvestigationsQuery::default()
+                .with_tags(&["maritime", "sanctions"])
+                .with_status(InvestigationStatus::Completed)
+                .with_correlation_id("CASE-1")
+                .with_limit(10),
+        );
+        round_trip(&AssessmentQuery {
+            format: AssessmentFormat::Markdown,
+        });
+        round_trip(&ReportQuery::default());
+        round_trip(&ReportQuery {
+            format: ReportFormat::Zip,
+        });
+        round_trip(&EntitySearchQuery {
+            q: "Northwind".into(),
+            kind: Some("vessel".into()),
+            limit: Some(5),
+            cursor: Some("abc".into()),
+        });
+        round_trip(&ClaimSearchQuery {
+            q: "reflagged".into(),
+            language: Some("ru".into()),
+            region: Some("UA".into()),
+            min_trust: Some(3),
+            limit: None,
+            cursor: None,
+            include_original: true,
+        });
+        round_trip(&PurgeQuery {
+            confirm: false,
+            dry_run: true,
+        });
+        round_trip(&TimelineQuery {
+            since: Some(Utc::now()),
+            until: None,
+            cursor: Some(42),
+            limit: Some(50),
+        });
+        round_trip(&StixExportQuery {
+            entity_ids: EntityId::new().to_string(),
+            depth: 2,
+        });
+    }
+
+    #[test]
+    fn test_response_types_round_trip() {
+        let investigation = Investigation::new("Who operates the Northwind Star?".into());
+        let source = Entity::new("Reuters".into(), "organization".into());
+        let claim = Claim::new(
+            "Northwind Star was reflagged.".into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            source.id,
+        );
+        let selftest = SelfTestReport {
+            passed: false,
+            investigation_id: Some(investigation.id),
+            started_at: Utc::now(),
+            duration_ms: 1200,
+            stages: vec![SelfTestStage {
+                name: "graph_write".into(),
+                passed: false,
+                detail: "timed out".into(),
+            }],
+        };
+
+        round_trip(&ErrorResponse::new("Investigation not found"));
+        round_trip(&ErrorResponse::invalid(vec![FieldError::new(
+            "prompt",
+            "must not be empty",
+        )]));
+        round_trip(&HealthResponse {
+            status: HealthStatus::Degraded,
+            services: ServiceHealth {
+                neo4j: HealthStatus::Healthy,
+                postgres: HealthStatus::Healthy,
+                redis: HealthStatus::Unhealthy,
+            },
+            tasks: vec![TaskStatus {
+                name: "queue_reaper".into(),
+                critical: true,
+                running: 1,
+                panics: 2,
+                restarts: 2,
+            }],
+            selftest: Some(selftest.clone()),
+            prompts: BTreeMap::from([("analyst".into(), "ab12".into())]),
+            read_only: true,
+        });
+        round_trip(&ReadOnlyStatus {
+            read_only: true,
+            toggle: false,
+            pinned: true,
+        });
+        round_trip(&FlagOverrideRequest {
+            flag: FeatureFlag::WriteCoalescing,
+            enabled: None,
+        });
+        round_trip(&FlagsReport {
+            flags: vec![FlagStatus {
+                flag: FeatureFlag::EntityLinking,
+                enabled: false,
+                default: true,
+                override_value: Some(false),
+            }],
+        });
+        let counts = PiiRedactionCounts {
+            matches: 3,
+            claims: 2,
+            rejected_claims: 0,
+        };
+        round_trip(&PiiRedactionReport {
+            enabled: true,
+            policy: PiiPolicy::Redact,
+            days: 30,
+            totals: BTreeMap::from([("email".to_string(), counts.clone())]),
+            daily: vec![PiiRedactionDay {
+                date: NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(),
+                kind: "email".into(),
+                counts,
+            }],
+        });
+        round_trip(&InvestigateResponse {
+            investigation_id: investigation.id,
+            status: InvestigationStatus::Pending,
+            message: "Investigation started.".into(),
+            triage: Some(triage()),
+        });
+        round_trip(&PreflightResponse { triage: triage() });
+        round_trip(&ListInvestigationsResponse {
+            investigations: vec![investigation.clone()],
+        });
+        round_trip(&WorkOrdersResponse {
+            work_orders: vec![WorkOrder::new(
+                investigation.id,
+                "Find the registry entry".into(),
+                WorkOrderPriority::High,
+            )],
+        });
+        round_trip(&ListMonitorsResponse {
+            monitors: vec![Monitor {
+                id: crate::MonitorId::new(),
+                name: "Port state control detentions".into(),
+                source_id: "rss".into(),
+                params: json!({ "feed_url": "https://example.org/feed.xml" })
+                    .as_object()
+                    .cloned()
+                    .unwrap(),
+                interval_seconds: 3600,
+                enabled: true,
+                strictness: StrictnessProfile::Strict,
+                focus_tags: vec!["maritime".into()],
+                max_items_per_run: 20,
+                investigation_id: investigation.id,
+                high_water_mark: Some(Utc::now()),
+                next_run_at: Utc::now(),
+                last_run: Some(MonitorRun {
+                    at: Utc::now(),
+                    status: MonitorRunStatus::Failed,
+                    error: Some("Fetch service returned 502".into()),
+                    seen: 0,
+                    ingested: 0,
+                    skipped: 0,
+                    deferred: 0,
+                }),
+                items_seen: 40,
+                items_ingested: 31,
+                items_skipped: 9,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+        });
+        round_trip(&FootprintResponse {
+            investigation_id: investigation.id,
+            footprint: OutboundFootprint {
+                fetches: 3,
+                fetches_by_domain: BTreeMap::from([("example.com".into(), 3)]),
+                ..Default::default()
+            },
+            work_orders: 2,
+            fetch_budget: Some(FetchBudget::default()),
+        });
+        round_trip(&TimelineResponse {
+            investigation_id: investigation.id,
+            events: vec![InvestigationEvent {
+                id: 7,
+                investigation_id: investigation.id,
+                kind: InvestigationEventKind::StatusChanged,
+                payload: json!({"from": "pending", "to": "analyst_running"}),
+                created_at: Utc::now(),
+            }],
+            next_cursor: Some(7),
+        });
+        round_trip(&TagsResponse {
+            tags: vec![TagUsage {
+                tag: "maritime".into(),
+                investigations: 3,
+                assessments: 1,
+            }],
+        });
+        round_trip(&EntitySearchResponse {
+            results: vec![ScoredEntity {
+                entity: source.clone(),
+                score: 2.5,
+            }],
+            next_cursor: Some("next".into()),
+        });
+        round_trip(&ClaimSearchResponse {
+            results: vec![ScoredClaim {
+                claim: claim.clone(),
+                score: 1.0,
+                cited_in_assessments: 2,
+            }],
+            next_cursor: None,
+        });
+        round_trip(&AssessmentFeedbackResponse {
+            feedback: vec![AssessmentFeedback {
+                id: 1,
+                assessment_id: crate::AssessmentId::new(),
+                rating: FeedbackRating::Partially,
+                comments: Some("Ownership changed in 2023.".into()),
+                disputed_claim_ids: vec![claim.id],
+                submitted_by: Some("3f2a9c01b7de".into()),
+                created_at: Utc::now(),
+            }],
+            summary: FeedbackSummary {
+                partially: 1,
+                ..Default::default()
+            },
+        });
+        round_trip(&QueueStats {
+            streams: vec![StreamStats {
+                priority: WorkOrderPriority::High,
+                length: 4,
+                pending: 1,
+            }],
+            delayed: 2,
+            waiting: vec![PriorityWaitStats {
+                priority: WorkOrderPriority::High,
+                queued: 3,
+                wait_age_p50_seconds: 1.5,
+                wait_age_p95_seconds: 9.0,
+            }],
+        });
+        round_trip(&GraphStats {
+            entities: 10,
+            relationships: 4,
+            claims: 22,
+            claims_by_language: [("en".to_string(), 20), ("unknown".to_string(), 2)].into(),
+        });
+        let consistency = GraphConsistencyReport {
+            checks: vec![ConsistencyCheckResult {
+                name: "orphaned_claims".into(),
+                description: "Claims with no source entity".into(),
+                severity: ConsistencySeverity::Error,
+                violations: 2,
+                sample_ids: vec![ClaimId::new().to_string()],
+                repairable: false,
+            }],
+            duration_ms: 40,
+        };
+        assert_eq!(consistency.violations(ConsistencySeverity::Error), 2);
+        assert_eq!(consistency.violations(ConsistencySeverity::Info), 0);
+        round_trip(&consistency);
+        round_trip(&GraphRepairReport {
+            dry_run: true,
+            repairs: vec![ConsistencyRepairResult {
+                name: "overdue_stubs".into(),
+                repairable: 3,
+                repaired: 0,
+            }],
+        });
+        round_trip(&PurgeReport {
+            investigation_id: investigation.id,
+            dry_run: true,
+            store: StorePurgeReport {
+                work_orders: 3,
+                assessments: 1,
+                child_investigations_detached: 0,
+                scratchpad_sections: 2,
+                claim_merges: 1,
+            },
+            graph: GraphPurgeReport {
+                claim_ids: vec![ClaimId::new()],
+                relationship_ids: vec![RelationshipId::new()],
+                entity_ids: vec![source.id],
+            },
+        });
+        round_trip(&ReembedReport {
+            entities: 1,
+            claims: 2,
+            relationships: 3,
+            dimensions: 768,
+        });
+        round_trip(&CreateMaintenanceJobRequest {
+            job_type: MaintenanceJobType::ReembedClaims,
+            params: serde_json::json!({"batch_size": 50}),
+            dry_run: true,
+        });
+        round_trip(&MaintenanceJobsResponse {
+            jobs: vec![MaintenanceJob::new(
+                MaintenanceJobType::NormalizeKinds,
+                serde_json::Value::Null,
+                false,
+            )],
+        });
+        round_trip(&PromptsReport::default());
+        round_trip(&selftest);
+        round_trip(&RecoveryReport {
+            started_at: Utc::now(),
+            completed_at: None,
+            by_prior_status: BTreeMap::from([("processing".into(), 1)]),
+            resumed: 1,
+            suspended: 0,
+            skipped: 0,
+            errored: 0,
+            investigations: vec![RecoveredInvestigation {
+                investigation_id: investigation.id,
+                prior_status: InvestigationStatus::Processing,
+                outcome: RecoveryOutcome::Resumed,
+                detail: None,
+                resumed_at: Some(Utc::now()),
+            }],
+        });
+        round_trip(&GeoSeedReport {
+            records: 250,
+            created: 10,
+            updated: 240,
+        });
+    }
+
+    fn fields(result: Result<(), Vec<FieldError>>) -> Vec<String> {
+        result.unwrap_err().into_iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn test_investigate_request_validation() {
+        let valid = InvestigateRequest {
+            prompt: "Who operates the Northwind Star?".into(),
+            tags: vec!["maritime".into()],
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = InvestigateRequest {
+            prompt: "  ".into(),
+            tags: vec!["a".repeat(200)],
+            correlation_id: Some("x".repeat(500)),
+            fetch_budget: Some(FetchBudget {
+                max_fetches: Some(0),
+                max_fetches_per_domain: Some(5),
+            }),
+            as_of: Some(Utc::now() + chrono::Duration::days(1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(invalid.validate()),
+            [
+                "prompt",
+                "tags",
+                "correlation_id",
+                "fetch_budget.max_fetches",
+                "as_of"
+            ],
+            "every failing field is reported"
+        );
+    }
+
+    #[test]
+    fn test_monitor_request_validation() {
+        let valid = CreateMonitorRequest {
+            name: "Port state control detentions".into(),
+            source_id: "rss".into(),
+            interval_seconds: 3600,
+            focus_tags: vec!["maritime".into()],
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = CreateMonitorRequest {
+            name: " ".into(),
+            source_id: String::new(),
+            interval_seconds: 5,
+            max_items_per_run: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(invalid.validate()),
+            ["name", "source_id", "interval_seconds", "max_items_per_run"]
+        );
+
+        assert!(UpdateMonitorRequest::default().validate().is_ok());
+        let update = UpdateMonitorRequest {
+            interval_seconds: Some(30),
+            focus_tags: Some(vec!["a".repeat(200)]),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(update.validate()),
+            ["interval_seconds", "focus_tags"]
+        );
+    }
+
+    #[test]
+    fn test_trust_level_request_validation() {
+        for trust_level in [None, Some(1), Some(5)] {
+            assert!(TrustLevelRequest { trust_level }.validate().is_ok());
+        }
+        for trust_level in [Some(0), Some(6)] {
+            assert_eq!(
+                fields(TrustLevelRequest { trust_level }.validate()),
+                ["trust_level"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_assessment_feedback_request_validation() {
+        let valid = AssessmentFeedbackRequest {
+            rating: FeedbackRating::Inaccurate,
+            comments: Some("The vessel was sold in 2023.".into()),
+            disputed_claim_ids: vec![ClaimId::new()],
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = AssessmentFeedbackRequest {
+            rating: FeedbackRating::Accurate,
+            comments: Some("x".repeat(MAX_FEEDBACK_COMMENTS_CHARS + 1)),
+            disputed_claim_ids: (0..=MAX_DISPUTED_CLAIMS).map(|_| ClaimId::new()).collect(),
+        };
+        assert_eq!(
+            fields(invalid.validate()),
+            ["comments", "disputed_claim_ids"]
+        );
+
+        let parsed: AssessmentFeedbackRequest =
+            serde_json::from_value(json!({"rating": "partially"})).unwrap();
+        assert_eq!(parsed.rating, FeedbackRating::Partially);
+        assert!(
+            serde_json::from_value::<AssessmentFeedbackRequest>(json!({"rating": "meh"})).is_err()
+        );
+    }
+
+    #[test]
+    fn test_update_investigation_validation() {
+        assert!(UpdateInvestigationRequest::default().validate().is_ok());
+        let update = UpdateInvestigationRequest {
+            version: 3,
+            prompt: Some("  ".into()),
+            focus_entities: Some(vec!["Crius".into(), " ".into()]),
+            max_cycles: Some(0),
+            tags: Some(vec!["a".repeat(200)]),
+            ..Default::default()
+        };
+        assert_eq!(
+            fields(update.validate()),
+            ["prompt", "focus_entities", "max_cycles", "tags"]
+        );
+    }
+
+    #[test]
+    fn test_query_validation() {
+        let list = ListInvestigationsQuery {
+            status: Some("finished".into()),
+            ..Default::default()
+        };
+        assert_eq!(fields(list.validate()), ["status"]);
+        assert!(ListInvestigationsQuery::default()
+            .with_status(InvestigationStatus::Failed)
+            .validate()
+            .is_ok());
+
+        let search = EntitySearchQuery {
+            q: " ".into(),
+            kind: None,
+            limit: None,
+            cursor: None,
+        };
+        assert_eq!(fields(search.validate()), ["q"]);
+        let search = ClaimSearchQuery {
+            q: "reflagged".into(),
+            language: Some("english".into()),
+            region: None,
+            min_trust: Some(9),
+            limit: None,
+            cursor: None,
+            include_original: false,
+        };
+        assert_eq!(fields(search.validate()), ["language", "min_trust"]);
+
+        assert_eq!(fields(PurgeQuery::default().validate()), ["confirm"]);
+
+        let job = CreateMaintenanceJobRequest {
+            job_type: MaintenanceJobType::ReembedEntities,
+            params: serde_json::Value::Null,
+            dry_run: false,
+        };
+        assert!(job.validate().is_ok());
+        let job = CreateMaintenanceJobRequest {
+            params: serde_json::json!([1, 2]),
+            ..job
+        };
+        assert_eq!(fields(job.validate()), ["params"]);
+
+        let now = Utc::now();
+        let timeline = TimelineQuery {
+            since: Some(now),
+            until: Some(now - chrono::Duration::minutes(5)),
+            ..Default::default()
+        };
+        assert_eq!(fields(timeline.validate()), ["until"]);
+        assert!(TimelineQuery::default().validate().is_ok());
+
+        let stix = StixExportQuery {
+            entity_ids: "not-a-uuid".into(),
+            depth: 1,
+        };
+        assert_eq!(fields(stix.validate()), ["entity_ids"]);
+        let stix = StixExportQuery {
+            entity_ids: ", ,".into(),
+            depth: 1,
+        };
+        assert_eq!(fields(stix.validate()), ["entity_ids"]);
+    }
+
+    #[test]
+    fn test_invalid_error_envelope() {
+        let body = serde_json::to_value(ErrorResponse::invalid(vec![
+            FieldError::new("prompt", "must not be empty"),
+            FieldError::new("tags", "too long"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "error": "Invalid request: prompt: must not be empty; tags: too long",
+                "fields": [
+                    {"field": "prompt", "message": "must not be empty"},
+                    {"field": "tags", "message": "too long"}
+                ]
+            })
+        );
+        // Other errors carry no `fields`.
+        assert_eq!(
+            serde_json::to_value(ErrorResponse::new("Internal error")).unwrap(),
+            json!({"error": "Internal error"})
+        );
+    }
+}
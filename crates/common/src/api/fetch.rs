@@ -1,6 +1,31 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::validate::FieldErrors;
+use super::{FieldError, HealthStatus, Validate};
+
+/// Fetch service GET /health response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthResponse {
+    pub status: HealthStatus,
+}
+
+/// Record an error unless `url` is an absolute http(s) URL.
+fn check_url(errors: &mut FieldErrors, url: &str) {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        Ok(parsed) => errors.push(
+            "url",
+            format!(
+                "unsupported scheme {:?}: use http or https",
+                parsed.scheme()
+            ),
+        ),
+        Err(e) => errors.push("url", format!("not a valid URL: {}", e)),
+    }
+}
+
 /// POST /fetch request — raw HTTP fetch.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FetchRequest {
@@ -18,15 +43,94 @@ pub struct FetchOptions {
     /// Additional headers as key-value pairs.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub headers: Option<std::collections::HashMap<String, String>>,
+    /// How to retrieve the page. Defaults to a plain HTTP fetch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render: Option<RenderMode>,
+    /// CSS selector to wait for when rendering in a browser. Without one the
+    /// render waits for network idle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_for: Option<String>,
+    /// What to return for HTML pages. Defaults to extracted readable text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract: Option<ExtractMode>,
+}
+
+impl Validate for FetchRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        check_url(&mut errors, &self.url);
+        if let Some(options) = &self.options {
+            errors.check(
+                options.timeout_ms != Some(0),
+                "options.timeout_ms",
+                "must be greater than 0",
+            );
+            errors.check(
+                options
+                    .wait_for
+                    .as_deref()
+                    .is_none_or(|s| !s.trim().is_empty()),
+                "options.wait_for",
+                "must not be empty",
+            );
+        }
+        errors.finish()
+    }
+}
+
+/// How the fetch service turns an HTML document into response content.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractMode {
+    /// Readable text with scripts, navigation, and boilerplate stripped.
+    #[default]
+    Readable,
+    /// The document body exactly as retrieved.
+    Raw,
+}
+
+/// Fetch rendering backend.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderMode {
+    /// Plain HTTP GET of the raw document.
+    #[default]
+    Plain,
+    /// Load in a headless browser and return the DOM after scripts run.
+    Browser,
 }
 
 /// POST /fetch response.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FetchResponse {
     pub content: String,
+    /// Tables found in the document, in document order. Their text is also
+    /// flattened into `content`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<ExtractedTable>,
     pub metadata: FetchMetadata,
 }
 
+/// A table extracted from a fetched document: HTML `<table>` markup, or
+/// column-aligned plain text.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractedTable {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// Column headers; empty when the table has no header row.
+    #[serde(default)]
+    pub headers: Vec<String>,
+    /// Body rows, each padded or cut to the column count.
+    pub rows: Vec<Vec<String>>,
+    /// Body rows dropped by the Fetch service's per-table row cap.
+    #[serde(default)]
+    pub omitted_rows: usize,
+    /// Set when the columns were inferred with low confidence — rows of
+    /// layout text that didn't split evenly — so cells may be misaligned.
+    #[serde(default)]
+    pub best_effort: bool,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FetchMetadata {
     pub status_code: u16,
@@ -36,6 +140,49 @@ pub struct FetchMetadata {
     /// Whether the response was served from cache.
     #[serde(default)]
     pub cached: bool,
+    /// Whether the content came from a headless browser render. False when a
+    /// browser render was requested but fell back to a plain fetch.
+    #[serde(default)]
+    pub rendered: bool,
+    /// ISO 639-1 language the document declares (HTML `lang` or
+    /// content-language meta), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>,
+    /// Whether the response was fetched with a configured credential for
+    /// the domain (a licensed, paywalled or API-keyed source).
+    #[serde(default)]
+    pub authenticated: bool,
+    /// How long the Fetch service caches the response, and why. None when
+    /// the origin forbade caching (`Cache-Control: no-store`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<CacheTtl>,
+    /// The HTML document's title (`og:title`, else `<title>`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// When the content was retrieved from the origin; for a cached copy,
+    /// when it was first fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+/// Time-to-live of a cached fetch: a cached copy is at most this old.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheTtl {
+    pub seconds: u64,
+    pub source: CacheTtlSource,
+}
+
+/// Where a cache TTL came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheTtlSource {
+    /// The origin's Cache-Control or Expires header, clamped to the
+    /// service's floor and ceiling.
+    Origin,
+    /// The service default; the origin sent no caching hints.
+    Default,
+    /// The source's entry in the Fetch service's TTL overrides.
+    SourceOverride,
 }
 
 /// POST /browse request — simple browser-automated render.
@@ -55,6 +202,21 @@ pub struct BrowseOptions {
     pub timeout_ms: Option<u64>,
 }
 
+impl Validate for BrowseRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        check_url(&mut errors, &self.url);
+        if let Some(options) = &self.options {
+            errors.check(
+                options.timeout_ms != Some(0),
+                "options.timeout_ms",
+                "must be greater than 0",
+            );
+        }
+        errors.finish()
+    }
+}
+
 /// POST /browse response.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BrowseResponse {
@@ -70,6 +232,19 @@ pub struct SearchRequest {
     pub num_results: Option<usize>,
 }
 
+impl Validate for SearchRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        errors.check(!self.query.trim().is_empty(), "query", "must not be empty");
+        errors.check(
+            self.num_results != Some(0),
+            "num_results",
+            "must be at least 1",
+        );
+        errors.finish()
+    }
+}
+
 /// POST /search response.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SearchResponse {
@@ -85,6 +260,115 @@ pub struct SearchResult {
     pub snippet: String,
 }
 
+/// GET /cache/entry query parameters.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CacheEntryQuery {
+    pub url: String,
+}
+
+/// GET /cache/entry response: the cached variants held for a URL.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntryResponse {
+    /// The URL as normalized for caching.
+    pub url: String,
+    /// Largest first.
+    pub variants: Vec<CacheVariant>,
+    pub total_bytes: usize,
+}
+
+/// One cached variant of a URL.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheVariant {
+    /// Body-affecting options; empty for a default fetch.
+    pub fingerprint: String,
+    pub age_seconds: u64,
+    pub size_bytes: usize,
+    pub status_code: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+/// POST /sitemap request: list a site's pages from its sitemap.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SitemapRequest {
+    /// The sitemap (or sitemap index), plain or gzipped.
+    pub url: String,
+    /// Only pages whose URL starts with this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Only pages last modified at or after this. Pages without a lastmod
+    /// are left out when `since` or `until` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+    /// Only pages last modified at or before this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<DateTime<Utc>>,
+    /// Most entries returned, newest first. Defaults to 100.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl Validate for SitemapRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut errors = FieldErrors::default();
+        check_url(&mut errors, &self.url);
+        errors.check(
+            self.prefix.as_deref().is_none_or(|p| !p.trim().is_empty()),
+            "prefix",
+            "must not be empty",
+        );
+        errors.check(
+            !matches!((self.since, self.until), (Some(since), Some(until)) if since > until),
+            "until",
+            "must not be before since",
+        );
+        errors.check(self.limit != Some(0), "limit", "must be at least 1");
+        errors.finish()
+    }
+}
+
+/// POST /sitemap response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SitemapResponse {
+    pub url: String,
+    /// Matching pages, newest first, undated last.
+    pub entries: Vec<SitemapEntry>,
+    /// Pages matching the filters, before `limit`.
+    pub matched: usize,
+    /// Sitemap files read, counting the requested one.
+    pub sitemaps_fetched: usize,
+    /// Sitemaps listed by an index but not read: past the nesting or file
+    /// cap, or not modified since `since`.
+    pub sitemaps_skipped: usize,
+    /// Entries or files that were skipped or only partly understood.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// A page listed in a sitemap.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SitemapEntry {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lastmod: Option<DateTime<Utc>>,
+    /// The sitemap's change frequency hint (`daily`, `monthly`, ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changefreq: Option<String>,
+}
+
+/// What querying a catalog source returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    /// Items with their content.
+    #[default]
+    Adapter,
+    /// A site section's sitemap: links to pages, with their last-modified
+    /// dates, to fetch. Queried with `prefix`, `query` (URL substring),
+    /// `since`, `until` and `limit`.
+    Sitemap,
+}
+
 /// Source adapter catalog entry from GET /sources.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SourceInfo {
@@ -92,9 +376,87 @@ pub struct SourceInfo {
     pub name: String,
     pub description: String,
     #[serde(default)]
+    pub kind: SourceKind,
+    #[serde(default)]
     pub capabilities: Vec<String>,
+    /// Default trust level for the publication entity of this source's
+    /// items, from 1 to 5. None leaves it unrated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_level: Option<u8>,
+    /// How the source's queries have gone lately. None from a Fetch service
+    /// that doesn't track it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<SourceHealth>,
+}
+
+/// A catalog source's recent query outcomes, over a rolling seven days.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceHealth {
+    pub status: SourceHealthStatus,
+    /// Share of the last seven days' queries that succeeded, from 0 to 1.
+    /// None when there were none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_rate_7d: Option<f64>,
+    /// Queries in the last seven days.
+    #[serde(default)]
+    pub queries_7d: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error_at: Option<DateTime<Utc>>,
+    /// The most recent failure's message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// A catalog source's health, from its seven-day success rate.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceHealthStatus {
+    /// Nearly every recent query succeeded.
+    Healthy,
+    /// A noticeable share of recent queries failed.
+    Degraded,
+    /// Most recent queries failed.
+    Failing,
+    /// No queries in the last seven days.
+    #[default]
+    Unknown,
 }
 
+impl SourceHealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Failing => "failing",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Source query parameter asking for items published at or after a time
+/// (RFC 3339). Set by monitors from their high-water mark.
+pub const SOURCE_QUERY_SINCE: &str = "since";
+
+/// Source query parameter asking for items published at or before a time
+/// (RFC 3339).
+pub const SOURCE_QUERY_UNTIL: &str = "until";
+
+/// Source query parameter capping the number of results.
+pub const SOURCE_QUERY_LIMIT: &str = "limit";
+
+/// Source query parameter restricting sitemap sources to URLs under a prefix.
+pub const SOURCE_QUERY_PREFIX: &str = "prefix";
+
+/// `SourceQueryResult::extra` field carrying the item's publication time
+/// (RFC 3339), for sources whose items have one.
+pub const SOURCE_RESULT_PUBLISHED_AT: &str = "published_at";
+
+/// `SourceQueryResult::extra` field carrying a sitemap entry's change
+/// frequency hint.
+pub const SOURCE_RESULT_CHANGEFREQ: &str = "changefreq";
+
 /// POST /sources/{id}/query request.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SourceQueryRequest {
@@ -128,3 +490,242 @@ pub struct SourceQueryMetadata {
     pub total_results: usize,
     pub returned_results: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+
+    /// Serialize `value`, decode it back and check nothing was lost.
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &T) {
+        let encoded = serde_json::to_value(value).unwrap();
+        let decoded: T = serde_json::from_value(encoded.clone())
+            .unwrap_or_else(|e| panic!("{} does not decode: {}", std::any::type_name::<T>(), e));
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            encoded,
+            "{} does not round-trip",
+            std::any::type_name::<T>()
+        );
+    }
+
+    fn fetch(url: &str, options: Option<FetchOptions>) -> FetchRequest {
+        FetchRequest {
+            url: url.into(),
+            options,
+        }
+    }
+
+    #[test]
+    fn test_types_round_trip() {
+        let metadata = FetchMetadata {
+            status_code: 200,
+            content_type: Some("text/html".into()),
+            url: "https://example.com/".into(),
+            cached: true,
+            rendered: false,
+            detected_language: Some("en".into()),
+            authenticated: true,
+            cache_ttl: Some(CacheTtl {
+                seconds: 300,
+                source: CacheTtlSource::SourceOverride,
+            }),
+            title: Some("Example Domain".into()),
+            fetched_at: Some(Utc::now()),
+        };
+        let mut extra = serde_json::Map::new();
+        extra.insert("page".into(), Value::from(2));
+
+        round_trip(&HealthResponse {
+            status: HealthStatus::Healthy,
+        });
+        round_trip(&fetch(
+            "https://example.com/",
+            Some(FetchOptions {
+                timeout_ms: Some(5000),
+                user_agent: Some("AutOSINT".into()),
+                headers: Some([("accept".to_string(), "text/html".to_string())].into()),
+                render: Some(RenderMode::Browser),
+                wait_for: Some("#main".into()),
+                extract: Some(ExtractMode::Raw),
+            }),
+        ));
+        round_trip(&FetchResponse {
+            content: "Hello".into(),
+            tables: vec![ExtractedTable {
+                caption: Some("Annex I".into()),
+                headers: vec!["Name".into(), "Listed".into()],
+                rows: vec![vec!["Acme Corp".into(), "2024-03-01".into()]],
+                omitted_rows: 12,
+                best_effort: true,
+            }],
+            metadata: metadata.clone(),
+        });
+        round_trip(&BrowseRequest {
+            url: "https://example.com/".into(),
+            options: Some(BrowseOptions {
+                wait_for: None,
+                timeout_ms: Some(1000),
+            }),
+        });
+        round_trip(&BrowseResponse {
+            content: "Hello".into(),
+            metadata,
+        });
+        round_trip(&SearchRequest {
+            query: "Northwind Star".into(),
+            num_results: Some(5),
+        });
+        round_trip(&SearchResponse {
+            query: "Northwind Star".into(),
+            results: vec![SearchResult {
+                url: "https://example.com/".into(),
+                title: "Example".into(),
+                snippet: "A vessel".into(),
+            }],
+        });
+        round_trip(&CacheEntryQuery {
+            url: "https://example.com/".into(),
+        });
+        round_trip(&CacheEntryResponse {
+            url: "https://example.com/".into(),
+            variants: vec![CacheVariant {
+                fingerprint: String::new(),
+                age_seconds: 3,
+                size_bytes: 5,
+                status_code: 200,
+                content_type: None,
+            }],
+            total_bytes: 5,
+        });
+        round_trip(&SourceInfo {
+            id: "gdelt".into(),
+            name: "GDELT".into(),
+            description: "Event database".into(),
+            kind: SourceKind::Adapter,
+            capabilities: vec!["search".into()],
+            trust_level: Some(4),
+            health: Some(SourceHealth {
+                status: SourceHealthStatus::Degraded,
+                success_rate_7d: Some(0.75),
+                queries_7d: 8,
+                last_success_at: Some("2026-02-03T00:00:00Z".parse().unwrap()),
+                last_error_at: Some("2026-02-04T00:00:00Z".parse().unwrap()),
+                last_error: Some("HTTP 503 Service Unavailable".into()),
+            }),
+        });
+        round_trip(&SourceInfo {
+            id: "mfa-press".into(),
+            name: "MFA press releases".into(),
+            description: "Ministry press release archive".into(),
+            kind: SourceKind::Sitemap,
+            capabilities: vec!["prefix".into(), "since".into()],
+            trust_level: None,
+            health: None,
+        });
+        round_trip(&SitemapRequest {
+            url: "https://example.org/sitemap.xml".into(),
+            prefix: Some("https://example.org/press/".into()),
+            since: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            until: None,
+            limit: Some(50),
+        });
+        round_trip(&SitemapResponse {
+            url: "https://example.org/sitemap.xml".into(),
+            entries: vec![SitemapEntry {
+                url: "https://example.org/press/1".into(),
+                lastmod: Some("2026-02-03T00:00:00Z".parse().unwrap()),
+                changefreq: Some("monthly".into()),
+            }],
+            matched: 12,
+            sitemaps_fetched: 2,
+            sitemaps_skipped: 1,
+            warnings: vec!["bad date".into()],
+        });
+        round_trip(&SourceQueryRequest {
+            params: extra.clone(),
+        });
+        round_trip(&SourceQueryResponse {
+            results: vec![SourceQueryResult {
+                content: "Event".into(),
+                url: None,
+                title: Some("Title".into()),
+                extra: Some(extra),
+            }],
+            metadata: SourceQueryMetadata {
+                source_id: "gdelt".into(),
+                total_results: 10,
+                returned_results: 1,
+            },
+        });
+    }
+
+    #[test]
+    fn test_fetch_request_validation() {
+        assert!(fetch("https://example.com/page", None).validate().is_ok());
+
+        for url in [
+            "",
+            "example.com",
+            "ftp://example.com/",
+            "file:///etc/passwd",
+        ] {
+            let errors = fetch(url, None).validate().unwrap_err();
+            assert_eq!(errors[0].field, "url", "{}", url);
+        }
+
+        let errors = fetch(
+            "https://example.com/",
+            Some(FetchOptions {
+                timeout_ms: Some(0),
+                wait_for: Some(" ".into()),
+                ..Default::default()
+            }),
+        )
+        .validate()
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["options.timeout_ms", "options.wait_for"]);
+    }
+
+    #[test]
+    fn test_sitemap_request_validation() {
+        let request = |url: &str| SitemapRequest {
+            url: url.into(),
+            prefix: None,
+            since: None,
+            until: None,
+            limit: None,
+        };
+        assert!(request("https://example.org/sitemap.xml.gz")
+            .validate()
+            .is_ok());
+
+        let errors = SitemapRequest {
+            prefix: Some(" ".into()),
+            since: Some("2026-02-01T00:00:00Z".parse().unwrap()),
+            until: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            limit: Some(0),
+            ..request("sitemap.xml")
+        }
+        .validate()
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["url", "prefix", "until", "limit"]);
+    }
+
+    #[test]
+    fn test_search_request_validation() {
+        let search = |query: &str, num_results| SearchRequest {
+            query: query.into(),
+            num_results,
+        };
+        assert!(search("Northwind Star", None).validate().is_ok());
+        assert_eq!(search("", None).validate().unwrap_err()[0].field, "query");
+        assert_eq!(
+            search("Northwind Star", Some(0)).validate().unwrap_err()[0].field,
+            "num_results"
+        );
+    }
+}
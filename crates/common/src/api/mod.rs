@@ -1,3 +1,67 @@
+//! Request and response types for the HTTP APIs, shared by the services that
+//! serve them and the clients that call them.
+
+use serde::{Deserialize, Serialize};
+
+pub mod engine;
 pub mod fetch;
 pub mod geo;
 pub mod scribe;
+mod validate;
+
+pub use validate::{describe, FieldError, Validate};
+
+/// Error envelope returned by the Engine and Fetch services on failure.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    /// The fields that failed validation, when the request was rejected as
+    /// invalid.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+impl ErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self {
+            error: error.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// The error message in a failed response's body: the envelope's `error`
+    /// when the body is one, otherwise the body as is.
+    pub fn message_from_body(body: String) -> String {
+        serde_json::from_str::<Self>(&body)
+            .map(|e| e.error)
+            .unwrap_or(body)
+    }
+
+    /// Envelope for a request rejected by [`Validate`].
+    pub fn invalid(fields: Vec<FieldError>) -> Self {
+        Self {
+            error: format!("Invalid request: {}", describe(&fields)),
+            fields,
+        }
+    }
+}
+
+/// Overall or per-dependency health reported by GET /health.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    /// Serving, but a self-test failed.
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    pub fn from_ok(ok: bool) -> Self {
+        if ok {
+            Self::Healthy
+        } else {
+            Self::Unhealthy
+        }
+    }
+}
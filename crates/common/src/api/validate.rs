@@ -0,0 +1,75 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A request field that failed validation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldError {
+    /// Field name as it appears on the wire, e.g. `options.timeout_ms`.
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Checks on a request beyond what deserialization enforces. Handlers call
+/// `validate` before doing any work and reject the request with every
+/// failing field at once.
+pub trait Validate {
+    fn validate(&self) -> Result<(), Vec<FieldError>>;
+}
+
+/// Accumulates field errors for a `Validate` impl.
+#[derive(Default)]
+pub(crate) struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub(crate) fn push(&mut self, field: &str, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, message));
+    }
+
+    /// Record `message` against `field` unless `ok`.
+    pub(crate) fn check(&mut self, ok: bool, field: &str, message: &str) {
+        if !ok {
+            self.push(field, message);
+        }
+    }
+
+    /// Record the error of a fallible normalization against `field`.
+    pub(crate) fn check_result<T, E: fmt::Display>(&mut self, result: Result<T, E>, field: &str) {
+        if let Err(e) = result {
+            self.push(field, e.to_string());
+        }
+    }
+
+    pub(crate) fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
+/// Summary of field errors for an error message, e.g.
+/// "prompt: must not be empty; tags: ...".
+pub fn describe(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -1,5 +1,9 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 
+use crate::types::FeatureFlag;
+
 /// Top-level system configuration, deserialized from system.toml.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SystemConfig {
@@ -11,6 +15,73 @@ pub struct SystemConfig {
     pub retry: RetryDefaults,
     pub cache: CacheConfig,
     pub tool_results: ToolResultLimits,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub stix: StixConfig,
+    #[serde(default)]
+    pub entity_linking: EntityLinkingConfig,
+    #[serde(default)]
+    pub relationship_weights: RelationshipWeightConfig,
+    #[serde(default)]
+    pub triage: TriageConfig,
+    #[serde(default)]
+    pub entity_shortlist: EntityShortlistConfig,
+    #[serde(default)]
+    pub selftest: SelfTestConfig,
+    #[serde(default)]
+    pub recovery: RecoveryConfig,
+    #[serde(default)]
+    pub scratchpad: ScratchpadConfig,
+    #[serde(default)]
+    pub work_order_proposals: WorkOrderProposalConfig,
+    #[serde(default)]
+    pub assessment_diff: AssessmentDiffConfig,
+    #[serde(default)]
+    pub source_trust: SourceTrustConfig,
+    #[serde(default)]
+    pub entity_kinds: EntityKindConfig,
+    #[serde(default)]
+    pub pii: PiiRedactionConfig,
+    /// Target model for re-embedding the graph. None disables POST /admin/reembed.
+    #[serde(default)]
+    pub reembed: Option<ReembedConfig>,
+    #[serde(default)]
+    pub http: HttpClientConfig,
+    #[serde(default)]
+    pub deadlines: DeadlineWatchdogConfig,
+    #[serde(default)]
+    pub graph_writes: GraphWriteConfig,
+    #[serde(default)]
+    pub graph_reads: GraphReadConfig,
+    #[serde(default)]
+    pub graph_consistency: GraphConsistencyConfig,
+    #[serde(default)]
+    pub stub_resolution: StubResolutionConfig,
+    #[serde(default)]
+    pub ingest: IngestConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub flags: FeatureFlagsConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub source_language: SourceLanguageConfig,
+    #[serde(default)]
+    pub jobs: MaintenanceJobConfig,
+    #[serde(default)]
+    pub exploration: ExplorationConfig,
+    #[serde(default)]
+    pub transfer: TransferConfig,
+    #[serde(default)]
+    pub verification: VerificationConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub events: EventsConfig,
 }
 
 /// Safety limits per PLAN.md §4.7.
@@ -22,14 +93,34 @@ pub struct SafetyLimits {
     pub max_turns_per_analyst_session: u32,
     /// Max tool calls per Processor session.
     pub max_turns_per_processor_session: u32,
+    /// Max wall-clock seconds per Analyst session, checked between turns.
+    #[serde(default = "default_max_seconds_per_analyst_session")]
+    pub max_seconds_per_analyst_session: u64,
+    /// Max wall-clock seconds per Processor session, checked between turns.
+    #[serde(default = "default_max_seconds_per_processor_session")]
+    pub max_seconds_per_processor_session: u64,
     /// Max work orders an Analyst can create in a single cycle.
     pub max_work_orders_per_cycle: u32,
-    /// Heartbeat TTL in seconds. Expired = Processor dead.
+    /// Heartbeat TTL in seconds. Expired = Processor dead. Also the TTL of an
+    /// investigation's ownership lease, so a crashed driver frees it this soon.
     pub heartbeat_ttl_seconds: u64,
     /// Consecutive cycles where ALL work orders failed → FAILED state.
     pub consecutive_all_fail_limit: u32,
     /// Consecutive malformed tool calls before ending LLM session.
     pub max_consecutive_malformed_tool_calls: u32,
+    /// Times a session may make the same tool call (same tool, same
+    /// arguments up to whitespace and `limit`) within its recent calls before
+    /// further repeats are answered with a nudge instead of being executed.
+    #[serde(default = "default_max_repeated_tool_calls")]
+    pub max_repeated_tool_calls: u32,
+    /// Nudged repeats before the session is ended as stuck in a loop.
+    #[serde(default = "default_max_tool_loop_nudges")]
+    pub max_tool_loop_nudges: u32,
+    /// Sessions compact older tool results, then wrap up, rather than send a
+    /// request whose estimated size plus reserved output exceeds this
+    /// fraction of the model's context window.
+    #[serde(default = "default_context_budget_fraction")]
+    pub context_budget_fraction: f64,
 }
 
 /// Concurrency parameters.
@@ -46,12 +137,40 @@ pub struct ConcurrencyConfig {
 pub struct LlmConfig {
     pub analyst: LlmRoleConfig,
     pub processor: LlmRoleConfig,
+    /// Cheap model for investigation prompt pre-flight. Pre-flight is skipped
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<LlmRoleConfig>,
+    /// Model capabilities keyed by model pattern (`*` matches any run of
+    /// characters), checked before the built-in table. A pattern without a
+    /// `/` also matches the part of a routed model name after its last `/`
+    /// (`claude-sonnet-4*` matches `anthropic/claude-sonnet-4`).
+    #[serde(default)]
+    pub models: BTreeMap<String, ModelCapabilities>,
+}
+
+/// What a model accepts: its context window and output limit, and whether it
+/// takes tool definitions and images.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    /// Tokens of input and output the model holds in one request.
+    pub context_window: u32,
+    /// Most tokens the model produces in one response.
+    pub max_output_tokens: u32,
+    #[serde(default = "default_supports_tools")]
+    pub supports_tools: bool,
+    #[serde(default)]
+    pub supports_vision: bool,
+}
+
+fn default_supports_tools() -> bool {
+    true
 }
 
 /// Configuration for a single LLM role.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LlmRoleConfig {
-    /// Provider name ("anthropic" or "openai").
+    /// Provider name ("anthropic", "openai", or "gemini").
     /// Use "openai" for OpenAI-compatible providers (OpenRouter, Azure, etc.).
     pub provider: String,
     /// Model identifier (e.g. "claude-opus-4-20250514", "anthropic/claude-sonnet-4-20250514").
@@ -69,6 +188,105 @@ pub struct LlmRoleConfig {
     /// Override to use a different key source (e.g. `OPENROUTER_API_KEY`).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub api_key_env: Option<String>,
+    /// Serve identical requests from the response cache instead of the API.
+    /// Only takes effect at temperature 0. For replays and prompt iteration;
+    /// leave off in production.
+    #[serde(default)]
+    pub cache_responses: bool,
+    /// Where and for how long cached responses are kept.
+    #[serde(default)]
+    pub response_cache: LlmCacheConfig,
+    /// Capabilities of `model`. Resolved from `[llm.models]` and the built-in
+    /// table when the configuration is loaded unless set here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ModelCapabilities>,
+    /// Secondary provider to fail over to while this one is having an outage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<LlmFallbackConfig>,
+}
+
+/// A role's secondary provider, used while the primary returns outage-class
+/// errors (server errors, timeouts, rate limiting past the retry budget).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LlmFallbackConfig {
+    /// Provider name, as for the primary.
+    pub provider: String,
+    /// Model identifier at that provider.
+    pub model: String,
+    /// Max tokens in the response.
+    pub max_tokens: u32,
+    /// Base URL for the API. Defaults to the provider's standard URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Environment variable name for the API key. Defaults to the provider's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// After failing over, requests stay on the fallback this long before the
+    /// primary is probed again.
+    #[serde(default = "default_llm_fallback_cooldown_seconds")]
+    pub cooldown_seconds: u64,
+    /// Capabilities of `model`, resolved like the primary's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<ModelCapabilities>,
+}
+
+fn default_llm_fallback_cooldown_seconds() -> u64 {
+    300
+}
+
+impl LlmFallbackConfig {
+    /// The role configuration `primary` would have with this provider in its
+    /// place. Temperature carries over; the response cache doesn't.
+    pub fn role_config(&self, primary: &LlmRoleConfig) -> LlmRoleConfig {
+        LlmRoleConfig {
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            max_tokens: self.max_tokens,
+            temperature: primary.temperature,
+            base_url: self.base_url.clone(),
+            api_key_env: self.api_key_env.clone(),
+            cache_responses: false,
+            response_cache: LlmCacheConfig::default(),
+            capabilities: self.capabilities,
+            fallback: None,
+        }
+    }
+}
+
+/// On-disk LLM response cache, used by roles with `cache_responses` set.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LlmCacheConfig {
+    /// Directory holding one file per cached response. Roles may share it.
+    #[serde(default = "default_llm_cache_dir")]
+    pub dir: String,
+    /// Cached responses older than this are ignored and removed.
+    #[serde(default = "default_llm_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Oldest responses are evicted once the cache holds more than this.
+    #[serde(default = "default_llm_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+impl Default for LlmCacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_llm_cache_dir(),
+            ttl_seconds: default_llm_cache_ttl_seconds(),
+            max_entries: default_llm_cache_max_entries(),
+        }
+    }
+}
+
+fn default_llm_cache_dir() -> String {
+    ".cache/llm".into()
+}
+
+fn default_llm_cache_ttl_seconds() -> u64 {
+    7 * 24 * 3600
+}
+
+fn default_llm_cache_max_entries() -> usize {
+    10_000
 }
 
 /// Embedding pipeline configuration per PLAN.md §11.
@@ -78,8 +296,13 @@ pub struct EmbeddingConfig {
     pub provider: String,
     /// Model identifier (e.g. "text-embedding-3-small").
     pub model: String,
-    /// Embedding vector dimensions.
+    /// Embedding vector dimensions requested from the model.
     pub dimensions: u32,
+    /// Dimensions stored in the graph when they differ from `dimensions`. Longer
+    /// vectors are truncated and re-normalized (Matryoshka-style; valid for OpenAI
+    /// text-embedding-3 models), shorter ones zero-padded. Defaults to `dimensions`.
+    #[serde(default)]
+    pub target_dimensions: Option<u32>,
     /// Max texts per batch API call.
     pub batch_size: u32,
     /// Interval in minutes for background backfill of pending embeddings.
@@ -91,6 +314,91 @@ pub struct EmbeddingConfig {
     /// Environment variable name for the API key. Defaults to "OPENAI_API_KEY".
     #[serde(default = "default_embedding_api_key_env")]
     pub api_key_env: String,
+    /// Chunk embeddings for long claims.
+    #[serde(default)]
+    pub claim_chunks: ClaimChunkConfig,
+}
+
+impl EmbeddingConfig {
+    /// Dimensions of the vectors the engine stores and indexes.
+    pub fn effective_dimensions(&self) -> u32 {
+        self.target_dimensions.unwrap_or(self.dimensions)
+    }
+}
+
+/// A long claim's single embedding is dominated by its main topic, so a
+/// semantic search for a topic mentioned in passing misses it. Claims longer
+/// than `min_chars` are also embedded as overlapping windows of sentences,
+/// which semantic claim search queries alongside the whole-claim vectors.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClaimChunkConfig {
+    #[serde(default = "default_claim_chunks_enabled")]
+    pub enabled: bool,
+    /// Claims of at most this many characters are not chunked.
+    #[serde(default = "default_claim_chunk_min_chars")]
+    pub min_chars: usize,
+    /// Target characters per chunk. A chunk holds whole sentences, so it runs
+    /// over when one sentence does.
+    #[serde(default = "default_claim_chunk_max_chars")]
+    pub max_chars: usize,
+    /// Sentences repeated at the start of the next chunk.
+    #[serde(default = "default_claim_chunk_overlap_sentences")]
+    pub overlap_sentences: usize,
+}
+
+impl Default for ClaimChunkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_claim_chunks_enabled(),
+            min_chars: default_claim_chunk_min_chars(),
+            max_chars: default_claim_chunk_max_chars(),
+            overlap_sentences: default_claim_chunk_overlap_sentences(),
+        }
+    }
+}
+
+fn default_claim_chunks_enabled() -> bool {
+    true
+}
+
+fn default_claim_chunk_min_chars() -> usize {
+    800
+}
+
+fn default_claim_chunk_max_chars() -> usize {
+    400
+}
+
+fn default_claim_chunk_overlap_sentences() -> usize {
+    1
+}
+
+/// Embedding model that POST /admin/reembed migrates the graph to. Batch size
+/// and provider are taken from `[embeddings]`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReembedConfig {
+    pub model: String,
+    pub dimensions: u32,
+    #[serde(default)]
+    pub target_dimensions: Option<u32>,
+    #[serde(default = "default_embedding_base_url")]
+    pub base_url: String,
+    #[serde(default = "default_embedding_api_key_env")]
+    pub api_key_env: String,
+}
+
+impl ReembedConfig {
+    /// Embedding settings for the new model, filling the rest from `current`.
+    pub fn embedding_config(&self, current: &EmbeddingConfig) -> EmbeddingConfig {
+        EmbeddingConfig {
+            model: self.model.clone(),
+            dimensions: self.dimensions,
+            target_dimensions: self.target_dimensions,
+            base_url: self.base_url.clone(),
+            api_key_env: self.api_key_env.clone(),
+            ..current.clone()
+        }
+    }
 }
 
 fn default_embedding_base_url() -> String {
@@ -108,6 +416,87 @@ pub struct DedupConfig {
     pub fuzzy_threshold: f64,
     /// Cosine similarity threshold for embedding-based matching (0.0–1.0).
     pub embedding_threshold: f64,
+    /// Jaro-Winkler threshold for matching a country/region candidate against a
+    /// seeded geographic entity. Kept a little below `fuzzy_threshold` so spelling
+    /// variants collapse into the seeded entity.
+    #[serde(default = "default_seeded_geo_fuzzy_threshold")]
+    pub seeded_geo_fuzzy_threshold: f64,
+    /// Jaro-Winkler threshold above which a new relationship's description makes
+    /// it a duplicate of an existing edge between the same two entities.
+    #[serde(default = "default_relationship_description_threshold")]
+    pub relationship_description_threshold: f64,
+    /// What `create_relationship` does with such a duplicate.
+    #[serde(default)]
+    pub duplicate_relationships: DuplicateRelationshipMode,
+    /// Scan of just-written entities, which the vector index may not show yet.
+    #[serde(default)]
+    pub fresh_entities: FreshEntityScanConfig,
+    /// Merge a new claim into an existing claim with exactly the same
+    /// content, as `merge_claims` would, instead of keeping both.
+    #[serde(default)]
+    pub merge_exact_duplicate_claims: bool,
+}
+
+fn default_seeded_geo_fuzzy_threshold() -> f64 {
+    0.80
+}
+
+fn default_relationship_description_threshold() -> f64 {
+    0.95
+}
+
+/// Supplements vector index queries with a scan of entities updated in the
+/// last `window_seconds`, compared against their stored embeddings. The
+/// index lags writes by a few seconds, so without it two Processors
+/// extracting the same new entity at once each find no match.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FreshEntityScanConfig {
+    /// Scan during embedding-similarity dedup.
+    #[serde(default = "default_fresh_entity_scan_enabled")]
+    pub enabled: bool,
+    /// Also merge the scan into semantic entity searches.
+    #[serde(default)]
+    pub semantic_search: bool,
+    #[serde(default = "default_fresh_entity_window_seconds")]
+    pub window_seconds: u64,
+    /// Skip the scan (with a warning) when more entities than this were
+    /// updated in the window.
+    #[serde(default = "default_fresh_entity_max_scan")]
+    pub max_scan: u32,
+}
+
+impl Default for FreshEntityScanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            semantic_search: false,
+            window_seconds: default_fresh_entity_window_seconds(),
+            max_scan: default_fresh_entity_max_scan(),
+        }
+    }
+}
+
+fn default_fresh_entity_scan_enabled() -> bool {
+    true
+}
+
+fn default_fresh_entity_window_seconds() -> u64 {
+    120
+}
+
+fn default_fresh_entity_max_scan() -> u32 {
+    500
+}
+
+/// Handling of a relationship that duplicates an existing edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateRelationshipMode {
+    /// Create the edge and report the existing one.
+    #[default]
+    Warn,
+    /// Skip creation and return the existing edge.
+    Dedupe,
 }
 
 /// Default retry parameters per PLAN.md §11.
@@ -145,4 +534,1681 @@ pub struct ToolResultLimits {
     pub max_entity_detail_chars: u32,
     /// Max characters for claim content previews.
     pub max_claim_preview_chars: u32,
+    /// Max referenced entity IDs listed per claim in tool results; longer
+    /// lists are cut with a note of the total.
+    #[serde(default = "default_max_claim_references_shown")]
+    pub max_claim_references_shown: u32,
+    /// Max tokens in any single tool result sent back to the LLM, counted with
+    /// the session model's tokenizer.
+    #[serde(default = "default_max_result_tokens")]
+    pub max_result_tokens: u32,
+    /// Max characters of the graph knowledge block preloaded into a Processor
+    /// session's first message. 0 disables the preload.
+    #[serde(default = "default_max_graph_context_chars")]
+    pub max_graph_context_chars: u32,
+    /// Max characters of passages verify_claim quotes from each outside
+    /// source.
+    #[serde(default = "default_max_verification_excerpt_chars")]
+    pub max_verification_excerpt_chars: u32,
+    /// Max characters of a result from a read tool that takes
+    /// `response_format`: JSON results drop trailing list items to fit,
+    /// summaries are cut at a sentence boundary.
+    #[serde(default = "default_max_formatted_result_chars")]
+    pub max_formatted_result_chars: u32,
+    /// `response_format` of each read tool named here when a call doesn't
+    /// give one; other tools default to JSON.
+    #[serde(default)]
+    pub response_formats: HashMap<String, ResponseFormat>,
+}
+
+/// How a read tool's result is sent back to the LLM.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// The structured result, for chaining IDs into further calls.
+    #[default]
+    Json,
+    /// A prose rendering: one line per result, for reading and synthesis.
+    Summary,
+}
+
+impl ResponseFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "json" => Some(Self::Json),
+            "summary" => Some(Self::Summary),
+            _ => None,
+        }
+    }
+}
+
+/// Data retention policy for terminal investigations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Automatically purge old terminal investigations. Off unless explicitly enabled.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Days after completion before a terminal investigation is purged.
+    #[serde(default = "default_retention_max_age_days")]
+    pub max_age_days: u32,
+    /// How often the retention task looks for expired investigations.
+    #[serde(default = "default_retention_check_interval_minutes")]
+    pub check_interval_minutes: u32,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_days: default_retention_max_age_days(),
+            check_interval_minutes: default_retention_check_interval_minutes(),
+        }
+    }
+}
+
+fn default_max_seconds_per_analyst_session() -> u64 {
+    1800
+}
+
+fn default_max_seconds_per_processor_session() -> u64 {
+    900
+}
+
+fn default_context_budget_fraction() -> f64 {
+    0.85
+}
+
+fn default_max_repeated_tool_calls() -> u32 {
+    3
+}
+
+fn default_max_tool_loop_nudges() -> u32 {
+    3
+}
+
+fn default_max_claim_references_shown() -> u32 {
+    10
+}
+
+fn default_max_result_tokens() -> u32 {
+    8000
+}
+
+fn default_max_graph_context_chars() -> u32 {
+    6000
+}
+
+fn default_max_verification_excerpt_chars() -> u32 {
+    1500
+}
+
+fn default_max_formatted_result_chars() -> u32 {
+    24000
+}
+
+fn default_retention_max_age_days() -> u32 {
+    90
+}
+
+fn default_retention_check_interval_minutes() -> u32 {
+    60
+}
+
+/// Automatic linking of new claims to known entities their content mentions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntityLinkingConfig {
+    #[serde(default = "default_entity_linking_enabled")]
+    pub enabled: bool,
+    /// Names and aliases shorter than this (in characters) are never matched,
+    /// so short acronyms like "US" don't link into everything.
+    #[serde(default = "default_entity_linking_min_name_length")]
+    pub min_name_length: usize,
+    /// How long the in-memory name index is reused before it is rebuilt from the graph.
+    #[serde(default = "default_entity_linking_refresh_interval_seconds")]
+    pub refresh_interval_seconds: u64,
+}
+
+impl Default for EntityLinkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_entity_linking_enabled(),
+            min_name_length: default_entity_linking_min_name_length(),
+            refresh_interval_seconds: default_entity_linking_refresh_interval_seconds(),
+        }
+    }
+}
+
+fn default_entity_linking_enabled() -> bool {
+    true
+}
+
+fn default_entity_linking_min_name_length() -> usize {
+    4
+}
+
+fn default_entity_linking_refresh_interval_seconds() -> u64 {
+    300
+}
+
+/// Per-investigation cache of resolved entity names, kept in Redis so tools can
+/// skip repeated graph lookups for entities the investigation already touched.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntityShortlistConfig {
+    #[serde(default = "default_entity_shortlist_enabled")]
+    pub enabled: bool,
+    /// Idle lifetime of a shortlist entry. Refreshed whenever the entry is read
+    /// or written.
+    #[serde(default = "default_entity_shortlist_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Most-referenced shortlist entities listed in each Analyst cycle prompt
+    /// and each Processor session's graph knowledge block. 0 disables the list.
+    #[serde(default = "default_entity_shortlist_prompt_entities")]
+    pub prompt_entities: u32,
+}
+
+impl Default for EntityShortlistConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_entity_shortlist_enabled(),
+            ttl_seconds: default_entity_shortlist_ttl_seconds(),
+            prompt_entities: default_entity_shortlist_prompt_entities(),
+        }
+    }
+}
+
+fn default_entity_shortlist_enabled() -> bool {
+    true
+}
+
+fn default_entity_shortlist_ttl_seconds() -> u64 {
+    3600
+}
+
+fn default_entity_shortlist_prompt_entities() -> u32 {
+    20
+}
+
+/// Periodic recalibration of relationship weights from supporting claims.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RelationshipWeightConfig {
+    /// Off unless explicitly enabled.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How often weights are recomputed.
+    #[serde(default = "default_relationship_weights_interval_minutes")]
+    pub interval_minutes: u32,
+    /// Age at which a source's support counts half as much.
+    #[serde(default = "default_relationship_weights_recency_half_life_days")]
+    pub recency_half_life_days: f64,
+    /// Fresh independent sources needed to reach weight 0.5. Weight approaches
+    /// 1.0 as support grows and never reaches it.
+    #[serde(default = "default_relationship_weights_half_weight_sources")]
+    pub half_weight_sources: f64,
+}
+
+impl Default for RelationshipWeightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_relationship_weights_interval_minutes(),
+            recency_half_life_days: default_relationship_weights_recency_half_life_days(),
+            half_weight_sources: default_relationship_weights_half_weight_sources(),
+        }
+    }
+}
+
+fn default_relationship_weights_interval_minutes() -> u32 {
+    60
+}
+
+fn default_relationship_weights_recency_half_life_days() -> f64 {
+    180.0
+}
+
+fn default_relationship_weights_half_weight_sources() -> f64 {
+    2.0
+}
+
+/// Canary self-test (POST /selftest, `ENGINE_RUN_SELFTEST_ON_START`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SelfTestConfig {
+    /// Wall-clock budget for the canary investigation, including Processor time.
+    #[serde(default = "default_selftest_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Max tool calls per canary Analyst session.
+    #[serde(default = "default_selftest_max_turns")]
+    pub max_turns: u32,
+    /// Analyst tools the canary may call. Everything else is withheld.
+    #[serde(default = "default_selftest_analyst_tools")]
+    pub analyst_tools: Vec<String>,
+}
+
+impl Default for SelfTestConfig {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: default_selftest_timeout_seconds(),
+            max_turns: default_selftest_max_turns(),
+            analyst_tools: default_selftest_analyst_tools(),
+        }
+    }
+}
+
+fn default_selftest_timeout_seconds() -> u64 {
+    600
+}
+
+fn default_selftest_max_turns() -> u32 {
+    3
+}
+
+fn default_selftest_analyst_tools() -> Vec<String> {
+    ["search_entities", "create_work_order", "produce_assessment"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Startup recovery of investigations left non-terminal by a restart.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecoveryConfig {
+    /// Resume recovered investigations. When false, investigations active at
+    /// shutdown are suspended and left for an operator to resume.
+    #[serde(default = "default_recovery_auto_resume")]
+    pub auto_resume: bool,
+    /// Delay between starting consecutive recovered investigations.
+    #[serde(default = "default_recovery_stagger_ms")]
+    pub stagger_ms: u64,
+    /// Most recovered investigations driven at once. The rest wait for one
+    /// to finish.
+    #[serde(default = "default_recovery_max_concurrent")]
+    pub max_concurrent: usize,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            auto_resume: default_recovery_auto_resume(),
+            stagger_ms: default_recovery_stagger_ms(),
+            max_concurrent: default_recovery_max_concurrent(),
+        }
+    }
+}
+
+fn default_recovery_auto_resume() -> bool {
+    true
+}
+
+fn default_recovery_stagger_ms() -> u64 {
+    2000
+}
+
+fn default_recovery_max_concurrent() -> usize {
+    4
+}
+
+/// Size caps on the Analyst scratchpad (write_scratchpad / read_scratchpad).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScratchpadConfig {
+    /// Longest section content, in characters. Longer writes are rejected.
+    #[serde(default = "default_scratchpad_max_section_chars")]
+    pub max_section_chars: usize,
+    /// Most characters across an investigation's sections. A write that
+    /// goes over evicts the least recently written other sections.
+    #[serde(default = "default_scratchpad_max_total_chars")]
+    pub max_total_chars: usize,
+}
+
+impl Default for ScratchpadConfig {
+    fn default() -> Self {
+        Self {
+            max_section_chars: default_scratchpad_max_section_chars(),
+            max_total_chars: default_scratchpad_max_total_chars(),
+        }
+    }
+}
+
+fn default_scratchpad_max_section_chars() -> usize {
+    4000
+}
+
+fn default_scratchpad_max_total_chars() -> usize {
+    16000
+}
+
+/// Follow-up work orders Processors propose (propose_work_order) for the
+/// Analyst to approve or dismiss.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkOrderProposalConfig {
+    #[serde(default = "default_work_order_proposals_enabled")]
+    pub enabled: bool,
+    /// Most proposals one Processor session may make.
+    #[serde(default = "default_proposals_per_session")]
+    pub max_per_processor_session: u32,
+    /// Most proposals an investigation may collect over its life, whatever
+    /// became of them.
+    #[serde(default = "default_proposals_per_investigation")]
+    pub max_per_investigation: u32,
+}
+
+impl Default for WorkOrderProposalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_per_processor_session: default_proposals_per_session(),
+            max_per_investigation: default_proposals_per_investigation(),
+        }
+    }
+}
+
+fn default_work_order_proposals_enabled() -> bool {
+    true
+}
+
+fn default_proposals_per_session() -> u32 {
+    3
+}
+
+fn default_proposals_per_investigation() -> u32 {
+    20
+}
+
+/// Matching key judgments of an assessment against those of the one it
+/// supersedes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AssessmentDiffConfig {
+    /// Lowest embedding cosine similarity at which two judgments are the
+    /// same judgment (possibly revised).
+    #[serde(default = "default_assessment_diff_similarity_threshold")]
+    pub similarity_threshold: f64,
+    /// Lowest Jaro-Winkler similarity of the judgment texts, used instead
+    /// when embeddings are unavailable.
+    #[serde(default = "default_assessment_diff_fuzzy_threshold")]
+    pub fuzzy_threshold: f64,
+}
+
+impl Default for AssessmentDiffConfig {
+    fn default() -> Self {
+        Self {
+            similarity_threshold: default_assessment_diff_similarity_threshold(),
+            fuzzy_threshold: default_assessment_diff_fuzzy_threshold(),
+        }
+    }
+}
+
+fn default_assessment_diff_similarity_threshold() -> f64 {
+    0.8
+}
+
+fn default_assessment_diff_fuzzy_threshold() -> f64 {
+    0.85
+}
+
+/// Use of source trust levels (the `trust_level` of publication entities).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SourceTrustConfig {
+    /// produce_assessment warns about a key judgment whose claims all come
+    /// from sources rated below this level.
+    #[serde(default = "default_source_trust_citation_floor")]
+    pub citation_floor: u8,
+}
+
+impl Default for SourceTrustConfig {
+    fn default() -> Self {
+        Self {
+            citation_floor: default_source_trust_citation_floor(),
+        }
+    }
+}
+
+fn default_source_trust_citation_floor() -> u8 {
+    3
+}
+
+/// The entity kind taxonomy and what creating an entity of each kind must
+/// or should include. Kinds stay free-form; `canonical` lists the ones the
+/// deployment expects, and only those can carry a policy.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EntityKindConfig {
+    #[serde(default)]
+    pub canonical: Vec<String>,
+    /// Creation policy per canonical kind, enforced by create_entity and
+    /// batch_extract.
+    #[serde(default)]
+    pub policies: BTreeMap<String, EntityKindPolicy>,
+}
+
+impl EntityKindConfig {
+    /// The policy for `kind`, matched case-insensitively.
+    pub fn policy(&self, kind: &str) -> Option<&EntityKindPolicy> {
+        let kind = kind.trim().to_lowercase();
+        self.policies.get(&kind)
+    }
+}
+
+/// What a new entity of one kind is expected to include.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EntityKindPolicy {
+    #[serde(default)]
+    pub required: Vec<RequiredEntityField>,
+    /// Properties stamped onto a new entity that doesn't set them.
+    #[serde(default)]
+    pub defaults: BTreeMap<String, serde_json::Value>,
+    /// Whether entities of this kind may be created as stubs. Stubs are
+    /// exempt from `required`.
+    #[serde(default = "default_allow_stubs")]
+    pub allow_stubs: bool,
+}
+
+fn default_allow_stubs() -> bool {
+    true
+}
+
+/// A requirement met by any one of `any_of`: a property name, `summary`,
+/// `aliases`, or `identifiers.<scheme>`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequiredEntityField {
+    pub any_of: Vec<String>,
+    #[serde(default)]
+    pub severity: PolicySeverity,
+}
+
+/// What happens when a new entity misses a required field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicySeverity {
+    /// Created, with a warning in the tool result.
+    #[default]
+    Warn,
+    /// Not created.
+    Reject,
+}
+
+/// Scrubbing of personal data (emails, phone numbers, national ID numbers)
+/// from claim content before it is stored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PiiRedactionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub policy: PiiPolicy,
+    /// Built-in detectors to run.
+    #[serde(default = "default_pii_detectors")]
+    pub detectors: Vec<PiiDetectorKind>,
+    /// Deployment-specific detectors, run alongside the built-in ones.
+    #[serde(default)]
+    pub patterns: Vec<PiiPatternConfig>,
+}
+
+impl Default for PiiRedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            policy: PiiPolicy::default(),
+            detectors: default_pii_detectors(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+fn default_pii_detectors() -> Vec<PiiDetectorKind> {
+    vec![
+        PiiDetectorKind::Email,
+        PiiDetectorKind::Phone,
+        PiiDetectorKind::NationalId,
+    ]
+}
+
+/// What happens to a claim whose content contains personal data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiPolicy {
+    /// Replace each match with a typed placeholder such as `[REDACTED:email]`.
+    #[default]
+    Redact,
+    /// Refuse to store the claim.
+    Reject,
+}
+
+/// A built-in personal data detector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiDetectorKind {
+    Email,
+    /// International (`+44 20 7946 0958`) and national (`(202) 555-0143`)
+    /// phone numbers.
+    Phone,
+    /// US Social Security and UK National Insurance numbers.
+    NationalId,
+}
+
+impl PiiDetectorKind {
+    /// Name used in placeholders and redaction counts.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Phone => "phone",
+            Self::NationalId => "national_id",
+        }
+    }
+}
+
+/// A deployment-specific detector: matches of `pattern` (a regular
+/// expression) are redacted as `[REDACTED:<name>]`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PiiPatternConfig {
+    pub name: String,
+    pub pattern: String,
+}
+
+/// Sources pushed to AutOSINT rather than fetched by Processors.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IngestConfig {
+    #[serde(default)]
+    pub imap: ImapIngestConfig,
+    #[serde(default)]
+    pub monitors: MonitorIngestConfig,
+    #[serde(default)]
+    pub enrichment: EnrichmentConfig,
+}
+
+/// Email newsletters polled from an IMAP mailbox. Each new message becomes a
+/// source document and a low-priority `ingest_document` work order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImapIngestConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Names the mailbox in seen-message tracking and its standing ingest
+    /// investigation (correlation ID `ingest-{name}`).
+    #[serde(default = "default_imap_name")]
+    pub name: String,
+    /// IMAP server, reached over implicit TLS.
+    #[serde(default)]
+    pub host: String,
+    #[serde(default = "default_imap_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub username: String,
+    /// Environment variable holding the password.
+    #[serde(default = "default_imap_password_env")]
+    pub password_env: String,
+    #[serde(default = "default_imap_folder")]
+    pub folder: String,
+    #[serde(default = "default_imap_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+    /// Larger messages are recorded as seen and skipped.
+    #[serde(default = "default_imap_max_message_bytes")]
+    pub max_message_bytes: u64,
+    /// Most messages ingested per poll; the rest wait for the next one.
+    #[serde(default = "default_imap_max_messages_per_poll")]
+    pub max_messages_per_poll: usize,
+    /// Trust level given to publication entities created for new senders.
+    /// None leaves them unrated.
+    #[serde(default)]
+    pub trust_level: Option<u8>,
+}
+
+impl Default for ImapIngestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            name: default_imap_name(),
+            host: String::new(),
+            port: default_imap_port(),
+            username: String::new(),
+            password_env: default_imap_password_env(),
+            folder: default_imap_folder(),
+            poll_interval_seconds: default_imap_poll_interval_seconds(),
+            max_message_bytes: default_imap_max_message_bytes(),
+            max_messages_per_poll: default_imap_max_messages_per_poll(),
+            trust_level: None,
+        }
+    }
+}
+
+fn default_imap_name() -> String {
+    "newsletters".into()
+}
+
+fn default_imap_port() -> u16 {
+    993
+}
+
+fn default_imap_password_env() -> String {
+    "INGEST_IMAP_PASSWORD".into()
+}
+
+fn default_imap_folder() -> String {
+    "INBOX".into()
+}
+
+fn default_imap_poll_interval_seconds() -> u64 {
+    300
+}
+
+fn default_imap_max_message_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+fn default_imap_max_messages_per_poll() -> usize {
+    50
+}
+
+/// Scheduler for monitors: source subscriptions created through the
+/// `/monitors` API, each polled on its own interval.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MonitorIngestConfig {
+    #[serde(default = "default_monitors_enabled")]
+    pub enabled: bool,
+    /// How often the scheduler looks for monitors that are due.
+    #[serde(default = "default_monitor_tick_seconds")]
+    pub tick_seconds: u64,
+    /// Items ingested per run for monitors created without a limit; the rest
+    /// wait for the next run.
+    #[serde(default = "default_monitor_max_items_per_run")]
+    pub max_items_per_run: u32,
+    /// Items requested from the source per run.
+    #[serde(default = "default_monitor_query_limit")]
+    pub query_limit: u32,
+}
+
+impl Default for MonitorIngestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tick_seconds: default_monitor_tick_seconds(),
+            max_items_per_run: default_monitor_max_items_per_run(),
+            query_limit: default_monitor_query_limit(),
+        }
+    }
+}
+
+fn default_monitors_enabled() -> bool {
+    true
+}
+
+fn default_monitor_tick_seconds() -> u64 {
+    60
+}
+
+fn default_monitor_max_items_per_run() -> u32 {
+    20
+}
+
+fn default_monitor_query_limit() -> u32 {
+    100
+}
+
+/// Asynchronous external enrichments (submit_external_enrichment): jobs
+/// posted to a provider that delivers the result later to a single-use,
+/// signed callback.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnrichmentConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Base URL providers reach the engine API at; callback URLs are
+    /// `{callback_base_url}/callbacks/enrichment/{token}`.
+    #[serde(default = "default_enrichment_callback_base_url")]
+    pub callback_base_url: String,
+    /// How long a callback token is accepted after submission.
+    #[serde(default = "default_enrichment_token_ttl_seconds")]
+    pub token_ttl_seconds: u64,
+    /// How far a callback's signed timestamp may be from now.
+    #[serde(default = "default_enrichment_signature_tolerance_seconds")]
+    pub signature_tolerance_seconds: u64,
+    /// Larger callback bodies are refused.
+    #[serde(default = "default_enrichment_max_result_bytes")]
+    pub max_result_bytes: usize,
+    /// Most requests an investigation may submit over its life.
+    #[serde(default = "default_enrichment_max_per_investigation")]
+    pub max_per_investigation: u32,
+    /// Characters of each result shown by list_enrichment_results.
+    #[serde(default = "default_enrichment_excerpt_chars")]
+    pub excerpt_chars: usize,
+    #[serde(default)]
+    pub providers: Vec<EnrichmentProviderConfig>,
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            callback_base_url: default_enrichment_callback_base_url(),
+            token_ttl_seconds: default_enrichment_token_ttl_seconds(),
+            signature_tolerance_seconds: default_enrichment_signature_tolerance_seconds(),
+            max_result_bytes: default_enrichment_max_result_bytes(),
+            max_per_investigation: default_enrichment_max_per_investigation(),
+            excerpt_chars: default_enrichment_excerpt_chars(),
+            providers: Vec::new(),
+        }
+    }
+}
+
+impl EnrichmentConfig {
+    pub fn provider(&self, name: &str) -> Option<&EnrichmentProviderConfig> {
+        self.providers.iter().find(|p| p.name == name)
+    }
+}
+
+/// One asynchronous enrichment provider.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnrichmentProviderConfig {
+    /// Name sessions submit to.
+    pub name: String,
+    /// What the provider does and the payload it expects, shown to sessions.
+    #[serde(default)]
+    pub description: String,
+    /// Jobs are POSTed here.
+    pub submit_url: String,
+    /// Environment variable holding the secret shared with the provider. It
+    /// signs submissions and callbacks both ways.
+    pub secret_env: String,
+    /// Default for whether arrived results get an ingest work order.
+    #[serde(default)]
+    pub ingest_results: bool,
+}
+
+fn default_enrichment_callback_base_url() -> String {
+    "http://localhost:8080".into()
+}
+
+fn default_enrichment_token_ttl_seconds() -> u64 {
+    86400
+}
+
+fn default_enrichment_signature_tolerance_seconds() -> u64 {
+    300
+}
+
+fn default_enrichment_max_result_bytes() -> usize {
+    1_048_576
+}
+
+fn default_enrichment_max_per_investigation() -> u32 {
+    20
+}
+
+fn default_enrichment_excerpt_chars() -> usize {
+    2000
+}
+
+/// Watchdog for queued work orders approaching their deadline.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeadlineWatchdogConfig {
+    /// How often the watchdog checks pending work orders.
+    #[serde(default = "default_deadline_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// A scheduled or queued work order is at risk once its deadline is
+    /// this close.
+    #[serde(default = "default_deadline_at_risk_window_seconds")]
+    pub at_risk_window_seconds: u64,
+}
+
+impl Default for DeadlineWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_seconds: default_deadline_check_interval_seconds(),
+            at_risk_window_seconds: default_deadline_at_risk_window_seconds(),
+        }
+    }
+}
+
+fn default_deadline_check_interval_seconds() -> u64 {
+    60
+}
+
+fn default_deadline_at_risk_window_seconds() -> u64 {
+    900
+}
+
+/// Read-only mode for maintenance windows (e.g. Neo4j upgrades): the engine
+/// keeps serving reads but mutates nothing and consumes no work orders.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    /// Start this replica read-only regardless of the shared toggle.
+    /// `ENGINE_READ_ONLY=true` sets it too.
+    #[serde(default)]
+    pub read_only: bool,
+    /// How often the shared toggle (POST /admin/read_only) is re-read from
+    /// Redis, so replicas agree.
+    #[serde(default = "default_read_only_sync_interval_seconds")]
+    pub sync_interval_seconds: u64,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            read_only: false,
+            sync_interval_seconds: default_read_only_sync_interval_seconds(),
+        }
+    }
+}
+
+fn default_read_only_sync_interval_seconds() -> u64 {
+    5
+}
+
+/// Maintenance jobs started through POST /admin/jobs. One engine instance at
+/// a time runs them, one job at a time, in batches paced to leave the
+/// database to the live workload.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaintenanceJobConfig {
+    /// How often the runner looks for queued jobs.
+    #[serde(default = "default_job_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+    /// Items per batch, unless the job's `batch_size` parameter says otherwise.
+    #[serde(default = "default_job_batch_size")]
+    pub batch_size: u32,
+    /// Least time between the starts of two batches.
+    #[serde(default = "default_job_min_batch_interval_ms")]
+    pub min_batch_interval_ms: u64,
+    /// Failed item IDs kept on a job for its progress report.
+    #[serde(default = "default_job_max_error_samples")]
+    pub max_error_samples: usize,
+}
+
+impl Default for MaintenanceJobConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: default_job_poll_interval_seconds(),
+            batch_size: default_job_batch_size(),
+            min_batch_interval_ms: default_job_min_batch_interval_ms(),
+            max_error_samples: default_job_max_error_samples(),
+        }
+    }
+}
+
+fn default_job_poll_interval_seconds() -> u64 {
+    5
+}
+
+fn default_job_batch_size() -> u32 {
+    100
+}
+
+fn default_job_min_batch_interval_ms() -> u64 {
+    500
+}
+
+fn default_job_max_error_samples() -> usize {
+    10
+}
+
+/// Limits on Processor sessions for exploratory work orders, which skim many
+/// sources rather than work a few thoroughly.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExplorationConfig {
+    /// Most fetch_url and fetch_source_query calls a session may make against
+    /// one domain or fetch source.
+    #[serde(default = "default_exploration_max_calls_per_source")]
+    pub max_calls_per_source: u32,
+    /// Longest fetched document content shown, in characters.
+    #[serde(default = "default_exploration_max_fetch_chars")]
+    pub max_fetch_chars: usize,
+}
+
+impl Default for ExplorationConfig {
+    fn default() -> Self {
+        Self {
+            max_calls_per_source: default_exploration_max_calls_per_source(),
+            max_fetch_chars: default_exploration_max_fetch_chars(),
+        }
+    }
+}
+
+fn default_exploration_max_calls_per_source() -> u32 {
+    3
+}
+
+fn default_exploration_max_fetch_chars() -> usize {
+    8000
+}
+
+/// Limits of the Analyst's verify_claim tool, which gathers outside evidence
+/// for or against a claim.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VerificationConfig {
+    /// Web search results considered per verification.
+    #[serde(default = "default_verification_max_search_results")]
+    pub max_search_results: usize,
+    /// Most pages fetched per verification. Each fetch spends the
+    /// investigation's fetch budget.
+    #[serde(default = "default_verification_max_fetches")]
+    pub max_fetches: usize,
+    /// High-trust fetch sources, by ID, also queried with the claim's search.
+    #[serde(default)]
+    pub trusted_sources: Vec<String>,
+    /// Domains verification may read from (subdomains included). Empty
+    /// allows any domain not denied.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+    /// Domains verification never reads from (subdomains included).
+    #[serde(default)]
+    pub denied_domains: Vec<String>,
+    /// Most claims from the graph about the same entities listed.
+    #[serde(default = "default_verification_max_graph_claims")]
+    pub max_graph_claims: usize,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            max_search_results: default_verification_max_search_results(),
+            max_fetches: default_verification_max_fetches(),
+            trusted_sources: Vec::new(),
+            allowed_domains: Vec::new(),
+            denied_domains: Vec::new(),
+            max_graph_claims: default_verification_max_graph_claims(),
+        }
+    }
+}
+
+fn default_verification_max_search_results() -> usize {
+    8
+}
+
+fn default_verification_max_fetches() -> usize {
+    3
+}
+
+fn default_verification_max_graph_claims() -> usize {
+    10
+}
+
+/// Bounds on investigation archives (GET /investigations/{id}/export, POST
+/// /investigations/import), which move an investigation between engines.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransferConfig {
+    /// Most entities an archive may carry. Larger investigations are refused
+    /// on export and on import.
+    #[serde(default = "default_transfer_max_entities")]
+    pub max_entities: usize,
+    /// Most claims an archive may carry.
+    #[serde(default = "default_transfer_max_claims")]
+    pub max_claims: usize,
+    /// Largest archive accepted for import, in bytes, compressed and again
+    /// once decompressed.
+    #[serde(default = "default_transfer_max_archive_bytes")]
+    pub max_archive_bytes: usize,
+}
+
+impl Default for TransferConfig {
+    fn default() -> Self {
+        Self {
+            max_entities: default_transfer_max_entities(),
+            max_claims: default_transfer_max_claims(),
+            max_archive_bytes: default_transfer_max_archive_bytes(),
+        }
+    }
+}
+
+fn default_transfer_max_entities() -> usize {
+    5_000
+}
+
+fn default_transfer_max_claims() -> usize {
+    20_000
+}
+
+fn default_transfer_max_archive_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+/// Telemetry rollups: per prompt hash, how Processor and Analyst sessions
+/// ran over each window (turns, tool calls by name, outcomes, tokens),
+/// stored locally and optionally pushed to a collector shared by several
+/// deployments. Which fields a rollup has is fixed in code
+/// (`types::ROLLUP_FIELDS`), not here.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Compute rollups. Off unless explicitly enabled.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Length of a rollup window. Windows are aligned to the Unix epoch.
+    #[serde(default = "default_telemetry_window_hours")]
+    pub window_hours: u32,
+    /// How often the rollup task looks for finished windows and unpushed
+    /// rollups.
+    #[serde(default = "default_telemetry_check_interval_minutes")]
+    pub check_interval_minutes: u32,
+    /// Collector that rollups are POSTed to. None keeps them local.
+    #[serde(default)]
+    pub collector_url: Option<String>,
+    /// Environment variable holding the secret shared with the collector,
+    /// which signs each push.
+    #[serde(default = "default_telemetry_secret_env")]
+    pub secret_env: String,
+    /// This deployment's label in pushes. Required with a collector.
+    #[serde(default)]
+    pub deployment: String,
+    /// Attempts per push before leaving the rollups for the next check.
+    #[serde(default = "default_telemetry_push_attempts")]
+    pub push_attempts: u32,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_hours: default_telemetry_window_hours(),
+            check_interval_minutes: default_telemetry_check_interval_minutes(),
+            collector_url: None,
+            secret_env: default_telemetry_secret_env(),
+            deployment: String::new(),
+            push_attempts: default_telemetry_push_attempts(),
+        }
+    }
+}
+
+fn default_telemetry_window_hours() -> u32 {
+    24
+}
+
+fn default_telemetry_check_interval_minutes() -> u32 {
+    60
+}
+
+fn default_telemetry_secret_env() -> String {
+    "AUTOSINT_TELEMETRY_SECRET".into()
+}
+
+fn default_telemetry_push_attempts() -> u32 {
+    3
+}
+
+/// The engine's event bus: what it does with the domain events (status
+/// changes, work order lifecycle, new claims and assessments) it publishes.
+/// Every event is offered to in-process subscribers over a bounded broadcast
+/// channel, which is best effort: a subscriber that falls `broadcast_capacity`
+/// events behind misses the oldest. With `durable` on, events are also
+/// appended to a Redis stream, which subscribers such as webhooks read through
+/// their own consumer group, at least once and across restarts.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventsConfig {
+    /// Events buffered per broadcast subscriber.
+    #[serde(default = "default_events_broadcast_capacity")]
+    pub broadcast_capacity: usize,
+    /// Append every event to the `events` Redis stream.
+    #[serde(default)]
+    pub durable: bool,
+    /// Approximate length the stream is trimmed to.
+    #[serde(default = "default_events_stream_max_len")]
+    pub stream_max_len: u64,
+    /// Times a durable subscriber is handed an event before it is given up
+    /// on, acknowledged and counted in `events.dead_lettered`.
+    #[serde(default = "default_events_max_deliveries")]
+    pub max_deliveries: u32,
+    /// Endpoints each sent a signed POST per event.
+    #[serde(default)]
+    pub webhooks: Vec<EventWebhookConfig>,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            broadcast_capacity: default_events_broadcast_capacity(),
+            durable: false,
+            stream_max_len: default_events_stream_max_len(),
+            max_deliveries: default_events_max_deliveries(),
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+fn default_events_broadcast_capacity() -> usize {
+    1024
+}
+
+fn default_events_stream_max_len() -> u64 {
+    100_000
+}
+
+fn default_events_max_deliveries() -> u32 {
+    5
+}
+
+/// An endpoint domain events are POSTed to as JSON, signed like telemetry
+/// pushes (`webhook::sign` over the timestamp and body).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventWebhookConfig {
+    /// Unique; names the webhook's consumer group and metrics.
+    pub name: String,
+    pub url: String,
+    /// Environment variable holding the secret shared with the endpoint.
+    pub secret_env: String,
+    /// Event types sent (e.g. "status_changed"). Empty sends every event.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Attempts per event before the delivery fails.
+    #[serde(default = "default_event_webhook_attempts")]
+    pub attempts: u32,
+}
+
+fn default_event_webhook_attempts() -> u32 {
+    3
+}
+
+/// The engine's `/metrics/federate` endpoint, which serves the fetch and geo
+/// services' metrics alongside its own for setups where only the engine is
+/// reachable from Prometheus.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Serve GET /metrics/federate. Off: it answers 404.
+    #[serde(default)]
+    pub federate: bool,
+    /// How long to wait for each service's metrics before leaving them out.
+    #[serde(default = "default_metrics_federate_timeout_ms")]
+    pub federate_timeout_ms: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            federate: false,
+            federate_timeout_ms: default_metrics_federate_timeout_ms(),
+        }
+    }
+}
+
+fn default_metrics_federate_timeout_ms() -> u64 {
+    2000
+}
+
+/// The audit log of mutating tool calls and HTTP writes. Records are queued
+/// in memory and written in batches; when the queue is full new records are
+/// dropped and counted (`audit.dropped`) rather than slowing the caller.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditConfig {
+    #[serde(default = "default_audit_enabled")]
+    pub enabled: bool,
+    /// Records held in memory waiting for the writer.
+    #[serde(default = "default_audit_buffer_size")]
+    pub buffer_size: usize,
+    /// Most records written in one insert.
+    #[serde(default = "default_audit_batch_size")]
+    pub batch_size: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_audit_enabled(),
+            buffer_size: default_audit_buffer_size(),
+            batch_size: default_audit_batch_size(),
+        }
+    }
+}
+
+fn default_audit_enabled() -> bool {
+    true
+}
+
+fn default_audit_buffer_size() -> usize {
+    10_000
+}
+
+fn default_audit_batch_size() -> usize {
+    200
+}
+
+/// Handling of claims written from a translation, which keep the source's
+/// own wording in `original_content`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SourceLanguageConfig {
+    /// Also index `original_content` for keyword claim search, so a search in
+    /// the source's language finds the claim. Applied to the fulltext index at
+    /// startup.
+    #[serde(default)]
+    pub index_original_content: bool,
+}
+
+/// Runtime feature flags. Overrides set through POST /admin/flags are kept in
+/// Redis and beat these defaults on every replica.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeatureFlagsConfig {
+    /// Value of each flag while it has no override. Flags not listed are on.
+    #[serde(default)]
+    pub defaults: BTreeMap<FeatureFlag, bool>,
+    /// How often overrides are re-read from Redis.
+    #[serde(default = "default_flag_sync_interval_seconds")]
+    pub sync_interval_seconds: u64,
+}
+
+impl Default for FeatureFlagsConfig {
+    fn default() -> Self {
+        Self {
+            defaults: BTreeMap::new(),
+            sync_interval_seconds: default_flag_sync_interval_seconds(),
+        }
+    }
+}
+
+fn default_flag_sync_interval_seconds() -> u64 {
+    10
+}
+
+/// Admission control for Neo4j writes, so a burst of Processor extractions
+/// doesn't starve everything else of the database, and bounds on what a
+/// single write may create.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphWriteConfig {
+    /// Max write transactions in flight at once; further writes wait for a slot.
+    #[serde(default = "default_graph_max_concurrent_writes")]
+    pub max_concurrent_writes: usize,
+    /// Max entities one claim may reference (REFERENCES edges). Claim tools
+    /// drop references past it; the graph consistency check flags existing
+    /// claims over it.
+    #[serde(default = "default_graph_max_claim_references")]
+    pub max_claim_references: usize,
+    #[serde(default)]
+    pub coalesce: WriteCoalesceConfig,
+}
+
+impl Default for GraphWriteConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_writes: default_graph_max_concurrent_writes(),
+            max_claim_references: default_graph_max_claim_references(),
+            coalesce: WriteCoalesceConfig::default(),
+        }
+    }
+}
+
+/// Server-side ceilings on graph reads. A caller's limit above a ceiling is
+/// lowered to it before the query runs, so no tool or endpoint can ask Neo4j
+/// for an unbounded result.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphReadConfig {
+    /// Most results one entity, claim or relationship search returns.
+    #[serde(default = "default_graph_max_search_results")]
+    pub max_search_results: u32,
+    /// Most relationships one traversal, or one pair of entities, returns.
+    #[serde(default = "default_graph_max_traversal_results")]
+    pub max_traversal_results: u32,
+}
+
+impl Default for GraphReadConfig {
+    fn default() -> Self {
+        Self {
+            max_search_results: default_graph_max_search_results(),
+            max_traversal_results: default_graph_max_traversal_results(),
+        }
+    }
+}
+
+/// Batching of entity, claim and relationship creates into single UNWIND
+/// writes. Off by default.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WriteCoalesceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long the first create in a batch waits for others to join it.
+    #[serde(default = "default_write_coalesce_window_ms")]
+    pub window_ms: u64,
+    /// A batch is written as soon as this many creates are waiting.
+    #[serde(default = "default_write_coalesce_max_items")]
+    pub max_items: usize,
+}
+
+impl Default for WriteCoalesceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_ms: default_write_coalesce_window_ms(),
+            max_items: default_write_coalesce_max_items(),
+        }
+    }
+}
+
+/// Graph consistency checks (GET /graph/consistency, `graph-consistency`
+/// subcommand) and their periodic run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GraphConsistencyConfig {
+    /// How often the checks run in the background, reporting violation
+    /// counts as metrics. 0 disables the periodic run.
+    #[serde(default = "default_graph_consistency_check_interval_minutes")]
+    pub check_interval_minutes: u32,
+    /// Violating IDs listed per check.
+    #[serde(default = "default_graph_consistency_sample_size")]
+    pub sample_size: usize,
+    /// Violations fixed per write transaction by a repair.
+    #[serde(default = "default_graph_consistency_repair_batch_size")]
+    pub repair_batch_size: usize,
+    /// Claims a stub with a summary needs before it's flagged (and, on
+    /// repair, un-stubbed) as a full entity.
+    #[serde(default = "default_graph_consistency_unstub_min_claims")]
+    pub unstub_min_claims: u32,
+}
+
+impl Default for GraphConsistencyConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_minutes: default_graph_consistency_check_interval_minutes(),
+            sample_size: default_graph_consistency_sample_size(),
+            repair_batch_size: default_graph_consistency_repair_batch_size(),
+            unstub_min_claims: default_graph_consistency_unstub_min_claims(),
+        }
+    }
+}
+
+fn default_graph_consistency_check_interval_minutes() -> u32 {
+    360
+}
+
+fn default_graph_consistency_sample_size() -> usize {
+    10
+}
+
+fn default_graph_consistency_repair_batch_size() -> usize {
+    500
+}
+
+fn default_graph_consistency_unstub_min_claims() -> u32 {
+    3
+}
+
+/// Stub entity resolution: stubs that enough claims mention are merged into
+/// a full entity that now matches them, or queued for a `profile_entity` work
+/// order that fleshes them out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StubResolutionConfig {
+    /// Run the resolution pass in the background. POST /admin/stubs/resolve
+    /// works either way.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_stub_resolution_interval_minutes")]
+    pub interval_minutes: u32,
+    /// Claims (published or referencing) a stub needs before it is resolved.
+    #[serde(default = "default_stub_resolution_min_claims")]
+    pub min_claims: u32,
+    /// Stubs considered per run, most-mentioned first.
+    #[serde(default = "default_stub_resolution_max_stubs_per_run")]
+    pub max_stubs_per_run: u32,
+    /// Name similarity (Jaro-Winkler against canonical name and aliases) at
+    /// which a full entity of the same kind absorbs the stub.
+    #[serde(default = "default_stub_resolution_name_threshold")]
+    pub name_threshold: f64,
+    /// Embedding similarity at which a full entity absorbs the stub.
+    #[serde(default = "default_stub_resolution_embedding_threshold")]
+    pub embedding_threshold: f64,
+    /// `profile_entity` work orders enqueued per run, to bound LLM cost.
+    #[serde(default = "default_stub_resolution_max_enrichments_per_run")]
+    pub max_enrichments_per_run: u32,
+    /// Delete stubs no claim mentions and nothing relates to once they are
+    /// this many days old. None keeps them.
+    #[serde(default)]
+    pub delete_unreferenced_after_days: Option<u32>,
+}
+
+impl Default for StubResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_stub_resolution_interval_minutes(),
+            min_claims: default_stub_resolution_min_claims(),
+            max_stubs_per_run: default_stub_resolution_max_stubs_per_run(),
+            name_threshold: default_stub_resolution_name_threshold(),
+            embedding_threshold: default_stub_resolution_embedding_threshold(),
+            max_enrichments_per_run: default_stub_resolution_max_enrichments_per_run(),
+            delete_unreferenced_after_days: None,
+        }
+    }
+}
+
+fn default_stub_resolution_interval_minutes() -> u32 {
+    1440
+}
+
+fn default_stub_resolution_min_claims() -> u32 {
+    2
+}
+
+fn default_stub_resolution_max_stubs_per_run() -> u32 {
+    200
+}
+
+fn default_stub_resolution_name_threshold() -> f64 {
+    0.95
+}
+
+fn default_stub_resolution_embedding_threshold() -> f64 {
+    0.93
+}
+
+fn default_stub_resolution_max_enrichments_per_run() -> u32 {
+    10
+}
+
+fn default_graph_max_concurrent_writes() -> usize {
+    4
+}
+
+fn default_graph_max_claim_references() -> usize {
+    25
+}
+
+fn default_graph_max_search_results() -> u32 {
+    200
+}
+
+fn default_graph_max_traversal_results() -> u32 {
+    500
+}
+
+fn default_write_coalesce_window_ms() -> u64 {
+    20
+}
+
+fn default_write_coalesce_max_items() -> usize {
+    50
+}
+
+/// Outbound HTTP clients (see `http_client`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HttpClientConfig {
+    #[serde(default = "default_http_connect_timeout_seconds")]
+    pub connect_timeout_seconds: u64,
+    /// Whole-request timeout. None = no limit (LLM calls can stream for minutes).
+    #[serde(default)]
+    pub request_timeout_seconds: Option<u64>,
+    /// Idle pooled connections are closed after this long.
+    #[serde(default = "default_http_pool_idle_timeout_seconds")]
+    pub pool_idle_timeout_seconds: u64,
+    #[serde(default = "default_http_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// PEM bundle of extra CA roots (e.g. a TLS-intercepting egress proxy),
+    /// trusted alongside the built-in roots.
+    #[serde(default)]
+    pub ca_bundle: Option<std::path::PathBuf>,
+    /// Hosts that bypass every proxy, in NO_PROXY syntax. Unset falls back to
+    /// the NO_PROXY env var.
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    #[serde(default)]
+    pub proxies: ProxyConfig,
+    #[serde(default)]
+    pub fetch_replicas: FetchReplicasConfig,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_seconds: default_http_connect_timeout_seconds(),
+            request_timeout_seconds: None,
+            pool_idle_timeout_seconds: default_http_pool_idle_timeout_seconds(),
+            pool_max_idle_per_host: default_http_pool_max_idle_per_host(),
+            ca_bundle: None,
+            no_proxy: None,
+            proxies: ProxyConfig::default(),
+            fetch_replicas: FetchReplicasConfig::default(),
+        }
+    }
+}
+
+/// Proxy URL per destination class (http, https or socks5). Unset falls back
+/// to HTTPS_PROXY / HTTP_PROXY / ALL_PROXY.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// LLM provider APIs.
+    #[serde(default)]
+    pub llm: Option<String>,
+    /// Embedding provider APIs.
+    #[serde(default)]
+    pub embedding: Option<String>,
+    /// Web pages fetched by the fetch service.
+    #[serde(default)]
+    pub fetch: Option<String>,
+    /// Engine to fetch service.
+    #[serde(default)]
+    pub internal: Option<String>,
+}
+
+/// How the engine's tools spread requests over the Fetch service's replicas
+/// (FETCH_BASE_URL, comma-separated).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FetchReplicasConfig {
+    /// A replica's latency and error rate are measured over this many
+    /// seconds of recent requests.
+    #[serde(default = "default_fetch_replica_window_seconds")]
+    pub window_seconds: u64,
+    /// Send a slow idempotent request (a plain fetch, a search) to a second
+    /// replica too and take whichever answers first. Off with one replica.
+    #[serde(default = "default_fetch_hedging")]
+    pub hedging: bool,
+    /// Hedge once a request has run longer than this percentile of the
+    /// replica's recent latencies.
+    #[serde(default = "default_fetch_hedge_percentile")]
+    pub hedge_percentile: f64,
+    /// Hedge delay while a replica has too few recent requests for a percentile.
+    #[serde(default = "default_fetch_initial_hedge_delay_ms")]
+    pub initial_hedge_delay_ms: u64,
+    /// Never hedge sooner than this.
+    #[serde(default = "default_fetch_min_hedge_delay_ms")]
+    pub min_hedge_delay_ms: u64,
+    /// Hedged requests in flight at once across all sessions. Past it, slow
+    /// requests wait for their replica, so hedging can't double the load.
+    #[serde(default = "default_fetch_max_concurrent_hedges")]
+    pub max_concurrent_hedges: usize,
+}
+
+impl Default for FetchReplicasConfig {
+    fn default() -> Self {
+        Self {
+            window_seconds: default_fetch_replica_window_seconds(),
+            hedging: default_fetch_hedging(),
+            hedge_percentile: default_fetch_hedge_percentile(),
+            initial_hedge_delay_ms: default_fetch_initial_hedge_delay_ms(),
+            min_hedge_delay_ms: default_fetch_min_hedge_delay_ms(),
+            max_concurrent_hedges: default_fetch_max_concurrent_hedges(),
+        }
+    }
+}
+
+fn default_fetch_replica_window_seconds() -> u64 {
+    120
+}
+
+fn default_fetch_hedging() -> bool {
+    true
+}
+
+fn default_fetch_hedge_percentile() -> f64 {
+    0.95
+}
+
+fn default_fetch_initial_hedge_delay_ms() -> u64 {
+    3000
+}
+
+fn default_fetch_min_hedge_delay_ms() -> u64 {
+    250
+}
+
+fn default_fetch_max_concurrent_hedges() -> usize {
+    4
+}
+
+fn default_http_connect_timeout_seconds() -> u64 {
+    10
+}
+
+fn default_http_pool_idle_timeout_seconds() -> u64 {
+    90
+}
+
+fn default_http_pool_max_idle_per_host() -> usize {
+    16
+}
+
+/// Investigation prompt pre-flight (`llm.triage`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TriageConfig {
+    /// Triage POST /investigate prompts that don't set `preflight`.
+    #[serde(default)]
+    pub preflight_by_default: bool,
+    /// Triage calls per request, re-asking after a reply that isn't valid JSON.
+    #[serde(default = "default_triage_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl Default for TriageConfig {
+    fn default() -> Self {
+        Self {
+            preflight_by_default: false,
+            max_attempts: default_triage_max_attempts(),
+        }
+    }
+}
+
+fn default_triage_max_attempts() -> u32 {
+    2
+}
+
+/// STIX 2.1 export settings.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StixConfig {
+    /// Entity kind → STIX object mapping. Kinds not listed export as custom
+    /// `x-autosint-entity` objects.
+    #[serde(default = "default_stix_kind_mappings")]
+    pub kind_mappings: HashMap<String, StixObjectMapping>,
+}
+
+impl Default for StixConfig {
+    fn default() -> Self {
+        Self {
+            kind_mappings: default_stix_kind_mappings(),
+        }
+    }
+}
+
+/// Target STIX domain object for one entity kind.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StixObjectMapping {
+    /// STIX SDO type ("identity", "location", "threat-actor", ...).
+    #[serde(rename = "type")]
+    pub stix_type: String,
+    /// `identity_class` for identity objects ("organization", "individual", ...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_class: Option<String>,
+}
+
+fn default_stix_kind_mappings() -> HashMap<String, StixObjectMapping> {
+    let identity = |class: &str| StixObjectMapping {
+        stix_type: "identity".into(),
+        identity_class: Some(class.into()),
+    };
+    let location = || StixObjectMapping {
+        stix_type: "location".into(),
+        identity_class: None,
+    };
+
+    HashMap::from([
+        ("organization".into(), identity("organization")),
+        ("company".into(), identity("organization")),
+        ("government".into(), identity("organization")),
+        ("person".into(), identity("individual")),
+        ("location".into(), location()),
+        ("country".into(), location()),
+        ("region".into(), location()),
+        ("city".into(), location()),
+    ])
 }
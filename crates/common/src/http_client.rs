@@ -0,0 +1,437 @@
+//! Outbound HTTP clients, built once per destination class and shared.
+//!
+//! Proxies come from `[http.proxies]` in system.toml, falling back to the
+//! usual `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY` env vars (either case);
+//! `[http].no_proxy` likewise takes precedence over `NO_PROXY`. SOCKS proxy
+//! URLs need the `socks` feature.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::{NoProxy, Proxy};
+
+use crate::config::HttpClientConfig;
+
+/// Class of service a client talks to. Each gets its own proxy setting and
+/// request counter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Destination {
+    /// LLM provider APIs.
+    Llm,
+    /// Embedding provider APIs.
+    Embedding,
+    /// Arbitrary web pages fetched by the fetch service.
+    Fetch,
+    /// AutOSINT's own services (engine to fetch service).
+    Internal,
+}
+
+impl Destination {
+    pub const ALL: [Self; 4] = [Self::Llm, Self::Embedding, Self::Fetch, Self::Internal];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Llm => "llm",
+            Self::Embedding => "embedding",
+            Self::Fetch => "fetch",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HttpClientError {
+    #[error("invalid {destination} proxy {url}: {message}")]
+    Proxy {
+        destination: &'static str,
+        url: String,
+        message: String,
+    },
+
+    #[error("invalid CA bundle {path}: {message}")]
+    CaBundle { path: String, message: String },
+
+    #[error("failed to build HTTP client: {0}")]
+    Build(String),
+}
+
+/// Apply `config` for `destination` to `builder`: timeouts, connection pool,
+/// extra CA roots and proxy. Use on builders that already carry other
+/// settings (e.g. `tls::client_builder_from_env`).
+pub fn configure(
+    builder: reqwest::ClientBuilder,
+    config: &HttpClientConfig,
+    destination: Destination,
+) -> Result<reqwest::ClientBuilder, HttpClientError> {
+    configure_with_env(builder, config, destination, env_var)
+}
+
+/// A client for `destination` built from `config`.
+pub fn build(
+    config: &HttpClientConfig,
+    destination: Destination,
+) -> Result<reqwest::Client, HttpClientError> {
+    configure(reqwest::Client::builder(), config, destination)?
+        .build()
+        .map_err(|e| HttpClientError::Build(e.to_string()))
+}
+
+fn configure_with_env(
+    builder: reqwest::ClientBuilder,
+    config: &HttpClientConfig,
+    destination: Destination,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<reqwest::ClientBuilder, HttpClientError> {
+    // Proxies are resolved here, so reqwest must not add its own from the env.
+    let mut builder = builder
+        .no_proxy()
+        .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_seconds))
+        .pool_max_idle_per_host(config.pool_max_idle_per_host);
+    if let Some(seconds) = config.request_timeout_seconds {
+        builder = builder.timeout(Duration::from_secs(seconds));
+    }
+
+    for proxy in proxies(config, destination, &lookup)? {
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(path) = &config.ca_bundle {
+        let ca_error = |message: String| HttpClientError::CaBundle {
+            path: path.display().to_string(),
+            message,
+        };
+        let pem = std::fs::read(path).map_err(|e| ca_error(e.to_string()))?;
+        let certs =
+            reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| ca_error(e.to_string()))?;
+        if certs.is_empty() {
+            return Err(ca_error("no certificates found".into()));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// The proxies for `destination`: the configured one for all schemes, or
+/// whatever the env sets per scheme.
+fn proxies(
+    config: &HttpClientConfig,
+    destination: Destination,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<Vec<Proxy>, HttpClientError> {
+    let no_proxy = match &config.no_proxy {
+        Some(list) => NoProxy::from_string(list),
+        None => env_either_case("NO_PROXY", lookup).and_then(|list| NoProxy::from_string(&list)),
+    };
+
+    // (env var, scheme the proxy applies to); the configured proxy covers all.
+    let scheme_urls: Vec<(&str, String)> = match configured_proxy(config, destination) {
+        Some(url) => vec![("ALL_PROXY", url.to_string())],
+        None => ["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY"]
+            .into_iter()
+            .filter_map(|var| env_either_case(var, lookup).map(|url| (var, url)))
+            .collect(),
+    };
+
+    scheme_urls
+        .into_iter()
+        .map(|(var, url)| {
+            let proxy_error = |message: String| HttpClientError::Proxy {
+                destination: destination.as_str(),
+                url: crate::sanitize::sanitize_error(&url),
+                message,
+            };
+            if url.starts_with("socks") && !cfg!(feature = "socks") {
+                return Err(proxy_error(
+                    "SOCKS proxies need autosint-common's `socks` feature".into(),
+                ));
+            }
+            let proxy = match var {
+                "HTTPS_PROXY" => Proxy::https(&url),
+                "HTTP_PROXY" => Proxy::http(&url),
+                _ => Proxy::all(&url),
+            }
+            .map_err(|e| proxy_error(e.to_string()))?;
+            Ok(proxy.no_proxy(no_proxy.clone()))
+        })
+        .collect()
+}
+
+fn configured_proxy(config: &HttpClientConfig, destination: Destination) -> Option<&str> {
+    let proxies = &config.proxies;
+    match destination {
+        Destination::Llm => proxies.llm.as_deref(),
+        Destination::Embedding => proxies.embedding.as_deref(),
+        Destination::Fetch => proxies.fetch.as_deref(),
+        Destination::Internal => proxies.internal.as_deref(),
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn env_either_case(name: &str, lookup: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    lookup(name).or_else(|| lookup(&name.to_lowercase()))
+}
+
+/// Count a request to `destination` in `http.outbound.requests`, and its
+/// transport failure in `http.outbound.errors`, then send it.
+pub async fn send(
+    destination: Destination,
+    request: reqwest::RequestBuilder,
+) -> reqwest::Result<reqwest::Response> {
+    let label = destination.as_str();
+    metrics::counter!("http.outbound.requests", "destination" => label).increment(1);
+    let result = request.send().await;
+    if result.is_err() {
+        metrics::counter!("http.outbound.errors", "destination" => label).increment(1);
+    }
+    result
+}
+
+/// One client per destination, shared process-wide so every caller shares
+/// its connection pool.
+static SHARED: OnceLock<[reqwest::Client; 4]> = OnceLock::new();
+
+fn build_all(config: &HttpClientConfig) -> Result<[reqwest::Client; 4], HttpClientError> {
+    Ok([
+        build(config, Destination::Llm)?,
+        build(config, Destination::Embedding)?,
+        build(config, Destination::Fetch)?,
+        build(config, Destination::Internal)?,
+    ])
+}
+
+/// Build the shared clients from `config`. Call once at startup, before any
+/// client is handed out; later calls are ignored.
+pub fn init_shared(config: &HttpClientConfig) -> Result<(), HttpClientError> {
+    if SHARED.set(build_all(config)?).is_err() {
+        tracing::warn!("Shared HTTP clients already initialized; keeping the first configuration");
+    }
+    Ok(())
+}
+
+/// The shared client for `destination`. Before `init_shared` (e.g. in tests),
+/// clients are built from the defaults and the env proxy settings.
+pub fn shared(destination: Destination) -> reqwest::Client {
+    let clients = SHARED.get_or_init(|| {
+        build_all(&HttpClientConfig::default()).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Invalid proxy environment; using plain HTTP clients");
+            std::array::from_fn(|_| reqwest::Client::new())
+        })
+    });
+    clients[destination as usize].clone()
+}
+
+/// Host part of a URL, as the Fetch service keys its per-domain rate limits
+/// and stats.
+pub fn extract_domain(url: &str) -> String {
+    url.split("//")
+        .nth(1)
+        .unwrap_or(url)
+        .split('/')
+        .next()
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server answering every request with `body`. Records the
+    /// request line of each request it sees — absolute-form URIs for proxied
+    /// requests, origin-form for direct ones.
+    async fn stub(body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let recorded = Arc::clone(&recorded);
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let head = String::from_utf8_lossy(&buf[..n]);
+                    let line = head.lines().next().unwrap_or_default().to_string();
+                    recorded.lock().unwrap().push(line);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (format!("http://{}", addr), seen)
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    async fn get(
+        config: &HttpClientConfig,
+        destination: Destination,
+        lookup: impl Fn(&str) -> Option<String>,
+        url: &str,
+    ) -> String {
+        let client = configure_with_env(reqwest::Client::builder(), config, destination, lookup)
+            .unwrap()
+            .build()
+            .unwrap();
+        send(destination, client.get(url))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_domain() {
+        assert_eq!(extract_domain("https://example.com/path"), "example.com");
+        assert_eq!(extract_domain("http://www.test.org/a/b"), "www.test.org");
+    }
+
+    #[tokio::test]
+    async fn test_configured_proxy_applied_per_destination() {
+        let (proxy_url, proxied) = stub("via proxy").await;
+        let mut config = HttpClientConfig::default();
+        config.proxies.llm = Some(proxy_url);
+
+        let body = get(
+            &config,
+            Destination::Llm,
+            env(&[]),
+            "http://llm.example.test/v1/chat/completions",
+        )
+        .await;
+        assert_eq!(body, "via proxy");
+        assert_eq!(
+            proxied.lock().unwrap().as_slice(),
+            ["GET http://llm.example.test/v1/chat/completions HTTP/1.1"]
+        );
+
+        // Other destinations don't use the LLM proxy.
+        let (target, direct) = stub("direct").await;
+        let body = get(&config, Destination::Embedding, env(&[]), &target).await;
+        assert_eq!(body, "direct");
+        assert_eq!(direct.lock().unwrap().as_slice(), ["GET / HTTP/1.1"]);
+        assert_eq!(proxied.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_config_takes_precedence_over_env() {
+        let (env_proxy, env_seen) = stub("env proxy").await;
+        let (config_proxy, config_seen) = stub("config proxy").await;
+
+        // Env alone is respected.
+        let config = HttpClientConfig::default();
+        let lookup = || env(&[("http_proxy", env_proxy.as_str())]);
+        let body = get(
+            &config,
+            Destination::Embedding,
+            lookup(),
+            "http://embeddings.example.test/",
+        )
+        .await;
+        assert_eq!(body, "env proxy");
+
+        let mut config = HttpClientConfig::default();
+        config.proxies.embedding = Some(config_proxy);
+        let body = get(
+            &config,
+            Destination::Embedding,
+            lookup(),
+            "http://embeddings.example.test/",
+        )
+        .await;
+        assert_eq!(body, "config proxy");
+        assert_eq!(env_seen.lock().unwrap().len(), 1);
+        assert_eq!(config_seen.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_no_proxy_excludes_internal_fetch_service() {
+        let (proxy_url, proxied) = stub("via proxy").await;
+        let (fetch_service, direct) = stub("fetch service").await;
+
+        // A blanket env proxy with NO_PROXY covering the fetch service host.
+        let lookup = || {
+            env(&[
+                ("HTTP_PROXY", proxy_url.as_str()),
+                ("NO_PROXY", "localhost,127.0.0.1"),
+            ])
+        };
+        let config = HttpClientConfig::default();
+        let body = get(&config, Destination::Internal, lookup(), &fetch_service).await;
+        assert_eq!(body, "fetch service");
+        assert_eq!(direct.lock().unwrap().len(), 1);
+        assert!(proxied.lock().unwrap().is_empty());
+
+        // The configured list replaces NO_PROXY: without the exclusion the
+        // request goes through the proxy.
+        let config = HttpClientConfig {
+            no_proxy: Some("fetch.internal".into()),
+            ..Default::default()
+        };
+        let body = get(&config, Destination::Internal, lookup(), &fetch_service).await;
+        assert_eq!(body, "via proxy");
+        assert_eq!(proxied.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_settings_rejected() {
+        let mut config = HttpClientConfig::default();
+        config.proxies.fetch = Some("http://user:hunter2@[bad".into());
+        let err = configure_with_env(
+            reqwest::Client::builder(),
+            &config,
+            Destination::Fetch,
+            env(&[]),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            HttpClientError::Proxy {
+                destination: "fetch",
+                ..
+            }
+        ));
+        assert!(!err.to_string().contains("hunter2"), "{}", err);
+
+        if !cfg!(feature = "socks") {
+            let mut config = HttpClientConfig::default();
+            config.proxies.llm = Some("socks5://127.0.0.1:1080".into());
+            let err = build(&config, Destination::Llm).unwrap_err();
+            assert!(err.to_string().contains("socks"), "{}", err);
+        }
+
+        let config = HttpClientConfig {
+            ca_bundle: Some(std::env::temp_dir().join("autosint-missing-ca.pem")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build(&config, Destination::Llm),
+            Err(HttpClientError::CaBundle { .. })
+        ));
+    }
+}
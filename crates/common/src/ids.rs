@@ -63,3 +63,16 @@ define_id!(
 );
 define_id!(InvestigationId, "Typed wrapper for investigation UUIDs.");
 define_id!(WorkOrderId, "Typed wrapper for work order UUIDs.");
+define_id!(
+    SourceDocumentId,
+    "Typed wrapper for ingested source document UUIDs."
+);
+define_id!(MonitorId, "Typed wrapper for source monitor UUIDs.");
+define_id!(
+    EnrichmentRequestId,
+    "Typed wrapper for external enrichment request UUIDs."
+);
+define_id!(
+    MaintenanceJobId,
+    "Typed wrapper for admin maintenance job UUIDs."
+);
@@ -1,8 +1,18 @@
 pub mod api;
 pub mod config;
 pub mod error;
+pub mod http_client;
 pub mod ids;
+pub mod pii;
+#[cfg(feature = "server")]
+pub mod prometheus;
+#[cfg(feature = "html")]
+pub mod readable;
+pub mod retry;
+pub mod sanitize;
+pub mod tls;
 pub mod types;
+pub mod webhook;
 
 pub use error::{AutOsintError, Result};
 pub use ids::*;
@@ -0,0 +1,373 @@
+//! Detection and redaction of personal data in claim content.
+//!
+//! Detectors are compiled once, when the scrubber is built from
+//! [`PiiRedactionConfig`], and then run over every claim. Each match is
+//! replaced with a typed placeholder (`[REDACTED:email]`); callers apply the
+//! configured [`PiiPolicy`] to decide whether the claim is stored redacted or
+//! refused.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+use crate::config::{PiiDetectorKind, PiiPolicy, PiiRedactionConfig};
+
+const EMAIL: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+
+/// International numbers: a `+` country code, an optional bracketed trunk
+/// or area code, then digit groups.
+const PHONE_INTERNATIONAL: &str = r"\+\d{1,3}(?:[ .-]?\(\d{1,4}\))?(?:[ .-]?\d{1,5}){2,6}";
+/// North American numbers: `(202) 555-0143`, `202-555-0143`, `202.555.0143`.
+const PHONE_NANP: &str = r"(?:\(\d{3}\)[ .-]?|\b\d{3}[.-])\d{3}[.-]\d{4}\b";
+/// National numbers with a leading trunk zero: `020 7946 0958`, `01 42 68 53 00`.
+const PHONE_TRUNK: &str = r"\b0\d{1,4}(?:[ -]\d{2,6}){1,4}\b";
+
+/// US Social Security numbers.
+const SSN: &str = r"\b\d{3}-\d{2}-\d{4}\b";
+/// UK National Insurance numbers, with or without spaces.
+const NINO: &str = r"\b[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b";
+
+/// Personal data found in one claim, redacted or not.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scrubbed {
+    /// The content with every match replaced by its placeholder.
+    pub content: String,
+    /// Matches by detector name. Empty when nothing was found.
+    pub redactions: BTreeMap<String, u32>,
+}
+
+impl Scrubbed {
+    pub fn is_clean(&self) -> bool {
+        self.redactions.is_empty()
+    }
+}
+
+/// `"2 email, 1 phone"`, for messages about what a claim contained.
+pub fn describe_redactions(redactions: &BTreeMap<String, u32>) -> String {
+    redactions
+        .iter()
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Rejects matches the pattern alone can't tell apart from personal data.
+type Accept = fn(&str) -> bool;
+
+struct Detector {
+    name: String,
+    regex: Regex,
+    accept: Accept,
+}
+
+/// The configured personal data detectors, compiled.
+pub struct PiiScrubber {
+    policy: PiiPolicy,
+    detectors: Vec<Detector>,
+}
+
+impl PiiScrubber {
+    /// Compile the built-in and configured detectors. Fails on a pattern
+    /// that doesn't compile or a name that is empty, malformed or taken.
+    pub fn new(config: &PiiRedactionConfig) -> Result<Self, String> {
+        let mut detectors = Vec::new();
+        for kind in &config.detectors {
+            let patterns: &[(&str, Accept)] = match kind {
+                PiiDetectorKind::Email => &[(EMAIL, accept_any)],
+                PiiDetectorKind::Phone => &[
+                    (PHONE_INTERNATIONAL, accept_international_phone),
+                    (PHONE_NANP, accept_any),
+                    (PHONE_TRUNK, accept_trunk_phone),
+                ],
+                PiiDetectorKind::NationalId => &[(SSN, accept_ssn), (NINO, accept_nino)],
+            };
+            for (pattern, accept) in patterns {
+                detectors.push(Detector {
+                    name: kind.as_str().to_string(),
+                    regex: Regex::new(pattern).expect("built-in PII pattern is valid"),
+                    accept: *accept,
+                });
+            }
+        }
+
+        for custom in &config.patterns {
+            let name = custom.name.trim();
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                return Err(format!(
+                    "PII pattern name '{}' must be lowercase letters, digits and underscores",
+                    custom.name
+                ));
+            }
+            let builtin = [
+                PiiDetectorKind::Email,
+                PiiDetectorKind::Phone,
+                PiiDetectorKind::NationalId,
+            ]
+            .iter()
+            .any(|kind| kind.as_str() == name);
+            let duplicate = config
+                .patterns
+                .iter()
+                .filter(|other| other.name.trim() == name)
+                .count()
+                > 1;
+            if builtin || duplicate {
+                return Err(format!("PII pattern name '{}' is already in use", name));
+            }
+            let regex = Regex::new(&custom.pattern)
+                .map_err(|e| format!("PII pattern '{}' is invalid: {}", name, e))?;
+            detectors.push(Detector {
+                name: name.to_string(),
+                regex,
+                accept: accept_any,
+            });
+        }
+
+        Ok(Self {
+            policy: config.policy,
+            detectors,
+        })
+    }
+
+    pub fn policy(&self) -> PiiPolicy {
+        self.policy
+    }
+
+    /// Find personal data in `content` and replace it with placeholders.
+    /// Text inside occurrences of `raw_source_link` is left alone: the claim
+    /// cites its own source, even when the URL holds an address.
+    pub fn scrub(&self, content: &str, raw_source_link: Option<&str>) -> Scrubbed {
+        let protected: Vec<(usize, usize)> = raw_source_link
+            .map(str::trim)
+            .filter(|link| !link.is_empty())
+            .map(|link| {
+                content
+                    .match_indices(link)
+                    .map(|(start, m)| (start, start + m.len()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut matches: Vec<(usize, usize, &str)> = Vec::new();
+        for detector in &self.detectors {
+            for m in detector.regex.find_iter(content) {
+                if m.is_empty() || !(detector.accept)(m.as_str()) {
+                    continue;
+                }
+                // A `+` glued to a word is part of something else (`C++11`).
+                if content[..m.start()]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric())
+                    && m.as_str().starts_with('+')
+                {
+                    continue;
+                }
+                if protected
+                    .iter()
+                    .any(|&(start, end)| m.start() < end && start < m.end())
+                {
+                    continue;
+                }
+                matches.push((m.start(), m.end(), detector.name.as_str()));
+            }
+        }
+
+        // Earliest match first; of two starting together, the longer.
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+        let mut scrubbed = Scrubbed::default();
+        let mut cursor = 0;
+        for (start, end, name) in matches {
+            if start < cursor {
+                continue;
+            }
+            scrubbed.content.push_str(&content[cursor..start]);
+            scrubbed.content.push_str(&format!("[REDACTED:{}]", name));
+            *scrubbed.redactions.entry(name.to_string()).or_insert(0) += 1;
+            cursor = end;
+        }
+        scrubbed.content.push_str(&content[cursor..]);
+        scrubbed
+    }
+}
+
+fn accept_any(_: &str) -> bool {
+    true
+}
+
+fn digit_count(s: &str) -> usize {
+    s.chars().filter(|c| c.is_ascii_digit()).count()
+}
+
+/// E.164 numbers have at most 15 digits; fewer than 8 is a figure such as
+/// `+2.5`.
+fn accept_international_phone(s: &str) -> bool {
+    (8..=15).contains(&digit_count(s))
+}
+
+fn accept_trunk_phone(s: &str) -> bool {
+    (10..=11).contains(&digit_count(s))
+}
+
+/// Area 000, 666 and 900-999, group 00 and serial 0000 are never issued.
+fn accept_ssn(s: &str) -> bool {
+    let mut parts = s.split('-');
+    let (Some(area), Some(group), Some(serial)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+/// Prefixes BG, GB, KN, NK, NT, TN and ZZ are never issued.
+fn accept_nino(s: &str) -> bool {
+    !matches!(&s[..2], "BG" | "GB" | "KN" | "NK" | "NT" | "TN" | "ZZ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::PiiPatternConfig;
+
+    fn scrubber() -> PiiScrubber {
+        PiiScrubber::new(&PiiRedactionConfig {
+            enabled: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn kinds(content: &str) -> BTreeMap<String, u32> {
+        scrubber().scrub(content, None).redactions
+    }
+
+    #[test]
+    fn test_redacts_with_typed_placeholders() {
+        let scrubbed = scrubber().scrub(
+            "Contact j.doe+press@example.co.uk or call +44 20 7946 0958; SSN 123-45-6789.",
+            None,
+        );
+        assert_eq!(
+            scrubbed.content,
+            "Contact [REDACTED:email] or call [REDACTED:phone]; SSN [REDACTED:national_id]."
+        );
+        assert_eq!(
+            describe_redactions(&scrubbed.redactions),
+            "1 email, 1 national_id, 1 phone"
+        );
+    }
+
+    #[test]
+    fn test_positive_corpus() {
+        let corpus = [
+            ("press@ministry.gov.ua", "email"),
+            ("First.Last_99@sub.mail.example.org", "email"),
+            ("+1-202-555-0143", "phone"),
+            ("+1 (202) 555-0143", "phone"),
+            ("+44 (0)20 7946 0958", "phone"),
+            ("+33 1 42 68 53 00", "phone"),
+            ("+49 30 1234567", "phone"),
+            ("+7 (495) 123-45-67", "phone"),
+            ("+86 138 0013 8000", "phone"),
+            ("+380441234567", "phone"),
+            ("(202) 555-0143", "phone"),
+            ("202.555.0143", "phone"),
+            ("020 7946 0958", "phone"),
+            ("07700 900123", "phone"),
+            ("01 42 68 53 00", "phone"),
+            ("078-05-1120", "national_id"),
+            ("AB 12 34 56 C", "national_id"),
+            ("JK123456D", "national_id"),
+        ];
+        for (text, kind) in corpus {
+            let content = format!("Reached at {} yesterday.", text);
+            assert_eq!(
+                kinds(&content),
+                BTreeMap::from([(kind.to_string(), 1)]),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_negative_corpus() {
+        let corpus = [
+            "GDP grew +2.3% in 2024-2025.",
+            "The vote was held on 2024-03-15 at 14:30.",
+            "Revenue reached 1,234,567,890 dollars.",
+            "The vessel IMO 9321483 sailed from 51.5074, -0.1278.",
+            "ISBN 978-3-16-148410-0 lists the report.",
+            "Follow @ministry_press for updates.",
+            "Reply to user@localhost only.",
+            "Shares rose +15 points to 4,512.",
+            "Figures 0.5 and 0.25 were revised.",
+            "Case 000-12-3456 and 666-12-3456 are test numbers.",
+            "Reference GB 12 34 56 A is invalid.",
+            "Version 10.4.2 shipped on 12/05/2024.",
+        ];
+        for text in corpus {
+            assert_eq!(kinds(text), BTreeMap::new(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_source_link_is_not_redacted() {
+        let link = "https://lists.example.org/archive?from=editor@example.org";
+        let content = format!(
+            "The notice ({}) asks readers to write to editor@example.org.",
+            link
+        );
+        let scrubbed = scrubber().scrub(&content, Some(link));
+        assert_eq!(
+            scrubbed.content,
+            format!(
+                "The notice ({}) asks readers to write to [REDACTED:email].",
+                link
+            )
+        );
+        assert_eq!(scrubbed.redactions["email"], 1);
+
+        // Other URLs get no such exemption.
+        let scrubbed = scrubber().scrub(&content, Some("https://example.org"));
+        assert_eq!(scrubbed.redactions["email"], 2);
+    }
+
+    #[test]
+    fn test_custom_patterns_and_detector_selection() {
+        let scrubber = PiiScrubber::new(&PiiRedactionConfig {
+            enabled: true,
+            policy: PiiPolicy::Reject,
+            detectors: vec![PiiDetectorKind::Email],
+            patterns: vec![PiiPatternConfig {
+                name: "passport".into(),
+                pattern: r"\b[A-Z]{2}\d{7}\b".into(),
+            }],
+        })
+        .unwrap();
+        assert_eq!(scrubber.policy(), PiiPolicy::Reject);
+        let scrubbed = scrubber.scrub("Passport XA1234567, phone +44 20 7946 0958.", None);
+        assert_eq!(
+            scrubbed.content,
+            "Passport [REDACTED:passport], phone +44 20 7946 0958."
+        );
+        assert!(!scrubbed.is_clean());
+        assert!(scrubber.scrub("Nothing here.", None).is_clean());
+
+        let config = |name: &str, pattern: &str| PiiRedactionConfig {
+            patterns: vec![PiiPatternConfig {
+                name: name.into(),
+                pattern: pattern.into(),
+            }],
+            ..Default::default()
+        };
+        assert!(PiiScrubber::new(&config("passport", "[unclosed")).is_err());
+        assert!(PiiScrubber::new(&config("email", "x")).is_err());
+        assert!(PiiScrubber::new(&config("Pass Port", "x")).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! The `/metrics` endpoint every service exposes.
+//!
+//! Everything is configured from env vars. `{PREFIX}_METRICS_TOKEN`, when set,
+//! must be presented as `Authorization: Bearer <token>`; it is separate from
+//! the API keys so scrape credentials can rotate on their own. Every metric
+//! carries `service`, `version` and `instance` labels, plus `environment` when
+//! `AUTOSINT_ENVIRONMENT` is set. The instance is `{PREFIX}_INSTANCE_ID`, else
+//! `HOSTNAME`, else random per process.
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use sha2::{Digest, Sha256};
+
+/// Content type of the Prometheus text exposition format.
+pub const EXPOSITION_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Global labels stamped on every metric a service emits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetricsLabels {
+    pub service: String,
+    pub version: String,
+    pub instance: String,
+    pub environment: Option<String>,
+}
+
+impl MetricsLabels {
+    /// Labels for `service`; pass `env!("CARGO_PKG_VERSION")` as `version`.
+    pub fn from_env(prefix: &str, service: &str, version: &str) -> Self {
+        Self::from_lookup(prefix, service, version, env_value)
+    }
+
+    fn from_lookup(
+        prefix: &str,
+        service: &str,
+        version: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let instance = lookup(&format!("{}_INSTANCE_ID", prefix))
+            .or_else(|| lookup("HOSTNAME"))
+            .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..8].to_string());
+        Self {
+            service: service.into(),
+            version: version.into(),
+            instance,
+            environment: lookup("AUTOSINT_ENVIRONMENT"),
+        }
+    }
+
+    /// A builder with these labels applied globally.
+    pub fn builder(&self) -> PrometheusBuilder {
+        let builder = PrometheusBuilder::new()
+            .add_global_label("service", &self.service)
+            .add_global_label("version", &self.version)
+            .add_global_label("instance", &self.instance);
+        match &self.environment {
+            Some(environment) => builder.add_global_label("environment", environment),
+            None => builder,
+        }
+    }
+}
+
+/// Read `{prefix}_METRICS_TOKEN`. `None` leaves /metrics open.
+pub fn token_from_env(prefix: &str) -> Option<String> {
+    env_value(&format!("{}_METRICS_TOKEN", prefix))
+}
+
+/// Whether `headers` carry `token` as a bearer credential. Always true
+/// without a token.
+pub fn authorized(token: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let Some(provided) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    // Compare digests so the comparison time doesn't depend on the token.
+    let (a, b) = (Sha256::digest(provided.trim()), Sha256::digest(token));
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// Answer a scrape: 401 with `WWW-Authenticate: Bearer` when `token` is set
+/// and not presented, otherwise `body()` in the exposition format.
+pub fn respond(
+    token: Option<&str>,
+    headers: &HeaderMap,
+    body: impl FnOnce() -> String,
+) -> Response {
+    if !authorized(token, headers) {
+        metrics::counter!("metrics.scrape.unauthorized").increment(1);
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))],
+        )
+            .into_response();
+    }
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(EXPOSITION_CONTENT_TYPE),
+        )],
+        body(),
+    )
+        .into_response()
+}
+
+/// Render `handle` for a scrape (see [`respond`]).
+pub fn render(handle: &PrometheusHandle, token: Option<&str>, headers: &HeaderMap) -> Response {
+    respond(token, headers, || handle.render())
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_auth_gating() {
+        let handle = PrometheusBuilder::new().build_recorder().handle();
+
+        let open = render(&handle, None, &HeaderMap::new());
+        assert_eq!(open.status(), StatusCode::OK);
+        assert_eq!(
+            open.headers()[header::CONTENT_TYPE],
+            EXPOSITION_CONTENT_TYPE
+        );
+
+        let missing = render(&handle, Some("scrape-secret"), &HeaderMap::new());
+        assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(missing.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+        let wrong = render(&handle, Some("scrape-secret"), &bearer("admin-key"));
+        assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+        let right = render(&handle, Some("scrape-secret"), &bearer("scrape-secret"));
+        assert_eq!(right.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_global_labels_on_emitted_metrics() {
+        let labels = MetricsLabels::from_lookup("FETCH", "fetch", "1.2.3", |name| match name {
+            "HOSTNAME" => Some("fetch-7d9f".into()),
+            "AUTOSINT_ENVIRONMENT" => Some("staging".into()),
+            _ => None,
+        });
+        assert_eq!(labels.instance, "fetch-7d9f");
+
+        let recorder = labels.builder().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("fetch.requests", "outcome" => "ok").increment(2);
+        });
+        let rendered = handle.render();
+        let sample = rendered
+            .lines()
+            .find(|l| l.starts_with("fetch_requests{"))
+            .unwrap_or_else(|| panic!("{}", rendered));
+        for label in [
+            "outcome=\"ok\"",
+            "service=\"fetch\"",
+            "version=\"1.2.3\"",
+            "instance=\"fetch-7d9f\"",
+            "environment=\"staging\"",
+        ] {
+            assert!(sample.contains(label), "{} missing from {}", label, sample);
+        }
+
+        // {PREFIX}_INSTANCE_ID beats HOSTNAME; no environment label without one.
+        let labels = MetricsLabels::from_lookup("GEO", "geo", "1.2.3", |name| match name {
+            "GEO_INSTANCE_ID" => Some("geo-0".into()),
+            "HOSTNAME" => Some("ignored".into()),
+            _ => None,
+        });
+        assert_eq!(labels.instance, "geo-0");
+        assert_eq!(labels.environment, None);
+    }
+}
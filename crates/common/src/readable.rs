@@ -0,0 +1,149 @@
+//! Readable text from HTML, shared by the Fetch service and document ingest.
+
+use scraper::{Html, Selector};
+
+/// Extract readable text from HTML by removing script, style, nav, footer, header elements.
+pub fn extract_html_content(html: &str) -> String {
+    let document = Html::parse_document(html);
+
+    // Selectors for elements to remove.
+    let remove_selectors = [
+        "script", "style", "nav", "footer", "header", "noscript", "svg",
+    ];
+
+    let mut skip_ids = std::collections::HashSet::new();
+
+    for sel_str in &remove_selectors {
+        if let Ok(selector) = Selector::parse(sel_str) {
+            for element in document.select(&selector) {
+                skip_ids.insert(element.id());
+            }
+        }
+    }
+
+    // Walk the document tree and collect text from elements not in skip set.
+    let mut text_parts = Vec::new();
+
+    for node in document.tree.nodes() {
+        // Skip if this node or any ancestor is in the skip set.
+        let mut should_skip = false;
+        let mut check_id = Some(node.id());
+        while let Some(id) = check_id {
+            if skip_ids.contains(&id) {
+                should_skip = true;
+                break;
+            }
+            check_id = document
+                .tree
+                .get(id)
+                .and_then(|n| n.parent())
+                .map(|p| p.id());
+        }
+
+        if should_skip {
+            continue;
+        }
+
+        if let Some(text) = node.value().as_text() {
+            let trimmed = text.text.trim();
+            if !trimmed.is_empty() {
+                text_parts.push(trimmed.to_string());
+            }
+        }
+    }
+
+    // Collapse multiple whitespace/newlines.
+    let joined = text_parts.join(" ");
+    collapse_whitespace(&joined)
+}
+
+/// The document's title: its `og:title` meta, else its `<title>`. None when
+/// both are missing or blank.
+pub fn extract_html_title(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let og_sel = Selector::parse("meta[property=\"og:title\"]").expect("valid selector");
+    let title_sel = Selector::parse("title").expect("valid selector");
+    document
+        .select(&og_sel)
+        .filter_map(|meta| meta.value().attr("content"))
+        .map(collapse_whitespace)
+        .chain(
+            document
+                .select(&title_sel)
+                .map(|title| collapse_whitespace(&title.text().collect::<String>())),
+        )
+        .find(|title| !title.is_empty())
+}
+
+/// Collapse runs of whitespace to single spaces and trim.
+pub fn collapse_whitespace(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut prev_was_space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !prev_was_space {
+                result.push(' ');
+                prev_was_space = true;
+            }
+        } else {
+            result.push(c);
+            prev_was_space = false;
+        }
+    }
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_html_basic() {
+        let html = r#"
+            <html>
+            <head><title>Test</title></head>
+            <body>
+                <nav>Navigation here</nav>
+                <main>
+                    <h1>Article Title</h1>
+                    <p>This is the main content of the article.</p>
+                    <p>Second paragraph with more information.</p>
+                </main>
+                <footer>Footer content</footer>
+                <script>alert('bad');</script>
+            </body>
+            </html>
+        "#;
+
+        let text = extract_html_content(html);
+        assert!(text.contains("Article Title"));
+        assert!(text.contains("main content"));
+        assert!(!text.contains("Navigation here"));
+        assert!(!text.contains("Footer content"));
+        assert!(!text.contains("alert"));
+    }
+
+    #[test]
+    fn test_extract_html_title() {
+        assert_eq!(
+            extract_html_title("<html><head><title>\n  Port  closes\n</title></head></html>")
+                .as_deref(),
+            Some("Port closes")
+        );
+        assert_eq!(
+            extract_html_title(
+                r#"<head><meta property="og:title" content="Port closes to tankers"><title>Port closes | News</title></head>"#
+            )
+            .as_deref(),
+            Some("Port closes to tankers")
+        );
+        assert_eq!(extract_html_title("<p>No title</p>"), None);
+        assert_eq!(extract_html_title("<title> </title>"), None);
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        assert_eq!(collapse_whitespace("hello   world"), "hello world");
+        assert_eq!(collapse_whitespace("  hello\n\n  world  "), "hello world");
+    }
+}
@@ -0,0 +1,346 @@
+//! Retry with exponential backoff and jitter, for calls to external services
+//! (LLM and embedding APIs, PostgreSQL, Neo4j).
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::config::RetryConfig;
+
+/// How a failed attempt is retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryClass {
+    /// Retry after the next backoff delay.
+    Retryable,
+    /// Rate limited: retry after the server's Retry-After, or after the
+    /// current backoff delay when it gave none. Doesn't grow the backoff.
+    RetryAfter(Option<Duration>),
+    /// Return the error without retrying.
+    Fatal,
+}
+
+/// When and how often to retry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, counting the first.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Add a random delay of up to half the backoff.
+    pub jitter: bool,
+    /// Give up instead of waiting past this long after the first attempt
+    /// started. None = bounded by `max_attempts` only.
+    pub deadline: Option<Duration>,
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            multiplier: config.backoff_multiplier,
+            jitter: config.jitter,
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+}
+
+/// The growing delay between retries.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    current_ms: u64,
+    max_ms: u64,
+    multiplier: f64,
+    jitter: bool,
+}
+
+impl Backoff {
+    pub fn new(policy: &RetryPolicy) -> Self {
+        Self {
+            current_ms: policy.initial_backoff.as_millis() as u64,
+            max_ms: policy.max_backoff.as_millis() as u64,
+            multiplier: policy.multiplier,
+            jitter: policy.jitter,
+        }
+    }
+
+    /// The current delay, without jitter.
+    pub fn current(&self) -> Duration {
+        Duration::from_millis(self.current_ms)
+    }
+
+    /// The delay before the next retry: the current delay plus jitter. Grows
+    /// the delay by the multiplier, up to the maximum.
+    pub fn next_delay(&mut self) -> Duration {
+        let jitter = if self.jitter {
+            rand::rng().random_range(0..=self.current_ms / 2)
+        } else {
+            0
+        };
+        let delay = Duration::from_millis(self.current_ms + jitter);
+        self.current_ms = ((self.current_ms as f64 * self.multiplier) as u64).min(self.max_ms);
+        delay
+    }
+}
+
+/// A failed attempt about to be retried, for call sites' logs and metrics.
+#[derive(Debug)]
+pub struct RetryAttempt<'a, E> {
+    /// The attempt that failed, from 1.
+    pub attempt: u32,
+    pub wait: Duration,
+    pub error: &'a E,
+    /// Classified [`RetryClass::RetryAfter`].
+    pub rate_limited: bool,
+}
+
+/// Why retrying stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GiveUp {
+    /// The error was classified [`RetryClass::Fatal`].
+    Fatal,
+    /// `max_attempts` were made.
+    Exhausted,
+    /// The next wait would have run past the deadline.
+    Deadline,
+}
+
+/// The last error of a call that wasn't retried to success.
+#[derive(Debug)]
+pub struct RetryFailure<E> {
+    pub error: E,
+    pub attempts: u32,
+    pub reason: GiveUp,
+}
+
+/// Run `op` until it succeeds, `classify` calls its error fatal, it has run
+/// `policy.max_attempts` times, or waiting again would pass the deadline.
+/// `on_retry` is called before each wait.
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    classify: impl Fn(&E) -> RetryClass,
+    mut on_retry: impl FnMut(&RetryAttempt<'_, E>),
+    mut op: F,
+) -> Result<T, RetryFailure<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let started = Instant::now();
+    let mut backoff = Backoff::new(policy);
+    let mut attempt = 0u32;
+
+    loop {
+        attempt += 1;
+        let error = match op().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let fail = |error, reason| {
+            Err(RetryFailure {
+                error,
+                attempts: attempt,
+                reason,
+            })
+        };
+
+        let class = classify(&error);
+        if class == RetryClass::Fatal {
+            return fail(error, GiveUp::Fatal);
+        }
+        if attempt >= policy.max_attempts {
+            return fail(error, GiveUp::Exhausted);
+        }
+        let wait = match class {
+            RetryClass::RetryAfter(after) => after.unwrap_or_else(|| backoff.current()),
+            _ => backoff.next_delay(),
+        };
+        if policy
+            .deadline
+            .is_some_and(|deadline| started.elapsed() + wait > deadline)
+        {
+            return fail(error, GiveUp::Deadline);
+        }
+
+        on_retry(&RetryAttempt {
+            attempt,
+            wait,
+            error: &error,
+            rate_limited: matches!(class, RetryClass::RetryAfter(_)),
+        });
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            multiplier: 2.0,
+            jitter: false,
+            deadline: None,
+        }
+    }
+
+    /// Fails with `errors` in turn, then succeeds.
+    async fn flaky(calls: &AtomicU32, errors: &[&'static str]) -> Result<u32, &'static str> {
+        let n = calls.fetch_add(1, Ordering::SeqCst);
+        match errors.get(n as usize) {
+            Some(error) => Err(*error),
+            None => Ok(n + 1),
+        }
+    }
+
+    fn classify(error: &&str) -> RetryClass {
+        match *error {
+            "fatal" => RetryClass::Fatal,
+            "slow down" => RetryClass::RetryAfter(Some(Duration::from_millis(2))),
+            "rate limited" => RetryClass::RetryAfter(None),
+            _ => RetryClass::Retryable,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_counts_attempts() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with_backoff(
+            &policy(3),
+            classify,
+            |_| {},
+            || flaky(&calls, &["reset", "reset"]),
+        )
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        let calls = AtomicU32::new(0);
+        let failure = retry_with_backoff(
+            &policy(3),
+            classify,
+            |_| {},
+            || flaky(&calls, &["reset"; 5]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(failure.attempts, 3);
+        assert_eq!(failure.reason, GiveUp::Exhausted);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_routes_by_classification() {
+        let calls = AtomicU32::new(0);
+        let failure = retry_with_backoff(
+            &policy(5),
+            classify,
+            |_| {},
+            || flaky(&calls, &["reset", "fatal", "reset"]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(failure.error, "fatal");
+        assert_eq!(failure.reason, GiveUp::Fatal);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Rate limits wait the server's delay, or the current backoff without
+        // growing it.
+        let calls = AtomicU32::new(0);
+        let mut retries = Vec::new();
+        let result = retry_with_backoff(
+            &policy(5),
+            classify,
+            |r| retries.push((r.attempt, r.wait.as_millis(), r.rate_limited)),
+            || flaky(&calls, &["slow down", "rate limited", "reset", "reset"]),
+        )
+        .await;
+        assert_eq!(result.unwrap(), 5);
+        assert_eq!(
+            retries,
+            [(1, 2, true), (2, 1, true), (3, 1, false), (4, 2, false)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_at_deadline() {
+        let calls = AtomicU32::new(0);
+        let slow = RetryPolicy {
+            initial_backoff: Duration::from_millis(40),
+            max_backoff: Duration::from_millis(40),
+            ..policy(10)
+        }
+        .with_deadline(Duration::from_millis(100));
+        let failure = retry_with_backoff(&slow, classify, |_| {}, || flaky(&calls, &["reset"; 10]))
+            .await
+            .unwrap_err();
+        assert_eq!(failure.reason, GiveUp::Deadline);
+        assert_eq!(failure.attempts, 3);
+
+        // A Retry-After past the deadline isn't waited out either.
+        let calls = AtomicU32::new(0);
+        let failure = retry_with_backoff(
+            &policy(10).with_deadline(Duration::from_millis(1)),
+            classify,
+            |_| {},
+            || flaky(&calls, &["slow down"]),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(failure.reason, GiveUp::Deadline);
+        assert_eq!(failure.attempts, 1);
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        let mut backoff = Backoff::new(&policy(10));
+        let delays: Vec<u128> = (0..5).map(|_| backoff.next_delay().as_millis()).collect();
+        assert_eq!(delays, [1, 2, 4, 4, 4]);
+
+        let mut slow = Backoff::new(&RetryPolicy {
+            initial_backoff: Duration::from_millis(1000),
+            max_backoff: Duration::from_millis(2500),
+            multiplier: 1.5,
+            ..policy(10)
+        });
+        let delays: Vec<u128> = (0..4).map(|_| slow.next_delay().as_millis()).collect();
+        assert_eq!(delays, [1000, 1500, 2250, 2500]);
+    }
+
+    #[test]
+    fn test_jitter_is_bounded() {
+        let jittered = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(100),
+            jitter: true,
+            ..policy(10)
+        };
+        let delays: Vec<u128> = (0..200)
+            .map(|_| Backoff::new(&jittered).next_delay().as_millis())
+            .collect();
+        assert!(
+            delays.iter().all(|d| (100..=150).contains(d)),
+            "{:?}",
+            delays
+        );
+        assert!(delays.iter().any(|d| *d != delays[0]), "no jitter applied");
+    }
+}
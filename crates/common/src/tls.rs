@@ -0,0 +1,489 @@
+//! Optional TLS (and mutual TLS) for service-to-service HTTP.
+//!
+//! Everything is configured from env vars and stays plain HTTP unless the
+//! certificate variables are set. Servers read `{PREFIX}_TLS_CERT`,
+//! `{PREFIX}_TLS_KEY` and, to require client certificates,
+//! `{PREFIX}_TLS_CLIENT_CA`. Clients read `{PREFIX}_TLS_CA`,
+//! `{PREFIX}_TLS_CERT` and `{PREFIX}_TLS_KEY`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TlsError {
+    #[error("{set} is set but {missing} is not")]
+    Incomplete { set: String, missing: String },
+
+    #[error("failed to read {path}: {message}")]
+    Read { path: PathBuf, message: String },
+
+    #[error("no {what} found in {path}")]
+    Empty { what: &'static str, path: PathBuf },
+
+    #[error("invalid TLS configuration: {0}")]
+    Config(String),
+}
+
+/// Certificate paths for a TLS-terminating server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerTlsPaths {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+    /// CA that client certificates must chain to. Set = mutual TLS.
+    pub client_ca: Option<PathBuf>,
+}
+
+impl ServerTlsPaths {
+    /// Read `{prefix}_TLS_CERT`, `{prefix}_TLS_KEY` and `{prefix}_TLS_CLIENT_CA`.
+    /// `None` when neither cert nor key is set.
+    pub fn from_env(prefix: &str) -> Result<Option<Self>, TlsError> {
+        Self::from_lookup(prefix, env_path)
+    }
+
+    fn from_lookup(
+        prefix: &str,
+        lookup: impl Fn(&str) -> Option<PathBuf>,
+    ) -> Result<Option<Self>, TlsError> {
+        let cert_var = format!("{}_TLS_CERT", prefix);
+        let key_var = format!("{}_TLS_KEY", prefix);
+        let client_ca = lookup(&format!("{}_TLS_CLIENT_CA", prefix));
+
+        match (lookup(&cert_var), lookup(&key_var)) {
+            (Some(cert), Some(key)) => Ok(Some(Self {
+                cert,
+                key,
+                client_ca,
+            })),
+            (None, None) if client_ca.is_none() => Ok(None),
+            (None, None) => Err(TlsError::Incomplete {
+                set: format!("{}_TLS_CLIENT_CA", prefix),
+                missing: cert_var,
+            }),
+            (Some(_), None) => Err(TlsError::Incomplete {
+                set: cert_var,
+                missing: key_var,
+            }),
+            (None, Some(_)) => Err(TlsError::Incomplete {
+                set: key_var,
+                missing: cert_var,
+            }),
+        }
+    }
+
+    /// Load the certificates into a rustls server config (HTTP/2 and HTTP/1.1 ALPN).
+    pub fn load(&self) -> Result<Arc<ServerConfig>, TlsError> {
+        let provider = provider();
+        let certs = read_certs(&self.cert)?;
+        let key = read_key(&self.key)?;
+
+        let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| TlsError::Config(e.to_string()))?;
+
+        let builder = match &self.client_ca {
+            Some(ca) => {
+                let verifier = WebPkiClientVerifier::builder_with_provider(
+                    Arc::new(read_roots(ca)?),
+                    provider,
+                )
+                .build()
+                .map_err(|e| TlsError::Config(e.to_string()))?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder
+            .with_single_cert(certs, key)
+            .map_err(|e| TlsError::Config(e.to_string()))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        Ok(Arc::new(config))
+    }
+}
+
+/// Server config from `{prefix}_TLS_*`, or `None` to serve plain HTTP.
+pub fn server_config_from_env(prefix: &str) -> Result<Option<Arc<ServerConfig>>, TlsError> {
+    let Some(paths) = ServerTlsPaths::from_env(prefix)? else {
+        return Ok(None);
+    };
+    let config = paths.load()?;
+    tracing::info!(
+        prefix,
+        cert = %paths.cert.display(),
+        mutual = paths.client_ca.is_some(),
+        "Server TLS enabled"
+    );
+    Ok(Some(config))
+}
+
+/// CA and client certificate paths for calls to a TLS-terminating service.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientTlsPaths {
+    /// CA the server certificate must chain to. Replaces the built-in roots.
+    pub ca: Option<PathBuf>,
+    /// Client certificate and key, presented when the server asks for one.
+    pub identity: Option<(PathBuf, PathBuf)>,
+}
+
+impl ClientTlsPaths {
+    /// Read `{prefix}_TLS_CA`, `{prefix}_TLS_CERT` and `{prefix}_TLS_KEY`.
+    /// `None` when none of them is set.
+    pub fn from_env(prefix: &str) -> Result<Option<Self>, TlsError> {
+        Self::from_lookup(prefix, env_path)
+    }
+
+    fn from_lookup(
+        prefix: &str,
+        lookup: impl Fn(&str) -> Option<PathBuf>,
+    ) -> Result<Option<Self>, TlsError> {
+        let cert_var = format!("{}_TLS_CERT", prefix);
+        let key_var = format!("{}_TLS_KEY", prefix);
+        let ca = lookup(&format!("{}_TLS_CA", prefix));
+
+        let identity = match (lookup(&cert_var), lookup(&key_var)) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+            (Some(_), None) => {
+                return Err(TlsError::Incomplete {
+                    set: cert_var,
+                    missing: key_var,
+                })
+            }
+            (None, Some(_)) => {
+                return Err(TlsError::Incomplete {
+                    set: key_var,
+                    missing: cert_var,
+                })
+            }
+        };
+
+        if ca.is_none() && identity.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { ca, identity }))
+    }
+
+    /// Switch `builder` to rustls with the configured CA and client identity.
+    /// Files are read and validated here, so a bad path fails at startup.
+    pub fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, TlsError> {
+        let mut builder = builder.use_rustls_tls();
+
+        if let Some(ca) = &self.ca {
+            builder = builder.tls_built_in_root_certs(false);
+            for cert in read_certs(ca)? {
+                let cert = reqwest::Certificate::from_der(&cert)
+                    .map_err(|e| TlsError::Config(format!("{}: {}", ca.display(), e)))?;
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        if let Some((cert, key)) = &self.identity {
+            // Validate both halves up front for clearer errors than reqwest gives.
+            read_certs(cert)?;
+            read_key(key)?;
+            let mut pem = read_file(cert)?;
+            pem.push(b'\n');
+            pem.extend(read_file(key)?);
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| TlsError::Config(format!("{}: {}", cert.display(), e)))?;
+            builder = builder.identity(identity);
+        }
+
+        Ok(builder)
+    }
+}
+
+/// A client builder for calls to `base_url`, with `{prefix}_TLS_*` applied when
+/// the URL is https. TLS variables alongside a plain-http URL are ignored with
+/// a warning.
+pub fn client_builder_from_env(
+    prefix: &str,
+    base_url: &str,
+) -> Result<reqwest::ClientBuilder, TlsError> {
+    let builder = reqwest::Client::builder();
+    let Some(paths) = ClientTlsPaths::from_env(prefix)? else {
+        return Ok(builder);
+    };
+
+    if !base_url.starts_with("https://") {
+        tracing::warn!(
+            prefix,
+            base_url,
+            "Client TLS configured but the URL is not https; ignoring"
+        );
+        return Ok(builder);
+    }
+
+    tracing::info!(
+        prefix,
+        base_url,
+        custom_ca = paths.ca.is_some(),
+        client_cert = paths.identity.is_some(),
+        "Client TLS enabled"
+    );
+    paths.apply(builder)
+}
+
+/// Serve `app` on `listener`, over TLS when a config is given.
+#[cfg(feature = "server")]
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    app: axum::Router,
+    tls: Option<Arc<ServerConfig>>,
+) -> std::io::Result<()> {
+    match tls {
+        Some(config) => {
+            let config = axum_server::tls_rustls::RustlsConfig::from_config(config);
+            axum_server::from_tcp_rustls(listener.into_std()?, config)
+                .serve(app.into_make_service())
+                .await
+        }
+        None => axum::serve(listener, app).await,
+    }
+}
+
+/// The crypto provider for every config built here. Explicit, because more than
+/// one rustls backend is compiled into the workspace.
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn env_path(var: &str) -> Option<PathBuf> {
+    std::env::var(var)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|e| TlsError::Read {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let pem = read_file(path)?;
+    let certs = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| TlsError::Read {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+    if certs.is_empty() {
+        return Err(TlsError::Empty {
+            what: "certificates",
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(certs)
+}
+
+fn read_key(path: &Path) -> Result<PrivateKeyDer<'static>, TlsError> {
+    let pem = read_file(path)?;
+    PrivateKeyDer::from_pem_slice(&pem).map_err(|_| TlsError::Empty {
+        what: "private key",
+        path: path.to_path_buf(),
+    })
+}
+
+fn read_roots(path: &Path) -> Result<RootCertStore, TlsError> {
+    let mut roots = RootCertStore::empty();
+    for cert in read_certs(path)? {
+        roots
+            .add(cert)
+            .map_err(|e| TlsError::Config(format!("{}: {}", path.display(), e)))?;
+    }
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    #[cfg(feature = "server")]
+    use axum::routing::get;
+    #[cfg(feature = "server")]
+    use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+
+    /// A CA plus a leaf certificate it signed, written to a temp directory.
+    #[cfg(feature = "server")]
+    struct Pki {
+        dir: PathBuf,
+        ca: PathBuf,
+    }
+
+    #[cfg(feature = "server")]
+    impl Pki {
+        fn new(name: &str) -> (Self, rcgen::Certificate, KeyPair) {
+            let dir = std::env::temp_dir().join(format!(
+                "autosint-tls-{}-{}",
+                name,
+                uuid::Uuid::new_v4()
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let key = KeyPair::generate().unwrap();
+            let cert = params.self_signed(&key).unwrap();
+
+            let ca = dir.join("ca.pem");
+            std::fs::write(&ca, cert.pem()).unwrap();
+            (Self { dir, ca }, cert, key)
+        }
+
+        /// Issue a leaf for `localhost`; returns (cert path, key path).
+        fn issue(
+            &self,
+            name: &str,
+            ca_cert: &rcgen::Certificate,
+            ca_key: &KeyPair,
+        ) -> (PathBuf, PathBuf) {
+            let params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+            let key = KeyPair::generate().unwrap();
+            let cert = params.signed_by(&key, ca_cert, ca_key).unwrap();
+
+            let cert_path = self.dir.join(format!("{}.pem", name));
+            let key_path = self.dir.join(format!("{}-key.pem", name));
+            std::fs::write(&cert_path, cert.pem()).unwrap();
+            std::fs::write(&key_path, key.serialize_pem()).unwrap();
+            (cert_path, key_path)
+        }
+    }
+
+    /// Stand up a TLS stub with a /health route; returns its https base URL.
+    #[cfg(feature = "server")]
+    async fn tls_stub(config: Arc<ServerConfig>) -> String {
+        let app = axum::Router::new().route("/health", get(|| async { "healthy" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { serve(listener, app, Some(config)).await.unwrap() });
+        format!("https://localhost:{}", port)
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn test_mutual_tls_round_trip_and_wrong_ca_rejected() {
+        let (pki, ca_cert, ca_key) = Pki::new("mtls");
+        let (server_cert, server_key) = pki.issue("server", &ca_cert, &ca_key);
+        let (client_cert, client_key) = pki.issue("client", &ca_cert, &ca_key);
+
+        let server = ServerTlsPaths {
+            cert: server_cert,
+            key: server_key,
+            client_ca: Some(pki.ca.clone()),
+        };
+        let base_url = tls_stub(server.load().unwrap()).await;
+        let health = format!("{}/health", base_url);
+
+        // Right CA and a client certificate: accepted.
+        let client = ClientTlsPaths {
+            ca: Some(pki.ca.clone()),
+            identity: Some((client_cert.clone(), client_key.clone())),
+        };
+        let http = client
+            .apply(reqwest::Client::builder())
+            .unwrap()
+            .build()
+            .unwrap();
+        let response = http.get(&health).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), "healthy");
+
+        // No client certificate: the server refuses the handshake.
+        let anonymous = ClientTlsPaths {
+            ca: Some(pki.ca.clone()),
+            identity: None,
+        }
+        .apply(reqwest::Client::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+        assert!(anonymous.get(&health).send().await.is_err());
+
+        // Server certificate from a CA the client doesn't trust: rejected.
+        let (other, _, _) = Pki::new("other");
+        let wrong_ca = ClientTlsPaths {
+            ca: Some(other.ca.clone()),
+            identity: Some((client_cert, client_key)),
+        }
+        .apply(reqwest::Client::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+        assert!(wrong_ca.get(&health).send().await.is_err());
+
+        let _ = std::fs::remove_dir_all(&pki.dir);
+        let _ = std::fs::remove_dir_all(&other.dir);
+    }
+
+    #[test]
+    fn test_env_lookup_requires_matching_pairs() {
+        let vars = |pairs: &[(&str, &str)]| {
+            let map: HashMap<String, PathBuf> = pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), PathBuf::from(v)))
+                .collect();
+            move |name: &str| map.get(name).cloned()
+        };
+
+        assert_eq!(
+            ServerTlsPaths::from_lookup("FETCH", vars(&[])).unwrap(),
+            None
+        );
+        let paths = ServerTlsPaths::from_lookup(
+            "FETCH",
+            vars(&[("FETCH_TLS_CERT", "c.pem"), ("FETCH_TLS_KEY", "k.pem")]),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(paths.cert, PathBuf::from("c.pem"));
+        assert!(paths.client_ca.is_none());
+
+        let err =
+            ServerTlsPaths::from_lookup("FETCH", vars(&[("FETCH_TLS_CERT", "c.pem")])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "FETCH_TLS_CERT is set but FETCH_TLS_KEY is not"
+        );
+
+        assert_eq!(
+            ClientTlsPaths::from_lookup("FETCH_CLIENT", vars(&[])).unwrap(),
+            None
+        );
+        let ca_only =
+            ClientTlsPaths::from_lookup("FETCH_CLIENT", vars(&[("FETCH_CLIENT_TLS_CA", "ca.pem")]))
+                .unwrap()
+                .unwrap();
+        assert!(ca_only.identity.is_none());
+        assert!(ClientTlsPaths::from_lookup(
+            "FETCH_CLIENT",
+            vars(&[("FETCH_CLIENT_TLS_KEY", "k.pem")])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unreadable_cert_fails_loudly() {
+        let server = ServerTlsPaths {
+            cert: PathBuf::from("/nonexistent/server.pem"),
+            key: PathBuf::from("/nonexistent/server-key.pem"),
+            client_ca: None,
+        };
+        let err = server.load().unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/server.pem"));
+    }
+}
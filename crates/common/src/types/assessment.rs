@@ -45,7 +45,29 @@ pub struct Assessment {
     /// Embedding for semantic search over assessments via pgvector.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub embedding: Option<Vec<f32>>,
+    /// Free-form organizational tags (see `normalize_tags`).
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub created_at: DateTime<Utc>,
+    /// The earlier assessment this one supersedes (recurring investigations,
+    /// forks), if the Analyst named one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes_assessment_id: Option<AssessmentId>,
+    /// What changed since the superseded assessment. Computed when the
+    /// investigation completes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<AssessmentDiff>,
+    /// The investigation's correlation ID. Read from the investigation, not
+    /// stored with the assessment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// A claim the assessment cites has been disputed since it was produced.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_review: bool,
+    /// Set when a snapshot investigation produced the assessment: the cutoff
+    /// of the graph it was drawn from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<DateTime<Utc>>,
 }
 
 impl Assessment {
@@ -58,7 +80,125 @@ impl Assessment {
             entity_refs: Vec::new(),
             claim_refs: Vec::new(),
             embedding: None,
+            tags: Vec::new(),
             created_at: Utc::now(),
+            supersedes_assessment_id: None,
+            diff: None,
+            correlation_id: None,
+            needs_review: false,
+            as_of: None,
+        }
+    }
+
+    /// Claims the assessment rests on: its `claim_refs`, then those named by
+    /// its citations and competing hypotheses, each once.
+    pub fn cited_claim_ids(&self) -> Vec<ClaimId> {
+        let mut ids: Vec<ClaimId> = Vec::new();
+        let in_content = ["citations", "competing_hypotheses"]
+            .into_iter()
+            .filter_map(|section| self.content.get(section)?.as_array())
+            .flatten()
+            .flat_map(|item| {
+                let citation = item.get("claim_id").into_iter();
+                let refs = item
+                    .get("claim_refs")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten();
+                citation.chain(refs)
+            })
+            .filter_map(|id| id.as_str()?.trim().parse::<uuid::Uuid>().ok())
+            .map(ClaimId::from_uuid);
+        for id in self.claim_refs.iter().copied().chain(in_content) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
         }
+        ids
+    }
+}
+
+/// How an assessment's key judgments (its competing hypotheses) changed
+/// since the assessment it supersedes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssessmentDiff {
+    pub previous_assessment_id: AssessmentId,
+    pub previous_confidence: Confidence,
+    /// How judgments were matched across the two assessments.
+    pub matched_by: JudgmentMatching,
+    /// The current judgments in order, then dropped ones in their earlier
+    /// order.
+    pub judgments: Vec<JudgmentChange>,
+}
+
+impl AssessmentDiff {
+    /// Number of judgments with each kind of change, as (unchanged, revised,
+    /// new, dropped).
+    pub fn counts(&self) -> (usize, usize, usize, usize) {
+        let mut counts = (0, 0, 0, 0);
+        for change in &self.judgments {
+            match change {
+                JudgmentChange::Unchanged { .. } => counts.0 += 1,
+                JudgmentChange::Revised { .. } => counts.1 += 1,
+                JudgmentChange::New { .. } => counts.2 += 1,
+                JudgmentChange::Dropped { .. } => counts.3 += 1,
+            }
+        }
+        counts
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgmentMatching {
+    /// Cosine similarity of judgment embeddings.
+    Embedding,
+    /// Fuzzy string similarity of judgment texts.
+    Fuzzy,
+}
+
+/// One key judgment's change. Judgments are the `competing_hypotheses`
+/// items, kept as the Analyst wrote them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum JudgmentChange {
+    /// Matched, with the same text and probability.
+    Unchanged { judgment: Value },
+    /// Matched, but reworded or reweighted.
+    Revised {
+        previous: Value,
+        current: Value,
+        similarity: f64,
+    },
+    /// Only in the current assessment.
+    New { judgment: Value },
+    /// Only in the superseded assessment.
+    Dropped { judgment: Value },
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_cited_claim_ids_dedups_across_sections() {
+        let (a, b, c) = (ClaimId::new(), ClaimId::new(), ClaimId::new());
+        let mut assessment = Assessment::new(
+            InvestigationId::new(),
+            json!({
+                "citations": [
+                    {"marker": "[1]", "claim_id": a.to_string()},
+                    {"marker": "[2]", "claim_id": "not-a-uuid"},
+                ],
+                "competing_hypotheses": [
+                    {"hypothesis": "H1", "claim_refs": [b.to_string(), c.to_string()]},
+                ],
+            }),
+            Confidence::Moderate,
+        );
+        assessment.claim_refs = vec![b];
+        assert_eq!(assessment.cited_claim_ids(), vec![b, a, c]);
     }
 }
@@ -0,0 +1,132 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{InvestigationId, WorkOrderId};
+
+use super::parse::{parse_variant, ParseEnumError};
+
+/// Who made an audited change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditActorType {
+    /// A tool call in an Analyst session.
+    AnalystSession,
+    /// A tool call in a Processor session.
+    ProcessorSession,
+    /// An HTTP request, identified by the credential it carried.
+    HttpApiKey,
+    /// An HTTP request authenticated with the admin key.
+    Admin,
+    /// The engine's own background work, e.g. stub resolution merges.
+    System,
+}
+
+impl AuditActorType {
+    pub const ALL: [Self; 5] = [
+        Self::AnalystSession,
+        Self::ProcessorSession,
+        Self::HttpApiKey,
+        Self::Admin,
+        Self::System,
+    ];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::AnalystSession => "analyst_session",
+            Self::ProcessorSession => "processor_session",
+            Self::HttpApiKey => "http_api_key",
+            Self::Admin => "admin",
+            Self::System => "system",
+        }
+    }
+}
+
+impl FromStr for AuditActorType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("audit actor type", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// Who made a change: the actor's type and, where there is one, its
+/// identity — a session's investigation and cycle or Processor instance, or
+/// the key ID of an HTTP credential.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditActor {
+    pub actor_type: AuditActorType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<String>,
+}
+
+impl AuditActor {
+    pub fn new(actor_type: AuditActorType, actor_id: Option<String>) -> Self {
+        Self {
+            actor_type,
+            actor_id,
+        }
+    }
+
+    pub fn system() -> Self {
+        Self::new(AuditActorType::System, None)
+    }
+}
+
+impl Default for AuditActor {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+/// One change to record in the audit log.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    #[serde(flatten)]
+    pub actor: AuditActor,
+    /// Investigation the change was made for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub investigation_id: Option<InvestigationId>,
+    /// Work order whose Processor session made the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_order_id: Option<WorkOrderId>,
+    /// Tool name for tool calls, `METHOD /route` for HTTP requests.
+    pub operation: String,
+    /// What was changed: entity, claim, relationship, investigation, ...
+    pub target_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_id: Option<String>,
+    /// Compact summary of the change, with long values cut.
+    #[serde(default)]
+    pub digest: serde_json::Value,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A stored audit log entry.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    #[serde(flatten)]
+    pub record: AuditRecord,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor_type_round_trip() {
+        for actor_type in AuditActorType::ALL {
+            assert_eq!(
+                actor_type.as_db_str().parse::<AuditActorType>().unwrap(),
+                actor_type
+            );
+            assert_eq!(
+                serde_json::to_value(actor_type).unwrap(),
+                actor_type.as_db_str()
+            );
+        }
+        assert!("robot".parse::<AuditActorType>().is_err());
+    }
+}
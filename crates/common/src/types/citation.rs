@@ -0,0 +1,213 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::SourceDocumentId;
+
+/// Scheme of the `url` cited for a stored source document, which has no web
+/// address of its own.
+pub const SOURCE_DOCUMENT_URL_SCHEME: &str = "source-document:";
+
+/// How the cited copy of a document was obtained.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CitationVia {
+    /// Fetched from the origin.
+    #[default]
+    Direct,
+    /// Served from the Fetch service's cache of an earlier fetch.
+    Cache,
+    /// An archived copy, such as a stored source document.
+    Archive,
+}
+
+/// Where a claim's information was read: the document, the place in it, and
+/// when and how it was accessed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    /// The document as cited, usually the URL that was fetched.
+    pub url: String,
+    /// `url` with the fragment, tracking parameters and letter case that
+    /// don't change the document removed, for grouping citations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Place in the document: a page, section, paragraph or `#anchor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub via: CitationVia,
+    /// The stored source document, when the copy read was archived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_document_id: Option<SourceDocumentId>,
+}
+
+impl Citation {
+    /// A citation of `url` and nothing else, as claims written before
+    /// citations carry.
+    pub fn from_url(url: &str) -> Self {
+        Self {
+            url: url.trim().to_string(),
+            normalized_url: None,
+            title: None,
+            locator: None,
+            accessed_at: None,
+            via: CitationVia::Direct,
+            source_document_id: None,
+        }
+        .normalized()
+    }
+
+    /// A citation of a stored source document.
+    pub fn for_source_document(
+        id: SourceDocumentId,
+        title: Option<String>,
+        accessed_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            url: format!("{}{}", SOURCE_DOCUMENT_URL_SCHEME, id),
+            normalized_url: None,
+            title,
+            locator: None,
+            accessed_at: Some(accessed_at),
+            via: CitationVia::Archive,
+            source_document_id: Some(id),
+        }
+    }
+
+    /// Fill in `normalized_url`, and take the locator from the URL's fragment
+    /// when none was given. Blank optional fields are dropped.
+    pub fn normalized(mut self) -> Self {
+        self.url = self.url.trim().to_string();
+        let blank = |field: &mut Option<String>| {
+            if field.as_deref().is_some_and(|s| s.trim().is_empty()) {
+                *field = None;
+            }
+        };
+        blank(&mut self.title);
+        blank(&mut self.locator);
+        if self.locator.is_none() {
+            self.locator = self
+                .url
+                .split_once('#')
+                .map(|(_, fragment)| format!("#{}", fragment))
+                .filter(|fragment| fragment.len() > 1);
+        }
+        self.normalized_url = Some(normalize_citation_url(&self.url));
+        self
+    }
+
+    /// Whether `url` is an http(s) address a reader can follow.
+    pub fn is_web(&self) -> bool {
+        let lower = self.url.to_ascii_lowercase();
+        lower.starts_with("http://") || lower.starts_with("https://")
+    }
+
+    /// The locator, access date and route, for rendering after the title:
+    /// `["p. 4", "accessed 2026-03-01", "cached copy"]`.
+    pub fn qualifiers(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        if let Some(locator) = &self.locator {
+            parts.push(locator.clone());
+        }
+        if let Some(accessed_at) = self.accessed_at {
+            parts.push(format!("accessed {}", accessed_at.format("%Y-%m-%d")));
+        }
+        match self.via {
+            CitationVia::Direct => {}
+            CitationVia::Cache => parts.push("cached copy".into()),
+            CitationVia::Archive => parts.push("archived copy".into()),
+        }
+        parts
+    }
+}
+
+/// Tracking query parameters dropped from normalized URLs.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid", "ref_src"];
+
+/// Lowercase scheme and host, drop default ports, the fragment and tracking
+/// parameters (`utm_*` and the like). Anything that isn't an http(s) URL is
+/// kept as written.
+pub fn normalize_citation_url(url: &str) -> String {
+    let url = url.trim();
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return url.to_string();
+    }
+    parsed.set_fragment(None);
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| {
+            let key = key.to_ascii_lowercase();
+            !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else if parsed.query().is_some() {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    parsed.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_normalize_for_grouping() {
+        assert_eq!(
+            normalize_citation_url(" HTTPS://News.Example:443/a/b?utm_source=x&id=7#para-3 "),
+            "https://news.example/a/b?id=7"
+        );
+        assert_eq!(
+            normalize_citation_url("https://news.example/a?fbclid=1&UTM_medium=2"),
+            "https://news.example/a"
+        );
+        assert_eq!(
+            normalize_citation_url("Reuters wire, 3 May"),
+            "Reuters wire, 3 May"
+        );
+    }
+
+    #[test]
+    fn legacy_url_becomes_a_citation() {
+        let citation = Citation::from_url("https://news.example/story#section-2");
+        assert_eq!(citation.url, "https://news.example/story#section-2");
+        assert_eq!(
+            citation.normalized_url.as_deref(),
+            Some("https://news.example/story")
+        );
+        assert_eq!(citation.locator.as_deref(), Some("#section-2"));
+        assert_eq!(citation.via, CitationVia::Direct);
+        assert!(citation.is_web());
+    }
+
+    #[test]
+    fn citation_round_trips_and_reads_minimal_json() {
+        let id = SourceDocumentId::new();
+        let mut citation = Citation::for_source_document(
+            id,
+            Some("Maritime Brief".into()),
+            "2026-03-01T08:00:00Z".parse().unwrap(),
+        );
+        citation.locator = Some("item 3".into());
+        let back: Citation =
+            serde_json::from_str(&serde_json::to_string(&citation).unwrap()).unwrap();
+        assert_eq!(back, citation);
+        assert!(!back.is_web());
+        assert_eq!(
+            back.qualifiers(),
+            vec!["item 3", "accessed 2026-03-01", "archived copy"]
+        );
+
+        let minimal: Citation = serde_json::from_str(r#"{"url": "https://x.example"}"#).unwrap();
+        assert_eq!(minimal.via, CitationVia::Direct);
+        assert!(minimal.qualifiers().is_empty());
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{ClaimId, EntityId};
+use crate::ids::{ClaimId, EntityId, InvestigationId, MonitorId, RelationshipId};
+
+use super::Citation;
 
 /// Attribution depth: chain of custody from original source.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,6 +19,17 @@ pub enum AttributionDepth {
     Indirect,
 }
 
+impl AttributionDepth {
+    /// Closeness to the original source: primary ranks highest.
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Primary => 2,
+            Self::Secondhand => 1,
+            Self::Indirect => 0,
+        }
+    }
+}
+
 /// Information type: how the source presents the information (form, not truth value).
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -43,9 +58,14 @@ pub struct Claim {
     pub published_timestamp: DateTime<Utc>,
     /// When the system added this claim to the graph.
     pub ingested_timestamp: DateTime<Utc>,
-    /// URL/reference back to the original document.
+    /// URL/reference back to the original document; the citation's `url`
+    /// when there is a citation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub raw_source_link: Option<String>,
+    /// Where in which document the information was read. Claims written
+    /// before citations get one holding just `raw_source_link`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<Citation>,
     /// Chain of custody from original source.
     pub attribution_depth: AttributionDepth,
     /// How the source presents the information (form, not truth value).
@@ -56,11 +76,71 @@ pub struct Claim {
     /// Entities this claim is about. Linked via REFERENCES edges in Neo4j.
     #[serde(default)]
     pub referenced_entity_ids: Vec<EntityId>,
+    /// Subset of `referenced_entity_ids` added by entity linking rather than
+    /// named by the Processor.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_linked_entity_ids: Vec<EntityId>,
+    /// Relationships this claim is evidence for. Stored as a property because
+    /// Neo4j edges can't point at RELATES_TO edges; drives weight recalibration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supports_relationship_ids: Vec<RelationshipId>,
     /// Embedding vector (content). None if embedding_pending.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub embedding: Option<Vec<f32>>,
     #[serde(default)]
     pub embedding_pending: bool,
+    /// Investigation whose session ingested this claim (purge provenance).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub investigation_id: Option<InvestigationId>,
+    /// Monitor whose item this claim was extracted from. Set instead of
+    /// `investigation_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor_id: Option<MonitorId>,
+    /// Language the source was written in (ISO 639-1, lowercase).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// The source's own wording, when `content` was written from a
+    /// translation. Kept for verification; `content` stays the working text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_content: Option<String>,
+    /// Language of `original_content` (ISO 639-1, lowercase).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_language: Option<String>,
+    /// Region the claim concerns: an ISO 3166 code (uppercase) where one
+    /// resolves, otherwise the Processor's free text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// The source entity's trust level when the claim was ingested. Kept as
+    /// ingested: re-rating the source later doesn't change it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_trust_level: Option<u8>,
+    /// Times assessment feedback disputed this claim.
+    #[serde(default)]
+    pub disputed_count: u32,
+    /// Personal data redacted from `content` before it was stored, as counts
+    /// by type (`"email"`, `"phone"`, ...). The values are never kept.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub redactions: BTreeMap<String, u32>,
+    /// Duplicate claims merged into this one, oldest merge first. Their
+    /// sources and citations count as this claim's too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_sources: Vec<MergedSource>,
+}
+
+/// The provenance of a duplicate claim merged into another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedSource {
+    /// The duplicate's ID, no longer in the graph.
+    pub claim_id: ClaimId,
+    pub source_entity_id: EntityId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub citation: Option<Citation>,
+    pub published_timestamp: DateTime<Utc>,
+    pub attribution_depth: AttributionDepth,
+    /// The merged claim's attribution depth before the merge, when the
+    /// duplicate's was stronger and replaced it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_attribution_depth: Option<AttributionDepth>,
 }
 
 impl Claim {
@@ -77,12 +157,245 @@ impl Claim {
             published_timestamp,
             ingested_timestamp: Utc::now(),
             raw_source_link: None,
+            citation: None,
             attribution_depth,
             information_type,
             source_entity_id,
             referenced_entity_ids: Vec::new(),
+            auto_linked_entity_ids: Vec::new(),
+            supports_relationship_ids: Vec::new(),
             embedding: None,
             embedding_pending: false,
+            investigation_id: None,
+            monitor_id: None,
+            language: None,
+            original_content: None,
+            original_language: None,
+            region: None,
+            source_trust_level: None,
+            disputed_count: 0,
+            redactions: BTreeMap::new(),
+            merged_sources: Vec::new(),
+        }
+    }
+
+    /// Cite `citation` (normalized), keeping `raw_source_link` in step.
+    pub fn set_citation(&mut self, citation: Citation) {
+        let citation = citation.normalized();
+        self.raw_source_link = Some(citation.url.clone());
+        self.citation = Some(citation);
+    }
+
+    /// Take in `duplicate`, a claim of the same information: its references
+    /// and supported relationships are added, the earlier publication time
+    /// and stronger attribution depth kept, and its provenance (with that of
+    /// any claims merged into it) recorded in `merged_sources`. Content and
+    /// embedding stay this claim's.
+    pub fn absorb(&mut self, duplicate: &Claim) {
+        let replaced_attribution_depth =
+            (duplicate.attribution_depth.rank() > self.attribution_depth.rank()).then(|| {
+                std::mem::replace(
+                    &mut self.attribution_depth,
+                    duplicate.attribution_depth.clone(),
+                )
+            });
+        self.merged_sources.push(MergedSource {
+            claim_id: duplicate.id,
+            source_entity_id: duplicate.source_entity_id,
+            citation: duplicate.citation.clone(),
+            published_timestamp: duplicate.published_timestamp,
+            attribution_depth: duplicate.attribution_depth.clone(),
+            replaced_attribution_depth,
+        });
+        for merged in &duplicate.merged_sources {
+            if !self
+                .merged_sources
+                .iter()
+                .any(|m| m.claim_id == merged.claim_id)
+            {
+                self.merged_sources.push(merged.clone());
+            }
+        }
+
+        self.published_timestamp = self.published_timestamp.min(duplicate.published_timestamp);
+        if self.citation.is_none() {
+            if let Some(citation) = &duplicate.citation {
+                self.set_citation(citation.clone());
+            }
+        }
+        for id in &duplicate.referenced_entity_ids {
+            if !self.referenced_entity_ids.contains(id) {
+                self.referenced_entity_ids.push(*id);
+                if duplicate.auto_linked_entity_ids.contains(id) {
+                    self.auto_linked_entity_ids.push(*id);
+                }
+            }
+        }
+        for id in &duplicate.supports_relationship_ids {
+            if !self.supports_relationship_ids.contains(id) {
+                self.supports_relationship_ids.push(*id);
+            }
         }
+        self.disputed_count += duplicate.disputed_count;
+    }
+}
+
+/// Normalize a language tag to its ISO 639-1 code: `"en-US"`, `"EN"` and
+/// `"en_gb"` all become `"en"`. None unless the primary subtag is two ASCII
+/// letters.
+pub fn normalize_language(tag: &str) -> Option<String> {
+    let primary = tag.trim().split(['-', '_']).next()?;
+    (primary.len() == 2 && primary.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| primary.to_ascii_lowercase())
+}
+
+/// Recognize an ISO 3166 code (alpha-2, alpha-3, or a subdivision such as
+/// `"US-CA"`) and return it uppercase. Free text returns None.
+pub fn iso_region_code(region: &str) -> Option<String> {
+    let region = region.trim();
+    let mut parts = region.split('-');
+    let country = parts.next()?;
+    let subdivision = parts.next();
+    let country_ok =
+        (2..=3).contains(&country.len()) && country.chars().all(|c| c.is_ascii_alphabetic());
+    let subdivision_ok = subdivision.is_none_or(|s| {
+        country.len() == 2
+            && (1..=3).contains(&s.len())
+            && s.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    (country_ok && subdivision_ok && parts.next().is_none()).then(|| region.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tags_normalize_to_iso_639_1() {
+        assert_eq!(normalize_language("en-US").as_deref(), Some("en"));
+        assert_eq!(normalize_language(" FR ").as_deref(), Some("fr"));
+        assert_eq!(normalize_language("pt_BR").as_deref(), Some("pt"));
+        assert_eq!(normalize_language("eng"), None);
+        assert_eq!(normalize_language(""), None);
+    }
+
+    #[test]
+    fn region_codes_are_recognized() {
+        assert_eq!(iso_region_code("ua").as_deref(), Some("UA"));
+        assert_eq!(iso_region_code("UKR").as_deref(), Some("UKR"));
+        assert_eq!(iso_region_code("us-ca").as_deref(), Some("US-CA"));
+        assert_eq!(iso_region_code("Eastern Europe"), None);
+        assert_eq!(iso_region_code("Crimea"), None);
+    }
+
+    #[test]
+    fn absorbing_a_duplicate_unions_its_provenance() {
+        let (reuters, ap) = (EntityId::new(), EntityId::new());
+        let (port, ministry, operator) = (EntityId::new(), EntityId::new(), EntityId::new());
+        let earlier = Utc::now() - chrono::Duration::days(2);
+
+        let mut claim = Claim::new(
+            "The port of Odesa closed on Monday.".into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            reuters,
+        );
+        claim.set_citation(Citation::from_url("https://reuters.example/odesa"));
+        claim.referenced_entity_ids = vec![port, ministry];
+        let mut duplicate = Claim::new(
+            claim.content.clone(),
+            earlier,
+            AttributionDepth::Primary,
+            InformationType::Assertion,
+            ap,
+        );
+        duplicate.set_citation(Citation::from_url("https://ap.example/odesa"));
+        duplicate.referenced_entity_ids = vec![port, operator];
+        duplicate.auto_linked_entity_ids = vec![operator];
+        duplicate.supports_relationship_ids = vec![RelationshipId::new()];
+        duplicate.disputed_count = 1;
+        let mut older = duplicate.clone();
+        older.id = ClaimId::new();
+        duplicate.merged_sources = vec![MergedSource {
+            claim_id: older.id,
+            source_entity_id: ap,
+            citation: None,
+            published_timestamp: earlier,
+            attribution_depth: AttributionDepth::Indirect,
+            replaced_attribution_depth: None,
+        }];
+
+        claim.absorb(&duplicate);
+        assert_eq!(claim.referenced_entity_ids, vec![port, ministry, operator]);
+        assert_eq!(claim.auto_linked_entity_ids, vec![operator]);
+        assert_eq!(
+            claim.supports_relationship_ids,
+            duplicate.supports_relationship_ids
+        );
+        assert_eq!(claim.published_timestamp, earlier);
+        assert_eq!(claim.disputed_count, 1);
+        // The stronger depth wins; the weaker is noted.
+        assert_eq!(claim.attribution_depth, AttributionDepth::Primary);
+        let merged = &claim.merged_sources;
+        assert_eq!(
+            merged.iter().map(|m| m.claim_id).collect::<Vec<_>>(),
+            vec![duplicate.id, older.id]
+        );
+        assert_eq!(merged[0].source_entity_id, ap);
+        assert_eq!(
+            merged[0].replaced_attribution_depth,
+            Some(AttributionDepth::Secondhand)
+        );
+        // Both sources stay cited.
+        assert_eq!(
+            claim.citation.as_ref().unwrap().url,
+            "https://reuters.example/odesa"
+        );
+        assert_eq!(
+            merged[0].citation.as_ref().unwrap().url,
+            "https://ap.example/odesa"
+        );
+
+        // A weaker duplicate leaves the depth alone.
+        let mut weaker = duplicate.clone();
+        weaker.id = ClaimId::new();
+        weaker.attribution_depth = AttributionDepth::Indirect;
+        weaker.merged_sources.clear();
+        claim.absorb(&weaker);
+        assert_eq!(claim.attribution_depth, AttributionDepth::Primary);
+        assert_eq!(
+            claim
+                .merged_sources
+                .last()
+                .unwrap()
+                .replaced_attribution_depth,
+            None
+        );
+        assert_eq!(claim.referenced_entity_ids.len(), 3);
+    }
+
+    #[test]
+    fn claim_without_language_or_region_deserializes() {
+        let claim = Claim::new(
+            "Port closed.".into(),
+            Utc::now(),
+            AttributionDepth::Primary,
+            InformationType::Assertion,
+            EntityId::new(),
+        );
+        let mut value = serde_json::to_value(&claim).unwrap();
+        assert!(value.get("language").is_none());
+        value.as_object_mut().unwrap().remove("region");
+        let back: Claim = serde_json::from_value(value).unwrap();
+        assert_eq!(back.language, None);
+        assert_eq!(back.region, None);
+
+        let mut claim = claim;
+        claim.language = Some("uk".into());
+        claim.region = Some("UA".into());
+        let back: Claim = serde_json::from_str(&serde_json::to_string(&claim).unwrap()).unwrap();
+        assert_eq!(back.language.as_deref(), Some("uk"));
+        assert_eq!(back.region.as_deref(), Some("UA"));
     }
 }
@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{WorkOrder, WorkOrderStatus};
+
+/// Change in new claims per work order between cycles that counts as rising
+/// or tapering rather than steady, as a fraction of the earlier rate.
+const TREND_TOLERANCE: f64 = 0.2;
+
+/// What one cycle's Processing phase produced: its work orders and, from
+/// their summaries, what they wrote to the graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleMetrics {
+    /// The Analyst cycle that created the work orders.
+    pub cycle: i32,
+    pub work_orders: u32,
+    pub completed: u32,
+    pub failed: u32,
+    #[serde(default)]
+    pub claims_created: u64,
+    #[serde(default)]
+    pub entities_created: u64,
+    #[serde(default)]
+    pub relationships_created: u64,
+}
+
+impl CycleMetrics {
+    /// New claims per work order; 0 for a cycle without work orders.
+    pub fn claims_per_work_order(&self) -> f64 {
+        if self.work_orders == 0 {
+            return 0.0;
+        }
+        self.claims_created as f64 / self.work_orders as f64
+    }
+}
+
+/// Direction of new claims per work order over an investigation's cycles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleTrend {
+    /// More new claims per work order than the cycle before (or the first
+    /// cycle to produce any).
+    Rising,
+    Steady,
+    /// Fewer new claims per work order than the cycle before: converging.
+    Tapering,
+    /// The latest cycle ran work orders but produced no new claims.
+    Stalled,
+}
+
+impl CycleTrend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rising => "rising",
+            Self::Steady => "steady",
+            Self::Tapering => "tapering",
+            Self::Stalled => "stalled",
+        }
+    }
+}
+
+/// Whether an investigation is converging or thrashing, from its latest
+/// cycles. See [`cycle_efficiency`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CycleEfficiency {
+    /// New claims per work order in the latest cycle.
+    pub claims_per_work_order: f64,
+    pub trend: CycleTrend,
+    /// Latest cycles in a row that ran work orders without producing a claim.
+    pub unproductive_cycles: u32,
+}
+
+/// Per-cycle aggregates of an investigation's work orders, by cycle. Claim,
+/// entity and relationship counts come from the work order summaries; a
+/// work order without a summary counts its recorded claims only. Proposals
+/// that were never dispatched don't count.
+pub fn aggregate_cycles(work_orders: &[WorkOrder]) -> Vec<CycleMetrics> {
+    let mut cycles: BTreeMap<i32, CycleMetrics> = BTreeMap::new();
+    for wo in work_orders.iter().filter(|wo| !wo.status.is_proposal()) {
+        let metrics = cycles.entry(wo.cycle).or_insert_with(|| CycleMetrics {
+            cycle: wo.cycle,
+            ..Default::default()
+        });
+        metrics.work_orders += 1;
+        match wo.status {
+            WorkOrderStatus::Completed => metrics.completed += 1,
+            WorkOrderStatus::Failed => metrics.failed += 1,
+            _ => {}
+        }
+        let count = |key: &str| {
+            wo.summary
+                .as_ref()
+                .and_then(|s| s.get(key))
+                .and_then(|v| v.as_u64())
+        };
+        metrics.claims_created += count("claims_created")
+            .unwrap_or_else(|| u64::try_from(wo.claims_produced_count).unwrap_or(0));
+        metrics.entities_created += count("entities_created").unwrap_or(0);
+        metrics.relationships_created += count("relationships_created").unwrap_or(0);
+    }
+    cycles.into_values().collect()
+}
+
+/// Efficiency of the latest cycle that ran work orders, compared with the
+/// one before it. `None` until a cycle has run work orders.
+pub fn cycle_efficiency(cycles: &[CycleMetrics]) -> Option<CycleEfficiency> {
+    let mut ran = cycles.iter().filter(|c| c.work_orders > 0).rev();
+    let latest = ran.next()?;
+    let previous = ran.next();
+
+    let unproductive_cycles = cycles
+        .iter()
+        .rev()
+        .filter(|c| c.work_orders > 0)
+        .take_while(|c| c.claims_created == 0)
+        .count() as u32;
+
+    let rate = latest.claims_per_work_order();
+    let trend = if latest.claims_created == 0 {
+        CycleTrend::Stalled
+    } else {
+        match previous.map(CycleMetrics::claims_per_work_order) {
+            None => CycleTrend::Rising,
+            Some(before) if rate > before * (1.0 + TREND_TOLERANCE) => CycleTrend::Rising,
+            Some(before) if rate < before * (1.0 - TREND_TOLERANCE) => CycleTrend::Tapering,
+            Some(_) => CycleTrend::Steady,
+        }
+    };
+
+    Some(CycleEfficiency {
+        claims_per_work_order: rate,
+        trend,
+        unproductive_cycles,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::ids::InvestigationId;
+    use crate::types::WorkOrderPriority;
+
+    fn work_order(cycle: i32, status: WorkOrderStatus, claims: u64) -> WorkOrder {
+        let mut wo = WorkOrder::new(
+            InvestigationId::new(),
+            "Find the vessel's operator".into(),
+            WorkOrderPriority::Normal,
+        );
+        wo.cycle = cycle;
+        wo.status = status;
+        wo.summary = Some(json!({
+            "outcome": "completed",
+            "claims_created": claims,
+            "entities_created": 1,
+            "relationships_created": 2,
+        }));
+        wo
+    }
+
+    fn cycle(cycle: i32, work_orders: u32, claims_created: u64) -> CycleMetrics {
+        CycleMetrics {
+            cycle,
+            work_orders,
+            completed: work_orders,
+            claims_created,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aggregate_groups_by_cycle() {
+        let mut no_summary = work_order(1, WorkOrderStatus::Failed, 0);
+        no_summary.summary = None;
+        no_summary.claims_produced_count = 4;
+        let cycles = aggregate_cycles(&[
+            work_order(1, WorkOrderStatus::Completed, 3),
+            work_order(0, WorkOrderStatus::Completed, 5),
+            no_summary,
+            work_order(1, WorkOrderStatus::Processing, 0),
+            work_order(1, WorkOrderStatus::Proposed, 0),
+            work_order(2, WorkOrderStatus::Dismissed, 0),
+        ]);
+
+        assert_eq!(
+            cycles,
+            [
+                CycleMetrics {
+                    cycle: 0,
+                    work_orders: 1,
+                    completed: 1,
+                    failed: 0,
+                    claims_created: 5,
+                    entities_created: 1,
+                    relationships_created: 2,
+                },
+                CycleMetrics {
+                    cycle: 1,
+                    work_orders: 3,
+                    completed: 1,
+                    failed: 1,
+                    claims_created: 7,
+                    entities_created: 2,
+                    relationships_created: 4,
+                },
+            ]
+        );
+        assert!(aggregate_cycles(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_efficiency_trend() {
+        assert_eq!(cycle_efficiency(&[]), None);
+        assert_eq!(cycle_efficiency(&[cycle(0, 0, 0)]), None);
+
+        let first = cycle_efficiency(&[cycle(0, 2, 6)]).unwrap();
+        assert_eq!(first.trend, CycleTrend::Rising);
+        assert_eq!(first.claims_per_work_order, 3.0);
+
+        let trend = |cycles: &[CycleMetrics]| cycle_efficiency(cycles).unwrap().trend;
+        assert_eq!(trend(&[cycle(0, 2, 2), cycle(1, 2, 6)]), CycleTrend::Rising);
+        assert_eq!(
+            trend(&[cycle(0, 2, 6), cycle(1, 4, 11)]),
+            CycleTrend::Steady
+        );
+        assert_eq!(
+            trend(&[cycle(0, 2, 6), cycle(1, 3, 2)]),
+            CycleTrend::Tapering
+        );
+        // A cycle without work orders doesn't count as a comparison point.
+        assert_eq!(
+            trend(&[cycle(0, 2, 6), cycle(1, 0, 0), cycle(2, 3, 2)]),
+            CycleTrend::Tapering
+        );
+    }
+
+    #[test]
+    fn test_efficiency_counts_unproductive_cycles() {
+        let efficiency = cycle_efficiency(&[
+            cycle(0, 2, 6),
+            cycle(1, 3, 0),
+            cycle(2, 0, 0),
+            cycle(3, 3, 0),
+        ])
+        .unwrap();
+        assert_eq!(efficiency.trend, CycleTrend::Stalled);
+        assert_eq!(efficiency.claims_per_work_order, 0.0);
+        assert_eq!(efficiency.unproductive_cycles, 2);
+
+        let recovered = cycle_efficiency(&[cycle(0, 3, 0), cycle(1, 3, 1)]).unwrap();
+        assert_eq!(recovered.trend, CycleTrend::Rising);
+        assert_eq!(recovered.unproductive_cycles, 0);
+    }
+}
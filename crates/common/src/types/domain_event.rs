@@ -0,0 +1,376 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{
+    AnalystSessionFinished, AttributionDepth, Claim, Confidence, EventPayload, InformationType,
+    InvestigationEventKind, InvestigationResumed, InvestigationSuspended, StatusChanged,
+    WorkOrderDequeued, WorkOrderEnqueued, WorkOrderFinished,
+};
+use crate::ids::{AssessmentId, ClaimId, EntityId, InvestigationId, MonitorId, WorkOrderId};
+
+/// Something that happened, as published on the engine's event bus to the
+/// timeline, webhooks and any other subscriber. Serialized with its
+/// [`Self::name`] under `type`.
+///
+/// A new kind of event is added here, with its name in [`Self::NAMES`]; the
+/// timeline records it if [`Self::timeline_entry`] maps it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// An investigation moved to another status (other than suspended).
+    StatusChanged {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        change: StatusChanged,
+    },
+    InvestigationSuspended {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        suspension: InvestigationSuspended,
+    },
+    InvestigationResumed {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        resumption: InvestigationResumed,
+    },
+    AnalystSessionFinished {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        session: AnalystSessionFinished,
+    },
+    /// A work order was put on a priority stream, or the delayed set.
+    WorkOrderEnqueued {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        enqueued: WorkOrderEnqueued,
+    },
+    /// A Processor took a work order off its stream.
+    WorkOrderDequeued {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        dequeued: WorkOrderDequeued,
+    },
+    /// A work order completed, or failed with a `failure_category`.
+    WorkOrderFinished {
+        investigation_id: InvestigationId,
+        #[serde(flatten)]
+        finished: WorkOrderFinished,
+    },
+    ClaimCreated(ClaimCreated),
+    AssessmentProduced(AssessmentProduced),
+}
+
+/// Payload of [`DomainEvent::ClaimCreated`]: the new claim and where it came from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClaimCreated {
+    pub claim_id: ClaimId,
+    pub source_entity_id: EntityId,
+    /// None for claims extracted for a monitor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub investigation_id: Option<InvestigationId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor_id: Option<MonitorId>,
+    /// The work order whose Processor session extracted it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_order_id: Option<WorkOrderId>,
+    pub attribution_depth: AttributionDepth,
+    pub information_type: InformationType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_source_link: Option<String>,
+    pub published_timestamp: DateTime<Utc>,
+}
+
+impl ClaimCreated {
+    /// The event for `claim`, extracted by `work_order_id`'s session.
+    pub fn new(claim: &Claim, work_order_id: Option<WorkOrderId>) -> Self {
+        Self {
+            claim_id: claim.id,
+            source_entity_id: claim.source_entity_id,
+            investigation_id: claim.investigation_id,
+            monitor_id: claim.monitor_id,
+            work_order_id,
+            attribution_depth: claim.attribution_depth.clone(),
+            information_type: claim.information_type.clone(),
+            raw_source_link: claim.raw_source_link.clone(),
+            published_timestamp: claim.published_timestamp,
+        }
+    }
+}
+
+/// Payload of [`DomainEvent::AssessmentProduced`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AssessmentProduced {
+    pub investigation_id: InvestigationId,
+    pub assessment_id: AssessmentId,
+    pub confidence: Confidence,
+    /// The earlier assessment this one replaces, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes_assessment_id: Option<AssessmentId>,
+}
+
+impl DomainEvent {
+    /// Every event's [`Self::name`].
+    pub const NAMES: [&'static str; 9] = [
+        "status_changed",
+        "investigation_suspended",
+        "investigation_resumed",
+        "analyst_session_finished",
+        "work_order_enqueued",
+        "work_order_dequeued",
+        "work_order_finished",
+        "claim_created",
+        "assessment_produced",
+    ];
+
+    /// The event's `type`, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::StatusChanged { .. } => "status_changed",
+            Self::InvestigationSuspended { .. } => "investigation_suspended",
+            Self::InvestigationResumed { .. } => "investigation_resumed",
+            Self::AnalystSessionFinished { .. } => "analyst_session_finished",
+            Self::WorkOrderEnqueued { .. } => "work_order_enqueued",
+            Self::WorkOrderDequeued { .. } => "work_order_dequeued",
+            Self::WorkOrderFinished { .. } => "work_order_finished",
+            Self::ClaimCreated(_) => "claim_created",
+            Self::AssessmentProduced(_) => "assessment_produced",
+        }
+    }
+
+    /// The investigation the event belongs to. None for a monitor's claims.
+    pub fn investigation_id(&self) -> Option<InvestigationId> {
+        match self {
+            Self::StatusChanged {
+                investigation_id, ..
+            }
+            | Self::InvestigationSuspended {
+                investigation_id, ..
+            }
+            | Self::InvestigationResumed {
+                investigation_id, ..
+            }
+            | Self::AnalystSessionFinished {
+                investigation_id, ..
+            }
+            | Self::WorkOrderEnqueued {
+                investigation_id, ..
+            }
+            | Self::WorkOrderDequeued {
+                investigation_id, ..
+            }
+            | Self::WorkOrderFinished {
+                investigation_id, ..
+            } => Some(*investigation_id),
+            Self::ClaimCreated(created) => created.investigation_id,
+            Self::AssessmentProduced(produced) => Some(produced.investigation_id),
+        }
+    }
+
+    /// The entry the investigation timeline records for this event, if it
+    /// records one from the bus. Status changes, suspensions and resumptions
+    /// are written by the store in the same transaction as the change, so
+    /// have none here; nor do claims and assessments, which have their own
+    /// history.
+    pub fn timeline_entry(&self) -> Option<(InvestigationId, InvestigationEventKind, Value)> {
+        fn entry<P: EventPayload>(
+            id: InvestigationId,
+            payload: &P,
+        ) -> Option<(InvestigationId, InvestigationEventKind, Value)> {
+            let value = serde_json::to_value(payload).ok()?;
+            Some((id, payload.kind(), value))
+        }
+        match self {
+            Self::AnalystSessionFinished {
+                investigation_id,
+                session,
+            } => entry(*investigation_id, session),
+            Self::WorkOrderEnqueued {
+                investigation_id,
+                enqueued,
+            } => entry(*investigation_id, enqueued),
+            Self::WorkOrderDequeued {
+                investigation_id,
+                dequeued,
+            } => entry(*investigation_id, dequeued),
+            Self::WorkOrderFinished {
+                investigation_id,
+                finished,
+            } => entry(*investigation_id, finished),
+            Self::StatusChanged { .. }
+            | Self::InvestigationSuspended { .. }
+            | Self::InvestigationResumed { .. }
+            | Self::ClaimCreated(_)
+            | Self::AssessmentProduced(_) => None,
+        }
+    }
+}
+
+/// A [`DomainEvent`] as delivered: with a unique ID, so an at-least-once
+/// consumer can recognize a redelivery, and when it was published.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    pub id: Uuid,
+    pub published_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+impl EventEnvelope {
+    pub fn new(event: DomainEvent) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            published_at: Utc::now(),
+            event,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::types::{FailureCategory, InvestigationStatus};
+
+    fn every_event() -> Vec<DomainEvent> {
+        let investigation_id = InvestigationId::new();
+        let work_order_id = WorkOrderId::new();
+        let claim = Claim::new(
+            "The MT Crius loaded at Bandar Abbas.".into(),
+            "2026-02-01T00:00:00Z".parse().unwrap(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            EntityId::new(),
+        );
+        vec![
+            DomainEvent::StatusChanged {
+                investigation_id,
+                change: StatusChanged {
+                    from: InvestigationStatus::Processing,
+                    to: InvestigationStatus::AnalystRunning,
+                },
+            },
+            DomainEvent::InvestigationSuspended {
+                investigation_id,
+                suspension: InvestigationSuspended {
+                    from: InvestigationStatus::Processing,
+                    reason: "engine_restart".into(),
+                    resume_from: "processing".into(),
+                },
+            },
+            DomainEvent::InvestigationResumed {
+                investigation_id,
+                resumption: InvestigationResumed {
+                    reason: Some("engine_restart".into()),
+                    resume_from: None,
+                    suspended_at: None,
+                },
+            },
+            DomainEvent::AnalystSessionFinished {
+                investigation_id,
+                session: AnalystSessionFinished {
+                    cycle: 1,
+                    mode: Some("cycle".into()),
+                    prompt_hash: None,
+                    outcome: "work_orders_created".into(),
+                    work_orders_created: Some(2),
+                    turns: 4,
+                    tool_calls: 6,
+                    tool_calls_by_name: BTreeMap::from([("create_work_order".into(), 2)]),
+                    input_tokens: 1000,
+                    output_tokens: 200,
+                    duration_ms: 5000,
+                },
+            },
+            DomainEvent::WorkOrderEnqueued {
+                investigation_id,
+                enqueued: WorkOrderEnqueued {
+                    work_order_id,
+                    stream: "workorders:normal".into(),
+                    entry_id: Some("1-0".into()),
+                    delayed_until: None,
+                },
+            },
+            DomainEvent::WorkOrderDequeued {
+                investigation_id,
+                dequeued: WorkOrderDequeued {
+                    work_order_id,
+                    consumer: "processor-0".into(),
+                    stream: "workorders:normal".into(),
+                    entry_id: "1-0".into(),
+                    delivery: 1,
+                },
+            },
+            DomainEvent::WorkOrderFinished {
+                investigation_id,
+                finished: WorkOrderFinished {
+                    work_order_id,
+                    duration_ms: 1200,
+                    claims_created: 0,
+                    failure_category: Some(FailureCategory::DeadlineExpired),
+                },
+            },
+            DomainEvent::ClaimCreated(ClaimCreated::new(&claim, Some(work_order_id))),
+            DomainEvent::AssessmentProduced(AssessmentProduced {
+                investigation_id,
+                assessment_id: AssessmentId::new(),
+                confidence: Confidence::Moderate,
+                supersedes_assessment_id: None,
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_events_round_trip_under_their_names() {
+        let events = every_event();
+        let names: Vec<&str> = events.iter().map(DomainEvent::name).collect();
+        assert_eq!(names, DomainEvent::NAMES);
+
+        for event in events {
+            let envelope = EventEnvelope::new(event);
+            let value = serde_json::to_value(&envelope).unwrap();
+            assert_eq!(value["type"], envelope.event.name());
+            assert_eq!(value["id"], envelope.id.to_string());
+            let decoded: EventEnvelope = serde_json::from_value(value).unwrap();
+            assert_eq!(decoded, envelope);
+        }
+    }
+
+    #[test]
+    fn test_timeline_entries() {
+        let recorded: Vec<(&str, InvestigationEventKind)> = every_event()
+            .iter()
+            .filter_map(|e| e.timeline_entry().map(|(_, kind, _)| (e.name(), kind)))
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                (
+                    "analyst_session_finished",
+                    InvestigationEventKind::AnalystSessionFinished
+                ),
+                (
+                    "work_order_enqueued",
+                    InvestigationEventKind::WorkOrderEnqueued
+                ),
+                (
+                    "work_order_dequeued",
+                    InvestigationEventKind::WorkOrderDequeued
+                ),
+                (
+                    "work_order_finished",
+                    InvestigationEventKind::WorkOrderFailed
+                ),
+            ]
+        );
+
+        // The payload is the timeline's own, without the bus's fields.
+        let events = every_event();
+        let (_, _, payload) = events[4].timeline_entry().unwrap();
+        assert_eq!(payload["stream"], "workorders:normal");
+        assert!(payload.get("investigation_id").is_none());
+        assert!(payload.get("type").is_none());
+    }
+}
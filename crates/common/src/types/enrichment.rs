@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::parse::{parse_variant, ParseEnumError};
+use crate::ids::{EnrichmentRequestId, InvestigationId, SourceDocumentId, WorkOrderId};
+
+/// Where an external enrichment request stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichmentStatus {
+    /// Submitted; the provider hasn't called back yet.
+    Pending,
+    /// The provider delivered a result, stored as a source document.
+    Completed,
+    /// The submission was refused, or the provider reported a failure.
+    Failed,
+    /// No callback arrived before the token expired.
+    Expired,
+}
+
+impl EnrichmentStatus {
+    pub const ALL: [Self; 4] = [Self::Pending, Self::Completed, Self::Failed, Self::Expired];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+impl FromStr for EnrichmentStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("enrichment status", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// A job submitted to an asynchronous enrichment provider, which answers at
+/// a single-use callback URL. The callback token itself is never stored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnrichmentRequest {
+    pub id: EnrichmentRequestId,
+    /// Configured provider name.
+    pub provider: String,
+    /// What was sent to the provider, as given by the session.
+    pub payload: Value,
+    pub investigation_id: InvestigationId,
+    /// Work order of the Processor session that asked. None when the
+    /// Analyst asked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_order_id: Option<WorkOrderId>,
+    /// Whether an arrived result is extracted by an ingest work order.
+    pub ingest: bool,
+    pub status: EnrichmentStatus,
+    /// Why the submission or the job failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The stored result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_document_id: Option<SourceDocumentId>,
+    /// The work order extracting the result, when `ingest` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingest_work_order_id: Option<WorkOrderId>,
+    /// Start of the stored result, when read for a listing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_excerpt: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// The callback token is refused after this.
+    pub expires_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl EnrichmentRequest {
+    pub fn new(
+        provider: String,
+        payload: Value,
+        investigation_id: InvestigationId,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: EnrichmentRequestId::new(),
+            provider,
+            payload,
+            investigation_id,
+            work_order_id: None,
+            ingest: false,
+            status: EnrichmentStatus::Pending,
+            error: None,
+            source_document_id: None,
+            ingest_work_order_id: None,
+            result_excerpt: None,
+            created_at: Utc::now(),
+            expires_at,
+            completed_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_mappings_in_sync() {
+        for status in EnrichmentStatus::ALL {
+            assert_eq!(status.as_db_str().parse::<EnrichmentStatus>(), Ok(status));
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_db_str());
+        }
+    }
+}
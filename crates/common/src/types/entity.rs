@@ -4,7 +4,18 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::ids::EntityId;
+use crate::ids::{EntityId, InvestigationId};
+
+use super::Identifiers;
+
+/// Freeform property holding a source entity's trust level.
+pub const TRUST_LEVEL_PROPERTY: &str = "trust_level";
+
+/// Lowest trust level: an unknown or unreliable source.
+pub const MIN_TRUST_LEVEL: u8 = 1;
+
+/// Highest trust level: a source with a strong record of accuracy.
+pub const MAX_TRUST_LEVEL: u8 = 5;
 
 /// An entity in the knowledge graph.
 ///
@@ -27,15 +38,27 @@ pub struct Entity {
     pub is_stub: bool,
     pub last_updated: DateTime<Utc>,
     /// Freeform key-value properties the LLM attaches.
-    /// Includes external identifiers (wikidata_qid, stock_ticker, iso_code, etc.).
+    /// May include weaker external identifiers (wikidata_qid, iso_code, etc.).
     #[serde(default)]
     pub properties: HashMap<String, Value>,
+    /// Strong external identifiers, validated and normalized per scheme.
+    /// Matching values make a dedup match; differing ones rule one out.
+    #[serde(default, skip_serializing_if = "Identifiers::is_empty")]
+    pub identifiers: Identifiers,
     /// Embedding vector (canonical_name + summary). None if embedding_pending.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub embedding: Option<Vec<f32>>,
     /// True if embedding computation failed and needs backfill.
     #[serde(default)]
     pub embedding_pending: bool,
+    /// Investigation whose session created this entity. Used by purge to decide
+    /// whether the entity is exclusive to that investigation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by_investigation: Option<InvestigationId>,
+    /// Reference entity loaded from bundled seed data (countries, admin regions).
+    /// Seeded entities are never purged and are preferred by dedup.
+    #[serde(default)]
+    pub is_seeded: bool,
 }
 
 impl Entity {
@@ -49,8 +72,11 @@ impl Entity {
             is_stub: false,
             last_updated: Utc::now(),
             properties: HashMap::new(),
+            identifiers: Identifiers::new(),
             embedding: None,
             embedding_pending: false,
+            created_by_investigation: None,
+            is_seeded: false,
         }
     }
 
@@ -59,4 +85,54 @@ impl Entity {
         entity.is_stub = true;
         entity
     }
+
+    /// How far claims published by this entity are trusted, from
+    /// `MIN_TRUST_LEVEL` to `MAX_TRUST_LEVEL`. None if unrated or the
+    /// property doesn't hold a valid level.
+    pub fn trust_level(&self) -> Option<u8> {
+        self.properties
+            .get(TRUST_LEVEL_PROPERTY)
+            .and_then(|value| parse_trust_level(value).ok())
+    }
+}
+
+/// Read a trust level: an integer from `MIN_TRUST_LEVEL` to `MAX_TRUST_LEVEL`.
+pub fn parse_trust_level(value: &Value) -> Result<u8, String> {
+    value
+        .as_u64()
+        .and_then(|level| u8::try_from(level).ok())
+        .filter(|level| (MIN_TRUST_LEVEL..=MAX_TRUST_LEVEL).contains(level))
+        .ok_or_else(|| {
+            format!(
+                "must be an integer from {} to {}, got {}",
+                MIN_TRUST_LEVEL, MAX_TRUST_LEVEL, value
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_trust_level() {
+        assert_eq!(parse_trust_level(&json!(1)), Ok(1));
+        assert_eq!(parse_trust_level(&json!(5)), Ok(5));
+        for invalid in [json!(0), json!(6), json!(2.5), json!("4"), json!(-1)] {
+            assert!(parse_trust_level(&invalid).is_err(), "{}", invalid);
+        }
+
+        let mut entity = Entity::new("Reuters".into(), "publication".into());
+        assert_eq!(entity.trust_level(), None);
+        entity
+            .properties
+            .insert(TRUST_LEVEL_PROPERTY.into(), json!(4));
+        assert_eq!(entity.trust_level(), Some(4));
+        entity
+            .properties
+            .insert(TRUST_LEVEL_PROPERTY.into(), json!("high"));
+        assert_eq!(entity.trust_level(), None);
+    }
 }
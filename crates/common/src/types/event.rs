@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::parse::{parse_variant, ParseEnumError};
+use super::{FailureCategory, InvestigationStatus, WorkOrderPriority};
+use crate::ids::{InvestigationId, WorkOrderId};
+
+/// What an investigation event records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvestigationEventKind {
+    /// The Analyst moved a pending work order to another priority stream.
+    WorkOrderReprioritized,
+    /// An Analyst session started; records the cycle and prompt hash.
+    AnalystSessionStarted,
+    /// An operator edited the investigation while it was Pending; records
+    /// each changed field's old and new value.
+    InvestigationEdited,
+    /// The investigation moved to another status ([`StatusChanged`]).
+    StatusChanged,
+    /// The investigation was suspended ([`InvestigationSuspended`]).
+    InvestigationSuspended,
+    /// A suspended investigation was picked up again ([`InvestigationResumed`]).
+    InvestigationResumed,
+    /// An Analyst session ended ([`AnalystSessionFinished`]).
+    AnalystSessionFinished,
+    /// A work order was stored ([`WorkOrderCreated`]).
+    WorkOrderCreated,
+    /// A work order was put on a priority stream or the delayed set
+    /// ([`WorkOrderEnqueued`]).
+    WorkOrderEnqueued,
+    /// A Processor took a work order off its stream ([`WorkOrderDequeued`]).
+    WorkOrderDequeued,
+    /// A work order's Processor session succeeded ([`WorkOrderFinished`]).
+    WorkOrderCompleted,
+    /// A work order failed ([`WorkOrderFinished`]).
+    WorkOrderFailed,
+}
+
+impl InvestigationEventKind {
+    pub const ALL: [Self; 12] = [
+        Self::WorkOrderReprioritized,
+        Self::AnalystSessionStarted,
+        Self::InvestigationEdited,
+        Self::StatusChanged,
+        Self::InvestigationSuspended,
+        Self::InvestigationResumed,
+        Self::AnalystSessionFinished,
+        Self::WorkOrderCreated,
+        Self::WorkOrderEnqueued,
+        Self::WorkOrderDequeued,
+        Self::WorkOrderCompleted,
+        Self::WorkOrderFailed,
+    ];
+
+    /// Returns the string representation used in PostgreSQL and serde.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::WorkOrderReprioritized => "work_order_reprioritized",
+            Self::AnalystSessionStarted => "analyst_session_started",
+            Self::InvestigationEdited => "investigation_edited",
+            Self::StatusChanged => "status_changed",
+            Self::InvestigationSuspended => "investigation_suspended",
+            Self::InvestigationResumed => "investigation_resumed",
+            Self::AnalystSessionFinished => "analyst_session_finished",
+            Self::WorkOrderCreated => "work_order_created",
+            Self::WorkOrderEnqueued => "work_order_enqueued",
+            Self::WorkOrderDequeued => "work_order_dequeued",
+            Self::WorkOrderCompleted => "work_order_completed",
+            Self::WorkOrderFailed => "work_order_failed",
+        }
+    }
+}
+
+impl FromStr for InvestigationEventKind {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("investigation event kind", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// An entry in an investigation's append-only event log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InvestigationEvent {
+    pub id: i64,
+    pub investigation_id: InvestigationId,
+    pub kind: InvestigationEventKind,
+    /// Kind-specific details.
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The typed payload of a lifecycle event, recorded under [`Self::kind`].
+pub trait EventPayload: Serialize {
+    fn kind(&self) -> InvestigationEventKind;
+}
+
+/// Payload of [`InvestigationEventKind::StatusChanged`]. Suspensions are
+/// recorded as [`InvestigationSuspended`] instead.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusChanged {
+    pub from: InvestigationStatus,
+    pub to: InvestigationStatus,
+}
+
+impl EventPayload for StatusChanged {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::StatusChanged
+    }
+}
+
+/// Payload of [`InvestigationEventKind::InvestigationSuspended`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InvestigationSuspended {
+    /// The status it was suspended from.
+    pub from: InvestigationStatus,
+    /// Sanitized, as stored on the investigation.
+    pub reason: String,
+    /// "analyst" or "processing".
+    pub resume_from: String,
+}
+
+impl EventPayload for InvestigationSuspended {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::InvestigationSuspended
+    }
+}
+
+/// Payload of [`InvestigationEventKind::InvestigationResumed`]: the
+/// suspension that was cleared.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InvestigationResumed {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspended_at: Option<DateTime<Utc>>,
+}
+
+impl EventPayload for InvestigationResumed {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::InvestigationResumed
+    }
+}
+
+/// Payload of [`InvestigationEventKind::AnalystSessionFinished`]: how the
+/// session ended and what it took.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnalystSessionFinished {
+    pub cycle: i32,
+    /// "cycle", "force_final" or "failure", as in the session's
+    /// `analyst_session_started` event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hash: Option<String>,
+    /// "work_orders_created", "assessment_produced", "empty_session" or "failed".
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_orders_created: Option<u32>,
+    pub turns: u32,
+    pub tool_calls: u32,
+    /// Calls by tool name, as in [`SessionTelemetry`](super::SessionTelemetry).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tool_calls_by_name: BTreeMap<String, u32>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub duration_ms: u64,
+}
+
+impl EventPayload for AnalystSessionFinished {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::AnalystSessionFinished
+    }
+}
+
+/// Payload of [`InvestigationEventKind::WorkOrderCreated`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderCreated {
+    pub work_order_id: WorkOrderId,
+    pub cycle: i32,
+    pub priority: WorkOrderPriority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+impl EventPayload for WorkOrderCreated {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::WorkOrderCreated
+    }
+}
+
+/// Payload of [`InvestigationEventKind::WorkOrderEnqueued`]. A delayed
+/// work order is recorded twice: with `delayed_until` when scheduled, and
+/// without once released onto its stream.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderEnqueued {
+    pub work_order_id: WorkOrderId,
+    pub stream: String,
+    /// The stream entry ID, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delayed_until: Option<DateTime<Utc>>,
+}
+
+impl EventPayload for WorkOrderEnqueued {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::WorkOrderEnqueued
+    }
+}
+
+/// Payload of [`InvestigationEventKind::WorkOrderDequeued`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderDequeued {
+    pub work_order_id: WorkOrderId,
+    pub consumer: String,
+    pub stream: String,
+    pub entry_id: String,
+    pub delivery: i32,
+}
+
+impl EventPayload for WorkOrderDequeued {
+    fn kind(&self) -> InvestigationEventKind {
+        InvestigationEventKind::WorkOrderDequeued
+    }
+}
+
+/// Payload of [`InvestigationEventKind::WorkOrderCompleted`] and
+/// [`InvestigationEventKind::WorkOrderFailed`]; failed when it has a
+/// `failure_category`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WorkOrderFinished {
+    pub work_order_id: WorkOrderId,
+    /// From dequeue to the final status.
+    pub duration_ms: u64,
+    pub claims_created: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_category: Option<FailureCategory>,
+}
+
+impl EventPayload for WorkOrderFinished {
+    fn kind(&self) -> InvestigationEventKind {
+        match self.failure_category {
+            Some(_) => InvestigationEventKind::WorkOrderFailed,
+            None => InvestigationEventKind::WorkOrderCompleted,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_mappings_in_sync() {
+        for kind in InvestigationEventKind::ALL {
+            assert_eq!(kind.as_db_str().parse::<InvestigationEventKind>(), Ok(kind));
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.as_db_str());
+        }
+    }
+
+    #[test]
+    fn test_work_order_finished_kind() {
+        let mut finished = WorkOrderFinished {
+            work_order_id: WorkOrderId::new(),
+            duration_ms: 1200,
+            claims_created: 3,
+            failure_category: None,
+        };
+        assert_eq!(finished.kind(), InvestigationEventKind::WorkOrderCompleted);
+        finished.failure_category = Some(FailureCategory::DeadlineExpired);
+        assert_eq!(finished.kind(), InvestigationEventKind::WorkOrderFailed);
+        assert_eq!(
+            serde_json::to_value(&finished).unwrap()["failure_category"],
+            "deadline_expired"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{AssessmentId, ClaimId};
+
+use super::parse::{parse_variant, ParseEnumError};
+
+/// A reader's verdict on an assessment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackRating {
+    Accurate,
+    /// Right in part, but wrong or incomplete in places.
+    Partially,
+    Inaccurate,
+}
+
+impl FeedbackRating {
+    pub const ALL: [Self; 3] = [Self::Accurate, Self::Partially, Self::Inaccurate];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Accurate => "accurate",
+            Self::Partially => "partially",
+            Self::Inaccurate => "inaccurate",
+        }
+    }
+}
+
+impl FromStr for FeedbackRating {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("feedback rating", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// Feedback on an assessment from someone who read it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssessmentFeedback {
+    pub id: i64,
+    pub assessment_id: AssessmentId,
+    pub rating: FeedbackRating,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    /// Claims the submitter disputes. Each is marked disputed in the graph.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disputed_claim_ids: Vec<ClaimId>,
+    /// Identifies the credential the feedback was submitted with, without
+    /// revealing it. None when the request carried none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Feedback counts for one assessment, by rating.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedbackSummary {
+    pub accurate: u32,
+    pub partially: u32,
+    pub inaccurate: u32,
+}
+
+impl FeedbackSummary {
+    pub fn add(&mut self, rating: FeedbackRating, count: u32) {
+        match rating {
+            FeedbackRating::Accurate => self.accurate += count,
+            FeedbackRating::Partially => self.partially += count,
+            FeedbackRating::Inaccurate => self.inaccurate += count,
+        }
+    }
+
+    /// Whether any reader rated the assessment less than accurate.
+    pub fn is_negative(&self) -> bool {
+        self.partially > 0 || self.inaccurate > 0
+    }
+
+    /// A warning to show alongside the assessment when feedback is negative,
+    /// so it isn't built on uncritically.
+    pub fn caveat(&self) -> Option<String> {
+        if !self.is_negative() {
+            return None;
+        }
+        let mut ratings = Vec::new();
+        if self.inaccurate > 0 {
+            ratings.push(format!("{} inaccurate", self.inaccurate));
+        }
+        if self.partially > 0 {
+            ratings.push(format!("{} partially accurate", self.partially));
+        }
+        if self.accurate > 0 {
+            ratings.push(format!("{} accurate", self.accurate));
+        }
+        Some(format!(
+            "Caveat: readers rated this assessment {}. Verify its conclusions before relying on them.",
+            ratings.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rating_mappings_in_sync() {
+        for rating in FeedbackRating::ALL {
+            assert_eq!(rating.as_db_str().parse::<FeedbackRating>(), Ok(rating));
+            let json = serde_json::to_value(rating).unwrap();
+            assert_eq!(json, rating.as_db_str());
+        }
+    }
+
+    #[test]
+    fn test_caveat_only_for_negative_feedback() {
+        let mut summary = FeedbackSummary::default();
+        assert_eq!(summary.caveat(), None);
+        summary.add(FeedbackRating::Accurate, 3);
+        assert_eq!(summary.caveat(), None);
+
+        summary.add(FeedbackRating::Inaccurate, 1);
+        summary.add(FeedbackRating::Partially, 2);
+        assert_eq!(
+            summary.caveat().unwrap(),
+            "Caveat: readers rated this assessment 1 inaccurate, 2 partially accurate, \
+             3 accurate. Verify its conclusions before relying on them."
+        );
+    }
+}
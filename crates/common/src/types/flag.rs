@@ -0,0 +1,68 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::parse::{parse_variant, ParseEnumError};
+
+/// An engine behavior that can be switched off (or back on) at runtime,
+/// without a restart. Each gates a behavior its own config section enables;
+/// the flag decides whether an enabled behavior runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureFlag {
+    /// Auto-linking new claims to known entities their content names
+    /// (`entity_linking`).
+    EntityLinking,
+    /// Batching concurrent graph creates into one write (`graph_writes.coalesce`).
+    WriteCoalescing,
+    /// Matching long claims by their chunk embeddings in semantic claim search
+    /// (`embeddings.claim_chunks`).
+    ClaimChunkSearch,
+    /// Merging just-written entities into semantic entity searches
+    /// (`dedup.fresh_entities.semantic_search`).
+    FreshEntitySearch,
+    /// Reusing cached LLM responses (`llm.<role>.cache_responses`).
+    LlmResponseCache,
+}
+
+impl FeatureFlag {
+    pub const ALL: [Self; 5] = [
+        Self::EntityLinking,
+        Self::WriteCoalescing,
+        Self::ClaimChunkSearch,
+        Self::FreshEntitySearch,
+        Self::LlmResponseCache,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EntityLinking => "entity_linking",
+            Self::WriteCoalescing => "write_coalescing",
+            Self::ClaimChunkSearch => "claim_chunk_search",
+            Self::FreshEntitySearch => "fresh_entity_search",
+            Self::LlmResponseCache => "llm_response_cache",
+        }
+    }
+}
+
+impl FromStr for FeatureFlag {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("feature flag", s, &Self::ALL, Self::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_mappings_in_sync() {
+        for (index, flag) in FeatureFlag::ALL.into_iter().enumerate() {
+            assert_eq!(flag as usize, index);
+            assert_eq!(flag.as_str().parse::<FeatureFlag>(), Ok(flag));
+            assert_eq!(serde_json::to_value(flag).unwrap(), flag.as_str());
+        }
+    }
+}
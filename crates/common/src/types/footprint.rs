@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Provider key for `web_search` in `OutboundFootprint::searches_by_provider`.
+/// Source queries count under their source ID.
+pub const WEB_SEARCH_PROVIDER: &str = "web";
+
+/// Outbound requests a Processor session (or, summed, a whole investigation)
+/// made through the Fetch service: its external footprint beyond LLM cost.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboundFootprint {
+    /// `fetch_url` calls answered by the Fetch service, cached ones included.
+    #[serde(default)]
+    pub fetches: u64,
+    /// Fetches answered from the Fetch service's cache, without contacting the site.
+    #[serde(default)]
+    pub cached_fetches: u64,
+    /// Fetches by domain (as the Fetch service keys its rate limits).
+    #[serde(default)]
+    pub fetches_by_domain: BTreeMap<String, u64>,
+    /// Web searches and source queries.
+    #[serde(default)]
+    pub searches: u64,
+    /// Searches by provider: `web` for web search, the source ID for source queries.
+    #[serde(default)]
+    pub searches_by_provider: BTreeMap<String, u64>,
+    /// Content bytes received from uncached fetches.
+    #[serde(default)]
+    pub bytes_downloaded: u64,
+    /// Fetches the Fetch service refused because the domain's rate limit was hit.
+    #[serde(default)]
+    pub rate_limited: u64,
+    /// Fetches refused because the investigation's fetch budget was spent.
+    #[serde(default)]
+    pub budget_rejections: u64,
+}
+
+impl OutboundFootprint {
+    /// Count a fetch of `domain` that returned `bytes` of content.
+    pub fn record_fetch(&mut self, domain: &str, bytes: u64, cached: bool) {
+        self.fetches += 1;
+        *self
+            .fetches_by_domain
+            .entry(domain.to_string())
+            .or_default() += 1;
+        if cached {
+            self.cached_fetches += 1;
+        } else {
+            self.bytes_downloaded += bytes;
+        }
+    }
+
+    /// Count a search against `provider`.
+    pub fn record_search(&mut self, provider: &str) {
+        self.searches += 1;
+        *self
+            .searches_by_provider
+            .entry(provider.to_string())
+            .or_default() += 1;
+    }
+
+    /// Add `other`'s counts to this one.
+    pub fn merge(&mut self, other: &OutboundFootprint) {
+        self.fetches += other.fetches;
+        self.cached_fetches += other.cached_fetches;
+        for (domain, count) in &other.fetches_by_domain {
+            *self.fetches_by_domain.entry(domain.clone()).or_default() += count;
+        }
+        self.searches += other.searches;
+        for (provider, count) in &other.searches_by_provider {
+            *self
+                .searches_by_provider
+                .entry(provider.clone())
+                .or_default() += count;
+        }
+        self.bytes_downloaded += other.bytes_downloaded;
+        self.rate_limited += other.rate_limited;
+        self.budget_rejections += other.budget_rejections;
+    }
+}
+
+/// Caller-set limit on an investigation's `fetch_url` calls, across all its
+/// work orders. Unset fields don't limit. Re-reads of a fetched document's
+/// tables don't count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchBudget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fetches: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fetches_per_domain: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_sums_counts() {
+        let mut first = OutboundFootprint::default();
+        first.record_fetch("example.com", 1200, false);
+        first.record_fetch("example.com", 800, true);
+        first.record_search(WEB_SEARCH_PROVIDER);
+
+        let mut second = OutboundFootprint::default();
+        second.record_fetch("registry.example.org", 300, false);
+        second.record_fetch("example.com", 50, false);
+        second.record_search("opensanctions");
+        second.rate_limited = 1;
+        second.budget_rejections = 2;
+
+        first.merge(&second);
+        assert_eq!(first.fetches, 4);
+        assert_eq!(first.cached_fetches, 1);
+        assert_eq!(first.fetches_by_domain["example.com"], 3);
+        assert_eq!(first.fetches_by_domain["registry.example.org"], 1);
+        assert_eq!(first.bytes_downloaded, 1550);
+        assert_eq!(first.searches, 2);
+        assert_eq!(first.searches_by_provider[WEB_SEARCH_PROVIDER], 1);
+        assert_eq!(first.searches_by_provider["opensanctions"], 1);
+        assert_eq!((first.rate_limited, first.budget_rejections), (1, 2));
+    }
+}
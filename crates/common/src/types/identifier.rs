@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::parse::{parse_variant, ParseEnumError};
+
+/// A strong external identifier scheme. Two entities with the same value
+/// under one scheme are the same real-world thing whatever their names; two
+/// with different values under one scheme are different things.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentifierScheme {
+    /// ISO 17442 Legal Entity Identifier: 20 characters, MOD 97-10 checksum.
+    Lei,
+    /// SEC Central Index Key, stored zero-padded to 10 digits.
+    Cik,
+    /// Maritime Mobile Service Identity: 9 digits.
+    Mmsi,
+    /// IMO ship number: 7 digits, the last a check digit.
+    Imo,
+    /// Listed security as `EXCHANGE:SYMBOL`, e.g. `NASDAQ:AAPL`.
+    Ticker,
+    /// Company register number as `CC:NUMBER`, with an ISO 3166-1 alpha-2
+    /// jurisdiction, e.g. `GB:00445790`.
+    CompanyRegistration,
+}
+
+/// An entity's identifiers: at most one value per scheme, normalized.
+pub type Identifiers = BTreeMap<IdentifierScheme, String>;
+
+impl IdentifierScheme {
+    pub const ALL: [Self; 6] = [
+        Self::Lei,
+        Self::Cik,
+        Self::Mmsi,
+        Self::Imo,
+        Self::Ticker,
+        Self::CompanyRegistration,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lei => "lei",
+            Self::Cik => "cik",
+            Self::Mmsi => "mmsi",
+            Self::Imo => "imo",
+            Self::Ticker => "ticker",
+            Self::CompanyRegistration => "company_registration",
+        }
+    }
+
+    /// Validate `value` under this scheme and return its normalized form,
+    /// the one stored and compared.
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self {
+            Self::Lei => {
+                let lei = value.to_ascii_uppercase();
+                if lei.len() != 20 || !lei.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(format!("LEI '{}' must be 20 letters and digits", value));
+                }
+                if !lei[18..].chars().all(|c| c.is_ascii_digit()) || lei_mod97(&lei) != 1 {
+                    return Err(format!("LEI '{}' fails its checksum", value));
+                }
+                Ok(lei)
+            }
+            Self::Cik => {
+                let digits = value.to_ascii_uppercase();
+                let digits = digits.strip_prefix("CIK").unwrap_or(&digits).trim();
+                if digits.is_empty()
+                    || digits.len() > 10
+                    || !digits.chars().all(|c| c.is_ascii_digit())
+                {
+                    return Err(format!("CIK '{}' must be 1 to 10 digits", value));
+                }
+                Ok(format!("{:0>10}", digits))
+            }
+            Self::Mmsi => {
+                if value.len() != 9 || !value.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(format!("MMSI '{}' must be 9 digits", value));
+                }
+                Ok(value.to_string())
+            }
+            Self::Imo => {
+                let upper = value.to_ascii_uppercase();
+                let digits = upper.strip_prefix("IMO").unwrap_or(&upper).trim();
+                if digits.len() != 7 || !digits.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(format!("IMO number '{}' must be 7 digits", value));
+                }
+                let d: Vec<u32> = digits.chars().filter_map(|c| c.to_digit(10)).collect();
+                let sum: u32 = d[..6].iter().zip((2..=7).rev()).map(|(d, w)| d * w).sum();
+                if sum % 10 != d[6] {
+                    return Err(format!("IMO number '{}' fails its check digit", value));
+                }
+                Ok(digits.to_string())
+            }
+            Self::Ticker => {
+                let upper = value.to_ascii_uppercase();
+                let invalid = || {
+                    format!(
+                        "Ticker '{}' must be EXCHANGE:SYMBOL, e.g. NASDAQ:AAPL",
+                        value
+                    )
+                };
+                let (exchange, symbol) = upper.split_once(':').ok_or_else(invalid)?;
+                let (exchange, symbol) = (exchange.trim(), symbol.trim());
+                let exchange_ok = (2..=12).contains(&exchange.len())
+                    && exchange.chars().all(|c| c.is_ascii_alphanumeric());
+                let symbol_ok = (1..=12).contains(&symbol.len())
+                    && symbol.starts_with(|c: char| c.is_ascii_alphanumeric())
+                    && symbol
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+                if !exchange_ok || !symbol_ok {
+                    return Err(invalid());
+                }
+                Ok(format!("{}:{}", exchange, symbol))
+            }
+            Self::CompanyRegistration => {
+                let upper = value.to_ascii_uppercase();
+                let invalid = || {
+                    format!(
+                        "Company registration '{}' must be CC:NUMBER with a two-letter \
+                         country code, e.g. GB:00445790",
+                        value
+                    )
+                };
+                let (country, number) = upper.split_once(':').ok_or_else(invalid)?;
+                let country = country.trim();
+                let number: String = number.chars().filter(|c| !c.is_whitespace()).collect();
+                let country_ok =
+                    country.len() == 2 && country.chars().all(|c| c.is_ascii_uppercase());
+                let number_ok = (1..=30).contains(&number.len())
+                    && number.starts_with(|c: char| c.is_ascii_alphanumeric())
+                    && number
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '/');
+                if !country_ok || !number_ok {
+                    return Err(invalid());
+                }
+                Ok(format!("{}:{}", country, number))
+            }
+        }
+    }
+}
+
+impl FromStr for IdentifierScheme {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("identifier scheme", s, &Self::ALL, Self::as_str)
+    }
+}
+
+/// ISO 7064 MOD 97-10 over an alphanumeric string, letters counting as
+/// 10 to 35.
+fn lei_mod97(lei: &str) -> u32 {
+    lei.chars().fold(0, |acc, c| {
+        let n = c.to_digit(36).unwrap_or(0);
+        if n < 10 {
+            (acc * 10 + n) % 97
+        } else {
+            (acc * 100 + n) % 97
+        }
+    })
+}
+
+/// Parse identifiers as given to a tool (scheme name → value), validating
+/// and normalizing each.
+pub fn parse_identifiers(raw: &HashMap<String, String>) -> Result<Identifiers, String> {
+    raw.iter()
+        .map(|(scheme, value)| {
+            let scheme: IdentifierScheme =
+                scheme.parse().map_err(|e: ParseEnumError| e.to_string())?;
+            Ok((scheme, scheme.normalize(value)?))
+        })
+        .collect()
+}
+
+/// Schemes under which `a` and `b` both have a value, and the values differ:
+/// proof they are different things.
+pub fn identifier_conflicts(a: &Identifiers, b: &Identifiers) -> Vec<IdentifierScheme> {
+    a.iter()
+        .filter(|(scheme, value)| b.get(scheme).is_some_and(|other| other != *value))
+        .map(|(scheme, _)| *scheme)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheme_mappings_in_sync() {
+        for scheme in IdentifierScheme::ALL {
+            assert_eq!(scheme.as_str().parse::<IdentifierScheme>(), Ok(scheme));
+            assert_eq!(serde_json::to_value(scheme).unwrap(), scheme.as_str());
+        }
+    }
+
+    #[test]
+    fn test_valid_identifiers_normalized() {
+        let cases = [
+            (
+                IdentifierScheme::Lei,
+                "hwupkr0mpou8fgxbt394",
+                "HWUPKR0MPOU8FGXBT394",
+            ),
+            (
+                IdentifierScheme::Lei,
+                "7LTWFZYICNSX8D621K86",
+                "7LTWFZYICNSX8D621K86",
+            ),
+            (IdentifierScheme::Cik, "320193", "0000320193"),
+            (IdentifierScheme::Cik, "CIK0000320193", "0000320193"),
+            (IdentifierScheme::Mmsi, "366982330", "366982330"),
+            (IdentifierScheme::Imo, "IMO 9074729", "9074729"),
+            (IdentifierScheme::Imo, "9321483", "9321483"),
+            (IdentifierScheme::Ticker, "nasdaq:aapl", "NASDAQ:AAPL"),
+            (IdentifierScheme::Ticker, "NYSE: BRK.B", "NYSE:BRK.B"),
+            (
+                IdentifierScheme::CompanyRegistration,
+                "gb:0044 5790",
+                "GB:00445790",
+            ),
+        ];
+        for (scheme, raw, normalized) in cases {
+            assert_eq!(scheme.normalize(raw).as_deref(), Ok(normalized), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_invalid_identifiers_rejected() {
+        let cases = [
+            // Last check digit off by one.
+            (IdentifierScheme::Lei, "HWUPKR0MPOU8FGXBT395"),
+            (IdentifierScheme::Lei, "HWUPKR0MPOU8FGXBT39"),
+            (IdentifierScheme::Cik, "12345678901"),
+            (IdentifierScheme::Cik, "12a45"),
+            (IdentifierScheme::Mmsi, "36698233"),
+            (IdentifierScheme::Imo, "9074728"),
+            (IdentifierScheme::Imo, "907472"),
+            (IdentifierScheme::Ticker, "AAPL"),
+            (IdentifierScheme::Ticker, "NASDAQ:"),
+            (IdentifierScheme::CompanyRegistration, "00445790"),
+            (IdentifierScheme::CompanyRegistration, "GBR:00445790"),
+        ];
+        for (scheme, raw) in cases {
+            assert!(
+                scheme.normalize(raw).is_err(),
+                "{} {}",
+                scheme.as_str(),
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_identifiers_and_conflicts() {
+        let raw = HashMap::from([
+            ("lei".to_string(), "hwupkr0mpou8fgxbt394".to_string()),
+            ("ticker".to_string(), "NASDAQ:AAPL".to_string()),
+        ]);
+        let apple = parse_identifiers(&raw).unwrap();
+        assert_eq!(apple[&IdentifierScheme::Lei], "HWUPKR0MPOU8FGXBT394");
+        assert!(parse_identifiers(&HashMap::from([(
+            "isin".to_string(),
+            "US0378331005".to_string()
+        )]))
+        .is_err());
+
+        let other = Identifiers::from([
+            (IdentifierScheme::Lei, "7LTWFZYICNSX8D621K86".to_string()),
+            (IdentifierScheme::Cik, "0000320193".to_string()),
+        ]);
+        assert_eq!(
+            identifier_conflicts(&apple, &other),
+            vec![IdentifierScheme::Lei]
+        );
+        assert!(identifier_conflicts(&apple, &apple).is_empty());
+        assert!(identifier_conflicts(&apple, &Identifiers::new()).is_empty());
+    }
+}
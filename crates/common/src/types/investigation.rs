@@ -1,10 +1,15 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::parse::{parse_variant, ParseEnumError};
+use super::{CycleEfficiency, CycleMetrics, FetchBudget, OutboundFootprint, WorkOrderPriority};
 use crate::ids::InvestigationId;
 
 /// Investigation lifecycle states per the state machine in PLAN.md §4.7.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum InvestigationStatus {
     /// Record created, not yet started.
@@ -22,6 +27,15 @@ pub enum InvestigationStatus {
 }
 
 impl InvestigationStatus {
+    pub const ALL: [Self; 6] = [
+        Self::Pending,
+        Self::AnalystRunning,
+        Self::Processing,
+        Self::Suspended,
+        Self::Completed,
+        Self::Failed,
+    ];
+
     pub fn is_terminal(&self) -> bool {
         matches!(self, Self::Completed | Self::Failed)
     }
@@ -43,6 +57,56 @@ impl InvestigationStatus {
     }
 }
 
+impl FromStr for InvestigationStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("investigation status", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+impl TryFrom<&str> for InvestigationStatus {
+    type Error = ParseEnumError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// How much corroboration the Analyst should require before treating a finding
+/// as established.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrictnessProfile {
+    /// A single credible source is enough; favors coverage.
+    Lenient,
+    #[default]
+    Standard,
+    /// Findings need independent corroboration; single-source claims are flagged.
+    Strict,
+}
+
+impl StrictnessProfile {
+    pub const ALL: [Self; 3] = [Self::Lenient, Self::Standard, Self::Strict];
+
+    /// Returns the string representation used in PostgreSQL and serde.
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Lenient => "lenient",
+            Self::Standard => "standard",
+            Self::Strict => "strict",
+        }
+    }
+}
+
+impl FromStr for StrictnessProfile {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("strictness profile", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
 /// An investigation record tracked in PostgreSQL.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Investigation {
@@ -64,6 +128,62 @@ pub struct Investigation {
     /// Where to resume from after suspension ("analyst" or "processing").
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resume_from: Option<String>,
+    /// Free-form organizational tags (see `normalize_tags`).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Cycle limit for this investigation, at most `safety.max_cycles_per_investigation`.
+    /// Unset uses the safety limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cycles: Option<u32>,
+    /// Entities the Analyst should prioritize (from pre-flight triage).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_entities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strictness: Option<StrictnessProfile>,
+    /// Highest priority this investigation's work orders may use. Unset allows
+    /// any priority.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_cap: Option<WorkOrderPriority>,
+    /// Limit on the investigation's fetches. Unset allows any number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_budget: Option<FetchBudget>,
+    /// Fetches and searches of all its work orders, summed when the
+    /// investigation ends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footprint: Option<OutboundFootprint>,
+    /// What each cycle's work orders produced, recorded as its Processing
+    /// phase completes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cycles: Vec<CycleMetrics>,
+    /// Derived from `cycles`: whether the investigation is still producing
+    /// new claims.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycle_efficiency: Option<CycleEfficiency>,
+    /// Caller-supplied ID (e.g. an upstream case number) carried into logs,
+    /// work order messages and exports. See `normalize_correlation_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Synthetic investigation run by the engine self-test. Its data is purged
+    /// once the self-test has checked it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub canary: bool,
+    /// Hash of each prompt template loaded when the investigation was created,
+    /// by name (e.g. "analyst"). Sessions started after a prompt reload record
+    /// their own hash.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prompt_hashes: BTreeMap<String, String>,
+    /// Bumped by each edit while Pending. Edits name the version they were
+    /// made against.
+    #[serde(default = "default_version")]
+    pub version: i32,
+    /// Snapshot mode: the Analyst sees the graph as it stood at this time and
+    /// cannot change it. Unset runs against the live graph.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+fn default_version() -> i32 {
+    1
 }
 
 impl Investigation {
@@ -79,6 +199,118 @@ impl Investigation {
             suspended_reason: None,
             suspended_at: None,
             resume_from: None,
+            tags: Vec::new(),
+            max_cycles: None,
+            focus_entities: Vec::new(),
+            strictness: None,
+            priority_cap: None,
+            fetch_budget: None,
+            footprint: None,
+            cycles: Vec::new(),
+            cycle_efficiency: None,
+            correlation_id: None,
+            canary: false,
+            prompt_hashes: BTreeMap::new(),
+            version: 1,
+            as_of: None,
+        }
+    }
+}
+
+/// Max length of a correlation ID, in characters.
+pub const MAX_CORRELATION_ID_LEN: usize = 128;
+
+/// Validate a caller-supplied correlation ID. Surrounding whitespace is trimmed
+/// and a blank ID counts as none. Control characters are rejected so the ID is
+/// safe to put in log lines verbatim.
+pub fn normalize_correlation_id(id: Option<&str>) -> Result<Option<String>, String> {
+    let Some(id) = id.map(str::trim).filter(|id| !id.is_empty()) else {
+        return Ok(None);
+    };
+    if id.chars().count() > MAX_CORRELATION_ID_LEN {
+        return Err(format!(
+            "correlation_id is longer than {} characters",
+            MAX_CORRELATION_ID_LEN
+        ));
+    }
+    if id.chars().any(char::is_control) {
+        return Err("correlation_id must not contain control characters".into());
+    }
+    Ok(Some(id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exhaustive: adding a variant fails to compile here until `ALL` and
+    /// `as_db_str` are updated alongside it.
+    fn index(status: InvestigationStatus) -> usize {
+        match status {
+            InvestigationStatus::Pending => 0,
+            InvestigationStatus::AnalystRunning => 1,
+            InvestigationStatus::Processing => 2,
+            InvestigationStatus::Suspended => 3,
+            InvestigationStatus::Completed => 4,
+            InvestigationStatus::Failed => 5,
+        }
+    }
+
+    #[test]
+    fn test_status_mappings_in_sync() {
+        for (i, status) in InvestigationStatus::ALL.into_iter().enumerate() {
+            assert_eq!(index(status), i);
+            assert_eq!(
+                status.as_db_str().parse::<InvestigationStatus>(),
+                Ok(status)
+            );
+            assert_eq!(
+                InvestigationStatus::try_from(status.as_db_str()),
+                Ok(status)
+            );
+
+            let json = serde_json::to_value(status).unwrap();
+            assert_eq!(json, status.as_db_str());
+            assert_eq!(
+                serde_json::from_value::<InvestigationStatus>(json).unwrap(),
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn test_strictness_mappings_in_sync() {
+        for profile in StrictnessProfile::ALL {
+            assert_eq!(
+                profile.as_db_str().parse::<StrictnessProfile>(),
+                Ok(profile)
+            );
+            assert_eq!(serde_json::to_value(profile).unwrap(), profile.as_db_str());
         }
     }
+
+    #[test]
+    fn test_normalize_correlation_id() {
+        assert_eq!(normalize_correlation_id(None), Ok(None));
+        assert_eq!(normalize_correlation_id(Some("   ")), Ok(None));
+        assert_eq!(
+            normalize_correlation_id(Some(" CASE-2291 ")),
+            Ok(Some("CASE-2291".into()))
+        );
+        assert!(normalize_correlation_id(Some("CASE\n2291")).is_err());
+
+        let longest = "x".repeat(MAX_CORRELATION_ID_LEN);
+        assert!(normalize_correlation_id(Some(&longest)).is_ok());
+        assert!(normalize_correlation_id(Some(&format!("{}x", longest))).is_err());
+    }
+
+    #[test]
+    fn test_unknown_status_lists_valid_ones() {
+        let err = "running".parse::<InvestigationStatus>().unwrap_err();
+        assert_eq!(err.value, "running");
+        assert_eq!(
+            err.expected,
+            InvestigationStatus::ALL.map(|s| s.as_db_str()).to_vec()
+        );
+    }
 }
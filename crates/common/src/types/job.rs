@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::parse::{parse_variant, ParseEnumError};
+use crate::ids::MaintenanceJobId;
+
+/// What a maintenance job does to the graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceJobType {
+    /// Recompute every entity's embedding with the configured model.
+    ReembedEntities,
+    /// Recompute every claim's embedding with the configured model.
+    ReembedClaims,
+    /// Rebuild the claim fulltext index over the configured properties.
+    RebuildFulltext,
+    /// Trim and lowercase entity kinds, with spaces and hyphens as
+    /// underscores.
+    NormalizeKinds,
+    /// Rewrite each entity's `aliases_text` from its `aliases` list.
+    BackfillAliasesList,
+}
+
+impl MaintenanceJobType {
+    pub const ALL: [Self; 5] = [
+        Self::ReembedEntities,
+        Self::ReembedClaims,
+        Self::RebuildFulltext,
+        Self::NormalizeKinds,
+        Self::BackfillAliasesList,
+    ];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::ReembedEntities => "reembed_entities",
+            Self::ReembedClaims => "reembed_claims",
+            Self::RebuildFulltext => "rebuild_fulltext",
+            Self::NormalizeKinds => "normalize_kinds",
+            Self::BackfillAliasesList => "backfill_aliases_list",
+        }
+    }
+}
+
+impl FromStr for MaintenanceJobType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("maintenance job type", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// Where a maintenance job stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceJobStatus {
+    /// Waiting for the job runner.
+    Queued,
+    /// Being worked through, or interrupted mid-run and waiting to resume from
+    /// its checkpoint.
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl MaintenanceJobStatus {
+    pub const ALL: [Self; 5] = [
+        Self::Queued,
+        Self::Running,
+        Self::Completed,
+        Self::Failed,
+        Self::Cancelled,
+    ];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the job will do no more work.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl FromStr for MaintenanceJobStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("maintenance job status", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// A graph-wide maintenance job started through POST /admin/jobs. Jobs work
+/// in batches and checkpoint after each one, so a restarted engine resumes
+/// a job rather than starting it over.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaintenanceJob {
+    pub id: MaintenanceJobId,
+    pub job_type: MaintenanceJobType,
+    /// Job-specific parameters, as given.
+    #[serde(default)]
+    pub params: Value,
+    /// Count what the job would change without changing it.
+    pub dry_run: bool,
+    pub status: MaintenanceJobStatus,
+    /// Where the next batch starts; opaque to everything but the job type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+    /// Items looked at so far.
+    pub processed: u64,
+    /// Items changed so far; in a dry run, the items that would have been.
+    pub changed: u64,
+    /// Items the job will look at, counted when it starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// Items that failed.
+    pub errors: u64,
+    /// The first failed items' IDs, each with its error.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub error_samples: Vec<String>,
+    /// A cancel was asked for; a running job stops after its current batch.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancel_requested: bool,
+    /// Why the job failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl MaintenanceJob {
+    /// A queued job.
+    pub fn new(job_type: MaintenanceJobType, params: Value, dry_run: bool) -> Self {
+        Self {
+            id: MaintenanceJobId::new(),
+            job_type,
+            params,
+            dry_run,
+            status: MaintenanceJobStatus::Queued,
+            checkpoint: None,
+            processed: 0,
+            changed: 0,
+            total: None,
+            errors: 0,
+            error_samples: Vec::new(),
+            cancel_requested: false,
+            error: None,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_enums_round_trip() {
+        for job_type in MaintenanceJobType::ALL {
+            assert_eq!(
+                job_type.as_db_str().parse::<MaintenanceJobType>().unwrap(),
+                job_type
+            );
+            assert_eq!(
+                serde_json::to_value(job_type).unwrap(),
+                job_type.as_db_str()
+            );
+        }
+        for status in MaintenanceJobStatus::ALL {
+            assert_eq!(
+                status.as_db_str().parse::<MaintenanceJobStatus>().unwrap(),
+                status
+            );
+        }
+        assert!("defrag".parse::<MaintenanceJobType>().is_err());
+    }
+}
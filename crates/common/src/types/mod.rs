@@ -1,13 +1,47 @@
 mod assessment;
+mod audit;
+mod citation;
 mod claim;
+mod cycle;
+mod domain_event;
+mod enrichment;
 mod entity;
+mod event;
+mod feedback;
+mod flag;
+mod footprint;
+mod identifier;
 mod investigation;
+mod job;
+mod monitor;
+mod parse;
 mod relationship;
+mod scratchpad;
+mod source_document;
+mod tag;
+mod telemetry;
 mod work_order;
 
 pub use assessment::*;
+pub use audit::*;
+pub use citation::*;
 pub use claim::*;
+pub use cycle::*;
+pub use domain_event::*;
+pub use enrichment::*;
 pub use entity::*;
+pub use event::*;
+pub use feedback::*;
+pub use flag::*;
+pub use footprint::*;
+pub use identifier::*;
 pub use investigation::*;
+pub use job::*;
+pub use monitor::*;
+pub use parse::ParseEnumError;
 pub use relationship::*;
+pub use scratchpad::*;
+pub use source_document::*;
+pub use tag::*;
+pub use telemetry::*;
 pub use work_order::*;
@@ -0,0 +1,126 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::parse::{parse_variant, ParseEnumError};
+use super::StrictnessProfile;
+use crate::ids::{InvestigationId, MonitorId};
+
+/// A standing subscription to a Fetch service source. Each run queries the
+/// source for items published since the high-water mark and files new ones
+/// as low-priority `IngestDocument` work orders, outside any investigation.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Monitor {
+    pub id: MonitorId,
+    pub name: String,
+    /// Fetch service source catalog ID queried each run.
+    pub source_id: String,
+    /// Source query parameters sent with every run, e.g. a feed URL or terms.
+    #[serde(default)]
+    pub params: serde_json::Map<String, Value>,
+    /// Time between runs.
+    pub interval_seconds: u64,
+    pub enabled: bool,
+    /// Corroboration standard the Processor is told to apply to the items.
+    #[serde(default)]
+    pub strictness: StrictnessProfile,
+    /// Topics the Processor is told to focus extraction on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub focus_tags: Vec<String>,
+    /// Most items ingested per run; the rest wait for the next one.
+    pub max_items_per_run: u32,
+    /// Standing ingest investigation the monitor's work orders are filed under.
+    pub investigation_id: InvestigationId,
+    /// Publication time of the newest item handled. Runs ask the source for
+    /// items since then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_water_mark: Option<DateTime<Utc>>,
+    /// When the scheduler runs the monitor next.
+    pub next_run_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<MonitorRun>,
+    /// Totals across all runs.
+    #[serde(default)]
+    pub items_seen: u64,
+    #[serde(default)]
+    pub items_ingested: u64,
+    #[serde(default)]
+    pub items_skipped: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Outcome of a monitor's most recent run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorRun {
+    pub at: DateTime<Utc>,
+    pub status: MonitorRunStatus,
+    /// Why the run failed, sanitized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Items the source returned.
+    #[serde(default)]
+    pub seen: u32,
+    /// Items stored as documents with a work order.
+    #[serde(default)]
+    pub ingested: u32,
+    /// Items not ingested: already seen, older than the high-water mark, or
+    /// without a URL.
+    #[serde(default)]
+    pub skipped: u32,
+    /// New items left for the next run by `max_items_per_run`.
+    #[serde(default)]
+    pub deferred: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorRunStatus {
+    Succeeded,
+    Failed,
+}
+
+impl MonitorRunStatus {
+    pub const ALL: [Self; 2] = [Self::Succeeded, Self::Failed];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for MonitorRunStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("monitor run status", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exhaustive: adding a variant fails to compile here until `ALL` and
+    /// `as_db_str` are updated alongside it.
+    fn index(status: MonitorRunStatus) -> usize {
+        match status {
+            MonitorRunStatus::Succeeded => 0,
+            MonitorRunStatus::Failed => 1,
+        }
+    }
+
+    #[test]
+    fn test_run_status_mappings_in_sync() {
+        for (i, status) in MonitorRunStatus::ALL.into_iter().enumerate() {
+            assert_eq!(index(status), i);
+            assert_eq!(status.as_db_str().parse::<MonitorRunStatus>(), Ok(status));
+            let json = serde_json::to_value(status).unwrap();
+            assert_eq!(json, status.as_db_str());
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+/// A string that doesn't name any variant of a status/priority enum.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid {kind} '{value}' (expected one of: {})", expected.join(", "))]
+pub struct ParseEnumError {
+    pub kind: &'static str,
+    pub value: String,
+    pub expected: Vec<&'static str>,
+}
+
+/// Parse `value` as the variant whose canonical string matches exactly.
+pub(crate) fn parse_variant<T: Copy>(
+    kind: &'static str,
+    value: &str,
+    variants: &[T],
+    as_str: impl Fn(&T) -> &'static str,
+) -> Result<T, ParseEnumError> {
+    variants
+        .iter()
+        .find(|v| as_str(v) == value)
+        .copied()
+        .ok_or_else(|| ParseEnumError {
+            kind,
+            value: value.to_string(),
+            expected: variants.iter().map(as_str).collect(),
+        })
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::ids::{EntityId, RelationshipId};
+use crate::ids::{EntityId, InvestigationId, RelationshipId};
 
 /// A relationship (RELATES_TO edge) between two entities in the knowledge graph.
 ///
@@ -15,9 +15,16 @@ pub struct Relationship {
     /// Freeform natural language description.
     /// e.g. "TSMC supplies approximately 40% of Apple's A-series chip production."
     pub description: String,
-    /// Numeric significance signal.
+    /// Numeric significance signal. Set by the LLM at creation; recalibrated from
+    /// supporting claims once any claim is linked to this relationship.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub weight: Option<f64>,
+    /// Weight before the most recent recalibration (audit trail).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_weight: Option<f64>,
+    /// When the weight was last recalibrated from supporting claims.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight_recalibrated_at: Option<DateTime<Utc>>,
     /// How certain the system is about this relationship.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub confidence: Option<f64>,
@@ -32,6 +39,9 @@ pub struct Relationship {
     pub embedding: Option<Vec<f32>>,
     #[serde(default)]
     pub embedding_pending: bool,
+    /// Investigation whose session created this relationship (purge provenance).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by_investigation: Option<InvestigationId>,
 }
 
 impl Relationship {
@@ -46,11 +56,14 @@ impl Relationship {
             target_entity_id,
             description,
             weight: None,
+            previous_weight: None,
+            weight_recalibrated_at: None,
             confidence: None,
             bidirectional: false,
             timestamp: None,
             embedding: None,
             embedding_pending: false,
+            created_by_investigation: None,
         }
     }
 }
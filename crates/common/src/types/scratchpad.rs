@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Max length of a scratchpad section name, in characters.
+pub const MAX_SCRATCHPAD_SECTION_NAME_LEN: usize = 64;
+
+/// One named section of an investigation's Analyst scratchpad: working notes
+/// the Analyst keeps across cycles.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScratchpadSection {
+    pub section: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Validate and normalize a scratchpad section name. Names are trimmed and
+/// lowercased so "Hypotheses" and "hypotheses " are the same section.
+pub fn normalize_scratchpad_section(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Section name must not be empty".into());
+    }
+    if name.chars().count() > MAX_SCRATCHPAD_SECTION_NAME_LEN {
+        return Err(format!(
+            "Section name '{}' is longer than {} characters",
+            name, MAX_SCRATCHPAD_SECTION_NAME_LEN
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Section name must not contain control characters".into());
+    }
+    Ok(name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_scratchpad_section() {
+        assert_eq!(
+            normalize_scratchpad_section(" Open Questions ").unwrap(),
+            "open questions"
+        );
+        assert!(normalize_scratchpad_section("  ").is_err());
+        assert!(normalize_scratchpad_section("a\nb").is_err());
+        assert!(
+            normalize_scratchpad_section(&"x".repeat(MAX_SCRATCHPAD_SECTION_NAME_LEN + 1)).is_err()
+        );
+    }
+}
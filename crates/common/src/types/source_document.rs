@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ids::{EntityId, SourceDocumentId};
+
+/// A document pushed to AutOSINT rather than fetched by a Processor, such as
+/// an email newsletter. Stored once; `IngestDocument` work orders extract
+/// from it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SourceDocument {
+    pub id: SourceDocumentId,
+    /// Where it came from, e.g. "imap".
+    pub origin: String,
+    /// Origin-specific identity, e.g. the message's Message-ID.
+    pub external_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Sender as written, e.g. `Maritime Brief <news@example.org>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The publication entity the document was attributed to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher_entity_id: Option<EntityId>,
+    /// Readable text: the plain-text body, or the HTML body stripped to text.
+    pub content: String,
+    /// When the source published it (the message's Date header).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<DateTime<Utc>>,
+    pub received_at: DateTime<Utc>,
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// Max tags on a single investigation or assessment.
+pub const MAX_TAGS: usize = 16;
+
+/// Max length of a single tag, in characters.
+pub const MAX_TAG_LEN: usize = 40;
+
+/// Validate and normalize free-form tags (e.g. "iran", "maritime", "q3-review").
+///
+/// Tags are trimmed and must be lowercase ASCII letters, digits, `-` or `_`, at most
+/// `MAX_TAG_LEN` characters. Duplicates are dropped, order is preserved. Matching is
+/// exact everywhere tags are filtered, so normalization is deliberately strict.
+pub fn normalize_tags<S: AsRef<str>>(tags: &[S]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+
+    for tag in tags {
+        let tag = tag.as_ref().trim();
+        if tag.is_empty() {
+            return Err("Tags must not be empty".into());
+        }
+        if tag.chars().count() > MAX_TAG_LEN {
+            return Err(format!(
+                "Tag '{}' is longer than {} characters",
+                tag, MAX_TAG_LEN
+            ));
+        }
+        if !tag
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Tag '{}' must contain only lowercase letters, digits, '-' or '_'",
+                tag
+            ));
+        }
+        if !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("At most {} tags are allowed", MAX_TAGS));
+    }
+
+    Ok(normalized)
+}
+
+/// How often a tag is used, for autocomplete.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagUsage {
+    pub tag: String,
+    pub investigations: i64,
+    pub assessments: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tags_trims_and_dedups() {
+        let tags = normalize_tags(&[" iran ", "maritime", "iran", "q3-review"]).unwrap();
+        assert_eq!(tags, vec!["iran", "maritime", "q3-review"]);
+    }
+
+    #[test]
+    fn test_normalize_tags_rejects_invalid() {
+        assert!(normalize_tags(&["Iran"]).is_err());
+        assert!(normalize_tags(&["red sea"]).is_err());
+        assert!(normalize_tags(&[""]).is_err());
+        assert!(normalize_tags(&["x".repeat(MAX_TAG_LEN + 1)]).is_err());
+
+        let too_many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("t{}", i)).collect();
+        assert!(normalize_tags(&too_many).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::parse::{parse_variant, ParseEnumError};
+
+/// Key that tool and outcome names outside `[a-z0-9_]{1,64}` are counted
+/// under, so free text can never become a telemetry key.
+pub const OTHER_TELEMETRY_KEY: &str = "other";
+
+/// Every field of a serialized [`PromptRollup`], in order. Rollups leave the
+/// deployment, so this list is fixed here rather than configurable; a test
+/// holds the struct to it.
+pub const ROLLUP_FIELDS: &[&str] = &[
+    "role",
+    "prompt_hash",
+    "window_start",
+    "window_end",
+    "sessions",
+    "mean_turns",
+    "median_turns",
+    "tool_calls",
+    "tool_calls_by_name",
+    "outcomes",
+    "input_tokens",
+    "output_tokens",
+    "claims_per_session",
+    "forced_final_rate",
+];
+
+/// Operational statistics of one Processor session, kept for telemetry
+/// rollups: counts and tool names, never prompt, argument or result text.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionTelemetry {
+    /// How the session ended ("completed", "max_turns_reached", ...).
+    pub outcome: String,
+    pub turns: u32,
+    pub tool_calls: u32,
+    /// Calls by tool name; calls to tools the session didn't offer count
+    /// under "unknown".
+    #[serde(default)]
+    pub tool_calls_by_name: BTreeMap<String, u32>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Which sessions a rollup covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryRole {
+    Processor,
+    Analyst,
+}
+
+impl TelemetryRole {
+    pub const ALL: [Self; 2] = [Self::Processor, Self::Analyst];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Processor => "processor",
+            Self::Analyst => "analyst",
+        }
+    }
+}
+
+impl FromStr for TelemetryRole {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("telemetry role", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+/// One prompt version's sessions over a window: how long they ran, which
+/// tools they called and how they ended. Nothing here is content.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PromptRollup {
+    pub role: TelemetryRole,
+    pub prompt_hash: String,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub sessions: u64,
+    pub mean_turns: f64,
+    pub median_turns: f64,
+    pub tool_calls: u64,
+    pub tool_calls_by_name: BTreeMap<String, u64>,
+    /// Sessions by how they ended.
+    pub outcomes: BTreeMap<String, u64>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Mean claims written per session. Processor rollups only.
+    pub claims_per_session: Option<f64>,
+    /// Fraction of sessions that were forced final assessments. Analyst
+    /// rollups only.
+    pub forced_final_rate: Option<f64>,
+}
+
+/// Body of a rollup push to the telemetry collector, signed like a webhook
+/// delivery (see `webhook`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryPush {
+    /// The operator's label for this deployment (`telemetry.deployment`).
+    pub deployment: String,
+    pub sent_at: DateTime<Utc>,
+    pub rollups: Vec<PromptRollup>,
+}
+
+/// `name` if it is a plain identifier (`[a-z0-9_]{1,64}`), else
+/// [`OTHER_TELEMETRY_KEY`].
+pub fn telemetry_key(name: &str) -> &str {
+    let plain = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+    if plain {
+        name
+    } else {
+        OTHER_TELEMETRY_KEY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollup_serializes_only_allowed_fields() {
+        let now = Utc::now();
+        let rollup = PromptRollup {
+            role: TelemetryRole::Processor,
+            prompt_hash: "ab12".into(),
+            window_start: now,
+            window_end: now,
+            sessions: 1,
+            mean_turns: 1.0,
+            median_turns: 1.0,
+            tool_calls: 0,
+            tool_calls_by_name: BTreeMap::new(),
+            outcomes: BTreeMap::new(),
+            input_tokens: 0,
+            output_tokens: 0,
+            claims_per_session: None,
+            forced_final_rate: None,
+        };
+        let value = serde_json::to_value(&rollup).unwrap();
+        let mut fields: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        fields.sort_unstable();
+        let mut allowed = ROLLUP_FIELDS.to_vec();
+        allowed.sort_unstable();
+        assert_eq!(fields, allowed);
+    }
+
+    #[test]
+    fn test_telemetry_key() {
+        assert_eq!(telemetry_key("fetch_url"), "fetch_url");
+        assert_eq!(telemetry_key("max_turns_reached"), "max_turns_reached");
+        for name in [
+            "",
+            "Fetch URL",
+            "search for Gulf Star Shipping",
+            "fetch-url",
+            &"a".repeat(65),
+        ] {
+            assert_eq!(telemetry_key(name), OTHER_TELEMETRY_KEY, "{:?}", name);
+        }
+    }
+}
@@ -1,33 +1,147 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::ids::{EntityId, InvestigationId, WorkOrderId};
+use super::parse::{parse_variant, ParseEnumError};
+use super::OutboundFootprint;
+use crate::ids::{EntityId, InvestigationId, MonitorId, SourceDocumentId, WorkOrderId};
 
 /// Work order lifecycle states.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum WorkOrderStatus {
+    /// Waiting in the delayed set for its `not_before` time; not yet dispatched.
+    Scheduled,
     Queued,
     Processing,
     Completed,
     Failed,
+    /// Suggested by a Processor; waits for the Analyst to approve (queued)
+    /// or dismiss it. Never dispatched as is.
+    Proposed,
+    /// A proposal the Analyst turned down.
+    Dismissed,
 }
 
 impl WorkOrderStatus {
+    pub const ALL: [Self; 7] = [
+        Self::Scheduled,
+        Self::Queued,
+        Self::Processing,
+        Self::Completed,
+        Self::Failed,
+        Self::Proposed,
+        Self::Dismissed,
+    ];
+
     pub fn as_db_str(&self) -> &'static str {
         match self {
+            Self::Scheduled => "scheduled",
             Self::Queued => "queued",
             Self::Processing => "processing",
             Self::Completed => "completed",
             Self::Failed => "failed",
+            Self::Proposed => "proposed",
+            Self::Dismissed => "dismissed",
         }
     }
+
+    /// Whether this is a Processor's proposal that was never dispatched:
+    /// pending or dismissed.
+    pub fn is_proposal(&self) -> bool {
+        matches!(self, Self::Proposed | Self::Dismissed)
+    }
+}
+
+impl FromStr for WorkOrderStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("work order status", s, &Self::ALL, Self::as_db_str)
+    }
+}
+
+impl TryFrom<&str> for WorkOrderStatus {
+    type Error = ParseEnumError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Coarse cause of a failed work order, for retry decisions and metrics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    /// The LLM call failed in a way a retry won't fix (auth, bad request,
+    /// unparseable response).
+    LlmError,
+    /// A service the session depends on — the LLM provider, the fetch service,
+    /// the graph — was unreachable, overloaded or rate limiting.
+    DependencyUnavailable,
+    /// The conversation outgrew the model's context window.
+    ContextExceeded,
+    /// The session hit a turn, time or malformed-call limit without writing
+    /// anything to the graph.
+    NoProgress,
+    /// The work order's deadline had passed by the time a Processor picked it
+    /// up; no session ran.
+    DeadlineExpired,
+}
+
+impl FailureCategory {
+    pub const ALL: [Self; 5] = [
+        Self::LlmError,
+        Self::DependencyUnavailable,
+        Self::ContextExceeded,
+        Self::NoProgress,
+        Self::DeadlineExpired,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LlmError => "llm_error",
+            Self::DependencyUnavailable => "dependency_unavailable",
+            Self::ContextExceeded => "context_exceeded",
+            Self::NoProgress => "no_progress",
+            Self::DeadlineExpired => "deadline_expired",
+        }
+    }
+}
+
+impl FromStr for FailureCategory {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("failure category", s, &Self::ALL, Self::as_str)
+    }
+}
+
+/// Diagnostics captured from a failed Processor session.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FailureDetail {
+    pub category: FailureCategory,
+    /// The session's error, sanitized.
+    pub error: String,
+    /// Tool called last, when the failure directly followed its error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_tool: Option<String>,
+    /// That tool call's error, sanitized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_tool_error: Option<String>,
+    /// Failed tool calls over the whole session, by tool name.
+    #[serde(default)]
+    pub tool_errors: BTreeMap<String, u32>,
+    /// Whether the session wrote any entity, claim or relationship.
+    pub graph_writes: bool,
 }
 
 /// Priority levels for work order queue routing.
 /// Maps to Redis streams: workorders:high, workorders:normal, workorders:low.
-#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WorkOrderPriority {
     High,
@@ -37,6 +151,17 @@ pub enum WorkOrderPriority {
 }
 
 impl WorkOrderPriority {
+    pub const ALL: [Self; 3] = [Self::High, Self::Normal, Self::Low];
+
+    /// Canonical name, as used in tool arguments and serde.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Low => "low",
+        }
+    }
+
     pub fn as_redis_stream(&self) -> &'static str {
         match self {
             Self::High => "workorders:high",
@@ -52,6 +177,132 @@ impl WorkOrderPriority {
             Self::Low => 0,
         }
     }
+
+    pub fn from_db_int(value: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.as_db_int() == value)
+    }
+
+    /// Whether this priority is dispatched ahead of `other`.
+    pub fn is_above(&self, other: WorkOrderPriority) -> bool {
+        self.as_db_int() > other.as_db_int()
+    }
+}
+
+impl FromStr for WorkOrderPriority {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("work order priority", s, &Self::ALL, Self::as_str)
+    }
+}
+
+impl TryFrom<&str> for WorkOrderPriority {
+    type Error = ParseEnumError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// How much effort a work order deserves. The Processor scales its turn and
+/// wall-clock budget by this, within the configured safety limits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkOrderEffort {
+    /// A quick answer from the most direct sources.
+    Quick,
+    #[default]
+    Standard,
+    /// Exhaustive coverage, up to the full session limits.
+    Thorough,
+}
+
+impl WorkOrderEffort {
+    pub const ALL: [Self; 3] = [Self::Quick, Self::Standard, Self::Thorough];
+
+    /// Canonical name, as used in tool arguments, serde and the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Quick => "quick",
+            Self::Standard => "standard",
+            Self::Thorough => "thorough",
+        }
+    }
+}
+
+impl FromStr for WorkOrderEffort {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("work order effort", s, &Self::ALL, Self::as_str)
+    }
+}
+
+/// How a Processor session spends its budget on a work order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkOrderMode {
+    /// Depth first: follow the objective through to primary sources.
+    #[default]
+    Focused,
+    /// Breadth first: skim many sources for what is out there, with a cap on
+    /// calls against any one domain or source.
+    Exploratory,
+}
+
+impl WorkOrderMode {
+    pub const ALL: [Self; 2] = [Self::Focused, Self::Exploratory];
+
+    /// Canonical name, as used in tool arguments, serde and the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Focused => "focused",
+            Self::Exploratory => "exploratory",
+        }
+    }
+}
+
+impl FromStr for WorkOrderMode {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("work order mode", s, &Self::ALL, Self::as_str)
+    }
+}
+
+/// What a Processor session does with a work order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkOrderKind {
+    /// Search the web and sources for the objective.
+    #[default]
+    Research,
+    /// Extract from an already-stored source document
+    /// (`WorkOrder::source_document_id`) instead of fetching anything.
+    IngestDocument,
+    /// Research a stub entity (the work order's referenced entity) and fill in
+    /// its summary and properties. Queued by stub resolution.
+    ProfileEntity,
+}
+
+impl WorkOrderKind {
+    pub const ALL: [Self; 3] = [Self::Research, Self::IngestDocument, Self::ProfileEntity];
+
+    pub fn as_db_str(&self) -> &'static str {
+        match self {
+            Self::Research => "research",
+            Self::IngestDocument => "ingest_document",
+            Self::ProfileEntity => "profile_entity",
+        }
+    }
+}
+
+impl FromStr for WorkOrderKind {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_variant("work order kind", s, &Self::ALL, Self::as_db_str)
+    }
 }
 
 /// Directional hints about where to look for information.
@@ -65,6 +316,39 @@ pub struct SourceGuidance {
     pub extra: serde_json::Map<String, Value>,
 }
 
+/// What the Analyst expects a work order to deliver. A Processor session that
+/// completes without meeting it leaves the work order marked `contract_unmet`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedOutputs {
+    /// Fewest claims the session should create.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_claims: Option<u32>,
+    /// Entities (by name) the session should create or find in the graph.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub require_entities: Vec<String>,
+    /// Fewest distinct sources the session's claims should cite.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_source_count: Option<u32>,
+}
+
+impl ExpectedOutputs {
+    /// Whether this asks for nothing.
+    pub fn is_empty(&self) -> bool {
+        self.min_claims.is_none()
+            && self.require_entities.is_empty()
+            && self.require_source_count.is_none()
+    }
+}
+
+/// A criterion of [`ExpectedOutputs`] a Processor session didn't meet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "criterion", rename_all = "snake_case")]
+pub enum UnmetOutput {
+    MinClaims { expected: u32, delivered: u32 },
+    RequireEntities { missing: Vec<String> },
+    RequireSourceCount { expected: u32, delivered: u32 },
+}
+
 /// A work order — a discovery directive from the Analyst to Processors.
 ///
 /// Directs WHERE to look and WHAT to look for, NOT what to extract.
@@ -77,12 +361,46 @@ pub struct WorkOrder {
     pub objective: String,
     pub status: WorkOrderStatus,
     pub priority: WorkOrderPriority,
+    #[serde(default)]
+    pub kind: WorkOrderKind,
+    /// The stored document an `IngestDocument` work order extracts from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_document_id: Option<SourceDocumentId>,
+    /// Monitor whose item the work order extracts. Its claims carry the
+    /// monitor as provenance instead of the investigation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor_id: Option<MonitorId>,
     /// Existing graph entities this relates to, for linking and dedup.
     #[serde(default)]
     pub referenced_entities: Vec<EntityId>,
     /// Where to look (specific source adapters, web search, etc.).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_guidance: Option<SourceGuidance>,
+    /// Earliest time the work order may be dispatched to Processors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<DateTime<Utc>>,
+    /// Time after which the result is no longer useful. Processors skip the
+    /// work order if it is dequeued later than this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub effort: WorkOrderEffort,
+    #[serde(default)]
+    pub mode: WorkOrderMode,
+    /// What the session is expected to deliver.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_outputs: Option<ExpectedOutputs>,
+    /// The session completed without meeting `expected_outputs`; the unmet
+    /// criteria are in `summary.contract`.
+    #[serde(default)]
+    pub contract_unmet: bool,
+    /// The work order whose Processor session proposed this one, for work
+    /// orders that started as proposals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposed_by: Option<WorkOrderId>,
+    /// Why the proposing Processor thinks the lead is worth following.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
     /// Which processor handled this work order.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub processor_id: Option<String>,
@@ -92,7 +410,32 @@ pub struct WorkOrder {
     /// Number of claims the Processor produced while processing this work order.
     #[serde(default)]
     pub claims_produced_count: i32,
+    /// Times the work order was redelivered after a Processor died mid-session.
+    #[serde(default)]
+    pub retry_count: i32,
+    /// Bumped each time the work order is re-enqueued (e.g. reprioritized).
+    /// Processors skip messages from earlier deliveries.
+    #[serde(default)]
+    pub delivery: i32,
+    /// Why processing failed, when it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    /// Outcome of the Processor session (kind, final text, turn and tool call counts).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Value>,
+    /// Diagnostics for a failed Processor session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_detail: Option<FailureDetail>,
+    /// Fetches and searches the Processor session made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footprint: Option<OutboundFootprint>,
+    /// Hash of the Processor prompt the last session ran with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_hash: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// When a Processor last started working this order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<DateTime<Utc>>,
 }
@@ -109,15 +452,48 @@ impl WorkOrder {
             objective,
             status: WorkOrderStatus::Queued,
             priority,
+            kind: WorkOrderKind::default(),
+            source_document_id: None,
+            monitor_id: None,
             referenced_entities: Vec::new(),
             source_guidance: None,
+            not_before: None,
+            deadline: None,
+            effort: WorkOrderEffort::default(),
+            mode: WorkOrderMode::default(),
+            expected_outputs: None,
+            contract_unmet: false,
+            proposed_by: None,
+            rationale: None,
             processor_id: None,
             cycle: 0,
             claims_produced_count: 0,
+            retry_count: 0,
+            delivery: 0,
+            failure_reason: None,
+            summary: None,
+            failure_detail: None,
+            footprint: None,
+            prompt_hash: None,
             created_at: Utc::now(),
+            started_at: None,
             completed_at: None,
         }
     }
+
+    /// The expected-output criteria its session missed, from the summary.
+    /// Empty unless `contract_unmet`.
+    pub fn unmet_outputs(&self) -> Vec<UnmetOutput> {
+        if !self.contract_unmet {
+            return Vec::new();
+        }
+        self.summary
+            .as_ref()
+            .and_then(|s| s.get("contract"))
+            .and_then(|c| c.get("unmet"))
+            .and_then(|u| serde_json::from_value(u.clone()).ok())
+            .unwrap_or_default()
+    }
 }
 
 /// Redis stream message payload for work order dispatch.
@@ -126,9 +502,33 @@ pub struct WorkOrderMessage {
     pub work_order_id: WorkOrderId,
     pub investigation_id: InvestigationId,
     pub objective: String,
+    #[serde(default)]
+    pub kind: WorkOrderKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_document_id: Option<SourceDocumentId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor_id: Option<MonitorId>,
     pub referenced_entities: Vec<EntityId>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_guidance: Option<SourceGuidance>,
+    /// When set in the future, the queue holds the message until this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub effort: WorkOrderEffort,
+    #[serde(default)]
+    pub mode: WorkOrderMode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_outputs: Option<ExpectedOutputs>,
+    /// The investigation's correlation ID, so Processors can tag their logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// `WorkOrder::delivery` when enqueued. A message older than the stored
+    /// delivery has been superseded and is acked without processing.
+    #[serde(default)]
+    pub delivery: i32,
 }
 
 impl From<&WorkOrder> for WorkOrderMessage {
@@ -137,8 +537,304 @@ impl From<&WorkOrder> for WorkOrderMessage {
             work_order_id: wo.id,
             investigation_id: wo.investigation_id,
             objective: wo.objective.clone(),
+            kind: wo.kind,
+            source_document_id: wo.source_document_id,
+            monitor_id: wo.monitor_id,
             referenced_entities: wo.referenced_entities.clone(),
             source_guidance: wo.source_guidance.clone(),
+            not_before: wo.not_before,
+            deadline: wo.deadline,
+            effort: wo.effort,
+            mode: wo.mode,
+            expected_outputs: wo.expected_outputs.clone(),
+            correlation_id: None,
+            delivery: wo.delivery,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exhaustive: adding a variant fails to compile here until `ALL` and the
+    /// string mappings are updated alongside it.
+    fn status_index(status: WorkOrderStatus) -> usize {
+        match status {
+            WorkOrderStatus::Scheduled => 0,
+            WorkOrderStatus::Queued => 1,
+            WorkOrderStatus::Processing => 2,
+            WorkOrderStatus::Completed => 3,
+            WorkOrderStatus::Failed => 4,
+            WorkOrderStatus::Proposed => 5,
+            WorkOrderStatus::Dismissed => 6,
+        }
+    }
+
+    fn priority_index(priority: WorkOrderPriority) -> usize {
+        match priority {
+            WorkOrderPriority::High => 0,
+            WorkOrderPriority::Normal => 1,
+            WorkOrderPriority::Low => 2,
+        }
+    }
+
+    fn failure_category_index(category: FailureCategory) -> usize {
+        match category {
+            FailureCategory::LlmError => 0,
+            FailureCategory::DependencyUnavailable => 1,
+            FailureCategory::ContextExceeded => 2,
+            FailureCategory::NoProgress => 3,
+            FailureCategory::DeadlineExpired => 4,
+        }
+    }
+
+    fn effort_index(effort: WorkOrderEffort) -> usize {
+        match effort {
+            WorkOrderEffort::Quick => 0,
+            WorkOrderEffort::Standard => 1,
+            WorkOrderEffort::Thorough => 2,
         }
     }
+
+    fn mode_index(mode: WorkOrderMode) -> usize {
+        match mode {
+            WorkOrderMode::Focused => 0,
+            WorkOrderMode::Exploratory => 1,
+        }
+    }
+
+    fn kind_index(kind: WorkOrderKind) -> usize {
+        match kind {
+            WorkOrderKind::Research => 0,
+            WorkOrderKind::IngestDocument => 1,
+            WorkOrderKind::ProfileEntity => 2,
+        }
+    }
+
+    #[test]
+    fn test_status_mappings_in_sync() {
+        for (i, status) in WorkOrderStatus::ALL.into_iter().enumerate() {
+            assert_eq!(status_index(status), i);
+            assert_eq!(status.as_db_str().parse::<WorkOrderStatus>(), Ok(status));
+            assert_eq!(WorkOrderStatus::try_from(status.as_db_str()), Ok(status));
+
+            let json = serde_json::to_value(status).unwrap();
+            assert_eq!(json, status.as_db_str());
+            assert_eq!(
+                serde_json::from_value::<WorkOrderStatus>(json).unwrap(),
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn test_priority_mappings_in_sync() {
+        for (i, priority) in WorkOrderPriority::ALL.into_iter().enumerate() {
+            assert_eq!(priority_index(priority), i);
+            assert_eq!(priority.as_str().parse::<WorkOrderPriority>(), Ok(priority));
+            assert_eq!(
+                priority.as_redis_stream(),
+                format!("workorders:{}", priority.as_str())
+            );
+            assert_eq!(
+                WorkOrderPriority::from_db_int(priority.as_db_int()),
+                Some(priority)
+            );
+
+            let json = serde_json::to_value(priority).unwrap();
+            assert_eq!(json, priority.as_str());
+            assert_eq!(
+                serde_json::from_value::<WorkOrderPriority>(json).unwrap(),
+                priority
+            );
+        }
+    }
+
+    #[test]
+    fn test_failure_category_mappings_in_sync() {
+        for (i, category) in FailureCategory::ALL.into_iter().enumerate() {
+            assert_eq!(failure_category_index(category), i);
+            assert_eq!(category.as_str().parse::<FailureCategory>(), Ok(category));
+
+            let json = serde_json::to_value(category).unwrap();
+            assert_eq!(json, category.as_str());
+            assert_eq!(
+                serde_json::from_value::<FailureCategory>(json).unwrap(),
+                category
+            );
+        }
+    }
+
+    #[test]
+    fn test_effort_mappings_in_sync() {
+        for (i, effort) in WorkOrderEffort::ALL.into_iter().enumerate() {
+            assert_eq!(effort_index(effort), i);
+            assert_eq!(effort.as_str().parse::<WorkOrderEffort>(), Ok(effort));
+
+            let json = serde_json::to_value(effort).unwrap();
+            assert_eq!(json, effort.as_str());
+            assert_eq!(
+                serde_json::from_value::<WorkOrderEffort>(json).unwrap(),
+                effort
+            );
+        }
+    }
+
+    #[test]
+    fn test_mode_mappings_in_sync() {
+        for (i, mode) in WorkOrderMode::ALL.into_iter().enumerate() {
+            assert_eq!(mode_index(mode), i);
+            assert_eq!(mode.as_str().parse::<WorkOrderMode>(), Ok(mode));
+
+            let json = serde_json::to_value(mode).unwrap();
+            assert_eq!(json, mode.as_str());
+            assert_eq!(serde_json::from_value::<WorkOrderMode>(json).unwrap(), mode);
+        }
+    }
+
+    #[test]
+    fn test_kind_mappings_in_sync() {
+        for (i, kind) in WorkOrderKind::ALL.into_iter().enumerate() {
+            assert_eq!(kind_index(kind), i);
+            assert_eq!(kind.as_db_str().parse::<WorkOrderKind>(), Ok(kind));
+
+            let json = serde_json::to_value(kind).unwrap();
+            assert_eq!(json, kind.as_db_str());
+            assert_eq!(serde_json::from_value::<WorkOrderKind>(json).unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn test_message_kind() {
+        let mut wo = WorkOrder::new(
+            InvestigationId::new(),
+            "Extract the newsletter".into(),
+            WorkOrderPriority::Low,
+        );
+        let mut json = serde_json::to_value(WorkOrderMessage::from(&wo)).unwrap();
+        assert!(json.get("source_document_id").is_none());
+
+        // Messages enqueued before work order kinds existed are research.
+        json.as_object_mut().unwrap().remove("kind");
+        let old: WorkOrderMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(old.kind, WorkOrderKind::Research);
+
+        let document_id = SourceDocumentId::new();
+        wo.kind = WorkOrderKind::IngestDocument;
+        wo.source_document_id = Some(document_id);
+        let json = serde_json::to_value(WorkOrderMessage::from(&wo)).unwrap();
+        assert_eq!(json["kind"], "ingest_document");
+        let parsed: WorkOrderMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.kind, WorkOrderKind::IngestDocument);
+        assert_eq!(parsed.source_document_id, Some(document_id));
+    }
+
+    #[test]
+    fn test_message_deadline_and_effort() {
+        let mut wo = WorkOrder::new(
+            InvestigationId::new(),
+            "Find the vessel's owner".into(),
+            WorkOrderPriority::Normal,
+        );
+        let json = serde_json::to_value(WorkOrderMessage::from(&wo)).unwrap();
+        assert!(json.get("deadline").is_none());
+
+        // Messages enqueued before deadlines existed still parse.
+        let mut old = json.clone();
+        old.as_object_mut().unwrap().remove("effort");
+        old.as_object_mut().unwrap().remove("mode");
+        let old: WorkOrderMessage = serde_json::from_value(old).unwrap();
+        assert_eq!(old.deadline, None);
+        assert_eq!(old.effort, WorkOrderEffort::Standard);
+        assert_eq!(old.mode, WorkOrderMode::Focused);
+
+        let deadline = "2026-10-16T17:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        wo.deadline = Some(deadline);
+        wo.effort = WorkOrderEffort::Quick;
+        wo.mode = WorkOrderMode::Exploratory;
+        let json = serde_json::to_value(WorkOrderMessage::from(&wo)).unwrap();
+        assert_eq!(json["effort"], "quick");
+        assert_eq!(json["mode"], "exploratory");
+        let parsed: WorkOrderMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.deadline, Some(deadline));
+        assert_eq!(parsed.effort, WorkOrderEffort::Quick);
+        assert_eq!(parsed.mode, WorkOrderMode::Exploratory);
+    }
+
+    #[test]
+    fn test_message_correlation_id() {
+        let wo = WorkOrder::new(
+            InvestigationId::new(),
+            "Find the vessel's owner".into(),
+            WorkOrderPriority::Normal,
+        );
+        let mut msg = WorkOrderMessage::from(&wo);
+        let json = serde_json::to_value(&msg).unwrap();
+        assert!(json.get("correlation_id").is_none());
+
+        // Messages enqueued before correlation IDs existed still parse.
+        let old: WorkOrderMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(old.correlation_id, None);
+
+        msg.correlation_id = Some("CASE-2291".into());
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["correlation_id"], "CASE-2291");
+        let parsed: WorkOrderMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.correlation_id.as_deref(), Some("CASE-2291"));
+    }
+
+    #[test]
+    fn test_message_expected_outputs() {
+        let mut wo = WorkOrder::new(
+            InvestigationId::new(),
+            "Find the vessel's owner".into(),
+            WorkOrderPriority::Normal,
+        );
+        let json = serde_json::to_value(WorkOrderMessage::from(&wo)).unwrap();
+        assert!(json.get("expected_outputs").is_none());
+
+        wo.expected_outputs = Some(ExpectedOutputs {
+            min_claims: Some(3),
+            require_entities: vec!["Crius".into()],
+            require_source_count: None,
+        });
+        let json = serde_json::to_value(WorkOrderMessage::from(&wo)).unwrap();
+        assert_eq!(
+            json["expected_outputs"],
+            serde_json::json!({"min_claims": 3, "require_entities": ["Crius"]})
+        );
+        let parsed: WorkOrderMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.expected_outputs, wo.expected_outputs);
+
+        let unmet = UnmetOutput::MinClaims {
+            expected: 3,
+            delivered: 0,
+        };
+        assert_eq!(
+            serde_json::to_value(&unmet).unwrap(),
+            serde_json::json!({"criterion": "min_claims", "expected": 3, "delivered": 0})
+        );
+    }
+
+    #[test]
+    fn test_priority_ordering() {
+        use WorkOrderPriority::*;
+        assert!(High.is_above(Normal));
+        assert!(Normal.is_above(Low));
+        assert!(!Normal.is_above(Normal));
+        assert!(!Low.is_above(High));
+    }
+
+    #[test]
+    fn test_unknown_values_list_valid_ones() {
+        let err = "urgent".parse::<WorkOrderPriority>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid work order priority 'urgent' (expected one of: high, normal, low)"
+        );
+        assert!("High".parse::<WorkOrderPriority>().is_err());
+        assert!("done".parse::<WorkOrderStatus>().is_err());
+        assert_eq!(WorkOrderPriority::from_db_int(7), None);
+    }
 }
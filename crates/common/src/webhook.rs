@@ -0,0 +1,197 @@
+//! Signed webhook deliveries between AutOSINT and external providers.
+//!
+//! A delivery carries two headers: [`TIMESTAMP_HEADER`], the Unix time it was
+//! sent, and [`SIGNATURE_HEADER`], `sha256=` followed by the hex HMAC-SHA256
+//! of `"{timestamp}.{body}"` under a secret both sides hold. Signing the
+//! timestamp with the body lets a receiver refuse old deliveries replayed
+//! outside its tolerance window; refusing repeats within the window is up to
+//! the receiver (single-use callback tokens, for enrichment callbacks).
+//!
+//! Callback tokens are random and only their SHA-256 is stored, so a leaked
+//! table doesn't hand out callback URLs.
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Unix time, in seconds, the delivery was sent.
+pub const TIMESTAMP_HEADER: &str = "x-autosint-timestamp";
+/// `sha256=<hex HMAC-SHA256>` of the timestamp and body.
+pub const SIGNATURE_HEADER: &str = "x-autosint-signature";
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum WebhookError {
+    #[error("Missing or malformed signature headers")]
+    Malformed,
+
+    #[error(
+        "Signature timestamp is {age_seconds}s from now, outside the {tolerance_seconds}s window"
+    )]
+    Stale {
+        age_seconds: i64,
+        tolerance_seconds: u64,
+    },
+
+    #[error("Signature does not match")]
+    BadSignature,
+}
+
+fn mac(secret: &[u8], timestamp: i64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// The [`SIGNATURE_HEADER`] value for `body` sent at `timestamp`.
+pub fn sign(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    format!(
+        "{}{}",
+        SIGNATURE_PREFIX,
+        to_hex(&mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+/// Check a delivery's headers against its body. `timestamp` is the raw
+/// [`TIMESTAMP_HEADER`] value; it must lie within `tolerance_seconds` of
+/// `now` (Unix seconds) either way. The signature is compared in constant
+/// time.
+pub fn verify(
+    secret: &[u8],
+    timestamp: &str,
+    signature: &str,
+    body: &[u8],
+    now: i64,
+    tolerance_seconds: u64,
+) -> Result<(), WebhookError> {
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| WebhookError::Malformed)?;
+    let tag = signature
+        .trim()
+        .strip_prefix(SIGNATURE_PREFIX)
+        .and_then(from_hex)
+        .ok_or(WebhookError::Malformed)?;
+
+    let age_seconds = now.saturating_sub(timestamp);
+    if age_seconds.unsigned_abs() > tolerance_seconds {
+        return Err(WebhookError::Stale {
+            age_seconds,
+            tolerance_seconds,
+        });
+    }
+    mac(secret, timestamp, body)
+        .verify_slice(&tag)
+        .map_err(|_| WebhookError::BadSignature)
+}
+
+/// A new single-use callback token: 32 random bytes, hex-encoded.
+pub fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// What is stored for `token` and looked up by: its hex SHA-256.
+pub fn token_hash(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"provider-secret";
+    const NOW: i64 = 1_790_000_000;
+
+    #[test]
+    fn test_signature_round_trip() {
+        let body = br#"{"status":"completed"}"#;
+        let signature = sign(SECRET, NOW, body);
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(
+            verify(SECRET, &NOW.to_string(), &signature, body, NOW + 10, 300),
+            Ok(())
+        );
+        // Clock skew either way is tolerated within the window.
+        assert_eq!(
+            verify(SECRET, &NOW.to_string(), &signature, body, NOW - 10, 300),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_tampered_deliveries_rejected() {
+        let body = br#"{"status":"completed"}"#;
+        let signature = sign(SECRET, NOW, body);
+        let timestamp = NOW.to_string();
+
+        assert_eq!(
+            verify(SECRET, &timestamp, &signature, b"{}", NOW, 300),
+            Err(WebhookError::BadSignature)
+        );
+        assert_eq!(
+            verify(b"other-secret", &timestamp, &signature, body, NOW, 300),
+            Err(WebhookError::BadSignature)
+        );
+        // The timestamp is signed too: moving it forward breaks the signature.
+        assert_eq!(
+            verify(SECRET, &(NOW + 1).to_string(), &signature, body, NOW, 300),
+            Err(WebhookError::BadSignature)
+        );
+        assert_eq!(
+            verify(SECRET, &timestamp, &signature, body, NOW + 301, 300),
+            Err(WebhookError::Stale {
+                age_seconds: 301,
+                tolerance_seconds: 300
+            })
+        );
+        for (timestamp, signature) in [
+            ("yesterday", signature.as_str()),
+            (timestamp.as_str(), "md5=abcd"),
+            (timestamp.as_str(), "sha256=xyz"),
+            (timestamp.as_str(), "sha256=abc"),
+            (timestamp.as_str(), ""),
+        ] {
+            assert_eq!(
+                verify(SECRET, timestamp, signature, body, NOW, 300),
+                Err(WebhookError::Malformed),
+                "{} {}",
+                timestamp,
+                signature
+            );
+        }
+    }
+
+    #[test]
+    fn test_tokens_random_and_hashed() {
+        let token = new_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, new_token());
+        assert_eq!(token_hash(&token), token_hash(&token));
+        assert_ne!(token_hash(&token), token);
+        assert_eq!(
+            token_hash("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,20 +1,15 @@
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-use autosint_common::config::{
-    DedupConfig, LlmRoleConfig, RetryConfig, SafetyLimits, ToolResultLimits,
-};
+use autosint_common::config::SafetyLimits;
 use autosint_common::ids::InvestigationId;
-use serde_json::Value;
+use tracing::Instrument;
 
-use crate::embeddings::EmbeddingClient;
-use crate::graph::GraphClient;
-use crate::llm::session::{run_session, SessionConfig, SessionResult};
-use crate::llm::LlmClient;
-use crate::queue::QueueClient;
-use crate::store::StoreClient;
-use crate::tools::handlers::register_analyst_tools;
-use crate::tools::{SessionCounters, ToolHandlerContext, ToolRegistry};
+use crate::llm::loop_guard::LoopLimits;
+use crate::llm::session::{run_session, LiveSessionStats, SessionConfig, SessionResult};
+use crate::llm::{LlmCaller, LlmFactory};
+use crate::tools::handlers::{register_analyst_tools, ENRICHMENT_TOOLS, SNAPSHOT_WITHHELD_TOOLS};
+use crate::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
 
 /// Outcome of an Analyst session, determined by which tools were called.
 pub enum AnalystOutcome {
@@ -37,68 +32,109 @@ pub struct AnalystSessionResult {
 /// An Analyst session — queries the knowledge graph, identifies gaps,
 /// creates work orders or produces an assessment.
 pub struct AnalystSession {
-    llm: Arc<LlmClient>,
+    llm: Arc<dyn LlmCaller>,
     system_prompt: String,
     tool_registry: ToolRegistry,
     session_config: SessionConfig,
+    /// Span the session runs in, tagged with the investigation and correlation IDs.
+    span: tracing::Span,
 }
 
 impl AnalystSession {
-    /// Create a new Analyst session.
-    #[allow(clippy::too_many_arguments)]
+    /// Create a new Analyst session for `investigation_id`, its model built
+    /// by `llm`. `scope` carries the cycle, correlation ID and
+    /// per-investigation state; the session fills in its turn, time and work
+    /// order limits.
     pub fn new(
-        llm_config: &LlmRoleConfig,
-        retry_config: &RetryConfig,
+        llm: &LlmFactory,
         safety_limits: &SafetyLimits,
-        graph: Arc<GraphClient>,
-        embedding_client: Option<Arc<EmbeddingClient>>,
-        store: Arc<StoreClient>,
-        queue: Arc<QueueClient>,
-        fetch_base_url: String,
+        services: SharedServices,
         system_prompt: String,
-        tool_schemas: &std::collections::HashMap<String, Value>,
-        tool_result_limits: ToolResultLimits,
-        dedup_config: DedupConfig,
         investigation_id: InvestigationId,
-        investigation_cycle: i32,
+        scope: SessionScope,
     ) -> Result<Self, String> {
-        let llm = LlmClient::new(llm_config.clone(), retry_config.clone())
-            .ok_or_else(|| "Failed to create LLM client — API key not set".to_string())?;
-
-        let context = ToolHandlerContext {
-            graph,
-            embedding_client,
-            fetch_base_url,
-            http: reqwest::Client::new(),
-            tool_result_limits,
-            dedup_config,
-            session_counters: SessionCounters::default(),
-            store: Some(store),
-            queue: Some(queue),
-            investigation_id: Some(investigation_id),
-            investigation_cycle: Some(investigation_cycle),
-            max_work_orders_per_cycle: Some(safety_limits.max_work_orders_per_cycle),
-        };
+        let llm = llm()?;
+
+        let live_stats = Arc::new(LiveSessionStats::default());
+        let max_turns = safety_limits.max_turns_per_analyst_session;
+        let max_duration =
+            std::time::Duration::from_secs(safety_limits.max_seconds_per_analyst_session);
+        let max_result_tokens = services.tool_result_limits.max_result_tokens;
+
+        let span = tracing::info_span!(
+            "analyst_session",
+            investigation_id = %investigation_id,
+            correlation_id = scope.correlation_id.as_deref(),
+            cycle = tracing::field::Empty,
+        );
+        if let Some(cycle) = scope.investigation_cycle {
+            span.record("cycle", cycle);
+        }
+
+        let tool_schemas = Arc::clone(&services.tool_schemas);
+        let enrichment_enabled = services.enrichment.is_some();
+        let snapshot = scope.as_of.is_some();
+        let actor = crate::audit::analyst_actor(investigation_id, scope.investigation_cycle);
+        let context = ToolHandlerContext::new(
+            services,
+            SessionScope {
+                investigation_id: Some(investigation_id),
+                actor,
+                live_stats: Arc::clone(&live_stats),
+                max_turns,
+                max_session_duration: Some(max_duration),
+                max_cycles_per_investigation: Some(safety_limits.max_cycles_per_investigation),
+                max_work_orders_per_cycle: Some(safety_limits.max_work_orders_per_cycle),
+                ..scope
+            },
+        );
 
         let mut tool_registry = ToolRegistry::new(context);
         register_analyst_tools(&mut tool_registry);
-        tool_registry.load_definitions(tool_schemas, "analyst")?;
+        tool_registry.load_definitions(&tool_schemas, "analyst")?;
+        if !enrichment_enabled {
+            tool_registry.withhold_tools(ENRICHMENT_TOOLS);
+        }
+        if snapshot {
+            tool_registry.withhold_tools(SNAPSHOT_WITHHELD_TOOLS);
+        }
 
         let session_config = SessionConfig {
-            max_turns: safety_limits.max_turns_per_analyst_session,
+            max_turns,
             max_consecutive_malformed: safety_limits.max_consecutive_malformed_tool_calls,
+            max_duration: Some(max_duration),
+            live_stats: Some(live_stats),
+            max_tool_result_tokens: Some(max_result_tokens),
+            loop_limits: Some(LoopLimits {
+                max_repeats: safety_limits.max_repeated_tool_calls,
+                max_nudges: safety_limits.max_tool_loop_nudges,
+            }),
+            context_budget_fraction: Some(safety_limits.context_budget_fraction),
         };
 
         Ok(Self {
-            llm: Arc::new(llm),
+            llm,
             system_prompt,
             tool_registry,
             session_config,
+            span,
         })
     }
 
+    /// Restrict the session to the named tools (see `ToolRegistry::retain_tools`).
+    pub fn with_tools(mut self, allowed: &[String]) -> Self {
+        self.tool_registry.retain_tools(allowed);
+        self
+    }
+
     /// Run the Analyst session with the investigation prompt.
     pub async fn run(&self, investigation_prompt: &str) -> AnalystSessionResult {
+        self.run_inner(investigation_prompt)
+            .instrument(self.span.clone())
+            .await
+    }
+
+    async fn run_inner(&self, investigation_prompt: &str) -> AnalystSessionResult {
         let start = std::time::Instant::now();
 
         let executor = self.tool_registry.as_executor();
@@ -132,9 +168,18 @@ impl AnalystSession {
             SessionResult::MaxTurnsReached { .. } => AnalystOutcome::Failed {
                 error: "Max turns reached".to_string(),
             },
+            SessionResult::TimeLimitReached { .. } => AnalystOutcome::Failed {
+                error: "Session time limit reached".to_string(),
+            },
             SessionResult::MalformedToolCallLimit { .. } => AnalystOutcome::Failed {
                 error: "Malformed tool call limit reached".to_string(),
             },
+            SessionResult::ToolLoopLimit { .. } => AnalystOutcome::Failed {
+                error: "Stuck repeating the same tool call".to_string(),
+            },
+            SessionResult::ContextLimitReached { .. } => AnalystOutcome::Failed {
+                error: "Context budget exhausted".to_string(),
+            },
             SessionResult::Completed { .. } => {
                 if assessment_produced {
                     AnalystOutcome::AssessmentProduced
@@ -187,8 +232,9 @@ pub fn force_final_prompt(base_prompt: &str) -> String {
         **CRITICAL: This is your FINAL cycle.** You MUST produce an assessment now using the \
         `produce_assessment` tool. You cannot create more work orders. Synthesize everything you \
         know, clearly state what remains unknown, and produce the best assessment possible with \
-        the available information. An honest assessment noting gaps and uncertainties is far more \
-        valuable than no assessment at all.",
+        the available information. Consult your scratchpad (`read_scratchpad`) for the hypotheses \
+        and open questions you noted in earlier cycles. An honest assessment noting gaps and \
+        uncertainties is far more valuable than no assessment at all.",
         base_prompt
     )
 }
@@ -0,0 +1,341 @@
+//! "What changed" between an assessment and the one it supersedes: key
+//! judgments (the competing hypotheses) are matched across the two and each
+//! is classed as unchanged, revised, new or dropped.
+
+use serde_json::Value;
+
+use autosint_common::config::AssessmentDiffConfig;
+use autosint_common::types::{Assessment, AssessmentDiff, JudgmentChange, JudgmentMatching};
+
+use crate::embeddings::EmbeddingClient;
+
+/// An assessment's key judgments, in order.
+pub fn judgments(content: &Value) -> Vec<Value> {
+    content
+        .get("competing_hypotheses")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Diff `current` against the assessment it supersedes. Judgments are
+/// matched by embedding similarity when `embedding` is available, otherwise
+/// (or if embedding fails) by fuzzy string similarity.
+pub async fn diff_assessments(
+    previous: &Assessment,
+    current: &Assessment,
+    embedding: Option<&EmbeddingClient>,
+    config: &AssessmentDiffConfig,
+) -> AssessmentDiff {
+    let before = judgments(&previous.content);
+    let after = judgments(&current.content);
+
+    let embedded = match embedding {
+        Some(client) => embedding_similarities(client, &before, &after).await,
+        None => None,
+    };
+    let (matched_by, similarity, threshold) = match embedded {
+        Some(similarity) => (
+            JudgmentMatching::Embedding,
+            similarity,
+            config.similarity_threshold,
+        ),
+        None => (
+            JudgmentMatching::Fuzzy,
+            fuzzy_similarities(&before, &after),
+            config.fuzzy_threshold,
+        ),
+    };
+
+    AssessmentDiff {
+        previous_assessment_id: previous.id,
+        previous_confidence: previous.confidence.clone(),
+        matched_by,
+        judgments: diff_judgments(&before, &after, &similarity, threshold),
+    }
+}
+
+/// Classify judgments given `similarity[i][j]`, the similarity of
+/// `previous[i]` and `current[j]`. Pairs at or above `threshold` are matched
+/// most similar first, each judgment at most once. A matched pair is
+/// unchanged if its text (ignoring case and spacing) and probability (to the
+/// percent) are the same, otherwise revised. Returns the current judgments in
+/// order, then the dropped ones in their earlier order.
+pub fn diff_judgments(
+    previous: &[Value],
+    current: &[Value],
+    similarity: &[Vec<f64>],
+    threshold: f64,
+) -> Vec<JudgmentChange> {
+    let mut pairs: Vec<(f64, usize, usize)> = Vec::new();
+    for (i, row) in similarity.iter().enumerate().take(previous.len()) {
+        for (j, &score) in row.iter().enumerate().take(current.len()) {
+            if score >= threshold {
+                pairs.push((score, i, j));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+    let mut previous_match: Vec<Option<usize>> = vec![None; previous.len()];
+    let mut current_match: Vec<Option<(usize, f64)>> = vec![None; current.len()];
+    for (score, i, j) in pairs {
+        if previous_match[i].is_none() && current_match[j].is_none() {
+            previous_match[i] = Some(j);
+            current_match[j] = Some((i, score));
+        }
+    }
+
+    let mut changes: Vec<JudgmentChange> = current
+        .iter()
+        .zip(&current_match)
+        .map(|(judgment, matched)| match *matched {
+            Some((i, _)) if same_judgment(&previous[i], judgment) => JudgmentChange::Unchanged {
+                judgment: judgment.clone(),
+            },
+            Some((i, similarity)) => JudgmentChange::Revised {
+                previous: previous[i].clone(),
+                current: judgment.clone(),
+                similarity,
+            },
+            None => JudgmentChange::New {
+                judgment: judgment.clone(),
+            },
+        })
+        .collect();
+    changes.extend(
+        previous
+            .iter()
+            .zip(&previous_match)
+            .filter(|(_, matched)| matched.is_none())
+            .map(|(judgment, _)| JudgmentChange::Dropped {
+                judgment: judgment.clone(),
+            }),
+    );
+    changes
+}
+
+/// A judgment's statement, for matching.
+fn judgment_text(judgment: &Value) -> String {
+    let text = match judgment.get("hypothesis") {
+        Some(Value::String(s)) => s.as_str(),
+        _ => "",
+    };
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn same_judgment(a: &Value, b: &Value) -> bool {
+    let percent = |j: &Value| {
+        j.get("probability")
+            .and_then(Value::as_f64)
+            .map(|p| (p * 100.0).round() as i64)
+    };
+    judgment_text(a) == judgment_text(b) && percent(a) == percent(b)
+}
+
+/// Jaro-Winkler similarity of every pair of judgment texts.
+pub fn fuzzy_similarities(previous: &[Value], current: &[Value]) -> Vec<Vec<f64>> {
+    let current: Vec<String> = current.iter().map(judgment_text).collect();
+    previous
+        .iter()
+        .map(|p| {
+            let p = judgment_text(p);
+            current
+                .iter()
+                .map(|c| strsim::jaro_winkler(&p, c))
+                .collect()
+        })
+        .collect()
+}
+
+/// Cosine similarity of every pair of judgment embeddings. `None` if
+/// embedding fails.
+async fn embedding_similarities(
+    client: &EmbeddingClient,
+    previous: &[Value],
+    current: &[Value],
+) -> Option<Vec<Vec<f64>>> {
+    if previous.is_empty() || current.is_empty() {
+        return Some(vec![Vec::new(); previous.len()]);
+    }
+    let texts: Vec<String> = previous
+        .iter()
+        .chain(current)
+        .map(|j| match j.get("hypothesis") {
+            Some(Value::String(s)) => s.clone(),
+            _ => String::new(),
+        })
+        .collect();
+    let vectors = match client.embed_batch(&texts).await {
+        Ok(vectors) if vectors.len() == texts.len() => vectors,
+        Ok(vectors) => {
+            tracing::warn!(
+                expected = texts.len(),
+                got = vectors.len(),
+                "Judgment embedding returned the wrong number of vectors, matching by text"
+            );
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to embed judgments, matching by text");
+            return None;
+        }
+    };
+    let (before, after) = vectors.split_at(previous.len());
+    Some(
+        before
+            .iter()
+            .map(|p| after.iter().map(|c| cosine(p, c)).collect())
+            .collect(),
+    )
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn judgment(text: &str, probability: f64) -> Value {
+        json!({"hypothesis": text, "probability": probability, "reasoning": "..."})
+    }
+
+    #[test]
+    fn test_classifies_each_kind_of_change() {
+        let previous = vec![
+            judgment("Sanctions evasion", 0.6),
+            judgment("Seasonal demand", 0.3),
+            judgment("Reporting error", 0.1),
+        ];
+        let current = vec![
+            judgment("Sanctions evasion via ship-to-ship transfers", 0.7),
+            judgment("seasonal  demand", 0.3),
+            judgment("Stockpiling ahead of tariffs", 0.2),
+        ];
+        let similarity = vec![
+            vec![0.9, 0.2, 0.1],
+            vec![0.1, 1.0, 0.3],
+            vec![0.2, 0.1, 0.4],
+        ];
+
+        let changes = diff_judgments(&previous, &current, &similarity, 0.8);
+        assert_eq!(
+            changes,
+            [
+                JudgmentChange::Revised {
+                    previous: previous[0].clone(),
+                    current: current[0].clone(),
+                    similarity: 0.9,
+                },
+                JudgmentChange::Unchanged {
+                    judgment: current[1].clone(),
+                },
+                JudgmentChange::New {
+                    judgment: current[2].clone(),
+                },
+                JudgmentChange::Dropped {
+                    judgment: previous[2].clone(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reweighting_alone_is_a_revision() {
+        let previous = vec![judgment("Sanctions evasion", 0.6)];
+        let reweighted = vec![judgment("Sanctions evasion", 0.8)];
+        let rounded = vec![judgment("Sanctions evasion", 0.601)];
+
+        assert!(matches!(
+            diff_judgments(&previous, &reweighted, &[vec![1.0]], 0.8)[..],
+            [JudgmentChange::Revised { .. }]
+        ));
+        assert!(matches!(
+            diff_judgments(&previous, &rounded, &[vec![1.0]], 0.8)[..],
+            [JudgmentChange::Unchanged { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_best_pair_matched_first_and_only_once() {
+        // Both current judgments resemble the earlier one; only the closer
+        // one is its revision.
+        let previous = vec![judgment("Port congestion", 0.5)];
+        let current = vec![
+            judgment("Congestion at the port", 0.4),
+            judgment("Port strike", 0.1),
+        ];
+
+        let changes = diff_judgments(&previous, &current, &[vec![0.85, 0.92]], 0.8);
+        assert!(matches!(changes[0], JudgmentChange::New { .. }));
+        assert!(matches!(
+            changes[1],
+            JudgmentChange::Revised { similarity, .. } if similarity == 0.92
+        ));
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_below_threshold_is_new_and_dropped() {
+        let previous = vec![judgment("Port congestion", 0.5)];
+        let current = vec![judgment("Labor dispute", 0.5)];
+
+        let changes = diff_judgments(&previous, &current, &[vec![0.79]], 0.8);
+        assert!(matches!(
+            changes[..],
+            [JudgmentChange::New { .. }, JudgmentChange::Dropped { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_empty_sides() {
+        let some = vec![judgment("Port congestion", 0.5)];
+        assert!(matches!(
+            diff_judgments(&[], &some, &[], 0.8)[..],
+            [JudgmentChange::New { .. }]
+        ));
+        assert!(matches!(
+            diff_judgments(&some, &[], &[Vec::new()], 0.8)[..],
+            [JudgmentChange::Dropped { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_fuzzy_similarities_match_rewordings() {
+        let previous = vec![judgment("The shipments are sanctions evasion", 0.6)];
+        let current = vec![
+            judgment("The shipments are sanctions  evasion.", 0.6),
+            judgment("Seasonal demand", 0.4),
+        ];
+        let similarity = fuzzy_similarities(&previous, &current);
+        assert!(similarity[0][0] > 0.95);
+        assert!(similarity[0][1] < 0.85);
+
+        let changes = diff_judgments(&previous, &current, &similarity, 0.85);
+        assert!(matches!(changes[0], JudgmentChange::Revised { .. }));
+        assert!(matches!(changes[1], JudgmentChange::New { .. }));
+    }
+
+    #[test]
+    fn test_judgments_of_content() {
+        assert_eq!(judgments(&json!("free text")), Vec::<Value>::new());
+        assert_eq!(
+            judgments(&json!({"competing_hypotheses": [judgment("A", 1.0)]})).len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use chrono::{Datelike, Utc};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::JoinHandle;
+
+use autosint_common::config::AuditConfig;
+use autosint_common::ids::InvestigationId;
+use autosint_common::types::{AuditActor, AuditRecord};
+
+use crate::store::StoreClient;
+use crate::supervisor;
+use crate::tools::SessionScope;
+
+pub use autosint_common::types::{AuditActorType, AuditEntry};
+
+/// Longest string kept in a digest, in characters.
+const DIGEST_MAX_STRING_CHARS: usize = 200;
+/// Most array items kept in a digest.
+const DIGEST_MAX_ARRAY_ITEMS: usize = 20;
+
+/// Handle for recording audit entries. Recording never waits: records queue
+/// for a background writer and, with the queue full, are dropped and
+/// counted. The default handle records nothing.
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    tx: Option<mpsc::Sender<AuditRecord>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl AuditLog {
+    /// A handle queueing up to `capacity` records on the returned receiver.
+    pub fn buffered(capacity: usize) -> (Self, mpsc::Receiver<AuditRecord>) {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let log = Self {
+            tx: Some(tx),
+            dropped: Arc::default(),
+        };
+        (log, rx)
+    }
+
+    /// A handle writing to `store` through a supervised writer task, or a
+    /// disabled handle (and no task) when the audit log is off.
+    pub fn spawn(store: Arc<StoreClient>, config: &AuditConfig) -> (Self, Option<JoinHandle<()>>) {
+        if !config.enabled {
+            return (Self::default(), None);
+        }
+        let (log, rx) = Self::buffered(config.buffer_size);
+        let batch_size = config.batch_size.max(1);
+        let handle =
+            supervisor::spawn_supervised("audit_writer", run_writer(store, rx, batch_size));
+        (log, Some(handle))
+    }
+
+    /// Queue `record` for writing.
+    pub fn record(&self, record: AuditRecord) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        match tx.try_send(record) {
+            Ok(()) => {}
+            Err(TrySendError::Full(record)) | Err(TrySendError::Closed(record)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                metrics::counter!("audit.dropped").increment(1);
+                tracing::debug!(operation = %record.operation, "Audit queue full, dropping record");
+            }
+        }
+    }
+
+    /// Records dropped because the queue was full or the writer gone.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Write queued records in batches until every handle is dropped. Each
+/// month's partition is created before the month's first write, along with
+/// the next month's.
+pub async fn run_writer(
+    store: Arc<StoreClient>,
+    mut rx: mpsc::Receiver<AuditRecord>,
+    batch_size: usize,
+) {
+    let mut partitioned_month = None;
+    let mut batch = Vec::with_capacity(batch_size);
+    while rx.recv_many(&mut batch, batch_size).await > 0 {
+        let now = Utc::now();
+        let month = (now.year(), now.month());
+        if partitioned_month != Some(month) {
+            let next = now + chrono::Months::new(1);
+            for at in [now, next] {
+                if let Err(e) = store.ensure_audit_partition(at).await {
+                    tracing::warn!(error = %e, "Failed to create audit log partition");
+                }
+            }
+            partitioned_month = Some(month);
+        }
+
+        match store.insert_audit_records(&batch).await {
+            Ok(()) => {
+                metrics::counter!("audit.written").increment(batch.len() as u64);
+            }
+            Err(e) => {
+                metrics::counter!("audit.write_errors").increment(batch.len() as u64);
+                tracing::warn!(error = %e, records = batch.len(), "Failed to write audit records");
+            }
+        }
+        batch.clear();
+    }
+}
+
+/// How a mutating tool's calls are audited: what it changes, and the field
+/// naming the changed record — looked up in the tool's result, then its
+/// arguments.
+struct AuditedTool {
+    name: &'static str,
+    target_type: &'static str,
+    id_field: Option<&'static str>,
+}
+
+const fn audited(
+    name: &'static str,
+    target_type: &'static str,
+    id_field: Option<&'static str>,
+) -> AuditedTool {
+    AuditedTool {
+        name,
+        target_type,
+        id_field,
+    }
+}
+
+/// Every tool that changes the graph or the store. Read-only tools are not
+/// audited.
+const AUDITED_TOOLS: &[AuditedTool] = &[
+    audited("create_entity", "entity", Some("entity_id")),
+    audited("update_entity", "entity", Some("entity_id")),
+    audited(
+        "update_entity_with_change_claim",
+        "entity",
+        Some("entity_id"),
+    ),
+    audited("merge_entities", "entity", Some("merged_entity_id")),
+    audited("merge_claims", "claim", Some("merged_claim_id")),
+    audited("create_claim", "claim", Some("claim_id")),
+    audited("batch_extract", "claim", None),
+    audited(
+        "create_relationship",
+        "relationship",
+        Some("relationship_id"),
+    ),
+    audited(
+        "update_relationship",
+        "relationship",
+        Some("relationship_id"),
+    ),
+    audited(
+        "reverse_relationship",
+        "relationship",
+        Some("relationship_id"),
+    ),
+    audited("create_work_order", "work_order", Some("work_order_id")),
+    audited(
+        "reprioritize_work_order",
+        "work_order",
+        Some("work_order_id"),
+    ),
+    audited("propose_work_order", "work_order", Some("work_order_id")),
+    audited("approve_proposals", "work_order", None),
+    audited("produce_assessment", "assessment", Some("assessment_id")),
+    audited(
+        "tag_investigation",
+        "investigation",
+        Some("investigation_id"),
+    ),
+    audited("write_scratchpad", "investigation", None),
+    audited(
+        "submit_external_enrichment",
+        "enrichment_request",
+        Some("request_id"),
+    ),
+];
+
+/// Whether calls to `tool` are audited.
+pub fn is_audited_tool(tool: &str) -> bool {
+    AUDITED_TOOLS.iter().any(|t| t.name == tool)
+}
+
+/// The audit record of a successful `tool` call in the session `scope`, or
+/// None for tools that change nothing.
+pub fn tool_record(
+    tool: &str,
+    args: &Value,
+    result: &Value,
+    scope: &SessionScope,
+) -> Option<AuditRecord> {
+    let audited = AUDITED_TOOLS.iter().find(|t| t.name == tool)?;
+    let target_id = audited
+        .id_field
+        .and_then(|field| {
+            [result, args]
+                .into_iter()
+                .find_map(|v| v.get(field).and_then(Value::as_str))
+        })
+        .map(str::to_string)
+        .or_else(|| {
+            (audited.target_type == "investigation")
+                .then(|| scope.investigation_id.map(|id| id.to_string()))
+                .flatten()
+        });
+    Some(AuditRecord {
+        actor: scope.actor.clone(),
+        investigation_id: scope.investigation_id,
+        work_order_id: scope.work_order_id,
+        operation: tool.to_string(),
+        target_type: audited.target_type.to_string(),
+        target_id,
+        digest: digest(args),
+        recorded_at: Utc::now(),
+    })
+}
+
+/// A compact copy of `value` for the audit log: strings cut to 200
+/// characters and arrays to 20 items, with a count of what was left out.
+pub fn digest(value: &Value) -> Value {
+    match value {
+        Value::String(s) if s.chars().count() > DIGEST_MAX_STRING_CHARS => {
+            let kept: String = s.chars().take(DIGEST_MAX_STRING_CHARS).collect();
+            Value::String(format!("{kept}…"))
+        }
+        Value::Array(items) => {
+            let mut kept: Vec<Value> = items
+                .iter()
+                .take(DIGEST_MAX_ARRAY_ITEMS)
+                .map(digest)
+                .collect();
+            if items.len() > DIGEST_MAX_ARRAY_ITEMS {
+                kept.push(Value::String(format!(
+                    "[{} more]",
+                    items.len() - DIGEST_MAX_ARRAY_ITEMS
+                )));
+            }
+            Value::Array(kept)
+        }
+        Value::Object(fields) => {
+            Value::Object(fields.iter().map(|(k, v)| (k.clone(), digest(v))).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// The actor of an Analyst session's tool calls: its investigation and cycle.
+pub fn analyst_actor(investigation_id: InvestigationId, cycle: Option<i32>) -> AuditActor {
+    let actor_id = match cycle {
+        Some(cycle) => format!("{investigation_id}/cycle-{cycle}"),
+        None => investigation_id.to_string(),
+    };
+    AuditActor::new(AuditActorType::AnalystSession, Some(actor_id))
+}
+
+/// The actor of a Processor session's tool calls: the worker running it.
+pub fn processor_actor(consumer_name: &str) -> AuditActor {
+    AuditActor::new(
+        AuditActorType::ProcessorSession,
+        Some(consumer_name.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use autosint_common::ids::WorkOrderId;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_tool_record_attribution() {
+        let investigation_id = InvestigationId::new();
+        let work_order_id = WorkOrderId::new();
+
+        let analyst = SessionScope {
+            investigation_id: Some(investigation_id),
+            investigation_cycle: Some(3),
+            actor: analyst_actor(investigation_id, Some(3)),
+            ..Default::default()
+        };
+        let record = tool_record(
+            "merge_entities",
+            &json!({"keep_entity_id": "a", "merge_entity_id": "b"}),
+            &json!({"merged_entity_id": "a"}),
+            &analyst,
+        )
+        .unwrap();
+        assert_eq!(record.actor.actor_type, AuditActorType::AnalystSession);
+        assert_eq!(
+            record.actor.actor_id.as_deref(),
+            Some(format!("{investigation_id}/cycle-3").as_str())
+        );
+        assert_eq!(record.investigation_id, Some(investigation_id));
+        assert_eq!(record.target_type, "entity");
+        assert_eq!(record.target_id.as_deref(), Some("a"));
+
+        let processor = SessionScope {
+            investigation_id: Some(investigation_id),
+            work_order_id: Some(work_order_id),
+            actor: processor_actor("engine-1:processor-0"),
+            ..Default::default()
+        };
+        let record = tool_record(
+            "create_claim",
+            &json!({"content": "x"}),
+            &json!({"claim_id": "c1"}),
+            &processor,
+        )
+        .unwrap();
+        assert_eq!(record.actor.actor_type, AuditActorType::ProcessorSession);
+        assert_eq!(record.work_order_id, Some(work_order_id));
+        assert_eq!(record.target_id.as_deref(), Some("c1"));
+
+        // The scratchpad belongs to the session's investigation.
+        let record = tool_record("write_scratchpad", &json!({}), &json!({}), &analyst).unwrap();
+        assert_eq!(record.target_id, Some(investigation_id.to_string()));
+
+        assert!(tool_record("get_entity", &json!({}), &json!({}), &analyst).is_none());
+    }
+
+    #[test]
+    fn test_digest_cuts_long_values() {
+        let long = "x".repeat(500);
+        let items: Vec<u32> = (0..30).collect();
+        let d = digest(&json!({"content": long, "ids": items, "n": 1}));
+        assert_eq!(
+            d["content"].as_str().unwrap().chars().count(),
+            DIGEST_MAX_STRING_CHARS + 1
+        );
+        let ids = d["ids"].as_array().unwrap();
+        assert_eq!(ids.len(), DIGEST_MAX_ARRAY_ITEMS + 1);
+        assert_eq!(ids.last().unwrap(), "[10 more]");
+        assert_eq!(d["n"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_and_counts() {
+        let (log, mut rx) = AuditLog::buffered(2);
+        let scope = SessionScope::default();
+        for _ in 0..5 {
+            log.record(tool_record("create_entity", &json!({}), &json!({}), &scope).unwrap());
+        }
+        assert_eq!(log.dropped(), 3);
+
+        let mut received = Vec::new();
+        rx.recv_many(&mut received, 10).await;
+        assert_eq!(received.len(), 2);
+
+        // A disabled handle drops silently.
+        let disabled = AuditLog::default();
+        disabled.record(received.pop().unwrap());
+        assert_eq!(disabled.dropped(), 0);
+    }
+}
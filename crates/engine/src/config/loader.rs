@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use autosint_common::config::SystemConfig;
+use autosint_common::config::{ModelCapabilities, SystemConfig};
 use serde_json::Value;
 
+use super::prompts::{self, PromptSet};
 use super::validation;
+use crate::llm::models::{ModelRegistry, UNKNOWN_MODEL};
+use crate::tools::entity_policy;
 
 /// Complete engine configuration loaded from the config directory.
 #[derive(Clone, Debug)]
@@ -13,10 +16,10 @@ pub struct EngineConfig {
     pub system: SystemConfig,
     /// Tool schemas keyed by "{role}/{tool_name}" (e.g. "analyst/search_entities").
     pub tool_schemas: HashMap<String, Value>,
-    /// Prompt templates keyed by filename stem (e.g. "analyst", "processor").
-    pub prompts: HashMap<String, String>,
-    /// Base config directory path (used for future config reload).
-    #[allow(dead_code)]
+    /// Prompt templates as loaded at startup. Sessions read the live set from
+    /// a `PromptStore`, which `/config/prompts/reload` replaces.
+    pub prompts: PromptSet,
+    /// Base config directory path (used for prompt reloads and seed data).
     pub config_dir: PathBuf,
 }
 
@@ -35,9 +38,9 @@ pub fn load_config(config_dir: &Path) -> Result<EngineConfig, ConfigError> {
     let tool_schemas = load_tool_schemas(&config_dir.join("tools"))?;
 
     // 3. Load prompt templates from config/prompts/*.md
-    let prompts = load_prompts(&config_dir.join("prompts"))?;
+    let prompts = prompts::load_prompts(&config_dir.join("prompts"))?;
 
-    let config = EngineConfig {
+    let mut config = EngineConfig {
         system,
         tool_schemas,
         prompts,
@@ -47,6 +50,12 @@ pub fn load_config(config_dir: &Path) -> Result<EngineConfig, ConfigError> {
     // 4. Validate everything
     validation::validate(&config)?;
 
+    // 5. Show the model the per-kind entity policies up front.
+    describe_entity_kinds(&mut config);
+
+    // 6. Pin each LLM role's model capabilities.
+    resolve_model_capabilities(&mut config);
+
     tracing::info!(
         tool_schemas = config.tool_schemas.len(),
         prompts = config.prompts.len(),
@@ -141,50 +150,64 @@ fn load_tool_schemas(tools_dir: &Path) -> Result<HashMap<String, Value>, ConfigE
     Ok(schemas)
 }
 
-fn load_prompts(prompts_dir: &Path) -> Result<HashMap<String, String>, ConfigError> {
-    let mut prompts = HashMap::new();
-
-    if !prompts_dir.exists() {
-        tracing::warn!(
-            path = %prompts_dir.display(),
-            "Prompts directory does not exist, no prompts loaded"
-        );
-        return Ok(prompts);
+/// Append the `[entity_kinds]` policies to the create_entity description.
+fn describe_entity_kinds(config: &mut EngineConfig) {
+    let Some(note) = entity_policy::describe(&config.system.entity_kinds) else {
+        return;
+    };
+    for (key, schema) in config.tool_schemas.iter_mut() {
+        if !key.ends_with("/create_entity") {
+            continue;
+        }
+        if let Some(Value::String(description)) = schema.get_mut("description") {
+            description.push(' ');
+            description.push_str(&note);
+        }
     }
+}
 
-    let entries = std::fs::read_dir(prompts_dir).map_err(|e| ConfigError::FileRead {
-        path: prompts_dir.to_path_buf(),
-        source: e,
-    })?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| ConfigError::FileRead {
-            path: prompts_dir.to_path_buf(),
-            source: e,
-        })?;
-
-        let path = entry.path();
-        if path
-            .extension()
-            .is_some_and(|ext| ext == "md" || ext == "txt")
-        {
-            let name = path
-                .file_stem()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown")
-                .to_string();
-
-            let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::FileRead {
-                path: path.clone(),
-                source: e,
-            })?;
-
-            tracing::debug!(prompt = %name, "Loaded prompt template");
-            prompts.insert(name, content);
+/// Resolve each role's model against `[llm.models]` and the built-in table.
+/// A model neither knows gets conservative defaults, with a warning.
+fn resolve_model_capabilities(config: &mut EngineConfig) {
+    let registry = ModelRegistry::new(&config.system.llm.models);
+    let llm = &mut config.system.llm;
+    let roles = [
+        ("analyst", Some(&mut llm.analyst)),
+        ("processor", Some(&mut llm.processor)),
+        ("triage", llm.triage.as_mut()),
+    ];
+    for (name, role) in roles {
+        let Some(role) = role else { continue };
+        resolve(&registry, name, &role.model, &mut role.capabilities);
+        if let Some(fallback) = &mut role.fallback {
+            resolve(&registry, name, &fallback.model, &mut fallback.capabilities);
         }
     }
+}
 
-    Ok(prompts)
+/// Fill in `capabilities` of `model` unless set.
+fn resolve(
+    registry: &ModelRegistry,
+    role: &str,
+    model: &str,
+    capabilities: &mut Option<ModelCapabilities>,
+) {
+    if capabilities.is_some() {
+        return;
+    }
+    match registry.lookup(model) {
+        Some((_, found)) => *capabilities = Some(found),
+        None => {
+            tracing::warn!(
+                role,
+                model,
+                context_window = UNKNOWN_MODEL.context_window,
+                max_output_tokens = UNKNOWN_MODEL.max_output_tokens,
+                "Unknown model, assuming a small context window; add it to [llm.models]"
+            );
+            *capabilities = Some(UNKNOWN_MODEL);
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
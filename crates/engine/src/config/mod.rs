@@ -1,4 +1,9 @@
 mod loader;
+mod prompts;
 mod validation;
 
-pub use loader::{load_config, EngineConfig};
+pub use loader::{load_config, ConfigError, EngineConfig};
+pub use prompts::{
+    prompt_hash, Prompt, PromptSet, PromptStore, ANALYST_PROMPT, PROCESSOR_EXPLORATORY_PROMPT,
+    PROCESSOR_PROMPT, REQUIRED_PROMPTS,
+};
@@ -0,0 +1,264 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use sha2::{Digest, Sha256};
+
+use super::loader::ConfigError;
+
+/// Analyst system prompt (`config/prompts/analyst.md`).
+pub const ANALYST_PROMPT: &str = "analyst";
+/// Processor system prompt (`config/prompts/processor.md`).
+pub const PROCESSOR_PROMPT: &str = "processor";
+
+/// Breadth-first Processor prompt for exploratory work orders
+/// (`config/prompts/processor_exploratory.md`). Optional: without it they
+/// run the Processor prompt.
+pub const PROCESSOR_EXPLORATORY_PROMPT: &str = "processor_exploratory";
+
+/// Prompts the engine cannot run without. Loads and reloads missing any fail.
+pub const REQUIRED_PROMPTS: [&str; 2] = [ANALYST_PROMPT, PROCESSOR_PROMPT];
+
+/// A prompt template and the hash of its text.
+#[derive(Clone, Debug)]
+pub struct Prompt {
+    pub text: String,
+    /// Hex SHA-256 of `text`, stamped on the work it produces.
+    pub hash: String,
+}
+
+impl Prompt {
+    pub fn new(text: String) -> Self {
+        let hash = prompt_hash(&text);
+        Self { text, hash }
+    }
+}
+
+/// Hex SHA-256 of a prompt's text.
+pub fn prompt_hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Prompt templates keyed by filename stem (e.g. "analyst", "processor"),
+/// loaded together.
+#[derive(Clone, Debug, Default)]
+pub struct PromptSet {
+    prompts: HashMap<String, Prompt>,
+}
+
+impl PromptSet {
+    pub fn from_texts<K, V>(texts: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            prompts: texts
+                .into_iter()
+                .map(|(name, text)| (name.into(), Prompt::new(text.into())))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prompt> {
+        self.prompts.get(name)
+    }
+
+    /// The named prompt's text, or "" if it isn't loaded.
+    pub fn text(&self, name: &str) -> &str {
+        self.get(name).map(|p| p.text.as_str()).unwrap_or_default()
+    }
+
+    /// Hash of every loaded prompt, by name.
+    pub fn hashes(&self) -> BTreeMap<String, String> {
+        self.prompts
+            .iter()
+            .map(|(name, p)| (name.clone(), p.hash.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.prompts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompts.is_empty()
+    }
+
+    /// Names from `REQUIRED_PROMPTS` this set lacks.
+    pub fn missing_required(&self) -> Vec<&'static str> {
+        REQUIRED_PROMPTS
+            .into_iter()
+            .filter(|name| !self.prompts.contains_key(*name))
+            .collect()
+    }
+}
+
+/// The live prompt set, shared by the Orchestrator, the Processor pool and the
+/// HTTP API. Sessions take a snapshot when they start, so a reload only
+/// affects sessions started after it.
+#[derive(Clone, Debug)]
+pub struct PromptStore {
+    current: Arc<ArcSwap<PromptSet>>,
+}
+
+impl PromptStore {
+    pub fn new(prompts: PromptSet) -> Self {
+        Self {
+            current: Arc::new(ArcSwap::from_pointee(prompts)),
+        }
+    }
+
+    /// The current prompt set. Unaffected by later reloads.
+    pub fn snapshot(&self) -> Arc<PromptSet> {
+        self.current.load_full()
+    }
+
+    /// Replace the prompt set as a whole. Rejected, leaving the current set
+    /// in place, if a required prompt is missing.
+    pub fn swap(&self, prompts: PromptSet) -> Result<Arc<PromptSet>, ConfigError> {
+        check_required(&prompts)?;
+        let prompts = Arc::new(prompts);
+        self.current.store(Arc::clone(&prompts));
+        Ok(prompts)
+    }
+
+    /// Re-read every prompt in `prompts_dir` and swap them in together.
+    pub fn reload(&self, prompts_dir: &Path) -> Result<Arc<PromptSet>, ConfigError> {
+        let prompts = self.swap(load_prompts(prompts_dir)?)?;
+        tracing::info!(hashes = ?prompts.hashes(), "Prompts reloaded");
+        Ok(prompts)
+    }
+}
+
+fn check_required(prompts: &PromptSet) -> Result<(), ConfigError> {
+    let missing = prompts.missing_required();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Validation(format!(
+            "missing required prompts: {}",
+            missing.join(", ")
+        )))
+    }
+}
+
+/// Load prompt templates from `prompts_dir/*.md` and `*.txt`.
+pub(super) fn load_prompts(prompts_dir: &Path) -> Result<PromptSet, ConfigError> {
+    let mut prompts = HashMap::new();
+
+    if !prompts_dir.exists() {
+        tracing::warn!(
+            path = %prompts_dir.display(),
+            "Prompts directory does not exist, no prompts loaded"
+        );
+        return Ok(PromptSet::default());
+    }
+
+    let entries = std::fs::read_dir(prompts_dir).map_err(|e| ConfigError::FileRead {
+        path: prompts_dir.to_path_buf(),
+        source: e,
+    })?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| ConfigError::FileRead {
+            path: prompts_dir.to_path_buf(),
+            source: e,
+        })?;
+
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext == "md" || ext == "txt")
+        {
+            let name = path
+                .file_stem()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::FileRead {
+                path: path.clone(),
+                source: e,
+            })?;
+
+            tracing::debug!(prompt = %name, "Loaded prompt template");
+            prompts.insert(name, content);
+        }
+    }
+
+    Ok(PromptSet::from_texts(prompts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompts(analyst: &str) -> PromptSet {
+        PromptSet::from_texts([
+            (ANALYST_PROMPT, analyst),
+            (PROCESSOR_PROMPT, "You are a Processor."),
+        ])
+    }
+
+    #[test]
+    fn test_prompt_hash() {
+        assert_eq!(
+            prompt_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        let set = prompts("You are the Analyst.");
+        assert_eq!(
+            set.get(ANALYST_PROMPT).unwrap().hash,
+            prompt_hash("You are the Analyst.")
+        );
+        assert_ne!(set.hashes()[ANALYST_PROMPT], set.hashes()[PROCESSOR_PROMPT]);
+    }
+
+    #[test]
+    fn test_swap_leaves_earlier_snapshots() {
+        let store = PromptStore::new(prompts("v1"));
+        let in_flight = store.snapshot();
+
+        store.swap(prompts("v2")).unwrap();
+
+        assert_eq!(in_flight.text(ANALYST_PROMPT), "v1");
+        assert_eq!(store.snapshot().text(ANALYST_PROMPT), "v2");
+        assert_eq!(store.snapshot().hashes()[ANALYST_PROMPT], prompt_hash("v2"));
+    }
+
+    #[test]
+    fn test_swap_rejects_missing_required_prompt() {
+        let store = PromptStore::new(prompts("v1"));
+
+        let err = store
+            .swap(PromptSet::from_texts([(ANALYST_PROMPT, "v2")]))
+            .unwrap_err();
+
+        assert!(err.to_string().contains(PROCESSOR_PROMPT), "{}", err);
+        assert_eq!(store.snapshot().text(ANALYST_PROMPT), "v1");
+    }
+
+    #[test]
+    fn test_reload_reads_directory() {
+        let dir = std::env::temp_dir().join(format!("autosint-prompts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("analyst.md"), "v2").unwrap();
+        let store = PromptStore::new(prompts("v1"));
+
+        // processor.md is missing: the reload is rejected.
+        assert!(store.reload(&dir).is_err());
+        assert_eq!(store.snapshot().text(ANALYST_PROMPT), "v1");
+
+        std::fs::write(dir.join("processor.md"), "You are a Processor.").unwrap();
+        let reloaded = store.reload(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(reloaded.text(ANALYST_PROMPT), "v2");
+        assert_eq!(store.snapshot().len(), 2);
+    }
+}
@@ -1,4 +1,11 @@
+use autosint_common::types::{
+    DomainEvent, IdentifierScheme, MAX_TRUST_LEVEL, MIN_TRUST_LEVEL, TRUST_LEVEL_PROPERTY,
+};
+
 use super::loader::{ConfigError, EngineConfig};
+use crate::stix::SUPPORTED_SDO_TYPES;
+use crate::tools::entity_policy::IDENTIFIER_FIELD_PREFIX;
+use crate::tools::format::FORMATTED_TOOLS;
 
 /// Validate the complete engine configuration.
 ///
@@ -14,6 +21,38 @@ pub fn validate(config: &EngineConfig) -> Result<(), ConfigError> {
     validate_embeddings(config, &mut errors);
     validate_dedup(config, &mut errors);
     validate_retry(config, &mut errors);
+    validate_retention(config, &mut errors);
+    validate_relationship_weights(config, &mut errors);
+    validate_triage(config, &mut errors);
+    validate_entity_shortlist(config, &mut errors);
+    validate_selftest(config, &mut errors);
+    validate_recovery(config, &mut errors);
+    validate_scratchpad(config, &mut errors);
+    validate_work_order_proposals(config, &mut errors);
+    validate_assessment_diff(config, &mut errors);
+    validate_source_trust(config, &mut errors);
+    validate_entity_kinds(config, &mut errors);
+    validate_pii(config, &mut errors);
+    validate_stix(config, &mut errors);
+    validate_graph_writes(config, &mut errors);
+    validate_graph_reads(config, &mut errors);
+    validate_graph_consistency(config, &mut errors);
+    validate_ingest(config, &mut errors);
+    validate_enrichment(config, &mut errors);
+    validate_maintenance(config, &mut errors);
+    validate_metrics(config, &mut errors);
+    validate_audit(config, &mut errors);
+    validate_jobs(config, &mut errors);
+    validate_exploration(config, &mut errors);
+    validate_transfer(config, &mut errors);
+    validate_verification(config, &mut errors);
+    validate_tool_results(config, &mut errors);
+    validate_fetch_replicas(config, &mut errors);
+    validate_telemetry(config, &mut errors);
+    validate_events(config, &mut errors);
+    validate_stub_resolution(config, &mut errors);
+    validate_flags(config, &mut errors);
+    validate_prompts(config, &mut errors);
 
     if errors.is_empty() {
         Ok(())
@@ -22,6 +61,13 @@ pub fn validate(config: &EngineConfig) -> Result<(), ConfigError> {
     }
 }
 
+fn validate_prompts(config: &EngineConfig, errors: &mut Vec<String>) {
+    let missing = config.prompts.missing_required();
+    if !missing.is_empty() {
+        errors.push(format!("missing required prompts: {}", missing.join(", ")));
+    }
+}
+
 fn validate_safety_limits(config: &EngineConfig, errors: &mut Vec<String>) {
     let s = &config.system.safety;
 
@@ -34,6 +80,12 @@ fn validate_safety_limits(config: &EngineConfig, errors: &mut Vec<String>) {
     if s.max_turns_per_processor_session == 0 {
         errors.push("safety.max_turns_per_processor_session must be > 0".into());
     }
+    if s.max_seconds_per_analyst_session == 0 {
+        errors.push("safety.max_seconds_per_analyst_session must be > 0".into());
+    }
+    if s.max_seconds_per_processor_session == 0 {
+        errors.push("safety.max_seconds_per_processor_session must be > 0".into());
+    }
     if s.max_work_orders_per_cycle == 0 {
         errors.push("safety.max_work_orders_per_cycle must be > 0".into());
     }
@@ -46,6 +98,15 @@ fn validate_safety_limits(config: &EngineConfig, errors: &mut Vec<String>) {
     if s.max_consecutive_malformed_tool_calls == 0 {
         errors.push("safety.max_consecutive_malformed_tool_calls must be > 0".into());
     }
+    if s.max_repeated_tool_calls == 0 {
+        errors.push("safety.max_repeated_tool_calls must be > 0".into());
+    }
+    if s.max_tool_loop_nudges == 0 {
+        errors.push("safety.max_tool_loop_nudges must be > 0".into());
+    }
+    if !(s.context_budget_fraction > 0.0 && s.context_budget_fraction <= 1.0) {
+        errors.push("safety.context_budget_fraction must be in (0.0, 1.0]".into());
+    }
 }
 
 fn validate_concurrency(config: &EngineConfig, errors: &mut Vec<String>) {
@@ -59,30 +120,87 @@ fn validate_concurrency(config: &EngineConfig, errors: &mut Vec<String>) {
     }
 }
 
+/// Providers with a client implementation in `llm`.
+const LLM_PROVIDERS: &[&str] = &["anthropic", "openai", "gemini"];
+
 fn validate_llm(config: &EngineConfig, errors: &mut Vec<String>) {
-    let validate_role =
-        |role: &autosint_common::config::LlmRoleConfig, name: &str, errors: &mut Vec<String>| {
-            if role.provider.is_empty() {
-                errors.push(format!("llm.{}.provider must not be empty", name));
-            }
-            if role.model.is_empty() {
-                errors.push(format!("llm.{}.model must not be empty", name));
-            }
-            if role.max_tokens == 0 {
-                errors.push(format!("llm.{}.max_tokens must be > 0", name));
-            }
-            if let Some(temp) = role.temperature {
-                if !(0.0..=2.0).contains(&temp) {
-                    errors.push(format!(
-                        "llm.{}.temperature must be between 0.0 and 2.0",
-                        name
-                    ));
-                }
+    let validate_role = |role: &autosint_common::config::LlmRoleConfig,
+                         name: &str,
+                         errors: &mut Vec<String>| {
+        if role.provider.is_empty() {
+            errors.push(format!("llm.{}.provider must not be empty", name));
+        } else if !LLM_PROVIDERS.contains(&role.provider.as_str()) {
+            errors.push(format!(
+                "llm.{}.provider must be one of {:?} (got {:?})",
+                name, LLM_PROVIDERS, role.provider
+            ));
+        }
+        if role.model.is_empty() {
+            errors.push(format!("llm.{}.model must not be empty", name));
+        }
+        if role.max_tokens == 0 {
+            errors.push(format!("llm.{}.max_tokens must be > 0", name));
+        }
+        if let Some(temp) = role.temperature {
+            if !(0.0..=2.0).contains(&temp) {
+                errors.push(format!(
+                    "llm.{}.temperature must be between 0.0 and 2.0",
+                    name
+                ));
             }
-        };
+        }
+        // Not an error: caching is a dev tool, but cached responses at a
+        // non-zero temperature wouldn't be representative, so none are used.
+        if role.cache_responses && !crate::llm::cache::caches_responses(role) {
+            tracing::warn!(
+                    role = name,
+                    "llm.{}.cache_responses only applies at temperature 0.0; responses will not be cached",
+                    name
+                );
+        }
+        if role.cache_responses && role.response_cache.ttl_seconds == 0 {
+            errors.push(format!(
+                "llm.{}.response_cache.ttl_seconds must be > 0",
+                name
+            ));
+        }
+    };
+
+    for (pattern, caps) in &config.system.llm.models {
+        if caps.context_window == 0 {
+            errors.push(format!(
+                "llm.models.\"{}\".context_window must be > 0",
+                pattern
+            ));
+        }
+        if caps.max_output_tokens == 0 || caps.max_output_tokens >= caps.context_window {
+            errors.push(format!(
+                "llm.models.\"{}\".max_output_tokens must be > 0 and < context_window",
+                pattern
+            ));
+        }
+    }
 
-    validate_role(&config.system.llm.analyst, "analyst", errors);
-    validate_role(&config.system.llm.processor, "processor", errors);
+    let llm = &config.system.llm;
+    let roles = [
+        ("analyst", Some(&llm.analyst)),
+        ("processor", Some(&llm.processor)),
+        ("triage", llm.triage.as_ref()),
+    ];
+    for (name, role) in roles {
+        let Some(role) = role else { continue };
+        validate_role(role, name, errors);
+        if let Some(fallback) = &role.fallback {
+            let fallback_name = format!("{}.fallback", name);
+            validate_role(&fallback.role_config(role), &fallback_name, errors);
+            if fallback.cooldown_seconds == 0 {
+                errors.push(format!(
+                    "llm.{}.cooldown_seconds must be > 0",
+                    fallback_name
+                ));
+            }
+        }
+    }
 }
 
 fn validate_embeddings(config: &EngineConfig, errors: &mut Vec<String>) {
@@ -100,6 +218,21 @@ fn validate_embeddings(config: &EngineConfig, errors: &mut Vec<String>) {
     if e.batch_size == 0 {
         errors.push("embeddings.batch_size must be > 0".into());
     }
+    if e.target_dimensions == Some(0) {
+        errors.push("embeddings.target_dimensions must be > 0".into());
+    }
+
+    if let Some(r) = &config.system.reembed {
+        if r.model.is_empty() {
+            errors.push("reembed.model must not be empty".into());
+        }
+        if r.dimensions == 0 {
+            errors.push("reembed.dimensions must be > 0".into());
+        }
+        if r.target_dimensions == Some(0) {
+            errors.push("reembed.target_dimensions must be > 0".into());
+        }
+    }
 }
 
 fn validate_dedup(config: &EngineConfig, errors: &mut Vec<String>) {
@@ -108,9 +241,21 @@ fn validate_dedup(config: &EngineConfig, errors: &mut Vec<String>) {
     if !(0.0..=1.0).contains(&d.fuzzy_threshold) {
         errors.push("dedup.fuzzy_threshold must be between 0.0 and 1.0".into());
     }
+    if !(0.0..=1.0).contains(&d.seeded_geo_fuzzy_threshold) {
+        errors.push("dedup.seeded_geo_fuzzy_threshold must be between 0.0 and 1.0".into());
+    }
     if !(0.0..=1.0).contains(&d.embedding_threshold) {
         errors.push("dedup.embedding_threshold must be between 0.0 and 1.0".into());
     }
+    if !(0.0..=1.0).contains(&d.relationship_description_threshold) {
+        errors.push("dedup.relationship_description_threshold must be between 0.0 and 1.0".into());
+    }
+    if d.fresh_entities.window_seconds == 0 {
+        errors.push("dedup.fresh_entities.window_seconds must be > 0".into());
+    }
+    if d.fresh_entities.max_scan == 0 {
+        errors.push("dedup.fresh_entities.max_scan must be > 0".into());
+    }
 }
 
 fn validate_retry(config: &EngineConfig, errors: &mut Vec<String>) {
@@ -141,3 +286,575 @@ fn validate_retry(config: &EngineConfig, errors: &mut Vec<String>) {
         errors,
     );
 }
+
+fn validate_retention(config: &EngineConfig, errors: &mut Vec<String>) {
+    let r = &config.system.retention;
+
+    if r.enabled && r.max_age_days == 0 {
+        errors.push("retention.max_age_days must be > 0 when retention is enabled".into());
+    }
+    if r.check_interval_minutes == 0 {
+        errors.push("retention.check_interval_minutes must be > 0".into());
+    }
+}
+
+fn validate_relationship_weights(config: &EngineConfig, errors: &mut Vec<String>) {
+    let w = &config.system.relationship_weights;
+
+    if w.interval_minutes == 0 {
+        errors.push("relationship_weights.interval_minutes must be > 0".into());
+    }
+    if w.recency_half_life_days <= 0.0 {
+        errors.push("relationship_weights.recency_half_life_days must be > 0".into());
+    }
+    if w.half_weight_sources <= 0.0 {
+        errors.push("relationship_weights.half_weight_sources must be > 0".into());
+    }
+}
+
+fn validate_triage(config: &EngineConfig, errors: &mut Vec<String>) {
+    let t = &config.system.triage;
+
+    if t.max_attempts == 0 {
+        errors.push("triage.max_attempts must be > 0".into());
+    }
+    if t.preflight_by_default && config.system.llm.triage.is_none() {
+        errors.push("triage.preflight_by_default requires an [llm.triage] section".into());
+    }
+}
+
+fn validate_entity_shortlist(config: &EngineConfig, errors: &mut Vec<String>) {
+    if config.system.entity_shortlist.enabled && config.system.entity_shortlist.ttl_seconds == 0 {
+        errors.push("entity_shortlist.ttl_seconds must be > 0".into());
+    }
+}
+
+fn validate_selftest(config: &EngineConfig, errors: &mut Vec<String>) {
+    let t = &config.system.selftest;
+
+    if t.timeout_seconds == 0 {
+        errors.push("selftest.timeout_seconds must be > 0".into());
+    }
+    if t.max_turns == 0 {
+        errors.push("selftest.max_turns must be > 0".into());
+    }
+    for required in ["create_work_order", "produce_assessment"] {
+        if !t.analyst_tools.iter().any(|tool| tool == required) {
+            errors.push(format!("selftest.analyst_tools must include {}", required));
+        }
+    }
+    for tool in &t.analyst_tools {
+        if !config
+            .tool_schemas
+            .contains_key(&format!("analyst/{}", tool))
+        {
+            errors.push(format!(
+                "selftest.analyst_tools: unknown Analyst tool '{}'",
+                tool
+            ));
+        }
+    }
+}
+
+fn validate_recovery(config: &EngineConfig, errors: &mut Vec<String>) {
+    if config.system.recovery.max_concurrent == 0 {
+        errors.push("recovery.max_concurrent must be > 0".into());
+    }
+}
+
+fn validate_scratchpad(config: &EngineConfig, errors: &mut Vec<String>) {
+    let s = &config.system.scratchpad;
+
+    if s.max_section_chars == 0 {
+        errors.push("scratchpad.max_section_chars must be > 0".into());
+    }
+    if s.max_total_chars < s.max_section_chars {
+        errors.push(format!(
+            "scratchpad.max_total_chars ({}) must be >= max_section_chars ({})",
+            s.max_total_chars, s.max_section_chars
+        ));
+    }
+}
+
+fn validate_work_order_proposals(config: &EngineConfig, errors: &mut Vec<String>) {
+    let p = &config.system.work_order_proposals;
+    if !p.enabled {
+        return;
+    }
+
+    if p.max_per_processor_session == 0 {
+        errors.push("work_order_proposals.max_per_processor_session must be > 0".into());
+    }
+    if p.max_per_investigation == 0 {
+        errors.push("work_order_proposals.max_per_investigation must be > 0".into());
+    }
+}
+
+fn validate_assessment_diff(config: &EngineConfig, errors: &mut Vec<String>) {
+    let d = &config.system.assessment_diff;
+
+    for (name, value) in [
+        ("similarity_threshold", d.similarity_threshold),
+        ("fuzzy_threshold", d.fuzzy_threshold),
+    ] {
+        if !(value > 0.0 && value <= 1.0) {
+            errors.push(format!(
+                "assessment_diff.{} ({}) must be in (0, 1]",
+                name, value
+            ));
+        }
+    }
+}
+
+fn validate_source_trust(config: &EngineConfig, errors: &mut Vec<String>) {
+    let floor = config.system.source_trust.citation_floor;
+    if !(MIN_TRUST_LEVEL..=MAX_TRUST_LEVEL).contains(&floor) {
+        errors.push(format!(
+            "source_trust.citation_floor ({}) must be in [{}, {}]",
+            floor, MIN_TRUST_LEVEL, MAX_TRUST_LEVEL
+        ));
+    }
+}
+
+fn validate_entity_kinds(config: &EngineConfig, errors: &mut Vec<String>) {
+    let kinds = &config.system.entity_kinds;
+
+    let mut seen = std::collections::HashSet::new();
+    for kind in &kinds.canonical {
+        if kind.is_empty() || *kind != kind.trim().to_lowercase() {
+            errors.push(format!(
+                "entity_kinds.canonical: '{}' must be lowercase without surrounding spaces",
+                kind
+            ));
+        }
+        if !seen.insert(kind) {
+            errors.push(format!("entity_kinds.canonical lists '{}' twice", kind));
+        }
+    }
+
+    for (kind, policy) in &kinds.policies {
+        if !kinds.canonical.contains(kind) {
+            errors.push(format!(
+                "entity_kinds.policies.{} is not a canonical kind (entity_kinds.canonical)",
+                kind
+            ));
+        }
+        for (i, field) in policy.required.iter().enumerate() {
+            if field.any_of.is_empty() {
+                errors.push(format!(
+                    "entity_kinds.policies.{}.required[{}].any_of must not be empty",
+                    kind, i
+                ));
+            }
+            for name in &field.any_of {
+                let scheme = name.strip_prefix(IDENTIFIER_FIELD_PREFIX);
+                if scheme.is_some_and(|s| s.parse::<IdentifierScheme>().is_err())
+                    || name.trim().is_empty()
+                {
+                    errors.push(format!(
+                        "entity_kinds.policies.{}.required[{}]: '{}' is not a property, \
+                         summary, aliases or identifiers.<scheme>",
+                        kind, i, name
+                    ));
+                }
+            }
+        }
+        for key in policy.defaults.keys() {
+            if key.trim().is_empty() || key == TRUST_LEVEL_PROPERTY {
+                errors.push(format!(
+                    "entity_kinds.policies.{}.defaults: '{}' can't be a default property",
+                    kind, key
+                ));
+            }
+        }
+    }
+}
+
+fn validate_pii(config: &EngineConfig, errors: &mut Vec<String>) {
+    // Checked even when disabled, so switching it on can't fail at startup.
+    if let Err(e) = autosint_common::pii::PiiScrubber::new(&config.system.pii) {
+        errors.push(format!("pii: {}", e));
+    }
+}
+
+fn validate_graph_writes(config: &EngineConfig, errors: &mut Vec<String>) {
+    let w = &config.system.graph_writes;
+
+    if w.max_concurrent_writes == 0 {
+        errors.push("graph_writes.max_concurrent_writes must be > 0".into());
+    }
+    if w.max_claim_references == 0 {
+        errors.push("graph_writes.max_claim_references must be > 0".into());
+    }
+    if w.coalesce.enabled && w.coalesce.max_items == 0 {
+        errors.push("graph_writes.coalesce.max_items must be > 0".into());
+    }
+}
+
+fn validate_graph_reads(config: &EngineConfig, errors: &mut Vec<String>) {
+    let r = &config.system.graph_reads;
+
+    if r.max_search_results == 0 {
+        errors.push("graph_reads.max_search_results must be > 0".into());
+    }
+    if r.max_traversal_results == 0 {
+        errors.push("graph_reads.max_traversal_results must be > 0".into());
+    }
+}
+
+fn validate_graph_consistency(config: &EngineConfig, errors: &mut Vec<String>) {
+    let c = &config.system.graph_consistency;
+
+    if c.repair_batch_size == 0 {
+        errors.push("graph_consistency.repair_batch_size must be > 0".into());
+    }
+    if c.unstub_min_claims == 0 {
+        errors.push("graph_consistency.unstub_min_claims must be > 0".into());
+    }
+}
+
+fn validate_maintenance(config: &EngineConfig, errors: &mut Vec<String>) {
+    if config.system.maintenance.sync_interval_seconds == 0 {
+        errors.push("maintenance.sync_interval_seconds must be > 0".into());
+    }
+}
+
+fn validate_metrics(config: &EngineConfig, errors: &mut Vec<String>) {
+    let metrics = &config.system.metrics;
+    if metrics.federate && metrics.federate_timeout_ms == 0 {
+        errors.push("metrics.federate_timeout_ms must be > 0".into());
+    }
+}
+
+fn validate_audit(config: &EngineConfig, errors: &mut Vec<String>) {
+    let audit = &config.system.audit;
+    if !audit.enabled {
+        return;
+    }
+    if audit.buffer_size == 0 {
+        errors.push("audit.buffer_size must be > 0".into());
+    }
+    if audit.batch_size == 0 {
+        errors.push("audit.batch_size must be > 0".into());
+    }
+}
+
+fn validate_jobs(config: &EngineConfig, errors: &mut Vec<String>) {
+    let jobs = &config.system.jobs;
+    if jobs.poll_interval_seconds == 0 {
+        errors.push("jobs.poll_interval_seconds must be > 0".into());
+    }
+    if jobs.batch_size == 0 {
+        errors.push("jobs.batch_size must be > 0".into());
+    }
+}
+
+fn validate_exploration(config: &EngineConfig, errors: &mut Vec<String>) {
+    let exploration = &config.system.exploration;
+    if exploration.max_calls_per_source == 0 {
+        errors.push("exploration.max_calls_per_source must be > 0".into());
+    }
+    if exploration.max_fetch_chars == 0 {
+        errors.push("exploration.max_fetch_chars must be > 0".into());
+    }
+}
+
+fn validate_transfer(config: &EngineConfig, errors: &mut Vec<String>) {
+    let transfer = &config.system.transfer;
+    if transfer.max_entities == 0 {
+        errors.push("transfer.max_entities must be > 0".into());
+    }
+    if transfer.max_claims == 0 {
+        errors.push("transfer.max_claims must be > 0".into());
+    }
+    if transfer.max_archive_bytes == 0 {
+        errors.push("transfer.max_archive_bytes must be > 0".into());
+    }
+}
+
+fn validate_verification(config: &EngineConfig, errors: &mut Vec<String>) {
+    let verification = &config.system.verification;
+    if verification.max_search_results == 0 {
+        errors.push("verification.max_search_results must be > 0".into());
+    }
+    for (name, domains) in [
+        ("allowed_domains", &verification.allowed_domains),
+        ("denied_domains", &verification.denied_domains),
+    ] {
+        if domains
+            .iter()
+            .any(|d| d.trim().is_empty() || d.contains('/'))
+        {
+            errors.push(format!(
+                "verification.{} entries must be bare domains (e.g. \"example.com\")",
+                name
+            ));
+        }
+    }
+    if config.system.tool_results.max_verification_excerpt_chars == 0 {
+        errors.push("tool_results.max_verification_excerpt_chars must be > 0".into());
+    }
+}
+
+fn validate_tool_results(config: &EngineConfig, errors: &mut Vec<String>) {
+    let tool_results = &config.system.tool_results;
+    if tool_results.max_formatted_result_chars == 0 {
+        errors.push("tool_results.max_formatted_result_chars must be > 0".into());
+    }
+    for tool in tool_results.response_formats.keys() {
+        if !FORMATTED_TOOLS.contains(&tool.as_str()) {
+            errors.push(format!(
+                "tool_results.response_formats.{} names a tool without response_format (expected one of: {})",
+                tool,
+                FORMATTED_TOOLS.join(", ")
+            ));
+        }
+    }
+}
+
+fn validate_fetch_replicas(config: &EngineConfig, errors: &mut Vec<String>) {
+    let replicas = &config.system.http.fetch_replicas;
+    if replicas.window_seconds == 0 {
+        errors.push("http.fetch_replicas.window_seconds must be > 0".into());
+    }
+    if !(replicas.hedge_percentile > 0.0 && replicas.hedge_percentile <= 1.0) {
+        errors.push("http.fetch_replicas.hedge_percentile must be in (0, 1]".into());
+    }
+}
+
+fn validate_telemetry(config: &EngineConfig, errors: &mut Vec<String>) {
+    let telemetry = &config.system.telemetry;
+    if telemetry.window_hours == 0 {
+        errors.push("telemetry.window_hours must be > 0".into());
+    }
+    if telemetry.enabled && telemetry.check_interval_minutes == 0 {
+        errors.push("telemetry.check_interval_minutes must be > 0".into());
+    }
+    if let Some(url) = &telemetry.collector_url {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            errors.push(format!(
+                "telemetry.collector_url '{}' must be an http(s) URL",
+                url
+            ));
+        }
+        if telemetry.deployment.trim().is_empty() {
+            errors.push("telemetry.deployment must be set when collector_url is".into());
+        }
+        if telemetry.secret_env.trim().is_empty() {
+            errors.push("telemetry.secret_env must be set when collector_url is".into());
+        }
+        if telemetry.push_attempts == 0 {
+            errors.push("telemetry.push_attempts must be > 0".into());
+        }
+    }
+}
+
+fn validate_events(config: &EngineConfig, errors: &mut Vec<String>) {
+    let events = &config.system.events;
+    if events.broadcast_capacity == 0 {
+        errors.push("events.broadcast_capacity must be > 0".into());
+    }
+    if events.durable {
+        if events.stream_max_len == 0 {
+            errors.push("events.stream_max_len must be > 0".into());
+        }
+        if events.max_deliveries == 0 {
+            errors.push("events.max_deliveries must be > 0".into());
+        }
+    }
+
+    let mut names = std::collections::HashSet::new();
+    for webhook in &events.webhooks {
+        if webhook.name.trim().is_empty() {
+            errors.push("events.webhooks: name must be set".into());
+        } else if !names.insert(webhook.name.as_str()) {
+            errors.push(format!(
+                "events.webhooks: '{}' is listed twice",
+                webhook.name
+            ));
+        }
+        if !webhook.url.starts_with("http://") && !webhook.url.starts_with("https://") {
+            errors.push(format!(
+                "events.webhooks.{}.url '{}' must be an http(s) URL",
+                webhook.name, webhook.url
+            ));
+        }
+        if webhook.secret_env.trim().is_empty() {
+            errors.push(format!(
+                "events.webhooks.{}.secret_env must be set",
+                webhook.name
+            ));
+        }
+        if webhook.attempts == 0 {
+            errors.push(format!(
+                "events.webhooks.{}.attempts must be > 0",
+                webhook.name
+            ));
+        }
+        for event in &webhook.events {
+            if !DomainEvent::NAMES.contains(&event.as_str()) {
+                errors.push(format!(
+                    "events.webhooks.{}.events: unknown event '{}' (expected one of: {})",
+                    webhook.name,
+                    event,
+                    DomainEvent::NAMES.join(", ")
+                ));
+            }
+        }
+    }
+}
+
+fn validate_stub_resolution(config: &EngineConfig, errors: &mut Vec<String>) {
+    let stubs = &config.system.stub_resolution;
+    if stubs.enabled && stubs.interval_minutes == 0 {
+        errors.push("stub_resolution.interval_minutes must be > 0".into());
+    }
+    if stubs.min_claims == 0 {
+        errors.push("stub_resolution.min_claims must be > 0".into());
+    }
+    for (name, value) in [
+        ("name_threshold", stubs.name_threshold),
+        ("embedding_threshold", stubs.embedding_threshold),
+    ] {
+        if !(0.0..=1.0).contains(&value) {
+            errors.push(format!(
+                "stub_resolution.{} must be between 0.0 and 1.0",
+                name
+            ));
+        }
+    }
+    if stubs.delete_unreferenced_after_days == Some(0) {
+        errors.push("stub_resolution.delete_unreferenced_after_days must be > 0".into());
+    }
+}
+
+fn validate_flags(config: &EngineConfig, errors: &mut Vec<String>) {
+    if config.system.flags.sync_interval_seconds == 0 {
+        errors.push("flags.sync_interval_seconds must be > 0".into());
+    }
+}
+
+fn validate_ingest(config: &EngineConfig, errors: &mut Vec<String>) {
+    let monitors = &config.system.ingest.monitors;
+    if monitors.enabled {
+        if monitors.tick_seconds == 0 {
+            errors.push("ingest.monitors.tick_seconds must be > 0".into());
+        }
+        if monitors.max_items_per_run == 0 {
+            errors.push("ingest.monitors.max_items_per_run must be > 0".into());
+        }
+        if monitors.query_limit == 0 {
+            errors.push("ingest.monitors.query_limit must be > 0".into());
+        }
+    }
+
+    let imap = &config.system.ingest.imap;
+    if !imap.enabled {
+        return;
+    }
+
+    for (field, value) in [
+        ("name", &imap.name),
+        ("host", &imap.host),
+        ("username", &imap.username),
+        ("password_env", &imap.password_env),
+        ("folder", &imap.folder),
+    ] {
+        if value.trim().is_empty() {
+            errors.push(format!("ingest.imap.{} must be set when enabled", field));
+        }
+    }
+    if imap.poll_interval_seconds == 0 {
+        errors.push("ingest.imap.poll_interval_seconds must be > 0".into());
+    }
+    if imap.max_message_bytes == 0 {
+        errors.push("ingest.imap.max_message_bytes must be > 0".into());
+    }
+    if imap.max_messages_per_poll == 0 {
+        errors.push("ingest.imap.max_messages_per_poll must be > 0".into());
+    }
+    if let Some(level) = imap.trust_level {
+        if !(MIN_TRUST_LEVEL..=MAX_TRUST_LEVEL).contains(&level) {
+            errors.push(format!(
+                "ingest.imap.trust_level ({}) must be in [{}, {}]",
+                level, MIN_TRUST_LEVEL, MAX_TRUST_LEVEL
+            ));
+        }
+    }
+}
+
+fn validate_enrichment(config: &EngineConfig, errors: &mut Vec<String>) {
+    let e = &config.system.ingest.enrichment;
+    if !e.enabled {
+        return;
+    }
+
+    if !e.callback_base_url.starts_with("http://") && !e.callback_base_url.starts_with("https://") {
+        errors.push(format!(
+            "ingest.enrichment.callback_base_url '{}' must be an http(s) URL",
+            e.callback_base_url
+        ));
+    }
+    for (field, value) in [
+        ("token_ttl_seconds", e.token_ttl_seconds),
+        ("signature_tolerance_seconds", e.signature_tolerance_seconds),
+        ("max_result_bytes", e.max_result_bytes as u64),
+        ("max_per_investigation", e.max_per_investigation as u64),
+        ("excerpt_chars", e.excerpt_chars as u64),
+    ] {
+        if value == 0 {
+            errors.push(format!("ingest.enrichment.{} must be > 0", field));
+        }
+    }
+    if e.providers.is_empty() {
+        errors.push("ingest.enrichment.providers must list a provider when enabled".into());
+    }
+
+    let mut names = std::collections::HashSet::new();
+    for provider in &e.providers {
+        if provider.name.trim().is_empty() {
+            errors.push("ingest.enrichment.providers: name must be set".into());
+        } else if !names.insert(provider.name.as_str()) {
+            errors.push(format!(
+                "ingest.enrichment.providers: '{}' is listed twice",
+                provider.name
+            ));
+        }
+        if !provider.submit_url.starts_with("http://")
+            && !provider.submit_url.starts_with("https://")
+        {
+            errors.push(format!(
+                "ingest.enrichment.providers.{}.submit_url '{}' must be an http(s) URL",
+                provider.name, provider.submit_url
+            ));
+        }
+        if provider.secret_env.trim().is_empty() {
+            errors.push(format!(
+                "ingest.enrichment.providers.{}.secret_env must be set",
+                provider.name
+            ));
+        }
+    }
+}
+
+fn validate_stix(config: &EngineConfig, errors: &mut Vec<String>) {
+    let mut kinds: Vec<_> = config.system.stix.kind_mappings.iter().collect();
+    kinds.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (kind, mapping) in kinds {
+        if !SUPPORTED_SDO_TYPES.contains(&mapping.stix_type.as_str()) {
+            errors.push(format!(
+                "stix.kind_mappings.{}.type '{}' is not supported (expected one of: {})",
+                kind,
+                mapping.stix_type,
+                SUPPORTED_SDO_TYPES.join(", ")
+            ));
+        }
+        if mapping.identity_class.is_some() && mapping.stix_type != "identity" {
+            errors.push(format!(
+                "stix.kind_mappings.{}.identity_class is only valid for identity objects",
+                kind
+            ));
+        }
+    }
+}
@@ -4,32 +4,64 @@ use std::time::Duration;
 use neo4rs::query;
 use tokio::task::JoinHandle;
 
+use autosint_common::config::ClaimChunkConfig;
+
+use crate::graph::conversions::parse_claim_id;
 use crate::graph::GraphClient;
+use crate::maintenance::ReadOnlyMode;
+use crate::store::StoreClient;
+use crate::supervisor;
 
 use super::EmbeddingClient;
 
 /// Spawn a background task that periodically finds nodes/relationships with
 /// `embedding_pending = true`, computes their embeddings, and updates them.
+/// It also chunks claims flagged `chunks_pending` per `claim_chunks`.
+/// Runs hold the `embedding_backfill` singleton lock in `store`, so only one
+/// engine instance backfills at a time.
 pub fn spawn_backfill_task(
     graph: Arc<GraphClient>,
+    store: Arc<StoreClient>,
     embedding_client: Arc<EmbeddingClient>,
     interval_minutes: u32,
     batch_size: u32,
+    claim_chunks: ClaimChunkConfig,
+    read_only: ReadOnlyMode,
 ) -> JoinHandle<()> {
     let interval = Duration::from_secs(interval_minutes as u64 * 60);
 
-    tokio::spawn(async move {
-        tracing::info!(
-            interval_minutes,
-            batch_size,
-            "Embedding backfill task started"
-        );
-
-        loop {
-            tokio::time::sleep(interval).await;
-
-            if let Err(e) = run_backfill_cycle(&graph, &embedding_client, batch_size).await {
-                tracing::error!(error = %e, "Embedding backfill cycle failed");
+    supervisor::spawn_supervised_restarting("embedding_backfill", move || {
+        let graph = Arc::clone(&graph);
+        let store = Arc::clone(&store);
+        let embedding_client = Arc::clone(&embedding_client);
+        let read_only = read_only.clone();
+        let claim_chunks = claim_chunks.clone();
+        async move {
+            tracing::info!(
+                interval_minutes,
+                batch_size,
+                "Embedding backfill task started"
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping embedding backfill");
+                    continue;
+                }
+
+                let cycle = async {
+                    if let Err(e) =
+                        run_backfill_cycle(&graph, &embedding_client, batch_size, &claim_chunks)
+                            .await
+                    {
+                        tracing::error!(error = %e, "Embedding backfill cycle failed");
+                    }
+                };
+                if let Err(e) = store.try_run_exclusive("embedding_backfill", cycle).await {
+                    tracing::error!(error = %e, "Failed to take the embedding backfill lock");
+                }
             }
         }
     })
@@ -39,6 +71,7 @@ async fn run_backfill_cycle(
     graph: &GraphClient,
     embedding_client: &EmbeddingClient,
     batch_size: u32,
+    claim_chunks: &ClaimChunkConfig,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Backfill entities.
     backfill_entities(graph, embedding_client, batch_size).await?;
@@ -49,6 +82,9 @@ async fn run_backfill_cycle(
     // Backfill relationships.
     backfill_relationships(graph, embedding_client, batch_size).await?;
 
+    // Chunk long claims.
+    backfill_claim_chunks(graph, embedding_client, batch_size, claim_chunks).await?;
+
     Ok(())
 }
 
@@ -200,3 +236,59 @@ async fn backfill_relationships(
     metrics::counter!("embedding.backfill.processed").increment(count as u64);
     Ok(())
 }
+
+async fn backfill_claim_chunks(
+    graph: &GraphClient,
+    embedding_client: &EmbeddingClient,
+    batch_size: u32,
+    config: &ClaimChunkConfig,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let q = query(
+        "MATCH (c:Claim {chunks_pending: true}) \
+         RETURN c.id AS id, c.content AS content \
+         LIMIT $limit",
+    )
+    .param("limit", batch_size as i64);
+
+    let mut result = graph.inner().execute(q).await?;
+
+    let mut claims = Vec::new();
+    while let Ok(Some(row)) = result.next().await {
+        let id: String = row.get("id")?;
+        let content: String = row.get("content")?;
+        claims.push((parse_claim_id(&id)?, super::chunk_claim(&content, config)));
+    }
+
+    if claims.is_empty() {
+        return Ok(());
+    }
+
+    let texts: Vec<String> = claims
+        .iter()
+        .flat_map(|(_, chunks)| chunks.clone())
+        .collect();
+    let mut embeddings = embedding_client.embed_batch(&texts).await?.into_iter();
+
+    let chunked = claims
+        .iter()
+        .filter(|(_, chunks)| !chunks.is_empty())
+        .count();
+    tracing::info!(
+        claims = claims.len(),
+        chunked,
+        chunks = texts.len(),
+        "Backfilling claim chunk embeddings"
+    );
+
+    for (id, chunks) in claims {
+        let chunks: Vec<(String, Vec<f32>)> = chunks.into_iter().zip(embeddings.by_ref()).collect();
+        graph.set_claim_chunks(id, &chunks).await?;
+    }
+
+    metrics::counter!("embedding.backfill.chunks").increment(texts.len() as u64);
+    Ok(())
+}
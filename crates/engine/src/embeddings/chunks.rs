@@ -0,0 +1,177 @@
+use autosint_common::config::ClaimChunkConfig;
+
+/// Split a long claim into overlapping windows of whole sentences, each of
+/// about `config.max_chars` characters, for chunk embeddings. Empty when
+/// chunking is disabled, the claim is no longer than `config.min_chars`, or
+/// it would fit in a single chunk anyway.
+pub fn chunk_claim(content: &str, config: &ClaimChunkConfig) -> Vec<String> {
+    if !config.enabled || content.chars().count() <= config.min_chars {
+        return Vec::new();
+    }
+
+    let sentences = split_sentences(content);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < sentences.len() {
+        // Take sentences while they fit, but always at least one.
+        let mut end = start + 1;
+        let mut chars = sentences[start].chars().count();
+        while end < sentences.len() {
+            let next = sentences[end].chars().count() + 1;
+            if chars + next > config.max_chars {
+                break;
+            }
+            chars += next;
+            end += 1;
+        }
+        chunks.push(sentences[start..end].join(" "));
+
+        if end == sentences.len() {
+            break;
+        }
+        start = end.saturating_sub(config.overlap_sentences).max(start + 1);
+    }
+
+    if chunks.len() < 2 {
+        return Vec::new();
+    }
+    chunks
+}
+
+/// Sentences of `text`, trimmed. A sentence ends at terminal punctuation
+/// followed by whitespace, or at a line break.
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let end = if c == '\n' {
+            Some(i)
+        } else if matches!(c, '.' | '!' | '?' | '。' | '！' | '？') {
+            match chars.peek() {
+                Some(&(_, next)) if next.is_whitespace() => Some(i + c.len_utf8()),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(end) = end {
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(min_chars: usize, max_chars: usize, overlap_sentences: usize) -> ClaimChunkConfig {
+        ClaimChunkConfig {
+            enabled: true,
+            min_chars,
+            max_chars,
+            overlap_sentences,
+        }
+    }
+
+    /// Ten numbered sentences of 20 characters each.
+    fn ten_sentences() -> String {
+        (0..10)
+            .map(|i| format!("Sentence number {}xx.", i))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("First one. Second? Third!\nA line\n\nv1.2 stays whole.  "),
+            vec![
+                "First one.",
+                "Second?",
+                "Third!",
+                "A line",
+                "v1.2 stays whole."
+            ]
+        );
+        assert_eq!(
+            split_sentences("No terminal punctuation"),
+            vec!["No terminal punctuation"]
+        );
+        assert_eq!(split_sentences("  \n "), Vec::<&str>::new());
+        assert_eq!(
+            split_sentences("船が出た。 港に着いた。"),
+            vec!["船が出た。", "港に着いた。"]
+        );
+    }
+
+    #[test]
+    fn test_short_or_disabled_claims_are_not_chunked() {
+        let text = ten_sentences();
+        assert!(chunk_claim(&text, &config(text.len(), 50, 1)).is_empty());
+        assert!(chunk_claim(
+            &text,
+            &ClaimChunkConfig {
+                enabled: false,
+                ..config(0, 50, 1)
+            }
+        )
+        .is_empty());
+        // Long enough, but one chunk would hold all of it.
+        assert!(chunk_claim(&text, &config(0, 1_000, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_windows_overlap_by_whole_sentences() {
+        let text = ten_sentences();
+        // Two 20-character sentences and a separator fit in 41 characters.
+        let chunks = chunk_claim(&text, &config(0, 41, 0));
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[0], "Sentence number 0xx. Sentence number 1xx.");
+
+        let chunks = chunk_claim(&text, &config(0, 62, 1));
+        assert_eq!(
+            chunks,
+            vec![
+                "Sentence number 0xx. Sentence number 1xx. Sentence number 2xx.",
+                "Sentence number 2xx. Sentence number 3xx. Sentence number 4xx.",
+                "Sentence number 4xx. Sentence number 5xx. Sentence number 6xx.",
+                "Sentence number 6xx. Sentence number 7xx. Sentence number 8xx.",
+                "Sentence number 8xx. Sentence number 9xx.",
+            ]
+        );
+        assert!(chunks.iter().all(|c| c.chars().count() <= 62));
+    }
+
+    #[test]
+    fn test_oversized_sentences_and_overlap_still_advance() {
+        let long = "x".repeat(100);
+        let text = format!("{}. Short one. {}.", long, long);
+        let chunks = chunk_claim(&text, &config(0, 30, 5));
+        assert_eq!(
+            chunks,
+            vec![
+                format!("{}.", long),
+                "Short one.".to_string(),
+                format!("{}.", long)
+            ]
+        );
+
+        // Overlap as large as the window moves forward one sentence at a time.
+        let chunks = chunk_claim(&ten_sentences(), &config(0, 41, 2));
+        assert_eq!(chunks.len(), 9);
+        assert!(chunks.last().unwrap().ends_with("9xx."));
+    }
+}
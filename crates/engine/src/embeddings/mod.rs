@@ -1,9 +1,16 @@
 mod backfill;
+mod chunks;
 mod openai;
+pub mod reembed;
+
+use std::time::Duration;
 
 use autosint_common::config::{EmbeddingConfig, RetryConfig};
+use autosint_common::http_client::{self, Destination};
+use autosint_common::retry::{retry_with_backoff, GiveUp, RetryAttempt, RetryClass, RetryPolicy};
 
 pub use backfill::spawn_backfill_task;
+pub use chunks::chunk_claim;
 
 /// Client for computing text embeddings via an external API.
 pub struct EmbeddingClient {
@@ -52,7 +59,7 @@ impl EmbeddingClient {
         };
 
         Some(Self {
-            http: reqwest::Client::new(),
+            http: http_client::shared(Destination::Embedding),
             config,
             retry_config,
             api_key,
@@ -78,83 +85,155 @@ impl EmbeddingClient {
         let mut all_embeddings = Vec::with_capacity(texts.len());
 
         for chunk in texts.chunks(batch_size) {
-            let embeddings = self.call_api(chunk).await?;
-            all_embeddings.extend(embeddings);
+            for embedding in self.call_api(chunk).await? {
+                all_embeddings.push(self.adapt(embedding)?);
+            }
         }
 
         Ok(all_embeddings)
     }
 
-    /// Get the configured embedding dimensions.
+    /// Dimensions of the vectors this client returns: `target_dimensions` when
+    /// set, otherwise the model's.
     pub fn dimensions(&self) -> u32 {
-        self.config.dimensions
+        self.config.effective_dimensions()
+    }
+
+    /// Fit a model vector to [`Self::dimensions`].
+    pub fn adapt(&self, embedding: Vec<f32>) -> Result<Vec<f32>, EmbeddingError> {
+        adapt_dimensions(embedding, self.dimensions() as usize)
     }
 
     /// Call the OpenAI-compatible embedding API with retry logic.
     async fn call_api(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
-        let mut attempt = 0u32;
-        let mut backoff_ms = self.retry_config.initial_backoff_ms;
-
-        loop {
-            attempt += 1;
-            match openai::call_openai_embeddings(
-                &self.http,
-                &self.api_key,
-                &self.config.base_url,
-                &self.config.model,
-                self.config.dimensions,
-                texts,
-            )
-            .await
-            {
-                Ok(embeddings) => {
-                    metrics::counter!("embedding.api.tokens").increment(
-                        texts.iter().map(|t| t.len() as u64 / 4).sum::<u64>(), // rough token estimate
-                    );
-                    return Ok(embeddings);
-                }
-                Err(EmbeddingError::Auth(_)) | Err(EmbeddingError::DimensionMismatch { .. }) => {
-                    // Non-retryable errors.
-                    metrics::counter!("embedding.api.errors").increment(1);
-                    return Err(EmbeddingError::Api(format!(
-                        "Non-retryable error on attempt {}",
-                        attempt
-                    )));
-                }
-                Err(EmbeddingError::RateLimited { retry_after }) => {
-                    if attempt >= self.retry_config.max_attempts {
-                        metrics::counter!("embedding.api.errors").increment(1);
-                        return Err(EmbeddingError::RateLimited { retry_after });
-                    }
-                    let wait = retry_after.map(|s| s * 1000).unwrap_or(backoff_ms);
-                    tracing::warn!(attempt, wait_ms = wait, "Rate limited, retrying");
-                    tokio::time::sleep(std::time::Duration::from_millis(wait)).await;
-                }
-                Err(e) => {
-                    if attempt >= self.retry_config.max_attempts {
-                        metrics::counter!("embedding.api.errors").increment(1);
-                        return Err(e);
-                    }
-                    let jitter = if self.retry_config.jitter {
-                        use std::hash::{Hash, Hasher};
-                        let mut hasher = std::hash::DefaultHasher::new();
-                        attempt.hash(&mut hasher);
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .subsec_nanos()
-                            .hash(&mut hasher);
-                        hasher.finish() % (backoff_ms / 2 + 1)
-                    } else {
-                        0
-                    };
-                    let wait = backoff_ms + jitter;
-                    tracing::warn!(attempt, wait_ms = wait, error = %e, "Embedding API error, retrying");
-                    tokio::time::sleep(std::time::Duration::from_millis(wait)).await;
-                    backoff_ms = (backoff_ms as f64 * self.retry_config.backoff_multiplier) as u64;
-                    backoff_ms = backoff_ms.min(self.retry_config.max_backoff_ms);
-                }
+        let classify = |e: &EmbeddingError| match e {
+            EmbeddingError::Auth(_) | EmbeddingError::DimensionMismatch { .. } => RetryClass::Fatal,
+            EmbeddingError::RateLimited { retry_after } => {
+                RetryClass::RetryAfter(retry_after.map(Duration::from_secs))
+            }
+            _ => RetryClass::Retryable,
+        };
+        let on_retry = |retry: &RetryAttempt<'_, EmbeddingError>| {
+            let wait_ms = retry.wait.as_millis() as u64;
+            if retry.rate_limited {
+                tracing::warn!(attempt = retry.attempt, wait_ms, "Rate limited, retrying");
+            } else {
+                tracing::warn!(attempt = retry.attempt, wait_ms, error = %retry.error, "Embedding API error, retrying");
+            }
+        };
+        let embeddings = retry_with_backoff(
+            &RetryPolicy::from(&self.retry_config),
+            classify,
+            on_retry,
+            || {
+                openai::call_openai_embeddings(
+                    &self.http,
+                    &self.api_key,
+                    &self.config.base_url,
+                    &self.config.model,
+                    self.config.dimensions,
+                    texts,
+                )
+            },
+        )
+        .await
+        .map_err(|failure| {
+            metrics::counter!("embedding.api.errors").increment(1);
+            match failure.reason {
+                GiveUp::Fatal => EmbeddingError::Api(format!(
+                    "Non-retryable error on attempt {}",
+                    failure.attempts
+                )),
+                _ => failure.error,
+            }
+        })?;
+        metrics::counter!("embedding.api.tokens").increment(
+            texts.iter().map(|t| t.len() as u64 / 4).sum::<u64>(), // rough token estimate
+        );
+        Ok(embeddings)
+    }
+}
+
+/// Fit a vector to `dimensions`. A longer vector keeps its leading components
+/// and is re-normalized to unit length, which preserves cosine ranking for
+/// Matryoshka-trained models such as OpenAI's text-embedding-3. A shorter one
+/// is zero-padded, leaving its norm and cosine similarities unchanged.
+pub fn adapt_dimensions(
+    mut embedding: Vec<f32>,
+    dimensions: usize,
+) -> Result<Vec<f32>, EmbeddingError> {
+    if embedding.is_empty() || dimensions == 0 {
+        return Err(EmbeddingError::DimensionMismatch {
+            expected: dimensions as u32,
+            got: embedding.len(),
+        });
+    }
+
+    if embedding.len() > dimensions {
+        embedding.truncate(dimensions);
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for x in &mut embedding {
+                *x /= norm;
             }
         }
+    } else {
+        embedding.resize(dimensions, 0.0);
+    }
+
+    Ok(embedding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn norm(v: &[f32]) -> f32 {
+        v.iter().map(|x| x * x).sum::<f32>().sqrt()
+    }
+
+    #[test]
+    fn test_truncate_renormalizes() {
+        let v = vec![0.6, 0.0, 0.0, 0.8];
+        let adapted = adapt_dimensions(v, 2).unwrap();
+        assert_eq!(adapted, vec![1.0, 0.0]);
+
+        let v = vec![0.5, 0.5, 0.5, 0.5];
+        let adapted = adapt_dimensions(v, 3).unwrap();
+        assert_eq!(adapted.len(), 3);
+        assert!((norm(&adapted) - 1.0).abs() < 1e-6);
+        assert!((adapted[0] - 1.0 / 3f32.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pad_keeps_norm() {
+        let v = vec![0.6, 0.8];
+        let adapted = adapt_dimensions(v, 4).unwrap();
+        assert_eq!(adapted, vec![0.6, 0.8, 0.0, 0.0]);
+        assert!((norm(&adapted) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_matching_dimensions_unchanged() {
+        // Not re-normalized: the model's own vector is stored as returned.
+        let v = vec![0.3, 0.4];
+        assert_eq!(adapt_dimensions(v.clone(), 2).unwrap(), v);
+    }
+
+    #[test]
+    fn test_truncated_zero_prefix_stays_zero() {
+        let adapted = adapt_dimensions(vec![0.0, 0.0, 1.0], 2).unwrap();
+        assert_eq!(adapted, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_empty_vector_rejected() {
+        assert!(matches!(
+            adapt_dimensions(Vec::new(), 4),
+            Err(EmbeddingError::DimensionMismatch {
+                expected: 4,
+                got: 0
+            })
+        ));
     }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use autosint_common::http_client::{self, Destination};
+
 use super::EmbeddingError;
 
 #[derive(Serialize)]
@@ -56,13 +58,12 @@ pub async fn call_openai_embeddings(
         dimensions,
     };
 
-    let response = http
-        .post(&url)
-        .bearer_auth(api_key)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| EmbeddingError::Http(e.to_string()))?;
+    let response = http_client::send(
+        Destination::Embedding,
+        http.post(&url).bearer_auth(api_key).json(&request),
+    )
+    .await
+    .map_err(|e| EmbeddingError::Http(e.to_string()))?;
 
     let status = response.status();
     let latency = start.elapsed().as_secs_f64();
@@ -101,15 +102,7 @@ pub async fn call_openai_embeddings(
     let mut sorted = body.data;
     sorted.sort_by_key(|d| d.index);
 
-    // Validate dimensions.
-    for item in &sorted {
-        if item.embedding.len() != dimensions as usize {
-            return Err(EmbeddingError::DimensionMismatch {
-                expected: dimensions,
-                got: item.embedding.len(),
-            });
-        }
-    }
-
+    // Dimensions are fitted by `EmbeddingClient::adapt`, not checked here:
+    // some providers ignore the `dimensions` parameter.
     Ok(sorted.into_iter().map(|d| d.embedding).collect())
 }
@@ -0,0 +1,226 @@
+//! Re-embed the whole graph with a new embedding model.
+//!
+//! New vectors are written to `embedding_next` in batches while `embedding`
+//! (and the vector indexes over it) keep serving searches. Once every entity,
+//! claim and relationship has one, the vector indexes are dropped, the new
+//! vectors are swapped into `embedding`, and the indexes are re-created at the
+//! new model's dimensions. An interrupted run resumes where it stopped.
+//! Claim chunk vectors are deleted at the swap and rebuilt by the embedding
+//! backfill.
+
+use neo4rs::query;
+
+use autosint_common::api::engine::ReembedReport;
+
+use crate::graph::conversions::{
+    embedding_text_for_claim, embedding_text_for_entity, embedding_text_for_relationship,
+};
+use crate::graph::GraphClient;
+
+use super::EmbeddingClient;
+
+type ReembedResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// Re-embed every entity, claim and relationship with `client`, then swap the
+/// new vectors in and rebuild the vector indexes at `client.dimensions()`.
+pub async fn reembed_graph(
+    graph: &GraphClient,
+    client: &EmbeddingClient,
+    batch_size: u32,
+) -> ReembedResult<ReembedReport> {
+    let entities = embed_all(graph, client, batch_size, Target::Entity).await?;
+    let claims = embed_all(graph, client, batch_size, Target::Claim).await?;
+    let relationships = embed_all(graph, client, batch_size, Target::Relationship).await?;
+
+    swap(graph, client.dimensions(), batch_size).await?;
+
+    tracing::info!(
+        entities,
+        claims,
+        relationships,
+        dimensions = client.dimensions(),
+        "Graph re-embedded"
+    );
+    Ok(ReembedReport {
+        entities,
+        claims,
+        relationships,
+        dimensions: client.dimensions(),
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Entity,
+    Claim,
+    Relationship,
+}
+
+impl Target {
+    /// Pattern binding `x` to the items of this kind.
+    fn pattern(self) -> &'static str {
+        match self {
+            Self::Entity => "(x:Entity)",
+            Self::Claim => "(x:Claim)",
+            Self::Relationship => "()-[x:RELATES_TO]->()",
+        }
+    }
+
+    /// Returns `id` and the fields the embedding text is built from.
+    fn text_columns(self) -> &'static str {
+        match self {
+            Self::Entity => "x.canonical_name AS name, x.summary AS summary",
+            Self::Claim => "x.content AS content",
+            Self::Relationship => "x.description AS description",
+        }
+    }
+
+    fn text(self, row: &neo4rs::Row) -> ReembedResult<String> {
+        Ok(match self {
+            Self::Entity => {
+                let name: String = row.get("name")?;
+                let summary: Option<String> = row.get("summary").ok();
+                embedding_text_for_entity(&name, summary.as_deref())
+            }
+            Self::Claim => embedding_text_for_claim(&row.get::<String>("content")?),
+            Self::Relationship => {
+                embedding_text_for_relationship(&row.get::<String>("description")?)
+            }
+        })
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Entity => "entity",
+            Self::Claim => "claim",
+            Self::Relationship => "relationship",
+        }
+    }
+}
+
+/// Write `embedding_next` on every item of `target` that lacks one. Returns the
+/// number written.
+async fn embed_all(
+    graph: &GraphClient,
+    client: &EmbeddingClient,
+    batch_size: u32,
+    target: Target,
+) -> ReembedResult<u64> {
+    let mut written = 0u64;
+
+    loop {
+        let q = query(&format!(
+            "MATCH {} WHERE x.embedding_next IS NULL \
+             RETURN x.id AS id, {} LIMIT $limit",
+            target.pattern(),
+            target.text_columns()
+        ))
+        .param("limit", batch_size as i64);
+        let mut result = graph.inner().execute(q).await?;
+
+        let mut ids = Vec::new();
+        let mut texts = Vec::new();
+        while let Some(row) = result.next().await? {
+            ids.push(row.get::<String>("id")?);
+            texts.push(target.text(&row)?);
+        }
+        if ids.is_empty() {
+            return Ok(written);
+        }
+
+        let embeddings = client.embed_batch(&texts).await?;
+        for (id, embedding) in ids.iter().zip(embeddings) {
+            let emb_f64: Vec<f64> = embedding.into_iter().map(f64::from).collect();
+            let update = query(&format!(
+                "MATCH {} WHERE x.id = $id SET x.embedding_next = $embedding",
+                target.pattern()
+            ))
+            .param("id", id.as_str())
+            .param("embedding", emb_f64);
+            graph.inner().run(update).await?;
+        }
+
+        written += ids.len() as u64;
+        metrics::counter!("embedding.reembed.processed", "kind" => target.label())
+            .increment(ids.len() as u64);
+        tracing::info!(kind = target.label(), written, "Re-embedding in progress");
+    }
+}
+
+/// Swap `embedding_next` into `embedding` and rebuild the vector indexes.
+/// Items without a new vector (written after their kind was walked) lose their
+/// old-model vector and are left to the pending-embedding backfill.
+async fn swap(graph: &GraphClient, dimensions: u32, batch_size: u32) -> ReembedResult<()> {
+    graph.drop_vector_indexes().await?;
+
+    for target in [Target::Entity, Target::Claim, Target::Relationship] {
+        graph
+            .inner()
+            .run(query(&format!(
+                "MATCH {} WHERE x.embedding_next IS NULL \
+                 SET x.embedding_pending = true REMOVE x.embedding",
+                target.pattern()
+            )))
+            .await?;
+
+        loop {
+            let mut result = graph
+                .inner()
+                .execute(
+                    query(&format!(
+                        "MATCH {} WHERE x.embedding_next IS NOT NULL \
+                         WITH x LIMIT $limit \
+                         SET x.embedding = x.embedding_next, x.embedding_pending = false \
+                         REMOVE x.embedding_next \
+                         RETURN count(x) AS swapped",
+                        target.pattern()
+                    ))
+                    .param("limit", batch_size as i64),
+                )
+                .await?;
+            let swapped: i64 = match result.next().await? {
+                Some(row) => row.get("swapped")?,
+                None => 0,
+            };
+            if swapped == 0 {
+                break;
+            }
+        }
+    }
+
+    in_batches(
+        graph,
+        "MATCH (k:ClaimChunk) WITH k LIMIT $limit DETACH DELETE k RETURN count(*) AS n",
+        batch_size,
+    )
+    .await?;
+    in_batches(
+        graph,
+        "MATCH (c:Claim) WHERE coalesce(c.chunks_pending, false) = false \
+         WITH c LIMIT $limit SET c.chunks_pending = true RETURN count(c) AS n",
+        batch_size,
+    )
+    .await?;
+
+    graph.create_vector_indexes(dimensions).await;
+    graph.check_vector_dimensions(dimensions).await?;
+    Ok(())
+}
+
+/// Run `cypher`, which handles up to `$limit` items and returns their count as
+/// `n`, until it handles none.
+async fn in_batches(graph: &GraphClient, cypher: &str, batch_size: u32) -> ReembedResult<()> {
+    loop {
+        let mut result = graph
+            .inner()
+            .execute(query(cypher).param("limit", batch_size as i64))
+            .await?;
+        let n: i64 = match result.next().await? {
+            Some(row) => row.get("n")?,
+            None => 0,
+        };
+        if n == 0 {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,588 @@
+//! The engine's event bus. The places that know something happened — the
+//! Orchestrator's transitions, the Processor worker loop, the enqueue paths,
+//! the claim and assessment tools — publish a [`DomainEvent`] once, and every
+//! feature that reacts to it subscribes here instead of being wired into
+//! those places.
+//!
+//! Delivery, per kind of subscription:
+//! - Inline subscribers (the investigation timeline) are awaited, in order,
+//!   before `publish` returns. A failure is logged and not retried.
+//! - Broadcast subscribers read a bounded channel and are best effort: one
+//!   that falls behind by the channel's capacity misses the oldest events
+//!   (`events.lagged`), and nothing published while it isn't running reaches
+//!   it.
+//! - Durable subscribers read the `events` Redis stream through their own
+//!   consumer group (see [`crate::queue::events`]): at least once, in order,
+//!   resuming from their offset after a restart. A failed event is retried
+//!   until `max_deliveries`, then given up on (`events.dead_lettered`).
+//!   Subscribers see an event again after a crash, so should tolerate
+//!   repeats; the envelope's `id` identifies one.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+use autosint_common::config::EventsConfig;
+use autosint_common::types::{DomainEvent, EventEnvelope};
+
+use crate::queue::events::event_group;
+use crate::queue::{QueueClient, QueueError};
+use crate::store::StoreClient;
+use crate::supervisor;
+
+pub mod webhooks;
+
+pub use webhooks::WebhookNotifier;
+
+/// Events buffered per broadcast subscriber by a bus built without config.
+const DEFAULT_BROADCAST_CAPACITY: usize = 1024;
+
+/// Entries read from the durable stream per round.
+const DURABLE_BATCH: usize = 50;
+/// How long a durable read waits for new events.
+const DURABLE_BLOCK_MS: u64 = 5000;
+/// Pause before retrying a failed event, or after a Redis error.
+const DURABLE_RETRY_DELAY: Duration = Duration::from_secs(2);
+/// How often a durable subscriber takes over events another consumer of its
+/// group has held for [`DURABLE_CLAIM_IDLE_MS`], e.g. a replica that died.
+const DURABLE_CLAIM_INTERVAL: Duration = Duration::from_secs(60);
+const DURABLE_CLAIM_IDLE_MS: u64 = 5 * 60 * 1000;
+
+/// What a subscriber's `handle` returns.
+pub type HandleFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Something that reacts to published events.
+pub trait EventSubscriber: Send + Sync {
+    /// Unique among subscribers; names its consumer group and metrics.
+    fn name(&self) -> &str;
+
+    /// Handle one event, or say why it couldn't. Events a subscriber has no
+    /// use for are `Ok`.
+    fn handle<'a>(&'a self, envelope: &'a EventEnvelope) -> HandleFuture<'a>;
+}
+
+/// Handle for publishing events. Cloning shares the subscribers. The default
+/// bus has no inline subscribers and no durable stream.
+#[derive(Clone)]
+pub struct EventBus {
+    inline: Vec<Arc<dyn EventSubscriber>>,
+    tx: broadcast::Sender<Arc<EventEnvelope>>,
+    durable: Option<DurableStream>,
+}
+
+#[derive(Clone)]
+struct DurableStream {
+    queue: Arc<QueueClient>,
+    max_len: u64,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_BROADCAST_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// A bus buffering `broadcast_capacity` events per broadcast subscriber.
+    pub fn new(broadcast_capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(broadcast_capacity.max(1));
+        Self {
+            inline: Vec::new(),
+            tx,
+            durable: None,
+        }
+    }
+
+    /// A bus that only writes the investigation timeline to `store`: what
+    /// components built without a configured bus publish to.
+    pub fn timeline(store: Arc<StoreClient>) -> Self {
+        Self::default().with_inline(Arc::new(TimelineWriter::new(store)))
+    }
+
+    /// The engine's bus: the timeline, and the durable stream when
+    /// `config.durable` is on.
+    pub fn from_config(
+        config: &EventsConfig,
+        store: Arc<StoreClient>,
+        queue: Arc<QueueClient>,
+    ) -> Self {
+        let bus =
+            Self::new(config.broadcast_capacity).with_inline(Arc::new(TimelineWriter::new(store)));
+        if config.durable {
+            bus.with_durable_stream(queue, config.stream_max_len)
+        } else {
+            bus
+        }
+    }
+
+    /// Have `publish` await `subscriber`, after those added before it.
+    pub fn with_inline(mut self, subscriber: Arc<dyn EventSubscriber>) -> Self {
+        self.inline.push(subscriber);
+        self
+    }
+
+    /// Also append every event to the durable stream in `queue`, trimmed to
+    /// about `max_len` entries.
+    pub fn with_durable_stream(mut self, queue: Arc<QueueClient>, max_len: u64) -> Self {
+        self.durable = Some(DurableStream { queue, max_len });
+        self
+    }
+
+    /// Whether events are appended to the durable stream.
+    pub fn is_durable(&self) -> bool {
+        self.durable.is_some()
+    }
+
+    /// A receiver of every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<EventEnvelope>> {
+        self.tx.subscribe()
+    }
+
+    /// Publish `event`: hand it to the inline subscribers, append it to the
+    /// durable stream, then broadcast it. Never fails; a subscriber or stream
+    /// failure is logged and counted rather than failing the step the event
+    /// describes.
+    pub async fn publish(&self, event: DomainEvent) {
+        let envelope = Arc::new(EventEnvelope::new(event));
+        let name = envelope.event.name();
+        metrics::counter!("events.published", "type" => name).increment(1);
+
+        for subscriber in &self.inline {
+            if let Err(e) = subscriber.handle(&envelope).await {
+                metrics::counter!("events.subscriber_errors", "subscriber" => subscriber.name().to_string())
+                    .increment(1);
+                tracing::warn!(
+                    subscriber = subscriber.name(),
+                    event = name,
+                    investigation_id = ?envelope.event.investigation_id(),
+                    error = %e,
+                    "Event subscriber failed"
+                );
+            }
+        }
+
+        if let Some(durable) = &self.durable {
+            if let Err(e) = durable.queue.append_event(&envelope, durable.max_len).await {
+                metrics::counter!("events.durable_errors").increment(1);
+                tracing::warn!(event = name, error = %e, "Failed to append event to the durable stream");
+            }
+        }
+
+        // No receivers is fine: nothing subscribes over broadcast.
+        let _ = self.tx.send(envelope);
+    }
+}
+
+/// Writes events that have a timeline entry to the investigation's timeline.
+pub struct TimelineWriter {
+    store: Arc<StoreClient>,
+}
+
+impl TimelineWriter {
+    pub fn new(store: Arc<StoreClient>) -> Self {
+        Self { store }
+    }
+}
+
+impl EventSubscriber for TimelineWriter {
+    fn name(&self) -> &str {
+        "timeline"
+    }
+
+    fn handle<'a>(&'a self, envelope: &'a EventEnvelope) -> HandleFuture<'a> {
+        Box::pin(async move {
+            let Some((investigation_id, kind, payload)) = envelope.event.timeline_entry() else {
+                return Ok(());
+            };
+            self.store
+                .record_investigation_event(investigation_id, kind, &payload)
+                .await
+                .map_err(|e| format!("{} event: {}", kind.as_db_str(), e))
+        })
+    }
+}
+
+/// Run `subscriber` on `bus`'s broadcast channel until the task is aborted.
+/// Best effort: see the module docs.
+pub fn spawn_broadcast_subscriber(
+    bus: &EventBus,
+    subscriber: Arc<dyn EventSubscriber>,
+) -> JoinHandle<()> {
+    let bus = bus.clone();
+    supervisor::spawn_supervised_restarting("event_subscriber", move || {
+        let mut rx = bus.subscribe();
+        let subscriber = Arc::clone(&subscriber);
+        async move {
+            tracing::info!(subscriber = subscriber.name(), "Event subscriber started");
+            loop {
+                match rx.recv().await {
+                    Ok(envelope) => {
+                        if let Err(e) = subscriber.handle(&envelope).await {
+                            metrics::counter!("events.subscriber_errors", "subscriber" => subscriber.name().to_string())
+                                .increment(1);
+                            tracing::warn!(
+                                subscriber = subscriber.name(),
+                                event = envelope.event.name(),
+                                error = %e,
+                                "Event subscriber failed"
+                            );
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        metrics::counter!("events.lagged", "subscriber" => subscriber.name().to_string())
+                            .increment(missed);
+                        tracing::warn!(
+                            subscriber = subscriber.name(),
+                            missed,
+                            "Event subscriber fell behind and missed events"
+                        );
+                    }
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        }
+    })
+}
+
+/// What one [`DurableSubscription::poll`] did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DurablePoll {
+    /// Events handled and acknowledged.
+    pub delivered: usize,
+    /// Events that failed and stay pending for the next poll.
+    pub failed: usize,
+    /// Events given up on after `max_deliveries`.
+    pub dead_lettered: usize,
+    /// Entries acknowledged without handling: trimmed or undecodable.
+    pub skipped: usize,
+}
+
+/// A subscriber reading the durable stream as `consumer` of its group.
+pub struct DurableSubscription {
+    queue: Arc<QueueClient>,
+    subscriber: Arc<dyn EventSubscriber>,
+    group: String,
+    consumer: String,
+    max_deliveries: u32,
+    /// Failed deliveries of entries this consumer holds, by entry ID.
+    attempts: HashMap<String, u32>,
+}
+
+impl DurableSubscription {
+    pub fn new(
+        queue: Arc<QueueClient>,
+        subscriber: Arc<dyn EventSubscriber>,
+        consumer: String,
+        max_deliveries: u32,
+    ) -> Self {
+        Self {
+            group: event_group(subscriber.name()),
+            queue,
+            subscriber,
+            consumer,
+            max_deliveries: max_deliveries.max(1),
+            attempts: HashMap::new(),
+        }
+    }
+
+    /// Create the subscriber's consumer group if it doesn't exist yet. Its
+    /// offset starts at the events appended from then on.
+    pub async fn register(&self) -> Result<(), QueueError> {
+        self.queue.create_event_group(&self.group).await
+    }
+
+    /// Deliver the events this consumer already holds or, holding none, up to
+    /// a batch of new ones, waiting up to `block_ms` for some. Events are
+    /// handled in stream order and acknowledged once handled; the first
+    /// failure ends the poll so later events don't overtake it.
+    pub async fn poll(&mut self, block_ms: u64) -> Result<DurablePoll, QueueError> {
+        let mut entries = self
+            .queue
+            .read_events(&self.group, &self.consumer, true, None, DURABLE_BATCH)
+            .await?;
+        if entries.is_empty() {
+            self.attempts.clear();
+            entries = self
+                .queue
+                .read_events(
+                    &self.group,
+                    &self.consumer,
+                    false,
+                    Some(block_ms),
+                    DURABLE_BATCH,
+                )
+                .await?;
+        }
+
+        let name = self.subscriber.name().to_string();
+        let mut poll = DurablePoll::default();
+        for entry in entries {
+            let Some(envelope) = entry.envelope else {
+                self.queue.ack_event(&self.group, &entry.entry_id).await?;
+                poll.skipped += 1;
+                continue;
+            };
+            match self.subscriber.handle(&envelope).await {
+                Ok(()) => {
+                    self.queue.ack_event(&self.group, &entry.entry_id).await?;
+                    self.attempts.remove(&entry.entry_id);
+                    poll.delivered += 1;
+                }
+                Err(e) => {
+                    let attempts = self.attempts.entry(entry.entry_id.clone()).or_default();
+                    *attempts += 1;
+                    metrics::counter!("events.subscriber_errors", "subscriber" => name.clone())
+                        .increment(1);
+                    if *attempts < self.max_deliveries {
+                        tracing::warn!(
+                            subscriber = %name,
+                            event = envelope.event.name(),
+                            event_id = %envelope.id,
+                            attempt = *attempts,
+                            error = %e,
+                            "Event subscriber failed, will retry"
+                        );
+                        poll.failed += 1;
+                        break;
+                    }
+                    tracing::error!(
+                        subscriber = %name,
+                        event = envelope.event.name(),
+                        event_id = %envelope.id,
+                        attempts = *attempts,
+                        error = %e,
+                        "Giving up on event"
+                    );
+                    metrics::counter!("events.dead_lettered", "subscriber" => name.clone())
+                        .increment(1);
+                    self.queue.ack_event(&self.group, &entry.entry_id).await?;
+                    self.attempts.remove(&entry.entry_id);
+                    poll.dead_lettered += 1;
+                }
+            }
+        }
+        Ok(poll)
+    }
+
+    /// Take over events other consumers of the group have held too long.
+    async fn claim_idle(&self) -> Result<usize, QueueError> {
+        self.queue
+            .claim_idle_events(
+                &self.group,
+                &self.consumer,
+                DURABLE_CLAIM_IDLE_MS,
+                DURABLE_BATCH,
+            )
+            .await
+    }
+}
+
+/// Run `subscriber` on the durable stream as `consumer` until the task is
+/// aborted. Replicas running the same subscriber share its group, so each
+/// event is handled by one of them.
+pub fn spawn_durable_subscriber(
+    queue: Arc<QueueClient>,
+    subscriber: Arc<dyn EventSubscriber>,
+    consumer: String,
+    max_deliveries: u32,
+) -> JoinHandle<()> {
+    supervisor::spawn_supervised_restarting("durable_event_subscriber", move || {
+        let mut subscription = DurableSubscription::new(
+            Arc::clone(&queue),
+            Arc::clone(&subscriber),
+            consumer.clone(),
+            max_deliveries,
+        );
+        async move {
+            while let Err(e) = subscription.register().await {
+                tracing::error!(error = %e, "Failed to register durable event subscriber");
+                tokio::time::sleep(DURABLE_RETRY_DELAY).await;
+            }
+            tracing::info!(
+                subscriber = subscription.subscriber.name(),
+                consumer = %subscription.consumer,
+                "Durable event subscriber started"
+            );
+
+            let mut last_claim: Option<std::time::Instant> = None;
+            loop {
+                if last_claim.is_none_or(|t| t.elapsed() >= DURABLE_CLAIM_INTERVAL) {
+                    match subscription.claim_idle().await {
+                        Ok(0) => {}
+                        Ok(claimed) => tracing::info!(
+                            subscriber = subscription.subscriber.name(),
+                            claimed,
+                            "Took over idle events"
+                        ),
+                        Err(e) => tracing::warn!(error = %e, "Failed to claim idle events"),
+                    }
+                    last_claim = Some(std::time::Instant::now());
+                }
+
+                match subscription.poll(DURABLE_BLOCK_MS).await {
+                    Ok(poll) if poll.failed > 0 => tokio::time::sleep(DURABLE_RETRY_DELAY).await,
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!(
+                            subscriber = subscription.subscriber.name(),
+                            error = %e,
+                            "Failed to read the durable event stream"
+                        );
+                        tokio::time::sleep(DURABLE_RETRY_DELAY).await;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Start the configured webhook notifiers: on the durable stream when it is
+/// on, over broadcast otherwise. A webhook whose secret isn't set is an
+/// error, like any other missing secret.
+pub fn spawn_webhooks(
+    config: &EventsConfig,
+    bus: &EventBus,
+    queue: Arc<QueueClient>,
+    consumer: &str,
+    http: reqwest::Client,
+) -> Result<Vec<JoinHandle<()>>, String> {
+    let notifiers = config
+        .webhooks
+        .iter()
+        .map(|webhook| WebhookNotifier::from_env(webhook, http.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(notifiers
+        .into_iter()
+        .map(|notifier| {
+            let notifier: Arc<dyn EventSubscriber> = Arc::new(notifier);
+            if bus.is_durable() {
+                spawn_durable_subscriber(
+                    Arc::clone(&queue),
+                    notifier,
+                    consumer.to_string(),
+                    config.max_deliveries,
+                )
+            } else {
+                spawn_broadcast_subscriber(bus, notifier)
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use autosint_common::ids::InvestigationId;
+    use autosint_common::types::{InvestigationStatus, StatusChanged};
+
+    use super::*;
+
+    /// Records the events it is handed, failing the first `failures`.
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<String>>,
+        failures: Mutex<u32>,
+    }
+
+    impl EventSubscriber for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn handle<'a>(&'a self, envelope: &'a EventEnvelope) -> HandleFuture<'a> {
+            Box::pin(async move {
+                let mut failures = self.failures.lock().unwrap();
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Err("unavailable".into());
+                }
+                self.seen
+                    .lock()
+                    .unwrap()
+                    .push(envelope.event.name().to_string());
+                Ok(())
+            })
+        }
+    }
+
+    fn status_changed(to: InvestigationStatus) -> DomainEvent {
+        DomainEvent::StatusChanged {
+            investigation_id: InvestigationId::new(),
+            change: StatusChanged {
+                from: InvestigationStatus::Pending,
+                to,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_awaits_inline_subscribers() {
+        let recorder = Arc::new(Recorder {
+            failures: Mutex::new(1),
+            ..Default::default()
+        });
+        let bus = EventBus::default().with_inline(Arc::clone(&recorder) as _);
+        let mut rx = bus.subscribe();
+
+        // A failing inline subscriber doesn't stop the event.
+        bus.publish(status_changed(InvestigationStatus::AnalystRunning))
+            .await;
+        assert!(recorder.seen.lock().unwrap().is_empty());
+        assert_eq!(rx.recv().await.unwrap().event.name(), "status_changed");
+
+        bus.publish(status_changed(InvestigationStatus::Processing))
+            .await;
+        assert_eq!(*recorder.seen.lock().unwrap(), ["status_changed"]);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_is_best_effort() {
+        let bus = EventBus::new(2);
+        // Publishing with nobody listening is fine.
+        bus.publish(status_changed(InvestigationStatus::AnalystRunning))
+            .await;
+
+        let mut rx = bus.subscribe();
+        for to in [
+            InvestigationStatus::AnalystRunning,
+            InvestigationStatus::Processing,
+            InvestigationStatus::Completed,
+        ] {
+            bus.publish(status_changed(to)).await;
+        }
+        // The slow receiver missed the oldest event, then gets the rest.
+        assert!(matches!(rx.recv().await, Err(RecvError::Lagged(1))));
+        let next = rx.recv().await.unwrap();
+        assert!(matches!(
+            &next.event,
+            DomainEvent::StatusChanged { change, .. } if change.to == InvestigationStatus::Processing
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_subscriber_task() {
+        let recorder = Arc::new(Recorder::default());
+        let bus = EventBus::default();
+        let handle = spawn_broadcast_subscriber(&bus, Arc::clone(&recorder) as _);
+        // Let the task subscribe.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        bus.publish(status_changed(InvestigationStatus::AnalystRunning))
+            .await;
+        for _ in 0..50 {
+            if !recorder.seen.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*recorder.seen.lock().unwrap(), ["status_changed"]);
+        handle.abort();
+    }
+}
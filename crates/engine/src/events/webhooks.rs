@@ -0,0 +1,272 @@
+//! Webhook notifications: each published event of a webhook's chosen types
+//! POSTed to its URL as the JSON [`EventEnvelope`], signed like a telemetry
+//! push, so the receiver can check it came from this deployment.
+
+use std::time::Duration;
+
+use chrono::Utc;
+
+use autosint_common::config::EventWebhookConfig;
+use autosint_common::types::EventEnvelope;
+use autosint_common::webhook::{self, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+use super::{EventSubscriber, HandleFuture};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends events to one webhook, retrying with backoff.
+pub struct WebhookNotifier {
+    http: reqwest::Client,
+    name: String,
+    url: String,
+    secret: String,
+    /// Event types sent; empty sends every event.
+    events: Vec<String>,
+    attempts: u32,
+    retry_delay: Duration,
+}
+
+impl WebhookNotifier {
+    /// A notifier for `config`, with the secret read from `config.secret_env`.
+    pub fn from_env(config: &EventWebhookConfig, http: reqwest::Client) -> Result<Self, String> {
+        let secret = match std::env::var(&config.secret_env) {
+            Ok(secret) if !secret.is_empty() => secret,
+            _ => {
+                return Err(format!(
+                    "{} is not set (secret of webhook '{}')",
+                    config.secret_env, config.name
+                ))
+            }
+        };
+        Ok(Self::new(config, secret, http))
+    }
+
+    pub fn new(config: &EventWebhookConfig, secret: String, http: reqwest::Client) -> Self {
+        Self {
+            http,
+            name: config.name.clone(),
+            url: config.url.clone(),
+            secret,
+            events: config.events.clone(),
+            attempts: config.attempts.max(1),
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Whether events of type `name` go to this webhook.
+    pub fn wants(&self, name: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == name)
+    }
+
+    /// POST `envelope`, retrying failed attempts with a doubling delay;
+    /// returns the last error once attempts run out.
+    pub async fn send(&self, envelope: &EventEnvelope) -> Result<(), String> {
+        let body = serde_json::to_string(envelope).map_err(|e| e.to_string())?;
+        let mut delay = self.retry_delay;
+        let mut attempt = 1;
+        loop {
+            match self.post(&body).await {
+                Ok(()) => {
+                    metrics::counter!("events.webhook.sent", "webhook" => self.name.clone())
+                        .increment(1);
+                    return Ok(());
+                }
+                Err(e) if attempt >= self.attempts => {
+                    metrics::counter!("events.webhook.failed", "webhook" => self.name.clone())
+                        .increment(1);
+                    return Err(e);
+                }
+                Err(e) => {
+                    tracing::debug!(webhook = %self.name, attempt, error = %e, "Webhook delivery failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn post(&self, body: &str) -> Result<(), String> {
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .http
+            .post(&self.url)
+            .timeout(SEND_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                webhook::sign(self.secret.as_bytes(), timestamp, body.as_bytes()),
+            )
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text().await.unwrap_or_default();
+        Err(format!(
+            "HTTP {}: {}",
+            status,
+            text.chars().take(200).collect::<String>()
+        ))
+    }
+}
+
+impl EventSubscriber for WebhookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn handle<'a>(&'a self, envelope: &'a EventEnvelope) -> HandleFuture<'a> {
+        Box::pin(async move {
+            if !self.wants(envelope.event.name()) {
+                return Ok(());
+            }
+            self.send(envelope).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+
+    use autosint_common::ids::{AssessmentId, InvestigationId};
+    use autosint_common::types::{
+        AssessmentProduced, Confidence, DomainEvent, InvestigationStatus, StatusChanged,
+    };
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Receiver {
+        /// Requests answered 503 before the receiver starts accepting.
+        failures: Arc<AtomicU32>,
+        received: Arc<tokio::sync::Mutex<Vec<EventEnvelope>>>,
+    }
+
+    async fn receive(
+        State(receiver): State<Receiver>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+        };
+        if webhook::verify(
+            b"hook-secret",
+            header(TIMESTAMP_HEADER),
+            header(SIGNATURE_HEADER),
+            &body,
+            Utc::now().timestamp(),
+            300,
+        )
+        .is_err()
+        {
+            return StatusCode::UNAUTHORIZED;
+        }
+        if receiver
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        let envelope: EventEnvelope = serde_json::from_slice(&body).unwrap();
+        receiver.received.lock().await.push(envelope);
+        StatusCode::NO_CONTENT
+    }
+
+    async fn mock_receiver(failures: u32) -> (String, Receiver) {
+        let receiver = Receiver {
+            failures: Arc::new(AtomicU32::new(failures)),
+            received: Default::default(),
+        };
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), receiver)
+    }
+
+    fn notifier(url: String, secret: &str, events: &[&str], attempts: u32) -> WebhookNotifier {
+        let config = EventWebhookConfig {
+            name: "ops".into(),
+            url,
+            secret_env: "UNUSED".into(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            attempts,
+        };
+        WebhookNotifier {
+            retry_delay: Duration::from_millis(10),
+            ..WebhookNotifier::new(&config, secret.into(), reqwest::Client::new())
+        }
+    }
+
+    fn status_changed() -> EventEnvelope {
+        EventEnvelope::new(DomainEvent::StatusChanged {
+            investigation_id: InvestigationId::new(),
+            change: StatusChanged {
+                from: InvestigationStatus::Processing,
+                to: InvestigationStatus::Completed,
+            },
+        })
+    }
+
+    fn assessment_produced() -> EventEnvelope {
+        EventEnvelope::new(DomainEvent::AssessmentProduced(AssessmentProduced {
+            investigation_id: InvestigationId::new(),
+            assessment_id: AssessmentId::new(),
+            confidence: Confidence::High,
+            supersedes_assessment_id: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_events_are_filtered_signed_and_retried() {
+        let (url, receiver) = mock_receiver(1).await;
+        let notifier = notifier(url, "hook-secret", &["status_changed"], 3);
+
+        let sent = status_changed();
+        notifier.handle(&sent).await.unwrap();
+        // Not one of the webhook's events: skipped without a request.
+        notifier.handle(&assessment_produced()).await.unwrap();
+
+        assert_eq!(*receiver.received.lock().await, [sent]);
+        assert_eq!(receiver.failures.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_fails_after_attempts() {
+        let (url, receiver) = mock_receiver(5).await;
+        let err = notifier(url, "hook-secret", &[], 2)
+            .handle(&assessment_produced())
+            .await
+            .unwrap_err();
+        assert!(err.contains("503"), "{}", err);
+        assert_eq!(receiver.failures.load(Ordering::SeqCst), 3);
+
+        let (url, receiver) = mock_receiver(0).await;
+        let err = notifier(url, "wrong-secret", &[], 1)
+            .handle(&status_changed())
+            .await
+            .unwrap_err();
+        assert!(err.contains("401"), "{}", err);
+        assert!(receiver.received.lock().await.is_empty());
+    }
+}
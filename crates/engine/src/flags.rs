@@ -0,0 +1,246 @@
+//! Runtime feature flags: switches for risky behaviors that can be flipped
+//! without a config rollout or restart. `[flags.defaults]` gives each flag's
+//! value; an override set through POST /admin/flags beats it. Overrides live
+//! in Redis so every replica agrees, and each replica re-reads them every
+//! `flags.sync_interval_seconds`.
+//!
+//! Code consults its flag at the point of decision, not when it is built, so
+//! a change reaches sessions already running.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use autosint_common::api::engine::FlagStatus;
+use autosint_common::config::FeatureFlagsConfig;
+use autosint_common::types::FeatureFlag;
+
+use crate::queue::QueueClient;
+use crate::supervisor;
+
+const FLAG_COUNT: usize = FeatureFlag::ALL.len();
+
+/// Values of an override slot.
+const NO_OVERRIDE: u8 = 0;
+const OVERRIDE_OFF: u8 = 1;
+const OVERRIDE_ON: u8 = 2;
+
+/// This replica's feature flags. Clones share state; reads are atomic loads.
+#[derive(Clone)]
+pub struct FlagSet {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    defaults: [bool; FLAG_COUNT],
+    overrides: [AtomicU8; FLAG_COUNT],
+}
+
+impl Default for FlagSet {
+    /// Every flag on, with no overrides.
+    fn default() -> Self {
+        Self::new(&FeatureFlagsConfig::default())
+    }
+}
+
+impl FlagSet {
+    pub fn new(config: &FeatureFlagsConfig) -> Self {
+        let defaults =
+            FeatureFlag::ALL.map(|flag| config.defaults.get(&flag).copied().unwrap_or(true));
+        let flags = Self {
+            inner: Arc::new(Inner {
+                defaults,
+                overrides: std::array::from_fn(|_| AtomicU8::new(NO_OVERRIDE)),
+            }),
+        };
+        for flag in FeatureFlag::ALL {
+            flags.record_state(flag);
+        }
+        flags
+    }
+
+    /// Whether `flag` is on now. Counted in `flags.evaluations`.
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        let enabled = self.status(flag).enabled;
+        metrics::counter!(
+            "flags.evaluations",
+            "flag" => flag.as_str(),
+            "enabled" => if enabled { "true" } else { "false" }
+        )
+        .increment(1);
+        enabled
+    }
+
+    pub fn status(&self, flag: FeatureFlag) -> FlagStatus {
+        let default = self.inner.defaults[flag as usize];
+        let override_value = match self.inner.overrides[flag as usize].load(Ordering::SeqCst) {
+            OVERRIDE_OFF => Some(false),
+            OVERRIDE_ON => Some(true),
+            _ => None,
+        };
+        FlagStatus {
+            flag,
+            enabled: override_value.unwrap_or(default),
+            default,
+            override_value,
+        }
+    }
+
+    /// Every flag's status, in [`FeatureFlag::ALL`] order.
+    pub fn statuses(&self) -> Vec<FlagStatus> {
+        FeatureFlag::ALL
+            .into_iter()
+            .map(|flag| self.status(flag))
+            .collect()
+    }
+
+    /// Override `flag`, or clear its override with None. Returns whether its
+    /// effective value changed.
+    pub fn set_override(&self, flag: FeatureFlag, enabled: Option<bool>) -> bool {
+        let slot = match enabled {
+            Some(true) => OVERRIDE_ON,
+            Some(false) => OVERRIDE_OFF,
+            None => NO_OVERRIDE,
+        };
+        let before = self.status(flag).enabled;
+        self.inner.overrides[flag as usize].store(slot, Ordering::SeqCst);
+        let after = self.status(flag).enabled;
+
+        if before != after {
+            tracing::warn!(
+                flag = flag.as_str(),
+                enabled = after,
+                "Feature flag changed"
+            );
+        }
+        self.record_state(flag);
+        before != after
+    }
+
+    /// Replace every override with `overrides`, as last read from Redis: flags
+    /// it leaves out lose theirs. Returns the flags whose effective value
+    /// changed.
+    pub fn apply_overrides(&self, overrides: &HashMap<FeatureFlag, bool>) -> Vec<FeatureFlag> {
+        FeatureFlag::ALL
+            .into_iter()
+            .filter(|flag| self.set_override(*flag, overrides.get(flag).copied()))
+            .collect()
+    }
+
+    fn record_state(&self, flag: FeatureFlag) {
+        let enabled = self.status(flag).enabled;
+        metrics::gauge!("flags.enabled", "flag" => flag.as_str()).set(if enabled {
+            1.0
+        } else {
+            0.0
+        });
+    }
+}
+
+/// Spawn the task that keeps this replica's flag overrides in step with the
+/// shared ones in Redis.
+pub fn spawn_flag_sync(
+    flags: FlagSet,
+    queue: Arc<QueueClient>,
+    config: FeatureFlagsConfig,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.sync_interval_seconds);
+
+    supervisor::spawn_supervised_restarting("flag_sync", move || {
+        let flags = flags.clone();
+        let queue = Arc::clone(&queue);
+        async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                match queue.flag_overrides().await {
+                    Ok(overrides) => {
+                        flags.apply_overrides(&overrides);
+                    }
+                    Err(e) => tracing::warn!(error = %e, "Failed to read feature flag overrides"),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn flags(defaults: &[(FeatureFlag, bool)]) -> FlagSet {
+        FlagSet::new(&FeatureFlagsConfig {
+            defaults: defaults.iter().copied().collect::<BTreeMap<_, _>>(),
+            sync_interval_seconds: 10,
+        })
+    }
+
+    #[test]
+    fn test_unlisted_flags_default_on() {
+        let flags = flags(&[(FeatureFlag::WriteCoalescing, false)]);
+        assert!(!flags.is_enabled(FeatureFlag::WriteCoalescing));
+        assert!(flags.is_enabled(FeatureFlag::EntityLinking));
+        assert_eq!(flags.statuses().len(), FeatureFlag::ALL.len());
+    }
+
+    #[test]
+    fn test_override_beats_config_default() {
+        let flags = flags(&[(FeatureFlag::WriteCoalescing, false)]);
+        let other = flags.clone();
+
+        assert!(flags.set_override(FeatureFlag::WriteCoalescing, Some(true)));
+        assert!(other.is_enabled(FeatureFlag::WriteCoalescing));
+        assert!(flags.set_override(FeatureFlag::EntityLinking, Some(false)));
+        assert!(!other.is_enabled(FeatureFlag::EntityLinking));
+        assert_eq!(
+            flags.status(FeatureFlag::EntityLinking),
+            FlagStatus {
+                flag: FeatureFlag::EntityLinking,
+                enabled: false,
+                default: true,
+                override_value: Some(false),
+            }
+        );
+
+        // Overriding to the default changes nothing; clearing restores it.
+        assert!(!flags.set_override(FeatureFlag::ClaimChunkSearch, Some(true)));
+        assert!(flags.set_override(FeatureFlag::WriteCoalescing, None));
+        assert!(!flags.is_enabled(FeatureFlag::WriteCoalescing));
+    }
+
+    #[test]
+    fn test_refresh_replaces_all_overrides() {
+        let flags = flags(&[]);
+        flags.set_override(FeatureFlag::EntityLinking, Some(false));
+
+        let changed = flags.apply_overrides(&HashMap::from([
+            (FeatureFlag::LlmResponseCache, false),
+            (FeatureFlag::FreshEntitySearch, true),
+        ]));
+        // Entity linking lost its override; the fresh entity override matches
+        // its default.
+        assert_eq!(
+            changed,
+            vec![FeatureFlag::EntityLinking, FeatureFlag::LlmResponseCache]
+        );
+        assert!(flags.is_enabled(FeatureFlag::EntityLinking));
+        assert!(!flags.is_enabled(FeatureFlag::LlmResponseCache));
+        assert_eq!(
+            flags.status(FeatureFlag::FreshEntitySearch).override_value,
+            Some(true)
+        );
+
+        assert_eq!(
+            flags.apply_overrides(&HashMap::new()),
+            vec![FeatureFlag::LlmResponseCache]
+        );
+        assert!(flags
+            .statuses()
+            .iter()
+            .all(|status| status.override_value.is_none()));
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use neo4rs::query;
+
+use autosint_common::types::{Claim, Entity, Relationship};
+use autosint_common::InvestigationId;
+
+use super::conversions::{format_datetime, node_to_entity, row_to_claim, row_to_relationship};
+use super::writes::record_read_latency;
+use super::GraphError;
+
+/// Which part of the graph a change query covers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangeScope {
+    /// Every change in the graph.
+    All,
+    /// Changes an investigation made: entities it created or whose claims
+    /// publish or reference them, its claims, and relationships it created.
+    Investigation(InvestigationId),
+    /// Changes around named focus entities (matched case-insensitively on
+    /// canonical name): the focus entities and their direct neighbors, claims
+    /// referencing a focus entity, and relationships touching one.
+    Focus(Vec<String>),
+}
+
+/// How much changed in a scope since a point in time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChangeCounts {
+    pub entities_created: u64,
+    /// Entities updated but not created since the cutoff.
+    pub entities_updated: u64,
+    pub claims: u64,
+    pub relationships: u64,
+}
+
+impl ChangeCounts {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// An entity created or updated since the cutoff.
+#[derive(Clone, Debug)]
+pub struct ChangedEntity {
+    pub entity: Entity,
+    /// Created, rather than only updated, since the cutoff.
+    pub created: bool,
+}
+
+/// Graph changes since a cutoff: full counts, plus the newest changes of each
+/// kind up to the requested limit.
+#[derive(Clone, Debug, Default)]
+pub struct GraphChanges {
+    pub counts: ChangeCounts,
+    /// Newest `last_updated` first.
+    pub entities: Vec<ChangedEntity>,
+    /// Newest ingested first.
+    pub claims: Vec<Claim>,
+    /// Newest created first.
+    pub relationships: Vec<Relationship>,
+}
+
+/// Cypher predicates restricting each query to a scope, over `e` (entities),
+/// `c` (claims) and `s`, `r`, `t` (relationships).
+struct ScopeFilter {
+    entity: &'static str,
+    claim: &'static str,
+    relationship: &'static str,
+}
+
+const ALL: ScopeFilter = ScopeFilter {
+    entity: "true",
+    claim: "true",
+    relationship: "true",
+};
+
+const INVESTIGATION: ScopeFilter = ScopeFilter {
+    entity: "(e.created_by_investigation = $investigation_id \
+             OR EXISTS { MATCH (e)-[:PUBLISHED|REFERENCES]-(:Claim {investigation_id: $investigation_id}) })",
+    claim: "c.investigation_id = $investigation_id",
+    relationship: "r.created_by_investigation = $investigation_id",
+};
+
+const FOCUS: ScopeFilter = ScopeFilter {
+    entity: "e.id IN $neighborhood",
+    claim: "EXISTS { MATCH (c)-[:REFERENCES]->(f:Entity) WHERE f.id IN $focus }",
+    relationship: "(s.id IN $focus OR t.id IN $focus)",
+};
+
+impl super::GraphClient {
+    /// Changes in `scope` since `since`: entities whose `last_updated` is
+    /// later, claims ingested later, and relationships created later. Counts
+    /// cover every change; each list holds at most `limit` of the newest, so
+    /// `limit` 0 fetches counts only.
+    ///
+    /// Entities and relationships written before creation times were recorded
+    /// count as updated, and aren't listed as new relationships.
+    pub async fn changes_since(
+        &self,
+        since: DateTime<Utc>,
+        scope: &ChangeScope,
+        limit: usize,
+    ) -> Result<GraphChanges, GraphError> {
+        let start = std::time::Instant::now();
+
+        let (filter, investigation_id, focus, neighborhood) = match scope {
+            ChangeScope::All => (&ALL, String::new(), Vec::new(), Vec::new()),
+            ChangeScope::Investigation(id) => {
+                (&INVESTIGATION, id.to_string(), Vec::new(), Vec::new())
+            }
+            ChangeScope::Focus(names) => {
+                let names: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+                let focus = self
+                    .collect_ids(
+                        query(
+                            "MATCH (e:Entity) WHERE toLower(e.canonical_name) IN $names \
+                             RETURN e.id AS id",
+                        )
+                        .param("names", names),
+                    )
+                    .await?;
+                let neighborhood = self
+                    .collect_ids(
+                        query(
+                            "MATCH (f:Entity) WHERE f.id IN $focus \
+                             MATCH (f)-[:RELATES_TO*0..1]-(e:Entity) \
+                             RETURN DISTINCT e.id AS id",
+                        )
+                        .param("focus", focus.clone()),
+                    )
+                    .await?;
+                (&FOCUS, String::new(), focus, neighborhood)
+            }
+        };
+        let scoped = |cypher: String| {
+            query(&cypher)
+                .param("since", format_datetime(&since))
+                .param("limit", limit as i64)
+                .param("investigation_id", investigation_id.as_str())
+                .param("focus", focus.clone())
+                .param("neighborhood", neighborhood.clone())
+        };
+
+        let mut changes = GraphChanges::default();
+
+        // 1. Counts.
+        let mut result = self
+            .graph
+            .execute(scoped(format!(
+                "CALL {{ \
+                   MATCH (e:Entity) WHERE e.last_updated > $since AND {entity} \
+                   RETURN count(e) AS entities, \
+                          count(CASE WHEN coalesce(e.created_at, '') > $since THEN 1 END) AS created \
+                 }} \
+                 CALL {{ \
+                   MATCH (c:Claim) WHERE c.ingested_timestamp > $since AND {claim} \
+                   RETURN count(c) AS claims \
+                 }} \
+                 CALL {{ \
+                   MATCH (s:Entity)-[r:RELATES_TO]->(t:Entity) \
+                   WHERE r.created_at > $since AND {relationship} \
+                   RETURN count(r) AS relationships \
+                 }} \
+                 RETURN entities, created, claims, relationships",
+                entity = filter.entity,
+                claim = filter.claim,
+                relationship = filter.relationship,
+            )))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        if let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let column = |name: &str| -> Result<u64, GraphError> {
+                row.get::<i64>(name)
+                    .map(|n| n as u64)
+                    .map_err(|e| GraphError::Query(format!("Missing '{}': {}", name, e)))
+            };
+            let created = column("created")?;
+            changes.counts = ChangeCounts {
+                entities_created: created,
+                entities_updated: column("entities")? - created,
+                claims: column("claims")?,
+                relationships: column("relationships")?,
+            };
+        }
+
+        if limit == 0 {
+            record_read_latency("changes", start);
+            return Ok(changes);
+        }
+
+        // 2. Newest entities.
+        let mut result = self
+            .graph
+            .execute(scoped(format!(
+                "MATCH (e:Entity) WHERE e.last_updated > $since AND {} \
+                 RETURN e, coalesce(e.created_at, '') > $since AS created \
+                 ORDER BY e.last_updated DESC, e.id \
+                 LIMIT $limit",
+                filter.entity
+            )))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("e")
+                .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+            let created: bool = row
+                .get("created")
+                .map_err(|e| GraphError::Query(format!("Missing 'created' column: {}", e)))?;
+            changes.entities.push(ChangedEntity {
+                entity: node_to_entity(&node)?,
+                created,
+            });
+        }
+
+        // 3. Newest claims, with publisher and referenced IDs.
+        let mut result = self
+            .graph
+            .execute(scoped(format!(
+                "MATCH (c:Claim) WHERE c.ingested_timestamp > $since AND {} \
+                 WITH c ORDER BY c.ingested_timestamp DESC, c.id LIMIT $limit \
+                 MATCH (source:Entity)-[:PUBLISHED]->(c) \
+                 OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
+                 WITH c, source, ref ORDER BY ref.id \
+                 RETURN c, source.id AS source_id, collect(ref.id) AS ref_ids \
+                 ORDER BY c.ingested_timestamp DESC, c.id",
+                filter.claim
+            )))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            changes.claims.push(row_to_claim(row)?);
+        }
+
+        // 4. Newest relationships.
+        let mut result = self
+            .graph
+            .execute(scoped(format!(
+                "MATCH (s:Entity)-[r:RELATES_TO]->(t:Entity) \
+                 WHERE r.created_at > $since AND {} \
+                 RETURN r, s.id AS source_id, t.id AS target_id \
+                 ORDER BY r.created_at DESC, r.id \
+                 LIMIT $limit",
+                filter.relationship
+            )))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            changes.relationships.push(row_to_relationship(row)?);
+        }
+
+        record_read_latency("changes", start);
+        Ok(changes)
+    }
+}
@@ -1,16 +1,30 @@
-use neo4rs::query;
+use std::collections::{HashMap, HashSet};
+
+use neo4rs::{query, BoltType};
 
 use autosint_common::types::{AttributionDepth, Claim, InformationType};
 use autosint_common::ClaimId;
 
-use super::conversions::{format_datetime, node_to_claim, parse_entity_id};
+use super::conversions::{format_datetime, node_to_claim, parse_claim_id, parse_entity_id};
+use super::digest::{ClaimDigestEntry, CLAIM_DIGEST_LEN, INSERT_ENTRY};
+use super::writes::record_read_latency;
 use super::GraphError;
 
+/// A finished `merge_claims`: both claims as they stood before it, and the
+/// merged target.
+#[derive(Clone, Debug)]
+pub struct ClaimMerge {
+    pub source: Claim,
+    pub target: Claim,
+    pub merged: Claim,
+}
+
 #[allow(dead_code)]
 impl super::GraphClient {
     /// Create a new claim with PUBLISHED and REFERENCES edges.
     /// Transaction: validates source entity, creates claim node, creates PUBLISHED edge,
     /// creates REFERENCES edges for each referenced entity.
+    /// With create coalescing enabled, concurrent creates are written in one batch.
     pub async fn create_claim(
         &self,
         claim: &Claim,
@@ -18,6 +32,31 @@ impl super::GraphClient {
     ) -> Result<Claim, GraphError> {
         let start = std::time::Instant::now();
 
+        match self.coalescers() {
+            Some(coalesce) => {
+                let client = self.clone();
+                coalesce
+                    .claims
+                    .submit((claim.clone(), embedding), move |batch| {
+                        let client = client.clone();
+                        async move { client.flush_claims(batch).await }
+                    })
+                    .await?
+            }
+            None => self.write_claim(claim, embedding).await?,
+        }
+
+        metrics::histogram!("graph.claim.create.latency").record(start.elapsed().as_secs_f64());
+
+        // Fetch and return the created claim.
+        self.get_claim(claim.id).await
+    }
+
+    async fn write_claim(
+        &self,
+        claim: &Claim,
+        embedding: Option<Vec<f32>>,
+    ) -> Result<(), GraphError> {
         // Validate source entity exists before starting transaction.
         let source_check = query("MATCH (e:Entity {id: $id}) RETURN e.id AS id")
             .param("id", claim.source_entity_id.to_string());
@@ -44,29 +83,16 @@ impl super::GraphClient {
             .map(|v| v.iter().map(|&f| f as f64).collect())
             .unwrap_or_default();
 
-        let attribution_depth_str = match claim.attribution_depth {
-            AttributionDepth::Primary => "primary",
-            AttributionDepth::Secondhand => "secondhand",
-            AttributionDepth::Indirect => "indirect",
-        };
-
-        let information_type_str = match claim.information_type {
-            InformationType::Assertion => "assertion",
-            InformationType::Analysis => "analysis",
-            InformationType::Discourse => "discourse",
-            InformationType::Testimony => "testimony",
-        };
-
         let published_ts = format_datetime(&claim.published_timestamp);
         let ingested_ts = format_datetime(&claim.ingested_timestamp);
         let embedding_pending = !has_embedding;
 
+        let _permit = self.writes.acquire("claim.create").await;
         let mut txn = self
             .graph
             .start_txn()
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
-
         // 1. Create claim node.
         let mut create_cypher = String::from(
             "CREATE (c:Claim { \
@@ -76,7 +102,19 @@ impl super::GraphClient {
                 ingested_timestamp: $ingested_timestamp, \
                 attribution_depth: $attribution_depth, \
                 information_type: $information_type, \
-                embedding_pending: $embedding_pending \
+                embedding_pending: $embedding_pending, \
+                chunks_pending: true, \
+                investigation_id: $investigation_id, \
+                monitor_id: $monitor_id, \
+                language: $language, \
+                original_content: $original_content, \
+                original_language: $original_language, \
+                region: $region, \
+                source_trust_level: $source_trust_level, \
+                auto_linked_entity_ids: $auto_linked_entity_ids, \
+                supports_relationship_ids: $supports_relationship_ids, \
+                redactions: $redactions, \
+                citation: $citation \
             })",
         );
 
@@ -94,9 +132,46 @@ impl super::GraphClient {
             .param("content", claim.content.as_str())
             .param("published_timestamp", published_ts.as_str())
             .param("ingested_timestamp", ingested_ts.as_str())
-            .param("attribution_depth", attribution_depth_str)
-            .param("information_type", information_type_str)
-            .param("embedding_pending", embedding_pending);
+            .param(
+                "attribution_depth",
+                attribution_depth_str(&claim.attribution_depth),
+            )
+            .param(
+                "information_type",
+                information_type_str(&claim.information_type),
+            )
+            .param("embedding_pending", embedding_pending)
+            .param(
+                "investigation_id",
+                claim.investigation_id.map(|id| id.to_string()),
+            )
+            .param("monitor_id", claim.monitor_id.map(|id| id.to_string()))
+            .param("language", claim.language.clone())
+            .param("original_content", claim.original_content.clone())
+            .param("original_language", claim.original_language.clone())
+            .param("region", claim.region.clone())
+            .param(
+                "source_trust_level",
+                claim.source_trust_level.map(i64::from),
+            )
+            .param(
+                "auto_linked_entity_ids",
+                claim
+                    .auto_linked_entity_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .param(
+                "supports_relationship_ids",
+                claim
+                    .supports_relationship_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .param("redactions", redactions_json(claim))
+            .param("citation", citation_json(claim));
 
         if let Some(ref link) = claim.raw_source_link {
             q1 = q1.param("raw_source_link", link.as_str());
@@ -135,14 +210,134 @@ impl super::GraphClient {
                 .map_err(|e| GraphError::Query(e.to_string()))?;
         }
 
+        // 4. Add the claim to the referenced entities' digests.
+        if !claim.referenced_entity_ids.is_empty() {
+            let q4 = query(
+                &[
+                    "MATCH (c:Claim {id: $claim_id})-[:REFERENCES]->(e:Entity) \
+                     WHERE e.claim_digest IS NOT NULL \
+                     WITH DISTINCT e, $digest_entry AS entry ",
+                    INSERT_ENTRY,
+                ]
+                .concat(),
+            )
+            .param("claim_id", claim.id.to_string())
+            .param("digest_entry", ClaimDigestEntry::from_claim(claim).encode())
+            .param("claim_digest_len", CLAIM_DIGEST_LEN as i64);
+
+            txn.run(q4)
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+        }
+
         txn.commit()
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
 
-        metrics::histogram!("graph.claim.create.latency").record(start.elapsed().as_secs_f64());
+        Ok(())
+    }
 
-        // Fetch and return the created claim.
-        self.get_claim(claim.id).await
+    /// Write a coalesced batch of claims, falling back to one write per claim
+    /// for any the batch didn't create.
+    async fn flush_claims(
+        &self,
+        batch: Vec<(Claim, Option<Vec<f32>>)>,
+    ) -> Vec<Result<(), GraphError>> {
+        let created = match self.create_claims(&batch).await {
+            Ok(created) => created,
+            Err(e) => {
+                tracing::warn!(error = %e, claims = batch.len(), "Batched claim create failed, writing individually");
+                HashSet::new()
+            }
+        };
+
+        let mut results = Vec::with_capacity(batch.len());
+        for (claim, embedding) in batch {
+            if created.contains(&claim.id) {
+                results.push(Ok(()));
+            } else {
+                metrics::counter!("graph.write.coalesce_fallbacks", "target" => "claim")
+                    .increment(1);
+                results.push(self.write_claim(&claim, embedding).await);
+            }
+        }
+        results
+    }
+
+    /// Create claims and their PUBLISHED and REFERENCES edges in one UNWIND
+    /// write. Claims whose source entity doesn't exist are skipped; returns
+    /// the IDs created.
+    async fn create_claims(
+        &self,
+        claims: &[(Claim, Option<Vec<f32>>)],
+    ) -> Result<HashSet<ClaimId>, GraphError> {
+        let rows: Vec<HashMap<String, BoltType>> = claims
+            .iter()
+            .map(|(claim, embedding)| claim_row(claim, embedding.as_deref()))
+            .collect();
+
+        let cypher = [
+            "UNWIND $rows AS row \
+             MATCH (e:Entity {id: row.source_entity_id}) \
+             CREATE (c:Claim { \
+                id: row.id, \
+                content: row.content, \
+                published_timestamp: row.published_timestamp, \
+                ingested_timestamp: row.ingested_timestamp, \
+                attribution_depth: row.attribution_depth, \
+                information_type: row.information_type, \
+                embedding_pending: row.embedding_pending, \
+                chunks_pending: true, \
+                investigation_id: row.investigation_id, \
+                monitor_id: row.monitor_id, \
+                language: row.language, \
+                original_content: row.original_content, \
+                original_language: row.original_language, \
+                region: row.region, \
+                source_trust_level: row.source_trust_level, \
+                auto_linked_entity_ids: row.auto_linked_entity_ids, \
+                supports_relationship_ids: row.supports_relationship_ids, \
+                redactions: row.redactions, \
+                citation: row.citation \
+             }) \
+             SET c.raw_source_link = row.raw_source_link, c.embedding = row.embedding \
+             CREATE (e)-[:PUBLISHED]->(c) \
+             WITH c, row \
+             CALL { \
+                WITH c, row \
+                UNWIND row.referenced_entity_ids AS ref_id \
+                MATCH (r:Entity {id: ref_id}) \
+                CREATE (c)-[:REFERENCES]->(r) \
+                WITH r AS e, row.digest_entry AS entry \
+                WHERE e.claim_digest IS NOT NULL ",
+            INSERT_ENTRY,
+            " } \
+             RETURN c.id AS id",
+        ]
+        .concat();
+        let q = query(&cypher)
+            .param("rows", rows)
+            .param("claim_digest_len", CLAIM_DIGEST_LEN as i64);
+
+        let _permit = self.writes.acquire("claim.create_batch").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut created = HashSet::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let id: String = row
+                .get("id")
+                .map_err(|e| GraphError::Query(format!("Missing 'id' column: {}", e)))?;
+            created.insert(parse_claim_id(&id)?);
+        }
+        Ok(created)
     }
 
     /// Get a claim by ID, including source entity and referenced entities from edges.
@@ -189,7 +384,390 @@ impl super::GraphClient {
         let claim = node_to_claim(&node, source_entity_id, referenced_entity_ids)?;
 
         metrics::histogram!("graph.claim.get.latency").record(start.elapsed().as_secs_f64());
+        record_read_latency("claim.get", start);
 
         Ok(claim)
     }
+
+    /// Merge the duplicate claim `source_id` into `target_id` (see
+    /// `Claim::absorb`): the target gains REFERENCES edges to the source's
+    /// entities, the relationships it supports and its provenance, and the
+    /// source and its chunks are deleted.
+    pub async fn merge_claims(
+        &self,
+        source_id: ClaimId,
+        target_id: ClaimId,
+    ) -> Result<ClaimMerge, GraphError> {
+        let start = std::time::Instant::now();
+
+        if source_id == target_id {
+            return Err(GraphError::Query("Cannot merge a claim with itself".into()));
+        }
+        let source = self.get_claim(source_id).await?;
+        let target = self.get_claim(target_id).await?;
+        let mut merged = target.clone();
+        merged.absorb(&source);
+
+        let permit = self.writes.acquire("claim.merge").await;
+        let mut txn = self
+            .graph
+            .start_txn()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        // 1. REFERENCES edges the target lacks.
+        let q1 = query(
+            "MATCH (source:Claim {id: $source_id})-[:REFERENCES]->(e:Entity) \
+             MATCH (target:Claim {id: $target_id}) \
+             WHERE NOT (target)-[:REFERENCES]->(e) \
+             CREATE (target)-[:REFERENCES]->(e)",
+        )
+        .param("source_id", source_id.to_string())
+        .param("target_id", target_id.to_string());
+        txn.run(q1)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        // 2. The target's merged properties.
+        let q2 = query(
+            "MATCH (target:Claim {id: $target_id}) \
+             SET target.published_timestamp = $published_timestamp, \
+                 target.attribution_depth = $attribution_depth, \
+                 target.auto_linked_entity_ids = $auto_linked_entity_ids, \
+                 target.supports_relationship_ids = $supports_relationship_ids, \
+                 target.disputed_count = $disputed_count, \
+                 target.raw_source_link = $raw_source_link, \
+                 target.citation = $citation, \
+                 target.merged_sources = $merged_sources",
+        )
+        .param("target_id", target_id.to_string())
+        .param(
+            "published_timestamp",
+            format_datetime(&merged.published_timestamp),
+        )
+        .param(
+            "attribution_depth",
+            attribution_depth_str(&merged.attribution_depth),
+        )
+        .param(
+            "auto_linked_entity_ids",
+            merged
+                .auto_linked_entity_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .param(
+            "supports_relationship_ids",
+            merged
+                .supports_relationship_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .param("disputed_count", i64::from(merged.disputed_count))
+        .param("raw_source_link", merged.raw_source_link.clone())
+        .param("citation", citation_json(&merged))
+        .param(
+            "merged_sources",
+            serde_json::to_string(&merged.merged_sources).unwrap_or_default(),
+        );
+        txn.run(q2)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        // 3. Delete the source and its chunks.
+        let q3 = query(
+            "MATCH (source:Claim {id: $source_id}) \
+             OPTIONAL MATCH (k:ClaimChunk)-[:PART_OF]->(source) \
+             DETACH DELETE k, source",
+        )
+        .param("source_id", source_id.to_string());
+        txn.run(q3)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        drop(permit);
+
+        metrics::histogram!("graph.claim.merge.latency").record(start.elapsed().as_secs_f64());
+        metrics::counter!("graph.claim.merge_count").increment(1);
+
+        // Digests listed the source; the target's date may have moved.
+        if let Err(e) = self
+            .rebuild_claim_digests(&merged.referenced_entity_ids)
+            .await
+        {
+            tracing::warn!(claim_id = %target_id, error = %e, "Failed to rebuild claim digests after merge");
+        }
+
+        let merged = self.get_claim(target_id).await?;
+        Ok(ClaimMerge {
+            source,
+            target,
+            merged,
+        })
+    }
+
+    /// The earliest-ingested other claim whose content is exactly `content`.
+    pub async fn find_claim_with_content(
+        &self,
+        content: &str,
+        exclude: ClaimId,
+    ) -> Result<Option<ClaimId>, GraphError> {
+        let q = query(
+            "MATCH (c:Claim {content: $content}) \
+             WHERE c.id <> $exclude \
+             RETURN c.id AS id \
+             ORDER BY c.ingested_timestamp \
+             LIMIT 1",
+        )
+        .param("content", content)
+        .param("exclude", exclude.to_string());
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        match result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            Some(row) => {
+                let id: String = row
+                    .get("id")
+                    .map_err(|e| GraphError::Query(format!("Missing 'id' column: {}", e)))?;
+                Ok(Some(parse_claim_id(&id)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Count a dispute of a claim by assessment feedback: bumps
+    /// `disputed_count` and sets `last_disputed_at`. Returns the new count.
+    pub async fn mark_claim_disputed(&self, id: ClaimId) -> Result<u32, GraphError> {
+        let q = query(
+            "MATCH (c:Claim {id: $id}) \
+             SET c.disputed_count = coalesce(c.disputed_count, 0) + 1, \
+                 c.last_disputed_at = $now \
+             RETURN c.disputed_count AS disputed_count",
+        )
+        .param("id", id.to_string())
+        .param("now", format_datetime(&chrono::Utc::now()));
+
+        let _permit = self.writes.acquire("claim.mark_disputed").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let row = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .ok_or_else(|| GraphError::NotFound(format!("Claim {}", id)))?;
+        let count: i64 = row
+            .get("disputed_count")
+            .map_err(|e| GraphError::Query(format!("Missing 'disputed_count' column: {}", e)))?;
+
+        metrics::counter!("graph.claim.disputed").increment(1);
+        Ok(count.max(0) as u32)
+    }
+
+    /// Replace a claim's chunk embeddings: one `ClaimChunk` node per
+    /// `(content, embedding)`, linked `PART_OF` the claim, and clear its
+    /// `chunks_pending` flag. Claims created by this client start flagged;
+    /// the embedding backfill chunks them.
+    pub async fn set_claim_chunks(
+        &self,
+        id: ClaimId,
+        chunks: &[(String, Vec<f32>)],
+    ) -> Result<(), GraphError> {
+        let rows: Vec<HashMap<String, BoltType>> = chunks
+            .iter()
+            .enumerate()
+            .map(|(ordinal, (content, embedding))| {
+                HashMap::from([
+                    ("ordinal".to_string(), (ordinal as i64).into()),
+                    ("content".to_string(), content.as_str().into()),
+                    (
+                        "embedding".to_string(),
+                        embedding
+                            .iter()
+                            .map(|&f| f as f64)
+                            .collect::<Vec<_>>()
+                            .into(),
+                    ),
+                ])
+            })
+            .collect();
+
+        let q = query(
+            "MATCH (c:Claim {id: $id}) \
+             SET c.chunks_pending = false \
+             WITH c \
+             OPTIONAL MATCH (old:ClaimChunk)-[:PART_OF]->(c) \
+             DETACH DELETE old \
+             WITH DISTINCT c \
+             CALL { \
+                WITH c \
+                UNWIND $rows AS row \
+                CREATE (k:ClaimChunk { \
+                   claim_id: c.id, \
+                   ordinal: row.ordinal, \
+                   content: row.content, \
+                   embedding: row.embedding \
+                })-[:PART_OF]->(c) \
+             } \
+             RETURN c.id AS id",
+        )
+        .param("id", id.to_string())
+        .param("rows", rows);
+
+        let _permit = self.writes.acquire("claim.set_chunks").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        if result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .is_none()
+        {
+            return Err(GraphError::NotFound(format!("Claim {}", id)));
+        }
+
+        metrics::counter!("graph.claim.chunks").increment(chunks.len() as u64);
+        Ok(())
+    }
+}
+
+pub(super) fn attribution_depth_str(depth: &AttributionDepth) -> &'static str {
+    match depth {
+        AttributionDepth::Primary => "primary",
+        AttributionDepth::Secondhand => "secondhand",
+        AttributionDepth::Indirect => "indirect",
+    }
+}
+
+pub(super) fn information_type_str(information_type: &InformationType) -> &'static str {
+    match information_type {
+        InformationType::Assertion => "assertion",
+        InformationType::Analysis => "analysis",
+        InformationType::Discourse => "discourse",
+        InformationType::Testimony => "testimony",
+    }
+}
+
+/// A claim's redaction counts as a JSON object string; None when nothing
+/// was redacted, so the property is left unset.
+fn redactions_json(claim: &Claim) -> Option<String> {
+    (!claim.redactions.is_empty())
+        .then(|| serde_json::to_string(&claim.redactions).unwrap_or_default())
+}
+
+/// The claim's citation as stored: a JSON string, like `redactions`.
+fn citation_json(claim: &Claim) -> Option<String> {
+    claim
+        .citation
+        .as_ref()
+        .map(|citation| serde_json::to_string(citation).unwrap_or_default())
+}
+
+/// A claim as an UNWIND row for `create_claims`.
+fn claim_row(claim: &Claim, embedding: Option<&[f32]>) -> HashMap<String, BoltType> {
+    let ids = |ids: Vec<String>| BoltType::from(ids);
+    HashMap::from([
+        ("id".to_string(), claim.id.to_string().into()),
+        ("content".to_string(), claim.content.as_str().into()),
+        (
+            "published_timestamp".to_string(),
+            format_datetime(&claim.published_timestamp).into(),
+        ),
+        (
+            "ingested_timestamp".to_string(),
+            format_datetime(&claim.ingested_timestamp).into(),
+        ),
+        (
+            "attribution_depth".to_string(),
+            attribution_depth_str(&claim.attribution_depth).into(),
+        ),
+        (
+            "information_type".to_string(),
+            information_type_str(&claim.information_type).into(),
+        ),
+        ("embedding_pending".to_string(), embedding.is_none().into()),
+        (
+            "investigation_id".to_string(),
+            claim.investigation_id.map(|id| id.to_string()).into(),
+        ),
+        (
+            "monitor_id".to_string(),
+            claim.monitor_id.map(|id| id.to_string()).into(),
+        ),
+        ("language".to_string(), claim.language.clone().into()),
+        (
+            "original_content".to_string(),
+            claim.original_content.clone().into(),
+        ),
+        (
+            "original_language".to_string(),
+            claim.original_language.clone().into(),
+        ),
+        ("region".to_string(), claim.region.clone().into()),
+        (
+            "source_trust_level".to_string(),
+            claim.source_trust_level.map(i64::from).into(),
+        ),
+        (
+            "auto_linked_entity_ids".to_string(),
+            ids(claim
+                .auto_linked_entity_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect()),
+        ),
+        (
+            "supports_relationship_ids".to_string(),
+            ids(claim
+                .supports_relationship_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect()),
+        ),
+        (
+            "raw_source_link".to_string(),
+            claim.raw_source_link.clone().into(),
+        ),
+        ("redactions".to_string(), redactions_json(claim).into()),
+        ("citation".to_string(), citation_json(claim).into()),
+        (
+            "digest_entry".to_string(),
+            ClaimDigestEntry::from_claim(claim).encode().into(),
+        ),
+        (
+            "embedding".to_string(),
+            embedding
+                .map(|v| v.iter().map(|&f| f as f64).collect::<Vec<_>>())
+                .into(),
+        ),
+        (
+            "source_entity_id".to_string(),
+            claim.source_entity_id.to_string().into(),
+        ),
+        (
+            "referenced_entity_ids".to_string(),
+            ids(claim
+                .referenced_entity_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect()),
+        ),
+    ])
 }
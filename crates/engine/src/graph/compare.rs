@@ -0,0 +1,346 @@
+//! Side-by-side comparison of two entities for merge decisions: how alike
+//! the dedup pipeline would find them, and what they share in the graph.
+
+use neo4rs::query;
+use serde::Serialize;
+
+use autosint_common::config::DedupConfig;
+use autosint_common::types::{identifier_conflicts, Entity, IdentifierScheme};
+use autosint_common::{ClaimId, EntityId};
+
+use super::conversions::parse_entity_id;
+use super::dedup::best_fuzzy_candidate;
+use super::limits::ReadLimit;
+use super::{GraphClient, GraphError};
+
+/// How far below a dedup threshold a score still makes a pair worth a look.
+const UNCLEAR_MARGIN: f64 = 0.1;
+
+/// Shared neighbors that make an otherwise dissimilar pair worth a look.
+const UNCLEAR_SHARED_NEIGHBORS: u64 = 2;
+
+/// Whether two entities look like the same thing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchBand {
+    LikelySame,
+    Unclear,
+    LikelyDifferent,
+}
+
+impl MatchBand {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LikelySame => "likely_same",
+            Self::Unclear => "unclear",
+            Self::LikelyDifferent => "likely_different",
+        }
+    }
+}
+
+/// Similarity of two entities by the signals the dedup pipeline uses.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PairScores {
+    /// Best Jaro-Winkler similarity between any name of one and any of the
+    /// other (canonical name and aliases, case-insensitive).
+    pub name_similarity: f64,
+    /// Names (lowercased) both carry.
+    pub shared_names: Vec<String>,
+    /// Cosine similarity of the embeddings, when both have one.
+    pub embedding_similarity: Option<f64>,
+    /// Schemes under which both carry the same identifier.
+    pub shared_identifiers: Vec<IdentifierScheme>,
+    /// Schemes under which they carry different identifiers.
+    pub conflicting_identifiers: Vec<IdentifierScheme>,
+    pub same_kind: bool,
+}
+
+/// Score `a` against `b`.
+pub fn score_pair(a: &Entity, b: &Entity) -> PairScores {
+    let names = |e: &Entity| -> Vec<String> {
+        let mut names: Vec<String> = std::iter::once(&e.canonical_name)
+            .chain(e.aliases.iter())
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    };
+    let (ours, theirs) = (names(a), names(b));
+    let name_similarity = ours
+        .iter()
+        .flat_map(|x| theirs.iter().map(move |y| strsim::jaro_winkler(x, y)))
+        .fold(0.0, f64::max);
+    let shared_names = ours.into_iter().filter(|n| theirs.contains(n)).collect();
+
+    let embedding_similarity = match (&a.embedding, &b.embedding) {
+        (Some(x), Some(y)) => cosine(x, y),
+        _ => None,
+    };
+    let shared_identifiers = a
+        .identifiers
+        .iter()
+        .filter(|(scheme, value)| b.identifiers.get(scheme) == Some(value))
+        .map(|(scheme, _)| *scheme)
+        .collect();
+
+    PairScores {
+        name_similarity,
+        shared_names,
+        embedding_similarity,
+        shared_identifiers,
+        conflicting_identifiers: identifier_conflicts(&a.identifiers, &b.identifiers),
+        same_kind: a.kind.trim().eq_ignore_ascii_case(b.kind.trim()),
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> Option<f64> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let denom = norm(a) * norm(b);
+    (denom > 0.0).then(|| dot / denom)
+}
+
+/// The band the dedup pipeline's stages put `b` in as a candidate for `a`,
+/// and why. Conflicting identifiers rule a match out, as in the pipeline;
+/// otherwise the first stage that would match (shared identifier, shared
+/// name, fuzzy name, embedding) makes it likely the same, unless the kinds
+/// differ. Scores within `UNCLEAR_MARGIN` of a threshold, or several shared
+/// neighbors, make it unclear.
+pub fn match_band(
+    a: &Entity,
+    b: &Entity,
+    scores: &PairScores,
+    shared_neighbors: u64,
+    config: &DedupConfig,
+) -> (MatchBand, &'static str) {
+    if !scores.conflicting_identifiers.is_empty() {
+        return (MatchBand::LikelyDifferent, "identifiers conflict");
+    }
+
+    let fuzzy = best_fuzzy_candidate(
+        &[(b.id, scores.name_similarity, a.is_seeded || b.is_seeded)],
+        &a.kind,
+        config,
+    )
+    .is_some();
+    let embedding = scores.embedding_similarity.unwrap_or(0.0);
+    let matched_by = if !scores.shared_identifiers.is_empty() {
+        Some("shared identifier")
+    } else if !scores.shared_names.is_empty() {
+        Some("shared name")
+    } else if fuzzy {
+        Some("similar names")
+    } else if embedding >= config.embedding_threshold {
+        Some("similar embeddings")
+    } else {
+        None
+    };
+    match matched_by {
+        Some(_) if !scores.same_kind => (MatchBand::Unclear, "matching but of different kinds"),
+        Some(reason) => (MatchBand::LikelySame, reason),
+        None if scores.name_similarity >= config.fuzzy_threshold - UNCLEAR_MARGIN => {
+            (MatchBand::Unclear, "names somewhat similar")
+        }
+        None if embedding >= config.embedding_threshold - UNCLEAR_MARGIN => {
+            (MatchBand::Unclear, "embeddings somewhat similar")
+        }
+        None if shared_neighbors >= UNCLEAR_SHARED_NEIGHBORS => {
+            (MatchBand::Unclear, "dissimilar but closely connected")
+        }
+        None => (MatchBand::LikelyDifferent, "no dedup signal"),
+    }
+}
+
+/// What two entities have in common in the graph.
+#[derive(Clone, Debug, Default)]
+pub struct SharedNeighbors {
+    /// Entities both relate to, with their names, up to the limit.
+    pub entities: Vec<(EntityId, String)>,
+    pub entity_total: u64,
+    /// Claims that mention both, up to the limit.
+    pub claims: Vec<ClaimId>,
+    pub claim_total: u64,
+}
+
+impl SharedNeighbors {
+    pub fn total(&self) -> u64 {
+        self.entity_total + self.claim_total
+    }
+}
+
+impl GraphClient {
+    /// Entities `a` and `b` both have a RELATES_TO edge with, and claims that
+    /// publish or reference both, each listing at most `limit`.
+    pub async fn shared_neighbors(
+        &self,
+        a: EntityId,
+        b: EntityId,
+        limit: u32,
+    ) -> Result<SharedNeighbors, GraphError> {
+        let limit = ReadLimit::new(
+            Some(limit),
+            limit,
+            self.reads.max_traversal_results,
+            "shared_neighbors",
+        )
+        .limit;
+        let q = query(
+            "MATCH (a:Entity {id: $a}), (b:Entity {id: $b}) \
+             OPTIONAL MATCH (a)-[:RELATES_TO]-(n:Entity)-[:RELATES_TO]-(b) \
+             WHERE n <> a AND n <> b \
+             WITH a, b, collect(DISTINCT n) AS entities \
+             OPTIONAL MATCH (a)-[:PUBLISHED|REFERENCES]-(c:Claim)-[:PUBLISHED|REFERENCES]-(b) \
+             WITH entities, collect(DISTINCT c) AS claims \
+             RETURN size(entities) AS entity_total, \
+                    [n IN entities[0..$limit] | n.id] AS entity_ids, \
+                    [n IN entities[0..$limit] | n.canonical_name] AS entity_names, \
+                    size(claims) AS claim_total, \
+                    [c IN claims[0..$limit] | c.id] AS claim_ids",
+        )
+        .param("a", a.to_string())
+        .param("b", b.to_string())
+        .param("limit", limit as i64);
+
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        else {
+            return Ok(SharedNeighbors::default());
+        };
+
+        let get = |column: &str| -> Result<Vec<String>, GraphError> {
+            row.get(column)
+                .map_err(|e| GraphError::Query(format!("Missing '{}': {}", column, e)))
+        };
+        let count = |column: &str| -> Result<u64, GraphError> {
+            row.get::<i64>(column)
+                .map(|n| n.max(0) as u64)
+                .map_err(|e| GraphError::Query(format!("Missing '{}': {}", column, e)))
+        };
+        let entities = get("entity_ids")?
+            .iter()
+            .map(|id| parse_entity_id(id))
+            .zip(get("entity_names")?)
+            .map(|(id, name)| id.map(|id| (id, name)))
+            .collect::<Result<_, _>>()?;
+        let claims = get("claim_ids")?
+            .iter()
+            .map(|id| {
+                id.parse::<uuid::Uuid>()
+                    .map(ClaimId::from_uuid)
+                    .map_err(|e| GraphError::Query(format!("Invalid claim id {}: {}", id, e)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SharedNeighbors {
+            entities,
+            entity_total: count("entity_total")?,
+            claims,
+            claim_total: count("claim_total")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autosint_common::types::IdentifierScheme;
+
+    fn config() -> DedupConfig {
+        DedupConfig {
+            fuzzy_threshold: 0.85,
+            embedding_threshold: 0.90,
+            seeded_geo_fuzzy_threshold: 0.80,
+            relationship_description_threshold: 0.95,
+            duplicate_relationships: Default::default(),
+            fresh_entities: Default::default(),
+            merge_exact_duplicate_claims: false,
+        }
+    }
+
+    fn org(name: &str) -> Entity {
+        Entity::new(name.into(), "organization".into())
+    }
+
+    fn band(a: &Entity, b: &Entity, shared: u64) -> MatchBand {
+        let scores = score_pair(a, b);
+        match_band(a, b, &scores, shared, &config()).0
+    }
+
+    #[test]
+    fn test_bands_follow_dedup_stages() {
+        let mut a = org("Gulf Star Shipping LLC");
+        a.aliases = vec!["Gulf Star".into()];
+        let mut b = org("GULF STAR");
+        b.aliases = vec!["Gulf Star Shipping Co".into()];
+        let scores = score_pair(&a, &b);
+        assert_eq!(scores.shared_names, vec!["gulf star".to_string()]);
+        assert_eq!(
+            match_band(&a, &b, &scores, 0, &config()),
+            (MatchBand::LikelySame, "shared name")
+        );
+
+        // Fuzzy names only.
+        assert_eq!(
+            band(&org("Rosneft Trading SA"), &org("Rosneft Trading S.A."), 0),
+            MatchBand::LikelySame
+        );
+
+        // Conflicting identifiers override everything.
+        let mut x = org("Aurora Holdings");
+        x.identifiers
+            .insert(IdentifierScheme::Lei, "5493001KJTIIGC8Y1R12".into());
+        let mut y = x.clone();
+        y.id = EntityId::new();
+        y.identifiers
+            .insert(IdentifierScheme::Lei, "529900T8BM49AURSDO55".into());
+        assert_eq!(band(&x, &y, 5), MatchBand::LikelyDifferent);
+
+        // A shared identifier matches despite unrelated names.
+        let mut z = org("Borealis Trading");
+        z.identifiers = x.identifiers.clone();
+        assert_eq!(
+            match_band(&x, &z, &score_pair(&x, &z), 0, &config()).1,
+            "shared identifier"
+        );
+
+        // Same name, different kinds.
+        let vessel = Entity::new("Gulf Star".into(), "vessel".into());
+        assert_eq!(band(&a, &vessel, 0), MatchBand::Unclear);
+
+        // Dissimilar names: different unless closely connected.
+        let (p, q) = (org("Lloyd's List"), org("Meridian Tankers"));
+        assert_eq!(band(&p, &q, 0), MatchBand::LikelyDifferent);
+        assert_eq!(band(&p, &q, 3), MatchBand::Unclear);
+    }
+
+    #[test]
+    fn test_embedding_similarity() {
+        let mut a = org("Northwind Group");
+        let mut b = org("Southwind Holdings PLC");
+        assert_eq!(score_pair(&a, &b).embedding_similarity, None);
+
+        a.embedding = Some(vec![1.0, 0.0, 0.0]);
+        b.embedding = Some(vec![0.95, 0.05, 0.0]);
+        let scores = score_pair(&a, &b);
+        assert!(scores.embedding_similarity.unwrap() > 0.99);
+        assert_eq!(
+            match_band(&a, &b, &scores, 0, &config()),
+            (MatchBand::LikelySame, "similar embeddings")
+        );
+
+        b.embedding = Some(vec![0.0, 1.0, 0.0]);
+        assert_eq!(score_pair(&a, &b).embedding_similarity, Some(0.0));
+    }
+}
@@ -0,0 +1,576 @@
+//! Graph consistency checks: read-only Cypher queries that find data LLM-driven
+//! writes have left inconsistent, with safe repairs for a subset of them.
+//!
+//! A check is a `ConsistencyCheck` listed in `CHECKS`. Its query returns one
+//! row per candidate with the offending node's or relationship's `id`; checks
+//! that need more than Cypher can test narrow the candidates in Rust. Repairs
+//! re-test their condition, so a violation fixed concurrently is skipped.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use neo4rs::{query, BoltType, Row};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use autosint_common::api::engine::{
+    ConsistencyCheckResult, ConsistencyRepairResult, ConsistencySeverity, GraphConsistencyReport,
+    GraphRepairReport,
+};
+use autosint_common::config::GraphConsistencyConfig;
+
+use super::conversions::build_aliases_text;
+use super::digest::{digest_repair_row, CLAIM_DIGEST_LEN, DIGEST_CHECK, DIGEST_REPAIR};
+use super::{GraphClient, GraphError};
+use crate::maintenance::ReadOnlyMode;
+use crate::store::StoreClient;
+use crate::supervisor;
+
+/// One consistency check.
+pub trait ConsistencyCheck: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// What a violation is, for reports.
+    fn description(&self) -> &'static str;
+
+    fn severity(&self) -> ConsistencySeverity;
+
+    /// Cypher returning each candidate violation's `id`. May use `$min_claims`,
+    /// `$max_references` and `$claim_digest_len`.
+    fn query(&self) -> &'static str;
+
+    /// Whether a candidate row is a violation. Default: every candidate is.
+    fn is_violation(&self, _row: &Row) -> bool {
+        true
+    }
+
+    /// Cypher fixing a batch of violations: `UNWIND $rows AS row`, re-testing
+    /// the violation, returning `count(*) AS repaired`. None when there is no
+    /// safe repair.
+    fn repair(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The `$rows` entry fixing one violation, or None if it can't be fixed
+    /// safely. Default: its ID.
+    fn repair_row(&self, row: &Row) -> Option<HashMap<String, BoltType>> {
+        let id: String = row.get("id").ok()?;
+        Some(HashMap::from([("id".to_string(), id.into())]))
+    }
+}
+
+/// Every check, in report order.
+pub static CHECKS: &[&dyn ConsistencyCheck] = &[
+    &OrphanedClaims,
+    &DanglingAutoLinks,
+    &MalformedAliases,
+    &StaleRelationshipEmbeddingPending,
+    &OverdueStubs,
+    &OverReferencedClaims,
+    &StaleClaimDigests,
+];
+
+/// Claims with no PUBLISHED edge: their source entity was deleted out from
+/// under them. Which entity published them is lost, so there's no repair.
+struct OrphanedClaims;
+
+impl ConsistencyCheck for OrphanedClaims {
+    fn name(&self) -> &'static str {
+        "orphaned_claims"
+    }
+
+    fn description(&self) -> &'static str {
+        "Claims with no source entity (no incoming PUBLISHED edge)"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Error
+    }
+
+    fn query(&self) -> &'static str {
+        "MATCH (c:Claim) WHERE NOT EXISTS { MATCH (:Entity)-[:PUBLISHED]->(c) } \
+         RETURN c.id AS id"
+    }
+}
+
+/// Claims whose `auto_linked_entity_ids` name entities that no longer exist,
+/// typically merged away: the REFERENCES edge moved to the merge target but
+/// the property still holds the old ID.
+struct DanglingAutoLinks;
+
+impl ConsistencyCheck for DanglingAutoLinks {
+    fn name(&self) -> &'static str {
+        "dangling_auto_links"
+    }
+
+    fn description(&self) -> &'static str {
+        "Claims whose auto-linked entity IDs name missing (e.g. merged) entities"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Warning
+    }
+
+    fn query(&self) -> &'static str {
+        "MATCH (c:Claim) WHERE size(coalesce(c.auto_linked_entity_ids, [])) > 0 \
+         AND any(linked IN c.auto_linked_entity_ids WHERE NOT EXISTS { \
+             MATCH (e:Entity {id: linked}) \
+         }) \
+         RETURN c.id AS id"
+    }
+}
+
+/// Entities whose `aliases` property isn't a JSON array of strings, which
+/// reads back as no aliases. Fixable when the aliases can be recovered.
+struct MalformedAliases;
+
+impl ConsistencyCheck for MalformedAliases {
+    fn name(&self) -> &'static str {
+        "malformed_aliases"
+    }
+
+    fn description(&self) -> &'static str {
+        "Entities whose aliases property is not a JSON array of strings"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Warning
+    }
+
+    fn query(&self) -> &'static str {
+        "MATCH (e:Entity) WHERE e.aliases IS NOT NULL \
+         RETURN e.id AS id, e.aliases AS aliases, e.last_updated AS last_updated"
+    }
+
+    fn is_violation(&self, row: &Row) -> bool {
+        match row.get::<Value>("aliases") {
+            Ok(Value::String(json)) => serde_json::from_str::<Vec<String>>(&json).is_err(),
+            Ok(_) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// Skipped if the entity was updated since it was read.
+    fn repair(&self) -> Option<&'static str> {
+        Some(
+            "UNWIND $rows AS row \
+             MATCH (e:Entity {id: row.id}) \
+             WHERE e.last_updated = row.last_updated \
+             SET e.aliases = row.aliases, e.aliases_text = row.aliases_text \
+             RETURN count(e) AS repaired",
+        )
+    }
+
+    fn repair_row(&self, row: &Row) -> Option<HashMap<String, BoltType>> {
+        let id: String = row.get("id").ok()?;
+        let last_updated: String = row.get("last_updated").ok()?;
+        let aliases = recover_aliases(&row.get::<Value>("aliases").ok()?)?;
+        let json = serde_json::to_string(&aliases).ok()?;
+        Some(HashMap::from([
+            ("id".to_string(), id.into()),
+            ("last_updated".to_string(), last_updated.into()),
+            ("aliases".to_string(), json.into()),
+            (
+                "aliases_text".to_string(),
+                build_aliases_text(&aliases).into(),
+            ),
+        ]))
+    }
+}
+
+/// Recover an alias list from a malformed `aliases` value: a native list, a
+/// JSON array with non-string scalars, or a JSON-encoded JSON string. None
+/// when there's nothing trustworthy to recover.
+pub(crate) fn recover_aliases(value: &Value) -> Option<Vec<String>> {
+    fn from_array(items: &[Value]) -> Option<Vec<String>> {
+        items
+            .iter()
+            .filter(|item| !item.is_null())
+            .map(|item| match item {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                Value::Bool(b) => Some(b.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    match value {
+        Value::Array(items) => from_array(items),
+        Value::String(json) => match serde_json::from_str::<Value>(json).ok()? {
+            Value::Array(items) => from_array(&items),
+            // Encoded twice: the inner string must itself be a valid array.
+            Value::String(inner) => serde_json::from_str::<Vec<String>>(&inner).ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Relationships flagged for embedding that already have one. The flag is only
+/// set on relationships created without an embedding, so the backfill would
+/// re-embed these for nothing.
+struct StaleRelationshipEmbeddingPending;
+
+impl ConsistencyCheck for StaleRelationshipEmbeddingPending {
+    fn name(&self) -> &'static str {
+        "stale_relationship_embedding_pending"
+    }
+
+    fn description(&self) -> &'static str {
+        "Relationships flagged embedding_pending that already have an embedding"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Info
+    }
+
+    fn query(&self) -> &'static str {
+        "MATCH ()-[r:RELATES_TO]->() \
+         WHERE r.embedding_pending = true AND r.embedding IS NOT NULL \
+         RETURN r.id AS id"
+    }
+
+    fn repair(&self) -> Option<&'static str> {
+        Some(
+            "UNWIND $rows AS row \
+             MATCH ()-[r:RELATES_TO {id: row.id}]->() \
+             WHERE r.embedding_pending = true AND r.embedding IS NOT NULL \
+             SET r.embedding_pending = false \
+             RETURN count(r) AS repaired",
+        )
+    }
+}
+
+/// Stubs that have grown a summary and at least `unstub_min_claims` claims
+/// (published by or referencing them): full entities still marked as
+/// placeholders.
+struct OverdueStubs;
+
+impl ConsistencyCheck for OverdueStubs {
+    fn name(&self) -> &'static str {
+        "overdue_stubs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stub entities with a summary and enough claims to be full entities"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Info
+    }
+
+    fn query(&self) -> &'static str {
+        "MATCH (e:Entity) \
+         WHERE e.is_stub = true AND trim(coalesce(e.summary, '')) <> '' \
+         AND COUNT { MATCH (e)-[:PUBLISHED|REFERENCES]-(c:Claim) RETURN DISTINCT c } \
+             >= $min_claims \
+         RETURN e.id AS id"
+    }
+
+    fn repair(&self) -> Option<&'static str> {
+        Some(
+            "UNWIND $rows AS row \
+             MATCH (e:Entity {id: row.id}) \
+             WHERE e.is_stub = true \
+             SET e.is_stub = false \
+             RETURN count(e) AS repaired",
+        )
+    }
+}
+
+/// Claims referencing more entities than `graph_writes.max_claim_references`,
+/// written before the cap or by a path that bypasses it. Which references
+/// matter is a judgement call, so there's no repair.
+struct OverReferencedClaims;
+
+impl ConsistencyCheck for OverReferencedClaims {
+    fn name(&self) -> &'static str {
+        "over_referenced_claims"
+    }
+
+    fn description(&self) -> &'static str {
+        "Claims referencing more entities than the per-claim limit"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Warning
+    }
+
+    fn query(&self) -> &'static str {
+        "MATCH (c:Claim) \
+         WHERE COUNT { MATCH (c)-[:REFERENCES]->(:Entity) } > $max_references \
+         RETURN c.id AS id"
+    }
+}
+
+/// Entities whose claim digest is missing or no longer holds their most
+/// recently ingested claims: written before digests existed, or missed by a
+/// write that moved REFERENCES edges. Rebuilt from the claims, skipping
+/// digests a claim write changed since the check read them.
+struct StaleClaimDigests;
+
+impl ConsistencyCheck for StaleClaimDigests {
+    fn name(&self) -> &'static str {
+        "stale_claim_digests"
+    }
+
+    fn description(&self) -> &'static str {
+        "Entities whose claim digest is missing or out of date"
+    }
+
+    fn severity(&self) -> ConsistencySeverity {
+        ConsistencySeverity::Info
+    }
+
+    fn query(&self) -> &'static str {
+        DIGEST_CHECK
+    }
+
+    fn is_violation(&self, row: &Row) -> bool {
+        digest_repair_row(row).is_some()
+    }
+
+    fn repair(&self) -> Option<&'static str> {
+        Some(DIGEST_REPAIR)
+    }
+
+    fn repair_row(&self, row: &Row) -> Option<HashMap<String, BoltType>> {
+        digest_repair_row(row)
+    }
+}
+
+impl GraphClient {
+    /// Run every consistency check, exporting violation counts as
+    /// `graph.consistency.violations` gauges. Read-only.
+    pub async fn check_consistency(
+        &self,
+        config: &GraphConsistencyConfig,
+    ) -> Result<GraphConsistencyReport, GraphError> {
+        let start = Instant::now();
+        let mut checks = Vec::with_capacity(CHECKS.len());
+
+        for check in CHECKS {
+            let mut violations = 0u64;
+            let mut sample_ids = Vec::new();
+            self.scan_violations(*check, config, |row| {
+                violations += 1;
+                if sample_ids.len() < config.sample_size {
+                    if let Ok(id) = row.get::<String>("id") {
+                        sample_ids.push(id);
+                    }
+                }
+            })
+            .await?;
+
+            metrics::gauge!(
+                "graph.consistency.violations",
+                "check" => check.name(),
+                "severity" => check.severity().as_str()
+            )
+            .set(violations as f64);
+            checks.push(ConsistencyCheckResult {
+                name: check.name().to_string(),
+                description: check.description().to_string(),
+                severity: check.severity(),
+                violations,
+                sample_ids,
+                repairable: check.repair().is_some(),
+            });
+        }
+
+        let report = GraphConsistencyReport {
+            checks,
+            duration_ms: start.elapsed().as_millis() as u64,
+        };
+        metrics::histogram!("graph.consistency.duration").record(start.elapsed().as_secs_f64());
+        Ok(report)
+    }
+
+    /// Apply the safe repairs, `repair_batch_size` fixes per transaction.
+    /// With `dry_run`, only count what would be fixed.
+    pub async fn repair_consistency(
+        &self,
+        config: &GraphConsistencyConfig,
+        dry_run: bool,
+    ) -> Result<GraphRepairReport, GraphError> {
+        let mut repairs = Vec::new();
+
+        for check in CHECKS {
+            let Some(repair) = check.repair() else {
+                continue;
+            };
+
+            let mut rows = Vec::new();
+            self.scan_violations(*check, config, |row| rows.extend(check.repair_row(&row)))
+                .await?;
+
+            let mut repaired = 0u64;
+            if !dry_run {
+                for batch in rows.chunks(config.repair_batch_size.max(1)) {
+                    repaired += self.repair_batch(repair, batch.to_vec()).await?;
+                }
+                metrics::counter!("graph.consistency.repaired", "check" => check.name())
+                    .increment(repaired);
+                if repaired > 0 {
+                    tracing::info!(
+                        check = check.name(),
+                        repaired,
+                        "Repaired graph inconsistencies"
+                    );
+                }
+            }
+
+            repairs.push(ConsistencyRepairResult {
+                name: check.name().to_string(),
+                repairable: rows.len() as u64,
+                repaired,
+            });
+        }
+
+        Ok(GraphRepairReport { dry_run, repairs })
+    }
+
+    /// Run `check`'s query and pass each violating row to `visit`.
+    async fn scan_violations(
+        &self,
+        check: &dyn ConsistencyCheck,
+        config: &GraphConsistencyConfig,
+        mut visit: impl FnMut(Row),
+    ) -> Result<(), GraphError> {
+        let error = |e: neo4rs::Error| {
+            GraphError::Query(format!("Consistency check {}: {}", check.name(), e))
+        };
+        let mut result = self
+            .graph
+            .execute(
+                query(check.query())
+                    .param("min_claims", config.unstub_min_claims as i64)
+                    .param("max_references", self.max_claim_references as i64)
+                    .param("claim_digest_len", CLAIM_DIGEST_LEN as i64),
+            )
+            .await
+            .map_err(error)?;
+        while let Some(row) = result.next().await.map_err(error)? {
+            if check.is_violation(&row) {
+                visit(row);
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) async fn repair_batch(
+        &self,
+        repair: &str,
+        rows: Vec<HashMap<String, BoltType>>,
+    ) -> Result<u64, GraphError> {
+        let _permit = self.writes.acquire("consistency.repair").await;
+        let mut result = self
+            .graph
+            .execute(query(repair).param("rows", rows))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let repaired = match result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            Some(row) => row.get::<i64>("repaired").unwrap_or(0).max(0) as u64,
+            None => 0,
+        };
+        Ok(repaired)
+    }
+}
+
+/// Spawn the periodic consistency check, which keeps the violation gauges
+/// current so drift shows up over time. Checks hold the `graph_consistency`
+/// singleton lock in `store`.
+pub fn spawn_consistency_check_task(
+    graph: Arc<GraphClient>,
+    store: Arc<StoreClient>,
+    config: GraphConsistencyConfig,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.check_interval_minutes as u64 * 60);
+
+    supervisor::spawn_supervised_restarting("graph_consistency", move || {
+        let graph = Arc::clone(&graph);
+        let store = Arc::clone(&store);
+        let config = config.clone();
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                interval_minutes = config.check_interval_minutes,
+                "Graph consistency check task started"
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping graph consistency check");
+                    continue;
+                }
+
+                let check = async {
+                    match graph.check_consistency(&config).await {
+                        Ok(report) => tracing::info!(
+                            errors = report.violations(ConsistencySeverity::Error),
+                            warnings = report.violations(ConsistencySeverity::Warning),
+                            info = report.violations(ConsistencySeverity::Info),
+                            duration_ms = report.duration_ms,
+                            "Graph consistency checked"
+                        ),
+                        Err(e) => {
+                            tracing::error!(error = %e, "Graph consistency check failed");
+                        }
+                    }
+                };
+                if let Err(e) = store.try_run_exclusive("graph_consistency", check).await {
+                    tracing::error!(error = %e, "Failed to take the graph consistency lock");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_recover_aliases() {
+        // Native list, as written by a hand-rolled Cypher update.
+        assert_eq!(
+            recover_aliases(&json!(["ACME", "Acme Corp"])),
+            Some(vec!["ACME".to_string(), "Acme Corp".to_string()])
+        );
+        // Non-string scalars are stringified and nulls dropped.
+        assert_eq!(
+            recover_aliases(&json!("[\"ACME\", 1947, null]")),
+            Some(vec!["ACME".to_string(), "1947".to_string()])
+        );
+        // Encoded twice.
+        assert_eq!(
+            recover_aliases(&json!("\"[\\\"ACME\\\"]\"")),
+            Some(vec!["ACME".to_string()])
+        );
+
+        // Nothing trustworthy to recover.
+        assert_eq!(recover_aliases(&json!("ACME, Acme Corp")), None);
+        assert_eq!(recover_aliases(&json!("[\"ACME\", {\"x\": 1}]")), None);
+        assert_eq!(recover_aliases(&json!("\"ACME\"")), None);
+        assert_eq!(recover_aliases(&json!(42)), None);
+    }
+
+    #[test]
+    fn test_check_names_unique() {
+        let mut names: Vec<&str> = CHECKS.iter().map(|c| c.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), CHECKS.len());
+    }
+}
@@ -7,8 +7,11 @@ use neo4rs::{Node, Relation, Row};
 use serde_json::Value;
 use uuid::Uuid;
 
-use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
-use autosint_common::{ClaimId, EntityId, RelationshipId};
+use autosint_common::types::{
+    AttributionDepth, Citation, Claim, Entity, IdentifierScheme, Identifiers, InformationType,
+    Relationship,
+};
+use autosint_common::{ClaimId, EntityId, InvestigationId, MonitorId, RelationshipId};
 
 use super::GraphError;
 
@@ -32,6 +35,13 @@ pub fn parse_relationship_id(s: &str) -> Result<RelationshipId, GraphError> {
     Ok(RelationshipId::from_uuid(parse_uuid(s)?))
 }
 
+/// Parse an optional provenance investigation ID. Malformed values are ignored
+/// rather than failing the read — provenance is advisory.
+pub fn parse_optional_investigation_id(s: Option<String>) -> Option<InvestigationId> {
+    s.and_then(|s| Uuid::parse_str(&s).ok())
+        .map(InvestigationId::from_uuid)
+}
+
 // ---------------------------------------------------------------------------
 // DateTime helpers
 // ---------------------------------------------------------------------------
@@ -61,41 +71,125 @@ pub fn parse_aliases(json_str: &str) -> Vec<String> {
 }
 
 // ---------------------------------------------------------------------------
-// Freeform property flattening
+// Identifiers
 // ---------------------------------------------------------------------------
 
-/// Flatten a HashMap<String, Value> into (prop_key, neo4j-safe-string) pairs.
-/// Nested JSON values are serialized as strings.
-pub fn flatten_properties(properties: &HashMap<String, Value>) -> Vec<(String, String)> {
-    properties
+/// Node key prefix of an entity identifier. Each scheme has its own key, and
+/// its own index, so identifier lookups never scan.
+pub const IDENTIFIER_PREFIX: &str = "ident_";
+
+/// Node key holding an entity's identifier under `scheme`, e.g. `ident_lei`.
+pub fn identifier_node_key(scheme: IdentifierScheme) -> String {
+    format!("{}{}", IDENTIFIER_PREFIX, scheme.as_str())
+}
+
+/// Identifiers as node keys and values, for `SET e += $map`.
+pub fn identifier_node_map(identifiers: &Identifiers) -> HashMap<String, String> {
+    identifiers
         .iter()
-        .map(|(key, value)| {
-            let flat_value = match value {
-                Value::String(s) => s.clone(),
-                Value::Null => String::new(),
-                other => other.to_string(),
-            };
-            (format!("prop_{}", key), flat_value)
+        .map(|(scheme, value)| (identifier_node_key(*scheme), value.clone()))
+        .collect()
+}
+
+fn node_identifiers(node: &Node) -> Identifiers {
+    IdentifierScheme::ALL
+        .into_iter()
+        .filter_map(|scheme| {
+            node_get_optional::<String>(node, &identifier_node_key(scheme))
+                .map(|value| (scheme, value))
         })
         .collect()
 }
 
-/// Collect all prop_* properties from a Node back into a HashMap.
-/// Uses Node::get which returns Result — errors are treated as missing values.
-pub fn unflatten_properties_from_node(node: &Node) -> HashMap<String, Value> {
+// ---------------------------------------------------------------------------
+// Freeform property flattening
+// ---------------------------------------------------------------------------
+
+/// Node key prefix of a flattened property's value.
+pub const PROPERTY_PREFIX: &str = "prop_";
+/// Node key prefix of a flattened property's type tag.
+pub const PROPERTY_TYPE_PREFIX: &str = "ptype_";
+/// Type tag of a value stored as the string itself.
+const PROPERTY_TYPE_STRING: &str = "string";
+/// Type tag of a value stored as JSON text (numbers, booleans, null, arrays, objects).
+const PROPERTY_TYPE_JSON: &str = "json";
+
+/// Node keys holding a freeform property: its value and its type tag.
+pub fn property_node_keys(key: &str) -> (String, String) {
+    (
+        format!("{}{}", PROPERTY_PREFIX, key),
+        format!("{}{}", PROPERTY_TYPE_PREFIX, key),
+    )
+}
+
+/// Quote a node key for use as a Cypher identifier, e.g. `` e.`prop_x` ``.
+pub fn quote_key(key: &str) -> String {
+    format!("`{}`", key.replace('`', "``"))
+}
+
+/// Flatten a HashMap<String, Value> into (node key, neo4j-safe-string) pairs:
+/// `prop_<key>` holds the value (strings as-is, anything else as JSON text)
+/// and `ptype_<key>` its type tag, so `unflatten_properties` restores it exactly.
+pub fn flatten_properties(properties: &HashMap<String, Value>) -> Vec<(String, String)> {
+    let mut flat = Vec::with_capacity(properties.len() * 2);
+    for (key, value) in properties {
+        let (value_key, type_key) = property_node_keys(key);
+        let (flat_value, type_tag) = match value {
+            Value::String(s) => (s.clone(), PROPERTY_TYPE_STRING),
+            other => (other.to_string(), PROPERTY_TYPE_JSON),
+        };
+        flat.push((value_key, flat_value));
+        flat.push((type_key, type_tag.to_string()));
+    }
+    flat
+}
+
+/// Inverse of `flatten_properties` over a node's string-valued keys. Values
+/// without a type tag were written before tags existed and are read the old
+/// way: as JSON if they parse, otherwise as a plain string.
+pub fn unflatten_properties(flat: &HashMap<String, String>) -> HashMap<String, Value> {
     let mut props = HashMap::new();
-    for key in node.keys() {
-        if let Some(stripped) = key.strip_prefix("prop_") {
-            if let Ok(val) = node.get::<String>(key) {
-                // Try to parse as JSON first; fall back to plain string.
-                let json_val = serde_json::from_str(&val).unwrap_or(Value::String(val));
-                props.insert(stripped.to_string(), json_val);
+    for (node_key, val) in flat {
+        let Some(key) = node_key.strip_prefix(PROPERTY_PREFIX) else {
+            continue;
+        };
+        let type_key = format!("{}{}", PROPERTY_TYPE_PREFIX, key);
+        let value = match flat.get(&type_key).map(String::as_str) {
+            Some(PROPERTY_TYPE_STRING) => Value::String(val.clone()),
+            Some(PROPERTY_TYPE_JSON) => {
+                serde_json::from_str(val).unwrap_or_else(|_| Value::String(val.clone()))
             }
-        }
+            _ => legacy_property_value(val),
+        };
+        props.insert(key.to_string(), value);
     }
     props
 }
 
+/// How an untagged property value was read before type tags.
+pub fn legacy_property_value(val: &str) -> Value {
+    serde_json::from_str(val).unwrap_or_else(|_| Value::String(val.to_string()))
+}
+
+/// A node's string-valued `prop_*` and `ptype_*` keys.
+pub fn flat_properties_of_node(node: &Node) -> HashMap<String, String> {
+    node.keys()
+        .into_iter()
+        .filter(|key| key.starts_with(PROPERTY_PREFIX) || key.starts_with(PROPERTY_TYPE_PREFIX))
+        .filter_map(|key| {
+            node.get::<String>(key)
+                .ok()
+                .map(|val| (key.to_string(), val))
+        })
+        .collect()
+}
+
+/// Collect all freeform properties from a Node back into a HashMap.
+/// Uses Node::get which returns Result — errors are treated as missing values.
+pub fn unflatten_properties_from_node(node: &Node) -> HashMap<String, Value> {
+    unflatten_properties(&flat_properties_of_node(node))
+}
+
 // ---------------------------------------------------------------------------
 // Embedding text builders
 // ---------------------------------------------------------------------------
@@ -162,6 +256,7 @@ pub fn node_to_entity(node: &Node) -> Result<Entity, GraphError> {
     let summary: Option<String> = node_get_optional(node, "summary");
     let is_stub: bool = node_get_optional(node, "is_stub").unwrap_or(false);
     let embedding_pending: bool = node_get_optional(node, "embedding_pending").unwrap_or(false);
+    let is_seeded: bool = node_get_optional(node, "is_seeded").unwrap_or(false);
 
     let aliases = match node_get_optional::<String>(node, "aliases") {
         Some(json_str) => parse_aliases(&json_str),
@@ -172,6 +267,9 @@ pub fn node_to_entity(node: &Node) -> Result<Entity, GraphError> {
         .map(|v| v.into_iter().map(|f| f as f32).collect());
 
     let properties = unflatten_properties_from_node(node);
+    let identifiers = node_identifiers(node);
+    let created_by_investigation =
+        parse_optional_investigation_id(node_get_optional(node, "created_by_investigation"));
 
     Ok(Entity {
         id: parse_entity_id(&id_str)?,
@@ -182,8 +280,11 @@ pub fn node_to_entity(node: &Node) -> Result<Entity, GraphError> {
         is_stub,
         last_updated: parse_datetime(&last_updated_str)?,
         properties,
+        identifiers,
         embedding,
         embedding_pending,
+        created_by_investigation,
+        is_seeded,
     })
 }
 
@@ -201,9 +302,28 @@ pub fn node_to_claim(
 
     let raw_source_link: Option<String> = node_get_optional(node, "raw_source_link");
     let embedding_pending: bool = node_get_optional(node, "embedding_pending").unwrap_or(false);
+    let investigation_id =
+        parse_optional_investigation_id(node_get_optional(node, "investigation_id"));
+    let monitor_id = node_get_optional::<String>(node, "monitor_id")
+        .and_then(|s| Uuid::parse_str(&s).ok())
+        .map(MonitorId::from_uuid);
 
     let embedding: Option<Vec<f32>> = node_get_optional::<Vec<f64>>(node, "embedding")
         .map(|v| v.into_iter().map(|f| f as f32).collect());
+    // Claims written before entity linking have no auto_linked_entity_ids property.
+    let auto_linked_entity_ids: Vec<EntityId> =
+        node_get_optional::<Vec<String>>(node, "auto_linked_entity_ids")
+            .unwrap_or_default()
+            .iter()
+            .map(|s| parse_entity_id(s))
+            .collect::<Result<_, _>>()?;
+
+    let supports_relationship_ids: Vec<RelationshipId> =
+        node_get_optional::<Vec<String>>(node, "supports_relationship_ids")
+            .unwrap_or_default()
+            .iter()
+            .map(|s| parse_relationship_id(s))
+            .collect::<Result<_, _>>()?;
 
     let attribution_depth = match attribution_depth_str.as_str() {
         "primary" => AttributionDepth::Primary,
@@ -236,16 +356,49 @@ pub fn node_to_claim(
         content,
         published_timestamp: parse_datetime(&published_str)?,
         ingested_timestamp: parse_datetime(&ingested_str)?,
+        citation: parse_citation(
+            node_get_optional(node, "citation"),
+            raw_source_link.as_deref(),
+        ),
         raw_source_link,
         attribution_depth,
         information_type,
         source_entity_id,
         referenced_entity_ids,
+        auto_linked_entity_ids,
+        supports_relationship_ids,
         embedding,
         embedding_pending,
+        investigation_id,
+        monitor_id,
+        // Absent on claims written before language/region were recorded.
+        language: node_get_optional(node, "language"),
+        original_content: node_get_optional(node, "original_content"),
+        original_language: node_get_optional(node, "original_language"),
+        region: node_get_optional(node, "region"),
+        source_trust_level: node_get_optional::<i64>(node, "source_trust_level")
+            .and_then(|level| u8::try_from(level).ok()),
+        // Set once feedback first disputes the claim.
+        disputed_count: node_get_optional::<i64>(node, "disputed_count")
+            .and_then(|count| u32::try_from(count).ok())
+            .unwrap_or(0),
+        redactions: node_get_optional::<String>(node, "redactions")
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
+        // Set once a duplicate is merged into the claim.
+        merged_sources: node_get_optional::<String>(node, "merged_sources")
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default(),
     })
 }
 
+/// A claim's stored citation JSON, or for claims written before citations
+/// (or with an unreadable one) a citation of just `raw_source_link`.
+pub fn parse_citation(json: Option<String>, raw_source_link: Option<&str>) -> Option<Citation> {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+        .or_else(|| raw_source_link.map(Citation::from_url))
+}
+
 /// Extract a Relationship from a Neo4j Relation plus endpoint entity IDs.
 pub fn relation_to_relationship(
     rel: &Relation,
@@ -256,6 +409,11 @@ pub fn relation_to_relationship(
     let description: String = rel_get_required(rel, "description")?;
 
     let weight: Option<f64> = rel_get_optional(rel, "weight");
+    let previous_weight: Option<f64> = rel_get_optional(rel, "previous_weight");
+    let weight_recalibrated_at: Option<DateTime<Utc>> =
+        rel_get_optional::<String>(rel, "weight_recalibrated_at")
+            .map(|s| parse_datetime(&s))
+            .transpose()?;
     let confidence: Option<f64> = rel_get_optional(rel, "confidence");
     let bidirectional: bool = rel_get_optional(rel, "bidirectional").unwrap_or(false);
     let embedding_pending: bool = rel_get_optional(rel, "embedding_pending").unwrap_or(false);
@@ -266,6 +424,8 @@ pub fn relation_to_relationship(
 
     let embedding: Option<Vec<f32>> = rel_get_optional::<Vec<f64>>(rel, "embedding")
         .map(|v| v.into_iter().map(|f| f as f32).collect());
+    let created_by_investigation =
+        parse_optional_investigation_id(rel_get_optional(rel, "created_by_investigation"));
 
     Ok(Relationship {
         id: parse_relationship_id(&id_str)?,
@@ -273,11 +433,14 @@ pub fn relation_to_relationship(
         target_entity_id,
         description,
         weight,
+        previous_weight,
+        weight_recalibrated_at,
         confidence,
         bidirectional,
         timestamp,
         embedding,
         embedding_pending,
+        created_by_investigation,
     })
 }
 
@@ -324,3 +487,225 @@ pub fn row_to_relationship(row: Row) -> Result<Relationship, GraphError> {
         parse_entity_id(&target_id_str)?,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    /// Deterministic xorshift generator, so failures reproduce.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    /// Strings chosen to look like other JSON types, plus random ones.
+    fn arbitrary_string(rng: &mut Rng) -> String {
+        const TRICKY: &[&str] = &[
+            "",
+            "true",
+            "false",
+            "null",
+            "0",
+            "-1.5",
+            "1e3",
+            "\"quoted\"",
+            "[1, 2]",
+            "{\"a\": 1}",
+            "{}",
+            " 42 ",
+            "NaN",
+            "`",
+            "prop_x",
+        ];
+        if rng.below(2) == 0 {
+            return TRICKY[rng.below(TRICKY.len() as u64) as usize].to_string();
+        }
+        (0..rng.below(8))
+            .map(|_| char::from_u32(rng.below(0x250) as u32).unwrap_or('?'))
+            .collect()
+    }
+
+    fn arbitrary_value(rng: &mut Rng, depth: u32) -> Value {
+        let kinds = if depth == 0 { 5 } else { 7 };
+        match rng.below(kinds) {
+            0 => Value::Null,
+            1 => Value::Bool(rng.below(2) == 0),
+            2 => json!(rng.next() as i64),
+            3 => json!((rng.next() as f64) / 7.0 - 1e18),
+            4 => Value::String(arbitrary_string(rng)),
+            5 => Value::Array(
+                (0..rng.below(4))
+                    .map(|_| arbitrary_value(rng, depth - 1))
+                    .collect(),
+            ),
+            _ => Value::Object(
+                (0..rng.below(4))
+                    .map(|_| (arbitrary_string(rng), arbitrary_value(rng, depth - 1)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn round_trip(properties: &HashMap<String, Value>) -> HashMap<String, Value> {
+        unflatten_properties(&flatten_properties(properties).into_iter().collect())
+    }
+
+    #[test]
+    fn test_flatten_round_trips_arbitrary_values() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..2000 {
+            let properties: HashMap<String, Value> = (0..rng.below(6))
+                .map(|_| (arbitrary_string(&mut rng), arbitrary_value(&mut rng, 3)))
+                .collect();
+            assert_eq!(round_trip(&properties), properties);
+        }
+    }
+
+    #[test]
+    fn test_strings_that_look_like_json_stay_strings() {
+        let properties: HashMap<String, Value> = [
+            ("flag", json!("true")),
+            ("count", json!("42")),
+            ("nothing", json!("null")),
+            ("nested", json!("{\"a\": [1]}")),
+            ("empty", json!("")),
+            ("real_flag", json!(true)),
+            ("real_nothing", Value::Null),
+            ("real_nested", json!({"a": [1, "2"]})),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        assert_eq!(round_trip(&properties), properties);
+
+        // Strings are stored as-is, so Cypher can match on them directly.
+        let flat: HashMap<String, String> = flatten_properties(&properties).into_iter().collect();
+        assert_eq!(flat["prop_flag"], "true");
+        assert_eq!(flat["ptype_flag"], "string");
+        assert_eq!(flat["ptype_real_flag"], "json");
+    }
+
+    #[test]
+    fn test_untagged_values_read_the_legacy_way() {
+        let flat: HashMap<String, String> = [
+            ("prop_flag", "true"),
+            ("prop_name", "Crius"),
+            ("prop_tagged", "true"),
+            ("ptype_tagged", "string"),
+            ("summary", "not a property"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let properties = unflatten_properties(&flat);
+        assert_eq!(properties.len(), 3);
+        assert_eq!(properties["flag"], json!(true));
+        assert_eq!(properties["name"], json!("Crius"));
+        assert_eq!(properties["tagged"], json!("true"));
+    }
+
+    #[test]
+    fn test_quote_key_escapes_backticks() {
+        assert_eq!(quote_key("prop_a`b"), "`prop_a``b`");
+    }
+
+    /// A Claim node with the required properties plus `extra`.
+    fn claim_node(extra: &[(&str, &str)]) -> Node {
+        use neo4rs::{BoltInteger, BoltList, BoltMap, BoltNode, BoltString, BoltType};
+
+        let id = ClaimId::new().to_string();
+        let properties: BoltMap = [
+            ("id", id.as_str()),
+            ("content", "The port closed."),
+            ("published_timestamp", "2026-03-01T00:00:00Z"),
+            ("ingested_timestamp", "2026-03-02T00:00:00Z"),
+            ("attribution_depth", "secondhand"),
+        ]
+        .iter()
+        .chain(extra)
+        .map(|(k, v)| (BoltString::from(*k), BoltType::from(*v)))
+        .collect();
+        Node::new(BoltNode::new(
+            BoltInteger::new(1),
+            BoltList::from(vec![BoltType::from("Claim")]),
+            properties,
+        ))
+    }
+
+    #[test]
+    fn test_claim_citation_round_trips_through_node() {
+        let mut claim = Claim::new(
+            "The port closed.".into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            EntityId::new(),
+        );
+        let mut citation = Citation::from_url("https://news.example/port?utm_source=feed");
+        citation.title = Some("Port closes".into());
+        citation.locator = Some("para. 2".into());
+        citation.accessed_at = Some("2026-03-02T09:30:00Z".parse().unwrap());
+        citation.via = autosint_common::types::CitationVia::Cache;
+        claim.set_citation(citation);
+
+        let stored = serde_json::to_string(claim.citation.as_ref().unwrap()).unwrap();
+        let link = claim.raw_source_link.clone().unwrap();
+        let node = claim_node(&[("citation", &stored), ("raw_source_link", &link)]);
+        let read = node_to_claim(&node, claim.source_entity_id, Vec::new()).unwrap();
+        assert_eq!(read.citation, claim.citation);
+        assert_eq!(
+            read.citation.unwrap().normalized_url.as_deref(),
+            Some("https://news.example/port")
+        );
+        assert_eq!(read.raw_source_link.as_deref(), Some(link.as_str()));
+    }
+
+    #[test]
+    fn test_legacy_claim_gets_citation_from_link() {
+        let node = claim_node(&[("raw_source_link", "https://news.example/port#p3")]);
+        let claim = node_to_claim(&node, EntityId::new(), Vec::new()).unwrap();
+        let citation = claim.citation.unwrap();
+        assert_eq!(citation.url, "https://news.example/port#p3");
+        assert_eq!(citation.locator.as_deref(), Some("#p3"));
+        assert_eq!(citation.title, None);
+        assert_eq!(citation.accessed_at, None);
+
+        // No link, no citation; an unreadable citation falls back to the link.
+        let bare = node_to_claim(&claim_node(&[]), EntityId::new(), Vec::new()).unwrap();
+        assert_eq!(bare.citation, None);
+        assert_eq!(
+            parse_citation(Some("{not json".into()), Some("https://x.example")).map(|c| c.url),
+            Some("https://x.example".to_string())
+        );
+    }
+
+    #[test]
+    fn test_claim_original_content_round_trips_through_node() {
+        let node = claim_node(&[
+            ("language", "uk"),
+            ("original_content", "Порт закрито."),
+            ("original_language", "uk"),
+        ]);
+        let claim = node_to_claim(&node, EntityId::new(), Vec::new()).unwrap();
+        assert_eq!(claim.content, "The port closed.");
+        assert_eq!(claim.original_content.as_deref(), Some("Порт закрито."));
+        assert_eq!(claim.original_language.as_deref(), Some("uk"));
+
+        // Claims written from untranslated text have neither.
+        let plain = node_to_claim(&claim_node(&[]), EntityId::new(), Vec::new()).unwrap();
+        assert_eq!(plain.original_content, None);
+        assert_eq!(plain.original_language, None);
+    }
+}
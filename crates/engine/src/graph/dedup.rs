@@ -6,10 +6,10 @@ use std::pin::Pin;
 use neo4rs::query;
 
 use autosint_common::config::DedupConfig;
-use autosint_common::types::Entity;
+use autosint_common::types::{identifier_conflicts, Entity, Identifiers, Relationship};
 use autosint_common::EntityId;
 
-use super::conversions::node_to_entity;
+use super::conversions::{identifier_node_key, node_to_entity};
 use super::escape_lucene_query;
 use super::GraphClient;
 use super::GraphError;
@@ -28,12 +28,101 @@ pub enum DedupResult {
 
 /// Which dedup pipeline stage produced the match.
 pub enum DedupStage {
+    Identifier,
     ExactString,
     FuzzyString,
     EmbeddingSimilarity,
     LlmJudgment,
 }
 
+/// Entity kinds matched against seeded geographic entities at the lower
+/// `seeded_geo_fuzzy_threshold`.
+const GEO_KINDS: &[&str] = &["country", "region", "state", "province", "territory"];
+
+/// Whether `kind` names a country or first-level administrative region.
+pub fn is_geo_kind(kind: &str) -> bool {
+    GEO_KINDS.contains(&kind.trim().to_lowercase().as_str())
+}
+
+/// Pick the best fuzzy candidate from `(id, score, is_seeded)` triples.
+///
+/// For geographic kinds, seeded entities pass at `seeded_geo_fuzzy_threshold`
+/// and win over any unseeded candidate; otherwise the highest score at or above
+/// `fuzzy_threshold` wins.
+pub fn best_fuzzy_candidate(
+    candidates: &[(EntityId, f64, bool)],
+    kind: &str,
+    config: &DedupConfig,
+) -> Option<(EntityId, f64)> {
+    let prefer_seeded = is_geo_kind(kind);
+    candidates
+        .iter()
+        .filter_map(|&(id, score, seeded)| {
+            let favoured = prefer_seeded && seeded;
+            let threshold = if favoured {
+                config.seeded_geo_fuzzy_threshold
+            } else {
+                config.fuzzy_threshold
+            };
+            (score >= threshold).then_some((favoured, score, id))
+        })
+        .max_by(|a, b| {
+            (a.0, a.1)
+                .partial_cmp(&(b.0, b.1))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(_, score, id)| (id, score))
+}
+
+/// The existing relationship whose description is most similar to `description`,
+/// if it reaches `relationship_description_threshold`. Descriptions are compared
+/// case- and whitespace-insensitively.
+pub fn duplicate_relationship<'r>(
+    existing: &'r [Relationship],
+    description: &str,
+    config: &DedupConfig,
+) -> Option<(&'r Relationship, f64)> {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    let description = normalize(description);
+    existing
+        .iter()
+        .map(|rel| {
+            let score = strsim::jaro_winkler(&description, &normalize(&rel.description));
+            (rel, score)
+        })
+        .filter(|&(_, score)| score >= config.relationship_description_threshold)
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+/// Whether `existing` has a different value than the candidate under some
+/// identifier scheme, which makes it a different thing however alike the
+/// names or embeddings. Each conflict is logged as a dedup decision.
+fn identifier_conflict(candidate: &Identifiers, existing: &Entity, stage: &'static str) -> bool {
+    let conflicts = identifier_conflicts(candidate, &existing.identifiers);
+    if conflicts.is_empty() {
+        return false;
+    }
+    let schemes = conflicts
+        .iter()
+        .map(|scheme| scheme.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    metrics::counter!("graph.dedup.identifier_conflict", "stage" => stage).increment(1);
+    tracing::info!(
+        entity_id = %existing.id,
+        canonical_name = %existing.canonical_name,
+        stage,
+        schemes = %schemes,
+        "Dedup match suppressed: identifiers conflict"
+    );
+    true
+}
+
 /// Trait for LLM-based deduplication judgment (interface only — M3 implements).
 /// Uses boxed future return for object safety (dyn dispatch).
 pub trait LlmDedupJudge: Send + Sync {
@@ -65,24 +154,36 @@ impl<'a> EntityDedup<'a> {
     }
 
     /// Run the cascading deduplication pipeline for a candidate entity.
-    /// Stages: exact string → fuzzy string → embedding similarity → LLM judgment.
+    /// Stages: identifier → exact string → fuzzy string → embedding similarity
+    /// → LLM judgment. Entities whose identifiers conflict with `identifiers`
+    /// are never matched by the later stages.
     pub async fn find_duplicate(
         &self,
         name: &str,
         kind: &str,
+        identifiers: &Identifiers,
         embedding: Option<&[f32]>,
     ) -> Result<DedupResult, GraphError> {
         let start = std::time::Instant::now();
 
+        // Stage 0: Identifier equality. The strongest signal there is.
+        if let Some(entity_id) = self.identifier_match(identifiers).await? {
+            metrics::counter!("graph.dedup.stage_hit", "stage" => "identifier").increment(1);
+            metrics::histogram!("graph.dedup.latency").record(start.elapsed().as_secs_f64());
+            return Ok(DedupResult::ExactMatch(entity_id));
+        }
+
         // Stage 1: Exact string match on canonical_name or aliases.
-        if let Some(entity_id) = self.exact_string_match(name).await? {
+        if let Some(entity_id) = self.exact_string_match(name, identifiers).await? {
             metrics::counter!("graph.dedup.stage_hit", "stage" => "exact_string").increment(1);
             metrics::histogram!("graph.dedup.latency").record(start.elapsed().as_secs_f64());
             return Ok(DedupResult::ExactMatch(entity_id));
         }
 
         // Stage 2: Fuzzy string match using fulltext search + Jaro-Winkler.
-        if let Some((entity_id, confidence)) = self.fuzzy_string_match(name, kind).await? {
+        if let Some((entity_id, confidence)) =
+            self.fuzzy_string_match(name, kind, identifiers).await?
+        {
             metrics::counter!("graph.dedup.stage_hit", "stage" => "fuzzy_string").increment(1);
             metrics::histogram!("graph.dedup.latency").record(start.elapsed().as_secs_f64());
             return Ok(DedupResult::ProbableMatch {
@@ -94,8 +195,9 @@ impl<'a> EntityDedup<'a> {
 
         // Stage 3: Embedding similarity via vector search.
         if let Some(embedding) = embedding {
-            if let Some((entity_id, confidence)) =
-                self.embedding_similarity_match(embedding).await?
+            if let Some((entity_id, confidence)) = self
+                .embedding_similarity_match(embedding, identifiers)
+                .await?
             {
                 metrics::counter!("graph.dedup.stage_hit", "stage" => "embedding_similarity")
                     .increment(1);
@@ -119,15 +221,73 @@ impl<'a> EntityDedup<'a> {
         Ok(DedupResult::NoMatch)
     }
 
-    /// Stage 1: Exact match on canonical_name or alias.
-    async fn exact_string_match(&self, name: &str) -> Result<Option<EntityId>, GraphError> {
+    /// Stage 0: An entity sharing an identifier with the candidate, and
+    /// conflicting on none. A seeded entity wins over unseeded ones.
+    async fn identifier_match(
+        &self,
+        identifiers: &Identifiers,
+    ) -> Result<Option<EntityId>, GraphError> {
+        if identifiers.is_empty() {
+            return Ok(None);
+        }
+        let conditions: Vec<String> = identifiers
+            .keys()
+            .map(|scheme| {
+                let key = identifier_node_key(*scheme);
+                format!("e.{} = ${}", key, key)
+            })
+            .collect();
+        let cypher = format!(
+            "MATCH (e:Entity) \
+             WHERE {} \
+             RETURN e AS node \
+             ORDER BY coalesce(e.is_seeded, false) DESC \
+             LIMIT 10",
+            conditions.join(" OR ")
+        );
+        let mut q = query(&cypher);
+        for (scheme, value) in identifiers {
+            q = q.param(&identifier_node_key(*scheme), value.as_str());
+        }
+
+        let mut result = self
+            .graph
+            .inner()
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("node")
+                .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
+            let entity = node_to_entity(&node)?;
+            if !identifier_conflict(identifiers, &entity, "identifier") {
+                return Ok(Some(entity.id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Stage 1: Exact match on canonical_name or alias. A seeded entity wins over
+    /// unseeded ones with the same name.
+    async fn exact_string_match(
+        &self,
+        name: &str,
+        identifiers: &Identifiers,
+    ) -> Result<Option<EntityId>, GraphError> {
         let name_lower = name.to_lowercase();
 
         let q = query(
             "MATCH (e:Entity) \
              WHERE toLower(e.canonical_name) = $name \
-             RETURN e.id AS id \
-             LIMIT 1",
+             RETURN e AS node \
+             ORDER BY coalesce(e.is_seeded, false) DESC \
+             LIMIT 10",
         )
         .param("name", name_lower.as_str());
 
@@ -138,20 +298,32 @@ impl<'a> EntityDedup<'a> {
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
 
-        if let Some(row) = result
+        let mut fallback = None;
+        while let Some(row) = result
             .next()
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?
         {
-            let id_str: String = row
-                .get("id")
-                .map_err(|e| GraphError::Query(format!("Missing 'id': {}", e)))?;
-            return Ok(Some(super::conversions::parse_entity_id(&id_str)?));
+            let node: neo4rs::Node = row
+                .get("node")
+                .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
+            let entity = node_to_entity(&node)?;
+            if identifier_conflict(identifiers, &entity, "exact_string") {
+                continue;
+            }
+            if entity.is_seeded {
+                return Ok(Some(entity.id));
+            }
+            fallback = Some(entity.id);
+            break;
         }
 
         // Also check aliases (stored as JSON arrays).
         // Use fulltext index for efficiency, then verify exact match in Rust.
         let escaped_name = escape_lucene_query(name);
+        if escaped_name.is_empty() {
+            return Ok(fallback);
+        }
         let q2 = query(
             "CALL db.index.fulltext.queryNodes('entity_name_fulltext', $name) \
              YIELD node, score \
@@ -177,28 +349,35 @@ impl<'a> EntityDedup<'a> {
                 .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
             let entity = node_to_entity(&node)?;
 
-            // Check exact match in aliases (case-insensitive).
-            for alias in &entity.aliases {
-                if alias.to_lowercase() == name_lower {
+            // Check exact match in aliases (case-insensitive), and canonical name
+            // again (the fulltext might have returned it).
+            let matched = entity
+                .aliases
+                .iter()
+                .any(|a| a.to_lowercase() == name_lower)
+                || entity.canonical_name.to_lowercase() == name_lower;
+            if matched && !identifier_conflict(identifiers, &entity, "exact_string") {
+                if entity.is_seeded {
                     return Ok(Some(entity.id));
                 }
-            }
-            // Also check canonical name again (the fulltext might have returned it).
-            if entity.canonical_name.to_lowercase() == name_lower {
-                return Ok(Some(entity.id));
+                fallback.get_or_insert(entity.id);
             }
         }
 
-        Ok(None)
+        Ok(fallback)
     }
 
     /// Stage 2: Fuzzy string match using fulltext search + Jaro-Winkler similarity.
     async fn fuzzy_string_match(
         &self,
         name: &str,
-        _kind: &str,
+        kind: &str,
+        identifiers: &Identifiers,
     ) -> Result<Option<(EntityId, f64)>, GraphError> {
         let escaped_name = escape_lucene_query(name);
+        if escaped_name.is_empty() {
+            return Ok(None);
+        }
         let q = query(
             "CALL db.index.fulltext.queryNodes('entity_name_fulltext', $name) \
              YIELD node, score \
@@ -214,7 +393,7 @@ impl<'a> EntityDedup<'a> {
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
 
-        let mut best_match: Option<(EntityId, f64)> = None;
+        let mut candidates = Vec::new();
 
         while let Some(row) = result
             .next()
@@ -225,6 +404,9 @@ impl<'a> EntityDedup<'a> {
                 .get("node")
                 .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
             let entity = node_to_entity(&node)?;
+            if identifier_conflict(identifiers, &entity, "fuzzy_string") {
+                continue;
+            }
 
             // Compute Jaro-Winkler against canonical name.
             let jw_score =
@@ -238,27 +420,18 @@ impl<'a> EntityDedup<'a> {
                 .fold(0.0_f64, f64::max);
 
             let score = jw_score.max(best_alias_score);
-
-            if score >= self.config.fuzzy_threshold {
-                match best_match {
-                    Some((_, existing_score)) if score > existing_score => {
-                        best_match = Some((entity.id, score));
-                    }
-                    None => {
-                        best_match = Some((entity.id, score));
-                    }
-                    _ => {}
-                }
-            }
+            candidates.push((entity.id, score, entity.is_seeded));
         }
 
-        Ok(best_match)
+        Ok(best_fuzzy_candidate(&candidates, kind, self.config))
     }
 
-    /// Stage 3: Embedding similarity via vector search.
+    /// Stage 3: Embedding similarity via vector search, plus a scan of
+    /// entities too fresh for the index when `fresh_entities.enabled`.
     async fn embedding_similarity_match(
         &self,
         embedding: &[f32],
+        identifiers: &Identifiers,
     ) -> Result<Option<(EntityId, f64)>, GraphError> {
         let emb_f64: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
 
@@ -266,8 +439,7 @@ impl<'a> EntityDedup<'a> {
             "CALL db.index.vector.queryNodes('entity_embedding', 5, $embedding) \
              YIELD node, score \
              RETURN node, score \
-             ORDER BY score DESC \
-             LIMIT 1",
+             ORDER BY score DESC",
         )
         .param("embedding", emb_f64);
 
@@ -278,7 +450,8 @@ impl<'a> EntityDedup<'a> {
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
 
-        if let Some(row) = result
+        let mut best = None;
+        while let Some(row) = result
             .next()
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?
@@ -290,12 +463,110 @@ impl<'a> EntityDedup<'a> {
                 .get("node")
                 .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
             let entity = node_to_entity(&node)?;
+            if !identifier_conflict(identifiers, &entity, "embedding_similarity") {
+                best = Some((entity.id, score));
+                break;
+            }
+        }
 
-            if score >= self.config.embedding_threshold {
-                return Ok(Some((entity.id, score)));
+        let threshold = self.config.embedding_threshold;
+        if self.config.fresh_entities.enabled {
+            let fresh = self
+                .graph
+                .fresh_entity_matches(embedding, &self.config.fresh_entities)
+                .await?;
+            let top = fresh
+                .iter()
+                .find(|m| !identifier_conflict(identifiers, &m.item, "embedding_similarity"));
+            if let Some(top) = top {
+                if top.score >= threshold && best.is_none_or(|(_, score)| top.score > score) {
+                    if best.is_none_or(|(_, score)| score < threshold) {
+                        // The index alone would have missed it.
+                        metrics::counter!("graph.fresh_scan.dedup_hit").increment(1);
+                    }
+                    best = Some((top.item.id, top.score));
+                }
             }
         }
 
-        Ok(None)
+        Ok(best.filter(|&(_, score)| score >= threshold))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> DedupConfig {
+        DedupConfig {
+            fuzzy_threshold: 0.85,
+            embedding_threshold: 0.90,
+            seeded_geo_fuzzy_threshold: 0.80,
+            relationship_description_threshold: 0.95,
+            duplicate_relationships: Default::default(),
+            fresh_entities: Default::default(),
+            merge_exact_duplicate_claims: false,
+        }
+    }
+
+    #[test]
+    fn test_geo_kinds() {
+        assert!(is_geo_kind("country"));
+        assert!(is_geo_kind(" Region "));
+        assert!(!is_geo_kind("organization"));
+    }
+
+    #[test]
+    fn test_seeded_geo_entity_matches_below_fuzzy_threshold() {
+        let seeded = EntityId::new();
+        let candidates = [(seeded, 0.82, true)];
+
+        assert_eq!(
+            best_fuzzy_candidate(&candidates, "country", &config()),
+            Some((seeded, 0.82))
+        );
+        // Non-geographic kinds keep the normal threshold.
+        assert_eq!(
+            best_fuzzy_candidate(&candidates, "organization", &config()),
+            None
+        );
+        // Unseeded geographic entities keep the normal threshold.
+        assert_eq!(
+            best_fuzzy_candidate(&[(seeded, 0.82, false)], "country", &config()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_seeded_geo_entity_preferred_over_better_unseeded_match() {
+        let seeded = EntityId::new();
+        let llm_created = EntityId::new();
+        let candidates = [(llm_created, 0.95, false), (seeded, 0.83, true)];
+
+        assert_eq!(
+            best_fuzzy_candidate(&candidates, "country", &config()),
+            Some((seeded, 0.83))
+        );
+        assert_eq!(
+            best_fuzzy_candidate(&candidates, "person", &config()),
+            Some((llm_created, 0.95))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_relationship_description() {
+        let (a, b) = (EntityId::new(), EntityId::new());
+        let existing = [
+            Relationship::new(a, b, "Apple supplies chips to TSMC".into()),
+            Relationship::new(a, b, "Apple is a major customer of TSMC".into()),
+        ];
+
+        let (dup, score) =
+            duplicate_relationship(&existing, "apple supplies  chips to TSMC.", &config()).unwrap();
+        assert_eq!(dup.id, existing[0].id);
+        assert!(score >= 0.95);
+
+        assert!(duplicate_relationship(&existing, "TSMC sued Apple in 2024", &config()).is_none());
+        assert!(duplicate_relationship(&[], "Apple supplies chips to TSMC", &config()).is_none());
     }
 }
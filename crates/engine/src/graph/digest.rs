@@ -0,0 +1,437 @@
+//! Per-entity claim digests: the most recently ingested claims referencing an
+//! entity, kept on the entity node so context assembly reads one property
+//! instead of querying claims.
+//!
+//! The digest is the `claim_digest` property, a list of JSON-encoded
+//! [`ClaimDigestEntry`] strings, newest first. An entry's encoding starts with
+//! its fixed-width ingestion timestamp, so the strings sort as the claims do
+//! and Cypher can place a new entry by comparing strings. Claim writes insert
+//! and trim in the same statement (`INSERT_ENTRY`); merges and purges rebuild
+//! the entities they touch, and the `stale_claim_digests` consistency check
+//! repairs drift. Entities written before digests existed have no property
+//! and are read with the claims query until the repair fills it in.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use neo4rs::{query, BoltType, Row};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use autosint_common::types::{Claim, Entity};
+use autosint_common::{ClaimId, EntityId};
+
+use super::conversions::{node_to_entity, parse_claim_id, parse_datetime};
+use super::{ClaimSearchParams, GraphClient, GraphError};
+use crate::tools::truncation::mark_truncated;
+
+/// Claims kept per entity digest.
+pub const CLAIM_DIGEST_LEN: usize = 10;
+
+/// Characters of claim content kept per entry. Previews up to this long are
+/// served from the digest.
+pub const DIGEST_SNIPPET_CHARS: usize = 1000;
+
+/// `SET` clause inserting the encoded entry `entry` into entity `e`'s digest
+/// in order, trimmed to `$claim_digest_len`. One statement reads and writes
+/// the property under the node's write lock, so concurrent claim writes never
+/// drop each other's entries; re-inserting an entry leaves one copy. Expects
+/// `e` to have a digest.
+pub(super) const INSERT_ENTRY: &str = "SET e.claim_digest = \
+    ([x IN e.claim_digest WHERE x > entry] + entry + [x IN e.claim_digest WHERE x < entry])\
+    [0..$claim_digest_len]";
+
+/// The newest `$claim_digest_len` claims referencing entity `e`, as `claims`.
+/// Shared by `DIGEST_SOURCE` and the consistency check's query.
+macro_rules! digest_claims_subquery {
+    () => {
+        "CALL { \
+            WITH e \
+            OPTIONAL MATCH (c:Claim)-[:REFERENCES]->(e) \
+            WITH DISTINCT c \
+            ORDER BY datetime(c.ingested_timestamp) DESC, c.id DESC \
+            LIMIT $claim_digest_len \
+            RETURN collect(c {.id, .content, .published_timestamp, .ingested_timestamp}) AS claims \
+         }"
+    };
+}
+
+/// Each entity in `$ids` with its stored digest and the claims it should
+/// hold, as `digest_repair_row` reads them.
+const DIGEST_SOURCE: &str = concat!(
+    "UNWIND $ids AS entity_id MATCH (e:Entity {id: entity_id}) ",
+    digest_claims_subquery!(),
+    " RETURN e.id AS id, e.claim_digest AS digest, claims"
+);
+
+/// `DIGEST_SOURCE` for every entity.
+pub(super) const DIGEST_CHECK: &str = concat!(
+    "MATCH (e:Entity) ",
+    digest_claims_subquery!(),
+    " RETURN e.id AS id, e.claim_digest AS digest, claims"
+);
+
+/// Replace digests from `digest_repair_row` rows, skipping any that changed
+/// since they were read.
+pub(super) const DIGEST_REPAIR: &str = "UNWIND $rows AS row \
+    MATCH (e:Entity {id: row.id}) \
+    WHERE (row.had_digest AND e.claim_digest = row.old_digest) \
+       OR (NOT row.had_digest AND e.claim_digest IS NULL) \
+    SET e.claim_digest = row.digest \
+    RETURN count(e) AS repaired";
+
+/// One claim in an entity's digest.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClaimDigestEntry {
+    /// First, so an entry's encoding sorts by it.
+    #[serde(with = "sortable_timestamp")]
+    pub ingested_timestamp: DateTime<Utc>,
+    pub claim_id: ClaimId,
+    pub published_timestamp: DateTime<Utc>,
+    /// The content's first `DIGEST_SNIPPET_CHARS` characters.
+    pub snippet: String,
+    /// Characters in the full content.
+    pub content_chars: usize,
+}
+
+impl ClaimDigestEntry {
+    pub fn new(
+        claim_id: ClaimId,
+        content: &str,
+        published_timestamp: DateTime<Utc>,
+        ingested_timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            ingested_timestamp,
+            claim_id,
+            published_timestamp,
+            snippet: content.chars().take(DIGEST_SNIPPET_CHARS).collect(),
+            content_chars: content.chars().count(),
+        }
+    }
+
+    pub fn from_claim(claim: &Claim) -> Self {
+        Self::new(
+            claim.id,
+            &claim.content,
+            claim.published_timestamp,
+            claim.ingested_timestamp,
+        )
+    }
+
+    /// The content cut to `max_chars` as `truncate_text` cuts it. Exact for
+    /// `max_chars` up to `DIGEST_SNIPPET_CHARS`.
+    pub fn preview(&self, max_chars: usize) -> String {
+        let kept: String = self.snippet.chars().take(max_chars).collect();
+        if self.content_chars <= max_chars {
+            kept
+        } else {
+            mark_truncated(&kept, self.content_chars)
+        }
+    }
+
+    /// The stored form: a JSON string that sorts by ingestion time.
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn decode(stored: &str) -> Option<Self> {
+        serde_json::from_str(stored).ok()
+    }
+}
+
+/// An entity's digest, newest claim first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClaimDigest {
+    pub entries: Vec<ClaimDigestEntry>,
+}
+
+impl ClaimDigest {
+    /// Decode a stored digest. None if any entry doesn't decode, so a
+    /// corrupted digest is read around rather than trusted.
+    pub fn decode(stored: &[String]) -> Option<Self> {
+        let entries = stored
+            .iter()
+            .map(|s| ClaimDigestEntry::decode(s))
+            .collect::<Option<_>>()?;
+        Some(Self { entries })
+    }
+
+    /// The `limit` newest entries with previews of `preview_chars`, or None
+    /// when that is more claims or longer previews than the digest holds.
+    pub fn recent(&self, limit: usize, preview_chars: usize) -> Option<&[ClaimDigestEntry]> {
+        if limit > CLAIM_DIGEST_LEN || preview_chars > DIGEST_SNIPPET_CHARS {
+            return None;
+        }
+        Some(&self.entries[..limit.min(self.entries.len())])
+    }
+}
+
+/// Encoded entries newest first, as a digest stores them.
+fn encode_digest(entries: &[ClaimDigestEntry]) -> Vec<String> {
+    let mut encoded: Vec<String> = entries.iter().map(ClaimDigestEntry::encode).collect();
+    encoded.sort_unstable();
+    encoded.reverse();
+    encoded.truncate(CLAIM_DIGEST_LEN);
+    encoded
+}
+
+/// A claim as the digest queries collect it.
+#[derive(Deserialize)]
+struct DigestSource {
+    id: String,
+    content: String,
+    published_timestamp: String,
+    ingested_timestamp: String,
+}
+
+impl DigestSource {
+    fn entry(&self) -> Result<ClaimDigestEntry, GraphError> {
+        Ok(ClaimDigestEntry::new(
+            parse_claim_id(&self.id)?,
+            &self.content,
+            parse_datetime(&self.published_timestamp)?,
+            parse_datetime(&self.ingested_timestamp)?,
+        ))
+    }
+}
+
+/// The `DIGEST_REPAIR` row bringing a `DIGEST_SOURCE` row's digest up to
+/// date, or None when it is current or its claims can't be read.
+pub(super) fn digest_repair_row(row: &Row) -> Option<HashMap<String, BoltType>> {
+    let id: String = row.get("id").ok()?;
+    let stored: Option<Vec<String>> = row.get("digest").ok()?;
+    let claims: Vec<DigestSource> = row.get("claims").ok()?;
+    let entries = claims
+        .iter()
+        .map(DigestSource::entry)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    let digest = encode_digest(&entries);
+    if stored.as_ref() == Some(&digest) {
+        return None;
+    }
+    Some(HashMap::from([
+        ("id".to_string(), id.into()),
+        ("had_digest".to_string(), stored.is_some().into()),
+        ("old_digest".to_string(), stored.unwrap_or_default().into()),
+        ("digest".to_string(), digest.into()),
+    ]))
+}
+
+/// Fixed-width UTC timestamps, nanosecond precision, which sort as strings.
+mod sortable_timestamp {
+    use super::*;
+
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.9fZ";
+
+    pub fn serialize<S: Serializer>(value: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&value.format(FORMAT).to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<DateTime<Utc>, D::Error> {
+        let s = String::deserialize(d)?;
+        NaiveDateTime::parse_from_str(&s, FORMAT)
+            .map(|dt| dt.and_utc())
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl GraphClient {
+    /// An entity and its claim digest. The digest is None for entities not
+    /// yet given one, or whose digest doesn't decode.
+    pub async fn get_entity_with_digest(
+        &self,
+        id: EntityId,
+    ) -> Result<(Entity, Option<ClaimDigest>), GraphError> {
+        let mut result = self
+            .graph
+            .execute(query("MATCH (e:Entity {id: $id}) RETURN e").param("id", id.to_string()))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let row = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .ok_or_else(|| GraphError::NotFound(format!("Entity {}", id)))?;
+        let node: neo4rs::Node = row
+            .get("e")
+            .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+
+        let digest = node
+            .get::<Vec<String>>("claim_digest")
+            .ok()
+            .and_then(|stored| ClaimDigest::decode(&stored));
+        Ok((node_to_entity(&node)?, digest))
+    }
+
+    /// The `limit` most recently ingested claims referencing `entity_id`,
+    /// from its digest when that can serve `preview_chars` previews, else
+    /// from the claims query.
+    pub async fn recent_claims(
+        &self,
+        entity_id: EntityId,
+        limit: usize,
+        preview_chars: usize,
+    ) -> Result<Vec<ClaimDigestEntry>, GraphError> {
+        let mut result = self
+            .graph
+            .execute(
+                query("MATCH (e:Entity {id: $id}) RETURN e.claim_digest AS digest")
+                    .param("id", entity_id.to_string()),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let digest = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .and_then(|row| row.get::<Vec<String>>("digest").ok())
+            .and_then(|stored| ClaimDigest::decode(&stored));
+        self.recent_claims_from(entity_id, digest.as_ref(), limit, preview_chars)
+            .await
+    }
+
+    /// `recent_claims` with the entity's digest already read.
+    pub async fn recent_claims_from(
+        &self,
+        entity_id: EntityId,
+        digest: Option<&ClaimDigest>,
+        limit: usize,
+        preview_chars: usize,
+    ) -> Result<Vec<ClaimDigestEntry>, GraphError> {
+        if let Some(entries) = digest.and_then(|d| d.recent(limit, preview_chars)) {
+            metrics::counter!("graph.claim_digest.reads", "outcome" => "hit").increment(1);
+            return Ok(entries.to_vec());
+        }
+        metrics::counter!("graph.claim_digest.reads", "outcome" => "miss").increment(1);
+        self.query_recent_claims(entity_id, limit).await
+    }
+
+    /// The `limit` most recently ingested claims referencing `entity_id`,
+    /// read from the claims themselves.
+    pub async fn query_recent_claims(
+        &self,
+        entity_id: EntityId,
+        limit: usize,
+    ) -> Result<Vec<ClaimDigestEntry>, GraphError> {
+        let page = self
+            .search_claims(
+                &ClaimSearchParams {
+                    query: None,
+                    mode: None,
+                    published_after: None,
+                    published_before: None,
+                    source_entity_id: None,
+                    referenced_entity_id: Some(entity_id),
+                    attribution_depth: None,
+                    information_type: None,
+                    language: None,
+                    region: None,
+                    min_trust: None,
+                    ingested_before: None,
+                    limit: Some(limit as u32),
+                    cursor: None,
+                },
+                None,
+            )
+            .await?;
+        Ok(page
+            .results
+            .iter()
+            .map(|r| ClaimDigestEntry::from_claim(&r.item))
+            .collect())
+    }
+
+    /// Rebuild the digests of `ids` from their claims, after a write that
+    /// moved or removed REFERENCES edges. Missing entities are skipped.
+    /// Returns the digests replaced.
+    pub async fn rebuild_claim_digests(&self, ids: &[EntityId]) -> Result<u64, GraphError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let mut result = self
+            .graph
+            .execute(
+                query(DIGEST_SOURCE)
+                    .param("ids", ids)
+                    .param("claim_digest_len", CLAIM_DIGEST_LEN as i64),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let mut rows = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            rows.extend(digest_repair_row(&row));
+        }
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        self.repair_batch(DIGEST_REPAIR, rows).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::tools::truncation::truncate_text;
+
+    fn entry(content: &str, ingested: DateTime<Utc>) -> ClaimDigestEntry {
+        ClaimDigestEntry::new(ClaimId::new(), content, Utc::now(), ingested)
+    }
+
+    #[test]
+    fn test_encoding_sorts_by_ingestion() {
+        let base = Utc::now();
+        // A second's and a nanosecond's difference both order correctly.
+        let entries = vec![
+            entry("oldest", base - Duration::seconds(1)),
+            entry("newest", base + Duration::nanoseconds(1)),
+            entry("middle", base),
+        ];
+        let digest = ClaimDigest::decode(&encode_digest(&entries)).unwrap();
+        let order: Vec<&str> = digest.entries.iter().map(|e| e.snippet.as_str()).collect();
+        assert_eq!(order, vec!["newest", "middle", "oldest"]);
+        assert_eq!(digest.entries[0], entries[1]);
+
+        let many: Vec<ClaimDigestEntry> = (0..CLAIM_DIGEST_LEN as i64 + 5)
+            .map(|i| entry(&i.to_string(), base + Duration::seconds(i)))
+            .collect();
+        let digest = ClaimDigest::decode(&encode_digest(&many)).unwrap();
+        assert_eq!(digest.entries.len(), CLAIM_DIGEST_LEN);
+        assert_eq!(
+            digest.entries[0].snippet,
+            (CLAIM_DIGEST_LEN + 4).to_string()
+        );
+    }
+
+    #[test]
+    fn test_preview_matches_truncate_text() {
+        let long = "Gulf Star Shipping chartered the Crius. ".repeat(40);
+        for content in ["Short claim.", long.as_str(), "é".repeat(1500).as_str()] {
+            let entry = entry(content, Utc::now());
+            for max in [0, 10, 200, 500, DIGEST_SNIPPET_CHARS] {
+                assert_eq!(entry.preview(max), truncate_text(content, max), "{}", max);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recent_within_digest_bounds() {
+        let digest = ClaimDigest {
+            entries: (0..3).map(|_| entry("claim", Utc::now())).collect(),
+        };
+        assert_eq!(digest.recent(2, 500).unwrap().len(), 2);
+        assert_eq!(digest.recent(CLAIM_DIGEST_LEN, 500).unwrap().len(), 3);
+        assert!(digest.recent(CLAIM_DIGEST_LEN + 1, 500).is_none());
+        assert!(digest.recent(2, DIGEST_SNIPPET_CHARS + 1).is_none());
+
+        assert!(ClaimDigest::decode(&["not json".to_string()]).is_none());
+        assert_eq!(ClaimDigest::decode(&[]), Some(ClaimDigest::default()));
+    }
+}
@@ -1,14 +1,23 @@
 use std::collections::HashMap;
 
-use neo4rs::query;
+use chrono::{DateTime, Utc};
+use neo4rs::{query, BoltType};
 use serde_json::Value;
 
-use autosint_common::types::Entity;
+use autosint_common::types::{identifier_conflicts, Entity, Identifiers};
 use autosint_common::EntityId;
 
-use super::conversions::{build_aliases_text, flatten_properties, format_datetime, node_to_entity};
+use super::conversions::{
+    build_aliases_text, flat_properties_of_node, flatten_properties, format_datetime,
+    identifier_node_key, identifier_node_map, legacy_property_value, node_to_entity,
+    property_node_keys, quote_key, PROPERTY_PREFIX, PROPERTY_TYPE_PREFIX,
+};
+use super::writes::record_read_latency;
 use super::GraphError;
 
+/// Entities tagged per statement by `backfill_property_types`.
+const PROPERTY_BACKFILL_BATCH: i64 = 500;
+
 /// Entity update with optional fields for partial updates.
 #[allow(dead_code)]
 pub struct EntityUpdate {
@@ -18,12 +27,18 @@ pub struct EntityUpdate {
     pub summary: Option<String>,
     pub is_stub: Option<bool>,
     pub properties: Option<HashMap<String, Value>>,
+    /// Freeform properties to delete. Applied before `properties`.
+    pub remove_properties: Vec<String>,
+    /// Identifiers to set, replacing any value under the same scheme. Schemes
+    /// left out keep theirs.
+    pub identifiers: Identifiers,
 }
 
 #[allow(dead_code)]
 impl super::GraphClient {
     /// Create a new entity in the knowledge graph.
     /// If embedding is provided, it's stored directly. Otherwise, embedding_pending is set.
+    /// With create coalescing enabled, concurrent creates are written in one batch.
     pub async fn create_entity(
         &self,
         entity: &Entity,
@@ -31,6 +46,30 @@ impl super::GraphClient {
     ) -> Result<Entity, GraphError> {
         let start = std::time::Instant::now();
 
+        let created = match self.coalescers() {
+            Some(coalesce) => {
+                let client = self.clone();
+                coalesce
+                    .entities
+                    .submit((entity.clone(), embedding), move |batch| {
+                        let client = client.clone();
+                        async move { client.flush_entities(batch).await }
+                    })
+                    .await?
+            }
+            None => self.write_entity(entity, embedding).await?,
+        };
+
+        metrics::histogram!("graph.entity.create.latency").record(start.elapsed().as_secs_f64());
+
+        Ok(created)
+    }
+
+    async fn write_entity(
+        &self,
+        entity: &Entity,
+        embedding: Option<Vec<f32>>,
+    ) -> Result<Entity, GraphError> {
         let aliases_json = serde_json::to_string(&entity.aliases)
             .map_err(|e| GraphError::Query(format!("Failed to serialize aliases: {}", e)))?;
         let aliases_text = build_aliases_text(&entity.aliases);
@@ -52,7 +91,11 @@ impl super::GraphClient {
                 kind: $kind, \
                 is_stub: $is_stub, \
                 last_updated: $last_updated, \
-                embedding_pending: $embedding_pending \
+                created_at: $last_updated, \
+                embedding_pending: $embedding_pending, \
+                created_by_investigation: $created_by_investigation, \
+                is_seeded: $is_seeded, \
+                claim_digest: [] \
             })",
         );
 
@@ -72,11 +115,14 @@ impl super::GraphClient {
         let flat_props = flatten_properties(&entity.properties);
         for (i, (prop_key, _)) in flat_props.iter().enumerate() {
             if i == 0 && entity.summary.is_none() && !has_embedding {
-                cypher.push_str(&format!(" SET e.`{}` = $prop_{}", prop_key, i));
+                cypher.push_str(&format!(" SET e.{} = $prop_{}", quote_key(prop_key), i));
             } else {
-                cypher.push_str(&format!(", e.`{}` = $prop_{}", prop_key, i));
+                cypher.push_str(&format!(", e.{} = $prop_{}", quote_key(prop_key), i));
             }
         }
+        if !entity.identifiers.is_empty() {
+            cypher.push_str(" SET e += $identifiers");
+        }
 
         cypher.push_str(" RETURN e");
 
@@ -90,7 +136,12 @@ impl super::GraphClient {
             .param("kind", entity.kind.as_str())
             .param("is_stub", entity.is_stub)
             .param("last_updated", last_updated.as_str())
-            .param("embedding_pending", embedding_pending);
+            .param("embedding_pending", embedding_pending)
+            .param(
+                "created_by_investigation",
+                entity.created_by_investigation.map(|id| id.to_string()),
+            )
+            .param("is_seeded", entity.is_seeded);
 
         if let Some(ref summary) = entity.summary {
             q = q.param("summary", summary.as_str());
@@ -102,7 +153,11 @@ impl super::GraphClient {
         for (i, (_, value)) in flat_props.iter().enumerate() {
             q = q.param(&format!("prop_{}", i), value.as_str());
         }
+        if !entity.identifiers.is_empty() {
+            q = q.param("identifiers", identifier_node_map(&entity.identifiers));
+        }
 
+        let _permit = self.writes.acquire("entity.create").await;
         let mut result = self
             .graph
             .execute(q)
@@ -119,18 +174,118 @@ impl super::GraphClient {
             .get("e")
             .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
 
-        let created = node_to_entity(&node)?;
+        node_to_entity(&node)
+    }
 
-        metrics::histogram!("graph.entity.create.latency").record(start.elapsed().as_secs_f64());
+    /// Write a coalesced batch of entities, falling back to one write per
+    /// entity for any the batch didn't create.
+    async fn flush_entities(
+        &self,
+        batch: Vec<(Entity, Option<Vec<f32>>)>,
+    ) -> Vec<Result<Entity, GraphError>> {
+        let mut created = match self.create_entities(&batch).await {
+            Ok(created) => created,
+            Err(e) => {
+                tracing::warn!(error = %e, entities = batch.len(), "Batched entity create failed, writing individually");
+                HashMap::new()
+            }
+        };
+
+        let mut results = Vec::with_capacity(batch.len());
+        for (entity, embedding) in batch {
+            match created.remove(&entity.id) {
+                Some(entity) => results.push(Ok(entity)),
+                None => {
+                    metrics::counter!("graph.write.coalesce_fallbacks", "target" => "entity")
+                        .increment(1);
+                    results.push(self.write_entity(&entity, embedding).await);
+                }
+            }
+        }
+        results
+    }
+
+    /// Create entities in one UNWIND write, returning them by ID.
+    async fn create_entities(
+        &self,
+        entities: &[(Entity, Option<Vec<f32>>)],
+    ) -> Result<HashMap<EntityId, Entity>, GraphError> {
+        let mut rows = Vec::with_capacity(entities.len());
+        for (entity, embedding) in entities {
+            rows.push(entity_row(entity, embedding.as_deref())?);
+        }
+
+        let q = query(
+            "UNWIND $rows AS row \
+             CREATE (e:Entity { \
+                id: row.id, \
+                canonical_name: row.canonical_name, \
+                aliases: row.aliases, \
+                aliases_text: row.aliases_text, \
+                kind: row.kind, \
+                is_stub: row.is_stub, \
+                last_updated: row.last_updated, \
+                created_at: row.last_updated, \
+                embedding_pending: row.embedding_pending, \
+                created_by_investigation: row.created_by_investigation, \
+                is_seeded: row.is_seeded, \
+                claim_digest: [] \
+             }) \
+             SET e.summary = row.summary, e.embedding = row.embedding \
+             SET e += row.properties \
+             SET e += row.identifiers \
+             RETURN e",
+        )
+        .param("rows", rows);
+
+        let _permit = self.writes.acquire("entity.create_batch").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
 
+        let mut created = HashMap::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("e")
+                .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+            let entity = node_to_entity(&node)?;
+            created.insert(entity.id, entity);
+        }
         Ok(created)
     }
 
     /// Get an entity by ID.
     pub async fn get_entity(&self, id: EntityId) -> Result<Entity, GraphError> {
+        self.fetch_entity(query("MATCH (e:Entity {id: $id}) RETURN e"), id)
+            .await
+    }
+
+    /// Get an entity by ID if it existed at `as_of`. Entities aren't
+    /// versioned: an entity that did exist comes back as it is now.
+    pub async fn get_entity_as_of(
+        &self,
+        id: EntityId,
+        as_of: DateTime<Utc>,
+    ) -> Result<Entity, GraphError> {
+        let q = query(
+            "MATCH (e:Entity {id: $id}) \
+             WHERE coalesce(e.created_at, e.last_updated) <= $as_of \
+             RETURN e",
+        )
+        .param("as_of", format_datetime(&as_of));
+        self.fetch_entity(q, id).await
+    }
+
+    async fn fetch_entity(&self, q: neo4rs::Query, id: EntityId) -> Result<Entity, GraphError> {
         let start = std::time::Instant::now();
 
-        let q = query("MATCH (e:Entity {id: $id}) RETURN e").param("id", id.to_string());
+        let q = q.param("id", id.to_string());
 
         let mut result = self
             .graph
@@ -151,6 +306,7 @@ impl super::GraphClient {
         let entity = node_to_entity(&node)?;
 
         metrics::histogram!("graph.entity.get.latency").record(start.elapsed().as_secs_f64());
+        record_read_latency("entity.get", start);
 
         Ok(entity)
     }
@@ -208,7 +364,26 @@ impl super::GraphClient {
             .unwrap_or_default();
 
         for (i, (prop_key, _)) in flat_props.iter().enumerate() {
-            set_clauses.push(format!("e.`{}` = $prop_{}", prop_key, i));
+            set_clauses.push(format!("e.{} = $prop_{}", quote_key(prop_key), i));
+        }
+        for scheme in update.identifiers.keys() {
+            let key = identifier_node_key(*scheme);
+            set_clauses.push(format!("e.{} = ${}", key, key));
+        }
+
+        let remove_clauses: Vec<String> = update
+            .remove_properties
+            .iter()
+            .flat_map(|key| {
+                let (value_key, type_key) = property_node_keys(key);
+                [
+                    format!("e.{}", quote_key(&value_key)),
+                    format!("e.{}", quote_key(&type_key)),
+                ]
+            })
+            .collect();
+        if !remove_clauses.is_empty() {
+            cypher.push_str(&format!(" REMOVE {}", remove_clauses.join(", ")));
         }
 
         cypher.push_str(&format!(" SET {} RETURN e", set_clauses.join(", ")));
@@ -244,7 +419,11 @@ impl super::GraphClient {
         for (i, (_, value)) in flat_props.iter().enumerate() {
             q = q.param(&format!("prop_{}", i), value.as_str());
         }
+        for (scheme, value) in &update.identifiers {
+            q = q.param(&identifier_node_key(*scheme), value.as_str());
+        }
 
+        let _permit = self.writes.acquire("entity.update").await;
         let mut result = self
             .graph
             .execute(q)
@@ -272,13 +451,17 @@ impl super::GraphClient {
     /// - PUBLISHED edges on claims pointing to source → target
     /// - REFERENCES edges on claims pointing to source → target
     /// - RELATES_TO edges (both directions) from source → target
-    /// - Combine aliases
+    /// - Combine aliases and identifiers
     /// - Delete source
+    ///
+    /// Entities whose identifiers conflict are different things, so merging
+    /// them is refused unless `force`; a forced merge keeps the target's values.
     pub async fn merge_entities(
         &self,
         source_id: EntityId,
         target_id: EntityId,
         _reason: Option<&str>,
+        force: bool,
     ) -> Result<Entity, GraphError> {
         let start = std::time::Instant::now();
 
@@ -289,9 +472,49 @@ impl super::GraphClient {
         }
 
         // Verify both entities exist.
-        let _ = self.get_entity(source_id).await?;
+        let source = self.get_entity(source_id).await?;
         let target = self.get_entity(target_id).await?;
 
+        // Seeded reference entities survive merges; merge the duplicate into them.
+        if source.is_seeded && !target.is_seeded {
+            return Err(GraphError::Query(format!(
+                "Entity {} is seeded reference data and cannot be merged away; merge {} into it instead",
+                source_id, target_id
+            )));
+        }
+
+        let conflicts = identifier_conflicts(&source.identifiers, &target.identifiers);
+        if !conflicts.is_empty() {
+            let schemes = conflicts
+                .iter()
+                .map(|scheme| scheme.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !force {
+                metrics::counter!("graph.entity.merge_refused", "reason" => "identifier_conflict")
+                    .increment(1);
+                return Err(GraphError::Query(format!(
+                    "Entities {} and {} have different identifiers ({}) and are likely different \
+                     things; pass force to merge them anyway",
+                    source_id, target_id, schemes
+                )));
+            }
+            tracing::warn!(
+                source_id = %source_id,
+                target_id = %target_id,
+                schemes = %schemes,
+                "Forced merge of entities with conflicting identifiers; keeping the target's"
+            );
+        }
+        // The target gains the source's identifiers under schemes it lacks.
+        let gained: Identifiers = source
+            .identifiers
+            .iter()
+            .filter(|(scheme, _)| !target.identifiers.contains_key(scheme))
+            .map(|(scheme, value)| (*scheme, value.clone()))
+            .collect();
+
+        let permit = self.writes.acquire("entity.merge").await;
         let mut txn = self
             .graph
             .start_txn()
@@ -387,12 +610,14 @@ impl super::GraphClient {
                  target.aliases_text = $aliases_text, \
                  target.last_updated = $last_updated, \
                  target.embedding_pending = true \
+             SET target += $identifiers \
              RETURN target",
         )
         .param("target_id", target_id.to_string())
         .param("aliases", aliases_json.as_str())
         .param("aliases_text", aliases_text.as_str())
-        .param("last_updated", now.as_str());
+        .param("last_updated", now.as_str())
+        .param("identifiers", identifier_node_map(&gained));
         txn.run(q6)
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
@@ -407,11 +632,143 @@ impl super::GraphClient {
         txn.commit()
             .await
             .map_err(|e| GraphError::Query(e.to_string()))?;
+        drop(permit);
 
         metrics::histogram!("graph.entity.merge.latency").record(start.elapsed().as_secs_f64());
         metrics::counter!("graph.entity.merge_count").increment(1);
 
+        // The target's digest has to cover the source's claims now.
+        if let Err(e) = self.rebuild_claim_digests(&[target_id]).await {
+            tracing::warn!(entity_id = %target_id, error = %e, "Failed to rebuild claim digest after merge");
+        }
+
         // Return the updated target entity.
         self.get_entity(target_id).await
     }
+
+    /// Add type tags to freeform properties written before tags existed,
+    /// keeping the value each was read as: JSON if it parsed, otherwise the
+    /// string. Run at startup; idempotent. Returns the entities tagged.
+    pub async fn backfill_property_types(&self) -> Result<u64, GraphError> {
+        let mut tagged = 0u64;
+        loop {
+            let q = query(
+                "MATCH (e:Entity) \
+                 WHERE any(k IN keys(e) WHERE k STARTS WITH $value_prefix \
+                     AND e[$type_prefix + substring(k, size($value_prefix))] IS NULL) \
+                 RETURN e LIMIT $limit",
+            )
+            .param("value_prefix", PROPERTY_PREFIX)
+            .param("type_prefix", PROPERTY_TYPE_PREFIX)
+            .param("limit", PROPERTY_BACKFILL_BATCH);
+            let mut result = self
+                .graph
+                .execute(q)
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+
+            let mut rows: Vec<HashMap<String, BoltType>> = Vec::new();
+            while let Some(row) = result
+                .next()
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?
+            {
+                let node: neo4rs::Node = row
+                    .get("e")
+                    .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+                let id: String = node
+                    .get("id")
+                    .map_err(|_| GraphError::Query("Entity node missing 'id'".into()))?;
+                let flat = flat_properties_of_node(&node);
+                let legacy: HashMap<String, Value> = flat
+                    .iter()
+                    .filter_map(|(node_key, val)| {
+                        let key = node_key.strip_prefix(PROPERTY_PREFIX)?;
+                        let (_, type_key) = property_node_keys(key);
+                        (!flat.contains_key(&type_key))
+                            .then(|| (key.to_string(), legacy_property_value(val)))
+                    })
+                    .collect();
+                if legacy.is_empty() {
+                    continue;
+                }
+                let properties: HashMap<String, String> =
+                    flatten_properties(&legacy).into_iter().collect();
+                rows.push(HashMap::from([
+                    ("id".to_string(), id.into()),
+                    ("properties".to_string(), properties.into()),
+                ]));
+            }
+
+            // Only untaggable (non-string) values left.
+            if rows.is_empty() {
+                break;
+            }
+            let count = rows.len() as u64;
+            let q = query(
+                "UNWIND $rows AS row \
+                 MATCH (e:Entity {id: row.id}) \
+                 SET e += row.properties",
+            )
+            .param("rows", rows);
+            let _permit = self.writes.acquire("entity.backfill_property_types").await;
+            self.graph
+                .run(q)
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+            tagged += count;
+        }
+        Ok(tagged)
+    }
+}
+
+/// An entity as an UNWIND row for `create_entities`.
+fn entity_row(
+    entity: &Entity,
+    embedding: Option<&[f32]>,
+) -> Result<HashMap<String, BoltType>, GraphError> {
+    let aliases_json = serde_json::to_string(&entity.aliases)
+        .map_err(|e| GraphError::Query(format!("Failed to serialize aliases: {}", e)))?;
+    let properties: HashMap<String, String> =
+        flatten_properties(&entity.properties).into_iter().collect();
+
+    Ok(HashMap::from([
+        ("id".to_string(), entity.id.to_string().into()),
+        (
+            "canonical_name".to_string(),
+            entity.canonical_name.as_str().into(),
+        ),
+        ("aliases".to_string(), aliases_json.into()),
+        (
+            "aliases_text".to_string(),
+            build_aliases_text(&entity.aliases).into(),
+        ),
+        ("kind".to_string(), entity.kind.as_str().into()),
+        ("is_stub".to_string(), entity.is_stub.into()),
+        (
+            "last_updated".to_string(),
+            format_datetime(&entity.last_updated).into(),
+        ),
+        ("embedding_pending".to_string(), embedding.is_none().into()),
+        (
+            "created_by_investigation".to_string(),
+            entity
+                .created_by_investigation
+                .map(|id| id.to_string())
+                .into(),
+        ),
+        ("is_seeded".to_string(), entity.is_seeded.into()),
+        ("summary".to_string(), entity.summary.clone().into()),
+        (
+            "embedding".to_string(),
+            embedding
+                .map(|v| v.iter().map(|&f| f as f64).collect::<Vec<_>>())
+                .into(),
+        ),
+        ("properties".to_string(), properties.into()),
+        (
+            "identifiers".to_string(),
+            identifier_node_map(&entity.identifiers).into(),
+        ),
+    ]))
 }
@@ -0,0 +1,189 @@
+use std::collections::HashSet;
+
+use neo4rs::query;
+
+use autosint_common::types::{Claim, Entity, Relationship};
+use autosint_common::{EntityId, InvestigationId};
+
+use super::conversions::{node_to_entity, parse_entity_id, row_to_claim, row_to_relationship};
+use super::writes::record_read_latency;
+use super::GraphError;
+
+/// Deepest RELATES_TO expansion an export may request.
+pub const MAX_EXPORT_DEPTH: u32 = 3;
+
+/// A self-contained slice of the graph: entities, the relationships among them,
+/// and the claims that reference them. Every list is ordered by ID.
+#[derive(Clone, Debug, Default)]
+pub struct Subgraph {
+    pub entities: Vec<Entity>,
+    pub relationships: Vec<Relationship>,
+    pub claims: Vec<Claim>,
+}
+
+impl super::GraphClient {
+    /// Export the neighborhood of `seeds`: every entity within `depth` RELATES_TO
+    /// hops (capped at `MAX_EXPORT_DEPTH`), relationships whose endpoints are both
+    /// in that set, and claims referencing any of those entities. Publishers of
+    /// those claims are included so claim provenance resolves within the export.
+    pub async fn export_subgraph(
+        &self,
+        seeds: &[EntityId],
+        depth: u32,
+    ) -> Result<Subgraph, GraphError> {
+        let start = std::time::Instant::now();
+
+        let seed_ids: Vec<String> = seeds.iter().map(|id| id.to_string()).collect();
+        let depth = depth.min(MAX_EXPORT_DEPTH);
+
+        // 1. Entities within `depth` hops. The hop bound can't be a parameter.
+        let mut entity_ids = self
+            .collect_ids(
+                query(&format!(
+                    "MATCH (seed:Entity) WHERE seed.id IN $ids \
+                     MATCH (seed)-[:RELATES_TO*0..{}]-(e:Entity) \
+                     RETURN DISTINCT e.id AS id",
+                    depth
+                ))
+                .param("ids", seed_ids),
+            )
+            .await?;
+
+        // 2. Relationships among them.
+        let mut relationships = Vec::new();
+        let mut result = self
+            .graph
+            .execute(
+                query(
+                    "MATCH (s:Entity)-[r:RELATES_TO]->(t:Entity) \
+                     WHERE s.id IN $ids AND t.id IN $ids \
+                     RETURN r, s.id AS source_id, t.id AS target_id \
+                     ORDER BY r.id",
+                )
+                .param("ids", entity_ids.clone()),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            relationships.push(row_to_relationship(row)?);
+        }
+
+        // 3. Claims referencing them, with publisher IDs.
+        let mut claims = Vec::new();
+        let mut result = self
+            .graph
+            .execute(
+                query(
+                    "MATCH (c:Claim)-[:REFERENCES]->(e:Entity) WHERE e.id IN $ids \
+                     WITH DISTINCT c \
+                     MATCH (source:Entity)-[:PUBLISHED]->(c) \
+                     OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
+                     WITH c, source, ref ORDER BY ref.id \
+                     RETURN c, source.id AS source_id, collect(ref.id) AS ref_ids \
+                     ORDER BY c.id",
+                )
+                .param("ids", entity_ids.clone()),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            claims.push(row_to_claim(row)?);
+        }
+
+        entity_ids.extend(claims.iter().map(|c| c.source_entity_id.to_string()));
+        entity_ids.sort();
+        entity_ids.dedup();
+
+        // 4. Entity nodes for everything collected.
+        let mut entities = Vec::new();
+        let mut result = self
+            .graph
+            .execute(
+                query("MATCH (e:Entity) WHERE e.id IN $ids RETURN e ORDER BY e.id")
+                    .param("ids", entity_ids),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("e")
+                .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+            entities.push(node_to_entity(&node)?);
+        }
+
+        metrics::histogram!("graph.export.latency").record(start.elapsed().as_secs_f64());
+        record_read_latency("export", start);
+
+        Ok(Subgraph {
+            entities,
+            relationships,
+            claims,
+        })
+    }
+
+    /// Export what an investigation touched: entities it created, endpoints of
+    /// relationships it created, and entities its claims publish or reference —
+    /// expanded through `export_subgraph` with depth 0.
+    pub async fn export_investigation_subgraph(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Subgraph, GraphError> {
+        let ids = self
+            .collect_ids(
+                query(
+                    "CALL { \
+                       MATCH (e:Entity {created_by_investigation: $investigation_id}) \
+                       RETURN e.id AS id \
+                       UNION \
+                       MATCH (e:Entity)-[:RELATES_TO {created_by_investigation: $investigation_id}]-() \
+                       RETURN e.id AS id \
+                       UNION \
+                       MATCH (e:Entity)-[:PUBLISHED|REFERENCES]-(:Claim {investigation_id: $investigation_id}) \
+                       RETURN e.id AS id \
+                     } \
+                     RETURN id",
+                )
+                .param("investigation_id", investigation_id.to_string()),
+            )
+            .await?;
+
+        let seeds = ids
+            .iter()
+            .map(|s| parse_entity_id(s))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.export_subgraph(&seeds, 0).await
+    }
+
+    /// Of `ids`, those already used by an entity, claim or relationship.
+    pub async fn existing_ids(&self, ids: Vec<String>) -> Result<HashSet<String>, GraphError> {
+        let found = self
+            .collect_ids(
+                query(
+                    "CALL { \
+                       MATCH (e:Entity) WHERE e.id IN $ids RETURN e.id AS id \
+                       UNION \
+                       MATCH (c:Claim) WHERE c.id IN $ids RETURN c.id AS id \
+                       UNION \
+                       MATCH ()-[r:RELATES_TO]->() WHERE r.id IN $ids RETURN r.id AS id \
+                     } \
+                     RETURN id",
+                )
+                .param("ids", ids),
+            )
+            .await?;
+        Ok(found.into_iter().collect())
+    }
+}
@@ -0,0 +1,219 @@
+//! Entities written too recently for the vector index to return them. Neo4j
+//! populates vector indexes asynchronously, so an entity is invisible to
+//! `db.index.vector.queryNodes` for a few seconds after it is created; vector
+//! lookups are supplemented with a scan of recently updated entities, scored
+//! against their stored embeddings here.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use neo4rs::query;
+
+use autosint_common::config::FreshEntityScanConfig;
+use autosint_common::types::Entity;
+
+use super::conversions::{format_datetime, node_to_entity};
+use super::search::SearchResult;
+use super::GraphError;
+
+impl super::GraphClient {
+    /// Entities updated within `config.window_seconds` that have an
+    /// embedding, scored against `embedding` on the vector index's scale,
+    /// best first. Empty, with a warning, if more than `config.max_scan`
+    /// were updated in the window.
+    pub async fn fresh_entity_matches(
+        &self,
+        embedding: &[f32],
+        config: &FreshEntityScanConfig,
+    ) -> Result<Vec<SearchResult<Entity>>, GraphError> {
+        let start = std::time::Instant::now();
+        let since = Utc::now() - chrono::Duration::seconds(config.window_seconds as i64);
+
+        // One row past the cap tells a full window from one exactly at it.
+        let q = query(
+            "MATCH (e:Entity) \
+             WHERE e.last_updated >= $since AND e.embedding IS NOT NULL \
+             RETURN e AS node \
+             LIMIT $limit",
+        )
+        .param("since", format_datetime(&since))
+        .param("limit", config.max_scan as i64 + 1);
+
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut entities = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("node")
+                .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
+            entities.push(node_to_entity(&node)?);
+        }
+
+        if entities.len() > config.max_scan as usize {
+            metrics::counter!("graph.fresh_scan.skipped").increment(1);
+            tracing::warn!(
+                window_seconds = config.window_seconds,
+                max_scan = config.max_scan,
+                "Too many recently updated entities to scan, relying on the vector index alone"
+            );
+            return Ok(Vec::new());
+        }
+
+        let matches = score_entities(embedding, entities);
+        metrics::histogram!("graph.fresh_scan.latency").record(start.elapsed().as_secs_f64());
+        Ok(matches)
+    }
+}
+
+/// Score entities against `embedding`, best first. Entities whose embedding
+/// has another dimension are skipped.
+pub fn score_entities(embedding: &[f32], entities: Vec<Entity>) -> Vec<SearchResult<Entity>> {
+    let mut scored: Vec<SearchResult<Entity>> = entities
+        .into_iter()
+        .filter_map(|entity| {
+            let score = index_score(embedding, entity.embedding.as_deref()?)?;
+            Some(SearchResult {
+                item: entity,
+                score,
+            })
+        })
+        .collect();
+    sort_by_score(&mut scored);
+    scored
+}
+
+/// Cosine similarity on the scale of Neo4j's cosine vector index, which
+/// reports (1 + cosine) / 2, so fresh and indexed scores compare directly.
+/// None for vectors of different or zero length.
+pub fn index_score(a: &[f32], b: &[f32]) -> Option<f64> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| *x as f64 * *y as f64).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| (*x as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        return None;
+    }
+    Some((1.0 + dot / norms) / 2.0)
+}
+
+/// Merge index hits with fresh-scan hits, best first. An entity found by
+/// both keeps its higher score.
+pub fn merge_fresh(
+    indexed: Vec<SearchResult<Entity>>,
+    fresh: Vec<SearchResult<Entity>>,
+) -> Vec<SearchResult<Entity>> {
+    let mut merged: Vec<SearchResult<Entity>> = Vec::with_capacity(indexed.len() + fresh.len());
+    let mut positions = HashMap::new();
+    for hit in indexed.into_iter().chain(fresh) {
+        match positions.get(&hit.item.id) {
+            Some(&i) => {
+                let existing: &mut SearchResult<Entity> = &mut merged[i];
+                if hit.score > existing.score {
+                    *existing = hit;
+                }
+            }
+            None => {
+                positions.insert(hit.item.id, merged.len());
+                merged.push(hit);
+            }
+        }
+    }
+    sort_by_score(&mut merged);
+    merged
+}
+
+fn sort_by_score(results: &mut [SearchResult<Entity>]) {
+    results.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.item.id.to_string().cmp(&b.item.id.to_string()))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(name: &str, embedding: Option<Vec<f32>>) -> Entity {
+        let mut entity = Entity::new(name.into(), "organization".into());
+        entity.embedding = embedding;
+        entity
+    }
+
+    #[test]
+    fn test_index_score_scale() {
+        assert_eq!(index_score(&[1.0, 0.0], &[2.0, 0.0]), Some(1.0));
+        assert_eq!(index_score(&[1.0, 0.0], &[0.0, 1.0]), Some(0.5));
+        assert_eq!(index_score(&[1.0, 0.0], &[-1.0, 0.0]), Some(0.0));
+        assert_eq!(index_score(&[1.0, 0.0], &[1.0, 0.0, 0.0]), None);
+        assert_eq!(index_score(&[0.0, 0.0], &[1.0, 0.0]), None);
+        assert_eq!(index_score(&[], &[]), None);
+    }
+
+    #[test]
+    fn test_score_entities_best_first_skipping_unusable() {
+        let scored = score_entities(
+            &[1.0, 0.0],
+            vec![
+                entity("Orthogonal", Some(vec![0.0, 1.0])),
+                entity("No embedding", None),
+                entity("Close", Some(vec![0.9, 0.1])),
+                entity("Wrong dimensions", Some(vec![1.0, 0.0, 0.0])),
+            ],
+        );
+        let names: Vec<&str> = scored
+            .iter()
+            .map(|r| r.item.canonical_name.as_str())
+            .collect();
+        assert_eq!(names, ["Close", "Orthogonal"]);
+    }
+
+    #[test]
+    fn test_merge_keeps_best_score_per_entity() {
+        let shared = entity("Shared", None);
+        let indexed = vec![
+            SearchResult {
+                item: shared.clone(),
+                score: 0.91,
+            },
+            SearchResult {
+                item: entity("Indexed only", None),
+                score: 0.80,
+            },
+        ];
+        let fresh = vec![
+            SearchResult {
+                item: entity("Fresh only", None),
+                score: 0.97,
+            },
+            SearchResult {
+                item: shared.clone(),
+                score: 0.93,
+            },
+        ];
+
+        let merged = merge_fresh(indexed, fresh);
+        let ranked: Vec<(&str, f64)> = merged
+            .iter()
+            .map(|r| (r.item.canonical_name.as_str(), r.score))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                ("Fresh only", 0.97),
+                ("Shared", 0.93),
+                ("Indexed only", 0.80)
+            ]
+        );
+    }
+}
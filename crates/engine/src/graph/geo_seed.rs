@@ -0,0 +1,513 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::types::{iso_region_code, Entity};
+
+use super::conversions::{
+    build_aliases_text, flatten_properties, format_datetime, node_to_entity, quote_key,
+};
+use super::{GraphClient, GraphError};
+
+pub use autosint_common::api::engine::GeoSeedReport;
+
+/// Seed file location, relative to the config directory.
+pub const GEO_SEED_FILE: &str = "seed/geo.csv";
+
+const HEADER: &str = "kind,iso_code,iso_alpha3,canonical_name,latitude,longitude,aliases";
+
+/// Entity kinds an unseeded entity may carry and still be adopted by a region record.
+const REGION_KINDS: &[&str] = &["region", "state", "province", "territory"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum GeoSeedError {
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Geo seed line {line}: {detail}")]
+    Parse { line: usize, detail: String },
+}
+
+/// Country (ISO 3166-1) or first-level subdivision (ISO 3166-2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeoSeedKind {
+    Country,
+    Region,
+}
+
+impl GeoSeedKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Country => "country",
+            Self::Region => "region",
+        }
+    }
+}
+
+/// One row of the geo seed file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeoSeedRecord {
+    pub kind: GeoSeedKind,
+    /// ISO 3166-1 alpha-2 for countries, ISO 3166-2 for regions ("US-CA").
+    pub iso_code: String,
+    /// ISO 3166-1 alpha-3, countries only.
+    pub iso_alpha3: Option<String>,
+    pub canonical_name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub aliases: Vec<String>,
+}
+
+impl GeoSeedRecord {
+    /// Freeform properties written to the entity.
+    pub fn properties(&self) -> HashMap<String, Value> {
+        let mut props = HashMap::new();
+        props.insert("iso_code".to_string(), json!(self.iso_code));
+        match self.kind {
+            GeoSeedKind::Country => {
+                props.insert("iso_alpha2".to_string(), json!(self.iso_code));
+                if let Some(ref alpha3) = self.iso_alpha3 {
+                    props.insert("iso_alpha3".to_string(), json!(alpha3));
+                }
+            }
+            GeoSeedKind::Region => {
+                props.insert("iso_3166_2".to_string(), json!(self.iso_code));
+                if let Some((country, _)) = self.iso_code.split_once('-') {
+                    props.insert("country_iso_alpha2".to_string(), json!(country));
+                }
+            }
+        }
+        props.insert("latitude".to_string(), json!(self.latitude));
+        props.insert("longitude".to_string(), json!(self.longitude));
+        props
+    }
+
+    /// Lowercased canonical name and aliases, for adopting unseeded entities.
+    fn names_lower(&self) -> Vec<String> {
+        std::iter::once(&self.canonical_name)
+            .chain(&self.aliases)
+            .map(|n| n.to_lowercase())
+            .collect()
+    }
+}
+
+/// Read and parse `seed/geo.csv` from the config directory.
+pub fn load_geo_seed(config_dir: &Path) -> Result<Vec<GeoSeedRecord>, GeoSeedError> {
+    let path = config_dir.join(GEO_SEED_FILE);
+    let content = std::fs::read_to_string(&path).map_err(|e| GeoSeedError::Read {
+        path: path.clone(),
+        source: e,
+    })?;
+    parse_geo_seed(&content)
+}
+
+/// Parse geo seed CSV. `#` lines are comments; the first other line must be the
+/// header. Fields are plain (no quoting) and aliases are `|`-separated.
+pub fn parse_geo_seed(content: &str) -> Result<Vec<GeoSeedRecord>, GeoSeedError> {
+    let mut records = Vec::new();
+    let mut seen_codes = HashSet::new();
+    let mut header_seen = false;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line_no = i + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parse_err = |detail: String| GeoSeedError::Parse {
+            line: line_no,
+            detail,
+        };
+
+        if !header_seen {
+            if line != HEADER {
+                return Err(parse_err(format!("expected header '{}'", HEADER)));
+            }
+            header_seen = true;
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [kind, iso_code, iso_alpha3, name, latitude, longitude, aliases] = fields[..] else {
+            return Err(parse_err(format!(
+                "expected 7 fields, got {}",
+                fields.len()
+            )));
+        };
+
+        let kind = match kind {
+            "country" => GeoSeedKind::Country,
+            "region" => GeoSeedKind::Region,
+            other => return Err(parse_err(format!("unknown kind '{}'", other))),
+        };
+
+        let code_ok = match kind {
+            GeoSeedKind::Country => is_upper_alpha(iso_code, 2),
+            GeoSeedKind::Region => iso_code.split_once('-').is_some_and(|(country, sub)| {
+                is_upper_alpha(country, 2)
+                    && (1..=3).contains(&sub.len())
+                    && sub
+                        .chars()
+                        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            }),
+        };
+        if !code_ok {
+            return Err(parse_err(format!(
+                "invalid ISO code '{}' for {}",
+                iso_code,
+                kind.as_str()
+            )));
+        }
+        if !seen_codes.insert(iso_code.to_string()) {
+            return Err(parse_err(format!("duplicate ISO code '{}'", iso_code)));
+        }
+
+        let iso_alpha3 = match (kind, iso_alpha3) {
+            (GeoSeedKind::Country, code) if is_upper_alpha(code, 3) => Some(code.to_string()),
+            (GeoSeedKind::Country, code) => {
+                return Err(parse_err(format!("invalid ISO alpha-3 '{}'", code)))
+            }
+            (GeoSeedKind::Region, "") => None,
+            (GeoSeedKind::Region, _) => {
+                return Err(parse_err("regions have no ISO alpha-3".into()))
+            }
+        };
+
+        if name.is_empty() {
+            return Err(parse_err("empty canonical_name".into()));
+        }
+
+        let coordinate = |value: &str, field: &str, bound: f64| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.abs() <= bound)
+                .ok_or_else(|| parse_err(format!("invalid {} '{}'", field, value)))
+        };
+        let latitude = coordinate(latitude, "latitude", 90.0)?;
+        let longitude = coordinate(longitude, "longitude", 180.0)?;
+
+        let aliases = aliases
+            .split('|')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        records.push(GeoSeedRecord {
+            kind,
+            iso_code: iso_code.to_string(),
+            iso_alpha3,
+            canonical_name: name.to_string(),
+            latitude,
+            longitude,
+            aliases,
+        });
+    }
+
+    if !header_seen {
+        return Err(GeoSeedError::Parse {
+            line: 0,
+            detail: "missing header".into(),
+        });
+    }
+    Ok(records)
+}
+
+fn is_upper_alpha(s: &str, len: usize) -> bool {
+    s.len() == len && s.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// The ISO code a seeded entity carries.
+fn seeded_iso_code(entity: &Entity) -> Option<String> {
+    entity
+        .properties
+        .get("iso_code")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Aliases for the seeded entity: the seed aliases plus any names the existing
+/// entity was known by, minus the canonical name, deduplicated case-insensitively.
+fn merged_aliases(record: &GeoSeedRecord, existing: Option<&Entity>) -> Vec<String> {
+    let mut seen = HashSet::from([record.canonical_name.to_lowercase()]);
+    let existing_names = existing
+        .into_iter()
+        .flat_map(|e| std::iter::once(&e.canonical_name).chain(&e.aliases));
+    record
+        .aliases
+        .iter()
+        .chain(existing_names)
+        .filter(|a| seen.insert(a.to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+impl GraphClient {
+    /// Create or refresh a seeded entity for every record.
+    ///
+    /// An entity already carrying the record's `iso_code` is refreshed in place;
+    /// otherwise an unseeded entity of a matching kind whose canonical name equals
+    /// one of the record's names is adopted. Only when neither exists is a new
+    /// entity created. Re-running with the same records creates nothing.
+    pub async fn seed_geo_entities(
+        &self,
+        records: &[GeoSeedRecord],
+    ) -> Result<GeoSeedReport, GraphError> {
+        let start = std::time::Instant::now();
+        let mut report = GeoSeedReport {
+            records: records.len() as u64,
+            ..Default::default()
+        };
+
+        for record in records {
+            match self.find_geo_seed_target(record).await? {
+                Some(existing) => {
+                    self.refresh_seeded_entity(&existing, record).await?;
+                    report.updated += 1;
+                }
+                None => {
+                    let mut entity =
+                        Entity::new(record.canonical_name.clone(), record.kind.as_str().into());
+                    entity.aliases = merged_aliases(record, None);
+                    entity.properties = record.properties();
+                    entity.is_seeded = true;
+                    self.create_entity(&entity, None).await?;
+                    report.created += 1;
+                }
+            }
+        }
+
+        metrics::histogram!("graph.geo_seed.latency").record(start.elapsed().as_secs_f64());
+        tracing::info!(
+            records = report.records,
+            created = report.created,
+            updated = report.updated,
+            "Geo entities seeded"
+        );
+
+        Ok(report)
+    }
+
+    /// Normalize a claim region against the seeded geo entities. A code is
+    /// matched on `iso_code` or `iso_alpha3`, free text on canonical name or
+    /// alias (case-insensitive); either way the seeded entity's `iso_code` is
+    /// returned. An unmatched code comes back uppercased and unmatched free
+    /// text trimmed. None for a blank region.
+    pub async fn normalize_region(&self, region: &str) -> Result<Option<String>, GraphError> {
+        let region = region.trim();
+        if region.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(code) = iso_region_code(region) {
+            let by_code = query(
+                "MATCH (e:Entity) \
+                 WHERE coalesce(e.is_seeded, false) \
+                   AND (e.prop_iso_code = $code OR e.prop_iso_alpha3 = $code) \
+                 RETURN e LIMIT 1",
+            )
+            .param("code", code.as_str());
+            let entity = self.fetch_one_entity(by_code).await?;
+            return Ok(Some(
+                entity.and_then(|e| seeded_iso_code(&e)).unwrap_or(code),
+            ));
+        }
+
+        // aliases_text narrows the candidates; exact alias equality is
+        // checked here so "Georgia" doesn't match "South Georgia".
+        let name = region.to_lowercase();
+        let mut result = self
+            .graph
+            .execute(
+                query(
+                    "MATCH (e:Entity) \
+                     WHERE coalesce(e.is_seeded, false) \
+                       AND (toLower(e.canonical_name) = $name \
+                            OR toLower(e.aliases_text) CONTAINS $name) \
+                     RETURN e ORDER BY toLower(e.canonical_name) = $name DESC",
+                )
+                .param("name", name.as_str()),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("e")
+                .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+            let entity = node_to_entity(&node)?;
+            let named = std::iter::once(&entity.canonical_name)
+                .chain(&entity.aliases)
+                .any(|n| n.to_lowercase() == name);
+            if let Some(code) = seeded_iso_code(&entity).filter(|_| named) {
+                return Ok(Some(code));
+            }
+        }
+        Ok(Some(region.to_string()))
+    }
+
+    async fn find_geo_seed_target(
+        &self,
+        record: &GeoSeedRecord,
+    ) -> Result<Option<Entity>, GraphError> {
+        let by_code = query(
+            "MATCH (e:Entity) WHERE e.prop_iso_code = $iso_code \
+             RETURN e ORDER BY coalesce(e.is_seeded, false) DESC LIMIT 1",
+        )
+        .param("iso_code", record.iso_code.as_str());
+        if let Some(entity) = self.fetch_one_entity(by_code).await? {
+            return Ok(Some(entity));
+        }
+
+        let kinds: Vec<&str> = match record.kind {
+            GeoSeedKind::Country => vec!["country"],
+            GeoSeedKind::Region => REGION_KINDS.to_vec(),
+        };
+        let by_name = query(
+            "MATCH (e:Entity) \
+             WHERE NOT coalesce(e.is_seeded, false) \
+               AND toLower(e.kind) IN $kinds \
+               AND toLower(e.canonical_name) IN $names \
+             RETURN e ORDER BY e.last_updated LIMIT 1",
+        )
+        .param("kinds", kinds)
+        .param("names", record.names_lower());
+        self.fetch_one_entity(by_name).await
+    }
+
+    async fn fetch_one_entity(&self, q: neo4rs::Query) -> Result<Option<Entity>, GraphError> {
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        match result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            Some(row) => {
+                let node: neo4rs::Node = row
+                    .get("e")
+                    .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+                Ok(Some(node_to_entity(&node)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn refresh_seeded_entity(
+        &self,
+        existing: &Entity,
+        record: &GeoSeedRecord,
+    ) -> Result<(), GraphError> {
+        let aliases = merged_aliases(record, Some(existing));
+        let aliases_json = serde_json::to_string(&aliases)
+            .map_err(|e| GraphError::Query(format!("Failed to serialize aliases: {}", e)))?;
+
+        let mut cypher = String::from(
+            "MATCH (e:Entity {id: $id}) \
+             SET e.canonical_name = $canonical_name, \
+                 e.aliases = $aliases, \
+                 e.aliases_text = $aliases_text, \
+                 e.kind = $kind, \
+                 e.is_seeded = true, \
+                 e.last_updated = $last_updated",
+        );
+        let flat_props = flatten_properties(&record.properties());
+        for (i, (prop_key, _)) in flat_props.iter().enumerate() {
+            cypher.push_str(&format!(", e.{} = $prop_{}", quote_key(prop_key), i));
+        }
+
+        let mut q = query(&cypher)
+            .param("id", existing.id.to_string())
+            .param("canonical_name", record.canonical_name.as_str())
+            .param("aliases", aliases_json.as_str())
+            .param("aliases_text", build_aliases_text(&aliases).as_str())
+            .param("kind", record.kind.as_str())
+            .param(
+                "last_updated",
+                format_datetime(&chrono::Utc::now()).as_str(),
+            );
+        for (i, (_, value)) in flat_props.iter().enumerate() {
+            q = q.param(&format!("prop_{}", i), value.as_str());
+        }
+
+        let _permit = self.writes.acquire("entity.refresh_seeded").await;
+        self.graph
+            .run(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUNDLED: &str = include_str!("../../../../config/seed/geo.csv");
+
+    fn parse_one(row: &str) -> Result<Vec<GeoSeedRecord>, GeoSeedError> {
+        parse_geo_seed(&format!("{}\n{}\n", HEADER, row))
+    }
+
+    #[test]
+    fn test_bundled_seed_file_parses() {
+        let records = parse_geo_seed(BUNDLED).unwrap();
+        let countries = records
+            .iter()
+            .filter(|r| r.kind == GeoSeedKind::Country)
+            .count();
+        assert_eq!(countries, 249);
+
+        let us = records.iter().find(|r| r.iso_code == "US").unwrap();
+        assert_eq!(us.canonical_name, "United States");
+        assert_eq!(us.iso_alpha3.as_deref(), Some("USA"));
+        assert!(us.aliases.iter().any(|a| a == "U.S."));
+
+        let california = records.iter().find(|r| r.iso_code == "US-CA").unwrap();
+        assert_eq!(california.kind, GeoSeedKind::Region);
+        assert_eq!(california.properties()["country_iso_alpha2"], json!("US"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_rows() {
+        assert!(parse_one("country,USA,USA,United States,0,0,").is_err());
+        assert!(parse_one("country,US,US,United States,0,0,").is_err());
+        assert!(parse_one("region,US-CA,USA,California,0,0,").is_err());
+        assert!(parse_one("country,US,USA,United States,95,0,").is_err());
+        assert!(parse_one("planet,EA,EAR,Earth,0,0,").is_err());
+        assert!(parse_one("country,US,USA,United States,0,0").is_err());
+        assert!(parse_geo_seed("# comment only\n").is_err());
+
+        let dup = format!(
+            "{}\ncountry,US,USA,United States,0,0,\ncountry,US,USA,America,0,0,\n",
+            HEADER
+        );
+        assert!(matches!(
+            parse_geo_seed(&dup),
+            Err(GeoSeedError::Parse { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_merged_aliases_keep_previous_names() {
+        let record = &parse_one("country,US,USA,United States,0,0,USA|U.S.").unwrap()[0];
+        let mut existing = Entity::new("U.S.".into(), "country".into());
+        existing.aliases = vec!["usa".into(), "Uncle Sam".into()];
+
+        assert_eq!(
+            merged_aliases(record, Some(&existing)),
+            vec!["USA", "U.S.", "Uncle Sam"]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Batched reads and writes for maintenance jobs. Reads walk nodes in `id`
+//! order a page at a time, starting after the last ID of the page before, so
+//! a job's checkpoint is just that ID. Writes are single UNWIND statements.
+
+use std::collections::HashMap;
+
+use neo4rs::{query, BoltType, Query, Row};
+
+use super::conversions::{embedding_text_for_claim, embedding_text_for_entity};
+use super::{GraphClient, GraphError};
+
+impl GraphClient {
+    /// Number of entities.
+    pub async fn count_entities(&self) -> Result<u64, GraphError> {
+        self.count("MATCH (e:Entity) RETURN count(e) AS n").await
+    }
+
+    /// Number of claims.
+    pub async fn count_claims(&self) -> Result<u64, GraphError> {
+        self.count("MATCH (c:Claim) RETURN count(c) AS n").await
+    }
+
+    /// Up to `limit` entities after `after` in ID order, each with its
+    /// embedding text.
+    pub async fn entity_embedding_page(
+        &self,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<(String, String)>, GraphError> {
+        let rows = self
+            .page(
+                "MATCH (e:Entity) WHERE $after IS NULL OR e.id > $after \
+                 RETURN e.id AS id, e.canonical_name AS name, e.summary AS summary \
+                 ORDER BY e.id LIMIT $limit",
+                after,
+                limit,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                let name: String = row.get("name").unwrap_or_default();
+                let summary: Option<String> = row.get("summary").ok();
+                Ok((
+                    row_id(row)?,
+                    embedding_text_for_entity(&name, summary.as_deref()),
+                ))
+            })
+            .collect()
+    }
+
+    /// Up to `limit` claims after `after` in ID order, each with its embedding
+    /// text.
+    pub async fn claim_embedding_page(
+        &self,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<(String, String)>, GraphError> {
+        let rows = self
+            .page(
+                "MATCH (c:Claim) WHERE $after IS NULL OR c.id > $after \
+                 RETURN c.id AS id, c.content AS content \
+                 ORDER BY c.id LIMIT $limit",
+                after,
+                limit,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                let content: String = row.get("content").unwrap_or_default();
+                Ok((row_id(row)?, embedding_text_for_claim(&content)))
+            })
+            .collect()
+    }
+
+    /// Up to `limit` entities after `after` in ID order, with their kinds.
+    pub async fn entity_kind_page(
+        &self,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<(String, String)>, GraphError> {
+        let rows = self
+            .page(
+                "MATCH (e:Entity) WHERE $after IS NULL OR e.id > $after \
+                 RETURN e.id AS id, e.kind AS kind \
+                 ORDER BY e.id LIMIT $limit",
+                after,
+                limit,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| Ok((row_id(row)?, row.get("kind").unwrap_or_default())))
+            .collect()
+    }
+
+    /// Up to `limit` entities after `after` in ID order, with their stored
+    /// `aliases` JSON and `aliases_text`.
+    pub async fn entity_aliases_page(
+        &self,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<(String, Option<String>, Option<String>)>, GraphError> {
+        let rows = self
+            .page(
+                "MATCH (e:Entity) WHERE $after IS NULL OR e.id > $after \
+                 RETURN e.id AS id, e.aliases AS aliases, e.aliases_text AS aliases_text \
+                 ORDER BY e.id LIMIT $limit",
+                after,
+                limit,
+            )
+            .await?;
+        rows.iter()
+            .map(|row| {
+                Ok((
+                    row_id(row)?,
+                    row.get("aliases").ok(),
+                    row.get("aliases_text").ok(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Set each entity's embedding. Returns how many entities were found.
+    pub async fn set_entity_embeddings(
+        &self,
+        embeddings: &[(String, Vec<f32>)],
+    ) -> Result<u64, GraphError> {
+        self.update_batch(
+            "entity.set_embeddings",
+            "UNWIND $rows AS row \
+             MATCH (e:Entity {id: row.id}) \
+             SET e.embedding = row.value, e.embedding_pending = false \
+             RETURN count(e) AS n",
+            embedding_rows(embeddings),
+        )
+        .await
+    }
+
+    /// Set each claim's embedding. Returns how many claims were found.
+    pub async fn set_claim_embeddings(
+        &self,
+        embeddings: &[(String, Vec<f32>)],
+    ) -> Result<u64, GraphError> {
+        self.update_batch(
+            "claim.set_embeddings",
+            "UNWIND $rows AS row \
+             MATCH (c:Claim {id: row.id}) \
+             SET c.embedding = row.value, c.embedding_pending = false \
+             RETURN count(c) AS n",
+            embedding_rows(embeddings),
+        )
+        .await
+    }
+
+    /// Set each entity's kind. Returns how many entities were found.
+    pub async fn set_entity_kinds(&self, kinds: &[(String, String)]) -> Result<u64, GraphError> {
+        self.update_batch(
+            "entity.set_kinds",
+            "UNWIND $rows AS row \
+             MATCH (e:Entity {id: row.id}) \
+             SET e.kind = row.value \
+             RETURN count(e) AS n",
+            string_rows(kinds),
+        )
+        .await
+    }
+
+    /// Set each entity's `aliases_text`. Returns how many entities were found.
+    pub async fn set_entity_aliases_text(
+        &self,
+        texts: &[(String, String)],
+    ) -> Result<u64, GraphError> {
+        self.update_batch(
+            "entity.set_aliases_text",
+            "UNWIND $rows AS row \
+             MATCH (e:Entity {id: row.id}) \
+             SET e.aliases_text = row.value \
+             RETURN count(e) AS n",
+            string_rows(texts),
+        )
+        .await
+    }
+
+    async fn count(&self, cypher: &str) -> Result<u64, GraphError> {
+        self.scalar(query(cypher)).await
+    }
+
+    async fn page(
+        &self,
+        cypher: &str,
+        after: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<Row>, GraphError> {
+        let q = query(cypher)
+            .param("after", after.map(str::to_string))
+            .param("limit", limit as i64);
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let mut rows = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    async fn update_batch(
+        &self,
+        op: &'static str,
+        cypher: &str,
+        rows: Vec<HashMap<String, BoltType>>,
+    ) -> Result<u64, GraphError> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let _permit = self.writes.acquire(op).await;
+        self.scalar(query(cypher).param("rows", rows)).await
+    }
+
+    /// The `n` column of `q`'s first row.
+    async fn scalar(&self, q: Query) -> Result<u64, GraphError> {
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let n: i64 = match result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            Some(row) => row.get("n").unwrap_or(0),
+            None => 0,
+        };
+        Ok(n.max(0) as u64)
+    }
+}
+
+fn row_id(row: &Row) -> Result<String, GraphError> {
+    row.get("id")
+        .map_err(|e| GraphError::Query(format!("Missing 'id' column: {}", e)))
+}
+
+fn embedding_rows(embeddings: &[(String, Vec<f32>)]) -> Vec<HashMap<String, BoltType>> {
+    embeddings
+        .iter()
+        .map(|(id, embedding)| {
+            let value: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
+            HashMap::from([
+                ("id".to_string(), id.as_str().into()),
+                ("value".to_string(), value.into()),
+            ])
+        })
+        .collect()
+}
+
+fn string_rows(values: &[(String, String)]) -> Vec<HashMap<String, BoltType>> {
+    values
+        .iter()
+        .map(|(id, value)| {
+            HashMap::from([
+                ("id".to_string(), id.as_str().into()),
+                ("value".to_string(), value.as_str().into()),
+            ])
+        })
+        .collect()
+}
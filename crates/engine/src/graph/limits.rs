@@ -0,0 +1,184 @@
+use autosint_common::types::{Claim, Entity, Relationship};
+
+/// Fixed per-row allowance for IDs, timestamps and scalar fields in
+/// [`ApproxBytes`] estimates.
+const ROW_OVERHEAD_BYTES: usize = 200;
+
+/// How much a graph read returned, and whether it held anything back.
+///
+/// Reads fetch one row past their limit: when that sentinel row comes back
+/// it is dropped and `truncated` is set, so callers can say "more matched"
+/// without a second count query.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResultSize {
+    /// Rows returned.
+    pub rows: usize,
+    /// The limit the read ran with, after the ceiling.
+    pub limit: u32,
+    /// Rough serialized size of the returned rows, embeddings included.
+    pub approx_bytes: usize,
+    /// More rows matched than were returned.
+    pub truncated: bool,
+    /// The caller's limit, when the `graph_reads` ceiling lowered it.
+    pub clamped_from: Option<u32>,
+}
+
+/// A read's row limit after the server-side ceiling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct ReadLimit {
+    pub limit: u32,
+    pub clamped_from: Option<u32>,
+}
+
+impl ReadLimit {
+    /// `requested` (or `default`), lowered to `ceiling`. Lowering counts in
+    /// `graph.read.clamped` under `read`.
+    pub fn new(requested: Option<u32>, default: u32, ceiling: u32, read: &'static str) -> Self {
+        let wanted = requested.unwrap_or(default).max(1);
+        let ceiling = ceiling.max(1);
+        if wanted <= ceiling {
+            return Self {
+                limit: wanted,
+                clamped_from: None,
+            };
+        }
+        metrics::counter!("graph.read.clamped", "read" => read).increment(1);
+        tracing::debug!(
+            read,
+            requested = wanted,
+            ceiling,
+            "Graph read limit clamped"
+        );
+        Self {
+            limit: ceiling,
+            clamped_from: Some(wanted),
+        }
+    }
+
+    /// Rows to ask Neo4j for: the limit plus the sentinel.
+    pub fn fetch(&self) -> u32 {
+        self.limit.saturating_add(1)
+    }
+
+    /// Drop the sentinel row from `rows`, if it came back, and size the rest.
+    pub fn finish<T>(&self, rows: &mut Vec<T>, bytes: impl Fn(&T) -> usize) -> ResultSize {
+        let truncated = rows.len() > self.limit as usize;
+        rows.truncate(self.limit as usize);
+        ResultSize {
+            rows: rows.len(),
+            limit: self.limit,
+            approx_bytes: rows.iter().map(bytes).sum(),
+            truncated,
+            clamped_from: self.clamped_from,
+        }
+    }
+}
+
+/// A cheap estimate of a graph item's serialized size.
+pub(super) trait ApproxBytes {
+    fn approx_bytes(&self) -> usize;
+}
+
+fn embedding_bytes(embedding: &Option<Vec<f32>>) -> usize {
+    // ~10 characters per float once serialized as JSON.
+    embedding.as_ref().map_or(0, |v| v.len() * 10)
+}
+
+impl ApproxBytes for Entity {
+    fn approx_bytes(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + self.canonical_name.len()
+            + self.kind.len()
+            + self.summary.as_ref().map_or(0, String::len)
+            + self.aliases.iter().map(String::len).sum::<usize>()
+            + self
+                .properties
+                .iter()
+                .map(|(k, v)| k.len() + v.to_string().len())
+                .sum::<usize>()
+            + embedding_bytes(&self.embedding)
+    }
+}
+
+impl ApproxBytes for Claim {
+    fn approx_bytes(&self) -> usize {
+        ROW_OVERHEAD_BYTES
+            + self.content.len()
+            + self.original_content.as_ref().map_or(0, String::len)
+            + self.raw_source_link.as_ref().map_or(0, String::len)
+            + (self.referenced_entity_ids.len() + self.supports_relationship_ids.len()) * 40
+            + embedding_bytes(&self.embedding)
+    }
+}
+
+impl ApproxBytes for Relationship {
+    fn approx_bytes(&self) -> usize {
+        ROW_OVERHEAD_BYTES + self.description.len() + embedding_bytes(&self.embedding)
+    }
+}
+
+impl<A: ApproxBytes, B: ApproxBytes> ApproxBytes for (A, B) {
+    fn approx_bytes(&self) -> usize {
+        self.0.approx_bytes() + self.1.approx_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_under_ceiling_is_kept() {
+        let limit = ReadLimit::new(Some(30), 20, 100, "test");
+        assert_eq!(limit.limit, 30);
+        assert_eq!(limit.clamped_from, None);
+        assert_eq!(limit.fetch(), 31);
+
+        let default = ReadLimit::new(None, 20, 100, "test");
+        assert_eq!(default.limit, 20);
+    }
+
+    #[test]
+    fn test_limit_over_ceiling_is_clamped() {
+        let limit = ReadLimit::new(Some(5_000), 20, 500, "test");
+        assert_eq!(limit.limit, 500);
+        assert_eq!(limit.clamped_from, Some(5_000));
+        assert_eq!(limit.fetch(), 501);
+
+        // A zero limit or ceiling still reads a row.
+        assert_eq!(ReadLimit::new(Some(0), 20, 0, "test").limit, 1);
+    }
+
+    #[test]
+    fn test_finish_drops_sentinel_and_flags_truncation() {
+        let limit = ReadLimit::new(Some(3), 20, 100, "test");
+
+        let mut full = vec![1, 2, 3, 4];
+        let size = limit.finish(&mut full, |_| 10);
+        assert_eq!(full, [1, 2, 3]);
+        assert_eq!(
+            size,
+            ResultSize {
+                rows: 3,
+                limit: 3,
+                approx_bytes: 30,
+                truncated: true,
+                clamped_from: None,
+            }
+        );
+
+        // Exactly the limit: the sentinel didn't come back, nothing more matched.
+        let mut exact = vec![1, 2, 3];
+        let size = limit.finish(&mut exact, |_| 10);
+        assert_eq!(exact.len(), 3);
+        assert!(!size.truncated);
+    }
+
+    #[test]
+    fn test_approx_bytes_counts_embeddings() {
+        let mut entity = Entity::new("Meridian Tankers".into(), "organization".into());
+        let bare = entity.approx_bytes();
+        entity.embedding = Some(vec![0.0; 1536]);
+        assert_eq!(entity.approx_bytes(), bare + 15_360);
+    }
+}
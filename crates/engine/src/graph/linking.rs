@@ -0,0 +1,325 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use aho_corasick::{AhoCorasick, MatchKind};
+use neo4rs::query;
+use tokio::sync::RwLock;
+
+use autosint_common::config::EntityLinkingConfig;
+use autosint_common::EntityId;
+
+use super::conversions::{parse_aliases, parse_entity_id};
+use super::{GraphClient, GraphError};
+
+/// Names under which one entity can be recognized in text.
+#[derive(Clone, Debug)]
+pub struct NameEntry {
+    pub entity_id: EntityId,
+    pub canonical_name: String,
+    pub aliases: Vec<String>,
+}
+
+/// An entity recognized in text by exact name or alias.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameMention {
+    pub entity_id: EntityId,
+    /// The name or alias that matched, as stored on the entity.
+    pub matched_name: String,
+}
+
+/// Case-insensitive, whole-word matcher over entity canonical names and aliases.
+///
+/// Names shared by more than one entity are ambiguous and never produce a
+/// mention; neither do names shorter than the configured minimum.
+pub struct EntityNameIndex {
+    automaton: Option<AhoCorasick>,
+    /// Per pattern: the stored name and its entity, or None when ambiguous.
+    targets: Vec<(String, Option<EntityId>)>,
+}
+
+impl EntityNameIndex {
+    pub fn build(entries: &[NameEntry], min_name_length: usize) -> Self {
+        let mut by_pattern: HashMap<String, (String, Option<EntityId>)> = HashMap::new();
+        for entry in entries {
+            let names = std::iter::once(&entry.canonical_name).chain(&entry.aliases);
+            for name in names {
+                let name = name.trim();
+                if name.chars().count() < min_name_length.max(1)
+                    || !name.chars().any(char::is_alphanumeric)
+                {
+                    continue;
+                }
+                by_pattern
+                    .entry(name.to_lowercase())
+                    .and_modify(|(_, id)| {
+                        if *id != Some(entry.entity_id) {
+                            *id = None;
+                        }
+                    })
+                    .or_insert_with(|| (name.to_string(), Some(entry.entity_id)));
+            }
+        }
+
+        let (patterns, targets): (Vec<String>, Vec<_>) = by_pattern.into_iter().unzip();
+        let automaton = if patterns.is_empty() {
+            None
+        } else {
+            // Standard semantics so overlapping candidates are all reported and the
+            // word-boundary filter below can pick among them.
+            AhoCorasick::builder()
+                .match_kind(MatchKind::Standard)
+                .build(&patterns)
+                .ok()
+        };
+
+        Self { automaton, targets }
+    }
+
+    /// Number of distinct names in the index.
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Entities mentioned in `text`, in order of first appearance, one per entity.
+    /// When matches overlap, the longest wins ("Bank of Japan" over "Japan").
+    pub fn find_mentions(&self, text: &str) -> Vec<NameMention> {
+        let Some(automaton) = &self.automaton else {
+            return Vec::new();
+        };
+        let haystack = text.to_lowercase();
+
+        let mut candidates: Vec<(usize, usize, usize)> = automaton
+            .find_overlapping_iter(&haystack)
+            .filter(|m| is_word_boundary(&haystack, m.start(), m.end()))
+            .map(|m| (m.start(), m.end(), m.pattern().as_usize()))
+            .collect();
+        candidates.sort_by_key(|&(start, end, _)| (std::cmp::Reverse(end - start), start));
+
+        let mut taken: Vec<(usize, usize)> = Vec::new();
+        let mut accepted: Vec<(usize, usize)> = Vec::new();
+        for (start, end, pattern) in candidates {
+            if taken.iter().any(|&(s, e)| start < e && s < end) {
+                continue;
+            }
+            taken.push((start, end));
+            accepted.push((start, pattern));
+        }
+        accepted.sort();
+
+        let mut seen = HashSet::new();
+        accepted
+            .into_iter()
+            .filter_map(|(_, pattern)| {
+                let (name, entity_id) = &self.targets[pattern];
+                let entity_id = (*entity_id)?;
+                seen.insert(entity_id).then(|| NameMention {
+                    entity_id,
+                    matched_name: name.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn is_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+/// Links claim content to known entities through a cached [`EntityNameIndex`],
+/// rebuilt from the graph once it is older than the refresh interval.
+pub struct EntityLinker {
+    graph: Arc<GraphClient>,
+    config: EntityLinkingConfig,
+    cached: RwLock<Option<(Instant, Arc<EntityNameIndex>)>>,
+}
+
+impl EntityLinker {
+    pub fn new(graph: Arc<GraphClient>, config: EntityLinkingConfig) -> Self {
+        Self {
+            graph,
+            config,
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Entities mentioned in `content`, excluding `exclude` (the source and any
+    /// explicit references). Failures to build the index are logged and yield
+    /// no links — linking never blocks claim creation.
+    pub async fn link(&self, content: &str, exclude: &[EntityId]) -> Vec<NameMention> {
+        let index = match self.index().await {
+            Ok(index) => index,
+            Err(e) => {
+                tracing::warn!(error = %e, "Entity name index unavailable, skipping auto-linking");
+                return Vec::new();
+            }
+        };
+
+        let mentions: Vec<NameMention> = index
+            .find_mentions(content)
+            .into_iter()
+            .filter(|m| !exclude.contains(&m.entity_id))
+            .collect();
+        metrics::counter!("graph.entity_linking.links").increment(mentions.len() as u64);
+        mentions
+    }
+
+    async fn index(&self) -> Result<Arc<EntityNameIndex>, GraphError> {
+        let max_age = Duration::from_secs(self.config.refresh_interval_seconds);
+        if let Some((built_at, index)) = self.cached.read().await.as_ref() {
+            if built_at.elapsed() < max_age {
+                return Ok(Arc::clone(index));
+            }
+        }
+
+        let mut cached = self.cached.write().await;
+        // Another task may have rebuilt while we waited for the write lock.
+        if let Some((built_at, index)) = cached.as_ref() {
+            if built_at.elapsed() < max_age {
+                return Ok(Arc::clone(index));
+            }
+        }
+
+        let start = Instant::now();
+        let entries = self.graph.entity_name_entries().await?;
+        let index = Arc::new(EntityNameIndex::build(
+            &entries,
+            self.config.min_name_length,
+        ));
+        metrics::histogram!("graph.entity_linking.index_build.latency")
+            .record(start.elapsed().as_secs_f64());
+        tracing::debug!(
+            entities = entries.len(),
+            names = index.len(),
+            "Entity name index rebuilt"
+        );
+
+        *cached = Some((Instant::now(), Arc::clone(&index)));
+        Ok(index)
+    }
+}
+
+impl GraphClient {
+    /// Canonical name and aliases of every entity, for building the name index.
+    pub async fn entity_name_entries(&self) -> Result<Vec<NameEntry>, GraphError> {
+        let mut result = self
+            .graph
+            .execute(query(
+                "MATCH (e:Entity) RETURN e.id AS id, e.canonical_name AS name, e.aliases AS aliases",
+            ))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut entries = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let id: String = row
+                .get("id")
+                .map_err(|e| GraphError::Query(format!("Missing 'id' column: {}", e)))?;
+            let aliases: Option<String> = row.get("aliases").ok();
+            entries.push(NameEntry {
+                entity_id: parse_entity_id(&id)?,
+                canonical_name: row.get("name").unwrap_or_default(),
+                aliases: aliases.as_deref().map(parse_aliases).unwrap_or_default(),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, aliases: &[&str]) -> NameEntry {
+        NameEntry {
+            entity_id: EntityId::new(),
+            canonical_name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_build_skips_short_and_ambiguous_names() {
+        let usa = entry("United States", &["US", "USA"]);
+        let acme_a = entry("Acme Corp", &["Acme"]);
+        let acme_b = entry("Acme Industries", &["Acme"]);
+        let index = EntityNameIndex::build(&[usa.clone(), acme_a.clone(), acme_b], 4);
+
+        // "US" and "USA" are under the minimum; "Acme" is shared by two entities.
+        assert_eq!(index.len(), 4);
+        assert!(index
+            .find_mentions("Talks between the US and USA Today")
+            .is_empty());
+        assert!(index.find_mentions("Acme shipped parts").is_empty());
+
+        let mentions = index.find_mentions("Acme Corp signed with the United States.");
+        assert_eq!(
+            mentions,
+            vec![
+                NameMention {
+                    entity_id: acme_a.entity_id,
+                    matched_name: "Acme Corp".into()
+                },
+                NameMention {
+                    entity_id: usa.entity_id,
+                    matched_name: "United States".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matches_aliases_case_insensitively_on_word_boundaries() {
+        let rosneft = entry("Rosneft Oil Company", &["Rosneft"]);
+        let index = EntityNameIndex::build(std::slice::from_ref(&rosneft), 4);
+
+        let mentions = index.find_mentions("Sanctions hit ROSNEFT's exports; rosneft denied it.");
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].entity_id, rosneft.entity_id);
+        assert_eq!(mentions[0].matched_name, "Rosneft");
+
+        // Substrings of longer words don't count.
+        assert!(index.find_mentions("Rosneftegaz reported").is_empty());
+    }
+
+    #[test]
+    fn test_longest_overlapping_match_wins() {
+        let boj = entry("Bank of Japan", &[]);
+        let japan = entry("Japan", &[]);
+        let index = EntityNameIndex::build(&[boj.clone(), japan.clone()], 4);
+
+        let mentions = index.find_mentions("The Bank of Japan held rates.");
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].entity_id, boj.entity_id);
+
+        let mentions = index.find_mentions("The Bank of Japan said Japan would grow.");
+        let ids: Vec<_> = mentions.iter().map(|m| m.entity_id).collect();
+        assert_eq!(ids, vec![boj.entity_id, japan.entity_id]);
+    }
+
+    #[test]
+    fn test_non_ascii_text() {
+        let munich = entry("München", &["Munich"]);
+        let index = EntityNameIndex::build(std::slice::from_ref(&munich), 4);
+        let mentions = index.find_mentions("Die Konferenz in MÜNCHEN, später Munich.");
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].matched_name, "München");
+    }
+
+    #[test]
+    fn test_empty_index() {
+        let index = EntityNameIndex::build(&[], 4);
+        assert!(index.is_empty());
+        assert!(index.find_mentions("anything at all").is_empty());
+    }
+}
@@ -1,31 +1,101 @@
+mod changes;
 mod claims;
+mod compare;
+pub mod consistency;
 pub(crate) mod conversions;
 pub mod dedup;
+mod digest;
 mod entities;
+mod export;
+pub mod fresh;
+pub mod geo_seed;
+mod jobs;
+mod limits;
+pub mod linking;
+mod purge;
 mod relationships;
 mod search;
+mod snapshot;
+mod stubs;
+pub mod weights;
+mod writes;
 
 // Re-exports for use by other engine modules.
+pub use changes::{ChangeCounts, ChangeScope, ChangedEntity, GraphChanges};
+pub use claims::ClaimMerge;
+pub use compare::{match_band, score_pair, MatchBand, PairScores, SharedNeighbors};
 #[allow(unused_imports)]
 pub use dedup::{DedupResult, DedupStage};
 #[allow(unused_imports)]
+pub use digest::{ClaimDigest, ClaimDigestEntry, CLAIM_DIGEST_LEN, DIGEST_SNIPPET_CHARS};
+#[allow(unused_imports)]
 pub use entities::EntityUpdate;
 #[allow(unused_imports)]
-pub use relationships::{RelationshipUpdate, TraversalDirection, TraversalParams};
+pub use export::{Subgraph, MAX_EXPORT_DEPTH};
+pub use limits::ResultSize;
+#[allow(unused_imports)]
+pub use purge::GraphPurgeReport;
+#[allow(unused_imports)]
+pub use relationships::{RelationshipUpdate, TraversalDirection, TraversalPage, TraversalParams};
 #[allow(unused_imports)]
 pub use search::{
-    ClaimSearchParams, EntitySearchParams, RelationshipSearchParams, SearchMode, SearchResult,
+    ClaimSearchParams, EntitySearchParams, RelationshipSearchParams, SearchCursor, SearchMode,
+    SearchPage, SearchResult,
 };
+#[allow(unused_imports)]
+pub use snapshot::SnapshotStats;
+pub use stubs::{StubCandidate, StubMatch};
+pub use writes::WriteStats;
+
+use std::sync::Arc;
 
 use neo4rs::{query, Graph};
 
-/// Neo4j client wrapping a connection pool.
+use autosint_common::config::{GraphReadConfig, GraphWriteConfig, RetryConfig};
+use autosint_common::retry::{retry_with_backoff, RetryAttempt, RetryClass, RetryPolicy};
+use autosint_common::types::FeatureFlag;
+
+use crate::flags::FlagSet;
+
+use writes::{CreateCoalescers, WriteGovernor};
+
+/// Embedding vector indexes: name, indexed pattern, and its variable.
+const VECTOR_INDEXES: [(&str, &str, &str); 4] = [
+    ("entity_embedding", "(e:Entity)", "e"),
+    ("claim_embedding", "(c:Claim)", "c"),
+    ("claim_chunk_embedding", "(k:ClaimChunk)", "k"),
+    ("relates_to_embedding", "()-[r:RELATES_TO]-()", "r"),
+];
+
+/// Fulltext index over claim wording, queried by keyword claim search.
+const CLAIM_FULLTEXT_INDEX: &str = "claim_content_fulltext";
+
+/// Claim properties the claim fulltext index covers.
+fn claim_fulltext_properties(index_original_content: bool) -> &'static [&'static str] {
+    if index_original_content {
+        &["content", "original_content"]
+    } else {
+        &["content"]
+    }
+}
+
+/// Neo4j client wrapping a connection pool. Clones share the pool and the
+/// write governor.
+#[derive(Clone)]
 pub struct GraphClient {
     graph: Graph,
+    writes: Arc<WriteGovernor>,
+    max_claim_references: usize,
+    /// Set when create coalescing is enabled.
+    coalesce: Option<Arc<CreateCoalescers>>,
+    flags: FlagSet,
+    /// Ceilings on read limits.
+    reads: GraphReadConfig,
 }
 
 impl GraphClient {
-    /// Connect to Neo4j and return a client with a connection pool.
+    /// Connect to Neo4j and return a client with a connection pool, using the
+    /// default write limits.
     pub async fn connect(uri: &str, user: &str, password: &str) -> Result<Self, GraphError> {
         tracing::info!(uri = uri, "Connecting to Neo4j");
 
@@ -33,13 +103,105 @@ impl GraphClient {
             .await
             .map_err(|e| GraphError::Connection(e.to_string()))?;
 
-        let client = Self { graph };
+        let client = Self::new(graph, &GraphWriteConfig::default(), FlagSet::default());
         client.health_check().await?;
         tracing::info!("Neo4j connection established");
 
         Ok(client)
     }
 
+    /// [`connect`](Self::connect), retrying while Neo4j is unreachable or
+    /// not yet answering queries, e.g. when it starts alongside the engine.
+    pub async fn connect_with_retry(
+        uri: &str,
+        user: &str,
+        password: &str,
+        retry: &RetryConfig,
+    ) -> Result<Self, GraphError> {
+        let on_retry = |retry: &RetryAttempt<'_, GraphError>| {
+            tracing::warn!(
+                attempt = retry.attempt,
+                wait_ms = retry.wait.as_millis() as u64,
+                error = %retry.error,
+                "Neo4j not reachable, retrying"
+            );
+            metrics::counter!("graph.connect.retries").increment(1);
+        };
+        retry_with_backoff(
+            &RetryPolicy::from(retry),
+            |e: &GraphError| match e {
+                GraphError::Connection(_) | GraphError::Query(_) => RetryClass::Retryable,
+                _ => RetryClass::Fatal,
+            },
+            on_retry,
+            || Self::connect(uri, user, password),
+        )
+        .await
+        .map_err(|failure| failure.error)
+    }
+
+    /// A client for unit tests of code that holds one but must not touch
+    /// Neo4j. Its pool connects on first use, to a port nothing listens on.
+    #[cfg(test)]
+    pub(crate) async fn unconnected() -> Self {
+        let graph = Graph::new("127.0.0.1:1", "neo4j", "unused")
+            .await
+            .expect("Building an unconnected Neo4j pool");
+        Self::new(graph, &GraphWriteConfig::default(), FlagSet::default())
+    }
+
+    /// Replace the write limits: the concurrent write bound, the per-claim
+    /// reference cap and, if enabled, create coalescing.
+    pub fn with_write_config(self, config: &GraphWriteConfig) -> Self {
+        Self {
+            reads: self.reads,
+            ..Self::new(self.graph, config, self.flags)
+        }
+    }
+
+    /// Replace the read ceilings that clamp search and traversal limits.
+    pub fn with_read_config(mut self, config: &GraphReadConfig) -> Self {
+        self.reads = config.clone();
+        self
+    }
+
+    /// Consult `flags` for write coalescing and chunk search.
+    pub fn with_flags(mut self, flags: FlagSet) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    fn new(graph: Graph, config: &GraphWriteConfig, flags: FlagSet) -> Self {
+        Self {
+            graph,
+            writes: Arc::new(WriteGovernor::new(config.max_concurrent_writes)),
+            max_claim_references: config.max_claim_references,
+            coalesce: config
+                .coalesce
+                .enabled
+                .then(|| Arc::new(CreateCoalescers::new(&config.coalesce))),
+            flags,
+            reads: GraphReadConfig::default(),
+        }
+    }
+
+    /// The create coalescers, when coalescing is enabled and its flag is on.
+    fn coalescers(&self) -> Option<&Arc<CreateCoalescers>> {
+        self.coalesce
+            .as_ref()
+            .filter(|_| self.flags.is_enabled(FeatureFlag::WriteCoalescing))
+    }
+
+    /// Max entities one claim may reference (`graph_writes.max_claim_references`).
+    pub fn max_claim_references(&self) -> usize {
+        self.max_claim_references
+    }
+
+    /// Write transaction counters since the write config was last set.
+    pub fn write_stats(&self) -> WriteStats {
+        self.writes.stats()
+    }
+
     /// Verify the connection is alive.
     pub async fn health_check(&self) -> Result<(), GraphError> {
         self.graph
@@ -49,9 +211,10 @@ impl GraphClient {
         Ok(())
     }
 
-    /// Initialize schema: create indexes and constraints.
-    /// Safe to run on every startup (CREATE ... IF NOT EXISTS).
-    pub async fn initialize_schema(&self) -> Result<(), GraphError> {
+    /// Initialize schema: create indexes and constraints, with vector indexes of
+    /// `vector_dimensions`. Safe to run on every startup (CREATE ... IF NOT EXISTS).
+    /// Fails if existing vector indexes have different dimensions.
+    pub async fn initialize_schema(&self, vector_dimensions: u32) -> Result<(), GraphError> {
         tracing::info!("Initializing Neo4j schema");
 
         let schema_statements = [
@@ -62,10 +225,27 @@ impl GraphClient {
             // Entity indexes
             "CREATE INDEX entity_kind_idx IF NOT EXISTS FOR (e:Entity) ON (e.kind)",
             "CREATE INDEX entity_last_updated_idx IF NOT EXISTS FOR (e:Entity) ON (e.last_updated)",
+            "CREATE INDEX entity_created_by_investigation_idx IF NOT EXISTS FOR (e:Entity) ON (e.created_by_investigation)",
+            // Entity identifiers, one per scheme (see conversions::identifier_node_key)
+            "CREATE INDEX entity_ident_lei_idx IF NOT EXISTS FOR (e:Entity) ON (e.ident_lei)",
+            "CREATE INDEX entity_ident_cik_idx IF NOT EXISTS FOR (e:Entity) ON (e.ident_cik)",
+            "CREATE INDEX entity_ident_mmsi_idx IF NOT EXISTS FOR (e:Entity) ON (e.ident_mmsi)",
+            "CREATE INDEX entity_ident_imo_idx IF NOT EXISTS FOR (e:Entity) ON (e.ident_imo)",
+            "CREATE INDEX entity_ident_ticker_idx IF NOT EXISTS FOR (e:Entity) ON (e.ident_ticker)",
+            "CREATE INDEX entity_ident_company_registration_idx IF NOT EXISTS FOR (e:Entity) ON (e.ident_company_registration)",
             // Claim indexes
             "CREATE INDEX claim_published_idx IF NOT EXISTS FOR (c:Claim) ON (c.published_timestamp)",
             "CREATE INDEX claim_ingested_idx IF NOT EXISTS FOR (c:Claim) ON (c.ingested_timestamp)",
             "CREATE INDEX claim_information_type_idx IF NOT EXISTS FOR (c:Claim) ON (c.information_type)",
+            "CREATE INDEX claim_investigation_idx IF NOT EXISTS FOR (c:Claim) ON (c.investigation_id)",
+            "CREATE INDEX claim_language_idx IF NOT EXISTS FOR (c:Claim) ON (c.language)",
+            "CREATE INDEX claim_region_idx IF NOT EXISTS FOR (c:Claim) ON (c.region)",
+            "CREATE INDEX claim_source_trust_idx IF NOT EXISTS FOR (c:Claim) ON (c.source_trust_level)",
+            "CREATE INDEX claim_chunks_pending_idx IF NOT EXISTS FOR (c:Claim) ON (c.chunks_pending)",
+            // Exact-content lookups for merging duplicate claims
+            "CREATE TEXT INDEX claim_content_text_idx IF NOT EXISTS FOR (c:Claim) ON (c.content)",
+            // Relationship indexes
+            "CREATE INDEX relationship_created_idx IF NOT EXISTS FOR ()-[r:RELATES_TO]-() ON (r.created_at)",
             // Full-text indexes (composite syntax)
             "CREATE FULLTEXT INDEX entity_name_fulltext IF NOT EXISTS FOR (e:Entity) ON EACH [e.canonical_name, e.aliases_text]",
             "CREATE FULLTEXT INDEX claim_content_fulltext IF NOT EXISTS FOR (c:Claim) ON EACH [c.content]",
@@ -82,32 +262,263 @@ impl GraphClient {
             }
         }
 
-        // Vector indexes — Neo4j 5.x uses CREATE VECTOR INDEX syntax.
-        let vector_indexes = [
-            "CREATE VECTOR INDEX entity_embedding IF NOT EXISTS FOR (e:Entity) ON (e.embedding) OPTIONS {indexConfig: {`vector.dimensions`: 1536, `vector.similarity_function`: 'cosine'}}",
-            "CREATE VECTOR INDEX claim_embedding IF NOT EXISTS FOR (c:Claim) ON (c.embedding) OPTIONS {indexConfig: {`vector.dimensions`: 1536, `vector.similarity_function`: 'cosine'}}",
-            "CREATE VECTOR INDEX relates_to_embedding IF NOT EXISTS FOR ()-[r:RELATES_TO]-() ON (r.embedding) OPTIONS {indexConfig: {`vector.dimensions`: 1536, `vector.similarity_function`: 'cosine'}}",
-        ];
+        self.create_vector_indexes(vector_dimensions).await;
+        self.check_vector_dimensions(vector_dimensions).await?;
 
-        for stmt in &vector_indexes {
-            if let Err(e) = self.graph.run(query(stmt)).await {
+        tracing::info!("Neo4j schema initialization complete");
+        Ok(())
+    }
+
+    /// Index claims' `original_content` in the claim fulltext index alongside
+    /// `content`, or stop indexing it. Rebuilds the index only when its
+    /// properties change.
+    pub async fn configure_claim_fulltext(
+        &self,
+        index_original_content: bool,
+    ) -> Result<(), GraphError> {
+        let wanted = claim_fulltext_properties(index_original_content);
+
+        let mut result = self
+            .graph
+            .execute(
+                query(
+                    "SHOW FULLTEXT INDEXES YIELD name, properties \
+                     WHERE name = $name RETURN properties",
+                )
+                .param("name", CLAIM_FULLTEXT_INDEX),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let current: Option<Vec<String>> = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .and_then(|row| row.get("properties").ok());
+        if current.as_deref().is_some_and(|props| props == wanted) {
+            return Ok(());
+        }
+        self.rebuild_claim_fulltext(index_original_content).await
+    }
+
+    /// Drop and re-create the claim fulltext index, over `original_content`
+    /// too when `index_original_content`, and wait for it to populate.
+    pub async fn rebuild_claim_fulltext(
+        &self,
+        index_original_content: bool,
+    ) -> Result<(), GraphError> {
+        let wanted = claim_fulltext_properties(index_original_content);
+        tracing::info!(
+            properties = ?wanted,
+            "Rebuilding claim fulltext index"
+        );
+        self.graph
+            .run(query(&format!(
+                "DROP INDEX {CLAIM_FULLTEXT_INDEX} IF EXISTS"
+            )))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let properties: Vec<String> = wanted.iter().map(|p| format!("c.{p}")).collect();
+        self.graph
+            .run(query(&format!(
+                "CREATE FULLTEXT INDEX {CLAIM_FULLTEXT_INDEX} IF NOT EXISTS \
+                 FOR (c:Claim) ON EACH [{}]",
+                properties.join(", ")
+            )))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        // Existing claims are indexed in the background; wait so searches
+        // right after startup see them.
+        self.graph
+            .run(query("CALL db.awaitIndex($name, 300)").param("name", CLAIM_FULLTEXT_INDEX))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Create the embedding vector indexes if they don't exist.
+    pub async fn create_vector_indexes(&self, dimensions: u32) {
+        // Neo4j 5.x uses CREATE VECTOR INDEX syntax.
+        for (name, pattern, variable) in VECTOR_INDEXES {
+            let stmt = format!(
+                "CREATE VECTOR INDEX {} IF NOT EXISTS FOR {} ON ({}.embedding) \
+                 OPTIONS {{indexConfig: {{`vector.dimensions`: {}, `vector.similarity_function`: 'cosine'}}}}",
+                name, pattern, variable, dimensions
+            );
+            if let Err(e) = self.graph.run(query(&stmt)).await {
                 let err_str = e.to_string();
                 if err_str.contains("already exists") || err_str.contains("EquivalentSchema") {
-                    tracing::debug!(statement = *stmt, "Vector index already exists, skipping");
+                    tracing::debug!(index = name, "Vector index already exists, skipping");
                 } else {
-                    tracing::warn!(
-                        statement = *stmt,
-                        error = %e,
-                        "Failed to create vector index"
-                    );
+                    tracing::warn!(index = name, error = %e, "Failed to create vector index");
                 }
             }
         }
+    }
 
-        tracing::info!("Neo4j schema initialization complete");
+    /// Drop the embedding vector indexes, e.g. before re-creating them at new
+    /// dimensions.
+    pub async fn drop_vector_indexes(&self) -> Result<(), GraphError> {
+        for (name, _, _) in VECTOR_INDEXES {
+            self.graph
+                .run(query(&format!("DROP INDEX {} IF EXISTS", name)))
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+        }
         Ok(())
     }
 
+    /// Dimensions of each existing embedding vector index, by index name.
+    pub async fn vector_index_dimensions(&self) -> Result<Vec<(String, u32)>, GraphError> {
+        let mut result = self
+            .graph
+            .execute(query(
+                "SHOW VECTOR INDEXES YIELD name, options \
+                 RETURN name, options.indexConfig['vector.dimensions'] AS dimensions",
+            ))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut dimensions = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let name: String = row.get("name").unwrap_or_default();
+            if let Ok(dims) = row.get::<i64>("dimensions") {
+                if VECTOR_INDEXES.iter().any(|(index, _, _)| *index == name) {
+                    dimensions.push((name, dims as u32));
+                }
+            }
+        }
+        dimensions.sort();
+        Ok(dimensions)
+    }
+
+    /// Fail unless every embedding vector index has `expected` dimensions, so a
+    /// changed embedding model is caught at startup rather than at first query.
+    pub async fn check_vector_dimensions(&self, expected: u32) -> Result<(), GraphError> {
+        let mismatched: Vec<String> = self
+            .vector_index_dimensions()
+            .await?
+            .into_iter()
+            .filter(|(_, dims)| *dims != expected)
+            .map(|(name, dims)| format!("{} has {}", name, dims))
+            .collect();
+
+        if mismatched.is_empty() {
+            return Ok(());
+        }
+        Err(GraphError::Query(format!(
+            "Vector index dimension mismatch: embeddings are {}-dimensional but {}. \
+             Set embeddings.target_dimensions to match, or re-embed the graph \
+             (POST /admin/reembed) with the previous settings in place.",
+            expected,
+            mismatched.join(", ")
+        )))
+    }
+
+    /// Wait until every index is ONLINE and fully populated, polling SHOW INDEXES.
+    /// Freshly created indexes (e.g. right after `initialize_schema` on an empty
+    /// database) are not queryable until population finishes.
+    pub async fn await_indexes(&self, timeout: std::time::Duration) -> Result<(), GraphError> {
+        let deadline = std::time::Instant::now() + timeout;
+
+        loop {
+            let mut pending = Vec::new();
+            let mut result = self
+                .graph
+                .execute(query(
+                    "SHOW INDEXES YIELD name, state, populationPercent \
+                     RETURN name, state, populationPercent",
+                ))
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+            while let Some(row) = result
+                .next()
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?
+            {
+                let name: String = row.get("name").unwrap_or_default();
+                let state: String = row.get("state").unwrap_or_default();
+                let percent: f64 = row.get("populationPercent").unwrap_or(0.0);
+                if state == "FAILED" {
+                    return Err(GraphError::Query(format!(
+                        "Index {} failed to populate",
+                        name
+                    )));
+                }
+                if state != "ONLINE" || percent < 100.0 {
+                    pending.push(format!("{} ({}, {:.0}%)", name, state, percent));
+                }
+            }
+
+            if pending.is_empty() {
+                return Ok(());
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(GraphError::Query(format!(
+                    "Indexes not online after {:?}: {}",
+                    timeout,
+                    pending.join(", ")
+                )));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Entity, relationship and claim counts, for GET /stats/graph.
+    pub async fn stats(&self) -> Result<autosint_common::api::engine::GraphStats, GraphError> {
+        let mut result = self
+            .graph
+            .execute(query(
+                "CALL { MATCH (e:Entity) RETURN count(e) AS entities } \
+                 CALL { MATCH (:Entity)-[r:RELATES_TO]->(:Entity) RETURN count(r) AS relationships } \
+                 CALL { MATCH (c:Claim) RETURN count(c) AS claims } \
+                 RETURN entities, relationships, claims",
+            ))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let row = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .ok_or_else(|| GraphError::Query("Graph stats query returned no rows".into()))?;
+        let count = |column: &str| -> u64 { row.get::<i64>(column).unwrap_or(0).max(0) as u64 };
+        let (entities, relationships, claims) =
+            (count("entities"), count("relationships"), count("claims"));
+
+        // Claims written before languages were recorded count as unknown.
+        let mut result = self
+            .graph
+            .execute(query(
+                "MATCH (c:Claim) \
+                 RETURN coalesce(c.language, 'unknown') AS language, count(c) AS claims",
+            ))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let mut claims_by_language = std::collections::BTreeMap::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let language: String = row
+                .get("language")
+                .map_err(|e| GraphError::Query(format!("Missing 'language': {}", e)))?;
+            let claims = row.get::<i64>("claims").unwrap_or(0).max(0) as u64;
+            claims_by_language.insert(language, claims);
+        }
+
+        Ok(autosint_common::api::engine::GraphStats {
+            entities,
+            relationships,
+            claims,
+            claims_by_language,
+        })
+    }
+
     /// Get a reference to the underlying neo4rs Graph for direct queries.
     #[allow(dead_code)]
     pub fn inner(&self) -> &Graph {
@@ -125,35 +536,56 @@ pub enum GraphError {
 
     #[error("Not found: {0}")]
     NotFound(String),
+
+    #[error("Snapshot error: {0}")]
+    Snapshot(String),
+
+    #[error("Invalid search cursor: {0}")]
+    InvalidCursor(String),
 }
 
-/// Escape Lucene special characters in a fulltext query string.
-/// Characters: + - && || ! ( ) { } [ ] ^ " ~ * ? : \ /
+/// Escape a fulltext query string so Lucene reads it as plain terms:
+/// backslash-escape the special characters + - & | ! ( ) { } [ ] ^ " ~ * ? : \ /
+/// and lowercase the bare operator words AND, OR and NOT (the analyzer
+/// lowercases terms anyway). A query with nothing left to search for, e.g. only
+/// whitespace, is empty; callers skip the index for it, which rejects it.
 pub fn escape_lucene_query(input: &str) -> String {
     let mut escaped = String::with_capacity(input.len() + 8);
-    for c in input.chars() {
-        if matches!(
-            c,
-            '+' | '-'
-                | '!'
-                | '('
-                | ')'
-                | '{'
-                | '}'
-                | '['
-                | ']'
-                | '^'
-                | '"'
-                | '~'
-                | '*'
-                | '?'
-                | ':'
-                | '\\'
-                | '/'
-        ) {
-            escaped.push('\\');
+    for piece in input.split_inclusive(char::is_whitespace) {
+        let word = piece.trim_end();
+        if matches!(word, "AND" | "OR" | "NOT") {
+            escaped.push_str(&piece.to_lowercase());
+            continue;
+        }
+        for c in piece.chars() {
+            if matches!(
+                c,
+                '+' | '-'
+                    | '&'
+                    | '|'
+                    | '!'
+                    | '('
+                    | ')'
+                    | '{'
+                    | '}'
+                    | '['
+                    | ']'
+                    | '^'
+                    | '"'
+                    | '~'
+                    | '*'
+                    | '?'
+                    | ':'
+                    | '\\'
+                    | '/'
+            ) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
         }
-        escaped.push(c);
+    }
+    if escaped.trim().is_empty() {
+        escaped.clear();
     }
     escaped
 }
@@ -166,3 +598,156 @@ impl From<GraphError> for autosint_common::AutOsintError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Text of the string literals in Rust `source`, skipping comments and
+    /// char literals. Enough for this module's sources, not a Rust lexer.
+    fn string_literals(source: &str) -> Vec<String> {
+        let chars: Vec<char> = source.chars().collect();
+        let mut literals = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '/' if chars.get(i + 1) == Some(&'/') => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+                '\'' if chars.get(i + 1) == Some(&'\\') => {
+                    i += 2;
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                '\'' if chars.get(i + 2) == Some(&'\'') => i += 3,
+                'r' if chars.get(i + 1) == Some(&'#') && chars.get(i + 2) == Some(&'"') => {
+                    let start = i + 3;
+                    i = start;
+                    while i + 1 < chars.len() && !(chars[i] == '"' && chars[i + 1] == '#') {
+                        i += 1;
+                    }
+                    literals.push(chars[start..i].iter().collect());
+                    i += 2;
+                }
+                '"' => {
+                    let mut literal = String::new();
+                    i += 1;
+                    while i < chars.len() && chars[i] != '"' {
+                        if chars[i] == '\\' {
+                            literal.push(chars[i]);
+                            i += 1;
+                        }
+                        if let Some(&c) = chars.get(i) {
+                            literal.push(c);
+                        }
+                        i += 1;
+                    }
+                    literals.push(literal);
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+        literals
+    }
+
+    /// Format placeholders where a query value belongs. Values go in bound
+    /// parameters (`$name`); only identifiers (`quote_key`), fixed fragments
+    /// and bounds Cypher can't parameterize (variable-length hops) are
+    /// formatted in.
+    const VALUE_PLACEHOLDERS: &[&str] = &[
+        "= '{}'",
+        "= \"{}\"",
+        "= {}",
+        "> {}",
+        "< {}",
+        " IN {}",
+        "CONTAINS {}",
+        "STARTS WITH {}",
+        "LIMIT {}",
+        "SKIP {}",
+        ": {}}}",
+    ];
+
+    #[test]
+    fn test_no_values_formatted_into_queries() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/graph");
+        let mut offenders = Vec::new();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            // Test modules hold sample queries, not real ones.
+            let source = source.split("#[cfg(test)]\nmod tests").next().unwrap();
+            for literal in string_literals(source) {
+                if VALUE_PLACEHOLDERS.iter().any(|p| literal.contains(p)) {
+                    offenders.push(format!("{}: {:?}", path.display(), literal));
+                }
+            }
+        }
+        assert!(offenders.is_empty(), "{:#?}", offenders);
+    }
+
+    #[test]
+    fn test_lint_catches_formatted_values() {
+        let source = r##"
+            // A comment with "r.weight >= {}" is ignored.
+            let quote = '"';
+            where_parts.push(format!("c.attribution_depth = '{}'", depth));
+            let cypher = format!(
+                "MATCH (e:Entity {{id: {}}}) \
+                 WHERE r.weight >= {} RETURN e",
+                id, min_weight
+            );
+            let ok = format!("e.{} = $prop_{}", quote_key(key), i);
+        "##;
+        let flagged: Vec<String> = string_literals(source)
+            .into_iter()
+            .filter(|l| VALUE_PLACEHOLDERS.iter().any(|p| l.contains(p)))
+            .collect();
+        assert_eq!(flagged.len(), 2, "{:#?}", flagged);
+        assert!(flagged[0].contains("attribution_depth"));
+        assert!(flagged[1].contains("r.weight >= {}"));
+    }
+
+    #[test]
+    fn test_escape_lucene_query() {
+        assert_eq!(escape_lucene_query("Crius"), "Crius");
+        assert_eq!(
+            escape_lucene_query("name:* OR (a && b) || !c"),
+            "name\\:\\* or \\(a \\&\\& b\\) \\|\\| \\!c"
+        );
+        assert_eq!(escape_lucene_query("AND"), "and");
+        assert_eq!(escape_lucene_query("NOT  the ANDES"), "not  the ANDES");
+        assert_eq!(escape_lucene_query("\"x\" ~2 ^3"), "\\\"x\\\" \\~2 \\^3");
+        assert_eq!(escape_lucene_query("a\\b/c"), "a\\\\b\\/c");
+        assert_eq!(escape_lucene_query(""), "");
+        assert_eq!(escape_lucene_query(" \t\n"), "");
+    }
+
+    #[tokio::test]
+    async fn test_write_coalescing_follows_flag() {
+        let mut config = GraphWriteConfig::default();
+        config.coalesce.enabled = true;
+        let flags = FlagSet::default();
+        let client = GraphClient::unconnected()
+            .await
+            .with_write_config(&config)
+            .with_flags(flags.clone());
+        assert!(client.coalescers().is_some());
+
+        flags.set_override(FeatureFlag::WriteCoalescing, Some(false));
+        assert!(client.coalescers().is_none());
+
+        // The flag can't turn on coalescing the config leaves off.
+        flags.set_override(FeatureFlag::WriteCoalescing, Some(true));
+        let client = client.with_write_config(&GraphWriteConfig::default());
+        assert!(client.coalescers().is_none());
+    }
+}
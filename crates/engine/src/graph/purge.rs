@@ -0,0 +1,220 @@
+use neo4rs::query;
+
+use autosint_common::{EntityId, InvestigationId};
+
+use super::conversions::{parse_claim_id, parse_entity_id, parse_relationship_id};
+use super::GraphError;
+
+pub use autosint_common::api::engine::GraphPurgeReport;
+
+impl super::GraphClient {
+    /// Remove everything an investigation contributed to the graph.
+    ///
+    /// - Claims stamped with `investigation_id` are always removed.
+    /// - Relationships stamped with `created_by_investigation` are removed unless
+    ///   an endpoint is in `protected_entities` (referenced by another investigation).
+    /// - Entities stamped with `created_by_investigation` are removed only when they
+    ///   are not protected or seeded, no surviving claim publishes or references them,
+    ///   and every RELATES_TO edge they carry is itself being removed.
+    ///
+    /// The plan is computed up front and the delete works from that ID list, so a
+    /// dry run reports exactly what a real run would delete.
+    pub async fn purge_by_investigation(
+        &self,
+        investigation_id: InvestigationId,
+        protected_entities: &[EntityId],
+        dry_run: bool,
+    ) -> Result<GraphPurgeReport, GraphError> {
+        let start = std::time::Instant::now();
+
+        let inv_id = investigation_id.to_string();
+        let protected: Vec<String> = protected_entities.iter().map(|id| id.to_string()).collect();
+
+        // 1. Claims ingested by this investigation.
+        let claim_ids = self
+            .collect_ids(
+                query("MATCH (c:Claim {investigation_id: $investigation_id}) RETURN c.id AS id")
+                    .param("investigation_id", inv_id.as_str()),
+            )
+            .await?;
+
+        // 2. Relationships created by this investigation whose endpoints no other
+        //    investigation relies on.
+        let relationship_ids = self
+            .collect_ids(
+                query(
+                    "MATCH (s:Entity)-[r:RELATES_TO {created_by_investigation: $investigation_id}]->(t:Entity) \
+                     WHERE NOT s.id IN $protected AND NOT t.id IN $protected \
+                     RETURN r.id AS id",
+                )
+                .param("investigation_id", inv_id.as_str())
+                .param("protected", protected.clone()),
+            )
+            .await?;
+
+        // 3. Entities created by this investigation that nothing else holds onto.
+        let entity_ids = self
+            .collect_ids(
+                query(
+                    "MATCH (e:Entity {created_by_investigation: $investigation_id}) \
+                     WHERE NOT e.id IN $protected \
+                       AND NOT coalesce(e.is_seeded, false) \
+                       AND NOT EXISTS { \
+                         MATCH (e)-[:PUBLISHED]->(c:Claim) \
+                         WHERE c.investigation_id IS NULL OR c.investigation_id <> $investigation_id \
+                       } \
+                       AND NOT EXISTS { \
+                         MATCH (c:Claim)-[:REFERENCES]->(e) \
+                         WHERE c.investigation_id IS NULL OR c.investigation_id <> $investigation_id \
+                       } \
+                       AND NOT EXISTS { \
+                         MATCH (e)-[r:RELATES_TO]-() \
+                         WHERE NOT r.id IN $relationship_ids \
+                       } \
+                     RETURN e.id AS id",
+                )
+                .param("investigation_id", inv_id.as_str())
+                .param("protected", protected)
+                .param("relationship_ids", relationship_ids.clone()),
+            )
+            .await?;
+
+        if !dry_run {
+            // Surviving entities the purged claims referenced lose them from
+            // their digests.
+            let digest_ids = self
+                .collect_ids(
+                    query(
+                        "MATCH (c:Claim)-[:REFERENCES]->(e:Entity) \
+                         WHERE c.id IN $claim_ids AND NOT e.id IN $entity_ids \
+                         RETURN DISTINCT e.id AS id",
+                    )
+                    .param("claim_ids", claim_ids.clone())
+                    .param("entity_ids", entity_ids.clone()),
+                )
+                .await?
+                .iter()
+                .map(|s| parse_entity_id(s))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let permit = self.writes.acquire("purge").await;
+            let mut txn = self
+                .graph
+                .start_txn()
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+
+            let q1 = query(
+                "MATCH (c:Claim) WHERE c.id IN $ids \
+                 OPTIONAL MATCH (k:ClaimChunk)-[:PART_OF]->(c) \
+                 DETACH DELETE k, c",
+            )
+            .param("ids", claim_ids.clone());
+            txn.run(q1)
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+
+            let q2 = query("MATCH ()-[r:RELATES_TO]->() WHERE r.id IN $ids DELETE r")
+                .param("ids", relationship_ids.clone());
+            txn.run(q2)
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+
+            let q3 = query("MATCH (e:Entity) WHERE e.id IN $ids DETACH DELETE e")
+                .param("ids", entity_ids.clone());
+            txn.run(q3)
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+
+            txn.commit()
+                .await
+                .map_err(|e| GraphError::Query(e.to_string()))?;
+            drop(permit);
+
+            if let Err(e) = self.rebuild_claim_digests(&digest_ids).await {
+                tracing::warn!(investigation_id = %investigation_id, error = %e, "Failed to rebuild claim digests after purge");
+            }
+
+            metrics::histogram!("graph.purge.latency").record(start.elapsed().as_secs_f64());
+        }
+
+        Ok(GraphPurgeReport {
+            claim_ids: claim_ids
+                .iter()
+                .map(|s| parse_claim_id(s))
+                .collect::<Result<_, _>>()?,
+            relationship_ids: relationship_ids
+                .iter()
+                .map(|s| parse_relationship_id(s))
+                .collect::<Result<_, _>>()?,
+            entity_ids: entity_ids
+                .iter()
+                .map(|s| parse_entity_id(s))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Set `canary = true` on every claim, relationship and entity an
+    /// investigation wrote, so self-test data stays identifiable if its purge
+    /// fails. Returns the number of claims marked.
+    pub async fn mark_investigation_canary(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<u64, GraphError> {
+        let inv_id = investigation_id.to_string();
+
+        let claim_ids = self
+            .collect_ids(
+                query(
+                    "MATCH (c:Claim {investigation_id: $investigation_id}) \
+                     SET c.canary = true RETURN c.id AS id",
+                )
+                .param("investigation_id", inv_id.as_str()),
+            )
+            .await?;
+        self.graph
+            .run(
+                query(
+                    "MATCH ()-[r:RELATES_TO {created_by_investigation: $investigation_id}]->() \
+                     SET r.canary = true",
+                )
+                .param("investigation_id", inv_id.as_str()),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        self.graph
+            .run(
+                query(
+                    "MATCH (e:Entity {created_by_investigation: $investigation_id}) \
+                     SET e.canary = true",
+                )
+                .param("investigation_id", inv_id.as_str()),
+            )
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        Ok(claim_ids.len() as u64)
+    }
+
+    /// Run a query returning a single `id` column and collect the values.
+    pub(super) async fn collect_ids(&self, q: neo4rs::Query) -> Result<Vec<String>, GraphError> {
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut ids = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let id: String = row
+                .get("id")
+                .map_err(|e| GraphError::Query(format!("Missing 'id': {}", e)))?;
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+}
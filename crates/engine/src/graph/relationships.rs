@@ -1,13 +1,21 @@
-use neo4rs::query;
+use std::collections::HashMap;
+
+use chrono::Utc;
+use neo4rs::{query, BoltType};
 
 use autosint_common::types::{Entity, Relationship};
 use autosint_common::{EntityId, RelationshipId};
 
 use super::conversions::{
-    format_datetime, node_to_entity, parse_entity_id, relation_to_relationship,
+    format_datetime, node_to_entity, parse_entity_id, relation_to_relationship, row_to_relationship,
 };
+use super::limits::{ApproxBytes, ReadLimit, ResultSize};
+use super::writes::record_read_latency;
 use super::GraphError;
 
+/// Relationships a traversal returns when it sets no limit.
+const DEFAULT_TRAVERSAL_LIMIT: u32 = 100;
+
 /// Relationship update with optional fields for partial updates.
 #[allow(dead_code)]
 pub struct RelationshipUpdate {
@@ -16,6 +24,9 @@ pub struct RelationshipUpdate {
     pub confidence: Option<f64>,
     pub bidirectional: Option<bool>,
     pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    /// Marks a weight change as a recalibration: the old weight is kept in
+    /// `previous_weight` and this time in `weight_recalibrated_at`.
+    pub weight_recalibrated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Direction filter for relationship traversal.
@@ -31,19 +42,63 @@ pub enum TraversalDirection {
 pub struct TraversalParams {
     pub direction: Option<TraversalDirection>,
     pub min_weight: Option<f64>,
+    /// Only relationships, and connected entities, that existed at this time.
+    pub as_of: Option<chrono::DateTime<chrono::Utc>>,
     pub limit: Option<u32>,
 }
 
+/// Relationships found by a traversal, each with the entity at its other end.
+pub struct TraversalPage {
+    pub results: Vec<(Relationship, Entity)>,
+    pub size: ResultSize,
+}
+
 #[allow(dead_code)]
 impl super::GraphClient {
     /// Create a new relationship (RELATES_TO edge) between two entities.
+    /// Self-referential edges are rejected unless `allow_self` is set.
+    /// With create coalescing enabled, concurrent creates are written in one batch.
     pub async fn create_relationship(
         &self,
         relationship: &Relationship,
         embedding: Option<Vec<f32>>,
+        allow_self: bool,
     ) -> Result<Relationship, GraphError> {
         let start = std::time::Instant::now();
 
+        if relationship.source_entity_id == relationship.target_entity_id && !allow_self {
+            return Err(GraphError::Query(format!(
+                "Relationship source and target are the same entity ({}); self-referential \
+                 relationships must be explicitly allowed",
+                relationship.source_entity_id
+            )));
+        }
+
+        let created = match self.coalescers() {
+            Some(coalesce) => {
+                let client = self.clone();
+                coalesce
+                    .relationships
+                    .submit((relationship.clone(), embedding), move |batch| {
+                        let client = client.clone();
+                        async move { client.flush_relationships(batch).await }
+                    })
+                    .await?
+            }
+            None => self.write_relationship(relationship, embedding).await?,
+        };
+
+        metrics::histogram!("graph.relationship.create.latency")
+            .record(start.elapsed().as_secs_f64());
+
+        Ok(created)
+    }
+
+    async fn write_relationship(
+        &self,
+        relationship: &Relationship,
+        embedding: Option<Vec<f32>>,
+    ) -> Result<Relationship, GraphError> {
         let has_embedding = embedding.is_some();
         let embedding_f64: Vec<f64> = embedding
             .as_ref()
@@ -56,6 +111,7 @@ impl super::GraphClient {
             "r.description = $description".to_string(),
             "r.bidirectional = $bidirectional".to_string(),
             "r.embedding_pending = $embedding_pending".to_string(),
+            "r.created_at = $created_at".to_string(),
         ];
 
         if relationship.weight.is_some() {
@@ -70,6 +126,9 @@ impl super::GraphClient {
         if has_embedding {
             set_parts.push("r.embedding = $embedding".to_string());
         }
+        if relationship.created_by_investigation.is_some() {
+            set_parts.push("r.created_by_investigation = $created_by_investigation".to_string());
+        }
 
         let cypher = format!(
             "MATCH (s:Entity {{id: $source_id}}), (t:Entity {{id: $target_id}}) \
@@ -85,7 +144,8 @@ impl super::GraphClient {
             .param("id", relationship.id.to_string())
             .param("description", relationship.description.as_str())
             .param("bidirectional", relationship.bidirectional)
-            .param("embedding_pending", embedding_pending);
+            .param("embedding_pending", embedding_pending)
+            .param("created_at", format_datetime(&Utc::now()));
 
         if let Some(weight) = relationship.weight {
             q = q.param("weight", weight);
@@ -99,7 +159,11 @@ impl super::GraphClient {
         if has_embedding {
             q = q.param("embedding", embedding_f64);
         }
+        if let Some(investigation_id) = relationship.created_by_investigation {
+            q = q.param("created_by_investigation", investigation_id.to_string());
+        }
 
+        let _permit = self.writes.acquire("relationship.create").await;
         let mut result = self
             .graph
             .execute(q)
@@ -127,15 +191,86 @@ impl super::GraphClient {
             .get("target_id")
             .map_err(|e| GraphError::Query(format!("Missing 'target_id' column: {}", e)))?;
 
-        let created = relation_to_relationship(
+        relation_to_relationship(
             &rel,
             parse_entity_id(&source_id_str)?,
             parse_entity_id(&target_id_str)?,
-        )?;
+        )
+    }
 
-        metrics::histogram!("graph.relationship.create.latency")
-            .record(start.elapsed().as_secs_f64());
+    /// Write a coalesced batch of relationships, falling back to one write per
+    /// relationship for any the batch didn't create.
+    async fn flush_relationships(
+        &self,
+        batch: Vec<(Relationship, Option<Vec<f32>>)>,
+    ) -> Vec<Result<Relationship, GraphError>> {
+        let mut created = match self.create_relationships(&batch).await {
+            Ok(created) => created,
+            Err(e) => {
+                tracing::warn!(error = %e, relationships = batch.len(), "Batched relationship create failed, writing individually");
+                HashMap::new()
+            }
+        };
+
+        let mut results = Vec::with_capacity(batch.len());
+        for (relationship, embedding) in batch {
+            match created.remove(&relationship.id) {
+                Some(relationship) => results.push(Ok(relationship)),
+                None => {
+                    metrics::counter!("graph.write.coalesce_fallbacks", "target" => "relationship")
+                        .increment(1);
+                    results.push(self.write_relationship(&relationship, embedding).await);
+                }
+            }
+        }
+        results
+    }
+
+    /// Create RELATES_TO edges in one UNWIND write, returning them by ID.
+    /// Edges whose source or target entity doesn't exist are skipped.
+    async fn create_relationships(
+        &self,
+        relationships: &[(Relationship, Option<Vec<f32>>)],
+    ) -> Result<HashMap<RelationshipId, Relationship>, GraphError> {
+        let rows: Vec<HashMap<String, BoltType>> = relationships
+            .iter()
+            .map(|(relationship, embedding)| relationship_row(relationship, embedding.as_deref()))
+            .collect();
 
+        let q = query(
+            "UNWIND $rows AS row \
+             MATCH (s:Entity {id: row.source_id}), (t:Entity {id: row.target_id}) \
+             CREATE (s)-[r:RELATES_TO]->(t) \
+             SET r.id = row.id, \
+                 r.description = row.description, \
+                 r.bidirectional = row.bidirectional, \
+                 r.embedding_pending = row.embedding_pending, \
+                 r.created_at = row.created_at, \
+                 r.weight = row.weight, \
+                 r.confidence = row.confidence, \
+                 r.timestamp = row.timestamp, \
+                 r.embedding = row.embedding, \
+                 r.created_by_investigation = row.created_by_investigation \
+             RETURN r, s.id AS source_id, t.id AS target_id",
+        )
+        .param("rows", rows);
+
+        let _permit = self.writes.acquire("relationship.create_batch").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut created = HashMap::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let relationship = row_to_relationship(row)?;
+            created.insert(relationship.id, relationship);
+        }
         Ok(created)
     }
 
@@ -150,6 +285,11 @@ impl super::GraphClient {
 
         let mut set_clauses = Vec::new();
 
+        // Before the weight assignment, so it reads the old value.
+        if update.weight.is_some() && update.weight_recalibrated_at.is_some() {
+            set_clauses.push("r.previous_weight = r.weight".to_string());
+            set_clauses.push("r.weight_recalibrated_at = $weight_recalibrated_at".to_string());
+        }
         if update.description.is_some() {
             set_clauses.push("r.description = $description".to_string());
         }
@@ -199,11 +339,15 @@ impl super::GraphClient {
         if let Some(ref ts) = update.timestamp {
             q = q.param("timestamp", format_datetime(ts));
         }
+        if let Some(ref ts) = update.weight_recalibrated_at {
+            q = q.param("weight_recalibrated_at", format_datetime(ts));
+        }
         if let Some(ref emb) = embedding {
             let emb_f64: Vec<f64> = emb.iter().map(|&f| f as f64).collect();
             q = q.param("embedding", emb_f64);
         }
 
+        let _permit = self.writes.acquire("relationship.update").await;
         let mut result = self
             .graph
             .execute(q)
@@ -238,6 +382,103 @@ impl super::GraphClient {
         Ok(updated)
     }
 
+    /// Flip a relationship's direction. The edge is recreated from target to
+    /// source in one statement, keeping its ID and properties, so claims that
+    /// support it stay linked.
+    pub async fn reverse_relationship(
+        &self,
+        id: RelationshipId,
+    ) -> Result<Relationship, GraphError> {
+        let q = query(
+            "MATCH (s:Entity)-[r:RELATES_TO {id: $id}]->(t:Entity) \
+             CREATE (t)-[reversed:RELATES_TO]->(s) \
+             SET reversed = properties(r) \
+             DELETE r \
+             RETURN reversed AS r, t.id AS source_id, s.id AS target_id",
+        )
+        .param("id", id.to_string());
+
+        let _permit = self.writes.acquire("relationship.reverse").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let row = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .ok_or_else(|| GraphError::NotFound(format!("Relationship {}", id)))?;
+
+        let rel: neo4rs::Relation = row
+            .get("r")
+            .map_err(|e| GraphError::Query(format!("Missing 'r' column: {}", e)))?;
+        let source_id_str: String = row
+            .get("source_id")
+            .map_err(|e| GraphError::Query(format!("Missing 'source_id' column: {}", e)))?;
+        let target_id_str: String = row
+            .get("target_id")
+            .map_err(|e| GraphError::Query(format!("Missing 'target_id' column: {}", e)))?;
+
+        metrics::counter!("graph.relationship.reversed").increment(1);
+
+        relation_to_relationship(
+            &rel,
+            parse_entity_id(&source_id_str)?,
+            parse_entity_id(&target_id_str)?,
+        )
+    }
+
+    /// Relationships from `source_id` to `target_id`, plus bidirectional ones
+    /// from `target_id` to `source_id`, at most `graph_reads.max_traversal_results`.
+    pub async fn relationships_between(
+        &self,
+        source_id: EntityId,
+        target_id: EntityId,
+    ) -> Result<Vec<Relationship>, GraphError> {
+        let q = query(
+            "MATCH (s:Entity)-[r:RELATES_TO]->(t:Entity) \
+             WHERE (s.id = $source_id AND t.id = $target_id) \
+                OR (s.id = $target_id AND t.id = $source_id AND r.bidirectional = true) \
+             RETURN r, s.id AS source_id, t.id AS target_id \
+             LIMIT $limit",
+        )
+        .param("source_id", source_id.to_string())
+        .param("target_id", target_id.to_string())
+        .param("limit", self.reads.max_traversal_results as i64);
+
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut relationships = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let rel: neo4rs::Relation = row
+                .get("r")
+                .map_err(|e| GraphError::Query(format!("Missing 'r': {}", e)))?;
+            let source_id_str: String = row
+                .get("source_id")
+                .map_err(|e| GraphError::Query(format!("Missing 'source_id': {}", e)))?;
+            let target_id_str: String = row
+                .get("target_id")
+                .map_err(|e| GraphError::Query(format!("Missing 'target_id': {}", e)))?;
+            relationships.push(relation_to_relationship(
+                &rel,
+                parse_entity_id(&source_id_str)?,
+                parse_entity_id(&target_id_str)?,
+            )?);
+        }
+
+        Ok(relationships)
+    }
+
     /// Get a single relationship by ID.
     pub async fn get_relationship(&self, id: RelationshipId) -> Result<Relationship, GraphError> {
         let q = query(
@@ -276,13 +517,19 @@ impl super::GraphClient {
     }
 
     /// Traverse relationships from an entity with direction/weight/limit filters.
-    /// Returns pairs of (Relationship, connected Entity).
+    /// The limit is capped at `graph_reads.max_traversal_results`.
     pub async fn traverse_relationships(
         &self,
         entity_id: EntityId,
         params: &TraversalParams,
-    ) -> Result<Vec<(Relationship, Entity)>, GraphError> {
+    ) -> Result<TraversalPage, GraphError> {
         let start = std::time::Instant::now();
+        let limit = ReadLimit::new(
+            params.limit,
+            DEFAULT_TRAVERSAL_LIMIT,
+            self.reads.max_traversal_results,
+            "relationship.traverse",
+        );
 
         // Build direction-specific MATCH patterns.
         let direction = params
@@ -290,30 +537,31 @@ impl super::GraphClient {
             .as_ref()
             .unwrap_or(&TraversalDirection::Both);
 
-        let mut where_clauses = Vec::new();
-        if let Some(min_weight) = params.min_weight {
-            where_clauses.push(format!("r.weight >= {}", min_weight));
+        let mut where_parts = Vec::new();
+        if params.min_weight.is_some() {
+            where_parts.push("r.weight >= $min_weight");
         }
-
-        let where_str = if where_clauses.is_empty() {
+        if params.as_of.is_some() {
+            where_parts.push("r.created_at <= $as_of");
+            where_parts.push("coalesce(other.created_at, other.last_updated) <= $as_of");
+        }
+        let where_str = if where_parts.is_empty() {
             String::new()
         } else {
-            format!(" WHERE {}", where_clauses.join(" AND "))
+            format!(" WHERE {}", where_parts.join(" AND "))
         };
 
-        let limit = params.limit.unwrap_or(100);
-
         let cypher = match direction {
             TraversalDirection::Outgoing => format!(
                 "MATCH (start:Entity {{id: $entity_id}})-[r:RELATES_TO]->(other:Entity){} \
                  RETURN r, start.id AS source_id, other.id AS target_id, other AS connected \
-                 LIMIT $limit",
+                 LIMIT $fetch",
                 where_str
             ),
             TraversalDirection::Incoming => format!(
                 "MATCH (other:Entity)-[r:RELATES_TO]->(start:Entity {{id: $entity_id}}){} \
                  RETURN r, other.id AS source_id, start.id AS target_id, other AS connected \
-                 LIMIT $limit",
+                 LIMIT $fetch",
                 where_str
             ),
             TraversalDirection::Both => format!(
@@ -323,14 +571,20 @@ impl super::GraphClient {
                       CASE WHEN endNode(r) = start THEN start.id ELSE other.id END AS target_id, \
                       other AS connected \
                  RETURN r, source_id, target_id, connected \
-                 LIMIT $limit",
+                 LIMIT $fetch",
                 where_str
             ),
         };
 
-        let q = query(&cypher)
+        let mut q = query(&cypher)
             .param("entity_id", entity_id.to_string())
-            .param("limit", limit as i64);
+            .param("fetch", limit.fetch() as i64);
+        if let Some(min_weight) = params.min_weight {
+            q = q.param("min_weight", min_weight);
+        }
+        if let Some(ref as_of) = params.as_of {
+            q = q.param("as_of", format_datetime(as_of));
+        }
 
         let mut result = self
             .graph
@@ -369,9 +623,65 @@ impl super::GraphClient {
             pairs.push((relationship, entity));
         }
 
+        let size = limit.finish(&mut pairs, ApproxBytes::approx_bytes);
+
         metrics::histogram!("graph.relationship.traverse.latency")
             .record(start.elapsed().as_secs_f64());
+        record_read_latency("relationship.traverse", start);
 
-        Ok(pairs)
+        Ok(TraversalPage {
+            results: pairs,
+            size,
+        })
     }
 }
+
+/// A relationship as an UNWIND row for `create_relationships`.
+fn relationship_row(
+    relationship: &Relationship,
+    embedding: Option<&[f32]>,
+) -> HashMap<String, BoltType> {
+    HashMap::from([
+        (
+            "source_id".to_string(),
+            relationship.source_entity_id.to_string().into(),
+        ),
+        (
+            "target_id".to_string(),
+            relationship.target_entity_id.to_string().into(),
+        ),
+        ("id".to_string(), relationship.id.to_string().into()),
+        (
+            "description".to_string(),
+            relationship.description.as_str().into(),
+        ),
+        (
+            "bidirectional".to_string(),
+            relationship.bidirectional.into(),
+        ),
+        ("embedding_pending".to_string(), embedding.is_none().into()),
+        (
+            "created_at".to_string(),
+            format_datetime(&Utc::now()).into(),
+        ),
+        ("weight".to_string(), relationship.weight.into()),
+        ("confidence".to_string(), relationship.confidence.into()),
+        (
+            "timestamp".to_string(),
+            relationship.timestamp.as_ref().map(format_datetime).into(),
+        ),
+        (
+            "embedding".to_string(),
+            embedding
+                .map(|v| v.iter().map(|&f| f as f64).collect::<Vec<_>>())
+                .into(),
+        ),
+        (
+            "created_by_investigation".to_string(),
+            relationship
+                .created_by_investigation
+                .map(|id| id.to_string())
+                .into(),
+        ),
+    ])
+}
@@ -1,13 +1,21 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use neo4rs::query;
+use serde::{Deserialize, Serialize};
 
-use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_common::types::{
+    AttributionDepth, Claim, Entity, FeatureFlag, InformationType, Relationship,
+};
 use autosint_common::EntityId;
 use chrono::{DateTime, Utc};
 
+use super::claims::{attribution_depth_str, information_type_str};
 use super::conversions::{
     format_datetime, node_to_claim, node_to_entity, parse_entity_id, relation_to_relationship,
 };
 use super::escape_lucene_query;
+use super::limits::{ApproxBytes, ReadLimit, ResultSize};
+use super::writes::record_read_latency;
 use super::GraphError;
 
 /// How to search: semantic (vector) or keyword (fulltext).
@@ -24,6 +32,150 @@ pub struct SearchResult<T> {
     pub score: f64,
 }
 
+/// Page size when a search sets no limit.
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+
+/// Chunk vectors fetched per claim wanted from a semantic claim search. Several
+/// chunks of one claim can rank near each other.
+const CHUNK_CANDIDATES_PER_CLAIM: u32 = 4;
+
+/// Keyset condition resuming score-ordered results after `$cursor_score` /
+/// `$cursor_id`. `{id}` is the result's id expression.
+const SCORE_KEYSET: &str =
+    "(score < $cursor_score OR (score = $cursor_score AND {id} > $cursor_id))";
+
+/// One page of search results.
+#[allow(dead_code)]
+pub struct SearchPage<T> {
+    pub results: Vec<SearchResult<T>>,
+    /// Set when more results matched: pass it back as the search's `cursor`
+    /// to get the results that follow.
+    pub next_cursor: Option<SearchCursor>,
+    pub size: ResultSize,
+}
+
+impl<T> SearchPage<T> {
+    fn empty() -> Self {
+        Self {
+            results: Vec::new(),
+            next_cursor: None,
+            size: ResultSize::default(),
+        }
+    }
+}
+
+/// Where the next page of a search starts: just after the last result of the
+/// previous page. A cursor is only valid with the exact query parameters that
+/// produced it; anything else skips or repeats results. Score-ordered pages
+/// can also shift slightly when the index changes between requests.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SearchCursor {
+    /// Fulltext or vector results, by score descending, ties broken by id.
+    Score {
+        #[serde(rename = "s")]
+        score: f64,
+        id: String,
+        /// Results on earlier pages. Vector queries ask the index for this
+        /// many nearest neighbours plus a page.
+        #[serde(rename = "n")]
+        seen: u32,
+    },
+    /// Filter-only claim search, newest ingested first, ties broken by id.
+    Ingested {
+        #[serde(rename = "t")]
+        ingested: String,
+        id: String,
+    },
+}
+
+impl SearchCursor {
+    /// The opaque form handed to callers.
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, GraphError> {
+        URL_SAFE_NO_PAD
+            .decode(encoded.trim())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| GraphError::InvalidCursor("not a search cursor".into()))
+    }
+
+    /// Score and id to resume after, plus results already returned.
+    fn score_position(cursor: Option<&Self>) -> Result<Option<(f64, &str, u32)>, GraphError> {
+        match cursor {
+            None => Ok(None),
+            Some(Self::Score { score, id, seen }) => Ok(Some((*score, id.as_str(), *seen))),
+            Some(Self::Ingested { .. }) => Err(GraphError::InvalidCursor(
+                "cursor is from a filter-only claim search".into(),
+            )),
+        }
+    }
+
+    /// Ingestion time and id to resume after.
+    fn ingested_position(cursor: Option<&Self>) -> Result<Option<(&str, &str)>, GraphError> {
+        match cursor {
+            None => Ok(None),
+            Some(Self::Ingested { ingested, id }) => Ok(Some((ingested.as_str(), id.as_str()))),
+            Some(Self::Score { .. }) => Err(GraphError::InvalidCursor(
+                "cursor is from a query search".into(),
+            )),
+        }
+    }
+}
+
+/// Add the score keyset condition for `id_expr` when resuming from a cursor.
+fn push_score_keyset(
+    position: Option<(f64, &str, u32)>,
+    id_expr: &str,
+    where_parts: &mut Vec<String>,
+) {
+    if position.is_some() {
+        where_parts.push(SCORE_KEYSET.replace("{id}", id_expr));
+    }
+}
+
+fn bind_score_position(q: neo4rs::Query, position: Option<(f64, &str, u32)>) -> neo4rs::Query {
+    match position {
+        Some((score, id, _)) => q.param("cursor_score", score).param("cursor_id", id),
+        None => q,
+    }
+}
+
+/// Cursor after a page of score-ordered results that held more back, None
+/// after the last page.
+fn next_score_cursor<T>(
+    results: &[SearchResult<T>],
+    size: &ResultSize,
+    seen: u32,
+    id: impl Fn(&T) -> String,
+) -> Option<SearchCursor> {
+    if !size.truncated {
+        return None;
+    }
+    let last = results.last()?;
+    Some(SearchCursor::Score {
+        score: last.score,
+        id: id(&last.item),
+        seen: seen + results.len() as u32,
+    })
+}
+
+/// Tail of a claim search: keep the first `$fetch` candidates in `order`,
+/// then look up each one's source and references. Limiting first keeps the
+/// OPTIONAL MATCHes and `collect` from running over every candidate.
+fn claim_page_tail(order: &str) -> String {
+    format!(
+        "WITH c, score ORDER BY {order} LIMIT $fetch \
+         OPTIONAL MATCH (source:Entity)-[:PUBLISHED]->(c) \
+         OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
+         WITH c, score, source.id AS source_id, collect(ref.id) AS ref_ids \
+         RETURN c, source_id, ref_ids, score ORDER BY {order}"
+    )
+}
+
 /// Parameters for searching entities.
 #[allow(dead_code)]
 pub struct EntitySearchParams {
@@ -32,7 +184,11 @@ pub struct EntitySearchParams {
     pub kind_filter: Option<String>,
     pub updated_after: Option<DateTime<Utc>>,
     pub updated_before: Option<DateTime<Utc>>,
+    /// Only entities that existed at this time.
+    pub created_before: Option<DateTime<Utc>>,
     pub limit: Option<u32>,
+    /// Resume after a previous page.
+    pub cursor: Option<SearchCursor>,
 }
 
 /// Parameters for searching claims.
@@ -46,129 +202,146 @@ pub struct ClaimSearchParams {
     pub referenced_entity_id: Option<EntityId>,
     pub attribution_depth: Option<AttributionDepth>,
     pub information_type: Option<InformationType>,
+    /// ISO 639-1 code; claims without a language never match.
+    pub language: Option<String>,
+    /// Normalized region (see `GraphClient::normalize_region`); claims
+    /// without a region never match.
+    pub region: Option<String>,
+    /// Lowest source trust level as ingested; claims from unrated sources
+    /// never match.
+    pub min_trust: Option<u8>,
+    /// Only claims ingested by this time.
+    pub ingested_before: Option<DateTime<Utc>>,
     pub limit: Option<u32>,
+    /// Resume after a previous page.
+    pub cursor: Option<SearchCursor>,
 }
 
 /// Parameters for searching relationships.
 #[allow(dead_code)]
 pub struct RelationshipSearchParams {
     pub query: String,
+    /// Only relationships that existed at this time.
+    pub created_before: Option<DateTime<Utc>>,
     pub limit: Option<u32>,
+    /// Resume after a previous page.
+    pub cursor: Option<SearchCursor>,
 }
 
 #[allow(dead_code)]
 impl super::GraphClient {
-    /// Search entities by vector similarity or fulltext.
+    /// Search entities by vector similarity or fulltext, one page at a time.
     pub async fn search_entities(
         &self,
         params: &EntitySearchParams,
         query_embedding: Option<Vec<f32>>,
-    ) -> Result<Vec<SearchResult<Entity>>, GraphError> {
+    ) -> Result<SearchPage<Entity>, GraphError> {
         let start = std::time::Instant::now();
-        let limit = params.limit.unwrap_or(20) as i64;
+        let limit = ReadLimit::new(
+            params.limit,
+            DEFAULT_SEARCH_LIMIT,
+            self.reads.max_search_results,
+            "entity.search",
+        );
+        let position = SearchCursor::score_position(params.cursor.as_ref())?;
+        let seen = position.map_or(0, |(_, _, seen)| seen);
+
+        // Build WHERE filters.
+        let mut where_parts = Vec::new();
+        if params.kind_filter.is_some() {
+            where_parts.push("node.kind = $kind_filter".to_string());
+        }
+        if params.updated_after.is_some() {
+            where_parts.push("node.last_updated >= $updated_after".to_string());
+        }
+        if params.updated_before.is_some() {
+            where_parts.push("node.last_updated <= $updated_before".to_string());
+        }
+        if params.created_before.is_some() {
+            where_parts.push(
+                "coalesce(node.created_at, node.last_updated) <= $created_before".to_string(),
+            );
+        }
+        push_score_keyset(position, "node.id", &mut where_parts);
+
+        let where_str = if where_parts.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", where_parts.join(" AND "))
+        };
 
-        let results = match params.mode {
+        let q = match params.mode {
             SearchMode::Semantic => {
                 let embedding = query_embedding.ok_or_else(|| {
                     GraphError::Query("Semantic search requires a query embedding".into())
                 })?;
                 let emb_f64: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
 
-                // Build WHERE filters.
-                let mut where_parts = Vec::new();
-                if params.kind_filter.is_some() {
-                    where_parts.push("node.kind = $kind_filter".to_string());
-                }
-                if params.updated_after.is_some() {
-                    where_parts.push("node.last_updated >= $updated_after".to_string());
-                }
-                if params.updated_before.is_some() {
-                    where_parts.push("node.last_updated <= $updated_before".to_string());
-                }
-
-                let where_str = if where_parts.is_empty() {
-                    String::new()
-                } else {
-                    format!(" WHERE {}", where_parts.join(" AND "))
-                };
-
+                // The index returns the k nearest neighbours; earlier pages
+                // are among them and skipped by the keyset condition.
                 let cypher = format!(
-                    "CALL db.index.vector.queryNodes('entity_embedding', $limit, $embedding) \
+                    "CALL db.index.vector.queryNodes('entity_embedding', $k, $embedding) \
                      YIELD node, score{} \
-                     RETURN node, score ORDER BY score DESC",
+                     RETURN node, score ORDER BY score DESC, node.id LIMIT $fetch",
                     where_str
                 );
 
-                let mut q = query(&cypher)
-                    .param("limit", limit)
-                    .param("embedding", emb_f64);
-
-                if let Some(ref kind) = params.kind_filter {
-                    q = q.param("kind_filter", kind.as_str());
-                }
-                if let Some(ref after) = params.updated_after {
-                    q = q.param("updated_after", format_datetime(after));
-                }
-                if let Some(ref before) = params.updated_before {
-                    q = q.param("updated_before", format_datetime(before));
-                }
-
-                self.execute_entity_search(q).await?
+                query(&cypher)
+                    .param("k", (seen + limit.fetch()) as i64)
+                    .param("fetch", limit.fetch() as i64)
+                    .param("embedding", emb_f64)
             }
             SearchMode::Keyword => {
-                let mut where_parts = Vec::new();
-                if params.kind_filter.is_some() {
-                    where_parts.push("node.kind = $kind_filter".to_string());
-                }
-                if params.updated_after.is_some() {
-                    where_parts.push("node.last_updated >= $updated_after".to_string());
-                }
-                if params.updated_before.is_some() {
-                    where_parts.push("node.last_updated <= $updated_before".to_string());
-                }
-
-                let where_str = if where_parts.is_empty() {
-                    String::new()
-                } else {
-                    format!(" WHERE {}", where_parts.join(" AND "))
-                };
-
                 let cypher = format!(
                     "CALL db.index.fulltext.queryNodes('entity_name_fulltext', $query) \
                      YIELD node, score{} \
-                     RETURN node, score ORDER BY score DESC LIMIT $limit",
+                     RETURN node, score ORDER BY score DESC, node.id LIMIT $fetch",
                     where_str
                 );
 
                 let escaped_query = escape_lucene_query(&params.query);
-                let mut q = query(&cypher)
-                    .param("query", escaped_query.as_str())
-                    .param("limit", limit);
-
-                if let Some(ref kind) = params.kind_filter {
-                    q = q.param("kind_filter", kind.as_str());
-                }
-                if let Some(ref after) = params.updated_after {
-                    q = q.param("updated_after", format_datetime(after));
+                if escaped_query.is_empty() {
+                    return Ok(SearchPage::empty());
                 }
-                if let Some(ref before) = params.updated_before {
-                    q = q.param("updated_before", format_datetime(before));
-                }
-
-                self.execute_entity_search(q).await?
+                query(&cypher)
+                    .param("query", escaped_query.as_str())
+                    .param("fetch", limit.fetch() as i64)
             }
         };
 
+        let mut q = bind_score_position(q, position);
+        if let Some(ref kind) = params.kind_filter {
+            q = q.param("kind_filter", kind.as_str());
+        }
+        if let Some(ref after) = params.updated_after {
+            q = q.param("updated_after", format_datetime(after));
+        }
+        if let Some(ref before) = params.updated_before {
+            q = q.param("updated_before", format_datetime(before));
+        }
+        if let Some(ref before) = params.created_before {
+            q = q.param("created_before", format_datetime(before));
+        }
+
+        let mut results = self.execute_entity_search(q).await?;
+        let size = limit.finish(&mut results, |r| r.item.approx_bytes());
+
         metrics::histogram!("graph.search.latency", "mode" => match params.mode {
             SearchMode::Semantic => "semantic",
             SearchMode::Keyword => "keyword",
         }, "target" => "entity")
         .record(start.elapsed().as_secs_f64());
+        record_read_latency("entity.search", start);
 
         metrics::histogram!("graph.search.results", "target" => "entity")
             .record(results.len() as f64);
 
-        Ok(results)
+        let next_cursor = next_score_cursor(&results, &size, seen, |e| e.id.to_string());
+        Ok(SearchPage {
+            results,
+            next_cursor,
+            size,
+        })
     }
 
     async fn execute_entity_search(
@@ -202,119 +375,137 @@ impl super::GraphClient {
         Ok(results)
     }
 
-    /// Search claims by content, entity references, or temporal filters.
+    /// Search claims by content, entity references, or temporal filters, one
+    /// page at a time. Query searches order by score, filter-only searches by
+    /// ingestion time, newest first.
     pub async fn search_claims(
         &self,
         params: &ClaimSearchParams,
         query_embedding: Option<Vec<f32>>,
-    ) -> Result<Vec<SearchResult<Claim>>, GraphError> {
+    ) -> Result<SearchPage<Claim>, GraphError> {
         let start = std::time::Instant::now();
-        let limit = params.limit.unwrap_or(20) as i64;
+        let limit = ReadLimit::new(
+            params.limit,
+            DEFAULT_SEARCH_LIMIT,
+            self.reads.max_search_results,
+            "claim.search",
+        );
 
         // Determine the search approach based on what parameters are provided.
-        let results = if let Some(ref query_text) = params.query {
+        let (results, next_cursor, size) = if let Some(ref query_text) = params.query {
+            let position = SearchCursor::score_position(params.cursor.as_ref())?;
+            let seen = position.map_or(0, |(_, _, seen)| seen);
+
+            let mut where_parts = Vec::new();
+            self.add_claim_filters(params, &mut where_parts);
+            push_score_keyset(position, "c.id", &mut where_parts);
+
+            let where_str = if where_parts.is_empty() {
+                String::new()
+            } else {
+                format!(" WHERE {}", where_parts.join(" AND "))
+            };
+
             let mode = params.mode.as_ref().unwrap_or(&SearchMode::Keyword);
-            match mode {
+            let q = match mode {
                 SearchMode::Semantic => {
                     let embedding = query_embedding.ok_or_else(|| {
                         GraphError::Query("Semantic claim search requires a query embedding".into())
                     })?;
                     let emb_f64: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
 
-                    let mut where_parts = Vec::new();
-                    self.add_claim_filters(params, &mut where_parts);
-
-                    let where_str = if where_parts.is_empty() {
-                        String::new()
+                    // Long claims also have chunk embeddings. A claim scores
+                    // its best match, whole or by chunk.
+                    let candidates = if self.flags.is_enabled(FeatureFlag::ClaimChunkSearch) {
+                        "CALL { \
+                            CALL db.index.vector.queryNodes('claim_embedding', $k, $embedding) \
+                            YIELD node, score \
+                            RETURN node AS c, score \
+                            UNION ALL \
+                            CALL db.index.vector.queryNodes('claim_chunk_embedding', $chunk_k, $embedding) \
+                            YIELD node, score \
+                            MATCH (node)-[:PART_OF]->(c:Claim) \
+                            RETURN c, score \
+                         } \
+                         WITH c, max(score) AS score"
                     } else {
-                        format!(" WHERE {}", where_parts.join(" AND "))
+                        "CALL db.index.vector.queryNodes('claim_embedding', $k, $embedding) \
+                         YIELD node AS c, score"
                     };
-
                     let cypher = format!(
-                        "CALL db.index.vector.queryNodes('claim_embedding', $limit, $embedding) \
-                         YIELD node AS c, score{} \
-                         OPTIONAL MATCH (source:Entity)-[:PUBLISHED]->(c) \
-                         OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
-                         RETURN c, source.id AS source_id, collect(ref.id) AS ref_ids, score \
-                         ORDER BY score DESC",
-                        where_str
+                        "{}{} {}",
+                        candidates,
+                        where_str,
+                        claim_page_tail("score DESC, c.id")
                     );
 
-                    let q = query(&cypher)
-                        .param("limit", limit)
-                        .param("embedding", emb_f64);
-
-                    let q = self.bind_claim_filter_params(params, q);
-
-                    self.execute_claim_search(q).await?
+                    query(&cypher)
+                        .param("k", (seen + limit.fetch()) as i64)
+                        .param(
+                            "chunk_k",
+                            ((seen + limit.fetch()) * CHUNK_CANDIDATES_PER_CLAIM) as i64,
+                        )
+                        .param("fetch", limit.fetch() as i64)
+                        .param("embedding", emb_f64)
                 }
                 SearchMode::Keyword => {
-                    let mut where_parts = Vec::new();
-                    self.add_claim_filters(params, &mut where_parts);
-
-                    let where_str = if where_parts.is_empty() {
-                        String::new()
-                    } else {
-                        format!(" WHERE {}", where_parts.join(" AND "))
-                    };
-
                     let cypher = format!(
                         "CALL db.index.fulltext.queryNodes('claim_content_fulltext', $query) \
-                         YIELD node AS c, score{} \
-                         OPTIONAL MATCH (source:Entity)-[:PUBLISHED]->(c) \
-                         OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
-                         RETURN c, source.id AS source_id, collect(ref.id) AS ref_ids, score \
-                         ORDER BY score DESC LIMIT $limit",
-                        where_str
+                         YIELD node AS c, score{} {}",
+                        where_str,
+                        claim_page_tail("score DESC, c.id")
                     );
 
                     let escaped_query = escape_lucene_query(query_text);
-                    let q = query(&cypher)
+                    if escaped_query.is_empty() {
+                        return Ok(SearchPage::empty());
+                    }
+                    query(&cypher)
                         .param("query", escaped_query.as_str())
-                        .param("limit", limit);
-
-                    let q = self.bind_claim_filter_params(params, q);
-
-                    self.execute_claim_search(q).await?
+                        .param("fetch", limit.fetch() as i64)
                 }
-            }
+            };
+
+            let q = bind_score_position(q, position);
+            let q = self.bind_claim_filter_params(params, q);
+            let mut results = self.execute_claim_search(q).await?;
+            let size = limit.finish(&mut results, |r| r.item.approx_bytes());
+            let next_cursor = next_score_cursor(&results, &size, seen, |c| c.id.to_string());
+            (results, next_cursor, size)
         } else {
             // No query text — filter-only search (by entity, time range, etc.).
+            // Entity filters anchor the match on the entity, so only its
+            // claims are read. Otherwise the `ingested_timestamp` predicate
+            // lets the planner walk claim_ingested_idx newest first and stop
+            // at the limit instead of sorting every claim.
+            let position = SearchCursor::ingested_position(params.cursor.as_ref())?;
             let mut where_parts = Vec::new();
-            let mut match_parts = vec!["MATCH (c:Claim)".to_string()];
+            let mut match_parts = Vec::new();
 
             if params.source_entity_id.is_some() {
                 match_parts.push(
-                    "MATCH (source_filter:Entity {id: $source_entity_id})-[:PUBLISHED]->(c)"
+                    "MATCH (source_filter:Entity {id: $source_entity_id})-[:PUBLISHED]->(c:Claim)"
                         .to_string(),
                 );
             }
             if params.referenced_entity_id.is_some() {
                 match_parts.push(
-                    "MATCH (c)-[:REFERENCES]->(ref_filter:Entity {id: $referenced_entity_id})"
+                    "MATCH (c:Claim)-[:REFERENCES]->(ref_filter:Entity {id: $referenced_entity_id})"
                         .to_string(),
                 );
             }
-
-            self.add_claim_temporal_filters(params, &mut where_parts);
-
-            if let Some(ref depth) = params.attribution_depth {
-                let depth_str = match depth {
-                    AttributionDepth::Primary => "primary",
-                    AttributionDepth::Secondhand => "secondhand",
-                    AttributionDepth::Indirect => "indirect",
-                };
-                where_parts.push(format!("c.attribution_depth = '{}'", depth_str));
+            if match_parts.is_empty() {
+                match_parts.push("MATCH (c:Claim)".to_string());
             }
 
-            if let Some(ref info_type) = params.information_type {
-                let type_str = match info_type {
-                    InformationType::Assertion => "assertion",
-                    InformationType::Analysis => "analysis",
-                    InformationType::Discourse => "discourse",
-                    InformationType::Testimony => "testimony",
-                };
-                where_parts.push(format!("c.information_type = '{}'", type_str));
+            where_parts.push("c.ingested_timestamp IS NOT NULL".to_string());
+            self.add_claim_filters(params, &mut where_parts);
+            if position.is_some() {
+                where_parts.push(
+                    "(c.ingested_timestamp < $cursor_ingested OR \
+                     (c.ingested_timestamp = $cursor_ingested AND c.id > $cursor_id))"
+                        .to_string(),
+                );
             }
 
             let where_str = if where_parts.is_empty() {
@@ -324,31 +515,36 @@ impl super::GraphClient {
             };
 
             let cypher = format!(
-                "{}{} \
-                 OPTIONAL MATCH (source:Entity)-[:PUBLISHED]->(c) \
-                 OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
-                 RETURN c, source.id AS source_id, collect(ref.id) AS ref_ids, 1.0 AS score \
-                 ORDER BY c.ingested_timestamp DESC LIMIT $limit",
+                "{}{} WITH c, 1.0 AS score {}",
                 match_parts.join(" "),
-                where_str
+                where_str,
+                claim_page_tail("c.ingested_timestamp DESC, c.id")
             );
 
-            let mut q = query(&cypher).param("limit", limit);
+            let mut q = query(&cypher).param("fetch", limit.fetch() as i64);
 
+            if let Some((ingested, id)) = position {
+                q = q.param("cursor_ingested", ingested).param("cursor_id", id);
+            }
             if let Some(ref source_id) = params.source_entity_id {
                 q = q.param("source_entity_id", source_id.to_string());
             }
             if let Some(ref ref_id) = params.referenced_entity_id {
                 q = q.param("referenced_entity_id", ref_id.to_string());
             }
-            if let Some(ref after) = params.published_after {
-                q = q.param("published_after", format_datetime(after));
-            }
-            if let Some(ref before) = params.published_before {
-                q = q.param("published_before", format_datetime(before));
-            }
-
-            self.execute_claim_search(q).await?
+            let q = self.bind_claim_filter_params(params, q);
+
+            let mut results = self.execute_claim_search(q).await?;
+            let size = limit.finish(&mut results, |r| r.item.approx_bytes());
+            let next_cursor = size
+                .truncated
+                .then(|| results.last())
+                .flatten()
+                .map(|last| SearchCursor::Ingested {
+                    ingested: format_datetime(&last.item.ingested_timestamp),
+                    id: last.item.id.to_string(),
+                });
+            (results, next_cursor, size)
         };
 
         let mode_label = params
@@ -364,32 +560,34 @@ impl super::GraphClient {
 
         metrics::histogram!("graph.search.latency", "mode" => mode_label, "target" => "claim")
             .record(start.elapsed().as_secs_f64());
+        record_read_latency("claim.search", start);
         metrics::histogram!("graph.search.results", "target" => "claim")
             .record(results.len() as f64);
 
-        Ok(results)
+        Ok(SearchPage {
+            results,
+            next_cursor,
+            size,
+        })
     }
 
     fn add_claim_filters(&self, params: &ClaimSearchParams, where_parts: &mut Vec<String>) {
         self.add_claim_temporal_filters(params, where_parts);
 
-        if let Some(ref depth) = params.attribution_depth {
-            let depth_str = match depth {
-                AttributionDepth::Primary => "primary",
-                AttributionDepth::Secondhand => "secondhand",
-                AttributionDepth::Indirect => "indirect",
-            };
-            where_parts.push(format!("c.attribution_depth = '{}'", depth_str));
+        if params.attribution_depth.is_some() {
+            where_parts.push("c.attribution_depth = $attribution_depth".to_string());
         }
-
-        if let Some(ref info_type) = params.information_type {
-            let type_str = match info_type {
-                InformationType::Assertion => "assertion",
-                InformationType::Analysis => "analysis",
-                InformationType::Discourse => "discourse",
-                InformationType::Testimony => "testimony",
-            };
-            where_parts.push(format!("c.information_type = '{}'", type_str));
+        if params.information_type.is_some() {
+            where_parts.push("c.information_type = $information_type".to_string());
+        }
+        if params.language.is_some() {
+            where_parts.push("c.language = $language".to_string());
+        }
+        if params.region.is_some() {
+            where_parts.push("c.region = $region".to_string());
+        }
+        if params.min_trust.is_some() {
+            where_parts.push("c.source_trust_level >= $min_trust".to_string());
         }
     }
 
@@ -404,6 +602,9 @@ impl super::GraphClient {
         if params.published_before.is_some() {
             where_parts.push("c.published_timestamp <= $published_before".to_string());
         }
+        if params.ingested_before.is_some() {
+            where_parts.push("c.ingested_timestamp <= $ingested_before".to_string());
+        }
     }
 
     fn bind_claim_filter_params(
@@ -416,10 +617,40 @@ impl super::GraphClient {
         } else {
             q
         };
-        if let Some(ref before) = params.published_before {
+        let q = if let Some(ref before) = params.published_before {
             q.param("published_before", format_datetime(before))
         } else {
             q
+        };
+        let q = if let Some(ref before) = params.ingested_before {
+            q.param("ingested_before", format_datetime(before))
+        } else {
+            q
+        };
+        let q = if let Some(ref depth) = params.attribution_depth {
+            q.param("attribution_depth", attribution_depth_str(depth))
+        } else {
+            q
+        };
+        let q = if let Some(ref info_type) = params.information_type {
+            q.param("information_type", information_type_str(info_type))
+        } else {
+            q
+        };
+        let q = if let Some(ref language) = params.language {
+            q.param("language", language.as_str())
+        } else {
+            q
+        };
+        let q = if let Some(ref region) = params.region {
+            q.param("region", region.as_str())
+        } else {
+            q
+        };
+        if let Some(min_trust) = params.min_trust {
+            q.param("min_trust", i64::from(min_trust))
+        } else {
+            q
         }
     }
 
@@ -465,30 +696,55 @@ impl super::GraphClient {
         Ok(results)
     }
 
-    /// Search relationships by semantic similarity.
+    /// Search relationships by semantic similarity, one page at a time.
     pub async fn search_relationships(
         &self,
         params: &RelationshipSearchParams,
         query_embedding: Option<Vec<f32>>,
-    ) -> Result<Vec<SearchResult<Relationship>>, GraphError> {
+    ) -> Result<SearchPage<Relationship>, GraphError> {
         let start = std::time::Instant::now();
-        let limit = params.limit.unwrap_or(20) as i64;
+        let limit = ReadLimit::new(
+            params.limit,
+            DEFAULT_SEARCH_LIMIT,
+            self.reads.max_search_results,
+            "relationship.search",
+        );
+        let position = SearchCursor::score_position(params.cursor.as_ref())?;
+        let seen = position.map_or(0, |(_, _, seen)| seen);
 
         let embedding = query_embedding.ok_or_else(|| {
             GraphError::Query("Relationship search requires a query embedding".into())
         })?;
         let emb_f64: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
 
-        let cypher =
-            "CALL db.index.vector.queryRelationships('relates_to_embedding', $limit, $embedding) \
-             YIELD relationship AS r, score \
+        let mut where_parts = Vec::new();
+        if params.created_before.is_some() {
+            where_parts.push("r.created_at <= $created_before".to_string());
+        }
+        push_score_keyset(position, "r.id", &mut where_parts);
+        let where_str = if where_parts.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", where_parts.join(" AND "))
+        };
+
+        let cypher = format!(
+            "CALL db.index.vector.queryRelationships('relates_to_embedding', $k, $embedding) \
+             YIELD relationship AS r, score{} \
              MATCH (s:Entity)-[r]->(t:Entity) \
              RETURN r, s.id AS source_id, t.id AS target_id, score \
-             ORDER BY score DESC";
+             ORDER BY score DESC, r.id LIMIT $fetch",
+            where_str
+        );
 
-        let q = query(cypher)
-            .param("limit", limit)
+        let q = query(&cypher)
+            .param("k", (seen + limit.fetch()) as i64)
+            .param("fetch", limit.fetch() as i64)
             .param("embedding", emb_f64);
+        let mut q = bind_score_position(q, position);
+        if let Some(ref before) = params.created_before {
+            q = q.param("created_before", format_datetime(before));
+        }
 
         let mut result = self
             .graph
@@ -526,11 +782,133 @@ impl super::GraphClient {
             });
         }
 
+        let size = limit.finish(&mut results, |r| r.item.approx_bytes());
+
         metrics::histogram!("graph.search.latency", "mode" => "semantic", "target" => "relationship")
             .record(start.elapsed().as_secs_f64());
+        record_read_latency("relationship.search", start);
         metrics::histogram!("graph.search.results", "target" => "relationship")
             .record(results.len() as f64);
 
-        Ok(results)
+        let next_cursor = next_score_cursor(&results, &size, seen, |r| r.id.to_string());
+        Ok(SearchPage {
+            results,
+            next_cursor,
+            size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(score: f64, id: &str) -> SearchResult<String> {
+        SearchResult {
+            item: id.to_string(),
+            score,
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursors = [
+            SearchCursor::Score {
+                score: 0.8125,
+                id: "3f1c9a2e-0000-4000-8000-000000000001".into(),
+                seen: 20,
+            },
+            SearchCursor::Ingested {
+                ingested: "2026-10-16T09:30:00.123456789+00:00".into(),
+                id: "3f1c9a2e-0000-4000-8000-000000000002".into(),
+            },
+        ];
+        for cursor in cursors {
+            let encoded = cursor.encode();
+            assert!(encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+            assert_eq!(SearchCursor::decode(&encoded).unwrap(), cursor);
+        }
+
+        assert!(matches!(
+            SearchCursor::decode("not a cursor"),
+            Err(GraphError::InvalidCursor(_))
+        ));
+        assert!(SearchCursor::decode(&URL_SAFE_NO_PAD.encode(b"{\"x\":1}")).is_err());
+    }
+
+    #[test]
+    fn test_cursor_kind_must_match_search() {
+        let ingested = SearchCursor::Ingested {
+            ingested: "2026-10-16T09:30:00+00:00".into(),
+            id: "a".into(),
+        };
+        assert!(SearchCursor::score_position(Some(&ingested)).is_err());
+        assert_eq!(
+            SearchCursor::ingested_position(Some(&ingested)).unwrap(),
+            Some(("2026-10-16T09:30:00+00:00", "a"))
+        );
+
+        let score = SearchCursor::Score {
+            score: 0.5,
+            id: "b".into(),
+            seen: 10,
+        };
+        assert!(SearchCursor::ingested_position(Some(&score)).is_err());
+        assert_eq!(
+            SearchCursor::score_position(Some(&score)).unwrap(),
+            Some((0.5, "b", 10))
+        );
+        assert_eq!(SearchCursor::score_position(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_next_score_cursor_only_when_more_matched() {
+        let page = [result(0.9, "a"), result(0.7, "b")];
+        let size = |truncated| ResultSize {
+            rows: 2,
+            truncated,
+            ..Default::default()
+        };
+        assert_eq!(
+            next_score_cursor(&page, &size(true), 10, |id| id.clone()),
+            Some(SearchCursor::Score {
+                score: 0.7,
+                id: "b".into(),
+                seen: 12,
+            })
+        );
+        // A full page whose sentinel row didn't come back is the last.
+        assert_eq!(
+            next_score_cursor(&page, &size(false), 10, |id| id.clone()),
+            None
+        );
+        assert_eq!(
+            next_score_cursor::<String>(&[], &size(true), 0, |id| id.clone()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_claim_page_tail_limits_before_collecting() {
+        let tail = claim_page_tail("c.ingested_timestamp DESC, c.id");
+        let limit = tail.find("LIMIT $fetch").unwrap();
+        assert!(limit < tail.find("OPTIONAL MATCH").unwrap());
+        assert!(limit < tail.find("collect(").unwrap());
+        assert!(tail.ends_with("ORDER BY c.ingested_timestamp DESC, c.id"));
+    }
+
+    #[test]
+    fn test_score_keyset_breaks_ties_by_id() {
+        let mut where_parts = Vec::new();
+        push_score_keyset(None, "c.id", &mut where_parts);
+        assert!(where_parts.is_empty());
+
+        push_score_keyset(Some((0.5, "b", 10)), "c.id", &mut where_parts);
+        assert_eq!(
+            where_parts,
+            ["(score < $cursor_score OR (score = $cursor_score AND c.id > $cursor_id))"]
+        );
     }
 }
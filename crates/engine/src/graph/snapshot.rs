@@ -0,0 +1,206 @@
+use std::io::{BufRead, Write};
+
+use neo4rs::query;
+use serde::{Deserialize, Serialize};
+
+use autosint_common::types::{Claim, Entity, Relationship};
+
+use super::conversions::{node_to_entity, row_to_claim, row_to_relationship};
+use super::GraphError;
+
+/// One line of a graph snapshot. Entities come first, then relationships, then
+/// claims, so a snapshot can be replayed in order.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SnapshotRecord {
+    Entity(Entity),
+    Relationship(Relationship),
+    Claim(Box<Claim>),
+}
+
+/// Counts written or restored by a snapshot operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SnapshotStats {
+    pub entities: usize,
+    pub relationships: usize,
+    pub claims: usize,
+}
+
+impl super::GraphClient {
+    /// Write every entity, relationship and claim (with embeddings) as JSON lines,
+    /// each list ordered by ID. Meant for test fixtures and small graphs — the
+    /// whole graph is read in three queries.
+    pub async fn export_snapshot<W: Write>(
+        &self,
+        out: &mut W,
+    ) -> Result<SnapshotStats, GraphError> {
+        let mut stats = SnapshotStats::default();
+
+        let mut result = self
+            .graph
+            .execute(query("MATCH (e:Entity) RETURN e ORDER BY e.id"))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("e")
+                .map_err(|e| GraphError::Query(format!("Missing 'e' column: {}", e)))?;
+            write_record(out, &SnapshotRecord::Entity(node_to_entity(&node)?))?;
+            stats.entities += 1;
+        }
+
+        let mut result = self
+            .graph
+            .execute(query(
+                "MATCH (s:Entity)-[r:RELATES_TO]->(t:Entity) \
+                 RETURN r, s.id AS source_id, t.id AS target_id \
+                 ORDER BY r.id",
+            ))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            write_record(
+                out,
+                &SnapshotRecord::Relationship(row_to_relationship(row)?),
+            )?;
+            stats.relationships += 1;
+        }
+
+        let mut result = self
+            .graph
+            .execute(query(
+                "MATCH (source:Entity)-[:PUBLISHED]->(c:Claim) \
+                 OPTIONAL MATCH (c)-[:REFERENCES]->(ref:Entity) \
+                 WITH c, source, ref ORDER BY ref.id \
+                 RETURN c, source.id AS source_id, collect(ref.id) AS ref_ids \
+                 ORDER BY c.id",
+            ))
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            write_record(out, &SnapshotRecord::Claim(Box::new(row_to_claim(row)?)))?;
+            stats.claims += 1;
+        }
+
+        out.flush()
+            .map_err(|e| GraphError::Snapshot(e.to_string()))?;
+
+        tracing::debug!(
+            entities = stats.entities,
+            relationships = stats.relationships,
+            claims = stats.claims,
+            "Graph snapshot exported"
+        );
+        Ok(stats)
+    }
+
+    /// Replay a snapshot written by `export_snapshot` through the regular create
+    /// paths, keeping IDs and embeddings. The graph should be empty.
+    pub async fn import_snapshot<R: BufRead>(&self, input: R) -> Result<SnapshotStats, GraphError> {
+        let mut stats = SnapshotStats::default();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.map_err(|e| GraphError::Snapshot(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: SnapshotRecord = serde_json::from_str(&line)
+                .map_err(|e| GraphError::Snapshot(format!("line {}: {}", i + 1, e)))?;
+
+            match record {
+                SnapshotRecord::Entity(entity) => {
+                    let embedding = entity.embedding.clone();
+                    self.create_entity(&entity, embedding).await?;
+                    stats.entities += 1;
+                }
+                SnapshotRecord::Relationship(rel) => {
+                    let embedding = rel.embedding.clone();
+                    self.create_relationship(&rel, embedding, true).await?;
+                    stats.relationships += 1;
+                }
+                SnapshotRecord::Claim(claim) => {
+                    let embedding = claim.embedding.clone();
+                    self.create_claim(&claim, embedding).await?;
+                    stats.claims += 1;
+                }
+            }
+        }
+
+        tracing::debug!(
+            entities = stats.entities,
+            relationships = stats.relationships,
+            claims = stats.claims,
+            "Graph snapshot imported"
+        );
+        Ok(stats)
+    }
+}
+
+fn write_record<W: Write>(out: &mut W, record: &SnapshotRecord) -> Result<(), GraphError> {
+    serde_json::to_writer(&mut *out, record).map_err(|e| GraphError::Snapshot(e.to_string()))?;
+    out.write_all(b"\n")
+        .map_err(|e| GraphError::Snapshot(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{AttributionDepth, InformationType};
+
+    #[test]
+    fn test_records_round_trip_through_json_lines() {
+        let mut entity = Entity::new("Acme Corp".into(), "organization".into());
+        entity.embedding = Some(vec![0.25, -0.5]);
+        let other = Entity::new("Jane Doe".into(), "person".into());
+        let rel = Relationship::new(entity.id, other.id, "Jane Doe runs Acme.".into());
+        let mut claim = Claim::new(
+            "Acme opened a Berlin office.".into(),
+            chrono::Utc::now(),
+            AttributionDepth::Primary,
+            InformationType::Assertion,
+            other.id,
+        );
+        claim.referenced_entity_ids = vec![entity.id];
+
+        let mut buf = Vec::new();
+        for record in [
+            SnapshotRecord::Entity(entity.clone()),
+            SnapshotRecord::Relationship(rel.clone()),
+            SnapshotRecord::Claim(Box::new(claim.clone())),
+        ] {
+            write_record(&mut buf, &record).unwrap();
+        }
+
+        let lines: Vec<SnapshotRecord> = std::str::from_utf8(&buf)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        match &lines[0] {
+            SnapshotRecord::Entity(e) => {
+                assert_eq!(e.id, entity.id);
+                assert_eq!(e.embedding, Some(vec![0.25, -0.5]));
+            }
+            other => panic!("expected entity, got {:?}", other),
+        }
+        assert!(matches!(&lines[1], SnapshotRecord::Relationship(r) if r.id == rel.id));
+        match &lines[2] {
+            SnapshotRecord::Claim(c) => assert_eq!(c.referenced_entity_ids, vec![entity.id]),
+            other => panic!("expected claim, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! Stub entities that claims keep mentioning, the full entities they may turn
+//! out to be, and stubs nothing mentions any more. What to do with each is
+//! decided by stub resolution (`crate::stubs`).
+
+use chrono::{DateTime, Utc};
+use neo4rs::query;
+
+use autosint_common::types::{identifier_conflicts, Entity};
+use autosint_common::{EntityId, InvestigationId};
+
+use super::conversions::{format_datetime, node_to_entity, parse_entity_id};
+use super::escape_lucene_query;
+use super::{GraphClient, GraphError};
+
+/// Full entities fetched per stub, by name and by embedding.
+const MATCH_CANDIDATES: i64 = 10;
+
+/// A stub and the claims that mention it.
+#[derive(Clone, Debug)]
+pub struct StubCandidate {
+    pub entity: Entity,
+    /// Claims the stub published or is referenced by.
+    pub claim_count: u64,
+    /// Investigation of the most recently ingested of those claims that has
+    /// one.
+    pub investigation_id: Option<InvestigationId>,
+}
+
+/// A full entity a stub matches.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StubMatch {
+    pub entity_id: EntityId,
+    /// `name` or `embedding`.
+    pub matched_by: &'static str,
+    pub similarity: f64,
+}
+
+impl GraphClient {
+    /// Stubs mentioned by at least `min_claims` claims, most-mentioned first.
+    /// With `scope`, only stubs that one of its claims mentions.
+    pub async fn stubs_to_resolve(
+        &self,
+        min_claims: u32,
+        scope: Option<InvestigationId>,
+        limit: u32,
+    ) -> Result<Vec<StubCandidate>, GraphError> {
+        let q = query(
+            "MATCH (e:Entity) \
+             WHERE e.is_stub = true AND NOT coalesce(e.is_seeded, false) \
+               AND ($scope IS NULL OR EXISTS { \
+                 MATCH (e)-[:PUBLISHED|REFERENCES]-(c:Claim {investigation_id: $scope}) \
+               }) \
+             WITH e, COUNT { MATCH (e)-[:PUBLISHED|REFERENCES]-(c:Claim) RETURN DISTINCT c } AS claims \
+             WHERE claims >= $min_claims \
+             OPTIONAL MATCH (e)-[:PUBLISHED|REFERENCES]-(c:Claim) \
+             WHERE c.investigation_id IS NOT NULL \
+             WITH e, claims, c ORDER BY c.ingested_timestamp DESC \
+             WITH e, claims, collect(c.investigation_id)[0] AS investigation_id \
+             RETURN e AS node, claims, investigation_id \
+             ORDER BY claims DESC, e.id \
+             LIMIT $limit",
+        )
+        .param("scope", scope.map(|id| id.to_string()))
+        .param("min_claims", min_claims as i64)
+        .param("limit", limit as i64);
+
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut stubs = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("node")
+                .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
+            let claims: i64 = row
+                .get("claims")
+                .map_err(|e| GraphError::Query(format!("Missing 'claims': {}", e)))?;
+            let investigation_id = row
+                .get::<Option<String>>("investigation_id")
+                .ok()
+                .flatten()
+                .and_then(|s| s.parse::<uuid::Uuid>().ok())
+                .map(InvestigationId::from_uuid);
+            stubs.push(StubCandidate {
+                entity: node_to_entity(&node)?,
+                claim_count: claims.max(0) as u64,
+                investigation_id,
+            });
+        }
+        Ok(stubs)
+    }
+
+    /// The full entity of the stub's kind that best matches it: by name at
+    /// `name_threshold`, else by embedding at `embedding_threshold`. Entities
+    /// whose identifiers conflict with the stub's are never matched.
+    pub async fn find_stub_match(
+        &self,
+        stub: &Entity,
+        name_threshold: f64,
+        embedding_threshold: f64,
+    ) -> Result<Option<StubMatch>, GraphError> {
+        let escaped = escape_lucene_query(&stub.canonical_name);
+        if !escaped.is_empty() {
+            let q = query(
+                "CALL db.index.fulltext.queryNodes('entity_name_fulltext', $name) \
+                 YIELD node, score \
+                 RETURN node \
+                 LIMIT $limit",
+            )
+            .param("name", escaped.as_str())
+            .param("limit", MATCH_CANDIDATES);
+            let best = self
+                .stub_match_candidates(q, stub)
+                .await?
+                .into_iter()
+                .map(|entity| (name_similarity(stub, &entity), entity.id))
+                .filter(|&(score, _)| score >= name_threshold)
+                .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            if let Some((similarity, entity_id)) = best {
+                return Ok(Some(StubMatch {
+                    entity_id,
+                    matched_by: "name",
+                    similarity,
+                }));
+            }
+        }
+
+        let Some(embedding) = &stub.embedding else {
+            return Ok(None);
+        };
+        let embedding: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
+        let q = query(
+            "CALL db.index.vector.queryNodes('entity_embedding', $limit, $embedding) \
+             YIELD node, score \
+             RETURN node, score \
+             ORDER BY score DESC",
+        )
+        .param("embedding", embedding)
+        .param("limit", MATCH_CANDIDATES);
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let score: f64 = row
+                .get("score")
+                .map_err(|e| GraphError::Query(format!("Missing 'score': {}", e)))?;
+            if score < embedding_threshold {
+                break;
+            }
+            let node: neo4rs::Node = row
+                .get("node")
+                .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
+            let entity = node_to_entity(&node)?;
+            if can_absorb(stub, &entity) {
+                return Ok(Some(StubMatch {
+                    entity_id: entity.id,
+                    matched_by: "embedding",
+                    similarity: score,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn stub_match_candidates(
+        &self,
+        q: neo4rs::Query,
+        stub: &Entity,
+    ) -> Result<Vec<Entity>, GraphError> {
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let mut candidates = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let node: neo4rs::Node = row
+                .get("node")
+                .map_err(|e| GraphError::Query(format!("Missing 'node': {}", e)))?;
+            let entity = node_to_entity(&node)?;
+            if can_absorb(stub, &entity) {
+                candidates.push(entity);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Delete up to `limit` stubs created before `cutoff` that no claim
+    /// mentions and no relationship touches. Returns their IDs.
+    pub async fn delete_unreferenced_stubs(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<EntityId>, GraphError> {
+        let _permit = self.writes.acquire("stub.delete").await;
+        let ids = self
+            .collect_ids(
+                query(
+                    "MATCH (e:Entity) \
+                     WHERE e.is_stub = true AND NOT coalesce(e.is_seeded, false) \
+                       AND coalesce(e.created_at, e.last_updated) < $cutoff \
+                       AND NOT EXISTS { MATCH (e)-[:PUBLISHED|REFERENCES]-(:Claim) } \
+                       AND NOT EXISTS { MATCH (e)-[:RELATES_TO]-() } \
+                     WITH e LIMIT $limit \
+                     WITH e, e.id AS id \
+                     DETACH DELETE e \
+                     RETURN id",
+                )
+                .param("cutoff", format_datetime(&cutoff))
+                .param("limit", limit as i64),
+            )
+            .await?;
+        ids.iter().map(|id| parse_entity_id(id)).collect()
+    }
+}
+
+/// Whether `candidate` may absorb `stub`: a different, full entity of the same
+/// kind whose identifiers don't conflict with the stub's.
+fn can_absorb(stub: &Entity, candidate: &Entity) -> bool {
+    candidate.id != stub.id
+        && !candidate.is_stub
+        && candidate.kind.trim().eq_ignore_ascii_case(stub.kind.trim())
+        && identifier_conflicts(&stub.identifiers, &candidate.identifiers).is_empty()
+}
+
+/// Best Jaro-Winkler similarity between any of the stub's names and any of
+/// the candidate's (canonical name and aliases, case-insensitive).
+fn name_similarity(stub: &Entity, candidate: &Entity) -> f64 {
+    let names = |e: &Entity| -> Vec<String> {
+        std::iter::once(&e.canonical_name)
+            .chain(e.aliases.iter())
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+            .collect()
+    };
+    let theirs = names(candidate);
+    names(stub)
+        .iter()
+        .flat_map(|a| theirs.iter().map(move |b| strsim::jaro_winkler(a, b)))
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_full_entities_of_the_same_kind_absorb_stubs() {
+        let mut stub = Entity::new("Gulf Star Shipping".into(), "organization".into());
+        stub.is_stub = true;
+
+        let mut full = Entity::new("Gulf Star Shipping LLC".into(), "Organization".into());
+        full.aliases = vec!["gulf star shipping".into()];
+        assert!(can_absorb(&stub, &full));
+        assert_eq!(name_similarity(&stub, &full), 1.0);
+
+        let mut other_stub = full.clone();
+        other_stub.id = EntityId::new();
+        other_stub.is_stub = true;
+        assert!(!can_absorb(&stub, &other_stub));
+
+        let vessel = Entity::new("Gulf Star Shipping".into(), "vessel".into());
+        assert!(!can_absorb(&stub, &vessel));
+        assert!(!can_absorb(&stub, &stub.clone()));
+
+        let unrelated = Entity::new("Lloyd's List".into(), "organization".into());
+        assert!(name_similarity(&stub, &unrelated) < 0.8);
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use neo4rs::query;
+use tokio::task::JoinHandle;
+
+use autosint_common::config::RelationshipWeightConfig;
+use autosint_common::{ClaimId, EntityId, RelationshipId};
+
+use super::conversions::{parse_datetime, parse_entity_id, parse_relationship_id};
+use super::relationships::RelationshipUpdate;
+use super::{GraphClient, GraphError};
+use crate::maintenance::ReadOnlyMode;
+use crate::store::StoreClient;
+use crate::supervisor;
+
+/// Recalibrated weights closer than this to the stored weight are not written,
+/// so slow recency decay doesn't rewrite every relationship on every run.
+const MIN_WEIGHT_CHANGE: f64 = 0.01;
+
+/// One claim's support for a relationship, as used by the weight function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupportingClaim {
+    pub source_entity_id: EntityId,
+    pub published_timestamp: DateTime<Utc>,
+}
+
+/// A relationship weight written by recalibration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightChange {
+    pub relationship_id: RelationshipId,
+    pub previous_weight: Option<f64>,
+    pub weight: f64,
+    /// Distinct sources among the supporting claims.
+    pub sources: usize,
+    pub claims: usize,
+}
+
+/// Weight in [0, 1) from a relationship's supporting claims. `None` when there
+/// are none (the LLM-assigned weight is left alone).
+///
+/// Each distinct source contributes once, discounted by the age of its most
+/// recent supporting claim (halving every `recency_half_life_days`). Repeated
+/// claims from one source therefore add nothing. The summed support `s` maps
+/// to `s / (s + half_weight_sources)`.
+pub fn recalibrated_weight(
+    support: &[SupportingClaim],
+    now: DateTime<Utc>,
+    config: &RelationshipWeightConfig,
+) -> Option<f64> {
+    if support.is_empty() {
+        return None;
+    }
+
+    let mut latest: HashMap<EntityId, DateTime<Utc>> = HashMap::new();
+    for claim in support {
+        latest
+            .entry(claim.source_entity_id)
+            .and_modify(|ts| *ts = (*ts).max(claim.published_timestamp))
+            .or_insert(claim.published_timestamp);
+    }
+
+    let half_life = config.recency_half_life_days.max(f64::EPSILON);
+    let effective: f64 = latest
+        .values()
+        .map(|published| {
+            // Future-dated claims count as fresh.
+            let age_days = (now - *published).num_seconds().max(0) as f64 / 86_400.0;
+            0.5f64.powf(age_days / half_life)
+        })
+        .sum();
+
+    Some(effective / (effective + config.half_weight_sources.max(f64::EPSILON)))
+}
+
+/// Support gathered for one relationship.
+struct RelationshipSupport {
+    id: RelationshipId,
+    weight: Option<f64>,
+    claims: Vec<SupportingClaim>,
+}
+
+impl GraphClient {
+    /// Record that an existing claim is evidence for `relationship_ids`.
+    /// IDs already recorded are not duplicated.
+    pub async fn add_relationship_support(
+        &self,
+        claim_id: ClaimId,
+        relationship_ids: &[RelationshipId],
+    ) -> Result<(), GraphError> {
+        let ids: Vec<String> = relationship_ids.iter().map(|id| id.to_string()).collect();
+        let q = query(
+            "MATCH (c:Claim {id: $claim_id}) \
+             WITH c, coalesce(c.supports_relationship_ids, []) AS existing \
+             SET c.supports_relationship_ids = existing + [rid IN $ids WHERE NOT rid IN existing] \
+             RETURN c.id AS id",
+        )
+        .param("claim_id", claim_id.to_string())
+        .param("ids", ids);
+
+        let _permit = self.writes.acquire("claim.add_relationship_support").await;
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+            .ok_or_else(|| GraphError::NotFound(format!("Claim {}", claim_id)))?;
+        Ok(())
+    }
+
+    /// The subset of `ids` that exist as RELATES_TO edges.
+    pub async fn existing_relationship_ids(
+        &self,
+        ids: &[RelationshipId],
+    ) -> Result<Vec<RelationshipId>, GraphError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let q = query(
+            "UNWIND $ids AS rid \
+             MATCH (:Entity)-[r:RELATES_TO {id: rid}]->(:Entity) \
+             RETURN DISTINCT r.id AS id",
+        )
+        .param(
+            "ids",
+            ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+        );
+
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+        let mut found = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let id: String = row
+                .get("id")
+                .map_err(|e| GraphError::Query(format!("Missing 'id' column: {}", e)))?;
+            found.push(parse_relationship_id(&id)?);
+        }
+        Ok(found)
+    }
+
+    /// Recompute the weight of every supported relationship (or only those
+    /// touching `entity`) and write the ones that moved through
+    /// `update_relationship`, keeping the old value in `previous_weight`.
+    pub async fn recalculate_relationship_weights(
+        &self,
+        entity: Option<EntityId>,
+        config: &RelationshipWeightConfig,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<WeightChange>, GraphError> {
+        let start = std::time::Instant::now();
+        let mut changes = Vec::new();
+
+        for support in self.relationship_support(entity).await? {
+            let Some(weight) = recalibrated_weight(&support.claims, now, config) else {
+                continue;
+            };
+            if support
+                .weight
+                .is_some_and(|old| (old - weight).abs() < MIN_WEIGHT_CHANGE)
+            {
+                continue;
+            }
+
+            let update = RelationshipUpdate {
+                description: None,
+                weight: Some(weight),
+                confidence: None,
+                bidirectional: None,
+                timestamp: None,
+                weight_recalibrated_at: Some(now),
+            };
+            self.update_relationship(support.id, &update, None).await?;
+
+            let sources = support
+                .claims
+                .iter()
+                .map(|c| c.source_entity_id)
+                .collect::<std::collections::HashSet<_>>()
+                .len();
+            changes.push(WeightChange {
+                relationship_id: support.id,
+                previous_weight: support.weight,
+                weight,
+                sources,
+                claims: support.claims.len(),
+            });
+        }
+
+        metrics::histogram!("graph.relationship.recalculate_weights.latency")
+            .record(start.elapsed().as_secs_f64());
+
+        Ok(changes)
+    }
+
+    /// Supporting claims per relationship, for relationships that have any.
+    async fn relationship_support(
+        &self,
+        entity: Option<EntityId>,
+    ) -> Result<Vec<RelationshipSupport>, GraphError> {
+        let q = query(
+            "MATCH (src:Entity)-[:PUBLISHED]->(c:Claim) \
+             WHERE size(coalesce(c.supports_relationship_ids, [])) > 0 \
+             UNWIND c.supports_relationship_ids AS rid \
+             MATCH (a:Entity)-[r:RELATES_TO {id: rid}]->(b:Entity) \
+             WHERE $entity_id IS NULL OR a.id = $entity_id OR b.id = $entity_id \
+             RETURN r.id AS id, r.weight AS weight, \
+                    collect(src.id) AS sources, collect(c.published_timestamp) AS published",
+        )
+        .param("entity_id", entity.map(|id| id.to_string()));
+
+        let mut result = self
+            .graph
+            .execute(q)
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?;
+
+        let mut support = Vec::new();
+        while let Some(row) = result
+            .next()
+            .await
+            .map_err(|e| GraphError::Query(e.to_string()))?
+        {
+            let id: String = row
+                .get("id")
+                .map_err(|e| GraphError::Query(format!("Missing 'id' column: {}", e)))?;
+            let weight: Option<f64> = row.get("weight").ok();
+            let sources: Vec<String> = row
+                .get("sources")
+                .map_err(|e| GraphError::Query(format!("Missing 'sources' column: {}", e)))?;
+            let published: Vec<String> = row
+                .get("published")
+                .map_err(|e| GraphError::Query(format!("Missing 'published' column: {}", e)))?;
+
+            let claims = sources
+                .iter()
+                .zip(&published)
+                .map(|(source, ts)| {
+                    Ok(SupportingClaim {
+                        source_entity_id: parse_entity_id(source)?,
+                        published_timestamp: parse_datetime(ts)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, GraphError>>()?;
+
+            support.push(RelationshipSupport {
+                id: parse_relationship_id(&id)?,
+                weight,
+                claims,
+            });
+        }
+        Ok(support)
+    }
+}
+
+/// Spawn a background task that periodically recalibrates relationship weights.
+/// Runs hold the `relationship_weights` singleton lock in `store`.
+pub fn spawn_weight_recalibration_task(
+    graph: Arc<GraphClient>,
+    store: Arc<StoreClient>,
+    config: RelationshipWeightConfig,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.interval_minutes as u64 * 60);
+
+    supervisor::spawn_supervised_restarting("relationship_weights", move || {
+        let graph = Arc::clone(&graph);
+        let store = Arc::clone(&store);
+        let config = config.clone();
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                interval_minutes = config.interval_minutes,
+                recency_half_life_days = config.recency_half_life_days,
+                "Relationship weight recalibration task started"
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                if read_only.is_enabled() {
+                    tracing::debug!(
+                        "Engine is read-only, skipping relationship weight recalibration"
+                    );
+                    continue;
+                }
+
+                let run = async {
+                    match graph
+                        .recalculate_relationship_weights(None, &config, Utc::now())
+                        .await
+                    {
+                        Ok(changes) => {
+                            metrics::counter!("graph.relationship.weights_recalibrated")
+                                .increment(changes.len() as u64);
+                            tracing::info!(
+                                updated = changes.len(),
+                                "Relationship weights recalibrated"
+                            );
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "Relationship weight recalibration failed");
+                        }
+                    }
+                };
+                if let Err(e) = store.try_run_exclusive("relationship_weights", run).await {
+                    tracing::error!(error = %e, "Failed to take the relationship weights lock");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RelationshipWeightConfig {
+        RelationshipWeightConfig {
+            recency_half_life_days: 100.0,
+            half_weight_sources: 2.0,
+            ..Default::default()
+        }
+    }
+
+    fn claim(source: EntityId, age_days: i64, now: DateTime<Utc>) -> SupportingClaim {
+        SupportingClaim {
+            source_entity_id: source,
+            published_timestamp: now - chrono::Duration::days(age_days),
+        }
+    }
+
+    #[test]
+    fn test_no_support_leaves_weight_alone() {
+        assert_eq!(recalibrated_weight(&[], Utc::now(), &config()), None);
+    }
+
+    #[test]
+    fn test_weight_grows_with_distinct_sources_and_stays_bounded() {
+        let now = Utc::now();
+        let weights: Vec<f64> = [1, 2, 6, 30]
+            .iter()
+            .map(|&n| {
+                let support: Vec<_> = (0..n).map(|_| claim(EntityId::new(), 0, now)).collect();
+                recalibrated_weight(&support, now, &config()).unwrap()
+            })
+            .collect();
+
+        assert!((weights[0] - 1.0 / 3.0).abs() < 1e-9);
+        assert!((weights[1] - 0.5).abs() < 1e-9);
+        assert!((weights[2] - 0.75).abs() < 1e-9);
+        assert!(weights.windows(2).all(|w| w[0] < w[1]));
+        assert!(weights[3] < 1.0);
+    }
+
+    #[test]
+    fn test_repeated_claims_from_one_source_count_once() {
+        let now = Utc::now();
+        let source = EntityId::new();
+        let once = recalibrated_weight(&[claim(source, 0, now)], now, &config()).unwrap();
+        let thirty: Vec<_> = (0..30).map(|_| claim(source, 0, now)).collect();
+        assert_eq!(recalibrated_weight(&thirty, now, &config()).unwrap(), once);
+    }
+
+    #[test]
+    fn test_old_support_counts_less() {
+        let now = Utc::now();
+        let source = EntityId::new();
+        let fresh = recalibrated_weight(&[claim(source, 0, now)], now, &config()).unwrap();
+        let stale = recalibrated_weight(&[claim(source, 100, now)], now, &config()).unwrap();
+        // One half-life old: half the support.
+        assert!((stale - 0.5 / 2.5).abs() < 1e-9);
+        assert!(stale < fresh);
+
+        // A source's most recent claim decides its contribution.
+        let both = recalibrated_weight(
+            &[claim(source, 100, now), claim(source, 0, now)],
+            now,
+            &config(),
+        )
+        .unwrap();
+        assert_eq!(both, fresh);
+    }
+
+    #[test]
+    fn test_future_dated_claims_count_as_fresh() {
+        let now = Utc::now();
+        let source = EntityId::new();
+        let fresh = recalibrated_weight(&[claim(source, 0, now)], now, &config()).unwrap();
+        let future = recalibrated_weight(&[claim(source, -10, now)], now, &config()).unwrap();
+        assert_eq!(future, fresh);
+    }
+}
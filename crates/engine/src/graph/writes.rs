@@ -0,0 +1,324 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{oneshot, Notify, Semaphore, SemaphorePermit};
+
+use autosint_common::config::WriteCoalesceConfig;
+use autosint_common::types::{Claim, Entity, Relationship};
+
+use super::GraphError;
+
+/// Bounds the write transactions GraphClient has in flight. Every write takes
+/// a permit for the duration of its transaction.
+pub(crate) struct WriteGovernor {
+    permits: Semaphore,
+    in_flight: AtomicUsize,
+    peak_in_flight: AtomicUsize,
+    transactions: AtomicU64,
+}
+
+/// Write counters since the client was created, e.g. for asserting the
+/// governor's bound in tests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WriteStats {
+    /// Most write transactions ever in flight at once.
+    pub peak_in_flight: usize,
+    /// Write transactions run, counting a coalesced batch once.
+    pub transactions: u64,
+}
+
+impl WriteGovernor {
+    pub(crate) fn new(max_concurrent_writes: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max_concurrent_writes.max(1)),
+            in_flight: AtomicUsize::new(0),
+            peak_in_flight: AtomicUsize::new(0),
+            transactions: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait for a write slot. The slot is held until the permit is dropped.
+    pub(crate) async fn acquire(&self, op: &'static str) -> WritePermit<'_> {
+        let waiting = Instant::now();
+        let permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("write semaphore is never closed");
+        metrics::histogram!("graph.write.wait", "op" => op).record(waiting.elapsed().as_secs_f64());
+
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        self.transactions.fetch_add(1, Ordering::Relaxed);
+        metrics::gauge!("graph.writes.in_flight").set(in_flight as f64);
+
+        WritePermit {
+            governor: self,
+            op,
+            acquired: Instant::now(),
+            _permit: permit,
+        }
+    }
+
+    pub(crate) fn stats(&self) -> WriteStats {
+        WriteStats {
+            peak_in_flight: self.peak_in_flight.load(Ordering::SeqCst),
+            transactions: self.transactions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A held write slot. Dropping it records the write's latency and frees the
+/// slot.
+pub(crate) struct WritePermit<'a> {
+    governor: &'a WriteGovernor,
+    op: &'static str,
+    acquired: Instant,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl Drop for WritePermit<'_> {
+    fn drop(&mut self) {
+        // Runs before the semaphore permit is released, so `in_flight` never
+        // exceeds the bound.
+        let in_flight = self.governor.in_flight.fetch_sub(1, Ordering::SeqCst) - 1;
+        metrics::gauge!("graph.writes.in_flight").set(in_flight as f64);
+        metrics::histogram!("graph.write.latency", "op" => self.op)
+            .record(self.acquired.elapsed().as_secs_f64());
+    }
+}
+
+/// Record a read's latency alongside its per-operation histogram.
+pub(crate) fn record_read_latency(op: &'static str, start: Instant) {
+    metrics::histogram!("graph.read.latency", "op" => op).record(start.elapsed().as_secs_f64());
+}
+
+type Waiter<T, R> = (T, oneshot::Sender<Result<R, GraphError>>);
+
+/// Gathers concurrent creates of one kind into batches. The first item into
+/// an empty batch starts a flush task that waits out the window (or until the
+/// batch is full), then writes everything gathered so far in one call.
+pub(crate) struct Coalescer<T, R> {
+    inner: Arc<CoalescerInner<T, R>>,
+}
+
+struct CoalescerInner<T, R> {
+    window: Duration,
+    max_items: usize,
+    pending: Mutex<Vec<Waiter<T, R>>>,
+    full: Notify,
+}
+
+impl<T, R> Coalescer<T, R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+{
+    pub(crate) fn new(config: &WriteCoalesceConfig) -> Self {
+        Self {
+            inner: Arc::new(CoalescerInner {
+                window: Duration::from_millis(config.window_ms),
+                max_items: config.max_items.max(1),
+                pending: Mutex::new(Vec::new()),
+                full: Notify::new(),
+            }),
+        }
+    }
+
+    /// Add `item` to the current batch and wait for its result. If this item
+    /// starts a new batch, `flush` writes it (and any batches queued behind
+    /// it); it must return one result per item, in order.
+    pub(crate) async fn submit<F, Fut>(&self, item: T, flush: F) -> Result<R, GraphError>
+    where
+        F: Fn(Vec<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = Vec<Result<R, GraphError>>> + Send,
+    {
+        let (tx, rx) = oneshot::channel();
+        let starts_batch = {
+            let mut pending = self.inner.pending.lock().expect("coalescer lock poisoned");
+            pending.push((item, tx));
+            if pending.len() >= self.inner.max_items {
+                self.inner.full.notify_one();
+            }
+            pending.len() == 1
+        };
+
+        if starts_batch {
+            // Flushed from its own task so a cancelled caller can't strand the
+            // rest of the batch.
+            let inner = Arc::clone(&self.inner);
+            tokio::spawn(async move {
+                loop {
+                    if inner.pending_len() < inner.max_items {
+                        tokio::select! {
+                            _ = tokio::time::sleep(inner.window) => {}
+                            _ = inner.full.notified() => {}
+                        }
+                    }
+                    let batch = inner.take_batch();
+                    if batch.is_empty() {
+                        break;
+                    }
+                    let (items, waiters): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+                    metrics::histogram!("graph.write.coalesced_batch_size")
+                        .record(items.len() as f64);
+
+                    let results = flush(items).await;
+                    for (waiter, result) in waiters.into_iter().zip(results) {
+                        let _ = waiter.send(result);
+                    }
+
+                    // Items that arrived while this batch was written have no
+                    // other task to flush them.
+                    if inner.pending_len() == 0 {
+                        break;
+                    }
+                }
+            });
+        }
+
+        rx.await.unwrap_or_else(|_| {
+            Err(GraphError::Query(
+                "Coalesced write batch was dropped".into(),
+            ))
+        })
+    }
+}
+
+impl<T, R> CoalescerInner<T, R> {
+    fn pending_len(&self) -> usize {
+        self.pending.lock().expect("coalescer lock poisoned").len()
+    }
+
+    /// Up to `max_items` of the oldest waiting items.
+    fn take_batch(&self) -> Vec<Waiter<T, R>> {
+        let mut pending = self.pending.lock().expect("coalescer lock poisoned");
+        let n = pending.len().min(self.max_items);
+        pending.drain(..n).collect()
+    }
+}
+
+/// One coalescer per create kind. Items carry their embeddings.
+pub(crate) struct CreateCoalescers {
+    pub(crate) entities: Coalescer<(Entity, Option<Vec<f32>>), Entity>,
+    /// Callers read the claim back themselves, as an individual create does.
+    pub(crate) claims: Coalescer<(Claim, Option<Vec<f32>>), ()>,
+    pub(crate) relationships: Coalescer<(Relationship, Option<Vec<f32>>), Relationship>,
+}
+
+impl CreateCoalescers {
+    pub(crate) fn new(config: &WriteCoalesceConfig) -> Self {
+        Self {
+            entities: Coalescer::new(config),
+            claims: Coalescer::new(config),
+            relationships: Coalescer::new(config),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Batches = Arc<Mutex<Vec<usize>>>;
+
+    fn coalescer(window_ms: u64, max_items: usize) -> Arc<Coalescer<u32, u32>> {
+        Arc::new(Coalescer::new(&WriteCoalesceConfig {
+            enabled: true,
+            window_ms,
+            max_items,
+        }))
+    }
+
+    /// Doubles each item, except 7, and records the batch size.
+    async fn double(items: Vec<u32>, batches: Batches) -> Vec<Result<u32, GraphError>> {
+        batches.lock().unwrap().push(items.len());
+        items
+            .into_iter()
+            .map(|n| match n {
+                7 => Err(GraphError::NotFound(n.to_string())),
+                _ => Ok(n * 2),
+            })
+            .collect()
+    }
+
+    async fn submit_all(
+        coalescer: &Arc<Coalescer<u32, u32>>,
+        batches: &Batches,
+        items: std::ops::Range<u32>,
+    ) -> Vec<Result<u32, GraphError>> {
+        let handles: Vec<_> = items
+            .map(|n| {
+                let coalescer = Arc::clone(coalescer);
+                let batches = Arc::clone(batches);
+                tokio::spawn(async move {
+                    coalescer
+                        .submit(n, move |items| double(items, Arc::clone(&batches)))
+                        .await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn test_coalescer_maps_results_to_waiters() {
+        let coalescer = coalescer(50, 100);
+        let batches = Batches::default();
+
+        let results = submit_all(&coalescer, &batches, 0..10).await;
+
+        for (n, result) in (0..10u32).zip(results) {
+            match n {
+                7 => assert!(matches!(result, Err(GraphError::NotFound(_)))),
+                _ => assert_eq!(result.unwrap(), n * 2),
+            }
+        }
+        assert_eq!(*batches.lock().unwrap(), [10]);
+    }
+
+    #[tokio::test]
+    async fn test_coalescer_flushes_when_full() {
+        // A window long enough that only a full batch can flush in time.
+        let coalescer = coalescer(60_000, 4);
+        let batches = Batches::default();
+
+        let results = tokio::time::timeout(
+            Duration::from_secs(5),
+            submit_all(&coalescer, &batches, 0..8),
+        )
+        .await
+        .expect("full batches flush without waiting out the window");
+
+        assert_eq!(results.len(), 8);
+        assert_eq!(*batches.lock().unwrap(), [4, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_governor_bounds_in_flight() {
+        let governor = Arc::new(WriteGovernor::new(3));
+
+        let handles: Vec<_> = (0..20)
+            .map(|_| {
+                let governor = Arc::clone(&governor);
+                tokio::spawn(async move {
+                    let _permit = governor.acquire("test").await;
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        let stats = governor.stats();
+        assert_eq!(stats.peak_in_flight, 3);
+        assert_eq!(stats.transactions, 20);
+    }
+}
@@ -0,0 +1,388 @@
+//! GET /metrics/federate: the engine's metrics plus the fetch and geo
+//! services', for setups where only the engine is reachable from Prometheus.
+//!
+//! Each sibling is pulled with a short timeout; one that is down or slow is
+//! left out (with a comment line saying so) rather than failing the scrape.
+//! Expositions are merged by metric family so each `# HELP`/`# TYPE` appears
+//! once, and sibling samples without a `service` label get one.
+
+use std::time::Duration;
+
+use autosint_common::http_client::{self, Destination};
+
+/// A service whose /metrics the engine re-serves.
+#[derive(Clone, Debug)]
+pub struct Sibling {
+    /// Value of the `service` label stamped on its samples.
+    pub service: String,
+    /// Base URL; `/metrics` is appended.
+    pub base_url: String,
+    /// Bearer token its /metrics requires.
+    pub token: Option<String>,
+}
+
+pub struct Federation {
+    http: reqwest::Client,
+    siblings: Vec<Sibling>,
+    timeout: Duration,
+}
+
+impl Federation {
+    pub fn new(http: reqwest::Client, siblings: Vec<Sibling>, timeout: Duration) -> Self {
+        Self {
+            http,
+            siblings,
+            timeout,
+        }
+    }
+
+    /// The fetch and geo services at FETCH_BASE_URL and GEO_BASE_URL, with
+    /// FETCH_METRICS_TOKEN and GEO_METRICS_TOKEN, pulled through the shared
+    /// internal client.
+    pub fn from_env(fetch_base_url: &str, timeout: Duration) -> Self {
+        let token = |name: &str| std::env::var(name).ok().filter(|t| !t.trim().is_empty());
+        let geo_base_url =
+            std::env::var("GEO_BASE_URL").unwrap_or_else(|_| "http://localhost:8082".into());
+        Self::new(
+            http_client::shared(Destination::Internal),
+            vec![
+                Sibling {
+                    service: "fetch".into(),
+                    base_url: fetch_base_url.into(),
+                    token: token("FETCH_METRICS_TOKEN"),
+                },
+                Sibling {
+                    service: "geo".into(),
+                    base_url: geo_base_url,
+                    token: token("GEO_METRICS_TOKEN"),
+                },
+            ],
+            timeout,
+        )
+    }
+
+    /// `local` (the engine's own exposition) merged with every sibling that
+    /// answered.
+    pub async fn render(&self, local: String) -> String {
+        // Pull concurrently so a slow sibling costs one timeout, not one each.
+        let pulls: Vec<_> = self
+            .siblings
+            .iter()
+            .map(|sibling| tokio::spawn(pull(self.http.clone(), sibling.clone(), self.timeout)))
+            .collect();
+        let mut results = Vec::with_capacity(pulls.len());
+        for pull in pulls {
+            results.push(pull.await.unwrap_or_else(|e| Err(e.to_string())));
+        }
+
+        let mut notes = Vec::new();
+        let mut sources = vec![Exposition {
+            service: None,
+            text: local,
+        }];
+        for (sibling, result) in self.siblings.iter().zip(results) {
+            match result {
+                Ok(text) => sources.push(Exposition {
+                    service: Some(&sibling.service),
+                    text,
+                }),
+                Err(e) => {
+                    tracing::warn!(service = %sibling.service, error = %e, "Metrics federation pull failed");
+                    metrics::counter!("metrics.federate.failures", "service" => sibling.service.clone())
+                        .increment(1);
+                    notes.push(format!("# {} metrics unavailable: {}", sibling.service, e));
+                }
+            }
+        }
+
+        let mut out = notes.join("\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&merge(&sources));
+        out
+    }
+}
+
+async fn pull(
+    http: reqwest::Client,
+    sibling: Sibling,
+    timeout: Duration,
+) -> Result<String, String> {
+    let url = format!("{}/metrics", sibling.base_url.trim_end_matches('/'));
+    let mut request = http.get(&url).timeout(timeout);
+    if let Some(token) = &sibling.token {
+        request = request.bearer_auth(token);
+    }
+    let response = http_client::send(Destination::Internal, request)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    response.text().await.map_err(|e| e.to_string())
+}
+
+/// One service's exposition; `service` is the label to stamp on samples that
+/// lack one (None for the engine's own, already labeled).
+struct Exposition<'a> {
+    service: Option<&'a str>,
+    text: String,
+}
+
+/// A metric family's lines in output order.
+#[derive(Default)]
+struct Family {
+    help: Option<String>,
+    kind: Option<String>,
+    samples: Vec<String>,
+}
+
+/// Merge expositions by family: families keep first-seen order, HELP and TYPE
+/// come from the first exposition that has them, samples from all.
+fn merge(sources: &[Exposition<'_>]) -> String {
+    let mut order: Vec<String> = Vec::new();
+    let mut families: std::collections::HashMap<String, Family> = std::collections::HashMap::new();
+
+    for source in sources {
+        // The family named by the last TYPE line, which owns the
+        // `_bucket`/`_sum`/`_count` samples that follow it.
+        let mut current: Option<String> = None;
+        for line in source.text.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("# ") {
+                let mut parts = rest.splitn(3, ' ');
+                let (Some(keyword @ ("HELP" | "TYPE")), Some(name)) = (parts.next(), parts.next())
+                else {
+                    continue;
+                };
+                let family = entry(&mut order, &mut families, name);
+                let slot = if keyword == "HELP" {
+                    &mut family.help
+                } else {
+                    current = Some(name.to_string());
+                    &mut family.kind
+                };
+                if slot.is_none() {
+                    *slot = Some(line.to_string());
+                }
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let name = sample_name(line);
+            let owner = match &current {
+                Some(family) if owned_by(name, family) => family.clone(),
+                _ => name.to_string(),
+            };
+            let sample = match source.service {
+                Some(service) => with_service_label(line, service),
+                None => line.to_string(),
+            };
+            entry(&mut order, &mut families, &owner)
+                .samples
+                .push(sample);
+        }
+    }
+
+    let mut out = String::new();
+    for name in &order {
+        let family = &families[name];
+        for line in family
+            .help
+            .iter()
+            .chain(family.kind.iter())
+            .chain(family.samples.iter())
+        {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn entry<'a>(
+    order: &mut Vec<String>,
+    families: &'a mut std::collections::HashMap<String, Family>,
+    name: &str,
+) -> &'a mut Family {
+    if !families.contains_key(name) {
+        order.push(name.to_string());
+    }
+    families.entry(name.to_string()).or_default()
+}
+
+/// Whether sample `name` belongs to `family`: itself, or a histogram or
+/// summary's `_bucket`, `_sum` or `_count`.
+fn owned_by(name: &str, family: &str) -> bool {
+    name.strip_prefix(family)
+        .is_some_and(|suffix| matches!(suffix, "" | "_bucket" | "_sum" | "_count"))
+}
+
+fn sample_name(line: &str) -> &str {
+    let end = line.find(['{', ' ']).unwrap_or(line.len());
+    &line[..end]
+}
+
+/// Add `service="<service>"` to a sample line that has no `service` label.
+fn with_service_label(line: &str, service: &str) -> String {
+    let name = sample_name(line);
+    let rest = &line[name.len()..];
+    let label = format!("service=\"{}\"", service);
+    match rest.strip_prefix('{') {
+        Some(labels) => {
+            if labels.starts_with("service=\"") || labels.contains(",service=\"") {
+                line.to_string()
+            } else if labels.starts_with('}') {
+                format!("{}{{{}{}", name, label, labels)
+            } else {
+                format!("{}{{{},{}", name, label, labels)
+            }
+        }
+        None => format!("{}{{{}}}{}", name, label, rest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::routing::get;
+    use axum::Router;
+
+    const ENGINE: &str = "\
+# TYPE http_requests counter
+http_requests{service=\"engine\",route=\"/health\"} 3
+
+# TYPE queue_depth gauge
+queue_depth{service=\"engine\"} 7
+";
+
+    const FETCH: &str = "\
+# HELP http_requests Requests served.
+# TYPE http_requests counter
+http_requests{service=\"fetch\",route=\"/fetch\"} 11
+
+# TYPE fetch_duration histogram
+fetch_duration_bucket{le=\"0.5\"} 4
+fetch_duration_bucket{le=\"+Inf\"} 5
+fetch_duration_sum 2.5
+fetch_duration_count 5
+";
+
+    #[test]
+    fn test_merge_by_family() {
+        let merged = merge(&[
+            Exposition {
+                service: None,
+                text: ENGINE.into(),
+            },
+            Exposition {
+                service: Some("fetch"),
+                text: FETCH.into(),
+            },
+        ]);
+        assert_eq!(
+            merged,
+            "\
+# HELP http_requests Requests served.
+# TYPE http_requests counter
+http_requests{service=\"engine\",route=\"/health\"} 3
+http_requests{service=\"fetch\",route=\"/fetch\"} 11
+
+# TYPE queue_depth gauge
+queue_depth{service=\"engine\"} 7
+
+# TYPE fetch_duration histogram
+fetch_duration_bucket{service=\"fetch\",le=\"0.5\"} 4
+fetch_duration_bucket{service=\"fetch\",le=\"+Inf\"} 5
+fetch_duration_sum{service=\"fetch\"} 2.5
+fetch_duration_count{service=\"fetch\"} 5
+
+"
+        );
+    }
+
+    #[test]
+    fn test_with_service_label() {
+        assert_eq!(with_service_label("up 1", "geo"), "up{service=\"geo\"} 1");
+        assert_eq!(with_service_label("up{} 1", "geo"), "up{service=\"geo\"} 1");
+        assert_eq!(
+            with_service_label("up{a=\"b\"} 1", "geo"),
+            "up{service=\"geo\",a=\"b\"} 1"
+        );
+        assert_eq!(
+            with_service_label("up{a=\"b\",service=\"x\"} 1", "geo"),
+            "up{a=\"b\",service=\"x\"} 1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_with_one_sibling_down() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let fetch_url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new().route(
+            "/metrics",
+            get(|headers: axum::http::HeaderMap| async move {
+                match headers.get("authorization") {
+                    Some(v) if v == "Bearer fetch-scrape" => Ok(FETCH),
+                    _ => Err(axum::http::StatusCode::UNAUTHORIZED),
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // Nothing listens on geo's port.
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let geo_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let federation = Federation::new(
+            reqwest::Client::new(),
+            vec![
+                Sibling {
+                    service: "fetch".into(),
+                    base_url: fetch_url,
+                    token: Some("fetch-scrape".into()),
+                },
+                Sibling {
+                    service: "geo".into(),
+                    base_url: geo_url,
+                    token: None,
+                },
+            ],
+            Duration::from_millis(500),
+        );
+        let rendered = federation.render(ENGINE.into()).await;
+
+        assert!(
+            rendered.starts_with("# geo metrics unavailable: "),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains("queue_depth{service=\"engine\"} 7\n"));
+        assert!(rendered.contains("http_requests{service=\"fetch\",route=\"/fetch\"} 11\n"));
+        assert!(rendered.contains("fetch_duration_count{service=\"fetch\"} 5\n"));
+        assert_eq!(rendered.matches("# TYPE http_requests").count(), 1);
+
+        // With every sibling down the engine's own metrics are still served.
+        let alone = Federation::new(
+            reqwest::Client::new(),
+            vec![Sibling {
+                service: "fetch".into(),
+                base_url: "http://127.0.0.1:9".into(),
+                token: None,
+            }],
+            Duration::from_millis(200),
+        )
+        .render(ENGINE.into())
+        .await;
+        assert!(alone.contains("http_requests{service=\"engine\",route=\"/health\"} 3\n"));
+    }
+}
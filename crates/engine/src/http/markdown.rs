@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use autosint_common::types::{Assessment, AssessmentDiff, Citation, JudgmentChange};
+use autosint_common::ClaimId;
+
+/// Claims named by the assessment's `citations[].claim_id`, whose stored
+/// citations [`assessment_to_markdown`] renders.
+pub fn cited_claim_ids(assessment: &Assessment) -> Vec<ClaimId> {
+    let mut ids: Vec<ClaimId> = Vec::new();
+    for citation in assessment
+        .content
+        .get("citations")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let id = citation
+            .get("claim_id")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse::<uuid::Uuid>().ok())
+            .map(ClaimId::from_uuid);
+        if let Some(id) = id.filter(|id| !ids.contains(id)) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// Render an assessment as Markdown, section by section in the order the
+/// `produce_assessment` schema defines them. Missing fields are skipped;
+/// content that isn't an object is shown as a JSON block. Citations whose
+/// claim is in `claim_citations` render the claim's stored citation (title,
+/// address, locator, access date) rather than the model's restatement of it.
+pub fn assessment_to_markdown(
+    assessment: &Assessment,
+    claim_citations: &HashMap<ClaimId, Citation>,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Assessment\n");
+    let _ = writeln!(out, "- **Investigation:** {}", assessment.investigation_id);
+    let _ = writeln!(
+        out,
+        "- **Confidence:** {}",
+        assessment.confidence.as_db_str()
+    );
+    let _ = writeln!(
+        out,
+        "- **Produced:** {}",
+        assessment.created_at.to_rfc3339()
+    );
+    if !assessment.tags.is_empty() {
+        let _ = writeln!(out, "- **Tags:** {}", assessment.tags.join(", "));
+    }
+    if let Some(correlation_id) = &assessment.correlation_id {
+        let _ = writeln!(out, "- **Correlation ID:** {}", correlation_id);
+    }
+
+    let Some(content) = assessment.content.as_object() else {
+        let pretty = serde_json::to_string_pretty(&assessment.content).unwrap_or_default();
+        let _ = write!(out, "\n```json\n{}\n```\n", pretty);
+        return out;
+    };
+    let text = |value: &Value, key: &str| -> Option<String> {
+        match value.get(key)? {
+            Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    };
+    let items = |key: &str| -> Vec<&Value> {
+        content
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|a| a.iter().collect())
+            .unwrap_or_default()
+    };
+
+    if let Some(body) = text(&assessment.content, "summary") {
+        let _ = write!(out, "\n## Summary\n\n{}\n", body);
+    }
+    if let Some(diff) = &assessment.diff {
+        out.push_str(&diff_to_markdown(diff));
+    }
+    if let Some(body) = text(&assessment.content, "analysis") {
+        let _ = write!(out, "\n## Analysis\n\n{}\n", body);
+    }
+
+    let hypotheses = items("competing_hypotheses");
+    if !hypotheses.is_empty() {
+        let _ = write!(out, "\n## Competing Hypotheses\n");
+        for (i, h) in hypotheses.iter().enumerate() {
+            let name = text(h, "hypothesis").unwrap_or_else(|| "(unnamed)".into());
+            match h.get("probability").and_then(Value::as_f64) {
+                Some(p) => {
+                    let _ = write!(out, "\n### {}. {} ({:.0}%)\n", i + 1, name, p * 100.0);
+                }
+                None => {
+                    let _ = write!(out, "\n### {}. {}\n", i + 1, name);
+                }
+            }
+            for (key, label) in [
+                ("reasoning", "Reasoning"),
+                ("supporting_evidence", "Supporting evidence"),
+                ("weaknesses", "Weaknesses"),
+            ] {
+                if let Some(body) = text(h, key) {
+                    let _ = write!(out, "\n**{}:** {}\n", label, body);
+                }
+            }
+        }
+    }
+
+    if let Some(body) = text(&assessment.content, "confidence_reasoning") {
+        let _ = write!(out, "\n## Confidence Reasoning\n\n{}\n", body);
+    }
+
+    let gaps = items("gaps");
+    if !gaps.is_empty() {
+        let _ = write!(out, "\n## Gaps\n\n");
+        for gap in gaps {
+            let mut line = format!("- {}", text(gap, "description").unwrap_or_default());
+            if let Some(impact) = text(gap, "impact") {
+                let _ = write!(line, " — *Impact:* {}", impact);
+            }
+            if let Some(resolution) = text(gap, "suggested_resolution") {
+                let _ = write!(line, " *Resolution:* {}", resolution);
+            }
+            let _ = writeln!(out, "{}", line);
+        }
+    }
+
+    let indicators = items("forward_indicators");
+    if !indicators.is_empty() {
+        let _ = write!(out, "\n## Forward Indicators\n\n");
+        for indicator in indicators {
+            let mut line = format!("- {}", text(indicator, "description").unwrap_or_default());
+            if let Some(implication) = text(indicator, "trigger_implication") {
+                let _ = write!(line, " — {}", implication);
+            }
+            let _ = writeln!(out, "{}", line);
+        }
+    }
+
+    let sources = items("sources_evaluated");
+    if !sources.is_empty() {
+        let _ = write!(out, "\n## Sources Evaluated\n");
+        for source in sources {
+            let name = text(source, "source_name").unwrap_or_else(|| "(unnamed source)".into());
+            let _ = write!(out, "\n### {}\n\n", name);
+            for (key, label) in [
+                ("structural_profile", "Profile"),
+                ("profile_basis", "Profile basis"),
+                ("claims_used", "Claims used"),
+                ("primary_vs_secondhand", "Attribution"),
+                ("sourcing_chain_notes", "Sourcing chain"),
+            ] {
+                if let Some(body) = text(source, key) {
+                    let _ = writeln!(out, "- **{}:** {}", label, body);
+                }
+            }
+        }
+    }
+
+    let citations = items("citations");
+    if !citations.is_empty() {
+        let _ = write!(out, "\n## Citations\n\n");
+        for citation in citations {
+            let mut line = format!(
+                "- {} {}",
+                text(citation, "marker").unwrap_or_default(),
+                text(citation, "source_name").unwrap_or_default()
+            );
+            if let Some(date) = text(citation, "date") {
+                let _ = write!(line, ", {}", date);
+            }
+            let stored = text(citation, "claim_id")
+                .and_then(|id| id.parse::<uuid::Uuid>().ok())
+                .and_then(|id| claim_citations.get(&ClaimId::from_uuid(id)));
+            match stored {
+                Some(stored) => {
+                    if let Some(title) = &stored.title {
+                        let _ = write!(line, ", \"{}\"", title);
+                    }
+                    for qualifier in stored.qualifiers() {
+                        let _ = write!(line, ", {}", qualifier);
+                    }
+                    if stored.is_web() {
+                        let _ = write!(line, " <{}>", stored.url);
+                    }
+                }
+                None => {
+                    if let Some(url) = text(citation, "source_url") {
+                        let _ = write!(line, " <{}>", url);
+                    }
+                }
+            }
+            if let Some(depth) = text(citation, "attribution_depth") {
+                let _ = write!(line, " ({})", depth);
+            }
+            let _ = writeln!(out, "{}", line);
+        }
+    }
+
+    out
+}
+
+/// The "What Changed" section: key judgments against the superseded
+/// assessment, with both versions of revised ones.
+fn diff_to_markdown(diff: &AssessmentDiff) -> String {
+    let judgment = |j: &serde_json::Value| {
+        let name = j
+            .get("hypothesis")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or("(unnamed)");
+        match j.get("probability").and_then(Value::as_f64) {
+            Some(p) => format!("{} ({:.0}%)", name, p * 100.0),
+            None => name.to_string(),
+        }
+    };
+
+    let mut out = String::from("\n## What Changed\n\n");
+    let _ = writeln!(
+        out,
+        "Compared with assessment {} (confidence: {}).\n",
+        diff.previous_assessment_id,
+        diff.previous_confidence.as_db_str()
+    );
+    if diff.judgments.is_empty() {
+        out.push_str("Neither assessment states key judgments.\n");
+    }
+    for change in &diff.judgments {
+        let _ = match change {
+            JudgmentChange::Unchanged { judgment: j } => {
+                writeln!(out, "- **Unchanged:** {}", judgment(j))
+            }
+            JudgmentChange::Revised {
+                previous, current, ..
+            } => writeln!(
+                out,
+                "- **Revised:** {} — previously: {}",
+                judgment(current),
+                judgment(previous)
+            ),
+            JudgmentChange::New { judgment: j } => writeln!(out, "- **New:** {}", judgment(j)),
+            JudgmentChange::Dropped { judgment: j } => {
+                writeln!(out, "- **Dropped:** {}", judgment(j))
+            }
+        };
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{CitationVia, Confidence};
+    use autosint_common::InvestigationId;
+    use serde_json::json;
+
+    #[test]
+    fn test_renders_sections_in_schema_order() {
+        let mut assessment = Assessment::new(
+            InvestigationId::new(),
+            json!({
+                "summary": "Shipments rose.",
+                "analysis": "Per Reuters [1], volumes doubled.",
+                "competing_hypotheses": [
+                    {"hypothesis": "Sanctions evasion", "probability": 0.7, "reasoning": "Routing."},
+                    {"hypothesis": "Seasonal demand", "probability": 0.3}
+                ],
+                "confidence_reasoning": "Two independent outlets.",
+                "gaps": [{"description": "No port data", "impact": "Volumes unverified"}],
+                "forward_indicators": [{"description": "New tanker charters", "trigger_implication": "Supports H1"}],
+                "sources_evaluated": [{"source_name": "Reuters", "claims_used": 3}],
+                "citations": [{"marker": "[1]", "source_name": "Reuters", "date": "2026-03-15", "source_url": "https://example.com/a"}]
+            }),
+            Confidence::Moderate,
+        );
+        assessment.tags = vec!["maritime".into()];
+        assessment.correlation_id = Some("CASE-2291".into());
+
+        let md = assessment_to_markdown(&assessment, &HashMap::new());
+        assert!(md.starts_with("# Assessment\n"));
+        assert!(md.contains("- **Confidence:** moderate"));
+        assert!(md.contains("- **Tags:** maritime"));
+        assert!(md.contains("- **Correlation ID:** CASE-2291"));
+        assert!(md.contains("### 1. Sanctions evasion (70%)"));
+        assert!(md.contains("**Reasoning:** Routing."));
+        assert!(md.contains("- No port data — *Impact:* Volumes unverified"));
+        assert!(md.contains("- **Claims used:** 3"));
+        assert!(md.contains("- [1] Reuters, 2026-03-15 <https://example.com/a>"));
+
+        let order: Vec<usize> = [
+            "## Summary",
+            "## Analysis",
+            "## Competing Hypotheses",
+            "## Confidence Reasoning",
+            "## Gaps",
+            "## Forward Indicators",
+            "## Sources Evaluated",
+            "## Citations",
+        ]
+        .iter()
+        .map(|h| md.find(h).unwrap())
+        .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_renders_stored_claim_citations() {
+        let cited = ClaimId::new();
+        let unknown = ClaimId::new();
+        let assessment = Assessment::new(
+            InvestigationId::new(),
+            json!({
+                "citations": [
+                    {"marker": "[1]", "claim_id": cited.to_string(), "source_name": "Reuters",
+                     "date": "2026-03-15", "source_url": "https://example.com/restated"},
+                    {"marker": "[2]", "claim_id": unknown.to_string(), "source_name": "AP",
+                     "source_url": "https://example.com/ap"},
+                    {"marker": "[1]", "claim_id": cited.to_string(), "source_name": "Reuters"}
+                ]
+            }),
+            Confidence::Moderate,
+        );
+        assert_eq!(cited_claim_ids(&assessment), vec![cited, unknown]);
+
+        let mut citation = Citation::from_url("https://reuters.example/tankers#p4");
+        citation.title = Some("Tanker exports double".into());
+        citation.accessed_at = Some("2026-03-16T09:00:00Z".parse().unwrap());
+        citation.via = CitationVia::Cache;
+        let md = assessment_to_markdown(&assessment, &HashMap::from([(cited, citation)]));
+        assert!(md.contains(
+            "- [1] Reuters, 2026-03-15, \"Tanker exports double\", #p4, accessed 2026-03-16, cached copy <https://reuters.example/tankers#p4>"
+        ));
+        assert!(md.contains("- [2] AP <https://example.com/ap>"));
+    }
+
+    #[test]
+    fn test_renders_what_changed_after_summary() {
+        let previous = json!({"hypothesis": "Sanctions evasion", "probability": 0.6});
+        let current = json!({"hypothesis": "Sanctions evasion via transfers", "probability": 0.7});
+        let mut assessment = Assessment::new(
+            InvestigationId::new(),
+            json!({"summary": "Shipments rose.", "analysis": "Volumes doubled."}),
+            Confidence::High,
+        );
+        let previous_id = autosint_common::ids::AssessmentId::new();
+        assessment.diff = Some(AssessmentDiff {
+            previous_assessment_id: previous_id,
+            previous_confidence: Confidence::Moderate,
+            matched_by: autosint_common::types::JudgmentMatching::Embedding,
+            judgments: vec![
+                JudgmentChange::Revised {
+                    previous,
+                    current,
+                    similarity: 0.9,
+                },
+                JudgmentChange::Dropped {
+                    judgment: json!({"hypothesis": "Reporting error"}),
+                },
+            ],
+        });
+
+        let md = assessment_to_markdown(&assessment, &HashMap::new());
+        assert!(md.contains(&format!(
+            "Compared with assessment {} (confidence: moderate).",
+            previous_id
+        )));
+        assert!(md.contains(
+            "- **Revised:** Sanctions evasion via transfers (70%) — previously: Sanctions evasion (60%)"
+        ));
+        assert!(md.contains("- **Dropped:** Reporting error"));
+        let changed = md.find("## What Changed").unwrap();
+        assert!(md.find("## Summary").unwrap() < changed);
+        assert!(changed < md.find("## Analysis").unwrap());
+    }
+
+    #[test]
+    fn test_non_object_content_rendered_as_json() {
+        let assessment =
+            Assessment::new(InvestigationId::new(), json!("free text"), Confidence::Low);
+        let md = assessment_to_markdown(&assessment, &HashMap::new());
+        assert!(md.contains("```json\n\"free text\"\n```"));
+        assert!(!md.contains("## Summary"));
+    }
+}
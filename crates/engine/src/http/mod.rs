@@ -0,0 +1,2119 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    extract::{DefaultBodyLimit, MatchedPath, Path, Query, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use metrics_exporter_prometheus::PrometheusHandle;
+
+use autosint_common::api::engine::{
+    AssessmentFeedbackRequest, AssessmentFeedbackResponse, AssessmentFormat, AssessmentQuery,
+    AuditQuery, AuditResponse, CancelMaintenanceJobResponse, ClaimSearchQuery, ClaimSearchResponse,
+    CreateMaintenanceJobRequest, CreateMonitorRequest, EnrichmentCallbackResponse,
+    EntitySearchQuery, EntitySearchResponse, ErrorResponse, FlagOverrideRequest, FlagsReport,
+    FootprintResponse, GeoSeedReport, GraphConsistencyReport, GraphStats, HealthResponse,
+    InvestigateRequest, InvestigateResponse, ListInvestigationsQuery, ListInvestigationsResponse,
+    ListMonitorsResponse, MaintenanceJobQuery, MaintenanceJobsResponse, PiiRedactionCounts,
+    PiiRedactionQuery, PiiRedactionReport, PreflightResponse, PromptsReport, PurgeQuery,
+    PurgeReport, QueueStats, ReadOnlyRequest, ReadOnlyStatus, RecoveryReport, ReembedReport,
+    ReportFormat, ReportQuery, ScoredClaim, ScoredEntity, SelfTestReport, ServiceHealth,
+    StixExportQuery, StubResolutionQuery, StubResolutionReport, TagsResponse,
+    TelemetryRollupsQuery, TelemetryRollupsResponse, TimelineQuery, TimelineResponse,
+    TrustLevelRequest, UpdateInvestigationRequest, UpdateMonitorRequest, WorkOrdersResponse,
+};
+use autosint_common::api::{FieldError, HealthStatus, Validate};
+use autosint_common::prometheus;
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{
+    normalize_correlation_id, normalize_language, normalize_tags, AssessmentFeedback, AuditActor,
+    AuditActorType, AuditRecord, Entity, FeedbackSummary, Investigation, InvestigationStatus,
+    MaintenanceJob, Monitor, TRUST_LEVEL_PROPERTY,
+};
+use autosint_common::webhook::{SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use autosint_common::{AssessmentId, EntityId, InvestigationId, MaintenanceJobId, MonitorId};
+
+use crate::audit::{self, AuditLog};
+use crate::config::{ConfigError, EngineConfig, PromptStore};
+use crate::embeddings::{reembed, EmbeddingClient};
+use crate::flags::FlagSet;
+use crate::graph::geo_seed;
+use crate::graph::{
+    ClaimSearchParams, EntitySearchParams, EntityUpdate, GraphClient, GraphError, SearchCursor,
+    SearchMode,
+};
+use crate::ingest::enrichment::Enrichment;
+use crate::ingest::monitors;
+use crate::jobs::{JobRefusal, JobRegistry};
+use crate::llm::LlmCaller;
+use crate::maintenance::ReadOnlyMode;
+use crate::orchestrator::{self, FailOutcome, Orchestrator, ResumeOutcome};
+use crate::queue::QueueClient;
+use crate::report::{Report, ReportError};
+use crate::retention::{self, PurgeError};
+use crate::stix;
+use crate::store::{self, StoreClient, StoreError};
+use crate::stubs;
+use crate::supervisor;
+use crate::transfer::{self, InvestigationArchive, TransferError};
+use crate::triage::{self, TriageError};
+
+pub mod federate;
+pub mod markdown;
+
+/// Default and maximum result counts for the graph search passthroughs.
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+const MAX_SEARCH_LIMIT: u32 = 100;
+
+/// Shared application state accessible from axum handlers.
+pub struct AppState {
+    pub graph: Arc<GraphClient>,
+    pub store: Arc<StoreClient>,
+    pub queue: Arc<QueueClient>,
+    pub embedding_client: Option<Arc<EmbeddingClient>>,
+    pub engine_config: Arc<EngineConfig>,
+    /// Live prompt templates, shared with the Orchestrator and Processor pool.
+    pub prompts: PromptStore,
+    pub orchestrator: Arc<Orchestrator>,
+    pub metrics_handle: PrometheusHandle,
+    /// Bearer token /metrics and /metrics/federate require
+    /// (ENGINE_METRICS_TOKEN). Open when unset.
+    pub metrics_token: Option<String>,
+    /// Pulls the sibling services' metrics for /metrics/federate, which
+    /// answers 404 when unset (`metrics.federate = false`).
+    pub metrics_federation: Option<Arc<federate::Federation>>,
+    /// Key required for admin endpoints (`x-admin-key` header). Admin endpoints are
+    /// disabled when unset.
+    pub admin_key: Option<String>,
+    /// Client for investigation prompt pre-flight (`llm.triage`). Pre-flight is
+    /// skipped when unset.
+    pub triage_llm: Option<Arc<dyn LlmCaller>>,
+    /// Mutating requests are refused while this is on.
+    pub read_only: ReadOnlyMode,
+    /// Runtime feature flags, shared with the graph client and sessions.
+    pub flags: FlagSet,
+    /// Receives external enrichment callbacks. The callback endpoint answers
+    /// 404 when unset (`ingest.enrichment.enabled = false`).
+    pub enrichment: Option<Arc<Enrichment>>,
+    /// Records successful mutating requests.
+    pub audit: AuditLog,
+    /// Maintenance job types POST /admin/jobs accepts.
+    pub jobs: Arc<JobRegistry>,
+}
+
+/// Non-GET endpoints left open in read-only mode: the toggle itself, feature
+/// flags, and the prompt reload, none of which change anything stored.
+const READ_ONLY_EXEMPT: &[&str] = &["/admin/read_only", "/admin/flags", "/config/prompts/reload"];
+
+/// The engine HTTP API. Request and response bodies are the
+/// `autosint_common::api::engine` types, shared with `autosint-client`.
+pub fn router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/health", get(health_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/metrics/federate", get(federate_metrics_handler))
+        .route("/investigate", post(investigate_handler))
+        .route("/investigate/{id}/resume", post(resume_handler))
+        .route("/investigate/{id}/fail", post(fail_handler))
+        .route("/investigate/{id}/footprint", get(footprint_handler))
+        .route("/investigate/{id}/timeline", get(timeline_handler))
+        .route("/investigations", get(list_investigations_handler))
+        .route(
+            "/investigations/{id}",
+            get(get_investigation_handler)
+                .patch(update_investigation_handler)
+                .delete(purge_investigation_handler),
+        )
+        .route(
+            "/investigations/{id}/assessment",
+            get(get_assessment_handler),
+        )
+        .route("/investigations/{id}/report", get(report_handler))
+        .route(
+            "/investigations/{id}/export",
+            get(export_investigation_handler),
+        )
+        .route(
+            "/investigations/import",
+            post(import_investigation_handler).layer(DefaultBodyLimit::max(
+                state.engine_config.system.transfer.max_archive_bytes,
+            )),
+        )
+        .route(
+            "/assessments/{id}/feedback",
+            get(list_assessment_feedback_handler).post(assessment_feedback_handler),
+        )
+        .route(
+            "/investigations/{id}/work_orders",
+            get(list_work_orders_handler),
+        )
+        .route("/tags", get(tags_handler))
+        .route(
+            "/monitors",
+            get(list_monitors_handler).post(create_monitor_handler),
+        )
+        .route(
+            "/monitors/{id}",
+            get(get_monitor_handler)
+                .patch(update_monitor_handler)
+                .delete(delete_monitor_handler),
+        )
+        .route("/graph/entities/search", get(search_entities_handler))
+        .route("/graph/claims/search", get(search_claims_handler))
+        .route("/stats/queue", get(queue_stats_handler))
+        .route("/stats/graph", get(graph_stats_handler))
+        .route("/telemetry/rollups", get(telemetry_rollups_handler))
+        .route("/graph/consistency", get(graph_consistency_handler))
+        .route("/export/stix", get(export_stix_handler))
+        .route(
+            "/investigations/{id}/export/stix",
+            get(export_investigation_stix_handler),
+        )
+        .route("/admin/seed/geo", post(seed_geo_handler))
+        .route("/admin/reembed", post(reembed_handler))
+        .route("/admin/stubs/resolve", post(resolve_stubs_handler))
+        .route("/admin/read_only", post(read_only_handler))
+        .route("/admin/flags", get(flags_handler).post(set_flag_handler))
+        .route("/admin/pii/redactions", get(pii_redactions_handler))
+        .route(
+            "/admin/jobs",
+            get(list_jobs_handler).post(create_job_handler),
+        )
+        .route("/admin/jobs/{id}", get(get_job_handler))
+        .route("/admin/jobs/{id}/cancel", post(cancel_job_handler))
+        .route(
+            "/admin/entities/{id}/trust_level",
+            put(entity_trust_level_handler),
+        )
+        .route("/config/prompts/reload", post(reload_prompts_handler))
+        .route("/selftest", post(selftest_handler))
+        .route("/recovery/last", get(last_recovery_handler))
+        .route(
+            "/callbacks/enrichment/{token}",
+            post(enrichment_callback_handler),
+        )
+        .route("/audit", get(audit_handler))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            audit_requests,
+        ))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            read_only_gate,
+        ))
+        .with_state(state)
+}
+
+/// Refuse mutating requests with 503 while the engine is read-only.
+async fn read_only_gate(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mutating = !matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if mutating && state.read_only.is_enabled() && !READ_ONLY_EXEMPT.contains(&request.uri().path())
+    {
+        metrics::counter!("http.read_only.rejected").increment(1);
+        return ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Engine is read-only for maintenance; retry once it ends",
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+/// Record each successful mutating request in the audit log, attributed to
+/// the admin key when it carries a valid one and to its API key otherwise.
+async fn audit_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    if matches!(method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+    let template = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_default();
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+    let actor = if require_admin(&state, request.headers()).is_ok() {
+        AuditActor::new(AuditActorType::Admin, Some(admin_key_id(request.headers())))
+    } else {
+        AuditActor::new(AuditActorType::HttpApiKey, api_key_id(request.headers()))
+    };
+
+    let response = next.run(request).await;
+    if response.status().is_success() {
+        let (target_type, target_id) = http_target(&template, &path);
+        let investigation_id = match target_type {
+            "investigation" => target_id
+                .as_deref()
+                .and_then(|id| id.parse().ok())
+                .map(InvestigationId::from_uuid),
+            _ => None,
+        };
+        let mut digest = serde_json::json!({ "status": response.status().as_u16() });
+        if let Some(query) = query {
+            digest["query"] = audit::digest(&serde_json::Value::String(query));
+        }
+        state.audit.record(AuditRecord {
+            actor,
+            investigation_id,
+            work_order_id: None,
+            operation: format!("{} {}", method, template),
+            target_type: target_type.to_string(),
+            target_id,
+            digest,
+            recorded_at: chrono::Utc::now(),
+        });
+    }
+    response
+}
+
+/// What a request to the route `template` changes, and the ID of the changed
+/// record from `path` when the route names one. Path parameters other than
+/// `{id}` (the enrichment callback token) are never returned.
+fn http_target(template: &str, path: &str) -> (&'static str, Option<String>) {
+    let mut target_type = "engine";
+    for (param, segment) in template.split('/').zip(path.split('/')) {
+        if param == "{id}" {
+            return (target_type, Some(segment.to_string()));
+        }
+        target_type = match param {
+            "investigate" | "investigations" => "investigation",
+            "assessments" => "assessment",
+            "monitors" => "monitor",
+            "entities" => "entity",
+            "enrichment" => "enrichment_request",
+            "jobs" => "maintenance_job",
+            _ => target_type,
+        };
+    }
+    (target_type, None)
+}
+
+/// A failed request, returned as an [`ErrorResponse`] body.
+pub struct ApiError {
+    status: StatusCode,
+    body: ErrorResponse,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: ErrorResponse::new(message),
+        }
+    }
+
+    /// 400 listing the request fields that failed validation.
+    fn invalid(fields: Vec<FieldError>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            body: ErrorResponse::invalid(fields),
+        }
+    }
+
+    /// Error text is redacted and bounded first, since store and graph errors
+    /// can embed connection strings or large response bodies.
+    fn from_error(status: StatusCode, e: &impl std::fmt::Display) -> Self {
+        Self::new(status, sanitize_error(&e.to_string()))
+    }
+
+    fn internal(e: &impl std::fmt::Display) -> Self {
+        Self::from_error(StatusCode::INTERNAL_SERVER_ERROR, e)
+    }
+
+    fn store(e: &StoreError) -> Self {
+        let status = match e {
+            StoreError::NotFound(_) => StatusCode::NOT_FOUND,
+            StoreError::Conflict(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::from_error(status, e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// Reject a request that fails its [`Validate`] checks.
+fn validate(request: &impl Validate) -> ApiResult<()> {
+    request.validate().map_err(ApiError::invalid)
+}
+
+/// Health check endpoint. Checks all three database connections and lists
+/// supervised background tasks with their panic and restart counts.
+async fn health_handler(State(state): State<Arc<AppState>>) -> (StatusCode, Json<HealthResponse>) {
+    let neo4j_ok = state.graph.health_check().await.is_ok();
+    let postgres_ok = state.store.health_check().await.is_ok();
+    let redis_ok = state.queue.health_check().await.is_ok();
+
+    let all_healthy = neo4j_ok && postgres_ok && redis_ok;
+    // A failed self-test degrades readiness without taking the engine out of rotation.
+    let selftest = orchestrator::last_selftest();
+    let selftest_failed = selftest.as_ref().is_some_and(|report| !report.passed);
+    // So does read-only mode, which tolerates Neo4j being down for maintenance.
+    let read_only = state.read_only.is_enabled();
+    let serving = all_healthy || (read_only && postgres_ok && redis_ok);
+
+    let status = if serving {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let body = HealthResponse {
+        status: match (serving, selftest_failed || read_only) {
+            (false, _) => HealthStatus::Unhealthy,
+            (true, true) => HealthStatus::Degraded,
+            (true, false) => HealthStatus::Healthy,
+        },
+        services: ServiceHealth {
+            neo4j: HealthStatus::from_ok(neo4j_ok),
+            postgres: HealthStatus::from_ok(postgres_ok),
+            redis: HealthStatus::from_ok(redis_ok),
+        },
+        tasks: supervisor::snapshot(),
+        selftest,
+        prompts: state.prompts.snapshot().hashes(),
+        read_only,
+    };
+
+    (status, Json(body))
+}
+
+/// Prometheus metrics endpoint.
+async fn metrics_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    prometheus::render(
+        &state.metrics_handle,
+        state.metrics_token.as_deref(),
+        &headers,
+    )
+}
+
+/// GET /metrics/federate — the engine's metrics followed by the fetch and
+/// geo services' (see [`federate`]).
+async fn federate_metrics_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
+    let Some(federation) = &state.metrics_federation else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !prometheus::authorized(state.metrics_token.as_deref(), &headers) {
+        return prometheus::respond(state.metrics_token.as_deref(), &headers, String::new);
+    }
+    let body = federation.render(state.metrics_handle.render()).await;
+    prometheus::respond(None, &headers, || body)
+}
+
+/// POST /investigate — start a new investigation.
+///
+/// With pre-flight (`preflight`, or `triage.preflight_by_default`) the prompt is
+/// triaged first: prompts flagged as too vague are rejected with 422, otherwise
+/// the suggestions are applied to the new investigation. `suggest_only` returns
+/// the triage with 200 and creates nothing. Pre-flight is skipped when no triage
+/// LLM is configured. `as_of` starts a snapshot investigation (see
+/// `Investigation::as_of`).
+async fn investigate_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<InvestigateRequest>,
+) -> ApiResult<Response> {
+    validate(&req)?;
+    let tags = normalize_tags(&req.tags).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+    let correlation_id = normalize_correlation_id(req.correlation_id.as_deref())
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+
+    let preflight = req.suggest_only
+        || req
+            .preflight
+            .unwrap_or(state.engine_config.system.triage.preflight_by_default);
+
+    let triage = match (&state.triage_llm, preflight) {
+        (_, false) => None,
+        (Some(llm), true) => {
+            let prompts = state.prompts.snapshot();
+            let triage = triage::triage_prompt(
+                llm.as_ref(),
+                prompts.text(triage::TRIAGE_PROMPT),
+                &req.prompt,
+                state
+                    .engine_config
+                    .system
+                    .safety
+                    .max_cycles_per_investigation,
+                state.engine_config.system.triage.max_attempts,
+            )
+            .await
+            .map_err(|e| {
+                tracing::warn!(error = %e, "Investigation pre-flight failed");
+                triage_error(&e)
+            })?;
+            Some(triage)
+        }
+        (None, true) => {
+            tracing::debug!("No triage LLM configured, skipping pre-flight");
+            None
+        }
+    };
+
+    if req.suggest_only {
+        let Some(triage) = triage else {
+            return Err(ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Pre-flight is unavailable: no triage LLM is configured",
+            ));
+        };
+        return Ok((StatusCode::OK, Json(PreflightResponse { triage })).into_response());
+    }
+
+    if let Some(triage) = &triage {
+        triage::ensure_actionable(triage).map_err(|e| triage_error(&e))?;
+    }
+
+    let orchestrator = Arc::clone(&state.orchestrator);
+
+    let investigation_id = orchestrator
+        .start_investigation(
+            &req.prompt,
+            tags,
+            correlation_id,
+            req.priority_cap,
+            req.fetch_budget,
+            req.as_of,
+            triage.as_ref(),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to start investigation");
+            ApiError::from_error(e.http_status(), &e)
+        })?;
+
+    // Spawn investigation lifecycle in background.
+    let orch = Arc::clone(&state.orchestrator);
+    let inv_id = investigation_id;
+    supervisor::spawn_supervised("investigation", async move {
+        if let Err(e) = orch.run_investigation(inv_id).await {
+            tracing::error!(
+                investigation_id = %inv_id,
+                error = %e,
+                "Investigation failed"
+            );
+        }
+    });
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(InvestigateResponse {
+            investigation_id,
+            status: InvestigationStatus::Pending,
+            message: "Investigation started.".into(),
+            triage,
+        }),
+    )
+        .into_response())
+}
+
+/// POST /investigate/{id}/resume — drive a non-terminal investigation no task
+/// owns, e.g. one left Suspended after an outage. 202 when a driver was
+/// started, 200 when one was already running; 409 for terminal investigations.
+/// Requires the admin key.
+async fn resume_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Response> {
+    require_admin(&state, &headers)?;
+
+    let investigation_id = InvestigationId::from_uuid(id);
+    let outcome = state
+        .orchestrator
+        .resume_investigation(investigation_id)
+        .await
+        .map_err(|e| orchestrator_error(investigation_id, "Resume failed", &e))?;
+    let (code, message) = match outcome {
+        ResumeOutcome::Started => (StatusCode::ACCEPTED, "Investigation resumed."),
+        ResumeOutcome::AlreadyRunning => (StatusCode::OK, "Investigation is already running."),
+    };
+    investigation_action_response(&state, investigation_id, code, message).await
+}
+
+/// POST /investigate/{id}/fail — run the failure-mode partial assessment and
+/// mark the investigation Failed. Runs to completion within the request. 409
+/// while a task drives the investigation or once it has completed; repeat
+/// calls on a Failed investigation return 200. Requires the admin key.
+async fn fail_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Response> {
+    require_admin(&state, &headers)?;
+
+    let investigation_id = InvestigationId::from_uuid(id);
+    let outcome = state
+        .orchestrator
+        .force_fail_investigation(investigation_id)
+        .await
+        .map_err(|e| orchestrator_error(investigation_id, "Force-fail failed", &e))?;
+    let message = match outcome {
+        FailOutcome::Failed => "Investigation failed with a partial assessment.",
+        FailOutcome::AlreadyFailed => "Investigation had already failed.",
+    };
+    investigation_action_response(&state, investigation_id, StatusCode::OK, message).await
+}
+
+/// GET /investigate/{id}/footprint — fetches and searches of the investigation's
+/// finished work orders, against its fetch budget.
+async fn footprint_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<FootprintResponse>> {
+    let investigation = state
+        .store
+        .get_investigation(InvestigationId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    let (footprint, work_orders) = state
+        .store
+        .investigation_footprint(investigation.id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    Ok(Json(FootprintResponse {
+        investigation_id: investigation.id,
+        footprint,
+        work_orders,
+        fetch_budget: investigation.fetch_budget,
+    }))
+}
+
+/// GET /investigate/{id}/timeline — the investigation's lifecycle events in
+/// the order they were recorded, paged by event ID.
+async fn timeline_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+    Query(params): Query<TimelineQuery>,
+) -> ApiResult<Json<TimelineResponse>> {
+    validate(&params)?;
+    let investigation = state
+        .store
+        .get_investigation(InvestigationId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    let mut filter = store::TimelineFilter {
+        since: params.since,
+        until: params.until,
+        after: params.cursor,
+        ..Default::default()
+    };
+    if let Some(limit) = params.limit {
+        filter.limit = limit.clamp(1, 500);
+    }
+    let page = state
+        .store
+        .get_investigation_timeline(investigation.id, &filter)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    Ok(Json(TimelineResponse {
+        investigation_id: investigation.id,
+        events: page.events,
+        next_cursor: page.next_after,
+    }))
+}
+
+fn orchestrator_error(
+    investigation_id: InvestigationId,
+    context: &str,
+    e: &orchestrator::OrchestratorError,
+) -> ApiError {
+    tracing::warn!(investigation_id = %investigation_id, error = %e, "{}", context);
+    ApiError::from_error(e.http_status(), e)
+}
+
+/// An [`InvestigateResponse`] carrying the investigation's current status.
+async fn investigation_action_response(
+    state: &AppState,
+    investigation_id: InvestigationId,
+    code: StatusCode,
+    message: &str,
+) -> ApiResult<Response> {
+    let investigation = state
+        .store
+        .get_investigation(investigation_id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok((
+        code,
+        Json(InvestigateResponse {
+            investigation_id,
+            status: investigation.status,
+            message: message.into(),
+            triage: None,
+        }),
+    )
+        .into_response())
+}
+
+fn triage_error(e: &TriageError) -> ApiError {
+    ApiError::from_error(e.http_status(), e)
+}
+
+/// GET /investigations — list investigations, newest first, optionally filtered by
+/// tags, status and correlation ID.
+async fn list_investigations_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ListInvestigationsQuery>,
+) -> ApiResult<Json<ListInvestigationsResponse>> {
+    validate(&params)?;
+    let tags = normalize_tags(&params.tag_list())
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+
+    let status = params
+        .status
+        .as_deref()
+        .map(str::parse::<InvestigationStatus>)
+        .transpose()
+        .map_err(|e| ApiError::from_error(StatusCode::BAD_REQUEST, &e))?;
+
+    let correlation_id = normalize_correlation_id(params.correlation_id.as_deref())
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+
+    let mut filter = store::InvestigationFilter {
+        tags,
+        status,
+        correlation_id,
+        ..Default::default()
+    };
+    if let Some(limit) = params.limit {
+        filter.limit = limit.clamp(1, 500);
+    }
+
+    let investigations = state
+        .store
+        .list_investigations(&filter)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list investigations");
+            ApiError::internal(&e)
+        })?;
+
+    Ok(Json(ListInvestigationsResponse { investigations }))
+}
+
+/// GET /investigations/{id} — investigation record with its current status.
+async fn get_investigation_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<Investigation>> {
+    let investigation = state
+        .store
+        .get_investigation(InvestigationId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(investigation))
+}
+
+/// PATCH /investigations/{id} — correct a Pending investigation's prompt,
+/// priority cap, focus entities, cycle limit or tags before it starts. 409 once
+/// it has started, or if it was edited since the `version` in the request.
+/// Each edit is logged as an `investigation_edited` event. Requires the admin
+/// key.
+async fn update_investigation_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+    Json(req): Json<UpdateInvestigationRequest>,
+) -> ApiResult<Json<Investigation>> {
+    require_admin(&state, &headers)?;
+    validate(&req)?;
+
+    let ceiling = state
+        .engine_config
+        .system
+        .safety
+        .max_cycles_per_investigation;
+    if req.max_cycles.is_some_and(|n| n > ceiling) {
+        return Err(ApiError::invalid(vec![FieldError::new(
+            "max_cycles",
+            format!("must be at most {}", ceiling),
+        )]));
+    }
+    let edit = store::PendingInvestigationEdit {
+        prompt: req.prompt.map(|p| p.trim().to_string()),
+        priority_cap: req.priority_cap,
+        focus_entities: req
+            .focus_entities
+            .map(|names| names.iter().map(|n| n.trim().to_string()).collect()),
+        max_cycles: req.max_cycles,
+        tags: req
+            .tags
+            .map(|tags| normalize_tags(&tags))
+            .transpose()
+            .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?,
+    };
+
+    let investigation_id = InvestigationId::from_uuid(id);
+    let investigation = state
+        .store
+        .update_investigation_pending_fields(
+            investigation_id,
+            req.version,
+            &edit,
+            Some(&admin_key_id(&headers)),
+        )
+        .await
+        .map_err(|e| {
+            tracing::warn!(investigation_id = %investigation_id, error = %e, "Investigation edit rejected");
+            ApiError::store(&e)
+        })?;
+    tracing::info!(
+        investigation_id = %investigation_id,
+        version = investigation.version,
+        "Investigation edited"
+    );
+    Ok(Json(investigation))
+}
+
+/// GET /investigations/{id}/assessment — the investigation's latest assessment,
+/// as JSON or (with `format=markdown`) rendered Markdown.
+async fn get_assessment_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+    Query(params): Query<AssessmentQuery>,
+) -> ApiResult<Response> {
+    let assessment = state
+        .store
+        .get_latest_assessment(InvestigationId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    Ok(match params.format {
+        AssessmentFormat::Json => Json(assessment).into_response(),
+        AssessmentFormat::Markdown => {
+            // Cited claims that can't be loaded fall back to the citation as
+            // the assessment states it.
+            let mut claim_citations = HashMap::new();
+            for claim_id in markdown::cited_claim_ids(&assessment) {
+                match state.graph.get_claim(claim_id).await {
+                    Ok(claim) => {
+                        if let Some(citation) = claim.citation {
+                            claim_citations.insert(claim_id, citation);
+                        }
+                    }
+                    Err(GraphError::NotFound(_)) => {}
+                    Err(e) => {
+                        tracing::warn!(claim_id = %claim_id, error = %e, "Assessment export could not load a cited claim")
+                    }
+                }
+            }
+            (
+                [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+                markdown::assessment_to_markdown(&assessment, &claim_citations),
+            )
+                .into_response()
+        }
+    })
+}
+
+/// POST /assessments/{id}/feedback — record a reader's rating of an
+/// assessment, with optional comments and disputed claims. Each disputed claim
+/// has its `disputed_count` bumped in the graph, and every assessment citing
+/// it is flagged `needs_review`; unknown claims reject the whole request. The submitter is recorded by the credential they sent.
+async fn assessment_feedback_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+    Json(req): Json<AssessmentFeedbackRequest>,
+) -> ApiResult<Response> {
+    validate(&req)?;
+    let assessment_id = AssessmentId::from_uuid(id);
+    state
+        .store
+        .get_assessment(assessment_id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    let mut disputed = req.disputed_claim_ids.clone();
+    let mut seen = std::collections::HashSet::new();
+    disputed.retain(|claim_id| seen.insert(*claim_id));
+    for claim_id in &disputed {
+        state
+            .graph
+            .get_claim(*claim_id)
+            .await
+            .map_err(|e| match e {
+                GraphError::NotFound(_) => ApiError::invalid(vec![FieldError::new(
+                    "disputed_claim_ids",
+                    format!("claim {} not found", claim_id),
+                )]),
+                _ => ApiError::internal(&e),
+            })?;
+    }
+
+    let comments = req
+        .comments
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    let feedback = state
+        .store
+        .create_assessment_feedback(
+            assessment_id,
+            req.rating,
+            comments,
+            &disputed,
+            api_key_id(&headers).as_deref(),
+        )
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    metrics::counter!("assessments.feedback", "rating" => req.rating.as_db_str()).increment(1);
+
+    // The feedback is stored; a claim that fails to mark stays unmarked.
+    for claim_id in &disputed {
+        if let Err(e) = state.graph.mark_claim_disputed(*claim_id).await {
+            tracing::warn!(claim_id = %claim_id, error = %e, "Failed to mark claim disputed");
+        }
+    }
+    if !disputed.is_empty() {
+        match state.store.flag_assessments_citing_claims(&disputed).await {
+            Ok(flagged) => {
+                metrics::counter!("assessments.flagged_for_review").increment(flagged.len() as u64);
+                for flagged_id in &flagged {
+                    tracing::info!(
+                        assessment_id = %flagged_id,
+                        "Assessment cites a disputed claim; flagged for review"
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to flag assessments citing disputed claims")
+            }
+        }
+    }
+    tracing::info!(
+        assessment_id = %assessment_id,
+        rating = req.rating.as_db_str(),
+        disputed_claims = disputed.len(),
+        "Assessment feedback recorded"
+    );
+    Ok((StatusCode::CREATED, Json(feedback)).into_response())
+}
+
+/// GET /assessments/{id}/feedback — an assessment's feedback, oldest first,
+/// with counts by rating.
+async fn list_assessment_feedback_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<AssessmentFeedbackResponse>> {
+    let assessment_id = AssessmentId::from_uuid(id);
+    state
+        .store
+        .get_assessment(assessment_id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    let feedback = state
+        .store
+        .list_assessment_feedback(assessment_id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(AssessmentFeedbackResponse {
+        summary: summarize_feedback(&feedback),
+        feedback,
+    }))
+}
+
+fn summarize_feedback(feedback: &[AssessmentFeedback]) -> FeedbackSummary {
+    let mut summary = FeedbackSummary::default();
+    for entry in feedback {
+        summary.add(entry.rating, 1);
+    }
+    summary
+}
+
+/// GET /investigations/{id}/report — a self-contained HTML report of the
+/// investigation: its latest assessment, the claims behind it, and the
+/// entities and relationships it touched. `format=zip` bundles it with the
+/// source documents the claims cite.
+async fn report_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+    Query(params): Query<ReportQuery>,
+) -> ApiResult<Response> {
+    let investigation_id = InvestigationId::from_uuid(id);
+    let report_error = |e: ReportError| {
+        let status = match e {
+            ReportError::NotFound(_) => StatusCode::NOT_FOUND,
+            ReportError::Store(_)
+            | ReportError::Graph(_)
+            | ReportError::Render(_)
+            | ReportError::Bundle(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            tracing::error!(investigation_id = %investigation_id, error = %e, "Report failed");
+        }
+        ApiError::from_error(status, &e)
+    };
+    let mut report = Report::load(&state.graph, &state.store, investigation_id)
+        .await
+        .map_err(report_error)?;
+
+    Ok(match params.format {
+        ReportFormat::Html => (
+            [
+                (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"autosint-report-{}.html\"", id),
+                ),
+            ],
+            report.to_html(chrono::Utc::now()).map_err(report_error)?,
+        )
+            .into_response(),
+        ReportFormat::Zip => {
+            report
+                .load_source_documents(&state.store)
+                .await
+                .map_err(report_error)?;
+            (
+                [
+                    (header::CONTENT_TYPE, "application/zip".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"autosint-report-{}.zip\"", id),
+                    ),
+                ],
+                report.to_zip(chrono::Utc::now()).map_err(report_error)?,
+            )
+                .into_response()
+        }
+    })
+}
+
+/// GET /investigations/{id}/work_orders — the investigation's work orders,
+/// including failure diagnostics for failed ones.
+async fn list_work_orders_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<WorkOrdersResponse>> {
+    let id = InvestigationId::from_uuid(id);
+    // Distinguish an unknown investigation from one without work orders.
+    state
+        .store
+        .get_investigation(id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    let work_orders = state
+        .store
+        .get_work_orders_by_investigation(id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(WorkOrdersResponse { work_orders }))
+}
+
+/// GET /tags — tag usage counts across investigations and assessments, for autocomplete.
+async fn tags_handler(State(state): State<Arc<AppState>>) -> ApiResult<Json<TagsResponse>> {
+    let tags = state.store.tag_usage().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load tag usage");
+        ApiError::internal(&e)
+    })?;
+    Ok(Json(TagsResponse { tags }))
+}
+
+/// POST /monitors — subscribe to a Fetch service source. 201 with the new
+/// monitor, which first runs at the scheduler's next tick.
+async fn create_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<CreateMonitorRequest>,
+) -> ApiResult<Response> {
+    validate(&req)?;
+    let focus_tags =
+        normalize_tags(&req.focus_tags).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+    let (monitor, investigation) = monitors::new_monitor(
+        &req,
+        focus_tags,
+        &state.engine_config.system.ingest.monitors,
+    );
+    let monitor = state
+        .store
+        .create_monitor(&monitor, &investigation)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to create monitor");
+            ApiError::internal(&e)
+        })?;
+    tracing::info!(
+        monitor_id = %monitor.id,
+        source_id = %monitor.source_id,
+        "Monitor created"
+    );
+    Ok((StatusCode::CREATED, Json(monitor)).into_response())
+}
+
+/// GET /monitors — every monitor with its last run and item totals.
+async fn list_monitors_handler(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<ListMonitorsResponse>> {
+    let monitors = state.store.list_monitors().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to list monitors");
+        ApiError::internal(&e)
+    })?;
+    Ok(Json(ListMonitorsResponse { monitors }))
+}
+
+/// GET /monitors/{id}
+async fn get_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<Monitor>> {
+    let monitor = state
+        .store
+        .get_monitor(MonitorId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(monitor))
+}
+
+/// PATCH /monitors/{id} — change a monitor's settings. Re-enabling a
+/// monitor makes it due at once.
+async fn update_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+    Json(req): Json<UpdateMonitorRequest>,
+) -> ApiResult<Json<Monitor>> {
+    validate(&req)?;
+    let mut monitor = state
+        .store
+        .get_monitor(MonitorId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    if let Some(name) = req.name {
+        monitor.name = name.trim().to_string();
+    }
+    if let Some(params) = req.params {
+        monitor.params = params;
+    }
+    if let Some(interval_seconds) = req.interval_seconds {
+        monitor.interval_seconds = interval_seconds;
+    }
+    if let Some(enabled) = req.enabled {
+        if enabled && !monitor.enabled {
+            monitor.next_run_at = chrono::Utc::now();
+        }
+        monitor.enabled = enabled;
+    }
+    if let Some(strictness) = req.strictness {
+        monitor.strictness = strictness;
+    }
+    if let Some(focus_tags) = req.focus_tags {
+        monitor.focus_tags =
+            normalize_tags(&focus_tags).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))?;
+    }
+    if let Some(max_items_per_run) = req.max_items_per_run {
+        monitor.max_items_per_run = max_items_per_run;
+    }
+
+    let monitor = state
+        .store
+        .update_monitor(&monitor)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(monitor))
+}
+
+/// DELETE /monitors/{id} — stop and remove a monitor. Documents and work
+/// orders it produced are kept. Returns the deleted monitor.
+async fn delete_monitor_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<Monitor>> {
+    let monitor = state
+        .store
+        .delete_monitor(MonitorId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    tracing::info!(monitor_id = %monitor.id, "Monitor deleted");
+    Ok(Json(monitor))
+}
+
+/// GET /graph/entities/search — fulltext entity search over names and aliases.
+async fn search_entities_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<EntitySearchQuery>,
+) -> ApiResult<Json<EntitySearchResponse>> {
+    validate(&params)?;
+    let search = EntitySearchParams {
+        query: params.q,
+        mode: SearchMode::Keyword,
+        kind_filter: params.kind,
+        updated_after: None,
+        updated_before: None,
+        created_before: None,
+        limit: Some(search_limit(params.limit)),
+        cursor: search_cursor(params.cursor.as_deref())?,
+    };
+
+    let page = state
+        .graph
+        .search_entities(&search, None)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Entity search failed");
+            ApiError::internal(&e)
+        })?;
+
+    Ok(Json(EntitySearchResponse {
+        next_cursor: page.next_cursor.map(|c| c.encode()),
+        results: page
+            .results
+            .into_iter()
+            .map(|r| {
+                let mut entity = r.item;
+                entity.embedding = None;
+                ScoredEntity {
+                    entity,
+                    score: r.score,
+                }
+            })
+            .collect(),
+    }))
+}
+
+/// GET /graph/claims/search — fulltext claim search over content.
+async fn search_claims_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ClaimSearchQuery>,
+) -> ApiResult<Json<ClaimSearchResponse>> {
+    validate(&params)?;
+    let include_original = params.include_original;
+    let region = match params.region.as_deref() {
+        Some(region) => state.graph.normalize_region(region).await.map_err(|e| {
+            tracing::error!(error = %e, "Claim region lookup failed");
+            ApiError::internal(&e)
+        })?,
+        None => None,
+    };
+    let search = ClaimSearchParams {
+        query: Some(params.q),
+        mode: Some(SearchMode::Keyword),
+        published_after: None,
+        published_before: None,
+        source_entity_id: None,
+        referenced_entity_id: None,
+        attribution_depth: None,
+        information_type: None,
+        language: params.language.as_deref().and_then(normalize_language),
+        region,
+        min_trust: params.min_trust,
+        ingested_before: None,
+        limit: Some(search_limit(params.limit)),
+        cursor: search_cursor(params.cursor.as_deref())?,
+    };
+
+    let page = state
+        .graph
+        .search_claims(&search, None)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Claim search failed");
+            ApiError::internal(&e)
+        })?;
+
+    let ids: Vec<_> = page.results.iter().map(|r| r.item.id).collect();
+    let citations = state
+        .store
+        .count_assessments_citing_claims(&ids, None)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to count claim citations");
+            ApiError::internal(&e)
+        })?;
+
+    Ok(Json(ClaimSearchResponse {
+        next_cursor: page.next_cursor.map(|c| c.encode()),
+        results: page
+            .results
+            .into_iter()
+            .map(|r| {
+                let mut claim = r.item;
+                claim.embedding = None;
+                if !include_original {
+                    claim.original_content = None;
+                }
+                ScoredClaim {
+                    cited_in_assessments: citations.get(&claim.id).copied().unwrap_or(0),
+                    claim,
+                    score: r.score,
+                }
+            })
+            .collect(),
+    }))
+}
+
+fn search_cursor(cursor: Option<&str>) -> ApiResult<Option<SearchCursor>> {
+    cursor
+        .map(SearchCursor::decode)
+        .transpose()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+fn search_limit(requested: Option<u32>) -> u32 {
+    requested
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT)
+}
+
+/// GET /stats/queue — work order stream depths, delayed-set size and queued wait ages.
+async fn queue_stats_handler(State(state): State<Arc<AppState>>) -> ApiResult<Json<QueueStats>> {
+    let mut stats = state.queue.stats().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to read queue stats");
+        ApiError::internal(&e)
+    })?;
+    stats.waiting = state
+        .store
+        .queued_work_order_wait_stats(chrono::Utc::now())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to read work order wait stats");
+            ApiError::store(&e)
+        })?;
+    Ok(Json(stats))
+}
+
+/// GET /stats/graph — entity, relationship and claim counts.
+async fn graph_stats_handler(State(state): State<Arc<AppState>>) -> ApiResult<Json<GraphStats>> {
+    let stats = state.graph.stats().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to read graph stats");
+        ApiError::internal(&e)
+    })?;
+    Ok(Json(stats))
+}
+
+/// Rollups returned by GET /telemetry/rollups when unset, and the most allowed.
+const DEFAULT_TELEMETRY_ROLLUPS: i64 = 200;
+const MAX_TELEMETRY_ROLLUPS: i64 = 1000;
+
+/// GET /telemetry/rollups — stored session statistics per prompt hash and
+/// window, newest first. Empty unless `[telemetry]` is enabled.
+async fn telemetry_rollups_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TelemetryRollupsQuery>,
+) -> ApiResult<Json<TelemetryRollupsResponse>> {
+    let filter = store::TelemetryRollupFilter {
+        role: params.role,
+        prompt_hash: params.prompt_hash,
+        since: params.since,
+        limit: params
+            .limit
+            .unwrap_or(DEFAULT_TELEMETRY_ROLLUPS)
+            .clamp(1, MAX_TELEMETRY_ROLLUPS),
+    };
+    let rollups = state
+        .store
+        .list_telemetry_rollups(&filter)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(TelemetryRollupsResponse { rollups }))
+}
+
+/// GET /graph/consistency — run the read-only graph consistency checks.
+async fn graph_consistency_handler(
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<Json<GraphConsistencyReport>> {
+    let report = state
+        .graph
+        .check_consistency(&state.engine_config.system.graph_consistency)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Graph consistency check failed");
+            ApiError::internal(&e)
+        })?;
+    Ok(Json(report))
+}
+
+/// GET /export/stix — STIX 2.1 bundle of the given entities and their neighborhood.
+async fn export_stix_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<StixExportQuery>,
+) -> ApiResult<Json<serde_json::Value>> {
+    validate(&params)?;
+    let seeds = params
+        .seeds()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let subgraph = state
+        .graph
+        .export_subgraph(&seeds, params.depth)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "STIX export failed");
+            ApiError::internal(&e)
+        })?;
+
+    Ok(Json(stix::to_bundle(
+        &subgraph,
+        &state.engine_config.system.stix,
+        chrono::Utc::now(),
+    )))
+}
+
+/// GET /investigations/{id}/export/stix — STIX 2.1 bundle of what an investigation
+/// touched in the graph.
+async fn export_investigation_stix_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let investigation_id = InvestigationId::from_uuid(id);
+    state
+        .store
+        .get_investigation(investigation_id)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    let subgraph = state
+        .graph
+        .export_investigation_subgraph(investigation_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(investigation_id = %investigation_id, error = %e, "STIX export failed");
+            ApiError::internal(&e)
+        })?;
+
+    Ok(Json(stix::to_bundle(
+        &subgraph,
+        &state.engine_config.system.stix,
+        chrono::Utc::now(),
+    )))
+}
+
+/// DELETE /investigations/{id} — purge an investigation and everything it produced.
+/// Requires the admin key and either `confirm=true` or `dry_run=true`.
+async fn purge_investigation_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+    Query(params): Query<PurgeQuery>,
+) -> ApiResult<Json<PurgeReport>> {
+    require_admin(&state, &headers)?;
+
+    validate(&params)?;
+
+    let investigation_id = InvestigationId::from_uuid(id);
+    let report = retention::purge_investigation(
+        &state.graph,
+        &state.store,
+        investigation_id,
+        params.dry_run,
+    )
+    .await
+    .map_err(|e| {
+        let status = match e {
+            PurgeError::NotFound(_) => StatusCode::NOT_FOUND,
+            PurgeError::NotTerminal(..) | PurgeError::Ingest(_) => StatusCode::CONFLICT,
+            PurgeError::Store(_) | PurgeError::Graph(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        tracing::error!(investigation_id = %investigation_id, error = %e, "Purge failed");
+        ApiError::from_error(status, &e)
+    })?;
+
+    Ok(Json(report))
+}
+
+/// Status for a failed export or import. Archives that can't be read or don't
+/// fit the limits are the caller's problem; store and graph failures are ours.
+fn transfer_status(e: &TransferError) -> StatusCode {
+    match e {
+        TransferError::NotFound(_) => StatusCode::NOT_FOUND,
+        TransferError::NotTerminal(..) => StatusCode::CONFLICT,
+        TransferError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        TransferError::Archive(transfer::ArchiveError::TooLarge(_)) => {
+            StatusCode::PAYLOAD_TOO_LARGE
+        }
+        TransferError::Archive(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        TransferError::Store(_) | TransferError::Graph(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// GET /investigations/{id}/export — a terminal investigation as a portable
+/// `.tar.gz` archive: its records and the graph data it created, without
+/// embeddings, credentials or API-key attributions.
+async fn export_investigation_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Response> {
+    let investigation_id = InvestigationId::from_uuid(id);
+    let config = &state.engine_config.system.transfer;
+    let bytes = transfer::export_investigation(
+        &state.graph,
+        &state.store,
+        investigation_id,
+        config,
+    )
+    .await
+    .and_then(|archive| Ok(archive.encode()?))
+    .map_err(|e| {
+        let status = transfer_status(&e);
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            tracing::error!(investigation_id = %investigation_id, error = %e, "Export failed");
+        }
+        ApiError::from_error(status, &e)
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"autosint-investigation-{}.tar.gz\"",
+                    id
+                ),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// POST /investigations/import — import an archive from
+/// `GET /investigations/{id}/export`, here or on another engine. Requires
+/// the admin key.
+async fn import_investigation_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> ApiResult<Response> {
+    require_admin(&state, &headers)?;
+
+    let config = &state.engine_config.system;
+    let result = match InvestigationArchive::decode(&body, config.transfer.max_archive_bytes) {
+        Ok(archive) => {
+            transfer::import_investigation(
+                &state.graph,
+                &state.store,
+                archive,
+                &config.transfer,
+                &config.dedup,
+            )
+            .await
+        }
+        Err(e) => Err(e.into()),
+    };
+    let report = result.map_err(|e| {
+        let status = transfer_status(&e);
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            tracing::error!(error = %e, "Import failed");
+        }
+        ApiError::from_error(status, &e)
+    })?;
+
+    Ok((StatusCode::CREATED, Json(report)).into_response())
+}
+
+/// POST /admin/seed/geo — create or refresh seeded country and region entities
+/// from `seed/geo.csv` in the config directory. Idempotent. Requires the admin key.
+async fn seed_geo_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<Json<GeoSeedReport>> {
+    require_admin(&state, &headers)?;
+
+    let records = geo_seed::load_geo_seed(&state.engine_config.config_dir).map_err(|e| {
+        tracing::error!(error = %e, "Failed to load geo seed data");
+        ApiError::internal(&e)
+    })?;
+
+    let report = state.graph.seed_geo_entities(&records).await.map_err(|e| {
+        tracing::error!(error = %e, "Geo seeding failed");
+        ApiError::internal(&e)
+    })?;
+
+    Ok(Json(report))
+}
+
+/// Set while a re-embedding run is in progress.
+static REEMBED_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Re-embed the graph with the `[reembed]` model and swap the new vectors in.
+/// Runs to completion within the request; old vectors serve searches until the
+/// swap at the end.
+async fn reembed_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<Json<ReembedReport>> {
+    require_admin(&state, &headers)?;
+
+    let embeddings = &state.engine_config.system.embeddings;
+    let Some(target) = &state.engine_config.system.reembed else {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "No [reembed] model configured",
+        ));
+    };
+    let Some(client) = EmbeddingClient::new(
+        target.embedding_config(embeddings),
+        state.engine_config.system.retry.llm_api.clone(),
+    ) else {
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("{} not set", target.api_key_env),
+        ));
+    };
+
+    if REEMBED_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "Re-embedding already in progress",
+        ));
+    }
+    let result = reembed::reembed_graph(&state.graph, &client, embeddings.batch_size).await;
+    REEMBED_RUNNING.store(false, Ordering::SeqCst);
+
+    let report = result.map_err(|e| {
+        tracing::error!(error = %e, "Re-embedding failed");
+        ApiError::internal(&e)
+    })?;
+    tracing::warn!(
+        model = %target.model,
+        dimensions = report.dimensions,
+        "Graph re-embedded — move the [reembed] settings into [embeddings] and restart"
+    );
+    Ok(Json(report))
+}
+
+/// Jobs listed by GET /admin/jobs when `limit` is unset, and the most allowed.
+const DEFAULT_JOB_LIST_LIMIT: u32 = 50;
+const MAX_JOB_LIST_LIMIT: u32 = 500;
+
+/// POST /admin/jobs — queue a maintenance job. 202 with the job, which the
+/// job runner picks up within `jobs.poll_interval_seconds`. Requires the
+/// admin key.
+async fn create_job_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<CreateMaintenanceJobRequest>,
+) -> ApiResult<Response> {
+    require_admin(&state, &headers)?;
+    validate(&req)?;
+    state
+        .jobs
+        .check(req.job_type, &req.params)
+        .map_err(|refusal| {
+            let status = match refusal {
+                JobRefusal::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+                JobRefusal::InvalidParams(_) => StatusCode::BAD_REQUEST,
+            };
+            ApiError::new(status, refusal.to_string())
+        })?;
+
+    let job = MaintenanceJob::new(req.job_type, req.params, req.dry_run);
+    state
+        .store
+        .create_maintenance_job(&job)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to queue maintenance job");
+            ApiError::internal(&e)
+        })?;
+    tracing::info!(
+        job_id = %job.id,
+        job_type = job.job_type.as_db_str(),
+        dry_run = job.dry_run,
+        "Maintenance job queued"
+    );
+    Ok((StatusCode::ACCEPTED, Json(job)).into_response())
+}
+
+/// GET /admin/jobs — maintenance jobs newest first, optionally only those
+/// with `status`. Requires the admin key.
+async fn list_jobs_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<MaintenanceJobQuery>,
+) -> ApiResult<Json<MaintenanceJobsResponse>> {
+    require_admin(&state, &headers)?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_JOB_LIST_LIMIT)
+        .clamp(1, MAX_JOB_LIST_LIMIT);
+    let jobs = state
+        .store
+        .list_maintenance_jobs(params.status, limit as i64)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list maintenance jobs");
+            ApiError::internal(&e)
+        })?;
+    Ok(Json(MaintenanceJobsResponse { jobs }))
+}
+
+/// GET /admin/jobs/{id} — a maintenance job with its progress. Requires the
+/// admin key.
+async fn get_job_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<MaintenanceJob>> {
+    require_admin(&state, &headers)?;
+    let job = state
+        .store
+        .get_maintenance_job(MaintenanceJobId::from_uuid(id))
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+    Ok(Json(job))
+}
+
+/// POST /admin/jobs/{id}/cancel — cancel a queued job, or stop a running one
+/// after its current batch. 409 when the job has already finished. Requires
+/// the admin key.
+async fn cancel_job_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+) -> ApiResult<Json<CancelMaintenanceJobResponse>> {
+    require_admin(&state, &headers)?;
+    let id = MaintenanceJobId::from_uuid(id);
+    let status = state
+        .store
+        .cancel_maintenance_job(id)
+        .await
+        .map_err(|e| ApiError::store(&e))?
+        .ok_or_else(|| ApiError::new(StatusCode::CONFLICT, "Job has already finished"))?;
+    tracing::info!(job_id = %id, status = status.as_db_str(), "Maintenance job cancel requested");
+    Ok(Json(CancelMaintenanceJobResponse { id, status }))
+}
+
+/// GET /audit — audit log entries, newest first, filtered by target, actor,
+/// operation and time. Requires the admin key.
+async fn audit_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<AuditQuery>,
+) -> ApiResult<Json<AuditResponse>> {
+    require_admin(&state, &headers)?;
+    validate(&params)?;
+
+    let mut filter = store::AuditFilter {
+        target_id: params.target_id,
+        actor_type: params.actor_type,
+        actor_id: params.actor_id,
+        operation: params.operation,
+        since: params.since,
+        until: params.until,
+        before: params.cursor,
+        ..Default::default()
+    };
+    if let Some(limit) = params.limit {
+        filter.limit = limit.clamp(1, 500);
+    }
+    let page = state
+        .store
+        .list_audit_entries(&filter)
+        .await
+        .map_err(|e| ApiError::store(&e))?;
+
+    Ok(Json(AuditResponse {
+        entries: page.entries,
+        next_cursor: page.next_before,
+    }))
+}
+
+/// POST /admin/stubs/resolve — run a stub resolution pass now, optionally
+/// limited to one investigation's stubs (`investigation_id`), and report what
+/// was merged, queued for profiling and deleted. Requires the admin key.
+async fn resolve_stubs_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<StubResolutionQuery>,
+) -> ApiResult<Json<StubResolutionReport>> {
+    require_admin(&state, &headers)?;
+
+    let report = stubs::resolve_stubs(
+        &state.graph,
+        &state.store,
+        &state.queue,
+        &state.audit,
+        state.orchestrator.events(),
+        &state.engine_config.system.stub_resolution,
+        params.investigation_id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Stub resolution failed");
+        ApiError::internal(&e)
+    })?;
+    Ok(Json(report))
+}
+
+/// POST /config/prompts/reload — re-read `prompts/` in the config directory and
+/// swap the whole set in. Sessions already running keep the prompts they
+/// started with. Rejected with 422, keeping the current prompts, if a required
+/// prompt is missing. Requires the admin key.
+async fn reload_prompts_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<Json<PromptsReport>> {
+    require_admin(&state, &headers)?;
+
+    let prompts = state
+        .prompts
+        .reload(&state.engine_config.config_dir.join("prompts"))
+        .map_err(|e| {
+            tracing::error!(error = %e, "Prompt reload failed");
+            let status = match e {
+                ConfigError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            ApiError::from_error(status, &e)
+        })?;
+    Ok(Json(PromptsReport {
+        prompts: prompts.hashes(),
+    }))
+}
+
+/// POST /admin/read_only — turn read-only mode on or off for every replica.
+/// The toggle is stored in Redis; other replicas pick it up within
+/// `maintenance.sync_interval_seconds`. Requires the admin key.
+async fn read_only_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<ReadOnlyRequest>,
+) -> ApiResult<Json<ReadOnlyStatus>> {
+    require_admin(&state, &headers)?;
+
+    state
+        .queue
+        .set_read_only_toggle(req.enabled)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to store the read-only toggle");
+            ApiError::internal(&e)
+        })?;
+    state.read_only.set_toggle(req.enabled);
+    tracing::warn!(enabled = req.enabled, "Read-only toggle set");
+
+    Ok(Json(ReadOnlyStatus {
+        read_only: state.read_only.is_enabled(),
+        toggle: state.read_only.toggle(),
+        pinned: state.read_only.is_pinned(),
+    }))
+}
+
+/// GET /admin/flags — every feature flag's effective value, config default and
+/// override. Requires the admin key.
+async fn flags_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<Json<FlagsReport>> {
+    require_admin(&state, &headers)?;
+    Ok(Json(FlagsReport {
+        flags: state.flags.statuses(),
+    }))
+}
+
+/// POST /admin/flags — override a feature flag for every replica, or clear
+/// the override with `"enabled": null`. Overrides are stored in Redis; other
+/// replicas pick them up within `flags.sync_interval_seconds`. Requires the
+/// admin key.
+async fn set_flag_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<FlagOverrideRequest>,
+) -> ApiResult<Json<FlagsReport>> {
+    require_admin(&state, &headers)?;
+
+    state
+        .queue
+        .set_flag_override(req.flag, req.enabled)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to store the feature flag override");
+            ApiError::internal(&e)
+        })?;
+    state.flags.set_override(req.flag, req.enabled);
+    tracing::warn!(
+        flag = req.flag.as_str(),
+        enabled = ?req.enabled,
+        "Feature flag override set"
+    );
+
+    Ok(Json(FlagsReport {
+        flags: state.flags.statuses(),
+    }))
+}
+
+/// Days reported by GET /admin/pii/redactions when unset, and the most allowed.
+const DEFAULT_PII_REPORT_DAYS: u32 = 30;
+const MAX_PII_REPORT_DAYS: u32 = 365;
+
+/// GET /admin/pii/redactions — personal data found in claim content, by type
+/// and day, over the last `days` days (default 30). Counts only. Requires the
+/// admin key.
+async fn pii_redactions_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<PiiRedactionQuery>,
+) -> ApiResult<Json<PiiRedactionReport>> {
+    require_admin(&state, &headers)?;
+    let days = params
+        .days
+        .unwrap_or(DEFAULT_PII_REPORT_DAYS)
+        .clamp(1, MAX_PII_REPORT_DAYS);
+
+    let daily = state.store.pii_redactions(days).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load PII redaction counts");
+        ApiError::internal(&e)
+    })?;
+    let mut totals: BTreeMap<String, PiiRedactionCounts> = BTreeMap::new();
+    for day in &daily {
+        let total = totals.entry(day.kind.clone()).or_default();
+        total.matches += day.counts.matches;
+        total.claims += day.counts.claims;
+        total.rejected_claims += day.counts.rejected_claims;
+    }
+
+    let config = &state.engine_config.system.pii;
+    Ok(Json(PiiRedactionReport {
+        enabled: config.enabled,
+        policy: config.policy,
+        days,
+        totals,
+        daily,
+    }))
+}
+
+/// PUT /admin/entities/{id}/trust_level — rate a source entity from 1
+/// (unreliable) to 5 (authoritative), or clear its rating with `null`. Claims
+/// keep the rating their source had when they were written. Requires the
+/// admin key.
+async fn entity_trust_level_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<uuid::Uuid>,
+    Json(req): Json<TrustLevelRequest>,
+) -> ApiResult<Json<Entity>> {
+    require_admin(&state, &headers)?;
+    validate(&req)?;
+
+    let (properties, remove_properties) = match req.trust_level {
+        Some(level) => (
+            Some(
+                [(TRUST_LEVEL_PROPERTY.to_string(), serde_json::json!(level))]
+                    .into_iter()
+                    .collect(),
+            ),
+            Vec::new(),
+        ),
+        None => (None, vec![TRUST_LEVEL_PROPERTY.to_string()]),
+    };
+    let update = EntityUpdate {
+        canonical_name: None,
+        aliases: None,
+        kind: None,
+        summary: None,
+        is_stub: None,
+        properties,
+        remove_properties,
+        identifiers: Default::default(),
+    };
+    let mut entity = state
+        .graph
+        .update_entity(EntityId::from_uuid(id), &update, None)
+        .await
+        .map_err(|e| match e {
+            GraphError::NotFound(_) => ApiError::from_error(StatusCode::NOT_FOUND, &e),
+            _ => {
+                tracing::error!(error = %e, "Failed to set entity trust level");
+                ApiError::internal(&e)
+            }
+        })?;
+    tracing::info!(entity_id = %entity.id, trust_level = ?req.trust_level, "Entity trust level set");
+
+    entity.embedding = None;
+    Ok(Json(entity))
+}
+
+/// GET /recovery/last — what startup recovery did with the investigations
+/// the last restart left non-terminal. 404 until recovery has started.
+async fn last_recovery_handler() -> ApiResult<Json<RecoveryReport>> {
+    orchestrator::last_recovery()
+        .map(Json)
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "Startup recovery has not run"))
+}
+
+/// POST /selftest — run a canary investigation through the full pipeline and
+/// report each stage. Runs to completion within the request, bounded by
+/// `selftest.timeout_seconds`; a failure marks `/health` degraded.
+async fn selftest_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<Json<SelfTestReport>> {
+    require_admin(&state, &headers)?;
+
+    let report = state
+        .orchestrator
+        .run_selftest()
+        .await
+        .ok_or_else(|| ApiError::new(StatusCode::CONFLICT, "Self-test already in progress"))?;
+    Ok(Json(report))
+}
+
+/// POST /callbacks/enrichment/{token} — a provider delivering an external
+/// enrichment result. Authenticated by the token in the path and the
+/// signature headers rather than the admin key; each token is accepted once.
+async fn enrichment_callback_handler(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> ApiResult<Json<EnrichmentCallbackResponse>> {
+    let enrichment = state
+        .enrichment
+        .as_ref()
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, "External enrichment is disabled"))?;
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let request = enrichment
+        .receive_callback(
+            &token,
+            header(TIMESTAMP_HEADER),
+            header(SIGNATURE_HEADER),
+            &body,
+        )
+        .await
+        .map_err(|e| {
+            tracing::warn!(error = %e, "Rejected enrichment callback");
+            ApiError::from_error(e.http_status(), &e)
+        })?;
+    Ok(Json(EnrichmentCallbackResponse {
+        request_id: request.id,
+        status: request.status,
+    }))
+}
+
+/// Identifies the admin key a request was made with in audit records, without
+/// revealing it: the first 12 hex digits of its SHA-256.
+fn admin_key_id(headers: &HeaderMap) -> String {
+    key_id(
+        headers
+            .get("x-admin-key")
+            .map(|v| v.as_bytes())
+            .unwrap_or_default(),
+    )
+}
+
+/// Identifies the credential a request was made with, like `admin_key_id`:
+/// the bearer token an authenticating proxy passed through, else the admin
+/// key. None when the request carried neither.
+fn api_key_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .or_else(|| headers.get("x-admin-key"))
+        .map(|v| key_id(v.as_bytes()))
+}
+
+fn key_id(key: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    Sha256::digest(key)
+        .iter()
+        .take(6)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Reject the request unless it carries the configured admin key.
+fn require_admin(state: &AppState, headers: &HeaderMap) -> ApiResult<()> {
+    let authorized = match (&state.admin_key, headers.get("x-admin-key")) {
+        (Some(expected), Some(provided)) => keys_match(provided.as_bytes(), expected.as_bytes()),
+        _ => false,
+    };
+    if !authorized {
+        return Err(ApiError::new(StatusCode::FORBIDDEN, "Admin key required"));
+    }
+    Ok(())
+}
+
+/// Compare digests so the comparison time doesn't depend on the key.
+fn keys_match(provided: &[u8], expected: &[u8]) -> bool {
+    use sha2::{Digest, Sha256};
+
+    let (a, b) = (Sha256::digest(provided), Sha256::digest(expected));
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_match() {
+        assert!(keys_match(b"admin-secret", b"admin-secret"));
+        assert!(!keys_match(b"admin-secreT", b"admin-secret"));
+        assert!(!keys_match(b"admin", b"admin-secret"));
+        assert!(!keys_match(b"", b"admin-secret"));
+    }
+
+    #[test]
+    fn test_http_target() {
+        assert_eq!(
+            http_target("/investigations/{id}", "/investigations/abc"),
+            ("investigation", Some("abc".to_string()))
+        );
+        assert_eq!(
+            http_target("/investigate/{id}/resume", "/investigate/abc/resume"),
+            ("investigation", Some("abc".to_string()))
+        );
+        assert_eq!(
+            http_target(
+                "/admin/entities/{id}/trust_level",
+                "/admin/entities/e1/trust_level"
+            ),
+            ("entity", Some("e1".to_string()))
+        );
+        assert_eq!(
+            http_target("/investigate", "/investigate"),
+            ("investigation", None)
+        );
+        assert_eq!(
+            http_target("/admin/reembed", "/admin/reembed"),
+            ("engine", None)
+        );
+        assert_eq!(
+            http_target("/admin/jobs/{id}/cancel", "/admin/jobs/j1/cancel"),
+            ("maintenance_job", Some("j1".to_string()))
+        );
+        // The callback token is a credential and never recorded.
+        assert_eq!(
+            http_target(
+                "/callbacks/enrichment/{token}",
+                "/callbacks/enrichment/s3cr3t"
+            ),
+            ("enrichment_request", None)
+        );
+    }
+}
@@ -0,0 +1,662 @@
+//! Asynchronous external enrichments: jobs a provider answers minutes
+//! later instead of within a tool call.
+//!
+//! `submit_external_enrichment` records a pending request under a fresh
+//! callback token, POSTs the job to the provider with the callback URL and
+//! returns at once. The provider delivers the result to
+//! `POST /callbacks/enrichment/{token}`, signed with the secret it shares
+//! with the engine (see `autosint_common::webhook`). A callback is accepted
+//! once: the token's request leaves `pending` exactly once, and a token past
+//! its expiry is refused. The result is stored as a source document and,
+//! when asked for, extracted by a low-priority `IngestDocument` work order;
+//! the Analyst sees it at its next cycle.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::config::{EnrichmentConfig, EnrichmentProviderConfig};
+use autosint_common::ids::{InvestigationId, SourceDocumentId, WorkOrderId};
+use autosint_common::types::{
+    DomainEvent, EnrichmentRequest, EnrichmentStatus, SourceDocument, WorkOrder, WorkOrderKind,
+    WorkOrderMessage, WorkOrderPriority, WorkOrderStatus,
+};
+use autosint_common::webhook::{self, WebhookError, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+use crate::events::EventBus;
+use crate::queue::QueueClient;
+use crate::store::{EnrichmentOutcome, StoreClient, StoreError};
+
+use super::IngestError;
+
+/// `SourceDocument::origin` of enrichment results; the external ID is the
+/// request ID.
+pub const ENRICHMENT_ORIGIN: &str = "enrichment";
+
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Payload characters quoted in an ingest work order's objective.
+const OBJECTIVE_PAYLOAD_CHARS: usize = 300;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SubmitError {
+    #[error("Unknown enrichment provider '{0}' (available: {1})")]
+    UnknownProvider(String, String),
+
+    #[error("This investigation already has {0} enrichment requests, the most allowed")]
+    LimitReached(u32),
+
+    #[error("Provider '{provider}' refused the job: {message}")]
+    Provider { provider: String, message: String },
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+/// Why a callback was refused. Nothing is written for any of these except
+/// `Expired`, which closes the request.
+#[derive(Debug, thiserror::Error)]
+pub enum CallbackError {
+    #[error("Unknown callback token")]
+    UnknownToken,
+
+    #[error(transparent)]
+    Signature(#[from] WebhookError),
+
+    #[error("Callback token already used")]
+    AlreadyUsed,
+
+    #[error("Callback token expired")]
+    Expired,
+
+    #[error("Callback body is {0} bytes, over the {1} byte limit")]
+    TooLarge(usize, usize),
+
+    #[error("Invalid callback body: {0}")]
+    InvalidBody(String),
+
+    #[error(transparent)]
+    Store(StoreError),
+}
+
+impl CallbackError {
+    /// HTTP status for the callback endpoint. Providers retry 5xx responses,
+    /// so only store failures are reported as one.
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            Self::UnknownToken => StatusCode::NOT_FOUND,
+            Self::Signature(_) => StatusCode::UNAUTHORIZED,
+            Self::AlreadyUsed => StatusCode::CONFLICT,
+            Self::Expired => StatusCode::GONE,
+            Self::TooLarge(..) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::InvalidBody(_) => StatusCode::BAD_REQUEST,
+            Self::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Body a provider POSTs to its callback URL.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+struct CallbackBody {
+    status: CallbackStatus,
+    /// The result: text, or any JSON, stored pretty-printed.
+    #[serde(default)]
+    result: Value,
+    #[serde(default)]
+    title: Option<String>,
+    /// Why the job failed, with `status: failed`.
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CallbackStatus {
+    Completed,
+    Failed,
+}
+
+/// Submits enrichment jobs and accepts their callbacks.
+pub struct Enrichment {
+    config: EnrichmentConfig,
+    /// Shared secret per provider name.
+    secrets: HashMap<String, String>,
+    store: Arc<StoreClient>,
+    queue: Arc<QueueClient>,
+    events: EventBus,
+    http: reqwest::Client,
+}
+
+impl Enrichment {
+    /// Read each provider's secret from its `secret_env` variable.
+    pub fn from_env(
+        config: EnrichmentConfig,
+        store: Arc<StoreClient>,
+        queue: Arc<QueueClient>,
+        http: reqwest::Client,
+    ) -> Result<Self, IngestError> {
+        let secrets = config
+            .providers
+            .iter()
+            .map(|p| match std::env::var(&p.secret_env) {
+                Ok(secret) if !secret.is_empty() => Ok((p.name.clone(), secret)),
+                _ => Err(IngestError::Config(format!(
+                    "{} is not set (secret of enrichment provider '{}')",
+                    p.secret_env, p.name
+                ))),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(config, secrets, store, queue, http))
+    }
+
+    pub fn new(
+        config: EnrichmentConfig,
+        secrets: HashMap<String, String>,
+        store: Arc<StoreClient>,
+        queue: Arc<QueueClient>,
+        http: reqwest::Client,
+    ) -> Self {
+        Self {
+            config,
+            secrets,
+            events: EventBus::timeline(Arc::clone(&store)),
+            store,
+            queue,
+            http,
+        }
+    }
+
+    /// Publish enqueued work orders to `events` instead of a bus that only
+    /// writes the timeline.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn config(&self) -> &EnrichmentConfig {
+        &self.config
+    }
+
+    /// Record a pending request for `investigation_id` and submit the job to
+    /// `provider`. `ingest` overrides the provider's `ingest_results`. If the
+    /// provider refuses the job, the request is closed as failed.
+    pub async fn submit(
+        &self,
+        provider: &str,
+        payload: Value,
+        investigation_id: InvestigationId,
+        work_order_id: Option<WorkOrderId>,
+        ingest: Option<bool>,
+    ) -> Result<EnrichmentRequest, SubmitError> {
+        let provider = self.provider(provider)?;
+        let secret = self.secret(provider)?;
+
+        let token = webhook::new_token();
+        let expires_at = Utc::now()
+            + chrono::Duration::seconds(self.config.token_ttl_seconds.min(i64::MAX as u64) as i64);
+        let mut request =
+            EnrichmentRequest::new(provider.name.clone(), payload, investigation_id, expires_at);
+        request.work_order_id = work_order_id;
+        request.ingest = ingest.unwrap_or(provider.ingest_results);
+
+        // Recorded before the provider hears of it: a fast callback must find it.
+        let request = self
+            .store
+            .create_enrichment_request(
+                &request,
+                &webhook::token_hash(&token),
+                self.config.max_per_investigation,
+            )
+            .await?
+            .ok_or(SubmitError::LimitReached(self.config.max_per_investigation))?;
+
+        let body = json!({
+            "request_id": request.id,
+            "payload": request.payload,
+            "callback_url": self.callback_url(&token),
+            "expires_at": request.expires_at,
+        })
+        .to_string();
+        if let Err(message) = self.post_job(provider, secret, body).await {
+            metrics::counter!("enrichment.requests", "provider" => provider.name.clone(), "outcome" => "refused")
+                .increment(1);
+            if let Err(e) = self
+                .store
+                .close_enrichment_request(request.id, EnrichmentStatus::Failed, Some(&message))
+                .await
+            {
+                tracing::warn!(request_id = %request.id, error = %e, "Failed to close refused enrichment request");
+            }
+            return Err(SubmitError::Provider {
+                provider: provider.name.clone(),
+                message,
+            });
+        }
+
+        metrics::counter!("enrichment.requests", "provider" => provider.name.clone(), "outcome" => "submitted")
+            .increment(1);
+        tracing::info!(
+            request_id = %request.id,
+            provider = %provider.name,
+            investigation_id = %investigation_id,
+            "Submitted enrichment request"
+        );
+        Ok(request)
+    }
+
+    /// Accept a provider's callback for `token`. The signature is checked
+    /// before anything about the request is revealed; the token is then
+    /// spent, whatever the body reports.
+    pub async fn receive_callback(
+        &self,
+        token: &str,
+        timestamp: Option<&str>,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<EnrichmentRequest, CallbackError> {
+        let result = self
+            .accept_callback(token, timestamp, signature, body)
+            .await;
+        let outcome = match &result {
+            Ok(request) => request.status.as_db_str(),
+            Err(CallbackError::UnknownToken) => "unknown_token",
+            Err(CallbackError::Signature(_)) => "bad_signature",
+            Err(CallbackError::AlreadyUsed) => "replayed",
+            Err(CallbackError::Expired) => "expired",
+            Err(CallbackError::TooLarge(..) | CallbackError::InvalidBody(_)) => "invalid",
+            Err(CallbackError::Store(_)) => "error",
+        };
+        metrics::counter!("enrichment.callbacks", "outcome" => outcome).increment(1);
+        result
+    }
+
+    async fn accept_callback(
+        &self,
+        token: &str,
+        timestamp: Option<&str>,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<EnrichmentRequest, CallbackError> {
+        let mut request = match self
+            .store
+            .get_enrichment_request_by_token(&webhook::token_hash(token))
+            .await
+        {
+            Ok(request) => request,
+            Err(StoreError::NotFound(_)) => return Err(CallbackError::UnknownToken),
+            Err(e) => return Err(CallbackError::Store(e)),
+        };
+
+        // A provider since removed from the config can't be verified.
+        let secret = self
+            .secrets
+            .get(&request.provider)
+            .ok_or(CallbackError::UnknownToken)?;
+        let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+            return Err(WebhookError::Malformed.into());
+        };
+        webhook::verify(
+            secret.as_bytes(),
+            timestamp,
+            signature,
+            body,
+            Utc::now().timestamp(),
+            self.config.signature_tolerance_seconds,
+        )?;
+
+        if request.status != EnrichmentStatus::Pending {
+            return Err(if request.status == EnrichmentStatus::Expired {
+                CallbackError::Expired
+            } else {
+                CallbackError::AlreadyUsed
+            });
+        }
+        if request.expires_at <= Utc::now() {
+            self.store
+                .close_enrichment_request(request.id, EnrichmentStatus::Expired, None)
+                .await
+                .map_err(CallbackError::Store)?;
+            return Err(CallbackError::Expired);
+        }
+
+        if body.len() > self.config.max_result_bytes {
+            return Err(CallbackError::TooLarge(
+                body.len(),
+                self.config.max_result_bytes,
+            ));
+        }
+        let callback: CallbackBody =
+            serde_json::from_slice(body).map_err(|e| CallbackError::InvalidBody(e.to_string()))?;
+
+        match callback.status {
+            CallbackStatus::Failed => {
+                let error = callback
+                    .error
+                    .unwrap_or_else(|| "Provider reported a failure".into());
+                self.complete(&request, &EnrichmentOutcome::Failed { error: &error })
+                    .await?;
+                request.status = EnrichmentStatus::Failed;
+                request.error = Some(error);
+            }
+            CallbackStatus::Completed => {
+                let document = result_document(&request, callback.title, &callback.result);
+                let (ingest_work_order, correlation_id) = if request.ingest {
+                    let (work_order, correlation_id) =
+                        self.ingest_work_order(&request, document.id).await?;
+                    (Some(work_order), correlation_id)
+                } else {
+                    (None, None)
+                };
+                self.complete(
+                    &request,
+                    &EnrichmentOutcome::Completed {
+                        document: &document,
+                        ingest_work_order: ingest_work_order.as_ref(),
+                    },
+                )
+                .await?;
+                request.status = EnrichmentStatus::Completed;
+                request.source_document_id = Some(document.id);
+                if let Some(work_order) = ingest_work_order {
+                    request.ingest_work_order_id = Some(work_order.id);
+                    self.enqueue(&request, &work_order, correlation_id).await;
+                }
+            }
+        }
+        request.completed_at = Some(Utc::now());
+
+        tracing::info!(
+            request_id = %request.id,
+            provider = %request.provider,
+            status = request.status.as_db_str(),
+            "Enrichment callback accepted"
+        );
+        Ok(request)
+    }
+
+    /// The investigation's requests with result excerpts, oldest first.
+    pub async fn list(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Vec<EnrichmentRequest>, StoreError> {
+        self.store
+            .list_enrichment_requests(investigation_id, self.config.excerpt_chars)
+            .await
+    }
+
+    fn provider(&self, name: &str) -> Result<&EnrichmentProviderConfig, SubmitError> {
+        self.config.provider(name).ok_or_else(|| {
+            SubmitError::UnknownProvider(
+                name.to_string(),
+                self.config
+                    .providers
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+        })
+    }
+
+    fn secret(&self, provider: &EnrichmentProviderConfig) -> Result<&str, SubmitError> {
+        self.secrets
+            .get(&provider.name)
+            .map(String::as_str)
+            .ok_or_else(|| SubmitError::Provider {
+                provider: provider.name.clone(),
+                message: "no secret configured".into(),
+            })
+    }
+
+    fn callback_url(&self, token: &str) -> String {
+        format!(
+            "{}/callbacks/enrichment/{}",
+            self.config.callback_base_url.trim_end_matches('/'),
+            token
+        )
+    }
+
+    /// POST the signed job. The provider's error text is bounded, since it
+    /// ends up in a tool result.
+    async fn post_job(
+        &self,
+        provider: &EnrichmentProviderConfig,
+        secret: &str,
+        body: String,
+    ) -> Result<(), String> {
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .http
+            .post(&provider.submit_url)
+            .timeout(SUBMIT_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                webhook::sign(secret.as_bytes(), timestamp, body.as_bytes()),
+            )
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text().await.unwrap_or_default();
+        Err(format!(
+            "HTTP {}: {}",
+            status,
+            text.chars().take(200).collect::<String>()
+        ))
+    }
+
+    async fn complete(
+        &self,
+        request: &EnrichmentRequest,
+        outcome: &EnrichmentOutcome<'_>,
+    ) -> Result<(), CallbackError> {
+        // Another delivery of the same token got there first, or it expired
+        // since it was read.
+        let completed = self
+            .store
+            .complete_enrichment_request(request.id, outcome)
+            .await
+            .map_err(CallbackError::Store)?;
+        if completed {
+            Ok(())
+        } else {
+            Err(CallbackError::AlreadyUsed)
+        }
+    }
+
+    /// A low-priority work order extracting `document_id`, in the
+    /// investigation's current cycle, and the investigation's correlation ID.
+    async fn ingest_work_order(
+        &self,
+        request: &EnrichmentRequest,
+        document_id: SourceDocumentId,
+    ) -> Result<(WorkOrder, Option<String>), CallbackError> {
+        let investigation = self
+            .store
+            .get_investigation(request.investigation_id)
+            .await
+            .map_err(CallbackError::Store)?;
+        let payload: String = request
+            .payload
+            .to_string()
+            .chars()
+            .take(OBJECTIVE_PAYLOAD_CHARS)
+            .collect();
+        let mut work_order = WorkOrder::new(
+            request.investigation_id,
+            format!(
+                "Extract the {} enrichment result (source document {}) for the request {}.",
+                request.provider, document_id, payload
+            ),
+            WorkOrderPriority::Low,
+        );
+        work_order.kind = WorkOrderKind::IngestDocument;
+        work_order.source_document_id = Some(document_id);
+        work_order.cycle = investigation.cycle_count;
+        Ok((work_order, investigation.correlation_id))
+    }
+
+    /// Enqueue the stored ingest work order. On failure the work order is
+    /// marked failed; the result itself is kept.
+    async fn enqueue(
+        &self,
+        request: &EnrichmentRequest,
+        work_order: &WorkOrder,
+        correlation_id: Option<String>,
+    ) {
+        let mut msg = WorkOrderMessage::from(work_order);
+        msg.correlation_id = correlation_id;
+        match self.queue.enqueue(&msg, &work_order.priority).await {
+            Ok(enqueued) => {
+                self.events
+                    .publish(DomainEvent::WorkOrderEnqueued {
+                        investigation_id: work_order.investigation_id,
+                        enqueued: enqueued.event(&msg, &work_order.priority),
+                    })
+                    .await
+            }
+            Err(e) => {
+                tracing::warn!(
+                    request_id = %request.id,
+                    work_order_id = %work_order.id,
+                    error = %e,
+                    "Failed to enqueue enrichment ingest work order"
+                );
+                if let Err(e) = self
+                    .store
+                    .update_work_order_status(work_order.id, &WorkOrderStatus::Failed, None, None)
+                    .await
+                {
+                    tracing::warn!(work_order_id = %work_order.id, error = %e, "Failed to mark work order failed");
+                }
+            }
+        }
+    }
+}
+
+/// The source document a completed callback is stored as.
+fn result_document(
+    request: &EnrichmentRequest,
+    title: Option<String>,
+    result: &Value,
+) -> SourceDocument {
+    let content = match result {
+        Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    };
+    SourceDocument {
+        id: SourceDocumentId::new(),
+        origin: ENRICHMENT_ORIGIN.into(),
+        external_id: request.id.to_string(),
+        title: Some(title.unwrap_or_else(|| format!("{} enrichment result", request.provider))),
+        author: Some(request.provider.clone()),
+        publisher_entity_id: None,
+        content,
+        published_at: None,
+        received_at: Utc::now(),
+    }
+}
+
+/// The block of newly finished enrichment requests added to the Analyst
+/// prompt, one entry per request. `None` when nothing arrived.
+pub fn format_results_block(requests: &[EnrichmentRequest]) -> Option<String> {
+    if requests.is_empty() {
+        return None;
+    }
+    let mut block = String::from(
+        "## External Enrichment Results\n\nThese enrichment requests finished since the last \
+         cycle. list_enrichment_results shows every request of the investigation.\n\n",
+    );
+    for request in requests {
+        block.push_str(&format!(
+            "- `{}` ({}, {}): payload {}",
+            request.id,
+            request.provider,
+            request.status.as_db_str(),
+            request.payload
+        ));
+        if let Some(error) = &request.error {
+            block.push_str(&format!(" — {}", error));
+        }
+        if let Some(work_order_id) = request.ingest_work_order_id {
+            block.push_str(&format!(
+                " — being extracted by work order `{}`",
+                work_order_id
+            ));
+        }
+        block.push('\n');
+        if let Some(excerpt) = &request.result_excerpt {
+            for line in excerpt.lines().filter(|l| !l.trim().is_empty()) {
+                block.push_str(&format!("  > {}\n", line));
+            }
+        }
+    }
+    Some(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(status: EnrichmentStatus) -> EnrichmentRequest {
+        let mut request = EnrichmentRequest::new(
+            "registry".into(),
+            json!({"number": "00445790"}),
+            InvestigationId::new(),
+            Utc::now(),
+        );
+        request.status = status;
+        request
+    }
+
+    #[test]
+    fn test_result_document_keeps_text_and_pretty_prints_json() {
+        let request = request(EnrichmentStatus::Pending);
+        let text = result_document(&request, None, &json!("Director: A. Smith"));
+        assert_eq!(text.content, "Director: A. Smith");
+        assert_eq!(text.origin, ENRICHMENT_ORIGIN);
+        assert_eq!(text.external_id, request.id.to_string());
+        assert_eq!(text.title.as_deref(), Some("registry enrichment result"));
+
+        let data = result_document(
+            &request,
+            Some("Company 00445790".into()),
+            &json!({"officers": ["A. Smith"]}),
+        );
+        assert!(data.content.contains("\"officers\": [\n"));
+        assert_eq!(data.title.as_deref(), Some("Company 00445790"));
+    }
+
+    #[test]
+    fn test_results_block_lists_finished_requests() {
+        assert_eq!(format_results_block(&[]), None);
+
+        let mut completed = request(EnrichmentStatus::Completed);
+        completed.result_excerpt = Some("Director: A. Smith\n\nSecretary: B. Jones".into());
+        let mut failed = request(EnrichmentStatus::Failed);
+        failed.error = Some("No such company".into());
+        let block = format_results_block(&[completed.clone(), failed.clone()]).unwrap();
+
+        assert!(block.starts_with("## External Enrichment Results\n"));
+        assert!(block.contains(&format!(
+            "- `{}` (registry, completed): payload {{\"number\":\"00445790\"}}\n  > Director: A. Smith\n  > Secretary: B. Jones\n",
+            completed.id
+        )));
+        assert!(block.ends_with(&format!(
+            "- `{}` (registry, failed): payload {{\"number\":\"00445790\"}} — No such company\n",
+            failed.id
+        )));
+    }
+}
@@ -0,0 +1,478 @@
+//! A minimal IMAP4rev1 client: just enough to log in, open a folder and read
+//! messages without changing their flags (RFC 3501 LOGIN, EXAMINE, UID FETCH
+//! with BODY.PEEK, LOGOUT).
+//!
+//! Generic over the stream, so tests can drive it with a scripted server.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+
+/// Longest response line outside a literal. Untagged responses are short;
+/// anything longer is a broken or hostile server.
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// A message's size and Message-ID, read without fetching its body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MessageSummary {
+    pub uid: u32,
+    pub size: u64,
+    /// The Message-ID header, if the message has one.
+    pub message_id: Option<String>,
+}
+
+/// The folder opened by `examine`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FolderStatus {
+    /// Messages in the folder.
+    pub exists: u32,
+    pub uid_validity: Option<u32>,
+}
+
+/// One untagged response: its text, with each literal replaced by a
+/// `{n}` marker, and the literals' contents in order.
+#[derive(Debug, Default)]
+struct Untagged {
+    text: String,
+    literals: Vec<Vec<u8>>,
+}
+
+/// An IMAP session on `S`, after the server greeting.
+pub struct ImapSession<S> {
+    stream: BufReader<S>,
+    next_tag: u32,
+    /// Largest literal accepted from the server.
+    max_literal_bytes: usize,
+}
+
+/// Connect to `host:port` over implicit TLS (IMAPS), verifying the server
+/// against the Mozilla root store.
+pub async fn connect_tls(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+    max_literal_bytes: usize,
+) -> Result<ImapSession<TlsStream<TcpStream>>, ImapError> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| ImapError::Tls(e.to_string()))?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+
+    let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|e| ImapError::Tls(e.to_string()))?;
+
+    let stream = tokio::time::timeout(timeout, async {
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| ImapError::Io(e.to_string()))?;
+        TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| ImapError::Tls(e.to_string()))
+    })
+    .await
+    .map_err(|_| ImapError::Io(format!("connecting to {}:{} timed out", host, port)))??;
+
+    ImapSession::new(stream, max_literal_bytes).await
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapSession<S> {
+    /// Start a session on a connected stream by reading the server greeting.
+    pub async fn new(stream: S, max_literal_bytes: usize) -> Result<Self, ImapError> {
+        let mut session = Self {
+            stream: BufReader::new(stream),
+            next_tag: 1,
+            max_literal_bytes,
+        };
+        let greeting = session.read_line().await?;
+        if !greeting.starts_with(b"* OK") && !greeting.starts_with(b"* PREAUTH") {
+            return Err(ImapError::Protocol(format!(
+                "unexpected greeting: {}",
+                String::from_utf8_lossy(&greeting).trim_end()
+            )));
+        }
+        Ok(session)
+    }
+
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<(), ImapError> {
+        let command = format!("LOGIN {} {}", quote(username)?, quote(password)?);
+        self.command(&command).await.map_err(|e| match e {
+            ImapError::Rejected(reason) => ImapError::Auth(reason),
+            other => other,
+        })?;
+        Ok(())
+    }
+
+    /// Open `folder` read-only.
+    pub async fn examine(&mut self, folder: &str) -> Result<FolderStatus, ImapError> {
+        let responses = self.command(&format!("EXAMINE {}", quote(folder)?)).await?;
+        let exists = responses
+            .iter()
+            .find_map(|r| {
+                let mut words = r.text.split_ascii_whitespace().skip(1);
+                let count = words.next()?.parse().ok()?;
+                words
+                    .next()
+                    .is_some_and(|w| w.eq_ignore_ascii_case("EXISTS"))
+                    .then_some(count)
+            })
+            .unwrap_or(0);
+        let uid_validity = responses.iter().find_map(|r| {
+            let rest = r.text.split_once("[UIDVALIDITY ")?.1;
+            rest.split(']').next()?.trim().parse().ok()
+        });
+        Ok(FolderStatus {
+            exists,
+            uid_validity,
+        })
+    }
+
+    /// UID, size and Message-ID of every message in the open folder. Only
+    /// call on a non-empty folder: some servers reject `1:*` on an empty one.
+    pub async fn summaries(&mut self) -> Result<Vec<MessageSummary>, ImapError> {
+        let responses = self
+            .command("UID FETCH 1:* (UID RFC822.SIZE BODY.PEEK[HEADER.FIELDS (MESSAGE-ID)])")
+            .await?;
+
+        let mut summaries = Vec::new();
+        for response in responses.iter().filter(|r| is_fetch(&r.text)) {
+            let (Some(uid), Some(size)) = (
+                fetch_number(&response.text, "UID"),
+                fetch_number(&response.text, "RFC822.SIZE"),
+            ) else {
+                continue;
+            };
+            let message_id = response
+                .literals
+                .first()
+                .and_then(|header| super::mime::header_value(header, "message-id"))
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty());
+            summaries.push(MessageSummary {
+                uid: uid as u32,
+                size,
+                message_id,
+            });
+        }
+        summaries.sort_by_key(|s| s.uid);
+        Ok(summaries)
+    }
+
+    /// The full RFC 5322 message with `uid`, without setting \Seen.
+    pub async fn fetch_message(&mut self, uid: u32) -> Result<Vec<u8>, ImapError> {
+        let responses = self
+            .command(&format!("UID FETCH {} (UID BODY.PEEK[])", uid))
+            .await?;
+        responses
+            .into_iter()
+            .filter(|r| is_fetch(&r.text))
+            .find(|r| fetch_number(&r.text, "UID") == Some(uid as u64))
+            .and_then(|r| r.literals.into_iter().next())
+            .ok_or_else(|| ImapError::Protocol(format!("no body returned for UID {}", uid)))
+    }
+
+    pub async fn logout(&mut self) -> Result<(), ImapError> {
+        self.command("LOGOUT").await?;
+        Ok(())
+    }
+
+    /// Send a tagged command and collect untagged responses until its
+    /// completion. NO and BAD completions are errors.
+    async fn command(&mut self, command: &str) -> Result<Vec<Untagged>, ImapError> {
+        let tag = format!("A{:04}", self.next_tag);
+        self.next_tag += 1;
+
+        let stream = self.stream.get_mut();
+        stream
+            .write_all(format!("{} {}\r\n", tag, command).as_bytes())
+            .await
+            .map_err(|e| ImapError::Io(e.to_string()))?;
+        stream
+            .flush()
+            .await
+            .map_err(|e| ImapError::Io(e.to_string()))?;
+
+        let mut responses = Vec::new();
+        loop {
+            let response = self.read_response().await?;
+            if let Some(status) = response.text.strip_prefix(&format!("{} ", tag)) {
+                let (code, reason) = status.split_once(' ').unwrap_or((status, ""));
+                return match code.to_ascii_uppercase().as_str() {
+                    "OK" => Ok(responses),
+                    "NO" | "BAD" => Err(ImapError::Rejected(reason.trim().to_string())),
+                    _ => Err(ImapError::Protocol(format!(
+                        "unexpected completion: {}",
+                        status.trim()
+                    ))),
+                };
+            }
+            if response.text.starts_with("* BYE") && !command.eq_ignore_ascii_case("LOGOUT") {
+                return Err(ImapError::Protocol(format!(
+                    "server closed the session: {}",
+                    response.text.trim()
+                )));
+            }
+            if response.text.starts_with('*') {
+                responses.push(response);
+            }
+            // Continuation requests ("+") don't arise for the commands sent here.
+        }
+    }
+
+    /// One response, reading through any literals it contains.
+    async fn read_response(&mut self) -> Result<Untagged, ImapError> {
+        let mut response = Untagged::default();
+        loop {
+            let line = self.read_line().await?;
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            response.text.push_str(line);
+
+            let Some(size) = literal_size(line) else {
+                return Ok(response);
+            };
+            if size > self.max_literal_bytes {
+                return Err(ImapError::Protocol(format!(
+                    "literal of {} bytes exceeds the {} byte limit",
+                    size, self.max_literal_bytes
+                )));
+            }
+            let mut literal = vec![0; size];
+            self.stream
+                .read_exact(&mut literal)
+                .await
+                .map_err(|e| ImapError::Io(e.to_string()))?;
+            response.literals.push(literal);
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<Vec<u8>, ImapError> {
+        let mut line = Vec::new();
+        let read = (&mut self.stream)
+            .take(MAX_LINE_BYTES as u64)
+            .read_until(b'\n', &mut line)
+            .await
+            .map_err(|e| ImapError::Io(e.to_string()))?;
+        if read == 0 {
+            return Err(ImapError::Io("connection closed by server".into()));
+        }
+        if !line.ends_with(b"\n") {
+            return Err(ImapError::Protocol(format!(
+                "response line longer than {} bytes",
+                MAX_LINE_BYTES
+            )));
+        }
+        Ok(line)
+    }
+}
+
+/// An IMAP quoted string. Values that can't be quoted (CR, LF, NUL) are
+/// rejected rather than sent as literals.
+fn quote(value: &str) -> Result<String, ImapError> {
+    if value.contains(['\r', '\n', '\0']) {
+        return Err(ImapError::Protocol(
+            "argument contains a line break or NUL".into(),
+        ));
+    }
+    Ok(format!(
+        "\"{}\"",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+/// Size of the literal announced at the end of `line` (`... {123}`).
+fn literal_size(line: &str) -> Option<usize> {
+    let open = line.strip_suffix('}')?.rfind('{')?;
+    line[open + 1..line.len() - 1]
+        .trim_end_matches('+')
+        .parse()
+        .ok()
+}
+
+/// Whether `text` is a `* n FETCH (...)` response.
+fn is_fetch(text: &str) -> bool {
+    let mut words = text.split_ascii_whitespace();
+    words.next() == Some("*")
+        && words.next().is_some_and(|n| n.parse::<u32>().is_ok())
+        && words
+            .next()
+            .is_some_and(|w| w.eq_ignore_ascii_case("FETCH"))
+}
+
+/// The number following data item `name` in a FETCH response.
+fn fetch_number(text: &str, name: &str) -> Option<u64> {
+    let mut words = text.split(|c: char| c.is_ascii_whitespace() || c == '(' || c == ')');
+    while let Some(word) = words.next() {
+        if word.eq_ignore_ascii_case(name) {
+            return words.next()?.parse().ok();
+        }
+    }
+    None
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImapError {
+    #[error("IMAP connection error: {0}")]
+    Io(String),
+
+    #[error("IMAP TLS error: {0}")]
+    Tls(String),
+
+    #[error("IMAP login failed: {0}")]
+    Auth(String),
+
+    #[error("IMAP command rejected: {0}")]
+    Rejected(String),
+
+    #[error("IMAP protocol error: {0}")]
+    Protocol(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::DuplexStream;
+
+    /// Play the server side of `transcript`: each `C:` line must be what the
+    /// client sends next, each `S:` block is written back verbatim.
+    fn scripted_server(transcript: Vec<(&'static str, String)>) -> DuplexStream {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let mut server = BufReader::new(server);
+            for (who, data) in transcript {
+                match who {
+                    "C" => {
+                        let mut line = String::new();
+                        server.read_line(&mut line).await.unwrap();
+                        assert_eq!(line, data, "unexpected client command");
+                    }
+                    _ => server.get_mut().write_all(data.as_bytes()).await.unwrap(),
+                }
+            }
+        });
+        client
+    }
+
+    #[tokio::test]
+    async fn test_session_reads_summaries_and_bodies() {
+        let header_1 = "Message-ID: <brief-41@example.org>\r\n\r\n";
+        let header_2 = "\r\n";
+        let body = "From: Maritime Brief <news@example.org>\r\nSubject: Brief 41\r\n\r\nHello\r\n";
+        let server = scripted_server(vec![
+            ("S", "* OK IMAP4rev1 ready\r\n".into()),
+            ("C", "A0001 LOGIN \"ingest\" \"p\\\"w\"\r\n".into()),
+            ("S", "A0001 OK LOGIN completed\r\n".into()),
+            ("C", "A0002 EXAMINE \"INBOX\"\r\n".into()),
+            (
+                "S",
+                "* 2 EXISTS\r\n* OK [UIDVALIDITY 1700] UIDs valid\r\nA0002 OK [READ-ONLY] done\r\n"
+                    .into(),
+            ),
+            (
+                "C",
+                "A0003 UID FETCH 1:* (UID RFC822.SIZE BODY.PEEK[HEADER.FIELDS (MESSAGE-ID)])\r\n"
+                    .into(),
+            ),
+            (
+                "S",
+                format!(
+                    "* 1 FETCH (UID 7 RFC822.SIZE 2048 BODY[HEADER.FIELDS (MESSAGE-ID)] {{{}}}\r\n{})\r\n\
+                     * 2 FETCH (UID 9 RFC822.SIZE 512 BODY[HEADER.FIELDS (MESSAGE-ID)] {{{}}}\r\n{})\r\n\
+                     A0003 OK FETCH completed\r\n",
+                    header_1.len(),
+                    header_1,
+                    header_2.len(),
+                    header_2
+                ),
+            ),
+            ("C", "A0004 UID FETCH 7 (UID BODY.PEEK[])\r\n".into()),
+            (
+                "S",
+                format!(
+                    "* 1 FETCH (UID 7 BODY[] {{{}}}\r\n{})\r\nA0004 OK FETCH completed\r\n",
+                    body.len(),
+                    body
+                ),
+            ),
+            ("C", "A0005 LOGOUT\r\n".into()),
+            ("S", "* BYE logging out\r\nA0005 OK LOGOUT completed\r\n".into()),
+        ]);
+
+        let mut session = ImapSession::new(server, 1 << 20).await.unwrap();
+        session.login("ingest", "p\"w").await.unwrap();
+        assert_eq!(
+            session.examine("INBOX").await.unwrap(),
+            FolderStatus {
+                exists: 2,
+                uid_validity: Some(1700),
+            }
+        );
+
+        let summaries = session.summaries().await.unwrap();
+        assert_eq!(
+            summaries,
+            vec![
+                MessageSummary {
+                    uid: 7,
+                    size: 2048,
+                    message_id: Some("<brief-41@example.org>".into()),
+                },
+                MessageSummary {
+                    uid: 9,
+                    size: 512,
+                    message_id: None,
+                },
+            ]
+        );
+
+        assert_eq!(session.fetch_message(7).await.unwrap(), body.as_bytes());
+        session.logout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejected_login_and_oversized_literal() {
+        let server = scripted_server(vec![
+            ("S", "* OK ready\r\n".into()),
+            ("C", "A0001 LOGIN \"ingest\" \"wrong\"\r\n".into()),
+            (
+                "S",
+                "A0001 NO [AUTHENTICATIONFAILED] Invalid credentials\r\n".into(),
+            ),
+            ("C", "A0002 UID FETCH 3 (UID BODY.PEEK[])\r\n".into()),
+            ("S", "* 1 FETCH (UID 3 BODY[] {4096}\r\n".into()),
+        ]);
+
+        let mut session = ImapSession::new(server, 1024).await.unwrap();
+        let err = session.login("ingest", "wrong").await.unwrap_err();
+        assert!(matches!(err, ImapError::Auth(ref r) if r.contains("Invalid credentials")));
+
+        let err = session.fetch_message(3).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds the 1024 byte limit"));
+    }
+
+    #[test]
+    fn test_response_parsing_helpers() {
+        assert_eq!(literal_size("* 1 FETCH (BODY[] {42}"), Some(42));
+        assert_eq!(literal_size("* 1 FETCH (BODY[] {42+}"), Some(42));
+        assert_eq!(literal_size("* OK done"), None);
+
+        let text = "* 12 FETCH (UID 345 RFC822.SIZE 6789 BODY[HEADER.FIELDS (MESSAGE-ID)] {40}";
+        assert!(is_fetch(text));
+        assert!(!is_fetch("* 12 EXISTS"));
+        assert_eq!(fetch_number(text, "UID"), Some(345));
+        assert_eq!(fetch_number(text, "RFC822.SIZE"), Some(6789));
+
+        assert_eq!(quote("a\"b\\c").unwrap(), "\"a\\\"b\\\\c\"");
+        assert!(quote("a\r\nb").is_err());
+    }
+}
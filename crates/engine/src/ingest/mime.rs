@@ -0,0 +1,489 @@
+//! Just enough RFC 5322 / MIME to turn a newsletter into text: headers
+//! (with RFC 2047 encoded words), multipart bodies, base64 and
+//! quoted-printable transfer encodings, and charsets. Attachments are skipped.
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+
+use autosint_common::readable::extract_html_content;
+
+/// Deepest multipart nesting followed; newsletters rarely go past three.
+const MAX_DEPTH: usize = 8;
+
+/// A parsed email, reduced to what ingest keeps.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedMessage {
+    pub message_id: Option<String>,
+    pub subject: Option<String>,
+    /// The From header, decoded.
+    pub from: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    /// First inline text/plain part.
+    pub text: Option<String>,
+    /// First inline text/html part.
+    pub html: Option<String>,
+}
+
+impl ParsedMessage {
+    /// Readable body: the plain-text part if it has any content, otherwise
+    /// the HTML part stripped to text.
+    pub fn readable_text(&self) -> Option<String> {
+        self.text
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(String::from)
+            .or_else(|| {
+                self.html
+                    .as_deref()
+                    .map(extract_html_content)
+                    .filter(|text| !text.is_empty())
+            })
+    }
+}
+
+/// A sender: `Display Name <address>` or a bare address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sender {
+    pub name: Option<String>,
+    pub address: String,
+}
+
+/// Parse a raw RFC 5322 message.
+pub fn parse_message(raw: &[u8]) -> ParsedMessage {
+    let (headers, body) = split_headers(raw);
+    let headers = parse_headers(headers);
+    let get = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+
+    let mut message = ParsedMessage {
+        message_id: get("message-id")
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty()),
+        subject: get("subject")
+            .map(decode_encoded_words)
+            .filter(|v| !v.is_empty()),
+        from: get("from")
+            .map(decode_encoded_words)
+            .filter(|v| !v.is_empty()),
+        date: get("date").and_then(|v| {
+            DateTime::parse_from_rfc2822(strip_comment(v))
+                .ok()
+                .map(|d| d.with_timezone(&Utc))
+        }),
+        text: None,
+        html: None,
+    };
+    collect_parts(&headers, body, 0, &mut message);
+    message
+}
+
+/// The first value of header `name` in a raw header block, unfolded.
+pub fn header_value(raw: &[u8], name: &str) -> Option<String> {
+    let (headers, _) = split_headers(raw);
+    parse_headers(headers)
+        .into_iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+/// Split a decoded From header into display name and address.
+pub fn parse_sender(from: &str) -> Option<Sender> {
+    let from = from.trim();
+    let (name, address) = match (from.rfind('<'), from.rfind('>')) {
+        (Some(open), Some(close)) if open < close => {
+            let name = from[..open].trim().trim_matches('"').trim();
+            (
+                (!name.is_empty()).then(|| name.to_string()),
+                &from[open + 1..close],
+            )
+        }
+        _ => (None, from),
+    };
+    let address = address.trim().to_ascii_lowercase();
+    address.contains('@').then_some(Sender { name, address })
+}
+
+/// Inline text parts of an entity with `headers` and `body`, depth first.
+fn collect_parts(headers: &[(String, String)], body: &[u8], depth: usize, out: &mut ParsedMessage) {
+    let get = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    let (mime_type, params) = parse_content_type(get("content-type").unwrap_or("text/plain"));
+    let is_attachment = get("content-disposition").is_some_and(|d| {
+        d.split(';')
+            .next()
+            .is_some_and(|kind| kind.trim().eq_ignore_ascii_case("attachment"))
+    });
+
+    if mime_type.starts_with("multipart/") {
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        let Some(boundary) = param(&params, "boundary") else {
+            return;
+        };
+        let parts = split_multipart(body, &boundary);
+        for part in parts {
+            let (part_headers, part_body) = split_headers(part);
+            collect_parts(&parse_headers(part_headers), part_body, depth + 1, out);
+        }
+        return;
+    }
+
+    if is_attachment {
+        return;
+    }
+    let slot = match mime_type.as_str() {
+        "text/plain" => &mut out.text,
+        "text/html" => &mut out.html,
+        _ => return,
+    };
+    if slot.is_some() {
+        return;
+    }
+    let decoded = decode_transfer(body, get("content-transfer-encoding").unwrap_or("7bit"));
+    *slot = Some(decode_charset(
+        &decoded,
+        param(&params, "charset").as_deref(),
+    ));
+}
+
+/// Header block and body, split at the first empty line.
+fn split_headers(raw: &[u8]) -> (&[u8], &[u8]) {
+    if let Some(body) = raw
+        .strip_prefix(b"\r\n")
+        .or_else(|| raw.strip_prefix(b"\n"))
+    {
+        return (&[], body);
+    }
+    for (i, window) in raw.windows(2).enumerate() {
+        if window == b"\n\n" {
+            return (&raw[..i + 1], &raw[i + 2..]);
+        }
+        if window == b"\n\r" && raw.get(i + 2) == Some(&b'\n') {
+            return (&raw[..i + 1], &raw[i + 3..]);
+        }
+    }
+    (raw, &[])
+}
+
+/// Unfolded `(name, value)` pairs, in order.
+fn parse_headers(block: &[u8]) -> Vec<(String, String)> {
+    let block = String::from_utf8_lossy(block);
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in block.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                if !value.is_empty() {
+                    value.push(' ');
+                }
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+/// Lowercased MIME type and the parameters after it.
+fn parse_content_type(value: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = value.split(';');
+    let mime_type = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+    let params = parts
+        .filter_map(|p| {
+            let (name, value) = p.split_once('=')?;
+            Some((
+                name.trim().to_ascii_lowercase(),
+                value.trim().trim_matches('"').to_string(),
+            ))
+        })
+        .collect();
+    (mime_type, params)
+}
+
+fn param(params: &[(String, String)], name: &str) -> Option<String> {
+    params
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.clone())
+}
+
+/// Body parts between `--boundary` delimiters, up to the closing one.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut pos = 0;
+    while pos < body.len() {
+        let end = body[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(body.len(), |i| pos + i + 1);
+        let line = String::from_utf8_lossy(&body[pos..end]);
+        let line = line.trim_end();
+        if line.starts_with(&delimiter) {
+            if let Some(start) = start {
+                // The CRLF before a delimiter belongs to the delimiter.
+                let mut part_end = pos;
+                if body[..part_end].ends_with(b"\n") {
+                    part_end -= 1;
+                }
+                if body[..part_end].ends_with(b"\r") {
+                    part_end -= 1;
+                }
+                parts.push(&body[start..part_end.max(start)]);
+            }
+            if line[delimiter.len()..].starts_with("--") {
+                break;
+            }
+            start = Some(end);
+        }
+        pos = end;
+    }
+    parts
+}
+
+fn decode_transfer(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "base64" => {
+            let compact: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            base64::engine::general_purpose::STANDARD
+                .decode(&compact)
+                .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(&compact))
+                .unwrap_or_else(|_| body.to_vec())
+        }
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+/// Quoted-printable (RFC 2045), or RFC 2047 "Q" encoding when `q_encoding`,
+/// where `_` stands for a space.
+fn decode_quoted_printable(input: &[u8], q_encoding: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' => {
+                let rest = &input[i + 1..];
+                if rest.starts_with(b"\r\n") {
+                    i += 3;
+                } else if rest.starts_with(b"\n") {
+                    i += 2;
+                } else if let Some(byte) = rest
+                    .get(..2)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    out.push(byte);
+                    i += 3;
+                } else {
+                    out.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if q_encoding => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Text in `charset`, defaulting to UTF-8. Undecodable bytes become U+FFFD.
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let encoding = charset
+        .and_then(|c| encoding_rs::Encoding::for_label(c.trim().as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+/// Decode RFC 2047 encoded words (`=?charset?B?...?=`) in a header value.
+/// Whitespace between adjacent encoded words is dropped.
+fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut pending_space = String::new();
+    let mut last_was_encoded = false;
+    while !rest.is_empty() {
+        if let Some((decoded, len)) = rest.strip_prefix("=?").and_then(decode_encoded_word) {
+            if !last_was_encoded {
+                out.push_str(&pending_space);
+            }
+            pending_space.clear();
+            out.push_str(&decoded);
+            rest = &rest[len + 2..];
+            last_was_encoded = true;
+            continue;
+        }
+        let c = rest.chars().next().unwrap();
+        if c.is_whitespace() {
+            pending_space.push(c);
+        } else {
+            out.push_str(&pending_space);
+            pending_space.clear();
+            out.push(c);
+            last_was_encoded = false;
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out.push_str(&pending_space);
+    out.trim().to_string()
+}
+
+/// Decode `charset?B?text?=` (after the leading `=?`). Returns the text and
+/// how many bytes of input it used.
+fn decode_encoded_word(word: &str) -> Option<(String, usize)> {
+    let mut fields = word.splitn(3, '?');
+    let charset = fields.next()?;
+    let encoding = fields.next()?;
+    let rest = fields.next()?;
+    let end = rest.find("?=")?;
+    let text = &rest[..end];
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+    let bytes = match encoding {
+        "B" | "b" => base64::engine::general_purpose::STANDARD
+            .decode(text)
+            .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(text))
+            .ok()?,
+        "Q" | "q" => decode_quoted_printable(text.as_bytes(), true),
+        _ => return None,
+    };
+    let used = charset.len() + 1 + encoding.len() + 1 + end + 2;
+    // RFC 2231 language suffix: charset*lang.
+    let charset = charset.split('*').next().unwrap_or(charset);
+    Some((decode_charset(&bytes, Some(charset)), used))
+}
+
+/// A Date header without a trailing `(comment)`, which chrono rejects.
+fn strip_comment(value: &str) -> &str {
+    value.split('(').next().unwrap_or(value).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTIPART: &str = "From: =?UTF-8?Q?Maritime_Br=C3=AFef?= <News@Example.org>\r\n\
+        To: ingest@example.com\r\n\
+        Subject: =?UTF-8?B?V2Vla2x5?= =?UTF-8?B?IGJyaWVm?= #41\r\n\
+        Date: Fri, 16 Oct 2026 08:30:00 +0200 (CEST)\r\n\
+        Message-ID: <brief-41@example.org>\r\n\
+        MIME-Version: 1.0\r\n\
+        Content-Type: multipart/mixed;\r\n\
+        \tboundary=\"outer\"\r\n\
+        \r\n\
+        preamble\r\n\
+        --outer\r\n\
+        Content-Type: multipart/alternative; boundary=inner\r\n\
+        \r\n\
+        --inner\r\n\
+        Content-Type: text/html; charset=utf-8\r\n\
+        \r\n\
+        <html><body><p>HTML version</p></body></html>\r\n\
+        --inner\r\n\
+        Content-Type: text/plain; charset=iso-8859-1\r\n\
+        Content-Transfer-Encoding: quoted-printable\r\n\
+        \r\n\
+        The tanker MV Caf=E9 left port on =\r\n\
+        Monday.\r\n\
+        --inner--\r\n\
+        --outer\r\n\
+        Content-Type: text/plain\r\n\
+        Content-Disposition: attachment; filename=\"manifest.txt\"\r\n\
+        \r\n\
+        attachment text\r\n\
+        --outer--\r\n";
+
+    #[test]
+    fn test_parse_multipart_newsletter() {
+        let message = parse_message(MULTIPART.as_bytes());
+        assert_eq!(
+            message.message_id.as_deref(),
+            Some("<brief-41@example.org>")
+        );
+        assert_eq!(message.subject.as_deref(), Some("Weekly brief #41"));
+        assert_eq!(
+            message.from.as_deref(),
+            Some("Maritime Brïef <News@Example.org>")
+        );
+        assert_eq!(
+            message.date,
+            Some("2026-10-16T06:30:00Z".parse::<DateTime<Utc>>().unwrap())
+        );
+        assert_eq!(
+            message.text.as_deref(),
+            Some("The tanker MV Café left port on Monday.")
+        );
+        assert!(message.html.as_deref().unwrap().contains("HTML version"));
+        assert_eq!(
+            message.readable_text().as_deref(),
+            Some("The tanker MV Café left port on Monday.")
+        );
+    }
+
+    #[test]
+    fn test_html_only_message_is_stripped() {
+        let raw = "From: alerts@example.net\r\n\
+            Subject: Alert\r\n\
+            Content-Type: text/html; charset=utf-8\r\n\
+            Content-Transfer-Encoding: base64\r\n\
+            \r\n\
+            PGh0bWw+PGJvZHk+PG5hdj5NZW51PC9uYXY+PHA+VmVzc2VsIHNlaXplZDwvcD48L2JvZHk+PC9o\r\n\
+            dG1sPg==\r\n";
+        let message = parse_message(raw.as_bytes());
+        assert_eq!(message.text, None);
+        assert_eq!(message.readable_text().as_deref(), Some("Vessel seized"));
+        assert_eq!(message.message_id, None);
+    }
+
+    #[test]
+    fn test_parse_sender() {
+        assert_eq!(
+            parse_sender("\"Maritime Brief\" <News@Example.org>"),
+            Some(Sender {
+                name: Some("Maritime Brief".into()),
+                address: "news@example.org".into(),
+            })
+        );
+        assert_eq!(
+            parse_sender("alerts@example.net"),
+            Some(Sender {
+                name: None,
+                address: "alerts@example.net".into(),
+            })
+        );
+        assert_eq!(parse_sender("undisclosed-recipients:;"), None);
+    }
+
+    #[test]
+    fn test_header_value_unfolds() {
+        let raw = b"Message-ID:\r\n <long-id@example.org>\r\n\r\n";
+        assert_eq!(
+            header_value(raw, "message-id").as_deref(),
+            Some("<long-id@example.org>")
+        );
+        assert_eq!(header_value(b"\r\n", "message-id"), None);
+    }
+}
@@ -0,0 +1,463 @@
+//! Ingest of sources pushed to AutOSINT rather than fetched by Processors.
+//!
+//! The IMAP poller reads email newsletters from a mailbox. Each new message
+//! becomes a source document attributed to its sender's publication entity,
+//! plus a low-priority `IngestDocument` work order that has a Processor
+//! extract it. Work orders need an investigation, so each mailbox has a
+//! standing ingest investigation: created terminal so the Orchestrator never
+//! drives it, and kept out of retention purges.
+//!
+//! Monitors (see `monitors`) pull from Fetch service sources on a schedule
+//! and feed the same kind of work orders. External enrichment providers (see
+//! `enrichment`) push results for jobs sessions submitted to them.
+
+pub mod enrichment;
+pub mod imap;
+pub mod mime;
+pub mod monitors;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::OnceCell;
+use tokio::task::JoinHandle;
+
+use autosint_common::config::{DedupConfig, ImapIngestConfig};
+use autosint_common::ids::{EntityId, InvestigationId, SourceDocumentId};
+use autosint_common::types::{
+    DomainEvent, Entity, Identifiers, Investigation, InvestigationStatus, SourceDocument,
+    WorkOrder, WorkOrderKind, WorkOrderMessage, WorkOrderPriority, WorkOrderStatus,
+    TRUST_LEVEL_PROPERTY,
+};
+
+use crate::events::EventBus;
+use crate::graph::dedup::{DedupResult, EntityDedup};
+use crate::graph::{GraphClient, GraphError};
+use crate::maintenance::ReadOnlyMode;
+use crate::queue::{QueueClient, QueueError};
+use crate::store::{InvestigationFilter, StoreClient, StoreError};
+use crate::supervisor;
+
+use self::imap::{ImapError, ImapSession};
+use self::mime::Sender;
+
+/// Tag of standing ingest investigations. Retention never purges them.
+pub const INGEST_TAG: &str = "ingest";
+
+/// `SourceDocument::origin` of documents read from a mailbox.
+pub const IMAP_ORIGIN: &str = "imap";
+
+/// Entity kind newsletter senders are matched against and created as.
+const PUBLICATION_KIND: &str = "publication";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What one poll of the mailbox did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PollReport {
+    /// Messages stored as documents with a work order.
+    pub ingested: usize,
+    /// New messages recorded as seen without a document (oversized, or no
+    /// readable text).
+    pub skipped: usize,
+    /// New messages left for the next poll by `max_messages_per_poll`.
+    pub deferred: usize,
+}
+
+/// Polls one IMAP mailbox into source documents and work orders.
+pub struct ImapIngest {
+    config: ImapIngestConfig,
+    dedup: DedupConfig,
+    graph: Arc<GraphClient>,
+    store: Arc<StoreClient>,
+    queue: Arc<QueueClient>,
+    events: EventBus,
+    investigation: OnceCell<InvestigationId>,
+}
+
+impl ImapIngest {
+    pub fn new(
+        config: ImapIngestConfig,
+        dedup: DedupConfig,
+        graph: Arc<GraphClient>,
+        store: Arc<StoreClient>,
+        queue: Arc<QueueClient>,
+    ) -> Self {
+        Self {
+            config,
+            dedup,
+            graph,
+            events: EventBus::timeline(Arc::clone(&store)),
+            store,
+            queue,
+            investigation: OnceCell::new(),
+        }
+    }
+
+    /// Publish enqueued work orders to `events` instead of a bus that only
+    /// writes the timeline.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Connect, log in and ingest new messages from the configured folder.
+    pub async fn poll(&self) -> Result<PollReport, IngestError> {
+        let password = std::env::var(&self.config.password_env)
+            .map_err(|_| IngestError::Config(format!("{} is not set", self.config.password_env)))?;
+        let mut session = imap::connect_tls(
+            &self.config.host,
+            self.config.port,
+            CONNECT_TIMEOUT,
+            self.max_literal_bytes(),
+        )
+        .await?;
+        session.login(&self.config.username, &password).await?;
+
+        let report = self.poll_session(&mut session).await;
+        if let Err(e) = session.logout().await {
+            tracing::debug!(error = %e, "IMAP logout failed");
+        }
+        report
+    }
+
+    /// Ingest new messages over a logged-in session. Messages are handled
+    /// oldest first and never modified on the server; what was already
+    /// handled is recognized by Message-ID.
+    pub async fn poll_session<S>(
+        &self,
+        session: &mut ImapSession<S>,
+    ) -> Result<PollReport, IngestError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut report = PollReport::default();
+        let folder = session.examine(&self.config.folder).await?;
+        if folder.exists == 0 {
+            return Ok(report);
+        }
+
+        // Messages without a Message-ID are tracked by UID instead, which
+        // holds as long as the folder's UIDVALIDITY does.
+        let messages: Vec<(u32, u64, String)> = session
+            .summaries()
+            .await?
+            .into_iter()
+            .map(|s| {
+                let id = s.message_id.unwrap_or_else(|| {
+                    format!("uid:{}:{}", folder.uid_validity.unwrap_or(0), s.uid)
+                });
+                (s.uid, s.size, id)
+            })
+            .collect();
+        let ids: Vec<String> = messages.iter().map(|(_, _, id)| id.clone()).collect();
+        let seen = self.store.seen_message_ids(&self.config.name, &ids).await?;
+
+        let new: Vec<_> = messages
+            .into_iter()
+            .filter(|(_, _, id)| !seen.contains(id))
+            .collect();
+        report.deferred = new.len().saturating_sub(self.config.max_messages_per_poll);
+
+        for (uid, size, message_id) in new.into_iter().take(self.config.max_messages_per_poll) {
+            if size > self.config.max_message_bytes {
+                tracing::warn!(
+                    mailbox = %self.config.name,
+                    message_id = %message_id,
+                    size,
+                    "Skipping oversized message"
+                );
+                self.skip(&message_id, "oversized", &mut report).await?;
+                continue;
+            }
+
+            let raw = session.fetch_message(uid).await?;
+            let message = mime::parse_message(&raw);
+            let Some(content) = message.readable_text() else {
+                tracing::warn!(
+                    mailbox = %self.config.name,
+                    message_id = %message_id,
+                    "Skipping message without readable text"
+                );
+                self.skip(&message_id, "no_text", &mut report).await?;
+                continue;
+            };
+
+            let sender = message.from.as_deref().and_then(mime::parse_sender);
+            let document = SourceDocument {
+                id: SourceDocumentId::new(),
+                origin: IMAP_ORIGIN.into(),
+                external_id: message_id.clone(),
+                title: message.subject.clone(),
+                author: message.from.clone(),
+                publisher_entity_id: None,
+                content,
+                published_at: message.date,
+                received_at: Utc::now(),
+            };
+            if self.ingest_document(&message_id, document, sender).await? {
+                report.ingested += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Store `document`, attributed to `sender`'s publication, with a
+    /// low-priority work order to extract it, and enqueue the work order.
+    /// False if another poll got there first.
+    async fn ingest_document(
+        &self,
+        message_id: &str,
+        mut document: SourceDocument,
+        sender: Option<Sender>,
+    ) -> Result<bool, IngestError> {
+        let investigation_id = self.investigation().await?;
+        let publisher = match &sender {
+            Some(sender) => Some(self.resolve_publisher(sender, investigation_id).await?),
+            None => None,
+        };
+        document.publisher_entity_id = publisher.as_ref().map(|(id, _)| *id);
+
+        let title = document.title.as_deref().unwrap_or("(no subject)");
+        let objective = match &publisher {
+            Some((_, name)) => format!(
+                "Extract the newsletter \"{}\" from {} (source document {}).",
+                title, name, document.id
+            ),
+            None => format!(
+                "Extract the newsletter \"{}\" (source document {}).",
+                title, document.id
+            ),
+        };
+        let mut work_order = WorkOrder::new(investigation_id, objective, WorkOrderPriority::Low);
+        work_order.kind = WorkOrderKind::IngestDocument;
+        work_order.source_document_id = Some(document.id);
+        work_order.referenced_entities = publisher.iter().map(|(id, _)| *id).collect();
+
+        let stored = self
+            .store
+            .ingest_source_document(&self.config.name, message_id, &document, &work_order)
+            .await?;
+        if !stored {
+            return Ok(false);
+        }
+
+        let mut msg = WorkOrderMessage::from(&work_order);
+        msg.correlation_id = Some(self.correlation_id());
+        match self.queue.enqueue(&msg, &work_order.priority).await {
+            Ok(enqueued) => {
+                self.events
+                    .publish(DomainEvent::WorkOrderEnqueued {
+                        investigation_id: work_order.investigation_id,
+                        enqueued: enqueued.event(&msg, &work_order.priority),
+                    })
+                    .await
+            }
+            Err(e) => {
+                // The document is kept; only its extraction is lost.
+                self.store
+                    .update_work_order_status(work_order.id, &WorkOrderStatus::Failed, None, None)
+                    .await?;
+                return Err(e.into());
+            }
+        }
+
+        metrics::counter!("ingest.imap.messages", "outcome" => "ingested").increment(1);
+        tracing::info!(
+            mailbox = %self.config.name,
+            message_id = %message_id,
+            document_id = %document.id,
+            work_order_id = %work_order.id,
+            "Ingested newsletter"
+        );
+        Ok(true)
+    }
+
+    async fn skip(
+        &self,
+        message_id: &str,
+        reason: &'static str,
+        report: &mut PollReport,
+    ) -> Result<(), IngestError> {
+        self.store
+            .mark_message_seen(&self.config.name, message_id)
+            .await?;
+        metrics::counter!("ingest.imap.messages", "outcome" => reason).increment(1);
+        report.skipped += 1;
+        Ok(())
+    }
+
+    /// The publication entity for `sender`: an exact name or address match,
+    /// or a new entity. Fuzzy matches are not trusted — two newsletters with
+    /// similar names are different sources. Returns its ID and name.
+    async fn resolve_publisher(
+        &self,
+        sender: &Sender,
+        investigation_id: InvestigationId,
+    ) -> Result<(EntityId, String), IngestError> {
+        let name = sender
+            .name
+            .clone()
+            .unwrap_or_else(|| sender.address.clone());
+        let dedup = EntityDedup::new(&self.graph, &self.dedup, None);
+        for candidate in [name.as_str(), sender.address.as_str()] {
+            if let DedupResult::ExactMatch(id) = dedup
+                .find_duplicate(candidate, PUBLICATION_KIND, &Identifiers::new(), None)
+                .await?
+            {
+                return Ok((id, name.clone()));
+            }
+        }
+
+        let mut entity = Entity::new(name.clone(), PUBLICATION_KIND.into());
+        if sender.name.is_some() {
+            entity.aliases.push(sender.address.clone());
+        }
+        entity.summary = Some(format!(
+            "Publication sending email newsletters from {}.",
+            sender.address
+        ));
+        entity
+            .properties
+            .insert("email".into(), sender.address.clone().into());
+        if let Some(level) = self.config.trust_level {
+            entity
+                .properties
+                .insert(TRUST_LEVEL_PROPERTY.into(), level.into());
+        }
+        entity.embedding_pending = true;
+        entity.created_by_investigation = Some(investigation_id);
+        let created = self.graph.create_entity(&entity, None).await?;
+        tracing::info!(
+            entity_id = %created.id,
+            name = %name,
+            "Created publication entity for newsletter sender"
+        );
+        Ok((created.id, name))
+    }
+
+    /// The mailbox's standing ingest investigation, created on first use.
+    async fn investigation(&self) -> Result<InvestigationId, IngestError> {
+        self.investigation
+            .get_or_try_init(|| async {
+                let correlation_id = self.correlation_id();
+                let existing = self
+                    .store
+                    .list_investigations(&InvestigationFilter {
+                        tags: vec![INGEST_TAG.into()],
+                        correlation_id: Some(correlation_id.clone()),
+                        limit: 1,
+                        ..Default::default()
+                    })
+                    .await?;
+                if let Some(investigation) = existing.into_iter().next() {
+                    return Ok(investigation.id);
+                }
+
+                let mut investigation = Investigation::new(format!(
+                    "Standing ingest investigation for the '{}' mailbox ({}). Newsletters \
+                     received there are stored as source documents and extracted by \
+                     low-priority work orders.",
+                    self.config.name, self.config.folder
+                ));
+                investigation.status = InvestigationStatus::Completed;
+                investigation.tags = vec![INGEST_TAG.into()];
+                investigation.correlation_id = Some(correlation_id);
+                self.store.create_investigation(&investigation).await?;
+                Ok(investigation.id)
+            })
+            .await
+            .copied()
+    }
+
+    fn correlation_id(&self) -> String {
+        format!("ingest-{}", self.config.name)
+    }
+
+    /// Literal size limit for the IMAP session: the largest message
+    /// accepted, plus room for the protocol around it.
+    fn max_literal_bytes(&self) -> usize {
+        (self.config.max_message_bytes as usize).saturating_add(64 * 1024)
+    }
+}
+
+/// Spawn the supervised poller. Polls once at startup, then every
+/// `poll_interval_seconds`; a failed poll is logged and retried at the next
+/// interval. No polls run while the engine is read-only. Polls hold the
+/// mailbox's `imap_ingest:<name>` singleton lock, so replicas don't each
+/// ingest the same messages.
+pub fn spawn_imap_ingest(ingest: Arc<ImapIngest>, read_only: ReadOnlyMode) -> JoinHandle<()> {
+    let interval = Duration::from_secs(ingest.config.poll_interval_seconds);
+    let lock_name = format!("imap_ingest:{}", ingest.config.name);
+    supervisor::spawn_supervised_restarting("imap_ingest", move || {
+        let lock_name = lock_name.clone();
+        let ingest = Arc::clone(&ingest);
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                mailbox = %ingest.config.name,
+                host = %ingest.config.host,
+                folder = %ingest.config.folder,
+                "IMAP ingest started"
+            );
+            loop {
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping IMAP poll");
+                    tokio::time::sleep(interval).await;
+                    continue;
+                }
+                let poll = async {
+                    match ingest.poll().await {
+                        Ok(report) => tracing::info!(
+                            mailbox = %ingest.config.name,
+                            ingested = report.ingested,
+                            skipped = report.skipped,
+                            deferred = report.deferred,
+                            "IMAP poll complete"
+                        ),
+                        Err(e) => {
+                            metrics::counter!("ingest.imap.poll_failures").increment(1);
+                            tracing::error!(
+                                mailbox = %ingest.config.name,
+                                error = %e,
+                                "IMAP poll failed"
+                            );
+                        }
+                    }
+                };
+                if let Err(e) = ingest.store.try_run_exclusive(&lock_name, poll).await {
+                    tracing::error!(
+                        mailbox = %ingest.config.name,
+                        error = %e,
+                        "Failed to take the IMAP ingest lock"
+                    );
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IngestError {
+    #[error("Ingest configuration error: {0}")]
+    Config(String),
+
+    #[error(transparent)]
+    Imap(#[from] ImapError),
+
+    #[error("Fetch service {0}")]
+    Fetch(String),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    #[error(transparent)]
+    Queue(#[from] QueueError),
+}
@@ -0,0 +1,609 @@
+//! Monitors: standing subscriptions to Fetch service sources.
+//!
+//! The scheduler claims monitors whose next run is due and queries each one's
+//! source for items published since its high-water mark. Items are
+//! recognized by normalized URL: new ones become source documents with
+//! low-priority `IngestDocument` work orders, up to the monitor's per-run
+//! cap, and the rest wait for the next run. The work orders are filed under
+//! the monitor's standing ingest investigation, but their claims carry the
+//! monitor as provenance instead.
+//!
+//! Sources report publication times in `extra.published_at`. Undated items
+//! are still deduplicated by URL but never move the high-water mark.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use autosint_common::api::engine::CreateMonitorRequest;
+use autosint_common::api::fetch::{
+    SourceQueryRequest, SourceQueryResponse, SourceQueryResult, SOURCE_QUERY_LIMIT,
+    SOURCE_QUERY_SINCE, SOURCE_RESULT_PUBLISHED_AT,
+};
+use autosint_common::api::ErrorResponse;
+use autosint_common::config::MonitorIngestConfig;
+use autosint_common::http_client::{self, Destination};
+use autosint_common::ids::{MonitorId, SourceDocumentId};
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{
+    DomainEvent, Investigation, InvestigationStatus, Monitor, MonitorRun, MonitorRunStatus,
+    SourceDocument, StrictnessProfile, WorkOrder, WorkOrderKind, WorkOrderMessage,
+    WorkOrderPriority, WorkOrderStatus,
+};
+
+use super::{IngestError, INGEST_TAG};
+use crate::events::EventBus;
+use crate::maintenance::ReadOnlyMode;
+use crate::queue::QueueClient;
+use crate::store::{StoreClient, StoreError};
+use crate::supervisor;
+
+/// `SourceDocument::origin` of documents read by monitors. Their external ID
+/// is the item's normalized URL, so two monitors never store an item twice.
+pub const MONITOR_ORIGIN: &str = "monitor";
+
+/// Query parameters that only track clicks and never change the item.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid"];
+
+/// A new monitor and its standing ingest investigation, from an already
+/// validated request. `max_items_per_run` defaults from `config`.
+pub fn new_monitor(
+    request: &CreateMonitorRequest,
+    focus_tags: Vec<String>,
+    config: &MonitorIngestConfig,
+) -> (Monitor, Investigation) {
+    let id = MonitorId::new();
+    let strictness = request.strictness.unwrap_or_default();
+
+    let mut investigation = Investigation::new(format!(
+        "Standing ingest investigation for the '{}' monitor of source '{}'. New items \
+         are stored as source documents and extracted by low-priority work orders.",
+        request.name.trim(),
+        request.source_id.trim()
+    ));
+    investigation.status = InvestigationStatus::Completed;
+    investigation.tags = vec![INGEST_TAG.into()];
+    investigation.correlation_id = Some(format!("monitor-{}", id));
+    investigation.strictness = Some(strictness);
+
+    let now = Utc::now();
+    let monitor = Monitor {
+        id,
+        name: request.name.trim().to_string(),
+        source_id: request.source_id.trim().to_string(),
+        params: request.params.clone(),
+        interval_seconds: request.interval_seconds,
+        enabled: request.enabled.unwrap_or(true),
+        strictness,
+        focus_tags,
+        max_items_per_run: request
+            .max_items_per_run
+            .unwrap_or(config.max_items_per_run),
+        investigation_id: investigation.id,
+        high_water_mark: None,
+        next_run_at: now,
+        last_run: None,
+        items_seen: 0,
+        items_ingested: 0,
+        items_skipped: 0,
+        created_at: now,
+        updated_at: now,
+    };
+    (monitor, investigation)
+}
+
+/// An item a source returned, keyed by normalized URL.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorItem {
+    pub url: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// What to do with one run's items.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ItemSelection {
+    /// New items to ingest, oldest first.
+    pub ingest: Vec<MonitorItem>,
+    /// Items without a URL, older than the high-water mark, already seen, or
+    /// repeated within the run.
+    pub skipped: u32,
+    /// New items over the per-run cap.
+    pub deferred: u32,
+    /// Where the high-water mark moves once `ingest` is stored: the newest
+    /// handled item, but never past a deferred one.
+    pub high_water_mark: Option<DateTime<Utc>>,
+}
+
+/// Pick the items to ingest from a run's results. `seen` holds the
+/// normalized URLs the monitor has already handled.
+pub fn select_items(
+    results: Vec<SourceQueryResult>,
+    high_water_mark: Option<DateTime<Utc>>,
+    seen: &HashSet<String>,
+    max_items: usize,
+) -> ItemSelection {
+    let mut selection = ItemSelection::default();
+    let mut newest_handled: Option<DateTime<Utc>> = None;
+    let mut in_run = HashSet::new();
+    let mut new = Vec::new();
+
+    for result in results {
+        let Some(url) = result.url.as_deref().and_then(normalize_item_url) else {
+            selection.skipped += 1;
+            continue;
+        };
+        let published_at = result
+            .extra
+            .as_ref()
+            .and_then(|extra| extra.get(SOURCE_RESULT_PUBLISHED_AT))
+            .and_then(Value::as_str)
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|t| t.with_timezone(&Utc));
+
+        let stale = matches!((published_at, high_water_mark), (Some(p), Some(h)) if p < h);
+        if stale || !in_run.insert(url.clone()) {
+            selection.skipped += 1;
+            continue;
+        }
+        if seen.contains(&url) {
+            selection.skipped += 1;
+            newest_handled = newest_handled.max(published_at);
+            continue;
+        }
+        new.push(MonitorItem {
+            url,
+            title: result.title,
+            content: result.content,
+            published_at,
+        });
+    }
+
+    // Oldest first, undated last, so the cap defers the newest items.
+    new.sort_by_key(|item| (item.published_at.is_none(), item.published_at));
+    let deferred = new.split_off(max_items.min(new.len()));
+    selection.deferred = deferred.len() as u32;
+
+    newest_handled = new
+        .iter()
+        .map(|item| item.published_at)
+        .fold(newest_handled, Option::max);
+    let oldest_deferred = deferred.iter().filter_map(|item| item.published_at).min();
+    selection.high_water_mark = match (newest_handled, oldest_deferred) {
+        (Some(handled), Some(deferred)) => Some(handled.min(deferred)),
+        (handled, _) => handled,
+    };
+    selection.ingest = new;
+    selection
+}
+
+/// Canonical form of an item URL for deduplication: lowercase scheme and
+/// host, no default port, fragment or click-tracking parameters. None for
+/// anything that isn't an http(s) URL.
+pub fn normalize_item_url(url: &str) -> Option<String> {
+    let mut parsed = reqwest::Url::parse(url.trim()).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    parsed.set_fragment(None);
+
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    Some(parsed.to_string())
+}
+
+/// Runs due monitors against the Fetch service.
+pub struct MonitorScheduler {
+    config: MonitorIngestConfig,
+    store: Arc<StoreClient>,
+    queue: Arc<QueueClient>,
+    events: EventBus,
+    fetch_base_url: String,
+    http: reqwest::Client,
+}
+
+impl MonitorScheduler {
+    pub fn new(
+        config: MonitorIngestConfig,
+        store: Arc<StoreClient>,
+        queue: Arc<QueueClient>,
+        fetch_base_url: String,
+        http: reqwest::Client,
+    ) -> Self {
+        Self {
+            config,
+            events: EventBus::timeline(Arc::clone(&store)),
+            store,
+            queue,
+            fetch_base_url,
+            http,
+        }
+    }
+
+    /// Publish enqueued work orders to `events` instead of a bus that only
+    /// writes the timeline.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Claim the monitors that are due and run each once. Returns how many ran.
+    pub async fn tick(&self) -> Result<usize, StoreError> {
+        let due = self.store.claim_due_monitors(Utc::now()).await?;
+        for monitor in &due {
+            self.run_monitor(monitor).await?;
+        }
+        Ok(due.len())
+    }
+
+    /// Run `monitor` once and record the outcome on it. A failed run keeps
+    /// whatever it ingested but leaves the high-water mark where it was.
+    pub async fn run_monitor(&self, monitor: &Monitor) -> Result<MonitorRun, StoreError> {
+        let mut run = MonitorRun {
+            at: Utc::now(),
+            status: MonitorRunStatus::Succeeded,
+            error: None,
+            seen: 0,
+            ingested: 0,
+            skipped: 0,
+            deferred: 0,
+        };
+
+        let high_water_mark = match self.poll(monitor, &mut run).await {
+            Ok(high_water_mark) => high_water_mark,
+            Err(e) => {
+                metrics::counter!("ingest.monitors.run_failures").increment(1);
+                tracing::error!(
+                    monitor_id = %monitor.id,
+                    monitor = %monitor.name,
+                    error = %e,
+                    "Monitor run failed"
+                );
+                run.status = MonitorRunStatus::Failed;
+                run.error = Some(sanitize_error(&e.to_string()));
+                None
+            }
+        };
+
+        let labels = [("monitor", monitor.name.clone())];
+        metrics::counter!("ingest.monitors.items_seen", &labels).increment(run.seen.into());
+        metrics::counter!("ingest.monitors.items_ingested", &labels).increment(run.ingested.into());
+        metrics::counter!("ingest.monitors.items_skipped", &labels).increment(run.skipped.into());
+
+        self.store
+            .record_monitor_run(monitor.id, &run, high_water_mark)
+            .await?;
+        tracing::info!(
+            monitor_id = %monitor.id,
+            monitor = %monitor.name,
+            seen = run.seen,
+            ingested = run.ingested,
+            skipped = run.skipped,
+            deferred = run.deferred,
+            "Monitor run complete"
+        );
+        Ok(run)
+    }
+
+    /// Query the source and ingest the new items, counting into `run`.
+    /// Returns the new high-water mark.
+    async fn poll(
+        &self,
+        monitor: &Monitor,
+        run: &mut MonitorRun,
+    ) -> Result<Option<DateTime<Utc>>, IngestError> {
+        let results = self.query_source(monitor).await?;
+        run.seen = results.len() as u32;
+
+        let urls: Vec<String> = results
+            .iter()
+            .filter_map(|r| r.url.as_deref().and_then(normalize_item_url))
+            .collect();
+        let seen = self.store.seen_monitor_urls(monitor.id, &urls).await?;
+        let selection = select_items(
+            results,
+            monitor.high_water_mark,
+            &seen,
+            monitor.max_items_per_run as usize,
+        );
+        run.skipped = selection.skipped;
+        run.deferred = selection.deferred;
+
+        for item in selection.ingest {
+            if self.ingest_item(monitor, item).await? {
+                run.ingested += 1;
+            } else {
+                run.skipped += 1;
+            }
+        }
+        Ok(selection.high_water_mark)
+    }
+
+    /// POST the monitor's query to the Fetch service, asking for items since
+    /// its high-water mark.
+    async fn query_source(&self, monitor: &Monitor) -> Result<Vec<SourceQueryResult>, IngestError> {
+        let mut params = monitor.params.clone();
+        if let Some(since) = monitor.high_water_mark {
+            params.insert(SOURCE_QUERY_SINCE.into(), since.to_rfc3339().into());
+        }
+        params.insert(SOURCE_QUERY_LIMIT.into(), self.config.query_limit.into());
+
+        let url = format!(
+            "{}/sources/{}/query",
+            self.fetch_base_url, monitor.source_id
+        );
+        let response = http_client::send(
+            Destination::Internal,
+            self.http.post(&url).json(&SourceQueryRequest { params }),
+        )
+        .await
+        .map_err(|e| IngestError::Fetch(format!("request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
+            return Err(IngestError::Fetch(format!("returned {}: {}", status, body)));
+        }
+        let response: SourceQueryResponse = response
+            .json()
+            .await
+            .map_err(|e| IngestError::Fetch(format!("unparseable response: {}", e)))?;
+        Ok(response.results)
+    }
+
+    /// Store `item` with a work order to extract it, and enqueue the work
+    /// order. False if it was stored before, by this monitor or another.
+    async fn ingest_item(&self, monitor: &Monitor, item: MonitorItem) -> Result<bool, IngestError> {
+        let document = SourceDocument {
+            id: SourceDocumentId::new(),
+            origin: MONITOR_ORIGIN.into(),
+            external_id: item.url.clone(),
+            title: item.title,
+            author: None,
+            publisher_entity_id: None,
+            content: item.content,
+            published_at: item.published_at,
+            received_at: Utc::now(),
+        };
+
+        let mut work_order = WorkOrder::new(
+            monitor.investigation_id,
+            objective(monitor, &document),
+            WorkOrderPriority::Low,
+        );
+        work_order.kind = WorkOrderKind::IngestDocument;
+        work_order.source_document_id = Some(document.id);
+        work_order.monitor_id = Some(monitor.id);
+
+        let stored = self
+            .store
+            .ingest_monitor_item(monitor.id, &item.url, &document, &work_order)
+            .await?;
+        if !stored {
+            return Ok(false);
+        }
+
+        let mut msg = WorkOrderMessage::from(&work_order);
+        msg.correlation_id = Some(format!("monitor-{}", monitor.id));
+        match self.queue.enqueue(&msg, &work_order.priority).await {
+            Ok(enqueued) => {
+                self.events
+                    .publish(DomainEvent::WorkOrderEnqueued {
+                        investigation_id: work_order.investigation_id,
+                        enqueued: enqueued.event(&msg, &work_order.priority),
+                    })
+                    .await
+            }
+            Err(e) => {
+                // The document is kept; only its extraction is lost.
+                self.store
+                    .update_work_order_status(work_order.id, &WorkOrderStatus::Failed, None, None)
+                    .await?;
+                return Err(e.into());
+            }
+        }
+
+        tracing::debug!(
+            monitor_id = %monitor.id,
+            url = %item.url,
+            document_id = %document.id,
+            work_order_id = %work_order.id,
+            "Ingested monitor item"
+        );
+        Ok(true)
+    }
+}
+
+/// Work order objective for one of the monitor's items.
+fn objective(monitor: &Monitor, document: &SourceDocument) -> String {
+    let mut objective = format!(
+        "Extract the item \"{}\" ({}) from the '{}' monitor (source document {}).",
+        document.title.as_deref().unwrap_or("(untitled)"),
+        document.external_id,
+        monitor.name,
+        document.id
+    );
+    if !monitor.focus_tags.is_empty() {
+        objective.push_str(&format!(" Focus: {}.", monitor.focus_tags.join(", ")));
+    }
+    objective.push(' ');
+    objective.push_str(match monitor.strictness {
+        StrictnessProfile::Lenient => {
+            "Strictness: lenient. Extract unattributed assertions as readily as sourced ones."
+        }
+        StrictnessProfile::Standard => {
+            "Strictness: standard. Record each claim's attribution as the item gives it."
+        }
+        StrictnessProfile::Strict => {
+            "Strictness: strict. Record each claim's attribution precisely; treat claims \
+             the item does not source as indirect."
+        }
+    });
+    objective
+}
+
+/// Spawn the supervised scheduler. Checks for due monitors every
+/// `tick_seconds`; a failed tick is logged and retried at the next one.
+/// Ticks are skipped while the engine is read-only, and hold the
+/// `monitor_scheduler` singleton lock so replicas don't run a monitor twice.
+pub fn spawn_monitor_scheduler(
+    scheduler: Arc<MonitorScheduler>,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(scheduler.config.tick_seconds);
+    supervisor::spawn_supervised_restarting("monitor_scheduler", move || {
+        let scheduler = Arc::clone(&scheduler);
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                tick_seconds = scheduler.config.tick_seconds,
+                "Monitor scheduler started"
+            );
+            loop {
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping monitor runs");
+                } else {
+                    let tick = async {
+                        if let Err(e) = scheduler.tick().await {
+                            tracing::error!(error = %e, "Monitor scheduler tick failed");
+                        }
+                    };
+                    if let Err(e) = scheduler
+                        .store
+                        .try_run_exclusive("monitor_scheduler", tick)
+                        .await
+                    {
+                        tracing::error!(error = %e, "Failed to take the monitor scheduler lock");
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn result(url: Option<&str>, published_at: Option<&str>) -> SourceQueryResult {
+        SourceQueryResult {
+            content: "Item text".into(),
+            url: url.map(String::from),
+            title: None,
+            extra: published_at.map(|p| {
+                json!({ SOURCE_RESULT_PUBLISHED_AT: p })
+                    .as_object()
+                    .cloned()
+                    .unwrap()
+            }),
+        }
+    }
+
+    fn time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn urls(selection: &ItemSelection) -> Vec<&str> {
+        selection.ingest.iter().map(|i| i.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_normalize_item_url() {
+        assert_eq!(
+            normalize_item_url("HTTPS://Example.ORG:443/news/1?utm_source=rss&id=7#top").as_deref(),
+            Some("https://example.org/news/1?id=7")
+        );
+        assert_eq!(
+            normalize_item_url("https://example.org/a?fbclid=x").as_deref(),
+            Some("https://example.org/a")
+        );
+        assert_eq!(normalize_item_url("mailto:desk@example.org"), None);
+        assert_eq!(normalize_item_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_select_skips_seen_stale_and_repeated() {
+        let seen = HashSet::from(["https://example.org/seen".to_string()]);
+        let selection = select_items(
+            vec![
+                result(
+                    Some("https://example.org/new"),
+                    Some("2026-10-02T00:00:00Z"),
+                ),
+                result(
+                    Some("https://example.org/seen"),
+                    Some("2026-10-03T00:00:00Z"),
+                ),
+                result(
+                    Some("https://example.org/old"),
+                    Some("2026-09-01T00:00:00Z"),
+                ),
+                result(
+                    Some("https://example.org/new#comments"),
+                    Some("2026-10-02T00:00:00Z"),
+                ),
+                result(None, None),
+            ],
+            Some(time("2026-10-01T00:00:00Z")),
+            &seen,
+            10,
+        );
+        assert_eq!(urls(&selection), ["https://example.org/new"]);
+        assert_eq!((selection.skipped, selection.deferred), (4, 0));
+        // The seen item is the newest handled.
+        assert_eq!(
+            selection.high_water_mark,
+            Some(time("2026-10-03T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_select_caps_oldest_first_and_holds_mark_at_deferred() {
+        let selection = select_items(
+            vec![
+                result(Some("https://example.org/3"), Some("2026-10-03T00:00:00Z")),
+                result(Some("https://example.org/undated"), None),
+                result(Some("https://example.org/1"), Some("2026-10-01T00:00:00Z")),
+                result(Some("https://example.org/2"), Some("2026-10-02T00:00:00Z")),
+            ],
+            None,
+            &HashSet::new(),
+            2,
+        );
+        assert_eq!(
+            urls(&selection),
+            ["https://example.org/1", "https://example.org/2"]
+        );
+        assert_eq!(selection.deferred, 2);
+        assert_eq!(
+            selection.high_water_mark,
+            Some(time("2026-10-02T00:00:00Z"))
+        );
+
+        // Undated items never move the mark.
+        let undated = select_items(
+            vec![result(Some("https://example.org/undated"), None)],
+            Some(time("2026-10-01T00:00:00Z")),
+            &HashSet::new(),
+            2,
+        );
+        assert_eq!(undated.ingest.len(), 1);
+        assert_eq!(undated.high_water_mark, None);
+    }
+}
@@ -0,0 +1,306 @@
+//! The standard maintenance job types.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use autosint_common::types::MaintenanceJob;
+
+use crate::embeddings::{EmbeddingClient, EmbeddingError};
+use crate::graph::conversions::build_aliases_text;
+use crate::graph::GraphClient;
+
+use super::{BatchOutcome, JobFuture, JobKind};
+
+/// Where the batch after a page of `limit` items starts: after its last item,
+/// or nowhere when the page came up short.
+fn next_checkpoint<T>(page: &[T], limit: u32, id: impl Fn(&T) -> &String) -> Option<String> {
+    (page.len() as u64 >= limit as u64)
+        .then(|| page.last().map(|item| id(item).clone()))
+        .flatten()
+}
+
+#[derive(Clone, Copy)]
+enum ReembedTarget {
+    Entities,
+    Claims,
+}
+
+/// Recompute embeddings with the configured model, in place. For a model
+/// with different dimensions use POST /admin/reembed, which rebuilds the
+/// vector indexes too.
+pub struct Reembed {
+    target: ReembedTarget,
+    graph: Arc<GraphClient>,
+    client: Arc<EmbeddingClient>,
+}
+
+impl Reembed {
+    pub fn entities(graph: Arc<GraphClient>, client: Arc<EmbeddingClient>) -> Self {
+        Self {
+            target: ReembedTarget::Entities,
+            graph,
+            client,
+        }
+    }
+
+    pub fn claims(graph: Arc<GraphClient>, client: Arc<EmbeddingClient>) -> Self {
+        Self {
+            target: ReembedTarget::Claims,
+            graph,
+            client,
+        }
+    }
+}
+
+impl JobKind for Reembed {
+    fn total<'a>(&'a self, _job: &'a MaintenanceJob) -> JobFuture<'a, Option<u64>> {
+        Box::pin(async move {
+            let total = match self.target {
+                ReembedTarget::Entities => self.graph.count_entities().await?,
+                ReembedTarget::Claims => self.graph.count_claims().await?,
+            };
+            Ok(Some(total))
+        })
+    }
+
+    fn run_batch<'a>(
+        &'a self,
+        job: &'a MaintenanceJob,
+        checkpoint: Option<&'a str>,
+        limit: u32,
+    ) -> JobFuture<'a, BatchOutcome> {
+        Box::pin(async move {
+            let page = match self.target {
+                ReembedTarget::Entities => {
+                    self.graph.entity_embedding_page(checkpoint, limit).await?
+                }
+                ReembedTarget::Claims => self.graph.claim_embedding_page(checkpoint, limit).await?,
+            };
+            let mut outcome = BatchOutcome {
+                processed: page.len() as u64,
+                next: next_checkpoint(&page, limit, |(id, _)| id),
+                ..Default::default()
+            };
+            if job.dry_run || page.is_empty() {
+                outcome.changed = page.len() as u64;
+                return Ok(outcome);
+            }
+
+            let texts: Vec<String> = page.iter().map(|(_, text)| text.clone()).collect();
+            let embeddings = match self.client.embed_batch(&texts).await {
+                Ok(embeddings) => embeddings,
+                // Every later batch would fail the same way.
+                Err(e @ EmbeddingError::Auth(_)) => return Err(e.into()),
+                Err(e) => {
+                    outcome.failures = page.iter().map(|(id, _)| format!("{id}: {e}")).collect();
+                    return Ok(outcome);
+                }
+            };
+            let rows: Vec<(String, Vec<f32>)> =
+                page.into_iter().map(|(id, _)| id).zip(embeddings).collect();
+            outcome.changed = match self.target {
+                ReembedTarget::Entities => self.graph.set_entity_embeddings(&rows).await?,
+                ReembedTarget::Claims => self.graph.set_claim_embeddings(&rows).await?,
+            };
+            Ok(outcome)
+        })
+    }
+}
+
+/// Rebuild the claim fulltext index, covering `original_content` per the
+/// `index_original_content` parameter (default: `source_language`'s).
+pub struct FulltextRebuild {
+    graph: Arc<GraphClient>,
+    index_original_content: bool,
+}
+
+impl FulltextRebuild {
+    pub fn new(graph: Arc<GraphClient>, index_original_content: bool) -> Self {
+        Self {
+            graph,
+            index_original_content,
+        }
+    }
+}
+
+impl JobKind for FulltextRebuild {
+    fn validate_params(&self, params: &Value) -> Result<(), String> {
+        match params.get("index_original_content") {
+            None | Some(Value::Bool(_)) => Ok(()),
+            Some(_) => Err("index_original_content must be a boolean".into()),
+        }
+    }
+
+    fn total<'a>(&'a self, _job: &'a MaintenanceJob) -> JobFuture<'a, Option<u64>> {
+        Box::pin(async { Ok(Some(1)) })
+    }
+
+    fn run_batch<'a>(
+        &'a self,
+        job: &'a MaintenanceJob,
+        _checkpoint: Option<&'a str>,
+        _limit: u32,
+    ) -> JobFuture<'a, BatchOutcome> {
+        Box::pin(async move {
+            let index_original_content = job
+                .params
+                .get("index_original_content")
+                .and_then(Value::as_bool)
+                .unwrap_or(self.index_original_content);
+            if !job.dry_run {
+                self.graph
+                    .rebuild_claim_fulltext(index_original_content)
+                    .await?;
+            }
+            Ok(BatchOutcome {
+                processed: 1,
+                changed: 1,
+                ..Default::default()
+            })
+        })
+    }
+}
+
+/// An entity kind in canonical form: trimmed, lowercase, with runs of
+/// spaces and hyphens as one underscore.
+pub fn normalize_kind(kind: &str) -> String {
+    kind.trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Rewrite entity kinds into [`normalize_kind`] form.
+pub struct KindNormalization {
+    graph: Arc<GraphClient>,
+}
+
+impl KindNormalization {
+    pub fn new(graph: Arc<GraphClient>) -> Self {
+        Self { graph }
+    }
+}
+
+impl JobKind for KindNormalization {
+    fn total<'a>(&'a self, _job: &'a MaintenanceJob) -> JobFuture<'a, Option<u64>> {
+        Box::pin(async move { Ok(Some(self.graph.count_entities().await?)) })
+    }
+
+    fn run_batch<'a>(
+        &'a self,
+        job: &'a MaintenanceJob,
+        checkpoint: Option<&'a str>,
+        limit: u32,
+    ) -> JobFuture<'a, BatchOutcome> {
+        Box::pin(async move {
+            let page = self.graph.entity_kind_page(checkpoint, limit).await?;
+            let mut outcome = BatchOutcome {
+                processed: page.len() as u64,
+                next: next_checkpoint(&page, limit, |(id, _)| id),
+                ..Default::default()
+            };
+            let mut updates = Vec::new();
+            for (id, kind) in &page {
+                let normalized = normalize_kind(kind);
+                if normalized.is_empty() {
+                    outcome.failures.push(format!("{id}: empty kind"));
+                } else if normalized != *kind {
+                    updates.push((id.clone(), normalized));
+                }
+            }
+            outcome.changed = if job.dry_run {
+                updates.len() as u64
+            } else {
+                self.graph.set_entity_kinds(&updates).await?
+            };
+            Ok(outcome)
+        })
+    }
+}
+
+/// Rewrite each entity's `aliases_text`, which the entity name fulltext
+/// index covers, from its `aliases` list where the two disagree.
+pub struct AliasesBackfill {
+    graph: Arc<GraphClient>,
+}
+
+impl AliasesBackfill {
+    pub fn new(graph: Arc<GraphClient>) -> Self {
+        Self { graph }
+    }
+}
+
+impl JobKind for AliasesBackfill {
+    fn total<'a>(&'a self, _job: &'a MaintenanceJob) -> JobFuture<'a, Option<u64>> {
+        Box::pin(async move { Ok(Some(self.graph.count_entities().await?)) })
+    }
+
+    fn run_batch<'a>(
+        &'a self,
+        job: &'a MaintenanceJob,
+        checkpoint: Option<&'a str>,
+        limit: u32,
+    ) -> JobFuture<'a, BatchOutcome> {
+        Box::pin(async move {
+            let page = self.graph.entity_aliases_page(checkpoint, limit).await?;
+            let mut outcome = BatchOutcome {
+                processed: page.len() as u64,
+                next: next_checkpoint(&page, limit, |(id, _, _)| id),
+                ..Default::default()
+            };
+            let mut updates = Vec::new();
+            for (id, aliases, aliases_text) in &page {
+                let aliases: Vec<String> = match aliases.as_deref() {
+                    None => Vec::new(),
+                    Some(json) => match serde_json::from_str(json) {
+                        Ok(aliases) => aliases,
+                        // The malformed_aliases consistency check repairs these.
+                        Err(_) => {
+                            outcome.failures.push(format!("{id}: malformed aliases"));
+                            continue;
+                        }
+                    },
+                };
+                let text = build_aliases_text(&aliases);
+                if aliases_text.as_deref() != Some(text.as_str()) {
+                    updates.push((id.clone(), text));
+                }
+            }
+            outcome.changed = if job.dry_run {
+                updates.len() as u64
+            } else {
+                self.graph.set_entity_aliases_text(&updates).await?
+            };
+            Ok(outcome)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_kind() {
+        assert_eq!(normalize_kind("vessel"), "vessel");
+        assert_eq!(normalize_kind("  Vessel "), "vessel");
+        assert_eq!(normalize_kind("Shell  Company"), "shell_company");
+        assert_eq!(
+            normalize_kind("state-owned enterprise"),
+            "state_owned_enterprise"
+        );
+        assert_eq!(normalize_kind(" - "), "");
+    }
+
+    #[test]
+    fn test_next_checkpoint() {
+        let page = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(next_checkpoint(&page, 2, |id| id).as_deref(), Some("b"));
+        // A short page is the last.
+        assert_eq!(next_checkpoint(&page, 3, |id| id), None);
+        assert_eq!(next_checkpoint(&Vec::<String>::new(), 3, |id| id), None);
+    }
+}
@@ -0,0 +1,455 @@
+//! Maintenance jobs: graph-wide work such as re-embedding or index rebuilds,
+//! started through POST /admin/jobs.
+//!
+//! A job type is a [`JobKind`] in the [`JobRegistry`]. The runner works
+//! through a job in batches, saving its progress and checkpoint after each,
+//! so an engine restarted mid-job resumes from the last checkpoint rather
+//! than starting over. Batches start at least `jobs.min_batch_interval_ms`
+//! apart, and a cancel takes effect between batches. One engine instance
+//! runs jobs at a time (the `maintenance_jobs` singleton lock), one job at a
+//! time.
+
+mod kinds;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use autosint_common::config::{MaintenanceJobConfig, SourceLanguageConfig};
+use autosint_common::types::{MaintenanceJob, MaintenanceJobStatus, MaintenanceJobType};
+
+use crate::embeddings::{EmbeddingClient, EmbeddingError};
+use crate::graph::{GraphClient, GraphError};
+use crate::maintenance::ReadOnlyMode;
+use crate::store::{StoreClient, StoreError};
+use crate::supervisor;
+
+pub use kinds::{normalize_kind, AliasesBackfill, FulltextRebuild, KindNormalization, Reembed};
+
+/// Singleton lock held while running jobs.
+const JOB_RUNNER_LOCK: &str = "maintenance_jobs";
+
+#[derive(Debug, thiserror::Error)]
+pub enum JobError {
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    #[error(transparent)]
+    Embedding(#[from] EmbeddingError),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+pub type JobFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, JobError>> + Send + 'a>>;
+
+/// What one batch of a job did.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchOutcome {
+    /// Items looked at.
+    pub processed: u64,
+    /// Items changed, or in a dry run the items that would have been.
+    pub changed: u64,
+    /// Items that failed, each as `id: error`.
+    pub failures: Vec<String>,
+    /// Where the next batch starts. None when this batch was the last.
+    pub next: Option<String>,
+}
+
+/// One type of maintenance job. Batches must be safe to repeat: a batch
+/// whose checkpoint wasn't saved before a restart runs again.
+pub trait JobKind: Send + Sync {
+    /// Refuse parameters this job type can't use. `batch_size` is checked
+    /// for every type.
+    fn validate_params(&self, _params: &Value) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Items the job will look at, for progress reports. None when unknown.
+    fn total<'a>(&'a self, job: &'a MaintenanceJob) -> JobFuture<'a, Option<u64>>;
+
+    /// Work through up to `limit` items, starting after `checkpoint` (from
+    /// the start when None). A dry run counts what it would change instead.
+    fn run_batch<'a>(
+        &'a self,
+        job: &'a MaintenanceJob,
+        checkpoint: Option<&'a str>,
+        limit: u32,
+    ) -> JobFuture<'a, BatchOutcome>;
+}
+
+/// The job types this engine can run.
+#[derive(Default)]
+pub struct JobRegistry {
+    kinds: HashMap<MaintenanceJobType, Arc<dyn JobKind>>,
+}
+
+impl JobRegistry {
+    /// The standard job types. The re-embedding jobs need `embedding_client`
+    /// and are left out without one.
+    pub fn standard(
+        graph: Arc<GraphClient>,
+        embedding_client: Option<Arc<EmbeddingClient>>,
+        source_language: &SourceLanguageConfig,
+    ) -> Self {
+        let mut registry = Self::default();
+        if let Some(client) = embedding_client {
+            registry.register(
+                MaintenanceJobType::ReembedEntities,
+                Reembed::entities(Arc::clone(&graph), Arc::clone(&client)),
+            );
+            registry.register(
+                MaintenanceJobType::ReembedClaims,
+                Reembed::claims(Arc::clone(&graph), client),
+            );
+        }
+        registry.register(
+            MaintenanceJobType::RebuildFulltext,
+            FulltextRebuild::new(Arc::clone(&graph), source_language.index_original_content),
+        );
+        registry.register(
+            MaintenanceJobType::NormalizeKinds,
+            KindNormalization::new(Arc::clone(&graph)),
+        );
+        registry.register(
+            MaintenanceJobType::BackfillAliasesList,
+            AliasesBackfill::new(graph),
+        );
+        registry
+    }
+
+    /// Run jobs of `job_type` with `kind`, replacing any registered before.
+    pub fn register(&mut self, job_type: MaintenanceJobType, kind: impl JobKind + 'static) {
+        self.kinds.insert(job_type, Arc::new(kind));
+    }
+
+    pub fn get(&self, job_type: MaintenanceJobType) -> Option<Arc<dyn JobKind>> {
+        self.kinds.get(&job_type).cloned()
+    }
+
+    /// Check that a job of `job_type` with `params` can be queued.
+    pub fn check(&self, job_type: MaintenanceJobType, params: &Value) -> Result<(), JobRefusal> {
+        let kind = self
+            .get(job_type)
+            .ok_or(JobRefusal::Unavailable(job_type))?;
+        if let Some(batch_size) = params.get("batch_size") {
+            if !batch_size
+                .as_u64()
+                .is_some_and(|n| n > 0 && n <= u32::MAX as u64)
+            {
+                return Err(JobRefusal::InvalidParams(
+                    "batch_size must be a positive integer".into(),
+                ));
+            }
+        }
+        kind.validate_params(params)
+            .map_err(JobRefusal::InvalidParams)
+    }
+}
+
+/// Why a job can't be queued.
+#[derive(Debug, thiserror::Error)]
+pub enum JobRefusal {
+    #[error("Job type {} is not available on this engine", .0.as_db_str())]
+    Unavailable(MaintenanceJobType),
+
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+}
+
+/// Spaces calls to [`BatchPacer::wait`] at least `interval` apart.
+pub struct BatchPacer {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl BatchPacer {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+        }
+    }
+
+    /// Wait until `interval` has passed since the previous call returned.
+    /// The first call returns at once.
+    pub async fn wait(&mut self) {
+        if let Some(last) = self.last {
+            tokio::time::sleep_until(last + self.interval).await;
+        }
+        self.last = Some(Instant::now());
+    }
+}
+
+/// Works through maintenance jobs.
+#[derive(Clone)]
+pub struct JobRunner {
+    store: Arc<StoreClient>,
+    registry: Arc<JobRegistry>,
+    config: MaintenanceJobConfig,
+}
+
+impl JobRunner {
+    pub fn new(
+        store: Arc<StoreClient>,
+        registry: Arc<JobRegistry>,
+        config: MaintenanceJobConfig,
+    ) -> Self {
+        Self {
+            store,
+            registry,
+            config,
+        }
+    }
+
+    /// Run jobs until none is left: interrupted ones first, then queued ones
+    /// oldest first. Returns how many were run.
+    pub async fn run_pending(&self) -> Result<u64, StoreError> {
+        let mut ran = 0;
+        while let Some(job) = self.store.next_maintenance_job().await? {
+            self.run_job(job).await?;
+            ran += 1;
+        }
+        Ok(ran)
+    }
+
+    /// Run `job` from its checkpoint until it completes, fails or is
+    /// cancelled. Returns it as it ended.
+    pub async fn run_job(&self, mut job: MaintenanceJob) -> Result<MaintenanceJob, StoreError> {
+        let Some(kind) = self.registry.get(job.job_type) else {
+            let error = JobRefusal::Unavailable(job.job_type).to_string();
+            return self
+                .finish(job, MaintenanceJobStatus::Failed, Some(error))
+                .await;
+        };
+        if job.cancel_requested {
+            return self
+                .finish(job, MaintenanceJobStatus::Cancelled, None)
+                .await;
+        }
+
+        if job.total.is_none() {
+            match kind.total(&job).await {
+                Ok(total) => job.total = total,
+                Err(e) => {
+                    let error = e.to_string();
+                    return self
+                        .finish(job, MaintenanceJobStatus::Failed, Some(error))
+                        .await;
+                }
+            }
+        }
+        self.store.start_maintenance_job(job.id, job.total).await?;
+        job.status = MaintenanceJobStatus::Running;
+        tracing::info!(
+            job_id = %job.id,
+            job_type = job.job_type.as_db_str(),
+            dry_run = job.dry_run,
+            resumed_from = job.checkpoint.as_deref(),
+            "Running maintenance job"
+        );
+
+        let batch_size = job
+            .params
+            .get("batch_size")
+            .and_then(Value::as_u64)
+            .map_or(self.config.batch_size, |n| n.min(u32::MAX as u64) as u32)
+            .max(1);
+        let mut pacer = BatchPacer::new(Duration::from_millis(self.config.min_batch_interval_ms));
+        loop {
+            pacer.wait().await;
+            let checkpoint = job.checkpoint.clone();
+            let outcome = match kind
+                .run_batch(&job, checkpoint.as_deref(), batch_size)
+                .await
+            {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    tracing::warn!(job_id = %job.id, error = %e, "Maintenance job failed");
+                    let error = e.to_string();
+                    return self
+                        .finish(job, MaintenanceJobStatus::Failed, Some(error))
+                        .await;
+                }
+            };
+            let done = outcome.next.is_none();
+            record_batch(&mut job, outcome, self.config.max_error_samples);
+            metrics::counter!("jobs.batches", "job_type" => job.job_type.as_db_str()).increment(1);
+
+            let cancel_requested = self.store.checkpoint_maintenance_job(&job).await?;
+            if done {
+                return self
+                    .finish(job, MaintenanceJobStatus::Completed, None)
+                    .await;
+            }
+            if cancel_requested {
+                return self
+                    .finish(job, MaintenanceJobStatus::Cancelled, None)
+                    .await;
+            }
+        }
+    }
+
+    async fn finish(
+        &self,
+        mut job: MaintenanceJob,
+        status: MaintenanceJobStatus,
+        error: Option<String>,
+    ) -> Result<MaintenanceJob, StoreError> {
+        self.store
+            .finish_maintenance_job(job.id, status, error.as_deref())
+            .await?;
+        tracing::info!(
+            job_id = %job.id,
+            job_type = job.job_type.as_db_str(),
+            status = status.as_db_str(),
+            processed = job.processed,
+            changed = job.changed,
+            errors = job.errors,
+            "Maintenance job finished"
+        );
+        job.status = status;
+        job.error = error;
+        job.finished_at = Some(chrono::Utc::now());
+        Ok(job)
+    }
+}
+
+/// Add a batch's counts to `job` and move its checkpoint on. The checkpoint
+/// stays at the last batch's start once the final batch is done, so a
+/// restart before the job is closed repeats only that batch.
+fn record_batch(job: &mut MaintenanceJob, outcome: BatchOutcome, max_error_samples: usize) {
+    job.processed += outcome.processed;
+    job.changed += outcome.changed;
+    job.errors += outcome.failures.len() as u64;
+    let room = max_error_samples.saturating_sub(job.error_samples.len());
+    job.error_samples
+        .extend(outcome.failures.into_iter().take(room));
+    if outcome.next.is_some() {
+        job.checkpoint = outcome.next;
+    }
+}
+
+/// Spawn the job runner: every `jobs.poll_interval_seconds` it runs any
+/// unfinished jobs, holding the `maintenance_jobs` singleton lock so only one
+/// engine instance does. Jobs wait while the engine is read-only.
+pub fn spawn_job_runner(runner: JobRunner, read_only: ReadOnlyMode) -> JoinHandle<()> {
+    let interval = Duration::from_secs(runner.config.poll_interval_seconds.max(1));
+    supervisor::spawn_supervised_restarting("maintenance_jobs", move || {
+        let runner = runner.clone();
+        let read_only = read_only.clone();
+        async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if read_only.is_enabled() {
+                    continue;
+                }
+                let store = Arc::clone(&runner.store);
+                match store
+                    .try_run_exclusive(JOB_RUNNER_LOCK, runner.run_pending())
+                    .await
+                {
+                    Ok(Some(Err(e))) => {
+                        tracing::error!(error = %e, "Maintenance job runner failed")
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to take the maintenance job lock")
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pacer_spaces_batches() {
+        let interval = Duration::from_millis(40);
+        let mut pacer = BatchPacer::new(interval);
+        let start = Instant::now();
+        pacer.wait().await;
+        assert!(start.elapsed() < interval, "first batch waits for nothing");
+        pacer.wait().await;
+        pacer.wait().await;
+        assert!(start.elapsed() >= interval * 2);
+
+        // Time already spent in a batch counts toward the interval.
+        tokio::time::sleep(interval).await;
+        let before = Instant::now();
+        pacer.wait().await;
+        assert!(before.elapsed() < interval);
+    }
+
+    #[test]
+    fn test_record_batch_keeps_samples_and_checkpoint() {
+        let mut job = MaintenanceJob::new(MaintenanceJobType::ReembedClaims, Value::Null, false);
+        record_batch(
+            &mut job,
+            BatchOutcome {
+                processed: 3,
+                changed: 2,
+                failures: vec!["a: failed".into(), "b: failed".into()],
+                next: Some("c".into()),
+            },
+            3,
+        );
+        record_batch(
+            &mut job,
+            BatchOutcome {
+                processed: 1,
+                changed: 0,
+                failures: vec!["d: failed".into(), "e: failed".into()],
+                next: None,
+            },
+            3,
+        );
+        assert_eq!((job.processed, job.changed, job.errors), (4, 2, 4));
+        assert_eq!(job.error_samples, ["a: failed", "b: failed", "d: failed"]);
+        // The last batch's start is kept until the job is closed.
+        assert_eq!(job.checkpoint.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_registry_checks_params() {
+        struct Noop;
+        impl JobKind for Noop {
+            fn total<'a>(&'a self, _job: &'a MaintenanceJob) -> JobFuture<'a, Option<u64>> {
+                Box::pin(async { Ok(None) })
+            }
+            fn run_batch<'a>(
+                &'a self,
+                _job: &'a MaintenanceJob,
+                _checkpoint: Option<&'a str>,
+                _limit: u32,
+            ) -> JobFuture<'a, BatchOutcome> {
+                Box::pin(async { Ok(BatchOutcome::default()) })
+            }
+        }
+
+        let mut registry = JobRegistry::default();
+        registry.register(MaintenanceJobType::NormalizeKinds, Noop);
+        let kinds = MaintenanceJobType::NormalizeKinds;
+        assert!(registry.check(kinds, &Value::Null).is_ok());
+        assert!(registry
+            .check(kinds, &serde_json::json!({"batch_size": 50}))
+            .is_ok());
+        assert!(matches!(
+            registry.check(kinds, &serde_json::json!({"batch_size": 0})),
+            Err(JobRefusal::InvalidParams(_))
+        ));
+        assert!(matches!(
+            registry.check(MaintenanceJobType::ReembedClaims, &Value::Null),
+            Err(JobRefusal::Unavailable(_))
+        ));
+    }
+}
@@ -1,11 +1,27 @@
 pub mod analyst;
+pub mod assessment_diff;
+pub mod audit;
 pub mod circuit_breaker;
 pub mod config;
 pub mod embeddings;
+pub mod events;
+pub mod flags;
 pub mod graph;
+pub mod http;
+pub mod ingest;
+pub mod jobs;
 pub mod llm;
+pub mod maintenance;
 pub mod orchestrator;
 pub mod processor;
 pub mod queue;
+pub mod report;
+pub mod retention;
+pub mod stix;
 pub mod store;
+pub mod stubs;
+pub mod supervisor;
+pub mod telemetry;
 pub mod tools;
+pub mod transfer;
+pub mod triage;
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use autosint_common::http_client::{self, Destination};
+
 use super::types::{
     ContentBlock, LlmResponse, Message, Role, StopReason, TokenUsage, ToolDefinition,
 };
@@ -163,6 +165,7 @@ fn from_wire_response(resp: AnthropicResponse) -> LlmResponse {
             input_tokens: resp.usage.input_tokens,
             output_tokens: resp.usage.output_tokens,
         },
+        served_by: None,
     }
 }
 
@@ -207,15 +210,16 @@ pub async fn send_messages(
         temperature,
     };
 
-    let response = http
-        .post(&url)
-        .header("x-api-key", api_key)
-        .header("anthropic-version", ANTHROPIC_VERSION)
-        .header("content-type", "application/json")
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| LlmError::Http(e.to_string()))?;
+    let response = http_client::send(
+        Destination::Llm,
+        http.post(&url)
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("content-type", "application/json")
+            .json(&request),
+    )
+    .await
+    .map_err(|e| LlmError::Http(e.to_string()))?;
 
     let status = response.status();
     let latency = start.elapsed().as_secs_f64();
@@ -253,6 +257,9 @@ pub async fn send_messages(
             }
             Err(_) => body,
         };
+        if status.is_server_error() {
+            return Err(LlmError::Server(format!("{}: {}", status, msg)));
+        }
         return Err(LlmError::Api(format!("{}: {}", status, msg)));
     }
 
@@ -0,0 +1,327 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use autosint_common::config::{LlmCacheConfig, LlmRoleConfig};
+
+use super::types::{LlmResponse, Message, ToolDefinition};
+
+/// Whether a role's responses are cached: only when opted in and at
+/// temperature 0, where a cached response stands in for a fresh one.
+pub fn caches_responses(config: &LlmRoleConfig) -> bool {
+    config.cache_responses && config.temperature == Some(0.0)
+}
+
+/// Hex SHA-256 identifying a request: provider, model, sampling settings and
+/// hashes of the system prompt, messages and tools. JSON object keys are
+/// sorted and tools ordered by name, so equal requests fingerprint the same
+/// however they were built.
+pub fn fingerprint(
+    config: &LlmRoleConfig,
+    system: &str,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+) -> String {
+    let mut tools: Vec<&ToolDefinition> = tools.iter().collect();
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let parts = [
+        config.provider.clone(),
+        config.model.clone(),
+        format!("{:?}", config.temperature),
+        config.max_tokens.to_string(),
+        hex_sha256(system),
+        hex_sha256(&canonical_json(messages)),
+        hex_sha256(&canonical_json(&tools)),
+    ];
+    hex_sha256(&parts.join("\n"))
+}
+
+fn hex_sha256(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// JSON with object keys sorted at every level.
+fn canonical_json<T: Serialize + ?Sized>(value: &T) -> String {
+    fn sorted(value: Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                Value::Object(entries.into_iter().map(|(k, v)| (k, sorted(v))).collect())
+            }
+            Value::Array(items) => Value::Array(items.into_iter().map(sorted).collect()),
+            other => other,
+        }
+    }
+    serde_json::to_value(value)
+        .map(sorted)
+        .map(|v| v.to_string())
+        .unwrap_or_default()
+}
+
+/// A stored response and when it was stored.
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    stored_at: DateTime<Utc>,
+    response: LlmResponse,
+}
+
+/// LLM responses on disk, one JSON file per request fingerprint. Best effort:
+/// unreadable entries are misses and failed writes are logged.
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl ResponseCache {
+    pub fn new(config: &LlmCacheConfig) -> Self {
+        Self {
+            dir: PathBuf::from(&config.dir),
+            ttl: Duration::seconds(config.ttl_seconds as i64),
+            max_entries: config.max_entries.max(1),
+        }
+    }
+
+    fn path(&self, fingerprint: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", fingerprint))
+    }
+
+    /// The cached response for `fingerprint`, unless missing or expired.
+    pub async fn get(&self, fingerprint: &str) -> Option<LlmResponse> {
+        let path = self.path(fingerprint);
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        let cached: CachedResponse = match serde_json::from_slice(&bytes) {
+            Ok(cached) => cached,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Unreadable LLM cache entry");
+                return None;
+            }
+        };
+        if Utc::now() - cached.stored_at > self.ttl {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+        Some(cached.response)
+    }
+
+    /// Store `response` under `fingerprint`, then evict the oldest entries
+    /// beyond `max_entries`.
+    pub async fn put(&self, fingerprint: &str, response: &LlmResponse) {
+        if let Err(e) = self.write(fingerprint, response).await {
+            tracing::warn!(dir = %self.dir.display(), error = %e, "Failed to write LLM cache entry");
+            return;
+        }
+        if let Err(e) = self.evict().await {
+            tracing::warn!(dir = %self.dir.display(), error = %e, "Failed to evict LLM cache entries");
+        }
+    }
+
+    async fn write(&self, fingerprint: &str, response: &LlmResponse) -> std::io::Result<()> {
+        let cached = CachedResponse {
+            stored_at: Utc::now(),
+            response: response.clone(),
+        };
+        let bytes = serde_json::to_vec(&cached)?;
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        // Write then rename, so a concurrent reader never sees a partial file.
+        let tmp = self
+            .dir
+            .join(format!("{}.{}.tmp", fingerprint, uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, self.path(fingerprint)).await
+    }
+
+    async fn evict(&self) -> std::io::Result<()> {
+        let mut entries = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if !is_entry(&path) {
+                continue;
+            }
+            let modified = entry.metadata().await?.modified()?;
+            entries.push((modified, path));
+        }
+        if entries.len() <= self.max_entries {
+            return Ok(());
+        }
+
+        entries.sort();
+        let excess = entries.len() - self.max_entries;
+        for (_, path) in entries.into_iter().take(excess) {
+            tokio::fs::remove_file(path).await?;
+        }
+        Ok(())
+    }
+}
+
+fn is_entry(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::llm::types::{ContentBlock, Role, StopReason, TokenUsage};
+
+    fn role(temperature: Option<f64>) -> LlmRoleConfig {
+        LlmRoleConfig {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4-20250514".into(),
+            max_tokens: 1024,
+            temperature,
+            base_url: None,
+            api_key_env: None,
+            cache_responses: true,
+            response_cache: LlmCacheConfig::default(),
+            capabilities: None,
+            fallback: None,
+        }
+    }
+
+    fn tool(name: &str, input_schema: Value) -> ToolDefinition {
+        ToolDefinition {
+            name: name.into(),
+            description: format!("The {} tool.", name),
+            input_schema,
+        }
+    }
+
+    fn messages(input: Value) -> Vec<Message> {
+        vec![Message {
+            role: Role::Assistant,
+            content: vec![ContentBlock::ToolUse {
+                id: "call_1".into(),
+                name: "search_entities".into(),
+                input,
+            }],
+        }]
+    }
+
+    fn response(text: &str) -> LlmResponse {
+        LlmResponse {
+            content: vec![ContentBlock::Text { text: text.into() }],
+            stop_reason: StopReason::EndTurn,
+            usage: TokenUsage {
+                input_tokens: 120,
+                output_tokens: 8,
+            },
+            served_by: None,
+        }
+    }
+
+    fn cache(max_entries: usize) -> ResponseCache {
+        let dir = std::env::temp_dir().join(format!("autosint-llm-cache-{}", uuid::Uuid::new_v4()));
+        ResponseCache::new(&LlmCacheConfig {
+            dir: dir.to_string_lossy().into_owned(),
+            ttl_seconds: 3600,
+            max_entries,
+        })
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_key_and_tool_order() {
+        let config = role(Some(0.0));
+        let schema_a: Value =
+            serde_json::from_str(r#"{"type": "object", "properties": {"q": {"type": "string"}}}"#)
+                .unwrap();
+        let schema_b: Value =
+            serde_json::from_str(r#"{"properties": {"q": {"type": "string"}}, "type": "object"}"#)
+                .unwrap();
+        let input_a: Value = serde_json::from_str(r#"{"query": "Acme", "limit": 5}"#).unwrap();
+        let input_b: Value = serde_json::from_str(r#"{"limit": 5, "query": "Acme"}"#).unwrap();
+
+        let a = fingerprint(
+            &config,
+            "system",
+            &messages(input_a),
+            &[tool("alpha", schema_a.clone()), tool("beta", json!({}))],
+        );
+        let b = fingerprint(
+            &config,
+            "system",
+            &messages(input_b),
+            &[tool("beta", json!({})), tool("alpha", schema_b)],
+        );
+        assert_eq!(a, b);
+
+        let other_model = LlmRoleConfig {
+            model: "claude-opus-4-20250514".into(),
+            ..config.clone()
+        };
+        let c = fingerprint(
+            &other_model,
+            "system",
+            &messages(json!({"query": "Acme", "limit": 5})),
+            &[tool("alpha", schema_a), tool("beta", json!({}))],
+        );
+        assert_ne!(a, c);
+        assert_ne!(
+            a,
+            fingerprint(&config, "other system", &messages(json!({})), &[])
+        );
+    }
+
+    #[test]
+    fn test_caches_only_at_temperature_zero() {
+        assert!(caches_responses(&role(Some(0.0))));
+        assert!(!caches_responses(&role(Some(0.7))));
+        assert!(!caches_responses(&role(None)));
+        let off = LlmRoleConfig {
+            cache_responses: false,
+            ..role(Some(0.0))
+        };
+        assert!(!caches_responses(&off));
+    }
+
+    #[tokio::test]
+    async fn test_hit_miss_and_expiry() {
+        let cache = cache(10);
+        assert!(cache.get("abc").await.is_none());
+
+        cache.put("abc", &response("cached answer")).await;
+        let hit = cache.get("abc").await.unwrap();
+        assert!(matches!(&hit.content[0], ContentBlock::Text { text } if text == "cached answer"));
+        assert_eq!(hit.stop_reason, StopReason::EndTurn);
+        assert_eq!(hit.usage.input_tokens, 120);
+        assert!(cache.get("def").await.is_none());
+
+        let expired = ResponseCache {
+            ttl: Duration::zero(),
+            ..cache
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        assert!(expired.get("abc").await.is_none());
+        assert!(!expired.path("abc").exists(), "expired entries are removed");
+
+        let _ = std::fs::remove_dir_all(&expired.dir);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_oldest_beyond_cap() {
+        let cache = cache(2);
+        for key in ["first", "second", "third"] {
+            cache.put(key, &response(key)).await;
+            // Distinct modification times.
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        assert!(cache.get("first").await.is_none());
+        assert!(cache.get("second").await.is_some());
+        assert!(cache.get("third").await.is_some());
+
+        let _ = std::fs::remove_dir_all(&cache.dir);
+    }
+}
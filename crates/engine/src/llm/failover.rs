@@ -0,0 +1,359 @@
+//! Failover from a role's primary provider to its fallback during outages.
+//!
+//! After an outage-class error (once the primary's own retries are used up),
+//! requests stick to the fallback for a cool-down rather than trying the
+//! primary every turn. Then a single request probes the primary; it takes
+//! over again once it answers.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{
+    ContextBudget, LlmCaller, LlmError, LlmResponse, Message, TokenCounter, ToolDefinition,
+};
+
+/// The primary's health as seen by this client.
+#[derive(Debug)]
+enum Health {
+    Up,
+    /// Requests go to the fallback until `until`.
+    Down {
+        until: Instant,
+    },
+    /// One request is trying the primary again. A probe older than the
+    /// cool-down was abandoned, and another may start.
+    Probing {
+        since: Instant,
+    },
+}
+
+enum Route {
+    Primary,
+    Probe,
+    Fallback,
+}
+
+/// A caller sending requests to `primary`, or to `fallback` while the primary
+/// is down.
+pub(super) struct Failover {
+    primary: Arc<dyn LlmCaller>,
+    fallback: Arc<dyn LlmCaller>,
+    cooldown: Duration,
+    health: Mutex<Health>,
+}
+
+impl Failover {
+    pub(super) fn new(
+        primary: Arc<dyn LlmCaller>,
+        fallback: Arc<dyn LlmCaller>,
+        cooldown: Duration,
+    ) -> Self {
+        Self {
+            primary,
+            fallback,
+            cooldown,
+            health: Mutex::new(Health::Up),
+        }
+    }
+
+    fn route(&self) -> Route {
+        let mut health = self.health.lock().unwrap();
+        let now = Instant::now();
+        match *health {
+            Health::Up => Route::Primary,
+            Health::Down { until } if now < until => Route::Fallback,
+            Health::Probing { since } if now < since + self.cooldown => Route::Fallback,
+            _ => {
+                *health = Health::Probing { since: now };
+                Route::Probe
+            }
+        }
+    }
+
+    /// The primary answered, if only with an error about the request.
+    fn primary_up(&self, probe: bool) {
+        let mut health = self.health.lock().unwrap();
+        if probe && !matches!(*health, Health::Up) {
+            tracing::info!("Primary LLM provider recovered — leaving fallback");
+            metrics::counter!("llm.failover.recoveries").increment(1);
+        }
+        *health = Health::Up;
+    }
+
+    fn primary_down(&self, error: &LlmError, probe: bool) {
+        let until = Instant::now() + self.cooldown;
+        *self.health.lock().unwrap() = Health::Down { until };
+        if probe {
+            tracing::warn!(error = %error, "Primary LLM provider still failing — staying on fallback");
+        } else {
+            tracing::warn!(
+                error = %error,
+                cooldown_secs = self.cooldown.as_secs(),
+                "Primary LLM provider outage — failing over to fallback"
+            );
+            metrics::counter!("llm.failovers").increment(1);
+        }
+    }
+
+    async fn chat_fallback(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Result<LlmResponse, LlmError> {
+        let mut response = self.fallback.chat(system, messages, tools).await?;
+        if let Some(served_by) = &mut response.served_by {
+            served_by.fallback = true;
+        }
+        metrics::counter!("llm.fallback.responses").increment(1);
+        Ok(response)
+    }
+
+    async fn chat(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Result<LlmResponse, LlmError> {
+        let probe = match self.route() {
+            Route::Fallback => return self.chat_fallback(system, messages, tools).await,
+            Route::Primary => false,
+            Route::Probe => true,
+        };
+        match self.primary.chat(system, messages, tools).await {
+            Err(e) if e.is_outage() => {
+                self.primary_down(&e, probe);
+                self.chat_fallback(system, messages, tools).await
+            }
+            // Auth and request errors would fail on the fallback too, differently.
+            result => {
+                self.primary_up(probe);
+                result
+            }
+        }
+    }
+}
+
+impl LlmCaller for Failover {
+    fn chat<'a>(
+        &'a self,
+        system: &'a str,
+        messages: &'a [Message],
+        tools: &'a [ToolDefinition],
+    ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+        Box::pin(self.chat(system, messages, tools))
+    }
+
+    fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        self.primary.token_counter()
+    }
+
+    /// The smaller of the two providers' budgets, so a history built on the
+    /// primary still fits after failing over.
+    fn context_budget(&self, fraction: f64) -> Option<ContextBudget> {
+        match (
+            self.primary.context_budget(fraction),
+            self.fallback.context_budget(fraction),
+        ) {
+            (Some(primary), Some(fallback)) => {
+                if fallback.max_input_tokens() < primary.max_input_tokens() {
+                    Some(fallback)
+                } else {
+                    Some(primary)
+                }
+            }
+            (primary, fallback) => primary.or(fallback),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::llm::{ContentBlock, ServedBy, StopReason, TokenUsage};
+
+    /// Pops queued results, then answers with its provider name.
+    struct MockLlm {
+        provider: &'static str,
+        responses: Mutex<Vec<Result<LlmResponse, LlmError>>>,
+        calls: AtomicU32,
+    }
+
+    impl MockLlm {
+        fn new(provider: &'static str, responses: Vec<Result<LlmResponse, LlmError>>) -> Arc<Self> {
+            let mut responses = responses;
+            responses.reverse();
+            Arc::new(Self {
+                provider,
+                responses: Mutex::new(responses),
+                calls: AtomicU32::new(0),
+            })
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    impl LlmCaller for MockLlm {
+        fn chat<'a>(
+            &'a self,
+            _system: &'a str,
+            _messages: &'a [Message],
+            _tools: &'a [ToolDefinition],
+        ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let result = self.responses.lock().unwrap().pop().unwrap_or_else(|| {
+                Ok(LlmResponse {
+                    content: vec![ContentBlock::Text {
+                        text: self.provider.into(),
+                    }],
+                    stop_reason: StopReason::EndTurn,
+                    usage: TokenUsage::default(),
+                    served_by: Some(ServedBy {
+                        provider: self.provider.into(),
+                        model: format!("{}-model", self.provider),
+                        fallback: false,
+                    }),
+                })
+            });
+            Box::pin(async move { result })
+        }
+    }
+
+    fn failover(primary: &Arc<MockLlm>, fallback: &Arc<MockLlm>, cooldown: Duration) -> Failover {
+        Failover::new(
+            Arc::clone(primary) as Arc<dyn LlmCaller>,
+            Arc::clone(fallback) as Arc<dyn LlmCaller>,
+            cooldown,
+        )
+    }
+
+    /// The provider that served a successful request, and whether it was
+    /// marked as the fallback.
+    async fn served_by(failover: &Failover) -> (String, bool) {
+        let response = failover.chat("system", &[], &[]).await.unwrap();
+        let served_by = response.served_by.unwrap();
+        (served_by.provider, served_by.fallback)
+    }
+
+    const LONG: Duration = Duration::from_secs(300);
+
+    #[tokio::test]
+    async fn test_fails_over_on_outage_errors() {
+        for error in [
+            LlmError::Server("529 Overloaded: overloaded_error".into()),
+            LlmError::Http("operation timed out".into()),
+            LlmError::RateLimited { retry_after: None },
+        ] {
+            let primary = MockLlm::new("anthropic", vec![Err(error)]);
+            let fallback = MockLlm::new("openai", vec![]);
+            let failover = failover(&primary, &fallback, LONG);
+
+            assert_eq!(served_by(&failover).await, ("openai".into(), true));
+            assert_eq!(primary.calls(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_failover_on_request_errors() {
+        for error in [
+            LlmError::Auth("401 Unauthorized: invalid x-api-key".into()),
+            LlmError::ContextWindowExceeded("prompt is too long".into()),
+            LlmError::Api("400 Bad Request: invalid message".into()),
+        ] {
+            let expected = error.to_string();
+            let primary = MockLlm::new("anthropic", vec![Err(error)]);
+            let fallback = MockLlm::new("openai", vec![]);
+            let failover = failover(&primary, &fallback, LONG);
+
+            let err = failover.chat("system", &[], &[]).await.unwrap_err();
+            assert_eq!(err.to_string(), expected);
+            assert_eq!(fallback.calls(), 0);
+            // The primary stays in use.
+            assert_eq!(served_by(&failover).await, ("anthropic".into(), false));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sticks_to_fallback_during_cooldown() {
+        let primary = MockLlm::new("anthropic", vec![Err(LlmError::Server("500".into()))]);
+        let fallback = MockLlm::new("openai", vec![]);
+        let failover = failover(&primary, &fallback, LONG);
+
+        for _ in 0..3 {
+            assert_eq!(served_by(&failover).await, ("openai".into(), true));
+        }
+        // The primary would answer now, but isn't asked until the cool-down ends.
+        assert_eq!(primary.calls(), 1);
+        assert_eq!(fallback.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fallback_errors_returned() {
+        let primary = MockLlm::new("anthropic", vec![Err(LlmError::Server("503".into()))]);
+        let fallback = MockLlm::new(
+            "openai",
+            vec![Err(LlmError::Auth("401 Unauthorized".into()))],
+        );
+        let failover = failover(&primary, &fallback, LONG);
+
+        let err = failover.chat("system", &[], &[]).await.unwrap_err();
+        assert!(matches!(err, LlmError::Auth(_)));
+        assert_eq!(primary.calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_probe_recovers_primary() {
+        let cooldown = Duration::from_millis(30);
+        let primary = MockLlm::new(
+            "anthropic",
+            vec![
+                Err(LlmError::Server("500".into())),
+                // The first probe finds it still down.
+                Err(LlmError::Http("connection reset".into())),
+            ],
+        );
+        let fallback = MockLlm::new("openai", vec![]);
+        let failover = failover(&primary, &fallback, cooldown);
+
+        assert_eq!(served_by(&failover).await, ("openai".into(), true));
+        tokio::time::sleep(cooldown * 2).await;
+        assert_eq!(served_by(&failover).await, ("openai".into(), true));
+        assert_eq!(primary.calls(), 2);
+        // A failed probe starts a fresh cool-down.
+        assert_eq!(served_by(&failover).await, ("openai".into(), true));
+        assert_eq!(primary.calls(), 2);
+
+        tokio::time::sleep(cooldown * 2).await;
+        assert_eq!(served_by(&failover).await, ("anthropic".into(), false));
+        assert_eq!(served_by(&failover).await, ("anthropic".into(), false));
+        assert_eq!(primary.calls(), 4);
+        assert_eq!(fallback.calls(), 3);
+    }
+
+    #[test]
+    fn test_probe_routing() {
+        let primary = MockLlm::new("anthropic", vec![]);
+        let fallback = MockLlm::new("openai", vec![]);
+        let failover = failover(&primary, &fallback, LONG);
+
+        *failover.health.lock().unwrap() = Health::Down {
+            until: Instant::now(),
+        };
+        assert!(matches!(failover.route(), Route::Probe));
+        // Only one request probes at a time.
+        assert!(matches!(failover.route(), Route::Fallback));
+
+        // A probe abandoned for longer than the cool-down is retried.
+        let failover = self::failover(&primary, &fallback, Duration::ZERO);
+        *failover.health.lock().unwrap() = Health::Probing {
+            since: Instant::now(),
+        };
+        assert!(matches!(failover.route(), Route::Probe));
+    }
+}
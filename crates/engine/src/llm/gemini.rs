@@ -0,0 +1,592 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use autosint_common::http_client::{self, Destination};
+
+use super::types::{
+    ContentBlock, LlmResponse, Message, Role, StopReason, TokenUsage, ToolDefinition,
+};
+use super::LlmError;
+
+// ---------------------------------------------------------------------------
+// Request wire types
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentRequest {
+    system_instruction: GeminiContent,
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<GeminiTool>,
+    generation_config: GenerationConfig,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GeminiContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+/// A content part. Gemini parts are objects with exactly one populated field.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_call: Option<GeminiFunctionCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_response: Option<GeminiFunctionResponse>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GeminiFunctionCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GeminiFunctionResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    response: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiTool {
+    function_declarations: Vec<GeminiFunctionDeclaration>,
+}
+
+#[derive(Serialize)]
+struct GeminiFunctionDeclaration {
+    name: String,
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerationConfig {
+    max_output_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+}
+
+// ---------------------------------------------------------------------------
+// Response wire types
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+}
+
+#[derive(Deserialize)]
+struct GeminiError {
+    error: GeminiErrorDetail,
+}
+
+#[derive(Deserialize)]
+struct GeminiErrorDetail {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    details: Vec<Value>,
+}
+
+// ---------------------------------------------------------------------------
+// Conversion helpers
+// ---------------------------------------------------------------------------
+
+/// Convert conversation history to Gemini `contents`.
+///
+/// Gemini rejects consecutive turns with the same role, so adjacent messages with
+/// the same role are merged. `functionResponse` parts must carry the function
+/// name, which our ToolResult blocks don't have — it is recovered from the
+/// matching ToolUse block earlier in the history.
+fn to_wire_contents(messages: &[Message]) -> Vec<GeminiContent> {
+    let mut tool_names: HashMap<&str, &str> = HashMap::new();
+    let mut contents: Vec<GeminiContent> = Vec::new();
+
+    for msg in messages {
+        let role = match msg.role {
+            Role::User => "user",
+            Role::Assistant => "model",
+        };
+
+        let mut parts = Vec::new();
+        for block in &msg.content {
+            match block {
+                ContentBlock::Text { text } => parts.push(GeminiPart {
+                    text: Some(text.clone()),
+                    ..Default::default()
+                }),
+                ContentBlock::ToolUse { id, name, input } => {
+                    tool_names.insert(id.as_str(), name.as_str());
+                    parts.push(GeminiPart {
+                        function_call: Some(GeminiFunctionCall {
+                            id: Some(id.clone()),
+                            name: name.clone(),
+                            args: input.clone(),
+                        }),
+                        ..Default::default()
+                    });
+                }
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } => {
+                    let name = tool_names
+                        .get(tool_use_id.as_str())
+                        .copied()
+                        .unwrap_or("unknown_tool");
+                    parts.push(GeminiPart {
+                        function_response: Some(GeminiFunctionResponse {
+                            id: Some(tool_use_id.clone()),
+                            name: name.to_string(),
+                            response: to_function_response(content, is_error.unwrap_or(false)),
+                        }),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        if parts.is_empty() {
+            continue;
+        }
+
+        match contents.last_mut() {
+            Some(last) if last.role.as_deref() == Some(role) => last.parts.extend(parts),
+            _ => contents.push(GeminiContent {
+                role: Some(role.to_string()),
+                parts,
+            }),
+        }
+    }
+
+    contents
+}
+
+/// `functionResponse.response` must be a JSON object. Tool results are JSON text
+/// (usually an object); anything else is wrapped.
+fn to_function_response(content: &str, is_error: bool) -> Value {
+    if is_error {
+        return serde_json::json!({ "error": content });
+    }
+    match serde_json::from_str::<Value>(content) {
+        Ok(Value::Object(map)) => Value::Object(map),
+        Ok(other) => serde_json::json!({ "content": other }),
+        Err(_) => serde_json::json!({ "content": content }),
+    }
+}
+
+/// Gemini accepts an OpenAPI subset for function parameters and rejects object
+/// schemas with no properties, so parameterless tools omit `parameters` entirely.
+fn to_function_parameters(schema: &Value) -> Option<Value> {
+    let has_properties = schema
+        .get("properties")
+        .and_then(|p| p.as_object())
+        .is_some_and(|p| !p.is_empty());
+    if !has_properties {
+        return None;
+    }
+
+    let mut schema = schema.clone();
+    strip_unsupported_schema_keys(&mut schema);
+    Some(schema)
+}
+
+fn strip_unsupported_schema_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("$schema");
+            map.remove("additionalProperties");
+            for v in map.values_mut() {
+                strip_unsupported_schema_keys(v);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_unsupported_schema_keys),
+        _ => {}
+    }
+}
+
+fn from_wire_response(resp: GenerateContentResponse) -> Result<LlmResponse, LlmError> {
+    let candidate = resp
+        .candidates
+        .into_iter()
+        .next()
+        .ok_or_else(|| LlmError::Parse("Empty candidates array".into()))?;
+
+    let mut content = Vec::new();
+    for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+        if let Some(text) = part.text {
+            if !text.is_empty() {
+                content.push(ContentBlock::Text { text });
+            }
+        }
+        if let Some(call) = part.function_call {
+            let input = match call.args {
+                Value::Null => Value::Object(serde_json::Map::new()),
+                args => args,
+            };
+            content.push(ContentBlock::ToolUse {
+                id: call
+                    .id
+                    .unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple())),
+                name: call.name,
+                input,
+            });
+        }
+    }
+
+    // Gemini reports STOP for function calls too; tool use is inferred from the parts.
+    let has_tool_use = content
+        .iter()
+        .any(|b| matches!(b, ContentBlock::ToolUse { .. }));
+    let stop_reason = match candidate.finish_reason.as_deref() {
+        _ if has_tool_use => StopReason::ToolUse,
+        Some("MAX_TOKENS") => StopReason::MaxTokens,
+        _ => StopReason::EndTurn,
+    };
+
+    let usage = resp
+        .usage_metadata
+        .map_or_else(TokenUsage::default, |u| TokenUsage {
+            input_tokens: u.prompt_token_count,
+            output_tokens: u.candidates_token_count,
+        });
+
+    Ok(LlmResponse {
+        content,
+        stop_reason,
+        usage,
+        served_by: None,
+    })
+}
+
+/// Extract the retry delay (seconds, rounded up) from a 429 body's
+/// `google.rpc.RetryInfo` detail, e.g. `"retryDelay": "37s"`.
+fn parse_retry_delay(body: &str) -> Option<u64> {
+    let error = serde_json::from_str::<GeminiError>(body).ok()?;
+    error.error.details.iter().find_map(|detail| {
+        let is_retry_info = detail
+            .get("@type")
+            .and_then(|t| t.as_str())
+            .is_some_and(|t| t.ends_with("RetryInfo"));
+        if !is_retry_info {
+            return None;
+        }
+        let delay = detail.get("retryDelay")?.as_str()?;
+        let secs: f64 = delay.trim_end_matches('s').parse().ok()?;
+        Some(secs.ceil() as u64)
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+#[allow(clippy::too_many_arguments)]
+/// Send a generateContent request to the Google Gemini API.
+pub async fn send_generate_content(
+    http: &reqwest::Client,
+    api_key: &str,
+    base_url: &str,
+    model: &str,
+    max_tokens: u32,
+    temperature: Option<f64>,
+    system: &str,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+) -> Result<LlmResponse, LlmError> {
+    let start = std::time::Instant::now();
+
+    let url = format!(
+        "{}/v1beta/models/{}:generateContent",
+        base_url.trim_end_matches('/'),
+        model
+    );
+
+    let function_declarations: Vec<GeminiFunctionDeclaration> = tools
+        .iter()
+        .map(|t| GeminiFunctionDeclaration {
+            name: t.name.clone(),
+            description: t.description.clone(),
+            parameters: to_function_parameters(&t.input_schema),
+        })
+        .collect();
+
+    let request = GenerateContentRequest {
+        system_instruction: GeminiContent {
+            role: None,
+            parts: vec![GeminiPart {
+                text: Some(system.to_string()),
+                ..Default::default()
+            }],
+        },
+        contents: to_wire_contents(messages),
+        tools: if function_declarations.is_empty() {
+            Vec::new()
+        } else {
+            vec![GeminiTool {
+                function_declarations,
+            }]
+        },
+        generation_config: GenerationConfig {
+            max_output_tokens: max_tokens,
+            temperature,
+        },
+    };
+
+    let response = http_client::send(
+        Destination::Llm,
+        http.post(&url)
+            .header("x-goog-api-key", api_key)
+            .json(&request),
+    )
+    .await
+    .map_err(|e| LlmError::Http(e.to_string()))?;
+
+    let status = response.status();
+    let latency = start.elapsed().as_secs_f64();
+    metrics::histogram!("llm.api.latency", "provider" => "gemini", "model" => model.to_string())
+        .record(latency);
+
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        let body = response.text().await.unwrap_or_default();
+        return Err(LlmError::Auth(format!("{}: {}", status, body)));
+    }
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        // Gemini signals the backoff in the body (RetryInfo), not a Retry-After header.
+        let header_retry = response
+            .headers()
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok());
+        let body = response.text().await.unwrap_or_default();
+        let retry_after = parse_retry_delay(&body).or(header_retry);
+        return Err(LlmError::RateLimited { retry_after });
+    }
+
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let msg = match serde_json::from_str::<GeminiError>(&body) {
+            Ok(e) => e.error.message,
+            Err(_) => body,
+        };
+        // Invalid keys come back as 400 INVALID_ARGUMENT rather than 401.
+        if msg.contains("API key not valid") {
+            return Err(LlmError::Auth(format!("{}: {}", status, msg)));
+        }
+        if msg.contains("exceeds the maximum number of tokens") {
+            return Err(LlmError::ContextWindowExceeded(msg));
+        }
+        if status.is_server_error() {
+            return Err(LlmError::Server(format!("{}: {}", status, msg)));
+        }
+        return Err(LlmError::Api(format!("{}: {}", status, msg)));
+    }
+
+    let body: GenerateContentResponse = response
+        .json()
+        .await
+        .map_err(|e| LlmError::Parse(format!("Failed to parse Gemini response: {}", e)))?;
+
+    let llm_response = from_wire_response(body)?;
+
+    metrics::counter!("llm.api.input_tokens", "provider" => "gemini")
+        .increment(llm_response.usage.input_tokens);
+    metrics::counter!("llm.api.output_tokens", "provider" => "gemini")
+        .increment(llm_response.usage.output_tokens);
+
+    Ok(llm_response)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gemini_text_response() {
+        let json = r#"{
+            "candidates": [{
+                "content": {"role": "model", "parts": [{"text": "Hello world"}]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 10, "candidatesTokenCount": 5, "totalTokenCount": 15}
+        }"#;
+
+        let resp: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = from_wire_response(resp).unwrap();
+
+        assert_eq!(parsed.stop_reason, StopReason::EndTurn);
+        assert_eq!(parsed.usage.input_tokens, 10);
+        assert_eq!(parsed.usage.output_tokens, 5);
+        assert_eq!(parsed.content.len(), 1);
+        match &parsed.content[0] {
+            ContentBlock::Text { text } => assert_eq!(text, "Hello world"),
+            _ => panic!("Expected text block"),
+        }
+    }
+
+    #[test]
+    fn test_parse_gemini_function_call_response() {
+        let json = r#"{
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{"functionCall": {"name": "search_entities", "args": {"query": "TSMC"}}}]
+                },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 100, "candidatesTokenCount": 50}
+        }"#;
+
+        let resp: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = from_wire_response(resp).unwrap();
+
+        assert_eq!(parsed.stop_reason, StopReason::ToolUse);
+        assert_eq!(parsed.content.len(), 1);
+        match &parsed.content[0] {
+            ContentBlock::ToolUse { id, name, input } => {
+                assert!(id.starts_with("call_"));
+                assert_eq!(name, "search_entities");
+                assert_eq!(input["query"], "TSMC");
+            }
+            _ => panic!("Expected tool_use block"),
+        }
+    }
+
+    #[test]
+    fn test_parse_gemini_max_tokens() {
+        let json = r#"{
+            "candidates": [{
+                "content": {"role": "model", "parts": [{"text": "Trunc"}]},
+                "finishReason": "MAX_TOKENS"
+            }]
+        }"#;
+
+        let resp: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = from_wire_response(resp).unwrap();
+        assert_eq!(parsed.stop_reason, StopReason::MaxTokens);
+        assert_eq!(parsed.usage.input_tokens, 0);
+    }
+
+    #[test]
+    fn test_message_wire_roundtrip() {
+        let messages = vec![
+            Message {
+                role: Role::User,
+                content: vec![ContentBlock::Text {
+                    text: "Find TSMC".into(),
+                }],
+            },
+            Message {
+                role: Role::Assistant,
+                content: vec![ContentBlock::ToolUse {
+                    id: "call_1".into(),
+                    name: "search_entities".into(),
+                    input: serde_json::json!({"query": "TSMC"}),
+                }],
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentBlock::ToolResult {
+                    tool_use_id: "call_1".into(),
+                    content: r#"{"results": []}"#.into(),
+                    is_error: None,
+                }],
+            },
+            // Consecutive user turn — must be merged into the previous one.
+            Message {
+                role: Role::User,
+                content: vec![ContentBlock::Text {
+                    text: "Continue.".into(),
+                }],
+            },
+        ];
+
+        let wire = to_wire_contents(&messages);
+        assert_eq!(wire.len(), 3);
+        assert_eq!(wire[0].role.as_deref(), Some("user"));
+        assert_eq!(wire[1].role.as_deref(), Some("model"));
+        assert_eq!(wire[2].role.as_deref(), Some("user"));
+        assert_eq!(wire[2].parts.len(), 2);
+
+        let response = wire[2].parts[0].function_response.as_ref().unwrap();
+        assert_eq!(response.name, "search_entities");
+        assert_eq!(response.response["results"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_parse_retry_delay_from_body() {
+        let body = r#"{
+            "error": {
+                "code": 429,
+                "message": "Resource has been exhausted",
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [
+                    {"@type": "type.googleapis.com/google.rpc.QuotaFailure", "violations": []},
+                    {"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "36.5s"}
+                ]
+            }
+        }"#;
+        assert_eq!(parse_retry_delay(body), Some(37));
+        assert_eq!(parse_retry_delay("not json"), None);
+    }
+
+    #[test]
+    fn test_parameterless_tool_omits_parameters() {
+        let empty = serde_json::json!({"type": "object", "properties": {}, "required": []});
+        assert!(to_function_parameters(&empty).is_none());
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {"query": {"type": "string"}}
+        });
+        let params = to_function_parameters(&schema).unwrap();
+        assert!(params.get("additionalProperties").is_none());
+        assert_eq!(params["properties"]["query"]["type"], "string");
+    }
+}
@@ -0,0 +1,176 @@
+//! Tool-call loop detection for `run_session`.
+//!
+//! A model can get stuck making the same valid call turn after turn
+//! (`search_entities` for one name ten times), burning its turn budget
+//! without tripping the malformed-call limit. Each call is reduced to a
+//! signature — the tool name and its arguments with whitespace collapsed,
+//! nulls and `limit` dropped and keys sorted — and the last [`LOOP_WINDOW`]
+//! signatures are kept. A call whose signature already fills its
+//! `max_repeats` is answered with a nudge instead of being executed again;
+//! after `max_nudges` nudges the session is ended.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use serde_json::{Map, Value};
+
+/// Recent calls a repeat is counted over.
+pub const LOOP_WINDOW: usize = 20;
+
+/// Top-level arguments that only change how much of the same answer comes
+/// back, ignored when comparing calls.
+const IGNORED_ARGS: &[&str] = &["limit"];
+
+/// Thresholds for loop detection (`safety.max_repeated_tool_calls` and
+/// `safety.max_tool_loop_nudges`).
+#[derive(Clone, Copy, Debug)]
+pub struct LoopLimits {
+    /// Identical calls executed within the window before repeats are nudged.
+    pub max_repeats: u32,
+    /// Nudges before the session ends.
+    pub max_nudges: u32,
+}
+
+/// Per-session record of recent call signatures.
+pub struct LoopGuard {
+    limits: LoopLimits,
+    window: VecDeque<u64>,
+    nudges: u32,
+}
+
+impl LoopGuard {
+    pub fn new(limits: LoopLimits) -> Self {
+        Self {
+            limits,
+            window: VecDeque::with_capacity(LOOP_WINDOW),
+            nudges: 0,
+        }
+    }
+
+    /// Record a call. Returns true if it should be nudged rather than
+    /// executed: the same call was already made `max_repeats` times within
+    /// the window. Nudged calls stay in the window, so continuing to repeat
+    /// keeps being caught.
+    pub fn check(&mut self, name: &str, input: &Value) -> bool {
+        let signature = signature(name, input);
+        let earlier = self.window.iter().filter(|s| **s == signature).count();
+
+        if self.window.len() == LOOP_WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(signature);
+
+        let repeat = earlier >= self.limits.max_repeats as usize;
+        if repeat {
+            self.nudges += 1;
+        }
+        repeat
+    }
+
+    /// Whether the session has been nudged `max_nudges` times.
+    pub fn exhausted(&self) -> bool {
+        self.nudges >= self.limits.max_nudges
+    }
+}
+
+/// The tool result sent in place of a repeated call.
+pub fn nudge(name: &str) -> String {
+    format!(
+        "Not executed: you have already called {} with these arguments; the result will \
+         not change. Try a different approach (other arguments or another tool), or \
+         conclude with what you have.",
+        name
+    )
+}
+
+/// Hash of the tool name and canonicalized arguments.
+fn signature(name: &str, input: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    canonicalize(input, true).to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// `value` with string whitespace trimmed and collapsed and null fields
+/// dropped, plus [`IGNORED_ARGS`] at the top level. Object keys serialize
+/// sorted, so key order doesn't matter either.
+fn canonicalize(value: &Value, top_level: bool) -> Value {
+    match value {
+        Value::String(s) => Value::String(s.split_whitespace().collect::<Vec<_>>().join(" ")),
+        Value::Array(items) => Value::Array(items.iter().map(|v| canonicalize(v, false)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .filter(|(k, v)| {
+                    !v.is_null() && (!top_level || !IGNORED_ARGS.contains(&k.as_str()))
+                })
+                .map(|(k, v)| (k.clone(), canonicalize(v, false)))
+                .collect::<Map<_, _>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_near_duplicates_share_a_signature() {
+        let base = signature("search_entities", &json!({"query": "Crius Shipping"}));
+        for variant in [
+            json!({"query": "  Crius   Shipping "}),
+            json!({"query": "Crius Shipping", "limit": 50}),
+            json!({"query": "Crius\tShipping", "kind": null}),
+        ] {
+            assert_eq!(signature("search_entities", &variant), base, "{}", variant);
+        }
+
+        for different in [
+            json!({"query": "Crius Marine"}),
+            json!({"query": "Crius Shipping", "kind": "organization"}),
+            json!({"query": "Crius Shipping", "cursor": "abc"}),
+        ] {
+            assert_ne!(
+                signature("search_entities", &different),
+                base,
+                "{}",
+                different
+            );
+        }
+        assert_ne!(
+            signature("search_claims", &json!({"query": "Crius Shipping"})),
+            base
+        );
+        // `limit` is only ignored at the top level.
+        assert_ne!(
+            signature("batch_extract", &json!({"filter": {"limit": 1}})),
+            signature("batch_extract", &json!({"filter": {"limit": 2}}))
+        );
+    }
+
+    #[test]
+    fn test_repeats_counted_within_window() {
+        let mut guard = LoopGuard::new(LoopLimits {
+            max_repeats: 2,
+            max_nudges: 2,
+        });
+        let call = json!({"query": "Crius"});
+
+        assert!(!guard.check("search_entities", &call));
+        assert!(!guard.check("search_entities", &call));
+        assert!(guard.check("search_entities", &call));
+        assert!(!guard.exhausted());
+
+        // Enough other calls push the repeats out of the window.
+        for i in 0..LOOP_WINDOW {
+            assert!(!guard.check("get_entity", &json!({ "id": i })));
+        }
+        assert!(!guard.check("search_entities", &call));
+        assert!(!guard.check("search_entities", &call));
+        assert!(guard.check("search_entities", &call));
+        assert!(guard.exhausted());
+    }
+}
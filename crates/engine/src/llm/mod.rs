@@ -1,21 +1,50 @@
 mod anthropic;
+pub mod cache;
+mod failover;
+mod gemini;
+pub mod loop_guard;
+pub mod models;
 mod openai;
 pub mod session;
+pub mod tokens;
 pub mod types;
 
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
-use autosint_common::config::{LlmRoleConfig, RetryConfig};
+use autosint_common::config::{LlmRoleConfig, ModelCapabilities, RetryConfig};
+use autosint_common::http_client::{self, Destination};
+use autosint_common::retry::{retry_with_backoff, RetryAttempt, RetryClass, RetryPolicy};
+use autosint_common::types::FeatureFlag;
 
-pub use types::{ContentBlock, LlmResponse, Message, Role, StopReason, TokenUsage, ToolDefinition};
+use crate::flags::FlagSet;
 
-/// LLM API client with provider dispatch and retry logic.
+pub use models::{ContextBudget, ModelRegistry};
+pub use tokens::TokenCounter;
+pub use types::{
+    ContentBlock, LlmResponse, Message, Role, ServedBy, StopReason, TokenUsage, ToolDefinition,
+};
+
+/// LLM client for a role: the configured provider, failing over to the
+/// role's fallback provider during outages, behind the response cache.
 pub struct LlmClient {
+    config: LlmRoleConfig,
+    inner: Arc<dyn LlmCaller>,
+    /// Set when the role caches responses.
+    cache: Option<cache::ResponseCache>,
+    flags: FlagSet,
+}
+
+/// A single provider's API client with retry logic.
+struct ProviderClient {
     http: reqwest::Client,
     config: LlmRoleConfig,
     retry_config: RetryConfig,
     api_key: String,
+    token_counter: Arc<dyn TokenCounter>,
+    capabilities: ModelCapabilities,
 }
 
 /// Errors from LLM API calls.
@@ -33,6 +62,9 @@ pub enum LlmError {
     #[error("LLM context window exceeded: {0}")]
     ContextWindowExceeded(String),
 
+    #[error("LLM server error: {0}")]
+    Server(String),
+
     #[error("LLM API error: {0}")]
     Api(String),
 
@@ -45,6 +77,15 @@ impl LlmError {
     fn is_non_retryable(&self) -> bool {
         matches!(self, LlmError::Auth(_) | LlmError::ContextWindowExceeded(_))
     }
+
+    /// Whether this error, once retries are exhausted, means the provider is
+    /// down or overloaded rather than rejecting this request.
+    fn is_outage(&self) -> bool {
+        matches!(
+            self,
+            LlmError::Server(_) | LlmError::Http(_) | LlmError::RateLimited { .. }
+        )
+    }
 }
 
 impl From<LlmError> for autosint_common::AutOsintError {
@@ -56,11 +97,90 @@ impl From<LlmError> for autosint_common::AutOsintError {
 impl LlmClient {
     /// Create a new LLM client.
     /// Reads the API key from the configured env var (or provider default).
-    /// Returns None if the key is not set.
+    /// Returns None if the key is not set. A fallback provider whose key is
+    /// not set is left out.
     pub fn new(config: LlmRoleConfig, retry_config: RetryConfig) -> Option<Self> {
+        let primary: Arc<dyn LlmCaller> =
+            Arc::new(ProviderClient::new(config.clone(), retry_config.clone())?);
+        let inner = match &config.fallback {
+            Some(fallback) => {
+                match ProviderClient::new(fallback.role_config(&config), retry_config) {
+                    Some(secondary) => Arc::new(failover::Failover::new(
+                        primary,
+                        Arc::new(secondary),
+                        Duration::from_secs(fallback.cooldown_seconds),
+                    )),
+                    None => {
+                        tracing::warn!(
+                            provider = fallback.provider.as_str(),
+                            "Fallback LLM provider unavailable — no failover for this role"
+                        );
+                        primary
+                    }
+                }
+            }
+            None => primary,
+        };
+        Some(Self::from_caller(config, inner))
+    }
+
+    /// A client for `config` that sends requests to `inner`.
+    pub fn from_caller(config: LlmRoleConfig, inner: Arc<dyn LlmCaller>) -> Self {
+        let cache = cache::caches_responses(&config)
+            .then(|| cache::ResponseCache::new(&config.response_cache));
+        Self {
+            config,
+            inner,
+            cache,
+            flags: FlagSet::default(),
+        }
+    }
+
+    /// Consult `flags` before using the response cache.
+    pub fn with_flags(mut self, flags: FlagSet) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Send a chat request to the configured provider with retry logic, or to
+    /// the fallback provider while the primary is down. With response caching
+    /// on (and its flag), an identical earlier request's response is returned
+    /// instead, and fresh responses are cached.
+    pub async fn chat(
+        &self,
+        system: &str,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+    ) -> Result<LlmResponse, LlmError> {
+        let Some(cache) = self
+            .cache
+            .as_ref()
+            .filter(|_| self.flags.is_enabled(FeatureFlag::LlmResponseCache))
+        else {
+            return self.inner.chat(system, messages, tools).await;
+        };
+
+        let fingerprint = cache::fingerprint(&self.config, system, messages, tools);
+        if let Some(response) = cache.get(&fingerprint).await {
+            metrics::counter!("llm.cache.hits", "provider" => self.config.provider.clone())
+                .increment(1);
+            return Ok(response);
+        }
+        metrics::counter!("llm.cache.misses", "provider" => self.config.provider.clone())
+            .increment(1);
+
+        let response = self.inner.chat(system, messages, tools).await?;
+        cache.put(&fingerprint, &response).await;
+        Ok(response)
+    }
+}
+
+impl ProviderClient {
+    fn new(config: LlmRoleConfig, retry_config: RetryConfig) -> Option<Self> {
         let default_env_var = match config.provider.as_str() {
             "anthropic" => "ANTHROPIC_API_KEY",
             "openai" => "OPENAI_API_KEY",
+            "gemini" => "GEMINI_API_KEY",
             other => {
                 tracing::warn!(provider = other, "Unknown LLM provider");
                 return None;
@@ -81,64 +201,63 @@ impl LlmClient {
             }
         };
 
+        let token_counter = tokens::counter_for_role(&config);
+        let capabilities = ModelRegistry::builtin().resolve(&config);
         Some(Self {
-            http: reqwest::Client::new(),
+            http: http_client::shared(Destination::Llm),
             config,
             retry_config,
             api_key,
+            token_counter,
+            capabilities,
         })
     }
 
-    /// Send a chat request to the configured provider with retry logic.
-    pub async fn chat(
+    async fn chat(
         &self,
         system: &str,
         messages: &[Message],
         tools: &[ToolDefinition],
     ) -> Result<LlmResponse, LlmError> {
-        let mut attempt = 0u32;
-        let mut backoff_ms = self.retry_config.initial_backoff_ms;
-
-        loop {
-            attempt += 1;
-            let result = self.send_once(system, messages, tools).await;
-
-            match result {
-                Ok(response) => return Ok(response),
-                Err(ref e) if e.is_non_retryable() => {
-                    metrics::counter!("llm.api.errors", "provider" => self.config.provider.clone())
-                        .increment(1);
-                    return result;
-                }
-                Err(LlmError::RateLimited { retry_after }) => {
-                    if attempt >= self.retry_config.max_attempts {
-                        metrics::counter!("llm.api.errors", "provider" => self.config.provider.clone())
-                            .increment(1);
-                        return Err(LlmError::RateLimited { retry_after });
-                    }
-                    let wait = retry_after.map(|s| s * 1000).unwrap_or(backoff_ms);
-                    tracing::warn!(attempt, wait_ms = wait, "LLM rate limited, retrying");
-                    tokio::time::sleep(std::time::Duration::from_millis(wait)).await;
-                }
-                Err(e) => {
-                    if attempt >= self.retry_config.max_attempts {
-                        metrics::counter!("llm.api.errors", "provider" => self.config.provider.clone())
-                            .increment(1);
-                        return Err(e);
-                    }
-                    let jitter = if self.retry_config.jitter {
-                        compute_jitter(attempt, backoff_ms)
-                    } else {
-                        0
-                    };
-                    let wait = backoff_ms + jitter;
-                    tracing::warn!(attempt, wait_ms = wait, error = %e, "LLM API error, retrying");
-                    tokio::time::sleep(std::time::Duration::from_millis(wait)).await;
-                    backoff_ms = (backoff_ms as f64 * self.retry_config.backoff_multiplier) as u64;
-                    backoff_ms = backoff_ms.min(self.retry_config.max_backoff_ms);
-                }
+        let classify = |e: &LlmError| match e {
+            e if e.is_non_retryable() => RetryClass::Fatal,
+            LlmError::RateLimited { retry_after } => {
+                RetryClass::RetryAfter(retry_after.map(Duration::from_secs))
             }
-        }
+            _ => RetryClass::Retryable,
+        };
+        let on_retry = |retry: &RetryAttempt<'_, LlmError>| {
+            let wait_ms = retry.wait.as_millis() as u64;
+            if retry.rate_limited {
+                tracing::warn!(
+                    attempt = retry.attempt,
+                    wait_ms,
+                    "LLM rate limited, retrying"
+                );
+            } else {
+                tracing::warn!(attempt = retry.attempt, wait_ms, error = %retry.error, "LLM API error, retrying");
+            }
+        };
+        retry_with_backoff(
+            &RetryPolicy::from(&self.retry_config),
+            classify,
+            on_retry,
+            || self.send_once(system, messages, tools),
+        )
+        .await
+        .map(|mut response| {
+            response.served_by = Some(ServedBy {
+                provider: self.config.provider.clone(),
+                model: self.config.model.clone(),
+                fallback: false,
+            });
+            response
+        })
+        .map_err(|failure| {
+            metrics::counter!("llm.api.errors", "provider" => self.config.provider.clone())
+                .increment(1);
+            failure.error
+        })
     }
 
     /// Single attempt — routes to provider-specific implementation.
@@ -187,24 +306,30 @@ impl LlmClient {
                 )
                 .await
             }
+            "gemini" => {
+                let base_url = self
+                    .config
+                    .base_url
+                    .as_deref()
+                    .unwrap_or("https://generativelanguage.googleapis.com");
+                gemini::send_generate_content(
+                    &self.http,
+                    &self.api_key,
+                    base_url,
+                    &self.config.model,
+                    self.config.max_tokens,
+                    self.config.temperature,
+                    system,
+                    messages,
+                    tools,
+                )
+                .await
+            }
             other => Err(LlmError::Api(format!("Unknown provider: {}", other))),
         }
     }
 }
 
-/// Compute jitter for retry backoff using simple hash-based approach.
-fn compute_jitter(attempt: u32, backoff_ms: u64) -> u64 {
-    use std::hash::{Hash, Hasher};
-    let mut hasher = std::hash::DefaultHasher::new();
-    attempt.hash(&mut hasher);
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .subsec_nanos()
-        .hash(&mut hasher);
-    hasher.finish() % (backoff_ms / 2 + 1)
-}
-
 /// Object-safe trait for testability (dyn dispatch).
 /// Tests provide MockLlmCaller; production uses LlmClient.
 pub trait LlmCaller: Send + Sync {
@@ -214,6 +339,36 @@ pub trait LlmCaller: Send + Sync {
         messages: &'a [Message],
         tools: &'a [ToolDefinition],
     ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>>;
+
+    /// Token counter matching this caller's model. Defaults to chars/4.
+    fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        Arc::new(tokens::HeuristicCounter)
+    }
+
+    /// Context budget at `fraction` of this caller's model's window. Defaults
+    /// to None: no budgeting.
+    fn context_budget(&self, _fraction: f64) -> Option<ContextBudget> {
+        None
+    }
+}
+
+/// Builds the LLM caller for each session of a role. Production builds the
+/// role's configured client ([`configured_llm`]); tests inject scripted
+/// callers through the Orchestrator and the Processor pool.
+pub type LlmFactory = Arc<dyn Fn() -> Result<Arc<dyn LlmCaller>, String> + Send + Sync>;
+
+/// The factory for a role's configured [`LlmClient`], consulting `flags`.
+pub fn configured_llm(
+    config: LlmRoleConfig,
+    retry_config: RetryConfig,
+    flags: FlagSet,
+) -> LlmFactory {
+    Arc::new(move || {
+        let client = LlmClient::new(config.clone(), retry_config.clone())
+            .ok_or_else(|| "Failed to create LLM client — API key not set".to_string())?
+            .with_flags(flags.clone());
+        Ok(Arc::new(client) as Arc<dyn LlmCaller>)
+    })
 }
 
 impl LlmCaller for LlmClient {
@@ -225,4 +380,35 @@ impl LlmCaller for LlmClient {
     ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
         Box::pin(self.chat(system, messages, tools))
     }
+
+    fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        self.inner.token_counter()
+    }
+
+    fn context_budget(&self, fraction: f64) -> Option<ContextBudget> {
+        self.inner.context_budget(fraction)
+    }
+}
+
+impl LlmCaller for ProviderClient {
+    fn chat<'a>(
+        &'a self,
+        system: &'a str,
+        messages: &'a [Message],
+        tools: &'a [ToolDefinition],
+    ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+        Box::pin(self.chat(system, messages, tools))
+    }
+
+    fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        Arc::clone(&self.token_counter)
+    }
+
+    fn context_budget(&self, fraction: f64) -> Option<ContextBudget> {
+        Some(ContextBudget::new(
+            &self.config,
+            &self.capabilities,
+            fraction,
+        ))
+    }
 }
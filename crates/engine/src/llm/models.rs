@@ -0,0 +1,255 @@
+//! Model capabilities — context window, output limit, tool and image support
+//! — and the context budget sessions derive from them.
+//!
+//! Capabilities come from `[llm.models]` patterns, then a built-in table of
+//! the models the engine is usually run with. A model neither knows gets
+//! `UNKNOWN_MODEL`, deliberately small so an unlisted model is compacted
+//! early rather than overrun.
+
+use std::collections::BTreeMap;
+
+use autosint_common::config::{LlmRoleConfig, ModelCapabilities};
+
+/// Capabilities assumed for a model no pattern matches.
+pub const UNKNOWN_MODEL: ModelCapabilities = ModelCapabilities {
+    context_window: 32_000,
+    max_output_tokens: 4_096,
+    supports_tools: true,
+    supports_vision: false,
+};
+
+const fn caps(
+    context_window: u32,
+    max_output_tokens: u32,
+    supports_vision: bool,
+) -> ModelCapabilities {
+    ModelCapabilities {
+        context_window,
+        max_output_tokens,
+        supports_tools: true,
+        supports_vision,
+    }
+}
+
+/// Built-in capabilities, by pattern.
+const BUILTIN_MODELS: &[(&str, ModelCapabilities)] = &[
+    ("claude-opus-4*", caps(200_000, 32_000, true)),
+    ("claude-sonnet-4*", caps(200_000, 64_000, true)),
+    ("claude-3-7-sonnet*", caps(200_000, 64_000, true)),
+    ("claude-3-5-sonnet*", caps(200_000, 8_192, true)),
+    ("claude-3.5-sonnet*", caps(200_000, 8_192, true)),
+    ("claude-3-5-haiku*", caps(200_000, 8_192, false)),
+    ("claude-3.5-haiku*", caps(200_000, 8_192, false)),
+    ("gpt-4o*", caps(128_000, 16_384, true)),
+    ("gpt-4.1*", caps(1_047_576, 32_768, true)),
+    ("o3*", caps(200_000, 100_000, true)),
+    ("o4-mini*", caps(200_000, 100_000, true)),
+    ("gemini-2.5-*", caps(1_048_576, 65_536, true)),
+    ("gemini-2.0-flash*", caps(1_048_576, 8_192, true)),
+    ("gemini-1.5-pro*", caps(2_097_152, 8_192, true)),
+    ("gemini-1.5-flash*", caps(1_048_576, 8_192, true)),
+];
+
+/// Configured patterns in front of the built-in table.
+pub struct ModelRegistry {
+    configured: Vec<(String, ModelCapabilities)>,
+}
+
+impl ModelRegistry {
+    /// `configured` is `[llm.models]`.
+    pub fn new(configured: &BTreeMap<String, ModelCapabilities>) -> Self {
+        Self {
+            configured: configured
+                .iter()
+                .map(|(pattern, caps)| (pattern.clone(), *caps))
+                .collect(),
+        }
+    }
+
+    /// The built-in table alone.
+    pub fn builtin() -> Self {
+        Self {
+            configured: Vec::new(),
+        }
+    }
+
+    /// The pattern that matches `model` and its capabilities. Configured
+    /// patterns win over built-in ones; among either, the longest matching
+    /// pattern wins.
+    pub fn lookup(&self, model: &str) -> Option<(&str, ModelCapabilities)> {
+        best_match(self.configured.iter().map(|(p, c)| (p.as_str(), *c)), model)
+            .or_else(|| best_match(BUILTIN_MODELS.iter().copied(), model))
+    }
+
+    /// Capabilities for `role`: its own when set, else `lookup`'s, else
+    /// `UNKNOWN_MODEL`.
+    pub fn resolve(&self, role: &LlmRoleConfig) -> ModelCapabilities {
+        role.capabilities
+            .or_else(|| self.lookup(&role.model).map(|(_, caps)| caps))
+            .unwrap_or(UNKNOWN_MODEL)
+    }
+}
+
+/// The longest of `entries`' patterns that matches `model`.
+fn best_match<'a>(
+    entries: impl Iterator<Item = (&'a str, ModelCapabilities)>,
+    model: &str,
+) -> Option<(&'a str, ModelCapabilities)> {
+    entries
+        .filter(|(pattern, _)| model_matches(pattern, model))
+        .max_by_key(|(pattern, _)| pattern.len())
+}
+
+/// Whether `pattern` matches `model`, case-insensitively. A pattern without a
+/// `/` is also tried against the part of `model` after its last `/`, so
+/// patterns name models the same way with or without a router prefix.
+fn model_matches(pattern: &str, model: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let model = model.trim().to_ascii_lowercase();
+    if glob_matches(&pattern, &model) {
+        return true;
+    }
+    match model.rsplit_once('/') {
+        Some((_, name)) if !pattern.contains('/') => glob_matches(&pattern, name),
+        _ => false,
+    }
+}
+
+/// `*` matches any run of characters; everything else matches itself.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*`: the whole text must be the pattern.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// How much of the context window a session lets a request fill.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContextBudget {
+    pub context_window: u32,
+    /// Output tokens every request asks for, which the window must also hold.
+    pub reserved_output: u32,
+    /// Fraction of the window input and reserved output may fill together.
+    pub fraction: f64,
+}
+
+impl ContextBudget {
+    /// Budget for `role` on a model with `capabilities`: the role's
+    /// `max_tokens` (capped at the model's output limit) is reserved.
+    pub fn new(role: &LlmRoleConfig, capabilities: &ModelCapabilities, fraction: f64) -> Self {
+        Self {
+            context_window: capabilities.context_window,
+            reserved_output: role.max_tokens.min(capabilities.max_output_tokens),
+            fraction,
+        }
+    }
+
+    /// Most input tokens a request may carry.
+    pub fn max_input_tokens(&self) -> usize {
+        let usable = (self.context_window as f64 * self.fraction.clamp(0.0, 1.0)).floor() as usize;
+        usable.saturating_sub(self.reserved_output as usize)
+    }
+
+    /// Whether a request of `input_tokens` stays within the budget.
+    pub fn fits(&self, input_tokens: usize) -> bool {
+        input_tokens <= self.max_input_tokens()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(model: &str, max_tokens: u32) -> LlmRoleConfig {
+        LlmRoleConfig {
+            provider: "openai".into(),
+            model: model.into(),
+            max_tokens,
+            temperature: None,
+            base_url: None,
+            api_key_env: None,
+            cache_responses: false,
+            response_cache: Default::default(),
+            capabilities: None,
+            fallback: None,
+        }
+    }
+
+    #[test]
+    fn test_lookup_matches_routed_names_and_prefers_configured() {
+        let registry = ModelRegistry::builtin();
+        let (pattern, sonnet) = registry.lookup("anthropic/claude-sonnet-4").unwrap();
+        assert_eq!(pattern, "claude-sonnet-4*");
+        assert_eq!(sonnet.context_window, 200_000);
+        assert_eq!(
+            registry.lookup("Claude-Sonnet-4-20250514").unwrap().0,
+            "claude-sonnet-4*"
+        );
+        assert_eq!(
+            registry.lookup("anthropic/claude-3.5-haiku").unwrap().0,
+            "claude-3.5-haiku*"
+        );
+        assert!(registry.lookup("acme/llama-local").is_none());
+        assert_eq!(
+            registry.resolve(&role("acme/llama-local", 1024)),
+            UNKNOWN_MODEL
+        );
+
+        let custom = caps(400_000, 16_000, false);
+        let mut configured = BTreeMap::new();
+        configured.insert("anthropic/claude-sonnet-4*".to_string(), custom);
+        configured.insert("*".to_string(), caps(8_000, 1_000, false));
+        let registry = ModelRegistry::new(&configured);
+        assert_eq!(
+            registry.lookup("anthropic/claude-sonnet-4").unwrap(),
+            ("anthropic/claude-sonnet-4*", custom)
+        );
+        // A pattern with a `/` only matches the full routed name.
+        assert_eq!(registry.lookup("claude-sonnet-4").unwrap().0, "*");
+
+        let mut explicit = role("acme/llama-local", 1024);
+        explicit.capabilities = Some(custom);
+        assert_eq!(ModelRegistry::builtin().resolve(&explicit), custom);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("gpt-4o*", "gpt-4o-mini"));
+        assert!(glob_matches("gpt-4o*", "gpt-4o"));
+        assert!(!glob_matches("gpt-4o", "gpt-4o-mini"));
+        assert!(glob_matches("claude-*-sonnet*", "claude-3-7-sonnet-latest"));
+        assert!(!glob_matches("claude-*-sonnet*", "claude-3-5-haiku"));
+        assert!(glob_matches("*haiku", "claude-3.5-haiku"));
+    }
+
+    #[test]
+    fn test_budget_reserves_output_within_fraction() {
+        let budget = ContextBudget::new(&role("m", 8_192), &caps(200_000, 64_000, true), 0.85);
+        assert_eq!(budget.reserved_output, 8_192);
+        assert_eq!(budget.max_input_tokens(), 170_000 - 8_192);
+        assert!(budget.fits(161_808));
+        assert!(!budget.fits(161_809));
+
+        // The reservation is capped at what the model can produce.
+        let capped = ContextBudget::new(&role("m", 50_000), &caps(32_000, 4_096, false), 1.0);
+        assert_eq!(capped.max_input_tokens(), 32_000 - 4_096);
+
+        // A budget smaller than the reservation admits nothing.
+        let tiny = ContextBudget::new(&role("m", 4_096), &caps(8_000, 4_096, false), 0.5);
+        assert_eq!(tiny.max_input_tokens(), 0);
+        assert!(!tiny.fits(1));
+    }
+}
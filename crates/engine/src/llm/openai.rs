@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use autosint_common::http_client::{self, Destination};
+
 use super::types::{
     ContentBlock, LlmResponse, Message, Role, StopReason, TokenUsage, ToolDefinition,
 };
@@ -225,6 +227,7 @@ fn from_wire_response(resp: ChatResponse) -> Result<LlmResponse, LlmError> {
             input_tokens: resp.usage.prompt_tokens,
             output_tokens: resp.usage.completion_tokens,
         },
+        served_by: None,
     })
 }
 
@@ -272,13 +275,12 @@ pub async fn send_chat_completion(
         temperature,
     };
 
-    let response = http
-        .post(&url)
-        .bearer_auth(api_key)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| LlmError::Http(e.to_string()))?;
+    let response = http_client::send(
+        Destination::Llm,
+        http.post(&url).bearer_auth(api_key).json(&request),
+    )
+    .await
+    .map_err(|e| LlmError::Http(e.to_string()))?;
 
     let status = response.status();
     let latency = start.elapsed().as_secs_f64();
@@ -314,6 +316,9 @@ pub async fn send_chat_completion(
             }
             Err(_) => body,
         };
+        if status.is_server_error() {
+            return Err(LlmError::Server(format!("{}: {}", status, msg)));
+        }
         return Err(LlmError::Api(format!("{}: {}", status, msg)));
     }
 
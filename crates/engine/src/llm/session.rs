@@ -1,8 +1,15 @@
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
+use super::loop_guard::{self, LoopGuard, LoopLimits};
+use super::tokens;
 use super::types::{ContentBlock, Message, Role, ToolDefinition};
 use super::LlmCaller;
+use crate::tools::truncation::truncate_to_tokens;
 
 /// Result of a completed agentic session.
 pub enum SessionResult {
@@ -13,37 +20,121 @@ pub enum SessionResult {
     },
     /// Hit the configured max turns limit.
     MaxTurnsReached { stats: SessionStats },
+    /// Hit the configured wall-clock limit (checked between turns, never mid-tool).
+    TimeLimitReached { stats: SessionStats },
     /// Too many consecutive malformed tool calls.
     MalformedToolCallLimit { stats: SessionStats },
+    /// Kept repeating the same tool call after being nudged to stop.
+    ToolLoopLimit { stats: SessionStats },
+    /// The next request would not fit the context budget even with older
+    /// tool results compacted. Ended before sending it.
+    ContextLimitReached { stats: SessionStats },
     /// An unrecoverable error occurred.
     Failed { error: String, stats: SessionStats },
 }
 
 impl SessionResult {
+    /// How the session ended, as stored in work order summaries.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Completed { .. } => "completed",
+            Self::MaxTurnsReached { .. } => "max_turns_reached",
+            Self::TimeLimitReached { .. } => "time_limit_reached",
+            Self::MalformedToolCallLimit { .. } => "malformed_tool_call_limit",
+            Self::ToolLoopLimit { .. } => "tool_loop_limit",
+            Self::ContextLimitReached { .. } => "context_limit_reached",
+            Self::Failed { .. } => "failed",
+        }
+    }
+
     pub fn stats(&self) -> &SessionStats {
         match self {
             Self::Completed { stats, .. }
             | Self::MaxTurnsReached { stats }
+            | Self::TimeLimitReached { stats }
             | Self::MalformedToolCallLimit { stats }
+            | Self::ToolLoopLimit { stats }
+            | Self::ContextLimitReached { stats }
             | Self::Failed { stats, .. } => stats,
         }
     }
 }
 
+/// Key of [`SessionStats::tool_calls_by_name`] for calls to tools the
+/// session doesn't offer.
+pub const UNKNOWN_TOOL: &str = "unknown";
+
 /// Accumulated statistics for a session.
 #[derive(Clone, Debug, Default)]
 pub struct SessionStats {
     pub turns: u32,
     pub tool_calls: u32,
+    /// `tool_calls` by tool name. Calls to tools the session doesn't offer
+    /// count under [`UNKNOWN_TOOL`], so model output never becomes a key.
+    pub tool_calls_by_name: BTreeMap<String, u32>,
     pub total_input_tokens: u64,
     pub total_output_tokens: u64,
     pub malformed_tool_calls: u32,
+    /// Repeated tool calls answered with a nudge instead of being executed.
+    pub loop_nudges: u32,
+    /// Estimated size of the latest request (system prompt, tools and history),
+    /// counted with the model's tokenizer.
+    pub context_tokens: u64,
+    /// Times older tool results were compacted to stay within the context
+    /// budget.
+    pub context_compactions: u32,
+    /// Turns answered by the role's fallback provider during an outage.
+    pub fallback_turns: u32,
+}
+
+/// Live mirror of `SessionStats`, readable while the session is still running
+/// (e.g. by tool handlers reporting budget usage).
+#[derive(Debug, Default)]
+pub struct LiveSessionStats {
+    pub turns: AtomicU32,
+    pub tool_calls: AtomicU32,
+    pub total_input_tokens: AtomicU64,
+    pub total_output_tokens: AtomicU64,
+    /// Set when the session loop starts.
+    pub started_at: OnceLock<Instant>,
+}
+
+impl LiveSessionStats {
+    /// Wall-clock time since the session started (zero before it starts).
+    pub fn elapsed(&self) -> Duration {
+        self.started_at
+            .get()
+            .map(Instant::elapsed)
+            .unwrap_or_default()
+    }
+
+    fn publish(&self, stats: &SessionStats) {
+        self.turns.store(stats.turns, Ordering::Relaxed);
+        self.tool_calls.store(stats.tool_calls, Ordering::Relaxed);
+        self.total_input_tokens
+            .store(stats.total_input_tokens, Ordering::Relaxed);
+        self.total_output_tokens
+            .store(stats.total_output_tokens, Ordering::Relaxed);
+    }
 }
 
 /// Configuration for the agentic loop.
 pub struct SessionConfig {
     pub max_turns: u32,
     pub max_consecutive_malformed: u32,
+    /// Wall-clock limit for the whole session. None = no limit.
+    pub max_duration: Option<Duration>,
+    /// Updated as the loop progresses, if set.
+    pub live_stats: Option<Arc<LiveSessionStats>>,
+    /// Tool results longer than this many tokens are truncated before they
+    /// reach the LLM. None = no limit.
+    pub max_tool_result_tokens: Option<u32>,
+    /// Repeated-call detection (see `loop_guard`). None = no detection.
+    pub loop_limits: Option<LoopLimits>,
+    /// Fraction of the model's context window a request may fill (see
+    /// `ContextBudget`). None, or a caller without a known window, = no
+    /// budgeting.
+    pub context_budget_fraction: Option<f64>,
 }
 
 /// Result from executing a single tool call.
@@ -82,6 +173,28 @@ pub async fn run_session(
 
     let mut stats = SessionStats::default();
     let mut consecutive_malformed: u32 = 0;
+    let mut loop_guard = config.loop_limits.map(LoopGuard::new);
+    // Running context size: the fixed preamble plus each message as it is added.
+    let counter = llm.token_counter();
+    let preamble_tokens = tokens::count_preamble(counter.as_ref(), system_prompt, tools);
+    let mut history_tokens = tokens::count_message(counter.as_ref(), &history[0]);
+    let budget = config
+        .context_budget_fraction
+        .and_then(|fraction| llm.context_budget(fraction));
+    let started_at = Instant::now();
+    if let Some(live) = &config.live_stats {
+        let _ = live.started_at.set(started_at);
+    }
+    let publish = |stats: &SessionStats| {
+        if let Some(live) = &config.live_stats {
+            live.publish(stats);
+        }
+    };
+    let out_of_time = || {
+        config
+            .max_duration
+            .is_some_and(|max| started_at.elapsed() >= max)
+    };
 
     loop {
         // Check turn limit.
@@ -90,7 +203,50 @@ pub async fn run_session(
             return SessionResult::MaxTurnsReached { stats };
         }
 
+        // Check time limit.
+        if out_of_time() {
+            tracing::warn!(
+                turns = stats.turns,
+                elapsed_secs = started_at.elapsed().as_secs(),
+                "Session hit time limit"
+            );
+            return SessionResult::TimeLimitReached { stats };
+        }
+
+        // Check the context budget before paying for a request that may not fit.
+        if let Some(budget) = &budget {
+            if !budget.fits(preamble_tokens + history_tokens) {
+                let limit = budget.max_input_tokens().saturating_sub(preamble_tokens);
+                let compacted =
+                    compact_tool_results(&mut history, counter.as_ref(), history_tokens, limit);
+                let freed = history_tokens.saturating_sub(compacted);
+                history_tokens = compacted;
+                if freed > 0 {
+                    stats.context_compactions += 1;
+                    metrics::counter!("llm.session.compactions").increment(1);
+                    tracing::info!(
+                        freed_tokens = freed,
+                        context_tokens = preamble_tokens + history_tokens,
+                        max_input_tokens = budget.max_input_tokens(),
+                        "Compacted older tool results to fit the context budget"
+                    );
+                }
+                if !budget.fits(preamble_tokens + history_tokens) {
+                    stats.context_tokens = (preamble_tokens + history_tokens) as u64;
+                    tracing::warn!(
+                        turns = stats.turns,
+                        context_tokens = stats.context_tokens,
+                        max_input_tokens = budget.max_input_tokens(),
+                        "Session context exceeds the budget, wrapping up"
+                    );
+                    return SessionResult::ContextLimitReached { stats };
+                }
+            }
+        }
+
         stats.turns += 1;
+        stats.context_tokens = (preamble_tokens + history_tokens) as u64;
+        publish(&stats);
 
         // Call LLM.
         let response = match llm.chat(system_prompt, &history, tools).await {
@@ -104,15 +260,30 @@ pub async fn run_session(
             }
         };
 
-        // Accumulate token usage.
-        stats.total_input_tokens += response.usage.input_tokens;
-        stats.total_output_tokens += response.usage.output_tokens;
+        if response.served_by.as_ref().is_some_and(|s| s.fallback) {
+            stats.fallback_turns += 1;
+        }
 
-        // Add assistant response to history.
-        history.push(Message {
+        let reply = Message {
             role: Role::Assistant,
             content: response.content.clone(),
-        });
+        };
+        let reply_tokens = tokens::count_message(counter.as_ref(), &reply);
+
+        // Accumulate token usage, estimating it when the response carries none.
+        if response.usage.input_tokens == 0 && response.usage.output_tokens == 0 {
+            stats.total_input_tokens += stats.context_tokens;
+            stats.total_output_tokens += reply_tokens as u64;
+            metrics::counter!("llm.usage.estimated", "counter" => counter.name()).increment(1);
+        } else {
+            stats.total_input_tokens += response.usage.input_tokens;
+            stats.total_output_tokens += response.usage.output_tokens;
+        }
+        publish(&stats);
+
+        // Add assistant response to history.
+        history_tokens += reply_tokens;
+        history.push(reply);
 
         // Extract tool use blocks.
         let tool_uses: Vec<_> = response
@@ -141,11 +312,48 @@ pub async fn run_session(
             return SessionResult::Completed { final_text, stats };
         }
 
+        // The LLM call may have used up the remaining time. Stop before starting
+        // the batch rather than in the middle of it.
+        if out_of_time() {
+            tracing::warn!(
+                turns = stats.turns,
+                pending_tool_calls = tool_uses.len(),
+                elapsed_secs = started_at.elapsed().as_secs(),
+                "Session hit time limit before executing tool calls"
+            );
+            return SessionResult::TimeLimitReached { stats };
+        }
+
         // Execute each tool call and collect results.
         let mut tool_results = Vec::new();
 
         for (id, name, input) in tool_uses {
             stats.tool_calls += 1;
+            let offered = tools.iter().find(|t| t.name == name);
+            *stats
+                .tool_calls_by_name
+                .entry(
+                    offered
+                        .map_or(UNKNOWN_TOOL, |t| t.name.as_str())
+                        .to_string(),
+                )
+                .or_default() += 1;
+            publish(&stats);
+
+            if let Some(guard) = loop_guard.as_mut() {
+                if guard.check(&name, &input) {
+                    tracing::info!(tool = %name, "Repeated tool call nudged instead of executed");
+                    metrics::counter!("llm.session.loop_nudges").increment(1);
+                    stats.loop_nudges += 1;
+                    tool_results.push(ContentBlock::ToolResult {
+                        tool_use_id: id,
+                        content: loop_guard::nudge(&name),
+                        is_error: Some(true),
+                    });
+                    continue;
+                }
+            }
+
             let result = tool_executor(name, input).await;
 
             if result.is_malformed {
@@ -155,9 +363,14 @@ pub async fn run_session(
                 consecutive_malformed = 0;
             }
 
+            let content = match config.max_tool_result_tokens {
+                Some(max) => truncate_to_tokens(&result.content, max as usize, counter.as_ref()),
+                None => result.content,
+            };
+
             tool_results.push(ContentBlock::ToolResult {
                 tool_use_id: id,
-                content: result.content,
+                content,
                 is_error: if result.is_error { Some(true) } else { None },
             });
         }
@@ -171,12 +384,63 @@ pub async fn run_session(
             return SessionResult::MalformedToolCallLimit { stats };
         }
 
+        // Check repeated-call limit.
+        if loop_guard.as_ref().is_some_and(LoopGuard::exhausted) {
+            tracing::warn!(
+                nudges = stats.loop_nudges,
+                "Session kept repeating tool calls after nudges"
+            );
+            return SessionResult::ToolLoopLimit { stats };
+        }
+
         // Add tool results as a user message.
-        history.push(Message {
+        let results = Message {
             role: Role::User,
             content: tool_results,
-        });
+        };
+        history_tokens += tokens::count_message(counter.as_ref(), &results);
+        history.push(results);
+    }
+}
+
+/// Messages at the end of the history never compacted: the latest reply and
+/// the tool results answering it.
+const KEEP_RECENT_MESSAGES: usize = 2;
+
+/// Stands in for a compacted tool result.
+const COMPACTED_TOOL_RESULT: &str = "[Earlier tool result removed to stay within the context \
+     window. Call the tool again if you still need it.]";
+
+/// Replace the content of tool results longer than the placeholder, oldest
+/// first and sparing the last `KEEP_RECENT_MESSAGES` messages, until the
+/// history (`history_tokens` of it) fits in `limit` tokens. Returns the
+/// history's new size.
+fn compact_tool_results(
+    history: &mut [Message],
+    counter: &dyn tokens::TokenCounter,
+    mut history_tokens: usize,
+    limit: usize,
+) -> usize {
+    let compactable = history.len().saturating_sub(KEEP_RECENT_MESSAGES);
+    for message in &mut history[..compactable] {
+        if history_tokens <= limit {
+            break;
+        }
+        let before = tokens::count_message(counter, message);
+        let mut changed = false;
+        for block in &mut message.content {
+            if let ContentBlock::ToolResult { content, .. } = block {
+                if content.len() > COMPACTED_TOOL_RESULT.len() {
+                    *content = COMPACTED_TOOL_RESULT.to_string();
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            history_tokens = history_tokens - before + tokens::count_message(counter, message);
+        }
     }
+    history_tokens
 }
 
 // ---------------------------------------------------------------------------
@@ -186,7 +450,7 @@ pub async fn run_session(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::llm::{LlmError, LlmResponse, StopReason, TokenUsage};
+    use crate::llm::{ContextBudget, LlmError, LlmResponse, StopReason, TokenUsage};
     use std::sync::atomic::{AtomicU32, Ordering};
     use std::sync::Arc;
 
@@ -220,6 +484,7 @@ mod tests {
                     }],
                     stop_reason: StopReason::EndTurn,
                     usage: TokenUsage::default(),
+                    served_by: None,
                 })
             });
             Box::pin(async move { result })
@@ -249,11 +514,17 @@ mod tests {
                 input_tokens: 10,
                 output_tokens: 5,
             },
+            served_by: None,
         })]);
 
         let config = SessionConfig {
             max_turns: 10,
             max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
         };
 
         let result = run_session(&llm, "system", "hello", &[], &noop_executor(), &config).await;
@@ -288,6 +559,7 @@ mod tests {
                     input_tokens: 50,
                     output_tokens: 30,
                 },
+                served_by: None,
             }),
             // Turn 2: final text
             Ok(LlmResponse {
@@ -299,6 +571,7 @@ mod tests {
                     input_tokens: 80,
                     output_tokens: 20,
                 },
+                served_by: None,
             }),
         ]);
 
@@ -317,6 +590,11 @@ mod tests {
         let config = SessionConfig {
             max_turns: 10,
             max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
         };
 
         let result = run_session(&llm, "system", "search for test", &[], &executor, &config).await;
@@ -348,6 +626,7 @@ mod tests {
                     }],
                     stop_reason: StopReason::ToolUse,
                     usage: TokenUsage::default(),
+                    served_by: None,
                 })
             })
             .collect();
@@ -357,6 +636,11 @@ mod tests {
         let config = SessionConfig {
             max_turns: 3,
             max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
         };
 
         let result = run_session(&llm, "system", "go", &[], &noop_executor(), &config).await;
@@ -381,6 +665,7 @@ mod tests {
                     }],
                     stop_reason: StopReason::ToolUse,
                     usage: TokenUsage::default(),
+                    served_by: None,
                 })
             })
             .collect();
@@ -401,6 +686,11 @@ mod tests {
         let config = SessionConfig {
             max_turns: 10,
             max_consecutive_malformed: 2,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
         };
 
         let result = run_session(&llm, "system", "go", &[], &executor, &config).await;
@@ -420,6 +710,11 @@ mod tests {
         let config = SessionConfig {
             max_turns: 10,
             max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
         };
 
         let result = run_session(&llm, "system", "go", &[], &noop_executor(), &config).await;
@@ -431,4 +726,668 @@ mod tests {
             _ => panic!("Expected Failed"),
         }
     }
+
+    #[tokio::test]
+    async fn test_live_stats_visible_to_tools() {
+        let llm = MockLlm::new(vec![
+            Ok(LlmResponse {
+                content: vec![ContentBlock::ToolUse {
+                    id: "toolu_1".into(),
+                    name: "budget".into(),
+                    input: serde_json::json!({}),
+                }],
+                stop_reason: StopReason::ToolUse,
+                usage: TokenUsage {
+                    input_tokens: 40,
+                    output_tokens: 10,
+                },
+                served_by: None,
+            }),
+            Ok(LlmResponse {
+                content: vec![ContentBlock::Text {
+                    text: "Done.".into(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: TokenUsage {
+                    input_tokens: 60,
+                    output_tokens: 5,
+                },
+                served_by: None,
+            }),
+        ]);
+
+        let live = Arc::new(LiveSessionStats::default());
+        let seen = Arc::new(std::sync::Mutex::new(None));
+
+        let live_clone = Arc::clone(&live);
+        let seen_clone = Arc::clone(&seen);
+        let executor: ToolExecutor = Box::new(move |_name, _input| {
+            // Snapshot what a tool handler would see mid-session.
+            *seen_clone.lock().unwrap() = Some((
+                live_clone.turns.load(Ordering::Relaxed),
+                live_clone.tool_calls.load(Ordering::Relaxed),
+                live_clone.total_input_tokens.load(Ordering::Relaxed),
+            ));
+            Box::pin(async {
+                ToolExecutionResult {
+                    content: "ok".into(),
+                    is_error: false,
+                    is_malformed: false,
+                }
+            })
+        });
+
+        let config = SessionConfig {
+            max_turns: 10,
+            max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: Some(Arc::clone(&live)),
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
+        };
+
+        run_session(&llm, "system", "go", &[], &executor, &config).await;
+
+        assert_eq!(*seen.lock().unwrap(), Some((1, 1, 40)));
+        assert_eq!(live.turns.load(Ordering::Relaxed), 2);
+        assert_eq!(live.total_input_tokens.load(Ordering::Relaxed), 100);
+        assert_eq!(live.total_output_tokens.load(Ordering::Relaxed), 15);
+    }
+
+    fn tool_call_response(ids: &[&str]) -> Result<LlmResponse, LlmError> {
+        Ok(LlmResponse {
+            content: ids
+                .iter()
+                .map(|id| ContentBlock::ToolUse {
+                    id: (*id).into(),
+                    name: "fetch_url".into(),
+                    input: serde_json::json!({}),
+                })
+                .collect(),
+            stop_reason: StopReason::ToolUse,
+            usage: TokenUsage::default(),
+            served_by: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_time_limit_stops_between_turns_not_mid_batch() {
+        let llm = MockLlm::new(vec![
+            tool_call_response(&["toolu_1", "toolu_2", "toolu_3"]),
+            tool_call_response(&["toolu_4"]),
+            tool_call_response(&["toolu_5"]),
+        ]);
+
+        let started = Arc::new(AtomicU32::new(0));
+        let finished = Arc::new(AtomicU32::new(0));
+        let (started_clone, finished_clone) = (Arc::clone(&started), Arc::clone(&finished));
+        let executor: ToolExecutor = Box::new(move |_name, _input| {
+            let started = Arc::clone(&started_clone);
+            let finished = Arc::clone(&finished_clone);
+            Box::pin(async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(40)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+                ToolExecutionResult {
+                    content: "ok".into(),
+                    is_error: false,
+                    is_malformed: false,
+                }
+            })
+        });
+
+        // The first batch alone (3 × 40ms) runs past the limit, but it finishes.
+        let config = SessionConfig {
+            max_turns: 10,
+            max_consecutive_malformed: 3,
+            max_duration: Some(Duration::from_millis(100)),
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
+        };
+
+        let result = run_session(&llm, "system", "go", &[], &executor, &config).await;
+
+        match result {
+            SessionResult::TimeLimitReached { stats } => {
+                assert_eq!(stats.turns, 1);
+                assert_eq!(stats.tool_calls, 3);
+            }
+            _ => panic!("Expected TimeLimitReached"),
+        }
+        assert_eq!(started.load(Ordering::SeqCst), 3);
+        assert_eq!(finished.load(Ordering::SeqCst), 3);
+    }
+
+    /// Mock LLM that takes `delay` to answer each call.
+    struct SlowLlm {
+        inner: MockLlm,
+        delay: Duration,
+    }
+
+    impl LlmCaller for SlowLlm {
+        fn chat<'a>(
+            &'a self,
+            system: &'a str,
+            messages: &'a [Message],
+            tools: &'a [ToolDefinition],
+        ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                self.inner.chat(system, messages, tools).await
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_time_limit_checked_before_tool_batch() {
+        let llm = SlowLlm {
+            inner: MockLlm::new(vec![tool_call_response(&["toolu_1"])]),
+            delay: Duration::from_millis(80),
+        };
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let calls_clone = Arc::clone(&calls);
+        let executor: ToolExecutor = Box::new(move |_name, _input| {
+            calls_clone.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {
+                ToolExecutionResult {
+                    content: "ok".into(),
+                    is_error: false,
+                    is_malformed: false,
+                }
+            })
+        });
+
+        let live = Arc::new(LiveSessionStats::default());
+        let config = SessionConfig {
+            max_turns: 10,
+            max_consecutive_malformed: 3,
+            max_duration: Some(Duration::from_millis(50)),
+            live_stats: Some(Arc::clone(&live)),
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
+        };
+
+        let result = run_session(&llm, "system", "go", &[], &executor, &config).await;
+
+        match result {
+            SessionResult::TimeLimitReached { stats } => {
+                assert_eq!(stats.turns, 1);
+                assert_eq!(stats.tool_calls, 0);
+            }
+            _ => panic!("Expected TimeLimitReached"),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(live.elapsed() >= Duration::from_millis(80));
+    }
+
+    /// Wraps MockLlm, recording each request's history and counting tokens
+    /// with chars/4.
+    struct RecordingLlm {
+        inner: MockLlm,
+        requests: std::sync::Mutex<Vec<Vec<Message>>>,
+    }
+
+    impl LlmCaller for RecordingLlm {
+        fn chat<'a>(
+            &'a self,
+            system: &'a str,
+            messages: &'a [Message],
+            tools: &'a [ToolDefinition],
+        ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+            self.requests.lock().unwrap().push(messages.to_vec());
+            self.inner.chat(system, messages, tools)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_results_truncated_and_missing_usage_estimated() {
+        let tool_call = LlmResponse {
+            content: vec![ContentBlock::ToolUse {
+                id: "t1".into(),
+                name: "fetch_url".into(),
+                input: serde_json::json!({}),
+            }],
+            stop_reason: StopReason::ToolUse,
+            usage: TokenUsage::default(),
+            served_by: None,
+        };
+        let done = LlmResponse {
+            content: vec![ContentBlock::Text {
+                text: "Done.".into(),
+            }],
+            stop_reason: StopReason::EndTurn,
+            usage: TokenUsage::default(),
+            served_by: None,
+        };
+        let llm = RecordingLlm {
+            inner: MockLlm::new(vec![Ok(tool_call), Ok(done)]),
+            requests: std::sync::Mutex::new(Vec::new()),
+        };
+        let executor: ToolExecutor = Box::new(|_name, _input| {
+            Box::pin(async {
+                ToolExecutionResult {
+                    content: "Приморск ".repeat(500),
+                    is_error: false,
+                    is_malformed: false,
+                }
+            })
+        });
+        let config = SessionConfig {
+            max_turns: 10,
+            max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: Some(100),
+            loop_limits: None,
+            context_budget_fraction: None,
+        };
+
+        let result = run_session(&llm, "system", "go", &[], &executor, &config).await;
+
+        let stats = match result {
+            SessionResult::Completed { stats, .. } => stats,
+            _ => panic!("Expected Completed"),
+        };
+        let requests = llm.requests.lock().unwrap();
+        let ContentBlock::ToolResult { content, .. } = &requests[1][2].content[0] else {
+            panic!("Expected a tool result");
+        };
+        let counter = tokens::HeuristicCounter;
+        assert!(tokens::TokenCounter::count(&counter, content) <= 100);
+        assert!(
+            content.contains("[truncated, 1125 tokens total]"),
+            "{}",
+            content
+        );
+
+        // Neither response carried usage: both turns were estimated from the
+        // context actually sent.
+        assert!(stats.context_tokens > 100);
+        assert!(stats.total_input_tokens > stats.context_tokens);
+        assert!(stats.total_output_tokens > 0);
+    }
+
+    /// Wraps MockLlm with a known context window, recording each request's
+    /// estimated size and history.
+    struct WindowedLlm {
+        inner: MockLlm,
+        context_window: u32,
+        reserved_output: u32,
+        estimates: std::sync::Mutex<Vec<usize>>,
+        requests: std::sync::Mutex<Vec<Vec<Message>>>,
+    }
+
+    impl WindowedLlm {
+        fn new(responses: Vec<Result<LlmResponse, LlmError>>) -> Self {
+            Self {
+                inner: MockLlm::new(responses),
+                context_window: 4_000,
+                reserved_output: 500,
+                estimates: std::sync::Mutex::new(Vec::new()),
+                requests: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl LlmCaller for WindowedLlm {
+        fn chat<'a>(
+            &'a self,
+            system: &'a str,
+            messages: &'a [Message],
+            tools: &'a [ToolDefinition],
+        ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+            let counter = tokens::HeuristicCounter;
+            let estimate = tokens::count_preamble(&counter, system, tools)
+                + messages
+                    .iter()
+                    .map(|m| tokens::count_message(&counter, m))
+                    .sum::<usize>();
+            self.estimates.lock().unwrap().push(estimate);
+            self.requests.lock().unwrap().push(messages.to_vec());
+            self.inner.chat(system, messages, tools)
+        }
+
+        fn context_budget(&self, fraction: f64) -> Option<ContextBudget> {
+            Some(ContextBudget {
+                context_window: self.context_window,
+                reserved_output: self.reserved_output,
+                fraction,
+            })
+        }
+    }
+
+    fn budget_config(fraction: f64) -> SessionConfig {
+        SessionConfig {
+            max_turns: 20,
+            max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: Some(fraction),
+        }
+    }
+
+    /// Executor whose every result is `tokens` chars/4 tokens long.
+    fn sized_executor(tokens: usize) -> ToolExecutor {
+        Box::new(move |_name, _input| {
+            Box::pin(async move {
+                ToolExecutionResult {
+                    content: "x".repeat(tokens * 4),
+                    is_error: false,
+                    is_malformed: false,
+                }
+            })
+        })
+    }
+
+    fn compacted(messages: &[Message]) -> usize {
+        messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter(|block| {
+                matches!(block, ContentBlock::ToolResult { content, .. } if content == COMPACTED_TOOL_RESULT)
+            })
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_compaction_triggers_at_budget_fraction() {
+        let script = || {
+            let mut responses: Vec<_> = (0..6)
+                .map(|i| tool_call_response(&[&format!("toolu_{}", i)]))
+                .collect();
+            responses.push(Ok(LlmResponse {
+                content: vec![ContentBlock::Text {
+                    text: "Done.".into(),
+                }],
+                stop_reason: StopReason::EndTurn,
+                usage: TokenUsage::default(),
+                served_by: None,
+            }));
+            responses
+        };
+
+        // Each tool turn adds ~404 tokens; half of a 4000-token window less
+        // 500 reserved leaves 1500, exceeded by the fifth request.
+        let llm = WindowedLlm::new(script());
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &[],
+            &sized_executor(400),
+            &budget_config(0.5),
+        )
+        .await;
+        let stats = match result {
+            SessionResult::Completed { stats, .. } => stats,
+            _ => panic!("Expected Completed"),
+        };
+        assert!(stats.context_compactions >= 1);
+        {
+            let estimates = llm.estimates.lock().unwrap();
+            assert_eq!(estimates.len(), 7);
+            assert!(estimates.iter().all(|&e| e <= 1_500), "{:?}", estimates);
+            let requests = llm.requests.lock().unwrap();
+            let first = requests.iter().position(|r| compacted(r) > 0).unwrap();
+            assert_eq!(first, 4);
+            // The latest results are never compacted.
+            let last = requests.last().unwrap();
+            let ContentBlock::ToolResult { content, .. } = &last[last.len() - 1].content[0] else {
+                panic!("Expected a tool result");
+            };
+            assert_eq!(content.len(), 1_600);
+        }
+
+        // The same session fits in the whole window untouched.
+        let llm = WindowedLlm::new(script());
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &[],
+            &sized_executor(400),
+            &budget_config(1.0),
+        )
+        .await;
+        assert_eq!(result.stats().context_compactions, 0);
+        assert!(llm
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|r| compacted(r) == 0));
+    }
+
+    #[tokio::test]
+    async fn test_request_over_window_never_sent() {
+        let llm = WindowedLlm::new(vec![
+            tool_call_response(&["toolu_1"]),
+            tool_call_response(&["toolu_2"]),
+        ]);
+
+        // The one tool result is bigger than the window and too recent to compact.
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &[],
+            &sized_executor(4_000),
+            &budget_config(1.0),
+        )
+        .await;
+
+        match result {
+            SessionResult::ContextLimitReached { stats } => {
+                assert_eq!(stats.turns, 1);
+                assert_eq!(stats.tool_calls, 1);
+                assert!(stats.context_tokens > 4_000);
+            }
+            _ => panic!("Expected ContextLimitReached"),
+        }
+        let estimates = llm.estimates.lock().unwrap();
+        assert_eq!(estimates.len(), 1);
+        assert!(estimates[0] <= 4_000 - 500);
+    }
+
+    fn search_call(id: &str, query: &str) -> Result<LlmResponse, LlmError> {
+        Ok(LlmResponse {
+            content: vec![ContentBlock::ToolUse {
+                id: id.into(),
+                name: "search_entities".into(),
+                input: serde_json::json!({ "query": query }),
+            }],
+            stop_reason: StopReason::ToolUse,
+            usage: TokenUsage::default(),
+            served_by: None,
+        })
+    }
+
+    fn counting_executor(calls: &Arc<AtomicU32>) -> ToolExecutor {
+        let calls = Arc::clone(calls);
+        Box::new(move |_name, _input| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async {
+                ToolExecutionResult {
+                    content: "[]".into(),
+                    is_error: false,
+                    is_malformed: false,
+                }
+            })
+        })
+    }
+
+    fn loop_config() -> SessionConfig {
+        SessionConfig {
+            max_turns: 20,
+            max_consecutive_malformed: 3,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: Some(LoopLimits {
+                max_repeats: 2,
+                max_nudges: 2,
+            }),
+            context_budget_fraction: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_tool_calls_nudged_then_cut_off() {
+        // The same search with trivially varied whitespace, turn after turn.
+        let queries = ["Crius", " Crius", "Crius  ", "Crius", "Crius", "Crius"];
+        let llm = RecordingLlm {
+            inner: MockLlm::new(
+                queries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, q)| search_call(&format!("toolu_{}", i), q))
+                    .collect(),
+            ),
+            requests: std::sync::Mutex::new(Vec::new()),
+        };
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &[],
+            &counting_executor(&calls),
+            &loop_config(),
+        )
+        .await;
+
+        let stats = match result {
+            SessionResult::ToolLoopLimit { stats } => stats,
+            _ => panic!("Expected ToolLoopLimit"),
+        };
+        // Two executions, then two nudges end the session.
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(stats.tool_calls, 4);
+        assert_eq!(stats.loop_nudges, 2);
+        assert_eq!(stats.turns, 4);
+
+        // The third call was answered with the nudge, as an error.
+        let requests = llm.requests.lock().unwrap();
+        let ContentBlock::ToolResult {
+            content, is_error, ..
+        } = &requests[3].last().unwrap().content[0]
+        else {
+            panic!("Expected a tool result");
+        };
+        assert_eq!(*is_error, Some(true));
+        assert!(
+            content.contains("already called search_entities with these arguments"),
+            "{}",
+            content
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nudge_lets_session_recover() {
+        let llm = MockLlm::new(vec![
+            search_call("toolu_1", "Crius"),
+            search_call("toolu_2", "Crius"),
+            search_call("toolu_3", "Crius"),
+            search_call("toolu_4", "Crius Shipping Ltd"),
+        ]);
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &[],
+            &counting_executor(&calls),
+            &loop_config(),
+        )
+        .await;
+
+        match result {
+            SessionResult::Completed { stats, .. } => {
+                assert_eq!(stats.loop_nudges, 1);
+                assert_eq!(stats.tool_calls, 4);
+            }
+            _ => panic!("Expected Completed"),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_counted_by_offered_name() {
+        let invented = Ok(LlmResponse {
+            content: vec![ContentBlock::ToolUse {
+                id: "toolu_3".into(),
+                name: "Look up Crius in the registry".into(),
+                input: serde_json::json!({}),
+            }],
+            stop_reason: StopReason::ToolUse,
+            usage: TokenUsage::default(),
+            served_by: None,
+        });
+        let llm = MockLlm::new(vec![
+            search_call("toolu_1", "Crius"),
+            search_call("toolu_2", "Gulf Star Shipping"),
+            invented,
+        ]);
+        let tools = [ToolDefinition {
+            name: "search_entities".into(),
+            description: String::new(),
+            input_schema: serde_json::json!({"type": "object"}),
+        }];
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &tools,
+            &counting_executor(&calls),
+            &loop_config(),
+        )
+        .await;
+
+        let stats = result.stats();
+        assert_eq!(stats.tool_calls, 3);
+        assert_eq!(
+            stats.tool_calls_by_name,
+            BTreeMap::from([("search_entities".into(), 2), (UNKNOWN_TOOL.into(), 1)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeated_tool_with_different_args_untouched() {
+        let llm = MockLlm::new(
+            (0..8)
+                .map(|i| search_call(&format!("toolu_{}", i), &format!("Vessel {}", i)))
+                .collect(),
+        );
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let result = run_session(
+            &llm,
+            "system",
+            "go",
+            &[],
+            &counting_executor(&calls),
+            &loop_config(),
+        )
+        .await;
+
+        match result {
+            SessionResult::Completed { stats, .. } => {
+                assert_eq!(stats.loop_nudges, 0);
+                assert_eq!(stats.tool_calls, 8);
+            }
+            _ => panic!("Expected Completed"),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 8);
+    }
 }
@@ -0,0 +1,273 @@
+use std::sync::{Arc, OnceLock};
+
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+use tiktoken_rs::CoreBPE;
+
+use autosint_common::config::LlmRoleConfig;
+
+use super::types::{ContentBlock, Message, ToolDefinition};
+
+/// Counts tokens the way a provider's model does, or as close as we can get
+/// offline. Used for tool result budgets, context-size estimates and usage
+/// accounting when a response carries no usage.
+pub trait TokenCounter: Send + Sync {
+    fn count(&self, text: &str) -> usize;
+
+    /// Short label for logs and metrics.
+    fn name(&self) -> &'static str;
+}
+
+/// Fallback for models without a known tokenizer: one token per four characters.
+pub struct HeuristicCounter;
+
+impl TokenCounter for HeuristicCounter {
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+
+    fn name(&self) -> &'static str {
+        "chars/4"
+    }
+}
+
+/// OpenAI BPE encodings via tiktoken.
+pub struct TiktokenCounter {
+    bpe: &'static CoreBPE,
+    name: &'static str,
+}
+
+impl TiktokenCounter {
+    /// The encoding OpenAI uses for `model`, if tiktoken knows it.
+    pub fn for_model(model: &str) -> Option<Self> {
+        match get_tokenizer(model)? {
+            Tokenizer::O200kBase => Some(Self::o200k()),
+            Tokenizer::Cl100kBase => Some(Self::cl100k()),
+            _ => None,
+        }
+    }
+
+    fn o200k() -> Self {
+        static BPE: OnceLock<CoreBPE> = OnceLock::new();
+        Self {
+            bpe: BPE.get_or_init(|| tiktoken_rs::o200k_base().expect("embedded o200k_base")),
+            name: "o200k_base",
+        }
+    }
+
+    fn cl100k() -> Self {
+        static BPE: OnceLock<CoreBPE> = OnceLock::new();
+        Self {
+            bpe: BPE.get_or_init(|| tiktoken_rs::cl100k_base().expect("embedded cl100k_base")),
+            name: "cl100k_base",
+        }
+    }
+}
+
+impl TokenCounter for TiktokenCounter {
+    fn count(&self, text: &str) -> usize {
+        self.bpe.encode_with_special_tokens(text).len()
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Claude models. Anthropic publishes no offline tokenizer for current
+/// models, so this counts with cl100k_base and adds the ~10% Claude's
+/// vocabulary typically needs on top, erring toward overcounting.
+pub struct AnthropicCounter {
+    inner: TiktokenCounter,
+}
+
+impl AnthropicCounter {
+    pub fn new() -> Self {
+        Self {
+            inner: TiktokenCounter::cl100k(),
+        }
+    }
+}
+
+impl Default for AnthropicCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenCounter for AnthropicCounter {
+    fn count(&self, text: &str) -> usize {
+        let base = self.inner.count(text);
+        base + base.div_ceil(10)
+    }
+
+    fn name(&self) -> &'static str {
+        "anthropic_approx"
+    }
+}
+
+/// The counter for an LLM role: tiktoken for OpenAI models it knows, the
+/// Claude approximation for Anthropic, chars/4 for everything else.
+pub fn counter_for_role(config: &LlmRoleConfig) -> Arc<dyn TokenCounter> {
+    match config.provider.as_str() {
+        "anthropic" => Arc::new(AnthropicCounter::new()),
+        "openai" => {
+            // OpenAI-compatible gateways often prefix the vendor ("openai/gpt-4o").
+            let model = config
+                .model
+                .rsplit_once('/')
+                .map_or(config.model.as_str(), |(_, m)| m);
+            match TiktokenCounter::for_model(model) {
+                Some(counter) => Arc::new(counter),
+                None if config.model.starts_with("anthropic/") => Arc::new(AnthropicCounter::new()),
+                None => Arc::new(HeuristicCounter),
+            }
+        }
+        _ => Arc::new(HeuristicCounter),
+    }
+}
+
+/// Tokens in one message's text, tool calls and tool results. Ignores the
+/// few tokens of per-message framing each provider adds.
+pub fn count_message(counter: &dyn TokenCounter, message: &Message) -> usize {
+    message
+        .content
+        .iter()
+        .map(|block| match block {
+            ContentBlock::Text { text } => counter.count(text),
+            ContentBlock::ToolUse { name, input, .. } => {
+                counter.count(name) + counter.count(&input.to_string())
+            }
+            ContentBlock::ToolResult { content, .. } => counter.count(content),
+        })
+        .sum()
+}
+
+/// Tokens in the system prompt plus tool definitions, sent with every request.
+pub fn count_preamble(counter: &dyn TokenCounter, system: &str, tools: &[ToolDefinition]) -> usize {
+    counter.count(system)
+        + tools
+            .iter()
+            .map(|t| {
+                counter.count(&t.name)
+                    + counter.count(&t.description)
+                    + counter.count(&t.input_schema.to_string())
+            })
+            .sum::<usize>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn role(provider: &str, model: &str) -> LlmRoleConfig {
+        LlmRoleConfig {
+            provider: provider.into(),
+            model: model.into(),
+            max_tokens: 1024,
+            temperature: None,
+            base_url: None,
+            api_key_env: None,
+            cache_responses: false,
+            response_cache: Default::default(),
+            capabilities: None,
+            fallback: None,
+        }
+    }
+
+    /// Prompt token counts OpenAI reported for these texts (cl100k_base models).
+    const CL100K_FIXTURES: &[(&str, usize)] = &[
+        ("hello world", 2),
+        ("tiktoken is great!", 6),
+        ("antidisestablishmentarianism", 6),
+    ];
+
+    #[test]
+    fn test_tiktoken_matches_reported_usage() {
+        let counter = TiktokenCounter::for_model("gpt-4").unwrap();
+        assert_eq!(counter.name(), "cl100k_base");
+        for (text, expected) in CL100K_FIXTURES {
+            let counted = counter.count(text);
+            assert!(
+                counted.abs_diff(*expected) <= 1,
+                "{:?}: counted {}, reported {}",
+                text,
+                counted,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_counter_selection() {
+        assert_eq!(
+            counter_for_role(&role("openai", "gpt-4o")).name(),
+            "o200k_base"
+        );
+        assert_eq!(
+            counter_for_role(&role("openai", "openai/gpt-4o-mini")).name(),
+            "o200k_base"
+        );
+        assert_eq!(
+            counter_for_role(&role("openai", "gpt-4-turbo")).name(),
+            "cl100k_base"
+        );
+        assert_eq!(
+            counter_for_role(&role("anthropic", "claude-sonnet-4-5")).name(),
+            "anthropic_approx"
+        );
+        assert_eq!(
+            counter_for_role(&role("openai", "anthropic/claude-sonnet-4-5")).name(),
+            "anthropic_approx"
+        );
+        assert_eq!(
+            counter_for_role(&role("openai", "llama-3.1-70b")).name(),
+            "chars/4"
+        );
+        assert_eq!(
+            counter_for_role(&role("gemini", "gemini-2.5-pro")).name(),
+            "chars/4"
+        );
+    }
+
+    #[test]
+    fn test_cyrillic_costs_more_than_the_heuristic_assumes() {
+        let text = "Танкер Криус принадлежит компании, зарегистрированной на Маршалловых островах.";
+        let tiktoken = TiktokenCounter::for_model("gpt-4").unwrap().count(text);
+        let heuristic = HeuristicCounter.count(text);
+        assert!(
+            tiktoken > heuristic * 3 / 2,
+            "{} vs {}",
+            tiktoken,
+            heuristic
+        );
+        assert!(AnthropicCounter::new().count(text) >= tiktoken);
+    }
+
+    #[test]
+    fn test_count_message_and_preamble() {
+        let counter = TiktokenCounter::for_model("gpt-4").unwrap();
+        let message = Message {
+            role: super::super::Role::Assistant,
+            content: vec![
+                ContentBlock::Text {
+                    text: "hello world".into(),
+                },
+                ContentBlock::ToolResult {
+                    tool_use_id: "t1".into(),
+                    content: "tiktoken is great!".into(),
+                    is_error: None,
+                },
+            ],
+        };
+        assert_eq!(count_message(&counter, &message), 8);
+
+        let tools = [ToolDefinition {
+            name: "search".into(),
+            description: "Search the graph.".into(),
+            input_schema: json!({"type": "object"}),
+        }];
+        assert!(count_preamble(&counter, "hello world", &tools) > 2);
+        assert_eq!(count_preamble(&counter, "hello world", &[]), 2);
+    }
+}
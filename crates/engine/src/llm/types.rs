@@ -45,15 +45,29 @@ pub struct ToolDefinition {
 }
 
 /// Parsed response from an LLM API call.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LlmResponse {
     pub content: Vec<ContentBlock>,
     pub stop_reason: StopReason,
     pub usage: TokenUsage,
+    /// The provider and model that produced this response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub served_by: Option<ServedBy>,
+}
+
+/// Which provider served a response — the role's primary, or its fallback
+/// during a primary outage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServedBy {
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub fallback: bool,
 }
 
 /// Why the LLM stopped generating.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StopReason {
     EndTurn,
     ToolUse,
@@ -62,7 +76,7 @@ pub enum StopReason {
 }
 
 /// Token usage from a single API call.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
     pub input_tokens: u64,
     pub output_tokens: u64,
@@ -1,44 +1,49 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use axum::{
-    extract::State, http::StatusCode, response::IntoResponse, routing::get, routing::post, Json,
-    Router,
-};
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
-use serde::Deserialize;
-
+use autosint_common::api::engine::ConsistencySeverity;
+use autosint_common::http_client::{self, Destination};
+use autosint_common::pii::PiiScrubber;
+use autosint_common::prometheus::{self, MetricsLabels};
+use autosint_common::tls;
+use autosint_engine::audit::AuditLog;
 use autosint_engine::circuit_breaker::CircuitBreakerRegistry;
-use autosint_engine::config;
+use autosint_engine::config::{self, PromptStore};
 use autosint_engine::embeddings;
+use autosint_engine::events::{self, EventBus};
+use autosint_engine::flags::{self, FlagSet};
 use autosint_engine::graph;
+use autosint_engine::graph::linking::EntityLinker;
+use autosint_engine::http;
+use autosint_engine::ingest::enrichment::Enrichment;
+use autosint_engine::ingest::monitors::MonitorScheduler;
+use autosint_engine::ingest::{self, ImapIngest};
+use autosint_engine::jobs::{self, JobRegistry, JobRunner};
+use autosint_engine::maintenance::{self, ReadOnlyMode};
 use autosint_engine::orchestrator::Orchestrator;
-use autosint_engine::processor::{ProcessorPool, ProcessorPoolConfig};
+use autosint_engine::processor::{self, ProcessorPool, ProcessorPoolConfig};
 use autosint_engine::queue;
+use autosint_engine::retention;
 use autosint_engine::store;
-
-/// Shared application state accessible from axum handlers.
-struct AppState {
-    graph: Arc<graph::GraphClient>,
-    store: Arc<store::StoreClient>,
-    queue: Arc<queue::QueueClient>,
-    #[allow(dead_code)]
-    embedding_client: Option<Arc<embeddings::EmbeddingClient>>,
-    #[allow(dead_code)]
-    engine_config: Arc<config::EngineConfig>,
-    orchestrator: Arc<Orchestrator>,
-    metrics_handle: PrometheusHandle,
-}
+use autosint_engine::stubs;
+use autosint_engine::supervisor;
+use autosint_engine::telemetry;
+use autosint_engine::tools::{FetchClient, SharedServices};
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt()
-        .json()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
-        )
-        .init();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Subcommands print their result on stdout, so they log to stderr.
+    let subscriber = tracing_subscriber::fmt().json().with_env_filter(
+        tracing_subscriber::EnvFilter::from_default_env()
+            .add_directive(tracing::Level::INFO.into()),
+    );
+    if args.is_empty() {
+        subscriber.init();
+    } else {
+        subscriber.with_writer(std::io::stderr).init();
+    }
 
     tracing::info!("AutOSINT Engine starting");
 
@@ -58,10 +63,48 @@ async fn main() {
         }
     };
 
-    // Install Prometheus metrics recorder.
-    let metrics_handle = PrometheusBuilder::new()
+    if let Some(subcommand) = args.first() {
+        let code = match subcommand.as_str() {
+            "graph-consistency" => run_graph_consistency(&args[1..], &engine_config).await,
+            other => {
+                eprintln!(
+                    "Unknown subcommand '{}'. Usage: autosint-engine [graph-consistency [--repair [--apply]]]",
+                    other
+                );
+                1
+            }
+        };
+        std::process::exit(code);
+    }
+
+    // Outbound clients for LLM and embedding providers, with per-destination
+    // proxies from `[http]`.
+    if let Err(e) = http_client::init_shared(&engine_config.system.http) {
+        tracing::error!(error = %e, "Invalid HTTP client configuration — refusing to start");
+        std::process::exit(1);
+    }
+
+    // Optional TLS for the HTTP API: ENGINE_TLS_CERT / ENGINE_TLS_KEY, plus
+    // ENGINE_TLS_CLIENT_CA to require client certificates.
+    let server_tls = match tls::server_config_from_env("ENGINE") {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid TLS configuration — refusing to start");
+            std::process::exit(1);
+        }
+    };
+
+    // Install Prometheus metrics recorder, labeling every metric with the
+    // service, version and instance. ENGINE_METRICS_TOKEN protects /metrics.
+    let metrics_handle = MetricsLabels::from_env("ENGINE", "engine", env!("CARGO_PKG_VERSION"))
+        .builder()
         .install_recorder()
         .expect("Failed to install Prometheus metrics recorder");
+    let metrics_token = prometheus::token_from_env("ENGINE");
+
+    // Feature flags: defaults from `[flags]`, overridden for every replica
+    // through Redis (POST /admin/flags).
+    let flag_set = FlagSet::new(&engine_config.system.flags);
 
     // Connect to databases.
     let neo4j_uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
@@ -72,20 +115,47 @@ async fn main() {
     let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".into());
 
     // Neo4j
-    let graph_client =
-        match graph::GraphClient::connect(&neo4j_uri, &neo4j_user, &neo4j_password).await {
-            Ok(client) => client,
-            Err(e) => {
-                tracing::error!(error = %e, "Failed to connect to Neo4j");
-                std::process::exit(1);
-            }
-        };
+    let graph_client = match graph::GraphClient::connect_with_retry(
+        &neo4j_uri,
+        &neo4j_user,
+        &neo4j_password,
+        &engine_config.system.retry.databases,
+    )
+    .await
+    {
+        Ok(client) => client
+            .with_write_config(&engine_config.system.graph_writes)
+            .with_read_config(&engine_config.system.graph_reads)
+            .with_flags(flag_set.clone()),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to connect to Neo4j");
+            std::process::exit(1);
+        }
+    };
 
-    if let Err(e) = graph_client.initialize_schema().await {
+    if let Err(e) = graph_client
+        .initialize_schema(engine_config.system.embeddings.effective_dimensions())
+        .await
+    {
         tracing::error!(error = %e, "Failed to initialize Neo4j schema");
         std::process::exit(1);
     }
 
+    if let Err(e) = graph_client
+        .configure_claim_fulltext(engine_config.system.source_language.index_original_content)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to configure claim fulltext index");
+    }
+
+    // Properties written before type tags are read by guessing their type
+    // from the stored string; tag them once so reads stop guessing.
+    match graph_client.backfill_property_types().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(entities = count, "Tagged legacy entity property types"),
+        Err(e) => tracing::warn!(error = %e, "Failed to tag legacy entity property types"),
+    }
+
     let graph_client = Arc::new(graph_client);
 
     // PostgreSQL
@@ -122,6 +192,34 @@ async fn main() {
 
     tracing::info!("All databases connected and initialized");
 
+    // Read-only mode: pinned by `[maintenance]` / ENGINE_READ_ONLY, toggled
+    // for every replica through Redis (POST /admin/read_only).
+    let read_only = ReadOnlyMode::from_config(&engine_config.system.maintenance);
+    match queue_client.read_only_toggle().await {
+        Ok(enabled) => {
+            read_only.set_toggle(enabled);
+        }
+        Err(e) => tracing::warn!(error = %e, "Failed to read the read-only toggle"),
+    }
+    match queue_client.flag_overrides().await {
+        Ok(overrides) => {
+            flag_set.apply_overrides(&overrides);
+        }
+        Err(e) => tracing::warn!(error = %e, "Failed to read feature flag overrides"),
+    }
+    let _flag_sync_handle = flags::spawn_flag_sync(
+        flag_set.clone(),
+        Arc::clone(&queue_client),
+        engine_config.system.flags.clone(),
+    );
+
+    if read_only.is_enabled() {
+        tracing::warn!(
+            pinned = read_only.is_pinned(),
+            "Starting read-only: no writes or work order consumption until it is turned off"
+        );
+    }
+
     // Embedding client (optional — gracefully handle missing API key).
     let embedding_client = embeddings::EmbeddingClient::new(
         engine_config.system.embeddings.clone(),
@@ -133,24 +231,138 @@ async fn main() {
     if let Some(ref client) = embedding_client {
         let _backfill_handle = embeddings::spawn_backfill_task(
             Arc::clone(&graph_client),
+            Arc::clone(&store_client),
             Arc::clone(client),
             engine_config.system.embeddings.backfill_interval_minutes,
             engine_config.system.embeddings.batch_size,
+            engine_config.system.embeddings.claim_chunks.clone(),
+            read_only.clone(),
         );
     }
 
-    let fetch_base_url =
+    // FETCH_BASE_URL may list several Fetch service replicas, comma-separated.
+    // Tool calls are spread over them (`[http.fetch_replicas]`); monitors,
+    // metrics federation and the client's TLS settings use the first.
+    let fetch_base_urls =
         std::env::var("FETCH_BASE_URL").unwrap_or_else(|_| "http://localhost:8081".into());
+    let fetch_base_url = fetch_base_urls
+        .split(',')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    // Client for the fetch service. With an https FETCH_BASE_URL, FETCH_CLIENT_TLS_CA
+    // pins the CA and FETCH_CLIENT_TLS_CERT / FETCH_CLIENT_TLS_KEY present a client
+    // certificate. `[http.proxies].internal` and `[http].no_proxy` apply on top.
+    let fetch_http = match tls::client_builder_from_env("FETCH_CLIENT", &fetch_base_url)
+        .map_err(|e| e.to_string())
+        .and_then(|b| {
+            http_client::configure(b, &engine_config.system.http, Destination::Internal)
+                .map_err(|e| e.to_string())
+        })
+        .and_then(|b| b.build().map_err(|e| e.to_string()))
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid fetch client configuration — refusing to start");
+            std::process::exit(1);
+        }
+    };
+
+    let fetch_client = FetchClient::new(
+        &fetch_base_urls,
+        fetch_http.clone(),
+        engine_config.system.http.fetch_replicas.clone(),
+    );
 
     let engine_config = Arc::new(engine_config);
     let tool_schemas = Arc::new(engine_config.tool_schemas.clone());
 
+    // Live prompt templates, replaced by POST /config/prompts/reload.
+    let prompts = PromptStore::new(engine_config.prompts.clone());
+
+    // Built once; every claim tool shares the compiled detectors.
+    let pii_scrubber = engine_config
+        .system
+        .pii
+        .enabled
+        .then(|| PiiScrubber::new(&engine_config.system.pii).map(Arc::new))
+        .transpose()
+        .unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Invalid PII configuration — refusing to start");
+            std::process::exit(1);
+        });
+
+    // Domain events: the timeline, the durable stream when configured, and
+    // webhooks.
+    let event_bus = EventBus::from_config(
+        &engine_config.system.events,
+        Arc::clone(&store_client),
+        Arc::clone(&queue_client),
+    );
+    let instance_id = processor::pool_instance_id();
+    let _webhook_handles = events::spawn_webhooks(
+        &engine_config.system.events,
+        &event_bus,
+        Arc::clone(&queue_client),
+        &instance_id,
+        http_client::shared(Destination::Fetch),
+    )
+    .unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Invalid events configuration — refusing to start");
+        std::process::exit(1);
+    });
+
+    // External enrichment providers answer through /callbacks/enrichment.
+    let enrichment = engine_config
+        .system
+        .ingest
+        .enrichment
+        .enabled
+        .then(|| {
+            Enrichment::from_env(
+                engine_config.system.ingest.enrichment.clone(),
+                Arc::clone(&store_client),
+                Arc::clone(&queue_client),
+                http_client::shared(Destination::Fetch),
+            )
+            .map(|enrichment| Arc::new(enrichment.with_events(event_bus.clone())))
+        })
+        .transpose()
+        .unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Invalid enrichment configuration — refusing to start");
+            std::process::exit(1);
+        });
+
+    // Audit log writer (records mutating tool calls and HTTP writes).
+    let (audit_log, _audit_handle) =
+        AuditLog::spawn(Arc::clone(&store_client), &engine_config.system.audit);
+
+    // Clients and configuration shared by every tool session.
+    let services = SharedServices::new(
+        Arc::clone(&graph_client),
+        fetch_base_url.clone(),
+        fetch_http.clone(),
+        Arc::clone(&tool_schemas),
+        engine_config.system.tool_results.clone(),
+        engine_config.system.dedup.clone(),
+    )
+    .with_store(Arc::clone(&store_client))
+    .with_events(event_bus.clone())
+    .with_queue(Arc::clone(&queue_client))
+    .with_fetch_client(fetch_client.clone())
+    .with_embedding_client(embedding_client.clone())
+    .with_work_order_proposals(engine_config.system.work_order_proposals.clone())
+    .with_exploration(engine_config.system.exploration.clone())
+    .with_verification(engine_config.system.verification.clone())
+    .with_entity_kinds(engine_config.system.entity_kinds.clone())
+    .with_pii_scrubber(pii_scrubber)
+    .with_enrichment(enrichment.clone())
+    .with_flags(flag_set.clone())
+    .with_audit_log(audit_log.clone());
+
     // Start Processor pool (if LLM key available).
-    let processor_prompt = engine_config
-        .prompts
-        .get("processor")
-        .cloned()
-        .unwrap_or_default();
 
     let _processor_pool = if autosint_engine::llm::LlmClient::new(
         engine_config.system.llm.processor.clone(),
@@ -158,26 +370,32 @@ async fn main() {
     )
     .is_some()
     {
+        let entity_linking = &engine_config.system.entity_linking;
+        let entity_linker = entity_linking.enabled.then(|| {
+            Arc::new(EntityLinker::new(
+                Arc::clone(&graph_client),
+                entity_linking.clone(),
+            ))
+        });
+
         let pool_config = ProcessorPoolConfig {
             pool_size: engine_config.system.concurrency.processor_pool_size,
             heartbeat_ttl_seconds: engine_config.system.safety.heartbeat_ttl_seconds,
             heartbeat_interval_seconds: engine_config.system.safety.heartbeat_ttl_seconds / 3,
+            instance_id: instance_id.clone(),
         };
 
         let pool = ProcessorPool::start(
             pool_config,
             engine_config.system.llm.processor.clone(),
             engine_config.system.retry.llm_api.clone(),
-            Arc::clone(&graph_client),
-            embedding_client.clone(),
+            services.clone().with_entity_linker(entity_linker),
             Arc::clone(&store_client),
             Arc::clone(&queue_client),
-            fetch_base_url.clone(),
-            processor_prompt,
-            Arc::clone(&tool_schemas),
-            engine_config.system.tool_results.clone(),
-            engine_config.system.dedup.clone(),
+            prompts.clone(),
             engine_config.system.safety.clone(),
+            engine_config.system.entity_shortlist.clone(),
+            read_only.clone(),
         );
 
         tracing::info!("Processor pool started");
@@ -191,58 +409,253 @@ async fn main() {
     let circuit_breakers = Arc::new(CircuitBreakerRegistry::new());
 
     // Create Orchestrator.
-    let analyst_prompt = engine_config
-        .prompts
-        .get("analyst")
-        .cloned()
-        .unwrap_or_default();
+    let orchestrator = Arc::new(
+        Orchestrator::new(
+            Arc::clone(&graph_client),
+            Arc::clone(&store_client),
+            Arc::clone(&queue_client),
+            embedding_client.clone(),
+            Arc::clone(&engine_config),
+            fetch_base_url.clone(),
+            fetch_http.clone(),
+            Arc::clone(&tool_schemas),
+            prompts.clone(),
+            Arc::clone(&circuit_breakers),
+        )
+        .with_read_only(read_only.clone())
+        .with_fetch_client(fetch_client)
+        .with_flags(flag_set.clone())
+        .with_events(event_bus.clone()),
+    );
+
+    // Keep read-only mode in step with the shared toggle; resumes the
+    // investigations it suspended when it goes off.
+    let _read_only_handle = maintenance::spawn_read_only_sync(
+        read_only.clone(),
+        Arc::clone(&queue_client),
+        Arc::clone(&orchestrator),
+        engine_config.system.maintenance.clone(),
+    );
 
-    let orchestrator = Arc::new(Orchestrator::new(
-        Arc::clone(&graph_client),
-        Arc::clone(&store_client),
+    // Recover any non-terminal investigations from before restart. Runs in
+    // the background so HTTP serving starts at once; GET /recovery/last
+    // reports progress.
+    {
+        let orchestrator = Arc::clone(&orchestrator);
+        supervisor::spawn_supervised("startup_recovery", async move {
+            if let Err(e) = orchestrator.recover_on_startup().await {
+                tracing::error!(error = %e, "Failed to recover investigations on startup");
+            }
+        });
+    }
+
+    // Optional canary self-test of the full pipeline (ENGINE_RUN_SELFTEST_ON_START).
+    // A failure is logged and reported as degraded on /health; startup continues.
+    if std::env::var("ENGINE_RUN_SELFTEST_ON_START").is_ok_and(|v| v == "true") {
+        let orchestrator = Arc::clone(&orchestrator);
+        supervisor::spawn_supervised("selftest", async move {
+            orchestrator.run_selftest().await;
+        });
+    }
+
+    // Spawn the mover that releases delayed work orders onto the priority streams.
+    let _delayed_mover_handle = queue::delayed::spawn_delayed_mover(
         Arc::clone(&queue_client),
+        Arc::clone(&store_client),
+        event_bus.clone(),
+        read_only.clone(),
+    );
+
+    // Spawn the reporter that exports queued work order wait ages.
+    let _wait_age_handle = queue::wait_metrics::spawn_wait_age_reporter(Arc::clone(&store_client));
+
+    // Spawn relationship weight recalibration task.
+    if engine_config.system.relationship_weights.enabled {
+        let _weights_handle = graph::weights::spawn_weight_recalibration_task(
+            Arc::clone(&graph_client),
+            Arc::clone(&store_client),
+            engine_config.system.relationship_weights.clone(),
+            read_only.clone(),
+        );
+    }
+
+    // Spawn the periodic graph consistency check (exports violation gauges).
+    if engine_config
+        .system
+        .graph_consistency
+        .check_interval_minutes
+        > 0
+    {
+        let _consistency_handle = graph::consistency::spawn_consistency_check_task(
+            Arc::clone(&graph_client),
+            Arc::clone(&store_client),
+            engine_config.system.graph_consistency.clone(),
+            read_only.clone(),
+        );
+    }
+
+    // Spawn deadline watchdog (flags waiting work orders close to their deadline).
+    let _deadline_handle = processor::spawn_deadline_watchdog(
+        Arc::clone(&store_client),
+        engine_config.system.deadlines.clone(),
+    );
+
+    // Spawn the maintenance job runner (works through POST /admin/jobs jobs,
+    // resuming any interrupted by a restart).
+    let job_registry = Arc::new(JobRegistry::standard(
+        Arc::clone(&graph_client),
         embedding_client.clone(),
-        Arc::clone(&engine_config),
-        fetch_base_url,
-        Arc::clone(&tool_schemas),
-        analyst_prompt,
-        Arc::clone(&circuit_breakers),
+        &engine_config.system.source_language,
     ));
+    let _job_runner_handle = jobs::spawn_job_runner(
+        JobRunner::new(
+            Arc::clone(&store_client),
+            Arc::clone(&job_registry),
+            engine_config.system.jobs.clone(),
+        ),
+        read_only.clone(),
+    );
+
+    // Spawn retention task (purges old terminal investigations).
+    if engine_config.system.retention.enabled {
+        let _retention_handle = retention::spawn_retention_task(
+            Arc::clone(&graph_client),
+            Arc::clone(&store_client),
+            engine_config.system.retention.clone(),
+            read_only.clone(),
+        );
+    }
+
+    // Spawn telemetry rollups (session statistics per prompt hash).
+    if engine_config.system.telemetry.enabled {
+        let pusher = telemetry::RollupPusher::from_env(
+            &engine_config.system.telemetry,
+            http_client::shared(Destination::Fetch),
+        )
+        .unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Invalid telemetry configuration — refusing to start");
+            std::process::exit(1);
+        });
+        let _telemetry_handle = telemetry::spawn_telemetry_task(
+            Arc::clone(&store_client),
+            engine_config.system.telemetry.clone(),
+            pusher,
+            read_only.clone(),
+        );
+    }
 
-    // Recover any non-terminal investigations from before restart.
-    if let Err(e) = orchestrator.recover_on_startup().await {
-        tracing::error!(error = %e, "Failed to recover investigations on startup");
+    // Spawn stub resolution (merges or queues profiling for mentioned stubs).
+    if engine_config.system.stub_resolution.enabled {
+        let _stubs_handle = stubs::spawn_stub_resolution_task(
+            Arc::clone(&graph_client),
+            Arc::clone(&store_client),
+            Arc::clone(&queue_client),
+            audit_log.clone(),
+            event_bus.clone(),
+            engine_config.system.stub_resolution.clone(),
+            read_only.clone(),
+        );
+    }
+
+    // Spawn the IMAP newsletter poller (feeds ingest_document work orders).
+    if engine_config.system.ingest.imap.enabled {
+        let _ingest_handle = ingest::spawn_imap_ingest(
+            Arc::new(
+                ImapIngest::new(
+                    engine_config.system.ingest.imap.clone(),
+                    engine_config.system.dedup.clone(),
+                    Arc::clone(&graph_client),
+                    Arc::clone(&store_client),
+                    Arc::clone(&queue_client),
+                )
+                .with_events(event_bus.clone()),
+            ),
+            read_only.clone(),
+        );
+    }
+
+    // Spawn the source monitor scheduler (feeds ingest_document work orders).
+    if engine_config.system.ingest.monitors.enabled {
+        let _monitor_handle = ingest::monitors::spawn_monitor_scheduler(
+            Arc::new(
+                MonitorScheduler::new(
+                    engine_config.system.ingest.monitors.clone(),
+                    Arc::clone(&store_client),
+                    Arc::clone(&queue_client),
+                    fetch_base_url.clone(),
+                    fetch_http,
+                )
+                .with_events(event_bus.clone()),
+            ),
+            read_only.clone(),
+        );
+    }
+
+    let admin_key = std::env::var("AUTOSINT_ADMIN_KEY")
+        .ok()
+        .filter(|k| !k.is_empty());
+    if admin_key.is_none() {
+        tracing::warn!("AUTOSINT_ADMIN_KEY not set — admin endpoints disabled");
     }
 
     // Spawn circuit breaker metrics reporter.
     {
         let cbs = Arc::clone(&circuit_breakers);
-        tokio::spawn(async move {
-            let interval = std::time::Duration::from_secs(30);
-            loop {
-                tokio::time::sleep(interval).await;
-                cbs.report_metrics();
+        supervisor::spawn_supervised_restarting("circuit_breaker_reporter", move || {
+            let cbs = Arc::clone(&cbs);
+            async move {
+                let interval = std::time::Duration::from_secs(30);
+                loop {
+                    tokio::time::sleep(interval).await;
+                    cbs.report_metrics();
+                }
             }
         });
     }
 
+    // Investigation prompt pre-flight (optional — skipped without a triage LLM).
+    let triage_llm = engine_config.system.llm.triage.clone().and_then(|config| {
+        autosint_engine::llm::LlmClient::new(config, engine_config.system.retry.llm_api.clone())
+            .map(|client| {
+                Arc::new(client.with_flags(flag_set.clone()))
+                    as Arc<dyn autosint_engine::llm::LlmCaller>
+            })
+    });
+    if triage_llm.is_none() {
+        tracing::info!("Triage LLM not available — investigation pre-flight disabled");
+    }
+
+    // GET /metrics/federate (optional — `metrics.federate`).
+    let metrics_federation = engine_config.system.metrics.federate.then(|| {
+        Arc::new(http::federate::Federation::from_env(
+            &fetch_base_url,
+            std::time::Duration::from_millis(engine_config.system.metrics.federate_timeout_ms),
+        ))
+    });
+
     // Build shared state.
-    let state = Arc::new(AppState {
+    let state = Arc::new(http::AppState {
         graph: graph_client,
         store: store_client,
         queue: queue_client,
         embedding_client,
         engine_config,
+        prompts,
         orchestrator,
         metrics_handle,
+        metrics_token,
+        metrics_federation,
+        admin_key,
+        triage_llm,
+        read_only,
+        flags: flag_set,
+        enrichment,
+        audit: audit_log,
+        jobs: job_registry,
     });
 
     // Build HTTP server.
-    let app = Router::new()
-        .route("/health", get(health_handler))
-        .route("/metrics", get(metrics_handler))
-        .route("/investigate", post(investigate_handler))
-        .with_state(state);
+    let app = http::router(state);
 
     let port: u16 = std::env::var("ENGINE_PORT")
         .ok()
@@ -253,83 +666,78 @@ async fn main() {
         .await
         .expect("Failed to bind TCP listener");
 
-    tracing::info!(port = port, "AutOSINT Engine listening");
+    tracing::info!(
+        port = port,
+        tls = server_tls.is_some(),
+        "AutOSINT Engine listening"
+    );
 
-    axum::serve(listener, app).await.expect("HTTP server error");
+    tls::serve(listener, app, server_tls)
+        .await
+        .expect("HTTP server error");
 }
 
-/// Health check endpoint. Checks all three database connections.
-async fn health_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let neo4j_ok = state.graph.health_check().await.is_ok();
-    let postgres_ok = state.store.health_check().await.is_ok();
-    let redis_ok = state.queue.health_check().await.is_ok();
+/// `graph-consistency [--repair [--apply]]`: run the graph consistency checks
+/// and print the report as JSON. With `--repair`, print the safe repairs
+/// instead — a dry run unless `--apply` is also given. Returns the exit code:
+/// 2 when the checks find error-severity violations, 1 on failure.
+async fn run_graph_consistency(args: &[String], engine_config: &config::EngineConfig) -> i32 {
+    let repair = args.iter().any(|a| a == "--repair");
+    let apply = args.iter().any(|a| a == "--apply");
+    if let Some(unknown) = args.iter().find(|a| *a != "--repair" && *a != "--apply") {
+        eprintln!("Unknown graph-consistency option '{}'", unknown);
+        return 1;
+    }
+    if apply && !repair {
+        eprintln!("--apply requires --repair");
+        return 1;
+    }
 
-    let all_healthy = neo4j_ok && postgres_ok && redis_ok;
+    let neo4j_uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let neo4j_user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let neo4j_password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+    let graph_client =
+        match graph::GraphClient::connect(&neo4j_uri, &neo4j_user, &neo4j_password).await {
+            Ok(client) => client.with_write_config(&engine_config.system.graph_writes),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to connect to Neo4j");
+                return 1;
+            }
+        };
 
-    let status = if all_healthy {
-        StatusCode::OK
+    let config = &engine_config.system.graph_consistency;
+    let (output, code) = if repair {
+        match graph_client.repair_consistency(config, !apply).await {
+            Ok(report) => (serde_json::to_string_pretty(&report), 0),
+            Err(e) => {
+                tracing::error!(error = %e, "Graph consistency repair failed");
+                return 1;
+            }
+        }
     } else {
-        StatusCode::SERVICE_UNAVAILABLE
-    };
-
-    let body = serde_json::json!({
-        "status": if all_healthy { "healthy" } else { "unhealthy" },
-        "services": {
-            "neo4j": if neo4j_ok { "healthy" } else { "unhealthy" },
-            "postgres": if postgres_ok { "healthy" } else { "unhealthy" },
-            "redis": if redis_ok { "healthy" } else { "unhealthy" },
+        match graph_client.check_consistency(config).await {
+            Ok(report) => {
+                let errors = report.violations(ConsistencySeverity::Error);
+                (
+                    serde_json::to_string_pretty(&report),
+                    if errors > 0 { 2 } else { 0 },
+                )
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Graph consistency check failed");
+                return 1;
+            }
         }
-    });
-
-    (status, Json(body))
-}
-
-/// Prometheus metrics endpoint.
-async fn metrics_handler(State(state): State<Arc<AppState>>) -> String {
-    state.metrics_handle.render()
-}
-
-/// Request body for starting an investigation.
-#[derive(Deserialize)]
-struct InvestigateRequest {
-    prompt: String,
-}
-
-/// POST /investigate — start a new investigation.
-async fn investigate_handler(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<InvestigateRequest>,
-) -> impl IntoResponse {
-    let orchestrator = Arc::clone(&state.orchestrator);
-
-    match orchestrator.start_investigation(&req.prompt).await {
-        Ok(investigation_id) => {
-            // Spawn investigation lifecycle in background.
-            let orch = Arc::clone(&state.orchestrator);
-            let inv_id = investigation_id;
-            tokio::spawn(async move {
-                if let Err(e) = orch.run_investigation(inv_id).await {
-                    tracing::error!(
-                        investigation_id = %inv_id,
-                        error = %e,
-                        "Investigation failed"
-                    );
-                }
-            });
-
-            let body = serde_json::json!({
-                "investigation_id": investigation_id.to_string(),
-                "status": "pending",
-                "message": "Investigation started."
-            });
+    };
 
-            (StatusCode::ACCEPTED, Json(body))
+    match output {
+        Ok(json) => {
+            println!("{}", json);
+            code
         }
         Err(e) => {
-            let body = serde_json::json!({
-                "error": e,
-            });
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(body))
+            tracing::error!(error = %e, "Failed to serialize report");
+            1
         }
     }
 }
@@ -0,0 +1,210 @@
+//! Read-only mode for maintenance windows such as Neo4j upgrades. While it is
+//! on, the engine keeps answering status and read queries but mutates
+//! nothing: mutating API calls get 503, investigations are suspended with
+//! reason [`MAINTENANCE_REASON`] instead of starting new cycles, Processors
+//! stop dequeuing, and background tasks that write skip their runs.
+//!
+//! The runtime toggle (POST /admin/read_only) lives in Redis so every replica
+//! agrees; `maintenance.read_only` or `ENGINE_READ_ONLY=true` pins a replica
+//! read-only whatever the toggle says. Turning the mode off resumes the
+//! investigations it suspended.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use autosint_common::config::MaintenanceConfig;
+
+use crate::orchestrator::Orchestrator;
+use crate::queue::QueueClient;
+use crate::supervisor;
+
+/// `suspended_reason` of investigations suspended by read-only mode.
+pub const MAINTENANCE_REASON: &str = "maintenance";
+
+/// This replica's view of read-only mode. Clones share state.
+#[derive(Clone)]
+pub struct ReadOnlyMode {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    pinned: bool,
+    toggle: AtomicBool,
+    /// Effective state: pinned or toggled on.
+    state: watch::Sender<bool>,
+}
+
+impl Default for ReadOnlyMode {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl ReadOnlyMode {
+    /// `pinned` keeps this replica read-only whatever the toggle says.
+    pub fn new(pinned: bool) -> Self {
+        let (state, _) = watch::channel(pinned);
+        metrics::gauge!("engine.read_only").set(if pinned { 1.0 } else { 0.0 });
+        Self {
+            inner: Arc::new(Inner {
+                pinned,
+                toggle: AtomicBool::new(false),
+                state,
+            }),
+        }
+    }
+
+    /// From `[maintenance]`, with `ENGINE_READ_ONLY=true` pinning too.
+    pub fn from_config(config: &MaintenanceConfig) -> Self {
+        let env = std::env::var("ENGINE_READ_ONLY").is_ok_and(|v| v == "true");
+        Self::new(config.read_only || env)
+    }
+
+    /// Whether this replica is read-only now.
+    pub fn is_enabled(&self) -> bool {
+        *self.inner.state.borrow()
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.inner.pinned
+    }
+
+    /// The shared toggle, as last read or set.
+    pub fn toggle(&self) -> bool {
+        self.inner.toggle.load(Ordering::SeqCst)
+    }
+
+    /// Record the shared toggle. Returns whether the effective state changed.
+    pub fn set_toggle(&self, enabled: bool) -> bool {
+        self.inner.toggle.store(enabled, Ordering::SeqCst);
+        let read_only = self.inner.pinned || enabled;
+        let changed = self.inner.state.send_if_modified(|state| {
+            let changed = *state != read_only;
+            *state = read_only;
+            changed
+        });
+        if changed {
+            if read_only {
+                tracing::warn!("Read-only mode on: pausing writes and work order consumption");
+            } else {
+                tracing::info!("Read-only mode off: resuming writes");
+            }
+            metrics::gauge!("engine.read_only").set(if read_only { 1.0 } else { 0.0 });
+        }
+        changed
+    }
+
+    /// Receives the effective state on every change.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.inner.state.subscribe()
+    }
+
+    /// Wait until the engine may write again; immediate when it already may.
+    pub async fn wait_until_writable(&self) {
+        let mut state = self.subscribe();
+        // Err only if the sender is gone, which `self` rules out.
+        let _ = state.wait_for(|read_only| !*read_only).await;
+    }
+}
+
+/// Spawn the task that keeps this replica's read-only mode in step with the
+/// shared toggle, and resumes the investigations read-only mode suspended
+/// once it is turned off.
+pub fn spawn_read_only_sync(
+    mode: ReadOnlyMode,
+    queue: Arc<QueueClient>,
+    orchestrator: Arc<Orchestrator>,
+    config: MaintenanceConfig,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.sync_interval_seconds);
+
+    supervisor::spawn_supervised_restarting("read_only_sync", move || {
+        let mode = mode.clone();
+        let queue = Arc::clone(&queue);
+        let orchestrator = Arc::clone(&orchestrator);
+        async move {
+            let mut changes = mode.subscribe();
+            let mut was_read_only = mode.is_enabled();
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {
+                        match queue.read_only_toggle().await {
+                            Ok(enabled) => {
+                                mode.set_toggle(enabled);
+                            }
+                            Err(e) => {
+                                tracing::warn!(error = %e, "Failed to read the read-only toggle");
+                            }
+                        }
+                    }
+                    // Toggled through this replica's API.
+                    _ = changes.changed() => {}
+                }
+
+                let read_only = *changes.borrow_and_update();
+                if was_read_only && !read_only {
+                    match orchestrator.resume_after_maintenance().await {
+                        Ok(resumed) => tracing::info!(
+                            resumed,
+                            "Resumed investigations suspended for maintenance"
+                        ),
+                        Err(e) => tracing::error!(
+                            error = %e,
+                            "Failed to resume investigations suspended for maintenance"
+                        ),
+                    }
+                }
+                was_read_only = read_only;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_changes_effective_state() {
+        let mode = ReadOnlyMode::default();
+        assert!(!mode.is_enabled());
+        assert!(mode.set_toggle(true));
+        assert!(mode.is_enabled());
+        assert!(!mode.set_toggle(true));
+        assert!(mode.set_toggle(false));
+        assert!(!mode.is_enabled());
+    }
+
+    #[test]
+    fn test_pinned_ignores_toggle_off() {
+        let mode = ReadOnlyMode::new(true);
+        assert!(mode.is_enabled());
+        assert!(!mode.set_toggle(true));
+        assert!(!mode.set_toggle(false));
+        assert!(mode.is_enabled());
+        assert!(!mode.toggle());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_writable() {
+        let mode = ReadOnlyMode::default();
+        mode.wait_until_writable().await;
+
+        mode.set_toggle(true);
+        let waiter = tokio::spawn({
+            let mode = mode.clone();
+            async move { mode.wait_until_writable().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        mode.set_toggle(false);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should wake once writable")
+            .unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use axum::http::StatusCode;
+
+use crate::graph::GraphError;
+use crate::queue::QueueError;
+use crate::store::StoreError;
+
+/// Errors surfaced by the Orchestrator state machine.
+#[derive(Debug, thiserror::Error)]
+pub enum OrchestratorError {
+    #[error(transparent)]
+    Store(#[from] StoreError),
+
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    #[error(transparent)]
+    Queue(#[from] QueueError),
+
+    #[error("Startup recovery failed: {0}")]
+    RecoveryFailed(#[source] StoreError),
+
+    #[error("Analyst session failed: {0}")]
+    AnalystSessionFailed(String),
+
+    #[error("Timed out after {0:?} waiting for work orders")]
+    WaitTimeout(Duration),
+
+    /// The investigation's state or ownership doesn't allow the request.
+    #[error("{0}")]
+    Conflict(String),
+}
+
+impl OrchestratorError {
+    /// HTTP status for API handlers surfacing this error.
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            Self::Store(StoreError::NotFound(_)) | Self::Graph(GraphError::NotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            Self::Store(StoreError::Connection(_))
+            | Self::Graph(GraphError::Connection(_))
+            | Self::Queue(QueueError::Connection(_)) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::AnalystSessionFailed(_) => StatusCode::BAD_GATEWAY,
+            Self::WaitTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::Store(_) | Self::Graph(_) | Self::Queue(_) | Self::RecoveryFailed(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl From<OrchestratorError> for autosint_common::AutOsintError {
+    fn from(e: OrchestratorError) -> Self {
+        use autosint_common::AutOsintError;
+        match e {
+            OrchestratorError::Store(StoreError::NotFound(msg)) => AutOsintError::NotFound(msg),
+            OrchestratorError::Store(e) | OrchestratorError::RecoveryFailed(e) => e.into(),
+            OrchestratorError::Graph(e) => e.into(),
+            OrchestratorError::Queue(e) => e.into(),
+            OrchestratorError::AnalystSessionFailed(msg) => AutOsintError::LlmApi(msg),
+            OrchestratorError::WaitTimeout(_) => AutOsintError::Timeout(e.to_string()),
+            OrchestratorError::Conflict(msg) => AutOsintError::Validation(msg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autosint_common::AutOsintError;
+
+    #[test]
+    fn test_http_status_mapping() {
+        assert_eq!(
+            OrchestratorError::Store(StoreError::NotFound("x".into())).http_status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            OrchestratorError::Store(StoreError::Connection("down".into())).http_status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            OrchestratorError::Queue(QueueError::Connection("down".into())).http_status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            OrchestratorError::Store(StoreError::Query("bad".into())).http_status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            OrchestratorError::AnalystSessionFailed("no key".into()).http_status(),
+            StatusCode::BAD_GATEWAY
+        );
+        assert_eq!(
+            OrchestratorError::WaitTimeout(Duration::from_secs(1)).http_status(),
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            OrchestratorError::Conflict("completed".into()).http_status(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            OrchestratorError::RecoveryFailed(StoreError::Query("bad".into())).http_status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn test_conversion_to_autosint_error() {
+        let e: AutOsintError = OrchestratorError::Store(StoreError::NotFound("inv".into())).into();
+        assert!(matches!(e, AutOsintError::NotFound(_)));
+
+        let e: AutOsintError = OrchestratorError::Store(StoreError::Query("q".into())).into();
+        assert!(matches!(e, AutOsintError::Postgres(_)));
+        assert!(e.is_hard_dependency());
+
+        let e: AutOsintError = OrchestratorError::AnalystSessionFailed("x".into()).into();
+        assert!(matches!(e, AutOsintError::LlmApi(_)));
+
+        let e: AutOsintError = OrchestratorError::WaitTimeout(Duration::from_secs(5)).into();
+        assert!(matches!(e, AutOsintError::Timeout(_)));
+    }
+}
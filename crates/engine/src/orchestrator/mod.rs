@@ -1,3 +1,12 @@
+mod error;
+mod operator;
+mod recovery;
+mod retry;
+mod selftest;
 mod state_machine;
 
+pub use error::OrchestratorError;
+pub use operator::{FailOutcome, ResumeOutcome};
+pub use recovery::last_recovery;
+pub use selftest::{last_selftest, SELFTEST_CORRELATION_ID};
 pub use state_machine::Orchestrator;
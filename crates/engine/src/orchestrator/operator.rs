@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use autosint_common::ids::InvestigationId;
+use autosint_common::types::InvestigationStatus;
+
+use crate::maintenance::MAINTENANCE_REASON;
+use crate::queue::ownership::{self, token_purpose};
+use crate::supervisor;
+
+use super::{Orchestrator, OrchestratorError};
+
+/// What an operator resume did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResumeOutcome {
+    /// A driver was spawned.
+    Started,
+    /// A task already drives the investigation; nothing was done.
+    AlreadyRunning,
+}
+
+/// What an operator force-fail did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailOutcome {
+    /// The partial assessment ran and the investigation is now Failed.
+    Failed,
+    /// The investigation was already Failed; nothing was done.
+    AlreadyFailed,
+}
+
+impl Orchestrator {
+    /// Operator resume: spawn a driver for a non-terminal investigation no
+    /// task owns, such as one left Suspended after an outage. Idempotent: an
+    /// investigation with a live driver is left to it.
+    pub async fn resume_investigation(
+        self: &Arc<Self>,
+        id: InvestigationId,
+    ) -> Result<ResumeOutcome, OrchestratorError> {
+        let investigation = self.load_investigation(id).await?;
+        if investigation.status.is_terminal() {
+            return Err(OrchestratorError::Conflict(format!(
+                "Investigation is {}",
+                investigation.status.as_db_str()
+            )));
+        }
+        if investigation.canary {
+            return Err(OrchestratorError::Conflict(
+                "Self-test investigations are not resumed".into(),
+            ));
+        }
+
+        match self.queue.investigation_owner(id).await? {
+            Some(token) if token_purpose(&token) == ownership::FORCE_FAIL => {
+                return Err(OrchestratorError::Conflict(
+                    "Investigation is being force-failed".into(),
+                ));
+            }
+            Some(_) => return Ok(ResumeOutcome::AlreadyRunning),
+            None => {}
+        }
+
+        // The driver takes the lease itself; if a concurrent resume or
+        // recovery gets there first, this one returns at once.
+        tracing::info!(
+            investigation_id = %id,
+            status = investigation.status.as_db_str(),
+            "Operator resuming investigation"
+        );
+        metrics::counter!("investigations.operator.resumed").increment(1);
+        let orchestrator = Arc::clone(self);
+        supervisor::spawn_supervised("investigation_resume", async move {
+            if let Err(e) = orchestrator.run_investigation(id).await {
+                tracing::error!(
+                    investigation_id = %id,
+                    error = %e,
+                    "Resumed investigation failed"
+                );
+            }
+        });
+        Ok(ResumeOutcome::Started)
+    }
+
+    /// Resume every investigation read-only mode suspended. Returns how many
+    /// drivers were started; those already driven elsewhere (another replica
+    /// got there first) are skipped.
+    pub async fn resume_after_maintenance(self: &Arc<Self>) -> Result<usize, OrchestratorError> {
+        let suspended = self
+            .store
+            .get_non_terminal_investigations()
+            .await?
+            .into_iter()
+            .filter(|i| {
+                i.status == InvestigationStatus::Suspended
+                    && i.suspended_reason.as_deref() == Some(MAINTENANCE_REASON)
+            });
+
+        let mut started = 0;
+        for investigation in suspended {
+            match self.resume_investigation(investigation.id).await {
+                Ok(ResumeOutcome::Started) => started += 1,
+                Ok(ResumeOutcome::AlreadyRunning) => {}
+                Err(e) => tracing::warn!(
+                    investigation_id = %investigation.id,
+                    error = %e,
+                    "Failed to resume investigation after maintenance"
+                ),
+            }
+        }
+        Ok(started)
+    }
+
+    /// Operator force-fail: run the failure-mode partial assessment and mark
+    /// the investigation Failed. Only while no driver owns it — a running
+    /// investigation fails through its own limits. Idempotent for an
+    /// investigation already Failed.
+    pub async fn force_fail_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> Result<FailOutcome, OrchestratorError> {
+        let investigation = self.load_investigation(id).await?;
+        match investigation.status {
+            InvestigationStatus::Failed => return Ok(FailOutcome::AlreadyFailed),
+            InvestigationStatus::Completed => {
+                return Err(OrchestratorError::Conflict(
+                    "Investigation is completed".into(),
+                ));
+            }
+            _ => {}
+        }
+
+        let Some(lease) = self.acquire_lease(id, ownership::FORCE_FAIL).await? else {
+            let message = match self.queue.investigation_owner(id).await? {
+                Some(token) if token_purpose(&token) == ownership::FORCE_FAIL => {
+                    "Investigation is already being force-failed"
+                }
+                _ => "Investigation is being driven by a running task",
+            };
+            return Err(OrchestratorError::Conflict(message.into()));
+        };
+
+        // The status may have moved between the read and taking the lease.
+        let result = async {
+            let investigation = self.load_investigation(id).await?;
+            if investigation.status == InvestigationStatus::Failed {
+                return Ok(FailOutcome::AlreadyFailed);
+            }
+            if investigation.status == InvestigationStatus::Completed {
+                return Err(OrchestratorError::Conflict(
+                    "Investigation is completed".into(),
+                ));
+            }
+            tracing::warn!(
+                investigation_id = %id,
+                status = investigation.status.as_db_str(),
+                "Operator force-failing investigation"
+            );
+            metrics::counter!("investigations.operator.failed").increment(1);
+            self.transition_to_failed(id, &investigation).await?;
+            Ok(FailOutcome::Failed)
+        }
+        .await;
+        lease.release().await;
+        result
+    }
+}
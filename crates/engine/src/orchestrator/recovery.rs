@@ -0,0 +1,406 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use tokio::sync::{mpsc, Semaphore};
+
+use autosint_common::api::engine::{RecoveredInvestigation, RecoveryOutcome, RecoveryReport};
+use autosint_common::ids::InvestigationId;
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{Investigation, InvestigationStatus};
+
+use crate::queue::ownership;
+use crate::supervisor;
+
+use super::retry::retry_store_op;
+use super::{Orchestrator, OrchestratorError};
+
+/// Most recent recovery report. Uses std::sync::Mutex because it is never held
+/// across await points.
+fn last_report() -> &'static Mutex<Option<RecoveryReport>> {
+    static LAST_REPORT: OnceLock<Mutex<Option<RecoveryReport>>> = OnceLock::new();
+    LAST_REPORT.get_or_init(|| Mutex::new(None))
+}
+
+/// The most recent startup recovery report since process start, complete or
+/// still under way. Served on GET /recovery/last.
+pub fn last_recovery() -> Option<RecoveryReport> {
+    last_report().lock().unwrap().clone()
+}
+
+/// How recovery handles an investigation before any driver starts.
+enum Plan {
+    Resume,
+    Settle(RecoveryOutcome, Option<String>),
+}
+
+/// An investigation's outcome, sent back by its recovery task.
+struct Settled {
+    index: usize,
+    outcome: RecoveryOutcome,
+    detail: Option<String>,
+    resumed_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// The report under construction, published on every change.
+struct Progress {
+    report: RecoveryReport,
+    settled: mpsc::UnboundedReceiver<Settled>,
+    /// Recovery tasks spawned that have not reported back.
+    outstanding: usize,
+}
+
+impl Progress {
+    fn settle(&mut self, settled: Settled) {
+        let entry = &mut self.report.investigations[settled.index];
+        entry.outcome = settled.outcome;
+        entry.detail = settled.detail;
+        entry.resumed_at = settled.resumed_at;
+        metrics::counter!("investigations.recovery.outcomes", "outcome" => settled.outcome.as_str())
+            .increment(1);
+        self.publish();
+    }
+
+    fn publish(&mut self) {
+        let report = &mut self.report;
+        let count = |outcome| {
+            report
+                .investigations
+                .iter()
+                .filter(|i| i.outcome == outcome)
+                .count() as u32
+        };
+        report.resumed = count(RecoveryOutcome::Resumed);
+        report.suspended = count(RecoveryOutcome::Suspended);
+        report.skipped = count(RecoveryOutcome::Skipped);
+        report.errored = count(RecoveryOutcome::Errored);
+        *last_report().lock().unwrap() = Some(report.clone());
+    }
+
+    /// Wait for `future`, recording outcomes the recovery tasks send back
+    /// meanwhile.
+    async fn until<T>(&mut self, future: impl Future<Output = T>) -> T {
+        tokio::pin!(future);
+        loop {
+            tokio::select! {
+                value = &mut future => return value,
+                Some(settled) = self.settled.recv() => {
+                    self.outstanding -= 1;
+                    self.settle(settled);
+                }
+            }
+        }
+    }
+}
+
+impl Orchestrator {
+    /// On startup, recover the investigations a restart left non-terminal.
+    /// Those active at shutdown are suspended first. Resumes start
+    /// `recovery.stagger_ms` apart, at most `recovery.max_concurrent` driven
+    /// at once, and an error recovering one investigation never stops the
+    /// rest. Returns once every investigation has an outcome; resumed drivers
+    /// keep running. The report is published (see `last_recovery`) as it
+    /// fills in.
+    pub async fn recover_on_startup(self: &Arc<Self>) -> Result<RecoveryReport, OrchestratorError> {
+        let settings = &self.config.system.recovery;
+        let retry = &self.config.system.retry.databases;
+        let start = Instant::now();
+        let started_at = Utc::now();
+
+        let investigations = retry_store_op(
+            retry,
+            retry.max_attempts,
+            "get_non_terminal_investigations",
+            || self.store.get_non_terminal_investigations(),
+        )
+        .await
+        .map_err(OrchestratorError::RecoveryFailed)?;
+
+        if investigations.is_empty() {
+            tracing::info!("No investigations to recover on startup");
+        } else {
+            tracing::info!(
+                count = investigations.len(),
+                "Found non-terminal investigations on startup"
+            );
+        }
+
+        let (sender, settled) = mpsc::unbounded_channel();
+        let mut progress = Progress {
+            report: new_report(started_at, &investigations),
+            settled,
+            outstanding: 0,
+        };
+        progress.publish();
+
+        let slots = Arc::new(Semaphore::new(settings.max_concurrent));
+        let stagger = Duration::from_millis(settings.stagger_ms);
+        let mut resumed_any = false;
+
+        for (index, investigation) in investigations.iter().enumerate() {
+            match progress.until(self.plan_recovery(investigation)).await {
+                Plan::Resume => {}
+                Plan::Settle(outcome, detail) => {
+                    progress.settle(Settled {
+                        index,
+                        outcome,
+                        detail,
+                        resumed_at: None,
+                    });
+                    continue;
+                }
+            }
+
+            if resumed_any {
+                progress.until(tokio::time::sleep(stagger)).await;
+            }
+            resumed_any = true;
+            let slot = progress
+                .until(Arc::clone(&slots).acquire_owned())
+                .await
+                .expect("recovery semaphore is never closed");
+
+            progress.outstanding += 1;
+            let orchestrator = Arc::clone(self);
+            let sender = sender.clone();
+            let id = investigation.id;
+            supervisor::spawn_supervised("investigation_recovery", async move {
+                let _slot = slot;
+                orchestrator
+                    .run_recovered_investigation(index, id, sender)
+                    .await;
+            });
+        }
+
+        drop(sender);
+        while progress.outstanding > 0 {
+            // None: a recovery task died before reporting back.
+            let Some(settled) = progress.settled.recv().await else {
+                break;
+            };
+            progress.outstanding -= 1;
+            progress.settle(settled);
+        }
+
+        progress.report.completed_at = Some(Utc::now());
+        progress.publish();
+        let report = progress.report;
+
+        metrics::histogram!("investigations.recovery.duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+        if report.errored > 0 {
+            tracing::warn!(
+                found = report.investigations.len(),
+                resumed = report.resumed,
+                suspended = report.suspended,
+                skipped = report.skipped,
+                errored = report.errored,
+                by_prior_status = ?report.by_prior_status,
+                "Startup recovery finished with errors — see GET /recovery/last"
+            );
+        } else {
+            tracing::info!(
+                found = report.investigations.len(),
+                resumed = report.resumed,
+                suspended = report.suspended,
+                skipped = report.skipped,
+                by_prior_status = ?report.by_prior_status,
+                "Startup recovery complete"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Get a recovered investigation ready to resume: discard an interrupted
+    /// self-test, suspend one that was active at shutdown.
+    async fn plan_recovery(&self, investigation: &Investigation) -> Plan {
+        let id = investigation.id;
+
+        // A self-test interrupted by the restart is never resumed.
+        if investigation.canary {
+            return match self.discard_canary(id).await {
+                Ok(_) => {
+                    tracing::info!(
+                        investigation_id = %id,
+                        "Discarded self-test investigation interrupted by restart"
+                    );
+                    Plan::Settle(RecoveryOutcome::Skipped, Some("self-test discarded".into()))
+                }
+                Err(e) => Plan::Settle(
+                    RecoveryOutcome::Errored,
+                    Some(format!(
+                        "failed to discard self-test: {}",
+                        sanitize_error(&e)
+                    )),
+                ),
+            };
+        }
+
+        match investigation.status {
+            InvestigationStatus::Suspended => {
+                tracing::info!(
+                    investigation_id = %id,
+                    reason = investigation.suspended_reason.as_deref().unwrap_or("unknown"),
+                    "Recovering suspended investigation"
+                );
+            }
+            InvestigationStatus::AnalystRunning | InvestigationStatus::Processing => {
+                // Crashed mid-operation — treat as suspended.
+                tracing::warn!(
+                    investigation_id = %id,
+                    status = investigation.status.as_db_str(),
+                    "Investigation was active at shutdown, treating as suspended"
+                );
+                let resume_from = if investigation.status == InvestigationStatus::Processing {
+                    "processing"
+                } else {
+                    "analyst"
+                };
+                let retry = &self.config.system.retry.databases;
+                match retry_store_op(retry, retry.max_attempts, "suspend_investigation", || {
+                    self.store
+                        .suspend_investigation(id, "engine_restart", resume_from)
+                })
+                .await
+                {
+                    Ok(suspension) => self.publish_suspension(id, suspension).await,
+                    Err(e) => {
+                        tracing::error!(
+                            investigation_id = %id,
+                            error = %e,
+                            "Failed to suspend investigation for recovery"
+                        );
+                        return Plan::Settle(
+                            RecoveryOutcome::Errored,
+                            Some(format!(
+                                "failed to suspend: {}",
+                                sanitize_error(&e.to_string())
+                            )),
+                        );
+                    }
+                }
+            }
+            InvestigationStatus::Pending => {
+                tracing::info!(investigation_id = %id, "Recovering pending investigation");
+            }
+            status => {
+                return Plan::Settle(
+                    RecoveryOutcome::Skipped,
+                    Some(format!("status is {}", status.as_db_str())),
+                );
+            }
+        }
+
+        if self.config.system.recovery.auto_resume {
+            Plan::Resume
+        } else if investigation.status == InvestigationStatus::Pending {
+            Plan::Settle(
+                RecoveryOutcome::Skipped,
+                Some("auto_resume is off; left pending".into()),
+            )
+        } else {
+            Plan::Settle(RecoveryOutcome::Suspended, None)
+        }
+    }
+
+    /// Drive a recovered investigation, reporting its outcome on `settled` as
+    /// soon as it is known. The lease of the process that drove it before the
+    /// restart outlives that process by up to a TTL, so wait that long for it
+    /// to lapse before giving up.
+    async fn run_recovered_investigation(
+        &self,
+        index: usize,
+        id: InvestigationId,
+        settled: mpsc::UnboundedSender<Settled>,
+    ) {
+        let settle = |outcome, detail: Option<String>, resumed_at| {
+            let _ = settled.send(Settled {
+                index,
+                outcome,
+                detail,
+                resumed_at,
+            });
+        };
+
+        let ttl = Duration::from_secs(self.config.system.safety.heartbeat_ttl_seconds);
+        let deadline = tokio::time::Instant::now() + ttl;
+        let lease = loop {
+            match self.acquire_lease(id, ownership::DRIVER).await {
+                Ok(Some(lease)) => break lease,
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::error!(
+                        investigation_id = %id,
+                        error = %e,
+                        "Failed to take ownership of recovered investigation"
+                    );
+                    settle(
+                        RecoveryOutcome::Errored,
+                        Some(format!(
+                            "failed to take ownership: {}",
+                            sanitize_error(&e.to_string())
+                        )),
+                        None,
+                    );
+                    return;
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                tracing::info!(
+                    investigation_id = %id,
+                    "Investigation still owned by another task, not recovering"
+                );
+                metrics::counter!("investigations.ownership.conflicts").increment(1);
+                settle(
+                    RecoveryOutcome::Skipped,
+                    Some("owned by another task".into()),
+                    None,
+                );
+                return;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        };
+
+        settle(RecoveryOutcome::Resumed, None, Some(Utc::now()));
+        if let Err(e) = self.drive_investigation(id, &lease).await {
+            tracing::error!(
+                investigation_id = %id,
+                error = %e,
+                "Recovered investigation failed"
+            );
+        }
+        lease.release().await;
+    }
+}
+
+/// A report with every investigation pending.
+fn new_report(
+    started_at: chrono::DateTime<Utc>,
+    investigations: &[Investigation],
+) -> RecoveryReport {
+    let mut report = RecoveryReport {
+        started_at,
+        completed_at: None,
+        by_prior_status: Default::default(),
+        resumed: 0,
+        suspended: 0,
+        skipped: 0,
+        errored: 0,
+        investigations: Vec::with_capacity(investigations.len()),
+    };
+    for investigation in investigations {
+        *report
+            .by_prior_status
+            .entry(investigation.status.as_db_str().to_string())
+            .or_insert(0) += 1;
+        report.investigations.push(RecoveredInvestigation {
+            investigation_id: investigation.id,
+            prior_status: investigation.status,
+            outcome: RecoveryOutcome::Pending,
+            detail: None,
+            resumed_at: None,
+        });
+    }
+    report
+}
@@ -0,0 +1,98 @@
+use std::future::Future;
+
+use autosint_common::config::RetryConfig;
+use autosint_common::retry::{retry_with_backoff, RetryAttempt, RetryClass, RetryPolicy};
+
+use crate::store::StoreError;
+
+/// Retry a store operation on transient errors with exponential backoff.
+///
+/// Permanent errors (e.g. NotFound) are returned immediately. After `max_attempts`
+/// the last error is returned.
+pub(crate) async fn retry_store_op<T, F, Fut>(
+    retry: &RetryConfig,
+    max_attempts: u32,
+    operation: &str,
+    op: F,
+) -> Result<T, StoreError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, StoreError>>,
+{
+    let classify = |e: &StoreError| {
+        if e.is_transient() {
+            RetryClass::Retryable
+        } else {
+            RetryClass::Fatal
+        }
+    };
+    let on_retry = |retry: &RetryAttempt<'_, StoreError>| {
+        tracing::warn!(
+            operation,
+            attempt = retry.attempt,
+            wait_ms = retry.wait.as_millis() as u64,
+            error = %retry.error,
+            "Store operation failed, retrying"
+        );
+        metrics::counter!("orchestrator.store.retries", "operation" => operation.to_string())
+            .increment(1);
+    };
+    let policy = RetryPolicy::from(retry).with_max_attempts(max_attempts);
+    retry_with_backoff(&policy, classify, on_retry, op)
+        .await
+        .map_err(|failure| failure.error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_retry() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 3,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 2,
+            backoff_multiplier: 2.0,
+            jitter: false,
+        }
+    }
+
+    /// Mock store call that fails `failures` times with a transient error, then succeeds.
+    async fn flaky(calls: &AtomicU32, failures: u32) -> Result<&'static str, StoreError> {
+        let n = calls.fetch_add(1, Ordering::SeqCst);
+        if n < failures {
+            Err(StoreError::Connection("connection reset".into()))
+        } else {
+            Ok("ok")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let calls = AtomicU32::new(0);
+        let result = retry_store_op(&fast_retry(), 3, "test", || flaky(&calls, 2)).await;
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result = retry_store_op(&fast_retry(), 3, "test", || flaky(&calls, 10)).await;
+        assert!(matches!(result, Err(StoreError::Connection(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_permanent_error_not_retried() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_store_op(&fast_retry(), 5, "test", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(StoreError::NotFound("Investigation x".into()))
+        })
+        .await;
+        assert!(matches!(result, Err(StoreError::NotFound(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
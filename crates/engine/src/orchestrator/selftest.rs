@@ -0,0 +1,222 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use autosint_common::api::engine::{PurgeReport, SelfTestReport, SelfTestStage};
+use autosint_common::ids::InvestigationId;
+use autosint_common::types::{Investigation, InvestigationStatus, WorkOrderStatus};
+
+use crate::retention;
+use crate::store::StoreError;
+
+use super::Orchestrator;
+
+/// Objective for the canary investigation. Small enough for one work order and
+/// a one-cycle assessment.
+const SELFTEST_PROMPT: &str = "Engine self-test. Create exactly one work order asking the \
+    Processor to record a single entity named \"AutOSINT Self-Test\" and one claim about it, \
+    then produce a brief assessment of what was recorded.";
+
+/// Correlation ID stamped on canary investigations.
+pub const SELFTEST_CORRELATION_ID: &str = "selftest";
+
+/// Set while a self-test is in progress.
+static SELFTEST_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Most recent self-test report. Uses std::sync::Mutex because it is never held
+/// across await points.
+fn last_report() -> &'static Mutex<Option<SelfTestReport>> {
+    static LAST_REPORT: OnceLock<Mutex<Option<SelfTestReport>>> = OnceLock::new();
+    LAST_REPORT.get_or_init(|| Mutex::new(None))
+}
+
+/// The most recent self-test report since process start, reported on `/health`.
+pub fn last_selftest() -> Option<SelfTestReport> {
+    last_report().lock().unwrap().clone()
+}
+
+fn stage(name: &str, passed: bool, detail: impl Into<String>) -> SelfTestStage {
+    SelfTestStage {
+        name: name.to_string(),
+        passed,
+        detail: detail.into(),
+    }
+}
+
+impl Orchestrator {
+    /// Run a bounded canary investigation through the full pipeline — Analyst
+    /// session, work order round trip through the queue and a Processor, claim
+    /// written to the graph, assessment stored — then purge everything it
+    /// produced. Returns None if a self-test is already running.
+    pub async fn run_selftest(&self) -> Option<SelfTestReport> {
+        if SELFTEST_RUNNING.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        let report = self.selftest().await;
+        SELFTEST_RUNNING.store(false, Ordering::SeqCst);
+
+        metrics::gauge!("selftest.passed").set(if report.passed { 1.0 } else { 0.0 });
+        if report.passed {
+            tracing::info!(duration_ms = report.duration_ms, "Self-test passed");
+        } else {
+            let failed: Vec<&str> = report
+                .stages
+                .iter()
+                .filter(|s| !s.passed)
+                .map(|s| s.name.as_str())
+                .collect();
+            tracing::error!(
+                duration_ms = report.duration_ms,
+                failed = ?failed,
+                "Self-test failed — engine readiness degraded"
+            );
+        }
+
+        *last_report().lock().unwrap() = Some(report.clone());
+        Some(report)
+    }
+
+    async fn selftest(&self) -> SelfTestReport {
+        let started_at = Utc::now();
+        let start = Instant::now();
+        let settings = &self.config.system.selftest;
+
+        let mut investigation = Investigation::new(SELFTEST_PROMPT.into());
+        investigation.canary = true;
+        investigation.max_cycles = Some(1);
+        investigation.correlation_id = Some(SELFTEST_CORRELATION_ID.into());
+        investigation.prompt_hashes = self.prompts.snapshot().hashes();
+
+        let mut stages = Vec::new();
+        if let Err(e) = self.store.create_investigation(&investigation).await {
+            stages.push(stage(
+                "analyst_session",
+                false,
+                format!("failed to create canary investigation: {}", e),
+            ));
+            return SelfTestReport {
+                passed: false,
+                investigation_id: None,
+                started_at,
+                duration_ms: start.elapsed().as_millis() as u64,
+                stages,
+            };
+        }
+        let id = investigation.id;
+        tracing::info!(investigation_id = %id, "Self-test started");
+
+        let timeout = Duration::from_secs(settings.timeout_seconds);
+        let run_error =
+            match tokio::time::timeout(timeout, self.canary().run_investigation(id)).await {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(e.to_string()),
+                Err(_) => Some(format!("timed out after {}s", settings.timeout_seconds)),
+            };
+        let failure = |detail: &str| match &run_error {
+            Some(e) => format!("{} (investigation run: {})", detail, e),
+            None => detail.to_string(),
+        };
+
+        match self.store.get_work_orders_by_investigation(id).await {
+            Ok(work_orders) => {
+                let created = work_orders.len();
+                stages.push(if created > 0 {
+                    stage(
+                        "analyst_session",
+                        true,
+                        format!("{} work order(s) created", created),
+                    )
+                } else {
+                    stage("analyst_session", false, failure("no work order created"))
+                });
+
+                let completed = work_orders
+                    .iter()
+                    .filter(|wo| wo.status == WorkOrderStatus::Completed)
+                    .count();
+                let detail = format!("{}/{} work order(s) completed", completed, created);
+                stages.push(if completed > 0 {
+                    stage("work_order_round_trip", true, detail)
+                } else {
+                    stage("work_order_round_trip", false, failure(&detail))
+                });
+            }
+            Err(e) => {
+                let detail = format!("failed to load work orders: {}", e);
+                stages.push(stage("analyst_session", false, detail.clone()));
+                stages.push(stage("work_order_round_trip", false, detail));
+            }
+        }
+
+        stages.push(match self.graph.mark_investigation_canary(id).await {
+            Ok(0) => stage("graph_write", false, failure("no claims written")),
+            Ok(n) => stage("graph_write", true, format!("{} claim(s) written", n)),
+            Err(e) => stage(
+                "graph_write",
+                false,
+                format!("failed to read canary claims: {}", e),
+            ),
+        });
+
+        stages.push(match self.store.get_latest_assessment(id).await {
+            Ok(assessment) => stage(
+                "assessment",
+                true,
+                format!("assessment {} stored", assessment.id),
+            ),
+            Err(StoreError::NotFound(_)) => {
+                stage("assessment", false, failure("no assessment produced"))
+            }
+            Err(e) => stage(
+                "assessment",
+                false,
+                format!("failed to load assessment: {}", e),
+            ),
+        });
+
+        stages.push(match self.discard_canary(id).await {
+            Ok(report) => stage(
+                "purge",
+                true,
+                format!(
+                    "removed {} claim(s), {} entities, {} relationship(s), {} work order(s)",
+                    report.graph.claim_ids.len(),
+                    report.graph.entity_ids.len(),
+                    report.graph.relationship_ids.len(),
+                    report.store.work_orders,
+                ),
+            ),
+            Err(e) => stage("purge", false, e),
+        });
+
+        SelfTestReport {
+            passed: stages.iter().all(|s| s.passed),
+            investigation_id: Some(id),
+            started_at,
+            duration_ms: start.elapsed().as_millis() as u64,
+            stages,
+        }
+    }
+
+    /// Fail a canary investigation if it is still active, then purge it.
+    pub(super) async fn discard_canary(&self, id: InvestigationId) -> Result<PurgeReport, String> {
+        let investigation = self.store.get_investigation(id).await.map_err(|e| {
+            tracing::error!(investigation_id = %id, error = %e, "Failed to load canary investigation");
+            e.to_string()
+        })?;
+        if !investigation.status.is_terminal() {
+            self.set_terminal_status(id, InvestigationStatus::Failed)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        retention::purge_investigation(&self.graph, &self.store, id, false)
+            .await
+            .map_err(|e| {
+                tracing::error!(investigation_id = %id, error = %e, "Failed to purge canary investigation");
+                e.to_string()
+            })
+    }
+}
@@ -1,30 +1,69 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use crate::config::EngineConfig;
+use crate::config::{EngineConfig, PromptSet, PromptStore, ANALYST_PROMPT};
+use autosint_common::config::SafetyLimits;
 use autosint_common::ids::InvestigationId;
-use autosint_common::types::{Investigation, InvestigationStatus};
-
-use crate::analyst::{force_final_prompt, AnalystOutcome, AnalystSession};
+use autosint_common::types::{
+    aggregate_cycles, cycle_efficiency, AnalystSessionFinished, CycleMetrics, CycleTrend,
+    DomainEvent, FetchBudget, Investigation, InvestigationEventKind, InvestigationStatus,
+    InvestigationSuspended, StatusChanged, StrictnessProfile, WorkOrder, WorkOrderPriority,
+    WorkOrderStatus,
+};
+
+use crate::analyst::{force_final_prompt, AnalystOutcome, AnalystSession, AnalystSessionResult};
+use crate::assessment_diff;
 use crate::circuit_breaker::CircuitBreakerRegistry;
 use crate::embeddings::EmbeddingClient;
-use crate::graph::GraphClient;
+use crate::events::EventBus;
+use crate::flags::FlagSet;
+use crate::graph::{ChangeCounts, ChangeScope, GraphClient};
+use crate::ingest::enrichment;
+use crate::llm::{configured_llm, LlmCaller, LlmFactory};
+use crate::maintenance::{ReadOnlyMode, MAINTENANCE_REASON};
+use crate::queue::ownership::{self, InvestigationLease};
+use crate::queue::shortlist::{format_shortlist_block, EntityShortlist};
 use crate::queue::QueueClient;
-use crate::store::StoreClient;
+use crate::store::{StoreClient, StoreError};
+use crate::tools::{FetchClient, SessionScope, SharedServices};
+use crate::triage::{self, PromptTriage};
+
+use super::retry::retry_store_op;
+use super::OrchestratorError;
+
+/// Attempts for terminal status writes (Completed/Failed). Far above the normal
+/// database retry budget — a lost terminal write strands the investigation.
+const TERMINAL_WRITE_MAX_ATTEMPTS: u32 = 30;
+
+/// How waiting on a cycle's work orders ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WorkOrderWait {
+    /// None is queued or processing any more.
+    Resolved,
+    /// The engine went read-only with work orders outstanding.
+    ReadOnly,
+}
 
 /// The Orchestrator drives investigation lifecycles as a deterministic state machine.
 pub struct Orchestrator {
-    graph: Arc<GraphClient>,
-    store: Arc<StoreClient>,
-    queue: Arc<QueueClient>,
-    embedding_client: Option<Arc<EmbeddingClient>>,
-    config: Arc<EngineConfig>,
-    fetch_base_url: String,
-    tool_schemas: Arc<HashMap<String, Value>>,
-    analyst_prompt: String,
+    pub(super) graph: Arc<GraphClient>,
+    pub(super) store: Arc<StoreClient>,
+    pub(super) queue: Arc<QueueClient>,
+    pub(super) config: Arc<EngineConfig>,
+    /// What Analyst sessions' tools run against.
+    services: SharedServices,
+    pub(super) prompts: PromptStore,
     circuit_breakers: Arc<CircuitBreakerRegistry>,
+    /// Builds Analyst models in place of the `llm.analyst` client when set
+    /// (see `with_analyst_llm`).
+    analyst_llm: Option<LlmFactory>,
+    /// Analyst tools allowed in this Orchestrator's sessions. None allows all.
+    analyst_tools: Option<Vec<String>>,
+    /// Investigations are suspended for maintenance while this is on.
+    read_only: ReadOnlyMode,
 }
 
 impl Orchestrator {
@@ -36,35 +75,142 @@ impl Orchestrator {
         embedding_client: Option<Arc<EmbeddingClient>>,
         config: Arc<EngineConfig>,
         fetch_base_url: String,
+        fetch_http: reqwest::Client,
         tool_schemas: Arc<HashMap<String, Value>>,
-        analyst_prompt: String,
+        prompts: PromptStore,
         circuit_breakers: Arc<CircuitBreakerRegistry>,
     ) -> Self {
+        let services = SharedServices::new(
+            Arc::clone(&graph),
+            fetch_base_url,
+            fetch_http,
+            tool_schemas,
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        )
+        .with_store(Arc::clone(&store))
+        .with_queue(Arc::clone(&queue))
+        .with_embedding_client(embedding_client);
         Self {
             graph,
             store,
             queue,
-            embedding_client,
             config,
-            fetch_base_url,
-            tool_schemas,
-            analyst_prompt,
+            services,
+            prompts,
             circuit_breakers,
+            analyst_llm: None,
+            analyst_tools: None,
+            read_only: ReadOnlyMode::default(),
+        }
+    }
+
+    /// Suspend investigations for maintenance while `read_only` is on.
+    pub fn with_read_only(mut self, read_only: ReadOnlyMode) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Reach the Fetch service through `fetch`, sharing its replicas' history
+    /// and hedge budget with the Processors.
+    pub fn with_fetch_client(mut self, fetch: FetchClient) -> Self {
+        self.services = self.services.with_fetch_client(fetch);
+        self
+    }
+
+    /// Give Analyst sessions `flags` to consult.
+    pub fn with_flags(mut self, flags: FlagSet) -> Self {
+        self.services = self.services.with_flags(flags);
+        self
+    }
+
+    /// Publish transitions and Analyst sessions, and have Analyst sessions
+    /// publish, to `events` instead of a bus that only writes the timeline.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.services = self.services.with_events(events);
+        self
+    }
+
+    /// The bus this Orchestrator and its Analyst sessions publish to.
+    pub fn events(&self) -> &EventBus {
+        &self.services.events
+    }
+
+    /// Publish `event` on the bus Analyst sessions share.
+    pub(super) async fn publish(&self, event: DomainEvent) {
+        self.services.events.publish(event).await;
+    }
+
+    /// Run Analyst sessions against `llm` instead of the configured provider.
+    /// Tests use this to script the Analyst with a mock.
+    pub fn with_analyst_llm(self, llm: Arc<dyn LlmCaller>) -> Self {
+        self.with_analyst_llm_factory(Arc::new(move || Ok(Arc::clone(&llm))))
+    }
+
+    /// Build each Analyst session's model with `factory` instead of the
+    /// configured provider, e.g. a fresh scripted mock per session.
+    pub fn with_analyst_llm_factory(mut self, factory: LlmFactory) -> Self {
+        self.analyst_llm = Some(factory);
+        self
+    }
+
+    /// A copy of this Orchestrator bounded by the self-test profile: capped
+    /// Analyst turns, one work order per cycle, and only the configured tools.
+    pub(super) fn canary(&self) -> Self {
+        let selftest = &self.config.system.selftest;
+        let mut config = (*self.config).clone();
+        config.system.safety.max_turns_per_analyst_session = selftest.max_turns;
+        config.system.safety.max_work_orders_per_cycle = 1;
+        Self {
+            graph: Arc::clone(&self.graph),
+            store: Arc::clone(&self.store),
+            queue: Arc::clone(&self.queue),
+            config: Arc::new(config),
+            services: self.services.clone(),
+            prompts: self.prompts.clone(),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            analyst_llm: self.analyst_llm.clone(),
+            analyst_tools: Some(selftest.analyst_tools.clone()),
+            read_only: self.read_only.clone(),
         }
     }
 
     /// Start a new investigation from a prompt. Returns the investigation ID.
-    pub async fn start_investigation(&self, prompt: &str) -> Result<InvestigationId, String> {
-        let investigation = Investigation::new(prompt.to_string());
+    /// `tags` and `correlation_id` must already be normalized (see `normalize_tags`
+    /// and `normalize_correlation_id`). `priority_cap` bounds the priority of
+    /// the investigation's work orders and `fetch_budget` their fetches.
+    /// `as_of` makes it a snapshot investigation of the graph at that time.
+    /// Accepted pre-flight suggestions in `triage` are applied to the
+    /// investigation.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn start_investigation(
+        &self,
+        prompt: &str,
+        tags: Vec<String>,
+        correlation_id: Option<String>,
+        priority_cap: Option<WorkOrderPriority>,
+        fetch_budget: Option<FetchBudget>,
+        as_of: Option<DateTime<Utc>>,
+        triage: Option<&PromptTriage>,
+    ) -> Result<InvestigationId, OrchestratorError> {
+        let mut investigation = Investigation::new(prompt.to_string());
+        investigation.tags = tags;
+        investigation.correlation_id = correlation_id;
+        investigation.priority_cap = priority_cap;
+        investigation.fetch_budget = fetch_budget;
+        investigation.as_of = as_of;
+        investigation.prompt_hashes = self.prompts.snapshot().hashes();
+        if let Some(triage) = triage {
+            triage::apply_triage(&mut investigation, triage);
+        }
         let id = investigation.id;
 
-        self.store
-            .create_investigation(&investigation)
-            .await
-            .map_err(|e| format!("Failed to create investigation: {}", e))?;
+        self.store.create_investigation(&investigation).await?;
 
         tracing::info!(
             investigation_id = %id,
+            correlation_id = investigation.correlation_id.as_deref(),
+            as_of = ?investigation.as_of,
             prompt = %prompt,
             "Investigation created"
         );
@@ -75,8 +221,41 @@ impl Orchestrator {
     }
 
     /// Run the full investigation lifecycle. Call from a spawned task.
-    pub async fn run_investigation(&self, id: InvestigationId) -> Result<(), String> {
-        let span = tracing::info_span!("investigation", investigation_id = %id);
+    ///
+    /// Holds the investigation's ownership lease for the whole run, so a
+    /// second driver (a resume racing startup recovery, say) returns at once.
+    pub async fn run_investigation(&self, id: InvestigationId) -> Result<(), OrchestratorError> {
+        let Some(lease) = self.acquire_lease(id, ownership::DRIVER).await? else {
+            tracing::info!(investigation_id = %id, "Investigation already owned by another task");
+            metrics::counter!("investigations.ownership.conflicts").increment(1);
+            return Ok(());
+        };
+        let result = self.drive_investigation(id, &lease).await;
+        lease.release().await;
+        result
+    }
+
+    /// Take the ownership lease of an investigation, expiring after the
+    /// heartbeat TTL unless renewed. None if another task holds it.
+    pub(super) async fn acquire_lease(
+        &self,
+        id: InvestigationId,
+        purpose: &str,
+    ) -> Result<Option<InvestigationLease>, OrchestratorError> {
+        let ttl = std::time::Duration::from_secs(self.config.system.safety.heartbeat_ttl_seconds);
+        Ok(InvestigationLease::acquire(Arc::clone(&self.queue), id, purpose, ttl).await?)
+    }
+
+    pub(super) async fn drive_investigation(
+        &self,
+        id: InvestigationId,
+        lease: &InvestigationLease,
+    ) -> Result<(), OrchestratorError> {
+        let span = tracing::info_span!(
+            "investigation",
+            investigation_id = %id,
+            correlation_id = tracing::field::Empty
+        );
         let _enter = span.enter();
 
         let safety = &self.config.system.safety;
@@ -84,12 +263,18 @@ impl Orchestrator {
         let mut consecutive_all_fail_cycles: u32 = 0;
 
         loop {
+            // Stop if the lease lapsed (e.g. Redis was unreachable past the
+            // TTL): another task may be driving the investigation now.
+            if lease.is_lost() {
+                tracing::warn!("Investigation ownership lost, stopping");
+                return Ok(());
+            }
+
             // Reload investigation state from DB.
-            let investigation = self
-                .store
-                .get_investigation(id)
-                .await
-                .map_err(|e| format!("Failed to get investigation: {}", e))?;
+            let investigation = self.load_investigation(id).await?;
+            if let Some(correlation_id) = &investigation.correlation_id {
+                span.record("correlation_id", correlation_id.as_str());
+            }
 
             if investigation.status.is_terminal() {
                 tracing::info!(
@@ -100,50 +285,45 @@ impl Orchestrator {
             }
 
             // Circuit breaker check — suspend if a hard dependency is down.
-            if let Some(circuit_name) = self.circuit_breakers.any_hard_open() {
+            // Read-only mode suspends the same way, for maintenance.
+            let suspend_reason = if let Some(circuit_name) = self.circuit_breakers.any_hard_open() {
                 tracing::warn!(
                     circuit = circuit_name,
                     "Hard dependency circuit open, suspending investigation"
                 );
+                Some(format!("circuit_breaker:{}", circuit_name))
+            } else if self.read_only.is_enabled() {
+                tracing::info!("Engine is read-only, suspending investigation for maintenance");
+                Some(MAINTENANCE_REASON.to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = suspend_reason {
                 let resume_from = match investigation.status {
                     InvestigationStatus::Processing => "processing",
                     _ => "analyst",
                 };
-                self.store
-                    .suspend_investigation(
-                        id,
-                        &format!("circuit_breaker:{}", circuit_name),
-                        resume_from,
-                    )
-                    .await
-                    .map_err(|e| format!("Failed to suspend investigation: {}", e))?;
-
+                self.suspend(id, &reason, resume_from).await?;
                 return Ok(());
             }
 
             match investigation.status {
                 InvestigationStatus::Pending | InvestigationStatus::AnalystRunning => {
                     // Check max cycles.
-                    let force_final =
-                        investigation.cycle_count as u32 >= safety.max_cycles_per_investigation;
+                    let max_cycles = self.max_cycles(&investigation);
+                    let force_final = investigation.cycle_count as u32 >= max_cycles;
 
                     if force_final {
                         tracing::warn!(
                             cycles = investigation.cycle_count,
-                            max = safety.max_cycles_per_investigation,
+                            max = max_cycles,
                             "Max cycles reached, forcing final assessment"
                         );
                     }
 
                     // Transition to ANALYST_RUNNING.
-                    self.store
-                        .update_investigation_status(
-                            id,
-                            &InvestigationStatus::AnalystRunning,
-                            false,
-                        )
-                        .await
-                        .map_err(|e| format!("Failed to update status: {}", e))?;
+                    self.set_status(id, InvestigationStatus::AnalystRunning, false)
+                        .await?;
 
                     // Run Analyst cycle.
                     let outcome = self
@@ -152,14 +332,9 @@ impl Orchestrator {
 
                     match outcome {
                         AnalystOutcome::AssessmentProduced => {
-                            self.store
-                                .update_investigation_status(
-                                    id,
-                                    &InvestigationStatus::Completed,
-                                    false,
-                                )
-                                .await
-                                .map_err(|e| format!("Failed to mark completed: {}", e))?;
+                            self.diff_superseded_assessment(id).await;
+                            self.set_terminal_status(id, InvestigationStatus::Completed)
+                                .await?;
 
                             tracing::info!("Investigation completed with assessment");
                             metrics::counter!("investigations.completed").increment(1);
@@ -167,14 +342,8 @@ impl Orchestrator {
                         }
                         AnalystOutcome::WorkOrdersCreated { count } => {
                             // Transition to PROCESSING, increment cycle.
-                            self.store
-                                .update_investigation_status(
-                                    id,
-                                    &InvestigationStatus::Processing,
-                                    true,
-                                )
-                                .await
-                                .map_err(|e| format!("Failed to mark processing: {}", e))?;
+                            self.set_status(id, InvestigationStatus::Processing, true)
+                                .await?;
 
                             tracing::info!(
                                 work_orders = count,
@@ -183,7 +352,11 @@ impl Orchestrator {
                             empty_session_count = 0;
 
                             // Wait for all work orders to complete.
-                            self.wait_for_work_orders(id).await?;
+                            if self.wait_for_work_orders(id).await? == WorkOrderWait::ReadOnly {
+                                self.suspend(id, MAINTENANCE_REASON, "processing").await?;
+                                return Ok(());
+                            }
+                            self.record_cycle_metrics(id).await;
 
                             // Check for all-fail cycle.
                             if self.check_all_failed_cycle(id).await? {
@@ -204,14 +377,8 @@ impl Orchestrator {
                             }
 
                             // Transition back to ANALYST_RUNNING for next cycle.
-                            self.store
-                                .update_investigation_status(
-                                    id,
-                                    &InvestigationStatus::AnalystRunning,
-                                    false,
-                                )
-                                .await
-                                .map_err(|e| format!("Failed to transition back: {}", e))?;
+                            self.set_status(id, InvestigationStatus::AnalystRunning, false)
+                                .await?;
                         }
                         AnalystOutcome::EmptySession => {
                             empty_session_count += 1;
@@ -228,16 +395,11 @@ impl Orchestrator {
 
                                 match forced_outcome {
                                     AnalystOutcome::AssessmentProduced => {
-                                        self.store
-                                            .update_investigation_status(
-                                                id,
-                                                &InvestigationStatus::Completed,
-                                                false,
-                                            )
-                                            .await
-                                            .map_err(|e| {
-                                                format!("Failed to mark completed: {}", e)
-                                            })?;
+                                        self.set_terminal_status(
+                                            id,
+                                            InvestigationStatus::Completed,
+                                        )
+                                        .await?;
                                         return Ok(());
                                     }
                                     _ => {
@@ -258,33 +420,37 @@ impl Orchestrator {
                 }
                 InvestigationStatus::Processing => {
                     // Resuming from processing state (e.g., after restart).
-                    self.wait_for_work_orders(id).await?;
-
-                    self.store
-                        .update_investigation_status(
-                            id,
-                            &InvestigationStatus::AnalystRunning,
-                            false,
-                        )
-                        .await
-                        .map_err(|e| format!("Failed to transition from processing: {}", e))?;
+                    if self.wait_for_work_orders(id).await? == WorkOrderWait::ReadOnly {
+                        self.suspend(id, MAINTENANCE_REASON, "processing").await?;
+                        return Ok(());
+                    }
+                    self.record_cycle_metrics(id).await;
+
+                    self.set_status(id, InvestigationStatus::AnalystRunning, false)
+                        .await?;
                 }
                 InvestigationStatus::Suspended => {
                     // Clear suspension and resume.
-                    self.store
-                        .clear_suspension(id)
-                        .await
-                        .map_err(|e| format!("Failed to clear suspension: {}", e))?;
+                    let retry = &self.config.system.retry.databases;
+                    let resumption =
+                        retry_store_op(retry, retry.max_attempts, "clear_suspension", || {
+                            self.store.clear_suspension(id)
+                        })
+                        .await?;
+                    if let Some(resumption) = resumption {
+                        self.publish(DomainEvent::InvestigationResumed {
+                            investigation_id: id,
+                            resumption,
+                        })
+                        .await;
+                    }
 
                     let resume_status = match investigation.resume_from.as_deref() {
                         Some("processing") => InvestigationStatus::Processing,
                         _ => InvestigationStatus::AnalystRunning,
                     };
 
-                    self.store
-                        .update_investigation_status(id, &resume_status, false)
-                        .await
-                        .map_err(|e| format!("Failed to resume: {}", e))?;
+                    self.set_status(id, resume_status, false).await?;
 
                     tracing::info!(
                         resume_from = investigation.resume_from.as_deref().unwrap_or("analyst"),
@@ -304,70 +470,209 @@ impl Orchestrator {
         id: InvestigationId,
         investigation: &Investigation,
         force_final: bool,
-    ) -> Result<AnalystOutcome, String> {
+    ) -> Result<AnalystOutcome, OrchestratorError> {
+        // The session keeps this snapshot even if prompts are reloaded mid-session.
+        let prompts = self.prompts.snapshot();
+        let analyst_prompt = prompts.text(ANALYST_PROMPT);
         let prompt = if force_final {
-            force_final_prompt(&self.analyst_prompt)
+            force_final_prompt(analyst_prompt)
         } else {
-            self.analyst_prompt.clone()
+            analyst_prompt.to_string()
         };
-
-        let session = AnalystSession::new(
-            &self.config.system.llm.analyst,
-            &self.config.system.retry.llm_api,
-            &self.config.system.safety,
-            Arc::clone(&self.graph),
-            self.embedding_client.clone(),
-            Arc::clone(&self.store),
-            Arc::clone(&self.queue),
-            self.fetch_base_url.clone(),
-            prompt,
-            &self.tool_schemas,
-            self.config.system.tool_results.clone(),
-            self.config.system.dedup.clone(),
+        self.record_analyst_session(
             id,
             investigation.cycle_count,
-        )?;
+            &prompts,
+            if force_final { "force_final" } else { "cycle" },
+        )
+        .await;
+
+        let mut safety = self.config.system.safety.clone();
+        safety.max_cycles_per_investigation = self.max_cycles(investigation);
+
+        let session = self
+            .analyst_session(&safety, prompt, investigation)
+            .map_err(OrchestratorError::AnalystSessionFailed)?;
+
+        let mut user_prompt = format!("## Investigation\n\n{}\n", investigation.prompt);
+        if !investigation.focus_entities.is_empty() {
+            user_prompt.push_str(&format!(
+                "\nFocus entities: {}\n",
+                investigation.focus_entities.join(", ")
+            ));
+        }
+        if let Some(strictness) = investigation.strictness {
+            user_prompt.push_str(&format!("\n{}\n", strictness_guidance(strictness)));
+        }
+        if let Some(block) = self.shortlist_block(id).await {
+            user_prompt.push_str(&format!("\n{}", block));
+        }
+        if let Some(as_of) = investigation.as_of {
+            user_prompt.push_str(&format!("\n{}\n", snapshot_line(as_of)));
+        } else if let Some(line) = self.graph_delta_line(id, investigation).await {
+            user_prompt.push_str(&format!("\n{}\n", line));
+        }
+        if let Some(line) = format_cycle_line(&investigation.cycles) {
+            user_prompt.push_str(&format!("\n{}\n", line));
+        }
+        if let Some(line) = self.scratchpad_line(id).await {
+            user_prompt.push_str(&format!("\n{}\n", line));
+        }
+        if investigation.as_of.is_none() {
+            if let Some(block) = self.proposals_block(id).await {
+                user_prompt.push_str(&format!("\n{}", block));
+            }
+            if let Some(block) = self.enrichment_block(id).await {
+                user_prompt.push_str(&format!("\n{}", block));
+            }
+        }
+        user_prompt.push_str(&format!(
+            "\n---\nCycle: {} | Max cycles: {}",
+            investigation.cycle_count, safety.max_cycles_per_investigation,
+        ));
 
-        let user_prompt = format!(
-            "## Investigation\n\n{}\n\n---\nCycle: {} | Max cycles: {}",
-            investigation.prompt,
+        let started = std::time::Instant::now();
+        let result = session.run(&user_prompt).await;
+        let finished = session_finished(
             investigation.cycle_count,
-            self.config.system.safety.max_cycles_per_investigation,
+            if force_final { "force_final" } else { "cycle" },
+            &prompts,
+            &result,
+            started,
         );
-
-        let result = session.run(&user_prompt).await;
+        self.publish(DomainEvent::AnalystSessionFinished {
+            investigation_id: id,
+            session: finished,
+        })
+        .await;
         Ok(result.outcome)
     }
 
+    /// Build an Analyst session for the investigation's current cycle, applying
+    /// this Orchestrator's LLM override and tool restrictions.
+    fn analyst_session(
+        &self,
+        safety: &SafetyLimits,
+        system_prompt: String,
+        investigation: &Investigation,
+    ) -> Result<AnalystSession, String> {
+        let llm = self.analyst_llm.clone().unwrap_or_else(|| {
+            configured_llm(
+                self.config.system.llm.analyst.clone(),
+                self.config.system.retry.llm_api.clone(),
+                self.services.flags.clone(),
+            )
+        });
+        let mut session = AnalystSession::new(
+            &llm,
+            safety,
+            self.services.clone(),
+            system_prompt,
+            investigation.id,
+            SessionScope {
+                correlation_id: investigation.correlation_id.clone(),
+                investigation_cycle: Some(investigation.cycle_count),
+                entity_shortlist: self.entity_shortlist(investigation.id),
+                scratchpad: Some(self.config.system.scratchpad.clone()),
+                source_trust: Some(self.config.system.source_trust.clone()),
+                as_of: investigation.as_of,
+                ..Default::default()
+            },
+        )?;
+        if let Some(tools) = &self.analyst_tools {
+            session = session.with_tools(tools);
+        }
+        Ok(session)
+    }
+
+    /// Record which Analyst prompt a session runs with, and when it started
+    /// (the watermark for the next session's graph delta). `mode` is "cycle",
+    /// "force_final" or "failure". Best effort: a failed write is logged.
+    async fn record_analyst_session(
+        &self,
+        id: InvestigationId,
+        cycle: i32,
+        prompts: &PromptSet,
+        mode: &str,
+    ) {
+        let payload = serde_json::json!({
+            "cycle": cycle,
+            "mode": mode,
+            "prompt_hash": prompts.get(ANALYST_PROMPT).map(|p| p.hash.as_str()),
+            "started_at": chrono::Utc::now(),
+        });
+        if let Err(e) = self
+            .store
+            .record_investigation_event(id, InvestigationEventKind::AnalystSessionStarted, &payload)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to record Analyst session prompt");
+        }
+    }
+
     /// Poll until all active work orders for an investigation are resolved.
     /// On timeout, fails any remaining active work orders and returns Ok
     /// so the orchestrator can continue (check_all_failed_cycle handles the fallout).
-    async fn wait_for_work_orders(&self, id: InvestigationId) -> Result<(), String> {
+    /// Scheduled work orders aren't stuck: the deadline runs from their release
+    /// time, not from the start of the wait.
+    /// Returns `WaitTimeout` only if the stuck work orders could not be failed.
+    async fn wait_for_work_orders(
+        &self,
+        id: InvestigationId,
+    ) -> Result<WorkOrderWait, OrchestratorError> {
         let poll_interval = std::time::Duration::from_secs(5);
         let max_wait = std::time::Duration::from_secs(3600); // 1 hour max.
-        let start = std::time::Instant::now();
+        let mut deadline = std::time::Instant::now() + max_wait;
 
         loop {
-            let active = self
-                .store
-                .count_active_work_orders(id)
-                .await
-                .map_err(|e| format!("Failed to count active work orders: {}", e))?;
+            let retry = &self.config.system.retry.databases;
+            let active = retry_store_op(
+                retry,
+                retry.max_attempts,
+                "count_active_work_orders",
+                || self.store.count_active_work_orders(id),
+            )
+            .await?;
 
             if active == 0 {
                 tracing::info!("All work orders resolved");
-                return Ok(());
+                return Ok(WorkOrderWait::Resolved);
             }
 
-            if start.elapsed() > max_wait {
+            // Processors don't dequeue while read-only; the wait resumes
+            // after maintenance instead of timing out.
+            if self.read_only.is_enabled() {
+                tracing::info!(
+                    remaining = active,
+                    "Engine is read-only, suspending investigation for maintenance"
+                );
+                return Ok(WorkOrderWait::ReadOnly);
+            }
+
+            let latest_scheduled = retry_store_op(
+                retry,
+                retry.max_attempts,
+                "latest_scheduled_not_before",
+                || self.store.latest_scheduled_not_before(id),
+            )
+            .await?;
+            if let Some(not_before) = latest_scheduled {
+                let until_due = (not_before - chrono::Utc::now())
+                    .to_std()
+                    .unwrap_or_default();
+                deadline = deadline.max(std::time::Instant::now() + until_due + max_wait);
+            }
+
+            if std::time::Instant::now() > deadline {
                 tracing::error!(remaining = active, "Timed out waiting for work orders");
 
                 // Fail any stuck work orders so the investigation can proceed.
                 if let Err(e) = self.fail_stuck_work_orders(id).await {
                     tracing::error!(error = %e, "Failed to mark stuck work orders as failed");
+                    return Err(OrchestratorError::WaitTimeout(max_wait));
                 }
 
-                return Ok(());
+                return Ok(WorkOrderWait::Resolved);
             }
 
             tracing::debug!(active = active, "Waiting for work orders");
@@ -378,12 +683,9 @@ impl Orchestrator {
 
     /// Mark all active (queued/processing) work orders for an investigation as Failed.
     /// Used when wait_for_work_orders times out — these WOs are stuck and won't complete.
-    async fn fail_stuck_work_orders(&self, id: InvestigationId) -> Result<(), String> {
-        let work_orders = self
-            .store
-            .get_work_orders_by_investigation(id)
-            .await
-            .map_err(|e| format!("Failed to get work orders: {}", e))?;
+    /// Scheduled work orders are left alone; they are waiting on purpose.
+    async fn fail_stuck_work_orders(&self, id: InvestigationId) -> Result<(), OrchestratorError> {
+        let work_orders = self.load_work_orders(id).await?;
 
         let stuck: Vec<_> = work_orders
             .iter()
@@ -424,182 +726,728 @@ impl Orchestrator {
         Ok(())
     }
 
-    /// Check if all work orders in the most recent cycle failed.
-    async fn check_all_failed_cycle(&self, id: InvestigationId) -> Result<bool, String> {
-        let work_orders = self
-            .store
-            .get_work_orders_by_investigation(id)
-            .await
-            .map_err(|e| format!("Failed to get work orders: {}", e))?;
+    /// Check if all work orders in the most recent cycle failed. A completed
+    /// work order that missed its expected outputs counts as failed.
+    async fn check_all_failed_cycle(&self, id: InvestigationId) -> Result<bool, OrchestratorError> {
+        let work_orders = self.load_work_orders(id).await?;
 
         if work_orders.is_empty() {
             return Ok(false);
         }
 
-        // Find the most recent cycle.
-        let max_cycle = work_orders.iter().map(|wo| wo.cycle).max().unwrap_or(0);
+        // Find the most recent cycle. Proposals never ran, so they don't count.
+        let max_cycle = work_orders
+            .iter()
+            .filter(|wo| !wo.status.is_proposal())
+            .map(|wo| wo.cycle)
+            .max()
+            .unwrap_or(0);
 
         // Check if all work orders in that cycle failed.
         let cycle_orders: Vec<_> = work_orders
             .iter()
-            .filter(|wo| wo.cycle == max_cycle)
+            .filter(|wo| wo.cycle == max_cycle && !wo.status.is_proposal())
             .collect();
 
         if cycle_orders.is_empty() {
             return Ok(false);
         }
 
-        let all_failed = cycle_orders
-            .iter()
-            .all(|wo| wo.status == autosint_common::types::WorkOrderStatus::Failed);
+        let all_failed = cycle_orders.iter().all(|wo| {
+            wo.status == autosint_common::types::WorkOrderStatus::Failed || wo.contract_unmet
+        });
+        if all_failed {
+            let contract_unmet = cycle_orders.iter().filter(|wo| wo.contract_unmet).count();
+            if contract_unmet > 0 {
+                tracing::warn!(
+                    investigation_id = %id,
+                    cycle = max_cycle,
+                    contract_unmet,
+                    "Cycle failed with work orders that missed their expected outputs"
+                );
+            }
+        }
 
         Ok(all_failed)
     }
 
     /// Transition to FAILED state — run one final Analyst session with failure prompt,
     /// then mark the investigation as failed.
-    async fn transition_to_failed(
+    pub(super) async fn transition_to_failed(
         &self,
         id: InvestigationId,
         investigation: &Investigation,
-    ) -> Result<(), String> {
+    ) -> Result<(), OrchestratorError> {
         tracing::warn!(investigation_id = %id, "Investigation transitioning to FAILED");
 
         // Attempt one final assessment with failure context.
+        let prompts = self.prompts.snapshot();
+        self.record_analyst_session(id, investigation.cycle_count, &prompts, "failure")
+            .await;
         let failure_prompt = format!(
             "{}\n\n---\n\n\
             **CRITICAL: This investigation has FAILED.** Produce the best assessment you can with \
             the available information. Clearly note all gaps, limitations, and failures encountered. \
-            A partial assessment documenting what is known and what is not is valuable.",
-            self.analyst_prompt
+            Consult your scratchpad (`read_scratchpad`) for the hypotheses and open questions you \
+            noted in earlier cycles. A partial assessment documenting what is known and what is not \
+            is valuable.",
+            prompts.text(ANALYST_PROMPT)
         );
 
-        let final_session = AnalystSession::new(
-            &self.config.system.llm.analyst,
-            &self.config.system.retry.llm_api,
-            &self.config.system.safety,
-            Arc::clone(&self.graph),
-            self.embedding_client.clone(),
-            Arc::clone(&self.store),
-            Arc::clone(&self.queue),
-            self.fetch_base_url.clone(),
-            failure_prompt,
-            &self.tool_schemas,
-            self.config.system.tool_results.clone(),
-            self.config.system.dedup.clone(),
-            id,
-            investigation.cycle_count,
-        );
+        let final_session =
+            self.analyst_session(&self.config.system.safety, failure_prompt, investigation);
 
         if let Ok(session) = final_session {
-            let user_prompt = format!(
+            let mut user_prompt = format!(
                 "## Investigation (FAILURE MODE)\n\n{}\n\n\
                 This investigation has failed. Produce a partial assessment.",
                 investigation.prompt
             );
-            let _ = session.run(&user_prompt).await;
+            if let Some(line) = self.scratchpad_line(id).await {
+                user_prompt.push_str(&format!("\n\n{}", line));
+            }
+            let started = std::time::Instant::now();
+            let result = session.run(&user_prompt).await;
+            let finished = session_finished(
+                investigation.cycle_count,
+                "failure",
+                &prompts,
+                &result,
+                started,
+            );
+            self.publish(DomainEvent::AnalystSessionFinished {
+                investigation_id: id,
+                session: finished,
+            })
+            .await;
         }
 
-        self.store
-            .update_investigation_status(id, &InvestigationStatus::Failed, false)
-            .await
-            .map_err(|e| format!("Failed to mark investigation as failed: {}", e))?;
+        self.diff_superseded_assessment(id).await;
+        self.set_terminal_status(id, InvestigationStatus::Failed)
+            .await?;
 
         metrics::counter!("investigations.failed").increment(1);
         Ok(())
     }
 
-    /// On startup, recover non-terminal investigations.
-    pub async fn recover_on_startup(&self) -> Result<(), String> {
-        let investigations = self
-            .store
-            .get_non_terminal_investigations()
-            .await
-            .map_err(|e| format!("Failed to query non-terminal investigations: {}", e))?;
+    /// Reload investigation state, retrying transient store errors.
+    pub(super) async fn load_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> Result<Investigation, OrchestratorError> {
+        let retry = &self.config.system.retry.databases;
+        let investigation = retry_store_op(retry, retry.max_attempts, "get_investigation", || {
+            self.store.get_investigation(id)
+        })
+        .await?;
+        Ok(investigation)
+    }
 
-        if investigations.is_empty() {
-            tracing::info!("No investigations to recover on startup");
-            return Ok(());
+    /// Cycle limit for an investigation: its own limit, capped by the safety limit.
+    fn max_cycles(&self, investigation: &Investigation) -> u32 {
+        let ceiling = self.config.system.safety.max_cycles_per_investigation;
+        investigation.max_cycles.map_or(ceiling, |n| n.min(ceiling))
+    }
+
+    /// Load all work orders for an investigation, retrying transient store errors.
+    async fn load_work_orders(
+        &self,
+        id: InvestigationId,
+    ) -> Result<Vec<autosint_common::types::WorkOrder>, OrchestratorError> {
+        let retry = &self.config.system.retry.databases;
+        let work_orders = retry_store_op(
+            retry,
+            retry.max_attempts,
+            "get_work_orders_by_investigation",
+            || self.store.get_work_orders_by_investigation(id),
+        )
+        .await?;
+        Ok(work_orders)
+    }
+
+    /// Non-terminal status transition with the standard database retry budget.
+    async fn set_status(
+        &self,
+        id: InvestigationId,
+        status: InvestigationStatus,
+        increment_cycle: bool,
+    ) -> Result<(), OrchestratorError> {
+        let retry = &self.config.system.retry.databases;
+        let change = retry_store_op(
+            retry,
+            retry.max_attempts,
+            "update_investigation_status",
+            || {
+                self.store
+                    .update_investigation_status(id, &status, increment_cycle)
+            },
+        )
+        .await?;
+        self.publish_status_change(id, change).await;
+        Ok(())
+    }
+
+    async fn publish_status_change(&self, id: InvestigationId, change: Option<StatusChanged>) {
+        if let Some(change) = change {
+            self.publish(DomainEvent::StatusChanged {
+                investigation_id: id,
+                change,
+            })
+            .await;
+        }
+    }
+
+    /// Suspend with `reason`, to be resumed at `resume_from` ("analyst" or
+    /// "processing").
+    async fn suspend(
+        &self,
+        id: InvestigationId,
+        reason: &str,
+        resume_from: &str,
+    ) -> Result<(), OrchestratorError> {
+        let retry = &self.config.system.retry.databases;
+        let suspension = retry_store_op(retry, retry.max_attempts, "suspend_investigation", || {
+            self.store.suspend_investigation(id, reason, resume_from)
+        })
+        .await?;
+        self.publish_suspension(id, suspension).await;
+        Ok(())
+    }
+
+    pub(super) async fn publish_suspension(
+        &self,
+        id: InvestigationId,
+        suspension: Option<InvestigationSuspended>,
+    ) {
+        if let Some(suspension) = suspension {
+            self.publish(DomainEvent::InvestigationSuspended {
+                investigation_id: id,
+                suspension,
+            })
+            .await;
+        }
+    }
+
+    /// Terminal status transition. Retried well past the normal budget; if it still
+    /// fails the investigation is left in an active state until the next restart
+    /// recovers it, so give up loudly.
+    pub(super) async fn set_terminal_status(
+        &self,
+        id: InvestigationId,
+        status: InvestigationStatus,
+    ) -> Result<(), OrchestratorError> {
+        let retry = &self.config.system.retry.databases;
+        let result = retry_store_op(
+            retry,
+            TERMINAL_WRITE_MAX_ATTEMPTS,
+            "update_investigation_status_terminal",
+            || self.store.update_investigation_status(id, &status, false),
+        )
+        .await;
+
+        if let Err(ref e) = result {
+            tracing::error!(
+                investigation_id = %id,
+                status = status.as_db_str(),
+                attempts = TERMINAL_WRITE_MAX_ATTEMPTS,
+                error = %e,
+                "Giving up on terminal status write — investigation stranded until restart recovery"
+            );
+            metrics::counter!("orchestrator.terminal_write.abandoned").increment(1);
         }
+        self.publish_status_change(id, result?).await;
+
+        if let Err(e) = self.queue.shortlist_clear(id).await {
+            tracing::warn!(investigation_id = %id, error = %e, "Failed to delete entity shortlist");
+        }
+        self.record_footprint(id).await;
+        self.finish_cycle_metrics(id).await;
+        Ok(())
+    }
 
+    /// Aggregate the investigation's work orders by cycle onto it and update
+    /// the per-investigation efficiency gauges. Best effort: the metrics are a
+    /// signal for the Analyst and dashboards, not state the investigation
+    /// depends on.
+    async fn record_cycle_metrics(&self, id: InvestigationId) {
+        let cycles = match self.store.get_work_orders_by_investigation(id).await {
+            Ok(work_orders) => aggregate_cycles(&work_orders),
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to load work orders for cycle metrics");
+                return;
+            }
+        };
+        if let Some(latest) = cycles.last() {
+            tracing::info!(
+                cycle = latest.cycle,
+                work_orders = latest.work_orders,
+                completed = latest.completed,
+                failed = latest.failed,
+                claims_created = latest.claims_created,
+                entities_created = latest.entities_created,
+                relationships_created = latest.relationships_created,
+                "Cycle work orders resolved"
+            );
+        }
+        if let Some(efficiency) = cycle_efficiency(&cycles) {
+            let investigation_id = id.to_string();
+            metrics::gauge!(
+                "investigations.cycle.claims_per_work_order",
+                "investigation_id" => investigation_id.clone()
+            )
+            .set(efficiency.claims_per_work_order);
+            metrics::gauge!(
+                "investigations.cycle.unproductive",
+                "investigation_id" => investigation_id
+            )
+            .set(efficiency.unproductive_cycles as f64);
+        }
+        if let Err(e) = self.store.set_investigation_cycles(id, &cycles).await {
+            tracing::warn!(investigation_id = %id, error = %e, "Failed to record cycle metrics");
+        }
+    }
+
+    /// Log the investigation's cycle efficiency as it ends, and clear its
+    /// unproductive-cycles gauge so stall alerts don't outlive it.
+    async fn finish_cycle_metrics(&self, id: InvestigationId) {
+        metrics::gauge!(
+            "investigations.cycle.unproductive",
+            "investigation_id" => id.to_string()
+        )
+        .set(0.0);
+        let investigation = match self.store.get_investigation(id).await {
+            Ok(investigation) => investigation,
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to load cycle metrics");
+                return;
+            }
+        };
+        let Some(efficiency) = &investigation.cycle_efficiency else {
+            return;
+        };
         tracing::info!(
-            count = investigations.len(),
-            "Found non-terminal investigations on startup"
+            investigation_id = %id,
+            cycles = investigation.cycles.len(),
+            claims_created = investigation.cycles.iter().map(|c| c.claims_created).sum::<u64>(),
+            claims_per_work_order = efficiency.claims_per_work_order,
+            trend = efficiency.trend.as_str(),
+            unproductive_cycles = efficiency.unproductive_cycles,
+            "Investigation cycle efficiency"
         );
+    }
 
-        for investigation in investigations {
-            match investigation.status {
-                InvestigationStatus::Suspended => {
-                    tracing::info!(
-                        id = %investigation.id,
-                        reason = investigation.suspended_reason.as_deref().unwrap_or("unknown"),
-                        "Resuming suspended investigation"
-                    );
-                }
-                InvestigationStatus::AnalystRunning | InvestigationStatus::Processing => {
-                    // Crashed mid-operation — treat as suspended.
-                    tracing::warn!(
-                        id = %investigation.id,
-                        status = investigation.status.as_db_str(),
-                        "Investigation was active at shutdown, treating as suspended"
-                    );
-                    let resume_from = if investigation.status == InvestigationStatus::Processing {
-                        "processing"
-                    } else {
-                        "analyst"
-                    };
-                    if let Err(e) = self
-                        .store
-                        .suspend_investigation(investigation.id, "engine_restart", resume_from)
-                        .await
-                    {
-                        tracing::error!(error = %e, "Failed to suspend investigation for recovery");
-                        continue;
-                    }
-                }
-                InvestigationStatus::Pending => {
-                    tracing::info!(
-                        id = %investigation.id,
-                        "Resuming pending investigation"
-                    );
-                }
-                _ => continue,
+    /// Sum the footprints of the investigation's work orders onto it. Best
+    /// effort: GET /investigate/{id}/footprint computes the same on demand.
+    async fn record_footprint(&self, id: InvestigationId) {
+        let result = match self.store.investigation_footprint(id).await {
+            Ok((footprint, _)) => {
+                tracing::info!(
+                    investigation_id = %id,
+                    fetches = footprint.fetches,
+                    domains = footprint.fetches_by_domain.len(),
+                    searches = footprint.searches,
+                    bytes_downloaded = footprint.bytes_downloaded,
+                    rate_limited = footprint.rate_limited,
+                    budget_rejections = footprint.budget_rejections,
+                    "Investigation outbound footprint"
+                );
+                self.store.set_investigation_footprint(id, &footprint).await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(investigation_id = %id, error = %e, "Failed to record investigation footprint");
+        }
+    }
+
+    /// The investigation's entity shortlist for tool handlers, if enabled.
+    fn entity_shortlist(&self, id: InvestigationId) -> Option<EntityShortlist> {
+        EntityShortlist::new(
+            Arc::clone(&self.queue),
+            id,
+            &self.config.system.entity_shortlist,
+        )
+    }
+
+    /// Prompt block of the investigation's most-referenced shortlist entities.
+    async fn shortlist_block(&self, id: InvestigationId) -> Option<String> {
+        let config = &self.config.system.entity_shortlist;
+        if !config.enabled {
+            return None;
+        }
+        match self.queue.shortlist_top(id, config.prompt_entities).await {
+            Ok(entries) => format_shortlist_block(&entries),
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to read entity shortlist");
+                None
             }
+        }
+    }
 
-            // Spawn investigation lifecycle as a background task.
-            let orchestrator_graph = Arc::clone(&self.graph);
-            let orchestrator_store = Arc::clone(&self.store);
-            let orchestrator_queue = Arc::clone(&self.queue);
-            let orchestrator_emb = self.embedding_client.clone();
-            let orchestrator_config = Arc::clone(&self.config);
-            let orchestrator_fetch = self.fetch_base_url.clone();
-            let orchestrator_schemas = Arc::clone(&self.tool_schemas);
-            let orchestrator_prompt = self.analyst_prompt.clone();
-            let orchestrator_cbs = Arc::clone(&self.circuit_breakers);
-            let inv_id = investigation.id;
-
-            tokio::spawn(async move {
-                let orch = Orchestrator::new(
-                    orchestrator_graph,
-                    orchestrator_store,
-                    orchestrator_queue,
-                    orchestrator_emb,
-                    orchestrator_config,
-                    orchestrator_fetch,
-                    orchestrator_schemas,
-                    orchestrator_prompt,
-                    orchestrator_cbs,
+    /// One-line summary of graph changes since the previous Analyst session,
+    /// around the focus entities if the investigation has any, otherwise in
+    /// the investigation. `None` for the first session or if the lookup fails.
+    async fn graph_delta_line(
+        &self,
+        id: InvestigationId,
+        investigation: &Investigation,
+    ) -> Option<String> {
+        let since = match self.store.previous_analyst_session_start(id).await {
+            Ok(since) => since?,
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to read previous Analyst session");
+                return None;
+            }
+        };
+        let scope = if investigation.focus_entities.is_empty() {
+            ChangeScope::Investigation(id)
+        } else {
+            ChangeScope::Focus(investigation.focus_entities.clone())
+        };
+        match self.graph.changes_since(since, &scope, 0).await {
+            Ok(changes) => Some(format_graph_delta(&changes.counts, &scope)),
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to read graph changes");
+                None
+            }
+        }
+    }
+
+    /// The names of the sections in the investigation's scratchpad, for the
+    /// Analyst prompt. `None` if it is empty or the lookup fails.
+    async fn scratchpad_line(&self, id: InvestigationId) -> Option<String> {
+        match self.store.scratchpad_section_names(id).await {
+            Ok(sections) => format_scratchpad_line(&sections),
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to read scratchpad");
+                None
+            }
+        }
+    }
+
+    /// Work orders Processors proposed that await the Analyst's decision, for
+    /// the Analyst prompt. `None` if there are none or the lookup fails.
+    async fn proposals_block(&self, id: InvestigationId) -> Option<String> {
+        match self.store.get_work_orders_by_investigation(id).await {
+            Ok(work_orders) => format_proposals_block(&work_orders),
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to read proposals");
+                None
+            }
+        }
+    }
+
+    /// External enrichment results that arrived since the last cycle, for the
+    /// Analyst prompt. Each is shown once. `None` if enrichment is disabled,
+    /// nothing arrived, or the lookup fails.
+    async fn enrichment_block(&self, id: InvestigationId) -> Option<String> {
+        let config = &self.config.system.ingest.enrichment;
+        if !config.enabled {
+            return None;
+        }
+        match self
+            .store
+            .take_unsurfaced_enrichment_results(id, config.excerpt_chars)
+            .await
+        {
+            Ok(requests) => enrichment::format_results_block(&requests),
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to read enrichment results");
+                None
+            }
+        }
+    }
+
+    /// If the investigation's latest assessment supersedes an earlier one
+    /// and has no diff yet, compute and store it. Failures are logged; the
+    /// assessment stands without a diff.
+    async fn diff_superseded_assessment(&self, id: InvestigationId) {
+        let current = match self.store.get_latest_assessment(id).await {
+            Ok(assessment) => assessment,
+            Err(StoreError::NotFound(_)) => return,
+            Err(e) => {
+                tracing::warn!(investigation_id = %id, error = %e, "Failed to load assessment for diff");
+                return;
+            }
+        };
+        let Some(previous_id) = current.supersedes_assessment_id else {
+            return;
+        };
+        if current.diff.is_some() {
+            return;
+        }
+        let previous = match self.store.get_assessment(previous_id).await {
+            Ok(assessment) => assessment,
+            Err(e) => {
+                tracing::warn!(
+                    investigation_id = %id,
+                    superseded = %previous_id,
+                    error = %e,
+                    "Failed to load superseded assessment"
                 );
-                if let Err(e) = orch.run_investigation(inv_id).await {
-                    tracing::error!(
-                        investigation_id = %inv_id,
-                        error = %e,
-                        "Recovery investigation failed"
-                    );
-                }
-            });
+                return;
+            }
+        };
+
+        let diff = assessment_diff::diff_assessments(
+            &previous,
+            &current,
+            self.services.embedding_client.as_deref(),
+            &self.config.system.assessment_diff,
+        )
+        .await;
+        let (unchanged, revised, new, dropped) = diff.counts();
+        match self.store.set_assessment_diff(current.id, &diff).await {
+            Ok(()) => tracing::info!(
+                assessment_id = %current.id,
+                superseded = %previous_id,
+                unchanged,
+                revised,
+                new,
+                dropped,
+                "Assessment diff recorded"
+            ),
+            Err(e) => tracing::warn!(
+                assessment_id = %current.id,
+                error = %e,
+                "Failed to store assessment diff"
+            ),
         }
+    }
+}
 
-        Ok(())
+/// The scratchpad line added to the Analyst prompt. `None` when the
+/// scratchpad is empty.
+fn format_scratchpad_line(sections: &[String]) -> Option<String> {
+    if sections.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Your scratchpad has sections: {}. Call read_scratchpad to review them.",
+        sections.join(", ")
+    ))
+}
+
+/// The pending proposals block added to the Analyst prompt, one line per
+/// proposal. `None` when nothing is pending.
+fn format_proposals_block(work_orders: &[WorkOrder]) -> Option<String> {
+    let pending: Vec<&WorkOrder> = work_orders
+        .iter()
+        .filter(|wo| wo.status == WorkOrderStatus::Proposed)
+        .collect();
+    if pending.is_empty() {
+        return None;
+    }
+    let mut block = String::from(
+        "## Pending Proposals\n\nProcessors proposed these follow-up work orders. Approve the \
+         ones worth running and dismiss the rest with approve_proposals; approving is cheaper \
+         than writing a new work order.\n\n",
+    );
+    for wo in pending {
+        block.push_str(&format!(
+            "- `{}` ({}): {}",
+            wo.id,
+            wo.priority.as_str(),
+            wo.objective
+        ));
+        if let Some(rationale) = &wo.rationale {
+            block.push_str(&format!(" — {}", rationale));
+        }
+        block.push('\n');
+    }
+    Some(block)
+}
+
+/// The line on the last cycle's yield added to the Analyst prompt, so the
+/// Analyst can wrap up once its work orders stop finding anything. `None`
+/// before any cycle has run work orders.
+fn format_cycle_line(cycles: &[CycleMetrics]) -> Option<String> {
+    let efficiency = cycle_efficiency(cycles)?;
+    let latest = cycles.iter().rev().find(|c| c.work_orders > 0)?;
+    let mut line = format!(
+        "Last cycle produced {} new claims across {} work orders ({} completed, {} failed).",
+        latest.claims_created, latest.work_orders, latest.completed, latest.failed,
+    );
+    match efficiency.trend {
+        CycleTrend::Stalled if efficiency.unproductive_cycles > 1 => line.push_str(&format!(
+            " That is {} cycles in a row without new claims; unless a different approach is \
+             likely to find more, produce your assessment now.",
+            efficiency.unproductive_cycles
+        )),
+        CycleTrend::Stalled => line.push_str(
+            " If further work orders are unlikely to find more, consider producing your assessment.",
+        ),
+        CycleTrend::Tapering => {
+            line.push_str(" New claims per work order are tapering off from the cycle before.")
+        }
+        CycleTrend::Rising | CycleTrend::Steady => {}
+    }
+    Some(line)
+}
+
+/// The line added to the Analyst prompt of a snapshot investigation in place
+/// of the graph delta.
+fn snapshot_line(as_of: DateTime<Utc>) -> String {
+    format!(
+        "Snapshot: you are seeing the graph as it stood at {}. Nothing written since is \
+         visible, and work orders and graph changes are unavailable. Produce your assessment \
+         from what the graph held then.",
+        as_of.format("%Y-%m-%d %H:%M UTC"),
+    )
+}
+
+/// The per-cycle graph delta line added to the Analyst prompt.
+fn format_graph_delta(counts: &ChangeCounts, scope: &ChangeScope) -> String {
+    let place = match scope {
+        ChangeScope::Focus(_) => "around your focus entities",
+        _ => "in this investigation",
+    };
+    if counts.is_empty() {
+        return format!("Since your last cycle: no graph changes {}.", place);
+    }
+    format!(
+        "Since your last cycle: {} new claims, {} new and {} updated entities, \
+         {} new relationships {}. Call get_graph_changes for details.",
+        counts.claims,
+        counts.entities_created,
+        counts.entities_updated,
+        counts.relationships,
+        place,
+    )
+}
+
+/// Evidence-bar instruction added to the Analyst prompt for a strictness profile.
+fn strictness_guidance(strictness: StrictnessProfile) -> &'static str {
+    match strictness {
+        StrictnessProfile::Lenient => {
+            "Strictness: lenient. A single credible source is enough to report a finding; \
+             favor coverage over corroboration."
+        }
+        StrictnessProfile::Standard => {
+            "Strictness: standard. Corroborate key findings where sources allow."
+        }
+        StrictnessProfile::Strict => {
+            "Strictness: strict. Treat findings as established only with independent \
+             corroboration; flag single-source claims explicitly in the assessment."
+        }
+    }
+}
+
+/// The timeline event for an Analyst session that started at `started`.
+/// `mode` is as passed to `record_analyst_session`.
+fn session_finished(
+    cycle: i32,
+    mode: &str,
+    prompts: &PromptSet,
+    result: &AnalystSessionResult,
+    started: std::time::Instant,
+) -> AnalystSessionFinished {
+    let stats = result.session_result.stats();
+    let (outcome, work_orders_created) = match &result.outcome {
+        AnalystOutcome::WorkOrdersCreated { count } => ("work_orders_created", Some(*count)),
+        AnalystOutcome::AssessmentProduced => ("assessment_produced", None),
+        AnalystOutcome::EmptySession => ("empty_session", None),
+        AnalystOutcome::Failed { .. } => ("failed", None),
+    };
+    AnalystSessionFinished {
+        cycle,
+        mode: Some(mode.to_string()),
+        prompt_hash: prompts.get(ANALYST_PROMPT).map(|p| p.hash.clone()),
+        outcome: outcome.to_string(),
+        work_orders_created,
+        turns: stats.turns,
+        tool_calls: stats.tool_calls,
+        tool_calls_by_name: stats.tool_calls_by_name.clone(),
+        input_tokens: stats.total_input_tokens,
+        output_tokens: stats.total_output_tokens,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(cycle: i32, work_orders: u32, failed: u32, claims_created: u64) -> CycleMetrics {
+        CycleMetrics {
+            cycle,
+            work_orders,
+            completed: work_orders - failed,
+            failed,
+            claims_created,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_line() {
+        let as_of = DateTime::parse_from_rfc3339("2026-03-01T09:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let line = snapshot_line(as_of);
+        assert!(line.starts_with(
+            "Snapshot: you are seeing the graph as it stood at 2026-03-01 09:30 UTC."
+        ));
+        assert!(line.contains("work orders and graph changes are unavailable"));
+    }
+
+    #[test]
+    fn test_cycle_line() {
+        assert_eq!(format_cycle_line(&[]), None);
+        assert_eq!(
+            format_cycle_line(&[cycle(0, 2, 0, 6)]).unwrap(),
+            "Last cycle produced 6 new claims across 2 work orders (2 completed, 0 failed)."
+        );
+        assert!(format_cycle_line(&[cycle(0, 2, 0, 6), cycle(1, 3, 0, 2)])
+            .unwrap()
+            .ends_with("tapering off from the cycle before."));
+        assert_eq!(
+            format_cycle_line(&[cycle(0, 2, 0, 6), cycle(1, 3, 1, 0)]).unwrap(),
+            "Last cycle produced 0 new claims across 3 work orders (2 completed, 1 failed). \
+             If further work orders are unlikely to find more, consider producing your assessment."
+        );
+        assert!(
+            format_cycle_line(&[cycle(0, 2, 0, 0), cycle(1, 0, 0, 0), cycle(2, 3, 0, 0)])
+                .unwrap()
+                .contains("That is 2 cycles in a row without new claims")
+        );
+    }
+
+    #[test]
+    fn test_proposals_block_lists_pending_proposals() {
+        let investigation_id = InvestigationId::new();
+        let order = |objective: &str, status: WorkOrderStatus| {
+            let mut wo = WorkOrder::new(investigation_id, objective.into(), WorkOrderPriority::Low);
+            wo.status = status;
+            wo
+        };
+        let completed = order("Find CEO", WorkOrderStatus::Completed);
+        let mut pending = order("Find board members", WorkOrderStatus::Proposed);
+        pending.rationale = Some("The annual report names a new board".into());
+        let dismissed = order("Find office address", WorkOrderStatus::Dismissed);
+
+        assert_eq!(format_proposals_block(&[]), None);
+        assert_eq!(
+            format_proposals_block(&[completed.clone(), dismissed.clone()]),
+            None
+        );
+        let block = format_proposals_block(&[completed, pending.clone(), dismissed]).unwrap();
+        assert!(block.starts_with("## Pending Proposals\n"));
+        assert!(block.contains("approve_proposals"));
+        assert!(block.ends_with(&format!(
+            "- `{}` (low): Find board members — The annual report names a new board\n",
+            pending.id
+        )));
+        assert!(!block.contains("Find office address"));
+    }
+
+    #[test]
+    fn test_scratchpad_line_lists_sections() {
+        assert_eq!(format_scratchpad_line(&[]), None);
+        assert_eq!(
+            format_scratchpad_line(&["hypotheses".into(), "open questions".into()]).unwrap(),
+            "Your scratchpad has sections: hypotheses, open questions. \
+             Call read_scratchpad to review them."
+        );
     }
 }
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use autosint_common::types::{ExpectedOutputs, UnmetOutput};
+
+/// What a Processor session delivered, to check against its work order's
+/// expected outputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Deliverables {
+    pub claims_created: u32,
+    /// Names (lowercased) of the entities the session created or matched.
+    pub entity_names: HashSet<String>,
+    /// Distinct sources cited by the session's claims.
+    pub source_count: u32,
+}
+
+/// The criteria of `expected` that `delivered` falls short of, in declaration
+/// order. Empty when the contract is met. Required entities match any created
+/// or matched name, ignoring case and surrounding whitespace.
+pub fn evaluate(expected: &ExpectedOutputs, delivered: &Deliverables) -> Vec<UnmetOutput> {
+    let mut unmet = Vec::new();
+
+    if let Some(min) = expected.min_claims {
+        if delivered.claims_created < min {
+            unmet.push(UnmetOutput::MinClaims {
+                expected: min,
+                delivered: delivered.claims_created,
+            });
+        }
+    }
+
+    let missing: Vec<String> = expected
+        .require_entities
+        .iter()
+        .filter(|name| !delivered.entity_names.contains(&name.trim().to_lowercase()))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        unmet.push(UnmetOutput::RequireEntities { missing });
+    }
+
+    if let Some(min) = expected.require_source_count {
+        if delivered.source_count < min {
+            unmet.push(UnmetOutput::RequireSourceCount {
+                expected: min,
+                delivered: delivered.source_count,
+            });
+        }
+    }
+
+    unmet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delivered(claims: u32, names: &[&str], sources: u32) -> Deliverables {
+        Deliverables {
+            claims_created: claims,
+            entity_names: names.iter().map(|n| n.to_lowercase()).collect(),
+            source_count: sources,
+        }
+    }
+
+    #[test]
+    fn test_empty_contract_is_met() {
+        let expected = ExpectedOutputs::default();
+        assert!(expected.is_empty());
+        assert!(evaluate(&expected, &Deliverables::default()).is_empty());
+    }
+
+    #[test]
+    fn test_met_contract() {
+        let expected = ExpectedOutputs {
+            min_claims: Some(3),
+            require_entities: vec!["Crius".into(), " Oceanic Shipping Ltd ".into()],
+            require_source_count: Some(2),
+        };
+        let result = evaluate(
+            &expected,
+            &delivered(3, &["crius", "Oceanic Shipping Ltd", "Panama"], 2),
+        );
+        assert!(result.is_empty(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_reports_each_unmet_criterion() {
+        let expected = ExpectedOutputs {
+            min_claims: Some(3),
+            require_entities: vec!["Crius".into(), "Oceanic Shipping Ltd".into()],
+            require_source_count: Some(2),
+        };
+        assert_eq!(
+            evaluate(&expected, &delivered(0, &["Crius"], 0)),
+            vec![
+                UnmetOutput::MinClaims {
+                    expected: 3,
+                    delivered: 0
+                },
+                UnmetOutput::RequireEntities {
+                    missing: vec!["Oceanic Shipping Ltd".into()]
+                },
+                UnmetOutput::RequireSourceCount {
+                    expected: 2,
+                    delivered: 0
+                },
+            ]
+        );
+
+        // Only the criteria that were declared are checked.
+        let claims_only = ExpectedOutputs {
+            min_claims: Some(1),
+            ..Default::default()
+        };
+        assert!(evaluate(&claims_only, &delivered(1, &[], 0)).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use autosint_common::config::DeadlineWatchdogConfig;
+use autosint_common::ids::WorkOrderId;
+use autosint_common::types::WorkOrder;
+
+use crate::store::StoreClient;
+use crate::supervisor;
+
+/// Spawn the deadline watchdog. Every check interval it looks for scheduled or
+/// queued work orders due within the at-risk window, warns once per order and
+/// reports how many are at risk. Orders that miss their deadline anyway are
+/// failed by the Processor that dequeues them. Passes hold the
+/// `deadline_watchdog` singleton lock, so replicas don't each warn.
+pub fn spawn_deadline_watchdog(
+    store: Arc<StoreClient>,
+    config: DeadlineWatchdogConfig,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.check_interval_seconds);
+    let window = chrono::Duration::seconds(config.at_risk_window_seconds as i64);
+
+    supervisor::spawn_supervised_restarting("deadline_watchdog", move || {
+        let store = Arc::clone(&store);
+        async move {
+            let mut flagged = HashSet::new();
+            loop {
+                tokio::time::sleep(interval).await;
+
+                let check = check_deadlines(&store, window, &mut flagged);
+                if let Err(e) = store.try_run_exclusive("deadline_watchdog", check).await {
+                    tracing::error!(error = %e, "Failed to take the deadline watchdog lock");
+                }
+            }
+        }
+    })
+}
+
+/// One watchdog pass: export the at-risk count and warn about orders newly
+/// at risk.
+async fn check_deadlines(
+    store: &StoreClient,
+    window: chrono::Duration,
+    flagged: &mut HashSet<WorkOrderId>,
+) {
+    let now = chrono::Utc::now();
+    let at_risk = match store.pending_work_orders_due_by(now + window).await {
+        Ok(orders) => orders,
+        Err(e) => {
+            tracing::error!(error = %e, "Deadline watchdog failed to list work orders");
+            return;
+        }
+    };
+    metrics::gauge!("work_orders.deadline_at_risk").set(at_risk.len() as f64);
+
+    for wo in newly_at_risk(&at_risk, flagged) {
+        metrics::counter!(
+            "work_orders.deadline_at_risk_flagged",
+            "priority" => wo.priority.as_str()
+        )
+        .increment(1);
+        tracing::warn!(
+            work_order_id = %wo.id,
+            investigation_id = %wo.investigation_id,
+            status = wo.status.as_db_str(),
+            priority = wo.priority.as_str(),
+            deadline = ?wo.deadline,
+            "Work order approaching its deadline without a Processor"
+        );
+    }
+}
+
+/// Work orders in `at_risk` not flagged on an earlier pass. `flagged` keeps
+/// only the current set, so it stays bounded as orders are picked up.
+fn newly_at_risk<'a>(
+    at_risk: &'a [WorkOrder],
+    flagged: &mut HashSet<WorkOrderId>,
+) -> Vec<&'a WorkOrder> {
+    let fresh = at_risk
+        .iter()
+        .filter(|wo| !flagged.contains(&wo.id))
+        .collect();
+    *flagged = at_risk.iter().map(|wo| wo.id).collect();
+    fresh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::WorkOrderPriority;
+    use autosint_common::InvestigationId;
+
+    fn work_order() -> WorkOrder {
+        WorkOrder::new(
+            InvestigationId::new(),
+            "Find the Crius' registered owner".into(),
+            WorkOrderPriority::Normal,
+        )
+    }
+
+    #[test]
+    fn test_each_order_flagged_once() {
+        let (a, b) = (work_order(), work_order());
+        let mut flagged = HashSet::new();
+
+        let first = newly_at_risk(std::slice::from_ref(&a), &mut flagged);
+        assert_eq!(first.len(), 1);
+
+        let pass = [a.clone(), b.clone()];
+        let second = newly_at_risk(&pass, &mut flagged);
+        assert_eq!(second.iter().map(|wo| wo.id).collect::<Vec<_>>(), [b.id]);
+
+        // Once picked up, an order drops out of the set.
+        assert!(newly_at_risk(std::slice::from_ref(&b), &mut flagged).is_empty());
+        assert_eq!(flagged.len(), 1);
+    }
+}
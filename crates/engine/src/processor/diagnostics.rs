@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{FailureCategory, FailureDetail};
+
+use crate::llm::session::{SessionResult, ToolExecutor};
+
+/// Error text that points at an unreachable, overloaded or throttling service
+/// rather than at the request itself. Matched case-insensitively.
+const DEPENDENCY_MARKERS: &[&str] = &[
+    "error sending request",
+    "request failed",
+    "connection refused",
+    "connection reset",
+    "connection closed",
+    "timed out",
+    "timeout",
+    "unavailable",
+    "unreachable",
+    "overloaded",
+    "bad gateway",
+    "rate limit",
+];
+
+/// Tool call outcomes of a Processor session, kept for failure diagnostics.
+#[derive(Clone, Debug, Default)]
+pub struct ToolCallLog {
+    /// Most recent call: tool name and its error, if it failed.
+    last_call: Option<(String, Option<String>)>,
+    errors_by_tool: BTreeMap<String, u32>,
+}
+
+impl ToolCallLog {
+    fn record(&mut self, tool: &str, error: Option<&str>) {
+        if error.is_some() {
+            *self.errors_by_tool.entry(tool.to_string()).or_default() += 1;
+        }
+        self.last_call = Some((tool.to_string(), error.map(String::from)));
+    }
+
+    /// The last tool call, if it failed: tool name and error.
+    fn last_error(&self) -> Option<(&str, &str)> {
+        match &self.last_call {
+            Some((tool, Some(error))) => Some((tool, error)),
+            _ => None,
+        }
+    }
+}
+
+/// Wrap `executor` so every call's outcome is recorded in `log`.
+pub fn record_tool_calls(executor: ToolExecutor, log: Arc<Mutex<ToolCallLog>>) -> ToolExecutor {
+    let executor = Arc::new(executor);
+    Box::new(move |name, args| {
+        let executor = Arc::clone(&executor);
+        let log = Arc::clone(&log);
+        Box::pin(async move {
+            let result = executor(name.clone(), args).await;
+            log.lock()
+                .unwrap()
+                .record(&name, result.is_error.then_some(result.content.as_str()));
+            result
+        })
+    })
+}
+
+fn is_dependency_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    DEPENDENCY_MARKERS.iter().any(|m| error.contains(m))
+}
+
+/// Diagnostics for a Processor session that failed, or None if it succeeded.
+///
+/// Sessions that error out always fail. Sessions that hit a turn, time,
+/// malformed-call, repeated-call or context limit fail only if nothing
+/// reached the graph — partial progress is kept and the work order completes.
+pub fn failure_detail(
+    outcome: &SessionResult,
+    tools: &ToolCallLog,
+    graph_writes: bool,
+) -> Option<FailureDetail> {
+    let last_tool_error = tools.last_error();
+    let after_dependency_error = last_tool_error.is_some_and(|(_, e)| is_dependency_error(e));
+
+    let (category, error) = match outcome {
+        SessionResult::Completed { .. } => return None,
+        SessionResult::MaxTurnsReached { .. }
+        | SessionResult::TimeLimitReached { .. }
+        | SessionResult::MalformedToolCallLimit { .. }
+        | SessionResult::ToolLoopLimit { .. }
+        | SessionResult::ContextLimitReached { .. }
+            if graph_writes =>
+        {
+            return None
+        }
+        SessionResult::MaxTurnsReached { .. } => (
+            FailureCategory::NoProgress,
+            "Turn limit reached without writing to the graph",
+        ),
+        SessionResult::TimeLimitReached { .. } => (
+            FailureCategory::NoProgress,
+            "Time limit reached without writing to the graph",
+        ),
+        SessionResult::MalformedToolCallLimit { .. } => (
+            FailureCategory::NoProgress,
+            "Too many consecutive malformed tool calls without writing to the graph",
+        ),
+        SessionResult::ToolLoopLimit { .. } => (
+            FailureCategory::NoProgress,
+            "Kept repeating the same tool call without writing to the graph",
+        ),
+        SessionResult::ContextLimitReached { .. } => (
+            FailureCategory::ContextExceeded,
+            "Context budget exhausted without writing to the graph",
+        ),
+        SessionResult::Failed { error, .. } => {
+            let category = if error.starts_with("LLM context window exceeded") {
+                FailureCategory::ContextExceeded
+            } else if after_dependency_error
+                || error.starts_with("LLM HTTP error")
+                || error.starts_with("LLM rate limited")
+                || is_dependency_error(error)
+            {
+                FailureCategory::DependencyUnavailable
+            } else {
+                FailureCategory::LlmError
+            };
+            (category, error.as_str())
+        }
+    };
+
+    // A limit hit while every tool call was failing on an outage isn't the
+    // model's lack of progress.
+    let category = if category == FailureCategory::NoProgress && after_dependency_error {
+        FailureCategory::DependencyUnavailable
+    } else {
+        category
+    };
+
+    Some(FailureDetail {
+        category,
+        error: sanitize_error(error),
+        last_tool: last_tool_error.map(|(tool, _)| tool.to_string()),
+        last_tool_error: last_tool_error.map(|(_, e)| sanitize_error(e)),
+        tool_errors: tools.errors_by_tool.clone(),
+        graph_writes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::future::Future;
+    use std::pin::Pin;
+
+    use serde_json::{json, Value};
+
+    use crate::llm::session::{run_session, SessionConfig, ToolExecutionResult};
+    use crate::llm::{
+        ContentBlock, LlmCaller, LlmError, LlmResponse, Message, StopReason, TokenUsage,
+        ToolDefinition,
+    };
+
+    /// Plays back scripted responses; text-only once the script runs out.
+    struct ScriptedLlm {
+        responses: Mutex<Vec<Result<LlmResponse, LlmError>>>,
+    }
+
+    impl ScriptedLlm {
+        fn new(mut responses: Vec<Result<LlmResponse, LlmError>>) -> Self {
+            responses.reverse();
+            Self {
+                responses: Mutex::new(responses),
+            }
+        }
+    }
+
+    impl LlmCaller for ScriptedLlm {
+        fn chat<'a>(
+            &'a self,
+            _system: &'a str,
+            _messages: &'a [Message],
+            _tools: &'a [ToolDefinition],
+        ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+            let result = self.responses.lock().unwrap().pop().unwrap_or_else(|| {
+                Ok(LlmResponse {
+                    content: vec![ContentBlock::Text {
+                        text: "Done.".into(),
+                    }],
+                    stop_reason: StopReason::EndTurn,
+                    usage: TokenUsage::default(),
+                    served_by: None,
+                })
+            });
+            Box::pin(async move { result })
+        }
+    }
+
+    fn tool_call(name: &str, input: Value) -> Result<LlmResponse, LlmError> {
+        Ok(LlmResponse {
+            content: vec![ContentBlock::ToolUse {
+                id: format!("call-{}", name),
+                name: name.into(),
+                input,
+            }],
+            stop_reason: StopReason::ToolUse,
+            usage: TokenUsage::default(),
+            served_by: None,
+        })
+    }
+
+    /// fetch_url fails as if the fetch service were down; search_entities
+    /// succeeds; everything else is unknown.
+    fn stub_tools() -> ToolExecutor {
+        Box::new(|name: String, _args: Value| {
+            Box::pin(async move {
+                match name.as_str() {
+                    "fetch_url" => ToolExecutionResult {
+                        content: "Fetch service request failed: error sending request for url \
+                                  (http://fetch:8081/fetch): connection refused"
+                            .into(),
+                        is_error: true,
+                        is_malformed: false,
+                    },
+                    "search_entities" => ToolExecutionResult {
+                        content: "{\"count\": 0, \"results\": []}".into(),
+                        is_error: false,
+                        is_malformed: false,
+                    },
+                    _ => ToolExecutionResult {
+                        content: format!("Unknown tool: '{}'", name),
+                        is_error: true,
+                        is_malformed: true,
+                    },
+                }
+            })
+        })
+    }
+
+    async fn run(
+        responses: Vec<Result<LlmResponse, LlmError>>,
+        graph_writes: bool,
+    ) -> Option<FailureDetail> {
+        let llm = ScriptedLlm::new(responses);
+        let log = Arc::new(Mutex::new(ToolCallLog::default()));
+        let executor = record_tool_calls(stub_tools(), Arc::clone(&log));
+        let config = SessionConfig {
+            max_turns: 4,
+            max_consecutive_malformed: 2,
+            max_duration: None,
+            live_stats: None,
+            max_tool_result_tokens: None,
+            loop_limits: None,
+            context_budget_fraction: None,
+        };
+        let outcome = run_session(&llm, "system", "work order", &[], &executor, &config).await;
+        let log = log.lock().unwrap().clone();
+        failure_detail(&outcome, &log, graph_writes)
+    }
+
+    #[tokio::test]
+    async fn test_llm_error() {
+        let detail = run(
+            vec![
+                tool_call("search_entities", json!({"query": "Crius"})),
+                Err(LlmError::Auth(
+                    "401 Unauthorized: bad api_key=sk-live-abcdefghijklmnop".into(),
+                )),
+            ],
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(detail.category, FailureCategory::LlmError);
+        assert!(detail.error.starts_with("LLM auth error"));
+        assert!(!detail.error.contains("sk-live"), "{}", detail.error);
+        assert_eq!(detail.last_tool, None);
+        assert!(detail.tool_errors.is_empty());
+        assert!(!detail.graph_writes);
+    }
+
+    #[tokio::test]
+    async fn test_dependency_unavailable_after_tool_error() {
+        let detail = run(
+            vec![
+                tool_call("fetch_url", json!({"url": "https://example.com"})),
+                tool_call("fetch_url", json!({"url": "https://example.org"})),
+                Err(LlmError::Api("400 Bad Request: invalid message".into())),
+            ],
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(detail.category, FailureCategory::DependencyUnavailable);
+        assert_eq!(detail.last_tool.as_deref(), Some("fetch_url"));
+        assert!(detail
+            .last_tool_error
+            .as_deref()
+            .unwrap()
+            .contains("connection refused"));
+        assert_eq!(detail.tool_errors["fetch_url"], 2);
+
+        // A throttled provider is a dependency too.
+        let detail = run(
+            vec![Err(LlmError::RateLimited { retry_after: None })],
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(detail.category, FailureCategory::DependencyUnavailable);
+    }
+
+    #[tokio::test]
+    async fn test_context_exceeded() {
+        let detail = run(
+            vec![
+                tool_call("fetch_url", json!({"url": "https://example.com"})),
+                tool_call("search_entities", json!({"query": "Crius"})),
+                Err(LlmError::ContextWindowExceeded(
+                    "This model's maximum context length is 128000 tokens \
+                     (context_length_exceeded)"
+                        .into(),
+                )),
+            ],
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(detail.category, FailureCategory::ContextExceeded);
+        // The failure followed a successful call.
+        assert_eq!(detail.last_tool, None);
+        assert_eq!(detail.tool_errors["fetch_url"], 1);
+        assert!(detail.graph_writes);
+    }
+
+    #[tokio::test]
+    async fn test_no_progress() {
+        let malformed = || {
+            (0..2)
+                .map(|_| tool_call("extract_everything", json!({})))
+                .collect::<Vec<_>>()
+        };
+        let detail = run(malformed(), false).await.unwrap();
+        assert_eq!(detail.category, FailureCategory::NoProgress);
+        assert_eq!(detail.last_tool.as_deref(), Some("extract_everything"));
+        assert_eq!(detail.tool_errors["extract_everything"], 2);
+
+        let searches = || {
+            (0..4)
+                .map(|_| tool_call("search_entities", json!({"query": "Crius"})))
+                .collect::<Vec<_>>()
+        };
+        let detail = run(searches(), false).await.unwrap();
+        assert_eq!(detail.category, FailureCategory::NoProgress);
+        assert!(detail.error.contains("Turn limit"));
+
+        // Hitting a limit after writing to the graph is partial progress.
+        assert!(run(searches(), true).await.is_none());
+        assert!(run(malformed(), true).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_limit_during_outage_is_dependency_unavailable() {
+        let fetches = (0..4)
+            .map(|_| tool_call("fetch_url", json!({"url": "https://example.com"})))
+            .collect();
+        let detail = run(fetches, false).await.unwrap();
+        assert_eq!(detail.category, FailureCategory::DependencyUnavailable);
+        assert_eq!(detail.tool_errors["fetch_url"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_completed_is_not_a_failure() {
+        assert!(run(vec![], false).await.is_none());
+    }
+}
@@ -0,0 +1,391 @@
+//! Graph knowledge preloaded into a Processor session's first message: the
+//! work order's referenced entities with their heaviest relationships and
+//! most recent claims, then the investigation's shortlisted entities, cut to
+//! `ToolResultLimits::max_graph_context_chars`. Saves the opening turns every
+//! session otherwise spends looking the referenced entities up.
+
+use autosint_common::config::ToolResultLimits;
+use autosint_common::ids::EntityId;
+use autosint_common::types::{Entity, Relationship};
+
+use crate::graph::{ClaimDigestEntry, GraphClient, GraphError, TraversalParams};
+use crate::queue::shortlist::{EntityShortlist, ShortlistEntry};
+use crate::tools::truncation::truncate_text;
+
+/// Relationships shown per entity, heaviest first, out of the first
+/// `RELATIONSHIPS_SCANNED`.
+const TOP_RELATIONSHIPS: usize = 5;
+const RELATIONSHIPS_SCANNED: u32 = 50;
+
+/// Most recently ingested claims shown per entity.
+const RECENT_CLAIMS: usize = 3;
+
+/// Room kept for the closing note on what was left out.
+const OMISSION_NOTE_RESERVE: usize = 120;
+
+/// A referenced entity as found in the graph.
+pub struct EntityContext {
+    pub entity: Entity,
+    /// Relationships and the entity at their other end, heaviest first.
+    pub relationships: Vec<(Relationship, Entity)>,
+    /// Most recently ingested claims referencing the entity.
+    pub claims: Vec<ClaimDigestEntry>,
+}
+
+/// What the graph knows about a work order, before rendering.
+#[derive(Default)]
+pub struct GraphContext {
+    /// Referenced entities found, in work order order.
+    pub entities: Vec<EntityContext>,
+    /// Referenced entities that could not be resolved.
+    pub unknown: Vec<EntityId>,
+    /// The investigation's most-referenced shortlist entries.
+    pub shortlist: Vec<ShortlistEntry>,
+}
+
+/// Fetch the graph context for `referenced` entities and the investigation's
+/// shortlist. Claims come from the entities' digests when those hold
+/// `preview_chars` previews. A lookup that fails lists its entity as unknown;
+/// relationship and claim lookups that fail leave those sections empty.
+pub async fn load_graph_context(
+    graph: &GraphClient,
+    referenced: &[EntityId],
+    shortlist: Option<&EntityShortlist>,
+    preview_chars: usize,
+) -> GraphContext {
+    let mut context = GraphContext::default();
+    for &id in referenced {
+        let (entity, digest) = match graph.get_entity_with_digest(id).await {
+            Ok(found) => found,
+            Err(e) => {
+                if !matches!(e, GraphError::NotFound(_)) {
+                    tracing::warn!(entity_id = %id, error = %e, "Failed to preload referenced entity");
+                }
+                context.unknown.push(id);
+                continue;
+            }
+        };
+
+        let mut relationships = graph
+            .traverse_relationships(
+                id,
+                &TraversalParams {
+                    direction: None,
+                    min_weight: None,
+                    as_of: None,
+                    limit: Some(RELATIONSHIPS_SCANNED),
+                },
+            )
+            .await
+            .map(|page| page.results)
+            .unwrap_or_else(|e| {
+                tracing::warn!(entity_id = %id, error = %e, "Failed to preload relationships");
+                Vec::new()
+            });
+        relationships.sort_by(|x, y| {
+            y.0.weight
+                .partial_cmp(&x.0.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        relationships.truncate(TOP_RELATIONSHIPS);
+
+        let claims = graph
+            .recent_claims_from(id, digest.as_ref(), RECENT_CLAIMS, preview_chars)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(entity_id = %id, error = %e, "Failed to preload claims");
+                Vec::new()
+            });
+
+        context.entities.push(EntityContext {
+            entity,
+            relationships,
+            claims,
+        });
+    }
+
+    if let Some(shortlist) = shortlist {
+        context.shortlist = shortlist.top().await;
+    }
+    context
+}
+
+/// Render `context` as the "Current graph knowledge" block of the first
+/// message, at most `limits.max_graph_context_chars` long.
+///
+/// Referenced entities come first, in work order order, with full sections
+/// while they fit and headings alone after that. Unresolved entities
+/// follow, then shortlisted entities not already shown. Whatever still does
+/// not fit is counted in a closing note. None when there is nothing to show or
+/// the budget is 0.
+pub fn format_graph_context(context: &GraphContext, limits: &ToolResultLimits) -> Option<String> {
+    let max_chars = limits.max_graph_context_chars as usize;
+    if max_chars == 0 {
+        return None;
+    }
+    let preview = limits.max_claim_preview_chars as usize;
+    let shown: Vec<EntityId> = context
+        .entities
+        .iter()
+        .map(|c| c.entity.id)
+        .chain(context.unknown.iter().copied())
+        .collect();
+    let shortlist: Vec<&ShortlistEntry> = context
+        .shortlist
+        .iter()
+        .filter(|entry| !shown.contains(&entry.entity_id))
+        .collect();
+    if shown.is_empty() && shortlist.is_empty() {
+        return None;
+    }
+
+    let mut block = String::from(
+        "\n## Current graph knowledge\n\n\
+         A snapshot of the knowledge graph as this session started, trimmed to fit. Use the \
+         tools for anything deeper or anything that may have changed.\n",
+    );
+    let budget = max_chars.saturating_sub(OMISSION_NOTE_RESERVE);
+    let mut omitted = 0usize;
+    let push = |block: &mut String, text: &str| {
+        if block.chars().count() + text.chars().count() <= budget {
+            block.push_str(text);
+            true
+        } else {
+            false
+        }
+    };
+
+    // Every referenced entity's heading is held back before any full section
+    // is added, so a long first section never crowds out the rest.
+    let sections: Vec<(String, String)> = context
+        .entities
+        .iter()
+        .map(|c| (entity_section(c, preview), entity_heading(&c.entity)))
+        .collect();
+    let mut held_back: usize = sections.iter().map(|(_, h)| h.chars().count()).sum();
+    for (section, heading) in &sections {
+        held_back -= heading.chars().count();
+        let room = budget.saturating_sub(held_back);
+        if block.chars().count() + section.chars().count() <= room {
+            block.push_str(section);
+        } else if !push(&mut block, heading) {
+            omitted += 1;
+        }
+    }
+
+    if !context.unknown.is_empty() {
+        let heading = "\n### Not in the graph\n\nThese referenced entities could not be found — \
+                       create them if the work order concerns them:\n";
+        if push(&mut block, heading) {
+            for id in &context.unknown {
+                if !push(&mut block, &format!("- `{}`\n", id)) {
+                    omitted += 1;
+                }
+            }
+        } else {
+            omitted += context.unknown.len();
+        }
+    }
+
+    if !shortlist.is_empty() {
+        let heading = "\n### Also known to this investigation\n\n";
+        if push(&mut block, heading) {
+            for entry in &shortlist {
+                let line = format!(
+                    "- {} ({}) — `{}`\n",
+                    entry.canonical_name, entry.kind, entry.entity_id
+                );
+                if !push(&mut block, &line) {
+                    omitted += 1;
+                }
+            }
+        } else {
+            omitted += shortlist.len();
+        }
+    }
+
+    if omitted > 0 {
+        block.push_str(&format!(
+            "\n[{} more entities left out to fit — search for them if needed.]\n",
+            omitted
+        ));
+    }
+    // A budget below the fixed text itself still never overruns.
+    if block.chars().count() > max_chars {
+        block = block.chars().take(max_chars).collect();
+    }
+    Some(block)
+}
+
+fn entity_heading(entity: &Entity) -> String {
+    let stub = if entity.is_stub { ", stub" } else { "" };
+    format!(
+        "\n### {} ({}{}) — `{}`\n",
+        entity.canonical_name, entity.kind, stub, entity.id
+    )
+}
+
+fn entity_section(context: &EntityContext, preview: usize) -> String {
+    let entity = &context.entity;
+    let mut section = entity_heading(entity);
+    if !entity.aliases.is_empty() {
+        section.push_str(&format!("Aliases: {}\n", entity.aliases.join(", ")));
+    }
+    if !entity.identifiers.is_empty() {
+        let identifiers: Vec<String> = entity
+            .identifiers
+            .iter()
+            .map(|(scheme, value)| format!("{} {}", scheme.as_str(), value))
+            .collect();
+        section.push_str(&format!("Identifiers: {}\n", identifiers.join(", ")));
+    }
+    if let Some(summary) = entity.summary.as_deref().filter(|s| !s.is_empty()) {
+        section.push_str(&format!("Summary: {}\n", truncate_text(summary, preview)));
+    }
+
+    if !context.relationships.is_empty() {
+        section.push_str("Relationships:\n");
+        for (relationship, other) in &context.relationships {
+            let arrow = if relationship.source_entity_id == entity.id {
+                "→"
+            } else {
+                "←"
+            };
+            let weight = relationship
+                .weight
+                .map(|w| format!(" (weight {:.2})", w))
+                .unwrap_or_default();
+            section.push_str(&format!(
+                "- {} {} `{}`: {}{}\n",
+                arrow,
+                other.canonical_name,
+                other.id,
+                truncate_text(&relationship.description, preview),
+                weight
+            ));
+        }
+    }
+
+    if !context.claims.is_empty() {
+        section.push_str("Recent claims:\n");
+        for claim in &context.claims {
+            section.push_str(&format!(
+                "- [{}] {} `{}`\n",
+                claim.published_timestamp.format("%Y-%m-%d"),
+                claim.preview(preview),
+                claim.claim_id
+            ));
+        }
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::ClaimId;
+    use chrono::Utc;
+
+    fn limits(max_graph_context_chars: u32) -> ToolResultLimits {
+        ToolResultLimits {
+            max_search_results: 20,
+            max_entity_detail_chars: 10000,
+            max_claim_preview_chars: 200,
+            max_claim_references_shown: 10,
+            max_result_tokens: 8000,
+            max_graph_context_chars,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+        }
+    }
+
+    fn known(name: &str) -> EntityContext {
+        let entity = Entity::new(name.into(), "organization".into());
+        let port = Entity::new("Fujairah".into(), "location".into());
+        let mut relationship = Relationship::new(entity.id, port.id, "operates from".into());
+        relationship.weight = Some(0.8);
+        let claim = ClaimDigestEntry::new(
+            ClaimId::new(),
+            &format!("{} chartered a tanker out of Fujairah.", name),
+            Utc::now(),
+            Utc::now(),
+        );
+        EntityContext {
+            entity,
+            relationships: vec![(relationship, port)],
+            claims: vec![claim],
+        }
+    }
+
+    fn entry(name: &str) -> ShortlistEntry {
+        ShortlistEntry::from_entity(&Entity::new(name.into(), "organization".into()))
+    }
+
+    #[test]
+    fn test_sections_in_order_within_budget() {
+        let first = known("Gulf Star Shipping LLC");
+        let second = known("Meridian Tankers");
+        let repeated = ShortlistEntry::from_entity(&first.entity);
+        let missing = EntityId::new();
+        let context = GraphContext {
+            entities: vec![first, second],
+            unknown: vec![missing],
+            shortlist: vec![repeated, entry("Aurora Holdings")],
+        };
+
+        let block = format_graph_context(&context, &limits(6000)).unwrap();
+        let at = |needle: &str| {
+            block
+                .find(needle)
+                .unwrap_or_else(|| panic!("missing {:?} in {}", needle, block))
+        };
+        assert!(at("## Current graph knowledge") < at("### Gulf Star Shipping LLC"));
+        assert!(at("### Gulf Star Shipping LLC") < at("### Meridian Tankers"));
+        assert!(at("### Meridian Tankers") < at(&format!("- `{}`", missing)));
+        assert!(at(&format!("- `{}`", missing)) < at("- Aurora Holdings (organization)"));
+        assert!(block.contains("- → Fujairah"));
+        assert!(block.contains("(weight 0.80)"));
+        assert!(block.contains("chartered a tanker out of Fujairah."));
+        // A shortlisted entity already shown is not listed twice.
+        assert!(!block.contains("- Gulf Star Shipping LLC (organization)"));
+        assert!(!block.contains("left out"));
+
+        assert!(format_graph_context(&context, &limits(0)).is_none());
+        assert!(format_graph_context(&GraphContext::default(), &limits(6000)).is_none());
+    }
+
+    #[test]
+    fn test_budget_degrades_to_headings_then_omits() {
+        let context = GraphContext {
+            entities: (0..20).map(|i| known(&format!("Entity {}", i))).collect(),
+            unknown: vec![EntityId::new()],
+            shortlist: vec![entry("Aurora Holdings")],
+        };
+
+        let full = format_graph_context(&context, &limits(100_000)).unwrap();
+        assert!(full.contains("Entity 19 chartered a tanker"));
+        for max in [600, 1200, 2500] {
+            let block = format_graph_context(&context, &limits(max)).unwrap();
+            assert!(
+                block.chars().count() <= max as usize,
+                "{} > {}",
+                block.len(),
+                max
+            );
+            assert!(block.contains("### Entity 0 ("));
+            assert!(block.contains("more entities left out to fit"));
+        }
+
+        // Early entities keep their full sections; later ones are named only.
+        let block = format_graph_context(&context, &limits(2500)).unwrap();
+        assert!(block.contains("Entity 0 chartered a tanker"));
+        assert!(block.contains("### Entity 19 ("));
+        assert!(!block.contains("Entity 19 chartered a tanker"));
+
+        // Even a budget below the fixed text is honored.
+        let tiny = format_graph_context(&context, &limits(50)).unwrap();
+        assert!(tiny.chars().count() <= 50);
+    }
+}
@@ -1,5 +1,11 @@
+mod contract;
+mod deadlines;
+mod diagnostics;
+mod graph_context;
 mod pool;
 mod session;
 
-pub use pool::{ProcessorPool, ProcessorPoolConfig};
+pub use deadlines::spawn_deadline_watchdog;
+pub use graph_context::{format_graph_context, load_graph_context, EntityContext, GraphContext};
+pub use pool::{pool_instance_id, ProcessorPool, ProcessorPoolConfig};
 pub use session::{ProcessorSession, ProcessorSessionResult};
@@ -1,21 +1,38 @@
-use std::collections::HashMap;
 use std::sync::Arc;
 
-use serde_json::Value;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 
-use autosint_common::config::{
-    DedupConfig, LlmRoleConfig, RetryConfig, SafetyLimits, ToolResultLimits,
+use autosint_common::config::{EntityShortlistConfig, LlmRoleConfig, RetryConfig, SafetyLimits};
+use autosint_common::ids::WorkOrderId;
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{
+    DomainEvent, FailureCategory, FailureDetail, SessionTelemetry, UnmetOutput, WorkOrderDequeued,
+    WorkOrderFinished, WorkOrderMessage, WorkOrderMode, WorkOrderPriority, WorkOrderStatus,
 };
-use autosint_common::types::WorkOrderStatus;
 
-use crate::embeddings::EmbeddingClient;
-use crate::graph::GraphClient;
+use crate::config::{PromptStore, PROCESSOR_EXPLORATORY_PROMPT};
+use crate::events::EventBus;
+use crate::maintenance::ReadOnlyMode;
+use crate::queue::consumers::consumer_name;
+use crate::queue::fetch_budget::FetchBudgetTracker;
+use crate::queue::shortlist::EntityShortlist;
 use crate::queue::QueueClient;
 use crate::store::StoreClient;
+use crate::supervisor;
+use crate::tools::{SessionScope, SharedServices};
 
-use super::ProcessorSession;
+use super::contract;
+use super::session::processor_prompt;
+use super::{ProcessorSession, ProcessorSessionResult};
+use crate::llm::session::SessionResult;
+use crate::llm::{configured_llm, LlmFactory};
+
+/// How long a paused worker waits between heartbeat and shutdown checks
+/// while read-only.
+const READ_ONLY_PAUSE: std::time::Duration = std::time::Duration::from_secs(5);
 
 /// Configuration for the Processor pool.
 pub struct ProcessorPoolConfig {
@@ -23,6 +40,20 @@ pub struct ProcessorPoolConfig {
     pub heartbeat_ttl_seconds: u64,
     /// Heartbeat refresh interval (typically ttl / 3).
     pub heartbeat_interval_seconds: u64,
+    /// Distinguishes this pool's queue consumers from other replicas'
+    /// (see `pool_instance_id`).
+    pub instance_id: String,
+}
+
+/// This engine's pool instance ID: `ENGINE_INSTANCE_ID` if set (keep it
+/// stable per replica, e.g. the pod name, so a restart resumes its own
+/// consumers), otherwise random per process.
+pub fn pool_instance_id() -> String {
+    std::env::var("ENGINE_INSTANCE_ID")
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..8].to_string())
 }
 
 /// Pool of Processor worker tasks that consume work orders from Redis.
@@ -32,56 +63,106 @@ pub struct ProcessorPool {
 }
 
 impl ProcessorPool {
-    /// Start the processor pool with the given number of workers.
+    /// Start the processor pool with the given number of workers. Workers
+    /// stop dequeuing while `read_only` is on and pick up again as soon as it
+    /// goes off; work orders already running finish.
     #[allow(clippy::too_many_arguments)]
     pub fn start(
         config: ProcessorPoolConfig,
         llm_config: LlmRoleConfig,
         retry_config: RetryConfig,
-        graph: Arc<GraphClient>,
-        embedding_client: Option<Arc<EmbeddingClient>>,
+        services: SharedServices,
+        store: Arc<StoreClient>,
+        queue: Arc<QueueClient>,
+        prompts: PromptStore,
+        safety_limits: SafetyLimits,
+        shortlist_config: EntityShortlistConfig,
+        read_only: ReadOnlyMode,
+    ) -> Self {
+        let llm = configured_llm(llm_config, retry_config, services.flags.clone());
+        Self::start_with_llm(
+            config,
+            llm,
+            services,
+            store,
+            queue,
+            prompts,
+            safety_limits,
+            shortlist_config,
+            read_only,
+        )
+    }
+
+    /// [`start`](Self::start), with each session's model built by `llm`
+    /// instead of from the `llm.processor` config. Tests pass scripted mocks.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_with_llm(
+        config: ProcessorPoolConfig,
+        llm: LlmFactory,
+        services: SharedServices,
         store: Arc<StoreClient>,
         queue: Arc<QueueClient>,
-        fetch_base_url: String,
-        system_prompt: String,
-        tool_schemas: Arc<HashMap<String, Value>>,
-        tool_result_limits: ToolResultLimits,
-        dedup_config: DedupConfig,
+        prompts: PromptStore,
         safety_limits: SafetyLimits,
+        shortlist_config: EntityShortlistConfig,
+        read_only: ReadOnlyMode,
     ) -> Self {
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
-        let llm_config = Arc::new(llm_config);
-        let retry_config = Arc::new(retry_config);
         let safety_limits = Arc::new(safety_limits);
 
+        let consumer_names: Vec<String> = (0..config.pool_size)
+            .map(|i| consumer_name(&config.instance_id, i))
+            .collect();
+
+        // Workers start once dead consumers' entries have been moved to them.
+        let (ready_tx, ready_rx) = watch::channel(false);
+        let cleanup_queue = Arc::clone(&queue);
+        let cleanup_names = consumer_names.clone();
+        let min_idle_ms = reclaim_min_idle_ms(config.heartbeat_ttl_seconds);
+        supervisor::spawn_supervised("queue_consumer_cleanup", async move {
+            match cleanup_queue
+                .clean_up_consumers(&cleanup_names, min_idle_ms)
+                .await
+            {
+                Ok(cleanup) => tracing::info!(
+                    deleted = cleanup.deleted.len(),
+                    orphaned_pending = cleanup.orphaned_pending,
+                    reclaimed = cleanup.reclaimed,
+                    "Queue consumer cleanup complete"
+                ),
+                Err(e) => tracing::warn!(error = %e, "Queue consumer cleanup failed"),
+            }
+            let _ = ready_tx.send(true);
+        });
+
         let mut workers = Vec::with_capacity(config.pool_size as usize);
 
-        for i in 0..config.pool_size {
-            let consumer_name = format!("processor-{}", i);
+        for consumer_name in consumer_names {
             let worker = processor_worker_loop(
                 consumer_name,
+                ready_rx.clone(),
                 shutdown_rx.clone(),
-                Arc::clone(&llm_config),
-                Arc::clone(&retry_config),
-                Arc::clone(&graph),
-                embedding_client.clone(),
+                Arc::clone(&llm),
+                services.clone(),
                 Arc::clone(&store),
                 Arc::clone(&queue),
-                fetch_base_url.clone(),
-                system_prompt.clone(),
-                Arc::clone(&tool_schemas),
-                tool_result_limits.clone(),
-                dedup_config.clone(),
+                prompts.clone(),
                 Arc::clone(&safety_limits),
+                shortlist_config.clone(),
+                read_only.clone(),
                 config.heartbeat_ttl_seconds,
                 config.heartbeat_interval_seconds,
             );
 
-            workers.push(tokio::spawn(worker));
+            workers.push(supervisor::spawn_supervised("processor_worker", worker));
         }
 
-        tracing::info!(pool_size = config.pool_size, "Processor pool started");
+        tracing::info!(
+            pool_size = config.pool_size,
+            instance_id = %config.instance_id,
+            "Processor pool started"
+        );
 
         Self {
             workers,
@@ -103,41 +184,82 @@ impl ProcessorPool {
     }
 }
 
+/// Idle time after which a consumer's pending entries are reclaimed: 2×
+/// heartbeat TTL — if a consumer hasn't heartbeated in that long, it's dead.
+fn reclaim_min_idle_ms(heartbeat_ttl_seconds: u64) -> u64 {
+    heartbeat_ttl_seconds * 2 * 1000
+}
+
 /// Main loop for a single Processor worker.
 #[allow(clippy::too_many_arguments)]
 async fn processor_worker_loop(
     consumer_name: String,
+    mut ready_rx: watch::Receiver<bool>,
     shutdown_rx: watch::Receiver<bool>,
-    llm_config: Arc<LlmRoleConfig>,
-    retry_config: Arc<RetryConfig>,
-    graph: Arc<GraphClient>,
-    embedding_client: Option<Arc<EmbeddingClient>>,
+    llm: LlmFactory,
+    services: SharedServices,
     store: Arc<StoreClient>,
     queue: Arc<QueueClient>,
-    fetch_base_url: String,
-    system_prompt: String,
-    tool_schemas: Arc<HashMap<String, Value>>,
-    tool_result_limits: ToolResultLimits,
-    dedup_config: DedupConfig,
+    prompts: PromptStore,
     safety_limits: Arc<SafetyLimits>,
+    shortlist_config: EntityShortlistConfig,
+    read_only: ReadOnlyMode,
     heartbeat_ttl: u64,
     heartbeat_interval: u64,
 ) {
+    // Err: the cleanup task died without signalling; start anyway.
+    let _ = ready_rx.wait_for(|ready| *ready).await;
     tracing::info!(consumer = %consumer_name, "Processor worker started");
 
     // Reclaim stale messages from dead consumers periodically.
-    // min_idle = 2× heartbeat TTL — if a consumer hasn't heartbeated in that long, it's dead.
-    let reclaim_min_idle_ms = (heartbeat_ttl * 2) * 1000;
+    let reclaim_min_idle_ms = reclaim_min_idle_ms(heartbeat_ttl);
     let reclaim_interval = std::time::Duration::from_secs(heartbeat_ttl);
     let mut last_reclaim = std::time::Instant::now();
+    let heartbeat_interval_idle = std::time::Duration::from_secs(heartbeat_interval);
+    let mut last_heartbeat: Option<std::time::Instant> = None;
+    let mut read_only_changes = read_only.subscribe();
+    let mut paused = false;
 
     loop {
         // Check shutdown.
         if *shutdown_rx.borrow() {
             tracing::info!(consumer = %consumer_name, "Processor worker shutting down");
+            if paused {
+                metrics::gauge!("processor.pool.paused").decrement(1.0);
+            }
             break;
         }
 
+        // Heartbeat while idle too, so another replica's consumer cleanup
+        // never takes this consumer for dead.
+        if last_heartbeat.is_none_or(|at| at.elapsed() >= heartbeat_interval_idle) {
+            if let Err(e) = queue.heartbeat(&consumer_name, heartbeat_ttl).await {
+                tracing::warn!(consumer = %consumer_name, error = %e, "Failed to refresh heartbeat");
+            }
+            last_heartbeat = Some(std::time::Instant::now());
+        }
+
+        // Read-only: leave work orders on the stream, heartbeating so the
+        // consumer isn't taken for dead, until the mode goes off.
+        if read_only.is_enabled() {
+            if !paused {
+                tracing::info!(consumer = %consumer_name, "Engine is read-only, pausing worker");
+                metrics::gauge!("processor.pool.paused").increment(1.0);
+                paused = true;
+            }
+            let _ = tokio::time::timeout(
+                READ_ONLY_PAUSE,
+                read_only_changes.wait_for(|read_only| !*read_only),
+            )
+            .await;
+            continue;
+        }
+        if paused {
+            tracing::info!(consumer = %consumer_name, "Engine writable again, resuming worker");
+            metrics::gauge!("processor.pool.paused").decrement(1.0);
+            paused = false;
+        }
+
         // Periodically reclaim stale messages from dead consumers.
         // XCLAIM transfers ownership to this consumer; the next dequeue(ID=0) picks them up.
         if last_reclaim.elapsed() >= reclaim_interval {
@@ -173,14 +295,101 @@ async fn processor_worker_loop(
             }
         };
 
+        let dequeued_at = std::time::Instant::now();
+        let priority = WorkOrderPriority::ALL
+            .into_iter()
+            .find(|p| p.as_redis_stream() == stream_name)
+            .unwrap_or_default();
         let work_order_id = msg.work_order_id;
-        tracing::info!(
-            consumer = %consumer_name,
+        let span = tracing::info_span!(
+            "work_order",
             work_order_id = %work_order_id,
-            objective = %msg.objective,
-            "Processing work order"
+            investigation_id = %msg.investigation_id,
+            correlation_id = msg.correlation_id.as_deref(),
         );
 
+        // Claim the work order (status to Processing). A message from an earlier
+        // delivery was superseded by a re-enqueue (e.g. reprioritization), whose
+        // copy is processed instead.
+        match store
+            .start_work_order(work_order_id, msg.delivery, &consumer_name)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                span.in_scope(|| {
+                    tracing::info!(
+                        delivery = msg.delivery,
+                        "Skipping superseded work order delivery"
+                    )
+                });
+                metrics::counter!("work_orders.superseded").increment(1);
+                if let Err(e) = queue.ack(&stream_name, &entry_id).await {
+                    tracing::error!(error = %e, "Failed to ack superseded work order");
+                }
+                continue;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to update work order status to Processing");
+            }
+        }
+        services
+            .events
+            .publish(DomainEvent::WorkOrderDequeued {
+                investigation_id: msg.investigation_id,
+                dequeued: WorkOrderDequeued {
+                    work_order_id,
+                    consumer: consumer_name.clone(),
+                    stream: stream_name.clone(),
+                    entry_id: entry_id.clone(),
+                    delivery: msg.delivery,
+                },
+            })
+            .await;
+
+        // A work order whose deadline passed while it waited is no longer
+        // worth a session.
+        if let Some(detail) = expired_on_dequeue(&msg, chrono::Utc::now()) {
+            span.in_scope(|| {
+                tracing::warn!(
+                    deadline = ?msg.deadline,
+                    "Skipping work order dequeued after its deadline"
+                )
+            });
+            record_failure_metric(&detail);
+            if let Err(e) = store
+                .update_work_order_status(work_order_id, &WorkOrderStatus::Failed, None, None)
+                .await
+            {
+                tracing::error!(error = %e, "Failed to update work order status to Failed");
+            }
+            if let Err(e) = store
+                .record_work_order_outcome(
+                    work_order_id,
+                    Some(&detail.error),
+                    None,
+                    Some(&detail),
+                    None,
+                )
+                .await
+            {
+                tracing::error!(error = %e, "Failed to record work order outcome");
+            }
+            publish_finished(&services.events, &msg, dequeued_at, 0, Some(&detail)).await;
+            if let Err(e) = queue.ack(&stream_name, &entry_id).await {
+                tracing::error!(error = %e, "Failed to ACK expired work order");
+            }
+            continue;
+        }
+
+        span.in_scope(|| {
+            tracing::info!(
+                consumer = %consumer_name,
+                objective = %msg.objective,
+                "Processing work order"
+            )
+        });
+
         metrics::gauge!("processor.pool.active").increment(1.0);
 
         // Start heartbeat task.
@@ -195,33 +404,86 @@ async fn processor_worker_loop(
             hb_cancel_rx,
         ));
 
-        // Update work order status to Processing.
-        if let Err(e) = store
-            .update_work_order_status(
-                work_order_id,
-                &WorkOrderStatus::Processing,
-                Some(&consumer_name),
-                None,
-            )
-            .await
-        {
-            tracing::error!(error = %e, "Failed to update work order status to Processing");
-        }
+        // The session runs with the prompts current at its start; a reload
+        // mid-session applies from the next work order.
+        let prompt_set = prompts.snapshot();
+        let prompt = processor_prompt(&prompt_set, msg.mode);
+        let prompt_hash = prompt.map(|p| p.hash.as_str());
+        let exploration = match msg.mode {
+            WorkOrderMode::Focused => None,
+            WorkOrderMode::Exploratory => {
+                if prompt_set.get(PROCESSOR_EXPLORATORY_PROMPT).is_none() {
+                    span.in_scope(|| {
+                        tracing::warn!(
+                            "No exploratory Processor prompt loaded, using the Processor prompt"
+                        )
+                    });
+                }
+                Some(services.exploration.clone())
+            }
+        };
+
+        // The investigation's fetch budget, shared with its other sessions.
+        let fetch_budget = match store.get_investigation(msg.investigation_id).await {
+            Ok(investigation) => investigation.fetch_budget,
+            Err(e) => {
+                span.in_scope(|| {
+                    tracing::warn!(error = %e, "Failed to load fetch budget, fetching without one")
+                });
+                None
+            }
+        };
 
-        // Create and run Processor session.
-        let session_result = match ProcessorSession::new(
-            &llm_config,
-            &retry_config,
-            &safety_limits,
-            Arc::clone(&graph),
-            embedding_client.clone(),
-            fetch_base_url.clone(),
-            system_prompt.clone(),
-            &tool_schemas,
-            tool_result_limits.clone(),
-            dedup_config.clone(),
-        ) {
+        // A monitor's claims carry the monitor as provenance, not its standing
+        // investigation.
+        let investigation_provenance = match msg.monitor_id {
+            Some(_) => None,
+            None => Some(msg.investigation_id),
+        };
+
+        // Create and run Processor session. Its span nests under the work order's.
+        let session = span.in_scope(|| {
+            ProcessorSession::new(
+                &llm,
+                &safety_limits,
+                services.clone(),
+                prompt.map(|p| p.text.clone()).unwrap_or_default(),
+                SessionScope {
+                    investigation_id: investigation_provenance,
+                    monitor_id: msg.monitor_id,
+                    correlation_id: msg.correlation_id.clone(),
+                    work_order_id: Some(msg.work_order_id),
+                    focus_entity_ids: msg.referenced_entities.clone(),
+                    actor: crate::audit::processor_actor(&consumer_name),
+                    entity_shortlist: EntityShortlist::new(
+                        Arc::clone(&queue),
+                        msg.investigation_id,
+                        &shortlist_config,
+                    ),
+                    fetch_budget: FetchBudgetTracker::new(
+                        Arc::clone(&queue),
+                        msg.investigation_id,
+                        fetch_budget,
+                    ),
+                    exploration,
+                    ..Default::default()
+                },
+                msg.effort,
+                msg.deadline,
+            )
+            .map(|session| {
+                session
+                    .for_work_order(msg.kind, msg.source_document_id)
+                    .with_expected_outputs(msg.expected_outputs.clone())
+            })
+        });
+        let session_result = match session {
             Ok(session) => {
+                metrics::histogram!(
+                    "work_orders.dequeue_to_start_seconds",
+                    "priority" => priority.as_str()
+                )
+                .record(dequeued_at.elapsed().as_secs_f64());
                 session
                     .run(
                         &msg.objective,
@@ -231,13 +493,36 @@ async fn processor_worker_loop(
                     .await
             }
             Err(e) => {
-                tracing::error!(error = %e, "Failed to create Processor session");
+                span.in_scope(|| tracing::error!(error = %e, "Failed to create Processor session"));
                 if let Err(e2) = store
                     .update_work_order_status(work_order_id, &WorkOrderStatus::Failed, None, None)
                     .await
                 {
                     tracing::error!(error = %e2, "Failed to update work order status to Failed");
                 }
+                let reason = format!("Failed to create Processor session: {}", e);
+                let detail = FailureDetail {
+                    category: FailureCategory::LlmError,
+                    error: sanitize_error(&reason),
+                    last_tool: None,
+                    last_tool_error: None,
+                    tool_errors: Default::default(),
+                    graph_writes: false,
+                };
+                record_failure_metric(&detail);
+                if let Err(e2) = store
+                    .record_work_order_outcome(
+                        work_order_id,
+                        Some(&reason),
+                        None,
+                        Some(&detail),
+                        prompt_hash,
+                    )
+                    .await
+                {
+                    tracing::error!(error = %e2, "Failed to record work order outcome");
+                }
+                publish_finished(&services.events, &msg, dequeued_at, 0, Some(&detail)).await;
 
                 let _ = hb_cancel_tx.send(());
                 let _ = hb_handle.await;
@@ -253,18 +538,35 @@ async fn processor_worker_loop(
         let _ = hb_handle.await;
 
         // Determine final status and claims count.
-        // MaxTurnsReached and MalformedToolCallLimit are treated as Completed — partial
-        // progress (entities, claims written to the graph) is still valid and non-transactional.
-        // Only actual errors (Failed) are terminal failures.
+        // MaxTurnsReached, TimeLimitReached, MalformedToolCallLimit, ToolLoopLimit and
+        // ContextLimitReached are treated as Completed when anything reached the graph —
+        // partial progress is still valid and non-transactional.
+        // Actual errors (Failed) and limits hit without any graph writes are failures.
         let claims_count = session_result.claims_created as i32;
-        let final_status = match &session_result.outcome {
-            crate::llm::session::SessionResult::Completed { .. }
-            | crate::llm::session::SessionResult::MaxTurnsReached { .. }
-            | crate::llm::session::SessionResult::MalformedToolCallLimit { .. } => {
-                WorkOrderStatus::Completed
+        let final_status = match &session_result.failure {
+            None => WorkOrderStatus::Completed,
+            Some(detail) => {
+                span.in_scope(|| {
+                    tracing::warn!(
+                        category = detail.category.as_str(),
+                        last_tool = detail.last_tool.as_deref(),
+                        error = %detail.error,
+                        "Processor session failed"
+                    )
+                });
+                record_failure_metric(detail);
+                WorkOrderStatus::Failed
+            }
+        };
+        // A completed session is held to the work order's expected outputs;
+        // a failed one is already flagged.
+        let unmet = match (&session_result.failure, &msg.expected_outputs) {
+            (None, Some(expected)) => {
+                Some(contract::evaluate(expected, &session_result.deliverables))
             }
-            _ => WorkOrderStatus::Failed,
+            _ => None,
         };
+        let (failure_reason, summary) = session_outcome(&session_result, unmet.as_deref());
 
         // Update work order status in PG.
         if let Err(e) = store
@@ -273,24 +575,202 @@ async fn processor_worker_loop(
         {
             tracing::error!(error = %e, "Failed to update work order final status");
         }
+        if let Err(e) = store
+            .record_work_order_outcome(
+                work_order_id,
+                failure_reason.as_deref(),
+                Some(&summary),
+                session_result.failure.as_ref(),
+                prompt_hash,
+            )
+            .await
+        {
+            tracing::error!(error = %e, "Failed to record work order outcome");
+        }
+        if let Some(unmet) = unmet.filter(|u| !u.is_empty()) {
+            span.in_scope(
+                || tracing::warn!(unmet = ?unmet, "Processor session missed its expected outputs"),
+            );
+            metrics::counter!("work_orders.contract_unmet").increment(1);
+            if let Err(e) = store.mark_work_order_contract_unmet(work_order_id).await {
+                tracing::error!(error = %e, "Failed to mark work order contract unmet");
+            }
+        }
+        if let Err(e) = store
+            .record_work_order_footprint(work_order_id, &session_result.footprint)
+            .await
+        {
+            tracing::error!(error = %e, "Failed to record work order footprint");
+        }
+        if let Err(e) = store
+            .record_work_order_session_stats(work_order_id, &session_telemetry(&session_result))
+            .await
+        {
+            tracing::error!(error = %e, "Failed to record work order session stats");
+        }
+        publish_finished(
+            &services.events,
+            &msg,
+            dequeued_at,
+            session_result.claims_created.into(),
+            session_result.failure.as_ref(),
+        )
+        .await;
 
         // ACK the message in Redis.
         if let Err(e) = queue.ack(&stream_name, &entry_id).await {
             tracing::error!(error = %e, "Failed to ACK work order message");
         }
 
-        tracing::info!(
-            consumer = %consumer_name,
-            work_order_id = %work_order_id,
-            status = ?final_status,
-            claims = claims_count,
-            "Work order processing complete"
-        );
+        record_completion_metrics(&store, work_order_id, &final_status).await;
+
+        span.in_scope(|| {
+            tracing::info!(
+                consumer = %consumer_name,
+                status = ?final_status,
+                claims = claims_count,
+                "Work order processing complete"
+            )
+        });
 
         metrics::gauge!("processor.pool.active").decrement(1.0);
     }
 }
 
+/// Record queue wait (created → started), processing time (started → completed)
+/// and end-to-end latency (created → completed) from the stored timestamps.
+/// Orders delivered after a delay count from `not_before` rather than creation.
+async fn record_completion_metrics(
+    store: &StoreClient,
+    work_order_id: WorkOrderId,
+    status: &WorkOrderStatus,
+) {
+    let wo = match store.get_work_order(work_order_id).await {
+        Ok(wo) => wo,
+        Err(e) => {
+            tracing::warn!(work_order_id = %work_order_id, error = %e, "Failed to load work order for metrics");
+            return;
+        }
+    };
+    let (Some(started_at), Some(completed_at)) = (wo.started_at, wo.completed_at) else {
+        return;
+    };
+    let available_at = wo
+        .not_before
+        .map_or(wo.created_at, |nb| nb.max(wo.created_at));
+    let seconds = |d: chrono::TimeDelta| d.num_milliseconds().max(0) as f64 / 1000.0;
+
+    let priority = wo.priority.as_str();
+    let status = status.as_db_str();
+    metrics::histogram!("work_orders.queue_wait_seconds", "priority" => priority)
+        .record(seconds(started_at - available_at));
+    metrics::histogram!(
+        "work_orders.processing_seconds",
+        "priority" => priority,
+        "status" => status
+    )
+    .record(seconds(completed_at - started_at));
+    metrics::histogram!(
+        "work_orders.latency_seconds",
+        "priority" => priority,
+        "status" => status
+    )
+    .record(seconds(completed_at - available_at));
+}
+
+/// Failure detail for a work order dequeued after its deadline, or None if it
+/// should run.
+fn expired_on_dequeue(msg: &WorkOrderMessage, now: DateTime<Utc>) -> Option<FailureDetail> {
+    let deadline = msg.deadline.filter(|d| *d <= now)?;
+    Some(FailureDetail {
+        category: FailureCategory::DeadlineExpired,
+        error: format!(
+            "Deadline {} passed before a Processor picked the work order up",
+            deadline.to_rfc3339()
+        ),
+        last_tool: None,
+        last_tool_error: None,
+        tool_errors: Default::default(),
+        graph_writes: false,
+    })
+}
+
+/// Publish a work order's completion or failure, timed from its dequeue.
+async fn publish_finished(
+    events: &EventBus,
+    msg: &WorkOrderMessage,
+    dequeued_at: std::time::Instant,
+    claims_created: u64,
+    failure: Option<&FailureDetail>,
+) {
+    let finished = WorkOrderFinished {
+        work_order_id: msg.work_order_id,
+        duration_ms: dequeued_at.elapsed().as_millis() as u64,
+        claims_created,
+        failure_category: failure.map(|f| f.category),
+    };
+    events
+        .publish(DomainEvent::WorkOrderFinished {
+            investigation_id: msg.investigation_id,
+            finished,
+        })
+        .await;
+}
+
+/// Count a failed work order by failure category.
+fn record_failure_metric(detail: &FailureDetail) {
+    metrics::counter!("work_orders.failures", "category" => detail.category.as_str()).increment(1);
+}
+
+/// Failure reason (for failed sessions) and stored summary of a Processor
+/// session. `unmet` is the outcome of the work order's expected outputs
+/// check, when it has any.
+fn session_outcome(
+    result: &ProcessorSessionResult,
+    unmet: Option<&[UnmetOutput]>,
+) -> (Option<String>, Value) {
+    let stats = result.outcome.stats();
+    let final_text = match &result.outcome {
+        SessionResult::Completed { final_text, .. } => Some(final_text.clone()),
+        _ => None,
+    };
+    let failure_reason = result.failure.as_ref().map(|f| f.error.clone());
+
+    let mut summary = json!({
+        "outcome": result.outcome.kind(),
+        "final_text": final_text,
+        "turns": stats.turns,
+        "tool_calls": stats.tool_calls,
+        "entities_created": result.entities_created,
+        "claims_created": result.claims_created,
+        "relationships_created": result.relationships_created,
+    });
+    if stats.fallback_turns > 0 {
+        summary["fallback_turns"] = json!(stats.fallback_turns);
+    }
+    if let Some(unmet) = unmet {
+        summary["contract"] = json!({
+            "met": unmet.is_empty(),
+            "unmet": unmet,
+        });
+    }
+
+    (failure_reason, summary)
+}
+
+/// The session's statistics as kept for telemetry rollups.
+fn session_telemetry(result: &ProcessorSessionResult) -> SessionTelemetry {
+    let stats = result.outcome.stats();
+    SessionTelemetry {
+        outcome: result.outcome.kind().to_string(),
+        turns: stats.turns,
+        tool_calls: stats.tool_calls,
+        tool_calls_by_name: stats.tool_calls_by_name.clone(),
+        input_tokens: stats.total_input_tokens,
+        output_tokens: stats.total_output_tokens,
+    }
+}
+
 /// Independent heartbeat task — runs until cancelled.
 async fn heartbeat_task(
     queue: Arc<QueueClient>,
@@ -322,3 +802,109 @@ async fn heartbeat_task(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::llm::session::SessionStats;
+
+    use super::super::diagnostics::{failure_detail, ToolCallLog};
+
+    fn result(outcome: SessionResult) -> ProcessorSessionResult {
+        let failure = failure_detail(&outcome, &ToolCallLog::default(), true);
+        ProcessorSessionResult {
+            outcome,
+            entities_created: 2,
+            claims_created: 5,
+            relationships_created: 1,
+            footprint: Default::default(),
+            failure,
+            deliverables: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_session_outcome_completed() {
+        let stats = SessionStats {
+            turns: 7,
+            tool_calls: 12,
+            ..Default::default()
+        };
+        let (reason, summary) = session_outcome(
+            &result(SessionResult::Completed {
+                final_text: "Found the registry filing.".into(),
+                stats,
+            }),
+            None,
+        );
+        assert!(reason.is_none());
+        assert_eq!(summary["outcome"], "completed");
+        assert_eq!(summary["final_text"], "Found the registry filing.");
+        assert_eq!(summary["turns"], 7);
+        assert_eq!(summary["tool_calls"], 12);
+        assert_eq!(summary["claims_created"], 5);
+        assert!(summary.get("contract").is_none());
+    }
+
+    #[test]
+    fn test_session_outcome_records_contract() {
+        let completed = result(SessionResult::Completed {
+            final_text: "Nothing found.".into(),
+            stats: SessionStats::default(),
+        });
+        let unmet = [UnmetOutput::MinClaims {
+            expected: 8,
+            delivered: 5,
+        }];
+        let (_, summary) = session_outcome(&completed, Some(&unmet));
+        assert_eq!(
+            summary["contract"],
+            json!({
+                "met": false,
+                "unmet": [{"criterion": "min_claims", "expected": 8, "delivered": 5}],
+            })
+        );
+
+        let (_, summary) = session_outcome(&completed, Some(&[]));
+        assert_eq!(summary["contract"], json!({"met": true, "unmet": []}));
+    }
+
+    #[test]
+    fn test_expired_on_dequeue() {
+        use autosint_common::types::WorkOrder;
+        use autosint_common::InvestigationId;
+
+        let now = Utc::now();
+        let mut wo = WorkOrder::new(
+            InvestigationId::new(),
+            "Find the Crius' registered owner".into(),
+            WorkOrderPriority::High,
+        );
+        assert!(expired_on_dequeue(&WorkOrderMessage::from(&wo), now).is_none());
+
+        wo.deadline = Some(now + chrono::Duration::minutes(5));
+        assert!(expired_on_dequeue(&WorkOrderMessage::from(&wo), now).is_none());
+
+        wo.deadline = Some(now - chrono::Duration::minutes(5));
+        let detail = expired_on_dequeue(&WorkOrderMessage::from(&wo), now).unwrap();
+        assert_eq!(detail.category, FailureCategory::DeadlineExpired);
+        assert!(detail.error.starts_with("Deadline "), "{}", detail.error);
+        assert!(!detail.graph_writes);
+        assert!(detail.tool_errors.is_empty());
+    }
+
+    #[test]
+    fn test_session_outcome_failed_carries_reason() {
+        let (reason, summary) = session_outcome(
+            &result(SessionResult::Failed {
+                error: "LLM API error: 529 overloaded".into(),
+                stats: SessionStats::default(),
+            }),
+            None,
+        );
+        assert_eq!(reason.as_deref(), Some("LLM API error: 529 overloaded"));
+        assert_eq!(summary["outcome"], "failed");
+        assert!(summary["final_text"].is_null());
+    }
+}
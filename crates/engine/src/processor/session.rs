@@ -1,19 +1,28 @@
 use std::sync::atomic::Ordering;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use autosint_common::config::{
-    DedupConfig, LlmRoleConfig, RetryConfig, SafetyLimits, ToolResultLimits,
+use autosint_common::config::{ExplorationConfig, SafetyLimits, ToolResultLimits};
+use autosint_common::ids::{EntityId, SourceDocumentId};
+use autosint_common::types::{
+    ExpectedOutputs, FailureDetail, OutboundFootprint, SourceGuidance, WorkOrderEffort,
+    WorkOrderKind, WorkOrderMode,
 };
-use autosint_common::ids::EntityId;
-use autosint_common::types::SourceGuidance;
-use serde_json::Value;
+use chrono::{DateTime, Utc};
+use tracing::Instrument;
 
-use crate::embeddings::EmbeddingClient;
+use crate::config::{Prompt, PromptSet, PROCESSOR_EXPLORATORY_PROMPT, PROCESSOR_PROMPT};
 use crate::graph::GraphClient;
-use crate::llm::session::{run_session, SessionConfig, SessionResult};
-use crate::llm::LlmClient;
-use crate::tools::handlers::register_processor_tools;
-use crate::tools::{SessionCounters, ToolHandlerContext, ToolRegistry};
+use crate::llm::loop_guard::LoopLimits;
+use crate::llm::session::{run_session, LiveSessionStats, SessionConfig, SessionResult};
+use crate::llm::{LlmCaller, LlmFactory};
+use crate::queue::shortlist::EntityShortlist;
+use crate::tools::handlers::{register_processor_tools, ENRICHMENT_TOOLS};
+use crate::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+use super::contract::Deliverables;
+use super::diagnostics::{self, ToolCallLog};
+use super::graph_context::{format_graph_context, load_graph_context};
 
 /// Result of a Processor session, wrapping the generic session result
 /// with domain-specific counters.
@@ -22,83 +31,286 @@ pub struct ProcessorSessionResult {
     pub entities_created: u32,
     pub claims_created: u32,
     pub relationships_created: u32,
+    /// Fetches and searches the session made.
+    pub footprint: OutboundFootprint,
+    /// Diagnostics when the session failed; None when the work order completes.
+    pub failure: Option<FailureDetail>,
+    /// What the session delivered, for the work order's expected outputs.
+    pub deliverables: Deliverables,
+}
+
+/// Tools that look for new material. `IngestDocument` sessions extract from
+/// a stored document instead, so they don't get these.
+const RESEARCH_TOOLS: &[&str] = &[
+    "fetch_url",
+    "web_search",
+    "fetch_source_catalog",
+    "fetch_source_query",
+    "submit_external_enrichment",
+];
+
+/// Tools only `IngestDocument` sessions get.
+const INGEST_TOOLS: &[&str] = &["get_source_document"];
+
+/// Turn and wall-clock limits of one Processor session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionBudget {
+    pub max_turns: u32,
+    pub max_duration: Duration,
+}
+
+/// Share of the safety limit ceilings each effort level gets, as a fraction.
+fn effort_share(effort: WorkOrderEffort) -> (u64, u64) {
+    match effort {
+        WorkOrderEffort::Quick => (1, 5),
+        WorkOrderEffort::Standard => (3, 5),
+        WorkOrderEffort::Thorough => (1, 1),
+    }
+}
+
+/// Budget for a work order: the effort level's share of the session ceilings
+/// in `limits`, with the wall-clock limit further cut to the time left before
+/// `deadline`.
+pub fn session_budget(
+    limits: &SafetyLimits,
+    effort: WorkOrderEffort,
+    deadline: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> SessionBudget {
+    let (num, den) = effort_share(effort);
+    let share = |ceiling: u64| (ceiling * num).div_ceil(den).max(1);
+
+    let max_turns = share(limits.max_turns_per_processor_session as u64) as u32;
+    let mut seconds = share(limits.max_seconds_per_processor_session);
+    if let Some(deadline) = deadline {
+        let remaining = (deadline - now).num_seconds().max(1) as u64;
+        seconds = seconds.min(remaining);
+    }
+
+    SessionBudget {
+        max_turns,
+        max_duration: Duration::from_secs(seconds),
+    }
 }
 
 /// A Processor session — fetches URLs, extracts entities/claims/relationships,
 /// and writes them to the knowledge graph.
 pub struct ProcessorSession {
-    llm: Arc<LlmClient>,
+    llm: Arc<dyn LlmCaller>,
     system_prompt: String,
     tool_registry: ToolRegistry,
     session_config: SessionConfig,
+    effort: WorkOrderEffort,
+    deadline: Option<DateTime<Utc>>,
+    /// The stored document an `IngestDocument` session extracts from.
+    source_document_id: Option<SourceDocumentId>,
+    /// What the work order expects the session to deliver, quoted to the model.
+    expected_outputs: Option<ExpectedOutputs>,
+    /// An exploratory work order's limits, quoted to the model.
+    exploration: Option<ExplorationConfig>,
+    /// Span the session runs in, tagged with the investigation (or monitor) and correlation IDs.
+    span: tracing::Span,
+    /// Graph, shortlist and limits the first message's graph knowledge block
+    /// is loaded from and cut to.
+    graph: Arc<GraphClient>,
+    entity_shortlist: Option<EntityShortlist>,
+    tool_result_limits: ToolResultLimits,
 }
 
 impl ProcessorSession {
-    /// Create a new Processor session.
+    /// Create a new Processor session, its model built by `llm`.
     ///
-    /// `tool_schemas` should contain the loaded schemas from config (keyed as "processor/tool_name").
+    /// `services` supplies the clients and the tool schemas (keyed as
+    /// "processor/tool_name"); its store backs the entity locks that keep
+    /// graph writes safe against concurrent merges.
+    /// `scope` carries the work order's provenance and per-investigation state:
+    /// `investigation_id` is stamped onto everything the session writes to the
+    /// graph, `monitor_id` onto claims of a monitor's item instead,
+    /// `correlation_id` tags the session's span alongside it, and
+    /// `focus_entity_ids` (the work order's referenced entities) survive the
+    /// per-claim reference cap.
+    /// `effort` and `deadline` set the session's budget (see `session_budget`)
+    /// and are passed on to the model.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        llm_config: &LlmRoleConfig,
-        retry_config: &RetryConfig,
+        llm: &LlmFactory,
         safety_limits: &SafetyLimits,
-        graph: Arc<GraphClient>,
-        embedding_client: Option<Arc<EmbeddingClient>>,
-        fetch_base_url: String,
+        services: SharedServices,
         system_prompt: String,
-        tool_schemas: &std::collections::HashMap<String, Value>,
-        tool_result_limits: ToolResultLimits,
-        dedup_config: DedupConfig,
+        scope: SessionScope,
+        effort: WorkOrderEffort,
+        deadline: Option<DateTime<Utc>>,
     ) -> Result<Self, String> {
-        let llm = LlmClient::new(llm_config.clone(), retry_config.clone())
-            .ok_or_else(|| "Failed to create LLM client — API key not set".to_string())?;
+        let llm = llm()?;
 
-        let context = ToolHandlerContext {
-            graph,
-            embedding_client,
-            fetch_base_url,
-            http: reqwest::Client::new(),
-            tool_result_limits,
-            dedup_config,
-            session_counters: SessionCounters::default(),
-            store: None,
-            queue: None,
-            investigation_id: None,
-            investigation_cycle: None,
-            max_work_orders_per_cycle: None,
-        };
+        let live_stats = Arc::new(LiveSessionStats::default());
+        let SessionBudget {
+            max_turns,
+            max_duration,
+        } = session_budget(safety_limits, effort, deadline, Utc::now());
+        let max_result_tokens = services.tool_result_limits.max_result_tokens;
+
+        let span = tracing::info_span!(
+            "processor_session",
+            investigation_id = tracing::field::Empty,
+            monitor_id = tracing::field::Empty,
+            correlation_id = scope.correlation_id.as_deref(),
+        );
+        if let Some(id) = scope.investigation_id {
+            span.record("investigation_id", tracing::field::display(id));
+        }
+        if let Some(id) = scope.monitor_id {
+            span.record("monitor_id", tracing::field::display(id));
+        }
+
+        let tool_schemas = Arc::clone(&services.tool_schemas);
+        let graph = Arc::clone(&services.graph);
+        let tool_result_limits = services.tool_result_limits.clone();
+        let entity_shortlist = scope.entity_shortlist.clone();
+        let exploration = scope.exploration.clone();
+        let enrichment_enabled = services.enrichment.is_some();
+        let context = ToolHandlerContext::new(
+            services,
+            SessionScope {
+                live_stats: Arc::clone(&live_stats),
+                max_turns,
+                max_session_duration: Some(max_duration),
+                ..scope
+            },
+        );
 
         let mut tool_registry = ToolRegistry::new(context);
         register_processor_tools(&mut tool_registry);
-        tool_registry.load_definitions(tool_schemas, "processor")?;
+        tool_registry.load_definitions(&tool_schemas, "processor")?;
+        if !enrichment_enabled {
+            tool_registry.withhold_tools(ENRICHMENT_TOOLS);
+        }
 
         let session_config = SessionConfig {
-            max_turns: safety_limits.max_turns_per_processor_session,
+            max_turns,
             max_consecutive_malformed: safety_limits.max_consecutive_malformed_tool_calls,
+            max_duration: Some(max_duration),
+            live_stats: Some(live_stats),
+            max_tool_result_tokens: Some(max_result_tokens),
+            loop_limits: Some(LoopLimits {
+                max_repeats: safety_limits.max_repeated_tool_calls,
+                max_nudges: safety_limits.max_tool_loop_nudges,
+            }),
+            context_budget_fraction: Some(safety_limits.context_budget_fraction),
         };
 
         Ok(Self {
-            llm: Arc::new(llm),
+            llm,
             system_prompt,
             tool_registry,
             session_config,
+            effort,
+            deadline,
+            source_document_id: None,
+            expected_outputs: None,
+            exploration,
+            span,
+            graph,
+            entity_shortlist,
+            tool_result_limits,
         })
     }
 
+    /// Fit the session to the work order's kind: research and entity profiling
+    /// sessions get the fetch and search tools, `IngestDocument` sessions get
+    /// the stored document through `get_source_document` and the ingest prompt
+    /// instead.
+    pub fn for_work_order(
+        mut self,
+        kind: WorkOrderKind,
+        source_document_id: Option<SourceDocumentId>,
+    ) -> Self {
+        match kind {
+            WorkOrderKind::Research | WorkOrderKind::ProfileEntity => {
+                self.tool_registry.withhold_tools(INGEST_TOOLS)
+            }
+            WorkOrderKind::IngestDocument => {
+                self.tool_registry.withhold_tools(RESEARCH_TOOLS);
+                self.system_prompt = ingest_document_prompt(&self.system_prompt);
+                self.source_document_id = source_document_id;
+            }
+        }
+        self
+    }
+
+    /// Tell the model what the work order expects it to deliver.
+    pub fn with_expected_outputs(mut self, expected_outputs: Option<ExpectedOutputs>) -> Self {
+        self.expected_outputs = expected_outputs.filter(|e| !e.is_empty());
+        self
+    }
+
     /// Run the Processor session with the given work order details.
     pub async fn run(
         &self,
         objective: &str,
         referenced_entities: &[EntityId],
         source_guidance: Option<&SourceGuidance>,
+    ) -> ProcessorSessionResult {
+        self.run_inner(objective, referenced_entities, source_guidance)
+            .instrument(self.span.clone())
+            .await
+    }
+
+    async fn run_inner(
+        &self,
+        objective: &str,
+        referenced_entities: &[EntityId],
+        source_guidance: Option<&SourceGuidance>,
     ) -> ProcessorSessionResult {
         let start = std::time::Instant::now();
 
+        // Preload what the graph knows about the referenced entities.
+        let graph_context = if self.tool_result_limits.max_graph_context_chars > 0 {
+            let context = load_graph_context(
+                &self.graph,
+                referenced_entities,
+                self.entity_shortlist.as_ref(),
+                self.tool_result_limits.max_claim_preview_chars as usize,
+            )
+            .await;
+            tracing::debug!(
+                known = context.entities.len(),
+                unknown = context.unknown.len(),
+                shortlisted = context.shortlist.len(),
+                "Preloaded graph context"
+            );
+            format_graph_context(&context, &self.tool_result_limits)
+        } else {
+            None
+        };
+
         // Format the initial user message from the work order details.
-        let initial_message =
-            format_work_order_message(objective, referenced_entities, source_guidance);
+        let mut initial_message = format_work_order_message(
+            objective,
+            referenced_entities,
+            graph_context.as_deref(),
+            source_guidance,
+            self.effort,
+            self.deadline,
+            self.session_config.max_turns,
+            Utc::now(),
+        );
+        if let Some(id) = self.source_document_id {
+            initial_message.push_str(&format!(
+                "\n**Source document:** `{}` — read it with get_source_document.\n",
+                id
+            ));
+        }
+        if let Some(expected) = &self.expected_outputs {
+            initial_message.push_str(&format_expected_outputs(expected));
+        }
+        if let Some(exploration) = &self.exploration {
+            initial_message.push_str(&format_exploration(exploration));
+        }
 
-        let executor = self.tool_registry.as_executor();
+        let tool_log = Arc::new(Mutex::new(ToolCallLog::default()));
+        let executor =
+            diagnostics::record_tool_calls(self.tool_registry.as_executor(), Arc::clone(&tool_log));
 
         let outcome = run_session(
             self.llm.as_ref(),
@@ -128,6 +340,29 @@ impl ProcessorSession {
             .relationships_created
             .load(Ordering::Relaxed);
 
+        let footprint = self.tool_registry.counters().footprint();
+        let deliverables = Deliverables {
+            claims_created,
+            entity_names: self
+                .tool_registry
+                .counters()
+                .entity_names
+                .lock()
+                .unwrap()
+                .clone(),
+            source_count: self
+                .tool_registry
+                .counters()
+                .claim_sources
+                .lock()
+                .unwrap()
+                .len() as u32,
+        };
+
+        let graph_writes = entities_created + claims_created + relationships_created > 0;
+        let failure =
+            diagnostics::failure_detail(&outcome, &tool_log.lock().unwrap(), graph_writes);
+
         // Record metrics.
         let duration = start.elapsed().as_secs_f64();
         metrics::histogram!("processor.session.duration").record(duration);
@@ -144,6 +379,8 @@ impl ProcessorSession {
             entities = entities_created,
             claims = claims_created,
             relationships = relationships_created,
+            fetches = footprint.fetches,
+            searches = footprint.searches,
             "Processor session completed"
         );
 
@@ -152,26 +389,95 @@ impl ProcessorSession {
             entities_created,
             claims_created,
             relationships_created,
+            footprint,
+            failure,
+            deliverables,
         }
     }
 }
 
+/// The Processor prompt a work order in `mode` runs: the breadth-first
+/// variant for exploratory work orders, when it is loaded.
+pub fn processor_prompt(prompts: &PromptSet, mode: WorkOrderMode) -> Option<&Prompt> {
+    let variant = match mode {
+        WorkOrderMode::Focused => None,
+        WorkOrderMode::Exploratory => prompts.get(PROCESSOR_EXPLORATORY_PROMPT),
+    };
+    variant.or_else(|| prompts.get(PROCESSOR_PROMPT))
+}
+
+/// Append the ingest directive to the Processor prompt for `IngestDocument`
+/// work orders.
+pub fn ingest_document_prompt(base_prompt: &str) -> String {
+    format!(
+        "{}\n\n---\n\n\
+        **This work order extracts from a stored source document, not the web.** Read it with \
+        `get_source_document`, paging with `next_offset` until you have read all of it. Phase 1 \
+        and Phase 2 do not apply: you cannot fetch or search, and the document is the only \
+        source. Extract everything in it as in Phase 3, citing the document's title and \
+        publisher as the source and passing the result's `citation` to batch_extract. The \
+        work order's referenced entity is the publication that sent it; link what you \
+        extract to it where the document reports on its behalf.",
+        base_prompt
+    )
+}
+
 /// Format the work order into the initial user message for the LLM.
+/// `graph_context` is the preloaded graph knowledge block, if any.
+/// `max_turns` is the session's budget, quoted alongside non-standard effort.
+#[allow(clippy::too_many_arguments)]
 fn format_work_order_message(
     objective: &str,
     referenced_entities: &[EntityId],
+    graph_context: Option<&str>,
     source_guidance: Option<&SourceGuidance>,
+    effort: WorkOrderEffort,
+    deadline: Option<DateTime<Utc>>,
+    max_turns: u32,
+    now: DateTime<Utc>,
 ) -> String {
     let mut message = format!("## Work Order\n\n**Objective:** {}\n", objective);
 
+    if let Some(deadline) = deadline {
+        let minutes = (deadline - now).num_minutes().max(0);
+        message.push_str(&format!(
+            "\n**Deadline:** {} (in about {} minutes). Results after this are no longer \
+             useful — go to the most direct sources first and record findings as you go.\n",
+            deadline.format("%Y-%m-%d %H:%M UTC"),
+            minutes
+        ));
+    }
+
+    match effort {
+        WorkOrderEffort::Quick => message.push_str(&format!(
+            "\n**Effort:** quick — you have at most {} turns. Answer the objective from the \
+             most direct sources and stop once it is answered.\n",
+            max_turns
+        )),
+        WorkOrderEffort::Thorough => message.push_str(&format!(
+            "\n**Effort:** thorough — you have up to {} turns. Cover the objective \
+             exhaustively across independent sources.\n",
+            max_turns
+        )),
+        WorkOrderEffort::Standard => {}
+    }
+
     if !referenced_entities.is_empty() {
         message.push_str("\n**Referenced Entities (already in the knowledge graph):**\n");
         for entity_id in referenced_entities {
             message.push_str(&format!("- `{}`\n", entity_id));
         }
-        message.push_str(
-            "\nSearch for these entities to understand their current state before proceeding.\n",
-        );
+        match graph_context {
+            Some(_) => message.push_str(
+                "\nTheir current state is summarized under Current graph knowledge below.\n",
+            ),
+            None => message.push_str(
+                "\nSearch for these entities to understand their current state before proceeding.\n",
+            ),
+        }
+    }
+    if let Some(block) = graph_context {
+        message.push_str(block);
     }
 
     if let Some(guidance) = source_guidance {
@@ -194,3 +500,229 @@ fn format_work_order_message(
 
     message
 }
+
+/// The work order's expected outputs, appended to the initial message.
+fn format_expected_outputs(expected: &ExpectedOutputs) -> String {
+    let mut message = String::from("\n**Expected Outputs:**\n");
+    if let Some(min) = expected.min_claims {
+        message.push_str(&format!("- At least {} claims\n", min));
+    }
+    if !expected.require_entities.is_empty() {
+        message.push_str(&format!(
+            "- Entities created or found in the graph: {}\n",
+            expected.require_entities.join(", ")
+        ));
+    }
+    if let Some(min) = expected.require_source_count {
+        message.push_str(&format!(
+            "- Claims from at least {} distinct sources\n",
+            min
+        ));
+    }
+    message.push_str(
+        "\nA session that ends without these is flagged for the Analyst. Record what you find \
+         even if you cannot meet them all.\n",
+    );
+    message
+}
+
+/// An exploratory work order's limits, appended to the initial message.
+fn format_exploration(exploration: &ExplorationConfig) -> String {
+    format!(
+        "\n**Mode:** exploratory — survey breadth-first. Fetched pages are cut to {} \
+         characters, and calls past {} against any one site or fetch source are refused: \
+         spread your fetches across many sources.\n",
+        exploration.max_fetch_chars, exploration.max_calls_per_source
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> SafetyLimits {
+        SafetyLimits {
+            max_cycles_per_investigation: 10,
+            max_turns_per_analyst_session: 50,
+            max_turns_per_processor_session: 50,
+            max_seconds_per_analyst_session: 1800,
+            max_seconds_per_processor_session: 900,
+            max_work_orders_per_cycle: 20,
+            heartbeat_ttl_seconds: 60,
+            consecutive_all_fail_limit: 2,
+            max_consecutive_malformed_tool_calls: 3,
+            max_repeated_tool_calls: 3,
+            max_tool_loop_nudges: 3,
+            context_budget_fraction: 0.85,
+        }
+    }
+
+    fn budget(turns: u32, seconds: u64) -> SessionBudget {
+        SessionBudget {
+            max_turns: turns,
+            max_duration: Duration::from_secs(seconds),
+        }
+    }
+
+    #[test]
+    fn test_session_budget_scales_with_effort() {
+        let now = Utc::now();
+        let limits = limits();
+        assert_eq!(
+            session_budget(&limits, WorkOrderEffort::Quick, None, now),
+            budget(10, 180)
+        );
+        assert_eq!(
+            session_budget(&limits, WorkOrderEffort::Standard, None, now),
+            budget(30, 540)
+        );
+        // Thorough gets the full ceilings and never more.
+        assert_eq!(
+            session_budget(&limits, WorkOrderEffort::Thorough, None, now),
+            budget(50, 900)
+        );
+
+        // Tiny ceilings still leave one turn.
+        let tiny = SafetyLimits {
+            max_turns_per_processor_session: 1,
+            max_seconds_per_processor_session: 1,
+            ..limits
+        };
+        assert_eq!(
+            session_budget(&tiny, WorkOrderEffort::Quick, None, now),
+            budget(1, 1)
+        );
+    }
+
+    #[test]
+    fn test_session_budget_cut_to_deadline() {
+        let now = Utc::now();
+        let limits = limits();
+        let soon = Some(now + chrono::Duration::seconds(120));
+        assert_eq!(
+            session_budget(&limits, WorkOrderEffort::Thorough, soon, now),
+            budget(50, 120)
+        );
+        // A distant deadline leaves the effort budget alone.
+        let later = Some(now + chrono::Duration::hours(6));
+        assert_eq!(
+            session_budget(&limits, WorkOrderEffort::Quick, later, now),
+            budget(10, 180)
+        );
+    }
+
+    #[test]
+    fn test_message_includes_deadline_and_effort() {
+        let now = "2026-10-16T14:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let deadline = "2026-10-16T17:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let message = format_work_order_message(
+            "Find the Crius' registered owner",
+            &[],
+            None,
+            None,
+            WorkOrderEffort::Quick,
+            Some(deadline),
+            10,
+            now,
+        );
+        assert!(
+            message.contains("**Deadline:** 2026-10-16 17:00 UTC (in about 150 minutes)"),
+            "{}",
+            message
+        );
+        assert!(message.contains("**Effort:** quick — you have at most 10 turns"));
+
+        let message = format_work_order_message(
+            "Find the Crius' registered owner",
+            &[],
+            None,
+            None,
+            WorkOrderEffort::Standard,
+            None,
+            30,
+            now,
+        );
+        assert!(!message.contains("**Deadline:**"));
+        assert!(!message.contains("**Effort:**"));
+    }
+
+    #[test]
+    fn test_message_embeds_graph_context() {
+        let now = Utc::now();
+        let referenced = [EntityId::new()];
+        let block = "\n## Current graph knowledge\n\n### Crius (vessel)\n";
+        let message = format_work_order_message(
+            "Find the Crius' registered owner",
+            &referenced,
+            Some(block),
+            None,
+            WorkOrderEffort::Standard,
+            None,
+            30,
+            now,
+        );
+        assert!(message.contains(block), "{}", message);
+        assert!(message.contains("summarized under Current graph knowledge"));
+        assert!(!message.contains("Search for these entities"));
+
+        let message = format_work_order_message(
+            "Find the Crius' registered owner",
+            &referenced,
+            None,
+            None,
+            WorkOrderEffort::Standard,
+            None,
+            30,
+            now,
+        );
+        assert!(message.contains("Search for these entities"));
+        assert!(!message.contains("## Current graph knowledge"));
+    }
+
+    #[test]
+    fn test_processor_prompt_by_mode() {
+        let prompts = PromptSet::from_texts([
+            (PROCESSOR_PROMPT, "Work in depth."),
+            (PROCESSOR_EXPLORATORY_PROMPT, "Skim broadly."),
+        ]);
+        let text = |prompts, mode| processor_prompt(prompts, mode).map(|p| p.text.as_str());
+        assert_eq!(
+            text(&prompts, WorkOrderMode::Focused),
+            Some("Work in depth.")
+        );
+        assert_eq!(
+            text(&prompts, WorkOrderMode::Exploratory),
+            Some("Skim broadly.")
+        );
+
+        // Without the variant, exploratory work orders run the Processor prompt.
+        let base_only = PromptSet::from_texts([(PROCESSOR_PROMPT, "Work in depth.")]);
+        assert_eq!(
+            text(&base_only, WorkOrderMode::Exploratory),
+            Some("Work in depth.")
+        );
+    }
+
+    #[test]
+    fn test_exploration_message() {
+        let message = format_exploration(&ExplorationConfig {
+            max_calls_per_source: 3,
+            max_fetch_chars: 8000,
+        });
+        assert!(message.contains("**Mode:** exploratory"), "{}", message);
+        assert!(message.contains("cut to 8000 characters"));
+        assert!(message.contains("calls past 3 against any one site"));
+    }
+
+    #[test]
+    fn test_expected_outputs_message() {
+        let message = format_expected_outputs(&ExpectedOutputs {
+            min_claims: Some(3),
+            require_entities: vec!["Crius".into(), "Oceanic Shipping Ltd".into()],
+            require_source_count: None,
+        });
+        assert!(message.contains("- At least 3 claims\n"), "{}", message);
+        assert!(message.contains("graph: Crius, Oceanic Shipping Ltd\n"));
+        assert!(!message.contains("distinct sources"));
+    }
+}
@@ -0,0 +1,286 @@
+//! Consumer group hygiene for the Processor pool.
+//!
+//! Every pool start registers its workers as consumers of the `processors`
+//! group on each priority stream, and Redis keeps a consumer until it is
+//! deleted. On startup the pool deletes consumers that are dead — no heartbeat
+//! key and idle for at least the reclaim threshold — first moving any entries
+//! they still hold to the starting workers, so those are retried right away
+//! instead of when some worker's reclaim timer next fires.
+//!
+//! Consumer names are `processor-{instance}-{index}`. The instance ID keeps
+//! replicas sharing the group apart; a replica's live workers refresh their
+//! heartbeat keys even when idle, so another replica never deletes them.
+
+use super::{QueueClient, QueueError, CONSUMER_GROUP, PRIORITY_STREAMS};
+
+/// Entries moved per XPENDING/XCLAIM round.
+const CLAIM_BATCH: usize = 100;
+
+/// Name of a Processor worker's consumer.
+pub fn consumer_name(instance_id: &str, index: u32) -> String {
+    format!("processor-{}-{}", instance_id, index)
+}
+
+/// A consumer of the Processor group on one stream (XINFO CONSUMERS).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsumerInfo {
+    pub name: String,
+    /// Entries delivered to the consumer and not yet acknowledged.
+    pub pending: u64,
+    /// Milliseconds since the consumer last read or claimed.
+    pub idle_ms: u64,
+}
+
+/// What a startup consumer cleanup did, across all priority streams.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConsumerCleanup {
+    /// Dead consumers deleted, as (stream, consumer).
+    pub deleted: Vec<(String, String)>,
+    /// Entries dead consumers held when the cleanup started.
+    pub orphaned_pending: u64,
+    /// Entries moved from dead consumers to the starting workers.
+    pub reclaimed: u64,
+}
+
+impl QueueClient {
+    /// Consumers of the Processor group on `stream`.
+    pub async fn list_consumers(&self, stream: &str) -> Result<Vec<ConsumerInfo>, QueueError> {
+        let mut conn = self.conn.clone();
+        let reply: redis::Value = redis::cmd("XINFO")
+            .arg("CONSUMERS")
+            .arg(stream)
+            .arg(CONSUMER_GROUP)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(parse_consumers(&reply))
+    }
+
+    /// Delete a consumer from the Processor group on `stream`. Its pending
+    /// entries are dropped with it, so move them first. Returns how many it
+    /// held.
+    pub async fn delete_consumer(&self, stream: &str, consumer: &str) -> Result<u64, QueueError> {
+        let mut conn = self.conn.clone();
+        redis::cmd("XGROUP")
+            .arg("DELCONSUMER")
+            .arg(stream)
+            .arg(CONSUMER_GROUP)
+            .arg(consumer)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+
+    /// Move every entry `from` holds on `stream` to `to`, spread round-robin.
+    /// Returns how many moved.
+    pub async fn transfer_pending(
+        &self,
+        stream: &str,
+        from: &str,
+        to: &[String],
+    ) -> Result<u64, QueueError> {
+        if to.is_empty() {
+            return Ok(0);
+        }
+        let mut conn = self.conn.clone();
+        let mut moved = 0u64;
+        let mut next = 0usize;
+        loop {
+            // XPENDING <stream> <group> - + <count> <consumer>
+            let pending: redis::Value = redis::cmd("XPENDING")
+                .arg(stream)
+                .arg(CONSUMER_GROUP)
+                .arg("-")
+                .arg("+")
+                .arg(CLAIM_BATCH)
+                .arg(from)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| QueueError::Command(e.to_string()))?;
+            let ids = super::extract_pending_ids(&pending);
+            if ids.is_empty() {
+                break;
+            }
+
+            let mut claimed_any = false;
+            for (target, ids) in round_robin(&ids, to.len(), next) {
+                // XCLAIM <stream> <group> <consumer> 0 <id>... JUSTID
+                let mut cmd = redis::cmd("XCLAIM");
+                cmd.arg(stream).arg(CONSUMER_GROUP).arg(&to[target]).arg(0);
+                for id in ids {
+                    cmd.arg(id);
+                }
+                cmd.arg("JUSTID");
+                let claimed: Vec<String> = cmd
+                    .query_async(&mut conn)
+                    .await
+                    .map_err(|e| QueueError::Command(e.to_string()))?;
+                claimed_any |= !claimed.is_empty();
+                moved += claimed.len() as u64;
+            }
+            next = (next + ids.len()) % to.len();
+
+            // Entries whose stream entry was trimmed can't be claimed and are
+            // dropped with the consumer.
+            if !claimed_any {
+                break;
+            }
+        }
+        Ok(moved)
+    }
+
+    /// Delete dead consumers from the Processor group, first moving their
+    /// pending entries to `workers` (the starting pool's consumers, which are
+    /// never touched). A consumer is dead if it has no heartbeat key and has
+    /// been idle for at least `min_idle_ms`. Records the `queue.consumers` and
+    /// `queue.consumers.orphaned_pending` gauges.
+    pub async fn clean_up_consumers(
+        &self,
+        workers: &[String],
+        min_idle_ms: u64,
+    ) -> Result<ConsumerCleanup, QueueError> {
+        let mut cleanup = ConsumerCleanup::default();
+        for stream in PRIORITY_STREAMS {
+            let consumers = self.list_consumers(stream).await?;
+            let mut remaining = consumers.len();
+            for consumer in consumers {
+                if workers.contains(&consumer.name)
+                    || consumer.idle_ms < min_idle_ms
+                    || self.check_heartbeat(&consumer.name).await?
+                {
+                    continue;
+                }
+
+                cleanup.orphaned_pending += consumer.pending;
+                if consumer.pending > 0 {
+                    cleanup.reclaimed += self
+                        .transfer_pending(stream, &consumer.name, workers)
+                        .await?;
+                }
+                self.delete_consumer(stream, &consumer.name).await?;
+                tracing::info!(
+                    stream = *stream,
+                    consumer = %consumer.name,
+                    pending = consumer.pending,
+                    "Deleted dead queue consumer"
+                );
+                cleanup.deleted.push((stream.to_string(), consumer.name));
+                remaining -= 1;
+            }
+            metrics::gauge!("queue.consumers", "stream" => *stream).set(remaining as f64);
+        }
+        metrics::gauge!("queue.consumers.orphaned_pending").set(cleanup.orphaned_pending as f64);
+        Ok(cleanup)
+    }
+}
+
+/// Split `ids` into batches for `targets` consumers, dealing them out one at
+/// a time starting with target `start`. Returns (target index, ids) pairs.
+fn round_robin(ids: &[String], targets: usize, start: usize) -> Vec<(usize, Vec<&str>)> {
+    let mut batches: Vec<Vec<&str>> = vec![Vec::new(); targets];
+    for (i, id) in ids.iter().enumerate() {
+        batches[(start + i) % targets].push(id);
+    }
+    batches
+        .into_iter()
+        .enumerate()
+        .filter(|(_, ids)| !ids.is_empty())
+        .collect()
+}
+
+/// XINFO CONSUMERS reply: one flat [field, value, ...] array (or map, over
+/// RESP3) per consumer.
+fn parse_consumers(reply: &redis::Value) -> Vec<ConsumerInfo> {
+    let redis::Value::Array(consumers) = reply else {
+        return Vec::new();
+    };
+    consumers
+        .iter()
+        .filter_map(|consumer| {
+            let fields: Vec<(&redis::Value, &redis::Value)> = match consumer {
+                redis::Value::Array(flat) => flat
+                    .chunks_exact(2)
+                    .map(|pair| (&pair[0], &pair[1]))
+                    .collect(),
+                redis::Value::Map(pairs) => pairs.iter().map(|(k, v)| (k, v)).collect(),
+                _ => return None,
+            };
+            let mut info = ConsumerInfo {
+                name: String::new(),
+                pending: 0,
+                idle_ms: 0,
+            };
+            for (key, value) in fields {
+                let key = match key {
+                    redis::Value::BulkString(b) => String::from_utf8_lossy(b),
+                    redis::Value::SimpleString(s) => s.as_str().into(),
+                    _ => continue,
+                };
+                match (key.as_ref(), value) {
+                    ("name", redis::Value::BulkString(b)) => {
+                        info.name = String::from_utf8_lossy(b).into_owned()
+                    }
+                    ("pending", redis::Value::Int(n)) => info.pending = (*n).max(0) as u64,
+                    ("idle", redis::Value::Int(n)) => info.idle_ms = (*n).max(0) as u64,
+                    _ => {}
+                }
+            }
+            (!info.name.is_empty()).then_some(info)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(s: &str) -> redis::Value {
+        redis::Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_parse_consumers() {
+        let reply = redis::Value::Array(vec![
+            redis::Value::Array(vec![
+                bulk("name"),
+                bulk("processor-a1-0"),
+                bulk("pending"),
+                redis::Value::Int(2),
+                bulk("idle"),
+                redis::Value::Int(90_000),
+                bulk("inactive"),
+                redis::Value::Int(90_000),
+            ]),
+            redis::Value::Map(vec![
+                (bulk("name"), bulk("processor-b2-1")),
+                (bulk("pending"), redis::Value::Int(0)),
+                (bulk("idle"), redis::Value::Int(5)),
+            ]),
+        ]);
+        assert_eq!(
+            parse_consumers(&reply),
+            [
+                ConsumerInfo {
+                    name: "processor-a1-0".into(),
+                    pending: 2,
+                    idle_ms: 90_000,
+                },
+                ConsumerInfo {
+                    name: "processor-b2-1".into(),
+                    pending: 0,
+                    idle_ms: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let ids: Vec<String> = ["1", "2", "3"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            round_robin(&ids, 2, 1),
+            [(0, vec!["2"]), (1, vec!["1", "3"])]
+        );
+        assert_eq!(round_robin(&ids, 4, 0).len(), 3);
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use autosint_common::ids::WorkOrderId;
+use autosint_common::types::{DomainEvent, WorkOrderEnqueued};
+
+use super::QueueClient;
+use crate::events::EventBus;
+use crate::maintenance::ReadOnlyMode;
+use crate::store::StoreClient;
+use crate::supervisor;
+
+/// How often the delayed set is checked for due work orders.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum work orders moved per Redis round trip.
+const BATCH_SIZE: usize = 100;
+
+/// Spawn the task that moves delayed work orders onto their priority streams
+/// once their `not_before` time has passed, marks them queued in Postgres and
+/// publishes their release to `events`. Paused while the engine is read-only.
+pub fn spawn_delayed_mover(
+    queue: Arc<QueueClient>,
+    store: Arc<StoreClient>,
+    events: EventBus,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    supervisor::spawn_supervised_restarting("delayed_work_orders", move || {
+        let queue = Arc::clone(&queue);
+        let store = Arc::clone(&store);
+        let events = events.clone();
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                poll_interval_ms = POLL_INTERVAL.as_millis() as u64,
+                "Delayed work order mover started"
+            );
+
+            loop {
+                tokio::time::sleep(POLL_INTERVAL).await;
+                if read_only.is_enabled() {
+                    continue;
+                }
+
+                // Drain everything that's due before sleeping again.
+                loop {
+                    let moved = match queue.promote_due(chrono::Utc::now(), BATCH_SIZE).await {
+                        Ok(moved) => moved,
+                        Err(e) => {
+                            tracing::error!(error = %e, "Failed to promote delayed work orders");
+                            break;
+                        }
+                    };
+                    if moved.is_empty() {
+                        break;
+                    }
+
+                    metrics::counter!("queue.delayed.promoted").increment(moved.len() as u64);
+                    for (stream, msg) in &moved {
+                        tracing::info!(
+                            work_order_id = %msg.work_order_id,
+                            stream = %stream,
+                            "Delayed work order released"
+                        );
+                    }
+
+                    // A failure here leaves the rows 'scheduled' until a Processor
+                    // picks them up and marks them processing.
+                    let ids: Vec<WorkOrderId> =
+                        moved.iter().map(|(_, msg)| msg.work_order_id).collect();
+                    if let Err(e) = store.mark_work_orders_queued(&ids).await {
+                        tracing::error!(error = %e, "Failed to mark released work orders queued");
+                    }
+                    for (stream, msg) in &moved {
+                        let released = WorkOrderEnqueued {
+                            work_order_id: msg.work_order_id,
+                            stream: stream.clone(),
+                            entry_id: None,
+                            delayed_until: None,
+                        };
+                        events
+                            .publish(DomainEvent::WorkOrderEnqueued {
+                                investigation_id: msg.investigation_id,
+                                enqueued: released,
+                            })
+                            .await;
+                    }
+
+                    if moved.len() < BATCH_SIZE {
+                        break;
+                    }
+                }
+
+                if let Ok(depth) = queue.delayed_len().await {
+                    metrics::gauge!("queue.delayed.depth").set(depth as f64);
+                }
+            }
+        }
+    })
+}
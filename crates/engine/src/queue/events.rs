@@ -0,0 +1,263 @@
+//! The durable event stream: every published domain event appended to one
+//! Redis stream, which each durable subscriber reads through its own consumer
+//! group. An entry stays pending in the group until the subscriber
+//! acknowledges it, so one handed to a subscriber that dies mid-event is
+//! handed out again; the group's last-delivered ID is the subscriber's
+//! offset, so a restarted subscriber carries on where it stopped.
+//!
+//! Entries are `data` → JSON [`EventEnvelope`].
+
+use autosint_common::types::EventEnvelope;
+
+use super::{QueueClient, QueueError};
+
+/// Stream holding published domain events.
+pub const EVENT_STREAM: &str = "events";
+
+/// Consumer group of the durable subscriber named `subscriber`.
+pub fn event_group(subscriber: &str) -> String {
+    format!("events:{}", subscriber)
+}
+
+/// An entry read from the event stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamedEvent {
+    pub entry_id: String,
+    /// None for an entry that no longer exists (trimmed while pending) or
+    /// doesn't decode.
+    pub envelope: Option<EventEnvelope>,
+}
+
+impl QueueClient {
+    /// Append `envelope` to the event stream, trimming it to about `max_len`
+    /// entries. Returns the entry ID.
+    pub async fn append_event(
+        &self,
+        envelope: &EventEnvelope,
+        max_len: u64,
+    ) -> Result<String, QueueError> {
+        let mut conn = self.conn.clone();
+        let data =
+            serde_json::to_string(envelope).map_err(|e| QueueError::Command(e.to_string()))?;
+        redis::cmd("XADD")
+            .arg(EVENT_STREAM)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(max_len)
+            .arg("*")
+            .arg("data")
+            .arg(&data)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+
+    /// Create `group` on the event stream, starting at events appended from
+    /// now on. A group that already exists keeps its offset.
+    pub async fn create_event_group(&self, group: &str) -> Result<(), QueueError> {
+        let mut conn = self.conn.clone();
+        let result: Result<String, redis::RedisError> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(EVENT_STREAM)
+            .arg(group)
+            .arg("$")
+            .arg("MKSTREAM")
+            .query_async(&mut conn)
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if e.to_string().contains("BUSYGROUP") => Ok(()),
+            Err(e) => Err(QueueError::Command(format!(
+                "Failed to create event consumer group {}: {}",
+                group, e
+            ))),
+        }
+    }
+
+    /// Move up to `count` entries of `group` that another consumer has held
+    /// for at least `min_idle_ms` over to `consumer`, so events held by a dead
+    /// replica are still delivered. Returns how many moved.
+    pub async fn claim_idle_events(
+        &self,
+        group: &str,
+        consumer: &str,
+        min_idle_ms: u64,
+        count: usize,
+    ) -> Result<usize, QueueError> {
+        let mut conn = self.conn.clone();
+        // XAUTOCLAIM replies [next cursor, [claimed IDs], [deleted IDs]].
+        let reply: redis::Value = redis::cmd("XAUTOCLAIM")
+            .arg(EVENT_STREAM)
+            .arg(group)
+            .arg(consumer)
+            .arg(min_idle_ms)
+            .arg("0-0")
+            .arg("COUNT")
+            .arg(count)
+            .arg("JUSTID")
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(match reply {
+            redis::Value::Array(parts) => match parts.get(1) {
+                Some(redis::Value::Array(ids)) => ids.len(),
+                _ => 0,
+            },
+            _ => 0,
+        })
+    }
+
+    /// Read up to `count` entries for `consumer` of `group`: with `pending`,
+    /// the entries already delivered to it and not yet acknowledged;
+    /// otherwise new entries, waiting up to `block_ms` for one.
+    pub async fn read_events(
+        &self,
+        group: &str,
+        consumer: &str,
+        pending: bool,
+        block_ms: Option<u64>,
+        count: usize,
+    ) -> Result<Vec<StreamedEvent>, QueueError> {
+        let mut conn = self.conn.clone();
+        let mut cmd = redis::cmd("XREADGROUP");
+        cmd.arg("GROUP").arg(group).arg(consumer);
+        if let Some(ms) = block_ms.filter(|_| !pending) {
+            cmd.arg("BLOCK").arg(ms);
+        }
+        cmd.arg("COUNT")
+            .arg(count)
+            .arg("STREAMS")
+            .arg(EVENT_STREAM)
+            .arg(if pending { "0" } else { ">" });
+
+        let reply: Option<redis::Value> = cmd
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(reply.map(|v| parse_event_entries(&v)).unwrap_or_default())
+    }
+
+    /// Acknowledge `entry_id` for `group`, so it is not delivered again.
+    pub async fn ack_event(&self, group: &str, entry_id: &str) -> Result<(), QueueError> {
+        let mut conn = self.conn.clone();
+        let _: i64 = redis::cmd("XACK")
+            .arg(EVENT_STREAM)
+            .arg(group)
+            .arg(entry_id)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Parse an XREADGROUP reply for the event stream.
+/// Redis returns: [[stream_name, [[entry_id, [field, value, ...] | nil], ...]]]
+fn parse_event_entries(value: &redis::Value) -> Vec<StreamedEvent> {
+    let redis::Value::Array(streams) = value else {
+        return Vec::new();
+    };
+    let mut events = Vec::new();
+    for stream in streams {
+        let redis::Value::Array(pair) = stream else {
+            continue;
+        };
+        let Some(redis::Value::Array(entries)) = pair.get(1) else {
+            continue;
+        };
+        for entry in entries {
+            let redis::Value::Array(entry) = entry else {
+                continue;
+            };
+            let Some(redis::Value::BulkString(id)) = entry.first() else {
+                continue;
+            };
+            let envelope = match entry.get(1) {
+                Some(redis::Value::Array(fields)) => decode_envelope(fields),
+                _ => None,
+            };
+            events.push(StreamedEvent {
+                entry_id: String::from_utf8_lossy(id).to_string(),
+                envelope,
+            });
+        }
+    }
+    events
+}
+
+/// The envelope in an entry's `data` field.
+fn decode_envelope(fields: &[redis::Value]) -> Option<EventEnvelope> {
+    let data = fields.chunks(2).find_map(|pair| match pair {
+        [redis::Value::BulkString(key), redis::Value::BulkString(value)] if key == b"data" => {
+            Some(value)
+        }
+        _ => None,
+    })?;
+    match serde_json::from_slice(data) {
+        Ok(envelope) => Some(envelope),
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to deserialize event from Redis stream");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autosint_common::ids::InvestigationId;
+    use autosint_common::types::{DomainEvent, InvestigationStatus, StatusChanged};
+
+    use super::*;
+
+    fn bulk(s: &str) -> redis::Value {
+        redis::Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_parse_event_entries() {
+        let envelope = EventEnvelope::new(DomainEvent::StatusChanged {
+            investigation_id: InvestigationId::new(),
+            change: StatusChanged {
+                from: InvestigationStatus::Pending,
+                to: InvestigationStatus::AnalystRunning,
+            },
+        });
+        let reply = redis::Value::Array(vec![redis::Value::Array(vec![
+            bulk(EVENT_STREAM),
+            redis::Value::Array(vec![
+                redis::Value::Array(vec![
+                    bulk("1-0"),
+                    redis::Value::Array(vec![
+                        bulk("data"),
+                        bulk(&serde_json::to_string(&envelope).unwrap()),
+                    ]),
+                ]),
+                // Trimmed while pending.
+                redis::Value::Array(vec![bulk("2-0"), redis::Value::Nil]),
+                redis::Value::Array(vec![
+                    bulk("3-0"),
+                    redis::Value::Array(vec![bulk("data"), bulk("{not json")]),
+                ]),
+            ]),
+        ])]);
+
+        assert_eq!(
+            parse_event_entries(&reply),
+            [
+                StreamedEvent {
+                    entry_id: "1-0".into(),
+                    envelope: Some(envelope),
+                },
+                StreamedEvent {
+                    entry_id: "2-0".into(),
+                    envelope: None,
+                },
+                StreamedEvent {
+                    entry_id: "3-0".into(),
+                    envelope: None,
+                },
+            ]
+        );
+        assert!(parse_event_entries(&redis::Value::Nil).is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! Investigation-scoped fetch budget: counts the fetches an investigation's
+//! Processors make and refuses those over its `FetchBudget`. Concurrent
+//! sessions of one investigation share the counts.
+//!
+//! Per investigation, `fetch_budget:{id}` is a hash of `total` and
+//! `domain:{domain}` → fetches reserved. Its TTL is refreshed on every
+//! reservation, so the counts outlive suspensions and operator resumes.
+
+use std::sync::{Arc, LazyLock};
+
+use autosint_common::ids::InvestigationId;
+use autosint_common::types::FetchBudget;
+
+use super::{QueueClient, QueueError};
+
+/// How long an idle investigation's counts are kept.
+const BUDGET_TTL_SECONDS: u64 = 7 * 24 * 3600;
+
+/// Reserves one fetch of a domain if both limits allow it. KEYS: budget hash.
+/// ARGV: ttl, domain, max total (0 = none), max per domain (0 = none).
+/// Returns 0 when reserved, 1 when the total is spent, 2 when the domain's is.
+static RESERVE_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local field = 'domain:' .. ARGV[2]
+        local total = tonumber(redis.call('HGET', KEYS[1], 'total') or '0')
+        local domain = tonumber(redis.call('HGET', KEYS[1], field) or '0')
+        local max_total, max_domain = tonumber(ARGV[3]), tonumber(ARGV[4])
+        if max_total > 0 and total >= max_total then return 1 end
+        if max_domain > 0 and domain >= max_domain then return 2 end
+        redis.call('HINCRBY', KEYS[1], 'total', 1)
+        redis.call('HINCRBY', KEYS[1], field, 1)
+        redis.call('EXPIRE', KEYS[1], ARGV[1])
+        return 0
+        "#,
+    )
+});
+
+/// Why a fetch was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetExhausted {
+    /// The investigation made `max_fetches` fetches.
+    Total { max_fetches: u32 },
+    /// The investigation made `max_fetches_per_domain` fetches of this domain.
+    Domain { max_fetches_per_domain: u32 },
+}
+
+fn budget_key(investigation_id: InvestigationId) -> String {
+    format!("fetch_budget:{}", investigation_id)
+}
+
+impl QueueClient {
+    /// Reserve a fetch of `domain` against an investigation's budget.
+    pub async fn fetch_budget_reserve(
+        &self,
+        investigation_id: InvestigationId,
+        domain: &str,
+        budget: &FetchBudget,
+    ) -> Result<Option<BudgetExhausted>, QueueError> {
+        let mut conn = self.conn.clone();
+        let code: i64 = RESERVE_SCRIPT
+            .key(budget_key(investigation_id))
+            .arg(BUDGET_TTL_SECONDS)
+            .arg(domain)
+            .arg(budget.max_fetches.unwrap_or(0))
+            .arg(budget.max_fetches_per_domain.unwrap_or(0))
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+
+        Ok(match code {
+            0 => None,
+            1 => Some(BudgetExhausted::Total {
+                max_fetches: budget.max_fetches.unwrap_or(0),
+            }),
+            _ => Some(BudgetExhausted::Domain {
+                max_fetches_per_domain: budget.max_fetches_per_domain.unwrap_or(0),
+            }),
+        })
+    }
+}
+
+/// One investigation's fetch budget, as tool handlers use it. Redis errors
+/// are logged and the fetch allowed: the budget limits footprint, it doesn't
+/// gate correctness.
+#[derive(Clone)]
+pub struct FetchBudgetTracker {
+    queue: Arc<QueueClient>,
+    investigation_id: InvestigationId,
+    budget: FetchBudget,
+}
+
+impl FetchBudgetTracker {
+    /// None when the investigation has no budget.
+    pub fn new(
+        queue: Arc<QueueClient>,
+        investigation_id: InvestigationId,
+        budget: Option<FetchBudget>,
+    ) -> Option<Self> {
+        budget.map(|budget| Self {
+            queue,
+            investigation_id,
+            budget,
+        })
+    }
+
+    /// Reserve a fetch of `domain`, or the tool error explaining the refusal.
+    pub async fn reserve(&self, domain: &str) -> Result<(), String> {
+        match self
+            .queue
+            .fetch_budget_reserve(self.investigation_id, domain, &self.budget)
+            .await
+        {
+            Ok(None) => Ok(()),
+            Ok(Some(exhausted)) => {
+                metrics::counter!("tools.fetch_budget.rejections").increment(1);
+                Err(exhausted_message(exhausted, domain))
+            }
+            Err(e) => {
+                tracing::warn!(domain, error = %e, "Fetch budget check failed, allowing fetch");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Tool error for a refused fetch, steering the model to what it can still do.
+pub fn exhausted_message(exhausted: BudgetExhausted, domain: &str) -> String {
+    match exhausted {
+        BudgetExhausted::Total { max_fetches } => format!(
+            "Fetch budget exhausted: this investigation may fetch at most {} pages and has \
+             used them all. No further fetches will succeed. Extract from the pages already \
+             fetched, use other tools, and finish the work order.",
+            max_fetches
+        ),
+        BudgetExhausted::Domain {
+            max_fetches_per_domain,
+        } => format!(
+            "Fetch budget exhausted for {}: this investigation may fetch at most {} pages \
+             from one domain. Fetch the most promising pages from other domains instead.",
+            domain, max_fetches_per_domain
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exhausted_message_names_limit() {
+        let total = exhausted_message(BudgetExhausted::Total { max_fetches: 40 }, "example.com");
+        assert!(total.starts_with("Fetch budget exhausted: "));
+        assert!(total.contains("at most 40 pages"));
+
+        let domain = exhausted_message(
+            BudgetExhausted::Domain {
+                max_fetches_per_domain: 5,
+            },
+            "registry.example.org",
+        );
+        assert!(domain.starts_with("Fetch budget exhausted for registry.example.org: "));
+        assert!(domain.contains("at most 5 pages from one domain"));
+
+        // Not mistaken for malformed arguments by the tool registry.
+        for message in [total, domain] {
+            assert!(!message.contains("expected") && !message.contains("invalid type"));
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Shared feature flag overrides (see `crate::flags`): the `engine:flags` hash
+//! maps a flag's name to "1" (on) or "0" (off). Flags without a field have no
+//! override.
+
+use std::collections::HashMap;
+
+use autosint_common::types::FeatureFlag;
+
+use super::{QueueClient, QueueError};
+
+/// Redis key of the shared flag overrides.
+pub const FLAGS_KEY: &str = "engine:flags";
+
+impl QueueClient {
+    /// Every flag override. Fields naming no known flag are skipped.
+    pub async fn flag_overrides(&self) -> Result<HashMap<FeatureFlag, bool>, QueueError> {
+        let mut conn = self.conn.clone();
+        let fields: HashMap<String, String> = redis::cmd("HGETALL")
+            .arg(FLAGS_KEY)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+
+        let mut overrides = HashMap::new();
+        for (name, value) in fields {
+            match name.parse::<FeatureFlag>() {
+                Ok(flag) => {
+                    overrides.insert(flag, value == "1");
+                }
+                Err(e) => tracing::warn!(error = %e, "Ignoring unknown flag override"),
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Override `flag` for every replica, or clear its override with None.
+    pub async fn set_flag_override(
+        &self,
+        flag: FeatureFlag,
+        enabled: Option<bool>,
+    ) -> Result<(), QueueError> {
+        let mut conn = self.conn.clone();
+        let cmd = match enabled {
+            Some(enabled) => redis::cmd("HSET")
+                .arg(FLAGS_KEY)
+                .arg(flag.as_str())
+                .arg(if enabled { "1" } else { "0" })
+                .clone(),
+            None => redis::cmd("HDEL").arg(FLAGS_KEY).arg(flag.as_str()).clone(),
+        };
+        cmd.query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+}
@@ -1,5 +1,21 @@
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Utc};
 use redis::aio::MultiplexedConnection;
 
+use autosint_common::api::engine::{QueueStats, StreamStats};
+use autosint_common::types::{WorkOrderEnqueued, WorkOrderMessage, WorkOrderPriority};
+
+pub mod consumers;
+pub mod delayed;
+pub mod events;
+pub mod fetch_budget;
+pub mod flags;
+pub mod ownership;
+pub mod read_only;
+pub mod shortlist;
+pub mod wait_metrics;
+
 /// Stream names for work order priority queues.
 pub const STREAM_HIGH: &str = "workorders:high";
 pub const STREAM_NORMAL: &str = "workorders:normal";
@@ -11,6 +27,65 @@ pub const CONSUMER_GROUP: &str = "processors";
 /// All priority streams in consumption order (high → normal → low).
 pub const PRIORITY_STREAMS: &[&str] = &[STREAM_HIGH, STREAM_NORMAL, STREAM_LOW];
 
+/// Sorted set holding work orders with a future `not_before`, scored by that
+/// time in epoch milliseconds. Members are JSON `{"stream": .., "data": ..}`.
+pub const DELAYED_SET: &str = "workorders:delayed";
+
+/// Moves due members of the delayed set onto their priority stream and removes
+/// them, in one atomic step. KEYS: delayed set, then the priority streams.
+/// ARGV: cutoff score, batch size. Returns `[stream, data, ...]` for each moved
+/// member; a member that can't be decoded or names an unknown stream is dropped
+/// and reported with an empty stream.
+static PROMOTE_DUE_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local streams = {}
+        for i = 2, #KEYS do streams[KEYS[i]] = true end
+        local due = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, ARGV[2])
+        local moved = {}
+        for _, member in ipairs(due) do
+            local ok, entry = pcall(cjson.decode, member)
+            if ok and type(entry) == 'table' and streams[entry.stream]
+                and type(entry.data) == 'string' then
+                redis.call('XADD', entry.stream, '*', 'data', entry.data)
+                table.insert(moved, entry.stream)
+                table.insert(moved, entry.data)
+            else
+                table.insert(moved, '')
+                table.insert(moved, member)
+            end
+            redis.call('ZREM', KEYS[1], member)
+        end
+        return moved
+        "#,
+    )
+});
+
+/// Where [`QueueClient::enqueue`] put a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Enqueued {
+    /// Added to a priority stream under this entry ID.
+    Stream(String),
+    /// Held in the delayed set until this time.
+    Delayed(DateTime<Utc>),
+}
+
+impl Enqueued {
+    /// The timeline event for `msg` enqueued on `priority`'s stream.
+    pub fn event(&self, msg: &WorkOrderMessage, priority: &WorkOrderPriority) -> WorkOrderEnqueued {
+        let (entry_id, delayed_until) = match self {
+            Self::Stream(entry_id) => (Some(entry_id.clone()), None),
+            Self::Delayed(until) => (None, Some(*until)),
+        };
+        WorkOrderEnqueued {
+            work_order_id: msg.work_order_id,
+            stream: priority.as_redis_stream().to_string(),
+            entry_id,
+            delayed_until,
+        }
+    }
+}
+
 /// Redis client for the work order queue.
 pub struct QueueClient {
     conn: MultiplexedConnection,
@@ -107,17 +182,37 @@ impl QueueClient {
         self.conn.clone()
     }
 
-    /// Enqueue a work order message to the appropriate priority stream.
-    /// Returns the Redis stream entry ID.
+    /// Enqueue a work order message to the appropriate priority stream. A
+    /// message whose `not_before` is in the future goes to the delayed set
+    /// instead, and is moved onto the stream by [`Self::promote_due`].
     pub async fn enqueue(
         &self,
         msg: &autosint_common::types::WorkOrderMessage,
         priority: &autosint_common::types::WorkOrderPriority,
-    ) -> Result<String, QueueError> {
+    ) -> Result<Enqueued, QueueError> {
         let mut conn = self.conn.clone();
         let stream = priority.as_redis_stream();
         let data = serde_json::to_string(msg).map_err(|e| QueueError::Command(e.to_string()))?;
 
+        if let Some(not_before) = msg.not_before.filter(|t| *t > Utc::now()) {
+            let member = serde_json::json!({ "stream": stream, "data": data }).to_string();
+            redis::cmd("ZADD")
+                .arg(DELAYED_SET)
+                .arg(not_before.timestamp_millis())
+                .arg(&member)
+                .query_async::<()>(&mut conn)
+                .await
+                .map_err(|e| QueueError::Command(e.to_string()))?;
+
+            tracing::debug!(
+                stream = stream,
+                not_before = %not_before,
+                work_order_id = %msg.work_order_id,
+                "Scheduled delayed work order"
+            );
+            return Ok(Enqueued::Delayed(not_before));
+        }
+
         let entry_id: String = redis::cmd("XADD")
             .arg(stream)
             .arg("*")
@@ -134,7 +229,102 @@ impl QueueClient {
             "Enqueued work order"
         );
 
-        Ok(entry_id)
+        Ok(Enqueued::Stream(entry_id))
+    }
+
+    /// Move up to `limit` delayed messages due at or before `now` onto their
+    /// priority streams. Returns `(stream, message)` for each one moved.
+    pub async fn promote_due(
+        &self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<(String, autosint_common::types::WorkOrderMessage)>, QueueError> {
+        let mut conn = self.conn.clone();
+
+        let mut invocation = PROMOTE_DUE_SCRIPT.key(DELAYED_SET);
+        for stream in PRIORITY_STREAMS {
+            invocation.key(*stream);
+        }
+        let flat: Vec<String> = invocation
+            .arg(now.timestamp_millis())
+            .arg(limit)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+
+        let mut moved = Vec::with_capacity(flat.len() / 2);
+        for pair in flat.chunks_exact(2) {
+            let (stream, data) = (&pair[0], &pair[1]);
+            if stream.is_empty() {
+                tracing::error!(member = %data, "Dropped malformed delayed work order");
+                continue;
+            }
+            match serde_json::from_str(data) {
+                Ok(msg) => moved.push((stream.clone(), msg)),
+                // Already on the stream; the Processor side logs it again on read.
+                Err(e) => tracing::error!(
+                    error = %e,
+                    data = %data,
+                    "Promoted delayed work order with an unreadable payload"
+                ),
+            }
+        }
+
+        Ok(moved)
+    }
+
+    /// Number of messages waiting in the delayed set.
+    pub async fn delayed_len(&self) -> Result<u64, QueueError> {
+        let mut conn = self.conn.clone();
+        redis::cmd("ZCARD")
+            .arg(DELAYED_SET)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+
+    /// Per-priority stream length and pending count plus the delayed-set size,
+    /// for GET /stats/queue.
+    pub async fn stats(&self) -> Result<QueueStats, QueueError> {
+        let mut conn = self.conn.clone();
+        let mut streams = Vec::with_capacity(WorkOrderPriority::ALL.len());
+
+        for priority in WorkOrderPriority::ALL {
+            let stream = priority.as_redis_stream();
+            let length: u64 = redis::cmd("XLEN")
+                .arg(stream)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| QueueError::Command(e.to_string()))?;
+
+            // Summary form: [count, min_id, max_id, [[consumer, count], ...]]
+            let summary: redis::Value = redis::cmd("XPENDING")
+                .arg(stream)
+                .arg(CONSUMER_GROUP)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| QueueError::Command(e.to_string()))?;
+            let pending = match summary {
+                redis::Value::Array(fields) => match fields.first() {
+                    Some(redis::Value::Int(n)) => (*n).max(0) as u64,
+                    _ => 0,
+                },
+                _ => 0,
+            };
+
+            streams.push(StreamStats {
+                priority,
+                length,
+                pending,
+            });
+        }
+
+        Ok(QueueStats {
+            streams,
+            delayed: self.delayed_len().await?,
+            // Wait ages live in Postgres; see StoreClient::queued_work_order_wait_stats.
+            waiting: Vec::new(),
+        })
     }
 
     /// Dequeue the next work order from any priority stream (high → normal → low).
@@ -0,0 +1,230 @@
+//! Investigation ownership: at most one task drives an investigation at a time.
+//!
+//! `investigation:{id}:owner` holds the owning task's token with a TTL. The
+//! owner renews it while it runs and deletes it when done; a crashed owner's
+//! key expires, freeing the investigation for startup recovery or an operator
+//! resume. Tokens are `{purpose}:{uuid}`, so callers can tell a driver from a
+//! force-fail in progress.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use autosint_common::ids::InvestigationId;
+
+use super::{QueueClient, QueueError};
+
+/// Token purpose of the task running `run_investigation`.
+pub const DRIVER: &str = "driver";
+/// Token purpose of an operator force-fail.
+pub const FORCE_FAIL: &str = "fail";
+
+/// Extends the TTL if the key still holds our token. KEYS: owner key.
+/// ARGV: token, TTL in milliseconds. Returns 1 if renewed.
+static RENEW_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        if redis.call('GET', KEYS[1]) == ARGV[1] then
+            return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+        end
+        return 0
+        "#,
+    )
+});
+
+/// Deletes the key if it still holds our token. KEYS: owner key. ARGV: token.
+/// Returns 1 if released.
+static RELEASE_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        if redis.call('GET', KEYS[1]) == ARGV[1] then
+            return redis.call('DEL', KEYS[1])
+        end
+        return 0
+        "#,
+    )
+});
+
+fn owner_key(id: InvestigationId) -> String {
+    format!("investigation:{}:owner", id)
+}
+
+/// Purpose part of an owner token.
+pub fn token_purpose(token: &str) -> &str {
+    token.split_once(':').map_or(token, |(purpose, _)| purpose)
+}
+
+impl QueueClient {
+    /// Take ownership of an investigation under `token` unless another task
+    /// holds it. Returns whether ownership was taken.
+    pub async fn acquire_investigation(
+        &self,
+        id: InvestigationId,
+        token: &str,
+        ttl: Duration,
+    ) -> Result<bool, QueueError> {
+        let mut conn = self.conn.clone();
+        let reply: Option<String> = redis::cmd("SET")
+            .arg(owner_key(id))
+            .arg(token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis() as u64)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(reply.is_some())
+    }
+
+    /// Extend ownership held under `token`. False if the key expired or
+    /// another task has taken it.
+    pub async fn renew_investigation(
+        &self,
+        id: InvestigationId,
+        token: &str,
+        ttl: Duration,
+    ) -> Result<bool, QueueError> {
+        let mut conn = self.conn.clone();
+        let renewed: i64 = RENEW_SCRIPT
+            .key(owner_key(id))
+            .arg(token)
+            .arg(ttl.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(renewed == 1)
+    }
+
+    /// Give up ownership held under `token`. Ownership another task has
+    /// taken since is left alone.
+    pub async fn release_investigation(
+        &self,
+        id: InvestigationId,
+        token: &str,
+    ) -> Result<bool, QueueError> {
+        let mut conn = self.conn.clone();
+        let released: i64 = RELEASE_SCRIPT
+            .key(owner_key(id))
+            .arg(token)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(released == 1)
+    }
+
+    /// Token of the task owning an investigation, if any.
+    pub async fn investigation_owner(
+        &self,
+        id: InvestigationId,
+    ) -> Result<Option<String>, QueueError> {
+        let mut conn = self.conn.clone();
+        redis::cmd("GET")
+            .arg(owner_key(id))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+}
+
+/// Ownership of an investigation, renewed in the background every third of
+/// its TTL until released or dropped. Once a renewal finds the key gone or
+/// taken, the lease is lost and stays lost; a failed renewal is retried at
+/// the next interval.
+pub struct InvestigationLease {
+    queue: Arc<QueueClient>,
+    id: InvestigationId,
+    token: String,
+    lost: Arc<AtomicBool>,
+    renewer: JoinHandle<()>,
+}
+
+impl InvestigationLease {
+    /// Take ownership of `id` for `purpose`. None if another task owns it.
+    pub async fn acquire(
+        queue: Arc<QueueClient>,
+        id: InvestigationId,
+        purpose: &str,
+        ttl: Duration,
+    ) -> Result<Option<Self>, QueueError> {
+        let token = format!("{}:{}", purpose, uuid::Uuid::new_v4());
+        if !queue.acquire_investigation(id, &token, ttl).await? {
+            return Ok(None);
+        }
+
+        let lost = Arc::new(AtomicBool::new(false));
+        let renewer = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            let token = token.clone();
+            let lost = Arc::clone(&lost);
+            async move {
+                let mut interval = tokio::time::interval(ttl / 3);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    match queue.renew_investigation(id, &token, ttl).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            tracing::warn!(investigation_id = %id, "Investigation ownership lost");
+                            metrics::counter!("investigations.ownership.lost").increment(1);
+                            lost.store(true, Ordering::SeqCst);
+                            return;
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                investigation_id = %id,
+                                error = %e,
+                                "Failed to renew investigation ownership"
+                            );
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Some(Self {
+            queue,
+            id,
+            token,
+            lost,
+            renewer,
+        }))
+    }
+
+    /// Whether another task may now own the investigation.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+
+    /// Stop renewing and give up ownership. Best effort: if the release
+    /// fails, the key expires after its TTL.
+    pub async fn release(self) {
+        self.renewer.abort();
+        if let Err(e) = self.queue.release_investigation(self.id, &self.token).await {
+            tracing::warn!(
+                investigation_id = %self.id,
+                error = %e,
+                "Failed to release investigation ownership"
+            );
+        }
+    }
+}
+
+impl Drop for InvestigationLease {
+    fn drop(&mut self) {
+        self.renewer.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_purpose() {
+        assert_eq!(token_purpose("driver:0b9e"), DRIVER);
+        assert_eq!(token_purpose("fail:0b9e"), FORCE_FAIL);
+        assert_eq!(token_purpose("legacy"), "legacy");
+    }
+}
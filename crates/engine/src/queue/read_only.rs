@@ -0,0 +1,33 @@
+//! The shared read-only toggle (see `crate::maintenance`): `engine:read_only`
+//! holds "1" while read-only mode is on, and is absent otherwise.
+
+use super::{QueueClient, QueueError};
+
+/// Redis key of the shared read-only toggle.
+pub const READ_ONLY_KEY: &str = "engine:read_only";
+
+impl QueueClient {
+    /// Whether the shared read-only toggle is on.
+    pub async fn read_only_toggle(&self) -> Result<bool, QueueError> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = redis::cmd("GET")
+            .arg(READ_ONLY_KEY)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        Ok(value.as_deref() == Some("1"))
+    }
+
+    /// Turn the shared read-only toggle on or off.
+    pub async fn set_read_only_toggle(&self, enabled: bool) -> Result<(), QueueError> {
+        let mut conn = self.conn.clone();
+        let cmd = if enabled {
+            redis::cmd("SET").arg(READ_ONLY_KEY).arg("1").clone()
+        } else {
+            redis::cmd("DEL").arg(READ_ONLY_KEY).clone()
+        };
+        cmd.query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+}
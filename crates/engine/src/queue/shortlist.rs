@@ -0,0 +1,515 @@
+//! Investigation-scoped entity shortlist: lowercased names and aliases the
+//! investigation has already resolved, mapped to entity IDs, so tools can skip
+//! repeated graph lookups.
+//!
+//! Per investigation, three keys hold the shortlist:
+//! - `shortlist:{id}:names` — hash of lowercased name → entity ID
+//! - `shortlist:{id}:entities` — hash of entity ID → JSON [`ShortlistEntry`]
+//! - `shortlist:{id}:refs` — sorted set of entity ID → times recorded or read
+//!
+//! Hash fields carry their own TTL (HEXPIRE), refreshed on every read and write.
+//! `shortlist:investigations` lists investigations with a shortlist so merges
+//! can rewrite every one of them.
+
+use std::sync::{Arc, LazyLock};
+
+use serde::{Deserialize, Serialize};
+
+use autosint_common::config::EntityShortlistConfig;
+use autosint_common::ids::{EntityId, InvestigationId};
+use autosint_common::types::Entity;
+
+use super::{QueueClient, QueueError};
+
+/// Set of investigation IDs that have shortlist keys.
+pub const SHORTLIST_REGISTRY: &str = "shortlist:investigations";
+
+/// Records entities and their names. KEYS: names, entities, refs, registry.
+/// ARGV: ttl, investigation ID, then per entity: ID, entry JSON, name count,
+/// names.
+static RECORD_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local ttl = ARGV[1]
+        redis.call('SADD', KEYS[4], ARGV[2])
+        local i = 3
+        while i <= #ARGV do
+            local id, entry, count = ARGV[i], ARGV[i + 1], tonumber(ARGV[i + 2])
+            redis.call('HSET', KEYS[2], id, entry)
+            redis.call('HEXPIRE', KEYS[2], ttl, 'FIELDS', 1, id)
+            for j = i + 3, i + 2 + count do
+                redis.call('HSET', KEYS[1], ARGV[j], id)
+                redis.call('HEXPIRE', KEYS[1], ttl, 'FIELDS', 1, ARGV[j])
+            end
+            redis.call('ZINCRBY', KEYS[3], 1, id)
+            i = i + 3 + count
+        end
+        for k = 1, 3 do redis.call('EXPIRE', KEYS[k], ttl) end
+        return 1
+        "#,
+    )
+});
+
+/// Looks up a name and refreshes its TTL. KEYS: names, entities, refs.
+/// ARGV: ttl, name. Returns the entry JSON, or nil on a miss.
+static LOOKUP_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local ttl = ARGV[1]
+        local id = redis.call('HGET', KEYS[1], ARGV[2])
+        if not id then return false end
+        local entry = redis.call('HGET', KEYS[2], id)
+        if not entry then
+            redis.call('HDEL', KEYS[1], ARGV[2])
+            return false
+        end
+        redis.call('HEXPIRE', KEYS[1], ttl, 'FIELDS', 1, ARGV[2])
+        redis.call('HEXPIRE', KEYS[2], ttl, 'FIELDS', 1, id)
+        redis.call('ZINCRBY', KEYS[3], 1, id)
+        for k = 1, 3 do redis.call('EXPIRE', KEYS[k], ttl) end
+        return entry
+        "#,
+    )
+});
+
+/// Points names of a merged-away entity at the merge target. KEYS: names,
+/// entities, refs. ARGV: ttl, source ID, target ID, target entry JSON.
+/// Returns the number of names rewritten, or -1 if the shortlist is gone.
+static MERGE_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        if redis.call('EXISTS', KEYS[1], KEYS[2]) == 0 then return -1 end
+        local ttl = ARGV[1]
+        local rewritten = 0
+        local names = redis.call('HGETALL', KEYS[1])
+        for i = 1, #names, 2 do
+            if names[i + 1] == ARGV[2] then
+                redis.call('HSET', KEYS[1], names[i], ARGV[3])
+                redis.call('HEXPIRE', KEYS[1], ttl, 'FIELDS', 1, names[i])
+                rewritten = rewritten + 1
+            end
+        end
+        local known = redis.call('HDEL', KEYS[2], ARGV[2])
+        if rewritten == 0 and known == 0 then return 0 end
+        redis.call('HSET', KEYS[2], ARGV[3], ARGV[4])
+        redis.call('HEXPIRE', KEYS[2], ttl, 'FIELDS', 1, ARGV[3])
+        local refs = redis.call('ZSCORE', KEYS[3], ARGV[2])
+        if refs then
+            redis.call('ZINCRBY', KEYS[3], refs, ARGV[3])
+            redis.call('ZREM', KEYS[3], ARGV[2])
+        end
+        return rewritten
+        "#,
+    )
+});
+
+/// Most-referenced live entries, dropping reference counts whose entry has
+/// expired. KEYS: entities, refs. ARGV: limit. Returns `[entry, refs, ...]`.
+static TOP_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local limit = tonumber(ARGV[1])
+        local ranked = redis.call('ZREVRANGE', KEYS[2], 0, -1, 'WITHSCORES')
+        local top = {}
+        for i = 1, #ranked, 2 do
+            if #top >= limit * 2 then break end
+            local entry = redis.call('HGET', KEYS[1], ranked[i])
+            if entry then
+                table.insert(top, entry)
+                table.insert(top, ranked[i + 1])
+            else
+                redis.call('ZREM', KEYS[2], ranked[i])
+            end
+        end
+        return top
+        "#,
+    )
+});
+
+/// An entity as the shortlist remembers it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortlistEntry {
+    pub entity_id: EntityId,
+    pub canonical_name: String,
+    pub kind: String,
+}
+
+impl ShortlistEntry {
+    pub fn from_entity(entity: &Entity) -> Self {
+        Self {
+            entity_id: entity.id,
+            canonical_name: entity.canonical_name.clone(),
+            kind: entity.kind.clone(),
+        }
+    }
+}
+
+/// Shortlist key for a name: trimmed and lowercased.
+pub fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn shortlist_keys(investigation_id: InvestigationId) -> [String; 3] {
+    [
+        format!("shortlist:{}:names", investigation_id),
+        format!("shortlist:{}:entities", investigation_id),
+        format!("shortlist:{}:refs", investigation_id),
+    ]
+}
+
+fn encode_entry(entry: &ShortlistEntry) -> Result<String, QueueError> {
+    serde_json::to_string(entry).map_err(|e| QueueError::Command(e.to_string()))
+}
+
+fn decode_entry(json: &str) -> Result<ShortlistEntry, QueueError> {
+    serde_json::from_str(json).map_err(|e| QueueError::Command(e.to_string()))
+}
+
+impl QueueClient {
+    /// Record entities in an investigation's shortlist under the given names
+    /// (normalized here; blank names are skipped).
+    pub async fn shortlist_record(
+        &self,
+        investigation_id: InvestigationId,
+        entries: &[(ShortlistEntry, Vec<String>)],
+        ttl_seconds: u64,
+    ) -> Result<(), QueueError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.clone();
+        let [names, entities, refs] = shortlist_keys(investigation_id);
+
+        let mut invocation = RECORD_SCRIPT.key(&names);
+        invocation
+            .key(&entities)
+            .key(&refs)
+            .key(SHORTLIST_REGISTRY)
+            .arg(ttl_seconds)
+            .arg(investigation_id.to_string());
+        for (entry, entry_names) in entries {
+            let mut normalized: Vec<String> = entry_names
+                .iter()
+                .map(|n| normalize_name(n))
+                .filter(|n| !n.is_empty())
+                .collect();
+            normalized.sort();
+            normalized.dedup();
+            invocation
+                .arg(entry.entity_id.to_string())
+                .arg(encode_entry(entry)?)
+                .arg(normalized.len())
+                .arg(normalized);
+        }
+
+        invocation
+            .invoke_async::<()>(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+
+    /// Look up a name in an investigation's shortlist, refreshing its TTL on a hit.
+    pub async fn shortlist_lookup(
+        &self,
+        investigation_id: InvestigationId,
+        name: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<ShortlistEntry>, QueueError> {
+        let name = normalize_name(name);
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let mut conn = self.conn.clone();
+        let [names, entities, refs] = shortlist_keys(investigation_id);
+
+        let entry: Option<String> = LOOKUP_SCRIPT
+            .key(&names)
+            .key(&entities)
+            .key(&refs)
+            .arg(ttl_seconds)
+            .arg(&name)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+
+        entry.as_deref().map(decode_entry).transpose()
+    }
+
+    /// Up to `limit` live entries of an investigation's shortlist with their
+    /// reference counts, most referenced first.
+    pub async fn shortlist_top(
+        &self,
+        investigation_id: InvestigationId,
+        limit: u32,
+    ) -> Result<Vec<(ShortlistEntry, u64)>, QueueError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.conn.clone();
+        let [_, entities, refs] = shortlist_keys(investigation_id);
+
+        let flat: Vec<String> = TOP_SCRIPT
+            .key(&entities)
+            .key(&refs)
+            .arg(limit)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+
+        flat.chunks_exact(2)
+            .map(|pair| {
+                let refs = pair[1].parse::<f64>().unwrap_or(0.0) as u64;
+                Ok((decode_entry(&pair[0])?, refs))
+            })
+            .collect()
+    }
+
+    /// After `source_id` was merged into `target`, point every shortlist name
+    /// that resolved to the source at the target instead. Merges are global,
+    /// so this rewrites the shortlists of all investigations. Returns the
+    /// number of names rewritten.
+    pub async fn shortlist_rewrite_merged(
+        &self,
+        source_id: EntityId,
+        target: &ShortlistEntry,
+        ttl_seconds: u64,
+    ) -> Result<u64, QueueError> {
+        let mut conn = self.conn.clone();
+        let investigations: Vec<String> = redis::cmd("SMEMBERS")
+            .arg(SHORTLIST_REGISTRY)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))?;
+        let target_json = encode_entry(target)?;
+
+        let mut rewritten = 0;
+        for investigation in investigations {
+            let Ok(uuid) = investigation.parse::<uuid::Uuid>() else {
+                continue;
+            };
+            let [names, entities, refs] = shortlist_keys(InvestigationId::from_uuid(uuid));
+            let count: i64 = MERGE_SCRIPT
+                .key(&names)
+                .key(&entities)
+                .key(&refs)
+                .arg(ttl_seconds)
+                .arg(source_id.to_string())
+                .arg(target.entity_id.to_string())
+                .arg(&target_json)
+                .invoke_async(&mut conn)
+                .await
+                .map_err(|e| QueueError::Command(e.to_string()))?;
+
+            if count < 0 {
+                // Every entry expired; forget the investigation.
+                redis::cmd("SREM")
+                    .arg(SHORTLIST_REGISTRY)
+                    .arg(&investigation)
+                    .query_async::<()>(&mut conn)
+                    .await
+                    .map_err(|e| QueueError::Command(e.to_string()))?;
+            } else {
+                rewritten += count as u64;
+            }
+        }
+
+        Ok(rewritten)
+    }
+
+    /// Delete an investigation's shortlist.
+    pub async fn shortlist_clear(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<(), QueueError> {
+        let mut conn = self.conn.clone();
+        let [names, entities, refs] = shortlist_keys(investigation_id);
+
+        redis::pipe()
+            .atomic()
+            .cmd("DEL")
+            .arg(&names)
+            .arg(&entities)
+            .arg(&refs)
+            .ignore()
+            .cmd("SREM")
+            .arg(SHORTLIST_REGISTRY)
+            .arg(investigation_id.to_string())
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await
+            .map_err(|e| QueueError::Command(e.to_string()))
+    }
+}
+
+/// One investigation's shortlist, as tool handlers use it. Redis errors are
+/// logged and treated as cache misses: the graph stays the source of truth.
+#[derive(Clone)]
+pub struct EntityShortlist {
+    queue: Arc<QueueClient>,
+    investigation_id: InvestigationId,
+    ttl_seconds: u64,
+    prompt_entities: u32,
+}
+
+impl EntityShortlist {
+    /// None when the shortlist is disabled.
+    pub fn new(
+        queue: Arc<QueueClient>,
+        investigation_id: InvestigationId,
+        config: &EntityShortlistConfig,
+    ) -> Option<Self> {
+        config.enabled.then_some(Self {
+            queue,
+            investigation_id,
+            ttl_seconds: config.ttl_seconds,
+            prompt_entities: config.prompt_entities,
+        })
+    }
+
+    /// The investigation's most-referenced entities, as many as prompts list.
+    pub async fn top(&self) -> Vec<ShortlistEntry> {
+        match self
+            .queue
+            .shortlist_top(self.investigation_id, self.prompt_entities)
+            .await
+        {
+            Ok(entries) => entries.into_iter().map(|(entry, _)| entry).collect(),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read entity shortlist");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Shortlisted entity for `name`. With `kind`, only an entity of that kind
+    /// (compared case-insensitively) counts as a hit.
+    pub async fn lookup(&self, name: &str, kind: Option<&str>) -> Option<ShortlistEntry> {
+        match self
+            .queue
+            .shortlist_lookup(self.investigation_id, name, self.ttl_seconds)
+            .await
+        {
+            Ok(Some(entry)) if kind.is_none_or(|k| entry.kind.eq_ignore_ascii_case(k)) => {
+                metrics::counter!("entity_shortlist.hits").increment(1);
+                Some(entry)
+            }
+            Ok(_) => {
+                metrics::counter!("entity_shortlist.misses").increment(1);
+                None
+            }
+            Err(e) => {
+                tracing::warn!(name, error = %e, "Entity shortlist lookup failed");
+                None
+            }
+        }
+    }
+
+    /// Record an entity under its canonical name and aliases, plus any
+    /// `extra_names` that resolved to it.
+    pub async fn record(&self, entity: &Entity, extra_names: &[&str]) {
+        let names = entity_names(entity, extra_names);
+        self.record_many(vec![(ShortlistEntry::from_entity(entity), names)])
+            .await;
+    }
+
+    /// Record several entities under their canonical names and aliases.
+    pub async fn record_entities<'a>(&self, entities: impl IntoIterator<Item = &'a Entity>) {
+        let entries = entities
+            .into_iter()
+            .map(|e| (ShortlistEntry::from_entity(e), entity_names(e, &[])))
+            .collect();
+        self.record_many(entries).await;
+    }
+
+    async fn record_many(&self, entries: Vec<(ShortlistEntry, Vec<String>)>) {
+        if let Err(e) = self
+            .queue
+            .shortlist_record(self.investigation_id, &entries, self.ttl_seconds)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to record entity shortlist entries");
+        }
+    }
+
+    /// Rewrite shortlist names after `source_id` was merged into `merged`.
+    pub async fn record_merge(&self, source_id: EntityId, merged: &Entity) {
+        match self
+            .queue
+            .shortlist_rewrite_merged(
+                source_id,
+                &ShortlistEntry::from_entity(merged),
+                self.ttl_seconds,
+            )
+            .await
+        {
+            Ok(rewritten) => {
+                tracing::debug!(source = %source_id, target = %merged.id, rewritten, "Rewrote shortlist entries for merge");
+            }
+            Err(e) => {
+                tracing::warn!(
+                    source = %source_id,
+                    target = %merged.id,
+                    error = %e,
+                    "Failed to rewrite entity shortlist after merge"
+                );
+            }
+        }
+    }
+}
+
+fn entity_names(entity: &Entity, extra_names: &[&str]) -> Vec<String> {
+    std::iter::once(entity.canonical_name.as_str())
+        .chain(entity.aliases.iter().map(String::as_str))
+        .chain(extra_names.iter().copied())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Analyst prompt block listing shortlisted entities. None when empty.
+pub fn format_shortlist_block(entries: &[(ShortlistEntry, u64)]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let mut block = String::from(
+        "## Known entities\n\nAlready resolved in this investigation — use these IDs directly \
+         instead of searching for them:\n",
+    );
+    for (entry, _) in entries {
+        block.push_str(&format!(
+            "- {} ({}) — {}\n",
+            entry.canonical_name, entry.kind, entry.entity_id
+        ));
+    }
+    Some(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("  Gazprom Neft "), "gazprom neft");
+        assert_eq!(normalize_name("   "), "");
+    }
+
+    #[test]
+    fn test_entity_names_include_aliases_and_extras() {
+        let mut entity = Entity::new("Rosneft".into(), "organization".into());
+        entity.aliases = vec!["NK Rosneft".into()];
+        assert_eq!(
+            entity_names(&entity, &["Rosneft Oil Company"]),
+            vec!["Rosneft", "NK Rosneft", "Rosneft Oil Company"]
+        );
+    }
+
+    #[test]
+    fn test_format_shortlist_block() {
+        assert_eq!(format_shortlist_block(&[]), None);
+
+        let entity = Entity::new("Rosneft".into(), "organization".into());
+        let block = format_shortlist_block(&[(ShortlistEntry::from_entity(&entity), 4)]).unwrap();
+        assert!(block.starts_with("## Known entities"));
+        assert!(block.contains(&format!("- Rosneft (organization) — {}", entity.id)));
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::store::StoreClient;
+use crate::supervisor;
+
+/// How often queued work order wait ages are recomputed.
+const REPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Spawn the task that exports queued work order counts and wait-age
+/// percentiles per priority as gauges:
+/// `work_orders.queued{priority}` and `work_orders.wait_age_seconds{priority,quantile}`.
+pub fn spawn_wait_age_reporter(store: Arc<StoreClient>) -> JoinHandle<()> {
+    supervisor::spawn_supervised_restarting("work_order_wait_ages", move || {
+        let store = Arc::clone(&store);
+        async move {
+            tracing::info!(
+                interval_secs = REPORT_INTERVAL.as_secs(),
+                "Work order wait-age reporter started"
+            );
+
+            loop {
+                tokio::time::sleep(REPORT_INTERVAL).await;
+
+                let stats = match store.queued_work_order_wait_stats(chrono::Utc::now()).await {
+                    Ok(stats) => stats,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to compute work order wait ages");
+                        continue;
+                    }
+                };
+
+                for s in stats {
+                    let priority = s.priority.as_str();
+                    metrics::gauge!("work_orders.queued", "priority" => priority)
+                        .set(s.queued as f64);
+                    metrics::gauge!(
+                        "work_orders.wait_age_seconds",
+                        "priority" => priority,
+                        "quantile" => "0.5"
+                    )
+                    .set(s.wait_age_p50_seconds);
+                    metrics::gauge!(
+                        "work_orders.wait_age_seconds",
+                        "priority" => priority,
+                        "quantile" => "0.95"
+                    )
+                    .set(s.wait_age_p95_seconds);
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,60 @@
+// Draws the entity graph from #graph-data into #graph: a fixed-iteration
+// force layout, so the same data always renders the same picture. Nodes link
+// to their entries in the Entities section.
+(function () {
+  var data = JSON.parse(document.getElementById("graph-data").textContent);
+  var svg = document.getElementById("graph");
+  var ns = "http://www.w3.org/2000/svg";
+  var width = svg.clientWidth || 900, height = svg.clientHeight || 420;
+  var index = {};
+  var nodes = data.nodes.map(function (n, i) {
+    var angle = (2 * Math.PI * i) / Math.max(data.nodes.length, 1);
+    index[n.id] = i;
+    return { id: n.id, label: n.label, x: width / 2 + Math.cos(angle) * width / 3,
+             y: height / 2 + Math.sin(angle) * height / 3, dx: 0, dy: 0 };
+  });
+  var edges = data.edges.filter(function (e) {
+    return e.source in index && e.target in index;
+  });
+
+  for (var step = 0; step < 300; step++) {
+    var heat = 1 - step / 300;
+    nodes.forEach(function (a) {
+      a.dx = (width / 2 - a.x) * 0.01;
+      a.dy = (height / 2 - a.y) * 0.01;
+      nodes.forEach(function (b) {
+        if (a === b) return;
+        var x = a.x - b.x, y = a.y - b.y, d2 = Math.max(x * x + y * y, 1);
+        a.dx += (x / d2) * 900;
+        a.dy += (y / d2) * 900;
+      });
+    });
+    edges.forEach(function (e) {
+      var s = nodes[index[e.source]], t = nodes[index[e.target]];
+      var x = t.x - s.x, y = t.y - s.y;
+      s.dx += x * 0.02; s.dy += y * 0.02;
+      t.dx -= x * 0.02; t.dy -= y * 0.02;
+    });
+    nodes.forEach(function (n) {
+      n.x = Math.min(width - 20, Math.max(20, n.x + Math.max(-10, Math.min(10, n.dx)) * heat));
+      n.y = Math.min(height - 20, Math.max(20, n.y + Math.max(-10, Math.min(10, n.dy)) * heat));
+    });
+  }
+
+  function el(name, attrs, parent) {
+    var node = document.createElementNS(ns, name);
+    Object.keys(attrs).forEach(function (k) { node.setAttribute(k, attrs[k]); });
+    parent.appendChild(node);
+    return node;
+  }
+  edges.forEach(function (e) {
+    var s = nodes[index[e.source]], t = nodes[index[e.target]];
+    var line = el("line", { x1: s.x, y1: s.y, x2: t.x, y2: t.y }, svg);
+    el("title", {}, line).textContent = e.label;
+  });
+  nodes.forEach(function (n) {
+    var link = el("a", { href: "#entity-" + n.id }, svg);
+    el("circle", { cx: n.x, cy: n.y, r: 7 }, link);
+    el("text", { x: n.x + 10, y: n.y + 4 }, link).textContent = n.label;
+  });
+})();
@@ -0,0 +1,1381 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{Cursor, Write};
+
+use askama::Template;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use autosint_common::ids::SourceDocumentId;
+use autosint_common::types::{
+    Assessment, Citation, Claim, Entity, Investigation, JudgmentChange, ScratchpadSection,
+    SourceDocument,
+};
+use autosint_common::{ClaimId, EntityId, InvestigationId};
+
+use crate::graph::{GraphClient, GraphError, Subgraph};
+use crate::store::{StoreClient, StoreError};
+
+/// Inlined so the report has no external dependencies and works offline.
+const STYLE: &str = include_str!("report.css");
+const GRAPH_SCRIPT: &str = include_str!("graph.js");
+
+/// Longest claim excerpt used as a link label.
+const CLAIM_LABEL_CHARS: usize = 80;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReportError {
+    #[error("Investigation {0} not found")]
+    NotFound(InvestigationId),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    #[error("Failed to render the report: {0}")]
+    Render(#[from] askama::Error),
+
+    #[error("Failed to bundle the report: {0}")]
+    Bundle(#[from] ZipError),
+}
+
+/// Everything an investigation report shows.
+pub struct Report {
+    pub investigation: Investigation,
+    /// The latest assessment, if the investigation has produced one.
+    pub assessment: Option<Assessment>,
+    /// What the investigation touched, plus claims and entities its assessment
+    /// cites from elsewhere in the graph.
+    pub subgraph: Subgraph,
+    /// The Analyst's working notes, least recently written first.
+    pub scratchpad: Vec<ScratchpadSection>,
+    /// Stored source documents the claims cite, for a bundle. Empty unless
+    /// loaded with [`Report::load_source_documents`].
+    pub source_documents: Vec<SourceDocument>,
+}
+
+impl Report {
+    /// Gather an investigation's report. Cited claims or entities that can't
+    /// be loaded are left out and shown as unavailable.
+    pub async fn load(
+        graph: &GraphClient,
+        store: &StoreClient,
+        id: InvestigationId,
+    ) -> Result<Self, ReportError> {
+        let investigation = store.get_investigation(id).await.map_err(|e| match e {
+            StoreError::NotFound(_) => ReportError::NotFound(id),
+            other => ReportError::Store(other),
+        })?;
+        let assessment = match store.get_latest_assessment(id).await {
+            Ok(assessment) => Some(assessment),
+            Err(StoreError::NotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let scratchpad = store.get_scratchpad(id, None).await?;
+        let mut subgraph = graph.export_investigation_subgraph(id).await?;
+
+        let Some(refs) = assessment.as_ref().map(References::of) else {
+            return Ok(Self {
+                investigation,
+                assessment,
+                subgraph,
+                scratchpad,
+                source_documents: Vec::new(),
+            });
+        };
+
+        let have: HashSet<ClaimId> = subgraph.claims.iter().map(|c| c.id).collect();
+        for claim_id in refs.claims.iter().filter(|id| !have.contains(id)) {
+            match graph.get_claim(*claim_id).await {
+                Ok(claim) => subgraph.claims.push(claim),
+                Err(GraphError::NotFound(_)) => {}
+                Err(e) => {
+                    tracing::warn!(claim_id = %claim_id, error = %e, "Report could not load a cited claim")
+                }
+            }
+        }
+
+        let have: HashSet<EntityId> = subgraph.entities.iter().map(|e| e.id).collect();
+        let mut wanted = refs.entities;
+        wanted.extend(subgraph.claims.iter().map(|c| c.source_entity_id));
+        let mut seen = HashSet::new();
+        for entity_id in wanted {
+            if have.contains(&entity_id) || !seen.insert(entity_id) {
+                continue;
+            }
+            match graph.get_entity(entity_id).await {
+                Ok(entity) => subgraph.entities.push(entity),
+                Err(GraphError::NotFound(_)) => {}
+                Err(e) => {
+                    tracing::warn!(entity_id = %entity_id, error = %e, "Report could not load a cited entity")
+                }
+            }
+        }
+
+        Ok(Self {
+            investigation,
+            assessment,
+            subgraph,
+            scratchpad,
+            source_documents: Vec::new(),
+        })
+    }
+
+    /// Load the stored source documents the report's claims cite. Documents
+    /// that were since deleted are left out.
+    pub async fn load_source_documents(&mut self, store: &StoreClient) -> Result<(), ReportError> {
+        let mut seen = HashSet::new();
+        let cited = self
+            .subgraph
+            .claims
+            .iter()
+            .filter_map(|c| c.citation.as_ref()?.source_document_id)
+            .filter(|id| seen.insert(*id))
+            .collect::<Vec<_>>();
+        for id in cited {
+            match store.get_source_document(id).await {
+                Ok(document) => self.source_documents.push(document),
+                Err(StoreError::NotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Render as a single self-contained HTML page: styles and the graph
+    /// script are inline, and every internal link targets an anchor on the
+    /// page. Links to claims or entities that couldn't be loaded resolve to
+    /// "unavailable" placeholders.
+    pub fn to_html(&self, generated_at: DateTime<Utc>) -> Result<String, ReportError> {
+        Ok(Renderer::new(self).render(generated_at)?)
+    }
+
+    /// Bundle the page as `report.html` with each loaded source document as
+    /// `sources/<id>.txt`, which the page links from the claims citing it.
+    pub fn to_zip(&self, generated_at: DateTime<Utc>) -> Result<Vec<u8>, ReportError> {
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("report.html", options)?;
+        zip.write_all(self.to_html(generated_at)?.as_bytes())
+            .map_err(ZipError::Io)?;
+        for document in &self.source_documents {
+            zip.start_file(snapshot_path(document.id), options)?;
+            zip.write_all(snapshot_text(document).as_bytes())
+                .map_err(ZipError::Io)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+}
+
+/// Where a bundle stores a source document, relative to `report.html`.
+fn snapshot_path(id: SourceDocumentId) -> String {
+    format!("sources/{}.txt", id)
+}
+
+/// A source document as its bundled text file: what it is, then its content.
+fn snapshot_text(document: &SourceDocument) -> String {
+    let mut header = Vec::new();
+    if let Some(title) = &document.title {
+        header.push(format!("Title: {}", title));
+    }
+    if let Some(author) = &document.author {
+        header.push(format!("From: {}", author));
+    }
+    if let Some(published) = document.published_at {
+        header.push(format!("Published: {}", published.to_rfc3339()));
+    }
+    header.push(format!("Received: {}", document.received_at.to_rfc3339()));
+    header.push(format!(
+        "Origin: {} ({})",
+        document.origin, document.external_id
+    ));
+    format!("{}\n\n{}\n", header.join("\n"), document.content)
+}
+
+/// Claim and entity IDs an assessment cites, in first-cited order.
+struct References {
+    claims: Vec<ClaimId>,
+    entities: Vec<EntityId>,
+}
+
+impl References {
+    fn of(assessment: &Assessment) -> Self {
+        let content = &assessment.content;
+
+        let mut claims: Vec<Uuid> = assessment.claim_refs.iter().map(|id| id.0).collect();
+        claims.extend(ids_in(content, "competing_hypotheses", "claim_refs"));
+        claims.extend(ids_in(content, "citations", "claim_id"));
+        claims.extend(ids_in(content, "forward_indicators", "claim_refs"));
+
+        let mut entities: Vec<Uuid> = assessment.entity_refs.iter().map(|id| id.0).collect();
+        entities.extend(ids_in(content, "citations", "source_entity_id"));
+        entities.extend(ids_in(content, "sources_evaluated", "source_entity_id"));
+        entities.extend(ids_in(content, "forward_indicators", "entity_refs"));
+
+        Self {
+            claims: dedup(claims).into_iter().map(ClaimId::from_uuid).collect(),
+            entities: dedup(entities)
+                .into_iter()
+                .map(EntityId::from_uuid)
+                .collect(),
+        }
+    }
+}
+
+/// UUIDs under `key` (a string or an array of strings) in each item of the
+/// `section` array. Anything that doesn't parse is skipped.
+fn ids_in(content: &Value, section: &str, key: &str) -> Vec<Uuid> {
+    items(content, section)
+        .iter()
+        .flat_map(|item| uuids(item.get(key)))
+        .collect()
+}
+
+fn uuids(value: Option<&Value>) -> Vec<Uuid> {
+    match value {
+        Some(Value::String(s)) => Uuid::parse_str(s.trim()).ok().into_iter().collect(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str().and_then(|s| Uuid::parse_str(s.trim()).ok()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn dedup(ids: Vec<Uuid>) -> Vec<Uuid> {
+    let mut seen = HashSet::new();
+    ids.into_iter().filter(|id| seen.insert(*id)).collect()
+}
+
+fn items<'a>(content: &'a Value, key: &str) -> &'a [Value] {
+    content
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Lowercase serde name of an enum, e.g. "secondhand".
+fn label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+/// Anchor key for a citation marker: "[1]" and "1" both become "1".
+fn marker_key(marker: &str) -> String {
+    marker
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+/// Only http(s) links are rendered as links.
+fn is_web_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// The page, as `templates/report/page.html` lays it out. Every text field
+/// is escaped by the template; only the inlined style, script and graph data
+/// are written as is.
+#[derive(Template)]
+#[template(path = "report/page.html")]
+struct Page<'a> {
+    prompt: &'a str,
+    style: &'static str,
+    meta: Vec<MetaRow>,
+    confidence: Option<&'static str>,
+    assessment: AssessmentView,
+    evidence: EvidenceView,
+    graph: GraphView,
+    entities: EntitiesView,
+    relationships: Vec<RelationshipRow>,
+    notes: Vec<NoteView>,
+    generated_at: String,
+}
+
+struct MetaRow {
+    term: &'static str,
+    value: String,
+}
+
+/// A link to a claim or entity elsewhere on the page.
+struct Link {
+    anchor: String,
+    label: String,
+}
+
+/// A run of text, or a citation marker linked to its citation.
+enum Segment {
+    Text(String),
+    Marker { key: String, marker: String },
+}
+
+enum AssessmentView {
+    Missing,
+    /// Content that isn't an object, shown as JSON.
+    Raw(String),
+    Structured(Box<StructuredAssessment>),
+}
+
+struct StructuredAssessment {
+    confidence: &'static str,
+    summary: Vec<Vec<Segment>>,
+    changes: Option<ChangesView>,
+    analysis: Vec<Vec<Segment>>,
+    judgments: Vec<JudgmentView>,
+    confidence_reasoning: Vec<Vec<Segment>>,
+    gaps: Vec<GapView>,
+    indicators: Vec<IndicatorView>,
+    sources: Vec<SourceView>,
+    citations: Vec<CitationView>,
+}
+
+/// "What changed" since the superseded assessment.
+struct ChangesView {
+    previous_assessment_id: String,
+    previous_confidence: &'static str,
+    judgments: Vec<ChangeView>,
+}
+
+struct ChangeView {
+    class: &'static str,
+    current: JudgmentLabel,
+    /// What a revised judgment said before.
+    previous: Option<JudgmentLabel>,
+}
+
+/// A key judgment as "hypothesis (80%)".
+struct JudgmentLabel {
+    name: String,
+    probability: Option<f64>,
+}
+
+impl JudgmentLabel {
+    fn of(judgment: &Value) -> Self {
+        Self {
+            name: text(judgment, "hypothesis").unwrap_or_else(|| "(unnamed)".into()),
+            probability: judgment.get("probability").and_then(Value::as_f64),
+        }
+    }
+}
+
+impl fmt::Display for JudgmentLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.probability {
+            Some(p) => write!(f, "{} ({:.0}%)", self.name, p * 100.0),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+struct JudgmentView {
+    number: usize,
+    name: String,
+    probability: Option<String>,
+    fields: Vec<ProseField>,
+    claims: Vec<Link>,
+}
+
+struct ProseField {
+    label: &'static str,
+    body: Vec<Segment>,
+}
+
+struct TextField {
+    label: &'static str,
+    text: String,
+}
+
+struct GapView {
+    description: String,
+    impact: Option<String>,
+    resolution: Option<String>,
+}
+
+struct IndicatorView {
+    description: String,
+    implication: Option<String>,
+    entities: Vec<Link>,
+    claims: Vec<Link>,
+}
+
+struct SourceView {
+    name: String,
+    anchor: Option<String>,
+    fields: Vec<TextField>,
+}
+
+struct CitationView {
+    key: Option<String>,
+    marker: String,
+    source_name: String,
+    source_anchor: Option<String>,
+    date: Option<String>,
+    depth: Option<String>,
+    claims: Vec<Link>,
+    url: Option<String>,
+}
+
+/// Cited claims first, in citation order, then the rest of the claims the
+/// investigation gathered.
+struct EvidenceView {
+    cited: Vec<EvidenceEntry>,
+    others: Vec<ClaimView>,
+}
+
+enum EvidenceEntry {
+    Claim(Box<ClaimView>),
+    Missing(ClaimId),
+}
+
+struct ClaimView {
+    id: ClaimId,
+    content: String,
+    attribution: String,
+    information_type: String,
+    published: String,
+    source: Link,
+    about: Vec<Link>,
+    citation: Option<CitationLine>,
+    original: Option<OriginalView>,
+}
+
+/// The claim's source: its citation's title (linked when it has a web
+/// address) followed by locator, access date and route.
+struct CitationLine {
+    url: Option<String>,
+    title: String,
+    qualifiers: Vec<String>,
+    /// The bundled copy of the cited source document.
+    snapshot: Option<String>,
+}
+
+impl CitationLine {
+    /// Claims written before citations fall back to their bare link.
+    /// `bundled` are the source documents the page is bundled with.
+    fn of(claim: &Claim, bundled: &HashSet<SourceDocumentId>) -> Option<Self> {
+        let citation = claim
+            .citation
+            .clone()
+            .or_else(|| claim.raw_source_link.as_deref().map(Citation::from_url))?;
+        let url = is_web_url(&citation.url).then(|| citation.url.clone());
+        if url.is_none() && citation.title.is_none() && citation.source_document_id.is_none() {
+            return None;
+        }
+        Some(Self {
+            url,
+            title: citation
+                .title
+                .clone()
+                .unwrap_or_else(|| "Source document".into()),
+            qualifiers: citation.qualifiers(),
+            snapshot: citation
+                .source_document_id
+                .filter(|id| bundled.contains(id))
+                .map(snapshot_path),
+        })
+    }
+}
+
+/// The source's own wording of a claim written from a translation.
+struct OriginalView {
+    language: Option<String>,
+    text: String,
+}
+
+struct GraphView {
+    entities: usize,
+    relationships: usize,
+    /// The subgraph as JSON for the graph script.
+    data: String,
+    script: &'static str,
+}
+
+/// Every entity in the subgraph, plus placeholders for linked entities that
+/// couldn't be loaded.
+struct EntitiesView {
+    present: Vec<EntityView>,
+    missing: Vec<EntityId>,
+}
+
+struct EntityView {
+    id: EntityId,
+    name: String,
+    kind: String,
+    aliases: Option<String>,
+    summary: Option<String>,
+}
+
+struct RelationshipRow {
+    source: Link,
+    description: String,
+    target: Link,
+    weight: String,
+}
+
+struct NoteView {
+    title: String,
+    updated: String,
+    content: String,
+}
+
+/// Builds the page's view models, tracking which claims and entities the
+/// page links to so each gets an anchor.
+struct Renderer<'a> {
+    report: &'a Report,
+    content: Value,
+    claims: HashMap<ClaimId, &'a Claim>,
+    entities: HashMap<EntityId, &'a Entity>,
+    citations: HashSet<String>,
+    bundled: HashSet<SourceDocumentId>,
+    /// Claims and entities linked so far, in first-linked order.
+    linked_claims: Vec<ClaimId>,
+    linked_entities: Vec<EntityId>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(report: &'a Report) -> Self {
+        let content = report
+            .assessment
+            .as_ref()
+            .map(|a| a.content.clone())
+            .unwrap_or(Value::Null);
+        let citations = items(&content, "citations")
+            .iter()
+            .filter_map(|c| text(c, "marker"))
+            .map(|m| marker_key(&m))
+            .filter(|k| !k.is_empty())
+            .collect();
+        Self {
+            report,
+            content,
+            claims: report.subgraph.claims.iter().map(|c| (c.id, c)).collect(),
+            entities: report.subgraph.entities.iter().map(|e| (e.id, e)).collect(),
+            citations,
+            bundled: report.source_documents.iter().map(|d| d.id).collect(),
+            linked_claims: Vec::new(),
+            linked_entities: Vec::new(),
+        }
+    }
+
+    fn render(mut self, generated_at: DateTime<Utc>) -> Result<String, askama::Error> {
+        // Sections that link to claims and entities are built first, so the
+        // evidence and entity sections can add placeholders for any missing.
+        let assessment = self.assessment();
+        let evidence = self.evidence();
+        let relationships = self.relationships();
+        let graph = self.graph();
+        let entities = self.entities();
+
+        let investigation = &self.report.investigation;
+        Page {
+            prompt: &investigation.prompt,
+            style: STYLE,
+            meta: self.meta(),
+            confidence: self
+                .report
+                .assessment
+                .as_ref()
+                .map(|a| a.confidence.as_db_str()),
+            assessment,
+            evidence,
+            graph,
+            entities,
+            relationships,
+            notes: self
+                .report
+                .scratchpad
+                .iter()
+                .map(|section| NoteView {
+                    title: section.section.clone(),
+                    updated: section.updated_at.to_rfc3339(),
+                    content: section.content.clone(),
+                })
+                .collect(),
+            generated_at: generated_at.to_rfc3339(),
+        }
+        .render()
+    }
+
+    fn meta(&self) -> Vec<MetaRow> {
+        let investigation = &self.report.investigation;
+        let mut rows = vec![
+            MetaRow {
+                term: "Investigation",
+                value: investigation.id.to_string(),
+            },
+            MetaRow {
+                term: "Status",
+                value: investigation.status.as_db_str().to_string(),
+            },
+            MetaRow {
+                term: "Created",
+                value: investigation.created_at.to_rfc3339(),
+            },
+        ];
+        if let Some(completed) = investigation.completed_at {
+            rows.push(MetaRow {
+                term: "Completed",
+                value: completed.to_rfc3339(),
+            });
+        }
+        if !investigation.tags.is_empty() {
+            rows.push(MetaRow {
+                term: "Tags",
+                value: investigation.tags.join(", "),
+            });
+        }
+        if let Some(correlation_id) = &investigation.correlation_id {
+            rows.push(MetaRow {
+                term: "Correlation ID",
+                value: correlation_id.clone(),
+            });
+        }
+        if let Some(efficiency) = &investigation.cycle_efficiency {
+            rows.push(MetaRow {
+                term: "Cycles",
+                value: format!(
+                    "{} ({:.1} new claims per work order in the last, {})",
+                    investigation.cycles.len(),
+                    efficiency.claims_per_work_order,
+                    efficiency.trend.as_str()
+                ),
+            });
+        }
+        rows
+    }
+
+    fn claim_link(&mut self, id: ClaimId) -> Link {
+        if !self.linked_claims.contains(&id) {
+            self.linked_claims.push(id);
+        }
+        let label = match self.claims.get(&id) {
+            Some(claim) if claim.content.chars().count() > CLAIM_LABEL_CHARS => {
+                let excerpt: String = claim.content.chars().take(CLAIM_LABEL_CHARS).collect();
+                format!("{}…", excerpt.trim_end())
+            }
+            Some(claim) => claim.content.clone(),
+            None => format!("Claim {}", id),
+        };
+        Link {
+            anchor: format!("claim-{}", id),
+            label,
+        }
+    }
+
+    fn entity_link(&mut self, id: EntityId) -> Link {
+        let label = match self.entities.get(&id) {
+            Some(entity) => entity.canonical_name.clone(),
+            None => format!("Entity {}", id),
+        };
+        Link {
+            anchor: self.entity_anchor(id),
+            label,
+        }
+    }
+
+    /// The anchor of an entity's entry, which the page must then have.
+    fn entity_anchor(&mut self, id: EntityId) -> String {
+        if !self.linked_entities.contains(&id) {
+            self.linked_entities.push(id);
+        }
+        format!("entity-{}", id)
+    }
+
+    fn claim_links(&mut self, value: Option<&Value>) -> Vec<Link> {
+        uuids(value)
+            .into_iter()
+            .map(|id| self.claim_link(ClaimId::from_uuid(id)))
+            .collect()
+    }
+
+    fn entity_links(&mut self, value: Option<&Value>) -> Vec<Link> {
+        uuids(value)
+            .into_iter()
+            .map(|id| self.entity_link(EntityId::from_uuid(id)))
+            .collect()
+    }
+
+    /// Paragraphs of `body`, split on blank lines.
+    fn prose(&self, body: &str) -> Vec<Vec<Segment>> {
+        body.split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| self.segments(p))
+            .collect()
+    }
+
+    /// `text` with its `[n]` markers that name a citation split out.
+    fn segments(&self, text: &str) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let mut plain = String::new();
+        let mut rest = text;
+        while let Some(open) = rest.find('[') {
+            plain.push_str(&rest[..open]);
+            let candidate = &rest[open..];
+            let marker = candidate
+                .find(']')
+                .map(|close| &candidate[..=close])
+                .filter(|m| {
+                    m.len() <= 10 && m[1..m.len() - 1].chars().all(|c| c.is_ascii_alphanumeric())
+                });
+            match marker {
+                Some(marker) if self.citations.contains(&marker_key(marker)) => {
+                    if !plain.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut plain)));
+                    }
+                    segments.push(Segment::Marker {
+                        key: marker_key(marker),
+                        marker: marker.to_string(),
+                    });
+                    rest = &candidate[marker.len()..];
+                }
+                _ => {
+                    plain.push('[');
+                    rest = &candidate[1..];
+                }
+            }
+        }
+        plain.push_str(rest);
+        if !plain.is_empty() {
+            segments.push(Segment::Text(plain));
+        }
+        segments
+    }
+
+    fn assessment(&mut self) -> AssessmentView {
+        let Some(assessment) = self.report.assessment.as_ref() else {
+            return AssessmentView::Missing;
+        };
+        let content = self.content.clone();
+        if !content.is_object() {
+            return AssessmentView::Raw(serde_json::to_string_pretty(&content).unwrap_or_default());
+        }
+
+        let prose = |key| {
+            text(&content, key)
+                .map(|body| self.prose(&body))
+                .unwrap_or_default()
+        };
+        let summary = prose("summary");
+        let analysis = prose("analysis");
+        let confidence_reasoning = prose("confidence_reasoning");
+
+        let changes = assessment.diff.as_ref().map(|diff| ChangesView {
+            previous_assessment_id: diff.previous_assessment_id.to_string(),
+            previous_confidence: diff.previous_confidence.as_db_str(),
+            judgments: diff
+                .judgments
+                .iter()
+                .map(|change| match change {
+                    JudgmentChange::Unchanged { judgment } => ChangeView {
+                        class: "unchanged",
+                        current: JudgmentLabel::of(judgment),
+                        previous: None,
+                    },
+                    JudgmentChange::Revised {
+                        previous, current, ..
+                    } => ChangeView {
+                        class: "revised",
+                        current: JudgmentLabel::of(current),
+                        previous: Some(JudgmentLabel::of(previous)),
+                    },
+                    JudgmentChange::New { judgment } => ChangeView {
+                        class: "new",
+                        current: JudgmentLabel::of(judgment),
+                        previous: None,
+                    },
+                    JudgmentChange::Dropped { judgment } => ChangeView {
+                        class: "dropped",
+                        current: JudgmentLabel::of(judgment),
+                        previous: None,
+                    },
+                })
+                .collect(),
+        });
+
+        let mut judgments = Vec::new();
+        for (i, h) in items(&content, "competing_hypotheses").iter().enumerate() {
+            let fields = [
+                ("reasoning", "Reasoning"),
+                ("supporting_evidence", "Supporting evidence"),
+                ("weaknesses", "Weaknesses"),
+            ]
+            .into_iter()
+            .filter_map(|(key, label)| {
+                text(h, key).map(|body| ProseField {
+                    label,
+                    body: self.segments(&body),
+                })
+            })
+            .collect();
+            judgments.push(JudgmentView {
+                number: i + 1,
+                name: text(h, "hypothesis").unwrap_or_else(|| "(unnamed)".into()),
+                probability: h
+                    .get("probability")
+                    .and_then(Value::as_f64)
+                    .map(|p| format!("{:.0}", p * 100.0)),
+                fields,
+                claims: self.claim_links(h.get("claim_refs")),
+            });
+        }
+
+        let gaps = items(&content, "gaps")
+            .iter()
+            .map(|gap| GapView {
+                description: text(gap, "description").unwrap_or_default(),
+                impact: text(gap, "impact"),
+                resolution: text(gap, "suggested_resolution"),
+            })
+            .collect();
+
+        let mut indicators = Vec::new();
+        for indicator in items(&content, "forward_indicators") {
+            indicators.push(IndicatorView {
+                description: text(indicator, "description").unwrap_or_default(),
+                implication: text(indicator, "trigger_implication"),
+                entities: self.entity_links(indicator.get("entity_refs")),
+                claims: self.claim_links(indicator.get("claim_refs")),
+            });
+        }
+
+        let mut sources = Vec::new();
+        for source in items(&content, "sources_evaluated") {
+            let anchor = uuids(source.get("source_entity_id"))
+                .first()
+                .map(|id| self.entity_anchor(EntityId::from_uuid(*id)));
+            let fields = [
+                ("structural_profile", "Profile"),
+                ("profile_basis", "Profile basis"),
+                ("claims_used", "Claims used"),
+                ("primary_vs_secondhand", "Attribution"),
+                ("sourcing_chain_notes", "Sourcing chain"),
+            ]
+            .into_iter()
+            .filter_map(|(key, label)| text(source, key).map(|text| TextField { label, text }))
+            .collect();
+            sources.push(SourceView {
+                name: text(source, "source_name").unwrap_or_else(|| "(unnamed source)".into()),
+                anchor,
+                fields,
+            });
+        }
+
+        let mut citations = Vec::new();
+        for citation in items(&content, "citations") {
+            let marker = text(citation, "marker").unwrap_or_default();
+            let key = marker_key(&marker);
+            let source_anchor = uuids(citation.get("source_entity_id"))
+                .first()
+                .map(|id| self.entity_anchor(EntityId::from_uuid(*id)));
+            citations.push(CitationView {
+                key: (!key.is_empty()).then_some(key),
+                marker,
+                source_name: text(citation, "source_name").unwrap_or_default(),
+                source_anchor,
+                date: text(citation, "date"),
+                depth: text(citation, "attribution_depth"),
+                claims: self.claim_links(citation.get("claim_id")),
+                url: text(citation, "source_url").filter(|u| is_web_url(u)),
+            });
+        }
+
+        AssessmentView::Structured(Box::new(StructuredAssessment {
+            confidence: assessment.confidence.as_db_str(),
+            summary,
+            changes,
+            analysis,
+            judgments,
+            confidence_reasoning,
+            gaps,
+            indicators,
+            sources,
+            citations,
+        }))
+    }
+
+    fn evidence(&mut self) -> EvidenceView {
+        let cited = self.linked_claims.clone();
+        let cited_set: HashSet<ClaimId> = cited.iter().copied().collect();
+        let cited = cited
+            .into_iter()
+            .map(|id| match self.claims.get(&id).copied() {
+                Some(claim) => EvidenceEntry::Claim(Box::new(self.claim(claim))),
+                None => EvidenceEntry::Missing(id),
+            })
+            .collect();
+        let others = self
+            .report
+            .subgraph
+            .claims
+            .iter()
+            .filter(|c| !cited_set.contains(&c.id))
+            .map(|claim| self.claim(claim))
+            .collect();
+        EvidenceView { cited, others }
+    }
+
+    fn claim(&mut self, claim: &Claim) -> ClaimView {
+        ClaimView {
+            id: claim.id,
+            content: claim.content.clone(),
+            attribution: label(&claim.attribution_depth),
+            information_type: label(&claim.information_type),
+            published: claim.published_timestamp.format("%Y-%m-%d").to_string(),
+            source: self.entity_link(claim.source_entity_id),
+            about: claim
+                .referenced_entity_ids
+                .iter()
+                .map(|id| self.entity_link(*id))
+                .collect(),
+            citation: CitationLine::of(claim, &self.bundled),
+            original: claim.original_content.clone().map(|text| OriginalView {
+                language: claim.original_language.clone(),
+                text,
+            }),
+        }
+    }
+
+    fn relationships(&mut self) -> Vec<RelationshipRow> {
+        let relationships = &self.report.subgraph.relationships;
+        relationships
+            .iter()
+            .map(|relationship| RelationshipRow {
+                source: self.entity_link(relationship.source_entity_id),
+                description: relationship.description.clone(),
+                target: self.entity_link(relationship.target_entity_id),
+                weight: relationship
+                    .weight
+                    .map(|w| format!("{:.2}", w))
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn graph(&self) -> GraphView {
+        let subgraph = &self.report.subgraph;
+        let data = json!({
+            "nodes": subgraph.entities.iter().map(|e| json!({
+                "id": e.id.to_string(),
+                "label": e.canonical_name,
+                "kind": e.kind,
+            })).collect::<Vec<_>>(),
+            "edges": subgraph.relationships.iter().map(|r| json!({
+                "source": r.source_entity_id.to_string(),
+                "target": r.target_entity_id.to_string(),
+                "label": r.description,
+            })).collect::<Vec<_>>(),
+        });
+        GraphView {
+            entities: subgraph.entities.len(),
+            relationships: subgraph.relationships.len(),
+            // "</" can't appear inside a script element.
+            data: data.to_string().replace("</", "<\\/"),
+            script: GRAPH_SCRIPT,
+        }
+    }
+
+    fn entities(&self) -> EntitiesView {
+        let mut present: Vec<&Entity> = self.report.subgraph.entities.iter().collect();
+        present.sort_by(|a, b| a.canonical_name.cmp(&b.canonical_name));
+        EntitiesView {
+            present: present
+                .into_iter()
+                .map(|entity| EntityView {
+                    id: entity.id,
+                    name: entity.canonical_name.clone(),
+                    kind: entity.kind.clone(),
+                    aliases: (!entity.aliases.is_empty()).then(|| entity.aliases.join(", ")),
+                    summary: entity.summary.clone(),
+                })
+                .collect(),
+            missing: self
+                .linked_entities
+                .iter()
+                .filter(|id| !self.entities.contains_key(id))
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{
+        AssessmentDiff, AttributionDepth, CitationVia, Confidence, InformationType,
+        InvestigationStatus, Relationship,
+    };
+
+    /// A completed investigation with two publishers, a vessel and an
+    /// operator, three claims and one relationship.
+    fn seeded() -> (Report, Vec<Claim>) {
+        let mut investigation = Investigation::new("Who operates the tanker <Crius>?".into());
+        investigation.status = InvestigationStatus::Completed;
+        investigation.tags = vec!["maritime".into()];
+
+        let reuters = Entity::new("Reuters".into(), "publication".into());
+        let lloyds = Entity::new("Lloyd's List".into(), "publication".into());
+        let crius = Entity::new("Crius".into(), "vessel".into());
+        let operator = Entity::new("Gulf Star Shipping".into(), "organization".into());
+
+        let mut claims: Vec<Claim> = [
+            ("Gulf Star Shipping operates the Crius.", &reuters),
+            ("The Crius changed flag to Gabon in 2025.", &lloyds),
+            ("Gulf Star Shipping is registered in Dubai.", &lloyds),
+        ]
+        .into_iter()
+        .map(|(content, source)| {
+            let mut claim = Claim::new(
+                content.into(),
+                Utc::now(),
+                AttributionDepth::Secondhand,
+                InformationType::Assertion,
+                source.id,
+            );
+            claim.referenced_entity_ids = vec![crius.id, operator.id];
+            claim
+        })
+        .collect();
+        claims[0].raw_source_link = Some("https://example.com/crius".into());
+
+        let relationship = Relationship::new(operator.id, crius.id, "operates".into());
+
+        let mut assessment = Assessment::new(
+            investigation.id,
+            json!({
+                "summary": "Gulf Star Shipping operates the Crius [1].",
+                "analysis": "Reuters reports the operator [1].\n\nLloyd's List reports the reflagging [2] & more.",
+                "competing_hypotheses": [
+                    {
+                        "hypothesis": "Gulf Star Shipping is the operator",
+                        "probability": 0.8,
+                        "reasoning": "Two outlets [1][2].",
+                        "claim_refs": [claims[0].id.to_string(), claims[1].id.to_string()]
+                    },
+                    {"hypothesis": "A front company operates it", "probability": 0.2, "claim_refs": []}
+                ],
+                "confidence_reasoning": "Two independent outlets.",
+                "citations": [
+                    {"marker": "[1]", "claim_id": claims[0].id.to_string(), "source_name": "Reuters",
+                     "source_entity_id": reuters.id.to_string(), "source_url": "https://example.com/crius"},
+                    {"marker": "[2]", "claim_id": claims[1].id.to_string(), "source_name": "Lloyd's List",
+                     "source_entity_id": lloyds.id.to_string(), "source_url": "javascript:alert(1)"}
+                ],
+                "forward_indicators": [
+                    {"description": "New charter", "entity_refs": [crius.id.to_string()],
+                     "claim_refs": [claims[2].id.to_string()], "trigger_implication": "Confirms"}
+                ]
+            }),
+            Confidence::Moderate,
+        );
+        assessment.claim_refs = vec![claims[0].id];
+
+        let report = Report {
+            investigation,
+            assessment: Some(assessment),
+            subgraph: Subgraph {
+                entities: vec![reuters, lloyds, crius, operator],
+                relationships: vec![relationship],
+                claims: claims.clone(),
+            },
+            scratchpad: vec![ScratchpadSection {
+                section: "open questions".into(),
+                content: "Is <Gulf Star> a front?".into(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }],
+            source_documents: Vec::new(),
+        };
+        (report, claims)
+    }
+
+    fn attribute_values<'a>(html: &'a str, attribute: &str) -> Vec<&'a str> {
+        let needle = format!("{}=\"", attribute);
+        html.match_indices(&needle)
+            .map(|(i, _)| {
+                let start = i + needle.len();
+                &html[start..start + html[start..].find('"').unwrap()]
+            })
+            .collect()
+    }
+
+    /// Every `href="#x"` has a matching `id="x"`, and no id repeats.
+    fn assert_anchors_resolve(html: &str) {
+        let ids = attribute_values(html, "id");
+        let unique: HashSet<&str> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), ids.len(), "duplicate ids");
+        for href in attribute_values(html, "href") {
+            if let Some(anchor) = href.strip_prefix('#') {
+                assert!(unique.contains(anchor), "unresolved link #{}", anchor);
+            }
+        }
+    }
+
+    #[test]
+    fn test_report_structure() {
+        let (report, claims) = seeded();
+        let html = report.to_html(Utc::now()).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        // Self-contained: no external scripts, styles or fonts.
+        assert!(!html.contains("<script src"));
+        assert!(!html.contains("<link"));
+        assert!(html.contains("Who operates the tanker &#60;Crius&#62;?"));
+        assert!(html.contains("<span class=\"confidence\">moderate</span>"));
+
+        for section in [
+            "assessment",
+            "evidence",
+            "graph-view",
+            "entities",
+            "relationships",
+            "scratchpad",
+        ] {
+            assert!(
+                html.contains(&format!("<section id=\"{}\">", section)),
+                "{}",
+                section
+            );
+        }
+        assert!(html.contains(
+            "<span class=\"probability\">80%</span>1. Gulf Star Shipping is the operator"
+        ));
+        assert!(html.contains(&format!(
+            "<strong>Supporting claims:</strong> <a href=\"#claim-{}\">",
+            claims[0].id
+        )));
+        assert!(html.contains("reports the operator <a href=\"#cite-1\">[1]</a>."));
+        assert!(html.contains("<p>Lloyd&#39;s List reports the reflagging <a href=\"#cite-2\">[2]</a> &#38; more.</p>"));
+        assert!(html.contains("<li id=\"cite-1\">[1] <a href=\"#entity-"));
+        assert!(html.contains(
+            "<a href=\"https://example.com/crius\" rel=\"noopener noreferrer\">Source document</a>"
+        ));
+        assert!(!html.contains("javascript:"));
+
+        // Cited claims come first, in citation order.
+        let positions: Vec<usize> = claims
+            .iter()
+            .map(|c| html.find(&format!("id=\"claim-{}\"", c.id)).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+
+        assert!(html.contains("\"label\":\"operates\""));
+        assert!(html.contains("<h3>open questions</h3>"));
+        assert!(html.contains("<pre>Is &#60;Gulf Star&#62; a front?</pre>"));
+        assert_anchors_resolve(&html);
+    }
+
+    #[test]
+    fn test_missing_pieces_render_as_unavailable() {
+        let (mut report, claims) = seeded();
+        // The cited reflagging claim and its publisher were since purged.
+        report.subgraph.claims.retain(|c| c.id != claims[1].id);
+        let lloyds = claims[1].source_entity_id;
+        report.subgraph.entities.retain(|e| e.id != lloyds);
+
+        let html = report.to_html(Utc::now()).unwrap();
+
+        assert!(html.contains(&format!(
+            "<article class=\"claim missing\" id=\"claim-{}\">",
+            claims[1].id
+        )));
+        assert!(html.contains(&format!(
+            "<li class=\"entity missing\" id=\"entity-{}\">",
+            lloyds
+        )));
+        assert_anchors_resolve(&html);
+
+        report.assessment = None;
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(html.contains("has not produced an assessment yet"));
+        assert_anchors_resolve(&html);
+    }
+
+    #[test]
+    fn test_structured_citation_renders() {
+        let (mut report, claims) = seeded();
+        let claim = report
+            .subgraph
+            .claims
+            .iter_mut()
+            .find(|c| c.id == claims[1].id)
+            .unwrap();
+        let mut citation = Citation::from_url("https://lloydslist.example/crius?utm_source=x");
+        citation.title = Some("Crius <reflagged>".into());
+        citation.locator = Some("para. 3".into());
+        citation.accessed_at = Some("2026-03-01T08:00:00Z".parse().unwrap());
+        citation.via = CitationVia::Cache;
+        claim.set_citation(citation);
+
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(html.contains(
+            "<p class=\"refs\"><a href=\"https://lloydslist.example/crius?utm_source=x\" rel=\"noopener noreferrer\">Crius &#60;reflagged&#62;</a> · para. 3 · accessed 2026-03-01 · cached copy</p>"
+        ));
+
+        // A non-web citation renders unlinked.
+        let claim = report
+            .subgraph
+            .claims
+            .iter_mut()
+            .find(|c| c.id == claims[2].id)
+            .unwrap();
+        claim.set_citation(Citation {
+            url: "javascript:alert(1)".into(),
+            title: Some("Registry extract".into()),
+            ..Citation::from_url("")
+        });
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(html.contains("<p class=\"refs\">Registry extract</p>"));
+        assert!(!html.contains("javascript:"));
+    }
+
+    #[test]
+    fn test_original_wording_renders_with_claim() {
+        let (mut report, claims) = seeded();
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(!html.contains("class=\"original\""));
+
+        let claim = report
+            .subgraph
+            .claims
+            .iter_mut()
+            .find(|c| c.id == claims[1].id)
+            .unwrap();
+        claim.original_content = Some("Судно «Криус» сменило флаг на габонский.".into());
+        claim.original_language = Some("ru".into());
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(html.contains(
+            "<p class=\"original\" lang=\"ru\">Original (ru): Судно «Криус» сменило флаг на габонский.</p>"
+        ));
+        assert_anchors_resolve(&html);
+    }
+
+    #[test]
+    fn test_what_changed_follows_summary() {
+        let (mut report, _) = seeded();
+        let previous_id = autosint_common::ids::AssessmentId::new();
+        report.assessment.as_mut().unwrap().diff = Some(AssessmentDiff {
+            previous_assessment_id: previous_id,
+            previous_confidence: autosint_common::types::Confidence::Low,
+            matched_by: autosint_common::types::JudgmentMatching::Fuzzy,
+            judgments: vec![
+                JudgmentChange::Revised {
+                    previous: json!({"hypothesis": "Operator <unknown>", "probability": 0.5}),
+                    current: json!({"hypothesis": "Gulf Star Shipping is the operator", "probability": 0.8}),
+                    similarity: 0.86,
+                },
+                JudgmentChange::Dropped {
+                    judgment: json!({"hypothesis": "Sold for scrap"}),
+                },
+            ],
+        });
+
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(html.contains(&format!(
+            "<p>Compared with assessment {} (confidence: low).</p>",
+            previous_id
+        )));
+        assert!(html.contains(
+            "<li><span class=\"change revised\">revised</span> Gulf Star Shipping is the operator (80%) \
+             <em>Previously:</em> Operator &#60;unknown&#62; (50%)</li>"
+        ));
+        assert!(
+            html.contains("<li><span class=\"change dropped\">dropped</span> Sold for scrap</li>")
+        );
+        let changed = html.find("<h3 id=\"changes\">").unwrap();
+        assert!(html.find("<h3>Summary</h3>").unwrap() < changed);
+        assert!(changed < html.find("<h3>Analysis</h3>").unwrap());
+        assert_anchors_resolve(&html);
+    }
+
+    #[test]
+    fn test_zip_bundles_cited_source_documents() {
+        let (mut report, claims) = seeded();
+        let document = SourceDocument {
+            id: SourceDocumentId::new(),
+            origin: "imap".into(),
+            external_id: "<crius@example.org>".into(),
+            title: Some("Maritime brief".into()),
+            author: Some("Maritime Brief <news@example.org>".into()),
+            publisher_entity_id: None,
+            content: "The Crius now flies the Gabonese flag.".into(),
+            published_at: None,
+            received_at: Utc::now(),
+        };
+        let claim = report
+            .subgraph
+            .claims
+            .iter_mut()
+            .find(|c| c.id == claims[1].id)
+            .unwrap();
+        claim.set_citation(Citation::for_source_document(
+            document.id,
+            document.title.clone(),
+            Utc::now(),
+        ));
+
+        // Without the document loaded, the page has no archived copy to link.
+        let html = report.to_html(Utc::now()).unwrap();
+        assert!(!html.contains("saved text"));
+
+        report.source_documents.push(document.clone());
+        let bundle = report.to_zip(Utc::now()).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bundle)).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        let snapshot = format!("sources/{}.txt", document.id);
+        assert_eq!(names, vec!["report.html", snapshot.as_str()]);
+
+        let read = |archive: &mut zip::ZipArchive<_>, name: &str| {
+            let mut text = String::new();
+            std::io::Read::read_to_string(&mut archive.by_name(name).unwrap(), &mut text).unwrap();
+            text
+        };
+        let html = read(&mut archive, "report.html");
+        assert!(html.contains(&format!(
+            "Maritime brief · accessed {} · archived copy · <a href=\"{}\">saved text</a></p>",
+            Utc::now().format("%Y-%m-%d"),
+            snapshot
+        )));
+        assert_anchors_resolve(&html);
+        let text = read(&mut archive, &snapshot);
+        assert!(
+            text.starts_with("Title: Maritime brief\nFrom: Maritime Brief <news@example.org>\n")
+        );
+        assert!(text.ends_with("\n\nThe Crius now flies the Gabonese flag.\n"));
+    }
+
+    #[test]
+    fn test_references_of_assessment() {
+        let (report, claims) = seeded();
+        let refs = References::of(report.assessment.as_ref().unwrap());
+
+        assert_eq!(
+            refs.claims,
+            vec![claims[0].id, claims[1].id, claims[2].id],
+            "deduplicated, in first-cited order"
+        );
+        assert_eq!(refs.entities.len(), 3);
+    }
+}
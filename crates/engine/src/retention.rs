@@ -0,0 +1,160 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use autosint_common::config::RetentionConfig;
+use autosint_common::ids::InvestigationId;
+
+use crate::graph::{GraphClient, GraphError};
+use crate::ingest::INGEST_TAG;
+use crate::maintenance::ReadOnlyMode;
+use crate::store::{StoreClient, StoreError};
+use crate::supervisor;
+
+pub use autosint_common::api::engine::PurgeReport;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PurgeError {
+    #[error("Investigation {0} not found")]
+    NotFound(InvestigationId),
+
+    #[error("Investigation {0} is still active (status: {1}); only terminal investigations can be purged")]
+    NotTerminal(InvestigationId, String),
+
+    #[error("Investigation {0} is a standing ingest investigation; delete its monitor or sources instead")]
+    Ingest(InvestigationId),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+}
+
+/// Purge an investigation and everything it produced.
+///
+/// Graph data goes first: it is located via provenance stamps rather than Postgres
+/// rows, so if the store step fails afterwards the purge can simply be re-run.
+pub async fn purge_investigation(
+    graph: &GraphClient,
+    store: &StoreClient,
+    id: InvestigationId,
+    dry_run: bool,
+) -> Result<PurgeReport, PurgeError> {
+    let investigation = store.get_investigation(id).await.map_err(|e| match e {
+        StoreError::NotFound(_) => PurgeError::NotFound(id),
+        other => PurgeError::Store(other),
+    })?;
+
+    if !investigation.status.is_terminal() {
+        return Err(PurgeError::NotTerminal(
+            id,
+            investigation.status.as_db_str().to_string(),
+        ));
+    }
+
+    // Ingest investigations stay, as in retention runs: monitors reference
+    // theirs, and new items keep arriving under them.
+    if investigation.tags.iter().any(|t| t == INGEST_TAG)
+        || store.investigation_has_monitor(id).await?
+    {
+        return Err(PurgeError::Ingest(id));
+    }
+
+    let protected = store.entity_refs_outside_investigation(id).await?;
+    let graph_report = graph
+        .purge_by_investigation(id, &protected, dry_run)
+        .await?;
+    let store_report = store.purge_investigation(id, dry_run).await?;
+
+    if !dry_run {
+        metrics::counter!("retention.investigations_purged").increment(1);
+        metrics::counter!("retention.claims_purged").increment(graph_report.claim_ids.len() as u64);
+        metrics::counter!("retention.entities_purged")
+            .increment(graph_report.entity_ids.len() as u64);
+        metrics::counter!("retention.relationships_purged")
+            .increment(graph_report.relationship_ids.len() as u64);
+
+        tracing::info!(
+            investigation_id = %id,
+            claims = graph_report.claim_ids.len(),
+            entities = graph_report.entity_ids.len(),
+            relationships = graph_report.relationship_ids.len(),
+            work_orders = store_report.work_orders,
+            assessments = store_report.assessments,
+            "Investigation purged"
+        );
+    }
+
+    Ok(PurgeReport {
+        investigation_id: id,
+        dry_run,
+        store: store_report,
+        graph: graph_report,
+    })
+}
+
+/// Spawn a background task that periodically purges terminal investigations
+/// older than `retention.max_age_days`. Runs are skipped while the engine is
+/// read-only, and hold the `retention` singleton lock in `store`.
+pub fn spawn_retention_task(
+    graph: Arc<GraphClient>,
+    store: Arc<StoreClient>,
+    config: RetentionConfig,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.check_interval_minutes as u64 * 60);
+
+    supervisor::spawn_supervised_restarting("retention", move || {
+        let graph = Arc::clone(&graph);
+        let store = Arc::clone(&store);
+        let config = config.clone();
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                max_age_days = config.max_age_days,
+                check_interval_minutes = config.check_interval_minutes,
+                "Retention task started"
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping retention purge");
+                    continue;
+                }
+
+                let run = purge_expired(&graph, &store, &config);
+                if let Err(e) = store.try_run_exclusive("retention", run).await {
+                    tracing::error!(error = %e, "Failed to take the retention lock");
+                }
+            }
+        }
+    })
+}
+
+/// Purge every terminal investigation past `config.max_age_days`, logging
+/// failures and moving on.
+async fn purge_expired(graph: &GraphClient, store: &StoreClient, config: &RetentionConfig) {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(config.max_age_days as i64);
+    let expired = match store.get_terminal_investigations_before(cutoff).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!(error = %e, "Retention task failed to list expired investigations");
+            return;
+        }
+    };
+
+    for id in expired {
+        if let Err(e) = purge_investigation(graph, store, id, false).await {
+            metrics::counter!("retention.purge_failures").increment(1);
+            tracing::error!(
+                investigation_id = %id,
+                error = %e,
+                "Retention purge failed"
+            );
+        }
+    }
+}
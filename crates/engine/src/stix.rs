@@ -0,0 +1,442 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use autosint_common::config::{StixConfig, StixObjectMapping};
+use autosint_common::types::{Claim, Entity, Relationship};
+
+use crate::graph::Subgraph;
+
+/// STIX domain object types an entity kind may be mapped to. All of them carry
+/// `name` and need nothing beyond what an entity provides.
+pub const SUPPORTED_SDO_TYPES: &[&str] = &[
+    "identity",
+    "location",
+    "threat-actor",
+    "intrusion-set",
+    "campaign",
+    "infrastructure",
+    "tool",
+];
+
+/// SDO types with a standard `aliases` property.
+const ALIAS_SDO_TYPES: &[&str] = &[
+    "threat-actor",
+    "intrusion-set",
+    "campaign",
+    "infrastructure",
+    "tool",
+];
+
+/// Custom object type for entity kinds with no mapping.
+const CUSTOM_ENTITY_TYPE: &str = "x-autosint-entity";
+
+/// Namespace for deriving STIX IDs from AutOSINT IDs. Never change it: every
+/// previously exported object would get a new identity downstream.
+const STIX_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6c0f_5a7e_3b1d_4e8a_9f2c_71d4_a5b8_e903);
+
+/// Deterministic STIX ID for an AutOSINT object, so repeated exports of the same
+/// data update objects downstream instead of duplicating them.
+pub fn stix_id(stix_type: &str, id: Uuid) -> String {
+    format!(
+        "{}--{}",
+        stix_type,
+        Uuid::new_v5(&STIX_ID_NAMESPACE, id.as_bytes())
+    )
+}
+
+/// Build a STIX 2.1 bundle from a graph export. Entities become SDOs per the
+/// kind mapping, relationships become `related-to` SROs, and claims become notes
+/// on the objects they reference. `now` stamps objects with no timestamp of
+/// their own.
+pub fn to_bundle(subgraph: &Subgraph, config: &StixConfig, now: DateTime<Utc>) -> Value {
+    let mut objects = Vec::new();
+    let mut ids: HashMap<Uuid, (String, &str)> = HashMap::new();
+
+    for entity in &subgraph.entities {
+        let mapping = config.kind_mappings.get(&entity.kind.to_lowercase());
+        let object = entity_object(entity, mapping);
+        let stix_type = mapping.map_or(CUSTOM_ENTITY_TYPE, |m| m.stix_type.as_str());
+        ids.insert(
+            entity.id.0,
+            (object["id"].as_str().unwrap().into(), stix_type),
+        );
+        objects.push(object);
+    }
+
+    for relationship in &subgraph.relationships {
+        let (Some((source_ref, _)), Some((target_ref, _))) = (
+            ids.get(&relationship.source_entity_id.0),
+            ids.get(&relationship.target_entity_id.0),
+        ) else {
+            continue;
+        };
+        objects.push(relationship_object(
+            relationship,
+            source_ref,
+            target_ref,
+            now,
+        ));
+    }
+
+    for claim in &subgraph.claims {
+        if let Some(note) = claim_note(claim, &ids) {
+            objects.push(note);
+        }
+    }
+
+    json!({
+        "type": "bundle",
+        "id": format!("bundle--{}", Uuid::new_v4()),
+        "objects": objects,
+    })
+}
+
+fn timestamp(dt: &DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn entity_object(entity: &Entity, mapping: Option<&StixObjectMapping>) -> Value {
+    let stix_type = mapping.map_or(CUSTOM_ENTITY_TYPE, |m| m.stix_type.as_str());
+    let updated = timestamp(&entity.last_updated);
+
+    let mut object = Map::new();
+    object.insert("type".into(), json!(stix_type));
+    object.insert("spec_version".into(), json!("2.1"));
+    object.insert("id".into(), json!(stix_id(stix_type, entity.id.0)));
+    object.insert("created".into(), json!(updated));
+    object.insert("modified".into(), json!(updated));
+    object.insert("name".into(), json!(entity.canonical_name));
+    if let Some(summary) = &entity.summary {
+        object.insert("description".into(), json!(summary));
+    }
+    if let Some(class) = mapping.and_then(|m| m.identity_class.as_ref()) {
+        object.insert("identity_class".into(), json!(class));
+    }
+    if !entity.aliases.is_empty() {
+        let key = if ALIAS_SDO_TYPES.contains(&stix_type) {
+            "aliases"
+        } else {
+            "x_autosint_aliases"
+        };
+        object.insert(key.into(), json!(entity.aliases));
+    }
+    if stix_type == "location" {
+        insert_location_fields(&mut object, entity);
+    }
+    object.insert("x_autosint_id".into(), json!(entity.id.to_string()));
+    object.insert("x_autosint_kind".into(), json!(entity.kind));
+
+    Value::Object(object)
+}
+
+/// Carry over coordinates and ISO country codes the processor attached as
+/// freeform properties.
+fn insert_location_fields(object: &mut Map<String, Value>, entity: &Entity) {
+    let latitude = entity.properties.get("latitude").and_then(Value::as_f64);
+    let longitude = entity.properties.get("longitude").and_then(Value::as_f64);
+    if let (Some(lat), Some(lon)) = (latitude, longitude) {
+        object.insert("latitude".into(), json!(lat));
+        object.insert("longitude".into(), json!(lon));
+    }
+
+    let country = ["iso_code", "country_code"]
+        .iter()
+        .filter_map(|key| entity.properties.get(*key).and_then(Value::as_str))
+        .find(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()));
+    if let Some(code) = country {
+        object.insert("country".into(), json!(code.to_uppercase()));
+    }
+}
+
+fn relationship_object(
+    relationship: &Relationship,
+    source_ref: &str,
+    target_ref: &str,
+    now: DateTime<Utc>,
+) -> Value {
+    let stamped = timestamp(&relationship.timestamp.unwrap_or(now));
+
+    let mut object = Map::new();
+    object.insert("type".into(), json!("relationship"));
+    object.insert("spec_version".into(), json!("2.1"));
+    object.insert(
+        "id".into(),
+        json!(stix_id("relationship", relationship.id.0)),
+    );
+    object.insert("created".into(), json!(stamped));
+    object.insert("modified".into(), json!(stamped));
+    object.insert("relationship_type".into(), json!("related-to"));
+    object.insert("description".into(), json!(relationship.description));
+    object.insert("source_ref".into(), json!(source_ref));
+    object.insert("target_ref".into(), json!(target_ref));
+    if let Some(confidence) = relationship.confidence {
+        let scaled = (confidence.clamp(0.0, 1.0) * 100.0).round() as u8;
+        object.insert("confidence".into(), json!(scaled));
+    }
+    if let Some(ts) = &relationship.timestamp {
+        object.insert("start_time".into(), json!(timestamp(ts)));
+    }
+    if let Some(weight) = relationship.weight {
+        object.insert("x_autosint_weight".into(), json!(weight));
+    }
+    if relationship.bidirectional {
+        object.insert("x_autosint_bidirectional".into(), json!(true));
+    }
+    object.insert("x_autosint_id".into(), json!(relationship.id.to_string()));
+
+    Value::Object(object)
+}
+
+/// Note attached to the claim's referenced entities, falling back to its
+/// publisher. `None` if none of them are in the bundle.
+fn claim_note(claim: &Claim, ids: &HashMap<Uuid, (String, &str)>) -> Option<Value> {
+    let mut seen = HashSet::new();
+    let mut object_refs: Vec<&str> = claim
+        .referenced_entity_ids
+        .iter()
+        .filter_map(|id| ids.get(&id.0))
+        .map(|(stix_id, _)| stix_id.as_str())
+        .filter(|id| seen.insert(*id))
+        .collect();
+    let publisher = ids.get(&claim.source_entity_id.0);
+    if object_refs.is_empty() {
+        object_refs.extend(publisher.map(|(stix_id, _)| stix_id.as_str()));
+    }
+    if object_refs.is_empty() {
+        return None;
+    }
+
+    let ingested = timestamp(&claim.ingested_timestamp);
+
+    let mut object = Map::new();
+    object.insert("type".into(), json!("note"));
+    object.insert("spec_version".into(), json!("2.1"));
+    object.insert("id".into(), json!(stix_id("note", claim.id.0)));
+    object.insert("created".into(), json!(ingested));
+    object.insert("modified".into(), json!(ingested));
+    object.insert("content".into(), json!(claim.content));
+    object.insert("object_refs".into(), json!(object_refs));
+    if let Some((publisher_ref, "identity")) = publisher {
+        object.insert("created_by_ref".into(), json!(publisher_ref));
+    }
+    if let Some(link) = &claim.raw_source_link {
+        object.insert(
+            "external_references".into(),
+            json!([{ "source_name": "autosint-source", "url": link }]),
+        );
+    }
+    object.insert(
+        "x_autosint_published".into(),
+        json!(timestamp(&claim.published_timestamp)),
+    );
+    object.insert(
+        "x_autosint_attribution_depth".into(),
+        serde_json::to_value(&claim.attribution_depth).unwrap_or_default(),
+    );
+    object.insert(
+        "x_autosint_information_type".into(),
+        serde_json::to_value(&claim.information_type).unwrap_or_default(),
+    );
+    object.insert("x_autosint_id".into(), json!(claim.id.to_string()));
+
+    Some(Value::Object(object))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autosint_common::types::{AttributionDepth, InformationType};
+    use chrono::TimeZone;
+
+    fn fixture() -> Subgraph {
+        let mut acme = Entity::new("Acme Corp".into(), "organization".into());
+        acme.aliases = vec!["ACME".into()];
+        acme.summary = Some("Defense contractor.".into());
+        let jane = Entity::new("Jane Doe".into(), "person".into());
+        let mut berlin = Entity::new("Berlin".into(), "city".into());
+        berlin.properties.insert("latitude".into(), json!(52.52));
+        berlin.properties.insert("longitude".into(), json!(13.405));
+        berlin.properties.insert("country_code".into(), json!("de"));
+        let vessel = Entity::new("MV Example".into(), "vessel".into());
+        let reuters = Entity::new("Reuters".into(), "organization".into());
+
+        let mut employs = Relationship::new(acme.id, jane.id, "Jane Doe is CEO of Acme.".into());
+        employs.confidence = Some(0.87);
+        let located = Relationship::new(acme.id, berlin.id, "Headquartered in Berlin.".into());
+
+        let mut claim = Claim::new(
+            "Acme chartered MV Example in March.".into(),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            reuters.id,
+        );
+        claim.raw_source_link = Some("https://example.com/acme".into());
+        claim.referenced_entity_ids = vec![acme.id, vessel.id];
+
+        Subgraph {
+            entities: vec![acme, jane, berlin, vessel, reuters],
+            relationships: vec![employs, located],
+            claims: vec![claim],
+        }
+    }
+
+    /// Structural checks from the STIX 2.1 spec: common properties, ID format,
+    /// timestamp format, required per-type properties, and resolvable references.
+    fn assert_valid_bundle(bundle: &Value) {
+        assert_eq!(bundle["type"], "bundle");
+        assert!(bundle["id"].as_str().unwrap().starts_with("bundle--"));
+
+        let objects = bundle["objects"].as_array().unwrap();
+        let ids: HashSet<&str> = objects.iter().map(|o| o["id"].as_str().unwrap()).collect();
+        assert_eq!(ids.len(), objects.len(), "duplicate object ids");
+
+        for object in objects {
+            let stix_type = object["type"].as_str().unwrap();
+            assert!(stix_type
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'));
+            assert_eq!(object["spec_version"], "2.1");
+
+            let id = object["id"].as_str().unwrap();
+            let (prefix, uuid) = id.split_once("--").unwrap();
+            assert_eq!(prefix, stix_type);
+            Uuid::parse_str(uuid).unwrap();
+
+            for field in ["created", "modified"] {
+                let ts = object[field].as_str().unwrap();
+                assert!(ts.ends_with('Z'), "{} not UTC: {}", field, ts);
+                DateTime::parse_from_rfc3339(ts).unwrap();
+            }
+
+            for (key, value) in object.as_object().unwrap() {
+                assert!(
+                    key.chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+                    "invalid property name {}",
+                    key
+                );
+                if key.ends_with("_ref") {
+                    assert!(ids.contains(value.as_str().unwrap()), "dangling {}", key);
+                }
+                if key.ends_with("_refs") {
+                    for r in value.as_array().unwrap() {
+                        assert!(ids.contains(r.as_str().unwrap()), "dangling {}", key);
+                    }
+                }
+            }
+
+            match stix_type {
+                "relationship" => {
+                    assert!(object["relationship_type"].is_string());
+                    assert!(object["source_ref"].is_string());
+                    assert!(object["target_ref"].is_string());
+                }
+                "note" => {
+                    assert!(object["content"].is_string());
+                    assert!(!object["object_refs"].as_array().unwrap().is_empty());
+                }
+                custom if custom.starts_with("x-") => {}
+                sdo => {
+                    assert!(SUPPORTED_SDO_TYPES.contains(&sdo));
+                    assert!(object["name"].is_string());
+                }
+            }
+        }
+    }
+
+    fn find<'a>(bundle: &'a Value, x_autosint_id: &str) -> &'a Value {
+        bundle["objects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|o| o["x_autosint_id"] == x_autosint_id)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bundle_is_structurally_valid() {
+        let subgraph = fixture();
+        let bundle = to_bundle(&subgraph, &StixConfig::default(), Utc::now());
+        assert_valid_bundle(&bundle);
+        assert_eq!(bundle["objects"].as_array().unwrap().len(), 8);
+
+        let [acme, jane, berlin, vessel, reuters] = &subgraph.entities[..] else {
+            unreachable!()
+        };
+
+        let acme_obj = find(&bundle, &acme.id.to_string());
+        assert_eq!(acme_obj["type"], "identity");
+        assert_eq!(acme_obj["identity_class"], "organization");
+        assert_eq!(acme_obj["x_autosint_aliases"], json!(["ACME"]));
+        assert_eq!(
+            find(&bundle, &jane.id.to_string())["identity_class"],
+            "individual"
+        );
+
+        let berlin_obj = find(&bundle, &berlin.id.to_string());
+        assert_eq!(berlin_obj["type"], "location");
+        assert_eq!(berlin_obj["country"], "DE");
+        assert_eq!(berlin_obj["latitude"], 52.52);
+
+        let vessel_obj = find(&bundle, &vessel.id.to_string());
+        assert_eq!(vessel_obj["type"], CUSTOM_ENTITY_TYPE);
+        assert_eq!(vessel_obj["x_autosint_kind"], "vessel");
+
+        let rel = find(&bundle, &subgraph.relationships[0].id.to_string());
+        assert_eq!(rel["description"], "Jane Doe is CEO of Acme.");
+        assert_eq!(rel["source_ref"], acme_obj["id"]);
+        assert_eq!(rel["confidence"], 87);
+
+        let note = find(&bundle, &subgraph.claims[0].id.to_string());
+        assert_eq!(
+            note["object_refs"],
+            json!([acme_obj["id"], vessel_obj["id"]])
+        );
+        assert_eq!(
+            note["created_by_ref"],
+            find(&bundle, &reuters.id.to_string())["id"]
+        );
+        assert_eq!(
+            note["external_references"][0]["url"],
+            "https://example.com/acme"
+        );
+    }
+
+    #[test]
+    fn test_ids_are_stable_across_exports() {
+        let subgraph = fixture();
+        let ids = |bundle: &Value| -> Vec<String> {
+            bundle["objects"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|o| o["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let first = to_bundle(&subgraph, &StixConfig::default(), Utc::now());
+        let second = to_bundle(
+            &subgraph,
+            &StixConfig::default(),
+            Utc::now() + chrono::Duration::hours(1),
+        );
+        assert_eq!(ids(&first), ids(&second));
+        assert_ne!(first["id"], second["id"]);
+
+        let id = stix_id("identity", subgraph.entities[0].id.0);
+        let uuid = Uuid::parse_str(id.split_once("--").unwrap().1).unwrap();
+        assert_eq!(uuid.get_version_num(), 5);
+    }
+
+    #[test]
+    fn test_claim_without_bundled_entities_is_dropped() {
+        let mut subgraph = fixture();
+        subgraph.entities.clear();
+        let bundle = to_bundle(&subgraph, &StixConfig::default(), Utc::now());
+        assert!(bundle["objects"].as_array().unwrap().is_empty());
+    }
+}
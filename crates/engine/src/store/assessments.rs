@@ -1,30 +1,61 @@
-use chrono::Utc;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use pgvector::Vector;
 use uuid::Uuid;
 
-use autosint_common::ids::{AssessmentId, InvestigationId};
-use autosint_common::types::{Assessment, Confidence};
+use autosint_common::ids::{AssessmentId, ClaimId, InvestigationId};
+use autosint_common::types::{
+    Assessment, AssessmentDiff, AssessmentFeedback, Confidence, FeedbackRating, FeedbackSummary,
+};
 
 use super::{StoreClient, StoreError};
 
 impl StoreClient {
-    /// Create a new assessment record with optional embedding for semantic search.
+    /// Create a new assessment record with optional embedding for semantic
+    /// search, and record the claims it cites (see
+    /// [`Assessment::cited_claim_ids`]) in the same transaction.
     pub async fn create_assessment(
         &self,
         assessment: &Assessment,
     ) -> Result<Assessment, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Self::insert_assessment(&mut tx, assessment).await?;
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(assessment.clone())
+    }
+
+    /// Insert an assessment and its cited claims on `conn`, so they can share
+    /// a caller's transaction.
+    pub(super) async fn insert_assessment(
+        conn: &mut sqlx::PgConnection,
+        assessment: &Assessment,
+    ) -> Result<(), StoreError> {
         let entity_refs_json = serde_json::to_value(&assessment.entity_refs).unwrap_or_default();
         let claim_refs_json = serde_json::to_value(&assessment.claim_refs).unwrap_or_default();
         let embedding = assessment
             .embedding
             .as_ref()
             .map(|v| Vector::from(v.clone()));
+        let cited: Vec<Uuid> = assessment
+            .cited_claim_ids()
+            .into_iter()
+            .map(|id| id.0)
+            .collect();
 
         sqlx::query(
             r#"
             INSERT INTO assessments (id, investigation_id, content, confidence,
-                                     entity_refs, claim_refs, embedding, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                                     entity_refs, claim_refs, embedding, tags, created_at,
+                                     supersedes_assessment_id, as_of)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             "#,
         )
         .bind(assessment.id.0)
@@ -34,22 +65,40 @@ impl StoreClient {
         .bind(&entity_refs_json)
         .bind(&claim_refs_json)
         .bind(embedding)
+        .bind(&assessment.tags)
         .bind(assessment.created_at)
-        .execute(&self.pool)
+        .bind(assessment.supersedes_assessment_id.map(|id| id.0))
+        .bind(assessment.as_of)
+        .execute(&mut *conn)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
 
-        Ok(assessment.clone())
+        sqlx::query(
+            r#"
+            INSERT INTO assessment_claims (assessment_id, claim_id)
+            SELECT $1, unnest($2::uuid[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(assessment.id.0)
+        .bind(&cited)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
     }
 
     /// Retrieve an assessment by ID.
     pub async fn get_assessment(&self, id: AssessmentId) -> Result<Assessment, StoreError> {
         let row = sqlx::query_as::<_, AssessmentRow>(
             r#"
-            SELECT id, investigation_id, content, confidence,
-                   entity_refs, claim_refs, created_at
-            FROM assessments
-            WHERE id = $1
+            SELECT a.id, a.investigation_id, a.content, a.confidence,
+                   a.entity_refs, a.claim_refs, a.tags, a.created_at, i.correlation_id,
+                   a.supersedes_assessment_id, a.diff, a.needs_review, a.as_of
+            FROM assessments a
+            LEFT JOIN investigations i ON i.id = a.investigation_id
+            WHERE a.id = $1
             "#,
         )
         .bind(id.0)
@@ -61,27 +110,108 @@ impl StoreClient {
         Ok(row.into())
     }
 
+    /// Every assessment an investigation produced, oldest first.
+    pub async fn get_assessments_by_investigation(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Vec<Assessment>, StoreError> {
+        let rows = sqlx::query_as::<_, AssessmentRow>(
+            r#"
+            SELECT a.id, a.investigation_id, a.content, a.confidence,
+                   a.entity_refs, a.claim_refs, a.tags, a.created_at, i.correlation_id,
+                   a.supersedes_assessment_id, a.diff, a.needs_review, a.as_of
+            FROM assessments a
+            LEFT JOIN investigations i ON i.id = a.investigation_id
+            WHERE a.investigation_id = $1
+            ORDER BY a.created_at, a.id
+            "#,
+        )
+        .bind(investigation_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// The most recent assessment produced by an investigation.
+    pub async fn get_latest_assessment(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Assessment, StoreError> {
+        let row = sqlx::query_as::<_, AssessmentRow>(
+            r#"
+            SELECT a.id, a.investigation_id, a.content, a.confidence,
+                   a.entity_refs, a.claim_refs, a.tags, a.created_at, i.correlation_id,
+                   a.supersedes_assessment_id, a.diff, a.needs_review, a.as_of
+            FROM assessments a
+            LEFT JOIN investigations i ON i.id = a.investigation_id
+            WHERE a.investigation_id = $1
+            ORDER BY a.created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(investigation_id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| {
+            StoreError::NotFound(format!("Assessment for investigation {}", investigation_id))
+        })?;
+
+        Ok(row.into())
+    }
+
+    /// Record what changed since the assessment `id` supersedes.
+    pub async fn set_assessment_diff(
+        &self,
+        id: AssessmentId,
+        diff: &AssessmentDiff,
+    ) -> Result<(), StoreError> {
+        let diff = serde_json::to_value(diff).map_err(|e| StoreError::Query(e.to_string()))?;
+        let result = sqlx::query("UPDATE assessments SET diff = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(diff)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(StoreError::NotFound(format!("Assessment {}", id)));
+        }
+        Ok(())
+    }
+
     /// Semantic search over assessments using pgvector cosine similarity.
     /// Returns assessments with similarity scores, ordered by relevance.
+    /// Only assessments carrying every tag in `tags` are considered (exact match),
+    /// and with `created_before` only those produced by then.
     pub async fn search_assessments(
         &self,
         query_embedding: Vec<f32>,
+        tags: &[String],
+        created_before: Option<DateTime<Utc>>,
         limit: i64,
     ) -> Result<Vec<(Assessment, f64)>, StoreError> {
         let query_vec = Vector::from(query_embedding);
 
         let rows = sqlx::query_as::<_, AssessmentWithScoreRow>(
             r#"
-            SELECT id, investigation_id, content, confidence,
-                   entity_refs, claim_refs, created_at,
-                   1 - (embedding <=> $1::vector) AS score
-            FROM assessments
-            WHERE embedding IS NOT NULL
-            ORDER BY embedding <=> $1::vector
-            LIMIT $2
+            SELECT a.id, a.investigation_id, a.content, a.confidence,
+                   a.entity_refs, a.claim_refs, a.tags, a.created_at, i.correlation_id,
+                   a.supersedes_assessment_id, a.diff, a.needs_review, a.as_of,
+                   1 - (a.embedding <=> $1::vector) AS score
+            FROM assessments a
+            LEFT JOIN investigations i ON i.id = a.investigation_id
+            WHERE a.embedding IS NOT NULL
+              AND a.tags @> $2
+              AND ($3::timestamptz IS NULL OR a.created_at <= $3)
+            ORDER BY a.embedding <=> $1::vector
+            LIMIT $4
             "#,
         )
         .bind(query_vec)
+        .bind(tags)
+        .bind(created_before)
         .bind(limit)
         .fetch_all(&self.pool)
         .await
@@ -98,13 +228,217 @@ impl StoreClient {
                     confidence: row.confidence,
                     entity_refs: row.entity_refs,
                     claim_refs: row.claim_refs,
+                    tags: row.tags,
                     created_at: row.created_at,
+                    correlation_id: row.correlation_id,
+                    supersedes_assessment_id: row.supersedes_assessment_id,
+                    diff: row.diff,
+                    needs_review: row.needs_review,
+                    as_of: row.as_of,
                 }
                 .into();
                 (assessment, score)
             })
             .collect())
     }
+
+    /// Assessments citing the claim `claim_id`, newest first.
+    pub async fn get_assessments_citing_claim(
+        &self,
+        claim_id: ClaimId,
+    ) -> Result<Vec<Assessment>, StoreError> {
+        let rows = sqlx::query_as::<_, AssessmentRow>(
+            r#"
+            SELECT a.id, a.investigation_id, a.content, a.confidence,
+                   a.entity_refs, a.claim_refs, a.tags, a.created_at, i.correlation_id,
+                   a.supersedes_assessment_id, a.diff, a.needs_review, a.as_of
+            FROM assessment_claims ac
+            JOIN assessments a ON a.id = ac.assessment_id
+            LEFT JOIN investigations i ON i.id = a.investigation_id
+            WHERE ac.claim_id = $1
+            ORDER BY a.created_at DESC
+            "#,
+        )
+        .bind(claim_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Number of assessments citing each of `claim_ids` that any cites. With
+    /// `created_before`, only assessments produced by then count.
+    pub async fn count_assessments_citing_claims(
+        &self,
+        claim_ids: &[ClaimId],
+        created_before: Option<DateTime<Utc>>,
+    ) -> Result<HashMap<ClaimId, u64>, StoreError> {
+        let ids: Vec<Uuid> = claim_ids.iter().map(|id| id.0).collect();
+        let rows = sqlx::query_as::<_, (Uuid, i64)>(
+            r#"
+            SELECT ac.claim_id, COUNT(*)
+            FROM assessment_claims ac
+            JOIN assessments a ON a.id = ac.assessment_id
+            WHERE ac.claim_id = ANY($1)
+              AND ($2::timestamptz IS NULL OR a.created_at <= $2)
+            GROUP BY ac.claim_id
+            "#,
+        )
+        .bind(&ids)
+        .bind(created_before)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, count)| (ClaimId::from_uuid(id), count as u64))
+            .collect())
+    }
+
+    /// Flag every assessment citing any of `claim_ids` as needing review.
+    /// Returns the IDs of those newly flagged.
+    pub async fn flag_assessments_citing_claims(
+        &self,
+        claim_ids: &[ClaimId],
+    ) -> Result<Vec<AssessmentId>, StoreError> {
+        let ids: Vec<Uuid> = claim_ids.iter().map(|id| id.0).collect();
+        let flagged: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            UPDATE assessments
+            SET needs_review = true
+            WHERE NOT needs_review
+              AND id IN (SELECT assessment_id FROM assessment_claims WHERE claim_id = ANY($1))
+            RETURNING id
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(flagged.into_iter().map(AssessmentId::from_uuid).collect())
+    }
+
+    /// Record feedback on an assessment.
+    pub async fn create_assessment_feedback(
+        &self,
+        assessment_id: AssessmentId,
+        rating: FeedbackRating,
+        comments: Option<&str>,
+        disputed_claim_ids: &[ClaimId],
+        submitted_by: Option<&str>,
+    ) -> Result<AssessmentFeedback, StoreError> {
+        let disputed: Vec<Uuid> = disputed_claim_ids.iter().map(|id| id.0).collect();
+        let row = sqlx::query_as::<_, FeedbackRow>(
+            r#"
+            INSERT INTO assessment_feedback (assessment_id, rating, comments,
+                                             disputed_claim_ids, submitted_by)
+            SELECT id, $2, $3, $4, $5 FROM assessments WHERE id = $1
+            RETURNING id, assessment_id, rating, comments, disputed_claim_ids,
+                      submitted_by, created_at
+            "#,
+        )
+        .bind(assessment_id.0)
+        .bind(rating.as_db_str())
+        .bind(comments)
+        .bind(&disputed)
+        .bind(submitted_by)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("Assessment {}", assessment_id)))?;
+
+        Ok(row.into())
+    }
+
+    /// Feedback on an assessment, oldest first.
+    pub async fn list_assessment_feedback(
+        &self,
+        assessment_id: AssessmentId,
+    ) -> Result<Vec<AssessmentFeedback>, StoreError> {
+        let rows = sqlx::query_as::<_, FeedbackRow>(
+            r#"
+            SELECT id, assessment_id, rating, comments, disputed_claim_ids,
+                   submitted_by, created_at
+            FROM assessment_feedback
+            WHERE assessment_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(assessment_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Feedback counts for each of `ids` that has any feedback.
+    pub async fn assessment_feedback_summaries(
+        &self,
+        ids: &[AssessmentId],
+    ) -> Result<HashMap<AssessmentId, FeedbackSummary>, StoreError> {
+        let ids: Vec<Uuid> = ids.iter().map(|id| id.0).collect();
+        let rows = sqlx::query_as::<_, (Uuid, String, i64)>(
+            r#"
+            SELECT assessment_id, rating, COUNT(*)
+            FROM assessment_feedback
+            WHERE assessment_id = ANY($1)
+            GROUP BY assessment_id, rating
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let mut summaries: HashMap<AssessmentId, FeedbackSummary> = HashMap::new();
+        for (id, rating, count) in rows {
+            summaries
+                .entry(AssessmentId::from_uuid(id))
+                .or_default()
+                .add(parse_rating(&rating), count as u32);
+        }
+        Ok(summaries)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct FeedbackRow {
+    id: i64,
+    assessment_id: Uuid,
+    rating: String,
+    comments: Option<String>,
+    disputed_claim_ids: Vec<Uuid>,
+    submitted_by: Option<String>,
+    created_at: chrono::DateTime<Utc>,
+}
+
+impl From<FeedbackRow> for AssessmentFeedback {
+    fn from(row: FeedbackRow) -> Self {
+        Self {
+            id: row.id,
+            assessment_id: AssessmentId::from_uuid(row.assessment_id),
+            rating: parse_rating(&row.rating),
+            comments: row.comments,
+            disputed_claim_ids: row
+                .disputed_claim_ids
+                .into_iter()
+                .map(ClaimId::from_uuid)
+                .collect(),
+            submitted_by: row.submitted_by,
+            created_at: row.created_at,
+        }
+    }
+}
+
+fn parse_rating(s: &str) -> FeedbackRating {
+    s.parse().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Unknown feedback rating, defaulting to Partially");
+        FeedbackRating::Partially
+    })
 }
 
 /// Internal row type for sqlx deserialization.
@@ -116,7 +450,13 @@ struct AssessmentRow {
     confidence: String,
     entity_refs: serde_json::Value,
     claim_refs: serde_json::Value,
+    tags: Vec<String>,
     created_at: chrono::DateTime<Utc>,
+    correlation_id: Option<String>,
+    supersedes_assessment_id: Option<Uuid>,
+    diff: Option<serde_json::Value>,
+    needs_review: bool,
+    as_of: Option<chrono::DateTime<Utc>>,
 }
 
 /// Row type with similarity score for search results.
@@ -128,7 +468,13 @@ struct AssessmentWithScoreRow {
     confidence: String,
     entity_refs: serde_json::Value,
     claim_refs: serde_json::Value,
+    tags: Vec<String>,
     created_at: chrono::DateTime<Utc>,
+    correlation_id: Option<String>,
+    supersedes_assessment_id: Option<Uuid>,
+    diff: Option<serde_json::Value>,
+    needs_review: bool,
+    as_of: Option<chrono::DateTime<Utc>>,
     score: f64,
 }
 
@@ -145,7 +491,13 @@ impl From<AssessmentRow> for Assessment {
             entity_refs,
             claim_refs,
             embedding: None, // Not retrieved in queries (large)
+            tags: row.tags,
             created_at: row.created_at,
+            supersedes_assessment_id: row.supersedes_assessment_id.map(AssessmentId::from_uuid),
+            diff: row.diff.and_then(|d| serde_json::from_value(d).ok()),
+            correlation_id: row.correlation_id,
+            needs_review: row.needs_review,
+            as_of: row.as_of,
         }
     }
 }
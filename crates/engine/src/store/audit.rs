@@ -0,0 +1,229 @@
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use uuid::Uuid;
+
+use autosint_common::ids::{InvestigationId, WorkOrderId};
+use autosint_common::types::{AuditActor, AuditActorType, AuditEntry, AuditRecord};
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Append audit records in one statement.
+    pub async fn insert_audit_records(&self, records: &[AuditRecord]) -> Result<(), StoreError> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let recorded_at: Vec<DateTime<Utc>> = records.iter().map(|r| r.recorded_at).collect();
+        let actor_types: Vec<&str> = records
+            .iter()
+            .map(|r| r.actor.actor_type.as_db_str())
+            .collect();
+        let actor_ids: Vec<Option<&str>> = records
+            .iter()
+            .map(|r| r.actor.actor_id.as_deref())
+            .collect();
+        let investigation_ids: Vec<Option<Uuid>> = records
+            .iter()
+            .map(|r| r.investigation_id.map(|id| id.0))
+            .collect();
+        let work_order_ids: Vec<Option<Uuid>> = records
+            .iter()
+            .map(|r| r.work_order_id.map(|id| id.0))
+            .collect();
+        let operations: Vec<&str> = records.iter().map(|r| r.operation.as_str()).collect();
+        let target_types: Vec<&str> = records.iter().map(|r| r.target_type.as_str()).collect();
+        let target_ids: Vec<Option<&str>> =
+            records.iter().map(|r| r.target_id.as_deref()).collect();
+        let digests: Vec<serde_json::Value> = records.iter().map(|r| r.digest.clone()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (
+                recorded_at, actor_type, actor_id, investigation_id, work_order_id,
+                operation, target_type, target_id, digest
+            )
+            SELECT * FROM UNNEST(
+                $1::timestamptz[], $2::text[], $3::text[], $4::uuid[], $5::uuid[],
+                $6::text[], $7::text[], $8::text[], $9::jsonb[]
+            )
+            "#,
+        )
+        .bind(&recorded_at)
+        .bind(&actor_types)
+        .bind(&actor_ids)
+        .bind(&investigation_ids)
+        .bind(&work_order_ids)
+        .bind(&operations)
+        .bind(&target_types)
+        .bind(&target_ids)
+        .bind(&digests)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Create the audit log partition for the month containing `at`, if it
+    /// does not exist yet.
+    pub async fn ensure_audit_partition(&self, at: DateTime<Utc>) -> Result<(), StoreError> {
+        let (start, end) = month_bounds(at);
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} PARTITION OF audit_log FOR VALUES FROM ('{}') TO ('{}')",
+            audit_partition_name(at),
+            start.to_rfc3339(),
+            end.to_rfc3339(),
+        );
+        sqlx::query(&sql)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// A page of audit entries matching `filter`, newest first.
+    pub async fn list_audit_entries(&self, filter: &AuditFilter) -> Result<AuditPage, StoreError> {
+        let mut rows = sqlx::query_as::<_, AuditRow>(
+            r#"
+            SELECT id, recorded_at, actor_type, actor_id, investigation_id, work_order_id,
+                   operation, target_type, target_id, digest
+            FROM audit_log
+            WHERE ($1::bigint IS NULL OR id < $1)
+              AND ($2::text IS NULL OR target_id = $2)
+              AND ($3::text IS NULL OR actor_type = $3)
+              AND ($4::text IS NULL OR actor_id = $4)
+              AND ($5::text IS NULL OR operation = $5)
+              AND ($6::timestamptz IS NULL OR recorded_at >= $6)
+              AND ($7::timestamptz IS NULL OR recorded_at < $7)
+            ORDER BY id DESC
+            LIMIT $8
+            "#,
+        )
+        .bind(filter.before)
+        .bind(filter.target_id.as_deref())
+        .bind(filter.actor_type.map(|a| a.as_db_str()))
+        .bind(filter.actor_id.as_deref())
+        .bind(filter.operation.as_deref())
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.limit + 1)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let next_before = if rows.len() as i64 > filter.limit {
+            rows.truncate(filter.limit as usize);
+            rows.last().map(|r| r.id)
+        } else {
+            None
+        };
+        Ok(AuditPage {
+            entries: rows.into_iter().filter_map(AuditRow::into_entry).collect(),
+            next_before,
+        })
+    }
+}
+
+/// Filters for `list_audit_entries`.
+#[derive(Clone, Debug)]
+pub struct AuditFilter {
+    pub target_id: Option<String>,
+    pub actor_type: Option<AuditActorType>,
+    pub actor_id: Option<String>,
+    pub operation: Option<String>,
+    /// Only entries recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries recorded before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries before this entry ID (the previous page's `next_before`).
+    pub before: Option<i64>,
+    pub limit: i64,
+}
+
+impl Default for AuditFilter {
+    fn default() -> Self {
+        Self {
+            target_id: None,
+            actor_type: None,
+            actor_id: None,
+            operation: None,
+            since: None,
+            until: None,
+            before: None,
+            limit: 100,
+        }
+    }
+}
+
+/// A page of the audit log.
+#[derive(Clone, Debug)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// Set when older entries may follow; pass back as `AuditFilter::before`.
+    pub next_before: Option<i64>,
+}
+
+/// `audit_log_YYYY_MM`: the partition holding `at`'s month.
+fn audit_partition_name(at: DateTime<Utc>) -> String {
+    format!("audit_log_{:04}_{:02}", at.year(), at.month())
+}
+
+/// Start of `at`'s month and of the month after, in UTC.
+fn month_bounds(at: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = NaiveDate::from_ymd_opt(at.year(), at.month(), 1).expect("first of month");
+    let end = start
+        .checked_add_months(chrono::Months::new(1))
+        .expect("month in range");
+    let midnight = |d: NaiveDate| Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).expect("midnight"));
+    (midnight(start), midnight(end))
+}
+
+/// Internal row type for sqlx deserialization.
+#[derive(sqlx::FromRow)]
+struct AuditRow {
+    id: i64,
+    recorded_at: DateTime<Utc>,
+    actor_type: String,
+    actor_id: Option<String>,
+    investigation_id: Option<Uuid>,
+    work_order_id: Option<Uuid>,
+    operation: String,
+    target_type: String,
+    target_id: Option<String>,
+    digest: serde_json::Value,
+}
+
+impl AuditRow {
+    fn into_entry(self) -> Option<AuditEntry> {
+        let actor_type = self
+            .actor_type
+            .parse()
+            .map_err(|e| tracing::warn!(error = %e, "Unknown audit actor type, skipping"))
+            .ok()?;
+        Some(AuditEntry {
+            id: self.id,
+            record: AuditRecord {
+                actor: AuditActor::new(actor_type, self.actor_id),
+                investigation_id: self.investigation_id.map(InvestigationId::from_uuid),
+                work_order_id: self.work_order_id.map(WorkOrderId::from_uuid),
+                operation: self.operation,
+                target_type: self.target_type,
+                target_id: self.target_id,
+                digest: self.digest,
+                recorded_at: self.recorded_at,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_partition_bounds() {
+        let at = Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(audit_partition_name(at), "audit_log_2026_12");
+        let (start, end) = month_bounds(at);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+    }
+}
@@ -0,0 +1,83 @@
+use autosint_common::ids::ClaimId;
+
+use super::{StoreClient, StoreError};
+use crate::graph::ClaimMerge;
+
+impl StoreClient {
+    /// Record a finished claim merge and move the assessments citing the
+    /// source onto the target. Earlier merges into the source are pointed at
+    /// the target too. Returns the assessments moved.
+    pub async fn record_claim_merge(
+        &self,
+        merge: &ClaimMerge,
+        rationale: &str,
+    ) -> Result<u64, StoreError> {
+        let source_id = merge.source.id.0;
+        let target_id = merge.target.id.0;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO claim_merges (source_id, target_id, rationale, source_claim, target_claim)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (source_id) DO UPDATE
+                SET target_id = $2, rationale = $3, source_claim = $4,
+                    target_claim = $5, merged_at = now()
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .bind(rationale)
+        .bind(serde_json::to_value(&merge.source).unwrap_or_default())
+        .bind(serde_json::to_value(&merge.target).unwrap_or_default())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        sqlx::query("UPDATE claim_merges SET target_id = $2 WHERE target_id = $1")
+            .bind(source_id)
+            .bind(target_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let moved = sqlx::query(
+            r#"
+            INSERT INTO assessment_claims (assessment_id, claim_id)
+            SELECT assessment_id, $2 FROM assessment_claims WHERE claim_id = $1
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        sqlx::query("DELETE FROM assessment_claims WHERE claim_id = $1")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(moved.rows_affected())
+    }
+
+    /// The claim `id` was merged into, if it was merged away.
+    pub async fn claim_merge_target(&self, id: ClaimId) -> Result<Option<ClaimId>, StoreError> {
+        let target = sqlx::query_scalar::<_, uuid::Uuid>(
+            "SELECT target_id FROM claim_merges WHERE source_id = $1",
+        )
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(target.map(ClaimId::from_uuid))
+    }
+}
@@ -0,0 +1,330 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use autosint_common::ids::{EnrichmentRequestId, InvestigationId, SourceDocumentId, WorkOrderId};
+use autosint_common::types::{EnrichmentRequest, EnrichmentStatus, SourceDocument, WorkOrder};
+
+use super::{StoreClient, StoreError};
+
+const REQUEST_COLUMNS: &str = "id, provider, payload, investigation_id, work_order_id, ingest, \
+     status, error, source_document_id, ingest_work_order_id, created_at, expires_at, \
+     completed_at";
+
+/// What a callback delivered: a result document (and the work order that
+/// extracts it), or the provider's failure.
+pub enum EnrichmentOutcome<'a> {
+    Completed {
+        document: &'a SourceDocument,
+        ingest_work_order: Option<&'a WorkOrder>,
+    },
+    Failed {
+        error: &'a str,
+    },
+}
+
+impl StoreClient {
+    /// Record a pending request, looked up later by `token_hash`. Returns None,
+    /// writing nothing, if the investigation already has
+    /// `max_per_investigation` requests.
+    pub async fn create_enrichment_request(
+        &self,
+        request: &EnrichmentRequest,
+        token_hash: &str,
+        max_per_investigation: u32,
+    ) -> Result<Option<EnrichmentRequest>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let locked: Option<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM investigations WHERE id = $1 FOR UPDATE")
+                .bind(request.investigation_id.0)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Query(e.to_string()))?;
+        if locked.is_none() {
+            return Err(StoreError::NotFound(format!(
+                "Investigation {}",
+                request.investigation_id
+            )));
+        }
+
+        let (requests,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM enrichment_requests WHERE investigation_id = $1")
+                .bind(request.investigation_id.0)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Query(e.to_string()))?;
+        if requests >= i64::from(max_per_investigation) {
+            return Ok(None);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO enrichment_requests (id, provider, payload, investigation_id,
+                                             work_order_id, ingest, token_hash, status,
+                                             created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(request.id.0)
+        .bind(&request.provider)
+        .bind(&request.payload)
+        .bind(request.investigation_id.0)
+        .bind(request.work_order_id.map(|id| id.0))
+        .bind(request.ingest)
+        .bind(token_hash)
+        .bind(request.status.as_db_str())
+        .bind(request.created_at)
+        .bind(request.expires_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(Some(request.clone()))
+    }
+
+    /// The request a callback token was issued for.
+    pub async fn get_enrichment_request_by_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<EnrichmentRequest, StoreError> {
+        let row = sqlx::query_as::<_, EnrichmentRequestRow>(&format!(
+            "SELECT {}, NULL::TEXT AS result_excerpt FROM enrichment_requests WHERE token_hash = $1",
+            REQUEST_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound("Enrichment callback token".into()))?;
+        Ok(row.into())
+    }
+
+    /// Move a pending request to `status` (`Failed` or `Expired`) with
+    /// `error`. False if it had already left pending.
+    pub async fn close_enrichment_request(
+        &self,
+        id: EnrichmentRequestId,
+        status: EnrichmentStatus,
+        error: Option<&str>,
+    ) -> Result<bool, StoreError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE enrichment_requests
+            SET status = $2, error = $3, completed_at = now()
+            WHERE id = $1
+              AND status = 'pending'
+            "#,
+        )
+        .bind(id.0)
+        .bind(status.as_db_str())
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Record a callback's outcome, storing its document and ingest work
+    /// order in the same transaction. Returns false, writing nothing, unless
+    /// the request was still pending and unexpired: a token is spent exactly
+    /// once, however many deliveries race for it.
+    pub async fn complete_enrichment_request(
+        &self,
+        id: EnrichmentRequestId,
+        outcome: &EnrichmentOutcome<'_>,
+    ) -> Result<bool, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let (status, error) = match outcome {
+            EnrichmentOutcome::Completed { .. } => (EnrichmentStatus::Completed, None),
+            EnrichmentOutcome::Failed { error } => (EnrichmentStatus::Failed, Some(*error)),
+        };
+        let claimed = sqlx::query(
+            r#"
+            UPDATE enrichment_requests
+            SET status = $2, error = $3, completed_at = now()
+            WHERE id = $1
+              AND status = 'pending'
+              AND expires_at > now()
+            "#,
+        )
+        .bind(id.0)
+        .bind(status.as_db_str())
+        .bind(error)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        if let EnrichmentOutcome::Completed {
+            document,
+            ingest_work_order,
+        } = outcome
+        {
+            Self::insert_source_document(&mut *tx, document).await?;
+            if let Some(work_order) = ingest_work_order {
+                Self::insert_work_order(&mut tx, work_order).await?;
+            }
+            sqlx::query(
+                r#"
+                UPDATE enrichment_requests
+                SET source_document_id = $2, ingest_work_order_id = $3
+                WHERE id = $1
+                "#,
+            )
+            .bind(id.0)
+            .bind(document.id.0)
+            .bind(ingest_work_order.map(|wo| wo.id.0))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(true)
+    }
+
+    /// An investigation's requests, oldest first, each finished one with the
+    /// first `excerpt_chars` characters of its result.
+    pub async fn list_enrichment_requests(
+        &self,
+        investigation_id: InvestigationId,
+        excerpt_chars: usize,
+    ) -> Result<Vec<EnrichmentRequest>, StoreError> {
+        self.expire_enrichment_requests(investigation_id).await?;
+        let rows = sqlx::query_as::<_, EnrichmentRequestRow>(
+            r#"
+            SELECT r.id, r.provider, r.payload, r.investigation_id, r.work_order_id, r.ingest,
+                   r.status, r.error, r.source_document_id, r.ingest_work_order_id,
+                   r.created_at, r.expires_at, r.completed_at,
+                   LEFT(d.content, $2) AS result_excerpt
+            FROM enrichment_requests r
+            LEFT JOIN source_documents d ON d.id = r.source_document_id
+            WHERE r.investigation_id = $1
+            ORDER BY r.created_at
+            "#,
+        )
+        .bind(investigation_id.0)
+        .bind(excerpt_chars as i32)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Finished requests the Analyst hasn't been shown yet, marked shown.
+    /// Requests past their expiry are closed as expired first, so they are
+    /// reported too.
+    pub async fn take_unsurfaced_enrichment_results(
+        &self,
+        investigation_id: InvestigationId,
+        excerpt_chars: usize,
+    ) -> Result<Vec<EnrichmentRequest>, StoreError> {
+        self.expire_enrichment_requests(investigation_id).await?;
+        let rows = sqlx::query_as::<_, EnrichmentRequestRow>(
+            r#"
+            WITH surfaced AS (
+                UPDATE enrichment_requests
+                SET surfaced_at = now()
+                WHERE investigation_id = $1
+                  AND status <> 'pending'
+                  AND surfaced_at IS NULL
+                RETURNING *
+            )
+            SELECT s.id, s.provider, s.payload, s.investigation_id, s.work_order_id, s.ingest,
+                   s.status, s.error, s.source_document_id, s.ingest_work_order_id,
+                   s.created_at, s.expires_at, s.completed_at,
+                   LEFT(d.content, $2) AS result_excerpt
+            FROM surfaced s
+            LEFT JOIN source_documents d ON d.id = s.source_document_id
+            ORDER BY s.created_at
+            "#,
+        )
+        .bind(investigation_id.0)
+        .bind(excerpt_chars as i32)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Close the investigation's pending requests whose tokens have expired.
+    async fn expire_enrichment_requests(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            UPDATE enrichment_requests
+            SET status = 'expired', completed_at = now()
+            WHERE investigation_id = $1
+              AND status = 'pending'
+              AND expires_at <= now()
+            "#,
+        )
+        .bind(investigation_id.0)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Internal row type for sqlx deserialization.
+#[derive(sqlx::FromRow)]
+struct EnrichmentRequestRow {
+    id: Uuid,
+    provider: String,
+    payload: Value,
+    investigation_id: Uuid,
+    work_order_id: Option<Uuid>,
+    ingest: bool,
+    status: String,
+    error: Option<String>,
+    source_document_id: Option<Uuid>,
+    ingest_work_order_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    result_excerpt: Option<String>,
+}
+
+impl From<EnrichmentRequestRow> for EnrichmentRequest {
+    fn from(row: EnrichmentRequestRow) -> Self {
+        Self {
+            id: EnrichmentRequestId::from_uuid(row.id),
+            provider: row.provider,
+            payload: row.payload,
+            investigation_id: InvestigationId::from_uuid(row.investigation_id),
+            work_order_id: row.work_order_id.map(WorkOrderId::from_uuid),
+            ingest: row.ingest,
+            status: row.status.parse().unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Unknown enrichment status, defaulting to Failed");
+                EnrichmentStatus::Failed
+            }),
+            error: row.error,
+            source_document_id: row.source_document_id.map(SourceDocumentId::from_uuid),
+            ingest_work_order_id: row.ingest_work_order_id.map(WorkOrderId::from_uuid),
+            result_excerpt: row.result_excerpt,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
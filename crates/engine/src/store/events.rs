@@ -0,0 +1,210 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use autosint_common::ids::InvestigationId;
+use autosint_common::types::{EventPayload, InvestigationEvent, InvestigationEventKind};
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Append an event to an investigation's event log.
+    pub async fn record_investigation_event(
+        &self,
+        investigation_id: InvestigationId,
+        kind: InvestigationEventKind,
+        payload: &serde_json::Value,
+    ) -> Result<(), StoreError> {
+        Self::insert_investigation_event(&self.pool, investigation_id, kind, payload).await
+    }
+
+    /// Insert an event with `executor`, so it can share a caller's transaction.
+    pub(super) async fn insert_investigation_event<'e, E>(
+        executor: E,
+        investigation_id: InvestigationId,
+        kind: InvestigationEventKind,
+        payload: &serde_json::Value,
+    ) -> Result<(), StoreError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO investigation_events (investigation_id, kind, payload)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(investigation_id.0)
+        .bind(kind.as_db_str())
+        .bind(payload)
+        .execute(executor)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Insert a typed event with `executor`, so it can share a caller's transaction.
+    pub(super) async fn insert_event_payload<'e, E, P>(
+        executor: E,
+        investigation_id: InvestigationId,
+        payload: &P,
+    ) -> Result<(), StoreError>
+    where
+        E: sqlx::PgExecutor<'e>,
+        P: EventPayload,
+    {
+        let value = serde_json::to_value(payload).map_err(|e| StoreError::Query(e.to_string()))?;
+        Self::insert_investigation_event(executor, investigation_id, payload.kind(), &value).await
+    }
+
+    /// A page of an investigation's events in the order they were recorded,
+    /// after `filter.after` and within `filter.since..filter.until`. Events of
+    /// unknown kinds are skipped (but still advance the page).
+    pub async fn get_investigation_timeline(
+        &self,
+        investigation_id: InvestigationId,
+        filter: &TimelineFilter,
+    ) -> Result<TimelinePage, StoreError> {
+        let mut rows = sqlx::query_as::<_, InvestigationEventRow>(
+            r#"
+            SELECT id, investigation_id, kind, payload, created_at
+            FROM investigation_events
+            WHERE investigation_id = $1
+              AND id > $2
+              AND ($3::timestamptz IS NULL OR created_at >= $3)
+              AND ($4::timestamptz IS NULL OR created_at < $4)
+            ORDER BY id
+            LIMIT $5
+            "#,
+        )
+        .bind(investigation_id.0)
+        .bind(filter.after.unwrap_or(0))
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.limit + 1)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let next_after = if rows.len() as i64 > filter.limit {
+            rows.truncate(filter.limit as usize);
+            rows.last().map(|r| r.id)
+        } else {
+            None
+        };
+        Ok(TimelinePage {
+            events: rows
+                .into_iter()
+                .filter_map(InvestigationEventRow::into_event)
+                .collect(),
+            next_after,
+        })
+    }
+
+    /// An investigation's events, oldest first. Events of unknown kinds are skipped.
+    pub async fn get_investigation_events(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Vec<InvestigationEvent>, StoreError> {
+        let rows = sqlx::query_as::<_, InvestigationEventRow>(
+            r#"
+            SELECT id, investigation_id, kind, payload, created_at
+            FROM investigation_events
+            WHERE investigation_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(investigation_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(InvestigationEventRow::into_event)
+            .collect())
+    }
+
+    /// When the Analyst session before the latest one started — the watermark
+    /// for what changed since the Analyst's last cycle. Uses the session's
+    /// recorded `started_at`, falling back to when its event was written.
+    /// `None` until a second session has started.
+    pub async fn previous_analyst_session_start(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Option<DateTime<Utc>>, StoreError> {
+        sqlx::query_scalar::<_, DateTime<Utc>>(
+            r#"
+            SELECT COALESCE((payload->>'started_at')::timestamptz, created_at)
+            FROM investigation_events
+            WHERE investigation_id = $1 AND kind = $2
+            ORDER BY created_at DESC, id DESC
+            OFFSET 1
+            LIMIT 1
+            "#,
+        )
+        .bind(investigation_id.0)
+        .bind(InvestigationEventKind::AnalystSessionStarted.as_db_str())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))
+    }
+}
+
+/// Filters for `get_investigation_timeline`.
+#[derive(Clone, Debug)]
+pub struct TimelineFilter {
+    /// Only events recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only events recorded before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only events after this event ID (the previous page's `next_after`).
+    pub after: Option<i64>,
+    pub limit: i64,
+}
+
+impl Default for TimelineFilter {
+    fn default() -> Self {
+        Self {
+            since: None,
+            until: None,
+            after: None,
+            limit: 100,
+        }
+    }
+}
+
+/// A page of an investigation's timeline.
+#[derive(Clone, Debug)]
+pub struct TimelinePage {
+    pub events: Vec<InvestigationEvent>,
+    /// Set when more events may follow; pass back as `TimelineFilter::after`.
+    pub next_after: Option<i64>,
+}
+
+/// Internal row type for sqlx deserialization.
+#[derive(sqlx::FromRow)]
+struct InvestigationEventRow {
+    id: i64,
+    investigation_id: Uuid,
+    kind: String,
+    payload: serde_json::Value,
+    created_at: DateTime<Utc>,
+}
+
+impl InvestigationEventRow {
+    fn into_event(self) -> Option<InvestigationEvent> {
+        let kind = self
+            .kind
+            .parse()
+            .map_err(|e| tracing::warn!(error = %e, "Unknown investigation event kind, skipping"))
+            .ok()?;
+        Some(InvestigationEvent {
+            id: self.id,
+            investigation_id: InvestigationId::from_uuid(self.investigation_id),
+            kind,
+            payload: self.payload,
+            created_at: self.created_at,
+        })
+    }
+}
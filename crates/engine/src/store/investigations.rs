@@ -1,8 +1,13 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use autosint_common::ids::InvestigationId;
-use autosint_common::types::{Investigation, InvestigationStatus};
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{
+    cycle_efficiency, CycleMetrics, Investigation, InvestigationEventKind, InvestigationResumed,
+    InvestigationStatus, InvestigationSuspended, OutboundFootprint, StatusChanged,
+    WorkOrderPriority,
+};
 
 use super::{StoreClient, StoreError};
 
@@ -12,10 +17,26 @@ impl StoreClient {
         &self,
         investigation: &Investigation,
     ) -> Result<Investigation, StoreError> {
+        Self::insert_investigation(&self.pool, investigation).await?;
+        Ok(investigation.clone())
+    }
+
+    /// Insert an investigation with `executor`, so it can share a caller's
+    /// transaction.
+    pub(super) async fn insert_investigation<'e, E>(
+        executor: E,
+        investigation: &Investigation,
+    ) -> Result<(), StoreError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
         sqlx::query(
             r#"
-            INSERT INTO investigations (id, prompt, status, parent_investigation_id, cycle_count, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO investigations (id, prompt, status, parent_investigation_id, cycle_count,
+                                        created_at, tags, max_cycles, focus_entities, strictness,
+                                        correlation_id, priority_cap, canary, prompt_hashes,
+                                        fetch_budget, as_of)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             "#,
         )
         .bind(investigation.id.0)
@@ -24,11 +45,25 @@ impl StoreClient {
         .bind(investigation.parent_investigation_id.map(|id| id.0))
         .bind(investigation.cycle_count)
         .bind(investigation.created_at)
-        .execute(&self.pool)
+        .bind(&investigation.tags)
+        .bind(investigation.max_cycles.map(|n| n as i32))
+        .bind(&investigation.focus_entities)
+        .bind(investigation.strictness.map(|s| s.as_db_str()))
+        .bind(&investigation.correlation_id)
+        .bind(investigation.priority_cap.map(|p| p.as_db_int()))
+        .bind(investigation.canary)
+        .bind(serde_json::to_value(&investigation.prompt_hashes).unwrap_or_default())
+        .bind(
+            investigation
+                .fetch_budget
+                .map(|b| serde_json::to_value(b).unwrap_or_default()),
+        )
+        .bind(investigation.as_of)
+        .execute(executor)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
 
-        Ok(investigation.clone())
+        Ok(())
     }
 
     /// Retrieve an investigation by ID.
@@ -39,7 +74,9 @@ impl StoreClient {
         let row = sqlx::query_as::<_, InvestigationRow>(
             r#"
             SELECT id, prompt, status, parent_investigation_id, cycle_count,
-                   created_at, completed_at, suspended_reason, suspended_at, resume_from
+                   created_at, completed_at, suspended_reason, suspended_at, resume_from, tags,
+                   max_cycles, focus_entities, strictness, correlation_id, priority_cap, canary,
+                   prompt_hashes, fetch_budget, footprint, cycle_metrics, version, as_of
             FROM investigations
             WHERE id = $1
             "#,
@@ -54,13 +91,14 @@ impl StoreClient {
     }
 
     /// Update investigation status. Optionally increments cycle_count.
-    /// Sets completed_at when transitioning to a terminal state.
+    /// Sets completed_at when transitioning to a terminal state. A change of
+    /// status is logged as a `status_changed` event, and returned.
     pub async fn update_investigation_status(
         &self,
         id: InvestigationId,
         status: &InvestigationStatus,
         increment_cycle: bool,
-    ) -> Result<(), StoreError> {
+    ) -> Result<Option<StatusChanged>, StoreError> {
         let completed_at = if status.is_terminal() {
             Some(Utc::now())
         } else {
@@ -69,6 +107,13 @@ impl StoreClient {
 
         let cycle_increment = if increment_cycle { 1 } else { 0 };
 
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        let from = Self::lock_investigation_status(&mut tx, id).await?;
+
         sqlx::query(
             r#"
             UPDATE investigations
@@ -82,20 +127,90 @@ impl StoreClient {
         .bind(status.as_db_str())
         .bind(cycle_increment)
         .bind(completed_at)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
 
+        let change = from
+            .filter(|from| from != status)
+            .map(|from| StatusChanged { from, to: *status });
+        if let Some(change) = &change {
+            Self::insert_event_payload(&mut *tx, id, change).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(change)
+    }
+
+    /// Lock an investigation's row for the rest of `tx` and read its status.
+    /// None if it doesn't exist (or has a status this build doesn't know).
+    async fn lock_investigation_status(
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        id: InvestigationId,
+    ) -> Result<Option<InvestigationStatus>, StoreError> {
+        let status: Option<String> =
+            sqlx::query_scalar("SELECT status FROM investigations WHERE id = $1 FOR UPDATE")
+                .bind(id.0)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(status.and_then(|s| s.parse().ok()))
+    }
+
+    /// Record the summed footprint of an investigation's work orders.
+    pub async fn set_investigation_footprint(
+        &self,
+        id: InvestigationId,
+        footprint: &OutboundFootprint,
+    ) -> Result<(), StoreError> {
+        sqlx::query("UPDATE investigations SET footprint = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(serde_json::to_value(footprint).unwrap_or_default())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Record the per-cycle aggregates of an investigation's work orders,
+    /// replacing those recorded before.
+    pub async fn set_investigation_cycles(
+        &self,
+        id: InvestigationId,
+        cycles: &[CycleMetrics],
+    ) -> Result<(), StoreError> {
+        sqlx::query("UPDATE investigations SET cycle_metrics = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(serde_json::to_value(cycles).unwrap_or_default())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
         Ok(())
     }
 
-    /// Suspend an investigation with reason and resume point.
+    /// Suspend an investigation with reason and resume point. The reason is
+    /// sanitized before storage since it may carry raw error text. Logged as
+    /// an `investigation_suspended` event, which is returned.
     pub async fn suspend_investigation(
         &self,
         id: InvestigationId,
         reason: &str,
         resume_from: &str,
-    ) -> Result<(), StoreError> {
+    ) -> Result<Option<InvestigationSuspended>, StoreError> {
+        let reason = sanitize_error(reason);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        let from = Self::lock_investigation_status(&mut tx, id).await?;
+
         sqlx::query(
             r#"
             UPDATE investigations
@@ -107,18 +222,55 @@ impl StoreClient {
             "#,
         )
         .bind(id.0)
-        .bind(reason)
+        .bind(&reason)
         .bind(Utc::now())
         .bind(resume_from)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
 
-        Ok(())
+        let suspension = from.map(|from| InvestigationSuspended {
+            from,
+            reason,
+            resume_from: resume_from.to_string(),
+        });
+        if let Some(suspension) = &suspension {
+            Self::insert_event_payload(&mut *tx, id, suspension).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(suspension)
     }
 
-    /// Clear suspension state (when resuming).
-    pub async fn clear_suspension(&self, id: InvestigationId) -> Result<(), StoreError> {
+    /// Clear suspension state (when resuming). Logged as an
+    /// `investigation_resumed` event carrying the cleared suspension, which
+    /// is returned. None if the investigation wasn't suspended.
+    pub async fn clear_suspension(
+        &self,
+        id: InvestigationId,
+    ) -> Result<Option<InvestigationResumed>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let suspension = sqlx::query_as::<_, SuspensionRow>(
+            r#"
+            SELECT suspended_reason, resume_from, suspended_at
+            FROM investigations
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
         sqlx::query(
             r#"
             UPDATE investigations
@@ -129,19 +281,188 @@ impl StoreClient {
             "#,
         )
         .bind(id.0)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
 
+        let resumption = suspension
+            .filter(|row| row.suspended_at.is_some())
+            .map(|row| InvestigationResumed {
+                reason: row.suspended_reason,
+                resume_from: row.resume_from,
+                suspended_at: row.suspended_at,
+            });
+        if let Some(resumption) = &resumption {
+            Self::insert_event_payload(&mut *tx, id, resumption).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(resumption)
+    }
+
+    /// Replace an investigation's tags. Callers normalize with `normalize_tags`.
+    pub async fn set_investigation_tags(
+        &self,
+        id: InvestigationId,
+        tags: &[String],
+    ) -> Result<(), StoreError> {
+        let result = sqlx::query("UPDATE investigations SET tags = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(tags)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(StoreError::NotFound(format!("Investigation {}", id)));
+        }
         Ok(())
     }
 
+    /// Apply an operator's edit to a Pending investigation, provided it is
+    /// still at `expected_version`, and log the changed fields with their old
+    /// and new values as an `investigation_edited` event. Returns the updated
+    /// investigation; an edit that changes nothing returns it as it is, with
+    /// no event. `Conflict` if the investigation has started or was edited
+    /// since `expected_version`.
+    pub async fn update_investigation_pending_fields(
+        &self,
+        id: InvestigationId,
+        expected_version: i32,
+        edit: &PendingInvestigationEdit,
+        api_key_id: Option<&str>,
+    ) -> Result<Investigation, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let current: Investigation = sqlx::query_as::<_, InvestigationRow>(
+            r#"
+            SELECT id, prompt, status, parent_investigation_id, cycle_count,
+                   created_at, completed_at, suspended_reason, suspended_at, resume_from, tags,
+                   max_cycles, focus_entities, strictness, correlation_id, priority_cap, canary,
+                   prompt_hashes, fetch_budget, footprint, cycle_metrics, version, as_of
+            FROM investigations
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("Investigation {}", id)))?
+        .into();
+
+        if current.status != InvestigationStatus::Pending {
+            return Err(StoreError::Conflict(format!(
+                "Investigation {} is {}; only pending investigations can be edited",
+                id,
+                current.status.as_db_str()
+            )));
+        }
+        if current.version != expected_version {
+            return Err(StoreError::Conflict(format!(
+                "Investigation {} is at version {}, not {}; re-read it and retry",
+                id, current.version, expected_version
+            )));
+        }
+
+        let changes = edit.changes(&current);
+        if changes.is_empty() {
+            return Ok(current);
+        }
+        let mut updated = current;
+        edit.apply(&mut updated);
+        updated.version += 1;
+
+        sqlx::query(
+            r#"
+            UPDATE investigations
+            SET prompt = $2,
+                priority_cap = $3,
+                focus_entities = $4,
+                max_cycles = $5,
+                tags = $6,
+                version = $7
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .bind(&updated.prompt)
+        .bind(updated.priority_cap.map(|p| p.as_db_int()))
+        .bind(&updated.focus_entities)
+        .bind(updated.max_cycles.map(|n| n as i32))
+        .bind(&updated.tags)
+        .bind(updated.version)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let payload = serde_json::json!({
+            "changes": changes,
+            "version": updated.version,
+            "api_key_id": api_key_id,
+        });
+        Self::insert_investigation_event(
+            &mut *tx,
+            id,
+            InvestigationEventKind::InvestigationEdited,
+            &payload,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(updated)
+    }
+
+    /// List investigations, newest first. Every tag in `filter.tags` must be present
+    /// (exact match), as must the correlation ID when given.
+    pub async fn list_investigations(
+        &self,
+        filter: &InvestigationFilter,
+    ) -> Result<Vec<Investigation>, StoreError> {
+        let rows = sqlx::query_as::<_, InvestigationRow>(
+            r#"
+            SELECT id, prompt, status, parent_investigation_id, cycle_count,
+                   created_at, completed_at, suspended_reason, suspended_at, resume_from, tags,
+                   max_cycles, focus_entities, strictness, correlation_id, priority_cap, canary,
+                   prompt_hashes, fetch_budget, footprint, cycle_metrics, version, as_of
+            FROM investigations
+            WHERE tags @> $1
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::text IS NULL OR correlation_id = $3)
+            ORDER BY created_at DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(&filter.tags)
+        .bind(filter.status.as_ref().map(|s| s.as_db_str()))
+        .bind(&filter.correlation_id)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
     /// Get all non-terminal investigations (for startup recovery).
     pub async fn get_non_terminal_investigations(&self) -> Result<Vec<Investigation>, StoreError> {
         let rows = sqlx::query_as::<_, InvestigationRow>(
             r#"
             SELECT id, prompt, status, parent_investigation_id, cycle_count,
-                   created_at, completed_at, suspended_reason, suspended_at, resume_from
+                   created_at, completed_at, suspended_reason, suspended_at, resume_from, tags,
+                   max_cycles, focus_entities, strictness, correlation_id, priority_cap, canary,
+                   prompt_hashes, fetch_budget, footprint, cycle_metrics, version, as_of
             FROM investigations
             WHERE status NOT IN ('completed', 'failed')
             ORDER BY created_at
@@ -155,6 +476,112 @@ impl StoreClient {
     }
 }
 
+/// Fields of a Pending investigation an operator may edit. Unset fields are
+/// left as they are. Callers validate and normalize the values.
+#[derive(Clone, Debug, Default)]
+pub struct PendingInvestigationEdit {
+    pub prompt: Option<String>,
+    pub priority_cap: Option<WorkOrderPriority>,
+    pub focus_entities: Option<Vec<String>>,
+    pub max_cycles: Option<u32>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl PendingInvestigationEdit {
+    /// `{field: {"old": ..., "new": ...}}` for each field the edit changes.
+    fn changes(&self, current: &Investigation) -> serde_json::Map<String, serde_json::Value> {
+        let mut changes = serde_json::Map::new();
+        let mut record = |field: &str, old: serde_json::Value, new: serde_json::Value| {
+            if old != new {
+                changes.insert(field.into(), serde_json::json!({"old": old, "new": new}));
+            }
+        };
+        if let Some(prompt) = &self.prompt {
+            record(
+                "prompt",
+                current.prompt.as_str().into(),
+                prompt.as_str().into(),
+            );
+        }
+        if let Some(priority_cap) = self.priority_cap {
+            record(
+                "priority_cap",
+                serde_json::json!(current.priority_cap),
+                serde_json::json!(priority_cap),
+            );
+        }
+        if let Some(focus_entities) = &self.focus_entities {
+            record(
+                "focus_entities",
+                serde_json::json!(current.focus_entities),
+                serde_json::json!(focus_entities),
+            );
+        }
+        if let Some(max_cycles) = self.max_cycles {
+            record(
+                "max_cycles",
+                serde_json::json!(current.max_cycles),
+                serde_json::json!(max_cycles),
+            );
+        }
+        if let Some(tags) = &self.tags {
+            record(
+                "tags",
+                serde_json::json!(current.tags),
+                serde_json::json!(tags),
+            );
+        }
+        changes
+    }
+
+    fn apply(&self, investigation: &mut Investigation) {
+        if let Some(prompt) = &self.prompt {
+            investigation.prompt = prompt.clone();
+        }
+        if let Some(priority_cap) = self.priority_cap {
+            investigation.priority_cap = Some(priority_cap);
+        }
+        if let Some(focus_entities) = &self.focus_entities {
+            investigation.focus_entities = focus_entities.clone();
+        }
+        if let Some(max_cycles) = self.max_cycles {
+            investigation.max_cycles = Some(max_cycles);
+        }
+        if let Some(tags) = &self.tags {
+            investigation.tags = tags.clone();
+        }
+    }
+}
+
+/// Filters for `list_investigations`.
+#[derive(Clone, Debug)]
+pub struct InvestigationFilter {
+    /// Required tags (all must match). Empty matches everything.
+    pub tags: Vec<String>,
+    pub status: Option<InvestigationStatus>,
+    pub correlation_id: Option<String>,
+    pub limit: i64,
+}
+
+impl Default for InvestigationFilter {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            status: None,
+            correlation_id: None,
+            limit: 50,
+        }
+    }
+}
+
+/// Suspension state read by `clear_suspension`.
+#[derive(sqlx::FromRow)]
+struct SuspensionRow {
+    suspended_reason: Option<String>,
+    resume_from: Option<String>,
+    suspended_at: Option<DateTime<Utc>>,
+}
+
 /// Internal row type for sqlx deserialization.
 #[derive(sqlx::FromRow)]
 struct InvestigationRow {
@@ -168,10 +595,28 @@ struct InvestigationRow {
     suspended_reason: Option<String>,
     suspended_at: Option<chrono::DateTime<Utc>>,
     resume_from: Option<String>,
+    tags: Vec<String>,
+    max_cycles: Option<i32>,
+    focus_entities: Vec<String>,
+    strictness: Option<String>,
+    correlation_id: Option<String>,
+    priority_cap: Option<i32>,
+    canary: bool,
+    prompt_hashes: serde_json::Value,
+    fetch_budget: Option<serde_json::Value>,
+    footprint: Option<serde_json::Value>,
+    cycle_metrics: serde_json::Value,
+    version: i32,
+    as_of: Option<chrono::DateTime<Utc>>,
 }
 
 impl From<InvestigationRow> for Investigation {
     fn from(row: InvestigationRow) -> Self {
+        let cycles: Vec<CycleMetrics> =
+            serde_json::from_value(row.cycle_metrics).unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Unreadable cycle metrics, ignoring");
+                Vec::new()
+            });
         Self {
             id: InvestigationId::from_uuid(row.id),
             prompt: row.prompt,
@@ -183,24 +628,38 @@ impl From<InvestigationRow> for Investigation {
             suspended_reason: row.suspended_reason,
             suspended_at: row.suspended_at,
             resume_from: row.resume_from,
+            tags: row.tags,
+            max_cycles: row.max_cycles.map(|n| n.max(1) as u32),
+            focus_entities: row.focus_entities,
+            strictness: row.strictness.as_deref().and_then(|s| {
+                s.parse()
+                    .map_err(|e| tracing::warn!(error = %e, "Unknown strictness profile, ignoring"))
+                    .ok()
+            }),
+            correlation_id: row.correlation_id,
+            priority_cap: row.priority_cap.and_then(|p| {
+                WorkOrderPriority::from_db_int(p).or_else(|| {
+                    tracing::warn!(priority = p, "Unknown priority cap, ignoring");
+                    None
+                })
+            }),
+            canary: row.canary,
+            prompt_hashes: serde_json::from_value(row.prompt_hashes).unwrap_or_default(),
+            fetch_budget: row
+                .fetch_budget
+                .and_then(|b| serde_json::from_value(b).ok()),
+            footprint: row.footprint.and_then(|f| serde_json::from_value(f).ok()),
+            cycle_efficiency: cycle_efficiency(&cycles),
+            cycles,
+            version: row.version,
+            as_of: row.as_of,
         }
     }
 }
 
 fn parse_investigation_status(s: &str) -> InvestigationStatus {
-    match s {
-        "pending" => InvestigationStatus::Pending,
-        "analyst_running" => InvestigationStatus::AnalystRunning,
-        "processing" => InvestigationStatus::Processing,
-        "suspended" => InvestigationStatus::Suspended,
-        "completed" => InvestigationStatus::Completed,
-        "failed" => InvestigationStatus::Failed,
-        other => {
-            tracing::warn!(
-                status = other,
-                "Unknown investigation status, defaulting to Pending"
-            );
-            InvestigationStatus::Pending
-        }
-    }
+    s.parse().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Unknown investigation status, defaulting to Pending");
+        InvestigationStatus::Pending
+    })
 }
@@ -0,0 +1,237 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use autosint_common::ids::MaintenanceJobId;
+use autosint_common::types::{MaintenanceJob, MaintenanceJobStatus, ParseEnumError};
+
+use super::{StoreClient, StoreError};
+
+const JOB_COLUMNS: &str = "id, job_type, params, dry_run, status, checkpoint, processed, changed, \
+     total, errors, error_samples, cancel_requested, error, created_at, started_at, finished_at";
+
+impl StoreClient {
+    /// Record a queued job.
+    pub async fn create_maintenance_job(&self, job: &MaintenanceJob) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            INSERT INTO maintenance_jobs (id, job_type, params, dry_run, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(job.id.0)
+        .bind(job.job_type.as_db_str())
+        .bind(&job.params)
+        .bind(job.dry_run)
+        .bind(job.status.as_db_str())
+        .bind(job.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    pub async fn get_maintenance_job(
+        &self,
+        id: MaintenanceJobId,
+    ) -> Result<MaintenanceJob, StoreError> {
+        let row = sqlx::query_as::<_, JobRow>(&format!(
+            "SELECT {JOB_COLUMNS} FROM maintenance_jobs WHERE id = $1"
+        ))
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("Maintenance job {}", id)))?;
+        row.into_job()
+    }
+
+    /// Jobs newest first, optionally only those with `status`.
+    pub async fn list_maintenance_jobs(
+        &self,
+        status: Option<MaintenanceJobStatus>,
+        limit: i64,
+    ) -> Result<Vec<MaintenanceJob>, StoreError> {
+        let rows = sqlx::query_as::<_, JobRow>(&format!(
+            "SELECT {JOB_COLUMNS} FROM maintenance_jobs \
+             WHERE ($1::text IS NULL OR status = $1) \
+             ORDER BY created_at DESC LIMIT $2"
+        ))
+        .bind(status.map(|s| s.as_db_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        rows.into_iter().map(JobRow::into_job).collect()
+    }
+
+    /// The job to work on next: one interrupted mid-run, else the oldest
+    /// queued one.
+    pub async fn next_maintenance_job(&self) -> Result<Option<MaintenanceJob>, StoreError> {
+        let row = sqlx::query_as::<_, JobRow>(&format!(
+            "SELECT {JOB_COLUMNS} FROM maintenance_jobs \
+             WHERE status IN ('queued', 'running') \
+             ORDER BY status = 'running' DESC, created_at \
+             LIMIT 1"
+        ))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        row.map(JobRow::into_job).transpose()
+    }
+
+    /// Mark a job running with `total` items to look at. A resumed job keeps
+    /// its first start time.
+    pub async fn start_maintenance_job(
+        &self,
+        id: MaintenanceJobId,
+        total: Option<u64>,
+    ) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            UPDATE maintenance_jobs
+            SET status = 'running',
+                started_at = COALESCE(started_at, now()),
+                total = COALESCE($2, total)
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .bind(total.map(|t| t as i64))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Save a job's progress after a batch. Returns whether a cancel has been
+    /// asked for since.
+    pub async fn checkpoint_maintenance_job(
+        &self,
+        job: &MaintenanceJob,
+    ) -> Result<bool, StoreError> {
+        let cancel_requested: Option<bool> = sqlx::query_scalar(
+            r#"
+            UPDATE maintenance_jobs
+            SET checkpoint = $2, processed = $3, changed = $4, errors = $5, error_samples = $6
+            WHERE id = $1
+            RETURNING cancel_requested
+            "#,
+        )
+        .bind(job.id.0)
+        .bind(job.checkpoint.as_deref())
+        .bind(job.processed as i64)
+        .bind(job.changed as i64)
+        .bind(job.errors as i64)
+        .bind(serde_json::to_value(&job.error_samples).unwrap_or_default())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(cancel_requested.unwrap_or(true))
+    }
+
+    /// Close a job as completed, failed (with `error`) or cancelled.
+    pub async fn finish_maintenance_job(
+        &self,
+        id: MaintenanceJobId,
+        status: MaintenanceJobStatus,
+        error: Option<&str>,
+    ) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            UPDATE maintenance_jobs
+            SET status = $2, error = $3, finished_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .bind(status.as_db_str())
+        .bind(error)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Ask an unfinished job to stop: a queued one is cancelled at once, a
+    /// running one after its current batch. Returns the job's status after,
+    /// or None when it had already finished.
+    pub async fn cancel_maintenance_job(
+        &self,
+        id: MaintenanceJobId,
+    ) -> Result<Option<MaintenanceJobStatus>, StoreError> {
+        let status: Option<String> = sqlx::query_scalar(
+            r#"
+            UPDATE maintenance_jobs
+            SET cancel_requested = true,
+                status = CASE WHEN status = 'queued' THEN 'cancelled' ELSE status END,
+                finished_at = CASE WHEN status = 'queued' THEN now() ELSE finished_at END
+            WHERE id = $1 AND status IN ('queued', 'running')
+            RETURNING status
+            "#,
+        )
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        match status {
+            Some(status) => Ok(Some(parse_db(&status)?)),
+            // Tell a missing job from a finished one.
+            None => self.get_maintenance_job(id).await.map(|_| None),
+        }
+    }
+}
+
+/// A job enum from its column value.
+fn parse_db<T: FromStr<Err = ParseEnumError>>(value: &str) -> Result<T, StoreError> {
+    value
+        .parse()
+        .map_err(|e: ParseEnumError| StoreError::Query(e.to_string()))
+}
+
+/// Internal row type for sqlx deserialization.
+#[derive(sqlx::FromRow)]
+struct JobRow {
+    id: Uuid,
+    job_type: String,
+    params: Value,
+    dry_run: bool,
+    status: String,
+    checkpoint: Option<String>,
+    processed: i64,
+    changed: i64,
+    total: Option<i64>,
+    errors: i64,
+    error_samples: Value,
+    cancel_requested: bool,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl JobRow {
+    fn into_job(self) -> Result<MaintenanceJob, StoreError> {
+        Ok(MaintenanceJob {
+            id: MaintenanceJobId::from_uuid(self.id),
+            job_type: parse_db(&self.job_type)?,
+            params: self.params,
+            dry_run: self.dry_run,
+            status: parse_db(&self.status)?,
+            checkpoint: self.checkpoint,
+            processed: self.processed.max(0) as u64,
+            changed: self.changed.max(0) as u64,
+            total: self.total.map(|t| t.max(0) as u64),
+            errors: self.errors.max(0) as u64,
+            error_samples: serde_json::from_value(self.error_samples).unwrap_or_default(),
+            cancel_requested: self.cancel_requested,
+            error: self.error,
+            created_at: self.created_at,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+        })
+    }
+}
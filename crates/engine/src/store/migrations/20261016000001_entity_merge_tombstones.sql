@@ -0,0 +1,10 @@
+-- Short-lived source → target markers left by merge_entities.
+-- Tool handlers holding a stale entity ID (captured before a merge) redirect through these.
+-- Rows older than the tombstone TTL are ignored and pruned on the next merge.
+
+CREATE TABLE IF NOT EXISTS entity_merge_tombstones (
+    source_id UUID PRIMARY KEY,
+    target_id UUID NOT NULL,
+    merged_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS idx_entity_merge_tombstones_merged_at ON entity_merge_tombstones(merged_at);
@@ -0,0 +1,7 @@
+-- Free-form organizational tags on investigations and assessments.
+-- Matching is exact (array containment), served by GIN indexes.
+
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE assessments ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS idx_investigations_tags ON investigations USING GIN (tags);
+CREATE INDEX IF NOT EXISTS idx_assessments_tags ON assessments USING GIN (tags);
@@ -0,0 +1,13 @@
+-- Constrain status/priority columns to the Rust enum variants.
+-- StoreClient::migrate() verifies these lists still match the Rust enums.
+ALTER TABLE investigations
+    ADD CONSTRAINT investigations_status_check
+    CHECK (status IN ('pending', 'analyst_running', 'processing', 'suspended', 'completed', 'failed'));
+
+ALTER TABLE work_orders
+    ADD CONSTRAINT work_orders_status_check
+    CHECK (status IN ('queued', 'processing', 'completed', 'failed'));
+
+ALTER TABLE work_orders
+    ADD CONSTRAINT work_orders_priority_check
+    CHECK (priority IN (0, 1, 2));
@@ -0,0 +1,8 @@
+-- Record how each work order ended, for the Analyst's list_work_orders / get_work_order tools.
+-- failure_reason: error text when the Processor session failed
+-- summary: session outcome (kind, final text, turns, tool calls), written on completion
+-- retry_count: times the work order was redelivered to a new Processor after the first attempt
+
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS failure_reason TEXT;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS summary JSONB;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS retry_count INT NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- Delayed work order delivery.
+-- not_before: earliest dispatch time; the work order sits in 'scheduled' until then.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS not_before TIMESTAMPTZ;
+
+ALTER TABLE work_orders DROP CONSTRAINT IF EXISTS work_orders_status_check;
+ALTER TABLE work_orders
+    ADD CONSTRAINT work_orders_status_check
+    CHECK (status IN ('scheduled', 'queued', 'processing', 'completed', 'failed'));
@@ -0,0 +1,7 @@
+-- Work order timing for wait-age and latency metrics.
+-- started_at: when a Processor last began working the order (reset on redelivery)
+-- The (status, priority, created_at) index keeps the periodic queued-age percentile query cheap.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS started_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_work_orders_status_priority_created
+    ON work_orders(status, priority, created_at);
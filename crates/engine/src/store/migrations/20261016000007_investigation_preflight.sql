@@ -0,0 +1,10 @@
+-- Per-investigation settings applied from pre-flight triage.
+-- max_cycles: cycle limit below safety.max_cycles_per_investigation (NULL = the safety limit)
+-- strictness: StrictnessProfile; StoreClient::migrate() verifies the CHECK list.
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS max_cycles INTEGER;
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS focus_entities TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS strictness TEXT;
+
+ALTER TABLE investigations
+    ADD CONSTRAINT investigations_strictness_check
+    CHECK (strictness IN ('lenient', 'standard', 'strict'));
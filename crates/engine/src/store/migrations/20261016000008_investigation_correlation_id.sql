@@ -0,0 +1,4 @@
+-- Caller-supplied correlation ID (e.g. an upstream case number), not unique.
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS correlation_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_investigations_correlation_id
+    ON investigations (correlation_id) WHERE correlation_id IS NOT NULL;
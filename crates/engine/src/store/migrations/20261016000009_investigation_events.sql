@@ -0,0 +1,17 @@
+-- Append-only log of notable changes made during an investigation.
+-- kind: InvestigationEventKind; StoreClient::migrate() verifies the CHECK list.
+-- payload: kind-specific details.
+CREATE TABLE IF NOT EXISTS investigation_events (
+    id               BIGSERIAL PRIMARY KEY,
+    investigation_id UUID NOT NULL REFERENCES investigations(id) ON DELETE CASCADE,
+    kind             TEXT NOT NULL,
+    payload          JSONB NOT NULL DEFAULT '{}',
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_investigation_events_investigation
+    ON investigation_events (investigation_id, created_at);
+
+ALTER TABLE investigation_events
+    ADD CONSTRAINT investigation_events_kind_check
+    CHECK (kind IN ('work_order_reprioritized'));
@@ -0,0 +1,11 @@
+-- Work order reprioritization.
+-- delivery: bumped each time a work order is re-enqueued; Processors skip
+--   messages carrying an older delivery, so a superseded copy is never processed.
+-- priority_cap: highest WorkOrderPriority an investigation's work orders may use
+--   (NULL = no cap); StoreClient::migrate() verifies the CHECK list.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS delivery INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS priority_cap INTEGER;
+
+ALTER TABLE investigations
+    ADD CONSTRAINT investigations_priority_cap_check
+    CHECK (priority_cap IN (0, 1, 2));
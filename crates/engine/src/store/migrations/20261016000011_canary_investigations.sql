@@ -0,0 +1,3 @@
+-- canary: synthetic investigation run by the engine self-test. Purged once
+-- the self-test has checked it; startup recovery purges interrupted ones.
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS canary BOOLEAN NOT NULL DEFAULT false;
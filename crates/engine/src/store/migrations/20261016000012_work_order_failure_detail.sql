@@ -0,0 +1,5 @@
+-- Work order failure diagnostics.
+-- failure_detail: FailureDetail JSON (category, sanitized error, last failing
+--   tool, tool error counts, whether anything reached the graph), set when a
+--   Processor session fails.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS failure_detail JSONB;
@@ -0,0 +1,11 @@
+-- Work order deadlines and effort hints.
+-- deadline: time after which the result is no longer useful; Processors skip
+--   orders dequeued later than this.
+-- effort: 'quick', 'standard' or 'thorough'; scales the Processor session's
+--   turn and time budget.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS deadline TIMESTAMPTZ;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS effort TEXT NOT NULL DEFAULT 'standard';
+
+CREATE INDEX IF NOT EXISTS idx_work_orders_pending_deadline
+    ON work_orders(deadline)
+    WHERE deadline IS NOT NULL AND status IN ('scheduled', 'queued');
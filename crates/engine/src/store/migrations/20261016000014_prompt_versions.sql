@@ -0,0 +1,12 @@
+-- Prompt versions.
+-- investigations.prompt_hashes: hex SHA-256 of each prompt template loaded
+--   when the investigation was created, keyed by name ("analyst", ...).
+-- work_orders.prompt_hash: hash of the Processor prompt its last session ran with.
+-- Analyst sessions record theirs as analyst_session_started events.
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS prompt_hashes JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS prompt_hash TEXT;
+
+ALTER TABLE investigation_events DROP CONSTRAINT IF EXISTS investigation_events_kind_check;
+ALTER TABLE investigation_events
+    ADD CONSTRAINT investigation_events_kind_check
+    CHECK (kind IN ('work_order_reprioritized', 'analyst_session_started'));
@@ -0,0 +1,37 @@
+-- Documents pushed to AutOSINT rather than fetched, such as email newsletters.
+-- origin/external_id: where the document came from and its identity there
+--   (e.g. 'imap' and the Message-ID).
+-- publisher_entity_id: graph entity the document was attributed to.
+CREATE TABLE IF NOT EXISTS source_documents (
+    id                  UUID PRIMARY KEY,
+    origin              TEXT NOT NULL,
+    external_id         TEXT NOT NULL,
+    title               TEXT,
+    author              TEXT,
+    publisher_entity_id UUID,
+    content             TEXT NOT NULL,
+    published_at        TIMESTAMPTZ,
+    received_at         TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (origin, external_id)
+);
+
+-- Messages an ingest mailbox has already handled, by Message-ID. Skipped
+-- messages (oversized, unparseable) are recorded with no document.
+CREATE TABLE IF NOT EXISTS ingest_seen_messages (
+    mailbox            TEXT NOT NULL,
+    message_id         TEXT NOT NULL,
+    source_document_id UUID REFERENCES source_documents(id) ON DELETE SET NULL,
+    seen_at            TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (mailbox, message_id)
+);
+
+-- kind: WorkOrderKind; StoreClient::migrate() verifies the CHECK list.
+-- source_document_id: the document an 'ingest_document' work order extracts from.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS kind TEXT NOT NULL DEFAULT 'research';
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS source_document_id UUID
+    REFERENCES source_documents(id) ON DELETE SET NULL;
+
+ALTER TABLE work_orders DROP CONSTRAINT IF EXISTS work_orders_kind_check;
+ALTER TABLE work_orders
+    ADD CONSTRAINT work_orders_kind_check
+    CHECK (kind IN ('research', 'ingest_document'));
@@ -0,0 +1,9 @@
+-- Outbound request footprint and fetch budgets.
+-- investigations.fetch_budget: FetchBudget JSON from the POST /investigate request (NULL = no limit)
+-- investigations.footprint: OutboundFootprint JSON summed over the work orders, written when the
+--   investigation ends
+-- work_orders.footprint: OutboundFootprint JSON of the Processor session (fetches by domain,
+--   searches by provider, bytes downloaded, rate-limit and budget rejections)
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS fetch_budget JSONB;
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS footprint JSONB;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS footprint JSONB;
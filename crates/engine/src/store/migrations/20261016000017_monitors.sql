@@ -0,0 +1,51 @@
+-- Standing subscriptions to Fetch service sources. Each run queries the
+-- source for items since high_water_mark and files new ones as ingest work
+-- orders under the monitor's standing ingest investigation.
+-- params: source query parameters sent with every run.
+-- strictness: StrictnessProfile; StoreClient::migrate() verifies the CHECK lists.
+-- last_run_*: outcome of the latest run. items_*: totals across runs.
+CREATE TABLE IF NOT EXISTS monitors (
+    id                UUID PRIMARY KEY,
+    name              TEXT NOT NULL,
+    source_id         TEXT NOT NULL,
+    params            JSONB NOT NULL DEFAULT '{}',
+    interval_seconds  BIGINT NOT NULL,
+    enabled           BOOLEAN NOT NULL DEFAULT true,
+    strictness        TEXT NOT NULL DEFAULT 'standard'
+        CONSTRAINT monitors_strictness_check
+        CHECK (strictness IN ('lenient', 'standard', 'strict')),
+    focus_tags        TEXT[] NOT NULL DEFAULT '{}',
+    max_items_per_run INTEGER NOT NULL,
+    investigation_id  UUID NOT NULL REFERENCES investigations(id),
+    high_water_mark   TIMESTAMPTZ,
+    next_run_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_run_at       TIMESTAMPTZ,
+    last_run_status   TEXT
+        CONSTRAINT monitors_last_run_status_check
+        CHECK (last_run_status IN ('succeeded', 'failed')),
+    last_run_error    TEXT,
+    last_run_seen     INTEGER NOT NULL DEFAULT 0,
+    last_run_ingested INTEGER NOT NULL DEFAULT 0,
+    last_run_skipped  INTEGER NOT NULL DEFAULT 0,
+    last_run_deferred INTEGER NOT NULL DEFAULT 0,
+    items_seen        BIGINT NOT NULL DEFAULT 0,
+    items_ingested    BIGINT NOT NULL DEFAULT 0,
+    items_skipped     BIGINT NOT NULL DEFAULT 0,
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitors_due ON monitors (next_run_at) WHERE enabled;
+
+-- Item URLs (normalized) a monitor has already handled.
+CREATE TABLE IF NOT EXISTS monitor_seen_items (
+    monitor_id         UUID NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+    url                TEXT NOT NULL,
+    source_document_id UUID REFERENCES source_documents(id) ON DELETE SET NULL,
+    seen_at            TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (monitor_id, url)
+);
+
+-- monitor_id: the monitor whose item an 'ingest_document' work order extracts.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS monitor_id UUID
+    REFERENCES monitors(id) ON DELETE SET NULL;
@@ -0,0 +1,10 @@
+-- The Analyst's working notes, one row per named section, kept across cycles.
+-- Section and total size caps are enforced by the engine ([scratchpad]).
+CREATE TABLE IF NOT EXISTS scratchpad_sections (
+    investigation_id UUID NOT NULL REFERENCES investigations(id),
+    section          TEXT NOT NULL,
+    content          TEXT NOT NULL,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (investigation_id, section)
+);
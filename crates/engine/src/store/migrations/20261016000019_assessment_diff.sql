@@ -0,0 +1,8 @@
+-- Differential assessments.
+-- assessments.supersedes_assessment_id: the earlier assessment a new one supersedes (kept when
+--   the earlier one is purged, as NULL)
+-- assessments.diff: AssessmentDiff JSON — key judgments unchanged / revised / new / dropped
+--   against the superseded assessment, written when the investigation completes
+ALTER TABLE assessments ADD COLUMN IF NOT EXISTS supersedes_assessment_id UUID
+    REFERENCES assessments(id) ON DELETE SET NULL;
+ALTER TABLE assessments ADD COLUMN IF NOT EXISTS diff JSONB;
@@ -0,0 +1,10 @@
+-- Edits to Pending investigations (PATCH /investigations/{id}).
+-- version: bumped by each edit; an edit names the version it was based on,
+--   so concurrent edits conflict rather than overwrite each other.
+-- Each edit is logged as an investigation_edited event.
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
+
+ALTER TABLE investigation_events DROP CONSTRAINT IF EXISTS investigation_events_kind_check;
+ALTER TABLE investigation_events
+    ADD CONSTRAINT investigation_events_kind_check
+    CHECK (kind IN ('work_order_reprioritized', 'analyst_session_started', 'investigation_edited'));
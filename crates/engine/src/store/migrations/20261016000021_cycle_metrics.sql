@@ -0,0 +1,4 @@
+-- Per-cycle work order aggregates (work orders run, completed and failed; claims, entities and
+-- relationships created), as a JSON array of CycleMetrics ordered by cycle. Rewritten as each
+-- Processing phase completes.
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS cycle_metrics JSONB NOT NULL DEFAULT '[]'::jsonb;
@@ -0,0 +1,18 @@
+-- Investigation timeline (GET /investigate/{id}/timeline).
+-- Status transitions, suspensions, Analyst session ends and the work order
+-- lifecycle (created, enqueued, dequeued, completed/failed) are logged as
+-- investigation_events; payloads are the EventPayload types.
+-- created_at: the insert's own time, so events written in one transaction
+--   keep their order.
+ALTER TABLE investigation_events ALTER COLUMN created_at SET DEFAULT clock_timestamp();
+
+CREATE INDEX IF NOT EXISTS idx_investigation_events_timeline
+    ON investigation_events (investigation_id, id);
+
+ALTER TABLE investigation_events DROP CONSTRAINT IF EXISTS investigation_events_kind_check;
+ALTER TABLE investigation_events
+    ADD CONSTRAINT investigation_events_kind_check
+    CHECK (kind IN ('work_order_reprioritized', 'analyst_session_started', 'investigation_edited',
+                    'status_changed', 'investigation_suspended', 'investigation_resumed',
+                    'analyst_session_finished', 'work_order_created', 'work_order_enqueued',
+                    'work_order_dequeued', 'work_order_completed', 'work_order_failed'));
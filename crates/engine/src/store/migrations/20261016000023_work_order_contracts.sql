@@ -0,0 +1,5 @@
+-- Declared output contracts: what the Analyst expects a work order's Processor session to deliver
+-- (ExpectedOutputs as JSON), and whether a completed session fell short of it. The unmet criteria
+-- are recorded under the summary's "contract" key.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS expected_outputs JSONB;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS contract_unmet BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,18 @@
+-- Readers' verdicts on assessments, submitted through
+-- POST /assessments/{id}/feedback. Disputed claims are also marked in the graph.
+-- rating: FeedbackRating; StoreClient::migrate() verifies the CHECK list.
+-- submitted_by: id of the credential the feedback was submitted with.
+CREATE TABLE IF NOT EXISTS assessment_feedback (
+    id                 BIGSERIAL PRIMARY KEY,
+    assessment_id      UUID NOT NULL REFERENCES assessments(id) ON DELETE CASCADE,
+    rating             TEXT NOT NULL
+        CONSTRAINT assessment_feedback_rating_check
+        CHECK (rating IN ('accurate', 'partially', 'inaccurate')),
+    comments           TEXT,
+    disputed_claim_ids UUID[] NOT NULL DEFAULT '{}',
+    submitted_by       TEXT,
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_assessment_feedback_assessment
+    ON assessment_feedback (assessment_id, created_at);
@@ -0,0 +1,17 @@
+-- Work orders proposed by Processor sessions for leads they uncover. A proposal sits in
+-- 'proposed' until the Analyst approves it (it is then queued like any other work order) or
+-- dismisses it ('dismissed').
+-- proposed_by: the work order whose session made the proposal.
+-- rationale: why the Processor thinks the lead is worth following.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS proposed_by UUID;
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS rationale TEXT;
+
+ALTER TABLE work_orders DROP CONSTRAINT IF EXISTS work_orders_status_check;
+ALTER TABLE work_orders
+    ADD CONSTRAINT work_orders_status_check
+    CHECK (status IN ('scheduled', 'queued', 'processing', 'completed', 'failed',
+                      'proposed', 'dismissed'));
+
+CREATE INDEX IF NOT EXISTS idx_work_orders_proposals
+    ON work_orders(investigation_id)
+    WHERE proposed_by IS NOT NULL;
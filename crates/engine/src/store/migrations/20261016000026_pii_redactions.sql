@@ -0,0 +1,11 @@
+-- Personal data found in claim content, counted by day and detector for
+-- GET /admin/pii/redactions. Counts only: the matched values are never stored.
+-- kind: PiiDetectorKind name or a configured [[pii.patterns]] name.
+CREATE TABLE IF NOT EXISTS pii_redactions (
+    day             DATE NOT NULL,
+    kind            TEXT NOT NULL,
+    matches         BIGINT NOT NULL DEFAULT 0,
+    claims          BIGINT NOT NULL DEFAULT 0,
+    rejected_claims BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, kind)
+);
@@ -0,0 +1,30 @@
+-- Jobs submitted to asynchronous enrichment providers, which deliver results to a
+-- single-use callback URL (POST /callbacks/enrichment/{token}).
+-- token_hash: hex SHA-256 of the callback token; the token itself is never stored.
+-- status: EnrichmentStatus; StoreClient::migrate() verifies the CHECK list. A request
+--   leaves 'pending' exactly once, which is what makes its token single-use.
+-- work_order_id: the Processor work order that asked (NULL when the Analyst asked).
+-- ingest: whether the result gets an ingest_document work order (ingest_work_order_id).
+-- surfaced_at: when the finished request was shown to the Analyst at a cycle start.
+CREATE TABLE IF NOT EXISTS enrichment_requests (
+    id                   UUID PRIMARY KEY,
+    provider             TEXT NOT NULL,
+    payload              JSONB NOT NULL,
+    investigation_id     UUID NOT NULL REFERENCES investigations(id) ON DELETE CASCADE,
+    work_order_id        UUID REFERENCES work_orders(id) ON DELETE SET NULL,
+    ingest               BOOLEAN NOT NULL DEFAULT false,
+    token_hash           TEXT NOT NULL UNIQUE,
+    status               TEXT NOT NULL DEFAULT 'pending',
+    error                TEXT,
+    source_document_id   UUID REFERENCES source_documents(id) ON DELETE SET NULL,
+    ingest_work_order_id UUID REFERENCES work_orders(id) ON DELETE SET NULL,
+    created_at           TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at           TIMESTAMPTZ NOT NULL,
+    completed_at         TIMESTAMPTZ,
+    surfaced_at          TIMESTAMPTZ,
+    CONSTRAINT enrichment_requests_status_check
+        CHECK (status IN ('pending', 'completed', 'failed', 'expired'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_enrichment_requests_investigation
+    ON enrichment_requests(investigation_id, created_at);
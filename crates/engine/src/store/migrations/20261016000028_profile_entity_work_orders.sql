@@ -0,0 +1,6 @@
+-- 'profile_entity' work orders research a stub entity queued by stub resolution.
+-- StoreClient::migrate() verifies the CHECK list against WorkOrderKind.
+ALTER TABLE work_orders DROP CONSTRAINT IF EXISTS work_orders_kind_check;
+ALTER TABLE work_orders
+    ADD CONSTRAINT work_orders_kind_check
+    CHECK (kind IN ('research', 'ingest_document', 'profile_entity'));
@@ -0,0 +1,27 @@
+-- Every mutating tool call, HTTP write and engine-made merge, with who made
+-- it, for GET /audit. Partitioned by month on recorded_at: the audit writer
+-- creates each month's partition ahead of time (StoreClient::
+-- ensure_audit_partition), and rows outside every month partition land in
+-- audit_log_default. StoreClient::migrate() verifies the actor_type CHECK
+-- list against AuditActorType.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id               BIGINT GENERATED ALWAYS AS IDENTITY,
+    recorded_at      TIMESTAMPTZ NOT NULL,
+    actor_type       TEXT NOT NULL
+        CONSTRAINT audit_log_actor_type_check
+        CHECK (actor_type IN ('analyst_session', 'processor_session', 'http_api_key', 'admin', 'system')),
+    actor_id         TEXT,
+    investigation_id UUID,
+    work_order_id    UUID,
+    operation        TEXT NOT NULL,
+    target_type      TEXT NOT NULL,
+    target_id        TEXT,
+    digest           JSONB NOT NULL DEFAULT '{}'::jsonb,
+    PRIMARY KEY (recorded_at, id)
+) PARTITION BY RANGE (recorded_at);
+
+CREATE TABLE IF NOT EXISTS audit_log_default PARTITION OF audit_log DEFAULT;
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_target ON audit_log (target_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log (actor_type, actor_id, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_id ON audit_log (id DESC);
@@ -0,0 +1,36 @@
+-- Maintenance jobs started through POST /admin/jobs (re-embedding, index
+-- rebuilds, data backfills), worked through in checkpointed batches.
+-- job_type: MaintenanceJobType; status: MaintenanceJobStatus.
+--   StoreClient::migrate() verifies both CHECK lists.
+-- checkpoint: where the next batch starts, opaque outside the job type. A job
+--   left 'running' by a stopped engine resumes from it.
+-- processed: items looked at; changed: items changed, or in a dry run the
+--   items that would have been.
+-- error_samples: the first failed items' IDs with their errors.
+CREATE TABLE IF NOT EXISTS maintenance_jobs (
+    id               UUID PRIMARY KEY,
+    job_type         TEXT NOT NULL,
+    params           JSONB NOT NULL DEFAULT '{}'::jsonb,
+    dry_run          BOOLEAN NOT NULL DEFAULT false,
+    status           TEXT NOT NULL DEFAULT 'queued',
+    checkpoint       TEXT,
+    processed        BIGINT NOT NULL DEFAULT 0,
+    changed          BIGINT NOT NULL DEFAULT 0,
+    total            BIGINT,
+    errors           BIGINT NOT NULL DEFAULT 0,
+    error_samples    JSONB NOT NULL DEFAULT '[]'::jsonb,
+    cancel_requested BOOLEAN NOT NULL DEFAULT false,
+    error            TEXT,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    started_at       TIMESTAMPTZ,
+    finished_at      TIMESTAMPTZ,
+    CONSTRAINT maintenance_jobs_type_check
+        CHECK (job_type IN ('reembed_entities', 'reembed_claims', 'rebuild_fulltext',
+                            'normalize_kinds', 'backfill_aliases_list')),
+    CONSTRAINT maintenance_jobs_status_check
+        CHECK (status IN ('queued', 'running', 'completed', 'failed', 'cancelled'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_maintenance_jobs_created ON maintenance_jobs(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_maintenance_jobs_unfinished
+    ON maintenance_jobs(created_at) WHERE status IN ('queued', 'running');
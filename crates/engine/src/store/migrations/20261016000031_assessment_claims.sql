@@ -0,0 +1,31 @@
+-- Claims each assessment cites (its claim_refs, citations and hypothesis
+-- claim_refs), for finding the assessments built on a claim. Written with the
+-- assessment. claim_id is a Neo4j claim ID, so has no foreign key.
+CREATE TABLE IF NOT EXISTS assessment_claims (
+    assessment_id UUID NOT NULL REFERENCES assessments(id) ON DELETE CASCADE,
+    claim_id      UUID NOT NULL,
+    PRIMARY KEY (assessment_id, claim_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_assessment_claims_claim
+    ON assessment_claims (claim_id);
+
+-- Set when a claim the assessment cites is disputed.
+ALTER TABLE assessments ADD COLUMN IF NOT EXISTS needs_review BOOLEAN NOT NULL DEFAULT false;
+
+-- Existing assessments: claim_refs and citations.
+INSERT INTO assessment_claims (assessment_id, claim_id)
+SELECT a.id, ref::uuid
+FROM assessments a,
+     LATERAL (
+         SELECT jsonb_array_elements_text(
+                    CASE WHEN jsonb_typeof(a.claim_refs) = 'array'
+                         THEN a.claim_refs ELSE '[]'::jsonb END) AS ref
+         UNION
+         SELECT c->>'claim_id'
+         FROM jsonb_array_elements(
+                  CASE WHEN jsonb_typeof(a.content->'citations') = 'array'
+                       THEN a.content->'citations' ELSE '[]'::jsonb END) AS c
+     ) refs
+WHERE ref ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,4 @@
+-- Work order spending style.
+-- mode: 'focused' or 'exploratory'; exploratory Processor sessions skim for
+--   breadth under a per-source call cap.
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'focused';
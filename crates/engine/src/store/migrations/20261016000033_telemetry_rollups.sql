@@ -0,0 +1,23 @@
+-- Telemetry rollups: non-content session statistics per prompt hash.
+-- work_orders.session_stats: SessionTelemetry JSON of the Processor session (outcome, turns,
+--   tool calls by name, tokens)
+-- telemetry_rollups.rollup: PromptRollup JSON for one role, prompt hash and window
+-- telemetry_rollups.pushed_at: when the rollup reached the collector (NULL = not yet, or no
+--   collector configured)
+ALTER TABLE work_orders ADD COLUMN IF NOT EXISTS session_stats JSONB;
+
+CREATE TABLE IF NOT EXISTS telemetry_rollups (
+    id            BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    role          TEXT NOT NULL,
+    prompt_hash   TEXT NOT NULL,
+    window_start  TIMESTAMPTZ NOT NULL,
+    window_end    TIMESTAMPTZ NOT NULL,
+    rollup        JSONB NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    pushed_at     TIMESTAMPTZ,
+    UNIQUE (role, prompt_hash, window_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_telemetry_rollups_window ON telemetry_rollups (window_start DESC);
+CREATE INDEX IF NOT EXISTS idx_telemetry_rollups_unpushed
+    ON telemetry_rollups (id) WHERE pushed_at IS NULL;
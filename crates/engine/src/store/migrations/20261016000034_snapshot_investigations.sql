@@ -0,0 +1,5 @@
+-- Snapshot investigations: the Analyst reads the graph as it stood at a past time.
+-- investigations.as_of: cutoff the investigation's graph reads are held to (NULL = live graph)
+-- assessments.as_of: cutoff of the snapshot investigation that produced the assessment
+ALTER TABLE investigations ADD COLUMN IF NOT EXISTS as_of TIMESTAMPTZ;
+ALTER TABLE assessments ADD COLUMN IF NOT EXISTS as_of TIMESTAMPTZ;
@@ -0,0 +1,14 @@
+-- Claims merged away by merge_claims, with both claims as they stood before
+-- the merge, so a merged ID still resolves and a merge can be reviewed or
+-- undone. target_id is kept pointing at the surviving claim when the target
+-- is itself merged later. Claim IDs are Neo4j IDs, so have no foreign keys.
+CREATE TABLE IF NOT EXISTS claim_merges (
+    source_id    UUID PRIMARY KEY,
+    target_id    UUID NOT NULL,
+    rationale    TEXT NOT NULL,
+    source_claim JSONB NOT NULL,
+    target_claim JSONB NOT NULL,
+    merged_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_claim_merges_target ON claim_merges (target_id);
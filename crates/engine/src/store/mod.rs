@@ -1,7 +1,34 @@
 mod assessments;
+mod audit;
+mod claim_merges;
+mod enrichment;
+mod entity_locks;
+mod events;
 mod investigations;
+mod jobs;
+mod monitors;
+mod pii;
+mod purge;
+mod schema_check;
+mod scratchpad;
+mod singleton_locks;
+mod source_documents;
+mod tags;
+mod telemetry;
+mod transfer;
 mod work_orders;
 
+pub use audit::{AuditFilter, AuditPage};
+pub use enrichment::EnrichmentOutcome;
+pub use entity_locks::EntityLockGuard;
+pub use events::{TimelineFilter, TimelinePage};
+pub use investigations::{InvestigationFilter, PendingInvestigationEdit};
+pub use purge::StorePurgeReport;
+pub use singleton_locks::SingletonLock;
+pub use tags::TagUsage;
+pub use telemetry::{SessionSample, TelemetryRollupFilter};
+pub use transfer::InvestigationRecordSet;
+
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 
@@ -46,6 +73,8 @@ impl StoreClient {
             .await
             .map_err(|e| StoreError::Migration(e.to_string()))?;
 
+        self.verify_enum_constraints().await?;
+
         tracing::info!("PostgreSQL migrations complete");
         Ok(())
     }
@@ -70,6 +99,26 @@ pub enum StoreError {
 
     #[error("Not found: {0}")]
     NotFound(String),
+
+    #[error("Lock not available: {0}")]
+    LockTimeout(String),
+
+    /// The row is not in a state that allows the change, or changed since
+    /// the caller read it.
+    #[error("Conflict: {0}")]
+    Conflict(String),
+}
+
+impl StoreError {
+    /// Whether retrying the operation might succeed (connection blips, query failures,
+    /// lock contention).
+    /// NotFound, conflict and migration errors are permanent.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::Connection(_) | Self::Query(_) | Self::LockTimeout(_)
+        )
+    }
 }
 
 impl From<StoreError> for autosint_common::AutOsintError {
@@ -0,0 +1,382 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use autosint_common::ids::{InvestigationId, MonitorId};
+use autosint_common::types::{
+    Investigation, Monitor, MonitorRun, MonitorRunStatus, SourceDocument, StrictnessProfile,
+    WorkOrder,
+};
+
+use super::{StoreClient, StoreError};
+
+const MONITOR_COLUMNS: &str = r#"
+    id, name, source_id, params, interval_seconds, enabled, strictness, focus_tags,
+    max_items_per_run, investigation_id, high_water_mark, next_run_at, last_run_at,
+    last_run_status, last_run_error, last_run_seen, last_run_ingested, last_run_skipped,
+    last_run_deferred, items_seen, items_ingested, items_skipped, created_at, updated_at
+"#;
+
+impl StoreClient {
+    /// Create a monitor together with its standing ingest investigation.
+    pub async fn create_monitor(
+        &self,
+        monitor: &Monitor,
+        investigation: &Investigation,
+    ) -> Result<Monitor, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Self::insert_investigation(&mut *tx, investigation).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO monitors (id, name, source_id, params, interval_seconds, enabled,
+                                  strictness, focus_tags, max_items_per_run, investigation_id,
+                                  next_run_at, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+        )
+        .bind(monitor.id.0)
+        .bind(&monitor.name)
+        .bind(&monitor.source_id)
+        .bind(serde_json::Value::Object(monitor.params.clone()))
+        .bind(monitor.interval_seconds as i64)
+        .bind(monitor.enabled)
+        .bind(monitor.strictness.as_db_str())
+        .bind(&monitor.focus_tags)
+        .bind(monitor.max_items_per_run as i32)
+        .bind(monitor.investigation_id.0)
+        .bind(monitor.next_run_at)
+        .bind(monitor.created_at)
+        .bind(monitor.updated_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(monitor.clone())
+    }
+
+    /// Retrieve a monitor by ID.
+    pub async fn get_monitor(&self, id: MonitorId) -> Result<Monitor, StoreError> {
+        let row = sqlx::query_as::<_, MonitorRow>(&format!(
+            "SELECT {} FROM monitors WHERE id = $1",
+            MONITOR_COLUMNS
+        ))
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("Monitor {}", id)))?;
+
+        Ok(row.into())
+    }
+
+    /// Whether a monitor stores its items under `investigation_id`.
+    pub async fn investigation_has_monitor(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<bool, StoreError> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM monitors WHERE investigation_id = $1)")
+            .bind(investigation_id.0)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))
+    }
+
+    /// All monitors, by name.
+    pub async fn list_monitors(&self) -> Result<Vec<Monitor>, StoreError> {
+        let rows = sqlx::query_as::<_, MonitorRow>(&format!(
+            "SELECT {} FROM monitors ORDER BY name, created_at",
+            MONITOR_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Claim the enabled monitors due at `now` by moving each one's next run
+    /// an interval ahead. Monitors another engine
+    /// is claiming are skipped, so each due run happens once.
+    pub async fn claim_due_monitors(&self, now: DateTime<Utc>) -> Result<Vec<Monitor>, StoreError> {
+        let rows = sqlx::query_as::<_, MonitorRow>(&format!(
+            r#"
+            UPDATE monitors
+            SET next_run_at = $1 + make_interval(secs => interval_seconds)
+            WHERE id IN (
+                SELECT id
+                FROM monitors
+                WHERE enabled
+                  AND next_run_at <= $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            MONITOR_COLUMNS
+        ))
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Save a monitor's settings: everything a caller may change, plus
+    /// `next_run_at`. Run state is left alone.
+    pub async fn update_monitor(&self, monitor: &Monitor) -> Result<Monitor, StoreError> {
+        let row = sqlx::query_as::<_, MonitorRow>(&format!(
+            r#"
+            UPDATE monitors
+            SET name = $2,
+                params = $3,
+                interval_seconds = $4,
+                enabled = $5,
+                strictness = $6,
+                focus_tags = $7,
+                max_items_per_run = $8,
+                next_run_at = $9,
+                updated_at = now()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            MONITOR_COLUMNS
+        ))
+        .bind(monitor.id.0)
+        .bind(&monitor.name)
+        .bind(serde_json::Value::Object(monitor.params.clone()))
+        .bind(monitor.interval_seconds as i64)
+        .bind(monitor.enabled)
+        .bind(monitor.strictness.as_db_str())
+        .bind(&monitor.focus_tags)
+        .bind(monitor.max_items_per_run as i32)
+        .bind(monitor.next_run_at)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("Monitor {}", monitor.id)))?;
+
+        Ok(row.into())
+    }
+
+    /// Delete a monitor and its seen items. Its standing investigation and
+    /// work orders are kept. Returns the deleted monitor.
+    pub async fn delete_monitor(&self, id: MonitorId) -> Result<Monitor, StoreError> {
+        let row = sqlx::query_as::<_, MonitorRow>(&format!(
+            "DELETE FROM monitors WHERE id = $1 RETURNING {}",
+            MONITOR_COLUMNS
+        ))
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("Monitor {}", id)))?;
+
+        Ok(row.into())
+    }
+
+    /// Record a finished run: its outcome, the totals and the high-water
+    /// mark, which only moves forward.
+    pub async fn record_monitor_run(
+        &self,
+        id: MonitorId,
+        run: &MonitorRun,
+        high_water_mark: Option<DateTime<Utc>>,
+    ) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            UPDATE monitors
+            SET last_run_at = $2,
+                last_run_status = $3,
+                last_run_error = $4,
+                last_run_seen = $5,
+                last_run_ingested = $6,
+                last_run_skipped = $7,
+                last_run_deferred = $8,
+                items_seen = items_seen + $5,
+                items_ingested = items_ingested + $6,
+                items_skipped = items_skipped + $7,
+                high_water_mark = GREATEST(high_water_mark, $9)
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .bind(run.at)
+        .bind(run.status.as_db_str())
+        .bind(&run.error)
+        .bind(run.seen as i32)
+        .bind(run.ingested as i32)
+        .bind(run.skipped as i32)
+        .bind(run.deferred as i32)
+        .bind(high_water_mark)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Which of `urls` the monitor has already handled.
+    pub async fn seen_monitor_urls(
+        &self,
+        id: MonitorId,
+        urls: &[String],
+    ) -> Result<HashSet<String>, StoreError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT url
+            FROM monitor_seen_items
+            WHERE monitor_id = $1
+              AND url = ANY($2)
+            "#,
+        )
+        .bind(id.0)
+        .bind(urls)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(rows.into_iter().map(|(url,)| url).collect())
+    }
+
+    /// Store a monitor's item as a document with the work order that extracts
+    /// it, marking `url` seen in the same transaction. Returns false, storing
+    /// no document or work order, if the URL was already seen or another
+    /// monitor already stored the document; the URL is marked seen either way.
+    pub async fn ingest_monitor_item(
+        &self,
+        id: MonitorId,
+        url: &str,
+        document: &SourceDocument,
+        work_order: &WorkOrder,
+    ) -> Result<bool, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO monitor_seen_items (monitor_id, url)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(id.0)
+        .bind(url)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let inserted = Self::insert_source_document(&mut *tx, document).await?;
+        if inserted {
+            sqlx::query(
+                r#"
+                UPDATE monitor_seen_items
+                SET source_document_id = $3
+                WHERE monitor_id = $1
+                  AND url = $2
+                "#,
+            )
+            .bind(id.0)
+            .bind(url)
+            .bind(document.id.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+            Self::insert_work_order(&mut tx, work_order).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(inserted)
+    }
+}
+
+/// Internal row type for sqlx deserialization.
+#[derive(sqlx::FromRow)]
+struct MonitorRow {
+    id: Uuid,
+    name: String,
+    source_id: String,
+    params: serde_json::Value,
+    interval_seconds: i64,
+    enabled: bool,
+    strictness: String,
+    focus_tags: Vec<String>,
+    max_items_per_run: i32,
+    investigation_id: Uuid,
+    high_water_mark: Option<DateTime<Utc>>,
+    next_run_at: DateTime<Utc>,
+    last_run_at: Option<DateTime<Utc>>,
+    last_run_status: Option<String>,
+    last_run_error: Option<String>,
+    last_run_seen: i32,
+    last_run_ingested: i32,
+    last_run_skipped: i32,
+    last_run_deferred: i32,
+    items_seen: i64,
+    items_ingested: i64,
+    items_skipped: i64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<MonitorRow> for Monitor {
+    fn from(row: MonitorRow) -> Self {
+        let last_run = match (row.last_run_at, row.last_run_status) {
+            (Some(at), Some(status)) => Some(MonitorRun {
+                at,
+                status: status.parse().unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "Unknown monitor run status, defaulting to Failed");
+                    MonitorRunStatus::Failed
+                }),
+                error: row.last_run_error,
+                seen: row.last_run_seen as u32,
+                ingested: row.last_run_ingested as u32,
+                skipped: row.last_run_skipped as u32,
+                deferred: row.last_run_deferred as u32,
+            }),
+            _ => None,
+        };
+
+        Self {
+            id: MonitorId::from_uuid(row.id),
+            name: row.name,
+            source_id: row.source_id,
+            params: match row.params {
+                serde_json::Value::Object(params) => params,
+                _ => serde_json::Map::new(),
+            },
+            interval_seconds: row.interval_seconds as u64,
+            enabled: row.enabled,
+            strictness: row.strictness.parse().unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Unknown monitor strictness, defaulting to Standard");
+                StrictnessProfile::Standard
+            }),
+            focus_tags: row.focus_tags,
+            max_items_per_run: row.max_items_per_run as u32,
+            investigation_id: InvestigationId::from_uuid(row.investigation_id),
+            high_water_mark: row.high_water_mark,
+            next_run_at: row.next_run_at,
+            last_run,
+            items_seen: row.items_seen as u64,
+            items_ingested: row.items_ingested as u64,
+            items_skipped: row.items_skipped as u64,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, Utc};
+
+use autosint_common::api::engine::{PiiRedactionCounts, PiiRedactionDay};
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Add one claim's redaction counts to today's totals. `rejected` when
+    /// the claim was refused rather than stored redacted.
+    pub async fn record_pii_redactions(
+        &self,
+        redactions: &BTreeMap<String, u32>,
+        rejected: bool,
+    ) -> Result<(), StoreError> {
+        if redactions.is_empty() {
+            return Ok(());
+        }
+        let kinds: Vec<&str> = redactions.keys().map(String::as_str).collect();
+        let matches: Vec<i64> = redactions.values().map(|&n| i64::from(n)).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO pii_redactions (day, kind, matches, claims, rejected_claims)
+            SELECT $1, kind, matches, 1, $4
+            FROM UNNEST($2::text[], $3::bigint[]) AS t(kind, matches)
+            ON CONFLICT (day, kind) DO UPDATE
+            SET matches = pii_redactions.matches + EXCLUDED.matches,
+                claims = pii_redactions.claims + 1,
+                rejected_claims = pii_redactions.rejected_claims + EXCLUDED.rejected_claims
+            "#,
+        )
+        .bind(Utc::now().date_naive())
+        .bind(&kinds)
+        .bind(&matches)
+        .bind(i64::from(rejected))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Redaction counts for the last `days` days, counting today: newest day
+    /// first, then by type.
+    pub async fn pii_redactions(&self, days: u32) -> Result<Vec<PiiRedactionDay>, StoreError> {
+        let since = Utc::now().date_naive() - chrono::Days::new(u64::from(days.saturating_sub(1)));
+        let rows = sqlx::query_as::<_, (NaiveDate, String, i64, i64, i64)>(
+            r#"
+            SELECT day, kind, matches, claims, rejected_claims
+            FROM pii_redactions
+            WHERE day >= $1
+            ORDER BY day DESC, kind
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(date, kind, matches, claims, rejected_claims)| PiiRedactionDay {
+                    date,
+                    kind,
+                    counts: PiiRedactionCounts {
+                        matches: matches.max(0) as u64,
+                        claims: claims.max(0) as u64,
+                        rejected_claims: rejected_claims.max(0) as u64,
+                    },
+                },
+            )
+            .collect())
+    }
+}
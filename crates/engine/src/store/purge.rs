@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use autosint_common::ids::{EntityId, InvestigationId};
+
+use super::{StoreClient, StoreError};
+use crate::ingest::INGEST_TAG;
+
+pub use autosint_common::api::engine::StorePurgeReport;
+
+/// `claim_merges` rows whose source or target claim, as copied at merge time,
+/// came from investigation `$1`.
+const CLAIM_MERGES_OF_INVESTIGATION: &str = "source_claim->>'investigation_id' = $1::text \
+     OR target_claim->>'investigation_id' = $1::text";
+
+impl StoreClient {
+    /// Delete an investigation and all rows that depend on it, in dependency order.
+    /// With `dry_run`, only counts what would be deleted.
+    pub async fn purge_investigation(
+        &self,
+        id: InvestigationId,
+        dry_run: bool,
+    ) -> Result<StorePurgeReport, StoreError> {
+        // Fail early (and identically for dry runs) on unknown IDs.
+        self.get_investigation(id).await?;
+
+        if dry_run {
+            let (work_orders, assessments, children, scratchpad, claim_merges): (
+                i64,
+                i64,
+                i64,
+                i64,
+                i64,
+            ) = sqlx::query_as(&format!(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM work_orders WHERE investigation_id = $1),
+                    (SELECT COUNT(*) FROM assessments WHERE investigation_id = $1),
+                    (SELECT COUNT(*) FROM investigations WHERE parent_investigation_id = $1),
+                    (SELECT COUNT(*) FROM scratchpad_sections WHERE investigation_id = $1),
+                    (SELECT COUNT(*) FROM claim_merges WHERE {})
+                "#,
+                CLAIM_MERGES_OF_INVESTIGATION
+            ))
+            .bind(id.0)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+            return Ok(StorePurgeReport {
+                work_orders: work_orders as u64,
+                assessments: assessments as u64,
+                child_investigations_detached: children as u64,
+                scratchpad_sections: scratchpad as u64,
+                claim_merges: claim_merges as u64,
+            });
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let children = sqlx::query(
+            "UPDATE investigations SET parent_investigation_id = NULL WHERE parent_investigation_id = $1",
+        )
+        .bind(id.0)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .rows_affected();
+
+        let assessments = sqlx::query("DELETE FROM assessments WHERE investigation_id = $1")
+            .bind(id.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?
+            .rows_affected();
+
+        let work_orders = sqlx::query("DELETE FROM work_orders WHERE investigation_id = $1")
+            .bind(id.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?
+            .rows_affected();
+
+        let scratchpad_sections =
+            sqlx::query("DELETE FROM scratchpad_sections WHERE investigation_id = $1")
+                .bind(id.0)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Query(e.to_string()))?
+                .rows_affected();
+
+        let claim_merges = sqlx::query(&format!(
+            "DELETE FROM claim_merges WHERE {}",
+            CLAIM_MERGES_OF_INVESTIGATION
+        ))
+        .bind(id.0)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .rows_affected();
+
+        sqlx::query("DELETE FROM investigations WHERE id = $1")
+            .bind(id.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(StorePurgeReport {
+            work_orders,
+            assessments,
+            child_investigations_detached: children,
+            scratchpad_sections,
+            claim_merges,
+        })
+    }
+
+    /// Entity IDs referenced by assessments or work orders of any *other* investigation.
+    /// Graph purge treats these as shared and never removes them.
+    pub async fn entity_refs_outside_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> Result<Vec<EntityId>, StoreError> {
+        let refs: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT ref FROM (
+                SELECT jsonb_array_elements_text(entity_refs) AS ref
+                FROM assessments
+                WHERE investigation_id <> $1 AND jsonb_typeof(entity_refs) = 'array'
+                UNION
+                SELECT jsonb_array_elements_text(referenced_entities) AS ref
+                FROM work_orders
+                WHERE investigation_id <> $1 AND jsonb_typeof(referenced_entities) = 'array'
+            ) refs
+            "#,
+        )
+        .bind(id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(refs
+            .iter()
+            .filter_map(|s| Uuid::parse_str(s).ok())
+            .map(EntityId::from_uuid)
+            .collect())
+    }
+
+    /// Terminal investigations that finished before `cutoff` (retention
+    /// candidates). Standing ingest investigations are never candidates.
+    pub async fn get_terminal_investigations_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<InvestigationId>, StoreError> {
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM investigations
+            WHERE status IN ('completed', 'failed')
+              AND COALESCE(completed_at, created_at) < $1
+              AND NOT tags @> ARRAY[$2]
+            ORDER BY created_at
+            "#,
+        )
+        .bind(cutoff)
+        .bind(INGEST_TAG)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(ids.into_iter().map(InvestigationId::from_uuid).collect())
+    }
+}
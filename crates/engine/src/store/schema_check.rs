@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use autosint_common::types::{
+    AuditActorType, EnrichmentStatus, FeedbackRating, InvestigationEventKind, InvestigationStatus,
+    MaintenanceJobStatus, MaintenanceJobType, MonitorRunStatus, StrictnessProfile, WorkOrderKind,
+    WorkOrderPriority, WorkOrderStatus,
+};
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Verify the status/priority CHECK constraints accept exactly the values the
+    /// Rust enums produce, so a variant added on one side only fails at startup
+    /// instead of at the first insert (or read) that hits it.
+    pub(super) async fn verify_enum_constraints(&self) -> Result<(), StoreError> {
+        let expected: [(&str, BTreeSet<String>); 14] = [
+            (
+                "investigations_status_check",
+                InvestigationStatus::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "investigations_strictness_check",
+                StrictnessProfile::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "work_orders_status_check",
+                WorkOrderStatus::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "work_orders_priority_check",
+                WorkOrderPriority::ALL
+                    .iter()
+                    .map(|p| p.as_db_int().to_string())
+                    .collect(),
+            ),
+            (
+                "investigations_priority_cap_check",
+                WorkOrderPriority::ALL
+                    .iter()
+                    .map(|p| p.as_db_int().to_string())
+                    .collect(),
+            ),
+            (
+                "work_orders_kind_check",
+                WorkOrderKind::ALL
+                    .iter()
+                    .map(|k| k.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "investigation_events_kind_check",
+                InvestigationEventKind::ALL
+                    .iter()
+                    .map(|k| k.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "monitors_strictness_check",
+                StrictnessProfile::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "monitors_last_run_status_check",
+                MonitorRunStatus::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "assessment_feedback_rating_check",
+                FeedbackRating::ALL
+                    .iter()
+                    .map(|r| r.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "enrichment_requests_status_check",
+                EnrichmentStatus::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "audit_log_actor_type_check",
+                AuditActorType::ALL
+                    .iter()
+                    .map(|a| a.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "maintenance_jobs_type_check",
+                MaintenanceJobType::ALL
+                    .iter()
+                    .map(|t| t.as_db_str().to_string())
+                    .collect(),
+            ),
+            (
+                "maintenance_jobs_status_check",
+                MaintenanceJobStatus::ALL
+                    .iter()
+                    .map(|s| s.as_db_str().to_string())
+                    .collect(),
+            ),
+        ];
+
+        for (constraint, rust_values) in expected {
+            let definition: Option<(String,)> = sqlx::query_as(
+                "SELECT pg_get_constraintdef(oid) FROM pg_constraint WHERE conname = $1",
+            )
+            .bind(constraint)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+            let Some((definition,)) = definition else {
+                return Err(StoreError::Migration(format!(
+                    "constraint {} is missing",
+                    constraint
+                )));
+            };
+
+            let db_values = constraint_values(&definition);
+            if db_values != rust_values {
+                return Err(StoreError::Migration(format!(
+                    "constraint {} allows {:?} but the Rust enum has {:?}",
+                    constraint, db_values, rust_values
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Extract the allowed values from a `pg_get_constraintdef` rendering of
+/// `col IN (...)`, which Postgres normalizes to `col = ANY (ARRAY[...])`, or
+/// to `col = value` when the list has a single entry.
+fn constraint_values(definition: &str) -> BTreeSet<String> {
+    let list = if let Some(start) = definition.find("ARRAY[") {
+        let list = &definition[start + "ARRAY[".len()..];
+        &list[..list.find(']').unwrap_or(list.len())]
+    } else if let Some(start) = definition.find(" = ") {
+        &definition[start + " = ".len()..]
+    } else {
+        return BTreeSet::new();
+    };
+
+    list.split(',')
+        .map(|item| {
+            let item = item.trim();
+            let item = item.split("::").next().unwrap_or(item);
+            item.trim_matches(|c| c == '(' || c == ')')
+                .trim_matches('\'')
+                .to_string()
+        })
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraint_values_text_and_int() {
+        let text =
+            "CHECK ((status = ANY (ARRAY['queued'::text, 'processing'::text, 'failed'::text])))";
+        assert_eq!(
+            constraint_values(text),
+            BTreeSet::from(["queued".into(), "processing".into(), "failed".into()])
+        );
+
+        let int = "CHECK ((priority = ANY (ARRAY[0, 1, 2])))";
+        assert_eq!(
+            constraint_values(int),
+            BTreeSet::from(["0".into(), "1".into(), "2".into()])
+        );
+
+        let single = "CHECK ((kind = 'work_order_reprioritized'::text))";
+        assert_eq!(
+            constraint_values(single),
+            BTreeSet::from(["work_order_reprioritized".into()])
+        );
+
+        assert!(constraint_values("CHECK ((priority >= 0))").is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+
+use autosint_common::ids::InvestigationId;
+use autosint_common::types::ScratchpadSection;
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Write a scratchpad section, replacing any previous content, then evict
+    /// the least recently written other sections until the investigation's
+    /// sections fit in `max_total_chars`. Empty content deletes the section.
+    /// Returns the evicted section names, oldest first.
+    pub async fn write_scratchpad_section(
+        &self,
+        investigation_id: InvestigationId,
+        section: &str,
+        content: &str,
+        max_total_chars: usize,
+    ) -> Result<Vec<String>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        // Serializes writes per investigation, so concurrent writes can't
+        // both decide they fit.
+        let found = sqlx::query("SELECT id FROM investigations WHERE id = $1 FOR UPDATE")
+            .bind(investigation_id.0)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        if found.is_none() {
+            return Err(StoreError::NotFound(format!(
+                "Investigation {}",
+                investigation_id
+            )));
+        }
+
+        if content.is_empty() {
+            sqlx::query(
+                "DELETE FROM scratchpad_sections WHERE investigation_id = $1 AND section = $2",
+            )
+            .bind(investigation_id.0)
+            .bind(section)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        } else {
+            sqlx::query(
+                r#"
+                INSERT INTO scratchpad_sections (investigation_id, section, content)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (investigation_id, section)
+                DO UPDATE SET content = EXCLUDED.content, updated_at = now()
+                "#,
+            )
+            .bind(investigation_id.0)
+            .bind(section)
+            .bind(content)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        }
+
+        let sizes = sqlx::query_as::<_, (String, i32)>(
+            r#"
+            SELECT section, char_length(content)
+            FROM scratchpad_sections
+            WHERE investigation_id = $1
+            ORDER BY updated_at, section
+            "#,
+        )
+        .bind(investigation_id.0)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        let sizes: Vec<(String, usize)> = sizes
+            .into_iter()
+            .map(|(name, chars)| (name, chars.max(0) as usize))
+            .collect();
+
+        let evicted = sections_to_evict(&sizes, section, max_total_chars);
+        if !evicted.is_empty() {
+            sqlx::query(
+                "DELETE FROM scratchpad_sections WHERE investigation_id = $1 AND section = ANY($2)",
+            )
+            .bind(investigation_id.0)
+            .bind(&evicted)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(evicted)
+    }
+
+    /// An investigation's scratchpad sections, least recently written first.
+    /// With `section`, only that one (empty if it doesn't exist).
+    pub async fn get_scratchpad(
+        &self,
+        investigation_id: InvestigationId,
+        section: Option<&str>,
+    ) -> Result<Vec<ScratchpadSection>, StoreError> {
+        let rows = sqlx::query_as::<_, (String, String, DateTime<Utc>, DateTime<Utc>)>(
+            r#"
+            SELECT section, content, created_at, updated_at
+            FROM scratchpad_sections
+            WHERE investigation_id = $1 AND ($2::TEXT IS NULL OR section = $2)
+            ORDER BY updated_at, section
+            "#,
+        )
+        .bind(investigation_id.0)
+        .bind(section)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(section, content, created_at, updated_at)| ScratchpadSection {
+                    section,
+                    content,
+                    created_at,
+                    updated_at,
+                },
+            )
+            .collect())
+    }
+
+    /// Names of an investigation's scratchpad sections, least recently
+    /// written first.
+    pub async fn scratchpad_section_names(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Vec<String>, StoreError> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT section FROM scratchpad_sections WHERE investigation_id = $1 \
+             ORDER BY updated_at, section",
+        )
+        .bind(investigation_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))
+    }
+}
+
+/// Sections to evict so the total fits in `max_total_chars`: the least
+/// recently written first, never `written` itself. `sizes` holds (section,
+/// characters) pairs, least recently written first.
+fn sections_to_evict(
+    sizes: &[(String, usize)],
+    written: &str,
+    max_total_chars: usize,
+) -> Vec<String> {
+    let mut total: usize = sizes.iter().map(|(_, chars)| chars).sum();
+    let mut evicted = Vec::new();
+    for (name, chars) in sizes {
+        if total <= max_total_chars {
+            break;
+        }
+        if name != written {
+            total -= chars;
+            evicted.push(name.clone());
+        }
+    }
+    evicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes(sections: &[(&str, usize)]) -> Vec<(String, usize)> {
+        sections.iter().map(|(n, c)| (n.to_string(), *c)).collect()
+    }
+
+    #[test]
+    fn test_nothing_evicted_within_cap() {
+        let sizes = sizes(&[("a", 100), ("b", 100)]);
+        assert!(sections_to_evict(&sizes, "b", 200).is_empty());
+    }
+
+    #[test]
+    fn test_oldest_evicted_first() {
+        let sizes = sizes(&[("a", 100), ("b", 100), ("c", 100), ("d", 150)]);
+        assert_eq!(sections_to_evict(&sizes, "d", 300), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_written_section_never_evicted() {
+        // Written in the same instant as "b", so listed first by name.
+        let same_instant = sizes(&[("a", 250), ("b", 100)]);
+        assert_eq!(sections_to_evict(&same_instant, "a", 300), ["b"]);
+        // Even when evicting the rest can't bring the total under the cap.
+        let alone = sizes(&[("a", 400)]);
+        assert!(sections_to_evict(&alone, "a", 300).is_empty());
+    }
+}
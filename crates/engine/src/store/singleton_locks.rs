@@ -0,0 +1,99 @@
+use std::future::Future;
+
+use sqlx::pool::PoolConnection;
+use sqlx::Postgres;
+
+use super::{StoreClient, StoreError};
+
+/// First key of every singleton lock. Two-key advisory locks live in their
+/// own key space, so task names never collide with the one-key entity locks.
+const SINGLETON_LOCK_CLASS: i32 = 0x4155_544f; // "AUTO"
+
+/// A held singleton lock: a session-level Postgres advisory lock on a
+/// connection taken out of the pool. `release()` unlocks and returns the
+/// connection. Dropped without it — a panic, or the task cancelled — the
+/// connection is closed instead of pooled, which ends the session and frees
+/// the lock; a crashed engine's locks go the same way.
+pub struct SingletonLock {
+    task: String,
+    conn: Option<PoolConnection<Postgres>>,
+}
+
+impl SingletonLock {
+    /// Unlock and return the connection to the pool.
+    pub async fn release(mut self) -> Result<(), StoreError> {
+        let Some(mut conn) = self.conn.take() else {
+            return Ok(());
+        };
+        let unlocked: bool = sqlx::query_scalar("SELECT pg_advisory_unlock($1, hashtext($2))")
+            .bind(SINGLETON_LOCK_CLASS)
+            .bind(&self.task)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| {
+                // Leave the lock to the connection closing on drop.
+                drop(conn.detach());
+                StoreError::Query(e.to_string())
+            })?;
+        if !unlocked {
+            tracing::warn!(task = %self.task, "Singleton lock was not held at release");
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SingletonLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            tracing::warn!(task = %self.task, "Singleton lock dropped unreleased, closing its connection");
+            drop(conn.detach());
+        }
+    }
+}
+
+impl StoreClient {
+    /// Take the singleton lock for `task` if no engine instance holds it.
+    /// None when another holds it.
+    pub async fn try_lock_singleton(
+        &self,
+        task: &str,
+    ) -> Result<Option<SingletonLock>, StoreError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| StoreError::Connection(e.to_string()))?;
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1, hashtext($2))")
+            .bind(SINGLETON_LOCK_CLASS)
+            .bind(task)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(acquired.then(|| SingletonLock {
+            task: task.to_string(),
+            conn: Some(conn),
+        }))
+    }
+
+    /// Run `fut` as the one engine instance running `task` right now. Returns
+    /// None without running it when another instance holds the task's lock.
+    /// The lock is held for the run and released after, whatever `fut`
+    /// returns.
+    pub async fn try_run_exclusive<F, T>(&self, task: &str, fut: F) -> Result<Option<T>, StoreError>
+    where
+        F: Future<Output = T>,
+    {
+        let Some(lock) = self.try_lock_singleton(task).await? else {
+            metrics::counter!("singleton_locks.contended", "task" => task.to_string()).increment(1);
+            tracing::info!(task, "Skipping run, another engine instance holds its lock");
+            return Ok(None);
+        };
+        metrics::counter!("singleton_locks.acquired", "task" => task.to_string()).increment(1);
+
+        let output = fut.await;
+        if let Err(e) = lock.release().await {
+            tracing::warn!(task, error = %e, "Failed to release singleton lock");
+        }
+        Ok(Some(output))
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use autosint_common::ids::{EntityId, SourceDocumentId};
+use autosint_common::types::{SourceDocument, WorkOrder};
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Store an ingested document and the work order that extracts from it,
+    /// marking `message_id` seen for `mailbox` in the same transaction.
+    /// Returns false, writing nothing, if the message was already seen or the
+    /// document already stored.
+    pub async fn ingest_source_document(
+        &self,
+        mailbox: &str,
+        message_id: &str,
+        document: &SourceDocument,
+        work_order: &WorkOrder,
+    ) -> Result<bool, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO ingest_seen_messages (mailbox, message_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(mailbox)
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let inserted = Self::insert_source_document(&mut *tx, document).await?;
+        if inserted {
+            sqlx::query(
+                r#"
+                UPDATE ingest_seen_messages
+                SET source_document_id = $3
+                WHERE mailbox = $1
+                  AND message_id = $2
+                "#,
+            )
+            .bind(mailbox)
+            .bind(message_id)
+            .bind(document.id.0)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+            Self::insert_work_order(&mut tx, work_order).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(inserted)
+    }
+
+    /// Insert a document with `executor`, so it can share a caller's
+    /// transaction. False if one with the same origin and external ID exists.
+    pub(super) async fn insert_source_document<'e, E>(
+        executor: E,
+        document: &SourceDocument,
+    ) -> Result<bool, StoreError>
+    where
+        E: sqlx::PgExecutor<'e>,
+    {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO source_documents (id, origin, external_id, title, author,
+                                          publisher_entity_id, content, published_at,
+                                          received_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (origin, external_id) DO NOTHING
+            "#,
+        )
+        .bind(document.id.0)
+        .bind(&document.origin)
+        .bind(&document.external_id)
+        .bind(&document.title)
+        .bind(&document.author)
+        .bind(document.publisher_entity_id.map(|id| id.0))
+        .bind(&document.content)
+        .bind(document.published_at)
+        .bind(document.received_at)
+        .execute(executor)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(inserted.rows_affected() == 1)
+    }
+
+    /// Record a message as handled without storing a document, e.g. one
+    /// skipped for its size.
+    pub async fn mark_message_seen(
+        &self,
+        mailbox: &str,
+        message_id: &str,
+    ) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            INSERT INTO ingest_seen_messages (mailbox, message_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(mailbox)
+        .bind(message_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Which of `message_ids` `mailbox` has already handled.
+    pub async fn seen_message_ids(
+        &self,
+        mailbox: &str,
+        message_ids: &[String],
+    ) -> Result<HashSet<String>, StoreError> {
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT message_id
+            FROM ingest_seen_messages
+            WHERE mailbox = $1
+              AND message_id = ANY($2)
+            "#,
+        )
+        .bind(mailbox)
+        .bind(message_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Retrieve a source document by ID.
+    pub async fn get_source_document(
+        &self,
+        id: SourceDocumentId,
+    ) -> Result<SourceDocument, StoreError> {
+        let row = sqlx::query_as::<_, SourceDocumentRow>(
+            r#"
+            SELECT id, origin, external_id, title, author, publisher_entity_id, content,
+                   published_at, received_at
+            FROM source_documents
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?
+        .ok_or_else(|| StoreError::NotFound(format!("SourceDocument {}", id)))?;
+
+        Ok(row.into())
+    }
+}
+
+/// Internal row type for sqlx deserialization.
+#[derive(sqlx::FromRow)]
+struct SourceDocumentRow {
+    id: Uuid,
+    origin: String,
+    external_id: String,
+    title: Option<String>,
+    author: Option<String>,
+    publisher_entity_id: Option<Uuid>,
+    content: String,
+    published_at: Option<DateTime<Utc>>,
+    received_at: DateTime<Utc>,
+}
+
+impl From<SourceDocumentRow> for SourceDocument {
+    fn from(row: SourceDocumentRow) -> Self {
+        Self {
+            id: SourceDocumentId::from_uuid(row.id),
+            origin: row.origin,
+            external_id: row.external_id,
+            title: row.title,
+            author: row.author,
+            publisher_entity_id: row.publisher_entity_id.map(EntityId::from_uuid),
+            content: row.content,
+            published_at: row.published_at,
+            received_at: row.received_at,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+pub use autosint_common::types::TagUsage;
+
+use super::{StoreClient, StoreError};
+
+impl StoreClient {
+    /// Usage counts for every tag across investigations and assessments,
+    /// most used first.
+    pub async fn tag_usage(&self) -> Result<Vec<TagUsage>, StoreError> {
+        let rows = sqlx::query_as::<_, (String, i64, i64)>(
+            r#"
+            SELECT tag,
+                   COUNT(*) FILTER (WHERE kind = 'investigation') AS investigations,
+                   COUNT(*) FILTER (WHERE kind = 'assessment') AS assessments
+            FROM (
+                SELECT unnest(tags) AS tag, 'investigation' AS kind FROM investigations
+                UNION ALL
+                SELECT unnest(tags) AS tag, 'assessment' AS kind FROM assessments
+            ) t
+            GROUP BY tag
+            ORDER BY COUNT(*) DESC, tag
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(tag, investigations, assessments)| TagUsage {
+                tag,
+                investigations,
+                assessments,
+            })
+            .collect())
+    }
+}
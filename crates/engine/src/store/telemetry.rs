@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use autosint_common::types::{
+    AnalystSessionFinished, InvestigationEventKind, PromptRollup, SessionTelemetry, TelemetryRole,
+};
+
+use super::{StoreClient, StoreError};
+
+/// One finished session's statistics, as read back for a rollup.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionSample {
+    pub prompt_hash: String,
+    pub outcome: String,
+    pub turns: u32,
+    pub tool_calls: u32,
+    pub tool_calls_by_name: BTreeMap<String, u32>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Claims the session wrote. Processor sessions only.
+    pub claims: Option<u64>,
+    /// Whether the session was a forced final assessment. Analyst sessions only.
+    pub forced_final: Option<bool>,
+}
+
+/// Filters for `list_telemetry_rollups`.
+#[derive(Clone, Debug)]
+pub struct TelemetryRollupFilter {
+    pub role: Option<TelemetryRole>,
+    pub prompt_hash: Option<String>,
+    /// Only windows starting at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+impl StoreClient {
+    /// Processor sessions with a recorded prompt hash and statistics whose
+    /// work order finished within `start..end`.
+    pub async fn processor_session_samples(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<SessionSample>, StoreError> {
+        let rows = sqlx::query_as::<_, (String, serde_json::Value, i32)>(
+            r#"
+            SELECT prompt_hash, session_stats, claims_produced_count
+            FROM work_orders
+            WHERE prompt_hash IS NOT NULL
+              AND session_stats IS NOT NULL
+              AND completed_at >= $1
+              AND completed_at < $2
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(prompt_hash, stats, claims)| {
+                let stats: SessionTelemetry = serde_json::from_value(stats).ok()?;
+                Some(SessionSample {
+                    prompt_hash,
+                    outcome: stats.outcome,
+                    turns: stats.turns,
+                    tool_calls: stats.tool_calls,
+                    tool_calls_by_name: stats.tool_calls_by_name,
+                    input_tokens: stats.input_tokens,
+                    output_tokens: stats.output_tokens,
+                    claims: Some(claims.max(0) as u64),
+                    forced_final: None,
+                })
+            })
+            .collect())
+    }
+
+    /// Analyst sessions with a recorded prompt hash that finished within
+    /// `start..end`.
+    pub async fn analyst_session_samples(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<SessionSample>, StoreError> {
+        let payloads = sqlx::query_scalar::<_, serde_json::Value>(
+            r#"
+            SELECT payload
+            FROM investigation_events
+            WHERE kind = $1
+              AND payload ? 'prompt_hash'
+              AND created_at >= $2
+              AND created_at < $3
+            "#,
+        )
+        .bind(InvestigationEventKind::AnalystSessionFinished.as_db_str())
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(payloads
+            .into_iter()
+            .filter_map(|payload| {
+                let finished: AnalystSessionFinished = serde_json::from_value(payload).ok()?;
+                Some(SessionSample {
+                    prompt_hash: finished.prompt_hash?,
+                    forced_final: Some(finished.mode.as_deref() == Some("force_final")),
+                    outcome: finished.outcome,
+                    turns: finished.turns,
+                    tool_calls: finished.tool_calls,
+                    tool_calls_by_name: finished.tool_calls_by_name,
+                    input_tokens: finished.input_tokens,
+                    output_tokens: finished.output_tokens,
+                    claims: None,
+                })
+            })
+            .collect())
+    }
+
+    /// Store rollups, keeping any already stored for the same role, prompt
+    /// hash and window. Returns how many were new.
+    pub async fn insert_telemetry_rollups(
+        &self,
+        rollups: &[PromptRollup],
+    ) -> Result<u64, StoreError> {
+        if rollups.is_empty() {
+            return Ok(0);
+        }
+        let roles: Vec<&str> = rollups.iter().map(|r| r.role.as_db_str()).collect();
+        let hashes: Vec<&str> = rollups.iter().map(|r| r.prompt_hash.as_str()).collect();
+        let starts: Vec<DateTime<Utc>> = rollups.iter().map(|r| r.window_start).collect();
+        let ends: Vec<DateTime<Utc>> = rollups.iter().map(|r| r.window_end).collect();
+        let bodies: Vec<serde_json::Value> = rollups
+            .iter()
+            .map(|r| serde_json::to_value(r).unwrap_or_default())
+            .collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO telemetry_rollups (role, prompt_hash, window_start, window_end, rollup)
+            SELECT * FROM UNNEST(
+                $1::text[], $2::text[], $3::timestamptz[], $4::timestamptz[], $5::jsonb[]
+            )
+            ON CONFLICT (role, prompt_hash, window_start) DO NOTHING
+            "#,
+        )
+        .bind(&roles)
+        .bind(&hashes)
+        .bind(&starts)
+        .bind(&ends)
+        .bind(&bodies)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Stored rollups matching `filter`, newest window first.
+    pub async fn list_telemetry_rollups(
+        &self,
+        filter: &TelemetryRollupFilter,
+    ) -> Result<Vec<PromptRollup>, StoreError> {
+        let bodies = sqlx::query_scalar::<_, serde_json::Value>(
+            r#"
+            SELECT rollup
+            FROM telemetry_rollups
+            WHERE ($1::text IS NULL OR role = $1)
+              AND ($2::text IS NULL OR prompt_hash = $2)
+              AND ($3::timestamptz IS NULL OR window_start >= $3)
+            ORDER BY window_start DESC, role, prompt_hash
+            LIMIT $4
+            "#,
+        )
+        .bind(filter.role.map(|r| r.as_db_str()))
+        .bind(filter.prompt_hash.as_deref())
+        .bind(filter.since)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(bodies
+            .into_iter()
+            .filter_map(|body| serde_json::from_value(body).ok())
+            .collect())
+    }
+
+    /// Up to `limit` rollups not yet pushed to the collector, oldest first,
+    /// with their row IDs.
+    pub async fn unpushed_telemetry_rollups(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(i64, PromptRollup)>, StoreError> {
+        let rows = sqlx::query_as::<_, (i64, serde_json::Value)>(
+            r#"
+            SELECT id, rollup
+            FROM telemetry_rollups
+            WHERE pushed_at IS NULL
+            ORDER BY id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, body)| Some((id, serde_json::from_value(body).ok()?)))
+            .collect())
+    }
+
+    /// Mark rollups as delivered to the collector.
+    pub async fn mark_telemetry_rollups_pushed(&self, ids: &[i64]) -> Result<(), StoreError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        sqlx::query("UPDATE telemetry_rollups SET pushed_at = now() WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(())
+    }
+}
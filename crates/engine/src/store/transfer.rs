@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use autosint_common::types::{
+    Assessment, AssessmentFeedback, Investigation, ScratchpadSection, WorkOrder,
+};
+
+use super::{StoreClient, StoreError};
+
+/// The Postgres side of an investigation archive: its row and everything
+/// stored under it.
+#[derive(Clone, Debug)]
+pub struct InvestigationRecordSet {
+    pub investigation: Investigation,
+    pub work_orders: Vec<WorkOrder>,
+    pub assessments: Vec<Assessment>,
+    pub feedback: Vec<AssessmentFeedback>,
+    pub scratchpad: Vec<ScratchpadSection>,
+}
+
+impl StoreClient {
+    /// Of `ids`, those already used by an investigation, work order or
+    /// assessment.
+    pub async fn existing_record_ids(&self, ids: &[Uuid]) -> Result<HashSet<Uuid>, StoreError> {
+        let rows: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM investigations WHERE id = ANY($1)
+            UNION SELECT id FROM work_orders WHERE id = ANY($1)
+            UNION SELECT id FROM assessments WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Insert an imported investigation and its records in one transaction,
+    /// with their recorded outcomes and timestamps rather than fresh ones.
+    /// IDs must already be free.
+    pub async fn import_investigation_records(
+        &self,
+        records: &InvestigationRecordSet,
+    ) -> Result<(), StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let investigation = &records.investigation;
+        Self::insert_investigation(&mut *tx, investigation).await?;
+        sqlx::query(
+            r#"
+            UPDATE investigations
+            SET completed_at = $2, suspended_reason = $3, suspended_at = $4,
+                resume_from = $5, footprint = $6, cycle_metrics = $7, version = $8
+            WHERE id = $1
+            "#,
+        )
+        .bind(investigation.id.0)
+        .bind(investigation.completed_at)
+        .bind(&investigation.suspended_reason)
+        .bind(investigation.suspended_at)
+        .bind(&investigation.resume_from)
+        .bind(
+            investigation
+                .footprint
+                .as_ref()
+                .map(|f| serde_json::to_value(f).unwrap_or_default()),
+        )
+        .bind(serde_json::to_value(&investigation.cycles).unwrap_or_default())
+        .bind(investigation.version)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        for wo in &records.work_orders {
+            Self::insert_work_order(&mut tx, wo).await?;
+            Self::restore_work_order_outcome(&mut tx, wo).await?;
+        }
+
+        for assessment in &records.assessments {
+            Self::insert_assessment(&mut tx, assessment).await?;
+            sqlx::query("UPDATE assessments SET diff = $2, needs_review = $3 WHERE id = $1")
+                .bind(assessment.id.0)
+                .bind(
+                    assessment
+                        .diff
+                        .as_ref()
+                        .map(|d| serde_json::to_value(d).unwrap_or_default()),
+                )
+                .bind(assessment.needs_review)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Query(e.to_string()))?;
+        }
+
+        for feedback in &records.feedback {
+            let disputed: Vec<Uuid> = feedback.disputed_claim_ids.iter().map(|id| id.0).collect();
+            sqlx::query(
+                r#"
+                INSERT INTO assessment_feedback (assessment_id, rating, comments,
+                                                 disputed_claim_ids, submitted_by, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(feedback.assessment_id.0)
+            .bind(feedback.rating.as_db_str())
+            .bind(&feedback.comments)
+            .bind(&disputed)
+            .bind(&feedback.submitted_by)
+            .bind(feedback.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        }
+
+        for section in &records.scratchpad {
+            sqlx::query(
+                r#"
+                INSERT INTO scratchpad_sections (investigation_id, section, content,
+                                                 created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(investigation.id.0)
+            .bind(&section.section)
+            .bind(&section.content)
+            .bind(section.created_at)
+            .bind(section.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Set what a work order's Processor session recorded, which
+    /// `insert_work_order` leaves at the defaults of a new work order.
+    async fn restore_work_order_outcome(
+        conn: &mut sqlx::PgConnection,
+        wo: &WorkOrder,
+    ) -> Result<(), StoreError> {
+        sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET processor_id = $2, claims_produced_count = $3, retry_count = $4,
+                delivery = $5, failure_reason = $6, summary = $7, failure_detail = $8,
+                prompt_hash = $9, footprint = $10, contract_unmet = $11,
+                started_at = $12, completed_at = $13
+            WHERE id = $1
+            "#,
+        )
+        .bind(wo.id.0)
+        .bind(&wo.processor_id)
+        .bind(wo.claims_produced_count)
+        .bind(wo.retry_count)
+        .bind(wo.delivery)
+        .bind(&wo.failure_reason)
+        .bind(&wo.summary)
+        .bind(
+            wo.failure_detail
+                .as_ref()
+                .map(|d| serde_json::to_value(d).unwrap_or_default()),
+        )
+        .bind(&wo.prompt_hash)
+        .bind(
+            wo.footprint
+                .as_ref()
+                .map(|f| serde_json::to_value(f).unwrap_or_default()),
+        )
+        .bind(wo.contract_unmet)
+        .bind(wo.started_at)
+        .bind(wo.completed_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+}
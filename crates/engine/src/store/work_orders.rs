@@ -1,26 +1,59 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use autosint_common::ids::{InvestigationId, WorkOrderId};
-use autosint_common::types::{SourceGuidance, WorkOrder, WorkOrderPriority, WorkOrderStatus};
+use autosint_common::api::engine::PriorityWaitStats;
+use autosint_common::ids::{EntityId, InvestigationId, MonitorId, SourceDocumentId, WorkOrderId};
+use autosint_common::sanitize::sanitize_error;
+use autosint_common::types::{
+    FailureDetail, InvestigationEventKind, OutboundFootprint, SessionTelemetry, SourceGuidance,
+    WorkOrder, WorkOrderCreated, WorkOrderEffort, WorkOrderKind, WorkOrderMode, WorkOrderPriority,
+    WorkOrderStatus,
+};
 
 use super::{StoreClient, StoreError};
 
 impl StoreClient {
     /// Create a new work order record.
     pub async fn create_work_order(&self, wo: &WorkOrder) -> Result<WorkOrder, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Self::insert_work_order(&mut tx, wo).await?;
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(wo.clone())
+    }
+
+    /// Insert a work order and its `work_order_created` event on `conn`, so
+    /// they can share a caller's transaction. A proposal gets its event when
+    /// it is approved.
+    pub(super) async fn insert_work_order(
+        conn: &mut sqlx::PgConnection,
+        wo: &WorkOrder,
+    ) -> Result<(), StoreError> {
         let referenced_entities_json =
             serde_json::to_value(&wo.referenced_entities).unwrap_or_default();
         let source_guidance_json = wo
             .source_guidance
             .as_ref()
             .map(|sg| serde_json::to_value(sg).unwrap_or_default());
+        let expected_outputs_json = wo
+            .expected_outputs
+            .as_ref()
+            .map(|e| serde_json::to_value(e).unwrap_or_default());
 
         sqlx::query(
             r#"
             INSERT INTO work_orders (id, investigation_id, objective, status, priority,
-                                     referenced_entities, source_guidance, cycle, created_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                                     referenced_entities, source_guidance, not_before,
+                                     deadline, effort, mode, cycle, kind, source_document_id,
+                                     monitor_id, expected_outputs, proposed_by, rationale,
+                                     created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                    $17, $18, $19)
             "#,
         )
         .bind(wo.id.0)
@@ -30,13 +63,32 @@ impl StoreClient {
         .bind(wo.priority.as_db_int())
         .bind(&referenced_entities_json)
         .bind(&source_guidance_json)
+        .bind(wo.not_before)
+        .bind(wo.deadline)
+        .bind(wo.effort.as_str())
+        .bind(wo.mode.as_str())
         .bind(wo.cycle)
+        .bind(wo.kind.as_db_str())
+        .bind(wo.source_document_id.map(|id| id.0))
+        .bind(wo.monitor_id.map(|id| id.0))
+        .bind(&expected_outputs_json)
+        .bind(wo.proposed_by.map(|id| id.0))
+        .bind(&wo.rationale)
         .bind(wo.created_at)
-        .execute(&self.pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
 
-        Ok(wo.clone())
+        if wo.status.is_proposal() {
+            return Ok(());
+        }
+        let created = WorkOrderCreated {
+            work_order_id: wo.id,
+            cycle: wo.cycle,
+            priority: wo.priority,
+            not_before: wo.not_before,
+        };
+        Self::insert_event_payload(conn, wo.investigation_id, &created).await
     }
 
     /// Retrieve a work order by ID.
@@ -44,8 +96,11 @@ impl StoreClient {
         let row = sqlx::query_as::<_, WorkOrderRow>(
             r#"
             SELECT id, investigation_id, objective, status, priority,
-                   referenced_entities, source_guidance, processor_id,
-                   cycle, claims_produced_count, created_at, completed_at
+                   referenced_entities, source_guidance, not_before, deadline, effort, mode, processor_id,
+                   cycle, claims_produced_count, retry_count, delivery, failure_reason,
+                   summary, failure_detail, prompt_hash, kind, source_document_id, monitor_id, footprint,
+                   expected_outputs, contract_unmet, proposed_by, rationale,
+                   created_at, started_at, completed_at
             FROM work_orders
             WHERE id = $1
             "#,
@@ -60,7 +115,10 @@ impl StoreClient {
     }
 
     /// Update work order status. Optionally sets processor_id and claims_count.
-    /// Sets completed_at when transitioning to a terminal state.
+    /// Sets started_at on every move to processing and completed_at when
+    /// transitioning to a terminal state. Moving a work order that is already
+    /// processing to processing again means it was redelivered after its
+    /// Processor died, and counts as a retry.
     pub async fn update_work_order_status(
         &self,
         id: WorkOrderId,
@@ -70,14 +128,22 @@ impl StoreClient {
     ) -> Result<(), StoreError> {
         let is_terminal = matches!(status, WorkOrderStatus::Completed | WorkOrderStatus::Failed);
         let completed_at = if is_terminal { Some(Utc::now()) } else { None };
+        let started_at = if matches!(status, WorkOrderStatus::Processing) {
+            Some(Utc::now())
+        } else {
+            None
+        };
 
         sqlx::query(
             r#"
             UPDATE work_orders
-            SET status = $2,
+            SET retry_count = retry_count
+                    + CASE WHEN status = 'processing' AND $2 = 'processing' THEN 1 ELSE 0 END,
+                status = $2,
                 processor_id = COALESCE($3, processor_id),
                 claims_produced_count = COALESCE($4, claims_produced_count),
-                completed_at = COALESCE($5, completed_at)
+                completed_at = COALESCE($5, completed_at),
+                started_at = COALESCE($6, started_at)
             WHERE id = $1
             "#,
         )
@@ -86,6 +152,150 @@ impl StoreClient {
         .bind(processor_id)
         .bind(claims_count)
         .bind(completed_at)
+        .bind(started_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Claim a work order for processing, as `update_work_order_status` does for
+    /// `Processing`, but only if `delivery` is still current. Returns false when
+    /// the message was superseded by a re-enqueue and must not be processed.
+    pub async fn start_work_order(
+        &self,
+        id: WorkOrderId,
+        delivery: i32,
+        processor_id: &str,
+    ) -> Result<bool, StoreError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET retry_count = retry_count
+                    + CASE WHEN status = 'processing' THEN 1 ELSE 0 END,
+                status = 'processing',
+                processor_id = $3,
+                started_at = $4
+            WHERE id = $1
+              AND delivery = $2
+            "#,
+        )
+        .bind(id.0)
+        .bind(delivery)
+        .bind(processor_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move a scheduled or queued work order to `priority` and bump its
+    /// delivery, recording a `WorkOrderReprioritized` event in the same
+    /// transaction. Returns the updated work order, or None when it is no
+    /// longer waiting (a Processor claimed it first). The caller re-enqueues it.
+    pub async fn reprioritize_work_order(
+        &self,
+        id: WorkOrderId,
+        priority: WorkOrderPriority,
+        reason: Option<&str>,
+    ) -> Result<Option<WorkOrder>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let previous: Option<(i32,)> = sqlx::query_as(
+            r#"
+            SELECT priority
+            FROM work_orders
+            WHERE id = $1
+              AND status IN ('scheduled', 'queued')
+            FOR UPDATE
+            "#,
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        let Some((previous,)) = previous else {
+            return Ok(None);
+        };
+
+        let row = sqlx::query_as::<_, WorkOrderRow>(
+            r#"
+            UPDATE work_orders
+            SET priority = $2,
+                delivery = delivery + 1
+            WHERE id = $1
+            RETURNING id, investigation_id, objective, status, priority,
+                      referenced_entities, source_guidance, not_before, deadline, effort, mode, processor_id,
+                      cycle, claims_produced_count, retry_count, delivery, failure_reason,
+                      summary, failure_detail, prompt_hash, kind, source_document_id, monitor_id, footprint,
+                      expected_outputs, contract_unmet, proposed_by, rationale,
+                      created_at, started_at, completed_at
+            "#,
+        )
+        .bind(id.0)
+        .bind(priority.as_db_int())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        let wo = WorkOrder::from(row);
+
+        let payload = serde_json::json!({
+            "work_order_id": wo.id,
+            "from": parse_priority(previous).as_str(),
+            "to": priority.as_str(),
+            "reason": reason,
+        });
+        Self::insert_investigation_event(
+            &mut *tx,
+            wo.investigation_id,
+            InvestigationEventKind::WorkOrderReprioritized,
+            &payload,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(Some(wo))
+    }
+
+    /// Record how the Processor session for a work order ended and the hash
+    /// of the prompt it ran with (None if no session ran). The failure reason
+    /// is redacted and bounded before storage; the failure detail's errors are
+    /// expected to be sanitized already.
+    pub async fn record_work_order_outcome(
+        &self,
+        id: WorkOrderId,
+        failure_reason: Option<&str>,
+        summary: Option<&serde_json::Value>,
+        failure_detail: Option<&FailureDetail>,
+        prompt_hash: Option<&str>,
+    ) -> Result<(), StoreError> {
+        let failure_detail_json = failure_detail.and_then(|d| serde_json::to_value(d).ok());
+
+        sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET failure_reason = $2,
+                summary = $3,
+                failure_detail = $4,
+                prompt_hash = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(id.0)
+        .bind(failure_reason.map(sanitize_error))
+        .bind(summary)
+        .bind(failure_detail_json)
+        .bind(prompt_hash)
         .execute(&self.pool)
         .await
         .map_err(|e| StoreError::Query(e.to_string()))?;
@@ -93,6 +303,83 @@ impl StoreClient {
         Ok(())
     }
 
+    /// Mark a completed work order whose session fell short of its expected
+    /// outputs.
+    pub async fn mark_work_order_contract_unmet(&self, id: WorkOrderId) -> Result<(), StoreError> {
+        sqlx::query("UPDATE work_orders SET contract_unmet = true WHERE id = $1")
+            .bind(id.0)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Record the fetches and searches a work order's Processor session made.
+    pub async fn record_work_order_footprint(
+        &self,
+        id: WorkOrderId,
+        footprint: &OutboundFootprint,
+    ) -> Result<(), StoreError> {
+        sqlx::query("UPDATE work_orders SET footprint = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(serde_json::to_value(footprint).unwrap_or_default())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Record the Processor session's statistics for telemetry rollups.
+    pub async fn record_work_order_session_stats(
+        &self,
+        id: WorkOrderId,
+        stats: &SessionTelemetry,
+    ) -> Result<(), StoreError> {
+        sqlx::query("UPDATE work_orders SET session_stats = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(serde_json::to_value(stats).unwrap_or_default())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Footprint of an investigation: the recorded footprints of its work
+    /// orders summed, and how many work orders recorded one.
+    pub async fn investigation_footprint(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<(OutboundFootprint, u64), StoreError> {
+        let rows: Vec<(serde_json::Value,)> = sqlx::query_as(
+            r#"
+            SELECT footprint
+            FROM work_orders
+            WHERE investigation_id = $1
+              AND footprint IS NOT NULL
+            "#,
+        )
+        .bind(investigation_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let mut total = OutboundFootprint::default();
+        let mut work_orders = 0;
+        for (json,) in rows {
+            match serde_json::from_value::<OutboundFootprint>(json) {
+                Ok(footprint) => {
+                    total.merge(&footprint);
+                    work_orders += 1;
+                }
+                Err(e) => tracing::warn!(error = %e, "Unreadable work order footprint, skipping"),
+            }
+        }
+        Ok((total, work_orders))
+    }
+
     /// Get all work orders for an investigation, ordered by cycle and creation time.
     pub async fn get_work_orders_by_investigation(
         &self,
@@ -101,8 +388,11 @@ impl StoreClient {
         let rows = sqlx::query_as::<_, WorkOrderRow>(
             r#"
             SELECT id, investigation_id, objective, status, priority,
-                   referenced_entities, source_guidance, processor_id,
-                   cycle, claims_produced_count, created_at, completed_at
+                   referenced_entities, source_guidance, not_before, deadline, effort, mode, processor_id,
+                   cycle, claims_produced_count, retry_count, delivery, failure_reason,
+                   summary, failure_detail, prompt_hash, kind, source_document_id, monitor_id, footprint,
+                   expected_outputs, contract_unmet, proposed_by, rationale,
+                   created_at, started_at, completed_at
             FROM work_orders
             WHERE investigation_id = $1
             ORDER BY cycle, created_at
@@ -116,7 +406,35 @@ impl StoreClient {
         Ok(rows.into_iter().map(Into::into).collect())
     }
 
-    /// Count active (queued or processing) work orders for an investigation.
+    /// Scheduled or queued work orders whose deadline falls at or before
+    /// `cutoff`, soonest first.
+    pub async fn pending_work_orders_due_by(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<WorkOrder>, StoreError> {
+        let rows = sqlx::query_as::<_, WorkOrderRow>(
+            r#"
+            SELECT id, investigation_id, objective, status, priority,
+                   referenced_entities, source_guidance, not_before, deadline, effort, mode, processor_id,
+                   cycle, claims_produced_count, retry_count, delivery, failure_reason,
+                   summary, failure_detail, prompt_hash, kind, source_document_id, monitor_id, footprint,
+                   expected_outputs, contract_unmet, proposed_by, rationale,
+                   created_at, started_at, completed_at
+            FROM work_orders
+            WHERE status IN ('scheduled', 'queued')
+              AND deadline <= $1
+            ORDER BY deadline
+            "#,
+        )
+        .bind(cutoff)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Count active (scheduled, queued or processing) work orders for an investigation.
     pub async fn count_active_work_orders(
         &self,
         investigation_id: InvestigationId,
@@ -126,7 +444,60 @@ impl StoreClient {
             SELECT COUNT(*)
             FROM work_orders
             WHERE investigation_id = $1
-              AND status IN ('queued', 'processing')
+              AND status IN ('scheduled', 'queued', 'processing')
+            "#,
+        )
+        .bind(investigation_id.0)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(row.0)
+    }
+
+    /// Of `entity_ids`, those an active (scheduled, queued or processing) work
+    /// order of `kind` references.
+    pub async fn entities_with_active_work_orders(
+        &self,
+        kind: WorkOrderKind,
+        entity_ids: &[EntityId],
+    ) -> Result<Vec<EntityId>, StoreError> {
+        if entity_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<String> = entity_ids.iter().map(|id| id.to_string()).collect();
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT ref.id
+            FROM work_orders, jsonb_array_elements_text(referenced_entities) AS ref(id)
+            WHERE kind = $1
+              AND status IN ('scheduled', 'queued', 'processing')
+              AND ref.id = ANY($2)
+            "#,
+        )
+        .bind(kind.as_db_str())
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id,)| id.parse::<Uuid>().ok().map(EntityId::from_uuid))
+            .collect())
+    }
+
+    /// Latest `not_before` among an investigation's scheduled work orders, if any.
+    pub async fn latest_scheduled_not_before(
+        &self,
+        investigation_id: InvestigationId,
+    ) -> Result<Option<DateTime<Utc>>, StoreError> {
+        let row: (Option<DateTime<Utc>>,) = sqlx::query_as(
+            r#"
+            SELECT MAX(not_before)
+            FROM work_orders
+            WHERE investigation_id = $1
+              AND status = 'scheduled'
             "#,
         )
         .bind(investigation_id.0)
@@ -136,6 +507,220 @@ impl StoreClient {
 
         Ok(row.0)
     }
+
+    /// Count and wait-age percentiles (P50/P95, seconds as of `now`) of queued
+    /// work orders, one entry per priority, high → low. Scheduled orders start
+    /// waiting at `not_before`, not at creation.
+    pub async fn queued_work_order_wait_stats(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PriorityWaitStats>, StoreError> {
+        let rows: Vec<(i32, i64, f64, f64)> = sqlx::query_as(
+            r#"
+            SELECT priority,
+                   COUNT(*),
+                   percentile_cont(0.5) WITHIN GROUP (ORDER BY age),
+                   percentile_cont(0.95) WITHIN GROUP (ORDER BY age)
+            FROM (
+                SELECT priority,
+                       GREATEST(EXTRACT(EPOCH FROM ($1 - GREATEST(created_at,
+                                COALESCE(not_before, created_at))))::float8, 0) AS age
+                FROM work_orders
+                WHERE status = 'queued'
+            ) queued
+            GROUP BY priority
+            "#,
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(WorkOrderPriority::ALL
+            .into_iter()
+            .map(|priority| {
+                let row = rows.iter().find(|r| r.0 == priority.as_db_int());
+                PriorityWaitStats {
+                    priority,
+                    queued: row.map_or(0, |r| r.1.max(0) as u64),
+                    wait_age_p50_seconds: row.map_or(0.0, |r| r.2),
+                    wait_age_p95_seconds: row.map_or(0.0, |r| r.3),
+                }
+            })
+            .collect())
+    }
+
+    /// Move scheduled work orders to queued once the queue has released them.
+    /// Orders in any other state are left alone.
+    pub async fn mark_work_orders_queued(&self, ids: &[WorkOrderId]) -> Result<u64, StoreError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<Uuid> = ids.iter().map(|id| id.0).collect();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET status = 'queued'
+            WHERE id = ANY($1)
+              AND status = 'scheduled'
+            "#,
+        )
+        .bind(&ids)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record a Processor's proposed work order, unless its investigation
+    /// has already collected `max_per_investigation` proposals; None then.
+    /// Proposals are counted under a lock on the investigation row, so
+    /// concurrent sessions can't overshoot the cap.
+    pub async fn propose_work_order(
+        &self,
+        wo: &WorkOrder,
+        max_per_investigation: u32,
+    ) -> Result<Option<WorkOrder>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let locked: Option<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM investigations WHERE id = $1 FOR UPDATE")
+                .bind(wo.investigation_id.0)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| StoreError::Query(e.to_string()))?;
+        if locked.is_none() {
+            return Err(StoreError::NotFound(format!(
+                "Investigation {}",
+                wo.investigation_id
+            )));
+        }
+
+        let (proposals,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM work_orders
+            WHERE investigation_id = $1
+              AND proposed_by IS NOT NULL
+            "#,
+        )
+        .bind(wo.investigation_id.0)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        if proposals >= i64::from(max_per_investigation) {
+            return Ok(None);
+        }
+
+        Self::insert_work_order(&mut tx, wo).await?;
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(Some(wo.clone()))
+    }
+
+    /// Turn a pending proposal of `investigation_id` into a queued work order
+    /// of `cycle` at `priority`, created now, recording its
+    /// `work_order_created` event in the same transaction. Returns None when
+    /// it isn't a pending proposal of that investigation. The caller enqueues
+    /// it.
+    pub async fn approve_work_order_proposal(
+        &self,
+        id: WorkOrderId,
+        investigation_id: InvestigationId,
+        cycle: i32,
+        priority: WorkOrderPriority,
+    ) -> Result<Option<WorkOrder>, StoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        let row = sqlx::query_as::<_, WorkOrderRow>(
+            r#"
+            UPDATE work_orders
+            SET status = 'queued',
+                cycle = $3,
+                priority = $4,
+                created_at = NOW()
+            WHERE id = $1
+              AND investigation_id = $2
+              AND status = 'proposed'
+            RETURNING id, investigation_id, objective, status, priority,
+                      referenced_entities, source_guidance, not_before, deadline, effort, mode, processor_id,
+                      cycle, claims_produced_count, retry_count, delivery, failure_reason,
+                      summary, failure_detail, prompt_hash, kind, source_document_id, monitor_id, footprint,
+                      expected_outputs, contract_unmet, proposed_by, rationale,
+                      created_at, started_at, completed_at
+            "#,
+        )
+        .bind(id.0)
+        .bind(investigation_id.0)
+        .bind(cycle)
+        .bind(priority.as_db_int())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let wo = WorkOrder::from(row);
+
+        let created = WorkOrderCreated {
+            work_order_id: wo.id,
+            cycle: wo.cycle,
+            priority: wo.priority,
+            not_before: wo.not_before,
+        };
+        Self::insert_event_payload(&mut *tx, investigation_id, &created).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| StoreError::Query(e.to_string()))?;
+        Ok(Some(wo))
+    }
+
+    /// Dismiss the pending proposals of `investigation_id` among `ids`.
+    /// Returns the ones dismissed.
+    pub async fn dismiss_work_order_proposals(
+        &self,
+        investigation_id: InvestigationId,
+        ids: &[WorkOrderId],
+    ) -> Result<Vec<WorkOrderId>, StoreError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<Uuid> = ids.iter().map(|id| id.0).collect();
+
+        let rows: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE work_orders
+            SET status = 'dismissed',
+                completed_at = NOW()
+            WHERE id = ANY($1)
+              AND investigation_id = $2
+              AND status = 'proposed'
+            RETURNING id
+            "#,
+        )
+        .bind(&ids)
+        .bind(investigation_id.0)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| StoreError::Query(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id,)| WorkOrderId::from_uuid(id))
+            .collect())
+    }
 }
 
 /// Internal row type for sqlx deserialization.
@@ -148,10 +733,29 @@ struct WorkOrderRow {
     priority: i32,
     referenced_entities: Option<serde_json::Value>,
     source_guidance: Option<serde_json::Value>,
+    not_before: Option<chrono::DateTime<Utc>>,
+    deadline: Option<chrono::DateTime<Utc>>,
+    effort: String,
+    mode: String,
     processor_id: Option<String>,
     cycle: i32,
     claims_produced_count: i32,
+    retry_count: i32,
+    delivery: i32,
+    failure_reason: Option<String>,
+    summary: Option<serde_json::Value>,
+    failure_detail: Option<serde_json::Value>,
+    prompt_hash: Option<String>,
+    kind: String,
+    source_document_id: Option<Uuid>,
+    monitor_id: Option<Uuid>,
+    footprint: Option<serde_json::Value>,
+    expected_outputs: Option<serde_json::Value>,
+    contract_unmet: bool,
+    proposed_by: Option<Uuid>,
+    rationale: Option<String>,
     created_at: chrono::DateTime<Utc>,
+    started_at: Option<chrono::DateTime<Utc>>,
     completed_at: Option<chrono::DateTime<Utc>>,
 }
 
@@ -174,36 +778,72 @@ impl From<WorkOrderRow> for WorkOrder {
             priority: parse_priority(row.priority),
             referenced_entities,
             source_guidance,
+            not_before: row.not_before,
+            deadline: row.deadline,
+            effort: parse_effort(&row.effort),
+            mode: parse_mode(&row.mode),
             processor_id: row.processor_id,
             cycle: row.cycle,
             claims_produced_count: row.claims_produced_count,
+            retry_count: row.retry_count,
+            delivery: row.delivery,
+            failure_reason: row.failure_reason,
+            summary: row.summary,
+            failure_detail: row
+                .failure_detail
+                .and_then(|v| serde_json::from_value(v).ok()),
+            footprint: row.footprint.and_then(|v| serde_json::from_value(v).ok()),
+            prompt_hash: row.prompt_hash,
+            kind: parse_kind(&row.kind),
+            source_document_id: row.source_document_id.map(SourceDocumentId::from_uuid),
+            monitor_id: row.monitor_id.map(MonitorId::from_uuid),
+            expected_outputs: row
+                .expected_outputs
+                .and_then(|v| serde_json::from_value(v).ok()),
+            contract_unmet: row.contract_unmet,
+            proposed_by: row.proposed_by.map(WorkOrderId::from_uuid),
+            rationale: row.rationale,
             created_at: row.created_at,
+            started_at: row.started_at,
             completed_at: row.completed_at,
         }
     }
 }
 
 fn parse_work_order_status(s: &str) -> WorkOrderStatus {
-    match s {
-        "queued" => WorkOrderStatus::Queued,
-        "processing" => WorkOrderStatus::Processing,
-        "completed" => WorkOrderStatus::Completed,
-        "failed" => WorkOrderStatus::Failed,
-        other => {
-            tracing::warn!(
-                status = other,
-                "Unknown work order status, defaulting to Queued"
-            );
-            WorkOrderStatus::Queued
-        }
-    }
+    s.parse().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Unknown work order status, defaulting to Queued");
+        WorkOrderStatus::Queued
+    })
+}
+
+fn parse_effort(s: &str) -> WorkOrderEffort {
+    s.parse().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Unknown work order effort, defaulting to Standard");
+        WorkOrderEffort::Standard
+    })
+}
+
+fn parse_mode(s: &str) -> WorkOrderMode {
+    s.parse().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Unknown work order mode, defaulting to Focused");
+        WorkOrderMode::Focused
+    })
+}
+
+fn parse_kind(s: &str) -> WorkOrderKind {
+    s.parse().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "Unknown work order kind, defaulting to Research");
+        WorkOrderKind::Research
+    })
 }
 
 fn parse_priority(p: i32) -> WorkOrderPriority {
-    match p {
-        2 => WorkOrderPriority::High,
-        1 => WorkOrderPriority::Normal,
-        0 => WorkOrderPriority::Low,
-        _ => WorkOrderPriority::Normal,
-    }
+    WorkOrderPriority::from_db_int(p).unwrap_or_else(|| {
+        tracing::warn!(
+            priority = p,
+            "Unknown work order priority, defaulting to Normal"
+        );
+        WorkOrderPriority::Normal
+    })
 }
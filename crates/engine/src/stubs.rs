@@ -0,0 +1,333 @@
+//! Stub entity resolution.
+//!
+//! Extraction creates stub entities as placeholders for things a claim
+//! mentions in passing; most are never fleshed out. A resolution pass looks at
+//! stubs enough claims mention: a stub that a full entity of the same kind now
+//! matches (by name or embedding) is merged into it, and any other is queued
+//! for a low-priority `profile_entity` work order, up to a per-run cap.
+//! Optionally, old stubs nothing mentions any more are deleted.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+
+use autosint_common::api::engine::{StubEnrichment, StubMerge};
+use autosint_common::config::StubResolutionConfig;
+use autosint_common::types::{
+    AuditActor, AuditRecord, DomainEvent, WorkOrder, WorkOrderKind, WorkOrderMessage,
+    WorkOrderPriority, WorkOrderStatus,
+};
+use autosint_common::{EntityId, InvestigationId};
+
+use crate::audit::AuditLog;
+use crate::events::EventBus;
+use crate::graph::{GraphClient, GraphError, StubCandidate, StubMatch};
+use crate::maintenance::ReadOnlyMode;
+use crate::queue::{QueueClient, QueueError};
+use crate::store::{StoreClient, StoreError};
+use crate::supervisor;
+
+pub use autosint_common::api::engine::StubResolutionReport;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StubResolutionError {
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+
+    #[error(transparent)]
+    Queue(#[from] QueueError),
+}
+
+/// Run one resolution pass. With `scope`, only stubs the investigation's
+/// claims mention are considered and nothing is deleted. Merges and deletions
+/// are audited as the engine's own.
+pub async fn resolve_stubs(
+    graph: &GraphClient,
+    store: &StoreClient,
+    queue: &QueueClient,
+    audit: &AuditLog,
+    events: &EventBus,
+    config: &StubResolutionConfig,
+    scope: Option<InvestigationId>,
+) -> Result<StubResolutionReport, StubResolutionError> {
+    let start = Instant::now();
+    let mut report = StubResolutionReport::default();
+
+    let stubs = graph
+        .stubs_to_resolve(config.min_claims, scope, config.max_stubs_per_run)
+        .await?;
+    let ids: Vec<_> = stubs.iter().map(|s| s.entity.id).collect();
+    let being_profiled: HashSet<_> = store
+        .entities_with_active_work_orders(WorkOrderKind::ProfileEntity, &ids)
+        .await?
+        .into_iter()
+        .collect();
+
+    for stub in &stubs {
+        let found = graph
+            .find_stub_match(
+                &stub.entity,
+                config.name_threshold,
+                config.embedding_threshold,
+            )
+            .await?;
+        if let Some(found) = found {
+            match merge_stub(graph, store, stub, &found).await {
+                Ok(()) => {
+                    let merge = StubMerge {
+                        stub_id: stub.entity.id,
+                        target_id: found.entity_id,
+                        matched_by: found.matched_by.to_string(),
+                        similarity: found.similarity,
+                    };
+                    audit.record(stub_audit_record(
+                        "stub_merge",
+                        found.entity_id,
+                        serde_json::to_value(&merge).unwrap_or_default(),
+                    ));
+                    report.merged.push(merge);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        stub_id = %stub.entity.id,
+                        target_id = %found.entity_id,
+                        error = %e,
+                        "Stub merge failed"
+                    );
+                    report.deferred += 1;
+                }
+            }
+            continue;
+        }
+
+        // Profiling runs under an investigation; stubs only monitor claims
+        // mention wait for one.
+        let Some(investigation_id) = scope.or(stub.investigation_id) else {
+            report.deferred += 1;
+            continue;
+        };
+        if being_profiled.contains(&stub.entity.id)
+            || report.enqueued.len() >= config.max_enrichments_per_run as usize
+        {
+            report.deferred += 1;
+            continue;
+        }
+        match enqueue_profile(store, queue, events, stub, investigation_id).await {
+            Ok(enrichment) => report.enqueued.push(enrichment),
+            Err(e) => {
+                tracing::error!(
+                    stub_id = %stub.entity.id,
+                    error = %e,
+                    "Failed to enqueue stub profile work order"
+                );
+                report.deferred += 1;
+            }
+        }
+    }
+
+    if let (None, Some(days)) = (scope, config.delete_unreferenced_after_days) {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+        report.deleted = graph
+            .delete_unreferenced_stubs(cutoff, config.max_stubs_per_run)
+            .await?;
+        for id in &report.deleted {
+            tracing::info!(entity_id = %id, "Deleted unreferenced stub");
+            audit.record(stub_audit_record(
+                "stub_delete",
+                *id,
+                serde_json::json!({ "older_than_days": days }),
+            ));
+        }
+    }
+
+    metrics::counter!("stubs.resolution.merged").increment(report.merged.len() as u64);
+    metrics::counter!("stubs.resolution.enqueued").increment(report.enqueued.len() as u64);
+    metrics::counter!("stubs.resolution.deleted").increment(report.deleted.len() as u64);
+    metrics::counter!("stubs.resolution.deferred").increment(report.deferred);
+    metrics::histogram!("stubs.resolution.duration").record(start.elapsed().as_secs_f64());
+    tracing::info!(
+        considered = stubs.len(),
+        merged = report.merged.len(),
+        enqueued = report.enqueued.len(),
+        deleted = report.deleted.len(),
+        deferred = report.deferred,
+        "Stub resolution pass finished"
+    );
+    Ok(report)
+}
+
+fn stub_audit_record(
+    operation: &str,
+    entity_id: EntityId,
+    digest: serde_json::Value,
+) -> AuditRecord {
+    AuditRecord {
+        actor: AuditActor::system(),
+        investigation_id: None,
+        work_order_id: None,
+        operation: operation.to_string(),
+        target_type: "entity".to_string(),
+        target_id: Some(entity_id.to_string()),
+        digest,
+        recorded_at: chrono::Utc::now(),
+    }
+}
+
+/// Merge the stub into the full entity it matches, under the same exclusive
+/// locks and tombstone the `merge_entities` tool uses.
+async fn merge_stub(
+    graph: &GraphClient,
+    store: &StoreClient,
+    stub: &StubCandidate,
+    found: &StubMatch,
+) -> Result<(), StubResolutionError> {
+    let mut guard = store
+        .lock_entities_exclusive(&[stub.entity.id, found.entity_id])
+        .await?;
+    let reason = format!(
+        "Stub resolution: {} similarity {:.3}",
+        found.matched_by, found.similarity
+    );
+    let merged = graph
+        .merge_entities(stub.entity.id, found.entity_id, Some(&reason), false)
+        .await;
+    if merged.is_ok() {
+        if let Err(e) = guard.record_merge(stub.entity.id, found.entity_id).await {
+            tracing::warn!(
+                source = %stub.entity.id,
+                target = %found.entity_id,
+                error = %e,
+                "Failed to record merge tombstone"
+            );
+        }
+    }
+    if let Err(e) = guard.release().await {
+        tracing::warn!(error = %e, "Failed to release entity locks");
+    }
+    let merged = merged?;
+
+    tracing::info!(
+        stub_id = %stub.entity.id,
+        stub_name = %stub.entity.canonical_name,
+        target_id = %merged.id,
+        target_name = %merged.canonical_name,
+        matched_by = found.matched_by,
+        similarity = found.similarity,
+        claims = stub.claim_count,
+        "Merged stub into matching entity"
+    );
+    Ok(())
+}
+
+/// Create and enqueue a low-priority `profile_entity` work order for the stub.
+async fn enqueue_profile(
+    store: &StoreClient,
+    queue: &QueueClient,
+    events: &EventBus,
+    stub: &StubCandidate,
+    investigation_id: InvestigationId,
+) -> Result<StubEnrichment, StubResolutionError> {
+    let mut work_order = WorkOrder::new(
+        investigation_id,
+        profile_objective(stub),
+        WorkOrderPriority::Low,
+    );
+    work_order.kind = WorkOrderKind::ProfileEntity;
+    work_order.referenced_entities = vec![stub.entity.id];
+    store.create_work_order(&work_order).await?;
+
+    let msg = WorkOrderMessage::from(&work_order);
+    match queue.enqueue(&msg, &work_order.priority).await {
+        Ok(enqueued) => {
+            events
+                .publish(DomainEvent::WorkOrderEnqueued {
+                    investigation_id,
+                    enqueued: enqueued.event(&msg, &work_order.priority),
+                })
+                .await
+        }
+        Err(e) => {
+            store
+                .update_work_order_status(work_order.id, &WorkOrderStatus::Failed, None, None)
+                .await?;
+            return Err(e.into());
+        }
+    }
+
+    tracing::info!(
+        stub_id = %stub.entity.id,
+        work_order_id = %work_order.id,
+        investigation_id = %investigation_id,
+        "Queued stub for profiling"
+    );
+    Ok(StubEnrichment {
+        stub_id: stub.entity.id,
+        work_order_id: work_order.id,
+        investigation_id,
+    })
+}
+
+fn profile_objective(stub: &StubCandidate) -> String {
+    format!(
+        "Profile the {} \"{}\" (entity {}), a stub {} claims mention: establish what it is and \
+         fill in its summary, aliases and identifiers with update_entity.",
+        stub.entity.kind, stub.entity.canonical_name, stub.entity.id, stub.claim_count
+    )
+}
+
+/// Spawn a background task running a resolution pass every
+/// `interval_minutes`. Runs are skipped while the engine is read-only, and
+/// hold the `stub_resolution` singleton lock in `store`.
+pub fn spawn_stub_resolution_task(
+    graph: Arc<GraphClient>,
+    store: Arc<StoreClient>,
+    queue: Arc<QueueClient>,
+    audit: AuditLog,
+    events: EventBus,
+    config: StubResolutionConfig,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.interval_minutes as u64 * 60);
+
+    supervisor::spawn_supervised_restarting("stub_resolution", move || {
+        let graph = Arc::clone(&graph);
+        let store = Arc::clone(&store);
+        let queue = Arc::clone(&queue);
+        let audit = audit.clone();
+        let events = events.clone();
+        let config = config.clone();
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                interval_minutes = config.interval_minutes,
+                "Stub resolution task started"
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping stub resolution");
+                    continue;
+                }
+
+                let pass = async {
+                    if let Err(e) =
+                        resolve_stubs(&graph, &store, &queue, &audit, &events, &config, None).await
+                    {
+                        metrics::counter!("stubs.resolution.failures").increment(1);
+                        tracing::error!(error = %e, "Stub resolution pass failed");
+                    }
+                };
+                if let Err(e) = store.try_run_exclusive("stub_resolution", pass).await {
+                    tracing::error!(error = %e, "Failed to take the stub resolution lock");
+                }
+            }
+        }
+    })
+}
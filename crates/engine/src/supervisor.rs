@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::task::{JoinError, JoinHandle};
+
+pub use autosint_common::api::engine::TaskStatus;
+
+/// First restart delay for a critical task after a panic.
+const INITIAL_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound on the restart delay. A task that ran at least this long before
+/// panicking starts again from the initial backoff.
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Process-wide registry of supervised tasks. Uses std::sync::Mutex because
+/// it is never held across await points.
+fn registry() -> &'static Mutex<BTreeMap<&'static str, TaskStatus>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<&'static str, TaskStatus>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+fn update(name: &'static str, critical: bool, f: impl FnOnce(&mut TaskStatus)) {
+    let mut tasks = registry().lock().unwrap();
+    let status = tasks.entry(name).or_insert_with(|| TaskStatus {
+        name: name.to_string(),
+        critical,
+        running: 0,
+        panics: 0,
+        restarts: 0,
+    });
+    f(status);
+}
+
+/// Snapshot of all tasks supervised since process start, ordered by name.
+pub fn snapshot() -> Vec<TaskStatus> {
+    registry().lock().unwrap().values().cloned().collect()
+}
+
+/// Spawn a one-shot task whose panic is logged and counted instead of vanishing
+/// with its `JoinHandle`. Aborting the returned handle aborts the task.
+pub fn spawn_supervised<F>(name: &'static str, fut: F) -> JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let mut running = RunningTask::spawn(name, false, fut);
+    tokio::spawn(async move {
+        if let Err(e) = running.join().await {
+            record_failure(name, e);
+        }
+    })
+}
+
+/// Spawn a critical long-running task. `factory` builds the task's future; if it
+/// panics, a fresh one is started after a capped exponential backoff. The
+/// supervisor exits when the task returns normally or is cancelled.
+pub fn spawn_supervised_restarting<F, Fut>(name: &'static str, factory: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    spawn_restarting_with_backoff(name, factory, INITIAL_RESTART_BACKOFF, MAX_RESTART_BACKOFF)
+}
+
+fn spawn_restarting_with_backoff<F, Fut>(
+    name: &'static str,
+    factory: F,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    update(name, true, |_| {});
+
+    tokio::spawn(async move {
+        let mut backoff = initial_backoff;
+        loop {
+            let started = Instant::now();
+            let mut running = RunningTask::spawn(name, true, factory());
+            let result = running.join().await;
+            drop(running);
+
+            match result {
+                Ok(()) => {
+                    tracing::warn!(task = name, "Supervised task exited");
+                    return;
+                }
+                Err(e) if e.is_panic() => {
+                    record_failure(name, e);
+                }
+                Err(_) => return,
+            }
+
+            if started.elapsed() >= max_backoff {
+                backoff = initial_backoff;
+            }
+
+            tracing::warn!(
+                task = name,
+                backoff_ms = backoff.as_millis() as u64,
+                "Restarting supervised task after panic"
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(max_backoff);
+
+            metrics::counter!("autosint.task.restarts", "task" => name).increment(1);
+            update(name, true, |s| s.restarts += 1);
+        }
+    })
+}
+
+/// A spawned task tracked in the registry. Dropping it aborts the task, so
+/// cancelling a supervisor also cancels what it supervises.
+struct RunningTask {
+    name: &'static str,
+    critical: bool,
+    handle: JoinHandle<()>,
+}
+
+impl RunningTask {
+    fn spawn<F>(name: &'static str, critical: bool, fut: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        update(name, critical, |s| s.running += 1);
+        Self {
+            name,
+            critical,
+            handle: tokio::spawn(fut),
+        }
+    }
+
+    async fn join(&mut self) -> Result<(), JoinError> {
+        (&mut self.handle).await
+    }
+}
+
+impl Drop for RunningTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+        update(self.name, self.critical, |s| {
+            s.running = s.running.saturating_sub(1)
+        });
+    }
+}
+
+fn record_failure(name: &'static str, error: JoinError) {
+    if !error.is_panic() {
+        tracing::debug!(task = name, "Supervised task cancelled");
+        return;
+    }
+
+    let payload = error.into_panic();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".into());
+
+    tracing::error!(task = name, panic = %message, "Supervised task panicked");
+    metrics::counter!("autosint.task.panics", "task" => name).increment(1);
+    update(name, false, |s| s.panics += 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use metrics_exporter_prometheus::PrometheusBuilder;
+
+    fn status(name: &str) -> TaskStatus {
+        snapshot().into_iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_restarts_and_counts_metric() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        // Current-thread runtime: every spawned task records into this recorder.
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&attempts);
+        let supervisor = spawn_restarting_with_backoff(
+            "test_restarting",
+            move || {
+                let counter = Arc::clone(&counter);
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                        panic!("deliberate test panic");
+                    }
+                }
+            },
+            Duration::from_millis(1),
+            Duration::from_millis(10),
+        );
+        supervisor.await.unwrap();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        let status = status("test_restarting");
+        assert!(status.critical);
+        assert_eq!(status.panics, 2);
+        assert_eq!(status.restarts, 2);
+        assert_eq!(status.running, 0);
+
+        let rendered = handle.render();
+        assert!(rendered.contains("autosint_task_panics{task=\"test_restarting\"} 2"));
+        assert!(rendered.contains("autosint_task_restarts{task=\"test_restarting\"} 2"));
+    }
+
+    #[tokio::test]
+    async fn test_one_shot_panic_is_contained() {
+        let handle = spawn_supervised("test_one_shot", async {
+            panic!("deliberate test panic");
+        });
+        handle.await.unwrap();
+
+        let status = status("test_one_shot");
+        assert!(!status.critical);
+        assert_eq!(status.panics, 1);
+        assert_eq!(status.restarts, 0);
+        assert_eq!(status.running, 0);
+    }
+
+    #[tokio::test]
+    async fn test_abort_cancels_supervised_task() {
+        let handle = spawn_supervised_restarting("test_abort", || async {
+            std::future::pending::<()>().await;
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(status("test_abort").running, 1);
+
+        handle.abort();
+        let _ = handle.await;
+        let status = status("test_abort");
+        assert_eq!(status.running, 0);
+        assert_eq!(status.panics, 0);
+    }
+}
@@ -0,0 +1,559 @@
+//! Telemetry rollups: how sessions ran per prompt hash, for comparing prompt
+//! versions within and across deployments.
+//!
+//! Each window, Processor sessions (from their work orders' recorded
+//! statistics) and Analyst sessions (from their `analyst_session_finished`
+//! events) are grouped by prompt hash and summarized into a
+//! [`PromptRollup`]: counts, turns, tool calls by name, outcomes and tokens.
+//! Rollups are stored locally (GET /telemetry/rollups) and, with a
+//! collector configured, pushed there signed like a webhook delivery. A
+//! failed push leaves the rollups unpushed for the next run; it never
+//! affects sessions or the local history.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use tokio::task::JoinHandle;
+
+use autosint_common::config::TelemetryConfig;
+use autosint_common::types::{telemetry_key, PromptRollup, TelemetryPush, TelemetryRole};
+use autosint_common::webhook::{self, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+use crate::maintenance::ReadOnlyMode;
+use crate::store::{SessionSample, StoreClient};
+use crate::supervisor;
+
+/// Complete windows before the latest that each run rolls up again if they
+/// are missing, so a few hours of downtime don't leave gaps.
+const BACKFILL_WINDOWS: u32 = 3;
+
+/// Most rollups sent in one push.
+const PUSH_BATCH: i64 = 100;
+
+const PUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The latest complete window of `window_hours` ending at or before `now`,
+/// aligned to the Unix epoch.
+pub fn latest_window(now: DateTime<Utc>, window_hours: u32) -> (DateTime<Utc>, DateTime<Utc>) {
+    let length = i64::from(window_hours.max(1)) * 3600;
+    let end_secs = now.timestamp().div_euclid(length) * length;
+    let end = Utc.timestamp_opt(end_secs, 0).single().unwrap_or(now);
+    (end - chrono::Duration::seconds(length), end)
+}
+
+/// Roll up one role's sessions in a window, one rollup per prompt hash.
+/// Tool and outcome names that aren't plain identifiers are counted under
+/// `other`.
+pub fn aggregate(
+    role: TelemetryRole,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+    samples: &[SessionSample],
+) -> Vec<PromptRollup> {
+    let mut by_hash: BTreeMap<&str, Vec<&SessionSample>> = BTreeMap::new();
+    for sample in samples {
+        by_hash
+            .entry(sample.prompt_hash.as_str())
+            .or_default()
+            .push(sample);
+    }
+
+    by_hash
+        .into_iter()
+        .map(|(prompt_hash, sessions)| {
+            let count = sessions.len() as u64;
+            let mut turns: Vec<u32> = sessions.iter().map(|s| s.turns).collect();
+            let mut tool_calls_by_name: BTreeMap<String, u64> = BTreeMap::new();
+            let mut outcomes: BTreeMap<String, u64> = BTreeMap::new();
+            for session in &sessions {
+                for (name, calls) in &session.tool_calls_by_name {
+                    *tool_calls_by_name
+                        .entry(telemetry_key(name).to_string())
+                        .or_default() += u64::from(*calls);
+                }
+                *outcomes
+                    .entry(telemetry_key(&session.outcome).to_string())
+                    .or_default() += 1;
+            }
+            let claims: Vec<u64> = sessions.iter().filter_map(|s| s.claims).collect();
+            let forced: Vec<bool> = sessions.iter().filter_map(|s| s.forced_final).collect();
+
+            PromptRollup {
+                role,
+                prompt_hash: telemetry_key(prompt_hash).to_string(),
+                window_start,
+                window_end,
+                sessions: count,
+                mean_turns: turns.iter().map(|&t| f64::from(t)).sum::<f64>() / count as f64,
+                median_turns: median(&mut turns),
+                tool_calls: sessions.iter().map(|s| u64::from(s.tool_calls)).sum(),
+                tool_calls_by_name,
+                outcomes,
+                input_tokens: sessions.iter().map(|s| s.input_tokens).sum(),
+                output_tokens: sessions.iter().map(|s| s.output_tokens).sum(),
+                claims_per_session: (!claims.is_empty())
+                    .then(|| claims.iter().sum::<u64>() as f64 / claims.len() as f64),
+                forced_final_rate: (!forced.is_empty())
+                    .then(|| forced.iter().filter(|&&f| f).count() as f64 / forced.len() as f64),
+            }
+        })
+        .collect()
+}
+
+/// Median of `values`; the mean of the middle two for an even count.
+fn median(values: &mut [u32]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (f64::from(values[mid - 1]) + f64::from(values[mid])) / 2.0
+    } else {
+        f64::from(values[mid])
+    }
+}
+
+/// Pushes rollups to the collector, retrying with backoff.
+#[derive(Clone)]
+pub struct RollupPusher {
+    http: reqwest::Client,
+    url: String,
+    secret: String,
+    deployment: String,
+    attempts: u32,
+    retry_delay: Duration,
+}
+
+impl RollupPusher {
+    /// A pusher for `config.collector_url`, or None when no collector is
+    /// configured. The secret is read from `config.secret_env`.
+    pub fn from_env(
+        config: &TelemetryConfig,
+        http: reqwest::Client,
+    ) -> Result<Option<Self>, String> {
+        let Some(url) = &config.collector_url else {
+            return Ok(None);
+        };
+        let secret = match std::env::var(&config.secret_env) {
+            Ok(secret) if !secret.is_empty() => secret,
+            _ => {
+                return Err(format!(
+                    "{} is not set (telemetry collector secret)",
+                    config.secret_env
+                ))
+            }
+        };
+        Ok(Some(Self::new(
+            http,
+            url.clone(),
+            secret,
+            config.deployment.clone(),
+            config.push_attempts,
+        )))
+    }
+
+    pub fn new(
+        http: reqwest::Client,
+        url: String,
+        secret: String,
+        deployment: String,
+        attempts: u32,
+    ) -> Self {
+        Self {
+            http,
+            url,
+            secret,
+            deployment,
+            attempts: attempts.max(1),
+            retry_delay: Duration::from_secs(2),
+        }
+    }
+
+    /// POST `rollups` to the collector, signed with the shared secret.
+    /// Retries failed attempts, doubling the delay each time; returns the
+    /// last error once attempts run out.
+    pub async fn push(&self, rollups: &[PromptRollup]) -> Result<(), String> {
+        let body = serde_json::to_string(&TelemetryPush {
+            deployment: self.deployment.clone(),
+            sent_at: Utc::now(),
+            rollups: rollups.to_vec(),
+        })
+        .map_err(|e| e.to_string())?;
+
+        let mut delay = self.retry_delay;
+        let mut attempt = 1;
+        loop {
+            match self.send(&body).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt >= self.attempts => return Err(e),
+                Err(e) => {
+                    tracing::warn!(attempt, error = %e, "Telemetry push failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn send(&self, body: &str) -> Result<(), String> {
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .http
+            .post(&self.url)
+            .timeout(PUSH_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                webhook::sign(self.secret.as_bytes(), timestamp, body.as_bytes()),
+            )
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let text = response.text().await.unwrap_or_default();
+        Err(format!(
+            "HTTP {}: {}",
+            status,
+            text.chars().take(200).collect::<String>()
+        ))
+    }
+}
+
+/// Roll up the latest complete windows that aren't stored yet, then push
+/// whatever the collector hasn't received. Failures are logged and left for
+/// the next run.
+pub async fn run_rollups(
+    store: &StoreClient,
+    config: &TelemetryConfig,
+    pusher: Option<&RollupPusher>,
+) {
+    let (mut start, mut end) = latest_window(Utc::now(), config.window_hours);
+    let length = end - start;
+    for _ in 0..=BACKFILL_WINDOWS {
+        for role in TelemetryRole::ALL {
+            let samples = match role {
+                TelemetryRole::Processor => store.processor_session_samples(start, end).await,
+                TelemetryRole::Analyst => store.analyst_session_samples(start, end).await,
+            };
+            let rollups = match samples {
+                Ok(samples) => aggregate(role, start, end, &samples),
+                Err(e) => {
+                    tracing::error!(role = role.as_db_str(), error = %e, "Failed to read session statistics for telemetry");
+                    continue;
+                }
+            };
+            match store.insert_telemetry_rollups(&rollups).await {
+                Ok(written) => {
+                    metrics::counter!("telemetry.rollups_written").increment(written);
+                }
+                Err(e) => {
+                    tracing::error!(role = role.as_db_str(), error = %e, "Failed to store telemetry rollups");
+                }
+            }
+        }
+        end = start;
+        start -= length;
+    }
+
+    if let Some(pusher) = pusher {
+        push_pending(store, pusher).await;
+    }
+}
+
+/// Push unpushed rollups in batches until none are left or a push fails.
+async fn push_pending(store: &StoreClient, pusher: &RollupPusher) {
+    loop {
+        let pending = match store.unpushed_telemetry_rollups(PUSH_BATCH).await {
+            Ok(pending) if !pending.is_empty() => pending,
+            Ok(_) => return,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to read unpushed telemetry rollups");
+                return;
+            }
+        };
+        let (ids, rollups): (Vec<i64>, Vec<PromptRollup>) = pending.into_iter().unzip();
+        if let Err(e) = pusher.push(&rollups).await {
+            metrics::counter!("telemetry.pushes", "outcome" => "failed").increment(1);
+            tracing::warn!(rollups = rollups.len(), error = %e, "Telemetry push failed; will retry at the next check");
+            return;
+        }
+        metrics::counter!("telemetry.pushes", "outcome" => "delivered").increment(1);
+        if let Err(e) = store.mark_telemetry_rollups_pushed(&ids).await {
+            tracing::error!(error = %e, "Failed to mark telemetry rollups pushed");
+            return;
+        }
+    }
+}
+
+/// Spawn a background task that rolls up and pushes telemetry every
+/// `telemetry.check_interval_minutes`. Runs are skipped while the engine is
+/// read-only, and hold the `telemetry_rollups` singleton lock in `store`.
+pub fn spawn_telemetry_task(
+    store: Arc<StoreClient>,
+    config: TelemetryConfig,
+    pusher: Option<RollupPusher>,
+    read_only: ReadOnlyMode,
+) -> JoinHandle<()> {
+    let interval = Duration::from_secs(config.check_interval_minutes as u64 * 60);
+
+    supervisor::spawn_supervised_restarting("telemetry_rollups", move || {
+        let store = Arc::clone(&store);
+        let config = config.clone();
+        let pusher = pusher.clone();
+        let read_only = read_only.clone();
+        async move {
+            tracing::info!(
+                window_hours = config.window_hours,
+                collector = pusher.is_some(),
+                "Telemetry rollup task started"
+            );
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                if read_only.is_enabled() {
+                    tracing::debug!("Engine is read-only, skipping telemetry rollups");
+                    continue;
+                }
+
+                let run = run_rollups(&store, &config, pusher.as_ref());
+                if let Err(e) = store.try_run_exclusive("telemetry_rollups", run).await {
+                    tracing::error!(error = %e, "Failed to take the telemetry rollup lock");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+
+    use super::*;
+
+    fn sample(hash: &str, outcome: &str, turns: u32, tools: &[(&str, u32)]) -> SessionSample {
+        let tool_calls_by_name: BTreeMap<String, u32> =
+            tools.iter().map(|&(n, c)| (n.to_string(), c)).collect();
+        SessionSample {
+            prompt_hash: hash.into(),
+            outcome: outcome.into(),
+            turns,
+            tool_calls: tool_calls_by_name.values().sum(),
+            tool_calls_by_name,
+            input_tokens: 1000,
+            output_tokens: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_latest_window() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 17, 13, 45, 0).unwrap();
+        let (start, end) = latest_window(now, 24);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 10, 17, 0, 0, 0).unwrap());
+        let (start, end) = latest_window(now, 6);
+        assert_eq!(start, Utc.with_ymd_and_hms(2026, 10, 17, 6, 0, 0).unwrap());
+        assert_eq!(end, Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_aggregate_processor_sessions() {
+        let (start, end) = latest_window(Utc::now(), 24);
+        let mut samples = vec![
+            sample(
+                "aaa",
+                "completed",
+                4,
+                &[("web_search", 2), ("create_claim", 3)],
+            ),
+            sample("aaa", "completed", 10, &[("web_search", 1)]),
+            sample("aaa", "max_turns_reached", 6, &[("fetch_url", 5)]),
+            sample("aaa", "completed", 8, &[("Search for the Crius", 1)]),
+            sample("bbb", "failed", 3, &[]),
+        ];
+        for (s, claims) in samples.iter_mut().zip([3, 0, 1, 4, 0]) {
+            s.claims = Some(claims);
+        }
+
+        let rollups = aggregate(TelemetryRole::Processor, start, end, &samples);
+        assert_eq!(rollups.len(), 2);
+        let a = &rollups[0];
+        assert_eq!(a.prompt_hash, "aaa");
+        assert_eq!(a.sessions, 4);
+        assert_eq!(a.mean_turns, 7.0);
+        // Turns 4, 6, 8, 10: the middle two averaged.
+        assert_eq!(a.median_turns, 7.0);
+        assert_eq!(a.tool_calls, 12);
+        assert_eq!(a.tool_calls_by_name["web_search"], 3);
+        assert_eq!(a.tool_calls_by_name["create_claim"], 3);
+        assert_eq!(a.tool_calls_by_name["fetch_url"], 5);
+        // Free text never becomes a key.
+        assert_eq!(a.tool_calls_by_name["other"], 1);
+        assert_eq!(a.outcomes["completed"], 3);
+        assert_eq!(a.outcomes["max_turns_reached"], 1);
+        assert_eq!(a.input_tokens, 4000);
+        assert_eq!(a.output_tokens, 400);
+        assert_eq!(a.claims_per_session, Some(2.0));
+        assert_eq!(a.forced_final_rate, None);
+
+        let b = &rollups[1];
+        assert_eq!(b.sessions, 1);
+        assert_eq!(b.median_turns, 3.0);
+        assert!(b.tool_calls_by_name.is_empty());
+        assert_eq!(b.claims_per_session, Some(0.0));
+    }
+
+    #[test]
+    fn test_aggregate_analyst_sessions() {
+        let (start, end) = latest_window(Utc::now(), 24);
+        let samples: Vec<_> = [(5, false), (9, true), (2, false)]
+            .into_iter()
+            .map(|(turns, forced)| SessionSample {
+                forced_final: Some(forced),
+                ..sample("ccc", "work_orders_created", turns, &[])
+            })
+            .collect();
+
+        let rollups = aggregate(TelemetryRole::Analyst, start, end, &samples);
+        assert_eq!(rollups.len(), 1);
+        assert_eq!(rollups[0].role, TelemetryRole::Analyst);
+        assert_eq!(rollups[0].median_turns, 5.0);
+        assert!((rollups[0].mean_turns - 16.0 / 3.0).abs() < 1e-9);
+        assert!((rollups[0].forced_final_rate.unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(rollups[0].claims_per_session, None);
+    }
+
+    #[derive(Clone)]
+    struct Collector {
+        secret: &'static str,
+        /// Requests answered 500 before the collector starts accepting.
+        failures: Arc<AtomicU32>,
+        received: Arc<tokio::sync::Mutex<Vec<TelemetryPush>>>,
+    }
+
+    async fn collect(
+        State(collector): State<Collector>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("")
+        };
+        if webhook::verify(
+            collector.secret.as_bytes(),
+            header(TIMESTAMP_HEADER),
+            header(SIGNATURE_HEADER),
+            &body,
+            Utc::now().timestamp(),
+            300,
+        )
+        .is_err()
+        {
+            return StatusCode::UNAUTHORIZED;
+        }
+        if collector
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+        let push: TelemetryPush = serde_json::from_slice(&body).unwrap();
+        collector.received.lock().await.push(push);
+        StatusCode::NO_CONTENT
+    }
+
+    async fn mock_collector(failures: u32) -> (String, Collector) {
+        let collector = Collector {
+            secret: "collector-secret",
+            failures: Arc::new(AtomicU32::new(failures)),
+            received: Default::default(),
+        };
+        let app = Router::new()
+            .route("/rollups", post(collect))
+            .with_state(collector.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/rollups", addr), collector)
+    }
+
+    fn pusher(url: String, secret: &str, attempts: u32) -> RollupPusher {
+        RollupPusher {
+            retry_delay: Duration::from_millis(10),
+            ..RollupPusher::new(
+                reqwest::Client::new(),
+                url,
+                secret.into(),
+                "eu-prod".into(),
+                attempts,
+            )
+        }
+    }
+
+    fn rollups() -> Vec<PromptRollup> {
+        let (start, end) = latest_window(Utc::now(), 24);
+        aggregate(
+            TelemetryRole::Processor,
+            start,
+            end,
+            &[sample("aaa", "completed", 4, &[("web_search", 2)])],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_push_is_signed_and_retried() {
+        let (url, collector) = mock_collector(1).await;
+        pusher(url, "collector-secret", 3)
+            .push(&rollups())
+            .await
+            .unwrap();
+
+        let received = collector.received.lock().await;
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].deployment, "eu-prod");
+        assert_eq!(received[0].rollups, rollups());
+    }
+
+    #[tokio::test]
+    async fn test_push_gives_up_after_attempts() {
+        let (url, collector) = mock_collector(5).await;
+        let err = pusher(url, "collector-secret", 2)
+            .push(&rollups())
+            .await
+            .unwrap_err();
+        assert!(err.contains("500"), "{}", err);
+        assert_eq!(collector.failures.load(Ordering::SeqCst), 3);
+        assert!(collector.received.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_push_with_wrong_secret_refused() {
+        let (url, collector) = mock_collector(0).await;
+        let err = pusher(url, "wrong-secret", 1)
+            .push(&rollups())
+            .await
+            .unwrap_err();
+        assert!(err.contains("401"), "{}", err);
+        assert!(collector.received.lock().await.is_empty());
+    }
+}
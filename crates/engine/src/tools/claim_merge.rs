@@ -0,0 +1,76 @@
+use autosint_common::types::Claim;
+use autosint_common::ClaimId;
+
+use crate::graph::ClaimMerge;
+
+use super::entity_locks::{lock_entities, release, LockMode};
+use super::registry::ToolHandlerContext;
+
+/// Rationale recorded for merges of exact duplicates at claim creation.
+const EXACT_DUPLICATE_RATIONALE: &str = "Exact duplicate content";
+
+/// Merge the claim `source_id` into `target_id` and record the merge, moving
+/// the assessments citing the source onto the target. Shared locks on the
+/// source's referenced entities keep a concurrent entity merge from dropping
+/// the REFERENCES edges copied to the target.
+pub async fn merge_claims(
+    ctx: &ToolHandlerContext,
+    source_id: ClaimId,
+    target_id: ClaimId,
+    rationale: &str,
+) -> Result<ClaimMerge, String> {
+    let source = ctx
+        .services
+        .graph
+        .get_claim(source_id)
+        .await
+        .map_err(|e| format!("Failed to read claim {}: {}", source_id, e))?;
+    let (guard, _) = lock_entities(ctx, &source.referenced_entity_ids, LockMode::Shared).await?;
+    let merge = ctx.services.graph.merge_claims(source_id, target_id).await;
+    release(guard).await;
+    let merge = merge.map_err(|e| format!("Failed to merge claims: {}", e))?;
+
+    if let Some(store) = &ctx.services.store {
+        if let Err(e) = store.record_claim_merge(&merge, rationale).await {
+            tracing::warn!(
+                source = %source_id,
+                target = %target_id,
+                error = %e,
+                "Failed to record claim merge"
+            );
+        }
+    }
+    tracing::info!(source = %source_id, target = %target_id, rationale, "Merged claims");
+    Ok(merge)
+}
+
+/// With `merge_exact_duplicate_claims` on, merge the just-created `claim`
+/// into the earliest claim with exactly its content. None when there is no
+/// such claim, the option is off, or the merge fails (the claim stays).
+pub async fn merge_exact_duplicate(ctx: &ToolHandlerContext, claim: &Claim) -> Option<ClaimMerge> {
+    if !ctx.services.dedup_config.merge_exact_duplicate_claims {
+        return None;
+    }
+    let existing = match ctx
+        .services
+        .graph
+        .find_claim_with_content(&claim.content, claim.id)
+        .await
+    {
+        Ok(existing) => existing?,
+        Err(e) => {
+            tracing::warn!(claim_id = %claim.id, error = %e, "Failed to look up duplicate claims");
+            return None;
+        }
+    };
+    match merge_claims(ctx, claim.id, existing, EXACT_DUPLICATE_RATIONALE).await {
+        Ok(merge) => {
+            metrics::counter!("tools.claims.exact_duplicates_merged").increment(1);
+            Some(merge)
+        }
+        Err(e) => {
+            tracing::warn!(claim_id = %claim.id, existing = %existing, error = %e, "Failed to merge duplicate claim");
+            None
+        }
+    }
+}
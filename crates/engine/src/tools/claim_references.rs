@@ -0,0 +1,127 @@
+use autosint_common::EntityId;
+
+use super::registry::ToolHandlerContext;
+
+/// A claim's references after applying the per-claim cap.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CappedReferences {
+    /// In the order given.
+    pub kept: Vec<EntityId>,
+    /// How many distinct references were dropped.
+    pub dropped: usize,
+}
+
+/// Cap a claim's references at `max`, duplicates removed. Past the cap, the
+/// `focus` entities it references are kept first, then the others in the
+/// order given (the LLM lists the entities a claim is about first).
+pub fn cap_references(ids: &[EntityId], max: usize, focus: &[EntityId]) -> CappedReferences {
+    let mut distinct: Vec<EntityId> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !distinct.contains(id) {
+            distinct.push(*id);
+        }
+    }
+    if distinct.len() <= max {
+        return CappedReferences {
+            kept: distinct,
+            dropped: 0,
+        };
+    }
+
+    let focused = distinct.iter().filter(|id| focus.contains(id)).count();
+    let mut focus_slots = focused.min(max);
+    let mut other_slots = max - focus_slots;
+    let total = distinct.len();
+    distinct.retain(|id| {
+        let slots = if focus.contains(id) {
+            &mut focus_slots
+        } else {
+            &mut other_slots
+        };
+        if *slots == 0 {
+            return false;
+        }
+        *slots -= 1;
+        true
+    });
+    CappedReferences {
+        dropped: total - distinct.len(),
+        kept: distinct,
+    }
+}
+
+/// Cap a claim's references at the session's limit, keeping the entities
+/// its work order references. Returns the tool-result warning for dropped
+/// references.
+pub fn cap_claim_references(
+    ctx: &ToolHandlerContext,
+    ids: &[EntityId],
+) -> (Vec<EntityId>, Option<String>) {
+    let max = ctx.services.graph.max_claim_references();
+    let capped = cap_references(ids, max, &ctx.scope.focus_entity_ids);
+    if capped.dropped == 0 {
+        return (capped.kept, None);
+    }
+    metrics::counter!("tools.claim_references.dropped").increment(capped.dropped as u64);
+    tracing::warn!(
+        referenced = capped.kept.len() + capped.dropped,
+        dropped = capped.dropped,
+        max,
+        "Claim references over the limit, dropping the excess"
+    );
+    let warning = format!(
+        "Claim referenced {} entities, over the limit of {}: kept {} and dropped {}. \
+         Reference only the entities the claim is about, most important first.",
+        capped.kept.len() + capped.dropped,
+        max,
+        capped.kept.len(),
+        capped.dropped
+    );
+    (capped.kept, Some(warning))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(n: usize) -> Vec<EntityId> {
+        (0..n).map(|_| EntityId::new()).collect()
+    }
+
+    #[test]
+    fn test_under_limit_keeps_all_without_duplicates() {
+        let refs = ids(3);
+        let with_duplicate = [refs[0], refs[1], refs[0], refs[2]];
+        assert_eq!(
+            cap_references(&with_duplicate, 3, &[]),
+            CappedReferences {
+                kept: refs,
+                dropped: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_over_limit_keeps_first_in_order() {
+        let refs = ids(100);
+        let capped = cap_references(&refs, 25, &[]);
+        assert_eq!(capped.kept, refs[..25]);
+        assert_eq!(capped.dropped, 75);
+    }
+
+    #[test]
+    fn test_over_limit_keeps_focus_entities() {
+        let refs = ids(6);
+        // refs[4] is a focus entity listed late; the unreferenced focus
+        // entity takes no slot.
+        let focus = [refs[4], EntityId::new()];
+        let capped = cap_references(&refs, 3, &focus);
+        assert_eq!(capped.kept, [refs[0], refs[1], refs[4]]);
+        assert_eq!(capped.dropped, 3);
+
+        // More focus entities than slots: the first focus entities win.
+        let capped = cap_references(&refs, 2, &refs[3..]);
+        assert_eq!(capped.kept, [refs[3], refs[4]]);
+        assert_eq!(capped.dropped, 4);
+    }
+}
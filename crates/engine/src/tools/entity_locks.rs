@@ -0,0 +1,98 @@
+use autosint_common::EntityId;
+
+use crate::store::{EntityLockGuard, StoreError};
+
+use super::registry::ToolHandlerContext;
+
+/// Attempts to settle on a stable set of IDs before giving up (each attempt follows
+/// merges that completed while we waited for the lock).
+const MAX_LOCK_ATTEMPTS: usize = 3;
+
+/// How a handler locks the entities it touches.
+#[derive(Clone, Copy, Debug)]
+pub enum LockMode {
+    /// Graph writes (claims, relationships, updates). Concurrent with each other.
+    Shared,
+    /// Entity merges. Excludes all writers of the same entities.
+    Exclusive,
+}
+
+/// Lock `ids` against concurrent merges and redirect any that were merged away.
+///
+/// Returns the guard — hold it until the graph write finishes — and the resolved IDs
+/// in input order. Sessions without a store (tests) skip locking entirely.
+pub async fn lock_entities(
+    ctx: &ToolHandlerContext,
+    ids: &[EntityId],
+    mode: LockMode,
+) -> Result<(Option<EntityLockGuard>, Vec<EntityId>), String> {
+    let Some(store) = ctx.services.store.as_ref() else {
+        return Ok((None, ids.to_vec()));
+    };
+
+    let mut current = ids.to_vec();
+    for _ in 0..MAX_LOCK_ATTEMPTS {
+        let guard = match mode {
+            LockMode::Shared => store.lock_entities_shared(&current).await,
+            LockMode::Exclusive => store.lock_entities_exclusive(&current).await,
+        }
+        .map_err(|e| lock_error(&current, e))?;
+
+        // Merges record their tombstone before releasing the lock, so anything merged
+        // away before we got the lock is visible now.
+        let mut resolved = Vec::with_capacity(current.len());
+        for id in &current {
+            let target = store
+                .resolve_merged_entity(*id)
+                .await
+                .map_err(|e| format!("Failed to resolve entity {}: {}", id, e))?;
+            if target != *id {
+                tracing::info!(from = %id, to = %target, "Redirecting merged entity");
+                metrics::counter!("tools.entity_lock.redirects").increment(1);
+            }
+            resolved.push(target);
+        }
+
+        if resolved == current {
+            return Ok((Some(guard), resolved));
+        }
+
+        // Redirected IDs aren't covered by the guard — re-lock the resolved set.
+        release(Some(guard)).await;
+        current = resolved;
+    }
+
+    Err(format!(
+        "Entities {} are being merged repeatedly. Retry the call; if it fails again, \
+         re-resolve the entities with search_entities.",
+        format_ids(&current)
+    ))
+}
+
+/// Release held entity locks. Failures only delay release until the connection
+/// is recycled, so they are logged rather than surfaced.
+pub async fn release(guard: Option<EntityLockGuard>) {
+    if let Some(guard) = guard {
+        if let Err(e) = guard.release().await {
+            tracing::warn!(error = %e, "Failed to release entity locks");
+        }
+    }
+}
+
+fn lock_error(ids: &[EntityId], e: StoreError) -> String {
+    match e {
+        StoreError::LockTimeout(_) => format!(
+            "Entities {} are locked by a merge in progress. Retry the call; if the entity \
+             no longer exists, re-resolve it with search_entities.",
+            format_ids(ids)
+        ),
+        other => format!("Failed to lock entities {}: {}", format_ids(ids), other),
+    }
+}
+
+fn format_ids(ids: &[EntityId]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
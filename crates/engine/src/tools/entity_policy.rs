@@ -0,0 +1,246 @@
+use serde_json::Value;
+
+use autosint_common::config::{EntityKindConfig, EntityKindPolicy, PolicySeverity};
+use autosint_common::types::Entity;
+
+/// Prefix of a required field naming an identifier scheme.
+pub const IDENTIFIER_FIELD_PREFIX: &str = "identifiers.";
+
+/// Apply the policy for `entity`'s kind before it is created: refuse a stub
+/// the kind doesn't allow or a missing `reject` field, stamp the default
+/// properties, and return a warning per missing `warn` field. An error names
+/// everything missing.
+pub fn apply(config: &EntityKindConfig, entity: &mut Entity) -> Result<Vec<String>, String> {
+    let Some(policy) = config.policy(&entity.kind) else {
+        return Ok(Vec::new());
+    };
+
+    if entity.is_stub && !policy.allow_stubs {
+        return Err(format!(
+            "Entity '{}' not created: {} entities can't be stubs. Create it with what \
+             the source says about it{}.",
+            entity.canonical_name,
+            entity.kind,
+            requirements_suffix(policy)
+        ));
+    }
+
+    let mut rejected = Vec::new();
+    let mut warnings = Vec::new();
+    if !entity.is_stub {
+        for field in &policy.required {
+            if field.any_of.iter().any(|name| has_field(entity, name)) {
+                continue;
+            }
+            let wanted = alternatives(&field.any_of);
+            match field.severity {
+                PolicySeverity::Reject => rejected.push(wanted),
+                PolicySeverity::Warn => warnings.push(format!(
+                    "'{}' ({}) should have {}; add it with update_entity if a source gives it.",
+                    entity.canonical_name, entity.kind, wanted
+                )),
+            }
+        }
+    }
+    if !rejected.is_empty() {
+        return Err(format!(
+            "Entity '{}' not created: a {} needs {}.",
+            entity.canonical_name,
+            entity.kind,
+            rejected.join(" and ")
+        ));
+    }
+
+    for (key, value) in &policy.defaults {
+        entity
+            .properties
+            .entry(key.clone())
+            .or_insert_with(|| value.clone());
+    }
+    Ok(warnings)
+}
+
+/// Whether `entity` has a usable value for the required field `name`.
+fn has_field(entity: &Entity, name: &str) -> bool {
+    if let Some(scheme) = name.strip_prefix(IDENTIFIER_FIELD_PREFIX) {
+        return entity.identifiers.keys().any(|s| s.as_str() == scheme);
+    }
+    match name {
+        "summary" => entity
+            .summary
+            .as_deref()
+            .is_some_and(|s| !s.trim().is_empty()),
+        "aliases" => !entity.aliases.is_empty(),
+        _ => entity
+            .properties
+            .get(name)
+            .is_some_and(|value| match value {
+                Value::Null => false,
+                Value::String(s) => !s.trim().is_empty(),
+                Value::Array(items) => !items.is_empty(),
+                Value::Object(fields) => !fields.is_empty(),
+                _ => true,
+            }),
+    }
+}
+
+/// "a", "a or b", "a, b or c".
+fn alternatives(names: &[String]) -> String {
+    match names {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} or {}", rest.join(", "), last),
+    }
+}
+
+/// ", including x and y" for the kind's reject fields, if any.
+fn requirements_suffix(policy: &EntityKindPolicy) -> String {
+    let required: Vec<String> = policy
+        .required
+        .iter()
+        .filter(|f| f.severity == PolicySeverity::Reject)
+        .map(|f| alternatives(&f.any_of))
+        .collect();
+    if required.is_empty() {
+        String::new()
+    } else {
+        format!(", including {}", required.join(" and "))
+    }
+}
+
+/// Text appended to the create_entity description listing each kind's
+/// expectations, or None without policies.
+pub fn describe(config: &EntityKindConfig) -> Option<String> {
+    let lines: Vec<String> = config
+        .policies
+        .iter()
+        .filter_map(|(kind, policy)| {
+            let mut parts = Vec::new();
+            for field in &policy.required {
+                let verb = match field.severity {
+                    PolicySeverity::Reject => "must have",
+                    PolicySeverity::Warn => "should have",
+                };
+                parts.push(format!("{} {}", verb, alternatives(&field.any_of)));
+            }
+            if !policy.allow_stubs {
+                parts.push("can't be a stub".into());
+            }
+            if !policy.defaults.is_empty() {
+                let defaults: Vec<String> = policy
+                    .defaults
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect();
+                parts.push(format!("defaults {}", defaults.join(", ")));
+            }
+            (!parts.is_empty()).then(|| format!("{}: {}.", kind, parts.join("; ")))
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Expectations by kind (a 'must' miss refuses creation; stubs are exempt): {}",
+        lines.join(" ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autosint_common::types::IdentifierScheme;
+    use serde_json::json;
+
+    fn config() -> EntityKindConfig {
+        toml::from_str(
+            r#"
+canonical = ["vessel", "person", "publication"]
+
+[policies.vessel]
+required = [{ any_of = ["identifiers.imo", "identifiers.mmsi"] }]
+defaults = { domain = "maritime" }
+
+[policies.publication]
+required = [{ any_of = ["url"], severity = "reject" }]
+allow_stubs = false
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_warn_creates_with_a_warning() {
+        let mut vessel = Entity::new("MV Example".into(), "Vessel".into());
+        let warnings = apply(&config(), &mut vessel).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("identifiers.imo or identifiers.mmsi"),
+            "{}",
+            warnings[0]
+        );
+
+        vessel
+            .identifiers
+            .insert(IdentifierScheme::Mmsi, "123456789".into());
+        assert!(apply(&config(), &mut vessel).unwrap().is_empty());
+
+        // Kinds without a policy are untouched.
+        let mut person = Entity::new("Jane Doe".into(), "person".into());
+        assert!(apply(&config(), &mut person).unwrap().is_empty());
+        assert!(person.properties.is_empty());
+    }
+
+    #[test]
+    fn test_reject_refuses_creation() {
+        let mut outlet = Entity::new("Example Times".into(), "publication".into());
+        let error = apply(&config(), &mut outlet).unwrap_err();
+        assert!(error.contains("needs url"), "{}", error);
+
+        outlet.properties.insert("url".into(), json!("  "));
+        assert!(apply(&config(), &mut outlet).is_err());
+        outlet
+            .properties
+            .insert("url".into(), json!("https://times.example"));
+        assert!(apply(&config(), &mut outlet).is_ok());
+
+        // Stubs skip the required fields, but this kind allows none.
+        let mut stub = Entity::new_stub("Example Post".into(), "publication".into());
+        let error = apply(&config(), &mut stub).unwrap_err();
+        assert!(error.contains("can't be stubs."), "{}", error);
+        assert!(error.contains("including url"), "{}", error);
+
+        let mut stub_vessel = Entity::new_stub("MV Sample".into(), "vessel".into());
+        assert!(apply(&config(), &mut stub_vessel).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_defaults_are_stamped_without_overriding() {
+        let mut vessel = Entity::new("MV Example".into(), "vessel".into());
+        apply(&config(), &mut vessel).unwrap();
+        assert_eq!(vessel.properties["domain"], json!("maritime"));
+
+        let mut fishing = Entity::new("FV Example".into(), "vessel".into());
+        fishing
+            .properties
+            .insert("domain".into(), json!("fisheries"));
+        apply(&config(), &mut fishing).unwrap();
+        assert_eq!(fishing.properties["domain"], json!("fisheries"));
+
+        // A refused entity gets no defaults.
+        let mut outlet = Entity::new("Example Times".into(), "publication".into());
+        assert!(apply(&config(), &mut outlet).is_err());
+        assert!(outlet.properties.is_empty());
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(&config()).unwrap(),
+            "Expectations by kind (a 'must' miss refuses creation; stubs are exempt): \
+             publication: must have url; can't be a stub. \
+             vessel: should have identifiers.imo or identifiers.mmsi; defaults domain=\"maritime\"."
+        );
+        assert_eq!(describe(&EntityKindConfig::default()), None);
+    }
+}
@@ -0,0 +1,177 @@
+//! The per-source call cap of exploratory work orders' Processor sessions,
+//! enforced by the tool executor so the model moves on to other sources.
+
+use serde_json::Value;
+
+use autosint_common::http_client::extract_domain;
+
+use super::services::SessionScope;
+
+/// The domain or fetch source a call to `tool` with `args` goes to. None for
+/// tools that don't fetch, and for paging through the tables of a document
+/// already fetched.
+pub fn source_key(tool: &str, args: &Value) -> Option<String> {
+    match tool {
+        "fetch_url" if args.get("table").is_none_or(Value::is_null) => {
+            let url = args.get("url")?.as_str()?.trim();
+            Some(extract_domain(url).to_lowercase())
+        }
+        "fetch_source_query" => {
+            let source_id = args.get("source_id")?.as_str()?.trim();
+            Some(format!("source:{}", source_id))
+        }
+        _ => None,
+    }
+}
+
+/// Count a call against its source in an exploratory session. Err with the
+/// message for the model, without counting, once the session has made
+/// `max_calls_per_source` calls there. Other sessions are never limited.
+pub fn check_source_budget(scope: &SessionScope, tool: &str, args: &Value) -> Result<(), String> {
+    let Some(exploration) = &scope.exploration else {
+        return Ok(());
+    };
+    let Some(source) = source_key(tool, args) else {
+        return Ok(());
+    };
+    let max = exploration.max_calls_per_source;
+    if scope.session_counters.try_source_call(&source, max) {
+        return Ok(());
+    }
+    metrics::counter!("tools.exploration.source_budget_exceeded").increment(1);
+    Err(format!(
+        "Per-source budget reached: this exploratory session has already made {} calls to {}. \
+         Move on to a different site or source; note anything left unread there in your summary.",
+        max, source
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use autosint_common::config::ExplorationConfig;
+
+    use super::*;
+    use crate::tools::{ToolHandler, ToolHandlerContext, ToolRegistry};
+
+    #[test]
+    fn test_source_key() {
+        assert_eq!(
+            source_key(
+                "fetch_url",
+                &json!({"url": "https://WWW.Reuters.com/world/"})
+            ),
+            Some("www.reuters.com".into())
+        );
+        assert_eq!(
+            source_key(
+                "fetch_url",
+                &json!({"url": "https://www.reuters.com/world/", "table": null})
+            ),
+            Some("www.reuters.com".into())
+        );
+        // Paging a fetched document's tables re-reads the cache.
+        assert_eq!(
+            source_key(
+                "fetch_url",
+                &json!({"url": "https://www.reuters.com/world/", "table": 1})
+            ),
+            None
+        );
+        assert_eq!(
+            source_key(
+                "fetch_source_query",
+                &json!({"source_id": "opencorporates"})
+            ),
+            Some("source:opencorporates".into())
+        );
+        assert_eq!(source_key("web_search", &json!({"query": "Crius"})), None);
+        assert_eq!(source_key("fetch_url", &json!({})), None);
+    }
+
+    async fn registry(exploration: Option<ExplorationConfig>) -> ToolRegistry {
+        let context = ToolHandlerContext::stub(None, SessionScope::default()).await;
+        let mut registry = ToolRegistry::new(context.scoped(|scope| {
+            scope.exploration = exploration;
+        }));
+        let fetched: ToolHandler =
+            Arc::new(|_args, _ctx| Box::pin(async { Ok(json!({"content": "..."})) }));
+        registry.register("fetch_url", Arc::clone(&fetched));
+        registry.register("fetch_source_query", fetched);
+        registry
+    }
+
+    fn fetch(url: &str) -> Value {
+        json!({ "url": url })
+    }
+
+    #[tokio::test]
+    async fn test_exploratory_session_cut_off_per_source() {
+        let registry = registry(Some(ExplorationConfig {
+            max_calls_per_source: 2,
+            ..Default::default()
+        }))
+        .await;
+
+        for path in ["a", "b"] {
+            let url = format!("https://example.org/{}", path);
+            let result = registry.execute("fetch_url", fetch(&url)).await;
+            assert!(!result.is_error, "{}", result.content);
+        }
+        let cut_off = registry
+            .execute("fetch_url", fetch("https://EXAMPLE.org/c"))
+            .await;
+        assert!(cut_off.is_error);
+        assert!(!cut_off.is_malformed);
+        assert!(
+            cut_off.content.contains("Per-source budget reached")
+                && cut_off.content.contains("example.org"),
+            "{}",
+            cut_off.content
+        );
+
+        // Other sources keep their own counts, refusals don't count, and
+        // paging tables is free.
+        let other = registry
+            .execute("fetch_url", fetch("https://apnews.com/x"))
+            .await;
+        assert!(!other.is_error);
+        let paging = registry
+            .execute(
+                "fetch_url",
+                json!({"url": "https://example.org/a", "table": 0}),
+            )
+            .await;
+        assert!(!paging.is_error);
+        let counts = registry.counters().source_calls.lock().unwrap().clone();
+        assert_eq!(counts["example.org"], 2);
+        assert_eq!(counts["apnews.com"], 1);
+
+        let args = json!({"source_id": "opencorporates", "query": "Gulf Star"});
+        for _ in 0..2 {
+            assert!(
+                !registry
+                    .execute("fetch_source_query", args.clone())
+                    .await
+                    .is_error
+            );
+        }
+        assert!(registry.execute("fetch_source_query", args).await.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_focused_session_unlimited() {
+        let registry = registry(None).await;
+
+        for _ in 0..10 {
+            let result = registry
+                .execute("fetch_url", fetch("https://example.org/a"))
+                .await;
+            assert!(!result.is_error, "{}", result.content);
+        }
+        assert!(registry.counters().source_calls.lock().unwrap().is_empty());
+    }
+}
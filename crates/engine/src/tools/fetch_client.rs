@@ -0,0 +1,493 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::Semaphore;
+
+use autosint_common::api::fetch::{FetchRequest, RenderMode, SearchRequest};
+use autosint_common::config::FetchReplicasConfig;
+use autosint_common::http_client::{self, Destination};
+
+/// Below this many recent requests a replica hedges after
+/// `initial_hedge_delay_ms` instead of at its latency percentile.
+const MIN_HEDGE_SAMPLES: usize = 10;
+/// Most requests remembered per replica, however busy it is.
+const MAX_SAMPLES: usize = 500;
+/// How much a replica's error rate counts against it when ranking: one that
+/// fails every request ranks as if five times slower.
+const ERROR_PENALTY: f64 = 4.0;
+
+/// The Fetch service's replicas, as the tools reach them (FETCH_BASE_URL,
+/// comma-separated). Each request goes to the replica with the best recent
+/// latency and error rate. A hedged request still unanswered at the
+/// replica's latency percentile is sent to the next replica too: the first
+/// answer wins and the other request is dropped. Hedges in flight are capped
+/// across all sessions. Cloning shares the replicas' history and the cap.
+#[derive(Clone)]
+pub struct FetchClient {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    http: reqwest::Client,
+    replicas: Vec<Replica>,
+    config: FetchReplicasConfig,
+    hedges: Semaphore,
+}
+
+struct Replica {
+    base_url: String,
+    history: Mutex<History>,
+}
+
+/// A replica's recent requests, oldest first, and those still running.
+#[derive(Default)]
+struct History {
+    samples: VecDeque<Sample>,
+    in_flight: usize,
+}
+
+struct Sample {
+    finished: Instant,
+    latency: Duration,
+    ok: bool,
+}
+
+impl FetchClient {
+    /// A client for the comma-separated `base_urls`.
+    pub fn new(base_urls: &str, http: reqwest::Client, config: FetchReplicasConfig) -> Self {
+        let mut replicas: Vec<Replica> = base_urls
+            .split(',')
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .map(|url| Replica {
+                base_url: url.to_string(),
+                history: Mutex::default(),
+            })
+            .collect();
+        if replicas.is_empty() {
+            replicas.push(Replica {
+                base_url: base_urls.trim().to_string(),
+                history: Mutex::default(),
+            });
+        }
+        let hedges = Semaphore::new(config.max_concurrent_hedges);
+        Self {
+            inner: Arc::new(Inner {
+                http,
+                replicas,
+                config,
+                hedges,
+            }),
+        }
+    }
+
+    /// The first configured replica's base URL.
+    pub fn base_url(&self) -> &str {
+        &self.inner.replicas[0].base_url
+    }
+
+    pub async fn get(&self, path: &str) -> reqwest::Result<reqwest::Response> {
+        self.send(false, |http, base| http.get(format!("{}{}", base, path)))
+            .await
+    }
+
+    pub async fn post(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> reqwest::Result<reqwest::Response> {
+        self.send(false, |http, base| {
+            http.post(format!("{}{}", base, path)).json(body)
+        })
+        .await
+    }
+
+    /// POST /fetch, hedged when [`is_hedgeable`].
+    pub async fn fetch(&self, request: &FetchRequest) -> reqwest::Result<reqwest::Response> {
+        if is_hedgeable(request) {
+            self.post_hedged("/fetch", request).await
+        } else {
+            self.post("/fetch", request).await
+        }
+    }
+
+    /// POST /search, hedged.
+    pub async fn search(&self, request: &SearchRequest) -> reqwest::Result<reqwest::Response> {
+        self.post_hedged("/search", request).await
+    }
+
+    /// POST a request that is safe to send twice, hedging it when its
+    /// replica is slow.
+    async fn post_hedged(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> reqwest::Result<reqwest::Response> {
+        self.send(true, |http, base| {
+            http.post(format!("{}{}", base, path)).json(body)
+        })
+        .await
+    }
+
+    async fn send(
+        &self,
+        hedge: bool,
+        request: impl Fn(&reqwest::Client, &str) -> reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let ranked = self.ranked();
+        let primary = &self.inner.replicas[ranked[0]];
+        let first = self.attempt(primary, &request);
+        if !hedge || !self.inner.config.hedging || ranked.len() < 2 {
+            return first.await;
+        }
+
+        tokio::pin!(first);
+        tokio::select! {
+            result = &mut first => return result,
+            _ = tokio::time::sleep(self.hedge_delay(primary)) => {}
+        }
+        let Ok(_permit) = self.inner.hedges.try_acquire() else {
+            metrics::counter!("tools.fetch_client.hedge.budget_exhausted").increment(1);
+            return first.await;
+        };
+        metrics::counter!("tools.fetch_client.hedge.attempts").increment(1);
+        let second = self.attempt(&self.inner.replicas[ranked[1]], &request);
+        tokio::pin!(second);
+
+        // The first answer wins; a request that fails outright waits for the
+        // other. The loser is dropped, which cancels it.
+        let (result, winner) = tokio::select! {
+            result = &mut first => match result {
+                Ok(response) => (Ok(response), "primary"),
+                Err(_) => ((&mut second).await, "hedge"),
+            },
+            result = &mut second => match result {
+                Ok(response) => (Ok(response), "hedge"),
+                Err(_) => ((&mut first).await, "primary"),
+            },
+        };
+        metrics::counter!("tools.fetch_client.hedge.wins", "winner" => winner).increment(1);
+        result
+    }
+
+    /// One request to `replica`, recorded in its history.
+    async fn attempt(
+        &self,
+        replica: &Replica,
+        request: &impl Fn(&reqwest::Client, &str) -> reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut attempt = Attempt::start(self, replica);
+        let result = http_client::send(
+            Destination::Internal,
+            request(&self.inner.http, &replica.base_url),
+        )
+        .await;
+        attempt.ok = Some(result.as_ref().is_ok_and(|r| !overloaded(r.status())));
+        result
+    }
+
+    /// Replica indexes, best first: by recent latency inflated by the error
+    /// rate, then fewest requests in flight, then configured order. A replica
+    /// without recent requests ranks first, so a recovered one gets tried.
+    fn ranked(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut scored: Vec<(f64, usize, usize)> = self
+            .inner
+            .replicas
+            .iter()
+            .enumerate()
+            .map(|(index, replica)| {
+                let mut history = replica.history.lock().unwrap();
+                self.prune(&mut history, now);
+                (history.score(), history.in_flight, index)
+            })
+            .collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+        scored.into_iter().map(|(_, _, index)| index).collect()
+    }
+
+    /// How long to wait on `replica` before hedging.
+    fn hedge_delay(&self, replica: &Replica) -> Duration {
+        let config = &self.inner.config;
+        let mut history = replica.history.lock().unwrap();
+        self.prune(&mut history, Instant::now());
+        let delay = match history.samples.len() {
+            n if n < MIN_HEDGE_SAMPLES => Duration::from_millis(config.initial_hedge_delay_ms),
+            n => {
+                let mut latencies: Vec<Duration> =
+                    history.samples.iter().map(|s| s.latency).collect();
+                latencies.sort();
+                let rank = (config.hedge_percentile * n as f64).ceil() as usize;
+                latencies[rank.clamp(1, n) - 1]
+            }
+        };
+        delay.max(Duration::from_millis(config.min_hedge_delay_ms))
+    }
+
+    fn prune(&self, history: &mut History, now: Instant) {
+        let window = Duration::from_secs(self.inner.config.window_seconds);
+        while history.samples.len() > MAX_SAMPLES
+            || history
+                .samples
+                .front()
+                .is_some_and(|s| now.duration_since(s.finished) > window)
+        {
+            history.samples.pop_front();
+        }
+    }
+}
+
+impl History {
+    fn score(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let n = self.samples.len() as f64;
+        let mean_ms = self
+            .samples
+            .iter()
+            .map(|s| s.latency.as_secs_f64() * 1000.0)
+            .sum::<f64>()
+            / n;
+        let error_rate = self.samples.iter().filter(|s| !s.ok).count() as f64 / n;
+        mean_ms * (1.0 + ERROR_PENALTY * error_rate)
+    }
+}
+
+/// A request in flight to one replica. Dropped unfinished (the losing side
+/// of a hedge), it still records its latency so far: the replica was at
+/// least that slow.
+struct Attempt<'a> {
+    client: &'a FetchClient,
+    replica: &'a Replica,
+    started: Instant,
+    ok: Option<bool>,
+}
+
+impl<'a> Attempt<'a> {
+    fn start(client: &'a FetchClient, replica: &'a Replica) -> Self {
+        replica.history.lock().unwrap().in_flight += 1;
+        Self {
+            client,
+            replica,
+            started: Instant::now(),
+            ok: None,
+        }
+    }
+}
+
+impl Drop for Attempt<'_> {
+    fn drop(&mut self) {
+        if self.ok.is_none() {
+            metrics::counter!("tools.fetch_client.cancelled").increment(1);
+        }
+        let now = Instant::now();
+        let mut history = self.replica.history.lock().unwrap();
+        history.in_flight -= 1;
+        history.samples.push_back(Sample {
+            finished: now,
+            latency: now - self.started,
+            ok: self.ok.unwrap_or(true),
+        });
+        self.client.prune(&mut history, now);
+    }
+}
+
+/// Responses that mean the replica is struggling, counted as errors.
+fn overloaded(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Whether a /fetch request may go to two replicas at once: a plain fetch
+/// the replicas cache alike. Browser renders are too costly to run twice,
+/// and custom headers may carry credentials.
+fn is_hedgeable(request: &FetchRequest) -> bool {
+    request.options.as_ref().is_none_or(|options| {
+        options.render != Some(RenderMode::Browser) && options.headers.is_none()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// A Fetch service replica answering /search after `delay`, counting
+    /// the searches it receives.
+    async fn replica(name: &'static str, delay: Duration) -> (String, Arc<AtomicUsize>) {
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&received);
+        let app = Router::new().route(
+            "/search",
+            post(move || {
+                let counter = Arc::clone(&counter);
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(delay).await;
+                    Json(json!({"replica": name}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), received)
+    }
+
+    fn config(max_concurrent_hedges: usize) -> FetchReplicasConfig {
+        FetchReplicasConfig {
+            initial_hedge_delay_ms: 50,
+            min_hedge_delay_ms: 10,
+            max_concurrent_hedges,
+            ..Default::default()
+        }
+    }
+
+    async fn search(client: &FetchClient) -> String {
+        let request = SearchRequest {
+            query: "crius".into(),
+            num_results: None,
+        };
+        let response = client.search(&request).await.unwrap();
+        let body: Value = response.json().await.unwrap();
+        body["replica"].as_str().unwrap().to_string()
+    }
+
+    /// Give replica `index` ten recent requests of `ms` each.
+    fn seed(client: &FetchClient, index: usize, ms: u64, ok: bool) {
+        let now = Instant::now();
+        client.inner.replicas[index].history.lock().unwrap().samples = (0..10)
+            .map(|_| Sample {
+                finished: now,
+                latency: Duration::from_millis(ms),
+                ok,
+            })
+            .collect();
+    }
+
+    fn in_flight(client: &FetchClient, index: usize) -> usize {
+        client.inner.replicas[index]
+            .history
+            .lock()
+            .unwrap()
+            .in_flight
+    }
+
+    #[tokio::test]
+    async fn test_hedge_to_fast_replica_wins() {
+        let (slow, slow_received) = replica("slow", Duration::from_secs(5)).await;
+        let (fast, fast_received) = replica("fast", Duration::ZERO).await;
+        let client = FetchClient::new(
+            &format!("{},{}", slow, fast),
+            reqwest::Client::new(),
+            config(4),
+        );
+
+        // Neither replica has history, so the slow one (listed first) is tried first.
+        let started = Instant::now();
+        assert_eq!(search(&client).await, "fast");
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(slow_received.load(Ordering::SeqCst), 1);
+        assert_eq!(fast_received.load(Ordering::SeqCst), 1);
+
+        // The slow request was dropped, not left running, and its wait
+        // counts against the slow replica: it now ranks second.
+        assert_eq!(in_flight(&client, 0), 0);
+        assert_eq!(client.ranked(), vec![1, 0]);
+
+        // Requests that mustn't be hedged just wait.
+        assert_eq!(
+            client
+                .post("/search", &json!({"query": "crius"}))
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()["replica"],
+            "fast"
+        );
+        assert_eq!(slow_received.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hedges_capped_by_budget() {
+        let (slow, _) = replica("slow", Duration::from_millis(800)).await;
+        let (fast, fast_received) = replica("fast", Duration::from_millis(300)).await;
+        let client = FetchClient::new(
+            &format!("{},{}", slow, fast),
+            reqwest::Client::new(),
+            config(1),
+        );
+        // A history that has every search go to the degraded replica first.
+        seed(&client, 0, 10, true);
+        seed(&client, 1, 500, true);
+
+        // Three slow searches at once, one hedge allowed: the hedged search
+        // holds the budget until its answer, so the others wait it out.
+        let (a, b, c) = tokio::join!(search(&client), search(&client), search(&client));
+        let mut winners = vec![a, b, c];
+        winners.sort();
+        assert_eq!(winners, vec!["fast", "slow", "slow"]);
+        assert_eq!(fast_received.load(Ordering::SeqCst), 1);
+        assert_eq!(client.inner.hedges.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_single_replica_never_hedges() {
+        let (only, received) = replica("only", Duration::from_millis(200)).await;
+        let client = FetchClient::new(&format!("{}/", only), reqwest::Client::new(), config(4));
+        assert_eq!(client.base_url(), only);
+
+        assert_eq!(search(&client).await, "only");
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_hedge_delay_follows_latency_percentile() {
+        let client = FetchClient::new("http://a,http://b", reqwest::Client::new(), config(4));
+        let replica = &client.inner.replicas[0];
+        assert_eq!(client.hedge_delay(replica), Duration::from_millis(50));
+
+        let now = Instant::now();
+        replica.history.lock().unwrap().samples = (1..=20)
+            .map(|i| Sample {
+                finished: now,
+                latency: Duration::from_millis(i * 100),
+                ok: true,
+            })
+            .collect();
+        assert_eq!(client.hedge_delay(replica), Duration::from_millis(1900));
+    }
+
+    #[test]
+    fn test_errors_count_against_replica() {
+        let client = FetchClient::new("http://a,http://b", reqwest::Client::new(), config(4));
+        // Faster, but failing.
+        seed(&client, 0, 100, false);
+        seed(&client, 1, 300, true);
+        assert_eq!(client.ranked(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_only_plain_fetches_hedgeable() {
+        let request = |options: Option<autosint_common::api::fetch::FetchOptions>| FetchRequest {
+            url: "https://maritime.example/crius".into(),
+            options,
+        };
+        assert!(is_hedgeable(&request(None)));
+        assert!(is_hedgeable(&request(Some(Default::default()))));
+        assert!(!is_hedgeable(&request(Some(
+            autosint_common::api::fetch::FetchOptions {
+                render: Some(RenderMode::Browser),
+                ..Default::default()
+            }
+        ))));
+    }
+}
@@ -0,0 +1,154 @@
+//! The Fetch service's source catalog as the Analyst sees it: each source
+//! with a compact view of its recent query health, and warnings when work
+//! order guidance prefers a source that is currently failing.
+
+use serde_json::{json, Value};
+
+use autosint_common::api::fetch::{SourceHealth, SourceHealthStatus, SourceInfo};
+use autosint_common::api::ErrorResponse;
+
+use super::fetch_client::FetchClient;
+
+/// GET /sources from the Fetch service.
+pub async fn list_sources(fetch: &FetchClient) -> Result<Vec<SourceInfo>, String> {
+    let response = fetch
+        .get("/sources")
+        .await
+        .map_err(|e| format!("Fetch service request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
+        return Err(format!("Fetch service returned {}: {}", status, body));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse sources response: {}", e))
+}
+
+/// A source for the Analyst's listing: its catalog entry with the health
+/// block cut down to status, seven-day success rate and last success.
+pub fn present(source: &SourceInfo) -> Value {
+    let mut value = json!({
+        "id": source.id,
+        "name": source.name,
+        "description": source.description,
+        "kind": source.kind,
+        "capabilities": source.capabilities,
+    });
+    if let Some(trust_level) = source.trust_level {
+        value["trust_level"] = json!(trust_level);
+    }
+    if let Some(health) = &source.health {
+        value["health"] = present_health(health);
+    }
+    value
+}
+
+fn present_health(health: &SourceHealth) -> Value {
+    let mut value = json!({ "status": health.status.as_str() });
+    if let Some(rate) = health.success_rate_7d {
+        // Whole percent is plenty to choose between sources.
+        value["success_rate_7d"] = json!((rate * 100.0).round() / 100.0);
+    }
+    if let Some(last_success_at) = health.last_success_at {
+        value["last_success_at"] = json!(last_success_at.to_rfc3339());
+    }
+    value
+}
+
+/// One warning per `prefer` entry naming a catalog source that is failing.
+/// Entries that aren't catalog source IDs (e.g. "web_search") are skipped.
+pub fn failing_preferences(prefer: &[String], sources: &[SourceInfo]) -> Vec<String> {
+    prefer
+        .iter()
+        .filter_map(|id| sources.iter().find(|s| &s.id == id))
+        .filter_map(|source| {
+            let health = source.health.as_ref()?;
+            (health.status == SourceHealthStatus::Failing).then(|| {
+                let since = health
+                    .last_success_at
+                    .map(|t| format!("last success {}", t.to_rfc3339()))
+                    .unwrap_or_else(|| "no success on record".into());
+                format!(
+                    "Preferred source '{}' is failing: {:.0}% of {} queries succeeded in the last 7 days ({}). Processors may not get anything from it.",
+                    source.id,
+                    health.success_rate_7d.unwrap_or(0.0) * 100.0,
+                    health.queries_7d,
+                    since
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(id: &str, health: Option<SourceHealth>) -> SourceInfo {
+        SourceInfo {
+            id: id.into(),
+            name: id.into(),
+            description: "d".into(),
+            kind: Default::default(),
+            capabilities: vec!["since".into()],
+            trust_level: Some(4),
+            health,
+        }
+    }
+
+    fn health(status: SourceHealthStatus, rate: f64, queries: u64) -> SourceHealth {
+        SourceHealth {
+            status,
+            success_rate_7d: Some(rate),
+            queries_7d: queries,
+            last_success_at: Some("2026-03-01T00:00:00Z".parse().unwrap()),
+            last_error_at: Some("2026-03-04T00:00:00Z".parse().unwrap()),
+            last_error: Some("HTTP 503".into()),
+        }
+    }
+
+    #[test]
+    fn test_present_is_compact() {
+        let value = present(&source(
+            "mfa-press",
+            Some(health(SourceHealthStatus::Degraded, 0.6667, 12)),
+        ));
+        assert_eq!(
+            value["health"],
+            json!({
+                "status": "degraded",
+                "success_rate_7d": 0.67,
+                "last_success_at": "2026-03-01T00:00:00+00:00",
+            })
+        );
+        assert_eq!(value["trust_level"], 4);
+
+        // An older Fetch service without health.
+        assert!(present(&source("mfa-press", None)).get("health").is_none());
+    }
+
+    #[test]
+    fn test_failing_preferences() {
+        let sources = [
+            source(
+                "gazette",
+                Some(health(SourceHealthStatus::Failing, 0.1, 20)),
+            ),
+            source(
+                "mfa-press",
+                Some(health(SourceHealthStatus::Degraded, 0.6, 10)),
+            ),
+            source("idle", Some(SourceHealth::default())),
+        ];
+        let prefer = ["web_search", "gazette", "mfa-press", "idle"].map(String::from);
+        let warnings = failing_preferences(&prefer, &sources);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'gazette' is failing"));
+        assert!(warnings[0].contains("10% of 20 queries"));
+        assert!(warnings[0].contains("last success 2026-03-01"));
+    }
+}
@@ -0,0 +1,306 @@
+//! Result formats of the read tools that take `response_format`: `json`, the
+//! structured result for chaining IDs into further calls, or `summary`, a
+//! prose rendering of it that is far cheaper to read. Such a tool's handler
+//! returns a [`Formatted`] — the structured value and a renderer for it — and
+//! the registry sends whichever the call asked for, fitted to
+//! `tool_results.max_formatted_result_chars`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use autosint_common::config::{ResponseFormat, ToolResultLimits};
+
+use super::registry::ToolHandlerContext;
+use super::truncation::{drop_results_to_fit, sentence_ends, truncate_at_sentence};
+
+/// Tools whose handlers return a [`Formatted`] result.
+pub const FORMATTED_TOOLS: &[&str] = &[
+    "search_entities",
+    "search_claims",
+    "get_entity",
+    "traverse_relationships",
+    "search_assessments",
+    "list_work_orders",
+];
+
+/// Longest excerpt of free text on one summary line.
+const MAX_LINE_TEXT_CHARS: usize = 160;
+
+/// A read tool's result, ready to send in either format.
+pub struct Formatted {
+    pub value: Value,
+    render: Box<dyn FnOnce(&Value) -> String + Send>,
+}
+
+impl Formatted {
+    /// `value`, with `render` giving its summary.
+    pub fn new(value: Value, render: impl FnOnce(&Value) -> String + Send + 'static) -> Self {
+        Self {
+            value,
+            render: Box::new(render),
+        }
+    }
+
+    /// The result as sent in `format`, at most about `max_chars` long.
+    pub fn into_content(self, format: ResponseFormat, max_chars: usize) -> String {
+        match format {
+            ResponseFormat::Json => {
+                let mut value = self.value;
+                drop_results_to_fit(&mut value, max_chars);
+                to_content(&value)
+            }
+            ResponseFormat::Summary => truncate_at_sentence(&(self.render)(&self.value), max_chars),
+        }
+    }
+}
+
+/// Handler signature of the tools in [`FORMATTED_TOOLS`].
+pub type FormattedHandler = Arc<
+    dyn Fn(
+            Value,
+            Arc<ToolHandlerContext>,
+        ) -> Pin<Box<dyn Future<Output = Result<Formatted, String>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// A JSON tool result as sent to the LLM.
+pub fn to_content(value: &Value) -> String {
+    serde_json::to_string(value)
+        .unwrap_or_else(|e| format!("{{\"error\": \"Failed to serialize result: {}\"}}", e))
+}
+
+/// The format a call to `tool` asked for, or the tool's configured default.
+pub fn requested_format(
+    tool: &str,
+    args: &Value,
+    limits: &ToolResultLimits,
+) -> Result<ResponseFormat, String> {
+    match args.get("response_format") {
+        None | Some(Value::Null) => Ok(limits
+            .response_formats
+            .get(tool)
+            .copied()
+            .unwrap_or_default()),
+        Some(Value::String(s)) => ResponseFormat::parse(s)
+            .ok_or_else(|| format!("Invalid response_format: '{}'. Use 'json' or 'summary'.", s)),
+        Some(other) => Err(format!(
+            "Invalid response_format: {}. Use 'json' or 'summary'.",
+            other
+        )),
+    }
+}
+
+/// Render a result's `results` as numbered lines under a count of `noun`
+/// (plural), `line` giving each one's text, followed by the result's paging
+/// and truncation notes.
+pub fn render_results(result: &Value, noun: &str, line: impl Fn(&Value) -> String) -> String {
+    let items = result
+        .get("results")
+        .and_then(|v| v.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut out = if items.is_empty() {
+        format!("No {} found.", noun)
+    } else {
+        format!("{} {}:", items.len(), noun)
+    };
+    for (i, item) in items.iter().enumerate() {
+        out.push_str(&format!("\n{}. {}", i + 1, line(item)));
+    }
+    for note in notes(result) {
+        out.push('\n');
+        out.push_str(&note);
+    }
+    out
+}
+
+/// The notes a result carries beside its data, as summary lines.
+pub fn notes(result: &Value) -> Vec<String> {
+    let mut notes = Vec::new();
+    for key in ["message", "truncated", "limit_note"] {
+        if let Some(note) = result.get(key).and_then(|v| v.as_str()) {
+            notes.push(note.to_string());
+        }
+    }
+    if let Some(cursor) = result.get("next_cursor").and_then(|v| v.as_str()) {
+        notes.push(format!(
+            "More results: pass cursor \"{}\" for the next page.",
+            cursor
+        ));
+    }
+    notes
+}
+
+/// `value[key]` as text, empty when missing or not a string.
+pub fn text<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+/// The date part of an RFC 3339 timestamp at `value[key]`.
+pub fn date<'a>(value: &'a Value, key: &str) -> &'a str {
+    let timestamp = text(value, key);
+    timestamp.get(..10).unwrap_or(timestamp)
+}
+
+/// An object's fields as `key: value` pairs separated by semicolons, text
+/// values unquoted.
+pub fn pairs(value: &Value) -> String {
+    let Some(obj) = value.as_object() else {
+        return String::new();
+    };
+    obj.iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => format!("{}: {}", key, s),
+            other => format!("{}: {}", key, other),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The first sentence of `text`, cut to a summary line's length.
+pub fn first_sentence(text: &str) -> String {
+    let text = text.trim();
+    let end = sentence_ends(text).next().unwrap_or(text.len());
+    let sentence = &text[..end];
+    if sentence.chars().count() <= MAX_LINE_TEXT_CHARS {
+        return sentence.to_string();
+    }
+    let cut: String = sentence.chars().take(MAX_LINE_TEXT_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::tools::registry::ToolRegistry;
+    use crate::tools::{SessionScope, ToolHandler};
+
+    use super::*;
+
+    #[test]
+    fn test_first_sentence() {
+        assert_eq!(
+            first_sentence("Tanker registered in Gabon. Reflagged twice in 2024."),
+            "Tanker registered in Gabon."
+        );
+        // Text without a sentence end is kept whole.
+        assert_eq!(first_sentence("  Shipping company  "), "Shipping company");
+        assert_eq!(first_sentence("Line one\nLine two"), "Line one");
+        // A decimal point isn't a sentence end.
+        assert_eq!(
+            first_sentence("Stake of 12.5 percent. Sold in 2023."),
+            "Stake of 12.5 percent."
+        );
+
+        let long = "word ".repeat(100);
+        let cut = first_sentence(&long);
+        assert!(cut.ends_with('…'));
+        assert!(cut.chars().count() <= MAX_LINE_TEXT_CHARS + 1);
+    }
+
+    #[test]
+    fn test_render_results() {
+        let result = json!({
+            "results": [{"name": "Acme"}, {"name": "Globex"}],
+            "truncated": "[3 more results omitted]",
+            "next_cursor": "abc",
+        });
+        let rendered = render_results(&result, "entities", |item| text(item, "name").into());
+        assert_eq!(
+            rendered,
+            "2 entities:\n1. Acme\n2. Globex\n[3 more results omitted]\n\
+             More results: pass cursor \"abc\" for the next page."
+        );
+        assert_eq!(
+            render_results(&json!({"results": []}), "claims", |_| unreachable!()),
+            "No claims found."
+        );
+    }
+
+    #[test]
+    fn test_requested_format() {
+        let mut limits = ToolResultLimits {
+            max_search_results: 20,
+            max_entity_detail_chars: 10000,
+            max_claim_preview_chars: 500,
+            max_claim_references_shown: 10,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+        };
+        limits
+            .response_formats
+            .insert("search_assessments".into(), ResponseFormat::Summary);
+
+        let format = |tool, args| requested_format(tool, &args, &limits);
+        assert_eq!(format("search_claims", json!({})), Ok(ResponseFormat::Json));
+        assert_eq!(
+            format("search_assessments", json!({})),
+            Ok(ResponseFormat::Summary)
+        );
+        assert_eq!(
+            format("search_assessments", json!({"response_format": "json"})),
+            Ok(ResponseFormat::Json)
+        );
+        assert_eq!(
+            format("search_claims", json!({"response_format": "summary"})),
+            Ok(ResponseFormat::Summary)
+        );
+        assert!(format("search_claims", json!({"response_format": "prose"}))
+            .unwrap_err()
+            .contains("'prose'"));
+        assert!(format("search_claims", json!({"response_format": 1})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_registry_sends_the_requested_format() {
+        let handler: FormattedHandler = Arc::new(|_args, _ctx| {
+            Box::pin(async {
+                let value = json!({"results": [{"name": "Acme"}, {"name": "Globex"}]});
+                Ok(Formatted::new(value, |v| {
+                    render_results(v, "entities", |item| text(item, "name").into())
+                }))
+            })
+        });
+        let plain: ToolHandler =
+            Arc::new(|_args, _ctx| Box::pin(async { Ok(json!({"ok": true})) }));
+        let ctx = ToolHandlerContext::stub(None, SessionScope::default()).await;
+        let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+            ctx.services.clone(),
+            SessionScope::default(),
+        ));
+        registry.register_formatted("search_entities", handler);
+        registry.register("get_work_order", plain);
+
+        let json = registry.execute("search_entities", json!({})).await;
+        assert!(!json.is_error);
+        assert_eq!(
+            serde_json::from_str::<Value>(&json.content).unwrap()["results"][1]["name"],
+            "Globex"
+        );
+
+        let summary = registry
+            .execute("search_entities", json!({"response_format": "summary"}))
+            .await;
+        assert_eq!(summary.content, "2 entities:\n1. Acme\n2. Globex");
+
+        let invalid = registry
+            .execute("search_entities", json!({"response_format": "xml"}))
+            .await;
+        assert!(invalid.is_error);
+        assert!(invalid.content.contains("response_format"));
+
+        // Tools without a summary ignore the argument.
+        let plain = registry
+            .execute("get_work_order", json!({"response_format": "summary"}))
+            .await;
+        assert_eq!(plain.content, r#"{"ok":true}"#);
+    }
+}
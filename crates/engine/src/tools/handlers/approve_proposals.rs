@@ -0,0 +1,169 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::types::{DomainEvent, WorkOrderMessage, WorkOrderStatus};
+use autosint_common::WorkOrderId;
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    #[serde(default)]
+    approve: Vec<String>,
+    #[serde(default)]
+    dismiss: Vec<String>,
+}
+
+fn parse_ids(ids: &[String]) -> Result<Vec<WorkOrderId>, String> {
+    ids.iter()
+        .map(|s| {
+            s.parse::<uuid::Uuid>()
+                .map(WorkOrderId::from_uuid)
+                .map_err(|e| format!("Invalid work order ID '{}': {}", s, e))
+        })
+        .collect()
+}
+
+/// Approve or dismiss work orders proposed by Processors. An approved
+/// proposal becomes a queued work order of the current cycle and is
+/// dispatched like one from create_work_order, counting against the same
+/// per-cycle limit and priority cap.
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+            let approve = parse_ids(&args.approve)?;
+            let dismiss = parse_ids(&args.dismiss)?;
+            if approve.is_empty() && dismiss.is_empty() {
+                return Err("Nothing to do: pass proposal IDs in approve or dismiss".to_string());
+            }
+            if let Some(id) = approve.iter().find(|id| dismiss.contains(id)) {
+                return Err(format!("Proposal {} is in both approve and dismiss", id));
+            }
+
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
+                "Proposal review not available (store not configured)".to_string()
+            })?;
+            let queue = ctx.services.queue.as_ref().ok_or_else(|| {
+                "Proposal review not available (queue not configured)".to_string()
+            })?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "Proposal review not available (no investigation context)".to_string()
+            })?;
+            let cycle = ctx.scope.investigation_cycle.unwrap_or(0);
+
+            let investigation = store
+                .get_investigation(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to load investigation: {}", e))?;
+
+            let mut approved = Vec::new();
+            let mut skipped = Vec::new();
+            for id in approve {
+                if let Some(max) = ctx.scope.max_work_orders_per_cycle {
+                    let current = ctx
+                        .scope
+                        .session_counters
+                        .work_orders_created
+                        .load(Ordering::Relaxed);
+                    if current >= max {
+                        skipped.push(json!({
+                            "work_order_id": id.to_string(),
+                            "reason": format!("work order limit reached ({}/{})", current, max),
+                        }));
+                        continue;
+                    }
+                }
+
+                let proposal = match store.get_work_order(id).await {
+                    Ok(wo) if wo.investigation_id == investigation_id => wo,
+                    _ => {
+                        skipped.push(json!({
+                            "work_order_id": id.to_string(),
+                            "reason": "not a proposal of this investigation",
+                        }));
+                        continue;
+                    }
+                };
+                if proposal.status != WorkOrderStatus::Proposed {
+                    skipped.push(json!({
+                        "work_order_id": id.to_string(),
+                        "reason": format!("status is {}", proposal.status.as_db_str()),
+                    }));
+                    continue;
+                }
+
+                // A Processor's suggestion can't outrank the investigation.
+                let priority = match investigation.priority_cap {
+                    Some(cap) if proposal.priority.is_above(cap) => cap,
+                    _ => proposal.priority,
+                };
+
+                let Some(created) = store
+                    .approve_work_order_proposal(id, investigation_id, cycle, priority)
+                    .await
+                    .map_err(|e| format!("Failed to approve proposal: {}", e))?
+                else {
+                    skipped.push(json!({
+                        "work_order_id": id.to_string(),
+                        "reason": "no longer pending",
+                    }));
+                    continue;
+                };
+
+                let mut msg = WorkOrderMessage::from(&created);
+                msg.correlation_id = ctx.scope.correlation_id.clone();
+                let enqueued = queue
+                    .enqueue(&msg, &created.priority)
+                    .await
+                    .map_err(|e| format!("Failed to enqueue work order: {}", e))?;
+                ctx.services
+                    .events
+                    .publish(DomainEvent::WorkOrderEnqueued {
+                        investigation_id,
+                        enqueued: enqueued.event(&msg, &created.priority),
+                    })
+                    .await;
+
+                ctx.scope
+                    .session_counters
+                    .work_orders_created
+                    .fetch_add(1, Ordering::Relaxed);
+                metrics::counter!("work_orders.proposals_approved").increment(1);
+                tracing::info!(
+                    work_order_id = %created.id,
+                    investigation_id = %investigation_id,
+                    cycle = cycle,
+                    "Work order proposal approved and enqueued"
+                );
+                approved.push(json!({
+                    "work_order_id": created.id.to_string(),
+                    "objective": created.objective,
+                    "priority": created.priority.as_str(),
+                }));
+            }
+
+            let dismissed = store
+                .dismiss_work_order_proposals(investigation_id, &dismiss)
+                .await
+                .map_err(|e| format!("Failed to dismiss proposals: {}", e))?;
+            metrics::counter!("work_orders.proposals_dismissed").increment(dismissed.len() as u64);
+            for id in dismiss.iter().filter(|id| !dismissed.contains(id)) {
+                skipped.push(json!({
+                    "work_order_id": id.to_string(),
+                    "reason": "not a pending proposal of this investigation",
+                }));
+            }
+
+            Ok(json!({
+                "approved": approved,
+                "dismissed": dismissed.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                "skipped": skipped,
+            }))
+        })
+    })
+}
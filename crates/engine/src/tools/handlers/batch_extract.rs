@@ -5,19 +5,38 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
-use autosint_common::EntityId;
+use autosint_common::types::{
+    parse_trust_level, AttributionDepth, Citation, Claim, ClaimCreated, DomainEvent, Entity,
+    IdentifierScheme, Identifiers, InformationType, Relationship, TRUST_LEVEL_PROPERTY,
+};
+use autosint_common::{ClaimId, EntityId};
 
 use crate::graph::conversions::{
     embedding_text_for_claim, embedding_text_for_entity, embedding_text_for_relationship,
 };
 use crate::graph::dedup::{DedupResult, EntityDedup};
+use crate::tools::claim_merge::merge_exact_duplicate;
+use crate::tools::claim_references::cap_claim_references;
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
+use crate::tools::entity_policy;
+use crate::tools::pii::{redaction_note, scrub_claim_content};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
+use super::create_claim::{
+    auto_link_entities, auto_linked_json, claim_citation, claim_language, claim_original,
+    claim_region, source_trust_level,
+};
+
 #[derive(Deserialize)]
 struct Args {
     source_entity_id: String,
-    source_url: String,
+    /// Required unless `citation` is given.
+    #[serde(default)]
+    source_url: Option<String>,
+    /// The document's citation (the fetch_url result's), applied to every
+    /// claim. Takes the place of `source_url`.
+    #[serde(default)]
+    citation: Option<Citation>,
     published_timestamp: String,
     #[serde(default)]
     entities: Vec<EntityArg>,
@@ -25,6 +44,9 @@ struct Args {
     claims: Vec<ClaimArg>,
     #[serde(default)]
     relationships: Vec<RelationshipArg>,
+    /// ISO 639-1 language of the document, applied to every claim.
+    #[serde(default)]
+    language: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -35,6 +57,8 @@ struct EntityArg {
     summary: Option<String>,
     #[serde(default)]
     properties: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    identifiers: HashMap<String, String>,
 }
 
 #[derive(Deserialize)]
@@ -46,6 +70,19 @@ struct ClaimArg {
     information_type: String,
     #[serde(default)]
     referenced_entity_names: Vec<String>,
+    #[serde(default)]
+    region: Option<String>,
+    /// The document's own wording of this claim, when `content` is a
+    /// translation. In the document's `language` unless `original_language`
+    /// says otherwise.
+    #[serde(default)]
+    original_content: Option<String>,
+    #[serde(default)]
+    original_language: Option<String>,
+    /// Where in the document this claim was read (page, section), set on
+    /// its copy of the citation.
+    #[serde(default)]
+    locator: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -55,6 +92,9 @@ struct RelationshipArg {
     description: String,
     #[serde(default)]
     confidence: Option<f64>,
+    /// Indexes into `claims` for the claims that evidence this relationship.
+    #[serde(default)]
+    supporting_claims: Vec<usize>,
 }
 
 fn default_attribution() -> String {
@@ -83,27 +123,46 @@ pub fn handler() -> ToolHandler {
                     .map_err(|e| {
                         format!("Invalid published_timestamp (expected RFC3339): {}", e)
                     })?;
+            let language = claim_language(args.language.as_deref())?;
+            let citation = claim_citation(args.citation, args.source_url.as_deref())?
+                .ok_or("source_url or citation is required")?;
 
             let mut warnings: Vec<String> = Vec::new();
             let mut entities_created: u32 = 0;
             let mut entities_matched: u32 = 0;
             let mut claims_created: u32 = 0;
+            let mut duplicates_merged: u32 = 0;
             let mut relationships_created: u32 = 0;
+            let mut auto_linked: Vec<Value> = Vec::new();
+            let mut redacted: Vec<Value> = Vec::new();
 
             // ---------------------------------------------------------------
             // Phase 1: Entity resolution — dedup each entity, build name→id map
             // ---------------------------------------------------------------
             let mut name_to_id: HashMap<String, EntityId> = HashMap::new();
 
-            let dedup = EntityDedup::new(&ctx.graph, &ctx.dedup_config, None);
+            let dedup = EntityDedup::new(&ctx.services.graph, &ctx.services.dedup_config, None);
 
             for entity_arg in &args.entities {
+                // Names this investigation already resolved skip the dedup pipeline.
+                if let Some(shortlist) = &ctx.scope.entity_shortlist {
+                    if let Some(entry) = shortlist
+                        .lookup(&entity_arg.canonical_name, Some(&entity_arg.kind))
+                        .await
+                    {
+                        name_to_id
+                            .insert(entity_arg.canonical_name.to_lowercase(), entry.entity_id);
+                        entities_matched += 1;
+                        continue;
+                    }
+                }
+
                 // Compute embedding for dedup + storage.
                 let embed_text = embedding_text_for_entity(
                     &entity_arg.canonical_name,
                     entity_arg.summary.as_deref(),
                 );
-                let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+                let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                     match emb_client.embed_single(&embed_text).await {
                         Ok(emb) => Some(emb),
                         Err(e) => {
@@ -119,10 +178,29 @@ pub fn handler() -> ToolHandler {
                     None
                 };
 
+                // An invalid identifier is dropped, not the entity.
+                let mut identifiers = Identifiers::new();
+                for (scheme, value) in &entity_arg.identifiers {
+                    match scheme
+                        .parse::<IdentifierScheme>()
+                        .map_err(|e| e.to_string())
+                        .and_then(|scheme| Ok((scheme, scheme.normalize(value)?)))
+                    {
+                        Ok((scheme, value)) => {
+                            identifiers.insert(scheme, value);
+                        }
+                        Err(e) => warnings.push(format!(
+                            "Invalid identifier for entity '{}' ({}), left out",
+                            entity_arg.canonical_name, e
+                        )),
+                    }
+                }
+
                 let dedup_result = match dedup
                     .find_duplicate(
                         &entity_arg.canonical_name,
                         &entity_arg.kind,
+                        &identifiers,
                         embedding.as_deref(),
                     )
                     .await
@@ -138,27 +216,65 @@ pub fn handler() -> ToolHandler {
                 };
 
                 match dedup_result {
-                    DedupResult::ExactMatch(entity_id)
-                    | DedupResult::ProbableMatch { entity_id, .. } => {
+                    DedupResult::ExactMatch(entity_id) => {
+                        shortlist_exact_match(&ctx, entity_id, &entity_arg.canonical_name).await;
+                        name_to_id.insert(entity_arg.canonical_name.to_lowercase(), entity_id);
+                        entities_matched += 1;
+                        ctx.scope
+                            .session_counters
+                            .record_entity_names([entity_arg.canonical_name.as_str()]);
+                    }
+                    DedupResult::ProbableMatch { entity_id, .. } => {
                         name_to_id.insert(entity_arg.canonical_name.to_lowercase(), entity_id);
                         entities_matched += 1;
+                        ctx.scope
+                            .session_counters
+                            .record_entity_names([entity_arg.canonical_name.as_str()]);
                     }
                     DedupResult::NoMatch => {
                         let mut entity =
                             Entity::new(entity_arg.canonical_name.clone(), entity_arg.kind.clone());
                         entity.summary = entity_arg.summary.clone();
+                        entity.created_by_investigation = ctx.scope.investigation_id;
+                        entity.identifiers = identifiers;
                         if let Some(ref props) = entity_arg.properties {
                             entity.properties = props.clone();
                         }
+                        if let Some(Err(e)) = entity
+                            .properties
+                            .get(TRUST_LEVEL_PROPERTY)
+                            .map(parse_trust_level)
+                        {
+                            warnings.push(format!(
+                                "Invalid trust_level for entity '{}' ({}), left unrated",
+                                entity_arg.canonical_name, e
+                            ));
+                            entity.properties.remove(TRUST_LEVEL_PROPERTY);
+                        }
+                        match entity_policy::apply(&ctx.services.entity_kinds, &mut entity) {
+                            Ok(policy_warnings) => warnings.extend(policy_warnings),
+                            Err(e) => {
+                                warnings.push(e);
+                                continue;
+                            }
+                        }
 
-                        match ctx.graph.create_entity(&entity, embedding).await {
+                        match ctx.services.graph.create_entity(&entity, embedding).await {
                             Ok(created) => {
+                                if let Some(shortlist) = &ctx.scope.entity_shortlist {
+                                    shortlist.record(&created, &[]).await;
+                                }
                                 name_to_id
                                     .insert(entity_arg.canonical_name.to_lowercase(), created.id);
                                 entities_created += 1;
-                                ctx.session_counters
+                                ctx.scope
+                                    .session_counters
                                     .entities_created
                                     .fetch_add(1, Ordering::Relaxed);
+                                ctx.scope.session_counters.record_entity_names(
+                                    std::iter::once(created.canonical_name.as_str())
+                                        .chain(created.aliases.iter().map(String::as_str)),
+                                );
                             }
                             Err(e) => {
                                 warnings.push(format!(
@@ -174,7 +290,12 @@ pub fn handler() -> ToolHandler {
             // ---------------------------------------------------------------
             // Phase 2: Claims
             // ---------------------------------------------------------------
-            for claim_arg in &args.claims {
+            let mut claim_ids: HashMap<usize, ClaimId> = HashMap::new();
+            let trust_level = source_trust_level(&ctx, source_entity_id).await;
+            // Claims from one document tend to share a region; look each up once.
+            let mut regions: HashMap<String, Option<String>> = HashMap::new();
+
+            for (index, claim_arg) in args.claims.iter().enumerate() {
                 let attribution_depth = match claim_arg.attribution_depth.as_str() {
                     "primary" => AttributionDepth::Primary,
                     "secondhand" | "secondary" => AttributionDepth::Secondhand,
@@ -202,6 +323,36 @@ pub fn handler() -> ToolHandler {
                     }
                 };
 
+                let (content, mut redactions) = match scrub_claim_content(
+                    &ctx,
+                    &claim_arg.content,
+                    Some(&citation.url),
+                )
+                .await
+                {
+                    Ok(scrubbed) => scrubbed,
+                    Err(e) => {
+                        warnings.push(format!("Claim {}: {}", index + 1, e));
+                        continue;
+                    }
+                };
+                let original = match claim_original(
+                    &ctx,
+                    claim_arg.original_content.as_deref(),
+                    claim_arg.original_language.as_deref(),
+                    language.as_deref(),
+                    Some(&citation.url),
+                    &mut redactions,
+                )
+                .await
+                {
+                    Ok(original) => original,
+                    Err(e) => {
+                        warnings.push(format!("Claim {}: {}", index + 1, e));
+                        continue;
+                    }
+                };
+
                 // Resolve referenced entity names to IDs.
                 let mut referenced_ids = Vec::new();
                 for name in &claim_arg.referenced_entity_names {
@@ -224,9 +375,30 @@ pub fn handler() -> ToolHandler {
                     }
                 }
 
+                let (referenced_ids, references_warning) =
+                    cap_claim_references(&ctx, &referenced_ids);
+                if let Some(warning) = references_warning {
+                    warnings.push(format!(
+                        "{} (claim: \"{}\")",
+                        warning,
+                        content.chars().take(80).collect::<String>()
+                    ));
+                }
+
+                let mut exclude = vec![source_entity_id];
+                exclude.extend(&referenced_ids);
+                let mut mentions = auto_link_entities(&ctx, &content, &exclude).await;
+                // Auto-links only fill references left under the cap.
+                mentions.truncate(
+                    ctx.services
+                        .graph
+                        .max_claim_references()
+                        .saturating_sub(referenced_ids.len()),
+                );
+
                 // Compute embedding.
-                let embed_text = embedding_text_for_claim(&claim_arg.content);
-                let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+                let embed_text = embedding_text_for_claim(&content);
+                let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                     match emb_client.embed_single(&embed_text).await {
                         Ok(emb) => Some(emb),
                         Err(e) => {
@@ -238,22 +410,101 @@ pub fn handler() -> ToolHandler {
                     None
                 };
 
+                // Shared locks keep a concurrent merge from dropping the new
+                // PUBLISHED / REFERENCES edges.
+                let mut lock_ids = vec![source_entity_id];
+                lock_ids.extend(&referenced_ids);
+                lock_ids.extend(mentions.iter().map(|m| m.entity_id));
+                let (guard, mut ids) = match lock_entities(&ctx, &lock_ids, LockMode::Shared).await
+                {
+                    Ok(locked) => locked,
+                    Err(e) => {
+                        warnings.push(format!("Claim {}: {}", index + 1, e));
+                        continue;
+                    }
+                };
+                let auto_linked_ids = ids.split_off(1 + referenced_ids.len());
+                let mut referenced_ids = ids.split_off(1);
+                let claim_source_id = ids[0];
+                for id in &auto_linked_ids {
+                    if !referenced_ids.contains(id) {
+                        referenced_ids.push(*id);
+                    }
+                }
+
                 let mut claim = Claim::new(
-                    claim_arg.content.clone(),
+                    content,
                     published_timestamp,
                     attribution_depth,
                     information_type,
-                    source_entity_id,
+                    claim_source_id,
                 );
+                claim.auto_linked_entity_ids = auto_linked_ids;
                 claim.referenced_entity_ids = referenced_ids;
-                claim.raw_source_link = Some(args.source_url.clone());
+                let mut claim_citation = citation.clone();
+                if let Some(locator) = claim_arg.locator.as_deref().map(str::trim) {
+                    if !locator.is_empty() {
+                        claim_citation.locator = Some(locator.to_string());
+                    }
+                }
+                claim.set_citation(claim_citation);
+                claim.investigation_id = ctx.scope.investigation_id;
+                claim.monitor_id = ctx.scope.monitor_id;
+                claim.language = language.clone();
+                if let Some((original_content, original_language)) = original {
+                    claim.original_content = Some(original_content);
+                    claim.original_language = Some(original_language);
+                }
+                claim.source_trust_level = trust_level;
+                claim.redactions = redactions;
+                if let Some(region) = &claim_arg.region {
+                    if !regions.contains_key(region) {
+                        let normalized = claim_region(&ctx, Some(region)).await;
+                        regions.insert(region.clone(), normalized);
+                    }
+                    claim.region = regions[region].clone();
+                }
 
-                match ctx.graph.create_claim(&claim, embedding).await {
-                    Ok(_) => {
+                let created = ctx.services.graph.create_claim(&claim, embedding).await;
+                release(guard).await;
+                match created {
+                    Ok(created) => {
+                        match merge_exact_duplicate(&ctx, &created).await {
+                            Some(merge) => {
+                                claim_ids.insert(index, merge.merged.id);
+                                duplicates_merged += 1;
+                            }
+                            None => {
+                                claim_ids.insert(index, created.id);
+                                ctx.services
+                                    .events
+                                    .publish(DomainEvent::ClaimCreated(ClaimCreated::new(
+                                        &created,
+                                        ctx.scope.work_order_id,
+                                    )))
+                                    .await;
+                            }
+                        }
+                        let excerpt = created.content.chars().take(80).collect::<String>();
+                        if !mentions.is_empty() {
+                            auto_linked.push(json!({
+                                "claim": excerpt,
+                                "entities": auto_linked_json(&mentions),
+                            }));
+                        }
+                        if !created.redactions.is_empty() {
+                            let mut note = redaction_note(&created.redactions);
+                            note["claim"] = json!(excerpt);
+                            redacted.push(note);
+                        }
                         claims_created += 1;
-                        ctx.session_counters
+                        ctx.scope
+                            .session_counters
                             .claims_created
                             .fetch_add(1, Ordering::Relaxed);
+                        ctx.scope
+                            .session_counters
+                            .record_claim_source(created.source_entity_id);
                     }
                     Err(e) => {
                         warnings.push(format!("Failed to create claim: {}", e));
@@ -300,7 +551,7 @@ pub fn handler() -> ToolHandler {
                 };
 
                 let embed_text = embedding_text_for_relationship(&rel_arg.description);
-                let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+                let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                     match emb_client.embed_single(&embed_text).await {
                         Ok(emb) => Some(emb),
                         Err(e) => {
@@ -312,18 +563,52 @@ pub fn handler() -> ToolHandler {
                     None
                 };
 
+                // Shared locks keep a concurrent merge from dropping the new edge.
+                let (guard, ids) =
+                    match lock_entities(&ctx, &[source_id, target_id], LockMode::Shared).await {
+                        Ok(locked) => locked,
+                        Err(e) => {
+                            warnings.push(format!("Relationship '{}': {}", rel_arg.description, e));
+                            continue;
+                        }
+                    };
+
                 let mut relationship =
-                    Relationship::new(source_id, target_id, rel_arg.description.clone());
+                    Relationship::new(ids[0], ids[1], rel_arg.description.clone());
                 relationship.confidence = rel_arg.confidence;
+                relationship.created_by_investigation = ctx.scope.investigation_id;
 
-                match ctx
+                let created = ctx
+                    .services
                     .graph
-                    .create_relationship(&relationship, embedding)
-                    .await
-                {
-                    Ok(_) => {
+                    .create_relationship(&relationship, embedding, false)
+                    .await;
+                release(guard).await;
+                match created {
+                    Ok(created) => {
+                        for &index in &rel_arg.supporting_claims {
+                            let Some(&claim_id) = claim_ids.get(&index) else {
+                                warnings.push(format!(
+                                    "Supporting claim {} for relationship '{}' was not created",
+                                    index, rel_arg.description
+                                ));
+                                continue;
+                            };
+                            if let Err(e) = ctx
+                                .services
+                                .graph
+                                .add_relationship_support(claim_id, &[created.id])
+                                .await
+                            {
+                                warnings.push(format!(
+                                    "Failed to link supporting claim {}: {}",
+                                    index, e
+                                ));
+                            }
+                        }
                         relationships_created += 1;
-                        ctx.session_counters
+                        ctx.scope
+                            .session_counters
                             .relationships_created
                             .fetch_add(1, Ordering::Relaxed);
                     }
@@ -344,6 +629,16 @@ pub fn handler() -> ToolHandler {
                 )
             });
 
+            if duplicates_merged > 0 {
+                // Counted in claims_created; each joined an existing claim.
+                result["duplicates_merged"] = json!(duplicates_merged);
+            }
+            if !auto_linked.is_empty() {
+                result["auto_linked"] = json!(auto_linked);
+            }
+            if !redacted.is_empty() {
+                result["redacted"] = json!(redacted);
+            }
             if !warnings.is_empty() {
                 result["warnings"] = json!(warnings);
             }
@@ -365,13 +660,39 @@ async fn resolve_name_to_id(
     resolve_entity_by_name(ctx, name).await
 }
 
-/// Search the graph for an entity by name (exact match via dedup stage 1).
+/// Resolve an entity name from the investigation's shortlist, falling back to
+/// searching the graph (exact match via dedup stage 1).
 async fn resolve_entity_by_name(ctx: &Arc<ToolHandlerContext>, name: &str) -> Option<EntityId> {
-    let dedup = EntityDedup::new(&ctx.graph, &ctx.dedup_config, None);
-    match dedup.find_duplicate(name, "", None).await {
-        Ok(DedupResult::ExactMatch(id)) | Ok(DedupResult::ProbableMatch { entity_id: id, .. }) => {
+    if let Some(shortlist) = &ctx.scope.entity_shortlist {
+        if let Some(entry) = shortlist.lookup(name, None).await {
+            return Some(entry.entity_id);
+        }
+    }
+
+    let dedup = EntityDedup::new(&ctx.services.graph, &ctx.services.dedup_config, None);
+    match dedup
+        .find_duplicate(name, "", &Identifiers::new(), None)
+        .await
+    {
+        Ok(DedupResult::ExactMatch(id)) => {
+            shortlist_exact_match(ctx, id, name).await;
             Some(id)
         }
+        Ok(DedupResult::ProbableMatch { entity_id: id, .. }) => Some(id),
         _ => None,
     }
 }
+
+/// Record an exact dedup match in the shortlist under the name that found it.
+/// Probable matches aren't recorded: they may be wrong.
+async fn shortlist_exact_match(ctx: &ToolHandlerContext, entity_id: EntityId, name: &str) {
+    let Some(shortlist) = &ctx.scope.entity_shortlist else {
+        return;
+    };
+    match ctx.services.graph.get_entity(entity_id).await {
+        Ok(entity) => shortlist.record(&entity, &[name]).await,
+        Err(e) => {
+            tracing::warn!(entity_id = %entity_id, error = %e, "Failed to load entity for shortlist")
+        }
+    }
+}
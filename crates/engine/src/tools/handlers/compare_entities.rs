@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::types::Entity;
+use autosint_common::EntityId;
+
+use crate::graph::{match_band, score_pair, TraversalParams};
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use crate::tools::truncation::{truncate_entity_detail, truncate_text};
+
+/// Relationships shown per entity, heaviest first, out of the first
+/// `RELATIONSHIPS_SCANNED`.
+const TOP_RELATIONSHIPS: usize = 5;
+const RELATIONSHIPS_SCANNED: u32 = 50;
+
+/// Most recently ingested claims shown per entity.
+const RECENT_CLAIMS: usize = 5;
+
+#[derive(Deserialize)]
+struct Args {
+    entity_id_a: String,
+    entity_id_b: String,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let parse = |raw: &str, field: &str| {
+                raw.parse::<uuid::Uuid>()
+                    .map(EntityId::from_uuid)
+                    .map_err(|e| format!("Invalid {}: {}", field, e))
+            };
+            let id_a = parse(&args.entity_id_a, "entity_id_a")?;
+            let id_b = parse(&args.entity_id_b, "entity_id_b")?;
+            if id_a == id_b {
+                return Err("entity_id_a and entity_id_b are the same entity".into());
+            }
+
+            let graph = &ctx.services.graph;
+            let a = graph
+                .get_entity(id_a)
+                .await
+                .map_err(|e| format!("Failed to get entity {}: {}", id_a, e))?;
+            let b = graph
+                .get_entity(id_b)
+                .await
+                .map_err(|e| format!("Failed to get entity {}: {}", id_b, e))?;
+
+            let limits = &ctx.services.tool_result_limits;
+            let shared = graph
+                .shared_neighbors(id_a, id_b, limits.max_search_results)
+                .await
+                .map_err(|e| format!("Failed to find shared neighbors: {}", e))?;
+
+            let scores = score_pair(&a, &b);
+            let (band, reason) =
+                match_band(&a, &b, &scores, shared.total(), &ctx.services.dedup_config);
+
+            ctx.scope
+                .session_counters
+                .record_comparison(id_a, id_b, band);
+            metrics::counter!("tools.compare_entities.verdicts", "verdict" => band.as_str())
+                .increment(1);
+            tracing::info!(
+                entity_id_a = %id_a,
+                entity_id_b = %id_b,
+                verdict = band.as_str(),
+                reason,
+                name_similarity = scores.name_similarity,
+                embedding_similarity = scores.embedding_similarity,
+                shared_neighbors = shared.total(),
+                "Entities compared"
+            );
+
+            let side_a = entity_side(&ctx, &a).await?;
+            let side_b = entity_side(&ctx, &b).await?;
+
+            let mut shared_section = json!({
+                "entity_count": shared.entity_total,
+                "entities": shared
+                    .entities
+                    .iter()
+                    .map(|(id, name)| json!({ "id": id.to_string(), "canonical_name": name }))
+                    .collect::<Vec<_>>(),
+                "claim_count": shared.claim_total,
+                "claim_ids": shared.claims.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            });
+            if shared.entity_total as usize > shared.entities.len()
+                || shared.claim_total as usize > shared.claims.len()
+            {
+                shared_section["truncated"] = json!(format!(
+                    "[showing at most {} of each]",
+                    limits.max_search_results
+                ));
+            }
+
+            Ok(json!({
+                "verdict": band.as_str(),
+                "reason": reason,
+                "scores": {
+                    "name_similarity": round(scores.name_similarity),
+                    "shared_names": scores.shared_names,
+                    "embedding_similarity": scores.embedding_similarity.map(round),
+                    "shared_identifiers": scores.shared_identifiers,
+                    "conflicting_identifiers": scores.conflicting_identifiers,
+                    "same_kind": scores.same_kind,
+                },
+                "shared_neighbors": shared_section,
+                "entity_a": side_a,
+                "entity_b": side_b,
+            }))
+        })
+    })
+}
+
+/// One entity's details, heaviest relationships and most recent claims,
+/// each section cut to the result limits.
+async fn entity_side(ctx: &ToolHandlerContext, entity: &Entity) -> Result<Value, String> {
+    let graph = &ctx.services.graph;
+    let limits = &ctx.services.tool_result_limits;
+    let preview = limits.max_claim_preview_chars as usize;
+
+    let mut relationships = graph
+        .traverse_relationships(
+            entity.id,
+            &TraversalParams {
+                direction: None,
+                min_weight: None,
+                as_of: None,
+                limit: Some(RELATIONSHIPS_SCANNED),
+            },
+        )
+        .await
+        .map_err(|e| format!("Failed to get relationships of {}: {}", entity.id, e))?
+        .results;
+    relationships.sort_by(|x, y| {
+        y.0.weight
+            .partial_cmp(&x.0.weight)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let relationships: Vec<Value> = relationships
+        .iter()
+        .take(TOP_RELATIONSHIPS)
+        .map(|(rel, other)| {
+            json!({
+                "description": truncate_text(&rel.description, preview),
+                "weight": rel.weight,
+                "outgoing": rel.source_entity_id == entity.id,
+                "other_entity_id": other.id.to_string(),
+                "other_entity_name": other.canonical_name,
+            })
+        })
+        .collect();
+
+    let claims: Vec<Value> = graph
+        .recent_claims(entity.id, RECENT_CLAIMS, preview)
+        .await
+        .map_err(|e| format!("Failed to get claims about {}: {}", entity.id, e))?
+        .iter()
+        .map(|claim| {
+            json!({
+                "id": claim.claim_id.to_string(),
+                "content": claim.preview(preview),
+                "published_timestamp": claim.published_timestamp.to_rfc3339(),
+            })
+        })
+        .collect();
+
+    let mut side = json!({
+        "id": entity.id.to_string(),
+        "canonical_name": entity.canonical_name,
+        "kind": entity.kind,
+        "summary": entity.summary,
+        "aliases": entity.aliases,
+        "is_stub": entity.is_stub,
+        "identifiers": entity.identifiers,
+        "properties": serde_json::to_value(&entity.properties).unwrap_or_default(),
+        "top_relationships": relationships,
+        "recent_claims": claims,
+    });
+    truncate_entity_detail(&mut side, limits);
+    Ok(side)
+}
+
+fn round(score: f64) -> f64 {
+    (score * 1000.0).round() / 1000.0
+}
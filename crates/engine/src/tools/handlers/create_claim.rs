@@ -1,13 +1,22 @@
+use std::collections::BTreeMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::types::{AttributionDepth, Claim, InformationType};
-use autosint_common::EntityId;
+use autosint_common::types::{
+    normalize_language, AttributionDepth, Citation, Claim, ClaimCreated, DomainEvent,
+    InformationType,
+};
+use autosint_common::{EntityId, RelationshipId};
 
 use crate::graph::conversions::embedding_text_for_claim;
+use crate::graph::linking::NameMention;
+use crate::tools::claim_merge::merge_exact_duplicate;
+use crate::tools::claim_references::cap_claim_references;
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
+use crate::tools::pii::{redaction_note, scrub_claim_content};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
 #[derive(Deserialize)]
@@ -23,6 +32,22 @@ struct Args {
     referenced_entity_ids: Vec<String>,
     #[serde(default)]
     raw_source_link: Option<String>,
+    /// Takes the place of `raw_source_link`, usually the fetch_url result's
+    /// citation passed through whole.
+    #[serde(default)]
+    citation: Option<Citation>,
+    #[serde(default)]
+    supports_relationship_ids: Vec<String>,
+    #[serde(default)]
+    language: Option<String>,
+    /// The source's own wording, when `content` is a translation.
+    #[serde(default)]
+    original_content: Option<String>,
+    /// Language of `original_content`; defaults to `language`.
+    #[serde(default)]
+    original_language: Option<String>,
+    #[serde(default)]
+    region: Option<String>,
 }
 
 fn default_attribution() -> String {
@@ -72,6 +97,28 @@ pub fn handler() -> ToolHandler {
                         format!("Invalid published_timestamp (expected RFC3339): {}", e)
                     })?;
 
+            let language = claim_language(args.language.as_deref())?;
+            let citation = claim_citation(args.citation, args.raw_source_link.as_deref())?;
+            let region = claim_region(&ctx, args.region.as_deref()).await;
+
+            // Scrub before anything else reads the content: the embedding and
+            // auto-links must not see what the stored claim won't.
+            let (content, mut redactions) = scrub_claim_content(
+                &ctx,
+                &args.content,
+                citation.as_ref().map(|c| c.url.as_str()),
+            )
+            .await?;
+            let original = claim_original(
+                &ctx,
+                args.original_content.as_deref(),
+                args.original_language.as_deref(),
+                language.as_deref(),
+                citation.as_ref().map(|c| c.url.as_str()),
+                &mut redactions,
+            )
+            .await?;
+
             let referenced_entity_ids: Vec<EntityId> = args
                 .referenced_entity_ids
                 .iter()
@@ -81,10 +128,50 @@ pub fn handler() -> ToolHandler {
                         .map_err(|e| format!("Invalid referenced_entity_id '{}': {}", s, e))
                 })
                 .collect::<Result<_, _>>()?;
+            let (referenced_entity_ids, references_warning) =
+                cap_claim_references(&ctx, &referenced_entity_ids);
+
+            let supports_relationship_ids: Vec<RelationshipId> = args
+                .supports_relationship_ids
+                .iter()
+                .map(|s| {
+                    s.parse::<uuid::Uuid>()
+                        .map(RelationshipId::from_uuid)
+                        .map_err(|e| format!("Invalid supports_relationship_id '{}': {}", s, e))
+                })
+                .collect::<Result<_, _>>()?;
+            if !supports_relationship_ids.is_empty() {
+                let existing = ctx
+                    .services
+                    .graph
+                    .existing_relationship_ids(&supports_relationship_ids)
+                    .await
+                    .map_err(|e| format!("Failed to look up relationships: {}", e))?;
+                if let Some(missing) = supports_relationship_ids
+                    .iter()
+                    .find(|id| !existing.contains(id))
+                {
+                    return Err(format!("Relationship {} not found", missing));
+                }
+            }
+
+            let auto_linked = {
+                let mut exclude = vec![source_entity_id];
+                exclude.extend(&referenced_entity_ids);
+                let mut mentions = auto_link_entities(&ctx, &content, &exclude).await;
+                // Auto-links only fill references left under the cap.
+                mentions.truncate(
+                    ctx.services
+                        .graph
+                        .max_claim_references()
+                        .saturating_sub(referenced_entity_ids.len()),
+                );
+                mentions
+            };
 
             // Compute embedding.
-            let embed_text = embedding_text_for_claim(&args.content);
-            let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+            let embed_text = embedding_text_for_claim(&content);
+            let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                 match emb_client.embed_single(&embed_text).await {
                     Ok(emb) => Some(emb),
                     Err(e) => {
@@ -96,33 +183,244 @@ pub fn handler() -> ToolHandler {
                 None
             };
 
+            // Shared locks keep a concurrent merge from dropping the new PUBLISHED /
+            // REFERENCES edges.
+            let mut lock_ids = vec![source_entity_id];
+            lock_ids.extend(&referenced_entity_ids);
+            lock_ids.extend(auto_linked.iter().map(|m| m.entity_id));
+            let (guard, mut ids) = lock_entities(&ctx, &lock_ids, LockMode::Shared).await?;
+            let auto_linked_ids = ids.split_off(1 + referenced_entity_ids.len());
+            let mut referenced_entity_ids = ids.split_off(1);
+            let source_entity_id = ids[0];
+            for id in &auto_linked_ids {
+                if !referenced_entity_ids.contains(id) {
+                    referenced_entity_ids.push(*id);
+                }
+            }
+
             let mut claim = Claim::new(
-                args.content,
+                content,
                 published_timestamp,
                 attribution_depth,
                 information_type,
                 source_entity_id,
             );
             claim.referenced_entity_ids = referenced_entity_ids;
-            claim.raw_source_link = args.raw_source_link;
+            claim.auto_linked_entity_ids = auto_linked_ids;
+            if let Some(citation) = citation {
+                claim.set_citation(citation);
+            }
+            claim.supports_relationship_ids = supports_relationship_ids;
+            claim.investigation_id = ctx.scope.investigation_id;
+            claim.monitor_id = ctx.scope.monitor_id;
+            claim.language = language;
+            if let Some((original_content, original_language)) = original {
+                claim.original_content = Some(original_content);
+                claim.original_language = Some(original_language);
+            }
+            claim.region = region;
+            claim.source_trust_level = source_trust_level(&ctx, source_entity_id).await;
+            claim.redactions = redactions;
 
-            let created = ctx
-                .graph
-                .create_claim(&claim, embedding)
-                .await
-                .map_err(|e| format!("Failed to create claim: {}", e))?;
+            let created = ctx.services.graph.create_claim(&claim, embedding).await;
+            release(guard).await;
+            let created = created.map_err(|e| format!("Failed to create claim: {}", e))?;
 
-            ctx.session_counters
+            ctx.scope
+                .session_counters
                 .claims_created
                 .fetch_add(1, Ordering::Relaxed);
+            ctx.scope
+                .session_counters
+                .record_claim_source(created.source_entity_id);
+
+            // An exact duplicate joins the existing claim, which then cites
+            // both sources.
+            if let Some(merge) = merge_exact_duplicate(&ctx, &created).await {
+                return Ok(json!({
+                    "claim_id": merge.merged.id.to_string(),
+                    "content": merge.merged.content,
+                    "source_entity_id": merge.merged.source_entity_id.to_string(),
+                    "referenced_entity_ids": merge.merged.referenced_entity_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                    "merged_into_existing": true,
+                    "message": format!(
+                        "Claim {} already held this content; this source was merged into it.",
+                        merge.merged.id
+                    )
+                }));
+            }
+            ctx.services
+                .events
+                .publish(DomainEvent::ClaimCreated(ClaimCreated::new(
+                    &created,
+                    ctx.scope.work_order_id,
+                )))
+                .await;
 
-            Ok(json!({
+            let mut result = json!({
                 "claim_id": created.id.to_string(),
                 "content": created.content,
                 "source_entity_id": created.source_entity_id.to_string(),
                 "referenced_entity_ids": created.referenced_entity_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
                 "message": "Claim created successfully."
-            }))
+            });
+            if !auto_linked.is_empty() {
+                result["auto_linked"] = auto_linked_json(&auto_linked);
+            }
+            if !created.redactions.is_empty() {
+                result["redacted"] = redaction_note(&created.redactions);
+            }
+            if let Some(warning) = references_warning {
+                result["warning"] = json!(warning);
+            }
+
+            Ok(result)
         })
     })
 }
+
+/// Known entities named in `content` that the Processor didn't reference, when
+/// entity linking is enabled and its flag is on. `exclude` holds the source and explicit references.
+pub(crate) async fn auto_link_entities(
+    ctx: &ToolHandlerContext,
+    content: &str,
+    exclude: &[EntityId],
+) -> Vec<NameMention> {
+    match ctx.services.entity_linker() {
+        Some(linker) => linker.link(content, exclude).await,
+        None => Vec::new(),
+    }
+}
+
+/// Validate a claim language argument as ISO 639-1. Blank means none.
+pub(crate) fn claim_language(language: Option<&str>) -> Result<Option<String>, String> {
+    match language.map(str::trim).filter(|l| !l.is_empty()) {
+        Some(tag) => normalize_language(tag).map(Some).ok_or_else(|| {
+            format!(
+                "Invalid language: '{}'. Use an ISO 639-1 code such as 'en'.",
+                tag
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// A claim's source-language wording and its language, scrubbed like the
+/// content, with what was redacted from it added to `redactions`. The
+/// language defaults to the claim's. Blank wording means none.
+pub(crate) async fn claim_original(
+    ctx: &ToolHandlerContext,
+    original_content: Option<&str>,
+    original_language: Option<&str>,
+    language: Option<&str>,
+    raw_source_link: Option<&str>,
+    redactions: &mut BTreeMap<String, u32>,
+) -> Result<Option<(String, String)>, String> {
+    let Some(original_content) = original_content.filter(|c| !c.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let original_language = claim_language(original_language)?
+        .or_else(|| language.map(str::to_string))
+        .ok_or(
+            "original_content needs original_language (or language): the ISO 639-1 code \
+                of the source's wording.",
+        )?;
+    let (original_content, original_redactions) =
+        scrub_claim_content(ctx, original_content, raw_source_link).await?;
+    for (kind, count) in original_redactions {
+        *redactions.entry(kind).or_default() += count;
+    }
+    Ok(Some((original_content, original_language)))
+}
+
+/// A claim's citation: `citation` if given, else one of just
+/// `raw_source_link`. Blank means none.
+pub(crate) fn claim_citation(
+    citation: Option<Citation>,
+    raw_source_link: Option<&str>,
+) -> Result<Option<Citation>, String> {
+    match citation {
+        Some(citation) if citation.url.trim().is_empty() => {
+            Err("Invalid citation: url is required.".into())
+        }
+        Some(citation) => Ok(Some(citation.normalized())),
+        None => Ok(raw_source_link
+            .map(str::trim)
+            .filter(|link| !link.is_empty())
+            .map(Citation::from_url)),
+    }
+}
+
+/// Normalize a claim region argument against the seeded geo entities. A
+/// failed lookup keeps the Processor's text rather than failing the claim.
+pub(crate) async fn claim_region(ctx: &ToolHandlerContext, region: Option<&str>) -> Option<String> {
+    let region = region?;
+    match ctx.services.graph.normalize_region(region).await {
+        Ok(normalized) => normalized,
+        Err(e) => {
+            tracing::warn!(error = %e, region, "Failed to normalize claim region");
+            Some(region.trim().to_string()).filter(|r| !r.is_empty())
+        }
+    }
+}
+
+/// The source entity's trust level, recorded on its claims as ingested. A
+/// failed lookup leaves the claim unrated rather than failing it.
+pub(crate) async fn source_trust_level(
+    ctx: &ToolHandlerContext,
+    source_entity_id: EntityId,
+) -> Option<u8> {
+    match ctx.services.graph.get_entity(source_entity_id).await {
+        Ok(source) => source.trust_level(),
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                source_entity_id = %source_entity_id,
+                "Failed to read source trust level"
+            );
+            None
+        }
+    }
+}
+
+/// Tool-result listing of auto-linked entities, so the LLM sees what was added.
+pub(crate) fn auto_linked_json(mentions: &[NameMention]) -> Value {
+    json!(mentions
+        .iter()
+        .map(|m| json!({
+            "entity_id": m.entity_id.to_string(),
+            "matched_name": m.matched_name,
+        }))
+        .collect::<Vec<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autosint_common::types::CitationVia;
+
+    #[test]
+    fn test_claim_citation() {
+        let fetched: Citation = serde_json::from_value(json!({
+            "url": "https://news.example/port?utm_source=rss",
+            "title": "Port closes",
+            "accessed_at": "2026-03-02T09:30:00Z",
+            "via": "cache",
+        }))
+        .unwrap();
+        let citation = claim_citation(Some(fetched), Some("https://other.example"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(citation.url, "https://news.example/port?utm_source=rss");
+        assert_eq!(
+            citation.normalized_url.as_deref(),
+            Some("https://news.example/port")
+        );
+        assert_eq!(citation.via, CitationVia::Cache);
+
+        let legacy = claim_citation(None, Some(" https://news.example/a ")).unwrap();
+        assert_eq!(legacy.unwrap().url, "https://news.example/a");
+        assert_eq!(claim_citation(None, Some("  ")).unwrap(), None);
+        assert!(claim_citation(Some(Citation::from_url("")), None).is_err());
+    }
+}
@@ -1,15 +1,19 @@
+use std::collections::HashMap;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::types::Entity;
+use autosint_common::types::{parse_identifiers, Entity};
 
 use crate::graph::conversions::embedding_text_for_entity;
 use crate::graph::dedup::{DedupResult, EntityDedup};
+use crate::tools::entity_policy;
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
+use super::update_entity::trust_level_properties;
+
 #[derive(Deserialize)]
 struct Args {
     canonical_name: String,
@@ -21,7 +25,11 @@ struct Args {
     #[serde(default)]
     is_stub: Option<bool>,
     #[serde(default)]
-    properties: Option<std::collections::HashMap<String, Value>>,
+    properties: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    trust_level: Option<Value>,
+    #[serde(default)]
+    identifiers: HashMap<String, String>,
 }
 
 pub fn handler() -> ToolHandler {
@@ -29,11 +37,14 @@ pub fn handler() -> ToolHandler {
         Box::pin(async move {
             let args: Args =
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+            let properties = trust_level_properties(args.properties, args.trust_level)?;
+            let identifiers = parse_identifiers(&args.identifiers)
+                .map_err(|e| format!("Invalid identifiers: {}", e))?;
 
             // Compute embedding for dedup + storage.
             let embed_text =
                 embedding_text_for_entity(&args.canonical_name, args.summary.as_deref());
-            let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+            let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                 match emb_client.embed_single(&embed_text).await {
                     Ok(emb) => Some(emb),
                     Err(e) => {
@@ -45,26 +56,39 @@ pub fn handler() -> ToolHandler {
                 None
             };
 
-            // Run dedup pipeline (stages 1-3; LLM judge = None for now).
-            let dedup = EntityDedup::new(&ctx.graph, &ctx.dedup_config, None);
+            // Run dedup pipeline (stages 0-3; LLM judge = None for now).
+            let dedup = EntityDedup::new(&ctx.services.graph, &ctx.services.dedup_config, None);
             let dedup_result = dedup
-                .find_duplicate(&args.canonical_name, &args.kind, embedding.as_deref())
+                .find_duplicate(
+                    &args.canonical_name,
+                    &args.kind,
+                    &identifiers,
+                    embedding.as_deref(),
+                )
                 .await
                 .map_err(|e| format!("Dedup check failed: {}", e))?;
 
             match dedup_result {
                 DedupResult::ExactMatch(entity_id) => {
                     let existing = ctx
+                        .services
                         .graph
                         .get_entity(entity_id)
                         .await
                         .map_err(|e| format!("Failed to get existing entity: {}", e))?;
+                    if let Some(shortlist) = &ctx.scope.entity_shortlist {
+                        shortlist.record(&existing, &[&args.canonical_name]).await;
+                    }
+                    ctx.scope.session_counters.record_entity_names(
+                        entity_names(&existing).chain([args.canonical_name.as_str()]),
+                    );
                     Ok(json!({
                         "deduplicated": true,
                         "entity_id": existing.id.to_string(),
                         "canonical_name": existing.canonical_name,
                         "kind": existing.kind,
                         "summary": existing.summary,
+                        "identifiers": existing.identifiers,
                         "message": "Entity already exists (exact match). Use update_entity to modify."
                     }))
                 }
@@ -74,10 +98,18 @@ pub fn handler() -> ToolHandler {
                     ..
                 } => {
                     let existing = ctx
+                        .services
                         .graph
                         .get_entity(entity_id)
                         .await
                         .map_err(|e| format!("Failed to get existing entity: {}", e))?;
+                    // Only the entity's own names: a probable match may be wrong.
+                    if let Some(shortlist) = &ctx.scope.entity_shortlist {
+                        shortlist.record(&existing, &[]).await;
+                    }
+                    ctx.scope
+                        .session_counters
+                        .record_entity_names(entity_names(&existing));
                     Ok(json!({
                         "deduplicated": true,
                         "entity_id": existing.id.to_string(),
@@ -92,36 +124,58 @@ pub fn handler() -> ToolHandler {
                     // Create the new entity.
                     let mut entity = Entity::new(args.canonical_name, args.kind);
                     entity.summary = args.summary;
+                    entity.created_by_investigation = ctx.scope.investigation_id;
+                    entity.identifiers = identifiers;
                     if let Some(aliases) = args.aliases {
                         entity.aliases = aliases;
                     }
                     if let Some(is_stub) = args.is_stub {
                         entity.is_stub = is_stub;
                     }
-                    if let Some(properties) = args.properties {
+                    if let Some(properties) = properties {
                         entity.properties = properties;
                     }
+                    let policy_warnings =
+                        entity_policy::apply(&ctx.services.entity_kinds, &mut entity)?;
 
                     let created = ctx
+                        .services
                         .graph
                         .create_entity(&entity, embedding)
                         .await
                         .map_err(|e| format!("Failed to create entity: {}", e))?;
 
-                    ctx.session_counters
+                    ctx.scope
+                        .session_counters
                         .entities_created
                         .fetch_add(1, Ordering::Relaxed);
+                    ctx.scope
+                        .session_counters
+                        .record_entity_names(entity_names(&created));
+                    if let Some(shortlist) = &ctx.scope.entity_shortlist {
+                        shortlist.record(&created, &[]).await;
+                    }
 
-                    Ok(json!({
+                    let mut result = json!({
                         "deduplicated": false,
                         "entity_id": created.id.to_string(),
                         "canonical_name": created.canonical_name,
                         "kind": created.kind,
                         "summary": created.summary,
+                        "identifiers": created.identifiers,
                         "message": "Entity created successfully."
-                    }))
+                    });
+                    if !policy_warnings.is_empty() {
+                        result["warnings"] = json!(policy_warnings);
+                    }
+                    Ok(result)
                 }
             }
         })
     })
 }
+
+/// An entity's canonical name and aliases.
+fn entity_names(entity: &Entity) -> impl Iterator<Item = &str> {
+    std::iter::once(entity.canonical_name.as_str()).chain(entity.aliases.iter().map(String::as_str))
+}
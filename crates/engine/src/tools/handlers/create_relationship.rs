@@ -4,10 +4,13 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use autosint_common::config::DuplicateRelationshipMode;
 use autosint_common::types::Relationship;
 use autosint_common::EntityId;
 
 use crate::graph::conversions::embedding_text_for_relationship;
+use crate::graph::dedup::duplicate_relationship;
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
 #[derive(Deserialize)]
@@ -23,6 +26,8 @@ struct Args {
     bidirectional: Option<bool>,
     #[serde(default)]
     timestamp: Option<String>,
+    #[serde(default)]
+    allow_self: bool,
 }
 
 pub fn handler() -> ToolHandler {
@@ -43,6 +48,15 @@ pub fn handler() -> ToolHandler {
                 .map(EntityId::from_uuid)
                 .map_err(|e| format!("Invalid target_entity_id: {}", e))?;
 
+            if source_id == target_id && !args.allow_self {
+                return Err(
+                    "source_entity_id and target_entity_id are the same entity. Relationships \
+                     connect two different entities; pass allow_self: true only if the entity \
+                     genuinely relates to itself."
+                        .into(),
+                );
+            }
+
             let timestamp = args
                 .timestamp
                 .as_deref()
@@ -55,7 +69,7 @@ pub fn handler() -> ToolHandler {
 
             // Compute embedding.
             let embed_text = embedding_text_for_relationship(&args.description);
-            let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+            let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                 match emb_client.embed_single(&embed_text).await {
                     Ok(emb) => Some(emb),
                     Err(e) => {
@@ -67,29 +81,85 @@ pub fn handler() -> ToolHandler {
                 None
             };
 
+            // Shared locks keep a concurrent merge from dropping the new edge.
+            let (guard, ids) =
+                lock_entities(&ctx, &[source_id, target_id], LockMode::Shared).await?;
+            let (source_id, target_id) = (ids[0], ids[1]);
+
+            let existing = match ctx
+                .services
+                .graph
+                .relationships_between(source_id, target_id)
+                .await
+            {
+                Ok(existing) => existing,
+                Err(e) => {
+                    release(guard).await;
+                    return Err(format!(
+                        "Failed to check for duplicate relationships: {}",
+                        e
+                    ));
+                }
+            };
+            let duplicate =
+                duplicate_relationship(&existing, &args.description, &ctx.services.dedup_config)
+                    .map(|(rel, score)| (rel.clone(), score));
+
+            if let Some((duplicate, score)) = &duplicate {
+                metrics::counter!("tools.create_relationship.duplicates").increment(1);
+                if ctx.services.dedup_config.duplicate_relationships
+                    == DuplicateRelationshipMode::Dedupe
+                {
+                    release(guard).await;
+                    return Ok(json!({
+                        "relationship_id": duplicate.id.to_string(),
+                        "source_entity_id": duplicate.source_entity_id.to_string(),
+                        "target_entity_id": duplicate.target_entity_id.to_string(),
+                        "description": duplicate.description,
+                        "deduplicated": true,
+                        "similarity": score,
+                        "message": "An equivalent relationship already exists; returned it instead of creating a new one."
+                    }));
+                }
+            }
+
             let mut relationship = Relationship::new(source_id, target_id, args.description);
             relationship.weight = args.weight;
             relationship.confidence = args.confidence;
             relationship.bidirectional = args.bidirectional.unwrap_or(false);
             relationship.timestamp = timestamp;
+            relationship.created_by_investigation = ctx.scope.investigation_id;
 
             let created = ctx
+                .services
                 .graph
-                .create_relationship(&relationship, embedding)
-                .await
-                .map_err(|e| format!("Failed to create relationship: {}", e))?;
+                .create_relationship(&relationship, embedding, args.allow_self)
+                .await;
+            release(guard).await;
+            let created = created.map_err(|e| format!("Failed to create relationship: {}", e))?;
 
-            ctx.session_counters
+            ctx.scope
+                .session_counters
                 .relationships_created
                 .fetch_add(1, Ordering::Relaxed);
 
-            Ok(json!({
+            let mut result = json!({
                 "relationship_id": created.id.to_string(),
                 "source_entity_id": created.source_entity_id.to_string(),
                 "target_entity_id": created.target_entity_id.to_string(),
                 "description": created.description,
                 "message": "Relationship created successfully."
-            }))
+            });
+            if let Some((duplicate, score)) = duplicate {
+                result["possible_duplicate_of"] = json!(duplicate.id.to_string());
+                result["similarity"] = json!(score);
+                result["warning"] = json!(format!(
+                    "A near-identical relationship already exists between these entities ({}: \"{}\"). Prefer update_relationship over creating duplicates.",
+                    duplicate.id, duplicate.description
+                ));
+            }
+
+            Ok(result)
         })
     })
 }
@@ -4,9 +4,13 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::types::{SourceGuidance, WorkOrder, WorkOrderPriority};
+use autosint_common::types::{
+    DomainEvent, ExpectedOutputs, SourceGuidance, WorkOrder, WorkOrderEffort, WorkOrderMode,
+    WorkOrderPriority, WorkOrderStatus,
+};
 use autosint_common::EntityId;
 
+use crate::tools::fetch_sources::{failing_preferences, list_sources};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
 #[derive(Deserialize)]
@@ -18,6 +22,16 @@ struct Args {
     source_guidance: Option<SourceGuidanceArgs>,
     #[serde(default)]
     priority: Option<String>,
+    #[serde(default)]
+    not_before: Option<String>,
+    #[serde(default)]
+    deadline: Option<String>,
+    #[serde(default)]
+    effort: Option<String>,
+    #[serde(default)]
+    mode: Option<String>,
+    #[serde(default)]
+    expected_outputs: Option<ExpectedOutputs>,
 }
 
 #[derive(Deserialize)]
@@ -33,20 +47,21 @@ pub fn handler() -> ToolHandler {
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
 
             // Verify analyst context is available.
-            let store = ctx.store.as_ref().ok_or_else(|| {
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
                 "Work order creation not available (store not configured)".to_string()
             })?;
-            let queue = ctx.queue.as_ref().ok_or_else(|| {
+            let queue = ctx.services.queue.as_ref().ok_or_else(|| {
                 "Work order creation not available (queue not configured)".to_string()
             })?;
-            let investigation_id = ctx.investigation_id.ok_or_else(|| {
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
                 "Work order creation not available (no investigation context)".to_string()
             })?;
-            let cycle = ctx.investigation_cycle.unwrap_or(0);
+            let cycle = ctx.scope.investigation_cycle.unwrap_or(0);
 
             // Enforce work order limit per cycle.
-            if let Some(max) = ctx.max_work_orders_per_cycle {
+            if let Some(max) = ctx.scope.max_work_orders_per_cycle {
                 let current = ctx
+                    .scope
                     .session_counters
                     .work_orders_created
                     .load(Ordering::Relaxed);
@@ -60,16 +75,23 @@ pub fn handler() -> ToolHandler {
 
             // Parse priority.
             let priority = match args.priority.as_deref() {
-                Some("high") => WorkOrderPriority::High,
-                Some("normal") | None => WorkOrderPriority::Normal,
-                Some("low") => WorkOrderPriority::Low,
-                Some(other) => {
-                    return Err(format!(
-                        "Invalid priority: '{}'. Use 'high', 'normal', or 'low'.",
-                        other
-                    ))
-                }
+                Some(p) => p.parse::<WorkOrderPriority>().map_err(|e| e.to_string())?,
+                None => WorkOrderPriority::default(),
             };
+            let investigation = store
+                .get_investigation(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to load investigation: {}", e))?;
+            if let Some(cap) = investigation
+                .priority_cap
+                .filter(|cap| priority.is_above(*cap))
+            {
+                return Err(format!(
+                    "Priority '{}' exceeds this investigation's cap of '{}'",
+                    priority.as_str(),
+                    cap.as_str()
+                ));
+            }
 
             // Parse referenced entity IDs.
             let referenced_entities: Vec<EntityId> = args
@@ -82,6 +104,77 @@ pub fn handler() -> ToolHandler {
                 })
                 .collect::<Result<_, _>>()?;
 
+            // Parse delay. Times in the past dispatch immediately.
+            let not_before = args
+                .not_before
+                .as_deref()
+                .map(|s| {
+                    chrono::DateTime::parse_from_rfc3339(s)
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                        .map_err(|e| format!("Invalid not_before '{}': {}", s, e))
+                })
+                .transpose()?
+                .filter(|t| *t > chrono::Utc::now());
+
+            // Parse deadline. Unlike not_before, a time in the past is a mistake.
+            let deadline = args
+                .deadline
+                .as_deref()
+                .map(|s| {
+                    chrono::DateTime::parse_from_rfc3339(s)
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                        .map_err(|e| format!("Invalid deadline '{}': {}", s, e))
+                })
+                .transpose()?;
+            if let Some(deadline) = deadline {
+                if deadline <= chrono::Utc::now() {
+                    return Err(format!(
+                        "Deadline {} has already passed",
+                        deadline.to_rfc3339()
+                    ));
+                }
+                if not_before.is_some_and(|nb| nb >= deadline) {
+                    return Err("Deadline must be later than not_before".to_string());
+                }
+            }
+
+            let effort = match args.effort.as_deref() {
+                Some(e) => e.parse::<WorkOrderEffort>().map_err(|e| e.to_string())?,
+                None => WorkOrderEffort::default(),
+            };
+            let mode = match args.mode.as_deref() {
+                Some(m) => m.parse::<WorkOrderMode>().map_err(|e| e.to_string())?,
+                None => WorkOrderMode::default(),
+            };
+
+            // An empty contract asks for nothing.
+            let expected_outputs = args.expected_outputs.filter(|e| !e.is_empty());
+            if let Some(expected) = &expected_outputs {
+                if expected
+                    .require_entities
+                    .iter()
+                    .any(|n| n.trim().is_empty())
+                {
+                    return Err("expected_outputs.require_entities has an empty name".to_string());
+                }
+            }
+
+            // Preferring a source that's currently failing is allowed, but
+            // worth a warning. No catalog, no warning.
+            let mut warnings = Vec::new();
+            if let Some(sg) = args
+                .source_guidance
+                .as_ref()
+                .filter(|sg| !sg.prefer.is_empty())
+            {
+                match list_sources(&ctx.services.fetch).await {
+                    Ok(sources) => warnings = failing_preferences(&sg.prefer, &sources),
+                    Err(e) => {
+                        tracing::debug!(error = %e, "Source health unavailable for source guidance")
+                    }
+                }
+            }
+
             // Build source guidance.
             let source_guidance = args.source_guidance.map(|sg| SourceGuidance {
                 prefer: sg.prefer,
@@ -93,6 +186,14 @@ pub fn handler() -> ToolHandler {
             wo.referenced_entities = referenced_entities;
             wo.source_guidance = source_guidance;
             wo.cycle = cycle;
+            wo.deadline = deadline;
+            wo.effort = effort;
+            wo.mode = mode;
+            wo.expected_outputs = expected_outputs;
+            if let Some(not_before) = not_before {
+                wo.not_before = Some(not_before);
+                wo.status = WorkOrderStatus::Scheduled;
+            }
 
             // Persist to PostgreSQL.
             let created = store
@@ -101,14 +202,23 @@ pub fn handler() -> ToolHandler {
                 .map_err(|e| format!("Failed to create work order: {}", e))?;
 
             // Enqueue to Redis.
-            let msg = autosint_common::types::WorkOrderMessage::from(&created);
-            queue
+            let mut msg = autosint_common::types::WorkOrderMessage::from(&created);
+            msg.correlation_id = ctx.scope.correlation_id.clone();
+            let enqueued = queue
                 .enqueue(&msg, &created.priority)
                 .await
                 .map_err(|e| format!("Failed to enqueue work order: {}", e))?;
+            ctx.services
+                .events
+                .publish(DomainEvent::WorkOrderEnqueued {
+                    investigation_id,
+                    enqueued: enqueued.event(&msg, &created.priority),
+                })
+                .await;
 
             // Increment counter.
-            ctx.session_counters
+            ctx.scope
+                .session_counters
                 .work_orders_created
                 .fetch_add(1, Ordering::Relaxed);
 
@@ -120,13 +230,32 @@ pub fn handler() -> ToolHandler {
                 "Work order created and enqueued"
             );
 
-            Ok(json!({
+            let mut result = json!({
                 "work_order_id": created.id.to_string(),
                 "objective": created.objective,
                 "priority": format!("{:?}", created.priority).to_lowercase(),
                 "cycle": created.cycle,
+                "effort": created.effort.as_str(),
+                "mode": created.mode.as_str(),
                 "message": "Work order created and dispatched to Processors."
-            }))
+            });
+            if let Some(expected) = &created.expected_outputs {
+                result["expected_outputs"] = json!(expected);
+            }
+            if let Some(deadline) = created.deadline {
+                result["deadline"] = json!(deadline.to_rfc3339());
+            }
+            if let Some(not_before) = created.not_before {
+                result["not_before"] = json!(not_before.to_rfc3339());
+                result["message"] = json!(format!(
+                    "Work order created and scheduled; it will be dispatched to Processors at {}.",
+                    not_before.to_rfc3339()
+                ));
+            }
+            if !warnings.is_empty() {
+                result["warnings"] = json!(warnings);
+            }
+            Ok(result)
         })
     })
 }
@@ -4,22 +4,22 @@ use serde_json::{json, Value};
 
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 use autosint_common::api::fetch::SourceInfo;
+use autosint_common::api::ErrorResponse;
 
 pub fn handler() -> ToolHandler {
     Arc::new(|_args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
-            let url = format!("{}/sources", ctx.fetch_base_url);
-
             let response = ctx
-                .http
-                .get(&url)
-                .send()
+                .services
+                .fetch
+                .get("/sources")
                 .await
                 .map_err(|e| format!("Fetch service request failed: {}", e))?;
 
             let status = response.status();
             if !status.is_success() {
-                let body = response.text().await.unwrap_or_default();
+                let body =
+                    ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
                 return Err(format!("Fetch service returned {}: {}", status, body));
             }
 
@@ -4,7 +4,8 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
-use autosint_common::api::fetch::SourceQueryResponse;
+use autosint_common::api::fetch::{SourceQueryResponse, SourceQueryResult};
+use autosint_common::api::ErrorResponse;
 
 #[derive(Deserialize)]
 struct Args {
@@ -22,8 +23,6 @@ pub fn handler() -> ToolHandler {
             let args: Args =
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
 
-            let url = format!("{}/sources/{}/query", ctx.fetch_base_url, args.source_id);
-
             let mut body = serde_json::Map::new();
             if let Some(query) = args.query {
                 body.insert("query".into(), Value::String(query));
@@ -35,16 +34,16 @@ pub fn handler() -> ToolHandler {
             }
 
             let response = ctx
-                .http
-                .post(&url)
-                .json(&body)
-                .send()
+                .services
+                .fetch
+                .post(&format!("/sources/{}/query", args.source_id), &body)
                 .await
                 .map_err(|e| format!("Fetch service request failed: {}", e))?;
 
             let status = response.status();
             if !status.is_success() {
-                let body = response.text().await.unwrap_or_default();
+                let body =
+                    ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
                 return Err(format!("Fetch service returned {}: {}", status, body));
             }
 
@@ -53,12 +52,92 @@ pub fn handler() -> ToolHandler {
                 .await
                 .map_err(|e| format!("Failed to parse source query response: {}", e))?;
 
-            Ok(json!({
-                "source_id": query_response.metadata.source_id,
-                "total_results": query_response.metadata.total_results,
-                "returned_results": query_response.metadata.returned_results,
-                "results": query_response.results,
-            }))
+            ctx.scope
+                .session_counters
+                .record_outbound(|f| f.record_search(&args.source_id));
+
+            Ok(present(query_response))
         })
     })
 }
+
+/// The tool result. Link-only results (a sitemap source's pages: a URL and no
+/// content) are listed as `candidates` to read with fetch_url, each with any
+/// `published_at` / `changefreq` from the source.
+fn present(response: SourceQueryResponse) -> Value {
+    let (results, links): (Vec<SourceQueryResult>, Vec<SourceQueryResult>) = response
+        .results
+        .into_iter()
+        .partition(|r| !r.content.is_empty() || r.url.is_none());
+
+    let mut output = json!({
+        "source_id": response.metadata.source_id,
+        "total_results": response.metadata.total_results,
+        "returned_results": response.metadata.returned_results,
+        "results": results,
+    });
+    if !links.is_empty() {
+        let candidates: Vec<Value> = links
+            .into_iter()
+            .map(|r| {
+                let mut candidate = r.extra.unwrap_or_default();
+                candidate.insert("url".into(), Value::from(r.url));
+                if let Some(title) = r.title {
+                    candidate.insert("title".into(), Value::String(title));
+                }
+                Value::Object(candidate)
+            })
+            .collect();
+        output["candidates"] = Value::Array(candidates);
+        output["hint"] = Value::String(
+            "Candidates are links only, newest first. Use fetch_url on the ones worth reading."
+                .into(),
+        );
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autosint_common::api::fetch::SourceQueryMetadata;
+
+    #[test]
+    fn test_link_only_results_become_candidates() {
+        let mut extra = serde_json::Map::new();
+        extra.insert("published_at".into(), json!("2026-02-01T00:00:00+00:00"));
+        let response = SourceQueryResponse {
+            results: vec![
+                SourceQueryResult {
+                    content: "Full item text".into(),
+                    url: Some("https://news.example/1".into()),
+                    title: Some("Item".into()),
+                    extra: None,
+                },
+                SourceQueryResult {
+                    content: String::new(),
+                    url: Some("https://mfa.example/press/new".into()),
+                    title: None,
+                    extra: Some(extra),
+                },
+            ],
+            metadata: SourceQueryMetadata {
+                source_id: "mixed".into(),
+                total_results: 7,
+                returned_results: 2,
+            },
+        };
+
+        let output = present(response);
+        assert_eq!(output["results"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            output["candidates"],
+            json!([{
+                "url": "https://mfa.example/press/new",
+                "published_at": "2026-02-01T00:00:00+00:00",
+            }])
+        );
+        assert!(output["hint"].as_str().unwrap().contains("fetch_url"));
+        assert_eq!(output["total_results"], 7);
+    }
+}
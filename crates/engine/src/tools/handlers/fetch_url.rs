@@ -4,13 +4,34 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
-use autosint_common::api::fetch::{FetchRequest, FetchResponse};
+use autosint_common::api::fetch::{
+    CacheTtl, CacheTtlSource, ExtractMode, ExtractedTable, FetchMetadata, FetchOptions,
+    FetchRequest, FetchResponse, RenderMode,
+};
+use autosint_common::api::ErrorResponse;
+use autosint_common::http_client::extract_domain;
+use autosint_common::types::{Citation, CitationVia};
 
 const MAX_CONTENT_CHARS: usize = 50_000;
+/// Tables up to this many rows are shown whole; longer ones show this many
+/// rows and are paged with `table` and `row_offset`.
+const INLINE_TABLE_ROWS: usize = 20;
+/// Rows per page when paging through one table.
+const TABLE_PAGE_ROWS: usize = 50;
+/// Widest rendered cell, in characters.
+const MAX_CELL_WIDTH: usize = 40;
 
 #[derive(Deserialize)]
 struct Args {
     url: String,
+    #[serde(default)]
+    render: Option<RenderMode>,
+    #[serde(default)]
+    wait_for: Option<String>,
+    #[serde(default)]
+    table: Option<usize>,
+    #[serde(default)]
+    row_offset: Option<usize>,
 }
 
 pub fn handler() -> ToolHandler {
@@ -19,24 +40,53 @@ pub fn handler() -> ToolHandler {
             let args: Args =
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
 
-            let fetch_url = format!("{}/fetch", ctx.fetch_base_url);
+            // Paging through a fetched document's tables re-reads the Fetch
+            // service's cache, so only new fetches spend the budget.
+            let domain = extract_domain(&args.url);
+            if let (Some(budget), None) = (&ctx.scope.fetch_budget, args.table) {
+                if let Err(message) = budget.reserve(&domain).await {
+                    ctx.scope
+                        .session_counters
+                        .record_outbound(|f| f.budget_rejections += 1);
+                    return Err(message);
+                }
+            }
 
+            // Exploratory sessions skim: readable text only, cut shorter.
+            let exploration = ctx.scope.exploration.as_ref();
+            let options = (args.render.is_some()
+                || args.wait_for.is_some()
+                || exploration.is_some())
+            .then(|| FetchOptions {
+                render: args.render,
+                wait_for: args.wait_for.clone(),
+                extract: exploration.map(|_| ExtractMode::Readable),
+                ..Default::default()
+            });
+            let max_content_chars = exploration.map_or(MAX_CONTENT_CHARS, |e| {
+                e.max_fetch_chars.min(MAX_CONTENT_CHARS)
+            });
             let request = FetchRequest {
                 url: args.url.clone(),
-                options: None,
+                options,
             };
 
             let response = ctx
-                .http
-                .post(&fetch_url)
-                .json(&request)
-                .send()
+                .services
+                .fetch
+                .fetch(&request)
                 .await
                 .map_err(|e| format!("Fetch service request failed: {}", e))?;
 
             let status = response.status();
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                ctx.scope
+                    .session_counters
+                    .record_outbound(|f| f.rate_limited += 1);
+            }
             if !status.is_success() {
-                let body = response.text().await.unwrap_or_default();
+                let body =
+                    ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
                 return Err(format!("Fetch service returned {}: {}", status, body));
             }
 
@@ -45,34 +95,328 @@ pub fn handler() -> ToolHandler {
                 .await
                 .map_err(|e| format!("Failed to parse fetch response: {}", e))?;
 
-            // Truncate content to keep within LLM context limits.
-            // Use char_indices to avoid panicking on multi-byte UTF-8 boundaries.
-            let content = if fetch_response.content.len() > MAX_CONTENT_CHARS {
-                let truncate_at = fetch_response
-                    .content
-                    .char_indices()
-                    .take_while(|(i, _)| *i <= MAX_CONTENT_CHARS)
-                    .last()
-                    .map(|(i, _)| i)
-                    .unwrap_or(0);
-                let truncated = &fetch_response.content[..truncate_at];
-                format!(
-                    "{}...\n[Content truncated: {} bytes total, showing first {}]",
-                    truncated,
-                    fetch_response.content.len(),
-                    truncate_at
-                )
-            } else {
-                fetch_response.content
-            };
+            // Page through one table of a document fetched earlier (served
+            // from the Fetch service's cache).
+            if let Some(index) = args.table {
+                let table = fetch_response.tables.get(index).ok_or_else(|| {
+                    format!(
+                        "Table {} not found: the document has {} tables",
+                        index,
+                        fetch_response.tables.len()
+                    )
+                })?;
+                let offset = args.row_offset.unwrap_or(0);
+                let end = (offset + TABLE_PAGE_ROWS).min(table.rows.len());
+                let mut result = json!({
+                    "url": fetch_response.metadata.url,
+                    "table": render_table(table, index, offset..end),
+                });
+                if end < table.rows.len() {
+                    result["next_row_offset"] = json!(end);
+                }
+                return Ok(result);
+            }
+
+            let cached = fetch_response.metadata.cached;
+            let bytes = fetch_response.content.len() as u64;
+            ctx.scope
+                .session_counters
+                .record_outbound(|f| f.record_fetch(&domain, bytes, cached));
 
-            Ok(json!({
+            let tables: Vec<String> = fetch_response
+                .tables
+                .iter()
+                .enumerate()
+                .map(|(index, table)| {
+                    render_table(table, index, 0..table.rows.len().min(INLINE_TABLE_ROWS))
+                })
+                .collect();
+
+            let content = truncate_content(fetch_response.content, max_content_chars);
+
+            let citation = citation(&fetch_response.metadata, chrono::Utc::now());
+            let mut result = json!({
                 "url": fetch_response.metadata.url,
+                "citation": citation,
                 "status_code": fetch_response.metadata.status_code,
                 "content_type": fetch_response.metadata.content_type,
                 "cached": fetch_response.metadata.cached,
+                "rendered": fetch_response.metadata.rendered,
+                "authenticated": fetch_response.metadata.authenticated,
                 "content": content,
-            }))
+                "tables": tables,
+            });
+            if let Some(language) = fetch_response.metadata.detected_language {
+                result["detected_language"] = json!(language);
+            }
+            if let (true, Some(ttl)) = (cached, fetch_response.metadata.cache_ttl) {
+                result["freshness"] = json!(freshness(&ttl));
+            }
+            Ok(result)
         })
     })
 }
+
+/// `content` cut to at most `max_chars` bytes, on a character boundary, with
+/// a note of how much was left out.
+fn truncate_content(content: String, max_chars: usize) -> String {
+    if content.len() <= max_chars {
+        return content;
+    }
+    // Use char_indices to avoid panicking on multi-byte UTF-8 boundaries.
+    let truncate_at = content
+        .char_indices()
+        .take_while(|(i, _)| *i <= max_chars)
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+    format!(
+        "{}...\n[Content truncated: {} bytes total, showing first {}]",
+        &content[..truncate_at],
+        content.len(),
+        truncate_at
+    )
+}
+
+/// The ready-made citation handed to the Processor for claims from this
+/// fetch: a cached copy is cited as of its original fetch.
+fn citation(metadata: &FetchMetadata, now: chrono::DateTime<chrono::Utc>) -> Citation {
+    let mut citation = Citation::from_url(&metadata.url);
+    citation.title = metadata.title.clone();
+    citation.via = if metadata.cached {
+        CitationVia::Cache
+    } else {
+        CitationVia::Direct
+    };
+    citation.accessed_at = Some(metadata.fetched_at.unwrap_or(now));
+    citation.normalized()
+}
+
+/// How stale a cached copy may be, and on whose word.
+fn freshness(ttl: &CacheTtl) -> String {
+    let age = match ttl.seconds {
+        s if s >= 86_400 && s % 86_400 == 0 => format!("{} days", s / 86_400),
+        s if s >= 3600 && s % 3600 == 0 => format!("{} hours", s / 3600),
+        s if s >= 60 && s % 60 == 0 => format!("{} minutes", s / 60),
+        s => format!("{} seconds", s),
+    };
+    let why = match ttl.source {
+        CacheTtlSource::Origin => "the site's caching headers",
+        CacheTtlSource::Default => "the Fetch service default",
+        CacheTtlSource::SourceOverride => "the configured TTL for this source",
+    };
+    format!("Cached copy, at most {} old ({})", age, why)
+}
+
+/// Render `rows` of a table as an aligned markdown table. The first column is
+/// the row index, for citing rows; a note follows when rows are left out.
+fn render_table(table: &ExtractedTable, index: usize, rows: std::ops::Range<usize>) -> String {
+    let shown = &table.rows[rows.clone()];
+    let columns = table
+        .headers
+        .len()
+        .max(table.rows.first().map_or(0, Vec::len));
+
+    let cell = |text: &str| -> String {
+        let text = text.replace('|', "\\|");
+        if text.chars().count() > MAX_CELL_WIDTH {
+            let cut: String = text.chars().take(MAX_CELL_WIDTH - 1).collect();
+            format!("{}…", cut)
+        } else {
+            text
+        }
+    };
+    let header: Vec<String> = std::iter::once("#".to_string())
+        .chain((0..columns).map(|c| cell(table.headers.get(c).map_or("", String::as_str))))
+        .collect();
+    let body: Vec<Vec<String>> = shown
+        .iter()
+        .zip(rows.clone())
+        .map(|(row, i)| {
+            std::iter::once(i.to_string())
+                .chain((0..columns).map(|c| cell(row.get(c).map_or("", String::as_str))))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..=columns)
+        .map(|c| {
+            body.iter()
+                .chain(std::iter::once(&header))
+                .map(|r| r[c].chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+    let line = |cells: &[String]| -> String {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{:<width$}", c, width = w))
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut out = format!("Table {}", index);
+    if let Some(caption) = &table.caption {
+        out.push_str(&format!(": {}", caption));
+    }
+    out.push_str(&format!(
+        " ({} rows, {} columns)",
+        table.rows.len() + table.omitted_rows,
+        columns
+    ));
+    if table.best_effort {
+        out.push_str(" [columns inferred from text layout; cells may be misaligned]");
+    }
+    out.push_str("\n\n");
+    out.push_str(&line(&header));
+    out.push('\n');
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    out.push_str(&format!("|-{}-|", rule.join("-|-")));
+    for row in &body {
+        out.push('\n');
+        out.push_str(&line(row));
+    }
+
+    if rows.start > 0 || rows.end < table.rows.len() {
+        out.push_str(&format!(
+            "\n[Rows {}-{} of {} shown.",
+            rows.start,
+            rows.end.saturating_sub(1),
+            table.rows.len()
+        ));
+        if rows.end < table.rows.len() {
+            out.push_str(&format!(
+                " Call fetch_url with table={} and row_offset={} for more.",
+                index, rows.end
+            ));
+        }
+        out.push(']');
+    }
+    if table.omitted_rows > 0 {
+        out.push_str(&format!(
+            "\n[{} further rows were too many to fetch.]",
+            table.omitted_rows
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: usize) -> ExtractedTable {
+        ExtractedTable {
+            caption: Some("Annex I".into()),
+            headers: vec!["Name".into(), "IMO".into()],
+            rows: (0..rows)
+                .map(|i| vec![format!("Vessel {}", i), format!("91{:05}", i)])
+                .collect(),
+            omitted_rows: 0,
+            best_effort: false,
+        }
+    }
+
+    #[test]
+    fn test_truncate_content() {
+        assert_eq!(truncate_content("short".into(), 10), "short");
+        let truncated = truncate_content("Crius, Crius, Crius".into(), 5);
+        assert!(
+            truncated.starts_with("Crius...\n[Content truncated: 19 bytes total"),
+            "{}",
+            truncated
+        );
+        // Never cuts inside a character.
+        let truncated = truncate_content("ééééé".into(), 3);
+        assert!(truncated.starts_with("é..."), "{}", truncated);
+    }
+
+    #[test]
+    fn test_renders_small_table_aligned() {
+        let mut small = table(2);
+        small.rows[1][0] = "Northwind | Star".into();
+
+        assert_eq!(
+            render_table(&small, 0, 0..2),
+            "Table 0: Annex I (2 rows, 2 columns)\n\n\
+             | #   | Name              | IMO     |\n\
+             |-----|-------------------|---------|\n\
+             | 0   | Vessel 0          | 9100000 |\n\
+             | 1   | Northwind \\| Star | 9100001 |"
+        );
+    }
+
+    #[test]
+    fn test_renders_page_of_large_table() {
+        let mut large = table(30);
+        large.best_effort = true;
+        large.omitted_rows = 5;
+
+        let rendered = render_table(&large, 2, 20..30);
+        assert!(rendered.starts_with(
+            "Table 2: Annex I (35 rows, 2 columns) [columns inferred from text layout"
+        ));
+        assert!(rendered.contains("| 20  | Vessel 20 | 9100020 |"));
+        assert!(!rendered.contains("Vessel 19"));
+        assert!(rendered.contains("[Rows 20-29 of 30 shown.]"));
+        assert!(rendered.ends_with("[5 further rows were too many to fetch.]"));
+
+        let first = render_table(&large, 2, 0..INLINE_TABLE_ROWS);
+        assert!(first.contains("Call fetch_url with table=2 and row_offset=20 for more."));
+    }
+
+    #[test]
+    fn test_citation_from_fetch_metadata() {
+        let fetched_at = "2026-03-01T08:00:00Z".parse().unwrap();
+        let now = "2026-03-02T10:00:00Z".parse().unwrap();
+        let mut metadata = FetchMetadata {
+            status_code: 200,
+            content_type: Some("text/html".into()),
+            url: "https://news.example/port?utm_source=rss#para-4".into(),
+            cached: true,
+            rendered: false,
+            detected_language: None,
+            authenticated: false,
+            cache_ttl: None,
+            title: Some("Port closes".into()),
+            fetched_at: Some(fetched_at),
+        };
+
+        let cached = citation(&metadata, now);
+        assert_eq!(cached.url, metadata.url);
+        assert_eq!(
+            cached.normalized_url.as_deref(),
+            Some("https://news.example/port")
+        );
+        assert_eq!(cached.title.as_deref(), Some("Port closes"));
+        assert_eq!(cached.locator.as_deref(), Some("#para-4"));
+        assert_eq!(cached.accessed_at, Some(fetched_at));
+        assert_eq!(cached.via, CitationVia::Cache);
+
+        metadata.cached = false;
+        metadata.fetched_at = None;
+        let direct = citation(&metadata, now);
+        assert_eq!(direct.via, CitationVia::Direct);
+        assert_eq!(direct.accessed_at, Some(now));
+    }
+
+    #[test]
+    fn test_freshness_names_ttl_and_source() {
+        let ttl = |seconds, source| freshness(&CacheTtl { seconds, source });
+        assert_eq!(
+            ttl(600, CacheTtlSource::Origin),
+            "Cached copy, at most 10 minutes old (the site's caching headers)"
+        );
+        assert_eq!(
+            ttl(1_209_600, CacheTtlSource::SourceOverride),
+            "Cached copy, at most 14 days old (the configured TTL for this source)"
+        );
+        assert_eq!(
+            ttl(90, CacheTtlSource::Default),
+            "Cached copy, at most 90 seconds old (the Fetch service default)"
+        );
+    }
+}
@@ -7,6 +7,8 @@ use autosint_common::ids::AssessmentId;
 
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
+use super::search_assessments::{insert_feedback, insert_review_flag};
+
 #[derive(Deserialize)]
 struct Args {
     assessment_id: String,
@@ -24,16 +26,25 @@ pub fn handler() -> ToolHandler {
                 .map(AssessmentId::from_uuid)
                 .map_err(|e| format!("Invalid assessment_id: {}", e))?;
 
-            let store = ctx.store.as_ref().ok_or_else(|| {
+            let records = ctx.services.records.as_ref().ok_or_else(|| {
                 "Assessment retrieval not available (store not configured)".to_string()
             })?;
 
-            let assessment = store
-                .get_assessment(assessment_id)
+            let assessment = ctx
+                .reads()
+                .get_assessment(records.as_ref(), assessment_id)
                 .await
                 .map_err(|e| format!("Failed to get assessment: {}", e))?;
 
-            Ok(json!({
+            let feedback = records
+                .assessment_feedback_summaries(&[assessment_id])
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "Failed to load assessment feedback");
+                    Default::default()
+                });
+
+            let mut result = json!({
                 "id": assessment.id.to_string(),
                 "investigation_id": assessment.investigation_id.to_string(),
                 "content": assessment.content,
@@ -41,7 +52,44 @@ pub fn handler() -> ToolHandler {
                 "entity_refs": assessment.entity_refs.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
                 "claim_refs": assessment.claim_refs.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
                 "created_at": assessment.created_at.to_rfc3339(),
-            }))
+            });
+            insert_feedback(&mut result, feedback.get(&assessment_id));
+            insert_review_flag(&mut result, &assessment);
+            Ok(result)
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{Assessment, Confidence, FeedbackRating, FeedbackSummary};
+    use autosint_common::InvestigationId;
+
+    use crate::tools::services::stub::StubRecords;
+    use crate::tools::SessionScope;
+
+    #[tokio::test]
+    async fn test_caveat_on_disputed_assessment() {
+        let assessment = Assessment::new(
+            InvestigationId::new(),
+            json!({ "summary": "The Crius is owned by Oceanic Shipping Ltd." }),
+            Confidence::Moderate,
+        );
+        let mut summary = FeedbackSummary::default();
+        summary.add(FeedbackRating::Inaccurate, 1);
+        let records = StubRecords {
+            assessments: vec![assessment.clone()],
+            feedback: [(assessment.id, summary)].into_iter().collect(),
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(Some(Arc::new(records)), SessionScope::default()).await;
+
+        let result = handler()(json!({ "assessment_id": assessment.id.to_string() }), ctx)
+            .await
+            .unwrap();
+        assert_eq!(result["feedback"]["inaccurate"], 1);
+        assert!(result["caveat"].as_str().unwrap().contains("1 inaccurate"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::ids::ClaimId;
+
+use crate::graph::GraphError;
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use crate::tools::services::InvestigationRecords;
+use crate::tools::snapshot::SnapshotReads;
+use crate::tools::truncation::insert_claim_references;
+
+#[derive(Deserialize)]
+struct Args {
+    claim_id: String,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let claim_id = args
+                .claim_id
+                .parse::<uuid::Uuid>()
+                .map(ClaimId::from_uuid)
+                .map_err(|e| format!("Invalid claim_id: {}", e))?;
+
+            let records =
+                ctx.services.records.as_ref().ok_or_else(|| {
+                    "Claim detail not available (store not configured)".to_string()
+                })?;
+
+            let reads = ctx.reads();
+            let claim = match reads.get_claim(claim_id).await {
+                Ok(claim) => claim,
+                Err(GraphError::NotFound(_)) => return Err(not_found(&ctx, claim_id).await),
+                Err(e) => return Err(format!("Failed to get claim: {}", e)),
+            };
+
+            let mut result = json!({
+                "id": claim.id.to_string(),
+                "content": claim.content,
+                "source_entity_id": claim.source_entity_id.to_string(),
+                "published_timestamp": claim.published_timestamp.to_rfc3339(),
+                "attribution_depth": format!("{:?}", claim.attribution_depth).to_lowercase(),
+                "information_type": format!("{:?}", claim.information_type).to_lowercase(),
+                "raw_source_link": claim.raw_source_link,
+                "citation": claim.citation,
+                "language": claim.language,
+                "region": claim.region,
+                "source_trust_level": claim.source_trust_level,
+                "disputed_count": claim.disputed_count,
+            });
+            if !claim.merged_sources.is_empty() {
+                result["merged_sources"] = json!(claim.merged_sources);
+            }
+            if let Some(original) = &claim.original_content {
+                result["original_content"] = json!(original);
+                result["original_language"] = json!(claim.original_language);
+            }
+            insert_claim_references(
+                &mut result,
+                &claim.referenced_entity_ids,
+                &ctx.services.tool_result_limits,
+            );
+            let citing = citing_assessments(&reads, records.as_ref(), claim_id)
+                .await
+                .map_err(|e| format!("Failed to look up citing assessments: {}", e))?;
+            result["cited_in_assessments"] = json!(citing);
+            Ok(result)
+        })
+    })
+}
+
+/// The error for a claim not in the graph, naming the claim it was merged
+/// into if it was merged away.
+async fn not_found(ctx: &ToolHandlerContext, claim_id: ClaimId) -> String {
+    let merged_into = match &ctx.services.store {
+        Some(store) => store
+            .claim_merge_target(claim_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(claim_id = %claim_id, error = %e, "Failed to look up claim merge");
+                None
+            }),
+        None => None,
+    };
+    match merged_into {
+        Some(target) => format!(
+            "Claim {} was merged into claim {} as a duplicate; use that ID",
+            claim_id, target
+        ),
+        None => format!("Claim {} not found", claim_id),
+    }
+}
+
+/// The assessments citing `claim_id`, newest first, each summarized.
+async fn citing_assessments(
+    reads: &SnapshotReads<'_>,
+    records: &dyn InvestigationRecords,
+    claim_id: ClaimId,
+) -> Result<Vec<Value>, crate::store::StoreError> {
+    let assessments = reads
+        .get_assessments_citing_claim(records, claim_id)
+        .await?;
+    Ok(assessments
+        .iter()
+        .map(|assessment| {
+            json!({
+                "id": assessment.id.to_string(),
+                "investigation_id": assessment.investigation_id.to_string(),
+                "confidence": assessment.confidence.as_db_str(),
+                "summary": assessment
+                    .content
+                    .get("summary")
+                    .and_then(Value::as_str)
+                    .unwrap_or("[no summary]"),
+                "created_at": assessment.created_at.to_rfc3339(),
+                "needs_review": assessment.needs_review,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{Assessment, Confidence};
+    use autosint_common::InvestigationId;
+
+    use crate::tools::services::stub::StubRecords;
+    use crate::tools::SessionScope;
+
+    #[tokio::test]
+    async fn test_citing_assessments_newest_first() {
+        let claim_id = ClaimId::new();
+        let assessment = |summary: &str, age_days: i64| {
+            let mut assessment = Assessment::new(
+                InvestigationId::new(),
+                json!({
+                    "summary": summary,
+                    "citations": [{"marker": "[1]", "claim_id": claim_id.to_string()}],
+                }),
+                Confidence::Moderate,
+            );
+            assessment.created_at -= chrono::Duration::days(age_days);
+            assessment
+        };
+        let mut flagged = assessment("The Crius changed owners in March.", 1);
+        flagged.needs_review = true;
+        let records = StubRecords {
+            assessments: vec![
+                assessment("The Crius was renamed in 2019.", 30),
+                flagged,
+                Assessment::new(InvestigationId::new(), json!({}), Confidence::Low),
+            ],
+            ..Default::default()
+        };
+
+        let ctx = ToolHandlerContext::stub(None, SessionScope::default()).await;
+        let citing = citing_assessments(&ctx.reads(), &records, claim_id)
+            .await
+            .unwrap();
+        assert_eq!(citing.len(), 2);
+        assert_eq!(citing[0]["summary"], "The Crius changed owners in March.");
+        assert_eq!(citing[0]["needs_review"], true);
+        assert_eq!(citing[1]["needs_review"], false);
+
+        // A snapshot session only sees assessments from before its cutoff.
+        let snapshot = ctx.scoped(|scope| {
+            scope.as_of = Some(chrono::Utc::now() - chrono::Duration::days(7));
+        });
+        let citing = citing_assessments(&snapshot.reads(), &records, claim_id)
+            .await
+            .unwrap();
+        assert_eq!(citing.len(), 1);
+        assert_eq!(citing[0]["summary"], "The Crius was renamed in 2019.");
+    }
+}
@@ -5,7 +5,8 @@ use serde_json::{json, Value};
 
 use autosint_common::EntityId;
 
-use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use crate::tools::format::{date, pairs, text, Formatted, FormattedHandler};
+use crate::tools::registry::ToolHandlerContext;
 use crate::tools::truncation::truncate_entity_detail;
 
 #[derive(Deserialize)]
@@ -13,7 +14,7 @@ struct Args {
     entity_id: String,
 }
 
-pub fn handler() -> ToolHandler {
+pub fn handler() -> FormattedHandler {
     Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
             let args: Args =
@@ -26,7 +27,7 @@ pub fn handler() -> ToolHandler {
                 .map_err(|e| format!("Invalid entity_id: {}", e))?;
 
             let entity = ctx
-                .graph
+                .reads()
                 .get_entity(entity_id)
                 .await
                 .map_err(|e| format!("Failed to get entity: {}", e))?;
@@ -43,9 +44,48 @@ pub fn handler() -> ToolHandler {
                 "last_updated": entity.last_updated.to_rfc3339(),
                 "properties": properties,
             });
+            if !entity.identifiers.is_empty() {
+                result["identifiers"] = json!(entity.identifiers);
+            }
 
-            truncate_entity_detail(&mut result, &ctx.tool_result_limits);
-            Ok(result)
+            truncate_entity_detail(&mut result, &ctx.services.tool_result_limits);
+            Ok(Formatted::new(result, summarize))
         })
     })
 }
+
+/// The entity as a short profile: name line, aliases, summary, then its
+/// properties and identifiers as `key: value` pairs.
+fn summarize(entity: &Value) -> String {
+    let stub = if entity["is_stub"] == true {
+        ", stub"
+    } else {
+        ""
+    };
+    let mut out = format!(
+        "{} ({}{}) [{}], last updated {}",
+        text(entity, "canonical_name"),
+        text(entity, "kind"),
+        stub,
+        text(entity, "id"),
+        date(entity, "last_updated")
+    );
+    let aliases: Vec<&str> = entity["aliases"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    if !aliases.is_empty() {
+        out.push_str(&format!("\nAliases: {}", aliases.join(", ")));
+    }
+    let summary = text(entity, "summary");
+    if !summary.is_empty() {
+        out.push_str(&format!("\n{}", summary));
+    }
+    for (label, key) in [("Properties", "properties"), ("Identifiers", "identifiers")] {
+        let fields = pairs(&entity[key]);
+        if !fields.is_empty() {
+            out.push_str(&format!("\n{}: {}", label, fields));
+        }
+    }
+    out
+}
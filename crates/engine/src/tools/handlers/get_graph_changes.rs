@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::config::ToolResultLimits;
+
+use crate::graph::{ChangeScope, GraphChanges};
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use crate::tools::truncation::{insert_claim_references, search_page_size, truncate_text};
+
+#[derive(Deserialize)]
+struct Args {
+    #[serde(default)]
+    scope: Option<String>,
+    #[serde(default)]
+    limit: Option<u32>,
+    #[serde(default)]
+    since: Option<String>,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let store =
+                ctx.services.store.as_ref().ok_or_else(|| {
+                    "Graph changes not available (store not configured)".to_string()
+                })?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "Graph changes not available (no investigation context)".to_string()
+            })?;
+
+            let investigation = store
+                .get_investigation(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to get investigation: {}", e))?;
+
+            let scope_name = args.scope.as_deref().unwrap_or("investigation");
+            let scope = match scope_name {
+                "investigation" => ChangeScope::Investigation(investigation_id),
+                "focus" if investigation.focus_entities.is_empty() => {
+                    return Err(
+                        "This investigation has no focus entities; use scope 'investigation' or 'all'"
+                            .to_string(),
+                    );
+                }
+                "focus" => ChangeScope::Focus(investigation.focus_entities.clone()),
+                "all" => ChangeScope::All,
+                other => return Err(format!("Invalid scope: '{}'", other)),
+            };
+
+            // Default to the start of the previous Analyst session, or the
+            // investigation's creation before a second session has run.
+            let since = match args.since {
+                Some(s) => DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|e| format!("Invalid since: {}", e))?,
+                None => store
+                    .previous_analyst_session_start(investigation_id)
+                    .await
+                    .map_err(|e| format!("Failed to read previous Analyst session: {}", e))?
+                    .unwrap_or(investigation.created_at),
+            };
+
+            let limit = search_page_size(args.limit, &ctx.services.tool_result_limits);
+            let changes = ctx
+                .services
+                .graph
+                .changes_since(since, &scope, limit as usize)
+                .await
+                .map_err(|e| format!("Graph change query failed: {}", e))?;
+
+            Ok(format_changes(
+                &changes,
+                since,
+                scope_name,
+                &ctx.services.tool_result_limits,
+            ))
+        })
+    })
+}
+
+/// Render changes as counts plus compact rows. Claim content is cut to the
+/// claim preview length; use search_claims or get_entity for full records.
+fn format_changes(
+    changes: &GraphChanges,
+    since: DateTime<Utc>,
+    scope: &str,
+    limits: &ToolResultLimits,
+) -> Value {
+    let max_chars = limits.max_claim_preview_chars as usize;
+
+    let entities: Vec<Value> = changes
+        .entities
+        .iter()
+        .map(|changed| {
+            let entity = &changed.entity;
+            json!({
+                "entity_id": entity.id.to_string(),
+                "name": entity.canonical_name,
+                "kind": entity.kind,
+                "change": if changed.created { "created" } else { "updated" },
+                "last_updated": entity.last_updated.to_rfc3339(),
+            })
+        })
+        .collect();
+
+    let claims: Vec<Value> = changes
+        .claims
+        .iter()
+        .map(|claim| {
+            let mut row = json!({
+                "claim_id": claim.id.to_string(),
+                "content": truncate_text(&claim.content, max_chars),
+                "source_entity_id": claim.source_entity_id.to_string(),
+                "ingested_timestamp": claim.ingested_timestamp.to_rfc3339(),
+            });
+            insert_claim_references(&mut row, &claim.referenced_entity_ids, limits);
+            row
+        })
+        .collect();
+
+    let relationships: Vec<Value> = changes
+        .relationships
+        .iter()
+        .map(|rel| {
+            json!({
+                "relationship_id": rel.id.to_string(),
+                "source_entity_id": rel.source_entity_id.to_string(),
+                "target_entity_id": rel.target_entity_id.to_string(),
+                "description": rel.description,
+            })
+        })
+        .collect();
+
+    let counts = &changes.counts;
+    json!({
+        "since": since.to_rfc3339(),
+        "scope": scope,
+        "counts": {
+            "entities_created": counts.entities_created,
+            "entities_updated": counts.entities_updated,
+            "claims": counts.claims,
+            "relationships": counts.relationships,
+        },
+        "entities": entities,
+        "claims": claims,
+        "relationships": relationships,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType};
+
+    use crate::graph::{ChangeCounts, ChangedEntity};
+
+    fn limits() -> ToolResultLimits {
+        ToolResultLimits {
+            max_search_results: 3,
+            max_entity_detail_chars: 10000,
+            max_claim_preview_chars: 20,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+            max_claim_references_shown: 10,
+        }
+    }
+
+    #[test]
+    fn test_formats_counts_and_rows() {
+        let entity = Entity::new("Acme Corp".into(), "organization".into());
+        let claim = Claim::new(
+            "Acme Corp opened a new office in Rotterdam last week.".into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            entity.id,
+        );
+        let changes = GraphChanges {
+            counts: ChangeCounts {
+                entities_created: 1,
+                entities_updated: 4,
+                claims: 7,
+                relationships: 0,
+            },
+            entities: vec![ChangedEntity {
+                entity: entity.clone(),
+                created: true,
+            }],
+            claims: vec![claim],
+            relationships: Vec::new(),
+        };
+
+        let output = format_changes(&changes, Utc::now(), "focus", &limits());
+        assert_eq!(output["scope"], "focus");
+        assert_eq!(output["counts"]["entities_updated"], 4);
+        assert_eq!(output["counts"]["claims"], 7);
+        assert_eq!(output["entities"][0]["name"], "Acme Corp");
+        assert_eq!(output["entities"][0]["change"], "created");
+        assert_eq!(
+            output["claims"][0]["content"],
+            "Acme Corp opened a n...[truncated, 53 chars total]"
+        );
+        assert_eq!(
+            output["claims"][0]["source_entity_id"],
+            entity.id.to_string()
+        );
+        assert_eq!(output["relationships"].as_array().unwrap().len(), 0);
+    }
+}
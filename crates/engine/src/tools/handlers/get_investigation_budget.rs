@@ -0,0 +1,173 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::llm::session::LiveSessionStats;
+use crate::tools::registry::{SessionCounters, ToolHandler, ToolHandlerContext};
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|_args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            Ok(budget_report(
+                &ctx.scope.live_stats,
+                &ctx.scope.session_counters,
+                ctx.scope.max_turns,
+                ctx.scope.live_stats.elapsed(),
+                ctx.scope.max_session_duration,
+                ctx.scope.investigation_cycle,
+                ctx.scope.max_cycles_per_investigation,
+                ctx.scope.max_work_orders_per_cycle,
+            ))
+        })
+    })
+}
+
+/// Build the budget summary from the session's live counters and configured limits.
+#[allow(clippy::too_many_arguments)]
+fn budget_report(
+    live: &LiveSessionStats,
+    counters: &SessionCounters,
+    max_turns: u32,
+    elapsed: Duration,
+    max_duration: Option<Duration>,
+    cycle: Option<i32>,
+    max_cycles: Option<u32>,
+    max_work_orders: Option<u32>,
+) -> Value {
+    let turns = live.turns.load(Ordering::Relaxed);
+    let input_tokens = live.total_input_tokens.load(Ordering::Relaxed);
+    let output_tokens = live.total_output_tokens.load(Ordering::Relaxed);
+    let work_orders_created = counters.work_orders_created.load(Ordering::Relaxed);
+
+    let cycle = cycle.unwrap_or(0).max(0) as u32;
+    // The Orchestrator forces a final assessment once cycle_count reaches the limit.
+    let cycles = max_cycles.map(|max| {
+        json!({
+            "current": cycle,
+            "max": max,
+            "remaining": max.saturating_sub(cycle),
+            "final_cycle": cycle >= max,
+        })
+    });
+
+    let work_orders = json!({
+        "created_this_cycle": work_orders_created,
+        "max_per_cycle": max_work_orders,
+        "remaining_this_cycle": max_work_orders.map(|max| max.saturating_sub(work_orders_created)),
+    });
+
+    // The session stops at the first turn boundary past the limit.
+    let time = json!({
+        "elapsed_seconds": elapsed.as_secs(),
+        "max_seconds": max_duration.map(|max| max.as_secs()),
+        "remaining_seconds": max_duration.map(|max| max.saturating_sub(elapsed).as_secs()),
+    });
+
+    json!({
+        "cycles": cycles,
+        "turns": {
+            "used": turns,
+            "max": max_turns,
+            "remaining": max_turns.saturating_sub(turns),
+        },
+        "time": time,
+        "tool_calls": live.tool_calls.load(Ordering::Relaxed),
+        "tokens": {
+            "input": input_tokens,
+            "output": output_tokens,
+            "total": input_tokens + output_tokens,
+        },
+        "work_orders": work_orders,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live(turns: u32, tool_calls: u32, input: u64, output: u64) -> LiveSessionStats {
+        let stats = LiveSessionStats::default();
+        stats.turns.store(turns, Ordering::Relaxed);
+        stats.tool_calls.store(tool_calls, Ordering::Relaxed);
+        stats.total_input_tokens.store(input, Ordering::Relaxed);
+        stats.total_output_tokens.store(output, Ordering::Relaxed);
+        stats
+    }
+
+    #[test]
+    fn test_report_reflects_counters() {
+        let counters = SessionCounters::default();
+        counters.work_orders_created.store(3, Ordering::Relaxed);
+
+        let report = budget_report(
+            &live(12, 20, 90_000, 4_000),
+            &counters,
+            50,
+            Duration::from_secs(300),
+            Some(Duration::from_secs(1800)),
+            Some(2),
+            Some(10),
+            Some(8),
+        );
+
+        assert_eq!(report["cycles"]["current"], 2);
+        assert_eq!(report["cycles"]["remaining"], 8);
+        assert_eq!(report["cycles"]["final_cycle"], false);
+        assert_eq!(report["turns"]["used"], 12);
+        assert_eq!(report["turns"]["remaining"], 38);
+        assert_eq!(report["time"]["elapsed_seconds"], 300);
+        assert_eq!(report["time"]["remaining_seconds"], 1500);
+        assert_eq!(report["tool_calls"], 20);
+        assert_eq!(report["tokens"]["total"], 94_000);
+        assert_eq!(report["work_orders"]["created_this_cycle"], 3);
+        assert_eq!(report["work_orders"]["remaining_this_cycle"], 5);
+    }
+
+    #[test]
+    fn test_report_in_forced_final_cycle() {
+        let counters = SessionCounters::default();
+        let report = budget_report(
+            &live(1, 1, 500, 20),
+            &counters,
+            50,
+            Duration::ZERO,
+            None,
+            Some(10),
+            Some(10),
+            Some(8),
+        );
+
+        assert_eq!(report["cycles"]["current"], 10);
+        assert_eq!(report["cycles"]["remaining"], 0);
+        assert_eq!(report["cycles"]["final_cycle"], true);
+        assert_eq!(report["turns"]["used"], 1);
+        assert_eq!(report["turns"]["remaining"], 49);
+        assert_eq!(report["tokens"]["input"], 500);
+        assert!(report["time"]["remaining_seconds"].is_null());
+        assert_eq!(report["work_orders"]["remaining_this_cycle"], 8);
+    }
+
+    #[test]
+    fn test_report_saturates_past_limits() {
+        let counters = SessionCounters::default();
+        counters.work_orders_created.store(9, Ordering::Relaxed);
+
+        let report = budget_report(
+            &live(60, 0, 0, 0),
+            &counters,
+            50,
+            Duration::from_secs(2000),
+            Some(Duration::from_secs(1800)),
+            Some(12),
+            Some(10),
+            Some(8),
+        );
+
+        assert_eq!(report["cycles"]["remaining"], 0);
+        assert_eq!(report["turns"]["remaining"], 0);
+        assert_eq!(report["time"]["remaining_seconds"], 0);
+        assert_eq!(report["work_orders"]["remaining_this_cycle"], 0);
+    }
+}
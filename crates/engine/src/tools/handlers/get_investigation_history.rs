@@ -8,14 +8,14 @@ use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 pub fn handler() -> ToolHandler {
     Arc::new(|_args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
-            let store = ctx.store.as_ref().ok_or_else(|| {
+            let records = ctx.services.records.as_ref().ok_or_else(|| {
                 "Investigation history not available (store not configured)".to_string()
             })?;
-            let investigation_id = ctx.investigation_id.ok_or_else(|| {
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
                 "Investigation history not available (no investigation context)".to_string()
             })?;
 
-            let work_orders = store
+            let work_orders = records
                 .get_work_orders_by_investigation(investigation_id)
                 .await
                 .map_err(|e| format!("Failed to get investigation history: {}", e))?;
@@ -23,13 +23,17 @@ pub fn handler() -> ToolHandler {
             // Group work orders by cycle.
             let mut cycles: BTreeMap<i32, Vec<Value>> = BTreeMap::new();
             for wo in &work_orders {
-                let entry = json!({
+                let mut entry = json!({
                     "work_order_id": wo.id.to_string(),
                     "objective": wo.objective,
                     "status": wo.status.as_db_str(),
                     "priority": format!("{:?}", wo.priority).to_lowercase(),
                     "claims_produced_count": wo.claims_produced_count,
                 });
+                if wo.contract_unmet {
+                    entry["contract_unmet"] = json!(true);
+                    entry["unmet_outputs"] = json!(wo.unmet_outputs());
+                }
                 cycles.entry(wo.cycle).or_default().push(entry);
             }
 
@@ -52,3 +56,81 @@ pub fn handler() -> ToolHandler {
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{WorkOrder, WorkOrderPriority};
+    use autosint_common::InvestigationId;
+
+    use crate::tools::services::stub::StubRecords;
+    use crate::tools::SessionScope;
+
+    fn work_order(investigation_id: InvestigationId, objective: &str, cycle: i32) -> WorkOrder {
+        let mut wo = WorkOrder::new(
+            investigation_id,
+            objective.into(),
+            WorkOrderPriority::Normal,
+        );
+        wo.cycle = cycle;
+        wo
+    }
+
+    #[tokio::test]
+    async fn test_groups_work_orders_by_cycle() {
+        let id = InvestigationId::new();
+        let mut owner = work_order(id, "Find the Crius's registered owner", 2);
+        owner.contract_unmet = true;
+        owner.summary = Some(json!({
+            "contract": {
+                "met": false,
+                "unmet": [{"criterion": "require_entities", "missing": ["Oceanic Shipping Ltd"]}],
+            },
+        }));
+        let records = StubRecords {
+            work_orders: vec![
+                owner,
+                work_order(id, "Find the Crius's flag history", 1),
+                work_order(id, "Find the Crius's port calls", 2),
+                work_order(InvestigationId::new(), "Another investigation's order", 1),
+            ],
+            ..Default::default()
+        };
+        let scope = SessionScope {
+            investigation_id: Some(id),
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(Some(Arc::new(records)), scope).await;
+
+        let result = handler()(json!({}), ctx).await.unwrap();
+        assert_eq!(result["total_work_orders"], 3);
+        let cycles = result["cycles"].as_array().unwrap();
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0]["cycle"], 1);
+        assert_eq!(cycles[0]["count"], 1);
+        assert_eq!(cycles[1]["cycle"], 2);
+        assert_eq!(
+            cycles[1]["work_orders"][1]["objective"],
+            "Find the Crius's port calls"
+        );
+        let owner = &cycles[1]["work_orders"][0];
+        assert_eq!(owner["contract_unmet"], true);
+        assert_eq!(
+            owner["unmet_outputs"][0]["missing"][0],
+            "Oceanic Shipping Ltd"
+        );
+        assert!(cycles[0]["work_orders"][0].get("contract_unmet").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_requires_store() {
+        let scope = SessionScope {
+            investigation_id: Some(InvestigationId::new()),
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(None, scope).await;
+        let err = handler()(json!({}), ctx).await.unwrap_err();
+        assert!(err.contains("store not configured"), "{}", err);
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::ids::SourceDocumentId;
+use autosint_common::types::{Citation, SourceDocument};
+
+use crate::store::StoreError;
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+/// Characters returned per call; longer documents are paged with `offset`.
+const PAGE_CHARS: usize = 50_000;
+
+#[derive(Deserialize)]
+struct Args {
+    document_id: String,
+    #[serde(default)]
+    offset: usize,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+            let id: uuid::Uuid = args
+                .document_id
+                .parse()
+                .map_err(|_| format!("Invalid document_id '{}'", args.document_id))?;
+
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
+                "Source documents not available (store not configured)".to_string()
+            })?;
+            let document = match store
+                .get_source_document(SourceDocumentId::from_uuid(id))
+                .await
+            {
+                Ok(document) => document,
+                Err(StoreError::NotFound(_)) => {
+                    return Err(format!("No source document {}", args.document_id))
+                }
+                Err(e) => return Err(format!("Failed to load source document: {}", e)),
+            };
+
+            Ok(render_document(&document, args.offset))
+        })
+    })
+}
+
+/// The document's metadata and one page of its content, starting at
+/// `offset` characters.
+fn render_document(document: &SourceDocument, offset: usize) -> Value {
+    let total_chars = document.content.chars().count();
+    let content: String = document
+        .content
+        .chars()
+        .skip(offset)
+        .take(PAGE_CHARS)
+        .collect();
+    let end = offset + content.chars().count();
+
+    let mut result = json!({
+        "document_id": document.id.to_string(),
+        "origin": document.origin,
+        "title": document.title,
+        "author": document.author,
+        "publisher_entity_id": document.publisher_entity_id.map(|id| id.to_string()),
+        "published_at": document.published_at.map(|t| t.to_rfc3339()),
+        "received_at": document.received_at.to_rfc3339(),
+        "citation": Citation::for_source_document(
+            document.id,
+            document.title.clone(),
+            document.received_at,
+        ),
+        "content": content,
+        "total_chars": total_chars,
+    });
+    if end < total_chars {
+        result["next_offset"] = json!(end);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+
+    #[test]
+    fn test_render_document_pages_content() {
+        let document = SourceDocument {
+            id: SourceDocumentId::new(),
+            origin: "imap".into(),
+            external_id: "<brief-41@example.org>".into(),
+            title: Some("Maritime Brief #41".into()),
+            author: Some("Maritime Brief <news@example.org>".into()),
+            publisher_entity_id: None,
+            content: "é".repeat(PAGE_CHARS + 10),
+            published_at: None,
+            received_at: Utc::now(),
+        };
+
+        let first = render_document(&document, 0);
+        assert_eq!(
+            first["content"].as_str().unwrap().chars().count(),
+            PAGE_CHARS
+        );
+        assert_eq!(first["next_offset"], PAGE_CHARS);
+        assert_eq!(first["title"], "Maritime Brief #41");
+        assert_eq!(first["citation"]["via"], "archive");
+        assert_eq!(
+            first["citation"]["source_document_id"],
+            document.id.to_string()
+        );
+
+        let last = render_document(&document, PAGE_CHARS);
+        assert_eq!(last["content"].as_str().unwrap().chars().count(), 10);
+        assert!(last.get("next_offset").is_none());
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::ids::WorkOrderId;
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use crate::tools::truncation::truncate_text;
+
+#[derive(Deserialize)]
+struct Args {
+    work_order_id: String,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let work_order_id = args
+                .work_order_id
+                .parse::<uuid::Uuid>()
+                .map(WorkOrderId::from_uuid)
+                .map_err(|e| format!("Invalid work_order_id: {}", e))?;
+
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
+                "Work order retrieval not available (store not configured)".to_string()
+            })?;
+
+            let wo = store
+                .get_work_order(work_order_id)
+                .await
+                .map_err(|e| format!("Failed to get work order: {}", e))?;
+            if ctx.scope.investigation_id != Some(wo.investigation_id) {
+                return Err(format!(
+                    "Work order {} belongs to another investigation",
+                    work_order_id
+                ));
+            }
+
+            // The session's final text is the only unbounded field in the summary.
+            let max_chars = ctx.services.tool_result_limits.max_entity_detail_chars as usize;
+            let mut summary = wo.summary.clone();
+            if let Some(text) = summary
+                .as_mut()
+                .and_then(|s| s.get_mut("final_text"))
+                .filter(|t| t.is_string())
+            {
+                *text = Value::String(truncate_text(text.as_str().unwrap_or_default(), max_chars));
+            }
+            let failure_detail = wo.failure_detail.map(|mut detail| {
+                detail.error = truncate_text(&detail.error, max_chars);
+                detail.last_tool_error =
+                    detail.last_tool_error.map(|e| truncate_text(&e, max_chars));
+                detail
+            });
+
+            Ok(json!({
+                "work_order_id": wo.id.to_string(),
+                "investigation_id": wo.investigation_id.to_string(),
+                "objective": wo.objective,
+                "status": wo.status.as_db_str(),
+                "priority": wo.priority.as_str(),
+                "effort": wo.effort.as_str(),
+                "mode": wo.mode.as_str(),
+                "deadline": wo.deadline.map(|t| t.to_rfc3339()),
+                "cycle": wo.cycle,
+                "referenced_entities": wo.referenced_entities.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                "source_guidance": wo.source_guidance,
+                "processor_id": wo.processor_id,
+                "claims_created": wo.claims_produced_count,
+                "retry_count": wo.retry_count,
+                "failure_reason": wo.failure_reason.as_deref().map(|r| truncate_text(r, max_chars)),
+                "summary": summary,
+                "failure_detail": failure_detail,
+                "created_at": wo.created_at.to_rfc3339(),
+                "completed_at": wo.completed_at.map(|t| t.to_rfc3339()),
+            }))
+        })
+    })
+}
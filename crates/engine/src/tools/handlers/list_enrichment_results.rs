@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+/// The investigation's external enrichment requests, oldest first, with the
+/// start of each arrived result.
+pub fn handler() -> ToolHandler {
+    Arc::new(|_args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let enrichment = ctx
+                .services
+                .enrichment
+                .as_ref()
+                .ok_or_else(|| "External enrichment is disabled".to_string())?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "External enrichment not available (no investigation)".to_string()
+            })?;
+
+            let requests = enrichment
+                .list(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to list enrichment requests: {}", e))?;
+
+            let providers: Vec<Value> = enrichment
+                .config()
+                .providers
+                .iter()
+                .map(|p| json!({"name": p.name, "description": p.description}))
+                .collect();
+            Ok(json!({
+                "providers": providers,
+                "requests": requests,
+                "count": requests.len(),
+            }))
+        })
+    })
+}
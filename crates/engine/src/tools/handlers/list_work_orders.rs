@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::config::ToolResultLimits;
+use autosint_common::types::{WorkOrder, WorkOrderStatus};
+
+use crate::tools::format::{first_sentence, render_results, text, Formatted, FormattedHandler};
+use crate::tools::registry::ToolHandlerContext;
+use crate::tools::truncation::{truncate_search_results, truncate_text};
+
+#[derive(Deserialize)]
+struct Args {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    cycle: Option<i32>,
+}
+
+pub fn handler() -> FormattedHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let status = args
+                .status
+                .as_deref()
+                .map(|s| s.parse::<WorkOrderStatus>().map_err(|e| e.to_string()))
+                .transpose()?;
+
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
+                "Work order listing not available (store not configured)".to_string()
+            })?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "Work order listing not available (no investigation context)".to_string()
+            })?;
+
+            let work_orders = store
+                .get_work_orders_by_investigation(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to list work orders: {}", e))?;
+
+            let result = format_work_orders(
+                &work_orders,
+                status,
+                args.cycle,
+                &ctx.services.tool_result_limits,
+            );
+            Ok(Formatted::new(result, summarize))
+        })
+    })
+}
+
+/// Filter work orders (already ordered by cycle, then creation) and render them
+/// as compact rows. Free text is cut to the claim preview length; use
+/// get_work_order for the full record.
+fn format_work_orders(
+    work_orders: &[WorkOrder],
+    status: Option<WorkOrderStatus>,
+    cycle: Option<i32>,
+    limits: &ToolResultLimits,
+) -> Value {
+    let max_chars = limits.max_claim_preview_chars as usize;
+
+    let results: Vec<Value> = work_orders
+        .iter()
+        .filter(|wo| status.is_none_or(|s| wo.status == s))
+        .filter(|wo| cycle.is_none_or(|c| wo.cycle == c))
+        .map(|wo| {
+            let mut row = json!({
+                "work_order_id": wo.id.to_string(),
+                "objective": truncate_text(&wo.objective, max_chars),
+                "status": wo.status.as_db_str(),
+                "priority": wo.priority.as_str(),
+                "cycle": wo.cycle,
+                "claims_created": wo.claims_produced_count,
+                "retry_count": wo.retry_count,
+                "processor_id": wo.processor_id,
+                "outcome": wo.summary.as_ref().and_then(|s| s.get("outcome")).cloned(),
+                "failure_reason": wo.failure_reason.as_deref().map(|r| truncate_text(r, max_chars)),
+                "failure_category": wo.failure_detail.as_ref().map(|d| d.category.as_str()),
+            });
+            if wo.contract_unmet {
+                row["contract_unmet"] = json!(true);
+                row["unmet_outputs"] = json!(wo.unmet_outputs());
+            }
+            if let Some(proposed_by) = wo.proposed_by {
+                row["proposed_by"] = json!(proposed_by.to_string());
+                row["rationale"] =
+                    json!(wo.rationale.as_deref().map(|r| truncate_text(r, max_chars)));
+            }
+            row
+        })
+        .collect();
+
+    let mut output = json!({
+        "count": results.len(),
+        "results": results,
+    });
+    truncate_search_results(&mut output, limits);
+    output
+}
+
+/// One line per work order: objective, status, priority, cycle and claims,
+/// then why it failed, what it left unmet or who proposed it, and its ID.
+fn summarize(result: &Value) -> String {
+    render_results(result, "work orders", |wo| {
+        let mut line = format!(
+            "{} — {}, {} priority, cycle {}, {} claims",
+            first_sentence(text(wo, "objective")),
+            text(wo, "status"),
+            text(wo, "priority"),
+            wo["cycle"],
+            wo["claims_created"]
+        );
+        if let Some(retries) = wo["retry_count"].as_u64().filter(|r| *r > 0) {
+            line.push_str(&format!(", retried {} times", retries));
+        }
+        let reason = text(wo, "failure_reason");
+        if !reason.is_empty() {
+            line.push_str(&format!(
+                "; failed ({}): {}",
+                text(wo, "failure_category"),
+                first_sentence(reason)
+            ));
+        }
+        if wo["contract_unmet"] == true {
+            let unmet: Vec<&str> = wo["unmet_outputs"]
+                .as_array()
+                .map(|u| u.iter().map(|o| text(o, "criterion")).collect())
+                .unwrap_or_default();
+            line.push_str(&format!("; contract unmet: {}", unmet.join(", ")));
+        }
+        let proposed_by = text(wo, "proposed_by");
+        if !proposed_by.is_empty() {
+            line.push_str(&format!(
+                "; proposed by {}: {}",
+                proposed_by,
+                first_sentence(text(wo, "rationale"))
+            ));
+        }
+        line.push_str(&format!(" [{}]", text(wo, "work_order_id")));
+        line
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::{FailureCategory, FailureDetail, WorkOrderPriority};
+    use autosint_common::InvestigationId;
+
+    fn limits() -> ToolResultLimits {
+        ToolResultLimits {
+            max_search_results: 3,
+            max_entity_detail_chars: 10000,
+            max_claim_preview_chars: 20,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+            max_claim_references_shown: 10,
+        }
+    }
+
+    fn work_order(cycle: i32, status: WorkOrderStatus, objective: &str) -> WorkOrder {
+        let mut wo = WorkOrder::new(
+            InvestigationId::new(),
+            objective.into(),
+            WorkOrderPriority::Normal,
+        );
+        wo.cycle = cycle;
+        wo.status = status;
+        wo
+    }
+
+    #[test]
+    fn test_filters_by_status_and_cycle() {
+        let mut failed = work_order(2, WorkOrderStatus::Failed, "Find vessel registry");
+        failed.failure_reason = Some("LLM API error".into());
+        failed.failure_detail = Some(FailureDetail {
+            category: FailureCategory::DependencyUnavailable,
+            error: "LLM API error".into(),
+            last_tool: Some("fetch_url".into()),
+            last_tool_error: Some("Fetch service request failed".into()),
+            tool_errors: [("fetch_url".to_string(), 3)].into(),
+            graph_writes: false,
+        });
+        failed.retry_count = 1;
+        let orders = vec![
+            work_order(1, WorkOrderStatus::Completed, "Find CEO"),
+            work_order(1, WorkOrderStatus::Failed, "Find filings"),
+            failed,
+            work_order(2, WorkOrderStatus::Queued, "Find subsidiaries"),
+        ];
+
+        let output = format_work_orders(&orders, Some(WorkOrderStatus::Failed), None, &limits());
+        assert_eq!(output["count"], 2);
+
+        let output = format_work_orders(&orders, Some(WorkOrderStatus::Failed), Some(2), &limits());
+        assert_eq!(output["count"], 1);
+        let row = &output["results"][0];
+        assert_eq!(row["objective"], "Find vessel registry");
+        assert_eq!(row["failure_reason"], "LLM API error");
+        assert_eq!(row["failure_category"], "dependency_unavailable");
+        assert_eq!(row["retry_count"], 1);
+        assert_eq!(row["cycle"], 2);
+
+        let output = format_work_orders(&orders, None, Some(3), &limits());
+        assert_eq!(output["count"], 0);
+    }
+
+    #[test]
+    fn test_shows_unmet_contracts() {
+        let mut unmet = work_order(1, WorkOrderStatus::Completed, "Find vessel registry");
+        unmet.contract_unmet = true;
+        unmet.summary = Some(json!({
+            "outcome": "completed",
+            "contract": {
+                "met": false,
+                "unmet": [{"criterion": "min_claims", "expected": 3, "delivered": 0}],
+            },
+        }));
+        let orders = vec![unmet, work_order(1, WorkOrderStatus::Completed, "Find CEO")];
+
+        let output = format_work_orders(&orders, None, None, &limits());
+        let rows = output["results"].as_array().unwrap();
+        assert_eq!(rows[0]["contract_unmet"], true);
+        assert_eq!(rows[0]["unmet_outputs"][0]["criterion"], "min_claims");
+        assert_eq!(rows[0]["unmet_outputs"][0]["delivered"], 0);
+        assert!(rows[1].get("contract_unmet").is_none());
+    }
+
+    #[test]
+    fn test_shows_proposals() {
+        let origin = work_order(1, WorkOrderStatus::Completed, "Find CEO");
+        let mut proposal = work_order(1, WorkOrderStatus::Proposed, "Find board");
+        proposal.proposed_by = Some(origin.id);
+        proposal.rationale = Some("Filing names two directors".into());
+        let orders = vec![origin.clone(), proposal];
+
+        let output = format_work_orders(&orders, Some(WorkOrderStatus::Proposed), None, &limits());
+        assert_eq!(output["count"], 1);
+        let row = &output["results"][0];
+        assert_eq!(row["status"], "proposed");
+        assert_eq!(row["proposed_by"], origin.id.to_string());
+        assert_eq!(
+            row["rationale"],
+            "Filing names two dir...[truncated, 26 chars total]"
+        );
+
+        let output = format_work_orders(&orders, Some(WorkOrderStatus::Completed), None, &limits());
+        assert!(output["results"][0].get("proposed_by").is_none());
+    }
+
+    #[test]
+    fn test_truncates_objectives_and_row_count() {
+        let orders: Vec<WorkOrder> = (0..5)
+            .map(|i| {
+                work_order(
+                    1,
+                    WorkOrderStatus::Queued,
+                    &format!("Objective {} with a long tail of search detail", i),
+                )
+            })
+            .collect();
+
+        let output = format_work_orders(&orders, None, None, &limits());
+        let results = output["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(output["total_results"], 5);
+        assert_eq!(
+            results[0]["objective"],
+            "Objective 0 with a l...[truncated, 45 chars total]"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::ClaimId;
+
+use crate::tools::claim_merge::merge_claims;
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    source_claim_id: String,
+    target_claim_id: String,
+    rationale: String,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let source_id = args
+                .source_claim_id
+                .parse::<uuid::Uuid>()
+                .map(ClaimId::from_uuid)
+                .map_err(|e| format!("Invalid source_claim_id: {}", e))?;
+            let target_id = args
+                .target_claim_id
+                .parse::<uuid::Uuid>()
+                .map(ClaimId::from_uuid)
+                .map_err(|e| format!("Invalid target_claim_id: {}", e))?;
+            let rationale = args.rationale.trim();
+            if rationale.is_empty() {
+                return Err("rationale is required: say why the claims are duplicates.".into());
+            }
+
+            let merge = merge_claims(&ctx, source_id, target_id, rationale).await?;
+            let merged = &merge.merged;
+
+            Ok(json!({
+                "merged_claim_id": merged.id.to_string(),
+                "content": merged.content,
+                "published_timestamp": merged.published_timestamp.to_rfc3339(),
+                "attribution_depth": format!("{:?}", merged.attribution_depth).to_lowercase(),
+                "referenced_entity_ids": merged.referenced_entity_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                "sources": 1 + merged.merged_sources.len(),
+                "message": format!(
+                    "Claim {} merged into {}. References, supported relationships and \
+                     citing assessments moved; both sources are cited.",
+                    source_id, target_id
+                )
+            }))
+        })
+    })
+}
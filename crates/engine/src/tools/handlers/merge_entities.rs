@@ -5,6 +5,7 @@ use serde_json::{json, Value};
 
 use autosint_common::EntityId;
 
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
 #[derive(Deserialize)]
@@ -13,6 +14,8 @@ struct Args {
     target_entity_id: String,
     #[serde(default)]
     reason: Option<String>,
+    #[serde(default)]
+    force: bool,
 }
 
 pub fn handler() -> ToolHandler {
@@ -33,17 +36,57 @@ pub fn handler() -> ToolHandler {
                 .map(EntityId::from_uuid)
                 .map_err(|e| format!("Invalid target_entity_id: {}", e))?;
 
+            // Exclusive locks wait out in-flight writes to either entity and hold off new
+            // ones until the tombstone below is visible.
+            let (mut guard, ids) =
+                lock_entities(&ctx, &[source_id, target_id], LockMode::Exclusive).await?;
+            let (source_id, target_id) = (ids[0], ids[1]);
+
+            // Whether merges follow compare_entities verdicts.
+            let verdict = ctx
+                .scope
+                .session_counters
+                .comparison(source_id, target_id)
+                .map_or("not_compared", |band| band.as_str());
+            metrics::counter!("tools.merge_entities.verdicts", "verdict" => verdict).increment(1);
+            tracing::info!(
+                source = %source_id,
+                target = %target_id,
+                verdict,
+                force = args.force,
+                "Merging entities"
+            );
+
             let merged = ctx
+                .services
                 .graph
-                .merge_entities(source_id, target_id, args.reason.as_deref())
+                .merge_entities(source_id, target_id, args.reason.as_deref(), args.force)
                 .await
                 .map_err(|e| format!("Failed to merge entities: {}", e))?;
 
+            // Redirect writers still holding the source ID to the target.
+            if let Some(guard) = guard.as_mut() {
+                if let Err(e) = guard.record_merge(source_id, target_id).await {
+                    tracing::warn!(
+                        source = %source_id,
+                        target = %target_id,
+                        error = %e,
+                        "Failed to record merge tombstone"
+                    );
+                }
+            }
+            // Point shortlisted names of the source at the target.
+            if let Some(shortlist) = &ctx.scope.entity_shortlist {
+                shortlist.record_merge(source_id, &merged).await;
+            }
+            release(guard).await;
+
             Ok(json!({
                 "merged_entity_id": merged.id.to_string(),
                 "canonical_name": merged.canonical_name,
                 "aliases": merged.aliases,
                 "kind": merged.kind,
+                "identifiers": merged.identifiers,
                 "message": format!(
                     "Entity {} merged into {}. All relationships and claims reassigned.",
                     source_id, target_id
@@ -1,4 +1,6 @@
+mod approve_proposals;
 mod batch_extract;
+mod compare_entities;
 mod create_claim;
 mod create_entity;
 mod create_relationship;
@@ -7,27 +9,64 @@ mod fetch_source_catalog;
 mod fetch_source_query;
 mod fetch_url;
 mod get_assessment;
+mod get_claim_detail;
 mod get_entity;
+mod get_graph_changes;
+mod get_investigation_budget;
 mod get_investigation_history;
+mod get_source_document;
+mod get_work_order;
+mod list_enrichment_results;
 mod list_fetch_sources;
+mod list_work_orders;
+mod merge_claims;
 mod merge_entities;
 mod produce_assessment;
+mod propose_work_order;
 mod query_geo;
+mod read_scratchpad;
+mod reprioritize_work_order;
+mod reverse_relationship;
 mod search_assessments;
 mod search_claims;
 mod search_entities;
 mod search_relationships;
+mod submit_external_enrichment;
+mod tag_investigation;
 mod traverse_relationships;
 mod update_entity;
 mod update_entity_with_change_claim;
 mod update_relationship;
+mod verify_claim;
 mod web_search;
+mod write_scratchpad;
 
 use super::registry::ToolRegistry;
 
+/// Tools withheld from sessions while external enrichment is disabled.
+pub const ENRICHMENT_TOOLS: &[&str] = &["submit_external_enrichment", "list_enrichment_results"];
+
+/// Analyst tools withheld from snapshot investigations: those that change
+/// the graph or queue work for it, those that look outside it for evidence
+/// from after the cutoff, and graph maintenance and change tracking, which
+/// have no cutoff filter.
+pub const SNAPSHOT_WITHHELD_TOOLS: &[&str] = &[
+    "create_work_order",
+    "reprioritize_work_order",
+    "approve_proposals",
+    "merge_entities",
+    "merge_claims",
+    "reverse_relationship",
+    "compare_entities",
+    "get_graph_changes",
+    "verify_claim",
+    "submit_external_enrichment",
+    "list_enrichment_results",
+];
+
 /// Register all Processor tool handlers with the registry.
 pub fn register_processor_tools(registry: &mut ToolRegistry) {
-    registry.register("search_entities", search_entities::handler());
+    registry.register_formatted("search_entities", search_entities::handler());
     registry.register("create_entity", create_entity::handler());
     registry.register("update_entity", update_entity::handler());
     registry.register("create_claim", create_claim::handler());
@@ -42,19 +81,32 @@ pub fn register_processor_tools(registry: &mut ToolRegistry) {
     registry.register("fetch_source_query", fetch_source_query::handler());
     registry.register("web_search", web_search::handler());
     registry.register("batch_extract", batch_extract::handler());
+    registry.register("get_source_document", get_source_document::handler());
+    registry.register("propose_work_order", propose_work_order::handler());
+    registry.register(
+        "submit_external_enrichment",
+        submit_external_enrichment::handler(),
+    );
+    registry.register(
+        "list_enrichment_results",
+        list_enrichment_results::handler(),
+    );
 }
 
 /// Register all Analyst tool handlers with the registry.
 pub fn register_analyst_tools(registry: &mut ToolRegistry) {
     // Graph read tools (shared with Processor where applicable).
-    registry.register("search_entities", search_entities::handler());
-    registry.register("get_entity", get_entity::handler());
-    registry.register("traverse_relationships", traverse_relationships::handler());
+    registry.register_formatted("search_entities", search_entities::handler());
+    registry.register_formatted("get_entity", get_entity::handler());
+    registry.register_formatted("traverse_relationships", traverse_relationships::handler());
     registry.register("search_relationships", search_relationships::handler());
-    registry.register("search_claims", search_claims::handler());
+    registry.register_formatted("search_claims", search_claims::handler());
+    registry.register("get_claim_detail", get_claim_detail::handler());
+    registry.register("get_graph_changes", get_graph_changes::handler());
+    registry.register("verify_claim", verify_claim::handler());
 
     // Assessment store tools.
-    registry.register("search_assessments", search_assessments::handler());
+    registry.register_formatted("search_assessments", search_assessments::handler());
     registry.register("get_assessment", get_assessment::handler());
 
     // Investigation action tools.
@@ -62,14 +114,39 @@ pub fn register_analyst_tools(registry: &mut ToolRegistry) {
     registry.register("produce_assessment", produce_assessment::handler());
 
     // Graph maintenance tools.
+    registry.register("compare_entities", compare_entities::handler());
     registry.register("merge_entities", merge_entities::handler());
+    registry.register("merge_claims", merge_claims::handler());
+    registry.register("reverse_relationship", reverse_relationship::handler());
 
     // Investigation context tools.
     registry.register(
         "get_investigation_history",
         get_investigation_history::handler(),
     );
+    registry.register_formatted("list_work_orders", list_work_orders::handler());
+    registry.register("get_work_order", get_work_order::handler());
+    registry.register(
+        "reprioritize_work_order",
+        reprioritize_work_order::handler(),
+    );
+    registry.register("approve_proposals", approve_proposals::handler());
     registry.register("list_fetch_sources", list_fetch_sources::handler());
+    registry.register(
+        "submit_external_enrichment",
+        submit_external_enrichment::handler(),
+    );
+    registry.register(
+        "list_enrichment_results",
+        list_enrichment_results::handler(),
+    );
+    registry.register(
+        "get_investigation_budget",
+        get_investigation_budget::handler(),
+    );
+    registry.register("tag_investigation", tag_investigation::handler());
+    registry.register("write_scratchpad", write_scratchpad::handler());
+    registry.register("read_scratchpad", read_scratchpad::handler());
 
     // Geographic intelligence (stub until M5).
     registry.register("query_geo", query_geo::handler());
@@ -1,12 +1,17 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::ids::{ClaimId, EntityId};
-use autosint_common::types::{Assessment, Confidence};
+use autosint_common::ids::{AssessmentId, ClaimId, EntityId};
+use autosint_common::types::{
+    normalize_tags, Assessment, AssessmentProduced, Confidence, DomainEvent,
+};
 
+use crate::assessment_diff::judgments;
+use crate::store::StoreError;
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
 #[derive(Deserialize)]
@@ -17,6 +22,10 @@ struct Args {
     entity_refs: Vec<String>,
     #[serde(default)]
     claim_refs: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    supersedes_assessment_id: Option<String>,
 }
 
 pub fn handler() -> ToolHandler {
@@ -27,6 +36,7 @@ pub fn handler() -> ToolHandler {
 
             // Only one assessment per session.
             if ctx
+                .scope
                 .session_counters
                 .assessment_produced
                 .load(Ordering::Relaxed)
@@ -37,10 +47,10 @@ pub fn handler() -> ToolHandler {
                 );
             }
 
-            let store = ctx.store.as_ref().ok_or_else(|| {
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
                 "Assessment production not available (store not configured)".to_string()
             })?;
-            let investigation_id = ctx.investigation_id.ok_or_else(|| {
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
                 "Assessment production not available (no investigation context)".to_string()
             })?;
 
@@ -79,9 +89,28 @@ pub fn handler() -> ToolHandler {
                 })
                 .collect::<Result<_, _>>()?;
 
+            let tags = normalize_tags(&args.tags)?;
+
+            // The superseded assessment must exist; the diff against it is
+            // computed when the investigation completes.
+            let supersedes = match args.supersedes_assessment_id.as_deref() {
+                Some(s) => {
+                    let id = s
+                        .parse::<uuid::Uuid>()
+                        .map(AssessmentId::from_uuid)
+                        .map_err(|e| format!("Invalid supersedes_assessment_id '{}': {}", s, e))?;
+                    store.get_assessment(id).await.map_err(|e| match e {
+                        StoreError::NotFound(_) => format!("Assessment {} not found", id),
+                        other => format!("Failed to look up superseded assessment: {}", other),
+                    })?;
+                    Some(id)
+                }
+                None => None,
+            };
+
             // Compute embedding for the assessment content.
             let embed_text = serde_json::to_string(&args.content).unwrap_or_default();
-            let embedding = if let Some(ref emb_client) = ctx.embedding_client {
+            let embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                 match emb_client.embed_single(&embed_text).await {
                     Ok(emb) => Some(emb),
                     Err(e) => {
@@ -97,14 +126,28 @@ pub fn handler() -> ToolHandler {
             assessment.entity_refs = entity_refs;
             assessment.claim_refs = claim_refs;
             assessment.embedding = embedding;
+            assessment.tags = tags;
+            assessment.supersedes_assessment_id = supersedes;
+            assessment.as_of = ctx.scope.as_of;
 
             let created = store
                 .create_assessment(&assessment)
                 .await
                 .map_err(|e| format!("Failed to store assessment: {}", e))?;
 
+            ctx.services
+                .events
+                .publish(DomainEvent::AssessmentProduced(AssessmentProduced {
+                    investigation_id,
+                    assessment_id: created.id,
+                    confidence: created.confidence.clone(),
+                    supersedes_assessment_id: created.supersedes_assessment_id,
+                }))
+                .await;
+
             // Mark assessment as produced (only one per session).
-            ctx.session_counters
+            ctx.scope
+                .session_counters
                 .assessment_produced
                 .store(true, Ordering::Relaxed);
 
@@ -115,12 +158,122 @@ pub fn handler() -> ToolHandler {
                 "Assessment produced"
             );
 
-            Ok(json!({
+            let mut result = json!({
                 "assessment_id": created.id.to_string(),
                 "investigation_id": investigation_id.to_string(),
                 "confidence": created.confidence.as_db_str(),
+                "tags": created.tags,
+                "supersedes_assessment_id": created.supersedes_assessment_id.map(|id| id.to_string()),
                 "message": "Assessment stored successfully. Investigation will complete."
-            }))
+            });
+            if let Some(source_trust) = &ctx.scope.source_trust {
+                let floor = source_trust.citation_floor;
+                let trust = claim_trust_levels(&ctx, &created.content).await;
+                let warnings: Vec<String> = low_trust_judgments(&created.content, &trust, floor)
+                    .into_iter()
+                    .map(|hypothesis| {
+                        format!(
+                            "Key judgment \"{}\" rests only on claims from sources rated below \
+                             trust level {}. Address the weak sourcing explicitly.",
+                            hypothesis, floor
+                        )
+                    })
+                    .collect();
+                if !warnings.is_empty() {
+                    metrics::counter!("assessments.low_trust_judgments")
+                        .increment(warnings.len() as u64);
+                    result["warnings"] = json!(warnings);
+                }
+            }
+
+            Ok(result)
         })
     })
 }
+
+/// Claim IDs in a `claim_refs` array; anything that doesn't parse is skipped.
+fn claim_refs(judgment: &Value) -> Vec<ClaimId> {
+    judgment
+        .get("claim_refs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|id| id.as_str()?.trim().parse::<uuid::Uuid>().ok())
+        .map(ClaimId::from_uuid)
+        .collect()
+}
+
+/// Source trust levels, as ingested, of the claims the key judgments cite.
+/// Claims from unrated sources, or that can't be read, are left out.
+async fn claim_trust_levels(ctx: &ToolHandlerContext, content: &Value) -> HashMap<ClaimId, u8> {
+    let mut trust = HashMap::new();
+    let mut seen = HashSet::new();
+    for id in judgments(content).iter().flat_map(claim_refs) {
+        if !seen.insert(id) {
+            continue;
+        }
+        match ctx.reads().get_claim(id).await {
+            Ok(claim) => {
+                if let Some(level) = claim.source_trust_level {
+                    trust.insert(id, level);
+                }
+            }
+            Err(e) => tracing::debug!(claim_id = %id, error = %e, "Cited claim not readable"),
+        }
+    }
+    trust
+}
+
+/// Key judgments (competing hypotheses) whose cited claims all come from
+/// sources rated below `floor`, by hypothesis text. A judgment citing any
+/// claim missing from `trust` (unrated or unreadable) isn't flagged.
+fn low_trust_judgments(content: &Value, trust: &HashMap<ClaimId, u8>, floor: u8) -> Vec<String> {
+    judgments(content)
+        .iter()
+        .filter(|judgment| {
+            let claims = claim_refs(judgment);
+            !claims.is_empty()
+                && claims
+                    .iter()
+                    .all(|id| trust.get(id).is_some_and(|level| *level < floor))
+        })
+        .map(|judgment| {
+            judgment
+                .get("hypothesis")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_low_trust_judgments() {
+        let ids: Vec<ClaimId> = (0..4).map(|_| ClaimId::new()).collect();
+        let refs = |claims: &[ClaimId]| claims.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let content = json!({
+            "competing_hypotheses": [
+                {"hypothesis": "Blog-sourced only", "claim_refs": refs(&ids[..2])},
+                {"hypothesis": "Corroborated by a wire service", "claim_refs": refs(&ids[1..3])},
+                {"hypothesis": "Cites an unrated source", "claim_refs": refs(&[ids[0], ids[3]])},
+                {"hypothesis": "No citations", "claim_refs": []},
+            ]
+        });
+        // ids[3] is from an unrated source.
+        let trust = HashMap::from([(ids[0], 1), (ids[1], 2), (ids[2], 5)]);
+
+        assert_eq!(
+            low_trust_judgments(&content, &trust, 3),
+            ["Blog-sourced only"]
+        );
+        assert_eq!(
+            low_trust_judgments(&content, &trust, 2),
+            Vec::<String>::new()
+        );
+        assert_eq!(low_trust_judgments(&json!({}), &trust, 3).len(), 0);
+    }
+}
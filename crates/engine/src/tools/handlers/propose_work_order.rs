@@ -0,0 +1,127 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::types::{WorkOrder, WorkOrderPriority, WorkOrderStatus};
+use autosint_common::EntityId;
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    objective: String,
+    rationale: String,
+    #[serde(default)]
+    referenced_entities: Vec<String>,
+    #[serde(default)]
+    priority: Option<String>,
+}
+
+/// Record a follow-up work order for the Analyst to approve or dismiss at its
+/// next cycle. Nothing is enqueued: a Processor can't spend the
+/// investigation's budget on its own.
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let config = &ctx.services.work_order_proposals;
+            if !config.enabled {
+                return Err("Work order proposals are disabled".to_string());
+            }
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
+                "Work order proposals not available (store not configured)".to_string()
+            })?;
+            let (Some(investigation_id), Some(origin_id)) =
+                (ctx.scope.investigation_id, ctx.scope.work_order_id)
+            else {
+                return Err(
+                    "Work order proposals not available (no investigation work order)".to_string(),
+                );
+            };
+
+            let objective = args.objective.trim();
+            let rationale = args.rationale.trim();
+            if objective.is_empty() {
+                return Err("objective must not be empty".to_string());
+            }
+            if rationale.is_empty() {
+                return Err("rationale must not be empty".to_string());
+            }
+
+            let proposed = ctx
+                .scope
+                .session_counters
+                .work_orders_proposed
+                .load(Ordering::Relaxed);
+            if proposed >= config.max_per_processor_session {
+                return Err(format!(
+                    "Proposal limit reached ({}/{}). Record what you found as claims instead.",
+                    proposed, config.max_per_processor_session
+                ));
+            }
+
+            let priority = match args.priority.as_deref() {
+                Some(p) => p.parse::<WorkOrderPriority>().map_err(|e| e.to_string())?,
+                None => WorkOrderPriority::default(),
+            };
+            let referenced_entities: Vec<EntityId> = args
+                .referenced_entities
+                .iter()
+                .map(|s| {
+                    s.parse::<uuid::Uuid>()
+                        .map(EntityId::from_uuid)
+                        .map_err(|e| format!("Invalid entity ID '{}': {}", s, e))
+                })
+                .collect::<Result<_, _>>()?;
+
+            // The proposal belongs to the cycle of the work order that found it.
+            let origin = store
+                .get_work_order(origin_id)
+                .await
+                .map_err(|e| format!("Failed to load work order: {}", e))?;
+
+            let mut wo = WorkOrder::new(investigation_id, objective.to_string(), priority);
+            wo.status = WorkOrderStatus::Proposed;
+            wo.cycle = origin.cycle;
+            wo.referenced_entities = referenced_entities;
+            wo.proposed_by = Some(origin_id);
+            wo.rationale = Some(rationale.to_string());
+
+            let created = store
+                .propose_work_order(&wo, config.max_per_investigation)
+                .await
+                .map_err(|e| format!("Failed to record proposal: {}", e))?
+                .ok_or_else(|| {
+                    format!(
+                        "This investigation already has {} proposals, the most allowed. \
+                         Record what you found as claims instead.",
+                        config.max_per_investigation
+                    )
+                })?;
+
+            ctx.scope
+                .session_counters
+                .work_orders_proposed
+                .fetch_add(1, Ordering::Relaxed);
+            metrics::counter!("work_orders.proposed").increment(1);
+            tracing::info!(
+                work_order_id = %created.id,
+                proposed_by = %origin_id,
+                objective = %created.objective,
+                "Work order proposed"
+            );
+
+            Ok(json!({
+                "work_order_id": created.id.to_string(),
+                "status": created.status.as_db_str(),
+                "priority": created.priority.as_str(),
+                "message": "Proposal recorded. The Analyst reviews it at the start of its next cycle; \
+                            carry on with your own work order.",
+            }))
+        })
+    })
+}
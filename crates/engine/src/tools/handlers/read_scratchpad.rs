@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::types::normalize_scratchpad_section;
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    section: Option<String>,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            if ctx.scope.scratchpad.is_none() {
+                return Err("Scratchpad not available in this session".into());
+            }
+            let records = ctx
+                .services
+                .records
+                .as_ref()
+                .ok_or_else(|| "Scratchpad not available (store not configured)".to_string())?;
+            let investigation_id = ctx
+                .scope
+                .investigation_id
+                .ok_or_else(|| "Scratchpad not available (no investigation context)".to_string())?;
+
+            let section = args
+                .section
+                .as_deref()
+                .map(normalize_scratchpad_section)
+                .transpose()?;
+
+            let sections = records
+                .get_scratchpad(investigation_id, section.as_deref())
+                .await
+                .map_err(|e| format!("Failed to read scratchpad: {}", e))?;
+
+            if let (Some(section), true) = (&section, sections.is_empty()) {
+                return Err(format!("No scratchpad section '{}'", section));
+            }
+
+            let sections: Vec<Value> = sections
+                .iter()
+                .map(|s| {
+                    json!({
+                        "section": s.section,
+                        "content": s.content,
+                        "updated_at": s.updated_at.to_rfc3339(),
+                    })
+                })
+                .collect();
+            Ok(json!({ "sections": sections }))
+        })
+    })
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::ids::WorkOrderId;
+use autosint_common::types::{DomainEvent, WorkOrderMessage, WorkOrderPriority, WorkOrderStatus};
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    work_order_id: String,
+    priority: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
+                "Work order reprioritization not available (store not configured)".to_string()
+            })?;
+            let queue = ctx.services.queue.as_ref().ok_or_else(|| {
+                "Work order reprioritization not available (queue not configured)".to_string()
+            })?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "Work order reprioritization not available (no investigation context)".to_string()
+            })?;
+
+            let work_order_id = args
+                .work_order_id
+                .parse::<uuid::Uuid>()
+                .map(WorkOrderId::from_uuid)
+                .map_err(|e| format!("Invalid work_order_id: {}", e))?;
+            let priority = args
+                .priority
+                .parse::<WorkOrderPriority>()
+                .map_err(|e| e.to_string())?;
+
+            let wo = store
+                .get_work_order(work_order_id)
+                .await
+                .map_err(|e| format!("Failed to get work order: {}", e))?;
+            if wo.investigation_id != investigation_id {
+                return Err(format!(
+                    "Work order {} belongs to another investigation",
+                    work_order_id
+                ));
+            }
+            match wo.status {
+                WorkOrderStatus::Scheduled | WorkOrderStatus::Queued => {}
+                WorkOrderStatus::Processing => {
+                    return Err(format!(
+                        "Work order {} is already being processed; its priority can no longer change",
+                        work_order_id
+                    ));
+                }
+                WorkOrderStatus::Completed | WorkOrderStatus::Failed => {
+                    return Err(format!(
+                        "Work order {} already finished ({}); create a new work order instead",
+                        work_order_id,
+                        wo.status.as_db_str()
+                    ));
+                }
+                WorkOrderStatus::Proposed => {
+                    return Err(format!(
+                        "Work order {} is a proposal; approve it with approve_proposals first",
+                        work_order_id
+                    ));
+                }
+                WorkOrderStatus::Dismissed => {
+                    return Err(format!(
+                        "Work order {} is a dismissed proposal; create a new work order instead",
+                        work_order_id
+                    ));
+                }
+            }
+            if wo.priority == priority {
+                return Err(format!(
+                    "Work order {} is already at {} priority",
+                    work_order_id,
+                    priority.as_str()
+                ));
+            }
+
+            let investigation = store
+                .get_investigation(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to load investigation: {}", e))?;
+            if let Some(cap) = investigation
+                .priority_cap
+                .filter(|cap| priority.is_above(*cap))
+            {
+                return Err(format!(
+                    "Priority '{}' exceeds this investigation's cap of '{}'",
+                    priority.as_str(),
+                    cap.as_str()
+                ));
+            }
+
+            // Bumping the delivery supersedes the queued message; Processors ack
+            // and skip it, and only the copy enqueued below is processed.
+            let updated = store
+                .reprioritize_work_order(work_order_id, priority, args.reason.as_deref())
+                .await
+                .map_err(|e| format!("Failed to reprioritize work order: {}", e))?
+                .ok_or_else(|| {
+                    format!(
+                        "Work order {} was picked up by a Processor before it could be reprioritized",
+                        work_order_id
+                    )
+                })?;
+
+            let mut msg = WorkOrderMessage::from(&updated);
+            msg.correlation_id = ctx.scope.correlation_id.clone();
+            let enqueued = queue
+                .enqueue(&msg, &updated.priority)
+                .await
+                .map_err(|e| format!("Failed to re-enqueue work order: {}", e))?;
+            ctx.services
+                .events
+                .publish(DomainEvent::WorkOrderEnqueued {
+                    investigation_id,
+                    enqueued: enqueued.event(&msg, &updated.priority),
+                })
+                .await;
+
+            tracing::info!(
+                work_order_id = %work_order_id,
+                investigation_id = %investigation_id,
+                from = wo.priority.as_str(),
+                to = priority.as_str(),
+                delivery = updated.delivery,
+                reason = args.reason.as_deref().unwrap_or(""),
+                "Work order reprioritized"
+            );
+
+            Ok(json!({
+                "work_order_id": work_order_id.to_string(),
+                "previous_priority": wo.priority.as_str(),
+                "priority": updated.priority.as_str(),
+                "status": updated.status.as_db_str(),
+                "message": format!(
+                    "Work order moved to the {} priority queue.",
+                    updated.priority.as_str()
+                ),
+            }))
+        })
+    })
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::RelationshipId;
+
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    relationship_id: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let rel_id: RelationshipId = args
+                .relationship_id
+                .parse::<uuid::Uuid>()
+                .map(RelationshipId::from_uuid)
+                .map_err(|e| format!("Invalid relationship_id: {}", e))?;
+
+            let current = ctx
+                .services
+                .graph
+                .get_relationship(rel_id)
+                .await
+                .map_err(|e| format!("Failed to load relationship: {}", e))?;
+
+            if current.source_entity_id == current.target_entity_id {
+                return Err(
+                    "Relationship is self-referential; reversing it changes nothing.".into(),
+                );
+            }
+
+            // Shared locks keep a concurrent merge from moving the edge mid-reversal.
+            let (guard, _) = lock_entities(
+                &ctx,
+                &[current.source_entity_id, current.target_entity_id],
+                LockMode::Shared,
+            )
+            .await?;
+            let reversed = ctx.services.graph.reverse_relationship(rel_id).await;
+            release(guard).await;
+            let reversed =
+                reversed.map_err(|e| format!("Failed to reverse relationship: {}", e))?;
+
+            tracing::info!(
+                relationship_id = %rel_id,
+                source = %reversed.source_entity_id,
+                target = %reversed.target_entity_id,
+                reason = args.reason.as_deref().unwrap_or(""),
+                "Relationship direction reversed"
+            );
+
+            Ok(json!({
+                "relationship_id": reversed.id.to_string(),
+                "source_entity_id": reversed.source_entity_id.to_string(),
+                "target_entity_id": reversed.target_entity_id.to_string(),
+                "description": reversed.description,
+                "message": "Relationship reversed. ID, properties, and supporting claims are unchanged."
+            }))
+        })
+    })
+}
@@ -3,7 +3,12 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use autosint_common::types::{normalize_tags, Assessment, FeedbackSummary};
+
+use crate::tools::format::{
+    date, first_sentence, render_results, text, Formatted, FormattedHandler,
+};
+use crate::tools::registry::ToolHandlerContext;
 use crate::tools::truncation::truncate_search_results;
 
 #[derive(Deserialize)]
@@ -11,19 +16,23 @@ struct Args {
     query: String,
     #[serde(default)]
     limit: Option<i64>,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
-pub fn handler() -> ToolHandler {
+pub fn handler() -> FormattedHandler {
     Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
             let args: Args =
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
 
-            let store = ctx.store.as_ref().ok_or_else(|| {
+            let tags = normalize_tags(&args.tags)?;
+
+            let store = ctx.services.store.as_ref().ok_or_else(|| {
                 "Assessment search not available (store not configured)".to_string()
             })?;
 
-            let emb_client = ctx.embedding_client.as_ref().ok_or_else(|| {
+            let emb_client = ctx.services.embedding_client.as_ref().ok_or_else(|| {
                 "Assessment search not available (embedding client not configured)".to_string()
             })?;
 
@@ -34,11 +43,25 @@ pub fn handler() -> ToolHandler {
 
             let limit = args.limit.unwrap_or(5);
 
-            let results = store
-                .search_assessments(query_embedding, limit)
+            let results = ctx
+                .reads()
+                .search_assessments(store, query_embedding, &tags, limit)
                 .await
                 .map_err(|e| format!("Assessment search failed: {}", e))?;
 
+            // Feedback only adds caveats; the results stand without it.
+            let ids: Vec<_> = results
+                .iter()
+                .map(|(assessment, _)| assessment.id)
+                .collect();
+            let feedback = store
+                .assessment_feedback_summaries(&ids)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "Failed to load assessment feedback");
+                    Default::default()
+                });
+
             let items: Vec<Value> = results
                 .iter()
                 .map(|(assessment, score)| {
@@ -49,20 +72,105 @@ pub fn handler() -> ToolHandler {
                         .and_then(|v| v.as_str())
                         .unwrap_or("[no summary]");
 
-                    json!({
+                    let mut item = json!({
                         "id": assessment.id.to_string(),
                         "investigation_id": assessment.investigation_id.to_string(),
                         "confidence": assessment.confidence.as_db_str(),
                         "summary": summary,
+                        "tags": assessment.tags,
                         "created_at": assessment.created_at.to_rfc3339(),
                         "score": score,
-                    })
+                    });
+                    insert_feedback(&mut item, feedback.get(&assessment.id));
+                    insert_review_flag(&mut item, assessment);
+                    item
                 })
                 .collect();
 
             let mut result = json!({ "results": items });
-            truncate_search_results(&mut result, &ctx.tool_result_limits);
-            Ok(result)
+            truncate_search_results(&mut result, &ctx.services.tool_result_limits);
+            Ok(Formatted::new(result, summarize))
         })
     })
 }
+
+/// One line per assessment: the first sentence of its summary, confidence,
+/// date and tags, any review flag or caveat, and its ID.
+fn summarize(result: &Value) -> String {
+    render_results(result, "assessments", |assessment| {
+        let mut details = vec![
+            format!("{} confidence", text(assessment, "confidence")),
+            date(assessment, "created_at").to_string(),
+        ];
+        let tags: Vec<&str> = assessment["tags"]
+            .as_array()
+            .map(|t| t.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        if !tags.is_empty() {
+            details.push(format!("tags {}", tags.join(", ")));
+        }
+        if assessment["needs_review"] == true {
+            details.push("needs review".into());
+        }
+        let mut line = format!(
+            "{} ({}) [{}]",
+            first_sentence(text(assessment, "summary")),
+            details.join(", "),
+            text(assessment, "id")
+        );
+        let caveat = text(assessment, "caveat");
+        if !caveat.is_empty() {
+            line.push(' ');
+            line.push_str(caveat);
+        }
+        line
+    })
+}
+
+/// Add an assessment's feedback counts to its tool result, with a caveat when
+/// readers rated it less than accurate.
+pub(super) fn insert_feedback(item: &mut Value, summary: Option<&FeedbackSummary>) {
+    let Some(summary) = summary else {
+        return;
+    };
+    item["feedback"] = json!(summary);
+    if let Some(caveat) = summary.caveat() {
+        item["caveat"] = json!(caveat);
+    }
+}
+
+/// Mark an assessment's tool result when a claim it cites has been disputed
+/// since it was produced.
+pub(super) fn insert_review_flag(item: &mut Value, assessment: &Assessment) {
+    if assessment.needs_review {
+        item["needs_review"] = json!(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::FeedbackRating;
+
+    #[test]
+    fn test_insert_feedback() {
+        let mut item = json!({ "id": "a" });
+        insert_feedback(&mut item, None);
+        assert!(item.get("feedback").is_none());
+
+        let mut praised = FeedbackSummary::default();
+        praised.add(FeedbackRating::Accurate, 2);
+        insert_feedback(&mut item, Some(&praised));
+        assert_eq!(item["feedback"]["accurate"], 2);
+        assert!(item.get("caveat").is_none());
+
+        let mut disputed = praised;
+        disputed.add(FeedbackRating::Inaccurate, 1);
+        insert_feedback(&mut item, Some(&disputed));
+        assert_eq!(item["feedback"]["inaccurate"], 1);
+        let caveat = item["caveat"].as_str().unwrap();
+        assert!(caveat.starts_with("Caveat:"), "{}", caveat);
+        assert!(caveat.contains("1 inaccurate"), "{}", caveat);
+    }
+}
@@ -3,12 +3,19 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::types::{AttributionDepth, InformationType};
+use autosint_common::types::{parse_trust_level, AttributionDepth, Claim, InformationType};
 use autosint_common::EntityId;
 
-use crate::graph::{ClaimSearchParams, SearchMode};
-use crate::tools::registry::{ToolHandler, ToolHandlerContext};
-use crate::tools::truncation::{truncate_claim_previews, truncate_search_results};
+use crate::graph::{ClaimSearchParams, SearchCursor, SearchMode};
+use crate::tools::format::{
+    date, first_sentence, render_results, text, Formatted, FormattedHandler,
+};
+use crate::tools::registry::ToolHandlerContext;
+use crate::tools::truncation::{
+    insert_claim_references, search_page_size, truncate_claim_previews, truncate_search_results,
+};
+
+use super::create_claim::{claim_language, claim_region};
 
 #[derive(Deserialize)]
 struct Args {
@@ -27,13 +34,23 @@ struct Args {
     #[serde(default)]
     information_type: Option<String>,
     #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    region: Option<String>,
+    #[serde(default)]
+    min_trust: Option<Value>,
+    #[serde(default)]
     #[allow(dead_code)]
     sort_by: Option<String>,
     #[serde(default)]
     limit: Option<u32>,
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    include_original: bool,
 }
 
-pub fn handler() -> ToolHandler {
+pub fn handler() -> FormattedHandler {
     Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
             let args: Args =
@@ -104,11 +121,36 @@ pub fn handler() -> ToolHandler {
                 })
                 .transpose()?;
 
+            let min_trust = args
+                .min_trust
+                .as_ref()
+                .map(parse_trust_level)
+                .transpose()
+                .map_err(|e| format!("Invalid min_trust: {}", e))?;
+
+            // Regions are matched in the form claims store them.
+            let language = claim_language(args.language.as_deref())?;
+            let region = claim_region(&ctx, args.region.as_deref()).await;
+
+            let cursor = args
+                .cursor
+                .as_deref()
+                .map(SearchCursor::decode)
+                .transpose()
+                .map_err(|e| e.to_string())?;
+
             // Determine search mode and compute embedding if doing semantic search.
             let (mode, query_embedding) = if let Some(ref query) = args.query {
-                if let Some(ref emb_client) = ctx.embedding_client {
+                if let Some(ref emb_client) = ctx.services.embedding_client {
                     match emb_client.embed_single(query).await {
                         Ok(emb) => (Some(SearchMode::Semantic), Some(emb)),
+                        Err(e) if cursor.is_some() => {
+                            // A semantic cursor means nothing to a keyword search.
+                            return Err(format!(
+                                "Failed to embed claim query, cannot continue from cursor: {}",
+                                e
+                            ));
+                        }
                         Err(e) => {
                             tracing::warn!(error = %e, "Failed to embed claim query, using keyword");
                             (Some(SearchMode::Keyword), None)
@@ -121,7 +163,7 @@ pub fn handler() -> ToolHandler {
                 (None, None)
             };
 
-            let params = ClaimSearchParams {
+            let mut params = ClaimSearchParams {
                 query: args.query,
                 mode,
                 published_after,
@@ -130,19 +172,43 @@ pub fn handler() -> ToolHandler {
                 referenced_entity_id,
                 attribution_depth,
                 information_type,
-                limit: args.limit,
+                language,
+                region,
+                min_trust,
+                ingested_before: None,
+                limit: Some(search_page_size(
+                    args.limit,
+                    &ctx.services.tool_result_limits,
+                )),
+                cursor,
             };
 
-            let results = ctx
-                .graph
-                .search_claims(&params, query_embedding)
+            let page = ctx
+                .reads()
+                .search_claims(&mut params, query_embedding)
                 .await
                 .map_err(|e| format!("Claim search failed: {}", e))?;
 
-            let items: Vec<Value> = results
+            // Citation counts only add context; the results stand without them.
+            let citations = match &ctx.services.store {
+                Some(store) => {
+                    let ids: Vec<_> = page.results.iter().map(|r| r.item.id).collect();
+                    ctx.reads()
+                        .count_assessments_citing_claims(store, &ids)
+                        .await
+                        .unwrap_or_else(|e| {
+                            tracing::warn!(error = %e, "Failed to count claim citations");
+                            Default::default()
+                        })
+                }
+                None => Default::default(),
+            };
+
+            let items: Vec<Value> = page
+                .results
                 .iter()
                 .map(|r| {
-                    json!({
+                    let mut item = json!({
                         "id": r.item.id.to_string(),
                         "content": r.item.content,
                         "source_entity_id": r.item.source_entity_id.to_string(),
@@ -150,15 +216,103 @@ pub fn handler() -> ToolHandler {
                         "attribution_depth": format!("{:?}", r.item.attribution_depth).to_lowercase(),
                         "information_type": format!("{:?}", r.item.information_type).to_lowercase(),
                         "raw_source_link": r.item.raw_source_link,
+                        "citation": r.item.citation,
+                        "language": r.item.language,
+                        "region": r.item.region,
+                        "source_trust_level": r.item.source_trust_level,
                         "score": r.score,
-                    })
+                    });
+                    if r.item.disputed_count > 0 {
+                        item["disputed_count"] = json!(r.item.disputed_count);
+                    }
+                    if let Some(count) = citations.get(&r.item.id) {
+                        item["cited_in_assessments"] = json!(count);
+                    }
+                    if args.include_original {
+                        insert_original(&mut item, &r.item);
+                    }
+                    insert_claim_references(
+                        &mut item,
+                        &r.item.referenced_entity_ids,
+                        &ctx.services.tool_result_limits,
+                    );
+                    item
                 })
                 .collect();
 
             let mut result = json!({ "results": items });
-            truncate_search_results(&mut result, &ctx.tool_result_limits);
-            truncate_claim_previews(&mut result, &ctx.tool_result_limits);
-            Ok(result)
+            if let Some(cursor) = &page.next_cursor {
+                result["next_cursor"] = json!(cursor.encode());
+            }
+            truncate_search_results(&mut result, &ctx.services.tool_result_limits);
+            truncate_claim_previews(&mut result, &ctx.services.tool_result_limits);
+            Ok(Formatted::new(result, summarize))
         })
     })
 }
+
+/// One line per claim: its first sentence, how it was sourced, and the
+/// claim and source IDs.
+fn summarize(result: &Value) -> String {
+    render_results(result, "claims", |claim| {
+        let mut details = vec![
+            text(claim, "attribution_depth").to_string(),
+            text(claim, "information_type").to_string(),
+            format!("published {}", date(claim, "published_timestamp")),
+        ];
+        if let Some(trust) = claim["source_trust_level"].as_u64() {
+            details.push(format!("source trust {}", trust));
+        }
+        if let Some(count) = claim["disputed_count"].as_u64() {
+            details.push(format!("disputed {} times", count));
+        }
+        if let Some(count) = claim["cited_in_assessments"].as_u64() {
+            details.push(format!("cited in {} assessments", count));
+        }
+        format!(
+            "{} ({}) [{}; source {}]",
+            first_sentence(text(claim, "content")),
+            details.join(", "),
+            text(claim, "id"),
+            text(claim, "source_entity_id")
+        )
+    })
+}
+
+/// Add a claim's source-language wording to its search result, for claims
+/// written from a translation.
+fn insert_original(item: &mut Value, claim: &Claim) {
+    if let Some(original) = &claim.original_content {
+        item["original_content"] = json!(original);
+        item["original_language"] = json!(claim.original_language);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autosint_common::types::{AttributionDepth, InformationType};
+    use autosint_common::EntityId;
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn test_insert_original_only_for_translated_claims() {
+        let mut claim = Claim::new(
+            "The vessel was reflagged to Panama.".into(),
+            Utc::now(),
+            AttributionDepth::Primary,
+            InformationType::Assertion,
+            EntityId::new(),
+        );
+        let mut item = json!({"content": claim.content});
+        insert_original(&mut item, &claim);
+        assert!(item.get("original_content").is_none());
+
+        claim.original_content = Some("Судно сменило флаг на панамский.".into());
+        claim.original_language = Some("ru".into());
+        insert_original(&mut item, &claim);
+        assert_eq!(item["original_content"], "Судно сменило флаг на панамский.");
+        assert_eq!(item["original_language"], "ru");
+    }
+}
@@ -3,9 +3,12 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::graph::{EntitySearchParams, SearchMode};
-use crate::tools::registry::{ToolHandler, ToolHandlerContext};
-use crate::tools::truncation::truncate_search_results;
+use autosint_common::types::{Entity, FeatureFlag};
+
+use crate::graph::{fresh, EntitySearchParams, SearchCursor, SearchMode, SearchPage, SearchResult};
+use crate::tools::format::{first_sentence, render_results, text, Formatted, FormattedHandler};
+use crate::tools::registry::ToolHandlerContext;
+use crate::tools::truncation::{search_page_size, truncate_search_results};
 
 #[derive(Deserialize)]
 struct Args {
@@ -16,14 +19,30 @@ struct Args {
     kind: Option<String>,
     #[serde(default)]
     limit: Option<u32>,
+    #[serde(default)]
+    cursor: Option<String>,
 }
 
-pub fn handler() -> ToolHandler {
+pub fn handler() -> FormattedHandler {
     Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
             let args: Args =
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
 
+            let cursor = args
+                .cursor
+                .as_deref()
+                .map(SearchCursor::decode)
+                .transpose()
+                .map_err(|e| e.to_string())?;
+
+            // The shortlist only answers first pages.
+            if cursor.is_none() {
+                if let Some(hit) = shortlist_hit(&args, &ctx).await {
+                    return Ok(Formatted::new(hit, summarize));
+                }
+            }
+
             let mode = match args.mode.as_deref() {
                 Some("semantic") | None => SearchMode::Semantic,
                 Some("keyword") => SearchMode::Keyword,
@@ -37,76 +56,183 @@ pub fn handler() -> ToolHandler {
 
             // Compute embedding for semantic search.
             let query_embedding = if matches!(mode, SearchMode::Semantic) {
-                if let Some(ref emb_client) = ctx.embedding_client {
+                if let Some(ref emb_client) = ctx.services.embedding_client {
                     match emb_client.embed_single(&args.query).await {
                         Ok(emb) => Some(emb),
+                        Err(e) if cursor.is_some() => {
+                            // A semantic cursor means nothing to a keyword search.
+                            return Err(format!(
+                                "Failed to embed search query, cannot continue from cursor: {}",
+                                e
+                            ));
+                        }
                         Err(e) => {
                             tracing::warn!(error = %e, "Failed to embed search query, falling back to keyword");
                             // Fall back: we'll do keyword search instead
-                            return do_keyword_search(&args, &ctx).await;
+                            return do_keyword_search(&args, cursor, &ctx).await;
                         }
                     }
                 } else {
-                    return do_keyword_search(&args, &ctx).await;
+                    return do_keyword_search(&args, cursor, &ctx).await;
                 }
             } else {
                 None
             };
 
-            let params = EntitySearchParams {
+            let mut params = EntitySearchParams {
                 query: args.query,
                 mode,
                 kind_filter: args.kind,
                 updated_after: None,
                 updated_before: None,
-                limit: args.limit,
+                created_before: None,
+                limit: Some(search_page_size(
+                    args.limit,
+                    &ctx.services.tool_result_limits,
+                )),
+                cursor,
             };
 
-            let results = ctx
-                .graph
-                .search_entities(&params, query_embedding)
+            let mut page = ctx
+                .reads()
+                .search_entities(&mut params, query_embedding.clone())
                 .await
                 .map_err(|e| format!("Search failed: {}", e))?;
-
-            let items: Vec<Value> = results
-                .iter()
-                .map(|r| {
-                    json!({
-                        "id": r.item.id.to_string(),
-                        "canonical_name": r.item.canonical_name,
-                        "kind": r.item.kind,
-                        "summary": r.item.summary,
-                        "aliases": r.item.aliases,
-                        "is_stub": r.item.is_stub,
-                        "score": r.score,
-                    })
-                })
-                .collect();
-
-            let mut result = json!({ "results": items });
-            truncate_search_results(&mut result, &ctx.tool_result_limits);
-            Ok(result)
+            if let Some(embedding) = &query_embedding {
+                if params.cursor.is_none() {
+                    merge_fresh_entities(&mut page, embedding, &params, &ctx).await;
+                }
+            }
+            Ok(render_page(&page, &ctx).await)
         })
     })
 }
 
-async fn do_keyword_search(args: &Args, ctx: &ToolHandlerContext) -> Result<Value, String> {
-    let params = EntitySearchParams {
+/// Add entities too fresh for the vector index to a semantic search's first
+/// page, when `dedup.fresh_entities.semantic_search` and its flag are on: up
+/// to `limit` of them, and on a full page only those outscoring its last hit.
+/// The page may run past `limit` by these; the cursor still follows the index.
+/// Snapshot sessions skip them: the fresh scan has no cutoff.
+async fn merge_fresh_entities(
+    page: &mut SearchPage<Entity>,
+    embedding: &[f32],
+    params: &EntitySearchParams,
+    ctx: &ToolHandlerContext,
+) {
+    let config = &ctx.services.dedup_config.fresh_entities;
+    if ctx.scope.as_of.is_some()
+        || !config.semantic_search
+        || !ctx
+            .services
+            .flags
+            .is_enabled(FeatureFlag::FreshEntitySearch)
+    {
+        return;
+    }
+    let fresh = match ctx
+        .services
+        .graph
+        .fresh_entity_matches(embedding, config)
+        .await
+    {
+        Ok(fresh) => fresh,
+        Err(e) => {
+            tracing::warn!(error = %e, "Fresh entity scan failed, using index results only");
+            return;
+        }
+    };
+
+    let limit = params.limit.unwrap_or(u32::MAX) as usize;
+    let floor = if page.results.len() >= limit {
+        page.results.last().map(|r| r.score)
+    } else {
+        None
+    };
+    let fresh: Vec<SearchResult<Entity>> = fresh
+        .into_iter()
+        .filter(|r| {
+            params
+                .kind_filter
+                .as_ref()
+                .is_none_or(|k| r.item.kind == *k)
+        })
+        .filter(|r| floor.is_none_or(|floor| r.score > floor))
+        .take(limit)
+        .collect();
+    if !fresh.is_empty() {
+        let indexed = std::mem::take(&mut page.results);
+        page.results = fresh::merge_fresh(indexed, fresh);
+    }
+}
+
+/// Answer a query that exactly names an entity this investigation already
+/// resolved from the shortlist, without touching the graph.
+async fn shortlist_hit(args: &Args, ctx: &ToolHandlerContext) -> Option<Value> {
+    let entry = ctx
+        .scope
+        .entity_shortlist
+        .as_ref()?
+        .lookup(&args.query, args.kind.as_deref())
+        .await?;
+    Some(json!({
+        "results": [{
+            "id": entry.entity_id.to_string(),
+            "canonical_name": entry.canonical_name,
+            "kind": entry.kind,
+            "score": 1.0,
+        }],
+        "from_shortlist": true,
+        "message": "Exact name match from this investigation's entity shortlist. Use get_entity for details, or search a broader phrase to query the graph."
+    }))
+}
+
+/// Remember every entity a search surfaced in the investigation's shortlist.
+async fn record_results(ctx: &ToolHandlerContext, results: &[SearchResult<Entity>]) {
+    if let Some(shortlist) = &ctx.scope.entity_shortlist {
+        shortlist
+            .record_entities(results.iter().map(|r| &r.item))
+            .await;
+    }
+}
+
+async fn do_keyword_search(
+    args: &Args,
+    cursor: Option<SearchCursor>,
+    ctx: &ToolHandlerContext,
+) -> Result<Formatted, String> {
+    let mut params = EntitySearchParams {
         query: args.query.clone(),
         mode: SearchMode::Keyword,
         kind_filter: args.kind.clone(),
         updated_after: None,
         updated_before: None,
-        limit: args.limit,
+        created_before: None,
+        limit: Some(search_page_size(
+            args.limit,
+            &ctx.services.tool_result_limits,
+        )),
+        cursor,
     };
 
-    let results = ctx
-        .graph
-        .search_entities(&params, None)
+    let page = ctx
+        .reads()
+        .search_entities(&mut params, None)
         .await
         .map_err(|e| format!("Search failed: {}", e))?;
+    Ok(render_page(&page, ctx).await)
+}
+
+async fn render_page(page: &SearchPage<Entity>, ctx: &ToolHandlerContext) -> Formatted {
+    record_results(ctx, &page.results).await;
+
+    let mut result = page_results(page);
+    truncate_search_results(&mut result, &ctx.services.tool_result_limits);
+    Formatted::new(result, summarize)
+}
 
-    let items: Vec<Value> = results
+fn page_results(page: &SearchPage<Entity>) -> Value {
+    let items: Vec<Value> = page
+        .results
         .iter()
         .map(|r| {
             json!({
@@ -122,6 +248,93 @@ async fn do_keyword_search(args: &Args, ctx: &ToolHandlerContext) -> Result<Valu
         .collect();
 
     let mut result = json!({ "results": items });
-    truncate_search_results(&mut result, &ctx.tool_result_limits);
-    Ok(result)
+    if let Some(cursor) = &page.next_cursor {
+        result["next_cursor"] = json!(cursor.encode());
+    }
+    result
+}
+
+/// One line per entity: name, kind, the first sentence of its summary, ID.
+fn summarize(result: &Value) -> String {
+    render_results(result, "entities", |entity| {
+        let stub = if entity["is_stub"] == true {
+            ", stub"
+        } else {
+            ""
+        };
+        let mut line = format!(
+            "{} ({}{})",
+            text(entity, "canonical_name"),
+            text(entity, "kind"),
+            stub
+        );
+        let summary = first_sentence(text(entity, "summary"));
+        if !summary.is_empty() {
+            line.push_str(" — ");
+            line.push_str(&summary);
+        }
+        line.push_str(&format!(" [{}]", text(entity, "id")));
+        line
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use autosint_common::config::ResponseFormat;
+
+    use crate::llm::tokens::HeuristicCounter;
+    use crate::llm::TokenCounter;
+
+    use super::*;
+
+    /// A page of 20 vessels and their operators, as a semantic search
+    /// returns them.
+    fn seeded_page() -> SearchPage<Entity> {
+        let results = (0..20)
+            .map(|i| {
+                let mut entity = Entity::new(format!("Tanker Crius {}", i), "vessel".into());
+                entity.aliases = vec![format!("CRIUS-{}", i), format!("Криус {}", i)];
+                entity.summary = Some(format!(
+                    "Crude oil tanker {} built in 2004 and flagged to Gabon since March 2024. \
+                     It loaded at Primorsk and Ust-Luga several times in 2024 with its AIS \
+                     transponder switched off. Its registered manager is a Dubai company \
+                     set up two months before the vessel was bought.",
+                    i
+                ));
+                SearchResult {
+                    item: entity,
+                    score: 0.9 - i as f64 * 0.01,
+                }
+            })
+            .collect();
+        SearchPage {
+            results,
+            next_cursor: None,
+            size: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_summary_is_much_smaller_than_json() {
+        let page = seeded_page();
+        let json = Formatted::new(page_results(&page), summarize)
+            .into_content(ResponseFormat::Json, 100_000);
+        let summary = Formatted::new(page_results(&page), summarize)
+            .into_content(ResponseFormat::Summary, 100_000);
+
+        let json_tokens = HeuristicCounter.count(&json);
+        let summary_tokens = HeuristicCounter.count(&summary);
+        assert!(
+            summary_tokens * 2 < json_tokens,
+            "summary {} tokens, JSON {} tokens",
+            summary_tokens,
+            json_tokens
+        );
+
+        // Still enough to chain on: every ID, in order.
+        assert!(summary.starts_with("20 entities:\n1. Tanker Crius 0 (vessel) — Crude oil tanker 0 built in 2004 and flagged to Gabon since March 2024. ["));
+        for result in &page.results {
+            assert!(summary.contains(&result.item.id.to_string()));
+        }
+    }
 }
@@ -3,15 +3,17 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use crate::graph::RelationshipSearchParams;
+use crate::graph::{RelationshipSearchParams, SearchCursor};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
-use crate::tools::truncation::truncate_search_results;
+use crate::tools::truncation::{search_page_size, truncate_search_results};
 
 #[derive(Deserialize)]
 struct Args {
     query: String,
     #[serde(default)]
     limit: Option<u32>,
+    #[serde(default)]
+    cursor: Option<String>,
 }
 
 pub fn handler() -> ToolHandler {
@@ -20,8 +22,15 @@ pub fn handler() -> ToolHandler {
             let args: Args =
                 serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
 
+            let cursor = args
+                .cursor
+                .as_deref()
+                .map(SearchCursor::decode)
+                .transpose()
+                .map_err(|e| e.to_string())?;
+
             // Compute embedding for semantic search.
-            let query_embedding = if let Some(ref emb_client) = ctx.embedding_client {
+            let query_embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
                 match emb_client.embed_single(&args.query).await {
                     Ok(emb) => Some(emb),
                     Err(e) => {
@@ -33,18 +42,24 @@ pub fn handler() -> ToolHandler {
                 None
             };
 
-            let params = RelationshipSearchParams {
+            let mut params = RelationshipSearchParams {
                 query: args.query,
-                limit: args.limit,
+                created_before: None,
+                limit: Some(search_page_size(
+                    args.limit,
+                    &ctx.services.tool_result_limits,
+                )),
+                cursor,
             };
 
-            let results = ctx
-                .graph
-                .search_relationships(&params, query_embedding)
+            let page = ctx
+                .reads()
+                .search_relationships(&mut params, query_embedding)
                 .await
                 .map_err(|e| format!("Relationship search failed: {}", e))?;
 
-            let items: Vec<Value> = results
+            let items: Vec<Value> = page
+                .results
                 .iter()
                 .map(|r| {
                     json!({
@@ -61,7 +76,10 @@ pub fn handler() -> ToolHandler {
                 .collect();
 
             let mut result = json!({ "results": items });
-            truncate_search_results(&mut result, &ctx.tool_result_limits);
+            if let Some(cursor) = &page.next_cursor {
+                result["next_cursor"] = json!(cursor.encode());
+            }
+            truncate_search_results(&mut result, &ctx.services.tool_result_limits);
             Ok(result)
         })
     })
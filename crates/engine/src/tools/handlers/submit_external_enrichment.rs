@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::ingest::enrichment::SubmitError;
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    provider: String,
+    payload: Value,
+    #[serde(default)]
+    ingest: Option<bool>,
+}
+
+/// Submit a job to an asynchronous enrichment provider. Returns as soon as
+/// the provider accepts it; the result arrives later at its callback.
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let enrichment = ctx
+                .services
+                .enrichment
+                .as_ref()
+                .ok_or_else(|| "External enrichment is disabled".to_string())?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "External enrichment not available (no investigation)".to_string()
+            })?;
+            if !args.payload.is_object() {
+                return Err("payload must be a JSON object".to_string());
+            }
+
+            let request = enrichment
+                .submit(
+                    args.provider.trim(),
+                    args.payload,
+                    investigation_id,
+                    ctx.scope.work_order_id,
+                    args.ingest,
+                )
+                .await
+                .map_err(|e| match e {
+                    SubmitError::Store(e) => format!("Failed to record enrichment request: {}", e),
+                    SubmitError::LimitReached(_) => {
+                        format!("{}. Work with what has arrived.", e)
+                    }
+                    e => e.to_string(),
+                })?;
+
+            let message = if request.ingest {
+                "Submitted. The result arrives later, not in this session: it is stored as a \
+                 source document, extracted by an ingest work order, and shown to the Analyst \
+                 at its next cycle. Carry on without waiting for it."
+            } else {
+                "Submitted. The result arrives later, not in this session: it is stored as a \
+                 source document and shown to the Analyst at its next cycle \
+                 (list_enrichment_results). Carry on without waiting for it."
+            };
+            Ok(json!({
+                "request_id": request.id.to_string(),
+                "provider": request.provider,
+                "status": request.status.as_db_str(),
+                "ingest": request.ingest,
+                "expires_at": request.expires_at.to_rfc3339(),
+                "message": message,
+            }))
+        })
+    })
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::types::normalize_tags;
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    tags: Vec<String>,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let records = ctx.services.records.as_ref().ok_or_else(|| {
+                "Investigation tagging not available (store not configured)".to_string()
+            })?;
+            let investigation_id = ctx.scope.investigation_id.ok_or_else(|| {
+                "Investigation tagging not available (no investigation context)".to_string()
+            })?;
+
+            let new_tags = normalize_tags(&args.tags)?;
+
+            let investigation = records
+                .get_investigation(investigation_id)
+                .await
+                .map_err(|e| format!("Failed to get investigation: {}", e))?;
+
+            // Tags are additive; existing ones are kept.
+            let mut tags = investigation.tags;
+            tags.extend(new_tags);
+            let tags = normalize_tags(&tags)?;
+
+            records
+                .set_investigation_tags(investigation_id, &tags)
+                .await
+                .map_err(|e| format!("Failed to tag investigation: {}", e))?;
+
+            Ok(json!({
+                "investigation_id": investigation_id.to_string(),
+                "tags": tags,
+            }))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::types::Investigation;
+
+    use crate::tools::services::stub::StubRecords;
+    use crate::tools::SessionScope;
+
+    #[tokio::test]
+    async fn test_tags_are_merged_and_normalized() {
+        let mut investigation = Investigation::new("Who owns the Crius?".into());
+        investigation.tags = vec!["shipping".into()];
+        let id = investigation.id;
+        let records = Arc::new(StubRecords::with_investigation(investigation));
+        let scope = SessionScope {
+            investigation_id: Some(id),
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(Some(records.clone()), scope).await;
+
+        let result = handler()(json!({"tags": [" sanctions ", "shipping"]}), ctx)
+            .await
+            .unwrap();
+        assert_eq!(result["tags"], json!(["shipping", "sanctions"]));
+        assert_eq!(
+            records.investigation.lock().unwrap().as_ref().unwrap().tags,
+            ["shipping", "sanctions"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_tags_and_missing_context() {
+        let investigation = Investigation::new("Who owns the Crius?".into());
+        let id = investigation.id;
+        let records = Arc::new(StubRecords::with_investigation(investigation));
+
+        let ctx = ToolHandlerContext::stub(Some(records.clone()), SessionScope::default()).await;
+        let err = handler()(json!({"tags": ["sanctions"]}), ctx)
+            .await
+            .unwrap_err();
+        assert!(err.contains("no investigation context"), "{}", err);
+
+        let scope = SessionScope {
+            investigation_id: Some(id),
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(Some(records.clone()), scope).await;
+        let err = handler()(json!({"tags": ["Sanctions"]}), ctx)
+            .await
+            .unwrap_err();
+        assert!(err.contains("lowercase"), "{}", err);
+        assert!(records
+            .investigation
+            .lock()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .tags
+            .is_empty());
+    }
+}
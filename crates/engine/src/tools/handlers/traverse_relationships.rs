@@ -6,8 +6,9 @@ use serde_json::{json, Value};
 use autosint_common::EntityId;
 
 use crate::graph::{TraversalDirection, TraversalParams};
-use crate::tools::registry::{ToolHandler, ToolHandlerContext};
-use crate::tools::truncation::truncate_search_results;
+use crate::tools::format::{first_sentence, render_results, text, Formatted, FormattedHandler};
+use crate::tools::registry::ToolHandlerContext;
+use crate::tools::truncation::{note_result_size, search_page_size, truncate_search_results};
 
 #[derive(Deserialize)]
 struct Args {
@@ -22,7 +23,7 @@ struct Args {
     limit: Option<u32>,
 }
 
-pub fn handler() -> ToolHandler {
+pub fn handler() -> FormattedHandler {
     Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
         Box::pin(async move {
             let args: Args =
@@ -51,19 +52,26 @@ pub fn handler() -> ToolHandler {
             // semantic filtering — we filter client-side if needed.
             let _ = args.description_query; // Reserved for future semantic filtering.
 
-            let params = TraversalParams {
+            let mut params = TraversalParams {
                 direction,
                 min_weight: args.min_weight,
-                limit: args.limit,
+                as_of: None,
+                // No more than the result limit, so the graph's own sentinel
+                // says whether relationships were left out.
+                limit: Some(search_page_size(
+                    args.limit,
+                    &ctx.services.tool_result_limits,
+                )),
             };
 
-            let results = ctx
-                .graph
-                .traverse_relationships(entity_id, &params)
+            let page = ctx
+                .reads()
+                .traverse_relationships(entity_id, &mut params)
                 .await
                 .map_err(|e| format!("Traversal failed: {}", e))?;
 
-            let items: Vec<Value> = results
+            let items: Vec<Value> = page
+                .results
                 .iter()
                 .map(|(rel, target)| {
                     json!({
@@ -87,8 +95,39 @@ pub fn handler() -> ToolHandler {
                 .collect();
 
             let mut result = json!({ "results": items });
-            truncate_search_results(&mut result, &ctx.tool_result_limits);
-            Ok(result)
+            truncate_search_results(&mut result, &ctx.services.tool_result_limits);
+            note_result_size(&mut result, &page.size);
+            Ok(Formatted::new(result, summarize))
         })
     })
 }
+
+/// One line per connection: which way it points, the connected entity, the
+/// relationship's description and strength, and both IDs.
+fn summarize(result: &Value) -> String {
+    render_results(result, "relationships", |item| {
+        let rel = &item["relationship"];
+        let other = &item["connected_entity"];
+        let arrow = if rel["bidirectional"] == true {
+            "↔"
+        } else if text(rel, "source_entity_id") == text(other, "id") {
+            "←"
+        } else {
+            "→"
+        };
+        let strength: Vec<String> = ["weight", "confidence"]
+            .into_iter()
+            .filter_map(|key| rel[key].as_f64().map(|v| format!("{} {}", key, v)))
+            .collect();
+        format!(
+            "{} {} ({}) — {} ({}) [entity {}; relationship {}]",
+            arrow,
+            text(other, "canonical_name"),
+            text(other, "kind"),
+            first_sentence(text(rel, "description")),
+            strength.join(", "),
+            text(other, "id"),
+            text(rel, "id")
+        )
+    })
+}
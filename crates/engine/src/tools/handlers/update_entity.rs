@@ -1,12 +1,15 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use autosint_common::types::{parse_identifiers, parse_trust_level, TRUST_LEVEL_PROPERTY};
 use autosint_common::EntityId;
 
 use crate::graph::conversions::embedding_text_for_entity;
 use crate::graph::EntityUpdate;
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
 #[derive(Deserialize)]
@@ -23,7 +26,33 @@ struct Args {
     #[serde(default)]
     is_stub: Option<bool>,
     #[serde(default)]
-    properties: Option<std::collections::HashMap<String, Value>>,
+    properties: Option<HashMap<String, Value>>,
+    #[serde(default)]
+    remove_properties: Vec<String>,
+    #[serde(default)]
+    trust_level: Option<Value>,
+    #[serde(default)]
+    identifiers: HashMap<String, String>,
+}
+
+/// Fold a `trust_level` argument into `properties`, rejecting an invalid
+/// trust level from either.
+pub(crate) fn trust_level_properties(
+    mut properties: Option<HashMap<String, Value>>,
+    trust_level: Option<Value>,
+) -> Result<Option<HashMap<String, Value>>, String> {
+    if let Some(level) = trust_level {
+        properties
+            .get_or_insert_with(HashMap::new)
+            .insert(TRUST_LEVEL_PROPERTY.into(), level);
+    }
+    if let Some(level) = properties
+        .as_ref()
+        .and_then(|properties| properties.get(TRUST_LEVEL_PROPERTY))
+    {
+        parse_trust_level(level).map_err(|e| format!("Invalid trust_level: {}", e))?;
+    }
+    Ok(properties)
 }
 
 pub fn handler() -> ToolHandler {
@@ -37,13 +66,32 @@ pub fn handler() -> ToolHandler {
                 .parse::<uuid::Uuid>()
                 .map(EntityId::from_uuid)
                 .map_err(|e| format!("Invalid entity_id: {}", e))?;
+            let properties = trust_level_properties(args.properties, args.trust_level)?;
+            let identifiers = parse_identifiers(&args.identifiers)
+                .map_err(|e| format!("Invalid identifiers: {}", e))?;
+
+            if let Some(key) = properties.as_ref().and_then(|properties| {
+                args.remove_properties
+                    .iter()
+                    .find(|key| properties.contains_key(*key))
+            }) {
+                return Err(format!(
+                    "Property '{}' is both set and removed; pick one",
+                    key
+                ));
+            }
+
+            // Held across the read-modify-write so a merge can't delete the entity mid-update.
+            let (guard, ids) = lock_entities(&ctx, &[entity_id], LockMode::Shared).await?;
+            let entity_id = ids[0];
 
             // Check if name or summary changed — recompute embedding if so.
             let needs_reembed = args.canonical_name.is_some() || args.summary.is_some();
             let embedding = if needs_reembed {
-                if let Some(ref emb_client) = ctx.embedding_client {
+                if let Some(ref emb_client) = ctx.services.embedding_client {
                     // Get current entity to merge with updates for embedding text.
                     let current = ctx
+                        .services
                         .graph
                         .get_entity(entity_id)
                         .await
@@ -74,22 +122,67 @@ pub fn handler() -> ToolHandler {
                 kind: args.kind,
                 summary: args.summary,
                 is_stub: args.is_stub,
-                properties: args.properties,
+                properties,
+                remove_properties: args.remove_properties,
+                identifiers,
             };
 
             let updated = ctx
+                .services
                 .graph
                 .update_entity(entity_id, &update, embedding)
-                .await
-                .map_err(|e| format!("Failed to update entity: {}", e))?;
+                .await;
+            release(guard).await;
+            let updated = updated.map_err(|e| format!("Failed to update entity: {}", e))?;
 
             Ok(json!({
                 "entity_id": updated.id.to_string(),
                 "canonical_name": updated.canonical_name,
                 "kind": updated.kind,
                 "summary": updated.summary,
+                "trust_level": updated.trust_level(),
+                "identifiers": updated.identifiers,
                 "message": "Entity updated successfully."
             }))
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_trust_level_properties() {
+        assert_eq!(trust_level_properties(None, None), Ok(None));
+
+        let properties = trust_level_properties(None, Some(json!(4)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(properties[TRUST_LEVEL_PROPERTY], 4);
+
+        // The argument wins over the same key in properties.
+        let set = HashMap::from([
+            (TRUST_LEVEL_PROPERTY.to_string(), json!(2)),
+            ("founded_year".to_string(), json!(1851)),
+        ]);
+        let properties = trust_level_properties(Some(set.clone()), Some(json!(5)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(properties[TRUST_LEVEL_PROPERTY], 5);
+        assert_eq!(properties["founded_year"], 1851);
+        assert_eq!(
+            trust_level_properties(Some(set), None)
+                .unwrap()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert!(trust_level_properties(None, Some(json!(9))).is_err());
+        let invalid = HashMap::from([(TRUST_LEVEL_PROPERTY.to_string(), json!("high"))]);
+        assert!(trust_level_properties(Some(invalid), None).is_err());
+    }
+}
@@ -4,13 +4,18 @@ use std::sync::Arc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use autosint_common::types::{AttributionDepth, Claim, InformationType};
+use autosint_common::types::{AttributionDepth, Claim, ClaimCreated, DomainEvent, InformationType};
 use autosint_common::EntityId;
 
 use crate::graph::conversions::{embedding_text_for_claim, embedding_text_for_entity};
 use crate::graph::EntityUpdate;
+use crate::tools::entity_locks::{lock_entities, release, LockMode};
+use crate::tools::pii::{redaction_note, scrub_claim_content};
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
+use super::create_claim::source_trust_level;
+use super::update_entity::trust_level_properties;
+
 #[derive(Deserialize)]
 struct Args {
     entity_id: String,
@@ -64,6 +69,7 @@ pub fn handler() -> ToolHandler {
                 .parse::<uuid::Uuid>()
                 .map(EntityId::from_uuid)
                 .map_err(|e| format!("Invalid claim_source_entity_id: {}", e))?;
+            let properties = trust_level_properties(args.properties, None)?;
 
             let attribution_depth = match args.claim_attribution_depth.as_str() {
                 "primary" => AttributionDepth::Primary,
@@ -100,11 +106,26 @@ pub fn handler() -> ToolHandler {
                         )
                     })?;
 
+            // Scrubbed before the entity update so a rejected claim leaves the
+            // entity untouched.
+            let (claim_content, redactions) = scrub_claim_content(
+                &ctx,
+                &args.claim_content,
+                args.claim_raw_source_link.as_deref(),
+            )
+            .await?;
+
+            // Held across both writes so a concurrent merge can't split them.
+            let (guard, ids) =
+                lock_entities(&ctx, &[entity_id, source_entity_id], LockMode::Shared).await?;
+            let (entity_id, source_entity_id) = (ids[0], ids[1]);
+
             // 1. Update the entity.
             let needs_reembed = args.canonical_name.is_some() || args.summary.is_some();
             let entity_embedding = if needs_reembed {
-                if let Some(ref emb_client) = ctx.embedding_client {
+                if let Some(ref emb_client) = ctx.services.embedding_client {
                     let current = ctx
+                        .services
                         .graph
                         .get_entity(entity_id)
                         .await
@@ -135,18 +156,21 @@ pub fn handler() -> ToolHandler {
                 kind: args.kind,
                 summary: args.summary,
                 is_stub: args.is_stub,
-                properties: args.properties,
+                properties,
+                remove_properties: Vec::new(),
+                identifiers: Default::default(),
             };
 
             let updated_entity = ctx
+                .services
                 .graph
                 .update_entity(entity_id, &update, entity_embedding)
                 .await
                 .map_err(|e| format!("Failed to update entity: {}", e))?;
 
             // 2. Create the change claim.
-            let claim_embedding = if let Some(ref emb_client) = ctx.embedding_client {
-                let text = embedding_text_for_claim(&args.claim_content);
+            let claim_embedding = if let Some(ref emb_client) = ctx.services.embedding_client {
+                let text = embedding_text_for_claim(&claim_content);
                 match emb_client.embed_single(&text).await {
                     Ok(emb) => Some(emb),
                     Err(e) => {
@@ -159,7 +183,7 @@ pub fn handler() -> ToolHandler {
             };
 
             let mut claim = Claim::new(
-                args.claim_content,
+                claim_content,
                 published_timestamp,
                 attribution_depth,
                 information_type,
@@ -167,23 +191,43 @@ pub fn handler() -> ToolHandler {
             );
             claim.referenced_entity_ids = vec![entity_id];
             claim.raw_source_link = args.claim_raw_source_link;
+            claim.investigation_id = ctx.scope.investigation_id;
+            claim.monitor_id = ctx.scope.monitor_id;
+            claim.source_trust_level = source_trust_level(&ctx, source_entity_id).await;
+            claim.redactions = redactions;
 
             let created_claim = ctx
+                .services
                 .graph
                 .create_claim(&claim, claim_embedding)
-                .await
+                .await;
+            release(guard).await;
+            let created_claim = created_claim
                 .map_err(|e| format!("Entity updated but claim creation failed: {}", e))?;
-
-            ctx.session_counters
+            ctx.services
+                .events
+                .publish(DomainEvent::ClaimCreated(ClaimCreated::new(
+                    &created_claim,
+                    ctx.scope.work_order_id,
+                )))
+                .await;
+
+            ctx.scope
+                .session_counters
                 .claims_created
                 .fetch_add(1, Ordering::Relaxed);
 
-            Ok(json!({
+            let mut result = json!({
                 "entity_id": updated_entity.id.to_string(),
                 "canonical_name": updated_entity.canonical_name,
                 "claim_id": created_claim.id.to_string(),
                 "message": "Entity updated and change claim created successfully."
-            }))
+            });
+            if !created_claim.redactions.is_empty() {
+                result["redacted"] = redaction_note(&created_claim.redactions);
+            }
+
+            Ok(result)
         })
     })
 }
@@ -48,7 +48,7 @@ pub fn handler() -> ToolHandler {
 
             // Recompute embedding if description changed.
             let embedding = if let Some(ref desc) = args.description {
-                if let Some(ref emb_client) = ctx.embedding_client {
+                if let Some(ref emb_client) = ctx.services.embedding_client {
                     let text = embedding_text_for_relationship(desc);
                     match emb_client.embed_single(&text).await {
                         Ok(emb) => Some(emb),
@@ -70,9 +70,11 @@ pub fn handler() -> ToolHandler {
                 confidence: args.confidence,
                 bidirectional: args.bidirectional,
                 timestamp,
+                weight_recalibrated_at: None,
             };
 
             let updated = ctx
+                .services
                 .graph
                 .update_relationship(rel_id, &update, embedding)
                 .await
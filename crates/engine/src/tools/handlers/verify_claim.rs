@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::ids::ClaimId;
+
+use crate::graph::GraphError;
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+use crate::tools::verification::{self, MAX_SIGNAL_ENTITIES};
+
+#[derive(Deserialize)]
+struct Args {
+    claim_id: String,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let claim_id = args
+                .claim_id
+                .parse::<uuid::Uuid>()
+                .map(ClaimId::from_uuid)
+                .map_err(|e| format!("Invalid claim_id: {}", e))?;
+
+            let graph = &ctx.services.graph;
+            let claim = graph.get_claim(claim_id).await.map_err(|e| match e {
+                GraphError::NotFound(_) => format!("Claim {} not found", claim_id),
+                other => format!("Failed to get claim: {}", other),
+            })?;
+
+            // Names of the entities the claim is about sharpen the search.
+            let mut names = Vec::new();
+            for &id in claim.referenced_entity_ids.iter().take(MAX_SIGNAL_ENTITIES) {
+                match graph.get_entity(id).await {
+                    Ok(entity) => names.push(entity.canonical_name),
+                    Err(GraphError::NotFound(_)) => {}
+                    Err(e) => return Err(format!("Failed to get entity {}: {}", id, e)),
+                }
+            }
+
+            let limits = &ctx.services.tool_result_limits;
+            let related = verification::related_claims(
+                graph,
+                &claim,
+                ctx.services.verification.max_graph_claims,
+                limits.max_claim_preview_chars as usize,
+            )
+            .await?;
+            let evidence = verification::gather_evidence(&ctx, &claim, &names).await;
+
+            Ok(json!({
+                "claim": {
+                    "id": claim.id.to_string(),
+                    "content": claim.content,
+                    "source_entity_id": claim.source_entity_id.to_string(),
+                    "source_domain": verification::source_domain(&claim),
+                    "source_trust_level": claim.source_trust_level,
+                    "disputed_count": claim.disputed_count,
+                },
+                "query": evidence.query,
+                "external": evidence.sources,
+                "skipped": evidence.skipped,
+                "notes": evidence.notes,
+                "graph": {
+                    "other_claims": related,
+                },
+                "hint": "This is evidence, not a verdict. Read each passage: it may corroborate, \
+                         contradict or only mention the claim. Weigh the sources before relying \
+                         on the claim; cite what you use through claims the Processor extracts.",
+            }))
+        })
+    })
+}
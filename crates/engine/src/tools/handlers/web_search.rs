@@ -4,6 +4,8 @@ use serde::Deserialize;
 use serde_json::{json, Value};
 
 use autosint_common::api::fetch::{SearchRequest, SearchResponse};
+use autosint_common::api::ErrorResponse;
+use autosint_common::types::WEB_SEARCH_PROVIDER;
 
 use crate::tools::registry::{ToolHandler, ToolHandlerContext};
 
@@ -24,24 +26,22 @@ pub fn handler() -> ToolHandler {
 
             let num_results = args.num_results.unwrap_or(MAX_RESULTS).min(MAX_RESULTS);
 
-            let search_url = format!("{}/search", ctx.fetch_base_url.trim_end_matches('/'));
-
             let request = SearchRequest {
                 query: args.query.clone(),
                 num_results: Some(num_results),
             };
 
             let response = ctx
-                .http
-                .post(&search_url)
-                .json(&request)
-                .send()
+                .services
+                .fetch
+                .search(&request)
                 .await
                 .map_err(|e| format!("Search request failed: {}", e))?;
 
             if !response.status().is_success() {
                 let status = response.status();
-                let body = response.text().await.unwrap_or_default();
+                let body =
+                    ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
                 return Err(format!("Search returned {}: {}", status, body));
             }
 
@@ -50,6 +50,10 @@ pub fn handler() -> ToolHandler {
                 .await
                 .map_err(|e| format!("Failed to parse search response: {}", e))?;
 
+            ctx.scope
+                .session_counters
+                .record_outbound(|f| f.record_search(WEB_SEARCH_PROVIDER));
+
             let results: Vec<Value> = search_response
                 .results
                 .into_iter()
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use autosint_common::types::normalize_scratchpad_section;
+
+use crate::tools::registry::{ToolHandler, ToolHandlerContext};
+
+#[derive(Deserialize)]
+struct Args {
+    section: String,
+    content: String,
+}
+
+pub fn handler() -> ToolHandler {
+    Arc::new(|args: Value, ctx: Arc<ToolHandlerContext>| {
+        Box::pin(async move {
+            let args: Args =
+                serde_json::from_value(args).map_err(|e| format!("Invalid arguments: {}", e))?;
+
+            let config = ctx
+                .scope
+                .scratchpad
+                .as_ref()
+                .ok_or_else(|| "Scratchpad not available in this session".to_string())?;
+            let records = ctx
+                .services
+                .records
+                .as_ref()
+                .ok_or_else(|| "Scratchpad not available (store not configured)".to_string())?;
+            let investigation_id = ctx
+                .scope
+                .investigation_id
+                .ok_or_else(|| "Scratchpad not available (no investigation context)".to_string())?;
+
+            let section = normalize_scratchpad_section(&args.section)?;
+            let chars = args.content.chars().count();
+            if chars > config.max_section_chars {
+                return Err(format!(
+                    "Content is {} characters; a section holds at most {}. Condense it or \
+                     split it across sections.",
+                    chars, config.max_section_chars
+                ));
+            }
+
+            let evicted = records
+                .write_scratchpad_section(
+                    investigation_id,
+                    &section,
+                    &args.content,
+                    config.max_total_chars,
+                )
+                .await
+                .map_err(|e| format!("Failed to write scratchpad: {}", e))?;
+
+            let mut result = json!({
+                "section": section,
+                "deleted": args.content.is_empty(),
+                "characters": chars,
+            });
+            if !evicted.is_empty() {
+                result["evicted"] = json!(evicted);
+                result["warning"] = json!(format!(
+                    "Scratchpad is over its {} character limit; evicted the oldest sections: {}",
+                    config.max_total_chars,
+                    evicted.join(", ")
+                ));
+            }
+            Ok(result)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::config::ScratchpadConfig;
+    use autosint_common::InvestigationId;
+
+    use crate::tools::services::stub::StubRecords;
+    use crate::tools::SessionScope;
+
+    fn scope(scratchpad: Option<ScratchpadConfig>) -> SessionScope {
+        SessionScope {
+            investigation_id: Some(InvestigationId::new()),
+            scratchpad,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_and_deletes_section() {
+        let records = Arc::new(StubRecords::default());
+        let ctx = ToolHandlerContext::stub(
+            Some(records.clone()),
+            scope(Some(ScratchpadConfig::default())),
+        )
+        .await;
+
+        let result = handler()(
+            json!({"section": "Open Questions", "content": "Who insures the Crius?"}),
+            ctx.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result["deleted"], false);
+        assert_eq!(result["characters"], 22);
+        let section = result["section"].as_str().unwrap().to_string();
+        assert_eq!(records.scratchpad.lock().unwrap()[0].section, section);
+
+        let result = handler()(json!({"section": section, "content": ""}), ctx)
+            .await
+            .unwrap();
+        assert_eq!(result["deleted"], true);
+        assert!(records.scratchpad.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_enforces_section_limit_and_session_gate() {
+        let records = Arc::new(StubRecords::default());
+        let config = ScratchpadConfig {
+            max_section_chars: 10,
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(Some(records.clone()), scope(Some(config))).await;
+        let err = handler()(
+            json!({"section": "notes", "content": "Who insures the Crius?"}),
+            ctx,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("at most 10"), "{}", err);
+
+        let ctx = ToolHandlerContext::stub(Some(records.clone()), scope(None)).await;
+        let err = handler()(json!({"section": "notes", "content": "x"}), ctx)
+            .await
+            .unwrap_err();
+        assert!(err.contains("not available in this session"), "{}", err);
+        assert!(records.scratchpad.lock().unwrap().is_empty());
+    }
+}
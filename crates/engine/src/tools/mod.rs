@@ -1,5 +1,20 @@
+pub mod claim_merge;
+pub mod claim_references;
+pub mod entity_locks;
+pub mod entity_policy;
+pub mod exploration;
+pub mod fetch_client;
+pub mod fetch_sources;
+pub mod format;
 pub mod handlers;
+pub mod pii;
 pub mod registry;
+pub mod services;
+pub mod snapshot;
 pub mod truncation;
+pub mod verification;
 
+pub use fetch_client::FetchClient;
 pub use registry::{SessionCounters, ToolHandler, ToolHandlerContext, ToolRegistry};
+pub use services::{InvestigationRecords, SessionScope, SharedServices};
+pub use snapshot::SnapshotReads;
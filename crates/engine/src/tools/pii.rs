@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use autosint_common::config::PiiPolicy;
+use autosint_common::pii::describe_redactions;
+
+use super::registry::ToolHandlerContext;
+
+/// Scrub personal data from a claim's content before it is embedded, linked
+/// or stored. Returns the content to store and the redaction counts to
+/// record on the claim; under the `reject` policy, content with personal
+/// data is an error naming what was found. Either way the counts go to the
+/// store for GET /admin/pii/redactions.
+pub async fn scrub_claim_content(
+    ctx: &ToolHandlerContext,
+    content: &str,
+    raw_source_link: Option<&str>,
+) -> Result<(String, BTreeMap<String, u32>), String> {
+    let Some(scrubber) = &ctx.services.pii_scrubber else {
+        return Ok((content.to_string(), BTreeMap::new()));
+    };
+    let scrubbed = scrubber.scrub(content, raw_source_link);
+    if scrubbed.is_clean() {
+        return Ok((scrubbed.content, scrubbed.redactions));
+    }
+
+    let rejected = scrubber.policy() == PiiPolicy::Reject;
+    for (kind, count) in &scrubbed.redactions {
+        metrics::counter!("claims.pii_matches", "kind" => kind.clone())
+            .increment(u64::from(*count));
+    }
+    if rejected {
+        metrics::counter!("claims.pii_rejected").increment(1);
+    }
+    if let Some(store) = &ctx.services.store {
+        if let Err(e) = store
+            .record_pii_redactions(&scrubbed.redactions, rejected)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to record PII redaction counts");
+        }
+    }
+
+    if rejected {
+        return Err(format!(
+            "Claim rejected: its content contains personal data ({}). Restate the claim \
+             without it.",
+            describe_redactions(&scrubbed.redactions)
+        ));
+    }
+    Ok((scrubbed.content, scrubbed.redactions))
+}
+
+/// Tool-result note telling the LLM the stored content differs from what it
+/// wrote.
+pub fn redaction_note(redactions: &BTreeMap<String, u32>) -> Value {
+    json!({
+        "counts": redactions,
+        "message": format!(
+            "Personal data was redacted from the claim content ({}); placeholders such as \
+             [REDACTED:email] stand in for it.",
+            describe_redactions(redactions)
+        ),
+    })
+}
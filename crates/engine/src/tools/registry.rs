@@ -1,36 +1,85 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicU32};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use serde_json::Value;
 
-use autosint_common::config::{DedupConfig, ToolResultLimits};
-use autosint_common::ids::InvestigationId;
+use autosint_common::ids::EntityId;
+use autosint_common::types::OutboundFootprint;
 
-use crate::embeddings::EmbeddingClient;
-use crate::graph::GraphClient;
+use crate::graph::MatchBand;
 use crate::llm::session::{ToolExecutionResult, ToolExecutor};
 use crate::llm::types::ToolDefinition;
-use crate::queue::QueueClient;
-use crate::store::StoreClient;
 
-/// Shared context available to all tool handlers.
+use super::exploration::check_source_budget;
+use super::format::{self, FormattedHandler};
+use super::services::{SessionScope, SharedServices};
+use super::snapshot::SnapshotReads;
+
+/// Context available to all tool handlers: the shared services and the
+/// running session's scope.
 pub struct ToolHandlerContext {
-    pub graph: Arc<GraphClient>,
-    pub embedding_client: Option<Arc<EmbeddingClient>>,
-    pub fetch_base_url: String,
-    pub http: reqwest::Client,
-    pub tool_result_limits: ToolResultLimits,
-    pub dedup_config: DedupConfig,
-    pub session_counters: SessionCounters,
-    // Analyst-specific context (None for Processor sessions).
-    pub store: Option<Arc<StoreClient>>,
-    pub queue: Option<Arc<QueueClient>>,
-    pub investigation_id: Option<InvestigationId>,
-    pub investigation_cycle: Option<i32>,
-    pub max_work_orders_per_cycle: Option<u32>,
+    pub services: SharedServices,
+    pub scope: SessionScope,
+}
+
+impl ToolHandlerContext {
+    pub fn new(services: SharedServices, scope: SessionScope) -> Self {
+        Self { services, scope }
+    }
+
+    /// A context on the same services and counters with `f` applied to a
+    /// copy of the scope, e.g. to act for another investigation.
+    pub fn scoped(&self, f: impl FnOnce(&mut SessionScope)) -> Self {
+        let mut scope = self.scope.clone();
+        f(&mut scope);
+        Self::new(self.services.clone(), scope)
+    }
+
+    /// The session's graph and assessment reads, held to its snapshot
+    /// cutoff. Read tools go through these rather than the graph client.
+    pub fn reads(&self) -> SnapshotReads<'_> {
+        SnapshotReads::new(&self.services.graph, self.scope.as_of)
+    }
+
+    /// A context for handler unit tests: `records` in place of the store, a
+    /// graph client that never connects, and no queue or fetch service.
+    #[cfg(test)]
+    pub(crate) async fn stub(
+        records: Option<Arc<dyn super::InvestigationRecords>>,
+        scope: SessionScope,
+    ) -> Arc<Self> {
+        let mut services = SharedServices::new(
+            Arc::new(crate::graph::GraphClient::unconnected().await),
+            "http://127.0.0.1:1".into(),
+            reqwest::Client::new(),
+            Arc::default(),
+            autosint_common::config::ToolResultLimits {
+                max_search_results: 10,
+                max_entity_detail_chars: 10000,
+                max_claim_preview_chars: 500,
+                max_claim_references_shown: 10,
+                max_result_tokens: 8000,
+                max_graph_context_chars: 6000,
+                max_verification_excerpt_chars: 1500,
+                max_formatted_result_chars: 24000,
+                response_formats: Default::default(),
+            },
+            autosint_common::config::DedupConfig {
+                fuzzy_threshold: 0.85,
+                embedding_threshold: 0.90,
+                seeded_geo_fuzzy_threshold: 0.80,
+                relationship_description_threshold: 0.95,
+                duplicate_relationships: Default::default(),
+                fresh_entities: Default::default(),
+                merge_exact_duplicate_claims: false,
+            },
+        );
+        services.records = records;
+        Arc::new(Self::new(services, scope))
+    }
 }
 
 /// Counters tracking write operations during a session.
@@ -39,7 +88,71 @@ pub struct SessionCounters {
     pub claims_created: AtomicU32,
     pub relationships_created: AtomicU32,
     pub work_orders_created: AtomicU32,
+    /// Follow-up work orders a Processor session proposed.
+    pub work_orders_proposed: AtomicU32,
     pub assessment_produced: AtomicBool,
+    /// Fetches and searches made through the Fetch service.
+    pub footprint: Mutex<OutboundFootprint>,
+    /// Names (lowercased) of the entities the session created or matched in
+    /// the graph.
+    pub entity_names: Mutex<HashSet<String>>,
+    /// Source entities of the claims the session created.
+    pub claim_sources: Mutex<HashSet<EntityId>>,
+    /// Verdicts of the entity pairs the session compared, under both orders.
+    pub entity_comparisons: Mutex<HashMap<(EntityId, EntityId), MatchBand>>,
+    /// Calls an exploratory session made against each domain or fetch source.
+    pub source_calls: Mutex<HashMap<String, u32>>,
+}
+
+impl SessionCounters {
+    /// Update the session's footprint.
+    pub fn record_outbound(&self, update: impl FnOnce(&mut OutboundFootprint)) {
+        update(&mut self.footprint.lock().unwrap());
+    }
+
+    /// The session's footprint so far.
+    pub fn footprint(&self) -> OutboundFootprint {
+        self.footprint.lock().unwrap().clone()
+    }
+
+    /// Note the names of an entity the session created or matched.
+    pub fn record_entity_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) {
+        let mut entity_names = self.entity_names.lock().unwrap();
+        entity_names.extend(names.into_iter().map(|n| n.trim().to_lowercase()));
+    }
+
+    /// Note the source of a claim the session created.
+    pub fn record_claim_source(&self, source_entity_id: EntityId) {
+        self.claim_sources.lock().unwrap().insert(source_entity_id);
+    }
+
+    /// Note the verdict of comparing `a` and `b`.
+    pub fn record_comparison(&self, a: EntityId, b: EntityId, band: MatchBand) {
+        let mut comparisons = self.entity_comparisons.lock().unwrap();
+        comparisons.insert((a, b), band);
+        comparisons.insert((b, a), band);
+    }
+
+    /// Count a call against `source`, unless the session already made `max`
+    /// there. Returns whether it was counted.
+    pub fn try_source_call(&self, source: &str, max: u32) -> bool {
+        let mut source_calls = self.source_calls.lock().unwrap();
+        let calls = source_calls.entry(source.to_string()).or_default();
+        if *calls >= max {
+            return false;
+        }
+        *calls += 1;
+        true
+    }
+
+    /// The verdict of the session's latest comparison of `a` and `b`.
+    pub fn comparison(&self, a: EntityId, b: EntityId) -> Option<MatchBand> {
+        self.entity_comparisons
+            .lock()
+            .unwrap()
+            .get(&(a, b))
+            .copied()
+    }
 }
 
 impl Default for SessionCounters {
@@ -49,11 +162,24 @@ impl Default for SessionCounters {
             claims_created: AtomicU32::new(0),
             relationships_created: AtomicU32::new(0),
             work_orders_created: AtomicU32::new(0),
+            work_orders_proposed: AtomicU32::new(0),
             assessment_produced: AtomicBool::new(false),
+            footprint: Mutex::new(OutboundFootprint::default()),
+            entity_names: Mutex::new(HashSet::new()),
+            claim_sources: Mutex::new(HashSet::new()),
+            entity_comparisons: Mutex::new(HashMap::new()),
+            source_calls: Mutex::new(HashMap::new()),
         }
     }
 }
 
+/// Record a successful call to a mutating tool in the audit log.
+fn audit_tool_call(context: &ToolHandlerContext, tool: &str, args: &Value, result: &Value) {
+    if let Some(record) = crate::audit::tool_record(tool, args, result, &context.scope) {
+        context.services.audit.record(record);
+    }
+}
+
 /// Handler function signature — takes args and context, returns JSON or error string.
 pub type ToolHandler = Arc<
     dyn Fn(
@@ -64,9 +190,46 @@ pub type ToolHandler = Arc<
         + Sync,
 >;
 
+/// A registered handler: one returning JSON, or a read tool's that returns
+/// its result in both formats for `response_format` to pick from.
+#[derive(Clone)]
+enum Handler {
+    Json(ToolHandler),
+    Formatted(FormattedHandler),
+}
+
+/// Run `handler` for a call to `tool`, returning the content sent back.
+async fn invoke(
+    handler: &Handler,
+    tool: &str,
+    args: Value,
+    context: &Arc<ToolHandlerContext>,
+) -> Result<String, String> {
+    let audit_args = crate::audit::is_audited_tool(tool).then(|| args.clone());
+    let audit = |value: &Value| {
+        if let Some(args) = &audit_args {
+            audit_tool_call(context, tool, args, value);
+        }
+    };
+    match handler {
+        Handler::Json(handler) => {
+            let value = handler(args, Arc::clone(context)).await?;
+            audit(&value);
+            Ok(format::to_content(&value))
+        }
+        Handler::Formatted(handler) => {
+            let limits = &context.services.tool_result_limits;
+            let response_format = format::requested_format(tool, &args, limits)?;
+            let formatted = handler(args, Arc::clone(context)).await?;
+            audit(&formatted.value);
+            Ok(formatted.into_content(response_format, limits.max_formatted_result_chars as usize))
+        }
+    }
+}
+
 /// Registry of tool handlers with their schema definitions.
 pub struct ToolRegistry {
-    handlers: HashMap<String, ToolHandler>,
+    handlers: HashMap<String, Handler>,
     definitions: Vec<ToolDefinition>,
     context: Arc<ToolHandlerContext>,
 }
@@ -82,7 +245,14 @@ impl ToolRegistry {
 
     /// Register a tool handler by name.
     pub fn register(&mut self, name: &str, handler: ToolHandler) {
-        self.handlers.insert(name.to_string(), handler);
+        self.handlers
+            .insert(name.to_string(), Handler::Json(handler));
+    }
+
+    /// Register a read tool's handler that takes `response_format`.
+    pub fn register_formatted(&mut self, name: &str, handler: FormattedHandler) {
+        self.handlers
+            .insert(name.to_string(), Handler::Formatted(handler));
     }
 
     /// Load tool definitions from the config-loaded schemas.
@@ -132,6 +302,22 @@ impl ToolRegistry {
         Ok(())
     }
 
+    /// Withhold every tool not in `allowed`: its definition is no longer sent
+    /// to the LLM and calls to it fail as unknown tools.
+    pub fn retain_tools(&mut self, allowed: &[String]) {
+        self.handlers
+            .retain(|name, _| allowed.iter().any(|a| a == name));
+        self.definitions.retain(|d| allowed.contains(&d.name));
+    }
+
+    /// Withhold the named tools, as `retain_tools` withholds everything else.
+    pub fn withhold_tools(&mut self, withheld: &[&str]) {
+        self.handlers
+            .retain(|name, _| !withheld.contains(&name.as_str()));
+        self.definitions
+            .retain(|d| !withheld.contains(&d.name.as_str()));
+    }
+
     /// Get the tool definitions for sending to the LLM.
     pub fn definitions(&self) -> &[ToolDefinition] {
         &self.definitions
@@ -139,7 +325,7 @@ impl ToolRegistry {
 
     /// Get a reference to the session counters.
     pub fn counters(&self) -> &SessionCounters {
-        &self.context.session_counters
+        &self.context.scope.session_counters
     }
 
     /// Execute a tool call by name.
@@ -163,7 +349,15 @@ impl ToolRegistry {
             }
         };
 
-        let result = handler(args, Arc::clone(&self.context)).await;
+        if let Err(message) = check_source_budget(&self.context.scope, tool_name, &args) {
+            return ToolExecutionResult {
+                content: message,
+                is_error: true,
+                is_malformed: false,
+            };
+        }
+
+        let result = invoke(handler, tool_name, args, &self.context).await;
 
         let latency = start.elapsed().as_secs_f64();
         metrics::histogram!("tools.execution.latency", "tool" => tool_name.to_string())
@@ -171,16 +365,11 @@ impl ToolRegistry {
         metrics::counter!("tools.execution.count", "tool" => tool_name.to_string()).increment(1);
 
         match result {
-            Ok(value) => {
-                let content = serde_json::to_string(&value).unwrap_or_else(|e| {
-                    format!("{{\"error\": \"Failed to serialize result: {}\"}}", e)
-                });
-                ToolExecutionResult {
-                    content,
-                    is_error: false,
-                    is_malformed: false,
-                }
-            }
+            Ok(content) => ToolExecutionResult {
+                content,
+                is_error: false,
+                is_malformed: false,
+            },
             Err(msg) => {
                 metrics::counter!("tools.execution.errors", "tool" => tool_name.to_string())
                     .increment(1);
@@ -228,7 +417,16 @@ impl ToolRegistry {
                     }
                 };
 
-                let result = handler(args, Arc::clone(&context)).await;
+                if let Err(message) = check_source_budget(&context.scope, &name, &args) {
+                    tracing::info!(tool = %name, "Tool call over the per-source budget");
+                    return ToolExecutionResult {
+                        content: message,
+                        is_error: true,
+                        is_malformed: false,
+                    };
+                }
+
+                let result = invoke(handler, &name, args, &context).await;
 
                 let latency = start.elapsed().as_secs_f64();
                 metrics::histogram!("tools.execution.latency", "tool" => name.clone())
@@ -236,10 +434,7 @@ impl ToolRegistry {
                 metrics::counter!("tools.execution.count", "tool" => name.clone()).increment(1);
 
                 match result {
-                    Ok(value) => {
-                        let content = serde_json::to_string(&value).unwrap_or_else(|e| {
-                            format!("{{\"error\": \"Failed to serialize result: {}\"}}", e)
-                        });
+                    Ok(content) => {
                         let content_len = content.len();
                         tracing::info!(
                             tool = %name,
@@ -0,0 +1,497 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use autosint_common::config::{
+    DedupConfig, EntityKindConfig, ExplorationConfig, FetchReplicasConfig, ScratchpadConfig,
+    SourceTrustConfig, ToolResultLimits, VerificationConfig, WorkOrderProposalConfig,
+};
+use autosint_common::ids::{
+    AssessmentId, ClaimId, EntityId, InvestigationId, MonitorId, WorkOrderId,
+};
+use autosint_common::pii::PiiScrubber;
+use autosint_common::types::{
+    Assessment, AuditActor, FeatureFlag, FeedbackSummary, Investigation, ScratchpadSection,
+    WorkOrder,
+};
+
+use crate::audit::AuditLog;
+use crate::embeddings::EmbeddingClient;
+use crate::events::EventBus;
+use crate::flags::FlagSet;
+use crate::graph::linking::EntityLinker;
+use crate::graph::GraphClient;
+use crate::ingest::enrichment::Enrichment;
+use crate::llm::session::LiveSessionStats;
+use crate::queue::fetch_budget::FetchBudgetTracker;
+use crate::queue::shortlist::EntityShortlist;
+use crate::queue::QueueClient;
+use crate::store::{StoreClient, StoreError};
+
+use super::fetch_client::FetchClient;
+use super::registry::SessionCounters;
+
+/// Future returned by [`InvestigationRecords`] methods.
+pub type RecordsFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StoreError>> + Send + 'a>>;
+
+/// The store operations behind the investigation record tools (tags,
+/// history, scratchpad, assessments), as an object-safe trait so handler
+/// tests can stub them. Production uses [`StoreClient`].
+pub trait InvestigationRecords: Send + Sync {
+    fn get_investigation(&self, id: InvestigationId) -> RecordsFuture<'_, Investigation>;
+
+    fn set_investigation_tags<'a>(
+        &'a self,
+        id: InvestigationId,
+        tags: &'a [String],
+    ) -> RecordsFuture<'a, ()>;
+
+    fn get_work_orders_by_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> RecordsFuture<'_, Vec<WorkOrder>>;
+
+    fn get_assessment(&self, id: AssessmentId) -> RecordsFuture<'_, Assessment>;
+
+    /// Assessments citing the claim `claim_id`, newest first.
+    fn get_assessments_citing_claim(&self, claim_id: ClaimId)
+        -> RecordsFuture<'_, Vec<Assessment>>;
+
+    /// Feedback counts for each of `ids` that has any feedback.
+    fn assessment_feedback_summaries<'a>(
+        &'a self,
+        ids: &'a [AssessmentId],
+    ) -> RecordsFuture<'a, HashMap<AssessmentId, FeedbackSummary>>;
+
+    fn get_scratchpad<'a>(
+        &'a self,
+        id: InvestigationId,
+        section: Option<&'a str>,
+    ) -> RecordsFuture<'a, Vec<ScratchpadSection>>;
+
+    /// Returns the sections evicted to stay within `max_total_chars`.
+    fn write_scratchpad_section<'a>(
+        &'a self,
+        id: InvestigationId,
+        section: &'a str,
+        content: &'a str,
+        max_total_chars: usize,
+    ) -> RecordsFuture<'a, Vec<String>>;
+}
+
+impl InvestigationRecords for StoreClient {
+    fn get_investigation(&self, id: InvestigationId) -> RecordsFuture<'_, Investigation> {
+        Box::pin(self.get_investigation(id))
+    }
+
+    fn set_investigation_tags<'a>(
+        &'a self,
+        id: InvestigationId,
+        tags: &'a [String],
+    ) -> RecordsFuture<'a, ()> {
+        Box::pin(self.set_investigation_tags(id, tags))
+    }
+
+    fn get_work_orders_by_investigation(
+        &self,
+        id: InvestigationId,
+    ) -> RecordsFuture<'_, Vec<WorkOrder>> {
+        Box::pin(self.get_work_orders_by_investigation(id))
+    }
+
+    fn get_assessment(&self, id: AssessmentId) -> RecordsFuture<'_, Assessment> {
+        Box::pin(self.get_assessment(id))
+    }
+
+    fn get_assessments_citing_claim(
+        &self,
+        claim_id: ClaimId,
+    ) -> RecordsFuture<'_, Vec<Assessment>> {
+        Box::pin(self.get_assessments_citing_claim(claim_id))
+    }
+
+    fn assessment_feedback_summaries<'a>(
+        &'a self,
+        ids: &'a [AssessmentId],
+    ) -> RecordsFuture<'a, HashMap<AssessmentId, FeedbackSummary>> {
+        Box::pin(self.assessment_feedback_summaries(ids))
+    }
+
+    fn get_scratchpad<'a>(
+        &'a self,
+        id: InvestigationId,
+        section: Option<&'a str>,
+    ) -> RecordsFuture<'a, Vec<ScratchpadSection>> {
+        Box::pin(self.get_scratchpad(id, section))
+    }
+
+    fn write_scratchpad_section<'a>(
+        &'a self,
+        id: InvestigationId,
+        section: &'a str,
+        content: &'a str,
+        max_total_chars: usize,
+    ) -> RecordsFuture<'a, Vec<String>> {
+        Box::pin(self.write_scratchpad_section(id, section, content, max_total_chars))
+    }
+}
+
+/// Clients and configuration shared by every tool session, built once at
+/// startup. Cloning copies `Arc`s.
+#[derive(Clone)]
+pub struct SharedServices {
+    pub graph: Arc<GraphClient>,
+    pub embedding_client: Option<Arc<EmbeddingClient>>,
+    /// Auto-links new claims to known entities their content mentions. None disables it.
+    pub entity_linker: Option<Arc<EntityLinker>>,
+    /// Entity locks and merge tombstones for graph writes; assessment and work order
+    /// state for the Analyst.
+    pub store: Option<Arc<StoreClient>>,
+    /// The investigation record operations of `store`, or a stub in tests.
+    pub records: Option<Arc<dyn InvestigationRecords>>,
+    pub queue: Option<Arc<QueueClient>>,
+    /// The Fetch service, over all its replicas.
+    pub fetch: FetchClient,
+    pub tool_schemas: Arc<HashMap<String, Value>>,
+    pub tool_result_limits: ToolResultLimits,
+    pub dedup_config: DedupConfig,
+    /// Limits on the follow-up work orders Processors propose.
+    pub work_order_proposals: WorkOrderProposalConfig,
+    /// Limits on Processor sessions for exploratory work orders.
+    pub exploration: ExplorationConfig,
+    /// Search and fetch limits and domain lists of the verify_claim tool.
+    pub verification: VerificationConfig,
+    /// Per-kind creation policies for create_entity and batch_extract.
+    pub entity_kinds: EntityKindConfig,
+    /// Scrubs personal data from claim content before it is stored. None
+    /// stores claims as written.
+    pub pii_scrubber: Option<Arc<PiiScrubber>>,
+    /// Asynchronous external enrichment providers. None disables the
+    /// enrichment tools.
+    pub enrichment: Option<Arc<Enrichment>>,
+    /// Runtime switches for the behaviors above, read at each decision.
+    pub flags: FlagSet,
+    /// Records mutating tool calls. Disabled unless set.
+    pub audit: AuditLog,
+    /// Where claims, assessments and work order lifecycle events are
+    /// published. With a store and no bus set, only the timeline subscribes.
+    pub events: EventBus,
+}
+
+impl SharedServices {
+    /// Services without a store, queue, embedding client or entity linker;
+    /// add those with the `with_*` methods. `fetch_base_url` may list several
+    /// Fetch service replicas, comma-separated.
+    pub fn new(
+        graph: Arc<GraphClient>,
+        fetch_base_url: String,
+        http: reqwest::Client,
+        tool_schemas: Arc<HashMap<String, Value>>,
+        tool_result_limits: ToolResultLimits,
+        dedup_config: DedupConfig,
+    ) -> Self {
+        Self {
+            graph,
+            embedding_client: None,
+            entity_linker: None,
+            store: None,
+            records: None,
+            queue: None,
+            fetch: FetchClient::new(&fetch_base_url, http, FetchReplicasConfig::default()),
+            tool_schemas,
+            tool_result_limits,
+            dedup_config,
+            work_order_proposals: WorkOrderProposalConfig::default(),
+            exploration: ExplorationConfig::default(),
+            verification: VerificationConfig::default(),
+            entity_kinds: EntityKindConfig::default(),
+            pii_scrubber: None,
+            enrichment: None,
+            flags: FlagSet::default(),
+            audit: AuditLog::default(),
+            events: EventBus::default(),
+        }
+    }
+
+    /// Use `store`, for its investigation records too, and publish to a bus
+    /// writing its timeline (replace it with `with_events`).
+    pub fn with_store(mut self, store: Arc<StoreClient>) -> Self {
+        self.records = Some(Arc::clone(&store) as Arc<dyn InvestigationRecords>);
+        self.events = EventBus::timeline(Arc::clone(&store));
+        self.store = Some(store);
+        self
+    }
+
+    /// Serve the investigation record tools from `records` instead of the store.
+    pub fn with_records(mut self, records: Arc<dyn InvestigationRecords>) -> Self {
+        self.records = Some(records);
+        self
+    }
+
+    /// Reach the Fetch service through `fetch`, shared with other services.
+    pub fn with_fetch_client(mut self, fetch: FetchClient) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    pub fn with_queue(mut self, queue: Arc<QueueClient>) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+
+    pub fn with_embedding_client(mut self, client: Option<Arc<EmbeddingClient>>) -> Self {
+        self.embedding_client = client;
+        self
+    }
+
+    pub fn with_entity_linker(mut self, linker: Option<Arc<EntityLinker>>) -> Self {
+        self.entity_linker = linker;
+        self
+    }
+
+    pub fn with_work_order_proposals(mut self, config: WorkOrderProposalConfig) -> Self {
+        self.work_order_proposals = config;
+        self
+    }
+
+    pub fn with_exploration(mut self, config: ExplorationConfig) -> Self {
+        self.exploration = config;
+        self
+    }
+
+    pub fn with_verification(mut self, config: VerificationConfig) -> Self {
+        self.verification = config;
+        self
+    }
+
+    pub fn with_entity_kinds(mut self, config: EntityKindConfig) -> Self {
+        self.entity_kinds = config;
+        self
+    }
+
+    pub fn with_pii_scrubber(mut self, scrubber: Option<Arc<PiiScrubber>>) -> Self {
+        self.pii_scrubber = scrubber;
+        self
+    }
+
+    pub fn with_enrichment(mut self, enrichment: Option<Arc<Enrichment>>) -> Self {
+        self.enrichment = enrichment;
+        self
+    }
+
+    pub fn with_flags(mut self, flags: FlagSet) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn with_audit_log(mut self, audit: AuditLog) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// The entity linker, while entity linking is configured and its flag is on.
+    pub fn entity_linker(&self) -> Option<&Arc<EntityLinker>> {
+        self.entity_linker
+            .as_ref()
+            .filter(|_| self.flags.is_enabled(FeatureFlag::EntityLinking))
+    }
+}
+
+/// What one tool session works on and how far it may go: its investigation
+/// or monitor, budgets, write counters and per-session caches. Cloning shares
+/// the counters and live stats.
+#[derive(Clone, Default)]
+pub struct SessionScope {
+    /// Investigation this session works for. Stamped onto created graph data as provenance.
+    pub investigation_id: Option<InvestigationId>,
+    /// Monitor whose item this session extracts. Stamped onto claims instead of
+    /// `investigation_id`.
+    pub monitor_id: Option<MonitorId>,
+    /// The investigation's correlation ID, passed on to the work orders it creates.
+    pub correlation_id: Option<String>,
+    /// Work order a Processor session runs. Work orders it proposes cite it.
+    pub work_order_id: Option<WorkOrderId>,
+    /// Entities the session's work order references. Claims over the
+    /// reference limit keep these first.
+    pub focus_entity_ids: Vec<EntityId>,
+    pub session_counters: Arc<SessionCounters>,
+    /// Live turn/token counters of the running session (shared with `SessionConfig`).
+    pub live_stats: Arc<LiveSessionStats>,
+    /// Turn limit of the running session.
+    pub max_turns: u32,
+    /// Wall-clock limit of the running session. None = no limit.
+    pub max_session_duration: Option<std::time::Duration>,
+    /// Names this investigation already resolved to entities. None disables it.
+    pub entity_shortlist: Option<EntityShortlist>,
+    /// The investigation's fetch budget, shared by its sessions. None = unlimited.
+    pub fetch_budget: Option<FetchBudgetTracker>,
+    /// Breadth-first limits of an exploratory work order's session: shorter
+    /// fetches and a per-source call cap. None for everything else.
+    pub exploration: Option<ExplorationConfig>,
+    // Analyst-specific scope (None for Processor sessions).
+    pub investigation_cycle: Option<i32>,
+    pub max_cycles_per_investigation: Option<u32>,
+    pub max_work_orders_per_cycle: Option<u32>,
+    /// Scratchpad caps; None withholds the scratchpad.
+    pub scratchpad: Option<ScratchpadConfig>,
+    /// Trust floor for assessment citations; None skips the check.
+    pub source_trust: Option<SourceTrustConfig>,
+    /// Snapshot cutoff: the session's reads only see graph data and
+    /// assessments from before it (see `ToolHandlerContext::reads`). None
+    /// reads the live graph.
+    pub as_of: Option<DateTime<Utc>>,
+    /// Who the session's changes are audited as.
+    pub actor: AuditActor,
+}
+
+/// Stub services for handler unit tests.
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// In-memory investigation records: one investigation, its work orders,
+    /// assessments and feedback, and its scratchpad. Scratchpad writes never
+    /// evict.
+    #[derive(Default)]
+    pub(crate) struct StubRecords {
+        pub investigation: Mutex<Option<Investigation>>,
+        pub work_orders: Vec<WorkOrder>,
+        pub assessments: Vec<Assessment>,
+        pub feedback: HashMap<AssessmentId, FeedbackSummary>,
+        pub scratchpad: Mutex<Vec<ScratchpadSection>>,
+    }
+
+    impl StubRecords {
+        pub fn with_investigation(investigation: Investigation) -> Self {
+            Self {
+                investigation: Mutex::new(Some(investigation)),
+                ..Default::default()
+            }
+        }
+    }
+
+    fn not_found<T>(what: String) -> RecordsFuture<'static, T>
+    where
+        T: Send + 'static,
+    {
+        Box::pin(async move { Err(StoreError::NotFound(what)) })
+    }
+
+    impl InvestigationRecords for StubRecords {
+        fn get_investigation(&self, id: InvestigationId) -> RecordsFuture<'_, Investigation> {
+            match self.investigation.lock().unwrap().clone() {
+                Some(investigation) if investigation.id == id => {
+                    Box::pin(async move { Ok(investigation) })
+                }
+                _ => not_found(format!("Investigation {}", id)),
+            }
+        }
+
+        fn set_investigation_tags<'a>(
+            &'a self,
+            id: InvestigationId,
+            tags: &'a [String],
+        ) -> RecordsFuture<'a, ()> {
+            match self.investigation.lock().unwrap().as_mut() {
+                Some(investigation) if investigation.id == id => {
+                    investigation.tags = tags.to_vec();
+                    Box::pin(async { Ok(()) })
+                }
+                _ => not_found(format!("Investigation {}", id)),
+            }
+        }
+
+        fn get_work_orders_by_investigation(
+            &self,
+            id: InvestigationId,
+        ) -> RecordsFuture<'_, Vec<WorkOrder>> {
+            let work_orders = self
+                .work_orders
+                .iter()
+                .filter(|wo| wo.investigation_id == id)
+                .cloned()
+                .collect();
+            Box::pin(async move { Ok(work_orders) })
+        }
+
+        fn get_assessment(&self, id: AssessmentId) -> RecordsFuture<'_, Assessment> {
+            match self.assessments.iter().find(|a| a.id == id).cloned() {
+                Some(assessment) => Box::pin(async move { Ok(assessment) }),
+                None => not_found(format!("Assessment {}", id)),
+            }
+        }
+
+        fn get_assessments_citing_claim(
+            &self,
+            claim_id: ClaimId,
+        ) -> RecordsFuture<'_, Vec<Assessment>> {
+            let mut citing: Vec<Assessment> = self
+                .assessments
+                .iter()
+                .filter(|a| a.cited_claim_ids().contains(&claim_id))
+                .cloned()
+                .collect();
+            citing.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+            Box::pin(async move { Ok(citing) })
+        }
+
+        fn assessment_feedback_summaries<'a>(
+            &'a self,
+            ids: &'a [AssessmentId],
+        ) -> RecordsFuture<'a, HashMap<AssessmentId, FeedbackSummary>> {
+            let summaries = ids
+                .iter()
+                .filter_map(|id| self.feedback.get(id).map(|summary| (*id, *summary)))
+                .collect();
+            Box::pin(async move { Ok(summaries) })
+        }
+
+        fn get_scratchpad<'a>(
+            &'a self,
+            _id: InvestigationId,
+            section: Option<&'a str>,
+        ) -> RecordsFuture<'a, Vec<ScratchpadSection>> {
+            let sections = self
+                .scratchpad
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| section.is_none_or(|name| s.section == name))
+                .cloned()
+                .collect();
+            Box::pin(async move { Ok(sections) })
+        }
+
+        fn write_scratchpad_section<'a>(
+            &'a self,
+            _id: InvestigationId,
+            section: &'a str,
+            content: &'a str,
+            _max_total_chars: usize,
+        ) -> RecordsFuture<'a, Vec<String>> {
+            let mut sections = self.scratchpad.lock().unwrap();
+            sections.retain(|s| s.section != section);
+            if !content.is_empty() {
+                let now = chrono::Utc::now();
+                sections.push(ScratchpadSection {
+                    section: section.to_string(),
+                    content: content.to_string(),
+                    created_at: now,
+                    updated_at: now,
+                });
+            }
+            Box::pin(async { Ok(Vec::new()) })
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use autosint_common::ids::{AssessmentId, ClaimId, EntityId};
+use autosint_common::types::{Assessment, Claim, Entity, Relationship};
+
+use crate::graph::{
+    ClaimSearchParams, EntitySearchParams, GraphClient, GraphError, RelationshipSearchParams,
+    SearchPage, TraversalPage, TraversalParams,
+};
+use crate::store::{StoreClient, StoreError};
+
+use super::services::InvestigationRecords;
+
+/// The graph and assessment reads of a tool session, held to its snapshot
+/// cutoff (`SessionScope::as_of`): searches and traversals get the cutoff as
+/// a filter, and lookups of anything newer fail as not found. Without a
+/// cutoff every read passes straight through.
+///
+/// Claims and relationships are cut by when they were written, entities by
+/// when they were created. Entities aren't versioned, so one that existed at
+/// the cutoff shows its current summary and properties.
+pub struct SnapshotReads<'a> {
+    graph: &'a GraphClient,
+    as_of: Option<DateTime<Utc>>,
+}
+
+impl<'a> SnapshotReads<'a> {
+    pub fn new(graph: &'a GraphClient, as_of: Option<DateTime<Utc>>) -> Self {
+        Self { graph, as_of }
+    }
+
+    /// The cutoff, if this is a snapshot session.
+    pub fn as_of(&self) -> Option<DateTime<Utc>> {
+        self.as_of
+    }
+
+    /// Whether something written at `at` is visible.
+    fn visible(&self, at: DateTime<Utc>) -> bool {
+        self.as_of.is_none_or(|as_of| at <= as_of)
+    }
+
+    /// `requested`, tightened to the cutoff.
+    fn cap(&self, requested: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        match (requested, self.as_of) {
+            (Some(requested), Some(as_of)) => Some(requested.min(as_of)),
+            (requested, as_of) => requested.or(as_of),
+        }
+    }
+
+    pub async fn search_entities(
+        &self,
+        params: &mut EntitySearchParams,
+        query_embedding: Option<Vec<f32>>,
+    ) -> Result<SearchPage<Entity>, GraphError> {
+        params.created_before = self.cap(params.created_before);
+        self.graph.search_entities(params, query_embedding).await
+    }
+
+    pub async fn get_entity(&self, id: EntityId) -> Result<Entity, GraphError> {
+        match self.as_of {
+            Some(as_of) => self.graph.get_entity_as_of(id, as_of).await,
+            None => self.graph.get_entity(id).await,
+        }
+    }
+
+    pub async fn traverse_relationships(
+        &self,
+        entity_id: EntityId,
+        params: &mut TraversalParams,
+    ) -> Result<TraversalPage, GraphError> {
+        params.as_of = self.cap(params.as_of);
+        self.graph.traverse_relationships(entity_id, params).await
+    }
+
+    pub async fn search_relationships(
+        &self,
+        params: &mut RelationshipSearchParams,
+        query_embedding: Option<Vec<f32>>,
+    ) -> Result<SearchPage<Relationship>, GraphError> {
+        params.created_before = self.cap(params.created_before);
+        self.graph
+            .search_relationships(params, query_embedding)
+            .await
+    }
+
+    pub async fn search_claims(
+        &self,
+        params: &mut ClaimSearchParams,
+        query_embedding: Option<Vec<f32>>,
+    ) -> Result<SearchPage<Claim>, GraphError> {
+        params.ingested_before = self.cap(params.ingested_before);
+        self.graph.search_claims(params, query_embedding).await
+    }
+
+    pub async fn get_claim(&self, id: ClaimId) -> Result<Claim, GraphError> {
+        let claim = self.graph.get_claim(id).await?;
+        if !self.visible(claim.ingested_timestamp) {
+            return Err(GraphError::NotFound(format!("Claim {}", id)));
+        }
+        Ok(claim)
+    }
+
+    pub async fn search_assessments(
+        &self,
+        store: &StoreClient,
+        query_embedding: Vec<f32>,
+        tags: &[String],
+        limit: i64,
+    ) -> Result<Vec<(Assessment, f64)>, StoreError> {
+        store
+            .search_assessments(query_embedding, tags, self.as_of, limit)
+            .await
+    }
+
+    pub async fn get_assessment(
+        &self,
+        records: &dyn InvestigationRecords,
+        id: AssessmentId,
+    ) -> Result<Assessment, StoreError> {
+        let assessment = records.get_assessment(id).await?;
+        if !self.visible(assessment.created_at) {
+            return Err(StoreError::NotFound(format!("Assessment {}", id)));
+        }
+        Ok(assessment)
+    }
+
+    pub async fn get_assessments_citing_claim(
+        &self,
+        records: &dyn InvestigationRecords,
+        claim_id: ClaimId,
+    ) -> Result<Vec<Assessment>, StoreError> {
+        let mut assessments = records.get_assessments_citing_claim(claim_id).await?;
+        assessments.retain(|a| self.visible(a.created_at));
+        Ok(assessments)
+    }
+
+    pub async fn count_assessments_citing_claims(
+        &self,
+        store: &StoreClient,
+        claim_ids: &[ClaimId],
+    ) -> Result<HashMap<ClaimId, u64>, StoreError> {
+        store
+            .count_assessments_citing_claims(claim_ids, self.as_of)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Duration;
+    use serde_json::json;
+
+    use autosint_common::types::Confidence;
+    use autosint_common::InvestigationId;
+
+    use super::*;
+    use crate::tools::services::stub::StubRecords;
+    use crate::tools::{SessionScope, ToolHandlerContext};
+
+    #[tokio::test]
+    async fn test_cap_tightens_to_cutoff() {
+        let ctx = ToolHandlerContext::stub(None, SessionScope::default()).await;
+        let now = Utc::now();
+        let earlier = now - Duration::days(30);
+
+        let live = SnapshotReads::new(&ctx.services.graph, None);
+        assert_eq!(live.cap(None), None);
+        assert_eq!(live.cap(Some(now)), Some(now));
+        assert!(live.visible(now));
+
+        let snapshot = SnapshotReads::new(&ctx.services.graph, Some(earlier));
+        assert_eq!(snapshot.cap(None), Some(earlier));
+        assert_eq!(snapshot.cap(Some(now)), Some(earlier));
+        let before = earlier - Duration::days(1);
+        assert_eq!(snapshot.cap(Some(before)), Some(before));
+        assert!(snapshot.visible(earlier));
+        assert!(!snapshot.visible(now));
+    }
+
+    #[tokio::test]
+    async fn test_assessments_after_cutoff_hidden() {
+        let claim_id = ClaimId::new();
+        let assessment = |age_days: i64| {
+            let mut assessment = Assessment::new(
+                InvestigationId::new(),
+                json!({
+                    "summary": "The Crius changed owners in March.",
+                    "citations": [{"marker": "[1]", "claim_id": claim_id.to_string()}],
+                }),
+                Confidence::Moderate,
+            );
+            assessment.created_at -= Duration::days(age_days);
+            assessment
+        };
+        let old = assessment(30);
+        let recent = assessment(1);
+        let records = StubRecords {
+            assessments: vec![old.clone(), recent.clone()],
+            ..Default::default()
+        };
+        let scope = SessionScope {
+            as_of: Some(Utc::now() - Duration::days(7)),
+            ..Default::default()
+        };
+        let ctx = ToolHandlerContext::stub(Some(Arc::new(records)), scope).await;
+        let records = ctx.services.records.as_deref().unwrap();
+        let reads = ctx.reads();
+
+        assert_eq!(
+            reads.get_assessment(records, old.id).await.unwrap().id,
+            old.id
+        );
+        assert!(matches!(
+            reads.get_assessment(records, recent.id).await,
+            Err(StoreError::NotFound(_))
+        ));
+        let citing = reads
+            .get_assessments_citing_claim(records, claim_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            citing.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![old.id]
+        );
+    }
+}
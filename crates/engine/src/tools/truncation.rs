@@ -1,6 +1,11 @@
 use autosint_common::config::ToolResultLimits;
+use autosint_common::EntityId;
 use serde_json::Value;
 
+use crate::graph::ResultSize;
+
+use crate::llm::TokenCounter;
+
 /// Truncate search result arrays to the configured max, adding a note about omitted results.
 pub fn truncate_search_results(results: &mut Value, limits: &ToolResultLimits) {
     let max = limits.max_search_results as usize;
@@ -20,6 +25,41 @@ pub fn truncate_search_results(results: &mut Value, limits: &ToolResultLimits) {
     }
 }
 
+/// Note on a graph read that held rows back or ran under a lowered limit,
+/// from the read's own accounting rather than a count query.
+pub fn note_result_size(result: &mut Value, size: &ResultSize) {
+    let Some(obj) = result.as_object_mut() else {
+        return;
+    };
+    if size.truncated {
+        obj.insert(
+            "truncated".into(),
+            Value::String(format!(
+                "[more than {} matched; only the first {} are shown]",
+                size.rows, size.rows
+            )),
+        );
+    }
+    if let Some(requested) = size.clamped_from {
+        obj.insert(
+            "limit_note".into(),
+            Value::String(format!(
+                "limit {} is over the server maximum; {} was used",
+                requested, size.limit
+            )),
+        );
+    }
+}
+
+/// Page size for a paginated search: the requested limit (default 20),
+/// capped so `truncate_search_results` never drops part of a page — the
+/// page's cursor would skip whatever was dropped.
+pub fn search_page_size(limit: Option<u32>, limits: &ToolResultLimits) -> u32 {
+    limit
+        .unwrap_or(20)
+        .clamp(1, limits.max_search_results.max(1))
+}
+
 /// Truncate entity detail responses — freeform properties before core fields.
 pub fn truncate_entity_detail(entity: &mut Value, limits: &ToolResultLimits) {
     let max_chars = limits.max_entity_detail_chars as usize;
@@ -97,6 +137,168 @@ pub fn truncate_claim_previews(claims: &mut Value, limits: &ToolResultLimits) {
     }
 }
 
+/// Add a claim's referenced entity IDs to a tool-result object as
+/// `referenced_entity_ids`, listing at most `max_claim_references_shown`
+/// with a `references_note` giving the total when there are more.
+pub fn insert_claim_references(item: &mut Value, ids: &[EntityId], limits: &ToolResultLimits) {
+    let Some(obj) = item.as_object_mut() else {
+        return;
+    };
+    let shown = ids.len().min(limits.max_claim_references_shown as usize);
+    obj.insert(
+        "referenced_entity_ids".into(),
+        Value::from(
+            ids[..shown]
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>(),
+        ),
+    );
+    if shown < ids.len() {
+        obj.insert(
+            "references_note".into(),
+            Value::String(format!(
+                "references {} entities, showing {}",
+                ids.len(),
+                shown
+            )),
+        );
+    }
+}
+
+/// Cut free text to at most `max_chars` characters, noting the original length.
+pub fn truncate_text(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    mark_truncated(&kept, total)
+}
+
+/// `kept` with the note `truncate_text` adds to text cut down from
+/// `total_chars` characters.
+pub fn mark_truncated(kept: &str, total_chars: usize) -> String {
+    format!("{}...[truncated, {} chars total]", kept, total_chars)
+}
+
+/// Drop trailing entries of a result's `results` until it serializes to at
+/// most `max_chars`, noting how many were left out. The page's `next_cursor`
+/// is dropped with them — it would skip them — so the note points at a lower
+/// `limit` instead.
+pub fn drop_results_to_fit(result: &mut Value, max_chars: usize) {
+    let size = result.to_string().len();
+    if size <= max_chars {
+        return;
+    }
+    let Some(obj) = result.as_object_mut() else {
+        return;
+    };
+    let Some(arr) = obj.get_mut("results").and_then(|v| v.as_array_mut()) else {
+        return;
+    };
+
+    let shown = arr.len();
+    let mut excess = size - max_chars;
+    while excess > 0 {
+        let Some(item) = arr.pop() else { break };
+        // The item and the comma before it.
+        excess = excess.saturating_sub(item.to_string().len() + 1);
+    }
+    let dropped = shown - arr.len();
+    let total = obj
+        .get("total_results")
+        .and_then(|v| v.as_u64())
+        .map_or(shown, |t| t as usize);
+    let paged = obj.remove("next_cursor").is_some();
+    obj.insert("total_results".into(), Value::from(total));
+    obj.insert(
+        "truncated".into(),
+        Value::String(format!(
+            "[{} more results omitted to fit the result size{}]",
+            total - (shown - dropped),
+            if paged {
+                "; call again with a lower limit to page through them"
+            } else {
+                ""
+            }
+        )),
+    );
+}
+
+/// Cut prose to at most `max_chars` characters, noting the original length:
+/// at the last line break that fits, so a rendered list loses whole lines,
+/// unless that would drop over half the budget; then at the last sentence
+/// end, or failing that the last space.
+pub fn truncate_at_sentence(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let marker = format!("\n[summary truncated, {} chars total]", total);
+    let budget = max_chars.saturating_sub(marker.chars().count());
+    let prefix = match text.char_indices().nth(budget) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    };
+    let cut = prefix
+        .rfind('\n')
+        .filter(|&i| i >= prefix.len() / 2)
+        .or_else(|| sentence_ends(prefix).last())
+        .or_else(|| prefix.rfind(' '))
+        .unwrap_or(prefix.len());
+    format!("{}{}", prefix[..cut].trim_end(), marker)
+}
+
+/// Byte offsets just past each `.`, `!` or `?` in `text` followed by
+/// whitespace, and at each line break.
+pub fn sentence_ends(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\n' => return Some(i),
+                '.' | '!' | '?' if chars.peek().is_some_and(|(_, next)| next.is_whitespace()) => {
+                    return Some(i + c.len_utf8());
+                }
+                _ => {}
+            }
+        }
+        None
+    })
+}
+
+/// Cut text to at most `max_tokens` tokens as `counter` counts them, marker
+/// included, noting the original size. Binary-searches the cut point over
+/// character boundaries, so multi-byte text is never split mid-character.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize, counter: &dyn TokenCounter) -> String {
+    let total = counter.count(text);
+    if total <= max_tokens {
+        return text.to_string();
+    }
+
+    let marker = format!("...[truncated, {} tokens total]", total);
+    let budget = max_tokens.saturating_sub(counter.count(&marker));
+
+    // Largest prefix (in characters) that fits the budget.
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let (mut lo, mut hi) = (0, boundaries.len() - 1);
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if counter.count(&text[..boundaries[mid]]) <= budget {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    format!("{}{}", &text[..boundaries[lo]], marker)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +311,12 @@ mod tests {
             max_search_results: 10,
             max_entity_detail_chars: 10000,
             max_claim_preview_chars: 500,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+            max_claim_references_shown: 10,
         };
         truncate_search_results(&mut results, &limits);
         assert_eq!(results["results"].as_array().unwrap().len(), 2);
@@ -123,6 +331,12 @@ mod tests {
             max_search_results: 10,
             max_entity_detail_chars: 10000,
             max_claim_preview_chars: 500,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+            max_claim_references_shown: 10,
         };
         truncate_search_results(&mut results, &limits);
         assert_eq!(results["results"].as_array().unwrap().len(), 10);
@@ -130,6 +344,40 @@ mod tests {
         assert!(results["truncated"].as_str().unwrap().contains("15 more"));
     }
 
+    #[test]
+    fn test_note_result_size() {
+        let mut untouched = json!({"results": [1, 2]});
+        note_result_size(
+            &mut untouched,
+            &ResultSize {
+                rows: 2,
+                limit: 20,
+                ..Default::default()
+            },
+        );
+        assert_eq!(untouched, json!({"results": [1, 2]}));
+
+        let mut held_back = json!({"results": [1, 2]});
+        note_result_size(
+            &mut held_back,
+            &ResultSize {
+                rows: 2,
+                limit: 2,
+                truncated: true,
+                clamped_from: Some(5_000),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            held_back["truncated"],
+            "[more than 2 matched; only the first 2 are shown]"
+        );
+        assert_eq!(
+            held_back["limit_note"],
+            "limit 5000 is over the server maximum; 2 was used"
+        );
+    }
+
     #[test]
     fn test_truncate_claim_previews() {
         let long_content = "a".repeat(1000);
@@ -138,10 +386,154 @@ mod tests {
             max_search_results: 20,
             max_entity_detail_chars: 10000,
             max_claim_preview_chars: 100,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+            max_claim_references_shown: 10,
         };
         truncate_claim_previews(&mut claims, &limits);
         let preview = claims["results"][0]["content"].as_str().unwrap();
         assert!(preview.contains("[truncated"));
         assert!(preview.len() < 200);
     }
+
+    #[test]
+    fn test_insert_claim_references_summarizes_long_lists() {
+        let limits = ToolResultLimits {
+            max_search_results: 20,
+            max_entity_detail_chars: 10000,
+            max_claim_preview_chars: 500,
+            max_result_tokens: 8000,
+            max_graph_context_chars: 6000,
+            max_verification_excerpt_chars: 1500,
+            max_formatted_result_chars: 24000,
+            response_formats: Default::default(),
+            max_claim_references_shown: 10,
+        };
+        let ids: Vec<EntityId> = (0..42).map(|_| EntityId::new()).collect();
+
+        let mut item = json!({"id": "c1"});
+        insert_claim_references(&mut item, &ids, &limits);
+        let shown = item["referenced_entity_ids"].as_array().unwrap();
+        assert_eq!(shown.len(), 10);
+        assert_eq!(shown[0], ids[0].to_string());
+        assert_eq!(
+            item["references_note"],
+            "references 42 entities, showing 10"
+        );
+
+        let mut item = json!({"id": "c2"});
+        insert_claim_references(&mut item, &ids[..3], &limits);
+        assert_eq!(item["referenced_entity_ids"].as_array().unwrap().len(), 3);
+        assert!(item.get("references_note").is_none());
+    }
+
+    #[test]
+    fn test_truncate_text_respects_char_boundaries() {
+        assert_eq!(truncate_text("short", 10), "short");
+        let truncated = truncate_text("Müller GmbH & Co. KG", 6);
+        assert_eq!(truncated, "Müller...[truncated, 20 chars total]");
+    }
+
+    #[test]
+    fn test_drop_results_to_fit() {
+        let items: Vec<Value> = (0..10)
+            .map(|i| json!({"id": i, "summary": "x".repeat(90)}))
+            .collect();
+        let mut result = json!({"results": items, "next_cursor": "abc"});
+        let untouched = result.clone();
+        drop_results_to_fit(&mut result, 10_000);
+        assert_eq!(result, untouched);
+
+        drop_results_to_fit(&mut result, 500);
+        assert!(result.to_string().len() <= 500 + 200, "{}", result);
+        let kept = result["results"].as_array().unwrap().len();
+        assert!((1..10).contains(&kept), "{}", kept);
+        assert_eq!(result["results"][0]["id"], 0);
+        assert_eq!(result["total_results"], 10);
+        assert!(result.get("next_cursor").is_none());
+        let note = result["truncated"].as_str().unwrap();
+        assert!(
+            note.starts_with(&format!("[{} more results omitted to fit", 10 - kept)),
+            "{}",
+            note
+        );
+        assert!(note.contains("lower limit"), "{}", note);
+
+        // Without results to drop, the result is left whole.
+        let mut entity = json!({"summary": "x".repeat(1000)});
+        drop_results_to_fit(&mut entity, 100);
+        assert_eq!(entity["summary"].as_str().unwrap().len(), 1000);
+    }
+
+    #[test]
+    fn test_truncate_at_sentence() {
+        let text = "The tanker Crius changed flag twice. It loaded at Primorsk in May. \
+                    Its manager is registered in Dubai.";
+        assert_eq!(truncate_at_sentence(text, 500), text);
+
+        let cut = truncate_at_sentence(text, 100);
+        assert!(cut.chars().count() <= 100, "{}", cut);
+        assert_eq!(
+            cut,
+            format!(
+                "The tanker Crius changed flag twice.\n[summary truncated, {} chars total]",
+                text.chars().count()
+            )
+        );
+
+        // A list is cut between lines.
+        let list = "3 entities:\n1. Acme Corp (organization)\n2. Globex (organization)\n\
+                    3. Initech (organization)";
+        assert_eq!(
+            truncate_at_sentence(list, 80),
+            "3 entities:\n1. Acme Corp (organization)\n[summary truncated, 90 chars total]"
+        );
+
+        // No sentence end in time: cut at a word.
+        let cut = truncate_at_sentence(&"Криус ".repeat(30), 80);
+        assert!(cut.starts_with("Криус Криус"), "{}", cut);
+        assert!(cut.chars().count() <= 80, "{}", cut);
+    }
+
+    #[test]
+    fn test_truncate_to_tokens_lands_under_budget() {
+        use crate::llm::tokens::{AnthropicCounter, HeuristicCounter, TiktokenCounter};
+
+        let samples = [
+            "The tanker Crius changed flag twice in 2024 before loading at Primorsk. ",
+            "Танкер Криус дважды сменил флаг в 2024 году перед погрузкой в Приморске. ",
+            "油轮克里乌斯号在2024年两次更换船旗，随后在普里莫尔斯克装货。",
+            "غيّرت الناقلة كريوس علمها مرتين في عام 2024 قبل التحميل في بريمورسك. ",
+            r#"{"entity_id": "5b1f0c2e", "aliases": ["Crius", "Криус"], "flag": "GA"} "#,
+        ];
+        let counters: [&dyn TokenCounter; 3] = [
+            &HeuristicCounter,
+            &TiktokenCounter::for_model("gpt-4o").unwrap(),
+            &AnthropicCounter::new(),
+        ];
+
+        for counter in counters {
+            for sample in samples {
+                let text = sample.repeat(40);
+                for budget in [20, 64, 300] {
+                    let truncated = truncate_to_tokens(&text, budget, counter);
+                    let counted = counter.count(&truncated);
+                    assert!(
+                        counted <= budget,
+                        "{}: {} tokens over budget {} for {:?}",
+                        counter.name(),
+                        counted,
+                        budget,
+                        &sample[..sample.char_indices().nth(10).unwrap().0]
+                    );
+                    assert!(truncated.contains("[truncated,"));
+                    assert!(text.starts_with(truncated.split("...[truncated").next().unwrap()));
+                }
+                assert_eq!(truncate_to_tokens(sample, 10_000, counter), sample);
+            }
+        }
+    }
 }
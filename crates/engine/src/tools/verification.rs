@@ -0,0 +1,846 @@
+//! Evidence gathering behind the Analyst's verify_claim tool: search the web
+//! and the trusted fetch sources for a claim, read a few pages independent of
+//! the claim's own source, and quote the passages that bear on it. Whether
+//! they corroborate or contradict the claim is left to the Analyst.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use autosint_common::api::fetch::{
+    ExtractMode, FetchOptions, FetchRequest, FetchResponse, SearchRequest, SearchResponse,
+    SourceQueryResponse,
+};
+use autosint_common::api::ErrorResponse;
+use autosint_common::config::VerificationConfig;
+use autosint_common::http_client::extract_domain;
+use autosint_common::types::{Claim, WEB_SEARCH_PROVIDER};
+
+use crate::graph::{ClaimSearchParams, GraphClient};
+
+use super::registry::ToolHandlerContext;
+use super::truncation::truncate_text;
+
+/// Words of the claim's content used in the search query.
+const QUERY_WORDS: usize = 20;
+
+/// Referenced entities whose other claims are listed.
+pub const MAX_SIGNAL_ENTITIES: usize = 3;
+
+/// Words too common to say whether a passage is about the claim.
+const STOPWORDS: &[&str] = &[
+    "about", "after", "against", "also", "among", "been", "before", "being", "between", "both",
+    "could", "does", "during", "each", "from", "have", "having", "into", "more", "most", "other",
+    "over", "said", "says", "since", "some", "such", "than", "that", "their", "them", "then",
+    "there", "these", "they", "this", "those", "through", "under", "until", "were", "what", "when",
+    "where", "which", "while", "will", "with", "would", "year", "years",
+];
+
+/// A page or source result that might bear on the claim.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub url: String,
+    pub title: Option<String>,
+    /// `web_search`, or `source:<id>` for a trusted fetch source.
+    pub via: String,
+    /// Text the source returned inline; None for links still to fetch.
+    pub content: Option<String>,
+}
+
+/// A passage quoted from a source, with the claim's terms it mentions.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Passage {
+    pub text: String,
+    pub matched_terms: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SourceEvidence {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub domain: String,
+    pub via: String,
+    pub passages: Vec<Passage>,
+}
+
+/// A candidate left unread, and why.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Skipped {
+    pub url: String,
+    pub reason: String,
+}
+
+/// What the outside sources said, for the tool result.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Evidence {
+    pub query: String,
+    pub sources: Vec<SourceEvidence>,
+    pub skipped: Vec<Skipped>,
+    /// Searches or fetches that failed, and budget refusals.
+    pub notes: Vec<String>,
+}
+
+/// Host of `url`, lowercased, without port or a leading `www.`.
+pub fn normalized_domain(url: &str) -> String {
+    let host = extract_domain(url).to_lowercase();
+    let host = host.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    host.strip_prefix("www.").unwrap_or(host).to_string()
+}
+
+/// Whether `domain` is `pattern` or one of its subdomains.
+fn domain_matches(domain: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches("www.").to_lowercase();
+    domain == pattern || domain.ends_with(&format!(".{}", pattern))
+}
+
+/// Domain of the document the claim was extracted from, if recorded.
+pub fn source_domain(claim: &Claim) -> Option<String> {
+    claim
+        .citation
+        .as_ref()
+        .map(|c| c.url.as_str())
+        .or(claim.raw_source_link.as_deref())
+        .map(normalized_domain)
+        .filter(|d| !d.is_empty())
+}
+
+/// The claim's significant words and the names of the entities it
+/// references, lowercased, for matching passages.
+pub fn claim_terms(content: &str, entity_names: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let names = entity_names
+        .iter()
+        .map(|n| n.trim().to_lowercase())
+        .filter(|n| !n.is_empty());
+    let words = words(content)
+        .into_iter()
+        .filter(|w| w.chars().count() >= 4 && !STOPWORDS.contains(&w.as_str()));
+    names
+        .chain(words)
+        .filter(|t| seen.insert(t.clone()))
+        .collect()
+}
+
+/// The search for a claim: its opening words, plus the names of referenced
+/// entities it doesn't mention, quoted.
+pub fn formulate_query(content: &str, entity_names: &[String]) -> String {
+    let mut query: Vec<String> = content
+        .split_whitespace()
+        .take(QUERY_WORDS)
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let lower = content.to_lowercase();
+    for name in entity_names {
+        let name = name.trim();
+        if !name.is_empty() && !lower.contains(&name.to_lowercase()) {
+            query.push(format!("\"{}\"", name));
+        }
+    }
+    query.join(" ")
+}
+
+/// Split candidates into those worth reading and those skipped: the claim's
+/// own source domain, denied domains, domains outside a non-empty allow list,
+/// and further results from a domain already kept (one page per domain keeps
+/// the sources independent of each other).
+pub fn filter_candidates(
+    candidates: Vec<Candidate>,
+    own_domain: Option<&str>,
+    config: &VerificationConfig,
+) -> (Vec<Candidate>, Vec<Skipped>) {
+    let mut kept = Vec::new();
+    let mut skipped = Vec::new();
+    let mut domains = HashSet::new();
+    for candidate in candidates {
+        let domain = normalized_domain(&candidate.url);
+        let reason = if domain.is_empty() {
+            Some("not a web address")
+        } else if own_domain
+            .is_some_and(|own| domain_matches(&domain, own) || domain_matches(own, &domain))
+        {
+            Some("the claim's own source")
+        } else if config
+            .denied_domains
+            .iter()
+            .any(|d| domain_matches(&domain, d))
+        {
+            Some("denied domain")
+        } else if !config.allowed_domains.is_empty()
+            && !config
+                .allowed_domains
+                .iter()
+                .any(|d| domain_matches(&domain, d))
+        {
+            Some("not an allowed domain")
+        } else if !domains.insert(domain) {
+            Some("another page from a domain already read")
+        } else {
+            None
+        };
+        match reason {
+            Some(reason) => skipped.push(Skipped {
+                url: candidate.url,
+                reason: reason.into(),
+            }),
+            None => kept.push(candidate),
+        }
+    }
+    (kept, skipped)
+}
+
+/// The passages of `content` that mention the most of `terms`, in document
+/// order, together at most `max_chars` long. Passages mentioning fewer than
+/// two terms (one, when there is only one) are left out.
+pub fn excerpt(content: &str, terms: &[String], max_chars: usize) -> Vec<Passage> {
+    let min_matches = terms.len().clamp(1, 2);
+    let mut scored: Vec<(usize, Passage)> = sentences(content)
+        .into_iter()
+        .enumerate()
+        .filter_map(|(position, text)| {
+            let lower = text.to_lowercase();
+            let text_words: HashSet<String> = words(&lower).into_iter().collect();
+            let matched_terms: Vec<String> = terms
+                .iter()
+                .filter(|t| {
+                    if t.contains(char::is_whitespace) {
+                        lower.contains(t.as_str())
+                    } else {
+                        text_words.contains(t.as_str())
+                    }
+                })
+                .cloned()
+                .collect();
+            (matched_terms.len() >= min_matches).then_some((
+                position,
+                Passage {
+                    text: text.to_string(),
+                    matched_terms,
+                },
+            ))
+        })
+        .collect();
+    // Best first, earlier first among equals.
+    scored.sort_by(|(a_pos, a), (b_pos, b)| {
+        b.matched_terms
+            .len()
+            .cmp(&a.matched_terms.len())
+            .then(a_pos.cmp(b_pos))
+    });
+
+    let mut chosen = Vec::new();
+    let mut remaining = max_chars;
+    for (position, mut passage) in scored {
+        if passage.text.chars().count() > remaining {
+            // The best passage is quoted even when it alone is over the cap.
+            if !chosen.is_empty() {
+                continue;
+            }
+            let cut: String = passage
+                .text
+                .chars()
+                .take(remaining.saturating_sub(1))
+                .collect();
+            passage.text = format!("{}…", cut.trim_end());
+        }
+        remaining = remaining.saturating_sub(passage.text.chars().count());
+        chosen.push((position, passage));
+        if remaining == 0 {
+            break;
+        }
+    }
+    chosen.sort_by_key(|(position, _)| *position);
+    chosen.into_iter().map(|(_, passage)| passage).collect()
+}
+
+/// `text` split into sentences, one line at a time.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let ends = matches!(c, '.' | '!' | '?')
+                && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if ends {
+                out.push(&line[start..i + c.len_utf8()]);
+                start = i + c.len_utf8();
+            }
+        }
+        out.push(&line[start..]);
+    }
+    out.into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Search for the claim, read what the search turns up and quote what bears
+/// on it. Fetches spend the investigation's fetch budget; failed searches and
+/// fetches are noted rather than failing the verification.
+pub async fn gather_evidence(
+    ctx: &ToolHandlerContext,
+    claim: &Claim,
+    entity_names: &[String],
+) -> Evidence {
+    let config = &ctx.services.verification;
+    let max_chars = ctx
+        .services
+        .tool_result_limits
+        .max_verification_excerpt_chars as usize;
+    let terms = claim_terms(&claim.content, entity_names);
+    let mut evidence = Evidence {
+        query: formulate_query(&claim.content, entity_names),
+        ..Default::default()
+    };
+
+    // Trusted sources first, so they win a domain over search results.
+    let mut candidates = Vec::new();
+    for source_id in &config.trusted_sources {
+        match query_source(ctx, source_id, &evidence.query).await {
+            Ok(found) => candidates.extend(found),
+            Err(e) => evidence
+                .notes
+                .push(format!("Source {} query failed: {}", source_id, e)),
+        }
+    }
+    match search(ctx, &evidence.query, config.max_search_results).await {
+        Ok(found) => candidates.extend(found),
+        Err(e) => evidence.notes.push(format!("Web search failed: {}", e)),
+    }
+
+    let own_domain = source_domain(claim);
+    let (candidates, skipped) = filter_candidates(candidates, own_domain.as_deref(), config);
+    evidence.skipped = skipped;
+
+    let mut fetches = 0;
+    let mut budget_exhausted = false;
+    for candidate in candidates {
+        let domain = normalized_domain(&candidate.url);
+        let content = match candidate.content {
+            Some(content) => content,
+            None if budget_exhausted => {
+                evidence.skipped.push(Skipped {
+                    url: candidate.url,
+                    reason: "fetch budget exhausted".into(),
+                });
+                continue;
+            }
+            None if fetches >= config.max_fetches => {
+                evidence.skipped.push(Skipped {
+                    url: candidate.url,
+                    reason: "over the verification fetch limit; read it with fetch_url".into(),
+                });
+                continue;
+            }
+            None => {
+                if let Some(budget) = &ctx.scope.fetch_budget {
+                    if let Err(message) = budget.reserve(&extract_domain(&candidate.url)).await {
+                        ctx.scope
+                            .session_counters
+                            .record_outbound(|f| f.budget_rejections += 1);
+                        evidence.notes.push(message);
+                        evidence.skipped.push(Skipped {
+                            url: candidate.url,
+                            reason: "fetch budget exhausted".into(),
+                        });
+                        budget_exhausted = true;
+                        continue;
+                    }
+                }
+                fetches += 1;
+                match fetch(ctx, &candidate.url).await {
+                    Ok(content) => content,
+                    Err(e) => {
+                        evidence.skipped.push(Skipped {
+                            url: candidate.url,
+                            reason: e,
+                        });
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let passages = excerpt(&content, &terms, max_chars);
+        if passages.is_empty() {
+            evidence.skipped.push(Skipped {
+                url: candidate.url,
+                reason: "no passage mentions the claim's terms".into(),
+            });
+            continue;
+        }
+        evidence.sources.push(SourceEvidence {
+            url: candidate.url,
+            title: candidate.title,
+            domain,
+            via: candidate.via,
+            passages,
+        });
+    }
+
+    metrics::counter!("tools.verify_claim.sources").increment(evidence.sources.len() as u64);
+    evidence
+}
+
+async fn search(
+    ctx: &ToolHandlerContext,
+    query: &str,
+    num_results: usize,
+) -> Result<Vec<Candidate>, String> {
+    let request = SearchRequest {
+        query: query.to_string(),
+        num_results: Some(num_results),
+    };
+    let response: SearchResponse = parse(ctx.services.fetch.search(&request).await).await?;
+    ctx.scope
+        .session_counters
+        .record_outbound(|f| f.record_search(WEB_SEARCH_PROVIDER));
+
+    Ok(response
+        .results
+        .into_iter()
+        .map(|r| Candidate {
+            url: r.url,
+            title: Some(r.title).filter(|t| !t.is_empty()),
+            via: "web_search".into(),
+            content: None,
+        })
+        .collect())
+}
+
+async fn query_source(
+    ctx: &ToolHandlerContext,
+    source_id: &str,
+    query: &str,
+) -> Result<Vec<Candidate>, String> {
+    let path = format!("/sources/{}/query", source_id);
+    let response: SourceQueryResponse = parse(
+        ctx.services
+            .fetch
+            .post(&path, &json!({ "query": query }))
+            .await,
+    )
+    .await?;
+    ctx.scope
+        .session_counters
+        .record_outbound(|f| f.record_search(source_id));
+
+    // Results without a URL can't be told apart from the claim's own source.
+    Ok(response
+        .results
+        .into_iter()
+        .filter_map(|r| {
+            Some(Candidate {
+                url: r.url?,
+                title: r.title,
+                via: format!("source:{}", source_id),
+                content: Some(r.content).filter(|c| !c.trim().is_empty()),
+            })
+        })
+        .collect())
+}
+
+/// The readable text of `url`.
+async fn fetch(ctx: &ToolHandlerContext, url: &str) -> Result<String, String> {
+    let request = FetchRequest {
+        url: url.to_string(),
+        options: Some(FetchOptions {
+            extract: Some(ExtractMode::Readable),
+            ..Default::default()
+        }),
+    };
+    let response: FetchResponse = parse(ctx.services.fetch.fetch(&request).await).await?;
+    let cached = response.metadata.cached;
+    let bytes = response.content.len() as u64;
+    ctx.scope
+        .session_counters
+        .record_outbound(|f| f.record_fetch(&extract_domain(url), bytes, cached));
+    Ok(response.content)
+}
+
+async fn parse<T: serde::de::DeserializeOwned>(
+    response: reqwest::Result<reqwest::Response>,
+) -> Result<T, String> {
+    let response = response.map_err(|e| format!("Fetch service request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = ErrorResponse::message_from_body(response.text().await.unwrap_or_default());
+        return Err(format!("Fetch service returned {}: {}", status, body));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Fetch service response: {}", e))
+}
+
+/// Claims in the graph about the same entities from other sources, most
+/// disputed first, for the graph side of the evidence.
+pub async fn related_claims(
+    graph: &GraphClient,
+    claim: &Claim,
+    max_claims: usize,
+    preview_chars: usize,
+) -> Result<Vec<Value>, String> {
+    let mut seen = HashSet::from([claim.id]);
+    let mut related = Vec::new();
+    for &entity_id in claim.referenced_entity_ids.iter().take(MAX_SIGNAL_ENTITIES) {
+        let page = graph
+            .search_claims(
+                &ClaimSearchParams {
+                    query: None,
+                    mode: None,
+                    published_after: None,
+                    published_before: None,
+                    source_entity_id: None,
+                    referenced_entity_id: Some(entity_id),
+                    attribution_depth: None,
+                    information_type: None,
+                    language: None,
+                    region: None,
+                    min_trust: None,
+                    ingested_before: None,
+                    limit: Some(max_claims as u32),
+                    cursor: None,
+                },
+                None,
+            )
+            .await
+            .map_err(|e| format!("Failed to get claims about {}: {}", entity_id, e))?;
+        related.extend(
+            page.results
+                .into_iter()
+                .map(|r| r.item)
+                .filter(|c| c.source_entity_id != claim.source_entity_id && seen.insert(c.id)),
+        );
+    }
+    related.sort_by_key(|c| std::cmp::Reverse(c.disputed_count));
+    related.truncate(max_claims);
+
+    Ok(related
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.id.to_string(),
+                "content": truncate_text(&c.content, preview_chars),
+                "source_entity_id": c.source_entity_id.to_string(),
+                "published_timestamp": c.published_timestamp.to_rfc3339(),
+                "source_trust_level": c.source_trust_level,
+                "disputed_count": c.disputed_count,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use chrono::Utc;
+
+    use autosint_common::types::{AttributionDepth, Citation, InformationType};
+    use autosint_common::EntityId;
+
+    use super::*;
+    use crate::tools::{FetchClient, SessionScope};
+
+    fn candidate(url: &str) -> Candidate {
+        Candidate {
+            url: url.into(),
+            title: None,
+            via: "web_search".into(),
+            content: None,
+        }
+    }
+
+    fn claim(content: &str, source_url: &str) -> Claim {
+        let mut claim = Claim::new(
+            content.into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            EntityId::new(),
+        );
+        claim.citation = Some(Citation::from_url(source_url));
+        claim
+    }
+
+    #[test]
+    fn test_normalized_domain() {
+        assert_eq!(
+            normalized_domain("https://WWW.Reuters.com:443/world/"),
+            "reuters.com"
+        );
+        assert_eq!(
+            normalized_domain("https://news.bbc.co.uk/"),
+            "news.bbc.co.uk"
+        );
+    }
+
+    #[test]
+    fn test_formulate_query() {
+        let names = vec!["Crius".to_string(), "Gulf Star Shipping".to_string()];
+        assert_eq!(
+            formulate_query("The tanker Crius was renamed in March.", &names),
+            "The tanker Crius was renamed in March \"Gulf Star Shipping\""
+        );
+    }
+
+    #[test]
+    fn test_claim_terms() {
+        let terms = claim_terms(
+            "The tanker Crius was renamed in March, they said.",
+            &["Gulf Star Shipping".to_string(), "Crius".to_string()],
+        );
+        assert_eq!(
+            terms,
+            ["gulf star shipping", "crius", "tanker", "renamed", "march"]
+        );
+    }
+
+    #[test]
+    fn test_filter_candidates() {
+        let config = VerificationConfig {
+            denied_domains: vec!["tabloid.example".into()],
+            ..Default::default()
+        };
+        let (kept, skipped) = filter_candidates(
+            vec![
+                candidate("https://www.maritime.example/crius"),
+                candidate("https://feeds.maritime.example/crius"),
+                candidate("https://news.tabloid.example/crius"),
+                candidate("https://registry.example/imo/9100001"),
+                candidate("https://registry.example/imo/9100002"),
+            ],
+            Some("maritime.example"),
+            &config,
+        );
+        let kept: Vec<_> = kept.iter().map(|c| c.url.as_str()).collect();
+        assert_eq!(kept, ["https://registry.example/imo/9100001"]);
+        let reasons: Vec<_> = skipped.iter().map(|s| s.reason.as_str()).collect();
+        assert_eq!(
+            reasons,
+            [
+                "the claim's own source",
+                "the claim's own source",
+                "denied domain",
+                "another page from a domain already read",
+            ]
+        );
+
+        let config = VerificationConfig {
+            allowed_domains: vec!["registry.example".into()],
+            ..Default::default()
+        };
+        let (kept, skipped) = filter_candidates(
+            vec![
+                candidate("https://registry.example/imo/9100001"),
+                candidate("https://blog.example/crius"),
+            ],
+            None,
+            &config,
+        );
+        assert_eq!(kept.len(), 1);
+        assert_eq!(skipped[0].reason, "not an allowed domain");
+    }
+
+    #[test]
+    fn test_excerpt_keeps_best_passages_in_order() {
+        let terms = claim_terms(
+            "The tanker Crius was renamed in March.",
+            &["Crius".to_string()],
+        );
+        let content = "Port news for the week.\n\
+            The Crius, a tanker, was renamed in March by its new owner. \
+            Weather delayed loading. Crius left Fujairah as a tanker in ballast.";
+
+        let passages = excerpt(content, &terms, 1000);
+        let texts: Vec<_> = passages.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            [
+                "The Crius, a tanker, was renamed in March by its new owner.",
+                "Crius left Fujairah as a tanker in ballast.",
+            ]
+        );
+        assert_eq!(
+            passages[0].matched_terms,
+            ["crius", "tanker", "renamed", "march"]
+        );
+
+        // Under a tight cap only the best passage is kept, cut to fit.
+        let passages = excerpt(content, &terms, 30);
+        assert_eq!(passages.len(), 1);
+        assert!(passages[0].text.starts_with("The Crius, a tanker"));
+        assert!(
+            passages[0].text.chars().count() <= 30,
+            "{}",
+            passages[0].text
+        );
+
+        assert!(excerpt("Nothing relevant here.", &terms, 1000).is_empty());
+    }
+
+    /// A Fetch service answering searches with pages on the claim's own site,
+    /// a denied site and two independent ones, one of which fails to fetch.
+    async fn mock_fetch_service() -> String {
+        let app = Router::new()
+            .route(
+                "/search",
+                post(|| async {
+                    Json(json!({"query": "crius", "results": [
+                        {"url": "https://maritime.example/crius", "title": "Original", "snippet": ""},
+                        {"url": "https://tabloid.example/crius", "title": "Gossip", "snippet": ""},
+                        {"url": "https://registry.example/crius", "title": "Registry", "snippet": ""},
+                        {"url": "https://down.example/crius", "title": "Down", "snippet": ""},
+                        {"url": "https://third.example/crius", "title": "Third", "snippet": ""},
+                    ]}))
+                }),
+            )
+            .route(
+                "/fetch",
+                post(|Json(request): Json<Value>| async move {
+                    let url = request["url"].as_str().unwrap_or_default().to_string();
+                    if url.contains("down.example") {
+                        return (
+                            axum::http::StatusCode::BAD_GATEWAY,
+                            Json(json!({"error": "upstream timed out"})),
+                        );
+                    }
+                    let content = "Registry entry. The tanker Crius was renamed in March \
+                                   and reflagged. Crius tanker renamed March. "
+                        .repeat(20);
+                    (
+                        axum::http::StatusCode::OK,
+                        Json(json!({
+                            "content": content,
+                            "metadata": {
+                                "status_code": 200,
+                                "url": url,
+                                "cached": false,
+                                "rendered": false,
+                            },
+                        })),
+                    )
+                }),
+            )
+            .route(
+                "/sources/{id}/query",
+                post(|| async {
+                    Json(json!({
+                        "results": [{
+                            "content": "Notice 14/2026: the tanker Crius was renamed Aurora in March.",
+                            "url": "https://notices.example/14-2026",
+                            "title": "Notice 14/2026",
+                        }],
+                        "metadata": {"source_id": "notices", "total_results": 1, "returned_results": 1},
+                    }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_gather_evidence_against_mock_fetch_service() {
+        let base_url = mock_fetch_service().await;
+        let stub = ToolHandlerContext::stub(None, SessionScope::default()).await;
+        let mut services = stub.services.clone();
+        services = services.with_fetch_client(FetchClient::new(
+            &base_url,
+            reqwest::Client::new(),
+            Default::default(),
+        ));
+        services.tool_result_limits.max_verification_excerpt_chars = 200;
+        let services = services.with_verification(VerificationConfig {
+            max_fetches: 2,
+            trusted_sources: vec!["notices".into()],
+            denied_domains: vec!["tabloid.example".into()],
+            ..Default::default()
+        });
+        let ctx = ToolHandlerContext::new(services, SessionScope::default());
+
+        let claim = claim(
+            "The tanker Crius was renamed in March.",
+            "https://www.maritime.example/2026/crius",
+        );
+        let evidence = gather_evidence(&ctx, &claim, &["Crius".to_string()]).await;
+
+        assert_eq!(evidence.query, "The tanker Crius was renamed in March");
+        assert!(evidence.notes.is_empty(), "{:?}", evidence.notes);
+        let read: Vec<_> = evidence
+            .sources
+            .iter()
+            .map(|s| (s.domain.as_str(), s.via.as_str()))
+            .collect();
+        assert_eq!(
+            read,
+            [
+                ("notices.example", "source:notices"),
+                ("registry.example", "web_search")
+            ]
+        );
+        // Quotes from each source stay under the per-source cap.
+        for source in &evidence.sources {
+            let chars: usize = source.passages.iter().map(|p| p.text.chars().count()).sum();
+            assert!(chars <= 200, "{} quoted {} chars", source.url, chars);
+        }
+
+        let skipped: Vec<_> = evidence
+            .skipped
+            .iter()
+            .map(|s| (s.url.as_str(), s.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped[0],
+            ("https://maritime.example/crius", "the claim's own source")
+        );
+        assert_eq!(
+            skipped[1],
+            ("https://tabloid.example/crius", "denied domain")
+        );
+        assert!(
+            skipped[2].1.contains("upstream timed out"),
+            "{:?}",
+            skipped[2]
+        );
+        // The failed fetch used up the second of two fetches.
+        assert_eq!(skipped[3].0, "https://third.example/crius");
+        assert!(skipped[3]
+            .1
+            .starts_with("over the verification fetch limit"));
+
+        let footprint = ctx.scope.session_counters.footprint.lock().unwrap();
+        assert_eq!(footprint.fetches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_fetch_service_is_noted() {
+        let ctx = ToolHandlerContext::stub(None, SessionScope::default()).await;
+        let evidence = gather_evidence(
+            &ctx,
+            &claim("The tanker Crius was renamed.", "https://maritime.example/"),
+            &[],
+        )
+        .await;
+        assert!(evidence.sources.is_empty());
+        assert!(
+            evidence.notes[0].starts_with("Web search failed"),
+            "{:?}",
+            evidence.notes
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! The investigation archive format: a gzipped tar holding a JSON manifest
+//! and one JSON-lines file per record type.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use autosint_common::InvestigationId;
+
+use crate::graph::Subgraph;
+use crate::store::InvestigationRecordSet;
+
+/// Archive format version written. Archives from newer versions are refused.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// `format` of every archive's manifest.
+const FORMAT_NAME: &str = "autosint-investigation";
+
+const MANIFEST: &str = "manifest.json";
+const INVESTIGATION: &str = "investigation.jsonl";
+const WORK_ORDERS: &str = "work_orders.jsonl";
+const ASSESSMENTS: &str = "assessments.jsonl";
+const FEEDBACK: &str = "assessment_feedback.jsonl";
+const SCRATCHPAD: &str = "scratchpad.jsonl";
+const ENTITIES: &str = "entities.jsonl";
+const RELATIONSHIPS: &str = "relationships.jsonl";
+const CLAIMS: &str = "claims.jsonl";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("Archive format version {found} is newer than this engine supports ({supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("Archive is larger than {0} bytes once decompressed")]
+    TooLarge(usize),
+
+    #[error("Malformed archive: {0}")]
+    Malformed(String),
+}
+
+/// What an archive holds, listed first so an archive can be identified
+/// without reading the rest.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub investigation_id: InvestigationId,
+    /// Lines in each section, by file name.
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// An investigation with its records and the slice of the graph it created.
+#[derive(Clone, Debug)]
+pub struct InvestigationArchive {
+    pub manifest: Manifest,
+    pub records: InvestigationRecordSet,
+    pub subgraph: Subgraph,
+}
+
+impl InvestigationArchive {
+    pub fn new(
+        records: InvestigationRecordSet,
+        subgraph: Subgraph,
+        exported_at: DateTime<Utc>,
+    ) -> Self {
+        let counts = [
+            (INVESTIGATION, 1),
+            (WORK_ORDERS, records.work_orders.len()),
+            (ASSESSMENTS, records.assessments.len()),
+            (FEEDBACK, records.feedback.len()),
+            (SCRATCHPAD, records.scratchpad.len()),
+            (ENTITIES, subgraph.entities.len()),
+            (RELATIONSHIPS, subgraph.relationships.len()),
+            (CLAIMS, subgraph.claims.len()),
+        ]
+        .into_iter()
+        .map(|(name, count)| (name.to_string(), count))
+        .collect();
+        Self {
+            manifest: Manifest {
+                format: FORMAT_NAME.into(),
+                version: FORMAT_VERSION,
+                exported_at,
+                investigation_id: records.investigation.id,
+                counts,
+            },
+            records,
+            subgraph,
+        }
+    }
+
+    /// The archive as a `.tar.gz`.
+    pub fn encode(&self) -> Result<Vec<u8>, ArchiveError> {
+        let records = &self.records;
+        let subgraph = &self.subgraph;
+        let manifest = serde_json::to_vec_pretty(&self.manifest)
+            .map_err(|e| ArchiveError::Malformed(e.to_string()))?;
+        let sections = [
+            (MANIFEST, manifest),
+            (
+                INVESTIGATION,
+                json_lines(std::slice::from_ref(&records.investigation))?,
+            ),
+            (WORK_ORDERS, json_lines(&records.work_orders)?),
+            (ASSESSMENTS, json_lines(&records.assessments)?),
+            (FEEDBACK, json_lines(&records.feedback)?),
+            (SCRATCHPAD, json_lines(&records.scratchpad)?),
+            (ENTITIES, json_lines(&subgraph.entities)?),
+            (RELATIONSHIPS, json_lines(&subgraph.relationships)?),
+            (CLAIMS, json_lines(&subgraph.claims)?),
+        ];
+
+        let io = |e: std::io::Error| ArchiveError::Malformed(e.to_string());
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), flate2::Compression::default()));
+        for (name, data) in sections {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(self.manifest.exported_at.timestamp().max(0) as u64);
+            header.set_cksum();
+            tar.append_data(&mut header, name, data.as_slice())
+                .map_err(io)?;
+        }
+        tar.into_inner().map_err(io)?.finish().map_err(io)
+    }
+
+    /// Read an archive, refusing one that decompresses to more than
+    /// `max_bytes` or was written by a newer format version.
+    pub fn decode(bytes: &[u8], max_bytes: usize) -> Result<Self, ArchiveError> {
+        let malformed = |e: std::io::Error| ArchiveError::Malformed(e.to_string());
+        let mut tarball = Vec::new();
+        GzDecoder::new(bytes)
+            .take(max_bytes as u64 + 1)
+            .read_to_end(&mut tarball)
+            .map_err(malformed)?;
+        if tarball.len() > max_bytes {
+            return Err(ArchiveError::TooLarge(max_bytes));
+        }
+
+        let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut tar = tar::Archive::new(tarball.as_slice());
+        for entry in tar.entries().map_err(malformed)? {
+            let mut entry = entry.map_err(malformed)?;
+            let name = entry
+                .path()
+                .map_err(malformed)?
+                .to_string_lossy()
+                .into_owned();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).map_err(malformed)?;
+            files.insert(name, data);
+        }
+
+        let manifest: Manifest = serde_json::from_slice(section(&files, MANIFEST)?)
+            .map_err(|e| ArchiveError::Malformed(format!("{}: {}", MANIFEST, e)))?;
+        if manifest.format != FORMAT_NAME {
+            return Err(ArchiveError::Malformed(format!(
+                "not an investigation archive (format {:?})",
+                manifest.format
+            )));
+        }
+        if manifest.version > FORMAT_VERSION {
+            return Err(ArchiveError::UnsupportedVersion {
+                found: manifest.version,
+                supported: FORMAT_VERSION,
+            });
+        }
+
+        let investigation = parse_lines(&files, INVESTIGATION)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ArchiveError::Malformed(format!("{} is empty", INVESTIGATION)))?;
+        let records = InvestigationRecordSet {
+            investigation,
+            work_orders: parse_lines(&files, WORK_ORDERS)?,
+            assessments: parse_lines(&files, ASSESSMENTS)?,
+            feedback: parse_lines(&files, FEEDBACK)?,
+            scratchpad: parse_lines(&files, SCRATCHPAD)?,
+        };
+        let subgraph = Subgraph {
+            entities: parse_lines(&files, ENTITIES)?,
+            relationships: parse_lines(&files, RELATIONSHIPS)?,
+            claims: parse_lines(&files, CLAIMS)?,
+        };
+        Ok(Self {
+            manifest,
+            records,
+            subgraph,
+        })
+    }
+}
+
+fn json_lines<T: Serialize>(items: &[T]) -> Result<Vec<u8>, ArchiveError> {
+    let mut out = Vec::new();
+    for item in items {
+        serde_json::to_writer(&mut out, item)
+            .map_err(|e| ArchiveError::Malformed(e.to_string()))?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+fn section<'a>(files: &'a HashMap<String, Vec<u8>>, name: &str) -> Result<&'a [u8], ArchiveError> {
+    files
+        .get(name)
+        .map(Vec::as_slice)
+        .ok_or_else(|| ArchiveError::Malformed(format!("missing {}", name)))
+}
+
+fn parse_lines<T: DeserializeOwned>(
+    files: &HashMap<String, Vec<u8>>,
+    name: &str,
+) -> Result<Vec<T>, ArchiveError> {
+    let text = std::str::from_utf8(section(files, name)?)
+        .map_err(|e| ArchiveError::Malformed(format!("{}: {}", name, e)))?;
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| ArchiveError::Malformed(format!("{} line {}: {}", name, i + 1, e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use serde_json::json;
+
+    use autosint_common::types::{
+        Assessment, AttributionDepth, Claim, Confidence, Entity, InformationType, Investigation,
+        Relationship, ScratchpadSection,
+    };
+
+    use super::*;
+
+    pub(in crate::transfer) fn sample() -> InvestigationArchive {
+        let investigation = Investigation::new("Who operates the tanker Crius?".into());
+        let operator = Entity::new("Gulf Star Shipping".into(), "organization".into());
+        let vessel = Entity::new("Crius".into(), "vessel".into());
+        let relationship = Relationship::new(operator.id, vessel.id, "operates".into());
+        let mut claim = Claim::new(
+            "Gulf Star Shipping operates the Crius.".into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            operator.id,
+        );
+        claim.referenced_entity_ids = vec![vessel.id];
+        claim.investigation_id = Some(investigation.id);
+        let mut assessment = Assessment::new(
+            investigation.id,
+            json!({"summary": "Gulf Star Shipping operates the Crius."}),
+            Confidence::Moderate,
+        );
+        assessment.claim_refs = vec![claim.id];
+        let now = Utc::now();
+        let records = InvestigationRecordSet {
+            investigation,
+            work_orders: Vec::new(),
+            assessments: vec![assessment],
+            feedback: Vec::new(),
+            scratchpad: vec![ScratchpadSection {
+                section: "leads".into(),
+                content: "Check the IMO registry.".into(),
+                created_at: now,
+                updated_at: now,
+            }],
+        };
+        let subgraph = Subgraph {
+            entities: vec![operator, vessel],
+            relationships: vec![relationship],
+            claims: vec![claim],
+        };
+        InvestigationArchive::new(records, subgraph, now)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let archive = sample();
+        let bytes = archive.encode().unwrap();
+        let decoded = InvestigationArchive::decode(&bytes, 1 << 20).unwrap();
+
+        assert_eq!(decoded.manifest, archive.manifest);
+        assert_eq!(decoded.manifest.counts[ENTITIES], 2);
+        assert_eq!(
+            decoded.records.investigation.id,
+            archive.records.investigation.id
+        );
+        assert_eq!(
+            decoded.records.assessments[0].claim_refs,
+            vec![archive.subgraph.claims[0].id]
+        );
+        assert_eq!(decoded.records.scratchpad, archive.records.scratchpad);
+        let names: Vec<_> = decoded
+            .subgraph
+            .entities
+            .iter()
+            .map(|e| e.canonical_name.as_str())
+            .collect();
+        assert_eq!(names, ["Gulf Star Shipping", "Crius"]);
+        assert_eq!(
+            decoded.subgraph.relationships[0].target_entity_id,
+            archive.subgraph.entities[1].id
+        );
+        assert_eq!(
+            decoded.subgraph.claims[0].content,
+            archive.subgraph.claims[0].content
+        );
+    }
+
+    #[test]
+    fn test_newer_version_refused() {
+        let mut archive = sample();
+        archive.manifest.version = FORMAT_VERSION + 1;
+        let bytes = archive.encode().unwrap();
+
+        let err = InvestigationArchive::decode(&bytes, 1 << 20).unwrap_err();
+        assert!(
+            matches!(err, ArchiveError::UnsupportedVersion { found, .. } if found == FORMAT_VERSION + 1),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_size_cap() {
+        let bytes = sample().encode().unwrap();
+        let err = InvestigationArchive::decode(&bytes, 256).unwrap_err();
+        assert!(matches!(err, ArchiveError::TooLarge(256)), "{}", err);
+    }
+
+    #[test]
+    fn test_not_an_archive() {
+        let err = InvestigationArchive::decode(b"not gzip", 1 << 20).unwrap_err();
+        assert!(matches!(err, ArchiveError::Malformed(_)), "{}", err);
+    }
+}
@@ -0,0 +1,462 @@
+//! Importing an investigation archive: IDs are kept where they are free here
+//! and reassigned where they're taken, archived entities are matched against
+//! this graph's, and references to anything in neither are dropped.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use autosint_common::api::engine::{ImportedEntityMatch, InvestigationImportReport};
+use autosint_common::config::{DedupConfig, TransferConfig};
+use autosint_common::ids::{
+    AssessmentId, ClaimId, EntityId, InvestigationId, RelationshipId, WorkOrderId,
+};
+
+use crate::graph::dedup::{self, EntityDedup};
+use crate::graph::{DedupResult, GraphClient};
+use crate::store::{InvestigationRecordSet, StoreClient};
+
+use super::{check_limits, ArchiveError, InvestigationArchive, TransferError};
+
+/// Where each archived ID lands here, and which IDs already exist here.
+struct IdMap {
+    ids: HashMap<Uuid, Uuid>,
+    /// Graph IDs already in this graph.
+    existing: HashSet<Uuid>,
+    reassigned: u64,
+    dropped: u64,
+}
+
+impl IdMap {
+    /// The ID `id` maps to: its import's ID if archived, itself if it
+    /// already exists here, None (counted as dropped) otherwise.
+    fn resolve(&mut self, id: Uuid) -> Option<Uuid> {
+        if let Some(&mapped) = self.ids.get(&id) {
+            return Some(mapped);
+        }
+        if self.existing.contains(&id) {
+            return Some(id);
+        }
+        self.dropped += 1;
+        None
+    }
+
+    fn resolve_all<T: Copy>(
+        &mut self,
+        ids: &[T],
+        uuid: impl Fn(T) -> Uuid,
+        wrap: impl Fn(Uuid) -> T,
+    ) -> Vec<T> {
+        ids.iter()
+            .filter_map(|&id| self.resolve(uuid(id)))
+            .map(wrap)
+            .collect()
+    }
+
+    /// Map an archived ID to itself, or to a new ID if it's `taken` here.
+    fn assign(&mut self, id: Uuid, taken: bool) {
+        let new_id = if taken {
+            self.reassigned += 1;
+            Uuid::new_v4()
+        } else {
+            id
+        };
+        self.ids.insert(id, new_id);
+    }
+
+    /// The archived ID's ID here. Only for IDs in the archive.
+    fn get(&self, id: Uuid) -> Uuid {
+        self.ids.get(&id).copied().unwrap_or(id)
+    }
+}
+
+/// Import an archive within `limits`: records into Postgres first (so an
+/// import that fails partway can be purged like any investigation), then
+/// entities, relationships and claims into the graph. Entities are only matched to
+/// existing ones on an exact dedup match; anything less creates a new
+/// entity.
+pub async fn import_investigation(
+    graph: &GraphClient,
+    store: &StoreClient,
+    archive: InvestigationArchive,
+    limits: &TransferConfig,
+    dedup_config: &DedupConfig,
+) -> Result<InvestigationImportReport, TransferError> {
+    let InvestigationArchive {
+        manifest,
+        records,
+        subgraph,
+    } = archive;
+    let source_investigation_id = records.investigation.id;
+    check_limits(&subgraph, limits)?;
+    check_endpoints(&subgraph)?;
+
+    let mut report = InvestigationImportReport {
+        investigation_id: source_investigation_id,
+        source_investigation_id,
+        format_version: manifest.version,
+        work_orders: records.work_orders.len() as u64,
+        assessments: records.assessments.len() as u64,
+        assessment_feedback: records.feedback.len() as u64,
+        scratchpad_sections: records.scratchpad.len() as u64,
+        entities_created: 0,
+        entities_matched: Vec::new(),
+        relationships_created: 0,
+        relationships_matched: 0,
+        claims_created: 0,
+        ids_reassigned: 0,
+        references_dropped: 0,
+    };
+
+    // Which IDs are taken here: the records' own, and every graph ID the
+    // archive holds or refers to.
+    let record_ids: Vec<Uuid> = std::iter::once(records.investigation.id.0)
+        .chain(records.work_orders.iter().map(|wo| wo.id.0))
+        .chain(records.assessments.iter().map(|a| a.id.0))
+        .collect();
+    let taken_records = store.existing_record_ids(&record_ids).await?;
+    let graph_ids = graph_ids(&records, &subgraph);
+    let existing: HashSet<Uuid> = graph
+        .existing_ids(graph_ids.iter().map(Uuid::to_string).collect())
+        .await?
+        .iter()
+        .filter_map(|id| id.parse().ok())
+        .collect();
+
+    let mut map = IdMap {
+        ids: HashMap::new(),
+        existing,
+        reassigned: 0,
+        dropped: 0,
+    };
+    for &id in &record_ids {
+        map.assign(id, taken_records.contains(&id));
+    }
+
+    let entity_dedup = EntityDedup::new(graph, dedup_config, None);
+    let mut matched: HashSet<Uuid> = HashSet::new();
+    for entity in &subgraph.entities {
+        let result = entity_dedup
+            .find_duplicate(
+                &entity.canonical_name,
+                &entity.kind,
+                &entity.identifiers,
+                None,
+            )
+            .await?;
+        if let DedupResult::ExactMatch(existing_id) = result {
+            map.ids.insert(entity.id.0, existing_id.0);
+            matched.insert(entity.id.0);
+            report.entities_matched.push(ImportedEntityMatch {
+                archived_id: entity.id,
+                entity_id: existing_id,
+                canonical_name: entity.canonical_name.clone(),
+            });
+        } else {
+            let taken = map.existing.contains(&entity.id.0);
+            map.assign(entity.id.0, taken);
+        }
+    }
+    for id in subgraph
+        .relationships
+        .iter()
+        .map(|r| r.id.0)
+        .chain(subgraph.claims.iter().map(|c| c.id.0))
+    {
+        let taken = map.existing.contains(&id);
+        map.assign(id, taken);
+    }
+
+    let investigation_id = InvestigationId::from_uuid(map.get(source_investigation_id.0));
+    report.investigation_id = investigation_id;
+    let records = remap_records(records, &mut map);
+    store.import_investigation_records(&records).await?;
+
+    let provenance = |id: Option<InvestigationId>| {
+        (id == Some(source_investigation_id)).then_some(investigation_id)
+    };
+
+    for entity in &subgraph.entities {
+        if matched.contains(&entity.id.0) {
+            continue;
+        }
+        let mut entity = entity.clone();
+        entity.id = EntityId::from_uuid(map.get(entity.id.0));
+        entity.created_by_investigation = provenance(entity.created_by_investigation);
+        graph.create_entity(&entity, None).await?;
+        report.entities_created += 1;
+    }
+
+    for rel in &subgraph.relationships {
+        let mut rel = rel.clone();
+        let both_matched =
+            matched.contains(&rel.source_entity_id.0) && matched.contains(&rel.target_entity_id.0);
+        rel.source_entity_id = EntityId::from_uuid(map.get(rel.source_entity_id.0));
+        rel.target_entity_id = EntityId::from_uuid(map.get(rel.target_entity_id.0));
+        if both_matched {
+            let existing = graph
+                .relationships_between(rel.source_entity_id, rel.target_entity_id)
+                .await?;
+            if let Some((duplicate, _)) =
+                dedup::duplicate_relationship(&existing, &rel.description, dedup_config)
+            {
+                map.ids.insert(rel.id.0, duplicate.id.0);
+                report.relationships_matched += 1;
+                continue;
+            }
+        }
+        rel.id = RelationshipId::from_uuid(map.get(rel.id.0));
+        rel.created_by_investigation = provenance(rel.created_by_investigation);
+        // Matched endpoints can make an archived edge a self-loop here.
+        graph.create_relationship(&rel, None, true).await?;
+        report.relationships_created += 1;
+    }
+
+    for claim in &subgraph.claims {
+        let mut claim = claim.clone();
+        claim.id = ClaimId::from_uuid(map.get(claim.id.0));
+        claim.source_entity_id = EntityId::from_uuid(map.get(claim.source_entity_id.0));
+        claim.referenced_entity_ids =
+            map.resolve_all(&claim.referenced_entity_ids, |id| id.0, EntityId::from_uuid);
+        claim.auto_linked_entity_ids = map.resolve_all(
+            &claim.auto_linked_entity_ids,
+            |id| id.0,
+            EntityId::from_uuid,
+        );
+        claim.supports_relationship_ids = map.resolve_all(
+            &claim.supports_relationship_ids,
+            |id| id.0,
+            RelationshipId::from_uuid,
+        );
+        claim.investigation_id = Some(investigation_id);
+        graph.create_claim(&claim, None).await?;
+        report.claims_created += 1;
+    }
+
+    report.ids_reassigned = map.reassigned;
+    report.references_dropped = map.dropped;
+    metrics::counter!("transfer.imports").increment(1);
+    tracing::info!(
+        investigation_id = %investigation_id,
+        source_investigation_id = %source_investigation_id,
+        entities_created = report.entities_created,
+        entities_matched = report.entities_matched.len(),
+        claims_created = report.claims_created,
+        ids_reassigned = report.ids_reassigned,
+        references_dropped = report.references_dropped,
+        "Investigation imported"
+    );
+    Ok(report)
+}
+
+/// Refuse an archive whose claims or relationships hang off entities it
+/// doesn't hold: they couldn't be written.
+fn check_endpoints(subgraph: &crate::graph::Subgraph) -> Result<(), ArchiveError> {
+    let entities: HashSet<EntityId> = subgraph.entities.iter().map(|e| e.id).collect();
+    let missing = subgraph
+        .claims
+        .iter()
+        .map(|c| c.source_entity_id)
+        .chain(
+            subgraph
+                .relationships
+                .iter()
+                .flat_map(|r| [r.source_entity_id, r.target_entity_id]),
+        )
+        .find(|id| !entities.contains(id));
+    match missing {
+        Some(id) => Err(ArchiveError::Malformed(format!(
+            "entity {} is used but not included",
+            id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Every graph ID the archive holds or its records refer to.
+fn graph_ids(records: &InvestigationRecordSet, subgraph: &crate::graph::Subgraph) -> Vec<Uuid> {
+    let mut ids: HashSet<Uuid> = HashSet::new();
+    ids.extend(subgraph.entities.iter().map(|e| e.id.0));
+    ids.extend(subgraph.relationships.iter().map(|r| r.id.0));
+    for claim in &subgraph.claims {
+        ids.insert(claim.id.0);
+        ids.extend(claim.referenced_entity_ids.iter().map(|id| id.0));
+        ids.extend(claim.auto_linked_entity_ids.iter().map(|id| id.0));
+        ids.extend(claim.supports_relationship_ids.iter().map(|id| id.0));
+    }
+    for wo in &records.work_orders {
+        ids.extend(wo.referenced_entities.iter().map(|id| id.0));
+    }
+    for assessment in &records.assessments {
+        ids.extend(assessment.entity_refs.iter().map(|id| id.0));
+        ids.extend(assessment.claim_refs.iter().map(|id| id.0));
+    }
+    for feedback in &records.feedback {
+        ids.extend(feedback.disputed_claim_ids.iter().map(|id| id.0));
+    }
+    ids.into_iter().collect()
+}
+
+/// The records with every ID moved to where it lands here, including IDs
+/// quoted in assessment content, work order summaries and scratchpad notes.
+fn remap_records(mut records: InvestigationRecordSet, map: &mut IdMap) -> InvestigationRecordSet {
+    let investigation_id = InvestigationId::from_uuid(map.get(records.investigation.id.0));
+    records.investigation.id = investigation_id;
+
+    let archived_work_orders: HashSet<Uuid> =
+        records.work_orders.iter().map(|wo| wo.id.0).collect();
+    for wo in &mut records.work_orders {
+        wo.id = WorkOrderId::from_uuid(map.get(wo.id.0));
+        wo.investigation_id = investigation_id;
+        wo.referenced_entities =
+            map.resolve_all(&wo.referenced_entities, |id| id.0, EntityId::from_uuid);
+        wo.proposed_by = wo
+            .proposed_by
+            .filter(|id| archived_work_orders.contains(&id.0))
+            .map(|id| WorkOrderId::from_uuid(map.get(id.0)));
+        if let Some(summary) = &mut wo.summary {
+            rewrite_json_ids(summary, &map.ids);
+        }
+    }
+
+    let archived_assessments: HashSet<Uuid> = records.assessments.iter().map(|a| a.id.0).collect();
+    for assessment in &mut records.assessments {
+        assessment.id = AssessmentId::from_uuid(map.get(assessment.id.0));
+        assessment.investigation_id = investigation_id;
+        assessment.entity_refs =
+            map.resolve_all(&assessment.entity_refs, |id| id.0, EntityId::from_uuid);
+        assessment.claim_refs =
+            map.resolve_all(&assessment.claim_refs, |id| id.0, ClaimId::from_uuid);
+        rewrite_json_ids(&mut assessment.content, &map.ids);
+        // A superseded assessment that wasn't archived isn't here to point at,
+        // and neither is the diff against it.
+        match assessment
+            .supersedes_assessment_id
+            .filter(|id| archived_assessments.contains(&id.0))
+        {
+            Some(previous) => {
+                let previous = AssessmentId::from_uuid(map.get(previous.0));
+                assessment.supersedes_assessment_id = Some(previous);
+                if let Some(diff) = &mut assessment.diff {
+                    diff.previous_assessment_id = previous;
+                }
+            }
+            None => {
+                assessment.supersedes_assessment_id = None;
+                assessment.diff = None;
+            }
+        }
+    }
+
+    for feedback in &mut records.feedback {
+        feedback.assessment_id = AssessmentId::from_uuid(map.get(feedback.assessment_id.0));
+        feedback.disputed_claim_ids =
+            map.resolve_all(&feedback.disputed_claim_ids, |id| id.0, ClaimId::from_uuid);
+    }
+    for section in &mut records.scratchpad {
+        section.content = rewrite_ids(&section.content, &map.ids);
+    }
+    records
+}
+
+fn rewrite_json_ids(value: &mut Value, ids: &HashMap<Uuid, Uuid>) {
+    match value {
+        Value::String(s) => *s = rewrite_ids(s, ids),
+        Value::Array(items) => items.iter_mut().for_each(|v| rewrite_json_ids(v, ids)),
+        Value::Object(map) => map.values_mut().for_each(|v| rewrite_json_ids(v, ids)),
+        _ => {}
+    }
+}
+
+/// Replace each hyphenated UUID in `text` found in `ids` with its mapping.
+fn rewrite_ids(text: &str, ids: &HashMap<Uuid, Uuid>) -> String {
+    const LEN: usize = 36;
+    let bytes = text.as_bytes();
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i + LEN <= bytes.len() {
+        let bounded = (i == 0 || !bytes[i - 1].is_ascii_hexdigit())
+            && bytes.get(i + LEN).is_none_or(|b| !b.is_ascii_hexdigit());
+        let mapped = bounded
+            .then(|| text.get(i..i + LEN))
+            .flatten()
+            .filter(|s| s.as_bytes()[8] == b'-')
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .and_then(|id| ids.get(&id));
+        match mapped {
+            Some(new_id) => {
+                out.push_str(&text[copied..i]);
+                out.push_str(&new_id.to_string());
+                i += LEN;
+                copied = i;
+            }
+            None => i += 1,
+        }
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_rewrite_ids() {
+        let (old, new, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let ids = HashMap::from([(old, new)]);
+
+        let text = format!("See claim {} [1] and {}; also {}x", old, other, old);
+        assert_eq!(
+            rewrite_ids(&text, &ids),
+            format!("See claim {} [1] and {}; also {}x", new, other, new)
+        );
+        // Part of a longer hex run: not an ID.
+        let embedded = format!("a{}", old);
+        assert_eq!(rewrite_ids(&embedded, &ids), embedded);
+        assert_eq!(rewrite_ids("Crius → Fujairah", &ids), "Crius → Fujairah");
+
+        let mut content = json!({
+            "summary": format!("Per {}", old),
+            "citations": [{"marker": "[1]", "claim_id": old.to_string()}],
+        });
+        rewrite_json_ids(&mut content, &ids);
+        assert_eq!(content["summary"], format!("Per {}", new));
+        assert_eq!(content["citations"][0]["claim_id"], new.to_string());
+    }
+
+    #[test]
+    fn test_remap_records() {
+        let archive = super::super::archive::tests::sample();
+        let mut records = archive.records;
+        let claim_id = archive.subgraph.claims[0].id;
+        let missing = EntityId::new();
+        records.assessments[0].entity_refs = vec![missing];
+        records.assessments[0].supersedes_assessment_id = Some(AssessmentId::new());
+
+        let (investigation, assessment) = (records.investigation.id, records.assessments[0].id);
+        let moved = Uuid::new_v4();
+        let mut map = IdMap {
+            ids: HashMap::from([
+                (investigation.0, investigation.0),
+                (assessment.0, moved),
+                (claim_id.0, claim_id.0),
+            ]),
+            existing: HashSet::new(),
+            reassigned: 0,
+            dropped: 0,
+        };
+        let records = remap_records(records, &mut map);
+
+        let assessment = &records.assessments[0];
+        assert_eq!(assessment.id.0, moved);
+        assert_eq!(assessment.claim_refs, vec![claim_id]);
+        assert!(assessment.entity_refs.is_empty());
+        assert!(assessment.supersedes_assessment_id.is_none());
+        assert_eq!(map.dropped, 1);
+    }
+}
@@ -0,0 +1,304 @@
+//! Investigation prompt pre-flight: one cheap LLM call that sizes a prompt
+//! before an investigation is created for it.
+
+use axum::http::StatusCode;
+use serde::Deserialize;
+
+use autosint_common::types::{Investigation, StrictnessProfile};
+
+use crate::llm::{ContentBlock, LlmCaller, LlmError, Message, Role};
+
+pub use autosint_common::api::engine::{PromptScope, PromptTriage};
+
+/// Prompt template name (`config/prompts/triage.md`).
+pub const TRIAGE_PROMPT: &str = "triage";
+
+/// Focus entities kept from a triage reply.
+const MAX_FOCUS_ENTITIES: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TriageError {
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+
+    #[error("Triage reply was not valid after {attempts} attempt(s): {message}")]
+    Parse { attempts: u32, message: String },
+
+    #[error("Prompt is too vague to investigate: {0}")]
+    TooVague(String),
+}
+
+impl TriageError {
+    /// HTTP status for API handlers surfacing this error.
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            Self::Llm(_) | Self::Parse { .. } => StatusCode::BAD_GATEWAY,
+            Self::TooVague(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+/// The reply format requested by the triage prompt. Anything else is a parse
+/// failure.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TriageReply {
+    scope: PromptScope,
+    focus_entities: Vec<String>,
+    max_cycles: u32,
+    strictness: StrictnessProfile,
+    too_vague: bool,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Triage `prompt`, re-asking up to `max_attempts` calls in total when the
+/// reply doesn't parse. `max_cycles` in the result is clamped to
+/// `1..=max_cycles_ceiling`.
+pub async fn triage_prompt(
+    llm: &dyn LlmCaller,
+    system_prompt: &str,
+    prompt: &str,
+    max_cycles_ceiling: u32,
+    max_attempts: u32,
+) -> Result<PromptTriage, TriageError> {
+    let mut messages = vec![text_message(
+        Role::User,
+        format!(
+            "## Prompt\n\n{}\n\n---\nMaximum cycles: {}",
+            prompt, max_cycles_ceiling
+        ),
+    )];
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        let response = llm.chat(system_prompt, &messages, &[]).await?;
+        let text: String = response
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        match parse_triage(&text, max_cycles_ceiling) {
+            Ok(triage) => {
+                let scope = match triage.scope {
+                    PromptScope::Narrow => "narrow",
+                    PromptScope::Medium => "medium",
+                    PromptScope::Broad => "broad",
+                };
+                metrics::counter!("triage.completed", "scope" => scope).increment(1);
+                return Ok(triage);
+            }
+            Err(message) => {
+                metrics::counter!("triage.parse_failures").increment(1);
+                if attempt >= max_attempts {
+                    return Err(TriageError::Parse {
+                        attempts: attempt,
+                        message,
+                    });
+                }
+                tracing::warn!(attempt, error = %message, "Triage reply did not parse, retrying");
+                messages.push(text_message(Role::Assistant, text));
+                messages.push(text_message(
+                    Role::User,
+                    format!(
+                        "That reply could not be used ({}). Respond with only the JSON object.",
+                        message
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Parse a triage reply. The whole reply must be the JSON object.
+pub fn parse_triage(text: &str, max_cycles_ceiling: u32) -> Result<PromptTriage, String> {
+    let reply: TriageReply = serde_json::from_str(text.trim()).map_err(|e| e.to_string())?;
+
+    let mut focus_entities: Vec<String> = Vec::new();
+    for name in reply.focus_entities {
+        let name = name.trim();
+        if !name.is_empty()
+            && !focus_entities
+                .iter()
+                .any(|existing| existing.eq_ignore_ascii_case(name))
+        {
+            focus_entities.push(name.to_string());
+        }
+    }
+    focus_entities.truncate(MAX_FOCUS_ENTITIES);
+
+    Ok(PromptTriage {
+        scope: reply.scope,
+        focus_entities,
+        max_cycles: reply.max_cycles.clamp(1, max_cycles_ceiling.max(1)),
+        strictness: reply.strictness,
+        too_vague: reply.too_vague,
+        reason: reply
+            .reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty()),
+    })
+}
+
+/// Reject prompts the triage flagged as too vague to investigate.
+pub fn ensure_actionable(triage: &PromptTriage) -> Result<(), TriageError> {
+    if triage.too_vague {
+        return Err(TriageError::TooVague(
+            triage
+                .reason
+                .clone()
+                .unwrap_or_else(|| "name a specific subject or question".into()),
+        ));
+    }
+    Ok(())
+}
+
+/// Apply accepted triage suggestions to a new investigation.
+pub fn apply_triage(investigation: &mut Investigation, triage: &PromptTriage) {
+    investigation.max_cycles = Some(triage.max_cycles);
+    investigation.focus_entities = triage.focus_entities.clone();
+    investigation.strictness = Some(triage.strictness);
+}
+
+fn text_message(role: Role, text: String) -> Message {
+    Message {
+        role,
+        content: vec![ContentBlock::Text { text }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LlmResponse, StopReason, TokenUsage, ToolDefinition};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    /// Mock LLM that returns pre-configured text replies in sequence and
+    /// records the message count of each call.
+    struct MockLlm {
+        replies: Mutex<Vec<String>>,
+        calls: Mutex<Vec<usize>>,
+    }
+
+    impl MockLlm {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: Mutex::new(replies.iter().rev().map(|r| r.to_string()).collect()),
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl LlmCaller for MockLlm {
+        fn chat<'a>(
+            &'a self,
+            _system: &'a str,
+            messages: &'a [Message],
+            _tools: &'a [ToolDefinition],
+        ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+            self.calls.lock().unwrap().push(messages.len());
+            let text = self.replies.lock().unwrap().pop().unwrap_or_default();
+            Box::pin(async move {
+                Ok(LlmResponse {
+                    content: vec![ContentBlock::Text { text }],
+                    stop_reason: StopReason::EndTurn,
+                    usage: TokenUsage::default(),
+                    served_by: None,
+                })
+            })
+        }
+    }
+
+    const NARROW: &str = r#"{"scope": "narrow", "focus_entities": ["Northwind Star", " northwind star ", ""],
+        "max_cycles": 2, "strictness": "strict", "too_vague": false, "reason": null}"#;
+
+    const VAGUE: &str = r#"{"scope": "broad", "focus_entities": ["China"], "max_cycles": 10,
+        "strictness": "standard", "too_vague": true,
+        "reason": "No question. Try: 'Chinese state investment in Zambian copper since 2018'."}"#;
+
+    #[test]
+    fn test_parse_normalizes_reply() {
+        let triage = parse_triage(NARROW, 10).unwrap();
+        assert_eq!(triage.scope, PromptScope::Narrow);
+        assert_eq!(triage.focus_entities, vec!["Northwind Star".to_string()]);
+        assert_eq!(triage.max_cycles, 2);
+        assert_eq!(triage.strictness, StrictnessProfile::Strict);
+        assert!(!triage.too_vague);
+        assert_eq!(triage.reason, None);
+
+        // Suggestions outside the safety ceiling are clamped.
+        let over = NARROW.replace("\"max_cycles\": 2", "\"max_cycles\": 40");
+        assert_eq!(parse_triage(&over, 10).unwrap().max_cycles, 10);
+        let zero = NARROW.replace("\"max_cycles\": 2", "\"max_cycles\": 0");
+        assert_eq!(parse_triage(&zero, 10).unwrap().max_cycles, 1);
+    }
+
+    #[test]
+    fn test_parse_is_strict() {
+        let fenced = format!("```json\n{}\n```", NARROW);
+        assert!(parse_triage(&fenced, 10).is_err());
+        assert!(parse_triage(&format!("Here you go: {}", NARROW), 10).is_err());
+
+        let extra_field = NARROW.replace("\"reason\": null", "\"reason\": null, \"notes\": \"x\"");
+        assert!(parse_triage(&extra_field, 10).is_err());
+        let bad_scope = NARROW.replace("\"narrow\"", "\"tiny\"");
+        assert!(parse_triage(&bad_scope, 10).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_triage_retries_after_parse_failure() {
+        let llm = MockLlm::new(&["Sure! This prompt is narrow.", NARROW]);
+        let triage = triage_prompt(&llm, "system", "Who owns the Northwind Star?", 10, 2)
+            .await
+            .unwrap();
+        assert_eq!(triage.scope, PromptScope::Narrow);
+        // The retry carries the bad reply and a correction.
+        assert_eq!(*llm.calls.lock().unwrap(), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_triage_gives_up_after_max_attempts() {
+        let llm = MockLlm::new(&["not json", "still not json", NARROW]);
+        let err = triage_prompt(&llm, "system", "Who owns it?", 10, 2)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TriageError::Parse { attempts: 2, .. }));
+        assert_eq!(err.http_status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(llm.calls.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_apply_triage_sets_investigation_defaults() {
+        let llm = MockLlm::new(&[NARROW]);
+        let triage = triage_prompt(&llm, "system", "Who owns the Northwind Star?", 10, 2)
+            .await
+            .unwrap();
+        ensure_actionable(&triage).unwrap();
+
+        let mut investigation = Investigation::new("Who owns the Northwind Star?".into());
+        apply_triage(&mut investigation, &triage);
+        assert_eq!(investigation.max_cycles, Some(2));
+        assert_eq!(investigation.focus_entities, vec!["Northwind Star"]);
+        assert_eq!(investigation.strictness, Some(StrictnessProfile::Strict));
+    }
+
+    #[tokio::test]
+    async fn test_vague_prompt_rejected() {
+        let llm = MockLlm::new(&[VAGUE]);
+        let triage = triage_prompt(&llm, "system", "tell me about china", 10, 2)
+            .await
+            .unwrap();
+        assert!(triage.too_vague);
+
+        let err = ensure_actionable(&triage).unwrap_err();
+        assert_eq!(err.http_status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(err.to_string().contains("Zambian copper"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Integration tests for claim chunk embeddings.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults).
+//! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`.
+//! Vectors are synthetic: each topic is one axis of the 1536-dimension space.
+use std::time::Duration;
+
+use autosint_common::config::ClaimChunkConfig;
+use autosint_common::ids::ClaimId;
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType};
+use chrono::Utc;
+use neo4rs::query;
+
+use autosint_engine::embeddings::chunk_claim;
+use autosint_engine::graph::{ClaimSearchParams, GraphClient, GraphError, SearchMode};
+
+/// Upper bound for index population after writes.
+const INDEX_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Axis of the claim's main topic, shipping.
+const SHIPPING: usize = 0;
+/// Axis of the topic the long claim mentions once, a sanctions designation.
+const SANCTIONS: usize = 1;
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let client = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    client
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+    client
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+    client
+}
+
+/// A unit vector mixing two topic axes.
+fn topic(weights: &[(usize, f32)]) -> Vec<f32> {
+    let mut v = vec![0.0f32; 1536];
+    for &(axis, weight) in weights {
+        v[axis] = weight;
+    }
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    v.iter().map(|x| x / norm).collect()
+}
+
+/// A long port report whose one sanctions sentence sits in the middle.
+fn long_report() -> String {
+    let mut sentences: Vec<String> = (0..12)
+        .map(|i| {
+            format!(
+                "Berth {} at the container terminal handled two feeder vessels this week.",
+                i
+            )
+        })
+        .collect();
+    sentences.insert(
+        6,
+        "The terminal operator was separately designated under new sanctions.".to_string(),
+    );
+    sentences.join(" ")
+}
+
+async fn create_claim(
+    graph: &GraphClient,
+    source: &Entity,
+    content: String,
+    embedding: Vec<f32>,
+) -> Claim {
+    let claim = Claim::new(
+        content,
+        Utc::now(),
+        AttributionDepth::Primary,
+        InformationType::Assertion,
+        source.id,
+    );
+    graph.create_claim(&claim, Some(embedding)).await.unwrap()
+}
+
+fn semantic(limit: u32) -> ClaimSearchParams {
+    ClaimSearchParams {
+        query: Some("sanctions designation".into()),
+        mode: Some(SearchMode::Semantic),
+        published_after: None,
+        published_before: None,
+        source_entity_id: None,
+        referenced_entity_id: None,
+        attribution_depth: None,
+        information_type: None,
+        language: None,
+        region: None,
+        min_trust: None,
+        ingested_before: None,
+        limit: Some(limit),
+        cursor: None,
+    }
+}
+
+/// Scores from the whole-claim vector index alone.
+async fn whole_claim_scores(graph: &GraphClient, embedding: &[f32]) -> Vec<(String, f64)> {
+    let emb: Vec<f64> = embedding.iter().map(|&f| f as f64).collect();
+    let mut result = graph
+        .inner()
+        .execute(
+            query(
+                "CALL db.index.vector.queryNodes('claim_embedding', 10, $embedding) \
+                 YIELD node, score RETURN node.id AS id, score",
+            )
+            .param("embedding", emb),
+        )
+        .await
+        .unwrap();
+    let mut scores = Vec::new();
+    while let Some(row) = result.next().await.unwrap() {
+        scores.push((row.get("id").unwrap(), row.get("score").unwrap()));
+    }
+    scores
+}
+
+async fn chunk_count(graph: &GraphClient, id: ClaimId) -> i64 {
+    let mut result = graph
+        .inner()
+        .execute(
+            query("MATCH (k:ClaimChunk)-[:PART_OF]->(:Claim {id: $id}) RETURN count(k) AS n")
+                .param("id", id.to_string()),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_buried_topic_found_through_chunks() {
+    let graph = setup().await;
+    let source = graph
+        .create_entity(
+            &Entity::new("Port Gazette".into(), "publication".into()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    // The long claim's whole vector is all shipping. A short claim leans
+    // towards sanctions, so it wins on whole-claim vectors alone.
+    let report = create_claim(&graph, &source, long_report(), topic(&[(SHIPPING, 1.0)])).await;
+    let decoy = create_claim(
+        &graph,
+        &source,
+        "Shipping insurers discussed sanctions exposure.".into(),
+        topic(&[(SHIPPING, 0.8), (SANCTIONS, 0.6)]),
+    )
+    .await;
+
+    let config = ClaimChunkConfig {
+        enabled: true,
+        min_chars: 400,
+        max_chars: 250,
+        overlap_sentences: 1,
+    };
+    let chunks = chunk_claim(&report.content, &config);
+    assert!(chunks.len() > 2);
+    let with_sanctions = chunks.iter().filter(|c| c.contains("sanctions")).count();
+    assert!((1..chunks.len()).contains(&with_sanctions), "{:?}", chunks);
+
+    let query_vector = topic(&[(SANCTIONS, 1.0)]);
+
+    // Without chunks the buried topic ranks below the decoy.
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+    let whole = whole_claim_scores(&graph, &query_vector).await;
+    assert_eq!(whole[0].0, decoy.id.to_string());
+    let report_whole_score = whole
+        .iter()
+        .find(|(id, _)| *id == report.id.to_string())
+        .map(|(_, score)| *score)
+        .unwrap();
+    assert!(report_whole_score < 0.6, "{}", report_whole_score);
+
+    let page = graph
+        .search_claims(&semantic(10), Some(query_vector.clone()))
+        .await
+        .unwrap();
+    assert_eq!(page.results[0].item.id, decoy.id);
+
+    // Chunks mentioning sanctions get the sanctions vector; the rest shipping.
+    let embedded: Vec<(String, Vec<f32>)> = chunks
+        .into_iter()
+        .map(|chunk| {
+            let embedding = if chunk.contains("sanctions") {
+                topic(&[(SANCTIONS, 1.0), (SHIPPING, 0.1)])
+            } else {
+                topic(&[(SHIPPING, 1.0)])
+            };
+            (chunk, embedding)
+        })
+        .collect();
+    graph.set_claim_chunks(report.id, &embedded).await.unwrap();
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+
+    // The whole-claim index is unchanged, but search finds the chunk.
+    assert_eq!(
+        whole_claim_scores(&graph, &query_vector).await[0].0,
+        decoy.id.to_string()
+    );
+    let page = graph
+        .search_claims(&semantic(10), Some(query_vector))
+        .await
+        .unwrap();
+    let ids: Vec<ClaimId> = page.results.iter().map(|r| r.item.id).collect();
+    assert_eq!(ids, vec![report.id, decoy.id], "one result per claim");
+    assert!(page.results[0].score > 0.99, "{}", page.results[0].score);
+    assert_eq!(page.results[0].item.content, report.content);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_set_claim_chunks_replaces_and_clears_pending() {
+    let graph = setup().await;
+    let source = graph
+        .create_entity(
+            &Entity::new("Port Gazette".into(), "publication".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let claim = create_claim(&graph, &source, long_report(), topic(&[(SHIPPING, 1.0)])).await;
+
+    let pending = |id: ClaimId| {
+        let graph = graph.clone();
+        async move {
+            let mut result = graph
+                .inner()
+                .execute(
+                    query("MATCH (c:Claim {id: $id}) RETURN c.chunks_pending AS pending")
+                        .param("id", id.to_string()),
+                )
+                .await
+                .unwrap();
+            result
+                .next()
+                .await
+                .unwrap()
+                .unwrap()
+                .get::<bool>("pending")
+                .unwrap()
+        }
+    };
+    assert!(pending(claim.id).await);
+
+    let chunk = |text: &str| (text.to_string(), topic(&[(SHIPPING, 1.0)]));
+    graph
+        .set_claim_chunks(claim.id, &[chunk("a"), chunk("b"), chunk("c")])
+        .await
+        .unwrap();
+    assert_eq!(chunk_count(&graph, claim.id).await, 3);
+    assert!(!pending(claim.id).await);
+
+    graph
+        .set_claim_chunks(claim.id, &[chunk("d")])
+        .await
+        .unwrap();
+    assert_eq!(chunk_count(&graph, claim.id).await, 1);
+
+    graph.set_claim_chunks(claim.id, &[]).await.unwrap();
+    assert_eq!(chunk_count(&graph, claim.id).await, 0);
+
+    let missing = graph
+        .set_claim_chunks(ClaimId::new(), &[chunk("e")])
+        .await
+        .unwrap_err();
+    assert!(matches!(missing, GraphError::NotFound(_)), "{}", missing);
+}
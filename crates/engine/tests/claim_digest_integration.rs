@@ -0,0 +1,222 @@
+//! Integration tests for per-entity claim digests.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use chrono::{Duration, Utc};
+use neo4rs::query;
+
+use autosint_common::config::{GraphConsistencyConfig, GraphWriteConfig, WriteCoalesceConfig};
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType};
+use autosint_common::{ClaimId, EntityId};
+use autosint_engine::graph::{GraphClient, CLAIM_DIGEST_LEN};
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+    graph
+}
+
+async fn create_entity(graph: &GraphClient, name: &str) -> EntityId {
+    let entity = Entity::new(name.into(), "organization".into());
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+/// `count` claims about `subject`, ingested a minute apart, written all at
+/// once. Returns their IDs, newest first.
+async fn create_claims_concurrently(
+    graph: &GraphClient,
+    publisher: EntityId,
+    subject: EntityId,
+    count: i64,
+) -> Vec<ClaimId> {
+    let base = Utc::now() - Duration::hours(1);
+    let claims: Vec<Claim> = (0..count)
+        .map(|i| {
+            let mut claim = Claim::new(
+                format!("Gulf Star Shipping chartered tanker {}.", i),
+                Utc::now(),
+                AttributionDepth::Secondhand,
+                InformationType::Assertion,
+                publisher,
+            );
+            claim.ingested_timestamp = base + Duration::minutes(i);
+            claim.referenced_entity_ids = vec![subject];
+            claim
+        })
+        .collect();
+
+    let handles: Vec<_> = claims
+        .iter()
+        .map(|claim| {
+            let graph = graph.clone();
+            let claim = claim.clone();
+            tokio::spawn(async move { graph.create_claim(&claim, None).await })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+    claims.iter().rev().map(|c| c.id).collect()
+}
+
+async fn digest_ids(graph: &GraphClient, id: EntityId) -> Option<Vec<ClaimId>> {
+    let (_, digest) = graph.get_entity_with_digest(id).await.unwrap();
+    digest.map(|d| d.entries.iter().map(|e| e.claim_id).collect())
+}
+
+async fn run(graph: &GraphClient, cypher: &str, id: EntityId) {
+    graph
+        .inner()
+        .run(query(cypher).param("id", id.to_string()))
+        .await
+        .unwrap();
+}
+
+// -----------------------------------------------------------------------
+// 1. Concurrent claim writes keep the newest claims, in order
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_concurrent_writes_append_and_trim() {
+    for coalesce in [false, true] {
+        let graph = setup().await.with_write_config(&GraphWriteConfig {
+            coalesce: WriteCoalesceConfig {
+                enabled: coalesce,
+                window_ms: 20,
+                max_items: 8,
+            },
+            ..Default::default()
+        });
+        let publisher = create_entity(&graph, "Lloyd's List").await;
+        let subject = create_entity(&graph, "Gulf Star Shipping").await;
+        assert_eq!(digest_ids(&graph, subject).await, Some(vec![]));
+
+        let newest = create_claims_concurrently(&graph, publisher, subject, 25).await;
+        assert_eq!(
+            digest_ids(&graph, subject).await.unwrap(),
+            newest[..CLAIM_DIGEST_LEN],
+            "coalesce: {}",
+            coalesce
+        );
+        // The publisher isn't referenced, so its digest stays empty.
+        assert_eq!(digest_ids(&graph, publisher).await, Some(vec![]));
+
+        // The digest serves what the claims query would return.
+        let served = graph.recent_claims(subject, 5, 500).await.unwrap();
+        let queried = graph.query_recent_claims(subject, 5).await.unwrap();
+        assert_eq!(served, queried);
+    }
+}
+
+// -----------------------------------------------------------------------
+// 2. Merges rebuild the target's digest
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_merge_rebuilds_digest() {
+    let graph = setup().await;
+    let publisher = create_entity(&graph, "Lloyd's List").await;
+    let duplicate = create_entity(&graph, "Gulf Star").await;
+    let canonical = create_entity(&graph, "Gulf Star Shipping").await;
+    let from_duplicate = create_claims_concurrently(&graph, publisher, duplicate, 3).await;
+
+    graph
+        .merge_entities(duplicate, canonical, None, false)
+        .await
+        .unwrap();
+    assert_eq!(digest_ids(&graph, canonical).await.unwrap(), from_duplicate);
+}
+
+// -----------------------------------------------------------------------
+// 3. The consistency check finds missing and corrupted digests and repairs them
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_repair_restores_digests() {
+    let graph = setup().await;
+    let config = GraphConsistencyConfig::default();
+    let publisher = create_entity(&graph, "Lloyd's List").await;
+    let legacy = create_entity(&graph, "Gulf Star Shipping").await;
+    let corrupted = create_entity(&graph, "Meridian Tankers").await;
+    let legacy_claims = create_claims_concurrently(&graph, publisher, legacy, 12).await;
+    let corrupted_claims = create_claims_concurrently(&graph, publisher, corrupted, 2).await;
+
+    // One entity predates digests; the other's digest has drifted.
+    run(
+        &graph,
+        "MATCH (e:Entity {id: $id}) REMOVE e.claim_digest",
+        legacy,
+    )
+    .await;
+    run(
+        &graph,
+        "MATCH (e:Entity {id: $id}) SET e.claim_digest = ['not an entry']",
+        corrupted,
+    )
+    .await;
+    assert_eq!(digest_ids(&graph, legacy).await, None);
+    assert_eq!(digest_ids(&graph, corrupted).await, None);
+    // Reads fall back to the claims query meanwhile.
+    let fallback = graph.recent_claims(legacy, 3, 500).await.unwrap();
+    let fallback: Vec<ClaimId> = fallback.iter().map(|e| e.claim_id).collect();
+    assert_eq!(fallback, legacy_claims[..3]);
+
+    let report = graph.check_consistency(&config).await.unwrap();
+    let check = report
+        .checks
+        .iter()
+        .find(|c| c.name == "stale_claim_digests")
+        .unwrap();
+    assert_eq!(check.violations, 2);
+    assert!(check.repairable);
+
+    let applied = graph.repair_consistency(&config, false).await.unwrap();
+    let repair = applied
+        .repairs
+        .iter()
+        .find(|r| r.name == "stale_claim_digests")
+        .unwrap();
+    assert_eq!((repair.repairable, repair.repaired), (2, 2));
+
+    assert_eq!(
+        digest_ids(&graph, legacy).await.unwrap(),
+        legacy_claims[..CLAIM_DIGEST_LEN]
+    );
+    assert_eq!(
+        digest_ids(&graph, corrupted).await.unwrap(),
+        corrupted_claims
+    );
+
+    // Repaired digests keep up with later writes.
+    let later = create_claims_concurrently(&graph, publisher, corrupted, 1).await;
+    let ids = digest_ids(&graph, corrupted).await.unwrap();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.contains(&later[0]));
+    let queried = graph.query_recent_claims(corrupted, 3).await.unwrap();
+    let queried: Vec<ClaimId> = queried.iter().map(|e| e.claim_id).collect();
+    assert_eq!(ids, queried);
+
+    let again = graph.check_consistency(&config).await.unwrap();
+    let check = again
+        .checks
+        .iter()
+        .find(|c| c.name == "stale_claim_digests")
+        .unwrap();
+    assert_eq!(check.violations, 0);
+}
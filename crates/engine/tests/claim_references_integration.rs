@@ -0,0 +1,208 @@
+//! Integration tests for the per-claim reference cap.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use std::sync::Arc;
+
+use chrono::Utc;
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::config::{GraphConsistencyConfig, GraphWriteConfig};
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType};
+use autosint_common::EntityId;
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::GraphClient;
+use autosint_engine::tools::handlers::register_processor_tools;
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+const MAX_REFERENCES: usize = 25;
+
+async fn setup() -> (Arc<GraphClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j")
+        .with_write_config(&GraphWriteConfig {
+            max_claim_references: MAX_REFERENCES,
+            ..Default::default()
+        });
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), engine_config)
+}
+
+/// Processor registry for a work order referencing `focus`.
+fn processor_registry(
+    graph: &Arc<GraphClient>,
+    config: &EngineConfig,
+    focus: Vec<EntityId>,
+) -> ToolRegistry {
+    let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+        SharedServices::new(
+            Arc::clone(graph),
+            "http://localhost:8081".into(),
+            reqwest::Client::new(),
+            Arc::default(),
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        ),
+        SessionScope {
+            max_turns: 50,
+            focus_entity_ids: focus,
+            ..Default::default()
+        },
+    ));
+    register_processor_tools(&mut registry);
+    registry
+}
+
+async fn create_entities(graph: &GraphClient, count: usize) -> Vec<EntityId> {
+    let mut ids = Vec::with_capacity(count);
+    for i in 0..count {
+        let entity = Entity::new(format!("Shell Company {}", i), "organization".into());
+        ids.push(graph.create_entity(&entity, None).await.unwrap().id);
+    }
+    ids
+}
+
+async fn reference_count(graph: &GraphClient, claim_id: &str) -> i64 {
+    let mut result = graph
+        .inner()
+        .execute(
+            query("MATCH (:Claim {id: $id})-[r:REFERENCES]->(:Entity) RETURN count(r) AS n")
+                .param("id", claim_id),
+        )
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+// -----------------------------------------------------------------------
+// 1. A 100-reference claim is capped, warned about and summarized in search
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_create_claim_caps_references() {
+    let (graph, config) = setup().await;
+    let publisher = create_entities(&graph, 1).await[0];
+    let referenced = create_entities(&graph, 100).await;
+
+    // The work order's entity is listed last but survives the cap.
+    let focus = referenced[99];
+    let processor = processor_registry(&graph, &config, vec![focus]);
+    let result = processor
+        .execute(
+            "create_claim",
+            json!({
+                "content": "The registry lists 100 shell companies sharing one Limassol address.",
+                "source_entity_id": publisher.to_string(),
+                "published_timestamp": "2026-03-15T00:00:00Z",
+                "referenced_entity_ids": referenced.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+            }),
+        )
+        .await;
+    assert!(!result.is_error, "create_claim failed: {}", result.content);
+
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    let warning = body["warning"].as_str().unwrap();
+    assert!(warning.contains("referenced 100 entities"), "{}", warning);
+    assert!(warning.contains("dropped 75"), "{}", warning);
+
+    let claim_id = body["claim_id"].as_str().unwrap();
+    assert_eq!(
+        reference_count(&graph, claim_id).await,
+        MAX_REFERENCES as i64
+    );
+    let claim = graph
+        .get_claim(claim_id.parse::<uuid::Uuid>().unwrap().into())
+        .await
+        .unwrap();
+    assert!(claim.referenced_entity_ids.contains(&focus));
+    assert!(claim.referenced_entity_ids.contains(&referenced[0]));
+    assert!(!claim
+        .referenced_entity_ids
+        .contains(&referenced[MAX_REFERENCES]));
+
+    // Search shows a bounded slice of the references and the total.
+    let result = processor
+        .execute(
+            "search_claims",
+            json!({ "source_entity_id": publisher.to_string() }),
+        )
+        .await;
+    assert!(!result.is_error, "search_claims failed: {}", result.content);
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    let item = &body["results"][0];
+    let shown = config.system.tool_results.max_claim_references_shown as usize;
+    assert_eq!(
+        item["referenced_entity_ids"].as_array().unwrap().len(),
+        shown
+    );
+    assert_eq!(
+        item["references_note"],
+        format!("references {} entities, showing {}", MAX_REFERENCES, shown)
+    );
+}
+
+// -----------------------------------------------------------------------
+// 2. Claims written past the cap are flagged by the consistency check
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_over_referenced_claim_flagged() {
+    let (graph, _config) = setup().await;
+    let publisher = create_entities(&graph, 1).await[0];
+    let referenced = create_entities(&graph, 40).await;
+
+    // Written straight to the graph, bypassing the tool-level cap.
+    let mut claim = Claim::new(
+        "The registry lists 40 shell companies sharing one Limassol address.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        publisher,
+    );
+    claim.referenced_entity_ids = referenced.clone();
+    let over = graph.create_claim(&claim, None).await.unwrap();
+
+    let mut within = Claim::new(
+        "The registry lists 25 of them as dissolved.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        publisher,
+    );
+    within.referenced_entity_ids = referenced[..MAX_REFERENCES].to_vec();
+    graph.create_claim(&within, None).await.unwrap();
+
+    let report = graph
+        .check_consistency(&GraphConsistencyConfig::default())
+        .await
+        .unwrap();
+    let check = report
+        .checks
+        .iter()
+        .find(|c| c.name == "over_referenced_claims")
+        .unwrap();
+    assert_eq!(check.violations, 1);
+    assert_eq!(check.sample_ids, vec![over.id.to_string()]);
+    assert!(!check.repairable);
+}
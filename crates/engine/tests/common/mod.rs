@@ -0,0 +1,319 @@
+//! Shared helpers for integration tests: deterministic graph fixtures.
+//!
+//! `FixtureBuilder::new(seed)` always produces the same entities, relationships
+//! and claims — same IDs, names, kinds, dates and embeddings — so ranking
+//! assertions can be written against exact values. Scripted LLM sessions for
+//! end-to-end tests live in [`scripted`].
+#![allow(dead_code)]
+
+pub mod scripted;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_common::{ClaimId, EntityId, RelationshipId};
+use autosint_engine::graph::GraphClient;
+
+/// Embedding dimension used by the vector indexes.
+pub const EMBEDDING_DIM: usize = 1536;
+
+pub const KINDS: &[&str] = &["person", "organization", "country", "city", "vessel"];
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Boris", "Chen", "Dara", "Elena", "Farid", "Greta", "Hiro", "Ines", "Jonas",
+];
+const LAST_NAMES: &[&str] = &[
+    "Novak", "Okafor", "Petrov", "Quinn", "Rossi", "Sato", "Tanaka", "Ulloa", "Varga", "Weber",
+];
+const ORG_WORDS: &[&str] = &[
+    "Acme",
+    "Borealis",
+    "Cobalt",
+    "Delta",
+    "Meridian",
+    "Northwind",
+    "Orion",
+    "Summit",
+];
+const ORG_SUFFIXES: &[&str] = &["Holdings", "Logistics", "Shipping", "Group", "Trading"];
+const PLACE_ROOTS: &[&str] = &[
+    "Al", "Bel", "Cor", "Dun", "Esk", "Fal", "Gor", "Hal", "Ist", "Kar",
+];
+const PLACE_ENDINGS: &[&str] = &["ania", "avia", "burg", "mora", "stan", "vale"];
+const VESSEL_WORDS: &[&str] = &["Aurora", "Horizon", "Pioneer", "Serenity", "Tempest"];
+
+/// SplitMix64 — tiny, seedable and stable across platforms and crate versions.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Uniform in `[-1, 1)`.
+    pub fn signed_unit(&mut self) -> f32 {
+        ((self.next_u64() >> 40) as f32 / (1u64 << 24) as f32) * 2.0 - 1.0
+    }
+
+    pub fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    pub fn uuid(&mut self) -> Uuid {
+        Uuid::from_u64_pair(self.next_u64(), self.next_u64())
+    }
+}
+
+/// Seeded generator for a graph fixture.
+pub struct FixtureBuilder {
+    seed: u64,
+    entities: usize,
+    relationships: usize,
+    claims: usize,
+    embeddings: bool,
+}
+
+impl FixtureBuilder {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            entities: 0,
+            relationships: 0,
+            claims: 0,
+            embeddings: false,
+        }
+    }
+
+    pub fn entities(mut self, n: usize) -> Self {
+        self.entities = n;
+        self
+    }
+
+    pub fn relationships(mut self, n: usize) -> Self {
+        self.relationships = n;
+        self
+    }
+
+    pub fn claims(mut self, n: usize) -> Self {
+        self.claims = n;
+        self
+    }
+
+    /// Attach synthetic embeddings. Entities of the same kind cluster around a
+    /// shared direction, so nearest neighbours are mostly same-kind.
+    pub fn with_embeddings(mut self) -> Self {
+        self.embeddings = true;
+        self
+    }
+
+    pub fn build(&self) -> Fixture {
+        let mut rng = Rng::new(self.seed);
+        let centroids: Vec<Vec<f32>> = KINDS
+            .iter()
+            .map(|_| normalize((0..EMBEDDING_DIM).map(|_| rng.signed_unit()).collect()))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut entities = Vec::with_capacity(self.entities);
+        for _ in 0..self.entities {
+            let kind_idx = rng.below(KINDS.len());
+            let kind = KINDS[kind_idx];
+
+            let base = generate_name(&mut rng, kind);
+            let mut name = base.clone();
+            let mut n = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{} {}", base, n);
+                n += 1;
+            }
+
+            let mut entity = Entity::new(name, kind.to_string());
+            entity.id = EntityId::from_uuid(rng.uuid());
+            entity.last_updated = date(&mut rng);
+            if self.embeddings {
+                entity.embedding = Some(clustered(&mut rng, &centroids[kind_idx]));
+                entity.embedding_pending = false;
+            }
+            entities.push(entity);
+        }
+
+        let mut relationships = Vec::with_capacity(self.relationships);
+        if entities.len() >= 2 {
+            for _ in 0..self.relationships {
+                let source = rng.below(entities.len());
+                let target = (source + 1 + rng.below(entities.len() - 1)) % entities.len();
+                let mut rel = Relationship::new(
+                    entities[source].id,
+                    entities[target].id,
+                    format!(
+                        "{} is linked to {}.",
+                        entities[source].canonical_name, entities[target].canonical_name
+                    ),
+                );
+                rel.id = RelationshipId::from_uuid(rng.uuid());
+                rel.timestamp = Some(date(&mut rng));
+                relationships.push(rel);
+            }
+        }
+
+        let mut claims = Vec::with_capacity(self.claims);
+        if !entities.is_empty() {
+            for _ in 0..self.claims {
+                let source = &entities[rng.below(entities.len())];
+                let subject = &entities[rng.below(entities.len())];
+                let published = date(&mut rng);
+                let mut claim = Claim::new(
+                    format!(
+                        "{} reported activity involving {} on {}.",
+                        source.canonical_name,
+                        subject.canonical_name,
+                        published.format("%Y-%m-%d")
+                    ),
+                    published,
+                    AttributionDepth::Secondhand,
+                    InformationType::Assertion,
+                    source.id,
+                );
+                claim.id = ClaimId::from_uuid(rng.uuid());
+                claim.ingested_timestamp = published + Duration::hours(6);
+                claim.referenced_entity_ids = vec![subject.id];
+                claims.push(claim);
+            }
+        }
+
+        Fixture {
+            entities,
+            relationships,
+            claims,
+        }
+    }
+
+    /// Path of the cached snapshot for this exact builder configuration.
+    fn snapshot_path(&self) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "autosint-fixture-{}-{}-{}-{}{}.jsonl",
+            self.seed,
+            self.entities,
+            self.relationships,
+            self.claims,
+            if self.embeddings { "-emb" } else { "" }
+        ))
+    }
+
+    /// Build the fixture and load it into an empty graph. The first run dumps a
+    /// snapshot to the temp dir; later runs restore from it instead of
+    /// regenerating.
+    pub async fn load_cached(&self, graph: &GraphClient) -> Fixture {
+        let fixture = self.build();
+        let path = self.snapshot_path();
+
+        match std::fs::File::open(&path) {
+            Ok(file) => {
+                graph
+                    .import_snapshot(std::io::BufReader::new(file))
+                    .await
+                    .expect("Failed to restore fixture snapshot");
+            }
+            Err(_) => {
+                fixture.load(graph).await;
+                let mut file =
+                    std::fs::File::create(&path).expect("Failed to create fixture snapshot");
+                graph
+                    .export_snapshot(&mut file)
+                    .await
+                    .expect("Failed to export fixture snapshot");
+            }
+        }
+
+        fixture
+    }
+}
+
+/// A generated graph, in creation order.
+pub struct Fixture {
+    pub entities: Vec<Entity>,
+    pub relationships: Vec<Relationship>,
+    pub claims: Vec<Claim>,
+}
+
+impl Fixture {
+    /// Create every item through the regular graph create paths.
+    pub async fn load(&self, graph: &GraphClient) {
+        for entity in &self.entities {
+            graph
+                .create_entity(entity, entity.embedding.clone())
+                .await
+                .expect("Failed to create fixture entity");
+        }
+        for rel in &self.relationships {
+            graph
+                .create_relationship(rel, rel.embedding.clone(), false)
+                .await
+                .expect("Failed to create fixture relationship");
+        }
+        for claim in &self.claims {
+            graph
+                .create_claim(claim, claim.embedding.clone())
+                .await
+                .expect("Failed to create fixture claim");
+        }
+    }
+}
+
+fn generate_name(rng: &mut Rng, kind: &str) -> String {
+    match kind {
+        "person" => format!("{} {}", rng.pick(FIRST_NAMES), rng.pick(LAST_NAMES)),
+        "organization" => format!("{} {}", rng.pick(ORG_WORDS), rng.pick(ORG_SUFFIXES)),
+        "country" => format!("{}{}", rng.pick(PLACE_ROOTS), rng.pick(PLACE_ENDINGS)),
+        "city" => format!("Port {}{}", rng.pick(PLACE_ROOTS), rng.pick(PLACE_ENDINGS)),
+        _ => format!("MV {}", rng.pick(VESSEL_WORDS)),
+    }
+}
+
+/// A date in 2020–2024, at whole-second precision.
+fn date(rng: &mut Rng) -> DateTime<Utc> {
+    let start = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    start + Duration::seconds(rng.below(5 * 365 * 24 * 3600) as i64)
+}
+
+/// 0.8 × kind centroid + 0.6 × random unit direction, renormalized.
+fn clustered(rng: &mut Rng, centroid: &[f32]) -> Vec<f32> {
+    let noise = normalize((0..centroid.len()).map(|_| rng.signed_unit()).collect());
+    normalize(
+        centroid
+            .iter()
+            .zip(noise)
+            .map(|(c, n)| 0.8 * c + 0.6 * n)
+            .collect(),
+    )
+}
+
+fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+/// Cosine similarity of two normalized vectors.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
@@ -0,0 +1,389 @@
+//! Scripted LLM sessions for end-to-end tests, loaded from YAML fixtures in
+//! `tests/fixtures/`.
+//!
+//! A fixture lists each role's sessions, each a list of turns the model
+//! "replies" with:
+//!
+//! ```yaml
+//! analyst:
+//!   - turns:
+//!       - tool_calls:
+//!           - name: create_work_order
+//!             input: { objective: "Identify the operator of the MT Crius" }
+//!             save: { operator_order: work_order_id }
+//!       - text: Dispatched.
+//! processor:
+//!   - match: operator of the MT Crius
+//!     turns:
+//!       - tool_calls:
+//!           - name: create_entity
+//!             input: { canonical_name: MT Crius, kind: vessel }
+//!             save: { crius: entity_id }
+//! ```
+//!
+//! - A turn with `tool_calls` replies with those calls (and any `text`); a
+//!   turn with only `text` ends the session. A session that runs out of turns
+//!   ends with "Done.".
+//! - `save` stores fields of a call's JSON result as variables. `{{name}}` in
+//!   any later input string, in any session, is replaced with the value; a
+//!   string that is only `{{name}}` takes the value's JSON type.
+//! - Analyst sessions run in fixture order. Each Processor session takes the
+//!   first unused script whose `match` is part of its work order message, so
+//!   the order workers pick work orders up in doesn't matter.
+//! - A tool call that fails, a field that isn't in a result and an unknown
+//!   variable are recorded as [`Script::problems`] rather than panicking
+//!   inside the engine; tests assert there are none.
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use autosint_engine::llm::{
+    ContentBlock, LlmCaller, LlmError, LlmFactory, LlmResponse, Message, StopReason, TokenUsage,
+    ToolDefinition,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Fixture {
+    #[serde(default)]
+    pub analyst: Vec<SessionScript>,
+    #[serde(default)]
+    pub processor: Vec<SessionScript>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SessionScript {
+    /// Processor sessions only: part of the work order message.
+    #[serde(default, rename = "match")]
+    pub matches: Option<String>,
+    #[serde(default)]
+    pub turns: Vec<Turn>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Turn {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub input: Value,
+    /// Variable name → field of the call's JSON result.
+    #[serde(default)]
+    pub save: HashMap<String, String>,
+}
+
+/// A loaded fixture, shared by the sessions it scripts.
+pub struct Script {
+    analyst: Mutex<VecDeque<SessionScript>>,
+    processor: Mutex<Vec<SessionScript>>,
+    variables: Mutex<HashMap<String, Value>>,
+    problems: Mutex<Vec<String>>,
+}
+
+impl Script {
+    /// Load `tests/fixtures/<name>`.
+    pub fn load(name: &str) -> Arc<Self> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name);
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        let fixture: Fixture = serde_yaml::from_str(&text)
+            .unwrap_or_else(|e| panic!("Invalid fixture {}: {}", path.display(), e));
+        Arc::new(Self {
+            analyst: Mutex::new(fixture.analyst.into()),
+            processor: Mutex::new(fixture.processor),
+            variables: Mutex::default(),
+            problems: Mutex::default(),
+        })
+    }
+
+    /// Builds each Analyst session from the next analyst script.
+    pub fn analyst_factory(self: &Arc<Self>) -> LlmFactory {
+        let script = Arc::clone(self);
+        Arc::new(move || {
+            let next = script
+                .analyst
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| "Fixture has no Analyst session left".to_string())?;
+            Ok(Arc::new(ScriptedSession::new(&script, Some(next))) as Arc<dyn LlmCaller>)
+        })
+    }
+
+    /// Builds each Processor session; its script is chosen by its work order.
+    pub fn processor_factory(self: &Arc<Self>) -> LlmFactory {
+        let script = Arc::clone(self);
+        Arc::new(move || Ok(Arc::new(ScriptedSession::new(&script, None)) as Arc<dyn LlmCaller>))
+    }
+
+    /// A saved variable.
+    pub fn variable(&self, name: &str) -> Value {
+        self.variables
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| panic!("Fixture variable {} was never saved", name))
+    }
+
+    /// A saved variable holding an ID.
+    pub fn id<T: From<uuid::Uuid>>(&self, name: &str) -> T {
+        let value = self.variable(name);
+        let uuid: uuid::Uuid = value
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| panic!("Fixture variable {} is not a UUID: {}", name, value));
+        T::from(uuid)
+    }
+
+    /// Everything that went off script.
+    pub fn problems(&self) -> Vec<String> {
+        self.problems.lock().unwrap().clone()
+    }
+
+    /// Scripted sessions that never ran, as "<role> #<n>" or the match text.
+    pub fn unused(&self) -> Vec<String> {
+        let analyst = self.analyst.lock().unwrap();
+        let processor = self.processor.lock().unwrap();
+        (0..analyst.len())
+            .map(|i| format!("analyst #{}", i))
+            .chain(
+                processor
+                    .iter()
+                    .map(|s| format!("processor {:?}", s.matches.as_deref().unwrap_or(""))),
+            )
+            .collect()
+    }
+
+    fn problem(&self, problem: String) {
+        self.problems.lock().unwrap().push(problem);
+    }
+
+    /// The first unused processor script matching `message`.
+    fn take_processor_script(&self, message: &str) -> Option<SessionScript> {
+        let mut scripts = self.processor.lock().unwrap();
+        let index = scripts
+            .iter()
+            .position(|s| s.matches.as_deref().is_none_or(|m| message.contains(m)))?;
+        Some(scripts.remove(index))
+    }
+
+    /// `value` with `{{name}}` references replaced.
+    fn substitute(&self, value: &Value) -> Value {
+        match value {
+            Value::String(s) => self.substitute_str(s),
+            Value::Array(items) => items.iter().map(|v| self.substitute(v)).collect(),
+            Value::Object(fields) => fields
+                .iter()
+                .map(|(k, v)| (k.clone(), self.substitute(v)))
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+            other => other.clone(),
+        }
+    }
+
+    fn substitute_str(&self, s: &str) -> Value {
+        let variables = self.variables.lock().unwrap();
+        let lookup = |name: &str| {
+            variables.get(name).cloned().unwrap_or_else(|| {
+                self.problem(format!("Unknown fixture variable {{{{{}}}}}", name));
+                Value::Null
+            })
+        };
+        if let Some(name) = s
+            .strip_prefix("{{")
+            .and_then(|rest| rest.strip_suffix("}}"))
+            .filter(|name| !name.contains("{{"))
+        {
+            return lookup(name.trim());
+        }
+        let mut out = String::new();
+        let mut rest = s;
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else {
+                break;
+            };
+            out.push_str(&rest[..start]);
+            match lookup(rest[start + 2..start + end].trim()) {
+                Value::String(value) => out.push_str(&value),
+                value => out.push_str(&value.to_string()),
+            }
+            rest = &rest[start + end + 2..];
+        }
+        out.push_str(rest);
+        Value::String(out)
+    }
+}
+
+/// One session's scripted model.
+struct ScriptedSession {
+    script: Arc<Script>,
+    state: Mutex<SessionState>,
+}
+
+struct SessionState {
+    /// None until a Processor session has seen its work order.
+    turns: Option<VecDeque<Turn>>,
+    /// Calls awaiting their results, by tool use ID.
+    pending: HashMap<String, ToolCall>,
+    calls: usize,
+}
+
+impl ScriptedSession {
+    fn new(script: &Arc<Script>, session: Option<SessionScript>) -> Self {
+        Self {
+            script: Arc::clone(script),
+            state: Mutex::new(SessionState {
+                turns: session.map(|s| s.turns.into()),
+                pending: HashMap::new(),
+                calls: 0,
+            }),
+        }
+    }
+
+    fn reply(&self, messages: &[Message]) -> LlmResponse {
+        let mut state = self.state.lock().unwrap();
+
+        // Results of the previous turn's calls.
+        if let Some(last) = messages.last() {
+            for block in &last.content {
+                if let ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } = block
+                {
+                    if let Some(call) = state.pending.remove(tool_use_id) {
+                        self.record_result(&call, content, is_error.unwrap_or(false));
+                    }
+                }
+            }
+        }
+
+        if state.turns.is_none() {
+            let message = first_text(messages);
+            match self.script.take_processor_script(&message) {
+                Some(session) => state.turns = Some(session.turns.into()),
+                None => {
+                    self.script.problem(format!(
+                        "No processor script matches the work order: {}",
+                        message
+                    ));
+                    state.turns = Some(VecDeque::new());
+                }
+            }
+        }
+
+        let Some(turn) = state.turns.as_mut().and_then(|t| t.pop_front()) else {
+            return end_turn("Done.");
+        };
+        let mut content = Vec::new();
+        if let Some(text) = &turn.text {
+            content.push(ContentBlock::Text { text: text.clone() });
+        }
+        if turn.tool_calls.is_empty() {
+            return end_turn(turn.text.as_deref().unwrap_or("Done."));
+        }
+        for call in turn.tool_calls {
+            state.calls += 1;
+            let id = format!("call_{}", state.calls);
+            let input = match &call.input {
+                Value::Null => Value::Object(Default::default()),
+                input => self.script.substitute(input),
+            };
+            content.push(ContentBlock::ToolUse {
+                id: id.clone(),
+                name: call.name.clone(),
+                input,
+            });
+            state.pending.insert(id, call);
+        }
+        LlmResponse {
+            content,
+            stop_reason: StopReason::ToolUse,
+            usage: TokenUsage::default(),
+            served_by: None,
+        }
+    }
+
+    fn record_result(&self, call: &ToolCall, content: &str, is_error: bool) {
+        if is_error {
+            self.script
+                .problem(format!("{} failed: {}", call.name, content));
+            return;
+        }
+        if call.save.is_empty() {
+            return;
+        }
+        let result: Value = match serde_json::from_str(content) {
+            Ok(result) => result,
+            Err(_) => {
+                self.script
+                    .problem(format!("{} returned non-JSON: {}", call.name, content));
+                return;
+            }
+        };
+        let mut variables = self.script.variables.lock().unwrap();
+        for (name, field) in &call.save {
+            match result.get(field) {
+                Some(value) => {
+                    variables.insert(name.clone(), value.clone());
+                }
+                None => self.script.problem(format!(
+                    "{} result has no {} to save as {}: {}",
+                    call.name, field, name, content
+                )),
+            }
+        }
+    }
+}
+
+impl LlmCaller for ScriptedSession {
+    fn chat<'a>(
+        &'a self,
+        _system: &'a str,
+        messages: &'a [Message],
+        _tools: &'a [ToolDefinition],
+    ) -> Pin<Box<dyn Future<Output = Result<LlmResponse, LlmError>> + Send + 'a>> {
+        let reply = self.reply(messages);
+        Box::pin(async move { Ok(reply) })
+    }
+}
+
+/// The text of the session's first message (the work order, for a Processor).
+fn first_text(messages: &[Message]) -> String {
+    messages
+        .first()
+        .map(|m| {
+            m.content
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+fn end_turn(text: &str) -> LlmResponse {
+    LlmResponse {
+        content: vec![ContentBlock::Text { text: text.into() }],
+        stop_reason: StopReason::EndTurn,
+        usage: TokenUsage::default(),
+        served_by: None,
+    }
+}
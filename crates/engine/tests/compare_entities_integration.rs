@@ -0,0 +1,210 @@
+//! Integration tests for the compare_entities tool.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use std::sync::Arc;
+
+use chrono::Utc;
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::types::{
+    AttributionDepth, Claim, Entity, IdentifierScheme, InformationType, Relationship,
+};
+use autosint_common::EntityId;
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::{GraphClient, MatchBand};
+use autosint_engine::tools::handlers::register_analyst_tools;
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+async fn setup() -> (Arc<GraphClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), engine_config)
+}
+
+fn analyst_registry(graph: &Arc<GraphClient>, config: &EngineConfig) -> ToolRegistry {
+    let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+        SharedServices::new(
+            Arc::clone(graph),
+            "http://localhost:8081".into(),
+            reqwest::Client::new(),
+            Arc::default(),
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        ),
+        SessionScope {
+            max_turns: 50,
+            ..Default::default()
+        },
+    ));
+    register_analyst_tools(&mut registry);
+    registry
+}
+
+async fn create(graph: &GraphClient, entity: Entity) -> EntityId {
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+async fn relate(graph: &GraphClient, source: EntityId, target: EntityId, description: &str) {
+    let relationship = Relationship::new(source, target, description.into());
+    graph
+        .create_relationship(&relationship, None, false)
+        .await
+        .unwrap();
+}
+
+async fn compare(registry: &ToolRegistry, a: EntityId, b: EntityId) -> Value {
+    let result = registry
+        .execute(
+            "compare_entities",
+            json!({ "entity_id_a": a.to_string(), "entity_id_b": b.to_string() }),
+        )
+        .await;
+    assert!(!result.is_error, "compare failed: {}", result.content);
+    serde_json::from_str(&result.content).unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_near_duplicates_compare_as_likely_same() {
+    let (graph, config) = setup().await;
+
+    let mut first = Entity::new("Gulf Star Shipping LLC".into(), "organization".into());
+    first.aliases = vec!["Gulf Star".into()];
+    first
+        .identifiers
+        .insert(IdentifierScheme::Imo, "9321483".into());
+    let first = create(&graph, first).await;
+    let second = create(
+        &graph,
+        Entity::new("Gulf Star Shipping".into(), "organization".into()),
+    )
+    .await;
+
+    let port = create(&graph, Entity::new("Fujairah".into(), "location".into())).await;
+    let vessel = create(&graph, Entity::new("MT Crius".into(), "vessel".into())).await;
+    for id in [first, second] {
+        relate(&graph, id, port, "operates from").await;
+        relate(&graph, id, vessel, "manages").await;
+    }
+    let publisher = create(&graph, Entity::new("Reuters".into(), "organization".into())).await;
+    let mut claim = Claim::new(
+        "Gulf Star Shipping, also known as Gulf Star Shipping LLC, manages the MT Crius.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        publisher,
+    );
+    claim.referenced_entity_ids = vec![first, second, vessel];
+    graph.create_claim(&claim, None).await.unwrap();
+
+    let registry = analyst_registry(&graph, &config);
+    let result = compare(&registry, first, second).await;
+
+    assert_eq!(result["verdict"], MatchBand::LikelySame.as_str());
+    assert!(result["scores"]["name_similarity"].as_f64().unwrap() >= 0.95);
+    assert_eq!(result["scores"]["conflicting_identifiers"], json!([]));
+    assert_eq!(result["shared_neighbors"]["entity_count"], 2);
+    assert_eq!(result["shared_neighbors"]["claim_count"], 1);
+    assert_eq!(
+        result["shared_neighbors"]["claim_ids"],
+        json!([claim.id.to_string()])
+    );
+    assert_eq!(result["entity_a"]["identifiers"]["imo"], "9321483");
+    assert_eq!(
+        result["entity_a"]["top_relationships"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        result["entity_b"]["recent_claims"][0]["id"],
+        claim.id.to_string()
+    );
+
+    // The verdict is remembered for the merge that follows.
+    let merge = registry
+        .execute(
+            "merge_entities",
+            json!({
+                "source_entity_id": second.to_string(),
+                "target_entity_id": first.to_string(),
+            }),
+        )
+        .await;
+    assert!(!merge.is_error, "merge failed: {}", merge.content);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_distinct_entities_compare_as_likely_different() {
+    let (graph, config) = setup().await;
+
+    let unrelated_a = create(
+        &graph,
+        Entity::new("Lloyd's List".into(), "organization".into()),
+    )
+    .await;
+    let unrelated_b = create(
+        &graph,
+        Entity::new("Meridian Tankers".into(), "organization".into()),
+    )
+    .await;
+
+    // Same name, but different IMO numbers: two ships.
+    let mut ship_a = Entity::new("Eagle".into(), "vessel".into());
+    ship_a
+        .identifiers
+        .insert(IdentifierScheme::Imo, "9321483".into());
+    let mut ship_b = ship_a.clone();
+    ship_b.id = EntityId::new();
+    ship_b
+        .identifiers
+        .insert(IdentifierScheme::Imo, "9187629".into());
+    let ship_a = create(&graph, ship_a).await;
+    let ship_b = create(&graph, ship_b).await;
+
+    let registry = analyst_registry(&graph, &config);
+
+    let result = compare(&registry, unrelated_a, unrelated_b).await;
+    assert_eq!(result["verdict"], MatchBand::LikelyDifferent.as_str());
+    assert_eq!(result["shared_neighbors"]["entity_count"], 0);
+    assert_eq!(result["shared_neighbors"]["claim_count"], 0);
+    assert!(result["scores"]["embedding_similarity"].is_null());
+
+    let result = compare(&registry, ship_a, ship_b).await;
+    assert_eq!(result["verdict"], MatchBand::LikelyDifferent.as_str());
+    assert_eq!(result["reason"], "identifiers conflict");
+    assert_eq!(result["scores"]["conflicting_identifiers"], json!(["imo"]));
+    assert_eq!(result["scores"]["shared_names"], json!(["eagle"]));
+
+    // Comparing an entity with itself is refused.
+    let same = registry
+        .execute(
+            "compare_entities",
+            json!({ "entity_id_a": ship_a.to_string(), "entity_id_b": ship_a.to_string() }),
+        )
+        .await;
+    assert!(same.is_error);
+}
@@ -0,0 +1,281 @@
+//! Integration tests for entity identifiers and identifier-aware dedup.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against a live Neo4j.
+use std::sync::Arc;
+
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::types::{Entity, IdentifierScheme, Identifiers};
+use autosint_common::EntityId;
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::dedup::{DedupResult, EntityDedup};
+use autosint_engine::graph::GraphClient;
+use autosint_engine::tools::handlers::{register_analyst_tools, register_processor_tools};
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+const APPLE_LEI: &str = "HWUPKR0MPOU8FGXBT394";
+const DEUTSCHE_BANK_LEI: &str = "7LTWFZYICNSX8D621K86";
+
+async fn setup() -> (Arc<GraphClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), engine_config)
+}
+
+fn registry(graph: &Arc<GraphClient>, config: &EngineConfig) -> ToolRegistry {
+    let services = SharedServices::new(
+        Arc::clone(graph),
+        "http://localhost:8081".into(),
+        reqwest::Client::new(),
+        Arc::default(),
+        config.system.tool_results.clone(),
+        config.system.dedup.clone(),
+    );
+    let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+        services,
+        SessionScope {
+            max_turns: 50,
+            ..Default::default()
+        },
+    ));
+    register_processor_tools(&mut registry);
+    register_analyst_tools(&mut registry);
+    registry
+}
+
+async fn create_with_lei(graph: &GraphClient, name: &str, lei: &str) -> EntityId {
+    let mut entity = Entity::new(name.into(), "organization".into());
+    entity
+        .identifiers
+        .insert(IdentifierScheme::Lei, lei.to_string());
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+fn lei(value: &str) -> Identifiers {
+    Identifiers::from([(IdentifierScheme::Lei, value.to_string())])
+}
+
+// -----------------------------------------------------------------------
+// 1. Stage 0: a shared identifier matches whatever the names
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_identifier_match_beats_names() {
+    let (graph, config) = setup().await;
+    let apple = create_with_lei(&graph, "Apple Inc.", APPLE_LEI).await;
+
+    let stored = graph.get_entity(apple).await.unwrap();
+    assert_eq!(stored.identifiers, lei(APPLE_LEI));
+
+    let dedup = EntityDedup::new(&graph, &config.system.dedup, None);
+    match dedup
+        .find_duplicate("AAPL Holdings", "organization", &lei(APPLE_LEI), None)
+        .await
+        .unwrap()
+    {
+        DedupResult::ExactMatch(id) => assert_eq!(id, apple),
+        _ => panic!("Expected an identifier match"),
+    }
+
+    // Through the tool, lowercase input is normalized before matching.
+    let processor = registry(&graph, &config);
+    let result = processor
+        .execute(
+            "create_entity",
+            json!({
+                "canonical_name": "Cupertino Computer Company",
+                "kind": "organization",
+                "identifiers": {"lei": APPLE_LEI.to_lowercase()},
+            }),
+        )
+        .await;
+    assert!(!result.is_error, "create_entity failed: {}", result.content);
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    assert_eq!(body["deduplicated"], true);
+    assert_eq!(body["entity_id"], apple.to_string());
+}
+
+// -----------------------------------------------------------------------
+// 2. A conflicting identifier rules out a same-name match
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_identifier_conflict_suppresses_name_match() {
+    let (graph, config) = setup().await;
+    let existing = create_with_lei(&graph, "Acme Holdings", APPLE_LEI).await;
+    let dedup = EntityDedup::new(&graph, &config.system.dedup, None);
+
+    // Same name, different LEI: a different company.
+    assert!(matches!(
+        dedup
+            .find_duplicate(
+                "Acme Holdings",
+                "organization",
+                &lei(DEUTSCHE_BANK_LEI),
+                None
+            )
+            .await
+            .unwrap(),
+        DedupResult::NoMatch
+    ));
+    // Close name, different LEI.
+    assert!(matches!(
+        dedup
+            .find_duplicate(
+                "Acme Holding",
+                "organization",
+                &lei(DEUTSCHE_BANK_LEI),
+                None
+            )
+            .await
+            .unwrap(),
+        DedupResult::NoMatch
+    ));
+    // Without identifiers the name still matches.
+    match dedup
+        .find_duplicate("Acme Holdings", "organization", &Identifiers::new(), None)
+        .await
+        .unwrap()
+    {
+        DedupResult::ExactMatch(id) => assert_eq!(id, existing),
+        _ => panic!("Expected a name match"),
+    }
+}
+
+// -----------------------------------------------------------------------
+// 3. Invalid identifiers are rejected before anything is written
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_invalid_identifiers_rejected() {
+    let (graph, config) = setup().await;
+    let processor = registry(&graph, &config);
+
+    for identifiers in [
+        json!({"lei": "HWUPKR0MPOU8FGXBT395"}),
+        json!({"imo": "9074728"}),
+        json!({"mmsi": "12345"}),
+        json!({"ticker": "AAPL"}),
+        json!({"isin": "US0378331005"}),
+    ] {
+        let result = processor
+            .execute(
+                "create_entity",
+                json!({
+                    "canonical_name": "Invalid Identifier Corp",
+                    "kind": "organization",
+                    "identifiers": identifiers,
+                }),
+            )
+            .await;
+        assert!(result.is_error, "{} accepted", identifiers);
+        assert!(result.content.contains("Invalid identifiers"));
+    }
+    let dedup = EntityDedup::new(&graph, &config.system.dedup, None);
+    assert!(matches!(
+        dedup
+            .find_duplicate(
+                "Invalid Identifier Corp",
+                "organization",
+                &Identifiers::new(),
+                None
+            )
+            .await
+            .unwrap(),
+        DedupResult::NoMatch
+    ));
+
+    // batch_extract drops the bad identifier but keeps the entity.
+    let source = graph
+        .create_entity(
+            &Entity::new("Lloyd's List".into(), "publication".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let result = processor
+        .execute(
+            "batch_extract",
+            json!({
+                "source_entity_id": source.id.to_string(),
+                "source_url": "https://example.org/vessel",
+                "published_timestamp": "2026-03-15T00:00:00Z",
+                "entities": [{
+                    "canonical_name": "MV Ever Given",
+                    "kind": "vessel",
+                    "identifiers": {"imo": "IMO 9811000", "mmsi": "123"},
+                }],
+            }),
+        )
+        .await;
+    assert!(!result.is_error, "batch_extract failed: {}", result.content);
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    assert_eq!(body["entities_created"], 1);
+    assert!(body["warnings"][0]
+        .as_str()
+        .unwrap()
+        .contains("Invalid identifier"));
+}
+
+// -----------------------------------------------------------------------
+// 4. Merging conflicting entities needs force; identifiers are combined
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_merge_refused_on_identifier_conflict() {
+    let (graph, _config) = setup().await;
+    let a = create_with_lei(&graph, "Apple Inc.", APPLE_LEI).await;
+    let b = create_with_lei(&graph, "Deutsche Bank AG", DEUTSCHE_BANK_LEI).await;
+
+    let err = graph.merge_entities(a, b, None, false).await.unwrap_err();
+    assert!(err.to_string().contains("lei"), "{}", err);
+    assert!(graph.get_entity(a).await.is_ok());
+
+    // Forced, the target keeps its LEI.
+    let merged = graph.merge_entities(a, b, None, true).await.unwrap();
+    assert_eq!(merged.identifiers, lei(DEUTSCHE_BANK_LEI));
+
+    // Without a conflict the target gains the source's identifiers.
+    let mut vessel = Entity::new("Ever Given".into(), "vessel".into());
+    vessel
+        .identifiers
+        .insert(IdentifierScheme::Imo, "9811000".into());
+    let vessel = graph.create_entity(&vessel, None).await.unwrap().id;
+    let mut duplicate = Entity::new("MV Ever Given".into(), "vessel".into());
+    duplicate
+        .identifiers
+        .insert(IdentifierScheme::Mmsi, "353136000".into());
+    let duplicate = graph.create_entity(&duplicate, None).await.unwrap().id;
+
+    let merged = graph
+        .merge_entities(duplicate, vessel, None, false)
+        .await
+        .unwrap();
+    assert_eq!(merged.identifiers[&IdentifierScheme::Imo], "9811000");
+    assert_eq!(merged.identifiers[&IdentifierScheme::Mmsi], "353136000");
+}
@@ -0,0 +1,152 @@
+//! Integration tests for automatic claim → entity linking.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use std::sync::Arc;
+
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::types::Entity;
+use autosint_common::EntityId;
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::linking::EntityLinker;
+use autosint_engine::graph::GraphClient;
+use autosint_engine::tools::handlers::register_processor_tools;
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+async fn setup() -> (Arc<GraphClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), engine_config)
+}
+
+/// Processor registry with entity linking on. Built after the test's entities
+/// exist, so the first lookup indexes them.
+fn processor_registry(graph: &Arc<GraphClient>, config: &EngineConfig) -> ToolRegistry {
+    let linker = EntityLinker::new(Arc::clone(graph), config.system.entity_linking.clone());
+    let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+        SharedServices::new(
+            Arc::clone(graph),
+            "http://localhost:8081".into(),
+            reqwest::Client::new(),
+            Arc::default(),
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        )
+        .with_entity_linker(Some(Arc::new(linker))),
+        SessionScope {
+            max_turns: 50,
+            ..Default::default()
+        },
+    ));
+    register_processor_tools(&mut registry);
+    registry
+}
+
+async fn create_entity(graph: &GraphClient, name: &str, kind: &str, aliases: &[&str]) -> EntityId {
+    let mut entity = Entity::new(name.into(), kind.into());
+    entity.aliases = aliases.iter().map(|a| a.to_string()).collect();
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+// -----------------------------------------------------------------------
+// 1. create_claim links an entity named by alias
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_create_claim_auto_links_alias_mention() {
+    let (graph, config) = setup().await;
+    let reuters = create_entity(&graph, "Reuters", "organization", &[]).await;
+    let rosneft = create_entity(&graph, "Rosneft Oil Company", "organization", &["Rosneft"]).await;
+    let india = create_entity(&graph, "India", "country", &[]).await;
+
+    let processor = processor_registry(&graph, &config);
+    let result = processor
+        .execute(
+            "create_claim",
+            json!({
+                "content": "Rosneft increased crude shipments to India in March, Reuters reported.",
+                "source_entity_id": reuters.to_string(),
+                "published_timestamp": "2026-03-15T00:00:00Z",
+                "referenced_entity_ids": [india.to_string()],
+            }),
+        )
+        .await;
+    assert!(!result.is_error, "create_claim failed: {}", result.content);
+
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    let auto_linked = body["auto_linked"].as_array().unwrap();
+    assert_eq!(auto_linked.len(), 1);
+    assert_eq!(auto_linked[0]["entity_id"], rosneft.to_string());
+    assert_eq!(auto_linked[0]["matched_name"], "Rosneft");
+
+    // The source (Reuters) and the explicit reference aren't auto-linked.
+    let claim_id = body["claim_id"].as_str().unwrap();
+    let claim = graph
+        .get_claim(claim_id.parse::<uuid::Uuid>().unwrap().into())
+        .await
+        .unwrap();
+    assert_eq!(claim.referenced_entity_ids.len(), 2);
+    assert!(claim.referenced_entity_ids.contains(&rosneft));
+    assert!(claim.referenced_entity_ids.contains(&india));
+    assert_eq!(claim.auto_linked_entity_ids, vec![rosneft]);
+}
+
+// -----------------------------------------------------------------------
+// 2. batch_extract links mentions the Processor didn't name
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_batch_extract_auto_links_mentions() {
+    let (graph, config) = setup().await;
+    let outlet = create_entity(&graph, "Lloyd's List", "organization", &[]).await;
+    let vessel = create_entity(&graph, "MV Example Star", "vessel", &["Example Star"]).await;
+    create_entity(&graph, "US", "country", &[]).await;
+
+    let processor = processor_registry(&graph, &config);
+    let result = processor
+        .execute(
+            "batch_extract",
+            json!({
+                "source_entity_id": outlet.to_string(),
+                "source_url": "https://example.com/shipping",
+                "published_timestamp": "2026-03-15T00:00:00Z",
+                "claims": [{
+                    "content": "The Example Star left port after US officials cleared it.",
+                    "attribution_depth": "secondhand",
+                    "information_type": "assertion",
+                }],
+            }),
+        )
+        .await;
+    assert!(!result.is_error, "batch_extract failed: {}", result.content);
+
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    assert_eq!(body["claims_created"], 1);
+    let entities = body["auto_linked"][0]["entities"].as_array().unwrap();
+    // "US" is below the minimum name length and is never linked.
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0]["entity_id"], vessel.to_string());
+}
@@ -0,0 +1,326 @@
+//! Integration tests for the investigation-scoped entity shortlist.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j and Redis.
+//!
+//! Setup: Connect to Neo4j and Redis from env vars (or localhost defaults).
+//! Each test cleans Neo4j via `MATCH (n) DETACH DELETE n` and uses a fresh
+//! investigation ID, so shortlists never overlap.
+use std::sync::Arc;
+
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::config::EntityShortlistConfig;
+use autosint_common::types::Entity;
+use autosint_common::{EntityId, InvestigationId};
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::GraphClient;
+use autosint_engine::queue::shortlist::{EntityShortlist, ShortlistEntry, SHORTLIST_REGISTRY};
+use autosint_engine::queue::QueueClient;
+use autosint_engine::tools::handlers::{register_analyst_tools, register_processor_tools};
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+const TTL: u64 = 600;
+
+async fn setup() -> (Arc<GraphClient>, Arc<QueueClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".into());
+    let queue = QueueClient::connect(&redis_url)
+        .await
+        .expect("Failed to connect to Redis");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), Arc::new(queue), engine_config)
+}
+
+fn shortlist(queue: &Arc<QueueClient>, investigation_id: InvestigationId) -> EntityShortlist {
+    let config = EntityShortlistConfig {
+        enabled: true,
+        ttl_seconds: TTL,
+        prompt_entities: 20,
+    };
+    EntityShortlist::new(Arc::clone(queue), investigation_id, &config).unwrap()
+}
+
+fn registry(
+    graph: &Arc<GraphClient>,
+    queue: &Arc<QueueClient>,
+    config: &EngineConfig,
+    investigation_id: InvestigationId,
+    analyst: bool,
+) -> ToolRegistry {
+    let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+        SharedServices::new(
+            Arc::clone(graph),
+            "http://localhost:8081".into(),
+            reqwest::Client::new(),
+            Arc::default(),
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        ),
+        SessionScope {
+            max_turns: 50,
+            investigation_id: Some(investigation_id),
+            entity_shortlist: Some(shortlist(queue, investigation_id)),
+            ..Default::default()
+        },
+    ));
+    if analyst {
+        register_analyst_tools(&mut registry);
+    } else {
+        register_processor_tools(&mut registry);
+    }
+    registry
+}
+
+async fn create_entity(graph: &GraphClient, name: &str, aliases: &[&str]) -> Entity {
+    let mut entity = Entity::new(name.into(), "organization".into());
+    entity.aliases = aliases.iter().map(|a| a.to_string()).collect();
+    graph.create_entity(&entity, None).await.unwrap()
+}
+
+async fn execute(registry: &ToolRegistry, tool: &str, args: Value) -> Value {
+    let result = registry.execute(tool, args).await;
+    assert!(!result.is_error, "{} failed: {}", tool, result.content);
+    serde_json::from_str(&result.content).unwrap()
+}
+
+// -----------------------------------------------------------------------
+// 1. Read-through
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_search_reads_through_shortlist() {
+    let (graph, queue, config) = setup().await;
+    let investigation_id = InvestigationId::new();
+    let processor = registry(&graph, &queue, &config, investigation_id, false);
+
+    let created = execute(
+        &processor,
+        "create_entity",
+        json!({
+            "canonical_name": "Sovcomflot",
+            "kind": "organization",
+            "aliases": ["SCF Group"],
+        }),
+    )
+    .await;
+    let id = created["entity_id"].clone();
+
+    // Alias hit, case-insensitive, served without a graph search.
+    let hit = execute(&processor, "search_entities", json!({"query": "scf group"})).await;
+    assert_eq!(hit["from_shortlist"], json!(true));
+    assert_eq!(hit["results"][0]["id"], id);
+    assert_eq!(hit["results"][0]["canonical_name"], "Sovcomflot");
+
+    // A kind filter that doesn't match falls through to the graph.
+    let miss = execute(
+        &processor,
+        "search_entities",
+        json!({"query": "Sovcomflot", "kind": "vessel", "mode": "keyword"}),
+    )
+    .await;
+    assert!(miss.get("from_shortlist").is_none());
+
+    // Other investigations don't share the shortlist.
+    let other = shortlist(&queue, InvestigationId::new());
+    assert!(other.lookup("Sovcomflot", None).await.is_none());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_batch_extract_resolves_names_from_shortlist() {
+    let (graph, queue, config) = setup().await;
+    let investigation_id = InvestigationId::new();
+    let outlet = create_entity(&graph, "Lloyd's List", &[]).await;
+    let owner = create_entity(&graph, "Gatik Ship Management", &[]).await;
+    let vessel = create_entity(&graph, "Crius", &[]).await;
+
+    // The Analyst resolved "Gatik" earlier; the graph knows no such alias.
+    shortlist(&queue, investigation_id)
+        .record(&owner, &["Gatik"])
+        .await;
+
+    let processor = registry(&graph, &queue, &config, investigation_id, false);
+    let body = execute(
+        &processor,
+        "batch_extract",
+        json!({
+            "source_entity_id": outlet.id.to_string(),
+            "source_url": "https://example.com/shadow-fleet",
+            "published_timestamp": "2026-03-15T00:00:00Z",
+            "relationships": [{
+                "source_entity_name": "Gatik",
+                "target_entity_name": "Crius",
+                "description": "Manages the tanker",
+            }],
+        }),
+    )
+    .await;
+    assert_eq!(body["relationships_created"], 1, "{}", body);
+
+    let edges = graph
+        .relationships_between(owner.id, vessel.id)
+        .await
+        .unwrap();
+    assert_eq!(edges.len(), 1);
+
+    // The graph fallback recorded "crius" for later lookups.
+    let entry = shortlist(&queue, investigation_id)
+        .lookup("CRIUS", None)
+        .await
+        .unwrap();
+    assert_eq!(entry.entity_id, vessel.id);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_lookup_refreshes_field_ttl() {
+    let (graph, queue, _) = setup().await;
+    let investigation_id = InvestigationId::new();
+    let entity = create_entity(&graph, "Fairmount Marine", &[]).await;
+    let shortlist = shortlist(&queue, investigation_id);
+    shortlist.record(&entity, &[]).await;
+
+    let names_key = format!("shortlist:{}:names", investigation_id);
+    let mut conn = queue.connection();
+    redis::cmd("HEXPIRE")
+        .arg(&names_key)
+        .arg(5)
+        .arg("FIELDS")
+        .arg(1)
+        .arg("fairmount marine")
+        .query_async::<()>(&mut conn)
+        .await
+        .unwrap();
+
+    assert!(shortlist.lookup("Fairmount Marine", None).await.is_some());
+    let ttl: Vec<i64> = redis::cmd("HTTL")
+        .arg(&names_key)
+        .arg("FIELDS")
+        .arg(1)
+        .arg("fairmount marine")
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(ttl[0] > 5, "TTL not refreshed: {:?}", ttl);
+}
+
+// -----------------------------------------------------------------------
+// 2. Merge invalidation
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_merge_rewrites_shortlist_entries() {
+    let (graph, queue, config) = setup().await;
+    let first = InvestigationId::new();
+    let second = InvestigationId::new();
+    let source = create_entity(&graph, "Rosneft Trading", &["RTSA"]).await;
+    let target = create_entity(&graph, "Rosneft", &[]).await;
+
+    shortlist(&queue, first).record(&source, &[]).await;
+    shortlist(&queue, first).record(&target, &[]).await;
+    shortlist(&queue, second).record(&source, &[]).await;
+
+    let analyst = registry(&graph, &queue, &config, first, true);
+    execute(
+        &analyst,
+        "merge_entities",
+        json!({
+            "source_entity_id": source.id.to_string(),
+            "target_entity_id": target.id.to_string(),
+        }),
+    )
+    .await;
+
+    // Both investigations' shortlists now resolve the source's names to the target.
+    for investigation_id in [first, second] {
+        let shortlist = shortlist(&queue, investigation_id);
+        for name in ["Rosneft Trading", "rtsa"] {
+            let entry = shortlist.lookup(name, None).await.unwrap();
+            assert_eq!(entry.entity_id, target.id, "{}", name);
+            assert_eq!(entry.canonical_name, "Rosneft");
+        }
+    }
+
+    // The source drops out of the prompt list and its references move over.
+    let top = queue.shortlist_top(first, 10).await.unwrap();
+    let ids: Vec<EntityId> = top.iter().map(|(entry, _)| entry.entity_id).collect();
+    assert_eq!(ids, vec![target.id]);
+    assert!(top[0].1 >= 2);
+}
+
+// -----------------------------------------------------------------------
+// 3. Terminal cleanup
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_clear_removes_shortlist() {
+    let (graph, queue, _) = setup().await;
+    let investigation_id = InvestigationId::new();
+    let entity = create_entity(&graph, "Ocean Speedstar", &[]).await;
+    queue
+        .shortlist_record(
+            investigation_id,
+            &[(
+                ShortlistEntry::from_entity(&entity),
+                vec!["Ocean Speedstar".into()],
+            )],
+            TTL,
+        )
+        .await
+        .unwrap();
+
+    let mut conn = queue.connection();
+    let pattern = format!("shortlist:{}:*", investigation_id);
+    let keys: Vec<String> = redis::cmd("KEYS")
+        .arg(&pattern)
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(keys.len(), 3);
+
+    queue.shortlist_clear(investigation_id).await.unwrap();
+
+    let keys: Vec<String> = redis::cmd("KEYS")
+        .arg(&pattern)
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(keys.is_empty());
+    let registered: bool = redis::cmd("SISMEMBER")
+        .arg(SHORTLIST_REGISTRY)
+        .arg(investigation_id.to_string())
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(!registered);
+    assert!(queue
+        .shortlist_lookup(investigation_id, "Ocean Speedstar", TTL)
+        .await
+        .unwrap()
+        .is_none());
+}
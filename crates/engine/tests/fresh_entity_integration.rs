@@ -0,0 +1,182 @@
+//! Integration tests for the fresh-entity scan that covers vector index lag.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against a live Neo4j.
+//!
+//! Entities are deduplicated straight after they are created, before the
+//! vector index has caught up. Each test cleans all data before running.
+use autosint_common::config::{DedupConfig, FreshEntityScanConfig};
+use autosint_common::types::Entity;
+use neo4rs::query;
+
+use autosint_engine::graph::dedup::EntityDedup;
+use autosint_engine::graph::{DedupResult, DedupStage, GraphClient};
+
+const DIMENSIONS: usize = 1536;
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let client = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    client
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+    client
+        .initialize_schema(DIMENSIONS as u32)
+        .await
+        .expect("Failed to initialize schema");
+    client
+}
+
+fn dedup_config(fresh_entities: FreshEntityScanConfig) -> DedupConfig {
+    DedupConfig {
+        fuzzy_threshold: 0.85,
+        embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities,
+        merge_exact_duplicate_claims: false,
+    }
+}
+
+fn fresh(enabled: bool) -> FreshEntityScanConfig {
+    FreshEntityScanConfig {
+        enabled,
+        ..Default::default()
+    }
+}
+
+/// A deterministic embedding; `nudge` perturbs it slightly.
+fn embedding(seed: usize, nudge: f32) -> Vec<f32> {
+    (0..DIMENSIONS)
+        .map(|i| {
+            ((i * 7 + seed * 13) % 17) as f32 / 17.0 - 0.5 + if i % 5 == 0 { nudge } else { 0.0 }
+        })
+        .collect()
+}
+
+async fn create(graph: &GraphClient, name: &str, seed: usize) -> Entity {
+    let mut entity = Entity::new(name.into(), "organization".into());
+    entity.embedding = Some(embedding(seed, 0.0));
+    graph
+        .create_entity(&entity, Some(embedding(seed, 0.0)))
+        .await
+        .unwrap()
+}
+
+// -----------------------------------------------------------------------
+// 1. A just-created entity is matched only with the scan enabled
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_dedup_matches_entity_created_moments_ago() {
+    let graph = setup().await;
+    let existing = create(&graph, "Halvard Maritime Logistics", 1).await;
+    // A name neither the exact nor the fuzzy stage matches; only the
+    // embedding links the two.
+    let candidate = embedding(1, 0.01);
+
+    // Straight after the write the vector index doesn't have the entity yet,
+    // so the index alone finds nothing.
+    let disabled = dedup_config(fresh(false));
+    let result = EntityDedup::new(&graph, &disabled, None)
+        .find_duplicate(
+            "HML Shipping Co",
+            "organization",
+            &Default::default(),
+            Some(&candidate),
+        )
+        .await
+        .unwrap();
+    assert!(
+        matches!(result, DedupResult::NoMatch),
+        "expected the vector index to lag the write"
+    );
+
+    let enabled = dedup_config(fresh(true));
+    let result = EntityDedup::new(&graph, &enabled, None)
+        .find_duplicate(
+            "HML Shipping Co",
+            "organization",
+            &Default::default(),
+            Some(&candidate),
+        )
+        .await
+        .unwrap();
+    match result {
+        DedupResult::ProbableMatch {
+            entity_id,
+            confidence,
+            stage: DedupStage::EmbeddingSimilarity,
+        } => {
+            assert_eq!(entity_id, existing.id);
+            assert!(confidence >= 0.90, "{}", confidence);
+        }
+        _ => panic!("expected an embedding match against the fresh entity"),
+    }
+}
+
+// -----------------------------------------------------------------------
+// 2. Dissimilar fresh entities don't match
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_fresh_scan_respects_embedding_threshold() {
+    let graph = setup().await;
+    create(&graph, "Halvard Maritime Logistics", 1).await;
+
+    let config = dedup_config(fresh(true));
+    let result = EntityDedup::new(&graph, &config, None)
+        .find_duplicate(
+            "Northwind Mining Corp",
+            "organization",
+            &Default::default(),
+            Some(&embedding(2, 0.0)),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(result, DedupResult::NoMatch));
+}
+
+// -----------------------------------------------------------------------
+// 3. The scan is skipped when the window holds more than max_scan entities
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_fresh_scan_skipped_over_cap() {
+    let graph = setup().await;
+    let existing = create(&graph, "Halvard Maritime Logistics", 1).await;
+    create(&graph, "Northwind Mining Corp", 2).await;
+    create(&graph, "Kestrel Freight", 3).await;
+
+    let capped = FreshEntityScanConfig {
+        max_scan: 2,
+        ..fresh(true)
+    };
+    let matches = graph
+        .fresh_entity_matches(&embedding(1, 0.01), &capped)
+        .await
+        .unwrap();
+    assert!(matches.is_empty());
+
+    let roomy = FreshEntityScanConfig {
+        max_scan: 3,
+        ..fresh(true)
+    };
+    let matches = graph
+        .fresh_entity_matches(&embedding(1, 0.01), &roomy)
+        .await
+        .unwrap();
+    assert_eq!(matches.len(), 3);
+    assert_eq!(matches[0].item.id, existing.id);
+    assert!(matches[0].score > matches[1].score);
+}
@@ -0,0 +1,257 @@
+//! Integration tests for seeded geographic entities.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults).
+//! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`.
+use std::path::Path;
+
+use autosint_common::config::DedupConfig;
+use autosint_common::types::Entity;
+use autosint_common::InvestigationId;
+use neo4rs::query;
+use serde_json::json;
+
+use autosint_engine::graph::dedup::EntityDedup;
+use autosint_engine::graph::geo_seed::{self, GeoSeedRecord};
+use autosint_engine::graph::{DedupResult, GraphClient};
+
+const SEED: &str = "\
+kind,iso_code,iso_alpha3,canonical_name,latitude,longitude,aliases
+country,US,USA,United States,39.83,-98.58,United States of America|USA|U.S.|U.S.A.
+country,ES,ESP,Spain,40.46,-3.75,Kingdom of Spain
+region,US-TX,,Texas,31.97,-99.9,State of Texas
+";
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let client = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+
+    client
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+
+    client
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    client
+}
+
+fn records() -> Vec<GeoSeedRecord> {
+    geo_seed::parse_geo_seed(SEED).unwrap()
+}
+
+fn dedup_config() -> DedupConfig {
+    DedupConfig {
+        fuzzy_threshold: 0.85,
+        embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities: Default::default(),
+        merge_exact_duplicate_claims: false,
+    }
+}
+
+async fn entity_count(graph: &GraphClient) -> i64 {
+    let mut result = graph
+        .inner()
+        .execute(query("MATCH (e:Entity) RETURN count(e) AS n"))
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+async fn find_duplicate(graph: &GraphClient, name: &str, kind: &str) -> DedupResult {
+    let config = dedup_config();
+    EntityDedup::new(graph, &config, None)
+        .find_duplicate(name, kind, &Default::default(), None)
+        .await
+        .unwrap()
+}
+
+// -----------------------------------------------------------------------
+// 1. Idempotent seeding
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_reseeding_is_idempotent() {
+    let graph = setup().await;
+
+    let first = graph.seed_geo_entities(&records()).await.unwrap();
+    assert_eq!(first.records, 3);
+    assert_eq!(first.created, 3);
+    assert_eq!(first.updated, 0);
+    assert_eq!(entity_count(&graph).await, 3);
+
+    let us = match find_duplicate(&graph, "United States", "country").await {
+        DedupResult::ExactMatch(id) => graph.get_entity(id).await.unwrap(),
+        _ => panic!("Expected ExactMatch"),
+    };
+    assert!(us.is_seeded);
+    assert_eq!(us.kind, "country");
+    assert_eq!(us.properties["iso_alpha2"], json!("US"));
+    assert_eq!(us.properties["iso_alpha3"], json!("USA"));
+    assert_eq!(us.properties["latitude"], json!(39.83));
+
+    let second = graph.seed_geo_entities(&records()).await.unwrap();
+    assert_eq!(second.created, 0);
+    assert_eq!(second.updated, 3);
+    assert_eq!(entity_count(&graph).await, 3);
+
+    let again = graph.get_entity(us.id).await.unwrap();
+    assert_eq!(again.canonical_name, "United States");
+    assert_eq!(again.aliases, us.aliases);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_seeding_adopts_existing_entity() {
+    let graph = setup().await;
+    let investigation = InvestigationId::new();
+
+    let mut llm_created = Entity::new("U.S.".into(), "country".into());
+    llm_created.aliases = vec!["Uncle Sam".into()];
+    llm_created.created_by_investigation = Some(investigation);
+    let llm_created = graph.create_entity(&llm_created, None).await.unwrap();
+
+    let report = graph.seed_geo_entities(&records()).await.unwrap();
+    assert_eq!(report.created, 2);
+    assert_eq!(report.updated, 1);
+
+    let adopted = graph.get_entity(llm_created.id).await.unwrap();
+    assert!(adopted.is_seeded);
+    assert_eq!(adopted.canonical_name, "United States");
+    assert!(adopted.aliases.contains(&"U.S.".to_string()));
+    assert!(adopted.aliases.contains(&"Uncle Sam".to_string()));
+
+    // Purging the investigation that first created it leaves the seeded entity.
+    let purge = graph
+        .purge_by_investigation(investigation, &[], false)
+        .await
+        .unwrap();
+    assert!(purge.entity_ids.is_empty());
+    assert!(graph.get_entity(adopted.id).await.is_ok());
+}
+
+// -----------------------------------------------------------------------
+// 2. Dedup against seeded entities
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_alias_matches_seeded_entity() {
+    let graph = setup().await;
+    graph.seed_geo_entities(&records()).await.unwrap();
+
+    // An unseeded entity with the same name doesn't win the exact match.
+    graph
+        .create_entity(&Entity::new("USA".into(), "country".into()), None)
+        .await
+        .unwrap();
+
+    for name in ["U.S.", "usa", "United States of America"] {
+        match find_duplicate(&graph, name, "country").await {
+            DedupResult::ExactMatch(id) => {
+                let entity = graph.get_entity(id).await.unwrap();
+                assert_eq!(entity.canonical_name, "United States", "{}", name);
+                assert!(entity.is_seeded);
+            }
+            _ => panic!("Expected ExactMatch for '{}'", name),
+        }
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_seeded_geo_fuzzy_threshold_preference() {
+    let graph = setup().await;
+
+    // Jaro-Winkler("the kingdom of spain", "kingdom of spain") ≈ 0.82: below the
+    // regular threshold, above the seeded geographic one. The unseeded entity has
+    // a different kind so seeding doesn't adopt it.
+    graph
+        .create_entity(
+            &Entity::new("Kingdom of Spain".into(), "organization".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        find_duplicate(&graph, "The Kingdom of Spain", "country").await,
+        DedupResult::NoMatch
+    ));
+
+    graph.seed_geo_entities(&records()).await.unwrap();
+    let seeded_spain = match find_duplicate(&graph, "The Kingdom of Spain", "country").await {
+        DedupResult::ProbableMatch { entity_id, .. } => entity_id,
+        _ => panic!("Expected ProbableMatch against the seeded entity"),
+    };
+    let entity = graph.get_entity(seeded_spain).await.unwrap();
+    assert_eq!(entity.canonical_name, "Spain");
+    assert!(entity.is_seeded);
+
+    // Non-geographic kinds keep the regular threshold.
+    assert!(matches!(
+        find_duplicate(&graph, "The Kingdom of Spain", "organization").await,
+        DedupResult::NoMatch
+    ));
+}
+
+// -----------------------------------------------------------------------
+// 3. Bundled seed file
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_bundled_seed_file_loads() {
+    let graph = setup().await;
+    let config_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config");
+
+    let records = geo_seed::load_geo_seed(&config_dir).unwrap();
+    let report = graph.seed_geo_entities(&records).await.unwrap();
+
+    assert_eq!(report.created, records.len() as u64);
+    assert_eq!(entity_count(&graph).await, records.len() as i64);
+}
+
+// -----------------------------------------------------------------------
+// 4. Claim regions normalize against seeded entities
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_normalize_region() {
+    let graph = setup().await;
+    graph.seed_geo_entities(&records()).await.unwrap();
+
+    let normalize = |region: &'static str| {
+        let graph = graph.clone();
+        async move { graph.normalize_region(region).await.unwrap() }
+    };
+    assert_eq!(normalize("us").await.as_deref(), Some("US"));
+    assert_eq!(normalize("ESP").await.as_deref(), Some("ES"));
+    assert_eq!(normalize("us-tx").await.as_deref(), Some("US-TX"));
+    assert_eq!(normalize(" united states ").await.as_deref(), Some("US"));
+    assert_eq!(normalize("Kingdom of Spain").await.as_deref(), Some("ES"));
+    assert_eq!(normalize("state of texas").await.as_deref(), Some("US-TX"));
+
+    // Unknown codes are kept uppercased, unknown names as written, and a
+    // name that only appears inside an alias doesn't match.
+    assert_eq!(normalize("fr").await.as_deref(), Some("FR"));
+    assert_eq!(
+        normalize("Eastern Europe").await.as_deref(),
+        Some("Eastern Europe")
+    );
+    assert_eq!(normalize("Kingdom").await.as_deref(), Some("Kingdom"));
+    assert_eq!(normalize("  ").await, None);
+}
@@ -0,0 +1,266 @@
+//! Integration tests for the graph consistency checks and their repairs.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults). Each test
+//! cleans Neo4j via `MATCH (n) DETACH DELETE n`, seeds consistent data and
+//! corrupts some of it by hand.
+use chrono::Utc;
+use neo4rs::query;
+
+use autosint_common::api::engine::{ConsistencySeverity, GraphConsistencyReport};
+use autosint_common::config::GraphConsistencyConfig;
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_common::EntityId;
+use autosint_engine::graph::GraphClient;
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+    graph
+}
+
+fn config() -> GraphConsistencyConfig {
+    GraphConsistencyConfig {
+        unstub_min_claims: 3,
+        // One fix per transaction, so repairs run in several batches.
+        repair_batch_size: 1,
+        ..Default::default()
+    }
+}
+
+async fn create_entity(graph: &GraphClient, name: &str) -> EntityId {
+    let entity = Entity::new(name.into(), "organization".into());
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+async fn create_claim(graph: &GraphClient, source: EntityId, references: &[EntityId]) -> Claim {
+    let mut claim = Claim::new(
+        "Example Shipping Ltd operates the tanker MV Example.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        source,
+    );
+    claim.referenced_entity_ids = references.to_vec();
+    graph.create_claim(&claim, None).await.unwrap()
+}
+
+async fn run(graph: &GraphClient, cypher: &str, id: &str) {
+    graph
+        .inner()
+        .run(query(cypher).param("id", id))
+        .await
+        .unwrap();
+}
+
+fn violations(report: &GraphConsistencyReport, name: &str) -> (u64, Vec<String>) {
+    let check = report
+        .checks
+        .iter()
+        .find(|c| c.name == name)
+        .unwrap_or_else(|| panic!("no check named {}", name));
+    let mut ids = check.sample_ids.clone();
+    ids.sort();
+    (check.violations, ids)
+}
+
+fn sorted(ids: &[&str]) -> Vec<String> {
+    let mut ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    ids.sort();
+    ids
+}
+
+// -----------------------------------------------------------------------
+// 1. Consistent data has no violations
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_clean_graph_passes() {
+    let graph = setup().await;
+    let publisher = create_entity(&graph, "Lloyd's List").await;
+    let subject = create_entity(&graph, "Example Shipping Ltd").await;
+    create_claim(&graph, publisher, &[subject]).await;
+    let relationship = Relationship::new(publisher, subject, "reports on".into());
+    graph
+        .create_relationship(&relationship, Some(vec![0.1; 1536]), false)
+        .await
+        .unwrap();
+
+    let report = graph.check_consistency(&config()).await.unwrap();
+    assert!(!report.checks.is_empty());
+    for check in &report.checks {
+        assert_eq!(check.violations, 0, "{} flagged clean data", check.name);
+    }
+}
+
+// -----------------------------------------------------------------------
+// 2. Corrupted data is detected and the safe subset repaired
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_detects_and_repairs_corruption() {
+    let graph = setup().await;
+    let config = config();
+
+    // Orphaned claim: its source entity deleted out from under it.
+    let doomed = create_entity(&graph, "Defunct Wire Service").await;
+    let orphan = create_claim(&graph, doomed, &[]).await;
+    run(
+        &graph,
+        "MATCH (e:Entity {id: $id}) DETACH DELETE e",
+        &doomed.to_string(),
+    )
+    .await;
+
+    // Dangling auto-link: the linked entity is merged away.
+    let publisher = create_entity(&graph, "Lloyd's List").await;
+    let duplicate = create_entity(&graph, "Example Shipping").await;
+    let canonical = create_entity(&graph, "Example Shipping Ltd").await;
+    let mut linked = Claim::new(
+        "Example Shipping chartered a second tanker.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        publisher,
+    );
+    linked.referenced_entity_ids = vec![duplicate];
+    linked.auto_linked_entity_ids = vec![duplicate];
+    let linked = graph.create_claim(&linked, None).await.unwrap();
+    graph
+        .merge_entities(duplicate, canonical, None, false)
+        .await
+        .unwrap();
+
+    // Aliases: one recoverable (a native list), one not.
+    let listed = create_entity(&graph, "Acme Maritime").await;
+    run(
+        &graph,
+        "MATCH (e:Entity {id: $id}) SET e.aliases = ['ACME', 'Acme Marine']",
+        &listed.to_string(),
+    )
+    .await;
+    let garbled = create_entity(&graph, "Blue Star Lines").await;
+    run(
+        &graph,
+        "MATCH (e:Entity {id: $id}) SET e.aliases = 'Blue Star, BSL'",
+        &garbled.to_string(),
+    )
+    .await;
+
+    // Relationship flagged for embedding though it has one.
+    let relationship = Relationship::new(publisher, canonical, "reports on".into());
+    let relationship = graph
+        .create_relationship(&relationship, Some(vec![0.1; 1536]), false)
+        .await
+        .unwrap();
+    run(
+        &graph,
+        "MATCH ()-[r:RELATES_TO {id: $id}]->() SET r.embedding_pending = true",
+        &relationship.id.to_string(),
+    )
+    .await;
+
+    // Stubs: one with a summary and three claims, one with claims but no summary.
+    let mut overdue = Entity::new("MV Example".into(), "vessel".into());
+    overdue.is_stub = true;
+    overdue.summary = Some("Crude tanker operated by Example Shipping Ltd.".into());
+    let overdue = graph.create_entity(&overdue, None).await.unwrap().id;
+    let mut bare = Entity::new("MV Sample".into(), "vessel".into());
+    bare.is_stub = true;
+    let bare = graph.create_entity(&bare, None).await.unwrap().id;
+    for _ in 0..3 {
+        create_claim(&graph, publisher, &[overdue, bare]).await;
+    }
+
+    // Detection.
+    let report = graph.check_consistency(&config).await.unwrap();
+    assert_eq!(
+        violations(&report, "orphaned_claims"),
+        (1, vec![orphan.id.to_string()])
+    );
+    assert_eq!(
+        violations(&report, "dangling_auto_links"),
+        (1, vec![linked.id.to_string()])
+    );
+    assert_eq!(
+        violations(&report, "malformed_aliases"),
+        (2, sorted(&[&listed.to_string(), &garbled.to_string()]))
+    );
+    assert_eq!(
+        violations(&report, "stale_relationship_embedding_pending"),
+        (1, vec![relationship.id.to_string()])
+    );
+    assert_eq!(
+        violations(&report, "overdue_stubs"),
+        (1, vec![overdue.to_string()])
+    );
+    assert_eq!(report.violations(ConsistencySeverity::Error), 1);
+    assert_eq!(report.violations(ConsistencySeverity::Warning), 3);
+
+    // Dry run counts the safe fixes and changes nothing.
+    let dry_run = graph.repair_consistency(&config, true).await.unwrap();
+    assert!(dry_run.dry_run);
+    let repairable: Vec<(&str, u64, u64)> = dry_run
+        .repairs
+        .iter()
+        .map(|r| (r.name.as_str(), r.repairable, r.repaired))
+        .collect();
+    assert_eq!(
+        repairable,
+        vec![
+            ("malformed_aliases", 1, 0),
+            ("stale_relationship_embedding_pending", 1, 0),
+            ("overdue_stubs", 1, 0),
+            ("stale_claim_digests", 0, 0),
+        ]
+    );
+    assert_eq!(
+        graph.check_consistency(&config).await.unwrap().checks,
+        report.checks
+    );
+
+    // Repair fixes exactly the safe subset.
+    let applied = graph.repair_consistency(&config, false).await.unwrap();
+    assert!(applied.repairs.iter().all(|r| r.repaired == r.repairable));
+
+    let after = graph.check_consistency(&config).await.unwrap();
+    assert_eq!(violations(&after, "orphaned_claims").0, 1);
+    assert_eq!(violations(&after, "dangling_auto_links").0, 1);
+    assert_eq!(
+        violations(&after, "malformed_aliases"),
+        (1, vec![garbled.to_string()])
+    );
+    assert_eq!(
+        violations(&after, "stale_relationship_embedding_pending").0,
+        0
+    );
+    assert_eq!(violations(&after, "overdue_stubs").0, 0);
+
+    let fixed = graph.get_entity(listed).await.unwrap();
+    assert_eq!(fixed.aliases, vec!["ACME", "Acme Marine"]);
+    assert!(!graph.get_entity(overdue).await.unwrap().is_stub);
+    assert!(graph.get_entity(bare).await.unwrap().is_stub);
+    let relationship = graph.get_relationship(relationship.id).await.unwrap();
+    assert!(!relationship.embedding_pending);
+
+    // Repairs are idempotent.
+    let again = graph.repair_consistency(&config, false).await.unwrap();
+    assert!(again.repairs.iter().all(|r| r.repaired == 0));
+}
@@ -0,0 +1,122 @@
+//! Integration tests for the Processor's preloaded graph context.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use chrono::Utc;
+use neo4rs::query;
+
+use autosint_common::config::ToolResultLimits;
+use autosint_common::types::{
+    AttributionDepth, Claim, Entity, IdentifierScheme, InformationType, Relationship,
+};
+use autosint_common::EntityId;
+use autosint_engine::graph::GraphClient;
+use autosint_engine::processor::{format_graph_context, load_graph_context};
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+    graph
+}
+
+fn limits() -> ToolResultLimits {
+    ToolResultLimits {
+        max_search_results: 20,
+        max_entity_detail_chars: 10000,
+        max_claim_preview_chars: 500,
+        max_claim_references_shown: 10,
+        max_result_tokens: 8000,
+        max_graph_context_chars: 6000,
+        max_verification_excerpt_chars: 1500,
+        max_formatted_result_chars: 24000,
+        response_formats: Default::default(),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_graph_context_renders_seeded_entities() {
+    let graph = setup().await;
+
+    let mut operator = Entity::new("Gulf Star Shipping LLC".into(), "organization".into());
+    operator.aliases = vec!["Gulf Star".into()];
+    operator
+        .identifiers
+        .insert(IdentifierScheme::Imo, "9321483".into());
+    let operator = graph.create_entity(&operator, None).await.unwrap();
+    let vessel = graph
+        .create_entity(&Entity::new("MT Crius".into(), "vessel".into()), None)
+        .await
+        .unwrap();
+    let publisher = graph
+        .create_entity(&Entity::new("Reuters".into(), "organization".into()), None)
+        .await
+        .unwrap();
+
+    let mut manages = Relationship::new(operator.id, vessel.id, "manages the tanker".into());
+    manages.weight = Some(0.9);
+    graph
+        .create_relationship(&manages, None, false)
+        .await
+        .unwrap();
+    let mut claim = Claim::new(
+        "Gulf Star Shipping took over management of the MT Crius in March.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        publisher.id,
+    );
+    claim.referenced_entity_ids = vec![operator.id, vessel.id];
+    graph.create_claim(&claim, None).await.unwrap();
+
+    let missing = EntityId::new();
+    let context = load_graph_context(
+        &graph,
+        &[vessel.id, missing, operator.id],
+        None,
+        limits().max_claim_preview_chars as usize,
+    )
+    .await;
+    assert_eq!(context.entities.len(), 2);
+    assert_eq!(context.unknown, vec![missing]);
+
+    let block = format_graph_context(&context, &limits()).unwrap();
+    // Work order order: the vessel first, then the operator, then the unknown.
+    let vessel_at = block.find("### MT Crius (vessel)").unwrap();
+    let operator_at = block
+        .find("### Gulf Star Shipping LLC (organization)")
+        .unwrap();
+    let missing_at = block.find(&format!("- `{}`", missing)).unwrap();
+    assert!(
+        vessel_at < operator_at && operator_at < missing_at,
+        "{}",
+        block
+    );
+
+    assert!(block.contains("Aliases: Gulf Star\n"));
+    assert!(block.contains("Identifiers: imo 9321483\n"));
+    assert!(block.contains(&format!(
+        "- → MT Crius `{}`: manages the tanker (weight 0.90)",
+        vessel.id
+    )));
+    assert!(block.contains(&format!(
+        "- ← Gulf Star Shipping LLC `{}`: manages the tanker",
+        operator.id
+    )));
+    assert_eq!(block.matches(&claim.id.to_string()).count(), 2);
+    assert!(block.contains("Not in the graph"));
+    assert!(block.chars().count() <= 6000);
+}
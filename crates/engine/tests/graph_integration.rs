@@ -1,18 +1,30 @@
-///! Integration tests for Neo4j graph operations.
-///! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
-///!
-///! Setup: Connect to Neo4j from env vars (or localhost defaults).
-///! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`.
-use autosint_common::types::{AttributionDepth, Claim, Entity, Relationship};
+//! Integration tests for Neo4j graph operations.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults).
+//! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`.
+mod common;
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use autosint_common::config::{GraphWriteConfig, WriteCoalesceConfig};
+use autosint_common::ids::{ClaimId, EntityId};
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
 use chrono::Utc;
 use neo4rs::query;
 use serde_json::json;
 
 use autosint_engine::graph::{
-    EntitySearchParams, EntityUpdate, GraphClient, RelationshipUpdate, SearchMode,
-    TraversalDirection, TraversalParams,
+    ClaimSearchParams, EntitySearchParams, EntityUpdate, GraphClient, GraphError,
+    RelationshipUpdate, SearchCursor, SearchMode, TraversalDirection, TraversalParams,
 };
 
+use common::FixtureBuilder;
+
+/// Upper bound for index population after schema setup.
+const INDEX_TIMEOUT: Duration = Duration::from_secs(30);
+
 async fn setup() -> GraphClient {
     let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
     let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
@@ -31,7 +43,7 @@ async fn setup() -> GraphClient {
 
     // Initialize schema.
     client
-        .initialize_schema()
+        .initialize_schema(1536)
         .await
         .expect("Failed to initialize schema");
 
@@ -68,6 +80,8 @@ async fn test_entity_crud() {
         summary: Some("A country in North America.".into()),
         is_stub: None,
         properties: None,
+        remove_properties: Vec::new(),
+        identifiers: Default::default(),
     };
     let updated = graph
         .update_entity(created.id, &update, None)
@@ -145,6 +159,7 @@ async fn test_claim_crud() {
         "China announced new trade tariffs on US goods.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     claim.referenced_entity_ids = vec![ref_entity.id];
@@ -183,7 +198,7 @@ async fn test_relationship_crud() {
     rel.weight = Some(0.8);
     rel.confidence = Some(0.95);
 
-    let created = graph.create_relationship(&rel, None).await.unwrap();
+    let created = graph.create_relationship(&rel, None, false).await.unwrap();
     assert_eq!(created.description, "Supplies A-series chips to Apple.");
     assert_eq!(created.weight, Some(0.8));
     assert_eq!(created.source_entity_id, e1.id);
@@ -196,6 +211,7 @@ async fn test_relationship_crud() {
         confidence: None,
         bidirectional: None,
         timestamp: None,
+        weight_recalibrated_at: None,
     };
     let updated = graph
         .update_relationship(created.id, &update, None)
@@ -229,7 +245,7 @@ async fn test_relationship_bidirectional() {
     let mut rel = Relationship::new(e1.id, e2.id, "Share a border in western Europe.".into());
     rel.bidirectional = true;
 
-    let created = graph.create_relationship(&rel, None).await.unwrap();
+    let created = graph.create_relationship(&rel, None, false).await.unwrap();
     assert!(created.bidirectional);
 
     // Traverse from e2 (incoming direction should also find it via Both).
@@ -239,11 +255,13 @@ async fn test_relationship_bidirectional() {
             &TraversalParams {
                 direction: Some(TraversalDirection::Both),
                 min_weight: None,
+                as_of: None,
                 limit: None,
             },
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(!from_e2.is_empty());
 }
@@ -272,8 +290,8 @@ async fn test_fulltext_search_entity_name() {
         .await
         .unwrap();
 
-    // Wait briefly for fulltext index to update.
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    // Fresh indexes must finish populating before they answer queries.
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
 
     let results = graph
         .search_entities(
@@ -283,12 +301,15 @@ async fn test_fulltext_search_entity_name() {
                 kind_filter: None,
                 updated_after: None,
                 updated_before: None,
+                created_before: None,
                 limit: Some(5),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(!results.is_empty());
     assert_eq!(results[0].item.canonical_name, "European Central Bank");
@@ -307,7 +328,7 @@ async fn test_fulltext_search_entity_alias() {
     entity.aliases = vec!["USA".into(), "United States".into()];
     graph.create_entity(&entity, None).await.unwrap();
 
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
 
     let results = graph
         .search_entities(
@@ -317,12 +338,15 @@ async fn test_fulltext_search_entity_alias() {
                 kind_filter: None,
                 updated_after: None,
                 updated_before: None,
+                created_before: None,
                 limit: Some(5),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(!results.is_empty());
     assert_eq!(results[0].item.canonical_name, "United States of America");
@@ -358,8 +382,7 @@ async fn test_vector_search_entities() {
     let e3 = Entity::new("Different C".into(), "test".into());
     graph.create_entity(&e3, Some(emb_c)).await.unwrap();
 
-    // Wait for vector index to update.
-    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
 
     // Search with a query vector close to emb_a.
     let results = graph
@@ -370,12 +393,15 @@ async fn test_vector_search_entities() {
                 kind_filter: None,
                 updated_after: None,
                 updated_before: None,
+                created_before: None,
                 limit: Some(3),
+                cursor: None,
             },
             Some(emb_a),
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(results.len() >= 2);
     // "Similar A" should be the closest match (exact vector match).
@@ -400,11 +426,12 @@ async fn test_fulltext_search_claims() {
         "Oil prices surged amid Middle East tensions.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     graph.create_claim(&claim, None).await.unwrap();
 
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
 
     let results = graph
         .search_claims(
@@ -416,12 +443,19 @@ async fn test_fulltext_search_claims() {
                 source_entity_id: None,
                 referenced_entity_id: None,
                 attribution_depth: None,
+                information_type: None,
+                language: None,
+                region: None,
+                min_trust: None,
+                ingested_before: None,
                 limit: Some(5),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(!results.is_empty());
     assert!(results[0].item.content.contains("Oil prices"));
@@ -449,6 +483,7 @@ async fn test_claim_search_by_source_entity() {
         "Claim from Reuters.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         reuters.id,
     );
     graph.create_claim(&claim1, None).await.unwrap();
@@ -457,6 +492,7 @@ async fn test_claim_search_by_source_entity() {
         "Claim from BBC.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         bbc.id,
     );
     graph.create_claim(&claim2, None).await.unwrap();
@@ -471,12 +507,19 @@ async fn test_claim_search_by_source_entity() {
                 source_entity_id: Some(reuters.id),
                 referenced_entity_id: None,
                 attribution_depth: None,
+                information_type: None,
+                language: None,
+                region: None,
+                min_trust: None,
+                ingested_before: None,
                 limit: Some(10),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert_eq!(results.len(), 1);
     assert!(results[0].item.content.contains("Reuters"));
@@ -508,6 +551,7 @@ async fn test_claim_search_by_referenced_entity() {
         "Claim about China.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     c1.referenced_entity_ids = vec![china.id];
@@ -517,6 +561,7 @@ async fn test_claim_search_by_referenced_entity() {
         "Claim about Japan.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     c2.referenced_entity_ids = vec![japan.id];
@@ -532,12 +577,19 @@ async fn test_claim_search_by_referenced_entity() {
                 source_entity_id: None,
                 referenced_entity_id: Some(china.id),
                 attribution_depth: None,
+                information_type: None,
+                language: None,
+                region: None,
+                min_trust: None,
+                ingested_before: None,
                 limit: Some(10),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert_eq!(results.len(), 1);
     assert!(results[0].item.content.contains("China"));
@@ -568,6 +620,7 @@ async fn test_claim_search_temporal() {
         "Old claim.".into(),
         old_date,
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     graph.create_claim(&c_old, None).await.unwrap();
@@ -576,6 +629,7 @@ async fn test_claim_search_temporal() {
         "New claim.".into(),
         new_date,
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     graph.create_claim(&c_new, None).await.unwrap();
@@ -594,12 +648,19 @@ async fn test_claim_search_temporal() {
                 source_entity_id: None,
                 referenced_entity_id: None,
                 attribution_depth: None,
+                information_type: None,
+                language: None,
+                region: None,
+                min_trust: None,
+                ingested_before: None,
                 limit: Some(10),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert_eq!(results.len(), 1);
     assert!(results[0].item.content.contains("New"));
@@ -623,6 +684,7 @@ async fn test_claim_search_attribution_depth() {
         "Primary claim.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source.id,
     );
     graph.create_claim(&c_primary, None).await.unwrap();
@@ -631,6 +693,7 @@ async fn test_claim_search_attribution_depth() {
         "Secondhand claim.".into(),
         Utc::now(),
         AttributionDepth::Secondhand,
+        InformationType::Assertion,
         source.id,
     );
     graph.create_claim(&c_secondhand, None).await.unwrap();
@@ -645,12 +708,19 @@ async fn test_claim_search_attribution_depth() {
                 source_entity_id: None,
                 referenced_entity_id: None,
                 attribution_depth: Some(AttributionDepth::Primary),
+                information_type: None,
+                language: None,
+                region: None,
+                min_trust: None,
+                ingested_before: None,
                 limit: Some(10),
+                cursor: None,
             },
             None,
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert_eq!(results.len(), 1);
     assert!(results[0].item.content.contains("Primary"));
@@ -679,10 +749,10 @@ async fn test_relationship_traverse() {
         .unwrap();
 
     let r1 = Relationship::new(a.id, b.id, "A to B".into());
-    graph.create_relationship(&r1, None).await.unwrap();
+    graph.create_relationship(&r1, None, false).await.unwrap();
 
     let r2 = Relationship::new(b.id, c.id, "B to C".into());
-    graph.create_relationship(&r2, None).await.unwrap();
+    graph.create_relationship(&r2, None, false).await.unwrap();
 
     // Traverse outgoing from A — should find B.
     let from_a = graph
@@ -691,11 +761,13 @@ async fn test_relationship_traverse() {
             &TraversalParams {
                 direction: Some(TraversalDirection::Outgoing),
                 min_weight: None,
+                as_of: None,
                 limit: None,
             },
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert_eq!(from_a.len(), 1);
     assert_eq!(from_a[0].1.canonical_name, "B");
@@ -724,23 +796,26 @@ async fn test_relationship_semantic_search() {
 
     let rel = Relationship::new(e1.id, e2.id, "X supplies widgets to Y.".into());
     graph
-        .create_relationship(&rel, Some(emb.clone()))
+        .create_relationship(&rel, Some(emb.clone()), false)
         .await
         .unwrap();
 
-    // Wait for vector index.
-    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    // Fresh indexes must finish populating before they answer queries.
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
 
     let results = graph
         .search_relationships(
             &autosint_engine::graph::RelationshipSearchParams {
                 query: String::new(),
+                created_before: None,
                 limit: Some(5),
+                cursor: None,
             },
             Some(emb),
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(!results.is_empty());
     assert!(results[0].item.description.contains("widgets"));
@@ -774,6 +849,7 @@ async fn test_entity_merge() {
         "US GDP grew.".into(),
         Utc::now(),
         AttributionDepth::Primary,
+        InformationType::Assertion,
         source_pub.id,
     );
     claim.referenced_entity_ids = vec![e1.id];
@@ -785,11 +861,11 @@ async fn test_entity_merge() {
         .await
         .unwrap();
     let rel = Relationship::new(e1.id, other.id, "Shares border with.".into());
-    graph.create_relationship(&rel, None).await.unwrap();
+    graph.create_relationship(&rel, None, false).await.unwrap();
 
     // Merge e1 (source) into e2 (target).
     let merged = graph
-        .merge_entities(e1.id, e2.id, Some("Duplicate entity"))
+        .merge_entities(e1.id, e2.id, Some("Duplicate entity"), false)
         .await
         .unwrap();
 
@@ -815,11 +891,13 @@ async fn test_entity_merge() {
             &TraversalParams {
                 direction: Some(TraversalDirection::Outgoing),
                 min_weight: None,
+                as_of: None,
                 limit: None,
             },
         )
         .await
-        .unwrap();
+        .unwrap()
+        .results;
 
     assert!(!rels.is_empty());
     assert_eq!(rels[0].1.canonical_name, "Canada");
@@ -842,11 +920,16 @@ async fn test_dedup_exact_match() {
     let config = autosint_common::config::DedupConfig {
         fuzzy_threshold: 0.85,
         embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities: Default::default(),
+        merge_exact_duplicate_claims: false,
     };
 
     let dedup = autosint_engine::graph::dedup::EntityDedup::new(&graph, &config, None);
     let result = dedup
-        .find_duplicate("United States", "country", None)
+        .find_duplicate("United States", "country", &Default::default(), None)
         .await
         .unwrap();
 
@@ -872,17 +955,22 @@ async fn test_dedup_fuzzy_match() {
     entity.aliases = vec!["United States".into(), "USA".into()];
     graph.create_entity(&entity, None).await.unwrap();
 
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
 
     let config = autosint_common::config::DedupConfig {
         fuzzy_threshold: 0.85,
         embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities: Default::default(),
+        merge_exact_duplicate_claims: false,
     };
 
     let dedup = autosint_engine::graph::dedup::EntityDedup::new(&graph, &config, None);
     // "United States" should fuzzy-match "United States of America".
     let result = dedup
-        .find_duplicate("United States", "country", None)
+        .find_duplicate("United States", "country", &Default::default(), None)
         .await
         .unwrap();
 
@@ -916,11 +1004,21 @@ async fn test_dedup_no_match() {
     let config = autosint_common::config::DedupConfig {
         fuzzy_threshold: 0.85,
         embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities: Default::default(),
+        merge_exact_duplicate_claims: false,
     };
 
     let dedup = autosint_engine::graph::dedup::EntityDedup::new(&graph, &config, None);
     let result = dedup
-        .find_duplicate("Completely Unrelated Name XYZ", "thing", None)
+        .find_duplicate(
+            "Completely Unrelated Name XYZ",
+            "thing",
+            &Default::default(),
+            None,
+        )
         .await
         .unwrap();
 
@@ -954,3 +1052,725 @@ async fn test_embedding_pending_flag() {
     assert!(!created2.embedding_pending);
     assert!(created2.embedding.is_some());
 }
+
+// -----------------------------------------------------------------------
+// 22. Snapshot export/import round-trip
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_snapshot_round_trip() {
+    let graph = setup().await;
+    let fixture = FixtureBuilder::new(7)
+        .entities(20)
+        .relationships(15)
+        .claims(10)
+        .with_embeddings()
+        .build();
+    fixture.load(&graph).await;
+
+    let mut first = Vec::new();
+    let stats = graph.export_snapshot(&mut first).await.unwrap();
+    assert_eq!(stats.entities, 20);
+    assert_eq!(stats.relationships, 15);
+    assert_eq!(stats.claims, 10);
+
+    // Restore into a clean database and export again — byte-identical.
+    let graph = setup().await;
+    let restored = graph.import_snapshot(first.as_slice()).await.unwrap();
+    assert_eq!(restored, stats);
+
+    let mut second = Vec::new();
+    graph.export_snapshot(&mut second).await.unwrap();
+    assert_eq!(
+        String::from_utf8(first).unwrap(),
+        String::from_utf8(second).unwrap()
+    );
+
+    let entity = &fixture.entities[3];
+    let fetched = graph.get_entity(entity.id).await.unwrap();
+    assert_eq!(fetched.canonical_name, entity.canonical_name);
+    assert_eq!(fetched.last_updated, entity.last_updated);
+}
+
+// -----------------------------------------------------------------------
+// 23. Dedup against a seeded fixture
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_dedup_against_fixture() {
+    let graph = setup().await;
+    let fixture = FixtureBuilder::new(42)
+        .entities(100)
+        .load_cached(&graph)
+        .await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+
+    let config = autosint_common::config::DedupConfig {
+        fuzzy_threshold: 0.85,
+        embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities: Default::default(),
+        merge_exact_duplicate_claims: false,
+    };
+    let dedup = autosint_engine::graph::dedup::EntityDedup::new(&graph, &config, None);
+
+    // Every fixture name resolves to its own entity.
+    for entity in fixture.entities.iter().step_by(10) {
+        match dedup
+            .find_duplicate(
+                &entity.canonical_name,
+                &entity.kind,
+                &Default::default(),
+                None,
+            )
+            .await
+            .unwrap()
+        {
+            autosint_engine::graph::DedupResult::ExactMatch(found) => assert_eq!(found, entity.id),
+            _ => panic!("Expected exact match for {}", entity.canonical_name),
+        }
+    }
+
+    let result = dedup
+        .find_duplicate(
+            "Completely Unrelated Name XYZ",
+            "thing",
+            &Default::default(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        result,
+        autosint_engine::graph::DedupResult::NoMatch
+    ));
+}
+
+// -----------------------------------------------------------------------
+// 24. Search ranking over a 500-entity fixture
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_search_ranking_large_fixture() {
+    let graph = setup().await;
+    let fixture = FixtureBuilder::new(2024)
+        .entities(500)
+        .with_embeddings()
+        .load_cached(&graph)
+        .await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+
+    let target = &fixture.entities[123];
+    let target_embedding = target.embedding.clone().unwrap();
+
+    // Semantic: the exact vector ranks first, and the rest of the top ten are
+    // the closest same-kind entities.
+    let results = graph
+        .search_entities(
+            &EntitySearchParams {
+                query: String::new(),
+                mode: SearchMode::Semantic,
+                kind_filter: None,
+                updated_after: None,
+                updated_before: None,
+                created_before: None,
+                limit: Some(10),
+                cursor: None,
+            },
+            Some(target_embedding.clone()),
+        )
+        .await
+        .unwrap()
+        .results;
+    assert_eq!(results.len(), 10);
+    assert_eq!(results[0].item.id, target.id);
+    assert!(results.iter().all(|r| r.item.kind == target.kind));
+
+    let mut expected: Vec<_> = fixture
+        .entities
+        .iter()
+        .map(|e| {
+            let score = common::cosine(e.embedding.as_ref().unwrap(), &target_embedding);
+            (e.id, score)
+        })
+        .collect();
+    expected.sort_by(|a, b| b.1.total_cmp(&a.1));
+    assert_eq!(results[1].item.id, expected[1].0);
+
+    // Keyword: an exact name ranks its entity first.
+    let results = graph
+        .search_entities(
+            &EntitySearchParams {
+                query: target.canonical_name.clone(),
+                mode: SearchMode::Keyword,
+                kind_filter: None,
+                updated_after: None,
+                updated_before: None,
+                created_before: None,
+                limit: Some(10),
+                cursor: None,
+            },
+            None,
+        )
+        .await
+        .unwrap()
+        .results;
+    assert_eq!(results[0].item.canonical_name, target.canonical_name);
+}
+
+// -----------------------------------------------------------------------
+// 25. Cursor pagination over claims
+// -----------------------------------------------------------------------
+
+/// Follow `next_cursor` from the first page to the last, returning every
+/// page's claim IDs in order.
+async fn collect_claim_pages(
+    graph: &GraphClient,
+    mut params: ClaimSearchParams,
+) -> Vec<Vec<ClaimId>> {
+    let mut pages = Vec::new();
+    loop {
+        let page = graph.search_claims(&params, None).await.unwrap();
+        pages.push(page.results.iter().map(|r| r.item.id).collect());
+        match page.next_cursor {
+            Some(cursor) => {
+                // Cursors travel as opaque strings through the tools.
+                params.cursor = Some(SearchCursor::decode(&cursor.encode()).unwrap());
+            }
+            None => return pages,
+        }
+        assert!(pages.len() <= 10, "pagination did not terminate");
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_claim_search_cursor_pagination() {
+    let graph = setup().await;
+
+    let source = graph
+        .create_entity(
+            &Entity::new("Lloyd's List".into(), "publication".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let mut expected = HashSet::new();
+    for i in 0..50 {
+        // Identical wording, so keyword scores tie and only the ID orders them.
+        let claim = Claim::new(
+            format!("The tanker was reflagged to a new registry (filing {}).", i),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            source.id,
+        );
+        expected.insert(graph.create_claim(&claim, None).await.unwrap().id);
+    }
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+
+    let params = |query: Option<&str>| ClaimSearchParams {
+        query: query.map(String::from),
+        mode: query.map(|_| SearchMode::Keyword),
+        published_after: None,
+        published_before: None,
+        source_entity_id: Some(source.id),
+        referenced_entity_id: None,
+        attribution_depth: None,
+        information_type: None,
+        language: None,
+        region: None,
+        min_trust: None,
+        ingested_before: None,
+        limit: Some(10),
+        cursor: None,
+    };
+
+    for query in [None, Some("reflagged tanker registry")] {
+        let pages = collect_claim_pages(&graph, params(query)).await;
+        let ids: Vec<ClaimId> = pages.iter().flatten().copied().collect();
+        let unique: HashSet<ClaimId> = ids.iter().copied().collect();
+
+        assert!(pages.iter().take(5).all(|p| p.len() == 10), "{:?}", query);
+        assert_eq!(ids.len(), 50, "duplicates across pages for {:?}", query);
+        assert_eq!(unique, expected, "gaps across pages for {:?}", query);
+    }
+
+    // A filter-only cursor can't continue a keyword search.
+    let first = graph.search_claims(&params(None), None).await.unwrap();
+    let mut keyword = params(Some("reflagged"));
+    keyword.cursor = first.next_cursor;
+    assert!(graph.search_claims(&keyword, None).await.is_err());
+}
+
+// -----------------------------------------------------------------------
+// 26. Concurrent writes stay within the write governor's bound
+// -----------------------------------------------------------------------
+
+/// Fire `count` concurrent create_claim calls from `source`, each referencing
+/// `referenced`. Every `missing_source_every`th claim (if set) names a source
+/// entity that doesn't exist. Returns each claim with its result, in order.
+async fn create_claims_concurrently(
+    graph: &GraphClient,
+    source: EntityId,
+    referenced: EntityId,
+    count: usize,
+    missing_source_every: Option<usize>,
+) -> Vec<(Claim, Result<Claim, GraphError>)> {
+    let handles: Vec<_> = (0..count)
+        .map(|i| {
+            let source = match missing_source_every {
+                Some(n) if i % n == 0 => EntityId::new(),
+                _ => source,
+            };
+            let mut claim = Claim::new(
+                format!("Vessel {} discharged cargo at Fujairah anchorage.", i),
+                Utc::now(),
+                AttributionDepth::Secondhand,
+                InformationType::Assertion,
+                source,
+            );
+            claim.referenced_entity_ids = vec![referenced];
+            claim.raw_source_link = (i % 2 == 0).then(|| format!("https://example.com/{}", i));
+
+            let graph = graph.clone();
+            tokio::spawn(async move {
+                let result = graph.create_claim(&claim, None).await;
+                (claim, result)
+            })
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for handle in handles {
+        results.push(handle.await.unwrap());
+    }
+    results
+}
+
+fn assert_claim_created(expected: &Claim, created: &Claim) {
+    assert_eq!(created.id, expected.id);
+    assert_eq!(created.content, expected.content);
+    assert_eq!(created.source_entity_id, expected.source_entity_id);
+    assert_eq!(
+        created.referenced_entity_ids,
+        expected.referenced_entity_ids
+    );
+    assert_eq!(created.raw_source_link, expected.raw_source_link);
+}
+
+async fn claim_count(graph: &GraphClient) -> i64 {
+    let mut result = graph
+        .inner()
+        .execute(query("MATCH (c:Claim) RETURN count(c) AS n"))
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_concurrent_claim_writes_within_bound() {
+    let graph = setup().await.with_write_config(&GraphWriteConfig {
+        max_concurrent_writes: 3,
+        coalesce: WriteCoalesceConfig::default(),
+        ..Default::default()
+    });
+
+    let source = graph
+        .create_entity(
+            &Entity::new("TankerTrackers".into(), "organization".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let port = graph
+        .create_entity(&Entity::new("Fujairah".into(), "location".into()), None)
+        .await
+        .unwrap();
+
+    let results = create_claims_concurrently(&graph, source.id, port.id, 200, None).await;
+
+    for (claim, result) in &results {
+        assert_claim_created(claim, result.as_ref().unwrap());
+    }
+    assert_eq!(claim_count(&graph).await, 200);
+
+    let stats = graph.write_stats();
+    assert!(stats.peak_in_flight <= 3, "{:?}", stats);
+    assert_eq!(stats.transactions, 202, "one write per create");
+}
+
+// -----------------------------------------------------------------------
+// 27. Coalesced creates: per-call results and fallback for failed rows
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_coalesced_creates() {
+    let graph = setup().await.with_write_config(&GraphWriteConfig {
+        max_concurrent_writes: 2,
+        coalesce: WriteCoalesceConfig {
+            enabled: true,
+            window_ms: 20,
+            max_items: 50,
+        },
+        ..Default::default()
+    });
+
+    let source = graph
+        .create_entity(
+            &Entity::new("TankerTrackers".into(), "organization".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let port = graph
+        .create_entity(&Entity::new("Fujairah".into(), "location".into()), None)
+        .await
+        .unwrap();
+
+    // Every 20th claim has a missing source: its row drops out of the batch
+    // and the individual fallback reports it.
+    let results = create_claims_concurrently(&graph, source.id, port.id, 200, Some(20)).await;
+
+    let mut missing = 0;
+    for (claim, result) in &results {
+        if claim.source_entity_id == source.id {
+            assert_claim_created(claim, result.as_ref().unwrap());
+        } else {
+            missing += 1;
+            assert!(
+                matches!(result, Err(GraphError::NotFound(_))),
+                "{:?}",
+                result
+            );
+        }
+    }
+    assert_eq!(missing, 10);
+    assert_eq!(claim_count(&graph).await, 190);
+
+    let stats = graph.write_stats();
+    assert!(stats.peak_in_flight <= 2, "{:?}", stats);
+    assert!(
+        stats.transactions < 100,
+        "creates should share batched writes: {:?}",
+        stats
+    );
+
+    // Relationships and entities coalesce the same way.
+    let handles: Vec<_> = (0..20)
+        .map(|i| {
+            let graph = graph.clone();
+            let target = if i == 0 { EntityId::new() } else { port.id };
+            tokio::spawn(async move {
+                let vessel = graph
+                    .create_entity(&Entity::new(format!("Vessel {}", i), "vessel".into()), None)
+                    .await
+                    .unwrap();
+                let mut relationship =
+                    Relationship::new(vessel.id, target, "Called at port".into());
+                relationship.weight = Some(0.5);
+                let created = graph.create_relationship(&relationship, None, false).await;
+                (vessel, relationship, created)
+            })
+        })
+        .collect();
+    for (i, handle) in handles.into_iter().enumerate() {
+        let (vessel, relationship, created) = handle.await.unwrap();
+        assert_eq!(vessel.canonical_name, format!("Vessel {}", i));
+        if i == 0 {
+            assert!(created.is_err());
+        } else {
+            let created = created.unwrap();
+            assert_eq!(created.id, relationship.id);
+            assert_eq!(created.source_entity_id, vessel.id);
+            assert_eq!(created.target_entity_id, port.id);
+            assert_eq!(created.weight, Some(0.5));
+        }
+    }
+}
+
+// -----------------------------------------------------------------------
+// 28. Claim language and region: round trip, filters, legacy claims, stats
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_claim_language_and_region() {
+    let graph = setup().await;
+
+    let source = graph
+        .create_entity(
+            &Entity::new("Ukrainska Pravda".into(), "publication".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let claim = |content: &str, language: Option<&str>, region: Option<&str>| {
+        let mut claim = Claim::new(
+            content.into(),
+            Utc::now(),
+            AttributionDepth::Secondhand,
+            InformationType::Assertion,
+            source.id,
+        );
+        claim.language = language.map(String::from);
+        claim.region = region.map(String::from);
+        claim
+    };
+
+    let ukrainian = graph
+        .create_claim(
+            &claim("Grain corridor reopened.", Some("uk"), Some("UA")),
+            None,
+        )
+        .await
+        .unwrap();
+    let russian = graph
+        .create_claim(
+            &claim("Grain corridor closed.", Some("ru"), Some("UA")),
+            None,
+        )
+        .await
+        .unwrap();
+    // With coalescing on, creates go through the batched UNWIND write.
+    let coalesced = graph.clone().with_write_config(&GraphWriteConfig {
+        max_concurrent_writes: 2,
+        coalesce: WriteCoalesceConfig {
+            enabled: true,
+            window_ms: 20,
+            max_items: 50,
+        },
+        ..Default::default()
+    });
+    let batched = coalesced
+        .create_claim(&claim("Grain exports rose.", Some("uk"), Some("PL")), None)
+        .await
+        .unwrap()
+        .id;
+    // Written before language and region were recorded.
+    graph
+        .inner()
+        .run(
+            query(
+                "MATCH (s:Entity {id: $source}) \
+                 CREATE (s)-[:PUBLISHED]->(:Claim { \
+                    id: $id, content: 'Grain corridor disputed.', \
+                    published_timestamp: $now, ingested_timestamp: $now, \
+                    attribution_depth: 'secondhand', information_type: 'assertion', \
+                    embedding_pending: false })",
+            )
+            .param("source", source.id.to_string())
+            .param("id", ClaimId::new().to_string())
+            .param("now", Utc::now().to_rfc3339()),
+        )
+        .await
+        .unwrap();
+
+    // Round trip, including through the batch path.
+    let fetched = graph.get_claim(ukrainian.id).await.unwrap();
+    assert_eq!(fetched.language.as_deref(), Some("uk"));
+    assert_eq!(fetched.region.as_deref(), Some("UA"));
+    let fetched = graph.get_claim(batched).await.unwrap();
+    assert_eq!(fetched.language.as_deref(), Some("uk"));
+    assert_eq!(fetched.region.as_deref(), Some("PL"));
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+
+    let search =
+        |query: Option<&str>, language: Option<&str>, region: Option<&str>| ClaimSearchParams {
+            query: query.map(String::from),
+            mode: query.map(|_| SearchMode::Keyword),
+            published_after: None,
+            published_before: None,
+            source_entity_id: None,
+            referenced_entity_id: None,
+            attribution_depth: None,
+            information_type: None,
+            language: language.map(String::from),
+            region: region.map(String::from),
+            min_trust: None,
+            ingested_before: None,
+            limit: None,
+            cursor: None,
+        };
+    let ids = |params: ClaimSearchParams| {
+        let graph = graph.clone();
+        async move {
+            let page = graph.search_claims(&params, None).await.unwrap();
+            page.results
+                .iter()
+                .map(|r| r.item.id)
+                .collect::<HashSet<ClaimId>>()
+        }
+    };
+
+    // Legacy claims still load and still match unfiltered searches.
+    assert_eq!(ids(search(None, None, None)).await.len(), 4);
+    assert_eq!(ids(search(Some("corridor"), None, None)).await.len(), 3);
+
+    assert_eq!(
+        ids(search(None, Some("uk"), None)).await,
+        HashSet::from([ukrainian.id, batched])
+    );
+    assert_eq!(
+        ids(search(None, None, Some("UA"))).await,
+        HashSet::from([ukrainian.id, russian.id])
+    );
+    assert_eq!(
+        ids(search(Some("corridor"), Some("ru"), Some("UA"))).await,
+        HashSet::from([russian.id])
+    );
+    assert!(ids(search(Some("corridor"), Some("uk"), Some("PL")))
+        .await
+        .is_empty());
+
+    let stats = graph.stats().await.unwrap();
+    assert_eq!(stats.claims, 4);
+    assert_eq!(
+        stats.claims_by_language,
+        [
+            ("ru".to_string(), 1),
+            ("uk".to_string(), 2),
+            ("unknown".to_string(), 1)
+        ]
+        .into()
+    );
+}
+
+// -----------------------------------------------------------------------
+// 29. Freeform properties: typed round trip, removal, legacy backfill
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_entity_property_types_and_removal() {
+    let graph = setup().await;
+
+    let mut entity = Entity::new("Crius".into(), "vessel".into());
+    entity.properties = [
+        ("sanctioned", json!("true")),
+        ("imo", json!("9187631")),
+        ("flag_history", json!("[\"PA\", \"GA\"]")),
+        ("tonnage", json!(159_000)),
+        ("active", json!(true)),
+        ("owner", json!({"name": "Gulf Star", "since": 2021})),
+        ("notes", json!(null)),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+    // Single and batched creates.
+    let single = graph.create_entity(&entity, None).await.unwrap();
+    assert_eq!(single.properties, entity.properties);
+    let mut other = Entity::new("Crius II".into(), "vessel".into());
+    other.properties = entity.properties.clone();
+    let batching = graph.clone().with_write_config(&GraphWriteConfig {
+        max_concurrent_writes: 2,
+        coalesce: WriteCoalesceConfig {
+            enabled: true,
+            window_ms: 20,
+            max_items: 50,
+        },
+        ..Default::default()
+    });
+    let batched = batching.create_entity(&other, None).await.unwrap();
+    assert_eq!(batched.properties, other.properties);
+
+    let update = EntityUpdate {
+        canonical_name: None,
+        aliases: None,
+        kind: None,
+        summary: None,
+        is_stub: None,
+        properties: Some(
+            [("active".to_string(), json!("false"))]
+                .into_iter()
+                .collect(),
+        ),
+        remove_properties: vec!["sanctioned".into(), "owner".into(), "absent".into()],
+        identifiers: Default::default(),
+    };
+    let updated = graph.update_entity(single.id, &update, None).await.unwrap();
+    assert_eq!(updated.properties["active"], json!("false"));
+    assert!(!updated.properties.contains_key("sanctioned"));
+    assert!(!updated.properties.contains_key("owner"));
+    assert_eq!(updated.properties["tonnage"], json!(159_000));
+
+    // Removal leaves no tag behind.
+    let mut result = graph
+        .inner()
+        .execute(
+            query(
+                "MATCH (e:Entity {id: $id}) \
+                 RETURN [k IN keys(e) WHERE k ENDS WITH 'sanctioned'] AS keys",
+            )
+            .param("id", single.id.to_string()),
+        )
+        .await
+        .unwrap();
+    let keys: Vec<String> = result.next().await.unwrap().unwrap().get("keys").unwrap();
+    assert!(keys.is_empty());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_backfill_legacy_property_types() {
+    let graph = setup().await;
+
+    // Written the pre-tag way: everything a string, JSON-looking values
+    // meant as their JSON type.
+    let id = EntityId::new();
+    graph
+        .inner()
+        .run(
+            query(
+                "CREATE (e:Entity {id: $id, canonical_name: 'Legacy', aliases: '[]', \
+                 aliases_text: '', kind: 'vessel', is_stub: false, \
+                 last_updated: $now, created_at: $now, embedding_pending: true, \
+                 is_seeded: false, prop_active: 'true', prop_tonnage: '159000', \
+                 prop_owner: '{\"name\":\"Gulf Star\"}', prop_name: 'Crius', prop_blank: ''})",
+            )
+            .param("id", id.to_string())
+            .param("now", Utc::now().to_rfc3339()),
+        )
+        .await
+        .unwrap();
+
+    let expected = [
+        ("active", json!(true)),
+        ("tonnage", json!(159000)),
+        ("owner", json!({"name": "Gulf Star"})),
+        ("name", json!("Crius")),
+        ("blank", json!("")),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+
+    // Read correctly before and after the backfill, which is idempotent.
+    assert_eq!(graph.get_entity(id).await.unwrap().properties, expected);
+    assert_eq!(graph.backfill_property_types().await.unwrap(), 1);
+    assert_eq!(graph.backfill_property_types().await.unwrap(), 0);
+    assert_eq!(graph.get_entity(id).await.unwrap().properties, expected);
+
+    // Once tagged, a string that looks like JSON stays a string.
+    let update = EntityUpdate {
+        canonical_name: None,
+        aliases: None,
+        kind: None,
+        summary: None,
+        is_stub: None,
+        properties: Some([("name".to_string(), json!("null"))].into_iter().collect()),
+        remove_properties: Vec::new(),
+        identifiers: Default::default(),
+    };
+    let updated = graph.update_entity(id, &update, None).await.unwrap();
+    assert_eq!(updated.properties["name"], json!("null"));
+    assert_eq!(updated.properties["active"], json!(true));
+}
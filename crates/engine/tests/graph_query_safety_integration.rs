@@ -0,0 +1,303 @@
+//! Integration tests passing hostile strings through the graph's search,
+//! traversal, dedup and change-feed parameters.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Every query must run without a Cypher or Lucene syntax error and return
+//! nothing, rather than erroring or matching everything. None of the terms
+//! below occur in the fixture's names or claim text.
+mod common;
+
+use std::time::Duration;
+
+use autosint_common::config::DedupConfig;
+use chrono::{TimeZone, Utc};
+use neo4rs::query;
+
+use autosint_engine::graph::dedup::EntityDedup;
+use autosint_engine::graph::{
+    ChangeScope, ClaimSearchParams, DedupResult, EntitySearchParams, GraphClient, SearchMode,
+    TraversalDirection, TraversalParams,
+};
+
+use common::{Fixture, FixtureBuilder};
+
+/// Upper bound for index population after loading the fixture.
+const INDEX_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Lucene syntax, Cypher fragments and parameter-looking strings.
+const HOSTILE: &[&str] = &[
+    "",
+    "   ",
+    "*",
+    "?",
+    "\\",
+    "*:*",
+    "name:*",
+    "canonical_name:*",
+    "AND",
+    "OR NOT",
+    "&& || !",
+    "((((",
+    "))",
+    "[* TO *]",
+    "{* TO *}",
+    "~",
+    "^2",
+    "/.*/",
+    "\"",
+    "'",
+    "' OR '1'='1",
+    "\" OR \"\"=\"",
+    "}) MATCH (x) DETACH DELETE x //",
+    "x` RETURN 1 //",
+    "$limit",
+    "x\nRETURN 1",
+    "\u{0}",
+];
+
+async fn setup() -> (GraphClient, Fixture) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let mut fixture = FixtureBuilder::new(2463)
+        .entities(20)
+        .relationships(30)
+        .claims(40)
+        .build();
+    for rel in &mut fixture.relationships {
+        rel.weight = Some(0.5);
+    }
+    fixture.load(&graph).await;
+    graph.await_indexes(INDEX_TIMEOUT).await.unwrap();
+    (graph, fixture)
+}
+
+async fn count(graph: &GraphClient, cypher: &str) -> i64 {
+    let mut result = graph.inner().execute(query(cypher)).await.unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+/// Nothing was deleted or rewritten by a hostile string.
+async fn assert_graph_intact(graph: &GraphClient, fixture: &Fixture) {
+    assert_eq!(
+        count(graph, "MATCH (e:Entity) RETURN count(e) AS n").await,
+        fixture.entities.len() as i64
+    );
+    assert_eq!(
+        count(graph, "MATCH (c:Claim) RETURN count(c) AS n").await,
+        fixture.claims.len() as i64
+    );
+    assert_eq!(
+        count(graph, "MATCH ()-[r:RELATES_TO]->() RETURN count(r) AS n").await,
+        fixture.relationships.len() as i64
+    );
+}
+
+fn entity_search(query: &str, kind_filter: Option<&str>) -> EntitySearchParams {
+    EntitySearchParams {
+        query: query.to_string(),
+        mode: SearchMode::Keyword,
+        kind_filter: kind_filter.map(String::from),
+        updated_after: None,
+        updated_before: None,
+        created_before: None,
+        limit: Some(100),
+        cursor: None,
+    }
+}
+
+fn claim_search(query: Option<&str>) -> ClaimSearchParams {
+    ClaimSearchParams {
+        query: query.map(String::from),
+        mode: query.map(|_| SearchMode::Keyword),
+        published_after: None,
+        published_before: None,
+        source_entity_id: None,
+        referenced_entity_id: None,
+        attribution_depth: None,
+        information_type: None,
+        language: None,
+        region: None,
+        min_trust: None,
+        ingested_before: None,
+        limit: Some(100),
+        cursor: None,
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_hostile_search_parameters() {
+    let (graph, fixture) = setup().await;
+
+    // Sanity: a real term does match, so empty results below mean something.
+    let page = graph
+        .search_entities(
+            &entity_search(&fixture.entities[0].canonical_name, None),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(!page.results.is_empty());
+
+    for hostile in HOSTILE {
+        let page = graph
+            .search_entities(&entity_search(hostile, None), None)
+            .await
+            .unwrap_or_else(|e| panic!("entity query {:?}: {}", hostile, e));
+        assert!(
+            page.results.is_empty(),
+            "entity query {:?} matched",
+            hostile
+        );
+
+        let page = graph
+            .search_entities(
+                &entity_search(&fixture.entities[0].canonical_name, Some(hostile)),
+                None,
+            )
+            .await
+            .unwrap_or_else(|e| panic!("entity kind {:?}: {}", hostile, e));
+        assert!(page.results.is_empty(), "entity kind {:?} matched", hostile);
+
+        let page = graph
+            .search_claims(&claim_search(Some(hostile)), None)
+            .await
+            .unwrap_or_else(|e| panic!("claim query {:?}: {}", hostile, e));
+        assert!(page.results.is_empty(), "claim query {:?} matched", hostile);
+
+        for (field, params) in [
+            (
+                "language",
+                ClaimSearchParams {
+                    language: Some(hostile.to_string()),
+                    ..claim_search(None)
+                },
+            ),
+            (
+                "region",
+                ClaimSearchParams {
+                    region: Some(hostile.to_string()),
+                    ..claim_search(Some("activity"))
+                },
+            ),
+        ] {
+            let page = graph
+                .search_claims(&params, None)
+                .await
+                .unwrap_or_else(|e| panic!("claim {} {:?}: {}", field, hostile, e));
+            assert!(
+                page.results.is_empty(),
+                "claim {} {:?} matched",
+                field,
+                hostile
+            );
+        }
+    }
+
+    assert_graph_intact(&graph, &fixture).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_hostile_dedup_and_change_scopes() {
+    let (graph, fixture) = setup().await;
+    let config = DedupConfig {
+        fuzzy_threshold: 0.85,
+        embedding_threshold: 0.90,
+        seeded_geo_fuzzy_threshold: 0.80,
+        relationship_description_threshold: 0.95,
+        duplicate_relationships: Default::default(),
+        fresh_entities: Default::default(),
+        merge_exact_duplicate_claims: false,
+    };
+    let dedup = EntityDedup::new(&graph, &config, None);
+    let epoch = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+
+    for hostile in HOSTILE {
+        let result = dedup
+            .find_duplicate(hostile, "person", &Default::default(), None)
+            .await
+            .unwrap_or_else(|e| panic!("dedup {:?}: {}", hostile, e));
+        assert!(
+            matches!(result, DedupResult::NoMatch),
+            "dedup {:?} matched",
+            hostile
+        );
+
+        let changes = graph
+            .changes_since(epoch, &ChangeScope::Focus(vec![hostile.to_string()]), 100)
+            .await
+            .unwrap_or_else(|e| panic!("focus {:?}: {}", hostile, e));
+        assert!(
+            changes.entities.is_empty() && changes.claims.is_empty(),
+            "focus {:?} matched",
+            hostile
+        );
+    }
+
+    assert_graph_intact(&graph, &fixture).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_hostile_traversal_weights() {
+    let (graph, fixture) = setup().await;
+    let hub = fixture.relationships[0].source_entity_id;
+
+    for min_weight in [f64::NAN, f64::INFINITY, f64::MAX, 2.0] {
+        for direction in [
+            TraversalDirection::Outgoing,
+            TraversalDirection::Incoming,
+            TraversalDirection::Both,
+        ] {
+            let params = TraversalParams {
+                direction: Some(direction),
+                min_weight: Some(min_weight),
+                as_of: None,
+                limit: Some(100),
+            };
+            let neighbors = graph
+                .traverse_relationships(hub, &params)
+                .await
+                .unwrap_or_else(|e| panic!("min_weight {}: {}", min_weight, e));
+            assert!(
+                neighbors.results.is_empty(),
+                "min_weight {} matched",
+                min_weight
+            );
+        }
+    }
+
+    // A floor below every weight keeps every edge.
+    for min_weight in [f64::NEG_INFINITY, -1.0, 0.5] {
+        let params = TraversalParams {
+            direction: Some(TraversalDirection::Both),
+            min_weight: Some(min_weight),
+            as_of: None,
+            limit: Some(100),
+        };
+        assert!(!graph
+            .traverse_relationships(hub, &params)
+            .await
+            .unwrap()
+            .results
+            .is_empty());
+    }
+
+    assert_graph_intact(&graph, &fixture).await;
+}
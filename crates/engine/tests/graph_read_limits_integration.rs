@@ -0,0 +1,300 @@
+//! Integration tests for the `graph_reads` ceilings and the sentinel-row
+//! truncation accounting on oversized result sets.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Oversized fixtures are written with one UNWIND each rather than through
+//! the create methods, which would take minutes at these sizes.
+use std::time::{Duration, Instant};
+
+use chrono::{Duration as ChronoDuration, TimeZone, Utc};
+use neo4rs::query;
+
+use autosint_common::config::GraphReadConfig;
+use autosint_common::types::Entity;
+use autosint_common::EntityId;
+use autosint_engine::graph::{ClaimSearchParams, GraphClient, TraversalDirection, TraversalParams};
+
+/// A filter-only search over the seeded claims must stay well under this once
+/// warm; a plan sorting every claim before the limit takes seconds.
+const INDEXED_READ_BOUND: Duration = Duration::from_millis(500);
+
+async fn setup(reads: GraphReadConfig) -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j")
+        .with_read_config(&reads);
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+    graph
+}
+
+/// `hub` related to `n` new entities, all pointing out from it.
+async fn seed_relationships(graph: &GraphClient, hub: EntityId, n: usize) {
+    let ids: Vec<String> = (0..n).map(|_| EntityId::new().to_string()).collect();
+    let now = Utc::now().to_rfc3339();
+    graph
+        .inner()
+        .run(
+            query(
+                "MATCH (hub:Entity {id: $hub}) \
+                 UNWIND range(0, size($ids) - 1) AS i \
+                 CREATE (other:Entity {id: $ids[i], canonical_name: 'Counterparty ' + i, \
+                                       kind: 'organization', last_updated: $now, created_at: $now}) \
+                 CREATE (hub)-[:RELATES_TO {id: randomUUID(), description: 'trades with', \
+                                            created_at: $now}]->(other)",
+            )
+            .param("hub", hub.to_string())
+            .param("ids", ids)
+            .param("now", now),
+        )
+        .await
+        .expect("Failed to seed relationships");
+}
+
+/// `n` claims published by `source`, one a minute back from 2026-01-01, so
+/// the newest is claim 0.
+async fn seed_claims(graph: &GraphClient, source: EntityId, n: usize) -> Vec<String> {
+    let newest = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let ids: Vec<String> = (0..n).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+    let times: Vec<String> = (0..n)
+        .map(|i| (newest - ChronoDuration::minutes(i as i64)).to_rfc3339())
+        .collect();
+    graph
+        .inner()
+        .run(
+            query(
+                "MATCH (source:Entity {id: $source}) \
+                 UNWIND range(0, size($ids) - 1) AS i \
+                 CREATE (source)-[:PUBLISHED]->(:Claim {id: $ids[i], \
+                     content: 'Shipment ' + i + ' left port.', \
+                     published_timestamp: $times[i], ingested_timestamp: $times[i], \
+                     attribution_depth: 'secondhand', information_type: 'assertion', \
+                     embedding_pending: false})",
+            )
+            .param("source", source.to_string())
+            .param("ids", ids.clone())
+            .param("times", times),
+        )
+        .await
+        .expect("Failed to seed claims");
+    ids
+}
+
+fn filter_params(source: Option<EntityId>, limit: u32) -> ClaimSearchParams {
+    ClaimSearchParams {
+        query: None,
+        mode: None,
+        published_after: None,
+        published_before: None,
+        source_entity_id: source,
+        referenced_entity_id: None,
+        attribution_depth: None,
+        information_type: None,
+        language: None,
+        region: None,
+        min_trust: None,
+        ingested_before: None,
+        limit: Some(limit),
+        cursor: None,
+    }
+}
+
+fn traversal(limit: u32) -> TraversalParams {
+    TraversalParams {
+        direction: Some(TraversalDirection::Outgoing),
+        min_weight: None,
+        as_of: None,
+        limit: Some(limit),
+    }
+}
+
+// -----------------------------------------------------------------------
+// 1. Traversal over a hub entity
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_traversal_clamped_to_ceiling_with_sentinel() {
+    let graph = setup(GraphReadConfig {
+        max_search_results: 200,
+        max_traversal_results: 500,
+    })
+    .await;
+    let hub = graph
+        .create_entity(
+            &Entity::new("Meridian Tankers".into(), "organization".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    seed_relationships(&graph, hub.id, 5_000).await;
+
+    // Far over the ceiling: clamped, and the sentinel says more matched.
+    let page = graph
+        .traverse_relationships(hub.id, &traversal(5_000))
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 500);
+    assert_eq!(page.size.rows, 500);
+    assert_eq!(page.size.limit, 500);
+    assert_eq!(page.size.clamped_from, Some(5_000));
+    assert!(page.size.truncated);
+    assert!(page.size.approx_bytes >= 500 * "trades with".len());
+
+    // Under the ceiling and under the match count.
+    let page = graph
+        .traverse_relationships(hub.id, &traversal(50))
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 50);
+    assert_eq!(page.size.clamped_from, None);
+    assert!(page.size.truncated);
+
+    // No limit: the default page.
+    let unlimited = TraversalParams {
+        limit: None,
+        ..traversal(0)
+    };
+    let page = graph
+        .traverse_relationships(hub.id, &unlimited)
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 100);
+    assert!(page.size.truncated);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_traversal_exact_fit_is_not_truncated() {
+    let graph = setup(GraphReadConfig::default()).await;
+    let hub = graph
+        .create_entity(
+            &Entity::new("Gulf Star Shipping".into(), "organization".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    seed_relationships(&graph, hub.id, 40).await;
+
+    let page = graph
+        .traverse_relationships(hub.id, &traversal(40))
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 40);
+    assert!(!page.size.truncated);
+
+    let page = graph
+        .traverse_relationships(hub.id, &traversal(39))
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 39);
+    assert!(page.size.truncated);
+}
+
+// -----------------------------------------------------------------------
+// 2. Filter-only claim search over many claims
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_filter_only_claim_search_clamped_and_index_backed() {
+    let graph = setup(GraphReadConfig {
+        max_search_results: 200,
+        max_traversal_results: 500,
+    })
+    .await;
+    let source = graph
+        .create_entity(
+            &Entity::new("Lloyd's List".into(), "publication".into()),
+            None,
+        )
+        .await
+        .unwrap();
+    let ids = seed_claims(&graph, source.id, 10_000).await;
+
+    // Newest first, one page, with more behind it.
+    let page = graph
+        .search_claims(&filter_params(None, 20), None)
+        .await
+        .unwrap();
+    let returned: Vec<String> = page.results.iter().map(|r| r.item.id.to_string()).collect();
+    assert_eq!(returned, ids[..20]);
+    assert!(page.size.truncated);
+    assert!(page.next_cursor.is_some());
+    assert!(page
+        .results
+        .iter()
+        .all(|r| r.item.source_entity_id == source.id));
+
+    // Over the ceiling: clamped.
+    let page = graph
+        .search_claims(&filter_params(None, 5_000), None)
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 200);
+    assert_eq!(page.size.clamped_from, Some(5_000));
+    assert!(page.size.truncated);
+
+    // Latency stays flat in the number of claims, anchored or not.
+    for source_filter in [None, Some(source.id)] {
+        let params = filter_params(source_filter, 20);
+        graph.search_claims(&params, None).await.unwrap();
+        let start = Instant::now();
+        let page = graph.search_claims(&params, None).await.unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(page.results.len(), 20);
+        assert!(
+            elapsed < INDEXED_READ_BOUND,
+            "filter-only search took {:?} (source filter {:?})",
+            elapsed,
+            source_filter
+        );
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_claim_search_last_page_has_no_cursor() {
+    let graph = setup(GraphReadConfig::default()).await;
+    let source = graph
+        .create_entity(&Entity::new("Reuters".into(), "publication".into()), None)
+        .await
+        .unwrap();
+    let ids = seed_claims(&graph, source.id, 30).await;
+
+    // Exactly a page's worth: the sentinel doesn't come back.
+    let page = graph
+        .search_claims(&filter_params(Some(source.id), 30), None)
+        .await
+        .unwrap();
+    assert_eq!(page.results.len(), 30);
+    assert!(!page.size.truncated);
+    assert_eq!(page.next_cursor, None);
+
+    // Paging by 20 ends after the second page.
+    let mut params = filter_params(Some(source.id), 20);
+    let first = graph.search_claims(&params, None).await.unwrap();
+    assert!(first.size.truncated);
+    params.cursor = first.next_cursor;
+    let second = graph.search_claims(&params, None).await.unwrap();
+    assert_eq!(second.results.len(), 10);
+    assert_eq!(second.next_cursor, None);
+    let returned: Vec<String> = first
+        .results
+        .iter()
+        .chain(&second.results)
+        .map(|r| r.item.id.to_string())
+        .collect();
+    assert_eq!(returned, ids);
+}
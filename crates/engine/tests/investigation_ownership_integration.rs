@@ -0,0 +1,195 @@
+//! Integration tests for investigation ownership leases: at most one task
+//! drives an investigation at a time.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Redis.
+//!
+//! Each test uses fresh investigation IDs, so no cleanup is needed.
+use std::sync::Arc;
+use std::time::Duration;
+
+use autosint_common::InvestigationId;
+use autosint_engine::queue::ownership::{token_purpose, InvestigationLease, DRIVER, FORCE_FAIL};
+use autosint_engine::queue::QueueClient;
+
+async fn setup() -> Arc<QueueClient> {
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".into());
+    let queue = QueueClient::connect(&redis_url)
+        .await
+        .expect("Failed to connect to Redis");
+    Arc::new(queue)
+}
+
+// -----------------------------------------------------------------------
+// 1. Concurrent drivers: exactly one wins
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_concurrent_drivers_exactly_one_wins() {
+    let queue = setup().await;
+    let ttl = Duration::from_secs(30);
+
+    for _ in 0..20 {
+        let id = InvestigationId::new();
+        // Startup recovery and an operator resume racing, plus stragglers.
+        let attempts: Vec<_> = (0..8)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                tokio::spawn(
+                    async move { InvestigationLease::acquire(queue, id, DRIVER, ttl).await },
+                )
+            })
+            .collect();
+        let mut leases = Vec::new();
+        for attempt in attempts {
+            if let Some(lease) = attempt.await.unwrap().unwrap() {
+                leases.push(lease);
+            }
+        }
+        assert_eq!(leases.len(), 1, "exactly one driver owns the investigation");
+
+        let owner = queue.investigation_owner(id).await.unwrap().unwrap();
+        assert_eq!(token_purpose(&owner), DRIVER);
+
+        // Once the winner finishes, the next driver can take over.
+        leases.pop().unwrap().release().await;
+        assert_eq!(queue.investigation_owner(id).await.unwrap(), None);
+        let next = InvestigationLease::acquire(Arc::clone(&queue), id, FORCE_FAIL, ttl)
+            .await
+            .unwrap()
+            .expect("released investigation can be owned again");
+        next.release().await;
+    }
+}
+
+// -----------------------------------------------------------------------
+// 2. Tokens: only the owner renews or releases
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_only_owner_renews_and_releases() {
+    let queue = setup().await;
+    let id = InvestigationId::new();
+    let ttl = Duration::from_secs(30);
+
+    assert!(queue
+        .acquire_investigation(id, "driver:first", ttl)
+        .await
+        .unwrap());
+    assert!(!queue
+        .acquire_investigation(id, "driver:second", ttl)
+        .await
+        .unwrap());
+
+    assert!(!queue
+        .renew_investigation(id, "driver:second", ttl)
+        .await
+        .unwrap());
+    assert!(!queue
+        .release_investigation(id, "driver:second")
+        .await
+        .unwrap());
+    assert_eq!(
+        queue.investigation_owner(id).await.unwrap().as_deref(),
+        Some("driver:first")
+    );
+
+    assert!(queue
+        .renew_investigation(id, "driver:first", ttl)
+        .await
+        .unwrap());
+    assert!(queue
+        .release_investigation(id, "driver:first")
+        .await
+        .unwrap());
+    assert!(!queue
+        .renew_investigation(id, "driver:first", ttl)
+        .await
+        .unwrap());
+}
+
+// -----------------------------------------------------------------------
+// 3. Expiry: renewal outlives the TTL; a crashed owner's lease lapses
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_renewal_and_expiry() {
+    let queue = setup().await;
+    let ttl = Duration::from_millis(600);
+
+    // A live driver keeps its lease well past the TTL.
+    let id = InvestigationId::new();
+    let lease = InvestigationLease::acquire(Arc::clone(&queue), id, DRIVER, ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    tokio::time::sleep(ttl * 3).await;
+    assert!(!lease.is_lost());
+    assert!(
+        InvestigationLease::acquire(Arc::clone(&queue), id, DRIVER, ttl)
+            .await
+            .unwrap()
+            .is_none(),
+        "renewed lease still excludes other drivers"
+    );
+    lease.release().await;
+
+    // A driver that dies without releasing stops renewing; its lease lapses
+    // after the TTL and another driver takes over.
+    let id = InvestigationId::new();
+    let crashed = InvestigationLease::acquire(Arc::clone(&queue), id, DRIVER, ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    drop(crashed);
+    assert!(queue.investigation_owner(id).await.unwrap().is_some());
+    tokio::time::sleep(ttl + Duration::from_millis(200)).await;
+    let successor = InvestigationLease::acquire(Arc::clone(&queue), id, DRIVER, ttl)
+        .await
+        .unwrap()
+        .expect("lapsed lease can be taken over");
+    successor.release().await;
+}
+
+// -----------------------------------------------------------------------
+// 4. Lost lease: a driver whose key was taken over notices
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_lease_lost_to_another_owner() {
+    let queue = setup().await;
+    let id = InvestigationId::new();
+    let ttl = Duration::from_millis(600);
+
+    let lease = InvestigationLease::acquire(Arc::clone(&queue), id, DRIVER, ttl)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Simulate the key lapsing during a Redis outage and another task
+    // taking the investigation over.
+    let mut conn = queue.connection();
+    redis::cmd("SET")
+        .arg(format!("investigation:{}:owner", id))
+        .arg("driver:other")
+        .query_async::<()>(&mut conn)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(ttl).await;
+    assert!(lease.is_lost());
+
+    // Releasing the lost lease leaves the new owner alone.
+    lease.release().await;
+    assert_eq!(
+        queue.investigation_owner(id).await.unwrap().as_deref(),
+        Some("driver:other")
+    );
+    queue
+        .release_investigation(id, "driver:other")
+        .await
+        .unwrap();
+}
@@ -0,0 +1,243 @@
+//! Integration tests for the outbound footprint and the fetch budget.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j and Redis.
+//!
+//! The Fetch service is a mock axum server on a random port. Each test uses
+//! a fresh investigation ID, so budgets never overlap.
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+
+use autosint_common::api::fetch::{
+    FetchMetadata, FetchRequest, FetchResponse, SearchResponse, SearchResult, SourceQueryMetadata,
+    SourceQueryResponse, SourceQueryResult,
+};
+use autosint_common::types::{FetchBudget, WEB_SEARCH_PROVIDER};
+use autosint_common::InvestigationId;
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::GraphClient;
+use autosint_engine::queue::fetch_budget::FetchBudgetTracker;
+use autosint_engine::queue::QueueClient;
+use autosint_engine::tools::handlers::register_processor_tools;
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+/// Content the mock Fetch service returns for every page.
+const PAGE: &str = "Registry extract for Example Shipping Ltd.";
+
+async fn setup() -> (Arc<GraphClient>, Arc<QueueClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".into());
+    let queue = QueueClient::connect(&redis_url)
+        .await
+        .expect("Failed to connect to Redis");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), Arc::new(queue), engine_config)
+}
+
+/// Mock Fetch service. URLs containing `cached` are served from cache and
+/// URLs on `throttled.example` are rate limited.
+async fn mock_fetch_service() -> String {
+    async fn fetch(Json(req): Json<FetchRequest>) -> Result<Json<FetchResponse>, StatusCode> {
+        if req.url.contains("throttled.example") {
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+        Ok(Json(FetchResponse {
+            content: PAGE.into(),
+            tables: Vec::new(),
+            metadata: FetchMetadata {
+                status_code: 200,
+                content_type: Some("text/html".into()),
+                cached: req.url.contains("cached"),
+                url: req.url,
+                rendered: false,
+                detected_language: None,
+                authenticated: false,
+                cache_ttl: None,
+                title: None,
+                fetched_at: None,
+            },
+        }))
+    }
+
+    async fn search(Json(req): Json<Value>) -> Json<SearchResponse> {
+        Json(SearchResponse {
+            query: req["query"].as_str().unwrap_or_default().into(),
+            results: vec![SearchResult {
+                url: "https://registry.example.org/ship".into(),
+                title: "Example Shipping Ltd".into(),
+                snippet: String::new(),
+            }],
+        })
+    }
+
+    async fn source_query(Path(source_id): Path<String>) -> Json<SourceQueryResponse> {
+        Json(SourceQueryResponse {
+            results: vec![SourceQueryResult {
+                content: PAGE.into(),
+                url: None,
+                title: None,
+                extra: None,
+            }],
+            metadata: SourceQueryMetadata {
+                source_id,
+                total_results: 1,
+                returned_results: 1,
+            },
+        })
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new()
+        .route("/fetch", post(fetch))
+        .route("/search", post(search))
+        .route("/sources/{id}/query", post(source_query));
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+async fn registry(budget: Option<FetchBudget>) -> ToolRegistry {
+    let (graph, queue, config) = setup().await;
+    let investigation_id = InvestigationId::new();
+    let mut registry = ToolRegistry::new(ToolHandlerContext::new(
+        SharedServices::new(
+            graph,
+            mock_fetch_service().await,
+            reqwest::Client::new(),
+            Arc::default(),
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        ),
+        SessionScope {
+            max_turns: 50,
+            investigation_id: Some(investigation_id),
+            fetch_budget: FetchBudgetTracker::new(queue, investigation_id, budget),
+            ..Default::default()
+        },
+    ));
+    register_processor_tools(&mut registry);
+    registry
+}
+
+async fn fetch(registry: &ToolRegistry, url: &str) -> Result<(), String> {
+    let result = registry.execute("fetch_url", json!({ "url": url })).await;
+    if result.is_error {
+        Err(result.content)
+    } else {
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------
+// 1. Accumulation across a session
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_footprint_accumulates_across_tools() {
+    let registry = registry(None).await;
+
+    fetch(&registry, "https://registry.example.org/company/1")
+        .await
+        .unwrap();
+    fetch(&registry, "https://registry.example.org/cached/2")
+        .await
+        .unwrap();
+    fetch(&registry, "https://news.example.com/story")
+        .await
+        .unwrap();
+    let throttled = fetch(&registry, "https://throttled.example/page").await;
+    assert!(throttled.unwrap_err().contains("429"));
+
+    for (tool, args) in [
+        ("web_search", json!({ "query": "Example Shipping Ltd" })),
+        ("web_search", json!({ "query": "Example Shipping owner" })),
+        (
+            "fetch_source_query",
+            json!({ "source_id": "opensanctions", "query": "Example Shipping" }),
+        ),
+    ] {
+        let result = registry.execute(tool, args).await;
+        assert!(!result.is_error, "{} failed: {}", tool, result.content);
+    }
+
+    let footprint = registry.counters().footprint();
+    assert_eq!(footprint.fetches, 3);
+    assert_eq!(footprint.cached_fetches, 1);
+    assert_eq!(footprint.fetches_by_domain["registry.example.org"], 2);
+    assert_eq!(footprint.fetches_by_domain["news.example.com"], 1);
+    assert!(!footprint
+        .fetches_by_domain
+        .contains_key("throttled.example"));
+    assert_eq!(footprint.bytes_downloaded, 2 * PAGE.len() as u64);
+    assert_eq!(footprint.rate_limited, 1);
+    assert_eq!(footprint.searches, 3);
+    assert_eq!(footprint.searches_by_provider[WEB_SEARCH_PROVIDER], 2);
+    assert_eq!(footprint.searches_by_provider["opensanctions"], 1);
+    assert_eq!(footprint.budget_rejections, 0);
+}
+
+// -----------------------------------------------------------------------
+// 2. Budget rejection
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_fetch_budget_rejects_over_limit() {
+    let registry = registry(Some(FetchBudget {
+        max_fetches: Some(3),
+        max_fetches_per_domain: Some(2),
+    }))
+    .await;
+
+    fetch(&registry, "https://registry.example.org/company/1")
+        .await
+        .unwrap();
+    fetch(&registry, "https://registry.example.org/company/2")
+        .await
+        .unwrap();
+
+    let per_domain = fetch(&registry, "https://registry.example.org/company/3")
+        .await
+        .unwrap_err();
+    assert!(
+        per_domain.starts_with("Fetch budget exhausted for registry.example.org"),
+        "{}",
+        per_domain
+    );
+
+    fetch(&registry, "https://news.example.com/story")
+        .await
+        .unwrap();
+    let total = fetch(&registry, "https://other.example.net/page")
+        .await
+        .unwrap_err();
+    assert!(total.starts_with("Fetch budget exhausted: "), "{}", total);
+
+    // Searches aren't limited by the fetch budget.
+    let result = registry
+        .execute("web_search", json!({ "query": "Example Shipping Ltd" }))
+        .await;
+    assert!(!result.is_error, "{}", result.content);
+
+    let footprint = registry.counters().footprint();
+    assert_eq!(footprint.fetches, 3);
+    assert_eq!(footprint.budget_rejections, 2);
+    assert_eq!(footprint.searches, 1);
+}
@@ -1,15 +1,17 @@
-///! Integration tests for Processor sessions.
-///! All tests are `#[ignore]` — run with `cargo test -- --ignored` against live services.
-///!
-///! Requirements: ANTHROPIC_API_KEY, running Neo4j, running Fetch service.
+//! Integration tests for Processor sessions.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against live services.
+//!
+//! Requirements: ANTHROPIC_API_KEY, running Neo4j, running Fetch service.
 use std::sync::Arc;
 
 use neo4rs::query;
 
-use autosint_common::config::{DedupConfig, ToolResultLimits};
+use autosint_common::types::WorkOrderEffort;
 use autosint_engine::config;
 use autosint_engine::graph::GraphClient;
+use autosint_engine::llm::configured_llm;
 use autosint_engine::processor::ProcessorSession;
+use autosint_engine::tools::{SessionScope, SharedServices};
 
 async fn setup() -> (Arc<GraphClient>, config::EngineConfig) {
     let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
@@ -29,7 +31,7 @@ async fn setup() -> (Arc<GraphClient>, config::EngineConfig) {
 
     // Initialize schema.
     client
-        .initialize_schema()
+        .initialize_schema(1536)
         .await
         .expect("Failed to initialize schema");
 
@@ -54,19 +56,29 @@ async fn test_processor_session_basic() {
         .prompts
         .get("processor")
         .expect("processor prompt not found")
+        .text
         .clone();
 
     let session = ProcessorSession::new(
-        &engine_config.system.llm.processor,
-        &engine_config.system.retry.llm_api,
+        &configured_llm(
+            engine_config.system.llm.processor.clone(),
+            engine_config.system.retry.llm_api.clone(),
+            Default::default(),
+        ),
         &engine_config.system.safety,
-        Arc::clone(&graph),
-        None, // No embedding client for basic test
-        fetch_base_url,
+        // No store (entity locking disabled), embedding client or entity linking.
+        SharedServices::new(
+            Arc::clone(&graph),
+            fetch_base_url,
+            reqwest::Client::new(),
+            Arc::new(engine_config.tool_schemas.clone()),
+            engine_config.system.tool_results.clone(),
+            engine_config.system.dedup.clone(),
+        ),
         system_prompt,
-        &engine_config.tool_schemas,
-        engine_config.system.tool_results.clone(),
-        engine_config.system.dedup.clone(),
+        SessionScope::default(),
+        WorkOrderEffort::Standard,
+        None, // No deadline
     )
     .expect("Failed to create ProcessorSession");
 
@@ -91,6 +103,12 @@ async fn test_processor_session_basic() {
                 stats.turns
             );
         }
+        autosint_engine::llm::session::SessionResult::TimeLimitReached { stats } => {
+            println!(
+                "Session hit time limit after {} turns — partial progress kept",
+                stats.turns
+            );
+        }
         autosint_engine::llm::session::SessionResult::Failed { error, .. } => {
             panic!("Session failed: {}", error);
         }
@@ -100,6 +118,18 @@ async fn test_processor_session_basic() {
                 stats.malformed_tool_calls
             );
         }
+        autosint_engine::llm::session::SessionResult::ToolLoopLimit { stats } => {
+            panic!(
+                "Session stuck in a tool call loop after {} nudges",
+                stats.loop_nudges
+            );
+        }
+        autosint_engine::llm::session::SessionResult::ContextLimitReached { stats } => {
+            println!(
+                "Session hit the context budget after {} turns — partial progress kept",
+                stats.turns
+            );
+        }
     }
 
     println!(
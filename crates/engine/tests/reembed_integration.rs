@@ -0,0 +1,178 @@
+//! Integration tests for re-embedding the graph with a new model.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults).
+//! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`
+//! and restores the 1536-dimension vector indexes the other suites expect.
+//! A local mock serves `/embeddings`, returning 8-dimension vectors.
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::Utc;
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::config::{EmbeddingConfig, RetryConfig};
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_engine::embeddings::{reembed, EmbeddingClient};
+use autosint_engine::graph::GraphClient;
+
+const KEY_ENV: &str = "AUTOSINT_TEST_REEMBED_KEY";
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let client = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+
+    client
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+
+    restore_indexes(&client).await;
+    client
+}
+
+async fn restore_indexes(client: &GraphClient) {
+    client.drop_vector_indexes().await.unwrap();
+    client
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+}
+
+/// Serve `/embeddings` with 8-dimension vectors, ignoring the requested size.
+async fn mock_embeddings_server() -> String {
+    async fn embeddings(Json(body): Json<Value>) -> Json<Value> {
+        let inputs = body["input"].as_array().cloned().unwrap_or_default();
+        let data: Vec<Value> = inputs
+            .iter()
+            .enumerate()
+            .map(|(index, _)| {
+                let embedding: Vec<f32> = (0..8).map(|i| (i + 1) as f32).collect();
+                json!({"embedding": embedding, "index": index})
+            })
+            .collect();
+        Json(json!({"data": data, "usage": {"total_tokens": inputs.len()}}))
+    }
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new().route("/embeddings", post(embeddings));
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn embedding_client(base_url: String) -> EmbeddingClient {
+    std::env::set_var(KEY_ENV, "test");
+    let config = EmbeddingConfig {
+        provider: "openai".into(),
+        model: "mock-embed".into(),
+        dimensions: 8,
+        target_dimensions: Some(4),
+        batch_size: 2,
+        backfill_interval_minutes: 5,
+        base_url,
+        api_key_env: KEY_ENV.into(),
+        claim_chunks: Default::default(),
+    };
+    let retry = RetryConfig {
+        max_attempts: 1,
+        initial_backoff_ms: 10,
+        max_backoff_ms: 10,
+        backoff_multiplier: 1.0,
+        jitter: false,
+    };
+    EmbeddingClient::new(config, retry).unwrap()
+}
+
+async fn count(graph: &GraphClient, cypher: &str) -> i64 {
+    let mut result = graph.inner().execute(query(cypher)).await.unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+// -----------------------------------------------------------------------
+// 1. Swap
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_reembed_swaps_vectors_and_rebuilds_indexes() {
+    let graph = setup().await;
+    let client = embedding_client(mock_embeddings_server().await);
+
+    let old = Some(vec![0.5f32; 1536]);
+    let mut ids = Vec::new();
+    for name in ["Maersk", "Hapag-Lloyd", "CMA CGM"] {
+        let entity = Entity::new(name.into(), "organization".into());
+        ids.push(graph.create_entity(&entity, old.clone()).await.unwrap().id);
+    }
+    let claim = Claim::new(
+        "Maersk and Hapag-Lloyd formed the Gemini Cooperation.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        ids[0],
+    );
+    graph.create_claim(&claim, old.clone()).await.unwrap();
+    let rel = Relationship::new(ids[0], ids[1], "Shipping alliance partner".into());
+    graph.create_relationship(&rel, old, false).await.unwrap();
+
+    let report = reembed::reembed_graph(&graph, &client, 2).await.unwrap();
+    assert_eq!(report.entities, 3);
+    assert_eq!(report.claims, 1);
+    assert_eq!(report.relationships, 1);
+    assert_eq!(report.dimensions, 4);
+
+    // Every vector was replaced with a truncated, unit-length one.
+    let cypher = "MATCH (e:Entity) WHERE size(e.embedding) = 4 RETURN count(e) AS n";
+    assert_eq!(count(&graph, cypher).await, 3);
+    let cypher = "MATCH (c:Claim) WHERE size(c.embedding) = 4 RETURN count(c) AS n";
+    assert_eq!(count(&graph, cypher).await, 1);
+    let cypher = "MATCH ()-[r:RELATES_TO]->() WHERE size(r.embedding) = 4 RETURN count(r) AS n";
+    assert_eq!(count(&graph, cypher).await, 1);
+    let cypher = "MATCH (n) WHERE n.embedding_next IS NOT NULL RETURN count(n) AS n";
+    assert_eq!(count(&graph, cypher).await, 0);
+
+    let mut result = graph
+        .inner()
+        .execute(
+            query("MATCH (e:Entity {id: $id}) RETURN e.embedding AS embedding")
+                .param("id", ids[0].to_string()),
+        )
+        .await
+        .unwrap();
+    let embedding: Vec<f64> = result
+        .next()
+        .await
+        .unwrap()
+        .unwrap()
+        .get("embedding")
+        .unwrap();
+    let norm: f64 = embedding.iter().map(|x| x * x).sum::<f64>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-6, "norm {}", norm);
+
+    // Indexes now match the new model.
+    let dims = graph.vector_index_dimensions().await.unwrap();
+    assert_eq!(dims.len(), 3);
+    assert!(dims.iter().all(|(_, d)| *d == 4), "{:?}", dims);
+    assert!(graph.check_vector_dimensions(4).await.is_ok());
+    assert!(graph.check_vector_dimensions(1536).await.is_err());
+
+    // Leave the database the way the other suites expect it.
+    setup().await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_startup_rejects_mismatched_index() {
+    let graph = setup().await;
+    let err = graph.initialize_schema(768).await.unwrap_err();
+    assert!(err.to_string().contains("1536"), "{}", err);
+    assert!(err.to_string().contains("768"), "{}", err);
+}
@@ -0,0 +1,270 @@
+//! Integration tests for relationship direction correction, self-loop rejection,
+//! and duplicate-edge detection in create_relationship.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults).
+//! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`.
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::config::{DuplicateRelationshipMode, RelationshipWeightConfig};
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_common::EntityId;
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::GraphClient;
+use autosint_engine::tools::handlers::{register_analyst_tools, register_processor_tools};
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+async fn setup() -> (Arc<GraphClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), engine_config)
+}
+
+fn context(graph: &Arc<GraphClient>, config: &EngineConfig) -> ToolHandlerContext {
+    ToolHandlerContext::new(
+        SharedServices::new(
+            Arc::clone(graph),
+            "http://localhost:8081".into(),
+            reqwest::Client::new(),
+            Arc::default(),
+            config.system.tool_results.clone(),
+            config.system.dedup.clone(),
+        ),
+        SessionScope {
+            max_turns: 50,
+            ..Default::default()
+        },
+    )
+}
+
+fn processor_registry(graph: &Arc<GraphClient>, config: &EngineConfig) -> ToolRegistry {
+    let mut registry = ToolRegistry::new(context(graph, config));
+    register_processor_tools(&mut registry);
+    registry
+}
+
+async fn create_entity(graph: &GraphClient, name: &str) -> EntityId {
+    let entity = Entity::new(name.into(), "organization".into());
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+async fn edge_count(graph: &GraphClient) -> i64 {
+    let mut result = graph
+        .inner()
+        .execute(query("MATCH ()-[r:RELATES_TO]->() RETURN count(r) AS n"))
+        .await
+        .unwrap();
+    result.next().await.unwrap().unwrap().get("n").unwrap()
+}
+
+async fn create_via_tool(registry: &ToolRegistry, args: Value) -> Value {
+    let result = registry.execute("create_relationship", args).await;
+    assert!(
+        !result.is_error,
+        "create_relationship failed: {}",
+        result.content
+    );
+    serde_json::from_str(&result.content).unwrap()
+}
+
+// -----------------------------------------------------------------------
+// 1. Reversal
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_reverse_relationship_preserves_id_properties_and_support() {
+    let (graph, config) = setup().await;
+    let apple = create_entity(&graph, "Apple").await;
+    let tsmc = create_entity(&graph, "TSMC").await;
+
+    // Extracted backwards: TSMC is the supplier.
+    let mut rel = Relationship::new(apple, tsmc, "Supplies A-series chips to Apple.".into());
+    rel.weight = Some(0.8);
+    rel.confidence = Some(0.7);
+    rel.timestamp = Some(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap());
+    let rel = graph.create_relationship(&rel, None, false).await.unwrap();
+
+    let mut claim = Claim::new(
+        "TSMC manufactures Apple's A-series chips.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        tsmc,
+    );
+    claim.supports_relationship_ids = vec![rel.id];
+    graph.create_claim(&claim, None).await.unwrap();
+
+    let mut registry = ToolRegistry::new(context(&graph, &config));
+    register_analyst_tools(&mut registry);
+    let result = registry
+        .execute(
+            "reverse_relationship",
+            json!({"relationship_id": rel.id.to_string(), "reason": "TSMC is the supplier"}),
+        )
+        .await;
+    assert!(!result.is_error, "reverse failed: {}", result.content);
+
+    let reversed = graph.get_relationship(rel.id).await.unwrap();
+    assert_eq!(reversed.source_entity_id, tsmc);
+    assert_eq!(reversed.target_entity_id, apple);
+    assert_eq!(reversed.description, rel.description);
+    assert_eq!(reversed.weight, Some(0.8));
+    assert_eq!(reversed.confidence, Some(0.7));
+    assert_eq!(reversed.timestamp, rel.timestamp);
+    assert_eq!(edge_count(&graph).await, 1);
+
+    // The supporting claim still reaches the edge.
+    let changes = graph
+        .recalculate_relationship_weights(None, &RelationshipWeightConfig::default(), Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].relationship_id, rel.id);
+    assert_eq!(changes[0].claims, 1);
+
+    // Reversing twice restores the original direction.
+    let back = graph.reverse_relationship(rel.id).await.unwrap();
+    assert_eq!(back.source_entity_id, apple);
+    assert_eq!(edge_count(&graph).await, 1);
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_reverse_missing_relationship() {
+    let (graph, _) = setup().await;
+    let result = graph
+        .reverse_relationship(autosint_common::RelationshipId::new())
+        .await;
+    assert!(matches!(
+        result,
+        Err(autosint_engine::graph::GraphError::NotFound(_))
+    ));
+}
+
+// -----------------------------------------------------------------------
+// 2. Self-referential edges
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_self_loop_rejected_unless_allowed() {
+    let (graph, config) = setup().await;
+    let acme = create_entity(&graph, "Acme Holdings").await;
+
+    let rel = Relationship::new(acme, acme, "Owns itself.".into());
+    let err = graph
+        .create_relationship(&rel, None, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("same entity"), "{}", err);
+
+    let registry = processor_registry(&graph, &config);
+    let result = registry
+        .execute(
+            "create_relationship",
+            json!({
+                "source_entity_id": acme.to_string(),
+                "target_entity_id": acme.to_string(),
+                "description": "Subsidiary of",
+            }),
+        )
+        .await;
+    assert!(result.is_error);
+    assert!(result.content.contains("allow_self"), "{}", result.content);
+    assert_eq!(edge_count(&graph).await, 0);
+
+    create_via_tool(
+        &registry,
+        json!({
+            "source_entity_id": acme.to_string(),
+            "target_entity_id": acme.to_string(),
+            "description": "Holds treasury shares in itself",
+            "allow_self": true,
+        }),
+    )
+    .await;
+    assert_eq!(edge_count(&graph).await, 1);
+}
+
+// -----------------------------------------------------------------------
+// 3. Duplicate edges
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_duplicate_relationship_warns() {
+    let (graph, config) = setup().await;
+    let tsmc = create_entity(&graph, "TSMC").await;
+    let apple = create_entity(&graph, "Apple").await;
+    let registry = processor_registry(&graph, &config);
+
+    let args = |description: &str| {
+        json!({
+            "source_entity_id": tsmc.to_string(),
+            "target_entity_id": apple.to_string(),
+            "description": description,
+        })
+    };
+
+    let first = create_via_tool(&registry, args("TSMC supplies chips to Apple")).await;
+    assert!(first.get("warning").is_none());
+
+    let second = create_via_tool(&registry, args("TSMC supplies chips to Apple.")).await;
+    assert_eq!(second["possible_duplicate_of"], first["relationship_id"]);
+    assert_ne!(second["relationship_id"], first["relationship_id"]);
+    assert!(second["warning"].is_string());
+    assert_eq!(edge_count(&graph).await, 2);
+
+    // A different relationship between the same entities is not a duplicate.
+    let other = create_via_tool(&registry, args("Apple is TSMC's largest customer")).await;
+    assert!(other.get("possible_duplicate_of").is_none());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_duplicate_relationship_dedupes_when_configured() {
+    let (graph, mut config) = setup().await;
+    config.system.dedup.duplicate_relationships = DuplicateRelationshipMode::Dedupe;
+    let tsmc = create_entity(&graph, "TSMC").await;
+    let apple = create_entity(&graph, "Apple").await;
+    let registry = processor_registry(&graph, &config);
+
+    let args = |description: &str| {
+        json!({
+            "source_entity_id": tsmc.to_string(),
+            "target_entity_id": apple.to_string(),
+            "description": description,
+        })
+    };
+
+    let first = create_via_tool(&registry, args("TSMC supplies chips to Apple")).await;
+    let second = create_via_tool(&registry, args("tsmc supplies chips to apple")).await;
+    assert_eq!(second["relationship_id"], first["relationship_id"]);
+    assert_eq!(second["deduplicated"], json!(true));
+    assert_eq!(edge_count(&graph).await, 1);
+}
@@ -0,0 +1,194 @@
+//! Integration tests for relationship weight recalibration from supporting claims.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored` against a live Neo4j.
+//!
+//! Setup: Connect to Neo4j from env vars (or localhost defaults).
+//! Each test cleans all data before running via `MATCH (n) DETACH DELETE n`.
+
+use autosint_common::config::RelationshipWeightConfig;
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_common::{EntityId, RelationshipId};
+use chrono::Utc;
+use neo4rs::query;
+
+use autosint_engine::graph::GraphClient;
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let client = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+
+    client
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean database");
+
+    client
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    client
+}
+
+async fn entity(graph: &GraphClient, name: &str, kind: &str) -> EntityId {
+    graph
+        .create_entity(&Entity::new(name.into(), kind.into()), None)
+        .await
+        .unwrap()
+        .id
+}
+
+/// A relationship between two fresh entities with an LLM-assigned weight.
+async fn relationship(graph: &GraphClient) -> RelationshipId {
+    let a = entity(graph, "Company A", "organization").await;
+    let b = entity(graph, "Company B", "organization").await;
+    let mut rel = Relationship::new(a, b, "supplies components to".into());
+    rel.weight = Some(0.9);
+    graph
+        .create_relationship(&rel, None, false)
+        .await
+        .unwrap()
+        .id
+}
+
+async fn supporting_claim(graph: &GraphClient, source: EntityId, rel: RelationshipId) {
+    let mut claim = Claim::new(
+        "Company A supplies components to Company B.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        source,
+    );
+    claim.supports_relationship_ids = vec![rel];
+    graph.create_claim(&claim, None).await.unwrap();
+}
+
+// -----------------------------------------------------------------------
+// 1. Support from distinct sources
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_weight_rises_with_distinct_sources() {
+    let graph = setup().await;
+    let config = RelationshipWeightConfig::default();
+    let rel = relationship(&graph).await;
+
+    let first = entity(&graph, "Outlet One", "publication").await;
+    supporting_claim(&graph, first, rel).await;
+
+    let changes = graph
+        .recalculate_relationship_weights(None, &config, Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].previous_weight, Some(0.9));
+    assert_eq!(changes[0].sources, 1);
+    let single = graph.get_relationship(rel).await.unwrap();
+    let single_weight = single.weight.unwrap();
+    assert!(single_weight < 0.9);
+    assert_eq!(single.previous_weight, Some(0.9));
+    assert!(single.weight_recalibrated_at.is_some());
+
+    for name in ["Outlet Two", "Outlet Three"] {
+        let source = entity(&graph, name, "publication").await;
+        supporting_claim(&graph, source, rel).await;
+    }
+
+    graph
+        .recalculate_relationship_weights(None, &config, Utc::now())
+        .await
+        .unwrap();
+    let multi = graph.get_relationship(rel).await.unwrap();
+    assert!(multi.weight.unwrap() > single_weight);
+    assert!(multi.weight.unwrap() < 1.0);
+    assert_eq!(multi.previous_weight, Some(single_weight));
+}
+
+// -----------------------------------------------------------------------
+// 2. Repeated claims from one source
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_repeated_claims_from_one_source_do_not_raise_weight() {
+    let graph = setup().await;
+    let config = RelationshipWeightConfig::default();
+    let rel = relationship(&graph).await;
+    let source = entity(&graph, "Outlet One", "publication").await;
+
+    supporting_claim(&graph, source, rel).await;
+    graph
+        .recalculate_relationship_weights(None, &config, Utc::now())
+        .await
+        .unwrap();
+    let before = graph.get_relationship(rel).await.unwrap().weight.unwrap();
+
+    for _ in 0..5 {
+        supporting_claim(&graph, source, rel).await;
+    }
+    let changes = graph
+        .recalculate_relationship_weights(None, &config, Utc::now())
+        .await
+        .unwrap();
+
+    assert!(changes.is_empty());
+    let after = graph.get_relationship(rel).await.unwrap().weight.unwrap();
+    assert_eq!(after, before);
+}
+
+// -----------------------------------------------------------------------
+// 3. Linking claims after creation
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_add_relationship_support_and_scope() {
+    let graph = setup().await;
+    let config = RelationshipWeightConfig::default();
+    let rel = relationship(&graph).await;
+    let source = entity(&graph, "Outlet One", "publication").await;
+    let unrelated = entity(&graph, "Elsewhere", "country").await;
+
+    let claim = Claim::new(
+        "Company A supplies components to Company B.".into(),
+        Utc::now(),
+        AttributionDepth::Primary,
+        InformationType::Assertion,
+        source,
+    );
+    let claim = graph.create_claim(&claim, None).await.unwrap();
+
+    // Linking twice records the relationship once.
+    graph
+        .add_relationship_support(claim.id, &[rel])
+        .await
+        .unwrap();
+    graph
+        .add_relationship_support(claim.id, &[rel])
+        .await
+        .unwrap();
+    let fetched = graph.get_claim(claim.id).await.unwrap();
+    assert_eq!(fetched.supports_relationship_ids, vec![rel]);
+
+    assert_eq!(
+        graph
+            .existing_relationship_ids(&[rel, RelationshipId::new()])
+            .await
+            .unwrap(),
+        vec![rel]
+    );
+
+    // Scoped to an entity with no relationships: nothing recalculated.
+    let changes = graph
+        .recalculate_relationship_weights(Some(unrelated), &config, Utc::now())
+        .await
+        .unwrap();
+    assert!(changes.is_empty());
+    assert_eq!(graph.get_relationship(rel).await.unwrap().weight, Some(0.9));
+}
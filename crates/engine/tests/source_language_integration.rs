@@ -0,0 +1,154 @@
+//! Integration tests for claims written from a translation, which keep the
+//! source's own wording in `original_content`.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use std::sync::Arc;
+
+use neo4rs::query;
+use serde_json::{json, Value};
+
+use autosint_common::types::Entity;
+use autosint_common::{ClaimId, EntityId};
+use autosint_engine::config::{self, EngineConfig};
+use autosint_engine::graph::GraphClient;
+use autosint_engine::tools::handlers::{register_analyst_tools, register_processor_tools};
+use autosint_engine::tools::{SessionScope, SharedServices, ToolHandlerContext, ToolRegistry};
+
+const TRANSLATED: &str = "The tanker Crius was reflagged to Gabon.";
+const ORIGINAL: &str = "Танкер Криус сменил флаг на габонский.";
+
+async fn setup() -> (Arc<GraphClient>, EngineConfig) {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+
+    let config_dir = std::env::var("AUTOSINT_CONFIG_DIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|_| std::path::PathBuf::from("../../config"));
+    let engine_config = config::load_config(&config_dir).expect("Failed to load config");
+
+    (Arc::new(graph), engine_config)
+}
+
+fn context(graph: &Arc<GraphClient>, config: &EngineConfig) -> ToolHandlerContext {
+    let services = SharedServices::new(
+        Arc::clone(graph),
+        "http://localhost:8081".into(),
+        reqwest::Client::new(),
+        Arc::default(),
+        config.system.tool_results.clone(),
+        config.system.dedup.clone(),
+    );
+    ToolHandlerContext::new(
+        services,
+        SessionScope {
+            max_turns: 50,
+            ..Default::default()
+        },
+    )
+}
+
+/// A translated claim from a Russian-language source, written through the
+/// create_claim tool.
+async fn create_translated_claim(graph: &Arc<GraphClient>, config: &EngineConfig) -> ClaimId {
+    let source = Entity::new("Коммерсантъ".into(), "publication".into());
+    let source_id: EntityId = graph.create_entity(&source, None).await.unwrap().id;
+
+    let mut processor = ToolRegistry::new(context(graph, config));
+    register_processor_tools(&mut processor);
+    let result = processor
+        .execute(
+            "create_claim",
+            json!({
+                "content": TRANSLATED,
+                "source_entity_id": source_id.to_string(),
+                "published_timestamp": "2026-03-15T00:00:00Z",
+                "language": "ru",
+                "original_content": ORIGINAL,
+            }),
+        )
+        .await;
+    assert!(!result.is_error, "create_claim failed: {}", result.content);
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    ClaimId::from_uuid(body["claim_id"].as_str().unwrap().parse().unwrap())
+}
+
+async fn search(registry: &ToolRegistry, args: Value) -> Vec<Value> {
+    let result = registry.execute("search_claims", args).await;
+    assert!(!result.is_error, "search_claims failed: {}", result.content);
+    let body: Value = serde_json::from_str(&result.content).unwrap();
+    body["results"].as_array().unwrap().clone()
+}
+
+// -----------------------------------------------------------------------
+// 1. The original wording round-trips, and search returns it on request
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_original_content_round_trip_and_search_flag() {
+    let (graph, config) = setup().await;
+    let claim_id = create_translated_claim(&graph, &config).await;
+
+    let claim = graph.get_claim(claim_id).await.unwrap();
+    assert_eq!(claim.content, TRANSLATED);
+    assert_eq!(claim.original_content.as_deref(), Some(ORIGINAL));
+    // Defaults to the claim's language.
+    assert_eq!(claim.original_language.as_deref(), Some("ru"));
+
+    let mut analyst = ToolRegistry::new(context(&graph, &config));
+    register_analyst_tools(&mut analyst);
+
+    let results = search(&analyst, json!({"query": "Crius"})).await;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].get("original_content").is_none());
+
+    let results = search(
+        &analyst,
+        json!({"query": "Crius", "include_original": true}),
+    )
+    .await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["original_content"], ORIGINAL);
+    assert_eq!(results[0]["original_language"], "ru");
+}
+
+// -----------------------------------------------------------------------
+// 2. With the index option on, a search in the source's language finds it
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_keyword_search_in_original_language() {
+    let (graph, config) = setup().await;
+    create_translated_claim(&graph, &config).await;
+
+    let mut analyst = ToolRegistry::new(context(&graph, &config));
+    register_analyst_tools(&mut analyst);
+
+    graph.configure_claim_fulltext(false).await.unwrap();
+    assert!(search(&analyst, json!({"query": "танкер"}))
+        .await
+        .is_empty());
+
+    graph.configure_claim_fulltext(true).await.unwrap();
+    let results = search(&analyst, json!({"query": "танкер"})).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["content"], TRANSLATED);
+
+    // Leave the index as the other tests expect it.
+    graph.configure_claim_fulltext(false).await.unwrap();
+}
@@ -0,0 +1,115 @@
+//! Integration tests for subgraph export and STIX bundles.
+//! All tests are `#[ignore]` — run with `cargo test -- --ignored --test-threads=1`
+//! against live Neo4j.
+use chrono::Utc;
+use neo4rs::query;
+use serde_json::Value;
+
+use autosint_common::config::StixConfig;
+use autosint_common::types::{AttributionDepth, Claim, Entity, InformationType, Relationship};
+use autosint_common::EntityId;
+use autosint_engine::graph::GraphClient;
+use autosint_engine::stix;
+
+async fn setup() -> GraphClient {
+    let uri = std::env::var("NEO4J_URI").unwrap_or_else(|_| "bolt://localhost:7687".into());
+    let user = std::env::var("NEO4J_USER").unwrap_or_else(|_| "neo4j".into());
+    let password = std::env::var("NEO4J_PASSWORD").unwrap_or_else(|_| "autosint_dev".into());
+
+    let graph = GraphClient::connect(&uri, &user, &password)
+        .await
+        .expect("Failed to connect to Neo4j");
+    graph
+        .inner()
+        .run(query("MATCH (n) DETACH DELETE n"))
+        .await
+        .expect("Failed to clean Neo4j");
+    graph
+        .initialize_schema(1536)
+        .await
+        .expect("Failed to initialize schema");
+    graph
+}
+
+async fn create_entity(graph: &GraphClient, name: &str, kind: &str) -> EntityId {
+    let entity = Entity::new(name.into(), kind.into());
+    graph.create_entity(&entity, None).await.unwrap().id
+}
+
+async fn relate(graph: &GraphClient, source: EntityId, target: EntityId, description: &str) {
+    let rel = Relationship::new(source, target, description.into());
+    graph.create_relationship(&rel, None, false).await.unwrap();
+}
+
+fn object_ids(bundle: &Value) -> Vec<String> {
+    bundle["objects"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+// -----------------------------------------------------------------------
+// 1. Depth-bounded export
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_export_subgraph_respects_depth() {
+    let graph = setup().await;
+    let acme = create_entity(&graph, "Acme Corp", "organization").await;
+    let jane = create_entity(&graph, "Jane Doe", "person").await;
+    let berlin = create_entity(&graph, "Berlin", "city").await;
+    relate(&graph, acme, jane, "Jane Doe is CEO of Acme.").await;
+    relate(&graph, jane, berlin, "Jane Doe lives in Berlin.").await;
+
+    let shallow = graph.export_subgraph(&[acme], 1).await.unwrap();
+    assert_eq!(shallow.entities.len(), 2);
+    assert_eq!(shallow.relationships.len(), 1);
+
+    let deep = graph.export_subgraph(&[acme], 2).await.unwrap();
+    assert_eq!(deep.entities.len(), 3);
+    assert_eq!(deep.relationships.len(), 2);
+}
+
+// -----------------------------------------------------------------------
+// 2. Repeated exports produce the same STIX IDs
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+#[ignore]
+async fn test_stix_export_ids_stable_across_exports() {
+    let graph = setup().await;
+    let acme = create_entity(&graph, "Acme Corp", "organization").await;
+    let vessel = create_entity(&graph, "MV Example", "vessel").await;
+    let reuters = create_entity(&graph, "Reuters", "organization").await;
+    relate(&graph, acme, vessel, "Acme operates MV Example.").await;
+
+    let mut claim = Claim::new(
+        "Acme chartered MV Example in March.".into(),
+        Utc::now(),
+        AttributionDepth::Secondhand,
+        InformationType::Assertion,
+        reuters,
+    );
+    claim.raw_source_link = Some("https://example.com/acme".into());
+    claim.referenced_entity_ids = vec![acme, vessel];
+    graph.create_claim(&claim, None).await.unwrap();
+
+    let config = StixConfig::default();
+    let first = stix::to_bundle(
+        &graph.export_subgraph(&[acme], 1).await.unwrap(),
+        &config,
+        Utc::now(),
+    );
+    let second = stix::to_bundle(
+        &graph.export_subgraph(&[acme], 1).await.unwrap(),
+        &config,
+        Utc::now(),
+    );
+
+    // Acme, MV Example, Reuters (publisher), one relationship, one note.
+    assert_eq!(object_ids(&first).len(), 5);
+    assert_eq!(object_ids(&first), object_ids(&second));
+}
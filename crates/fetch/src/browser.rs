@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::sync::{Mutex, Semaphore};
+
+use autosint_common::http_client::extract_domain;
+
+use crate::fetch::FetchError;
+use crate::rate_limit::DomainRateLimiter;
+
+/// Headless browser rendering through a browserless sidecar's `/content` API.
+///
+/// Rendering is far more expensive than a plain fetch and far more visible to the
+/// target site, so it runs under its own limits: a lower per-domain rate, at most
+/// one render per domain at a time, and a hard timeout covering the whole render.
+pub struct BrowserBackend {
+    http: reqwest::Client,
+    base_url: String,
+    rate_limiter: DomainRateLimiter,
+    domain_slots: Mutex<HashMap<String, Arc<Semaphore>>>,
+    timeout: Duration,
+}
+
+impl BrowserBackend {
+    pub fn new(http: reqwest::Client, base_url: String, rate: f64, timeout: Duration) -> Self {
+        Self {
+            http,
+            base_url,
+            rate_limiter: DomainRateLimiter::new(rate),
+            domain_slots: Mutex::new(HashMap::new()),
+            timeout,
+        }
+    }
+
+    /// Render `url` and return (rendered HTML, page status code).
+    pub async fn render(
+        &self,
+        url: &str,
+        wait_for: Option<&str>,
+    ) -> Result<(String, u16), FetchError> {
+        let start = std::time::Instant::now();
+        let domain = extract_domain(url);
+
+        self.rate_limiter
+            .acquire(&domain, self.timeout)
+            .await
+            .map_err(|_| FetchError::RateLimited(domain.clone()))?;
+
+        let slot = {
+            let mut slots = self.domain_slots.lock().await;
+            Arc::clone(
+                slots
+                    .entry(domain.clone())
+                    .or_insert_with(|| Arc::new(Semaphore::new(1))),
+            )
+        };
+
+        let result = tokio::time::timeout(self.timeout, async {
+            let _permit = slot
+                .acquire_owned()
+                .await
+                .map_err(|e| FetchError::Browser(e.to_string()))?;
+            self.send(url, wait_for).await
+        })
+        .await
+        .map_err(|_| FetchError::Browser(format!("render timed out after {:?}", self.timeout)))?;
+
+        metrics::histogram!("fetch.browser.latency", "domain" => domain)
+            .record(start.elapsed().as_secs_f64());
+
+        result
+    }
+
+    async fn send(&self, url: &str, wait_for: Option<&str>) -> Result<(String, u16), FetchError> {
+        let endpoint = format!("{}/content", self.base_url.trim_end_matches('/'));
+
+        let response = self
+            .http
+            .post(&endpoint)
+            .json(&render_request(url, wait_for, self.timeout))
+            .send()
+            .await
+            .map_err(|e| FetchError::Browser(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(FetchError::Browser(format!(
+                "browser backend returned {}: {}",
+                status, body
+            )));
+        }
+
+        // browserless reports the target page's own status in a header.
+        let page_status = response
+            .headers()
+            .get("x-response-code")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(200);
+
+        let html = response
+            .text()
+            .await
+            .map_err(|e| FetchError::Browser(e.to_string()))?;
+
+        Ok((html, page_status))
+    }
+}
+
+/// Body for browserless `POST /content`. Waits for the selector when given,
+/// otherwise for network idle. Inner timeouts leave headroom under the hard
+/// timeout so browserless reports its own error first.
+fn render_request(url: &str, wait_for: Option<&str>, timeout: Duration) -> Value {
+    let inner_timeout_ms = (timeout.as_millis() as u64)
+        .saturating_sub(2_000)
+        .max(1_000);
+
+    let mut body = json!({
+        "url": url,
+        "gotoOptions": {
+            "waitUntil": if wait_for.is_some() { "domcontentloaded" } else { "networkidle2" },
+            "timeout": inner_timeout_ms,
+        },
+        "rejectResourceTypes": ["image", "media", "font"],
+    });
+    if let Some(selector) = wait_for {
+        body["waitForSelector"] = json!({
+            "selector": selector,
+            "timeout": inner_timeout_ms,
+        });
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    /// Mock browserless: records request bodies and returns fixed HTML.
+    async fn mock_browserless() -> (String, Arc<std::sync::Mutex<Vec<Value>>>) {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+        let app = Router::new().route(
+            "/content",
+            post(move |Json(body): Json<Value>| {
+                let recorded = Arc::clone(&recorded);
+                async move {
+                    recorded.lock().unwrap().push(body);
+                    let mut headers = HeaderMap::new();
+                    headers.insert("x-response-code", "203".parse().unwrap());
+                    (headers, "<html><body><p>Rendered content</p></body></html>")
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}", addr), requests)
+    }
+
+    #[test]
+    fn test_render_request_waits_for_network_idle_by_default() {
+        let body = render_request("https://example.com", None, Duration::from_secs(30));
+        assert_eq!(body["url"], "https://example.com");
+        assert_eq!(body["gotoOptions"]["waitUntil"], "networkidle2");
+        assert_eq!(body["gotoOptions"]["timeout"], 28_000);
+        assert!(body.get("waitForSelector").is_none());
+    }
+
+    #[test]
+    fn test_render_request_waits_for_selector() {
+        let body = render_request(
+            "https://example.com",
+            Some("#results"),
+            Duration::from_secs(30),
+        );
+        assert_eq!(body["gotoOptions"]["waitUntil"], "domcontentloaded");
+        assert_eq!(body["waitForSelector"]["selector"], "#results");
+    }
+
+    #[tokio::test]
+    async fn test_render_against_mock_browserless() {
+        let (base_url, requests) = mock_browserless().await;
+        let backend = BrowserBackend::new(
+            reqwest::Client::new(),
+            base_url,
+            10.0,
+            Duration::from_secs(10),
+        );
+
+        let (html, status) = backend
+            .render("https://spa.example.com/page", Some("main"))
+            .await
+            .unwrap();
+        assert!(html.contains("Rendered content"));
+        assert_eq!(status, 203);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["url"], "https://spa.example.com/page");
+        assert_eq!(requests[0]["waitForSelector"]["selector"], "main");
+    }
+
+    #[tokio::test]
+    async fn test_render_fails_when_backend_down() {
+        // Bind then drop to get a port with nothing listening.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let backend = BrowserBackend::new(
+            reqwest::Client::new(),
+            format!("http://{}", addr),
+            10.0,
+            Duration::from_secs(5),
+        );
+        let err = backend
+            .render("https://example.com", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FetchError::Browser(_)));
+    }
+}
@@ -1,64 +1,213 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-/// Simple in-memory URL cache with TTL-based expiration.
+use chrono::{DateTime, Utc};
+
+use autosint_common::api::fetch::{
+    CacheTtl, CacheVariant, ExtractMode, ExtractedTable, FetchOptions, RenderMode,
+};
+
+/// Headers that identify or trace a request without changing the response body.
+/// Ignored when fingerprinting so they don't fragment the cache.
+const NON_CONTENT_HEADERS: &[&str] = &[
+    "x-request-id",
+    "x-correlation-id",
+    "traceparent",
+    "tracestate",
+];
+
+/// Cache identity of a fetch: the normalized URL plus a fingerprint of the
+/// request options that shape the response body.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub url: String,
+    pub fingerprint: String,
+}
+
+impl CacheKey {
+    pub fn new(url: &str, options: &FetchOptions) -> Self {
+        Self {
+            url: normalize_url(url),
+            fingerprint: fingerprint(options),
+        }
+    }
+
+    /// Key a fetch made with a credential apart from unauthenticated ones and
+    /// from those made with any other credential.
+    pub fn with_credential(mut self, credential_fingerprint: Option<&str>) -> Self {
+        if let Some(credential) = credential_fingerprint {
+            if !self.fingerprint.is_empty() {
+                self.fingerprint.push('&');
+            }
+            self.fingerprint
+                .push_str(&format!("credential={}", credential));
+        }
+        self
+    }
+
+    /// Whether the key is for a fetch made with a credential.
+    pub fn authenticated(&self) -> bool {
+        self.fingerprint
+            .split('&')
+            .any(|part| part.starts_with("credential="))
+    }
+}
+
+/// Lowercase scheme and host, drop default ports and the fragment. Unparseable
+/// URLs are used as-is.
+pub fn normalize_url(url: &str) -> String {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            parsed.to_string()
+        }
+        Err(_) => url.trim().to_string(),
+    }
+}
+
+/// Canonical rendering of the body-affecting options. Defaults are omitted, so
+/// an explicit default and an absent option share an entry.
+fn fingerprint(options: &FetchOptions) -> String {
+    let mut parts = Vec::new();
+
+    if options.render == Some(RenderMode::Browser) {
+        parts.push("render=browser".to_string());
+        if let Some(selector) = &options.wait_for {
+            parts.push(format!("wait_for={}", selector));
+        }
+    }
+    if options.extract == Some(ExtractMode::Raw) {
+        parts.push("extract=raw".to_string());
+    }
+    if let Some(user_agent) = &options.user_agent {
+        parts.push(format!("user_agent={}", user_agent));
+    }
+    if let Some(headers) = &options.headers {
+        let mut headers: Vec<(String, &str)> = headers
+            .iter()
+            .map(|(k, v)| (k.to_lowercase(), v.as_str()))
+            .filter(|(k, _)| !NON_CONTENT_HEADERS.contains(&k.as_str()))
+            .collect();
+        headers.sort();
+        parts.extend(
+            headers
+                .into_iter()
+                .map(|(k, v)| format!("header.{}={}", k, v)),
+        );
+    }
+
+    parts.join("&")
+}
+
+/// Simple in-memory URL cache with per-entry TTLs (see
+/// [`CachePolicy`](crate::cache_policy::CachePolicy)). Each URL may hold
+/// several variants, one per distinct option fingerprint.
+#[derive(Default)]
 pub struct UrlCache {
-    entries: HashMap<String, CacheEntry>,
-    ttl: Duration,
+    entries: HashMap<String, HashMap<String, CacheEntry>>,
+}
+
+/// An extracted document as cached.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedDocument {
+    pub content: String,
+    pub tables: Vec<ExtractedTable>,
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    pub detected_language: Option<String>,
+    pub title: Option<String>,
+    /// When the document was fetched from the origin.
+    pub fetched_at: DateTime<Utc>,
+    /// How long the document is kept, and why.
+    pub cache_ttl: CacheTtl,
 }
 
 struct CacheEntry {
-    content: String,
-    status_code: u16,
-    content_type: Option<String>,
+    document: CachedDocument,
     inserted_at: Instant,
 }
 
+impl CacheEntry {
+    fn expired(&self) -> bool {
+        self.inserted_at.elapsed() >= Duration::from_secs(self.document.cache_ttl.seconds)
+    }
+}
+
 impl UrlCache {
-    pub fn new(ttl: Duration) -> Self {
-        Self {
-            entries: HashMap::new(),
-            ttl,
-        }
+    pub fn new() -> Self {
+        Self::default()
     }
 
     /// Get a cached response if it exists and hasn't expired.
-    pub fn get(&self, url: &str) -> Option<(String, u16, Option<String>)> {
-        if let Some(entry) = self.entries.get(url) {
-            if entry.inserted_at.elapsed() < self.ttl {
+    pub fn get(&self, key: &CacheKey) -> Option<CachedDocument> {
+        let entry = self
+            .entries
+            .get(&key.url)
+            .and_then(|variants| variants.get(&key.fingerprint));
+        if let Some(entry) = entry {
+            if !entry.expired() {
                 metrics::counter!("fetch.cache.hit").increment(1);
-                return Some((
-                    entry.content.clone(),
-                    entry.status_code,
-                    entry.content_type.clone(),
-                ));
+                return Some(entry.document.clone());
             }
         }
         metrics::counter!("fetch.cache.miss").increment(1);
         None
     }
 
-    /// Insert a response into the cache, evicting expired entries.
-    pub fn insert(
-        &mut self,
-        url: String,
-        content: String,
-        status_code: u16,
-        content_type: Option<String>,
-    ) {
+    /// Insert a response into the cache for its `cache_ttl`, evicting
+    /// expired entries. A zero TTL only evicts.
+    pub fn insert(&mut self, key: CacheKey, document: CachedDocument) {
         // Evict expired entries on insert.
-        self.entries
-            .retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+        self.entries.retain(|_, variants| {
+            variants.retain(|_, entry| !entry.expired());
+            !variants.is_empty()
+        });
+        if document.cache_ttl.seconds == 0 {
+            return;
+        }
 
-        self.entries.insert(
-            url,
+        self.entries.entry(key.url).or_default().insert(
+            key.fingerprint,
             CacheEntry {
-                content,
-                status_code,
-                content_type,
+                document,
                 inserted_at: Instant::now(),
             },
         );
+
+        metrics::gauge!("fetch.cache.bytes").set(self.total_bytes() as f64);
+    }
+
+    /// Unexpired variants held for `url`, largest first.
+    pub fn variants(&self, url: &str) -> Vec<CacheVariant> {
+        let mut variants: Vec<CacheVariant> = self
+            .entries
+            .get(&normalize_url(url))
+            .into_iter()
+            .flatten()
+            .filter(|(_, entry)| !entry.expired())
+            .map(|(fingerprint, entry)| CacheVariant {
+                fingerprint: fingerprint.clone(),
+                age_seconds: entry.inserted_at.elapsed().as_secs(),
+                size_bytes: entry.document.content.len(),
+                status_code: entry.document.status_code,
+                content_type: entry.document.content_type.clone(),
+            })
+            .collect();
+        variants.sort_by(|a, b| {
+            b.size_bytes
+                .cmp(&a.size_bytes)
+                .then_with(|| a.fingerprint.cmp(&b.fingerprint))
+        });
+        variants
+    }
+
+    /// Content bytes held across all URLs and variants.
+    pub fn total_bytes(&self) -> usize {
+        self.entries
+            .values()
+            .flat_map(|variants| variants.values())
+            .map(|entry| entry.document.content.len())
+            .sum()
     }
 }
 
@@ -66,32 +215,132 @@ impl UrlCache {
 mod tests {
     use super::*;
 
+    use autosint_common::api::fetch::CacheTtlSource;
+
+    fn key(url: &str) -> CacheKey {
+        CacheKey::new(url, &FetchOptions::default())
+    }
+
+    fn document(content: &str, content_type: Option<&str>) -> CachedDocument {
+        expiring_document(content, content_type, 3600)
+    }
+
+    fn expiring_document(
+        content: &str,
+        content_type: Option<&str>,
+        ttl_secs: u64,
+    ) -> CachedDocument {
+        CachedDocument {
+            content: content.into(),
+            tables: Vec::new(),
+            status_code: 200,
+            content_type: content_type.map(String::from),
+            detected_language: None,
+            title: None,
+            fetched_at: Utc::now(),
+            cache_ttl: CacheTtl {
+                seconds: ttl_secs,
+                source: CacheTtlSource::Default,
+            },
+        }
+    }
+
     #[test]
     fn test_cache_hit_miss() {
-        let mut cache = UrlCache::new(Duration::from_secs(3600));
-        assert!(cache.get("https://example.com").is_none());
+        let mut cache = UrlCache::new();
+        assert!(cache.get(&key("https://example.com")).is_none());
 
         cache.insert(
-            "https://example.com".into(),
-            "content".into(),
-            200,
-            Some("text/html".into()),
+            key("https://example.com"),
+            document("content", Some("text/html")),
         );
 
-        let hit = cache.get("https://example.com");
+        let hit = cache.get(&key("https://example.com"));
         assert!(hit.is_some());
-        let (content, status, ct) = hit.unwrap();
-        assert_eq!(content, "content");
-        assert_eq!(status, 200);
-        assert_eq!(ct.as_deref(), Some("text/html"));
+        let hit = hit.unwrap();
+        assert_eq!(hit.content, "content");
+        assert_eq!(hit.status_code, 200);
+        assert_eq!(hit.content_type.as_deref(), Some("text/html"));
     }
 
     #[test]
     fn test_cache_expiry() {
-        let mut cache = UrlCache::new(Duration::from_millis(1));
-        cache.insert("https://example.com".into(), "old".into(), 200, None);
+        let mut cache = UrlCache::new();
+        cache.insert(
+            key("https://example.com"),
+            expiring_document("old", None, 1),
+        );
+        cache.insert(key("https://example.org"), document("kept", None));
+        cache.insert(
+            key("https://example.net"),
+            expiring_document("never", None, 0),
+        );
+        assert!(cache.get(&key("https://example.com")).is_some());
+        assert!(cache.get(&key("https://example.net")).is_none());
+
+        std::thread::sleep(Duration::from_millis(1100));
+        assert!(cache.get(&key("https://example.com")).is_none());
+        assert_eq!(
+            cache.get(&key("https://example.org")).unwrap().content,
+            "kept"
+        );
+    }
+
+    #[test]
+    fn test_raw_and_readable_are_distinct_entries() {
+        let mut cache = UrlCache::new();
+        let url = "https://example.com/article";
+        let raw = CacheKey::new(
+            url,
+            &FetchOptions {
+                extract: Some(ExtractMode::Raw),
+                ..Default::default()
+            },
+        );
+        let readable = key(url);
+        assert_ne!(raw, readable);
+
+        cache.insert(raw.clone(), document("<html><p>Text</p></html>", None));
+        assert!(cache.get(&readable).is_none());
+
+        cache.insert(readable.clone(), document("Text", None));
+        assert_eq!(cache.get(&raw).unwrap().content, "<html><p>Text</p></html>");
+        assert_eq!(cache.get(&readable).unwrap().content, "Text");
+
+        let variants = cache.variants(url);
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].fingerprint, "extract=raw");
+        assert_eq!(variants[1].fingerprint, "");
+        assert_eq!(cache.total_bytes(), 24 + 4);
+    }
+
+    #[test]
+    fn test_irrelevant_options_share_entry() {
+        let with_request_id = CacheKey::new(
+            "https://Example.com/article#comments",
+            &FetchOptions {
+                timeout_ms: Some(5_000),
+                extract: Some(ExtractMode::Readable),
+                headers: Some(HashMap::from([(
+                    "X-Request-Id".to_string(),
+                    "abc123".to_string(),
+                )])),
+                ..Default::default()
+            },
+        );
+        assert_eq!(with_request_id, key("https://example.com/article"));
 
-        std::thread::sleep(Duration::from_millis(10));
-        assert!(cache.get("https://example.com").is_none());
+        // Body-affecting headers do split the cache, regardless of name case.
+        let lang = |name: &str| {
+            CacheKey::new(
+                "https://example.com/article",
+                &FetchOptions {
+                    headers: Some(HashMap::from([(name.to_string(), "de".to_string())])),
+                    ..Default::default()
+                },
+            )
+        };
+        assert_ne!(lang("Accept-Language"), key("https://example.com/article"));
+        assert_eq!(lang("Accept-Language"), lang("accept-language"));
     }
 }
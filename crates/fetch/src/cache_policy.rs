@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, CACHE_CONTROL, DATE, EXPIRES};
+use serde::Deserialize;
+
+use autosint_common::api::fetch::{CacheTtl, CacheTtlSource};
+
+/// Caching hints an origin sent with a response.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OriginCaching {
+    /// `Cache-Control: no-store`: the response must not be cached.
+    pub no_store: bool,
+    /// Freshness lifetime from `s-maxage`, `max-age` or `Expires`, in that
+    /// order. `no-cache` counts as zero, since the service can't revalidate.
+    pub max_age: Option<Duration>,
+}
+
+impl OriginCaching {
+    /// Read the hints from response headers. Malformed `max-age` and
+    /// `s-maxage` values are ignored; a malformed `Expires` means already
+    /// expired (RFC 9111 §5.3). `Expires` is measured from the `Date` header
+    /// when there is one, else from `now`.
+    pub fn from_headers(headers: &HeaderMap, now: DateTime<Utc>) -> Self {
+        let mut caching = Self::default();
+        let mut max_age = None;
+        let mut s_maxage = None;
+        let mut no_cache = false;
+
+        let directives = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','));
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name, Some(value.trim().trim_matches('"'))),
+                None => (directive, None),
+            };
+            let seconds = || value.and_then(|v| v.parse::<u64>().ok());
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => caching.no_store = true,
+                "no-cache" => no_cache = true,
+                "max-age" => max_age = max_age.or_else(seconds),
+                "s-maxage" => s_maxage = s_maxage.or_else(seconds),
+                _ => {}
+            }
+        }
+
+        caching.max_age = if no_cache {
+            Some(Duration::ZERO)
+        } else if let Some(seconds) = s_maxage.or(max_age) {
+            Some(Duration::from_secs(seconds))
+        } else {
+            headers.get(EXPIRES).map(|expires| {
+                let date = |value: &str| DateTime::parse_from_rfc2822(value.trim()).ok();
+                let origin_now = headers
+                    .get(DATE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(date)
+                    .map_or(now, |d| d.with_timezone(&Utc));
+                expires
+                    .to_str()
+                    .ok()
+                    .and_then(date)
+                    .and_then(|expires| (expires.with_timezone(&Utc) - origin_now).to_std().ok())
+                    .unwrap_or(Duration::ZERO)
+            })
+        };
+        caching
+    }
+}
+
+/// How long the cache keeps a fetch: the source's override if it has one,
+/// else the origin's hints clamped to `[min_ttl, max_ttl]`, else the default.
+/// An origin's `no-store` beats everything.
+pub struct CachePolicy {
+    pub default_ttl: Duration,
+    pub min_ttl: Duration,
+    pub max_ttl: Duration,
+    pub overrides: TtlOverrides,
+}
+
+impl CachePolicy {
+    /// The TTL to cache a fetch of `url` for; None when it must not be cached.
+    pub fn ttl_for(&self, url: &str, origin: &OriginCaching) -> Option<CacheTtl> {
+        if origin.no_store {
+            return None;
+        }
+        let (ttl, source) = if let Some(ttl) = self.overrides.for_url(url) {
+            (ttl, CacheTtlSource::SourceOverride)
+        } else if let Some(max_age) = origin.max_age {
+            (
+                max_age.clamp(self.min_ttl, self.max_ttl),
+                CacheTtlSource::Origin,
+            )
+        } else {
+            (self.default_ttl, CacheTtlSource::Default)
+        };
+        Some(CacheTtl {
+            seconds: ttl.as_secs(),
+            source,
+        })
+    }
+}
+
+/// Per-source cache TTLs for known-stable or known-volatile sources, loaded
+/// from the TOML file at FETCH_CACHE_OVERRIDES_PATH:
+///
+/// ```toml
+/// [[sources]]
+/// domain = "*.legislation.gov.uk"   # exact host, or "*.example.com" for subdomains
+/// ttl_secs = 1209600
+/// ```
+///
+/// `ttl_secs = 0` never caches the source. Overrides are not clamped.
+#[derive(Default)]
+pub struct TtlOverrides {
+    exact: HashMap<String, Duration>,
+    /// (suffix including the leading dot, TTL), longest suffix first.
+    wildcard: Vec<(String, Duration)>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CachePolicyError {
+    #[error("Failed to read cache overrides file {path}: {message}")]
+    Read { path: String, message: String },
+
+    #[error("Invalid cache overrides file: {0}")]
+    Parse(String),
+
+    #[error("Invalid cache override for {domain}: {message}")]
+    Invalid { domain: String, message: String },
+}
+
+#[derive(Deserialize)]
+struct OverridesFile {
+    #[serde(default)]
+    sources: Vec<OverrideEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OverrideEntry {
+    domain: String,
+    ttl_secs: u64,
+}
+
+impl TtlOverrides {
+    /// Load from FETCH_CACHE_OVERRIDES_PATH; empty when it is unset.
+    pub fn from_env() -> Result<Self, CachePolicyError> {
+        match std::env::var("FETCH_CACHE_OVERRIDES_PATH") {
+            Ok(path) if !path.is_empty() => Self::load(Path::new(&path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CachePolicyError> {
+        let text = std::fs::read_to_string(path).map_err(|e| CachePolicyError::Read {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, CachePolicyError> {
+        let file: OverridesFile =
+            toml::from_str(text).map_err(|e| CachePolicyError::Parse(e.to_string()))?;
+
+        let mut overrides = Self::default();
+        for entry in file.sources {
+            let domain = entry.domain.trim().to_lowercase();
+            let invalid = |message: &str| CachePolicyError::Invalid {
+                domain: domain.clone(),
+                message: message.into(),
+            };
+
+            let host = domain.strip_prefix("*.").unwrap_or(&domain);
+            if host.is_empty() || host.contains(['*', '/', ':']) {
+                return Err(invalid(
+                    "domain must be a host name, optionally prefixed with \"*.\"",
+                ));
+            }
+
+            let ttl = Duration::from_secs(entry.ttl_secs);
+            let duplicate = match domain.strip_prefix('*') {
+                Some(suffix) => {
+                    let suffix = suffix.to_string();
+                    let duplicate = overrides.wildcard.iter().any(|(s, _)| *s == suffix);
+                    overrides.wildcard.push((suffix, ttl));
+                    duplicate
+                }
+                None => overrides.exact.insert(domain.clone(), ttl).is_some(),
+            };
+            if duplicate {
+                return Err(invalid("configured more than once"));
+            }
+        }
+        overrides
+            .wildcard
+            .sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+        Ok(overrides)
+    }
+
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.wildcard.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The override for `url`'s host: an exact match first, then the most
+    /// specific wildcard.
+    pub fn for_url(&self, url: &str) -> Option<Duration> {
+        let parsed = reqwest::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_lowercase();
+        self.exact.get(&host).copied().or_else(|| {
+            self.wildcard
+                .iter()
+                .find(|(suffix, _)| host.ends_with(suffix.as_str()))
+                .map(|(_, ttl)| *ttl)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn parse(pairs: &[(&'static str, &str)]) -> OriginCaching {
+        let now = DateTime::parse_from_rfc2822("Thu, 15 Oct 2026 12:00:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        OriginCaching::from_headers(&headers(pairs), now)
+    }
+
+    fn secs(seconds: u64) -> Option<Duration> {
+        Some(Duration::from_secs(seconds))
+    }
+
+    fn policy(overrides: &str) -> CachePolicy {
+        CachePolicy {
+            default_ttl: Duration::from_secs(3600),
+            min_ttl: Duration::from_secs(60),
+            max_ttl: Duration::from_secs(86400),
+            overrides: TtlOverrides::parse(overrides).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_parses_cache_control() {
+        assert_eq!(parse(&[]), OriginCaching::default());
+        assert_eq!(
+            parse(&[("cache-control", "public, max-age=600")]).max_age,
+            secs(600)
+        );
+        // s-maxage is for shared caches like this one and wins over max-age.
+        assert_eq!(
+            parse(&[("cache-control", "max-age=600, S-MaxAge=\"120\"")]).max_age,
+            secs(120)
+        );
+        // Directives may be split across header lines.
+        assert_eq!(
+            parse(&[("cache-control", "public"), ("cache-control", "max-age=30")]).max_age,
+            secs(30)
+        );
+        assert_eq!(
+            parse(&[("cache-control", "no-cache, max-age=600")]).max_age,
+            secs(0)
+        );
+        let no_store = parse(&[("cache-control", "private, no-store")]);
+        assert!(no_store.no_store);
+    }
+
+    #[test]
+    fn test_malformed_values() {
+        assert_eq!(
+            parse(&[("cache-control", "max-age=soon, max-age=-5, =, ,")]).max_age,
+            None
+        );
+        // A malformed max-age falls through to Expires.
+        assert_eq!(
+            parse(&[
+                ("cache-control", "max-age=1.5"),
+                ("expires", "Thu, 15 Oct 2026 12:10:00 GMT"),
+            ])
+            .max_age,
+            secs(600)
+        );
+        // A malformed or past Expires means already expired.
+        assert_eq!(parse(&[("expires", "0")]).max_age, secs(0));
+        assert_eq!(
+            parse(&[("expires", "Thu, 01 Jan 2026 00:00:00 GMT")]).max_age,
+            secs(0)
+        );
+    }
+
+    #[test]
+    fn test_expires_is_measured_from_date() {
+        assert_eq!(
+            parse(&[
+                ("date", "Thu, 15 Oct 2026 11:00:00 GMT"),
+                ("expires", "Thu, 15 Oct 2026 13:00:00 GMT"),
+            ])
+            .max_age,
+            secs(7200)
+        );
+        // max-age wins over Expires.
+        assert_eq!(
+            parse(&[
+                ("cache-control", "max-age=60"),
+                ("expires", "Thu, 15 Oct 2026 13:00:00 GMT"),
+            ])
+            .max_age,
+            secs(60)
+        );
+    }
+
+    #[test]
+    fn test_origin_ttl_is_clamped() {
+        let policy = policy("");
+        let ttl = |max_age: u64| {
+            policy
+                .ttl_for(
+                    "https://example.com/",
+                    &OriginCaching {
+                        no_store: false,
+                        max_age: secs(max_age),
+                    },
+                )
+                .unwrap()
+        };
+        assert_eq!(ttl(5).seconds, 60);
+        assert_eq!(ttl(600).seconds, 600);
+        assert_eq!(ttl(31_536_000).seconds, 86400);
+        assert_eq!(ttl(600).source, CacheTtlSource::Origin);
+
+        let default = policy
+            .ttl_for("https://example.com/", &OriginCaching::default())
+            .unwrap();
+        assert_eq!(
+            default,
+            CacheTtl {
+                seconds: 3600,
+                source: CacheTtlSource::Default
+            }
+        );
+    }
+
+    #[test]
+    fn test_override_precedence() {
+        let policy = policy(
+            "[[sources]]\ndomain = \"*.gov.uk\"\nttl_secs = 1209600\n\
+             [[sources]]\ndomain = \"*.news.gov.uk\"\nttl_secs = 120\n\
+             [[sources]]\ndomain = \"live.example.com\"\nttl_secs = 0",
+        );
+        let origin = OriginCaching {
+            no_store: false,
+            max_age: secs(600),
+        };
+
+        // The override beats the origin's hint and isn't clamped.
+        let ttl = policy
+            .ttl_for("https://www.legislation.gov.uk/ukpga", &origin)
+            .unwrap();
+        assert_eq!(ttl.seconds, 1_209_600);
+        assert_eq!(ttl.source, CacheTtlSource::SourceOverride);
+        // The most specific wildcard wins.
+        assert_eq!(
+            policy
+                .ttl_for("https://press.news.gov.uk/", &origin)
+                .unwrap()
+                .seconds,
+            120
+        );
+        assert_eq!(
+            policy
+                .ttl_for("https://LIVE.example.com/feed", &OriginCaching::default())
+                .unwrap(),
+            CacheTtl {
+                seconds: 0,
+                source: CacheTtlSource::SourceOverride
+            }
+        );
+        // Other hosts use the origin's hint; the bare domain doesn't match
+        // its wildcard.
+        assert_eq!(
+            policy.ttl_for("https://gov.uk/", &origin).unwrap().source,
+            CacheTtlSource::Origin
+        );
+        // no-store beats an override.
+        let no_store = OriginCaching {
+            no_store: true,
+            max_age: None,
+        };
+        assert_eq!(
+            policy.ttl_for("https://www.legislation.gov.uk/", &no_store),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_overrides() {
+        for text in [
+            "[[sources]]\ndomain = \"https://example.com\"\nttl_secs = 60",
+            "[[sources]]\ndomain = \"example.com\"\nttl_secs = -1",
+            "[[sources]]\ndomain = \"example.com\"\nttl = 60",
+            "[[sources]]\ndomain = \"example.com\"\nttl_secs = 60\n\
+             [[sources]]\ndomain = \"Example.com\"\nttl_secs = 120",
+        ] {
+            assert!(TtlOverrides::parse(text).is_err(), "{}", text);
+        }
+    }
+}
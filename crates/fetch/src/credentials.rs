@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION, COOKIE};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// Per-domain credentials for licensed sources, loaded from the TOML file at
+/// FETCH_CREDENTIALS_PATH:
+///
+/// ```toml
+/// [[credentials]]
+/// domain = "api.example.com"   # exact host, or "*.example.com" for subdomains
+/// auth = "header"              # bearer | basic | header | cookie
+/// header = "X-Api-Key"         # header name, for auth = "header"
+/// secret_env = "EXAMPLE_API_KEY"
+/// ```
+///
+/// Secrets stay in the environment; the file only names the variables. A basic
+/// secret is `user:password`, a cookie secret the full Cookie header value.
+/// Credentials are sent only over https unless the entry sets
+/// `allow_http = true`.
+#[derive(Default)]
+pub struct CredentialStore {
+    exact: HashMap<String, Credential>,
+    /// (suffix including the leading dot, credential), longest suffix first.
+    wildcard: Vec<(String, Credential)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthType {
+    Bearer,
+    Basic,
+    Header,
+    Cookie,
+}
+
+/// A loaded credential. Debug output never includes the secret.
+#[derive(Clone)]
+pub struct Credential {
+    /// The domain pattern it was configured for.
+    pub domain: String,
+    pub auth: AuthType,
+    header: Option<HeaderName>,
+    secret: String,
+    allow_http: bool,
+    fingerprint: String,
+}
+
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credential")
+            .field("domain", &self.domain)
+            .field("auth", &self.auth)
+            .field("fingerprint", &self.fingerprint)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialsError {
+    #[error("Failed to read credentials file {path}: {message}")]
+    Read { path: String, message: String },
+
+    #[error("Invalid credentials file: {0}")]
+    Parse(String),
+
+    #[error("Invalid credential for {domain}: {message}")]
+    Invalid { domain: String, message: String },
+}
+
+#[derive(Deserialize)]
+struct CredentialsFile {
+    #[serde(default)]
+    credentials: Vec<CredentialEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CredentialEntry {
+    domain: String,
+    auth: AuthType,
+    #[serde(default)]
+    header: Option<String>,
+    secret_env: String,
+    #[serde(default)]
+    allow_http: bool,
+}
+
+impl CredentialStore {
+    /// Load from FETCH_CREDENTIALS_PATH; empty when it is unset.
+    pub fn from_env() -> Result<Self, CredentialsError> {
+        match std::env::var("FETCH_CREDENTIALS_PATH") {
+            Ok(path) if !path.is_empty() => Self::load(Path::new(&path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CredentialsError> {
+        let text = std::fs::read_to_string(path).map_err(|e| CredentialsError::Read {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::parse(&text, |var| std::env::var(var).ok())
+    }
+
+    /// Parse a credentials file, reading secrets with `lookup`. An entry whose
+    /// secret variable is unset or empty fails the whole load.
+    pub fn parse(
+        text: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, CredentialsError> {
+        let file: CredentialsFile =
+            toml::from_str(text).map_err(|e| CredentialsError::Parse(e.to_string()))?;
+
+        let mut store = Self::default();
+        for entry in file.credentials {
+            let domain = entry.domain.trim().to_lowercase();
+            let invalid = |message: String| CredentialsError::Invalid {
+                domain: domain.clone(),
+                message,
+            };
+
+            let host = domain.strip_prefix("*.").unwrap_or(&domain);
+            if host.is_empty() || host.contains(['*', '/', ':']) {
+                return Err(invalid(
+                    "domain must be a host name, optionally prefixed with \"*.\"".into(),
+                ));
+            }
+
+            let header = match (entry.auth, entry.header.as_deref()) {
+                (AuthType::Header, Some(name)) => Some(
+                    HeaderName::from_bytes(name.trim().as_bytes())
+                        .map_err(|e| invalid(format!("invalid header name: {}", e)))?,
+                ),
+                (AuthType::Header, None) => {
+                    return Err(invalid("auth = \"header\" needs a header name".into()))
+                }
+                (_, Some(_)) => {
+                    return Err(invalid("header is only used with auth = \"header\"".into()))
+                }
+                (_, None) => None,
+            };
+
+            let secret = lookup(&entry.secret_env)
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| invalid(format!("{} is not set", entry.secret_env)))?;
+            if entry.auth == AuthType::Basic && !secret.contains(':') {
+                return Err(invalid(format!(
+                    "{} must hold user:password for basic auth",
+                    entry.secret_env
+                )));
+            }
+            if HeaderValue::from_str(&secret).is_err() {
+                return Err(invalid(format!(
+                    "{} is not a valid header value",
+                    entry.secret_env
+                )));
+            }
+
+            let fingerprint = fingerprint(&domain, entry.auth, header.as_ref(), &secret);
+            let credential = Credential {
+                domain: domain.clone(),
+                auth: entry.auth,
+                header,
+                secret,
+                allow_http: entry.allow_http,
+                fingerprint,
+            };
+
+            let duplicate = match domain.strip_prefix('*') {
+                Some(suffix) => {
+                    let suffix = suffix.to_string();
+                    let duplicate = store.wildcard.iter().any(|(s, _)| *s == suffix);
+                    store.wildcard.push((suffix, credential));
+                    duplicate
+                }
+                None => store.exact.insert(domain.clone(), credential).is_some(),
+            };
+            if duplicate {
+                return Err(invalid("configured more than once".into()));
+            }
+        }
+        store
+            .wildcard
+            .sort_by_key(|(suffix, _)| std::cmp::Reverse(suffix.len()));
+        Ok(store)
+    }
+
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.wildcard.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The credential to send with a request for `url`: an exact host match
+    /// first, then the most specific wildcard. None for plain http unless the
+    /// credential allows it.
+    pub fn for_url(&self, url: &str) -> Option<&Credential> {
+        let parsed = reqwest::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.trim_end_matches('.').to_lowercase();
+        let credential = self.exact.get(&host).or_else(|| {
+            self.wildcard
+                .iter()
+                .find(|(suffix, _)| host.ends_with(suffix.as_str()))
+                .map(|(_, credential)| credential)
+        })?;
+        match parsed.scheme() {
+            "https" => Some(credential),
+            "http" if credential.allow_http => Some(credential),
+            _ => None,
+        }
+    }
+}
+
+impl Credential {
+    /// Short digest identifying the credential, secret included, so cached
+    /// responses are never shared across credentials and a rotated secret
+    /// starts fresh. Safe to expose.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// The header the credential is sent in. Request headers of the same
+    /// name are replaced by it.
+    pub fn header_name(&self) -> HeaderName {
+        match self.auth {
+            AuthType::Bearer | AuthType::Basic => AUTHORIZATION,
+            AuthType::Cookie => COOKIE,
+            AuthType::Header => self.header.clone().expect("header auth has a header name"),
+        }
+    }
+
+    /// Add the credential to `request`, marked sensitive so it stays out of
+    /// debug output.
+    pub fn apply(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.auth {
+            AuthType::Bearer => request.bearer_auth(&self.secret),
+            AuthType::Basic => {
+                let (user, password) = self.secret.split_once(':').unwrap_or((&self.secret, ""));
+                request.basic_auth(user, Some(password))
+            }
+            AuthType::Cookie | AuthType::Header => {
+                let mut value = HeaderValue::from_str(&self.secret).expect("validated when loaded");
+                value.set_sensitive(true);
+                request.header(self.header_name(), value)
+            }
+        }
+    }
+}
+
+/// Redirect policy for credentialed fetches: follow redirects within the
+/// host, stop at one to another host so a secret in a custom header is never
+/// forwarded there.
+pub fn same_host_redirects() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        let origin = attempt.previous().first().and_then(|u| u.host_str());
+        if attempt.previous().len() >= 10 {
+            attempt.error("too many redirects")
+        } else if origin != attempt.url().host_str() {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+fn fingerprint(domain: &str, auth: AuthType, header: Option<&HeaderName>, secret: &str) -> String {
+    let identity = format!(
+        "{}\n{:?}\n{}\n{}",
+        domain,
+        auth,
+        header.map(HeaderName::as_str).unwrap_or_default(),
+        secret
+    );
+    Sha256::digest(identity.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(var: &str) -> Option<String> {
+        match var {
+            "TOKEN" => Some("t0ken".into()),
+            "OTHER_TOKEN" => Some("other".into()),
+            "BASIC" => Some("reader:hunter2".into()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn store(text: &str) -> CredentialStore {
+        CredentialStore::parse(text, env).unwrap()
+    }
+
+    fn error(text: &str) -> String {
+        match CredentialStore::parse(text, env) {
+            Ok(_) => panic!("loaded: {}", text),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matches_exact_host_then_most_specific_wildcard() {
+        let store = store(
+            r#"
+            [[credentials]]
+            domain = "*.example.com"
+            auth = "bearer"
+            secret_env = "TOKEN"
+
+            [[credentials]]
+            domain = "*.api.example.com"
+            auth = "header"
+            header = "X-Api-Key"
+            secret_env = "OTHER_TOKEN"
+
+            [[credentials]]
+            domain = "Example.com"
+            auth = "basic"
+            secret_env = "BASIC"
+            "#,
+        );
+        assert_eq!(store.len(), 3);
+
+        let domain = |url: &str| store.for_url(url).map(|c| c.domain.as_str());
+        assert_eq!(domain("https://example.com/a"), Some("example.com"));
+        assert_eq!(domain("https://EXAMPLE.com:8443/a"), Some("example.com"));
+        assert_eq!(domain("https://news.example.com/a"), Some("*.example.com"));
+        assert_eq!(
+            domain("https://v2.api.example.com/a"),
+            Some("*.api.example.com")
+        );
+        assert_eq!(domain("https://notexample.com/"), None);
+        assert_eq!(domain("https://example.com.evil.net/"), None);
+        // Never over plain http unless allowed.
+        assert_eq!(domain("http://example.com/a"), None);
+    }
+
+    #[test]
+    fn test_allow_http() {
+        let store = store(
+            r#"
+            [[credentials]]
+            domain = "127.0.0.1"
+            auth = "cookie"
+            secret_env = "TOKEN"
+            allow_http = true
+            "#,
+        );
+        assert!(store.for_url("http://127.0.0.1:8080/a").is_some());
+    }
+
+    #[test]
+    fn test_misconfigured_entries_fail_the_load() {
+        let entry = |fields: &str| format!("[[credentials]]\ndomain = \"example.com\"\n{}", fields);
+        assert!(error(&entry("auth = \"bearer\"\nsecret_env = \"MISSING\""))
+            .contains("MISSING is not set"));
+        assert!(
+            error(&entry("auth = \"bearer\"\nsecret_env = \"EMPTY\"")).contains("EMPTY is not set")
+        );
+        assert!(error(&entry("auth = \"header\"\nsecret_env = \"TOKEN\""))
+            .contains("needs a header name"));
+        assert!(error(&entry(
+            "auth = \"bearer\"\nheader = \"X\"\nsecret_env = \"TOKEN\""
+        ))
+        .contains("only used with"));
+        assert!(error(&entry("auth = \"basic\"\nsecret_env = \"TOKEN\"")).contains("user:password"));
+        assert!(error(&entry("auth = \"oauth\"\nsecret_env = \"TOKEN\"")).contains("Invalid"));
+        assert!(error(
+            "[[credentials]]\ndomain = \"https://example.com/\"\nauth = \"bearer\"\nsecret_env = \"TOKEN\""
+        )
+        .contains("host name"));
+        let twice = entry("auth = \"bearer\"\nsecret_env = \"TOKEN\"");
+        assert!(error(&format!("{}\n{}", twice, twice)).contains("more than once"));
+    }
+
+    #[test]
+    fn test_secret_never_in_debug_or_fingerprint() {
+        let store = store(
+            "[[credentials]]\ndomain = \"example.com\"\nauth = \"basic\"\nsecret_env = \"BASIC\"",
+        );
+        let credential = store.for_url("https://example.com/").unwrap();
+        let debug = format!("{:?}", credential);
+        assert!(!debug.contains("hunter2"), "{}", debug);
+        assert_eq!(credential.fingerprint().len(), 16);
+        assert!(!credential.fingerprint().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_fingerprint_changes_with_the_secret() {
+        let text = |var: &str| {
+            format!(
+                "[[credentials]]\ndomain = \"example.com\"\nauth = \"bearer\"\nsecret_env = \"{}\"",
+                var
+            )
+        };
+        let a = store(&text("TOKEN"));
+        let b = store(&text("OTHER_TOKEN"));
+        let fingerprint = |s: &CredentialStore| {
+            s.for_url("https://example.com/")
+                .unwrap()
+                .fingerprint()
+                .to_string()
+        };
+        assert_ne!(fingerprint(&a), fingerprint(&b));
+        assert_eq!(fingerprint(&a), fingerprint(&store(&text("TOKEN"))));
+    }
+}
@@ -0,0 +1,65 @@
+//! Document language detection from the markup's own declarations. No
+//! statistical guessing: a page that doesn't declare a language gets none.
+
+use scraper::{Html, Selector};
+
+use autosint_common::types::normalize_language;
+
+/// The ISO 639-1 language an HTML document declares: the root element's
+/// `lang` (or `xml:lang`), else a `<meta http-equiv="content-language">`.
+pub fn detect_html_language(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let root = document.root_element();
+    let declared = root
+        .value()
+        .attr("lang")
+        .or_else(|| root.value().attr("xml:lang"))
+        .and_then(normalize_language);
+    if declared.is_some() {
+        return declared;
+    }
+
+    let meta_sel = Selector::parse("meta[http-equiv]").expect("valid selector");
+    document
+        .select(&meta_sel)
+        .filter(|meta| {
+            meta.value()
+                .attr("http-equiv")
+                .is_some_and(|v| v.eq_ignore_ascii_case("content-language"))
+        })
+        .filter_map(|meta| meta.value().attr("content"))
+        // A header value may list several languages; the first is primary.
+        .find_map(|content| content.split(',').next().and_then(normalize_language))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_lang_attribute() {
+        assert_eq!(
+            detect_html_language(r#"<html lang="uk-UA"><body>Привіт</body></html>"#).as_deref(),
+            Some("uk")
+        );
+    }
+
+    #[test]
+    fn test_meta_content_language() {
+        let html =
+            r#"<html><head><meta http-equiv="Content-Language" content="de, en"></head></html>"#;
+        assert_eq!(detect_html_language(html).as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn test_undeclared_language() {
+        assert_eq!(
+            detect_html_language("<html><body>Hello</body></html>"),
+            None
+        );
+        assert_eq!(
+            detect_html_language(r#"<html lang="x-klingon"></html>"#),
+            None
+        );
+    }
+}
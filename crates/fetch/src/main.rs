@@ -2,29 +2,71 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::State;
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::http::HeaderMap;
+use axum::response::Response;
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_exporter_prometheus::PrometheusHandle;
 use tokio::sync::RwLock;
 
+use autosint_common::api::fetch::HealthResponse;
+use autosint_common::api::HealthStatus;
+use autosint_common::config::HttpClientConfig;
+use autosint_common::http_client::{self, Destination};
+use autosint_common::prometheus::{self, MetricsLabels};
+use autosint_common::tls;
+
+mod browser;
 mod cache;
+mod cache_policy;
+mod credentials;
 mod fetch;
+mod language;
 mod rate_limit;
+mod robots;
 mod routes;
+mod sitemap;
+mod source_health;
+mod sources;
+mod tables;
 
+use browser::BrowserBackend;
 use cache::UrlCache;
+use cache_policy::{CachePolicy, TtlOverrides};
+use credentials::CredentialStore;
 use rate_limit::DomainRateLimiter;
+use robots::RobotsCache;
+use source_health::SourceHealthTracker;
+use sources::SourceCatalog;
 
 /// Shared application state.
 pub struct AppState {
     pub http: reqwest::Client,
+    /// Client for fetches carrying a credential: like `http`, but never
+    /// follows a redirect to another host.
+    pub credentialed_http: reqwest::Client,
+    /// Per-domain credentials from FETCH_CREDENTIALS_PATH.
+    pub credentials: Arc<CredentialStore>,
     pub cache: Arc<RwLock<UrlCache>>,
+    /// How long `cache` keeps each fetch.
+    pub cache_policy: CachePolicy,
     pub rate_limiter: Arc<DomainRateLimiter>,
     pub metrics_handle: PrometheusHandle,
+    /// Bearer token /metrics requires (FETCH_METRICS_TOKEN). Open when unset.
+    pub metrics_token: Option<String>,
     /// SearXNG backend URL for web search.
     pub search_backend_url: String,
+    /// Client for the search backend; carries SEARCH_BACKEND_TLS_* when set.
+    pub search_http: reqwest::Client,
+    /// Headless browser backend for `render: browser` fetches. None when
+    /// FETCH_BROWSER_URL is unset.
+    pub browser: Option<Arc<BrowserBackend>>,
+    /// robots.txt per origin, checked before each sitemap fetch.
+    pub robots: Arc<RobotsCache>,
+    /// Catalog sources from FETCH_SOURCES_PATH.
+    pub sources: Arc<SourceCatalog>,
+    /// Per-source query outcomes, kept at FETCH_SOURCE_HEALTH_PATH.
+    pub source_health: Arc<SourceHealthTracker>,
 }
 
 #[tokio::main]
@@ -39,16 +81,63 @@ async fn main() {
 
     tracing::info!("AutOSINT Fetch starting");
 
-    // Install Prometheus metrics recorder.
-    let metrics_handle = PrometheusBuilder::new()
+    // Optional TLS for the HTTP API: FETCH_TLS_CERT / FETCH_TLS_KEY, plus
+    // FETCH_TLS_CLIENT_CA to require client certificates.
+    let server_tls = match tls::server_config_from_env("FETCH") {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid TLS configuration — refusing to start");
+            std::process::exit(1);
+        }
+    };
+
+    // Install Prometheus metrics recorder, labeling every metric with the
+    // service, version and instance. FETCH_METRICS_TOKEN protects /metrics.
+    let metrics_handle = MetricsLabels::from_env("FETCH", "fetch", env!("CARGO_PKG_VERSION"))
+        .builder()
         .install_recorder()
         .expect("Failed to install Prometheus metrics recorder");
+    let metrics_token = prometheus::token_from_env("FETCH");
 
-    // Configure cache TTL from env (default 3600 seconds).
-    let cache_ttl_secs: u64 = std::env::var("FETCH_CACHE_TTL")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(3600);
+    // Cache TTLs from env: the default for responses without caching hints
+    // (3600 seconds), and the floor and ceiling for origin hints (one minute,
+    // one week). Per-source overrides come from FETCH_CACHE_OVERRIDES_PATH.
+    let env_secs = |name: &str, default: u64| -> Duration {
+        Duration::from_secs(
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default),
+        )
+    };
+    let min_ttl = env_secs("FETCH_CACHE_TTL_MIN", 60);
+    let max_ttl = env_secs("FETCH_CACHE_TTL_MAX", 7 * 24 * 3600);
+    if min_ttl > max_ttl {
+        tracing::error!(
+            min_secs = min_ttl.as_secs(),
+            max_secs = max_ttl.as_secs(),
+            "FETCH_CACHE_TTL_MIN is above FETCH_CACHE_TTL_MAX — refusing to start"
+        );
+        std::process::exit(1);
+    }
+    let overrides = match TtlOverrides::from_env() {
+        Ok(overrides) => {
+            if !overrides.is_empty() {
+                tracing::info!(count = overrides.len(), "Loaded cache TTL overrides");
+            }
+            overrides
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid cache TTL overrides — refusing to start");
+            std::process::exit(1);
+        }
+    };
+    let cache_policy = CachePolicy {
+        default_ttl: env_secs("FETCH_CACHE_TTL", 3600),
+        min_ttl,
+        max_ttl,
+        overrides,
+    };
 
     // Configure rate limit from env (default 2.0 requests per second per domain).
     let rate_limit: f64 = std::env::var("FETCH_RATE_LIMIT")
@@ -56,22 +145,105 @@ async fn main() {
         .and_then(|s| s.parse().ok())
         .unwrap_or(2.0);
 
-    let http = reqwest::Client::builder()
-        .user_agent("AutOSINT-Fetch/0.1")
-        .build()
-        .expect("Failed to build HTTP client");
+    // Web fetches go through FETCH_PROXY when set, else the usual
+    // HTTPS_PROXY / NO_PROXY (and ALL_PROXY for SOCKS).
+    let mut http_config = HttpClientConfig::default();
+    http_config.proxies.fetch = std::env::var("FETCH_PROXY").ok().filter(|p| !p.is_empty());
+    let build_http = |builder: reqwest::ClientBuilder| match http_client::configure(
+        builder.user_agent("AutOSINT-Fetch/0.1"),
+        &http_config,
+        Destination::Fetch,
+    )
+    .and_then(|b| {
+        b.build()
+            .map_err(|e| http_client::HttpClientError::Build(e.to_string()))
+    }) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid HTTP client configuration — refusing to start");
+            std::process::exit(1);
+        }
+    };
+    let http = build_http(reqwest::Client::builder());
+    let credentialed_http =
+        build_http(reqwest::Client::builder().redirect(credentials::same_host_redirects()));
+
+    // Per-domain credentials for licensed sources. A misconfigured entry
+    // (e.g. its secret's env var is unset) stops startup.
+    let credentials = match CredentialStore::from_env() {
+        Ok(store) => {
+            if !store.is_empty() {
+                tracing::info!(count = store.len(), "Loaded fetch credentials");
+            }
+            Arc::new(store)
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid fetch credentials — refusing to start");
+            std::process::exit(1);
+        }
+    };
+
+    // Catalog sources (sitemaps of site sections) for /sources.
+    let sources = match SourceCatalog::from_env() {
+        Ok(catalog) => {
+            if !catalog.is_empty() {
+                tracing::info!(count = catalog.len(), "Loaded catalog sources");
+            }
+            Arc::new(catalog)
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid source catalog — refusing to start");
+            std::process::exit(1);
+        }
+    };
+
+    // Per-source query health for /sources, persisted across restarts when
+    // FETCH_SOURCE_HEALTH_PATH is set.
+    let source_health = Arc::new(SourceHealthTracker::from_env());
 
     let search_backend_url =
         std::env::var("SEARCH_BACKEND_URL").unwrap_or_else(|_| "http://localhost:8888".into());
+    let search_http = backend_client("SEARCH_BACKEND", &search_backend_url);
+
+    // Optional headless browser backend (browserless) for JS-rendered pages.
+    // Renders get their own, stricter per-domain rate (default 0.2/s) and a
+    // hard timeout (default 45s).
+    let browser = std::env::var("FETCH_BROWSER_URL")
+        .ok()
+        .filter(|u| !u.is_empty())
+        .map(|base_url| {
+            let rate: f64 = std::env::var("FETCH_BROWSER_RATE_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.2);
+            let timeout_secs: u64 = std::env::var("FETCH_BROWSER_TIMEOUT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(45);
+            tracing::info!(base_url = %base_url, rate, timeout_secs, "Browser rendering enabled");
+            Arc::new(BrowserBackend::new(
+                backend_client("FETCH_BROWSER", &base_url),
+                base_url,
+                rate,
+                Duration::from_secs(timeout_secs),
+            ))
+        });
 
     let state = Arc::new(AppState {
         http,
-        cache: Arc::new(RwLock::new(UrlCache::new(Duration::from_secs(
-            cache_ttl_secs,
-        )))),
+        credentialed_http,
+        credentials,
+        cache: Arc::new(RwLock::new(UrlCache::new())),
+        cache_policy,
         rate_limiter: Arc::new(DomainRateLimiter::new(rate_limit)),
         metrics_handle,
+        metrics_token,
         search_backend_url,
+        search_http,
+        browser,
+        robots: Arc::new(RobotsCache::new()),
+        sources,
+        source_health,
     });
 
     let app = Router::new()
@@ -79,7 +251,10 @@ async fn main() {
         .route("/metrics", get(metrics_handler))
         .route("/fetch", post(routes::fetch_handler))
         .route("/search", post(routes::search_handler))
+        .route("/sitemap", post(routes::sitemap_handler))
         .route("/sources", get(routes::sources_handler))
+        .route("/sources/{id}/query", post(routes::source_query_handler))
+        .route("/cache/entry", get(routes::cache_entry_handler))
         .with_state(state);
 
     let port: u16 = std::env::var("FETCH_PORT")
@@ -91,18 +266,43 @@ async fn main() {
         .await
         .expect("Failed to bind TCP listener");
 
-    tracing::info!(port = port, "AutOSINT Fetch listening");
+    tracing::info!(
+        port = port,
+        tls = server_tls.is_some(),
+        "AutOSINT Fetch listening"
+    );
+
+    tls::serve(listener, app, server_tls)
+        .await
+        .expect("HTTP server error");
+}
 
-    axum::serve(listener, app).await.expect("HTTP server error");
+/// Client for an internal backend at `base_url`, with `{prefix}_TLS_*` applied
+/// when it is https. Exits on invalid TLS configuration.
+fn backend_client(prefix: &str, base_url: &str) -> reqwest::Client {
+    match tls::client_builder_from_env(prefix, base_url).and_then(|b| {
+        b.user_agent("AutOSINT-Fetch/0.1")
+            .build()
+            .map_err(|e| tls::TlsError::Config(e.to_string()))
+    }) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(prefix, error = %e, "Invalid backend TLS configuration — refusing to start");
+            std::process::exit(1);
+        }
+    }
 }
 
-async fn health_handler() -> impl IntoResponse {
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({ "status": "healthy" })),
-    )
+async fn health_handler() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: HealthStatus::Healthy,
+    })
 }
 
-async fn metrics_handler(State(state): State<Arc<AppState>>) -> String {
-    state.metrics_handle.render()
+async fn metrics_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    prometheus::render(
+        &state.metrics_handle,
+        state.metrics_token.as_deref(),
+        &headers,
+    )
 }
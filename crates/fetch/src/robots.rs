@@ -0,0 +1,246 @@
+//! robots.txt checks for crawls the service drives itself (sitemaps). Fetches
+//! of a single URL an analyst asked for are not checked.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+
+use autosint_common::http_client::{self, extract_domain, Destination};
+
+use crate::rate_limit::DomainRateLimiter;
+
+/// The product token matched against `User-agent` lines; `autosint` alone
+/// matches too.
+const USER_AGENT_TOKEN: &str = "autosint-fetch";
+
+/// How long a host's robots.txt is trusted.
+const ROBOTS_TTL: Duration = Duration::from_secs(3600);
+
+/// The rules in one robots.txt that apply to this service.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    /// (allow, path pattern).
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parse robots.txt, keeping the group for this service's user agent, or
+    /// the `*` group if there is none.
+    pub fn parse(text: &str) -> Self {
+        let mut specific: Option<Vec<(bool, String)>> = None;
+        let mut wildcard: Option<Vec<(bool, String)>> = None;
+
+        // User agents of the group being read, and whether its rules started
+        // (a User-agent line after a rule starts a new group).
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        let mut rules: Vec<(bool, String)> = Vec::new();
+
+        let mut close = |agents: &[String], rules: &[(bool, String)]| {
+            for agent in agents {
+                let target = if agent == "*" {
+                    &mut wildcard
+                } else if agent == USER_AGENT_TOKEN || agent == "autosint" {
+                    &mut specific
+                } else {
+                    continue;
+                };
+                target.get_or_insert_with(Vec::new).extend_from_slice(rules);
+            }
+        };
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        close(&agents, &rules);
+                        agents.clear();
+                        rules.clear();
+                        in_rules = false;
+                    }
+                    agents.push(value.to_lowercase());
+                }
+                directive @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // An empty Disallow allows everything; it adds no rule.
+                    if !value.is_empty() {
+                        rules.push((directive == "allow", value.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        close(&agents, &rules);
+
+        Self {
+            rules: specific.or(wildcard).unwrap_or_default(),
+        }
+    }
+
+    /// Whether `path` (path and query) may be fetched: the longest matching
+    /// rule decides, Allow winning a tie; no match allows.
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// Match a robots path pattern: a prefix, where `*` matches any run of
+/// characters and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+/// robots.txt per origin, fetched on first use and kept for an hour.
+#[derive(Default)]
+pub struct RobotsCache {
+    entries: RwLock<HashMap<String, (Instant, RobotsRules)>>,
+}
+
+impl RobotsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether robots.txt lets this service fetch `url`. A missing robots.txt
+    /// (any 4xx) allows everything; one that can't be read (5xx, network
+    /// error) is an error, and is retried on the next call.
+    pub async fn allowed(
+        &self,
+        http: &reqwest::Client,
+        rate_limiter: &DomainRateLimiter,
+        url: &str,
+    ) -> Result<bool, String> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        let origin = parsed.origin().ascii_serialization();
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+
+        if let Some((fetched_at, rules)) = self.entries.read().await.get(&origin) {
+            if fetched_at.elapsed() < ROBOTS_TTL {
+                return Ok(rules.allows(&path));
+            }
+        }
+
+        let rules = fetch_robots(http, rate_limiter, &origin).await?;
+        let allowed = rules.allows(&path);
+        self.entries
+            .write()
+            .await
+            .insert(origin, (Instant::now(), rules));
+        Ok(allowed)
+    }
+}
+
+async fn fetch_robots(
+    http: &reqwest::Client,
+    rate_limiter: &DomainRateLimiter,
+    origin: &str,
+) -> Result<RobotsRules, String> {
+    let url = format!("{}/robots.txt", origin);
+    rate_limiter
+        .acquire(&extract_domain(&url), Duration::from_secs(120))
+        .await?;
+    let response = http_client::send(
+        Destination::Fetch,
+        http.get(&url).timeout(Duration::from_secs(30)),
+    )
+    .await
+    .map_err(|e| format!("Could not fetch {}: {}", url, e))?;
+
+    let status = response.status();
+    if status.is_client_error() {
+        return Ok(RobotsRules::default());
+    }
+    if !status.is_success() {
+        return Err(format!("{} returned {}", url, status));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Could not read {}: {}", url, e))?;
+    Ok(RobotsRules::parse(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+User-agent: *
+Disallow: /private/
+Disallow: /*.pdf$
+Allow: /private/press/
+
+# Ours, split across two user-agent lines.
+User-agent: SomeBot
+User-agent: autosint-fetch
+Disallow: /drafts
+Allow: /drafts/public*
+";
+
+    #[test]
+    fn test_specific_group_wins_over_wildcard() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert!(!rules.allows("/drafts/2026"));
+        assert!(rules.allows("/drafts/public/note"));
+        // The `*` group's rules don't apply once ours exists.
+        assert!(rules.allows("/private/x"));
+    }
+
+    #[test]
+    fn test_wildcard_group_rules() {
+        let rules = RobotsRules::parse(&ROBOTS.replace("autosint-fetch", "OtherBot"));
+        assert!(!rules.allows("/private/x"));
+        assert!(rules.allows("/private/press/2026"));
+        assert!(!rules.allows("/files/report.pdf"));
+        assert!(rules.allows("/files/report.pdf?download=1"));
+        assert!(rules.allows("/sitemap.xml"));
+
+        assert!(RobotsRules::parse("").allows("/anything"));
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n").allows("/anything"));
+        assert!(!RobotsRules::parse("User-agent: *\nDisallow: /\n").allows("/sitemap.xml"));
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(pattern_matches("/a", "/abc"));
+        assert!(pattern_matches("/a*c", "/a/b/c/d"));
+        assert!(pattern_matches("/a*c$", "/a/b/c"));
+        assert!(!pattern_matches("/a*c$", "/a/b/c/d"));
+        assert!(pattern_matches("/exact$", "/exact"));
+        assert!(!pattern_matches("/exact$", "/exact/"));
+        assert!(!pattern_matches("/b", "/abc"));
+    }
+}
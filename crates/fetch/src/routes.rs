@@ -1,73 +1,126 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use axum::extract::State;
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 
 use autosint_common::api::fetch::{
-    FetchMetadata, FetchRequest, FetchResponse, SearchRequest, SearchResponse, SearchResult,
-    SourceInfo,
+    CacheEntryQuery, CacheEntryResponse, CacheTtlSource, ExtractMode, FetchMetadata, FetchOptions,
+    FetchRequest, FetchResponse, RenderMode, SearchRequest, SearchResponse, SearchResult,
+    SitemapRequest, SitemapResponse, SourceInfo, SourceQueryMetadata, SourceQueryRequest,
+    SourceQueryResponse, SourceQueryResult, SOURCE_QUERY_LIMIT, SOURCE_QUERY_PREFIX,
+    SOURCE_QUERY_SINCE, SOURCE_QUERY_UNTIL, SOURCE_RESULT_CHANGEFREQ, SOURCE_RESULT_PUBLISHED_AT,
 };
+use autosint_common::api::{ErrorResponse, FieldError, Validate};
+use autosint_common::http_client::{self, extract_domain, Destination};
+use autosint_common::readable::{extract_html_content, extract_html_title};
 
-use crate::fetch::{extract_html_content, fetch_url};
+use crate::cache::{normalize_url, CacheKey, CachedDocument};
+use crate::cache_policy::OriginCaching;
+use crate::credentials::Credential;
+use crate::fetch::fetch_url;
+use crate::language::detect_html_language;
+use crate::sitemap::{self, CrawlLimits, SitemapFilter, MAX_SITEMAP_BYTES};
+use crate::tables::{extract_html_tables, extract_text_tables};
 use crate::AppState;
 
+/// A failed request, returned as an [`ErrorResponse`] body.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    body: ErrorResponse,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: ErrorResponse::new(message),
+        }
+    }
+
+    /// 400 listing the request fields that failed validation.
+    fn invalid(fields: Vec<FieldError>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            body: ErrorResponse::invalid(fields),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self.body)).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// Reject a request that fails its [`Validate`] checks.
+fn validate(request: &impl Validate) -> ApiResult<()> {
+    request.validate().map_err(ApiError::invalid)
+}
+
 /// POST /fetch — fetch a URL, extract text, return content.
 pub async fn fetch_handler(
     State(state): State<Arc<AppState>>,
     Json(request): Json<FetchRequest>,
-) -> Result<Json<FetchResponse>, (StatusCode, String)> {
+) -> ApiResult<Json<FetchResponse>> {
+    validate(&request)?;
     let start = std::time::Instant::now();
+    let mut options = request.options.clone().unwrap_or_default();
 
-    // Check cache first.
+    // A configured credential for the host is sent with the fetch. The
+    // browser backend can't carry it, so credentialed fetches are never
+    // rendered.
+    let credential = state.credentials.for_url(&request.url);
+    if credential.is_some() && options.render == Some(RenderMode::Browser) {
+        tracing::info!(url = %request.url, "Credentialed fetch, skipping browser render");
+        options.render = None;
+    }
+    let credential_fingerprint = credential.map(|c| c.fingerprint());
+    let render = options.render.unwrap_or_default();
+
+    // Check cache first. Entries are keyed by URL and the options that shape
+    // the body, so e.g. rendered and plain fetches never serve each other,
+    // and by the credential used, if any.
     {
         let cache = state.cache.read().await;
-        if let Some((content, status_code, content_type)) = cache.get(&request.url) {
+        let key = CacheKey::new(&request.url, &options).with_credential(credential_fingerprint);
+        if let Some(document) = cache.get(&key) {
             return Ok(Json(FetchResponse {
-                content,
+                content: document.content,
+                tables: document.tables,
                 metadata: FetchMetadata {
-                    status_code,
-                    content_type,
+                    status_code: document.status_code,
+                    content_type: document.content_type,
                     url: request.url,
                     cached: true,
+                    rendered: render == RenderMode::Browser,
+                    detected_language: document.detected_language,
+                    authenticated: credential.is_some(),
+                    cache_ttl: Some(document.cache_ttl),
+                    title: document.title,
+                    fetched_at: Some(document.fetched_at),
                 },
             }));
         }
     }
 
-    // Rate limit check.
     let domain = extract_domain(&request.url);
-    state
-        .rate_limiter
-        .acquire(&domain, Duration::from_secs(120))
-        .await
-        .map_err(|e| (StatusCode::TOO_MANY_REQUESTS, e))?;
-
-    // Fetch the URL.
-    let timeout = request
-        .options
-        .as_ref()
-        .and_then(|o| o.timeout_ms)
-        .map(Duration::from_millis)
-        .unwrap_or(Duration::from_secs(120));
-
-    let (body, status_code, content_type) = fetch_url(&state.http, &request.url, Some(timeout))
-        .await
-        .map_err(|e| {
-            metrics::counter!("fetch.request.errors", "domain" => domain.clone()).increment(1);
-            (StatusCode::BAD_GATEWAY, e.to_string())
-        })?;
+    let fetched = fetch_document(&state, &request.url, &options, credential).await?;
 
     // Reject binary content types — only text-based responses are useful.
-    if let Some(ref ct) = content_type {
+    if let Some(ref ct) = fetched.content_type {
         let ct_lower = ct.to_lowercase();
         let is_text = ct_lower.contains("text/")
             || ct_lower.contains("application/json")
             || ct_lower.contains("application/xml")
             || ct_lower.contains("application/xhtml");
         if !is_text {
-            return Err((
+            return Err(ApiError::new(
                 StatusCode::UNPROCESSABLE_ENTITY,
                 format!(
                     "Unsupported content type: {}. Only text-based content is supported.",
@@ -77,25 +130,62 @@ pub async fn fetch_handler(
         }
     }
 
-    // Extract text from HTML content.
-    let content = if content_type
-        .as_deref()
-        .is_some_and(|ct| ct.contains("text/html"))
-    {
-        extract_html_content(&body)
+    // Extract text and tables unless the raw document was requested: HTML
+    // markup tables from HTML, column-aligned tables from plain text (e.g. a
+    // PDF's text layer).
+    let content_type = fetched.content_type.as_deref().unwrap_or_default();
+    let fetched_at = chrono::Utc::now();
+    let (detected_language, title) = if content_type.contains("html") {
+        (
+            detect_html_language(&fetched.body),
+            extract_html_title(&fetched.body),
+        )
     } else {
-        body.clone()
+        (None, None)
     };
+    let (content, tables) = if options.extract == Some(ExtractMode::Raw) {
+        (fetched.body, Vec::new())
+    } else if content_type.contains("text/html") {
+        let tables = extract_html_tables(&fetched.body);
+        (extract_html_content(&fetched.body), tables)
+    } else if content_type.contains("text/plain") {
+        let tables = extract_text_tables(&fetched.body);
+        (fetched.body, tables)
+    } else {
+        (fetched.body, Vec::new())
+    };
+    if !tables.is_empty() {
+        metrics::counter!("fetch.tables.extracted", "domain" => domain.clone())
+            .increment(tables.len() as u64);
+    }
 
-    // Cache the result.
-    {
+    // Cache the result under the mode that actually produced it: a render that
+    // fell back to a plain fetch is stored as a plain fetch. Unless the origin
+    // said no-store, for as long as the cache policy allows.
+    let mut cached_options = options.clone();
+    if !fetched.rendered {
+        cached_options.render = None;
+    }
+    let cache_ttl = state.cache_policy.ttl_for(&request.url, &fetched.caching);
+    if let Some(cache_ttl) = cache_ttl {
+        metrics::counter!("fetch.cache.ttl_source", "source" => ttl_source_label(cache_ttl.source))
+            .increment(1);
         let mut cache = state.cache.write().await;
         cache.insert(
-            request.url.clone(),
-            content.clone(),
-            status_code,
-            content_type.clone(),
+            CacheKey::new(&request.url, &cached_options).with_credential(credential_fingerprint),
+            CachedDocument {
+                content: content.clone(),
+                tables: tables.clone(),
+                status_code: fetched.status_code,
+                content_type: fetched.content_type.clone(),
+                detected_language: detected_language.clone(),
+                title: title.clone(),
+                fetched_at,
+                cache_ttl,
+            },
         );
+    } else {
+        metrics::counter!("fetch.cache.no_store").increment(1);
     }
 
     let latency = start.elapsed().as_secs_f64();
@@ -103,27 +193,139 @@ pub async fn fetch_handler(
 
     Ok(Json(FetchResponse {
         content,
+        tables,
         metadata: FetchMetadata {
-            status_code,
-            content_type,
+            status_code: fetched.status_code,
+            content_type: fetched.content_type,
             url: request.url,
             cached: false,
+            rendered: fetched.rendered,
+            detected_language,
+            authenticated: credential.is_some(),
+            cache_ttl,
+            title,
+            fetched_at: Some(fetched_at),
         },
     }))
 }
 
+fn ttl_source_label(source: CacheTtlSource) -> &'static str {
+    match source {
+        CacheTtlSource::Origin => "origin",
+        CacheTtlSource::Default => "default",
+        CacheTtlSource::SourceOverride => "source_override",
+    }
+}
+
+/// A retrieved document before text extraction.
+struct FetchedDocument {
+    body: String,
+    status_code: u16,
+    content_type: Option<String>,
+    rendered: bool,
+    caching: OriginCaching,
+}
+
+/// Retrieve `url` with the requested render mode. A browser render that can't be
+/// served — no backend configured, backend down, render failed — falls back to a
+/// plain fetch with a warning. With a `credential` the fetch goes through the
+/// client that won't follow redirects off the host.
+async fn fetch_document(
+    state: &AppState,
+    url: &str,
+    options: &FetchOptions,
+    credential: Option<&Credential>,
+) -> ApiResult<FetchedDocument> {
+    let domain = extract_domain(url);
+
+    if options.render == Some(RenderMode::Browser) {
+        match &state.browser {
+            Some(browser) => match browser.render(url, options.wait_for.as_deref()).await {
+                Ok((body, status_code)) => {
+                    metrics::counter!("fetch.browser.renders", "domain" => domain).increment(1);
+                    return Ok(FetchedDocument {
+                        body,
+                        status_code,
+                        content_type: Some("text/html".into()),
+                        rendered: true,
+                        caching: OriginCaching::default(),
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(url, error = %e, "Browser render failed, falling back to plain fetch");
+                }
+            },
+            None => {
+                tracing::warn!(
+                    url,
+                    "Browser render requested but FETCH_BROWSER_URL is not set, falling back to plain fetch"
+                );
+            }
+        }
+        metrics::counter!("fetch.browser.fallbacks", "domain" => domain.clone()).increment(1);
+    }
+
+    // Rate limit check.
+    state
+        .rate_limiter
+        .acquire(&domain, Duration::from_secs(120))
+        .await
+        .map_err(|e| ApiError::new(StatusCode::TOO_MANY_REQUESTS, e))?;
+
+    let timeout = options
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(Duration::from_secs(120));
+
+    let http = match credential {
+        Some(_) => &state.credentialed_http,
+        None => &state.http,
+    };
+    let page = fetch_url(http, url, Some(timeout), options, credential)
+        .await
+        .map_err(|e| {
+            metrics::counter!("fetch.request.errors", "domain" => domain.clone()).increment(1);
+            ApiError::new(StatusCode::BAD_GATEWAY, e.to_string())
+        })?;
+
+    Ok(FetchedDocument {
+        body: page.body,
+        status_code: page.status_code,
+        content_type: page.content_type,
+        rendered: false,
+        caching: page.caching,
+    })
+}
+
+/// GET /cache/entry — list the cached variants held for a URL.
+pub async fn cache_entry_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CacheEntryQuery>,
+) -> Json<CacheEntryResponse> {
+    let cache = state.cache.read().await;
+    let variants = cache.variants(&params.url);
+    let total_bytes: usize = variants.iter().map(|v| v.size_bytes).sum();
+
+    Json(CacheEntryResponse {
+        url: normalize_url(&params.url),
+        variants,
+        total_bytes,
+    })
+}
+
 /// POST /search — web search via SearXNG backend.
 pub async fn search_handler(
     State(state): State<Arc<AppState>>,
     Json(request): Json<SearchRequest>,
-) -> Result<Json<SearchResponse>, (StatusCode, String)> {
+) -> ApiResult<Json<SearchResponse>> {
+    validate(&request)?;
     let start = std::time::Instant::now();
     let num_results = request.num_results.unwrap_or(10).min(20);
 
     let search_url = format!("{}/search", state.search_backend_url.trim_end_matches('/'));
 
     let response = state
-        .http
+        .search_http
         .get(&search_url)
         .query(&[
             ("q", request.query.as_str()),
@@ -134,7 +336,7 @@ pub async fn search_handler(
         .await
         .map_err(|e| {
             metrics::counter!("fetch.search.errors").increment(1);
-            (
+            ApiError::new(
                 StatusCode::BAD_GATEWAY,
                 format!("Search backend request failed: {}", e),
             )
@@ -144,14 +346,14 @@ pub async fn search_handler(
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
         metrics::counter!("fetch.search.errors").increment(1);
-        return Err((
+        return Err(ApiError::new(
             StatusCode::BAD_GATEWAY,
             format!("Search backend returned {}: {}", status, body),
         ));
     }
 
     let searx: SearxResponse = response.json().await.map_err(|e| {
-        (
+        ApiError::new(
             StatusCode::BAD_GATEWAY,
             format!("Failed to parse search response: {}", e),
         )
@@ -193,17 +395,797 @@ struct SearxResult {
     content: String,
 }
 
-/// GET /sources — return the source catalog (empty until M5).
-pub async fn sources_handler() -> Json<Vec<SourceInfo>> {
-    Json(Vec::new())
+/// POST /sitemap — list a site's pages from its sitemap, following sitemap
+/// index files.
+pub async fn sitemap_handler(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<SitemapRequest>,
+) -> ApiResult<Json<SitemapResponse>> {
+    validate(&request)?;
+    let filter = SitemapFilter {
+        prefix: request.prefix,
+        contains: None,
+        since: request.since,
+        until: request.until,
+        limit: request.limit,
+    };
+    Ok(Json(crawl_sitemap(&state, &request.url, &filter).await?))
+}
+
+/// Crawl the sitemap at `url`. Every sitemap file read is checked against
+/// robots.txt and goes through the rate limiter.
+async fn crawl_sitemap(
+    state: &AppState,
+    url: &str,
+    filter: &SitemapFilter,
+) -> ApiResult<SitemapResponse> {
+    let start = std::time::Instant::now();
+    let domain = extract_domain(url);
+    let response = sitemap::crawl(url, filter, CrawlLimits::default(), |sitemap_url| {
+        fetch_sitemap(state, sitemap_url)
+    })
+    .await
+    .map_err(|e| {
+        metrics::counter!("fetch.sitemap.errors", "domain" => domain.clone()).increment(1);
+        ApiError::new(StatusCode::BAD_GATEWAY, e)
+    })?;
+
+    metrics::counter!("fetch.sitemap.files", "domain" => domain.clone())
+        .increment(response.sitemaps_fetched as u64);
+    metrics::counter!("fetch.sitemap.entries", "domain" => domain.clone())
+        .increment(response.entries.len() as u64);
+    if !response.warnings.is_empty() {
+        metrics::counter!("fetch.sitemap.warnings", "domain" => domain.clone())
+            .increment(response.warnings.len() as u64);
+    }
+    metrics::histogram!("fetch.sitemap.latency", "domain" => domain)
+        .record(start.elapsed().as_secs_f64());
+    Ok(response)
+}
+
+/// Fetch one sitemap file's raw body, if robots.txt allows it.
+async fn fetch_sitemap(state: &AppState, url: String) -> Result<Vec<u8>, String> {
+    let allowed = state
+        .robots
+        .allowed(&state.http, &state.rate_limiter, &url)
+        .await
+        .map_err(|e| format!("robots.txt unavailable: {}", e))?;
+    if !allowed {
+        metrics::counter!("fetch.sitemap.robots_disallowed", "domain" => extract_domain(&url))
+            .increment(1);
+        return Err("Disallowed by robots.txt".into());
+    }
+
+    state
+        .rate_limiter
+        .acquire(&extract_domain(&url), Duration::from_secs(120))
+        .await?;
+    let response = http_client::send(
+        Destination::Fetch,
+        state.http.get(&url).timeout(Duration::from_secs(60)),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_SITEMAP_BYTES as u64)
+    {
+        return Err(format!(
+            "Sitemap is larger than {} bytes",
+            MAX_SITEMAP_BYTES
+        ));
+    }
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    if body.len() > MAX_SITEMAP_BYTES {
+        return Err(format!(
+            "Sitemap is larger than {} bytes",
+            MAX_SITEMAP_BYTES
+        ));
+    }
+    Ok(body.to_vec())
+}
+
+/// GET /sources — the source catalog from FETCH_SOURCES_PATH, each source
+/// with its recent query health.
+pub async fn sources_handler(State(state): State<Arc<AppState>>) -> Json<Vec<SourceInfo>> {
+    let mut health = state.source_health.snapshot(chrono::Utc::now()).await;
+    let sources = state
+        .sources
+        .list()
+        .into_iter()
+        .map(|mut source| {
+            source.health = Some(health.remove(&source.id).unwrap_or_default());
+            source
+        })
+        .collect();
+    Json(sources)
+}
+
+/// POST /sources/{id}/query — query a catalog source. A sitemap source
+/// returns its matching pages as links (empty `content`) with
+/// `published_at` (the page's lastmod) and `changefreq` in `extra`.
+pub async fn source_query_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(request): Json<SourceQueryRequest>,
+) -> ApiResult<Json<SourceQueryResponse>> {
+    let Some(source) = state.sources.get(&id) else {
+        return Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            format!("Unknown source: {}", id),
+        ));
+    };
+
+    let filter = sitemap_filter(&request, source.prefix.as_deref())?;
+    let start = std::time::Instant::now();
+    let crawled = crawl_sitemap(&state, &source.url, &filter).await;
+    metrics::histogram!("fetch.source.latency", "source" => id.clone())
+        .record(start.elapsed().as_secs_f64());
+    let outcome = match &crawled {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            metrics::counter!("fetch.source.errors", "source" => id.clone()).increment(1);
+            Err(e.body.error.as_str())
+        }
+    };
+    state
+        .source_health
+        .record(&id, outcome, chrono::Utc::now())
+        .await;
+    let response = crawled?;
+    metrics::counter!("fetch.source.queries", "source" => id.clone()).increment(1);
+
+    let results: Vec<SourceQueryResult> = response
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let mut extra = serde_json::Map::new();
+            if let Some(lastmod) = entry.lastmod {
+                extra.insert(
+                    SOURCE_RESULT_PUBLISHED_AT.into(),
+                    serde_json::Value::String(lastmod.to_rfc3339()),
+                );
+            }
+            if let Some(changefreq) = entry.changefreq {
+                extra.insert(
+                    SOURCE_RESULT_CHANGEFREQ.into(),
+                    serde_json::Value::String(changefreq),
+                );
+            }
+            SourceQueryResult {
+                content: String::new(),
+                url: Some(entry.url),
+                title: None,
+                extra: (!extra.is_empty()).then_some(extra),
+            }
+        })
+        .collect();
+
+    Ok(Json(SourceQueryResponse {
+        metadata: SourceQueryMetadata {
+            source_id: id,
+            total_results: response.matched,
+            returned_results: results.len(),
+        },
+        results,
+    }))
 }
 
-fn extract_domain(url: &str) -> String {
-    url.split("//")
-        .nth(1)
-        .unwrap_or(url)
-        .split('/')
-        .next()
-        .unwrap_or("unknown")
-        .to_string()
+/// Read a sitemap source's query parameters: `prefix` (within the source's
+/// own prefix), `query` (URL substring), `since`, `until` (RFC 3339) and
+/// `limit`. Other parameters are ignored.
+fn sitemap_filter(
+    request: &SourceQueryRequest,
+    source_prefix: Option<&str>,
+) -> ApiResult<SitemapFilter> {
+    let mut errors = Vec::new();
+    let mut string = |name: &str| match request.params.get(name) {
+        None | Some(serde_json::Value::Null) => None,
+        Some(serde_json::Value::String(s)) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Some(_) => {
+            errors.push(FieldError::new(name, "must be a non-empty string"));
+            None
+        }
+    };
+    let prefix = string(SOURCE_QUERY_PREFIX);
+    let contains = string("query");
+    let since = string(SOURCE_QUERY_SINCE);
+    let until = string(SOURCE_QUERY_UNTIL);
+
+    let mut date = |name: &str, value: Option<String>| {
+        value.and_then(|v| match chrono::DateTime::parse_from_rfc3339(&v) {
+            Ok(date) => Some(date.with_timezone(&chrono::Utc)),
+            Err(_) => {
+                errors.push(FieldError::new(name, "must be an RFC 3339 timestamp"));
+                None
+            }
+        })
+    };
+    let since = date(SOURCE_QUERY_SINCE, since);
+    let until = date(SOURCE_QUERY_UNTIL, until);
+
+    let limit = match request.params.get(SOURCE_QUERY_LIMIT) {
+        None | Some(serde_json::Value::Null) => None,
+        Some(value) => match value.as_u64().filter(|l| *l > 0) {
+            Some(limit) => Some(limit as usize),
+            None => {
+                errors.push(FieldError::new(SOURCE_QUERY_LIMIT, "must be at least 1"));
+                None
+            }
+        },
+    };
+
+    // A narrower prefix is fine; one outside the source's section isn't.
+    let prefix = match (prefix, source_prefix) {
+        (Some(prefix), Some(source_prefix)) if !prefix.starts_with(source_prefix) => {
+            errors.push(FieldError::new(
+                SOURCE_QUERY_PREFIX,
+                format!("must start with {}", source_prefix),
+            ));
+            None
+        }
+        (prefix, source_prefix) => prefix.or(source_prefix.map(String::from)),
+    };
+    if matches!((since, until), (Some(since), Some(until)) if since > until) {
+        errors.push(FieldError::new(
+            SOURCE_QUERY_UNTIL,
+            "must not be before since",
+        ));
+    }
+
+    if !errors.is_empty() {
+        return Err(ApiError::invalid(errors));
+    }
+    Ok(SitemapFilter {
+        prefix,
+        contains,
+        since,
+        until,
+        limit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use autosint_common::api::fetch::{CacheTtl, SourceHealthStatus};
+    use axum::http::header;
+    use axum::routing::get;
+    use axum::Router;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use tokio::sync::RwLock;
+
+    use crate::browser::BrowserBackend;
+    use crate::cache::UrlCache;
+    use crate::cache_policy::{CachePolicy, TtlOverrides};
+    use crate::credentials::CredentialStore;
+    use crate::rate_limit::DomainRateLimiter;
+    use crate::robots::RobotsCache;
+    use crate::source_health::SourceHealthTracker;
+    use crate::sources::SourceCatalog;
+
+    async fn serve(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn state(browser_url: String) -> Arc<AppState> {
+        Arc::new(app_state(browser_url, CredentialStore::default()))
+    }
+
+    fn app_state(browser_url: String, credentials: CredentialStore) -> AppState {
+        let http = reqwest::Client::new();
+        AppState {
+            credentialed_http: http.clone(),
+            credentials: Arc::new(credentials),
+            browser: Some(Arc::new(BrowserBackend::new(
+                http.clone(),
+                browser_url,
+                10.0,
+                Duration::from_secs(5),
+            ))),
+            http,
+            cache: Arc::new(RwLock::new(UrlCache::new())),
+            cache_policy: CachePolicy {
+                default_ttl: Duration::from_secs(60),
+                min_ttl: Duration::from_secs(30),
+                max_ttl: Duration::from_secs(600),
+                overrides: TtlOverrides::default(),
+            },
+            rate_limiter: Arc::new(DomainRateLimiter::new(10.0)),
+            metrics_handle: PrometheusBuilder::new().build_recorder().handle(),
+            metrics_token: None,
+            search_backend_url: String::new(),
+            search_http: reqwest::Client::new(),
+            robots: Arc::new(RobotsCache::new()),
+            sources: Arc::new(SourceCatalog::default()),
+            source_health: Arc::new(SourceHealthTracker::default()),
+        }
+    }
+
+    fn browser_request(url: String) -> FetchRequest {
+        FetchRequest {
+            url,
+            options: Some(FetchOptions {
+                render: Some(RenderMode::Browser),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn plain_key(url: &str) -> CacheKey {
+        CacheKey::new(url, &FetchOptions::default())
+    }
+
+    fn browser_key(url: &str) -> CacheKey {
+        CacheKey::new(
+            url,
+            browser_request(url.to_string()).options.as_ref().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_browser_render_falls_back_to_plain_fetch() {
+        let site = serve(Router::new().route(
+            "/page",
+            get(|| async {
+                (
+                    [(header::CONTENT_TYPE, "text/html")],
+                    "<html><body><p>Static shell</p></body></html>",
+                )
+            }),
+        ))
+        .await;
+
+        // Nothing listens on the browser backend's port.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let state = state(dead);
+        let url = format!("{}/page", site);
+        let Json(response) = fetch_handler(
+            State(Arc::clone(&state)),
+            Json(browser_request(url.clone())),
+        )
+        .await
+        .unwrap();
+
+        assert!(!response.metadata.rendered);
+        assert_eq!(response.content, "Static shell");
+
+        // The fallback is cached as a plain fetch, not as a render.
+        let cache = state.cache.read().await;
+        assert!(cache.get(&plain_key(&url)).is_some());
+        assert!(cache.get(&browser_key(&url)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_browser_render_is_cached_separately() {
+        let browser = serve(Router::new().route(
+            "/content",
+            axum::routing::post(|| async {
+                "<html><body><script>x()</script><p>Rendered text</p></body></html>"
+            }),
+        ))
+        .await;
+
+        let state = state(browser);
+        let url = "https://spa.example.com/".to_string();
+        let Json(response) = fetch_handler(
+            State(Arc::clone(&state)),
+            Json(browser_request(url.clone())),
+        )
+        .await
+        .unwrap();
+
+        assert!(response.metadata.rendered);
+        assert_eq!(response.content, "Rendered text");
+
+        let cache = state.cache.read().await;
+        assert!(cache.get(&browser_key(&url)).is_some());
+        assert!(cache.get(&plain_key(&url)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_request_renders_error_envelope() {
+        let state = state(String::new());
+        let request = FetchRequest {
+            url: "ftp://example.com/".into(),
+            options: Some(FetchOptions {
+                timeout_ms: Some(0),
+                ..Default::default()
+            }),
+        };
+
+        let response = fetch_handler(State(state), Json(request))
+            .await
+            .unwrap_err()
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        let fields: Vec<&str> = error.fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, ["url", "options.timeout_ms"]);
+        assert!(
+            error.error.starts_with("Invalid request: url: "),
+            "{}",
+            error.error
+        );
+    }
+
+    /// A source that answers only requests carrying one of the test
+    /// credentials, echoing which one it saw.
+    async fn private_site(hits: Arc<std::sync::atomic::AtomicUsize>) -> String {
+        serve(Router::new().route(
+            "/report",
+            get(move |headers: axum::http::HeaderMap| async move {
+                hits.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+                let holder = match (
+                    header("authorization"),
+                    header("x-api-key"),
+                    header("cookie"),
+                ) {
+                    (Some("Bearer t0ken"), _, _) => "bearer",
+                    (Some("Bearer other"), _, _) => "other bearer",
+                    (Some("Basic cmVhZGVyOmh1bnRlcjI="), _, _) => "basic",
+                    (_, Some("k3y"), _) => "header",
+                    (_, _, Some("session=abc")) => "cookie",
+                    _ => return (StatusCode::UNAUTHORIZED, "subscribers only".to_string()),
+                };
+                (StatusCode::OK, format!("licensed for {}", holder))
+            }),
+        ))
+        .await
+    }
+
+    fn credentials(auth: &str, secret_env: &str) -> CredentialStore {
+        let header = if auth == "header" {
+            "header = \"X-Api-Key\"\n"
+        } else {
+            ""
+        };
+        let text = format!(
+            "[[credentials]]\ndomain = \"127.0.0.1\"\nauth = \"{}\"\n{}secret_env = \"{}\"\nallow_http = true",
+            auth, header, secret_env
+        );
+        CredentialStore::parse(&text, |var| {
+            match var {
+                "TOKEN" => Some("t0ken"),
+                "OTHER_TOKEN" => Some("other"),
+                "BASIC" => Some("reader:hunter2"),
+                "API_KEY" => Some("k3y"),
+                "COOKIE" => Some("session=abc"),
+                _ => None,
+            }
+            .map(String::from)
+        })
+        .unwrap()
+    }
+
+    fn plain_request(url: &str) -> Json<FetchRequest> {
+        Json(FetchRequest {
+            url: url.to_string(),
+            options: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_each_auth_type_is_applied() {
+        let site = private_site(Arc::default()).await;
+        let url = format!("{}/report", site);
+
+        for (auth, secret_env) in [
+            ("bearer", "TOKEN"),
+            ("basic", "BASIC"),
+            ("header", "API_KEY"),
+            ("cookie", "COOKIE"),
+        ] {
+            let state = Arc::new(app_state(String::new(), credentials(auth, secret_env)));
+            let Json(response) = fetch_handler(State(state), plain_request(&url))
+                .await
+                .unwrap();
+            assert_eq!(response.metadata.status_code, 200, "{}", auth);
+            assert_eq!(response.content, format!("licensed for {}", auth));
+            assert!(response.metadata.authenticated);
+        }
+
+        let Json(response) = fetch_handler(State(state(String::new())), plain_request(&url))
+            .await
+            .unwrap();
+        assert_eq!(response.metadata.status_code, 401);
+        assert!(!response.metadata.authenticated);
+    }
+
+    #[tokio::test]
+    async fn test_credentialed_fetches_are_cached_per_credential() {
+        let hits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let site = private_site(Arc::clone(&hits)).await;
+        let url = format!("{}/report", site);
+
+        // Three services sharing one cache: two credentials and none.
+        let with_token = Arc::new(app_state(String::new(), credentials("bearer", "TOKEN")));
+        let with_other = Arc::new(AppState {
+            cache: Arc::clone(&with_token.cache),
+            ..app_state(String::new(), credentials("bearer", "OTHER_TOKEN"))
+        });
+        let anonymous = Arc::new(AppState {
+            cache: Arc::clone(&with_token.cache),
+            ..app_state(String::new(), CredentialStore::default())
+        });
+
+        let fetch = |state: &Arc<AppState>| {
+            let state = Arc::clone(state);
+            let url = url.clone();
+            async move {
+                let Json(response) = fetch_handler(State(state), plain_request(&url))
+                    .await
+                    .unwrap();
+                response
+            }
+        };
+
+        let first = fetch(&with_token).await;
+        assert_eq!(first.content, "licensed for bearer");
+        assert!(!first.metadata.cached);
+
+        // Neither another credential nor an anonymous fetch is served the
+        // licensed copy.
+        let other = fetch(&with_other).await;
+        assert_eq!(other.content, "licensed for other bearer");
+        assert!(!other.metadata.cached);
+        let public = fetch(&anonymous).await;
+        assert_eq!(public.metadata.status_code, 401);
+        assert!(!public.metadata.cached && !public.metadata.authenticated);
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        let again = fetch(&with_token).await;
+        assert_eq!(again.content, "licensed for bearer");
+        assert!(again.metadata.cached && again.metadata.authenticated);
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        // The cache key names the credential by fingerprint only.
+        let variants = with_token.cache.read().await.variants(&url);
+        assert_eq!(variants.len(), 3);
+        for variant in &variants {
+            assert!(!variant.fingerprint.contains("t0ken"));
+            assert!(!variant.fingerprint.contains("other"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl_follows_origin_headers() {
+        let hits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&hits);
+        let site = serve(
+            Router::new()
+                .route(
+                    "/live",
+                    get(move || async move {
+                        counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        ([(header::CACHE_CONTROL, "no-store")], "breaking")
+                    }),
+                )
+                .route(
+                    "/reference",
+                    get(|| async { ([(header::CACHE_CONTROL, "max-age=86400")], "statute") }),
+                )
+                .route("/plain", get(|| async { "no hints" })),
+        )
+        .await;
+        let state = state(String::new());
+        let fetch = |path: &str| {
+            let state = Arc::clone(&state);
+            let url = format!("{}{}", site, path);
+            async move {
+                let Json(response) = fetch_handler(State(state), plain_request(&url))
+                    .await
+                    .unwrap();
+                response.metadata
+            }
+        };
+
+        // no-store is never cached.
+        assert_eq!(fetch("/live").await.cache_ttl, None);
+        assert!(!fetch("/live").await.cached);
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // An origin max-age is clamped to the ceiling, and a cache hit
+        // reports the TTL it was stored with.
+        let ttl = CacheTtl {
+            seconds: 600,
+            source: CacheTtlSource::Origin,
+        };
+        assert_eq!(fetch("/reference").await.cache_ttl, Some(ttl));
+        let again = fetch("/reference").await;
+        assert!(again.cached);
+        assert_eq!(again.cache_ttl, Some(ttl));
+
+        assert_eq!(
+            fetch("/plain").await.cache_ttl,
+            Some(CacheTtl {
+                seconds: 60,
+                source: CacheTtlSource::Default,
+            })
+        );
+    }
+
+    /// A site with a sitemap index over a urlset, and a
+    /// robots.txt that closes /private/.
+    async fn sitemap_site() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let index = format!(
+            "<sitemapindex><sitemap><loc>{0}/sitemap-press.xml</loc></sitemap>\
+             <sitemap><loc>{0}/private/sitemap.xml</loc></sitemap></sitemapindex>",
+            base
+        );
+        let press = format!(
+            "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\
+             <url><loc>{0}/press/old</loc><lastmod>2025-05-01</lastmod></url>\
+             <url><loc>{0}/press/new</loc><lastmod>2026-02-01</lastmod><changefreq>never</changefreq></url>\
+             <url><loc>{0}/about</loc><lastmod>2026-03-01</lastmod></url></urlset>",
+            base
+        );
+        let app = Router::new()
+            .route(
+                "/robots.txt",
+                get(|| async { "User-agent: *\nDisallow: /private/\n" }),
+            )
+            .route("/sitemap.xml", get(move || async move { index }))
+            .route("/sitemap-press.xml", get(move || async move { press }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_sitemap_source_query() {
+        let site = sitemap_site().await;
+        let catalog = SourceCatalog::parse(&format!(
+            "[[sitemaps]]\nid = \"press\"\nname = \"Press\"\ndescription = \"d\"\n\
+             url = \"{0}/sitemap.xml\"\nprefix = \"{0}/press/\"",
+            site
+        ))
+        .unwrap();
+        let state = Arc::new(AppState {
+            sources: Arc::new(catalog),
+            ..app_state(String::new(), CredentialStore::default())
+        });
+        let query = |params: serde_json::Value| {
+            let state = Arc::clone(&state);
+            async move {
+                source_query_handler(
+                    State(state),
+                    Path("press".to_string()),
+                    Json(serde_json::from_value(params).unwrap()),
+                )
+                .await
+            }
+        };
+
+        // Only pages under the source's prefix, newest first, as links.
+        let Json(response) = query(serde_json::json!({})).await.unwrap();
+        assert_eq!(response.metadata.total_results, 2);
+        let first = &response.results[0];
+        assert_eq!(
+            first.url.as_deref(),
+            Some(format!("{}/press/new", site).as_str())
+        );
+        assert!(first.content.is_empty());
+        let extra = first.extra.as_ref().unwrap();
+        assert_eq!(
+            extra[SOURCE_RESULT_PUBLISHED_AT],
+            "2026-02-01T00:00:00+00:00"
+        );
+        assert_eq!(extra[SOURCE_RESULT_CHANGEFREQ], "never");
+
+        let Json(response) = query(serde_json::json!({"since": "2026-01-01T00:00:00Z"}))
+            .await
+            .unwrap();
+        assert_eq!(response.results.len(), 1);
+
+        // The robots-disallowed child sitemap is never fetched, and the
+        // crawl carries on without it.
+        let Json(direct) = sitemap_handler(
+            State(Arc::clone(&state)),
+            Json(SitemapRequest {
+                url: format!("{}/sitemap.xml", site),
+                prefix: None,
+                since: None,
+                until: None,
+                limit: None,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(direct.sitemaps_fetched, 2);
+        assert_eq!(direct.entries.len(), 3);
+        assert!(direct.warnings[0].contains("Disallowed by robots.txt"));
+
+        let error = query(serde_json::json!({"prefix": "https://elsewhere.example/", "limit": 0}))
+            .await
+            .unwrap_err();
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        let fields: Vec<&str> = error.body.fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, ["limit", "prefix"]);
+
+        let unknown = source_query_handler(
+            State(state),
+            Path("nope".to_string()),
+            Json(serde_json::from_value(serde_json::json!({})).unwrap()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(unknown.status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_source_queries_feed_source_health() {
+        let site = sitemap_site().await;
+        let catalog = SourceCatalog::parse(&format!(
+            "[[sitemaps]]\nid = \"press\"\nname = \"Press\"\ndescription = \"d\"\n\
+             url = \"{0}/sitemap.xml\"\n\
+             [[sitemaps]]\nid = \"gone\"\nname = \"Gone\"\ndescription = \"d\"\n\
+             url = \"{0}/moved.xml\"\n\
+             [[sitemaps]]\nid = \"idle\"\nname = \"Idle\"\ndescription = \"d\"\n\
+             url = \"{0}/idle.xml\"",
+            site
+        ))
+        .unwrap();
+        let state = Arc::new(AppState {
+            sources: Arc::new(catalog),
+            ..app_state(String::new(), CredentialStore::default())
+        });
+        let query = |id: &str, params: serde_json::Value| {
+            source_query_handler(
+                State(Arc::clone(&state)),
+                Path(id.to_string()),
+                Json(serde_json::from_value(params).unwrap()),
+            )
+        };
+
+        let Json(pages) = query("press", serde_json::json!({})).await.unwrap();
+        assert_eq!(pages.metadata.source_id, "press");
+        for _ in 0..3 {
+            let error = query("gone", serde_json::json!({})).await.unwrap_err();
+            assert_eq!(error.status, StatusCode::BAD_GATEWAY);
+        }
+        // A rejected request never reached the source, so doesn't count.
+        query("press", serde_json::json!({"limit": 0}))
+            .await
+            .unwrap_err();
+
+        let Json(listed) = sources_handler(State(Arc::clone(&state))).await;
+        let health = |id: &str| {
+            listed
+                .iter()
+                .find(|s| s.id == id)
+                .and_then(|s| s.health.clone())
+                .unwrap()
+        };
+        let press = health("press");
+        assert_eq!(press.status, SourceHealthStatus::Healthy);
+        assert_eq!(press.queries_7d, 1);
+        assert!(press.last_success_at.is_some());
+
+        let gone = health("gone");
+        assert_eq!(gone.status, SourceHealthStatus::Failing);
+        assert_eq!(gone.success_rate_7d, Some(0.0));
+        assert_eq!(gone.last_success_at, None);
+        assert!(gone.last_error.unwrap().contains("404"));
+
+        let idle = health("idle");
+        assert_eq!(idle.status, SourceHealthStatus::Unknown);
+        assert_eq!(idle.queries_7d, 0);
+    }
 }
@@ -0,0 +1,744 @@
+//! Sitemap parsing and crawling for POST /sitemap and sitemap sources.
+//!
+//! Sitemaps in the wild are often off-spec: no namespace or the wrong one,
+//! dates in any ISO 8601 shape, stray markup, truncated files. Parsing is
+//! lenient — elements are matched by local name, a bad entry is skipped with
+//! a warning, and a file that stops parsing keeps what was read before the
+//! error. Plain-text sitemaps (one URL per line) are accepted too.
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::io::Read;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use autosint_common::api::fetch::{SitemapEntry, SitemapResponse};
+
+/// Largest sitemap accepted, compressed or not (the protocol's own limit).
+pub const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+
+/// Entries returned when the request sets no limit.
+pub const DEFAULT_LIMIT: usize = 100;
+
+/// Most entries returned by one request.
+pub const MAX_LIMIT: usize = 1000;
+
+/// Per-entry warnings kept per request; the rest are counted.
+const MAX_WARNINGS: usize = 20;
+
+/// How far a crawl follows sitemap index files.
+#[derive(Clone, Copy, Debug)]
+pub struct CrawlLimits {
+    /// Index levels followed below the requested sitemap.
+    pub max_depth: usize,
+    /// Sitemap files read in total.
+    pub max_sitemaps: usize,
+}
+
+impl Default for CrawlLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            max_sitemaps: 50,
+        }
+    }
+}
+
+/// Which entries a crawl returns.
+#[derive(Clone, Debug, Default)]
+pub struct SitemapFilter {
+    pub prefix: Option<String>,
+    /// URL substring, case-insensitive.
+    pub contains: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl SitemapFilter {
+    fn matches(&self, entry: &SitemapEntry) -> bool {
+        if self
+            .prefix
+            .as_deref()
+            .is_some_and(|p| !entry.url.starts_with(p))
+        {
+            return false;
+        }
+        if self
+            .contains
+            .as_deref()
+            .is_some_and(|c| !entry.url.to_lowercase().contains(&c.to_lowercase()))
+        {
+            return false;
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        entry.lastmod.is_some_and(|lastmod| {
+            self.since.is_none_or(|since| lastmod >= since)
+                && self.until.is_none_or(|until| lastmod <= until)
+        })
+    }
+}
+
+/// A sitemap listed by a sitemap index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChildSitemap {
+    pub url: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// One parsed sitemap file.
+#[derive(Debug, Default)]
+pub struct ParsedSitemap {
+    pub entries: Vec<SitemapEntry>,
+    /// Set for a sitemap index.
+    pub children: Vec<ChildSitemap>,
+    pub warnings: Vec<String>,
+}
+
+/// Parse a sitemap body: a `<urlset>`, a `<sitemapindex>`, or a plain list of
+/// URLs, gzipped or not.
+pub fn parse(body: &[u8]) -> Result<ParsedSitemap, String> {
+    let body = decompress(body)?;
+    let text = String::from_utf8_lossy(&body);
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('<') {
+        Ok(parse_xml(trimmed))
+    } else {
+        Ok(parse_text(trimmed))
+    }
+}
+
+/// Gunzip `body` if it starts with the gzip magic bytes. Servers send
+/// `sitemap.xml.gz` with all sorts of content types, so the bytes decide.
+fn decompress(body: &[u8]) -> Result<Vec<u8>, String> {
+    if !body.starts_with(&[0x1f, 0x8b]) {
+        return Ok(body.to_vec());
+    }
+    let mut out = Vec::new();
+    flate2::read::GzDecoder::new(body)
+        .take(MAX_SITEMAP_BYTES as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("Invalid gzip data: {}", e))?;
+    if out.len() > MAX_SITEMAP_BYTES {
+        return Err(format!(
+            "Sitemap is larger than {} bytes uncompressed",
+            MAX_SITEMAP_BYTES
+        ));
+    }
+    Ok(out)
+}
+
+/// The `<url>` or `<sitemap>` element being read.
+#[derive(Default)]
+struct Item {
+    loc: Option<String>,
+    lastmod: Option<String>,
+    changefreq: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Loc,
+    Lastmod,
+    Changefreq,
+}
+
+fn parse_xml(text: &str) -> ParsedSitemap {
+    let mut parsed = ParsedSitemap::default();
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    // Mismatched end tags are common in hand-rolled sitemaps; the element
+    // structure here is flat enough not to need them checked.
+    reader.config_mut().check_end_names = false;
+
+    // (is_index_child, item) for the open <url> or <sitemap>.
+    let mut item: Option<(bool, Item)> = None;
+    let mut field: Option<Field> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                name @ (b"url" | b"sitemap") => {
+                    // An entry whose end tag went missing ends where the next begins.
+                    if let Some((is_child, open)) = item.take() {
+                        finish_item(&mut parsed, is_child, open);
+                    }
+                    item = Some((name == b"sitemap", Item::default()));
+                }
+                b"loc" => field = Some(Field::Loc),
+                b"lastmod" => field = Some(Field::Lastmod),
+                b"changefreq" => field = Some(Field::Changefreq),
+                _ => field = None,
+            },
+            Ok(Event::Text(t)) => {
+                let value = match t.unescape() {
+                    Ok(value) => value.into_owned(),
+                    Err(_) => String::from_utf8_lossy(&t).into_owned(),
+                };
+                set_field(&mut item, field, value);
+            }
+            Ok(Event::CData(c)) => {
+                let value = String::from_utf8_lossy(&c).into_owned();
+                set_field(&mut item, field, value);
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"url" | b"sitemap" => {
+                    if let Some((is_child, item)) = item.take() {
+                        finish_item(&mut parsed, is_child, item);
+                    }
+                }
+                _ => field = None,
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => {
+                parsed.warnings.push(format!(
+                    "XML error at byte {}, kept the {} entries before it: {}",
+                    reader.error_position(),
+                    parsed.entries.len() + parsed.children.len(),
+                    e
+                ));
+                break;
+            }
+        }
+    }
+    // An entry cut off by a truncated file is still usable if its <loc> was read.
+    if let Some((is_child, item)) = item.take() {
+        finish_item(&mut parsed, is_child, item);
+    }
+    parsed
+}
+
+fn set_field(item: &mut Option<(bool, Item)>, field: Option<Field>, value: String) {
+    let (Some((_, item)), Some(field)) = (item.as_mut(), field) else {
+        return;
+    };
+    let slot = match field {
+        Field::Loc => &mut item.loc,
+        Field::Lastmod => &mut item.lastmod,
+        Field::Changefreq => &mut item.changefreq,
+    };
+    // Text split around an entity or comment arrives in pieces.
+    slot.get_or_insert_with(String::new).push_str(&value);
+}
+
+fn finish_item(parsed: &mut ParsedSitemap, is_child: bool, item: Item) {
+    let Some(loc) = item.loc.as_deref().map(str::trim).filter(|l| !l.is_empty()) else {
+        parsed
+            .warnings
+            .push("Skipped an entry without <loc>".into());
+        return;
+    };
+    let Some(url) = valid_url(loc) else {
+        parsed
+            .warnings
+            .push(format!("Skipped an entry with an invalid URL: {}", loc));
+        return;
+    };
+    let lastmod = item.lastmod.as_deref().map(str::trim).and_then(|raw| {
+        let parsed_date = parse_lastmod(raw);
+        if parsed_date.is_none() {
+            parsed.warnings.push(format!(
+                "Unreadable lastmod {:?} for {}; left undated",
+                raw, url
+            ));
+        }
+        parsed_date
+    });
+    if is_child {
+        parsed.children.push(ChildSitemap { url, lastmod });
+    } else {
+        let changefreq = item
+            .changefreq
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty());
+        parsed.entries.push(SitemapEntry {
+            url,
+            lastmod,
+            changefreq,
+        });
+    }
+}
+
+/// A text sitemap: one URL per line. Lines that aren't URLs are skipped.
+fn parse_text(text: &str) -> ParsedSitemap {
+    let mut parsed = ParsedSitemap::default();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        match valid_url(line) {
+            Some(url) => parsed.entries.push(SitemapEntry {
+                url,
+                lastmod: None,
+                changefreq: None,
+            }),
+            None => parsed
+                .warnings
+                .push(format!("Skipped a line that is not a URL: {}", line)),
+        }
+    }
+    parsed
+}
+
+fn valid_url(loc: &str) -> Option<String> {
+    let url = reqwest::Url::parse(loc).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| loc.to_string())
+}
+
+/// Read a lastmod in any of the W3C Datetime profiles (`2026`, `2026-03`,
+/// `2026-03-04`, with a time with or without seconds and fraction), plus the
+/// usual deviations: no time zone (taken as UTC), a space instead of `T`, or
+/// an RFC 2822 date.
+pub fn parse_lastmod(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M%:z",
+        "%Y-%m-%dT%H:%M%#z",
+        "%Y-%m-%d %H:%M:%S%:z",
+    ] {
+        if let Ok(date) = DateTime::parse_from_str(raw, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(raw) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(date.and_utc());
+        }
+    }
+    let date = match raw.len() {
+        4 => format!("{}-01-01", raw),
+        7 => format!("{}-01", raw),
+        _ => raw.to_string(),
+    };
+    NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+}
+
+/// Read the sitemap at `root` and every sitemap it indexes, up to `limits`,
+/// and return the entries matching `filter`, newest first. `fetch` returns a
+/// sitemap's raw body. A failure reading `root` fails the crawl; a failure
+/// reading an indexed sitemap is a warning.
+pub async fn crawl<F, Fut>(
+    root: &str,
+    filter: &SitemapFilter,
+    limits: CrawlLimits,
+    mut fetch: F,
+) -> Result<SitemapResponse, String>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    let mut queue = VecDeque::from([(root.to_string(), 0usize)]);
+    let mut visited = HashSet::from([root.to_string()]);
+    let mut entries: Vec<SitemapEntry> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut dropped_warnings = 0usize;
+    let mut fetched = 0usize;
+    let mut skipped = 0usize;
+    let mut seen_urls = HashSet::new();
+
+    while let Some((url, depth)) = queue.pop_front() {
+        if fetched >= limits.max_sitemaps {
+            skipped += 1;
+            continue;
+        }
+        let parsed = match fetch(url.clone()).await.and_then(|body| parse(&body)) {
+            Ok(parsed) => parsed,
+            Err(e) if fetched == 0 => return Err(e),
+            Err(e) => {
+                warnings.push(format!("Could not read {}: {}", url, e));
+                continue;
+            }
+        };
+        fetched += 1;
+
+        for warning in parsed.warnings {
+            if warnings.len() < MAX_WARNINGS {
+                warnings.push(format!("{}: {}", url, warning));
+            } else {
+                dropped_warnings += 1;
+            }
+        }
+
+        for child in parsed.children {
+            if !visited.insert(child.url.clone()) {
+                continue;
+            }
+            // A child sitemap untouched since `since` can't hold anything newer.
+            let stale = matches!((filter.since, child.lastmod), (Some(since), Some(lastmod)) if lastmod < since);
+            if depth + 1 > limits.max_depth || stale {
+                skipped += 1;
+                continue;
+            }
+            queue.push_back((child.url, depth + 1));
+        }
+
+        entries.extend(
+            parsed
+                .entries
+                .into_iter()
+                .filter(|entry| filter.matches(entry))
+                .filter(|entry| seen_urls.insert(entry.url.clone())),
+        );
+    }
+
+    if dropped_warnings > 0 {
+        warnings.push(format!("{} more warnings not shown", dropped_warnings));
+    }
+
+    // Newest first, undated last.
+    entries.sort_by_key(|e| std::cmp::Reverse(e.lastmod));
+    let matched = entries.len();
+    entries.truncate(filter.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT));
+
+    Ok(SitemapResponse {
+        url: root.to_string(),
+        entries,
+        matched,
+        sitemaps_fetched: fetched,
+        sitemaps_skipped: skipped,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://mfa.example/press/2026/03/statement</loc>
+    <lastmod>2026-03-04T10:15:00+02:00</lastmod>
+    <changefreq>Monthly</changefreq>
+  </url>
+  <url>
+    <loc>https://mfa.example/press/2026/01/visit</loc>
+    <lastmod>2026-01-20</lastmod>
+  </url>
+  <url>
+    <loc>https://mfa.example/about</loc>
+    <lastmod>2025-06</lastmod>
+  </url>
+  <url>
+    <loc>https://mfa.example/press/archive</loc>
+  </url>
+</urlset>"#;
+
+    /// No namespace, a bad date, a URL that isn't one, an entry without a
+    /// <loc>, and a file cut off mid-entry.
+    const MALFORMED: &str = r#"<urlset>
+  <url><loc>https://mfa.example/press/a</loc><lastmod>yesterday</lastmod></url>
+  <url><loc>not a url</loc></url>
+  <url><lastmod>2026-01-01</lastmod></url>
+  <url><loc>https://mfa.example/press/b?id=1&amp;lang=en</loc><lastmod>2026-02-01 09:00:00</lastmod></url>
+  <url><loc>https://mfa.example/press/c</loc></oops>
+  <url><loc>https://mfa.example/press/d</loc><lastmod>2026-02-02</lastmod"#;
+
+    fn index(children: &[(&str, Option<&str>)]) -> String {
+        let mut xml =
+            String::from(r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+        for (url, lastmod) in children {
+            xml.push_str(&format!("<sitemap><loc>{}</loc>", url));
+            if let Some(lastmod) = lastmod {
+                xml.push_str(&format!("<lastmod>{}</lastmod>", lastmod));
+            }
+            xml.push_str("</sitemap>");
+        }
+        xml.push_str("</sitemapindex>");
+        xml
+    }
+
+    fn gzip(text: &str) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn date(raw: &str) -> DateTime<Utc> {
+        raw.parse().unwrap()
+    }
+
+    async fn crawl_site(
+        site: &HashMap<String, Vec<u8>>,
+        root: &str,
+        filter: SitemapFilter,
+        limits: CrawlLimits,
+    ) -> Result<SitemapResponse, String> {
+        crawl(root, &filter, limits, |url| {
+            let body = site.get(&url).cloned().ok_or_else(|| "404".to_string());
+            async move { body }
+        })
+        .await
+    }
+
+    fn urls(response: &SitemapResponse) -> Vec<&str> {
+        response.entries.iter().map(|e| e.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_urlset() {
+        let parsed = parse(URLSET.as_bytes()).unwrap();
+        assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+        assert!(parsed.children.is_empty());
+        assert_eq!(parsed.entries.len(), 4);
+        assert_eq!(
+            parsed.entries[0],
+            SitemapEntry {
+                url: "https://mfa.example/press/2026/03/statement".into(),
+                lastmod: Some(date("2026-03-04T08:15:00Z")),
+                changefreq: Some("monthly".into()),
+            }
+        );
+        assert_eq!(
+            parsed.entries[1].lastmod,
+            Some(date("2026-01-20T00:00:00Z"))
+        );
+        assert_eq!(
+            parsed.entries[2].lastmod,
+            Some(date("2025-06-01T00:00:00Z"))
+        );
+        assert_eq!(parsed.entries[3].lastmod, None);
+    }
+
+    #[test]
+    fn test_parse_gzipped() {
+        let parsed = parse(&gzip(URLSET)).unwrap();
+        assert_eq!(parsed.entries.len(), 4);
+
+        let mut corrupt = gzip(URLSET);
+        corrupt.truncate(20);
+        assert!(parse(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_parse_malformed_leniently() {
+        let parsed = parse(MALFORMED.as_bytes()).unwrap();
+        let urls: Vec<&str> = parsed.entries.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://mfa.example/press/a",
+                "https://mfa.example/press/b?id=1&lang=en",
+                "https://mfa.example/press/c",
+                "https://mfa.example/press/d",
+            ]
+        );
+        // The bad date leaves its entry undated rather than dropping it.
+        assert_eq!(parsed.entries[0].lastmod, None);
+        assert_eq!(
+            parsed.entries[1].lastmod,
+            Some(date("2026-02-01T09:00:00Z"))
+        );
+
+        let warnings = parsed.warnings.join("\n");
+        assert!(warnings.contains("\"yesterday\""), "{}", warnings);
+        assert!(warnings.contains("not a url"), "{}", warnings);
+        assert!(warnings.contains("without <loc>"), "{}", warnings);
+    }
+
+    #[test]
+    fn test_parse_index_and_text() {
+        let parsed = parse(
+            index(&[
+                ("https://mfa.example/sitemap-press.xml", Some("2026-03-01")),
+                ("https://mfa.example/sitemap-pages.xml", None),
+            ])
+            .as_bytes(),
+        )
+        .unwrap();
+        assert!(parsed.entries.is_empty());
+        assert_eq!(
+            parsed.children,
+            [
+                ChildSitemap {
+                    url: "https://mfa.example/sitemap-press.xml".into(),
+                    lastmod: Some(date("2026-03-01T00:00:00Z")),
+                },
+                ChildSitemap {
+                    url: "https://mfa.example/sitemap-pages.xml".into(),
+                    lastmod: None,
+                },
+            ]
+        );
+
+        let parsed = parse(b"https://mfa.example/a\n\n# comment\nhttps://mfa.example/b\n").unwrap();
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_lastmod_variants() {
+        for (raw, expected) in [
+            ("2026-03-04T10:15:30.5+02:00", "2026-03-04T08:15:30.5Z"),
+            ("2026-03-04T10:15+02:00", "2026-03-04T08:15:00Z"),
+            ("2026-03-04T10:15:30", "2026-03-04T10:15:30Z"),
+            ("2026-03-04 10:15:30", "2026-03-04T10:15:30Z"),
+            ("Wed, 04 Mar 2026 10:15:30 GMT", "2026-03-04T10:15:30Z"),
+            ("2026-03-04", "2026-03-04T00:00:00Z"),
+            ("2026", "2026-01-01T00:00:00Z"),
+        ] {
+            assert_eq!(parse_lastmod(raw), Some(date(expected)), "{}", raw);
+        }
+        for raw in ["", "yesterday", "2026-13-01", "04/03/2026"] {
+            assert_eq!(parse_lastmod(raw), None, "{}", raw);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crawl_index_with_filters() {
+        let site = HashMap::from([
+            (
+                "https://mfa.example/sitemap.xml".to_string(),
+                index(&[
+                    (
+                        "https://mfa.example/sitemap-2026.xml.gz",
+                        Some("2026-03-04"),
+                    ),
+                    ("https://mfa.example/sitemap-2024.xml", Some("2024-12-31")),
+                    ("https://mfa.example/sitemap-missing.xml", None),
+                ])
+                .into_bytes(),
+            ),
+            (
+                "https://mfa.example/sitemap-2026.xml.gz".to_string(),
+                gzip(URLSET),
+            ),
+            (
+                "https://mfa.example/sitemap-2024.xml".to_string(),
+                b"https://mfa.example/press/2024/old\n".to_vec(),
+            ),
+        ]);
+        let root = "https://mfa.example/sitemap.xml";
+
+        let all = crawl_site(
+            &site,
+            root,
+            SitemapFilter::default(),
+            CrawlLimits::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(all.sitemaps_fetched, 3);
+        assert_eq!(all.matched, 5);
+        // Newest first, undated last.
+        assert_eq!(
+            all.entries[0].url,
+            "https://mfa.example/press/2026/03/statement"
+        );
+        assert!(all.entries[3..].iter().all(|e| e.lastmod.is_none()));
+        assert!(all.warnings[0].contains("sitemap-missing.xml"));
+
+        // The 2024 sitemap isn't read for a range starting in 2026, and undated
+        // pages are left out of a date range.
+        let filtered = crawl_site(
+            &site,
+            root,
+            SitemapFilter {
+                prefix: Some("https://mfa.example/press/".into()),
+                since: Some(date("2026-01-01T00:00:00Z")),
+                ..Default::default()
+            },
+            CrawlLimits::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            urls(&filtered),
+            [
+                "https://mfa.example/press/2026/03/statement",
+                "https://mfa.example/press/2026/01/visit",
+            ]
+        );
+        assert_eq!(filtered.sitemaps_fetched, 2);
+        assert_eq!(filtered.sitemaps_skipped, 1);
+
+        let limited = crawl_site(
+            &site,
+            root,
+            SitemapFilter {
+                contains: Some("VISIT".into()),
+                until: Some(date("2026-02-01T00:00:00Z")),
+                limit: Some(1),
+                ..Default::default()
+            },
+            CrawlLimits::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(urls(&limited), ["https://mfa.example/press/2026/01/visit"]);
+
+        let missing = crawl_site(
+            &site,
+            "https://mfa.example/none.xml",
+            SitemapFilter::default(),
+            CrawlLimits::default(),
+        )
+        .await;
+        assert_eq!(missing.unwrap_err(), "404");
+    }
+
+    #[tokio::test]
+    async fn test_crawl_recursion_is_capped() {
+        // Each level's index lists the next level and itself (a cycle).
+        let url = |level: usize| format!("https://deep.example/level-{}.xml", level);
+        let site: HashMap<String, Vec<u8>> = (0..10)
+            .map(|level| {
+                let xml = format!(
+                    r#"<sitemapindex><sitemap><loc>{}</loc></sitemap><sitemap><loc>{}</loc></sitemap></sitemapindex>"#,
+                    url(level + 1),
+                    url(level),
+                );
+                (url(level), xml.into_bytes())
+            })
+            .collect();
+
+        let response = crawl_site(
+            &site,
+            &url(0),
+            SitemapFilter::default(),
+            CrawlLimits {
+                max_depth: 3,
+                max_sitemaps: 50,
+            },
+        )
+        .await
+        .unwrap();
+        // The root and three levels below it; level 4 is skipped.
+        assert_eq!(response.sitemaps_fetched, 4);
+        assert_eq!(response.sitemaps_skipped, 1);
+
+        // The file cap applies across the whole crawl.
+        let capped = crawl_site(
+            &site,
+            &url(0),
+            SitemapFilter::default(),
+            CrawlLimits {
+                max_depth: 10,
+                max_sitemaps: 2,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(capped.sitemaps_fetched, 2);
+        assert_eq!(capped.sitemaps_skipped, 1);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use autosint_common::api::fetch::{SourceHealth, SourceHealthStatus};
+
+/// Days of history a source's health is judged on: the current UTC day and
+/// the six before it.
+pub const WINDOW_DAYS: u64 = 7;
+
+/// Success rate at or above which a source is healthy.
+const HEALTHY_RATE: f64 = 0.9;
+/// Success rate below which a source is failing.
+const FAILING_RATE: f64 = 0.5;
+/// Fewer queries than this are too few to call a source failing; a low
+/// success rate over them reads as degraded.
+const MIN_QUERIES_TO_FAIL: u64 = 3;
+
+/// Longest `last_error` message kept.
+const MAX_ERROR_CHARS: usize = 300;
+
+/// Per-source outcomes of catalog source queries, counted per UTC day over a
+/// rolling [`WINDOW_DAYS`]. With FETCH_SOURCE_HEALTH_PATH set the counts are
+/// written to that JSON file after every query and read back at startup, so a
+/// restart doesn't forget a source that has been failing all week. Each
+/// replica keeps its own file and judges sources on the queries it served.
+#[derive(Default)]
+pub struct SourceHealthTracker {
+    path: Option<PathBuf>,
+    sources: Mutex<HashMap<String, SourceStats>>,
+}
+
+/// What the health file holds.
+#[derive(Default, Serialize, Deserialize)]
+struct StoredHealth {
+    sources: HashMap<String, SourceStats>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct SourceStats {
+    /// Outcomes per UTC day, within the window.
+    days: BTreeMap<NaiveDate, DayCounts>,
+    #[serde(default)]
+    last_success_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_error_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    last_error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct DayCounts {
+    successes: u64,
+    errors: u64,
+}
+
+impl SourceStats {
+    fn record(&mut self, outcome: Result<(), &str>, now: DateTime<Utc>) {
+        let day = self.days.entry(now.date_naive()).or_default();
+        match outcome {
+            Ok(()) => {
+                day.successes += 1;
+                self.last_success_at = Some(now);
+            }
+            Err(error) => {
+                day.errors += 1;
+                self.last_error_at = Some(now);
+                self.last_error = Some(error.chars().take(MAX_ERROR_CHARS).collect());
+            }
+        }
+        self.prune(now);
+    }
+
+    /// Drop days that have left the window.
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let oldest = window_start(now);
+        self.days.retain(|day, _| *day >= oldest);
+    }
+
+    fn health(&self, now: DateTime<Utc>) -> SourceHealth {
+        let oldest = window_start(now);
+        let (successes, errors) = self.days.range(oldest..).fold((0, 0), |(s, e), (_, day)| {
+            (s + day.successes, e + day.errors)
+        });
+        let queries = successes + errors;
+        SourceHealth {
+            status: classify(successes, queries),
+            success_rate_7d: (queries > 0).then(|| successes as f64 / queries as f64),
+            queries_7d: queries,
+            last_success_at: self.last_success_at,
+            last_error_at: self.last_error_at,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+/// The first day inside the window ending on `now`'s day.
+fn window_start(now: DateTime<Utc>) -> NaiveDate {
+    now.date_naive()
+        .checked_sub_days(Days::new(WINDOW_DAYS - 1))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+/// A source's status from `successes` out of `queries` in the window.
+pub fn classify(successes: u64, queries: u64) -> SourceHealthStatus {
+    if queries == 0 {
+        return SourceHealthStatus::Unknown;
+    }
+    let rate = successes as f64 / queries as f64;
+    if rate >= HEALTHY_RATE {
+        SourceHealthStatus::Healthy
+    } else if rate >= FAILING_RATE || queries < MIN_QUERIES_TO_FAIL {
+        SourceHealthStatus::Degraded
+    } else {
+        SourceHealthStatus::Failing
+    }
+}
+
+impl SourceHealthTracker {
+    /// Persisted at FETCH_SOURCE_HEALTH_PATH; in memory only when it is unset.
+    pub fn from_env() -> Self {
+        match std::env::var("FETCH_SOURCE_HEALTH_PATH") {
+            Ok(path) if !path.is_empty() => Self::open(PathBuf::from(path)),
+            _ => Self::default(),
+        }
+    }
+
+    /// Persisted at `path`, starting from what it already holds. An
+    /// unreadable file is logged and replaced: health is advisory, and not
+    /// worth refusing to start over.
+    pub fn open(path: PathBuf) -> Self {
+        let stored = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<StoredHealth>(&bytes).unwrap_or_else(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "Unreadable source health file — starting empty");
+                StoredHealth::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StoredHealth::default(),
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to read source health file — starting empty");
+                StoredHealth::default()
+            }
+        };
+        Self {
+            path: Some(path),
+            sources: Mutex::new(stored.sources),
+        }
+    }
+
+    /// Count one query of source `id` as of `now`.
+    pub async fn record(&self, id: &str, outcome: Result<(), &str>, now: DateTime<Utc>) {
+        let mut sources = self.sources.lock().await;
+        let stats = sources.entry(id.to_string()).or_default();
+        stats.record(outcome, now);
+        if let Some(rate) = stats.health(now).success_rate_7d {
+            metrics::gauge!("fetch.source.success_rate_7d", "source" => id.to_string()).set(rate);
+        }
+
+        // Written under the lock, so files land in the order queries finished.
+        if let Some(path) = &self.path {
+            for stats in sources.values_mut() {
+                stats.prune(now);
+            }
+            let stored = StoredHealth {
+                sources: sources.clone(),
+            };
+            if let Err(e) = write_atomic(path, &stored).await {
+                tracing::warn!(path = %path.display(), error = %e, "Failed to save source health");
+            }
+        }
+    }
+
+    /// Every tracked source's health as of `now`. Sources never queried
+    /// aren't listed.
+    pub async fn snapshot(&self, now: DateTime<Utc>) -> HashMap<String, SourceHealth> {
+        self.sources
+            .lock()
+            .await
+            .iter()
+            .map(|(id, stats)| (id.clone(), stats.health(now)))
+            .collect()
+    }
+}
+
+/// Write `stored` to a sibling temp file and rename it over `path`, so a
+/// crash mid-write leaves the previous file intact.
+async fn write_atomic(path: &Path, stored: &StoredHealth) -> std::io::Result<()> {
+    let bytes = serde_json::to_vec(stored).map_err(std::io::Error::other)?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str) -> DateTime<Utc> {
+        format!("{}T12:00:00Z", date).parse().unwrap()
+    }
+
+    #[test]
+    fn test_classification_thresholds() {
+        assert_eq!(classify(0, 0), SourceHealthStatus::Unknown);
+        assert_eq!(classify(10, 10), SourceHealthStatus::Healthy);
+        assert_eq!(classify(9, 10), SourceHealthStatus::Healthy);
+        assert_eq!(classify(89, 100), SourceHealthStatus::Degraded);
+        assert_eq!(classify(5, 10), SourceHealthStatus::Degraded);
+        assert_eq!(classify(49, 100), SourceHealthStatus::Failing);
+        assert_eq!(classify(0, 25), SourceHealthStatus::Failing);
+
+        // Too few queries to call failing.
+        assert_eq!(classify(0, 1), SourceHealthStatus::Degraded);
+        assert_eq!(classify(0, 2), SourceHealthStatus::Degraded);
+        assert_eq!(classify(0, 3), SourceHealthStatus::Failing);
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut stats = SourceStats::default();
+        // A week of failures ending 2026-03-01...
+        for days_back in (0..7).rev() {
+            stats.record(
+                Err("HTTP 503"),
+                at("2026-03-01") - chrono::Duration::days(days_back),
+            );
+        }
+        // ...then three days of success.
+        for day in 2..=4 {
+            stats.record(Ok(()), at(&format!("2026-03-{:02}", day)));
+        }
+
+        let health = stats.health(at("2026-03-04"));
+        // 2026-02-26 .. 2026-03-04: four failures, three successes.
+        assert_eq!(health.queries_7d, 7);
+        assert_eq!(health.success_rate_7d, Some(3.0 / 7.0));
+        assert_eq!(health.status, SourceHealthStatus::Failing);
+        assert_eq!(health.last_success_at, Some(at("2026-03-04")));
+        assert_eq!(health.last_error_at, Some(at("2026-03-01")));
+        assert_eq!(health.last_error.as_deref(), Some("HTTP 503"));
+        // Days before the window were pruned when recording.
+        assert_eq!(
+            stats.days.keys().next().copied(),
+            Some("2026-02-26".parse().unwrap())
+        );
+
+        // Two days on the failures have aged out further.
+        let health = stats.health(at("2026-03-06"));
+        assert_eq!(health.queries_7d, 5);
+        assert_eq!(health.status, SourceHealthStatus::Degraded);
+
+        // A week past the last query nothing is left to judge on.
+        let health = stats.health(at("2026-03-11"));
+        assert_eq!(health.queries_7d, 0);
+        assert_eq!(health.success_rate_7d, None);
+        assert_eq!(health.status, SourceHealthStatus::Unknown);
+        assert_eq!(health.last_success_at, Some(at("2026-03-04")));
+    }
+
+    #[tokio::test]
+    async fn test_health_survives_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "autosint-source-health-{}.json",
+            uuid::Uuid::new_v4()
+        ));
+        let now = at("2026-03-04");
+
+        let tracker = SourceHealthTracker::open(path.clone());
+        tracker.record("mfa-press", Ok(()), now).await;
+        for _ in 0..3 {
+            tracker.record("mfa-press", Err("HTTP 500"), now).await;
+        }
+        tracker.record("gazette", Ok(()), now).await;
+        let before = tracker.snapshot(now).await;
+        drop(tracker);
+
+        let reopened = SourceHealthTracker::open(path.clone());
+        let after = reopened.snapshot(now).await;
+        assert_eq!(after, before);
+        assert_eq!(after["mfa-press"].queries_7d, 4);
+        assert_eq!(after["mfa-press"].status, SourceHealthStatus::Failing);
+        assert_eq!(after["gazette"].status, SourceHealthStatus::Healthy);
+
+        // Counting carries on from the stored days.
+        reopened.record("mfa-press", Ok(()), now).await;
+        assert_eq!(reopened.snapshot(now).await["mfa-press"].queries_7d, 5);
+
+        // A corrupt file starts empty rather than failing.
+        std::fs::write(&path, b"{not json").unwrap();
+        assert!(SourceHealthTracker::open(path.clone())
+            .snapshot(now)
+            .await
+            .is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use autosint_common::api::fetch::{
+    SourceInfo, SourceKind, SOURCE_QUERY_LIMIT, SOURCE_QUERY_PREFIX, SOURCE_QUERY_SINCE,
+    SOURCE_QUERY_UNTIL,
+};
+use autosint_common::types::{MAX_TRUST_LEVEL, MIN_TRUST_LEVEL};
+
+/// Catalog sources served by GET /sources and POST /sources/{id}/query,
+/// loaded from the TOML file at FETCH_SOURCES_PATH:
+///
+/// ```toml
+/// [[sitemaps]]
+/// id = "mfa-press"
+/// name = "MFA press releases"
+/// description = "Ministry of Foreign Affairs press release archive"
+/// url = "https://mfa.example/sitemap.xml"
+/// prefix = "https://mfa.example/press/"   # optional: only pages under it
+/// trust_level = 4                         # optional
+/// ```
+#[derive(Default)]
+pub struct SourceCatalog {
+    sitemaps: Vec<SitemapSource>,
+}
+
+/// A site section exposed through its sitemap.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SitemapSource {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub trust_level: Option<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SourcesError {
+    #[error("Failed to read sources file {path}: {message}")]
+    Read { path: String, message: String },
+
+    #[error("Invalid sources file: {0}")]
+    Parse(String),
+
+    #[error("Invalid source {id}: {message}")]
+    Invalid { id: String, message: String },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SourcesFile {
+    #[serde(default)]
+    sitemaps: Vec<SitemapSource>,
+}
+
+impl SourceCatalog {
+    /// Load from FETCH_SOURCES_PATH; empty when it is unset.
+    pub fn from_env() -> Result<Self, SourcesError> {
+        match std::env::var("FETCH_SOURCES_PATH") {
+            Ok(path) if !path.is_empty() => Self::load(Path::new(&path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SourcesError> {
+        let text = std::fs::read_to_string(path).map_err(|e| SourcesError::Read {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, SourcesError> {
+        let file: SourcesFile =
+            toml::from_str(text).map_err(|e| SourcesError::Parse(e.to_string()))?;
+
+        let mut catalog = Self::default();
+        for source in file.sitemaps {
+            let invalid = |message: &str| SourcesError::Invalid {
+                id: source.id.clone(),
+                message: message.into(),
+            };
+            if source.id.is_empty()
+                || !source
+                    .id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(invalid(
+                    "id must be letters, digits, '-' and '_' (it is a URL path segment)",
+                ));
+            }
+            if !source.url.starts_with("http://") && !source.url.starts_with("https://") {
+                return Err(invalid("url must be http or https"));
+            }
+            if source
+                .trust_level
+                .is_some_and(|t| !(MIN_TRUST_LEVEL..=MAX_TRUST_LEVEL).contains(&t))
+            {
+                return Err(invalid("trust_level must be from 1 to 5"));
+            }
+            if catalog.get(&source.id).is_some() {
+                return Err(invalid("configured more than once"));
+            }
+            catalog.sitemaps.push(source);
+        }
+        Ok(catalog)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sitemaps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SitemapSource> {
+        self.sitemaps.iter().find(|s| s.id == id)
+    }
+
+    /// The GET /sources listing, without health.
+    pub fn list(&self) -> Vec<SourceInfo> {
+        self.sitemaps
+            .iter()
+            .map(|source| SourceInfo {
+                id: source.id.clone(),
+                name: source.name.clone(),
+                description: source.description.clone(),
+                kind: SourceKind::Sitemap,
+                capabilities: [
+                    SOURCE_QUERY_PREFIX,
+                    "query",
+                    SOURCE_QUERY_SINCE,
+                    SOURCE_QUERY_UNTIL,
+                    SOURCE_QUERY_LIMIT,
+                ]
+                .map(String::from)
+                .to_vec(),
+                trust_level: source.trust_level,
+                health: None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_list() {
+        let catalog = SourceCatalog::parse(
+            r#"
+[[sitemaps]]
+id = "mfa-press"
+name = "MFA press releases"
+description = "Press release archive"
+url = "https://mfa.example/sitemap.xml"
+prefix = "https://mfa.example/press/"
+trust_level = 4
+"#,
+        )
+        .unwrap();
+        assert_eq!(catalog.len(), 1);
+        let source = catalog.get("mfa-press").unwrap();
+        assert_eq!(source.prefix.as_deref(), Some("https://mfa.example/press/"));
+
+        let listed = catalog.list();
+        assert_eq!(listed[0].kind, SourceKind::Sitemap);
+        assert_eq!(listed[0].trust_level, Some(4));
+        assert!(listed[0].capabilities.contains(&"prefix".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_entries_fail_the_load() {
+        let entry = |id: &str, url: &str, extra: &str| {
+            format!(
+                "[[sitemaps]]\nid = \"{}\"\nname = \"n\"\ndescription = \"d\"\nurl = \"{}\"\n{}\n",
+                id, url, extra
+            )
+        };
+        for text in [
+            entry("a/b", "https://x.example/sitemap.xml", ""),
+            entry("a", "ftp://x.example/sitemap.xml", ""),
+            entry("a", "https://x.example/sitemap.xml", "trust_level = 9"),
+            entry("a", "https://x.example/sitemap.xml", "typo = 1"),
+            entry("a", "https://x.example/1.xml", "") + &entry("a", "https://x.example/2.xml", ""),
+        ] {
+            assert!(SourceCatalog::parse(&text).is_err(), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Table extraction. Readable-text extraction flattens tables into run-on
+//! text; these pass them through as rows and columns alongside it.
+
+use scraper::{ElementRef, Html, Selector};
+
+use autosint_common::api::fetch::ExtractedTable;
+use autosint_common::readable::collapse_whitespace;
+
+/// Most tables returned per document.
+const MAX_TABLES: usize = 20;
+/// Most body rows kept per table; the rest are counted in `omitted_rows`.
+const MAX_ROWS: usize = 500;
+/// Most columns kept per table.
+const MAX_COLUMNS: usize = 30;
+/// Longest cell text kept, in characters.
+const MAX_CELL_CHARS: usize = 500;
+/// How far a layout-text field may start from its column's start and still
+/// count as aligned.
+const ALIGN_TOLERANCE: usize = 2;
+
+/// Tables in an HTML document, in document order. Tables with fewer than two
+/// columns or fewer than two rows are layout, not data, and are skipped. A
+/// nested table is extracted on its own, and its text also appears in the
+/// enclosing cell.
+pub fn extract_html_tables(html: &str) -> Vec<ExtractedTable> {
+    let document = Html::parse_document(html);
+    let table_sel = Selector::parse("table").expect("valid selector");
+    let row_sel = Selector::parse("tr").expect("valid selector");
+    let caption_sel = Selector::parse("caption").expect("valid selector");
+
+    let mut tables = Vec::new();
+    for table in document.select(&table_sel) {
+        let mut headers = Vec::new();
+        let mut rows = Vec::new();
+        for row in table.select(&row_sel) {
+            if nearest_table(row).map(|t| t.id()) != Some(table.id()) {
+                continue;
+            }
+            let cells = row_cells(row);
+            if cells.is_empty() {
+                continue;
+            }
+            if headers.is_empty() && rows.is_empty() && is_header_row(row) {
+                headers = cells;
+            } else {
+                rows.push(cells);
+            }
+        }
+
+        let caption = table
+            .select(&caption_sel)
+            .find(|c| nearest_table(*c).map(|t| t.id()) == Some(table.id()))
+            .map(|c| cell_text(c.text()))
+            .filter(|c| !c.is_empty());
+
+        if let Some(table) = finish_table(caption, headers, rows, false) {
+            tables.push(table);
+            if tables.len() == MAX_TABLES {
+                break;
+            }
+        }
+    }
+    tables
+}
+
+/// The closest `table` element enclosing `element`.
+fn nearest_table(element: ElementRef<'_>) -> Option<ElementRef<'_>> {
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|e| e.value().name() == "table")
+}
+
+/// A header row sits in `<thead>` or holds only `<th>` cells.
+fn is_header_row(row: ElementRef<'_>) -> bool {
+    let in_thead = row
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .take_while(|e| e.value().name() != "table")
+        .any(|e| e.value().name() == "thead");
+    in_thead
+        || row
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|c| matches!(c.value().name(), "td" | "th"))
+            .all(|c| c.value().name() == "th")
+}
+
+/// Cell texts of a row. A cell spanning several columns is followed by empty
+/// cells so later cells stay in their columns.
+fn row_cells(row: ElementRef<'_>) -> Vec<String> {
+    let mut cells = Vec::new();
+    for cell in row
+        .children()
+        .filter_map(ElementRef::wrap)
+        .filter(|c| matches!(c.value().name(), "td" | "th"))
+    {
+        cells.push(cell_text(cell.text()));
+        let span = cell
+            .value()
+            .attr("colspan")
+            .and_then(|s| s.trim().parse::<usize>().ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_COLUMNS);
+        cells.extend(std::iter::repeat_n(String::new(), span - 1));
+    }
+    cells
+}
+
+fn cell_text<'a>(text: impl Iterator<Item = &'a str>) -> String {
+    let text = collapse_whitespace(&text.collect::<Vec<_>>().join(" "));
+    match text.char_indices().nth(MAX_CELL_CHARS) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+/// Tables in column-aligned plain text, such as a PDF's text layer extracted
+/// with its layout. A table is a run of three or more consecutive lines that
+/// each split into two or more fields on tabs or runs of two or more spaces;
+/// the first line is its header and sets the columns. Fields are placed in
+/// the column they start under; a table is `best_effort` when some field
+/// didn't line up with a column.
+pub fn extract_text_tables(text: &str) -> Vec<ExtractedTable> {
+    let mut tables = Vec::new();
+    let mut block: Vec<Vec<(usize, String)>> = Vec::new();
+
+    for line in text.lines().chain(std::iter::once("")) {
+        let fields = split_fields(line);
+        if fields.len() >= 2 {
+            block.push(fields);
+            continue;
+        }
+        if block.len() >= 3 {
+            if let Some(table) = layout_table(&block) {
+                tables.push(table);
+                if tables.len() == MAX_TABLES {
+                    break;
+                }
+            }
+        }
+        block.clear();
+    }
+    tables
+}
+
+/// Fields of a layout-text line with the character column each starts at.
+/// Fields are separated by a tab or two or more spaces.
+fn split_fields(line: &str) -> Vec<(usize, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut fields = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() {
+            let gap = chars[i] == '\t'
+                || (chars[i] == ' ' && chars.get(i + 1).is_some_and(|c| c.is_whitespace()));
+            if gap {
+                break;
+            }
+            i += 1;
+        }
+        let field: String = chars[start..i].iter().collect();
+        fields.push((start, field.trim().to_string()));
+    }
+    fields
+}
+
+fn layout_table(block: &[Vec<(usize, String)>]) -> Option<ExtractedTable> {
+    let (header, body) = block.split_first()?;
+    let starts: Vec<usize> = header
+        .iter()
+        .map(|(start, _)| *start)
+        .take(MAX_COLUMNS)
+        .collect();
+    let headers: Vec<String> = header
+        .iter()
+        .take(MAX_COLUMNS)
+        .map(|(_, f)| cell_text(std::iter::once(f.as_str())))
+        .collect();
+
+    let mut best_effort = false;
+    let mut rows = Vec::with_capacity(body.len());
+    for fields in body {
+        let mut row = vec![String::new(); starts.len()];
+        for (start, field) in fields {
+            let column = starts
+                .iter()
+                .rposition(|s| *s <= start + ALIGN_TOLERANCE)
+                .unwrap_or(0);
+            if start.abs_diff(starts[column]) > ALIGN_TOLERANCE || !row[column].is_empty() {
+                best_effort = true;
+            }
+            if !row[column].is_empty() {
+                row[column].push(' ');
+            }
+            row[column].push_str(field);
+        }
+        rows.push(
+            row.into_iter()
+                .map(|c| cell_text(std::iter::once(c.as_str())))
+                .collect(),
+        );
+    }
+
+    finish_table(None, headers, rows, best_effort)
+}
+
+/// Square up rows to a common column count and apply the size caps. `None`
+/// if the result is too small to be a data table.
+fn finish_table(
+    caption: Option<String>,
+    mut headers: Vec<String>,
+    mut rows: Vec<Vec<String>>,
+    best_effort: bool,
+) -> Option<ExtractedTable> {
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .chain(std::iter::once(headers.len()))
+        .max()
+        .unwrap_or(0)
+        .min(MAX_COLUMNS);
+    let total_rows = rows.len() + usize::from(!headers.is_empty());
+    if columns < 2 || total_rows < 2 || rows.is_empty() {
+        return None;
+    }
+
+    if !headers.is_empty() {
+        headers.resize(columns, String::new());
+    }
+    let omitted_rows = rows.len().saturating_sub(MAX_ROWS);
+    rows.truncate(MAX_ROWS);
+    for row in &mut rows {
+        row.resize(columns, String::new());
+    }
+
+    Some(ExtractedTable {
+        caption,
+        headers,
+        rows,
+        omitted_rows,
+        best_effort,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANNEX_HTML: &str = include_str!("testdata/annex.html");
+    const ANNEX_TXT: &str = include_str!("testdata/annex.txt");
+
+    fn row(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_html_tables() {
+        let tables = extract_html_tables(ANNEX_HTML);
+        // The listing, the exports table, and the quarterly table nested in
+        // it; the one-cell layout table is skipped.
+        assert_eq!(tables.len(), 3);
+
+        let listing = &tables[0];
+        assert_eq!(
+            listing.caption.as_deref(),
+            Some("Entities subject to restrictive measures")
+        );
+        assert_eq!(
+            listing.headers,
+            row(&["No.", "Name", "Identifying information", "Date of listing"])
+        );
+        assert_eq!(listing.rows.len(), 3);
+        assert_eq!(
+            listing.rows[0],
+            row(&[
+                "1",
+                "Acme Shipping LLC",
+                "Registration no. 4471; Dubai, UAE",
+                "12.3.2025"
+            ])
+        );
+        // The colspan keeps the date in its column.
+        assert_eq!(
+            listing.rows[2],
+            row(&["3", "Globex Trading (details withheld)", "", "14.3.2025"])
+        );
+        assert!(!listing.best_effort);
+
+        let exports = &tables[1];
+        assert_eq!(exports.headers, row(&["Year", "Exports (USD m)"]));
+        assert_eq!(exports.rows[0], row(&["2023", "1,204"]));
+        assert_eq!(exports.rows[1], row(&["2024", "Q1 310 Q2 298"]));
+
+        let quarterly = &tables[2];
+        assert!(quarterly.headers.is_empty());
+        assert_eq!(
+            quarterly.rows,
+            vec![row(&["Q1", "310"]), row(&["Q2", "298"])]
+        );
+    }
+
+    #[test]
+    fn test_html_row_cap() {
+        let body: String = (0..MAX_ROWS + 7)
+            .map(|i| format!("<tr><td>{}</td><td>x</td></tr>", i))
+            .collect();
+        let tables = extract_html_tables(&format!("<table>{}</table>", body));
+        assert_eq!(tables[0].rows.len(), MAX_ROWS);
+        assert_eq!(tables[0].omitted_rows, 7);
+    }
+
+    #[test]
+    fn test_layout_text_tables() {
+        let tables = extract_text_tables(ANNEX_TXT);
+        assert_eq!(tables.len(), 1);
+
+        let vessels = &tables[0];
+        assert_eq!(
+            vessels.headers,
+            row(&["Name", "IMO", "Flag", "Date of listing"])
+        );
+        assert_eq!(vessels.rows.len(), 4);
+        assert_eq!(
+            vessels.rows[0],
+            row(&["Northwind Star", "9187629", "Cameroon", "12.3.2025"])
+        );
+        // A blank cell leaves the following field in its own column.
+        assert_eq!(
+            vessels.rows[2],
+            row(&["Sea Harrier", "9305611", "", "14.3.2025"])
+        );
+        // A trailing note past the last column joins it and lowers confidence.
+        assert_eq!(vessels.rows[3][3], "14.3.2025 see note 3");
+        assert!(vessels.best_effort);
+    }
+
+    #[test]
+    fn test_prose_is_not_a_table() {
+        let text = "First sentence.  Second sentence.\nMore prose here.\n\nA  B\nC  D\n";
+        assert!(extract_text_tables(text).is_empty());
+    }
+}
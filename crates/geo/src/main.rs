@@ -1,11 +1,22 @@
 use std::sync::Arc;
 
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use metrics_exporter_prometheus::PrometheusHandle;
+
+use autosint_common::prometheus::{self, MetricsLabels};
+use autosint_common::tls;
 
 /// Shared application state.
 struct AppState {
     metrics_handle: PrometheusHandle,
+    /// Bearer token /metrics requires (GEO_METRICS_TOKEN). Open when unset.
+    metrics_token: Option<String>,
 }
 
 #[tokio::main]
@@ -20,12 +31,27 @@ async fn main() {
 
     tracing::info!("AutOSINT Geo starting");
 
-    // Install Prometheus metrics recorder.
-    let metrics_handle = PrometheusBuilder::new()
+    // Optional TLS for the HTTP API: GEO_TLS_CERT / GEO_TLS_KEY, plus
+    // GEO_TLS_CLIENT_CA to require client certificates.
+    let server_tls = match tls::server_config_from_env("GEO") {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!(error = %e, "Invalid TLS configuration — refusing to start");
+            std::process::exit(1);
+        }
+    };
+
+    // Install Prometheus metrics recorder, labeling every metric with the
+    // service, version and instance. GEO_METRICS_TOKEN protects /metrics.
+    let metrics_handle = MetricsLabels::from_env("GEO", "geo", env!("CARGO_PKG_VERSION"))
+        .builder()
         .install_recorder()
         .expect("Failed to install Prometheus metrics recorder");
 
-    let state = Arc::new(AppState { metrics_handle });
+    let state = Arc::new(AppState {
+        metrics_handle,
+        metrics_token: prometheus::token_from_env("GEO"),
+    });
 
     let app = Router::new()
         .route("/health", get(health_handler))
@@ -41,9 +67,15 @@ async fn main() {
         .await
         .expect("Failed to bind TCP listener");
 
-    tracing::info!(port = port, "AutOSINT Geo listening");
+    tracing::info!(
+        port = port,
+        tls = server_tls.is_some(),
+        "AutOSINT Geo listening"
+    );
 
-    axum::serve(listener, app).await.expect("HTTP server error");
+    tls::serve(listener, app, server_tls)
+        .await
+        .expect("HTTP server error");
 }
 
 async fn health_handler() -> impl IntoResponse {
@@ -53,6 +85,10 @@ async fn health_handler() -> impl IntoResponse {
     )
 }
 
-async fn metrics_handler(State(state): State<Arc<AppState>>) -> String {
-    state.metrics_handle.render()
+async fn metrics_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    prometheus::render(
+        &state.metrics_handle,
+        state.metrics_token.as_deref(),
+        &headers,
+    )
 }